@@ -1,4 +1,5 @@
 use auto_update::{AutoUpdateStatus, AutoUpdater, DismissErrorMessage};
+use client::{NetworkSettings, ToggleOffline};
 use editor::Editor;
 use extension::ExtensionStore;
 use futures::StreamExt;
@@ -9,6 +10,7 @@ use gpui::{
 };
 use language::{LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName};
 use project::{LanguageServerProgress, Project};
+use settings::{Settings, SettingsStore};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc};
 use ui::prelude::*;
@@ -17,6 +19,7 @@ use workspace::{item::ItemHandle, StatusItemView, Workspace};
 
 actions!(activity_indicator, [ShowErrorMessage]);
 
+const DISCONNECTED_ICON: &str = "icons/disconnected.svg";
 const DOWNLOAD_ICON: &str = "icons/download.svg";
 const WARNING_ICON: &str = "icons/warning.svg";
 
@@ -70,6 +73,8 @@ impl ActivityIndicator {
             })
             .detach();
             cx.observe(&project, |_, _, cx| cx.notify()).detach();
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify())
+                .detach();
 
             if let Some(auto_updater) = auto_updater.as_ref() {
                 cx.observe(auto_updater, |_, _, cx| cx.notify()).detach();
@@ -248,7 +253,7 @@ impl ActivityIndicator {
                 icon: Some(WARNING_ICON),
                 message: format!("Formatting failed: {}. Click to see logs.", failure),
                 on_click: Some(Arc::new(|_, cx| {
-                    cx.dispatch_action(Box::new(workspace::OpenLog));
+                    cx.dispatch_action(Box::new(workspace::OpenLogViewer));
                 })),
             };
         }
@@ -285,7 +290,7 @@ impl ActivityIndicator {
                         this.dismiss_error_message(&Default::default(), cx)
                     })),
                 },
-                AutoUpdateStatus::Idle => Default::default(),
+                AutoUpdateStatus::Idle => Self::offline_content(cx),
             };
         }
 
@@ -301,7 +306,20 @@ impl ActivityIndicator {
             }
         }
 
-        Default::default()
+        Self::offline_content(cx)
+    }
+
+    fn offline_content(cx: &AppContext) -> Content {
+        if !NetworkSettings::get_global(cx).offline {
+            return Default::default();
+        }
+        Content {
+            icon: Some(DISCONNECTED_ICON),
+            message: "Working offline. Click to go online.".to_string(),
+            on_click: Some(Arc::new(|_, cx| {
+                cx.dispatch_action(Box::new(ToggleOffline));
+            })),
+        }
     }
 }
 
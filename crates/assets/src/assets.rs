@@ -3,6 +3,8 @@ use anyhow::anyhow;
 
 use gpui::{AppContext, AssetSource, Result, SharedString};
 use rust_embed::RustEmbed;
+use std::path::{Component, Path};
+use util::paths::EXTENSIONS_DIR;
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
@@ -17,9 +19,19 @@ pub struct Assets;
 
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<std::borrow::Cow<'static, [u8]>> {
-        Self::get(path)
-            .map(|f| f.data)
-            .ok_or_else(|| anyhow!("could not find asset at path \"{}\"", path))
+        if let Some(file) = Self::get(path) {
+            return Ok(file.data);
+        }
+
+        // Assets provided by extensions, such as the icons of icon themes,
+        // are referred to by their absolute path.
+        if is_installed_extension_file(Path::new(path), &EXTENSIONS_DIR.join("installed")) {
+            if let Ok(data) = std::fs::read(path) {
+                return Ok(data.into());
+            }
+        }
+
+        Err(anyhow!("could not find asset at path \"{}\"", path))
     }
 
     fn list(&self, path: &str) -> Result<Vec<SharedString>> {
@@ -35,6 +47,26 @@ impl AssetSource for Assets {
     }
 }
 
+/// Returns whether the given path is that of a file within one of the extensions
+/// installed in `installed_dir`. Paths that escape their extension's directory, such
+/// as through `..` or symlinks, aren't, so that extensions can't load arbitrary files.
+/// Extensions that are themselves symlinked, like dev extensions, are allowed.
+fn is_installed_extension_file(path: &Path, installed_dir: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(installed_dir) else {
+        return false;
+    };
+    let Some(Component::Normal(extension_id)) = relative_path.components().next() else {
+        return false;
+    };
+    match (
+        path.canonicalize(),
+        installed_dir.join(extension_id).canonicalize(),
+    ) {
+        (Ok(path), Ok(extension_dir)) => path.starts_with(extension_dir),
+        _ => false,
+    }
+}
+
 impl Assets {
     /// Populate the [`TextSystem`] of the given [`AppContext`] with all `.ttf` fonts in the `fonts` directory.
     pub fn load_fonts(&self, cx: &AppContext) -> gpui::Result<()> {
@@ -50,3 +82,62 @@ impl Assets {
         cx.text_system().add_fonts(embedded_fonts)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_is_installed_extension_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let installed_dir = root.join("installed");
+        fs::create_dir_all(installed_dir.join("icons/icons")).unwrap();
+        fs::write(installed_dir.join("icons/icons/rust.svg"), "<svg/>").unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+
+        assert!(is_installed_extension_file(
+            &installed_dir.join("icons/icons/rust.svg"),
+            &installed_dir
+        ));
+
+        // Files outside of the extension's directory are rejected.
+        assert!(!is_installed_extension_file(
+            &root.join("secret.txt"),
+            &installed_dir
+        ));
+        assert!(!is_installed_extension_file(
+            &installed_dir.join("icons/../../secret.txt"),
+            &installed_dir
+        ));
+        assert!(!is_installed_extension_file(
+            &installed_dir.join("icons/missing.svg"),
+            &installed_dir
+        ));
+
+        #[cfg(unix)]
+        {
+            // Dev extensions are symlinked into the installed directory, but symlinks
+            // within an extension can't point outside of it.
+            let dev_extension_dir = root.join("dev-extension");
+            fs::create_dir_all(&dev_extension_dir).unwrap();
+            fs::write(dev_extension_dir.join("icon.svg"), "<svg/>").unwrap();
+            std::os::unix::fs::symlink(&dev_extension_dir, installed_dir.join("dev")).unwrap();
+            assert!(is_installed_extension_file(
+                &installed_dir.join("dev/icon.svg"),
+                &installed_dir
+            ));
+
+            std::os::unix::fs::symlink(
+                root.join("secret.txt"),
+                installed_dir.join("icons/secret.svg"),
+            )
+            .unwrap();
+            assert!(!is_installed_extension_file(
+                &installed_dir.join("icons/secret.svg"),
+                &installed_dir
+            ));
+        }
+    }
+}
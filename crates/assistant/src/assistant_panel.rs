@@ -17,8 +17,8 @@ use editor::{
         BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, ToDisplayPoint,
     },
     scroll::{Autoscroll, AutoscrollStrategy},
-    Anchor, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer, MultiBufferSnapshot,
-    ToOffset as _, ToPoint,
+    Anchor, DraggedText, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer,
+    MultiBufferSnapshot, ToOffset as _, ToPoint,
 };
 use file_icons::FileIcons;
 use fs::Fs;
@@ -1906,6 +1906,16 @@ impl Conversation {
         result
     }
 
+    fn message_text(&self, message_id: MessageId, cx: &AppContext) -> Option<String> {
+        let message = self.messages(cx).find(|message| message.id == message_id)?;
+        let text = self
+            .buffer
+            .read(cx)
+            .text_for_range(message.offset_range)
+            .collect::<String>();
+        Some(text.trim_end().to_string())
+    }
+
     fn messages<'a>(&'a self, cx: &'a AppContext) -> impl 'a + Iterator<Item = Message> {
         let buffer = self.buffer.read(cx);
         let mut message_anchors = self.message_anchors.iter().enumerate().peekable();
@@ -2271,8 +2281,9 @@ impl ConversationEditor {
                     style: BlockStyle::Sticky,
                     render: Box::new({
                         let conversation = self.conversation.clone();
-                        move |_cx| {
+                        move |cx| {
                             let message_id = message.id;
+                            let message_text = conversation.read(cx).message_text(message_id, cx);
                             let sender = ButtonLike::new("role")
                                 .style(ButtonStyle::Filled)
                                 .child(match message.role {
@@ -2302,6 +2313,11 @@ impl ConversationEditor {
 
                             h_flex()
                                 .id(("message_header", message_id.0))
+                                .when_some(message_text, |header, text| {
+                                    header.on_drag(DraggedText::new(text), |text, cx| {
+                                        cx.new_view(|_| text.clone())
+                                    })
+                                })
                                 .h_11()
                                 .relative()
                                 .gap_1()
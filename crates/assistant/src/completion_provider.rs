@@ -14,12 +14,18 @@ use crate::{
 };
 use anyhow::Result;
 use client::Client;
+use credentials::open_ai_credential;
 use futures::{future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, AppContext, BorrowAppContext, Task, WindowContext};
 use settings::{Settings, SettingsStore};
 use std::sync::Arc;
 
 pub fn init(client: Arc<Client>, cx: &mut AppContext) {
+    credentials::register(cx, |cx| match &AssistantSettings::get_global(cx).provider {
+        AssistantProvider::OpenAi { api_url, .. } => Some(open_ai_credential(api_url)),
+        AssistantProvider::ZedDotDev { .. } => None,
+    });
+
     let mut settings_version = 0;
     let provider = match &AssistantSettings::get_global(cx).provider {
         AssistantProvider::ZedDotDev { default_model } => {
@@ -2,12 +2,13 @@ use crate::{
     assistant_settings::OpenAiModel, CompletionProvider, LanguageModel, LanguageModelRequest, Role,
 };
 use anyhow::{anyhow, Result};
+use credentials::open_ai_credential;
 use editor::{Editor, EditorElement, EditorStyle};
 use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
 use gpui::{AnyView, AppContext, FontStyle, FontWeight, Task, TextStyle, View, WhiteSpace};
 use open_ai::{stream_completion, Request, RequestMessage, Role as OpenAiRole};
 use settings::Settings;
-use std::{env, sync::Arc};
+use std::sync::Arc;
 use theme::ThemeSettings;
 use ui::prelude::*;
 use util::{http::HttpClient, ResultExt};
@@ -54,17 +55,11 @@ impl OpenAiCompletionProvider {
         if self.is_authenticated() {
             Task::ready(Ok(()))
         } else {
-            let api_url = self.api_url.clone();
+            let read_credential = open_ai_credential(&self.api_url).read(cx);
             cx.spawn(|mut cx| async move {
-                let api_key = if let Ok(api_key) = env::var("OPENAI_API_KEY") {
-                    api_key
-                } else {
-                    let (_, api_key) = cx
-                        .update(|cx| cx.read_credentials(&api_url))?
-                        .await?
-                        .ok_or_else(|| anyhow!("credentials not found"))?;
-                    String::from_utf8(api_key)?
-                };
+                let (api_key, _) = read_credential
+                    .await?
+                    .ok_or_else(|| anyhow!("credentials not found"))?;
                 cx.update_global::<CompletionProvider, _>(|provider, _cx| {
                     if let CompletionProvider::OpenAi(provider) = provider {
                         provider.api_key = Some(api_key);
@@ -75,7 +70,7 @@ impl OpenAiCompletionProvider {
     }
 
     pub fn reset_credentials(&self, cx: &AppContext) -> Task<Result<()>> {
-        let delete_credentials = cx.delete_credentials(&self.api_url);
+        let delete_credentials = open_ai_credential(&self.api_url).delete(cx);
         cx.spawn(|mut cx| async move {
             delete_credentials.await.log_err();
             cx.update_global::<CompletionProvider, _>(|provider, _cx| {
@@ -224,7 +219,7 @@ impl AuthenticationPrompt {
             return;
         }
 
-        let write_credentials = cx.write_credentials(&self.api_url, "Bearer", api_key.as_bytes());
+        let write_credentials = open_ai_credential(&self.api_url).write(&api_key, cx);
         cx.spawn(|_, mut cx| async move {
             write_credentials.await?;
             cx.update_global::<CompletionProvider, _>(|provider, _cx| {
@@ -63,15 +63,15 @@ fn main() {
 
         let http = Arc::new(HttpClientWithUrl::new("http://localhost:11434"));
 
-        let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY not set");
-        let embedding_provider = OpenAiEmbeddingProvider::new(
+        let embedding_provider = OpenAiEmbeddingProvider::authenticate(
             http.clone(),
             OpenAiEmbeddingModel::TextEmbedding3Small,
             open_ai::OPEN_AI_API_URL.to_string(),
-            api_key,
+            cx,
         );
 
         cx.spawn(|mut cx| async move {
+            let embedding_provider = embedding_provider.await?;
             let mut semantic_index = SemanticIndex::new(
                 PathBuf::from("/tmp/semantic-index-db.mdb"),
                 Arc::new(embedding_provider),
@@ -2,11 +2,13 @@ mod assistant_settings;
 mod completion_provider;
 pub mod tools;
 
-use anyhow::{Context, Result};
-use assistant_tooling::{ToolFunctionCall, ToolRegistry};
+use anyhow::{anyhow, Context, Result};
+use assistant_tooling::{LanguageModelTool as _, ToolFunctionCall, ToolRegistry};
 use client::{proto, Client};
 use completion_provider::*;
+use credentials::open_ai_credential;
 use editor::{Editor, EditorEvent};
+use extension::ExtensionStore;
 use feature_flags::FeatureFlagAppExt as _;
 use futures::{channel::oneshot, future::join_all, Future, FutureExt, StreamExt};
 use gpui::{
@@ -17,7 +19,19 @@ use language::{language_settings::SoftWrap, LanguageRegistry};
 use open_ai::{FunctionContent, ToolCall, ToolCallContent};
 use project::Fs;
 use rich_text::RichText;
-use semantic_index::{CloudEmbeddingProvider, ProjectIndex, SemanticIndex};
+use semantic_index::{
+    azure_open_ai_credential, cohere_credential, google_ai_credential, hugging_face_credential,
+    jina_credential, mistral_credential, voyage_credential, AzureOpenaiEmbeddingProvider,
+    BedrockCredentials, BedrockEmbeddingModel, BedrockEmbeddingProvider, CloudEmbeddingProvider,
+    CohereEmbeddingModel, CohereEmbeddingProvider, EmbeddingProvider, GoogleAiEmbeddingModel,
+    GoogleAiEmbeddingProvider, HuggingFaceEmbeddingProvider, JinaEmbeddingModel,
+    JinaEmbeddingProvider, LlamaCppEmbeddingProvider, LmStudioEmbeddingProvider,
+    LocalEmbeddingModel, LocalEmbeddingProvider, MistralEmbeddingModel, MistralEmbeddingProvider,
+    OllamaEmbeddingModel, OllamaEmbeddingProvider, OpenAiEmbeddingModel, OpenAiEmbeddingProvider,
+    ProjectIndex, SemanticIndex, VoyageEmbeddingModel, VoyageEmbeddingProvider,
+    BEDROCK_DEFAULT_REGION, HUGGING_FACE_DEFAULT_MODEL, LLAMA_CPP_API_URL, LM_STUDIO_API_URL,
+    LM_STUDIO_DEFAULT_MODEL, OLLAMA_API_URL,
+};
 use serde::Deserialize;
 use settings::Settings;
 use std::{cmp, sync::Arc};
@@ -30,7 +44,9 @@ use workspace::{
     Workspace,
 };
 
-pub use assistant_settings::AssistantSettings;
+pub use assistant_settings::{
+    AssistantSettings, CohereEmbeddingModelSetting, EmbeddingProviderSetting,
+};
 
 const MAX_COMPLETION_CALLS_PER_SUBMISSION: usize = 5;
 
@@ -56,18 +72,30 @@ gpui::impl_actions!(assistant2, [Submit]);
 pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     AssistantSettings::register(cx);
 
-    cx.spawn(|mut cx| {
-        let client = client.clone();
-        async move {
-            let embedding_provider = CloudEmbeddingProvider::new(client.clone());
-            let semantic_index = SemanticIndex::new(
-                EMBEDDINGS_DIR.join("semantic-index-db.0.mdb"),
-                Arc::new(embedding_provider),
-                &mut cx,
-            )
-            .await?;
-            cx.update(|cx| cx.set_global(semantic_index))
+    credentials::register(cx, |cx| {
+        let settings = AssistantSettings::get_global(cx);
+        match settings.embedding_provider {
+            EmbeddingProviderSetting::OpenAi => Some(open_ai_credential(open_ai_api_url(settings))),
+            EmbeddingProviderSetting::Jina => Some(jina_credential()),
+            EmbeddingProviderSetting::Mistral => Some(mistral_credential()),
+            EmbeddingProviderSetting::Cohere => Some(cohere_credential()),
+            EmbeddingProviderSetting::Voyage => Some(voyage_credential()),
+            EmbeddingProviderSetting::GoogleAi => Some(google_ai_credential()),
+            EmbeddingProviderSetting::HuggingFace => Some(hugging_face_credential()),
+            EmbeddingProviderSetting::AzureOpenAi => settings
+                .azure_open_ai_endpoint
+                .as_deref()
+                .map(azure_open_ai_credential),
+            _ => None,
         }
+    });
+
+    let (embedding_provider, db_name) = embedding_provider(&client, cx);
+    cx.spawn(|mut cx| async move {
+        let embedding_provider = embedding_provider.await?;
+        let semantic_index =
+            SemanticIndex::new(EMBEDDINGS_DIR.join(db_name), embedding_provider, &mut cx).await?;
+        cx.update(|cx| cx.set_global(semantic_index))
     })
     .detach();
 
@@ -85,6 +113,305 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     .detach();
 }
 
+/// Returns the embedding provider selected in the settings, along with the name of
+/// the database that its embeddings are stored in. Each provider, model and endpoint
+/// gets a database of its own, as their embeddings can't be compared with each other.
+fn embedding_provider(
+    client: &Arc<Client>,
+    cx: &AppContext,
+) -> (Task<Result<Arc<dyn EmbeddingProvider>>>, String) {
+    let settings = AssistantSettings::get_global(cx);
+    match settings.embedding_provider {
+        EmbeddingProviderSetting::ZedDotDev => (
+            Task::ready(Ok(Arc::new(CloudEmbeddingProvider::new(client.clone())))),
+            "semantic-index-db.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::Ollama => {
+            let model = OllamaEmbeddingModel::from_name(
+                settings
+                    .ollama_embedding_model
+                    .as_deref()
+                    .unwrap_or("nomic-embed-text"),
+                settings.ollama_embedding_dimensions,
+            );
+            // The index of the default model keeps the name it had before other
+            // models could be chosen.
+            let db_name = match model {
+                OllamaEmbeddingModel::NomicEmbedText => "semantic-index-db.ollama.0.mdb".into(),
+                _ => format!(
+                    "semantic-index-db.ollama.{}.0.mdb",
+                    db_name_component(model.name())
+                ),
+            };
+            (
+                Task::ready(Ok(Arc::new(OllamaEmbeddingProvider::new(
+                    client.http_client(),
+                    model,
+                    settings.ollama_api_url.as_deref().unwrap_or(OLLAMA_API_URL),
+                )))),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::LlamaCpp => {
+            // The server's model can't be told, so each server gets its own index.
+            let (api_url, db_name) = match settings.llama_cpp_api_url.as_deref() {
+                Some(api_url) if api_url != LLAMA_CPP_API_URL => (
+                    api_url,
+                    format!(
+                        "semantic-index-db.llama_cpp.{}.0.mdb",
+                        db_name_component(api_url)
+                    ),
+                ),
+                _ => (
+                    LLAMA_CPP_API_URL,
+                    "semantic-index-db.llama_cpp.0.mdb".into(),
+                ),
+            };
+            (
+                Task::ready(Ok(Arc::new(LlamaCppEmbeddingProvider::new(
+                    client.http_client(),
+                    api_url,
+                )))),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::LmStudio => {
+            let model = settings
+                .lm_studio_embedding_model
+                .as_deref()
+                .unwrap_or(LM_STUDIO_DEFAULT_MODEL);
+            // Each model gets its own index, since their embeddings can't be compared.
+            let db_name = format!(
+                "semantic-index-db.lm_studio.{}.0.mdb",
+                db_name_component(model)
+            );
+            (
+                Task::ready(Ok(Arc::new(LmStudioEmbeddingProvider::new(
+                    client.http_client(),
+                    model,
+                    settings
+                        .lm_studio_api_url
+                        .as_deref()
+                        .unwrap_or(LM_STUDIO_API_URL),
+                )))),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::Local => (
+            Task::ready(Ok(Arc::new(LocalEmbeddingProvider::new(
+                client.http_client(),
+                LocalEmbeddingModel::AllMiniLmL6V2,
+                EMBEDDINGS_DIR.join("models"),
+            )))),
+            "semantic-index-db.local.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::OpenAi => {
+            let authenticate = OpenAiEmbeddingProvider::authenticate(
+                client.http_client(),
+                OpenAiEmbeddingModel::TextEmbedding3Small,
+                open_ai_api_url(settings).to_string(),
+                cx,
+            );
+            let dimensions = settings.open_ai_embedding_dimensions;
+            // A compatible API may serve another model under the same name, so each
+            // one gets its own index.
+            let api_url = open_ai_api_url(settings);
+            let mut db_name = "semantic-index-db.open_ai".to_string();
+            if api_url != open_ai::OPEN_AI_API_URL {
+                db_name = format!("{db_name}.{}", db_name_component(api_url));
+            }
+            if let Some(dimensions) = dimensions {
+                db_name = format!("{db_name}.{dimensions}d");
+            }
+            db_name.push_str(".0.mdb");
+            (
+                cx.background_executor().spawn(async move {
+                    let provider: Arc<dyn EmbeddingProvider> =
+                        Arc::new(authenticate.await?.with_dimensions(dimensions));
+                    Ok(provider)
+                }),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::Jina => (
+            erase_provider(
+                JinaEmbeddingProvider::authenticate(
+                    client.http_client(),
+                    JinaEmbeddingModel::JinaEmbeddingsV2BaseCode,
+                    cx,
+                ),
+                cx,
+            ),
+            "semantic-index-db.jina.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::Mistral => (
+            erase_provider(
+                MistralEmbeddingProvider::authenticate(
+                    client.http_client(),
+                    MistralEmbeddingModel::MistralEmbed,
+                    cx,
+                ),
+                cx,
+            ),
+            "semantic-index-db.mistral.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::Cohere => {
+            let model = match settings.cohere_embedding_model {
+                CohereEmbeddingModelSetting::EmbedEnglishV3 => CohereEmbeddingModel::EmbedEnglishV3,
+                CohereEmbeddingModelSetting::EmbedMultilingualV3 => {
+                    CohereEmbeddingModel::EmbedMultilingualV3
+                }
+            };
+            // Each model gets its own index, since their embeddings can't be compared.
+            let db_name = format!(
+                "semantic-index-db.cohere.{}.0.mdb",
+                db_name_component(model.id())
+            );
+            (
+                erase_provider(
+                    CohereEmbeddingProvider::authenticate(client.http_client(), model, cx),
+                    cx,
+                ),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::Voyage => (
+            erase_provider(
+                VoyageEmbeddingProvider::authenticate(
+                    client.http_client(),
+                    VoyageEmbeddingModel::VoyageCode2,
+                    cx,
+                ),
+                cx,
+            ),
+            "semantic-index-db.voyage.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::GoogleAi => (
+            erase_provider(
+                GoogleAiEmbeddingProvider::authenticate(
+                    client.http_client(),
+                    GoogleAiEmbeddingModel::TextEmbedding004,
+                    cx,
+                ),
+                cx,
+            ),
+            "semantic-index-db.google_ai.0.mdb".into(),
+        ),
+        EmbeddingProviderSetting::HuggingFace => {
+            let model = settings
+                .hugging_face_embedding_model
+                .clone()
+                .unwrap_or_else(|| HUGGING_FACE_DEFAULT_MODEL.to_string());
+            // Each model gets its own index, since their embeddings can't be compared.
+            let db_name = format!(
+                "semantic-index-db.hugging_face.{}.0.mdb",
+                db_name_component(&model)
+            );
+            (
+                erase_provider(
+                    HuggingFaceEmbeddingProvider::authenticate(client.http_client(), model, cx),
+                    cx,
+                ),
+                db_name,
+            )
+        }
+        EmbeddingProviderSetting::Bedrock => {
+            let region = settings
+                .bedrock_region
+                .clone()
+                .or_else(|| std::env::var("AWS_REGION").ok())
+                .unwrap_or_else(|| BEDROCK_DEFAULT_REGION.to_string());
+            let provider = BedrockCredentials::from_env().map(|credentials| {
+                let provider: Arc<dyn EmbeddingProvider> = Arc::new(BedrockEmbeddingProvider::new(
+                    client.http_client(),
+                    BedrockEmbeddingModel::TitanEmbedTextV1,
+                    region,
+                    credentials,
+                ));
+                provider
+            });
+            (
+                Task::ready(provider),
+                "semantic-index-db.bedrock.0.mdb".into(),
+            )
+        }
+        EmbeddingProviderSetting::AzureOpenAi => {
+            let (Some(endpoint), Some(deployment)) = (
+                settings.azure_open_ai_endpoint.clone(),
+                settings.azure_open_ai_deployment.clone(),
+            ) else {
+                return (
+                    Task::ready(Err(anyhow!(
+                        "azure_open_ai_endpoint and azure_open_ai_deployment must be set"
+                    ))),
+                    String::new(),
+                );
+            };
+            // Each deployment gets its own index, since it may run any model.
+            let db_name = format!(
+                "semantic-index-db.azure_open_ai.{}.{}.0.mdb",
+                db_name_component(&endpoint),
+                db_name_component(&deployment)
+            );
+            let api_version = settings.azure_open_ai_api_version.clone();
+            let authenticate = AzureOpenaiEmbeddingProvider::authenticate(
+                client.http_client(),
+                endpoint,
+                deployment,
+                cx,
+            );
+            (
+                cx.background_executor().spawn(async move {
+                    let mut provider = authenticate.await?;
+                    if let Some(api_version) = api_version {
+                        provider = provider.with_api_version(api_version);
+                    }
+                    let provider: Arc<dyn EmbeddingProvider> = Arc::new(provider);
+                    Ok(provider)
+                }),
+                db_name,
+            )
+        }
+    }
+}
+
+/// Turns the task that creates a provider, e.g. once its API key is read, into one
+/// that creates any provider.
+fn erase_provider<P: 'static + EmbeddingProvider>(
+    provider: Task<Result<P>>,
+    cx: &AppContext,
+) -> Task<Result<Arc<dyn EmbeddingProvider>>> {
+    cx.background_executor().spawn(async move {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(provider.await?);
+        Ok(provider)
+    })
+}
+
+/// Turns a model's name or a URL into one that can be part of the name of a database,
+/// like `snowflake-arctic-embed_3alatest` for `snowflake-arctic-embed:latest`. Bytes
+/// other than letters, digits, `.` and `-` are escaped with their hex code, so that
+/// different names never share a database.
+fn db_name_component(name: &str) -> String {
+    use std::fmt::Write as _;
+
+    let mut component = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'-' {
+            component.push(byte as char);
+        } else {
+            write!(component, "_{byte:02x}").unwrap();
+        }
+    }
+    component
+}
+
+fn open_ai_api_url(settings: &AssistantSettings) -> &str {
+    settings
+        .open_ai_api_url
+        .as_deref()
+        .unwrap_or(open_ai::OPEN_AI_API_URL)
+}
+
 pub fn enabled(cx: &AppContext) -> bool {
     cx.is_staff()
 }
@@ -119,6 +446,16 @@ impl AssistantPanel {
                     .context("failed to register ProjectIndexTool")
                     .log_err();
 
+                if let Some(extension_store) = ExtensionStore::try_global(cx) {
+                    for tool in extension_store.read(cx).assistant_tools(&project) {
+                        let tool_name = tool.name();
+                        tool_registry
+                            .register(tool)
+                            .with_context(|| format!("failed to register tool {tool_name}"))
+                            .log_err();
+                    }
+                }
+
                 let tool_registry = Arc::new(tool_registry);
 
                 Self::new(app_state.languages.clone(), tool_registry, cx)
@@ -950,3 +1287,44 @@ impl CodebaseContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_name_component() {
+        assert_eq!(db_name_component("nomic-embed-text"), "nomic-embed-text");
+        assert_eq!(
+            db_name_component("snowflake-arctic-embed:latest"),
+            "snowflake-arctic-embed_3alatest"
+        );
+        assert_eq!(
+            db_name_component("http://localhost:8080"),
+            "http_3a_2f_2flocalhost_3a8080"
+        );
+
+        // Names that only differ in the characters that are escaped get different
+        // databases.
+        assert_ne!(db_name_component("a:b"), db_name_component("a-b"));
+        assert_ne!(db_name_component("a:b"), db_name_component("a_3ab"));
+    }
+
+    #[test]
+    fn test_cohere_embedding_model_setting() {
+        let settings: AssistantSettings = serde_json::from_str(
+            r#"{ "enabled": true, "cohere_embedding_model": "embed-multilingual-v3.0" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.cohere_embedding_model,
+            CohereEmbeddingModelSetting::EmbedMultilingualV3
+        );
+
+        let settings: AssistantSettings = serde_json::from_str(r#"{ "enabled": true }"#).unwrap();
+        assert_eq!(
+            settings.cohere_embedding_model,
+            CohereEmbeddingModelSetting::EmbedEnglishV3
+        );
+    }
+}
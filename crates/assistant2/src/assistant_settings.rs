@@ -5,11 +5,196 @@ use settings::{Settings, SettingsSources};
 #[derive(Default, Debug, Deserialize, Serialize, Clone)]
 pub struct AssistantSettings {
     pub enabled: bool,
+    #[serde(default)]
+    pub embedding_provider: EmbeddingProviderSetting,
+    #[serde(default)]
+    pub ollama_api_url: Option<String>,
+    #[serde(default)]
+    pub ollama_embedding_model: Option<String>,
+    #[serde(default)]
+    pub ollama_embedding_dimensions: Option<usize>,
+    #[serde(default)]
+    pub llama_cpp_api_url: Option<String>,
+    #[serde(default)]
+    pub lm_studio_api_url: Option<String>,
+    #[serde(default)]
+    pub lm_studio_embedding_model: Option<String>,
+    #[serde(default)]
+    pub open_ai_api_url: Option<String>,
+    #[serde(default)]
+    pub open_ai_embedding_dimensions: Option<usize>,
+    #[serde(default)]
+    pub cohere_embedding_model: CohereEmbeddingModelSetting,
+    #[serde(default)]
+    pub bedrock_region: Option<String>,
+    #[serde(default)]
+    pub hugging_face_embedding_model: Option<String>,
+    #[serde(default)]
+    pub azure_open_ai_endpoint: Option<String>,
+    #[serde(default)]
+    pub azure_open_ai_deployment: Option<String>,
+    #[serde(default)]
+    pub azure_open_ai_api_version: Option<String>,
+}
+
+/// Which service computes the embeddings of the semantic index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderSetting {
+    /// Embeddings are computed by zed.dev.
+    #[default]
+    ZedDotDev,
+    /// Embeddings are computed by an Ollama server, with the model given by
+    /// `ollama_embedding_model`.
+    Ollama,
+    /// Embeddings are computed by a llama.cpp server started with `--embedding`,
+    /// with whichever model it was started with.
+    LlamaCpp,
+    /// Embeddings are computed by LM Studio's local server, with the model given
+    /// by `lm_studio_embedding_model`.
+    LmStudio,
+    /// Embeddings are computed inside Zed with `all-MiniLM-L6-v2`, which is
+    /// downloaded the first time it's used.
+    Local,
+    /// Embeddings are computed by OpenAI's API, or an API that's compatible with it,
+    /// with `text-embedding-3-small`.
+    OpenAi,
+    /// Embeddings are computed by Jina's API with `jina-embeddings-v2-base-code`,
+    /// which is trained on code, with the API key from the `JINA_API_KEY`
+    /// environment variable or the keychain.
+    Jina,
+    /// Embeddings are computed by Mistral's API with `mistral-embed`, with the API
+    /// key from the `MISTRAL_API_KEY` environment variable or the keychain.
+    Mistral,
+    /// Embeddings are computed by AWS Bedrock with Titan Embeddings G1, signed with
+    /// the credentials in the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+    /// `AWS_SESSION_TOKEN` environment variables.
+    Bedrock,
+    /// Embeddings are computed by the model deployed as `azure_open_ai_deployment`
+    /// to the Azure OpenAI resource at `azure_open_ai_endpoint`, with the API key
+    /// from the `AZURE_OPENAI_API_KEY` environment variable or the keychain.
+    AzureOpenAi,
+    /// Embeddings are computed by Cohere's API with the model given by
+    /// `cohere_embedding_model`, which embeds search queries differently from the
+    /// code they search, with the API key from the `COHERE_API_KEY` environment
+    /// variable or the keychain.
+    Cohere,
+    /// Embeddings are computed by Voyage's API with `voyage-code-2`, which is
+    /// trained on code, with the API key from the `VOYAGE_API_KEY` environment
+    /// variable or the keychain.
+    Voyage,
+    /// Embeddings are computed by Google AI's API with `text-embedding-004`, with
+    /// the API key from the `GOOGLE_AI_API_KEY` environment variable or the keychain.
+    GoogleAi,
+    /// Embeddings are computed by Hugging Face's Inference API with the model
+    /// given by `hugging_face_embedding_model`, with the access token from the
+    /// `HUGGING_FACE_API_KEY` environment variable or the keychain.
+    HuggingFace,
+}
+
+/// Which of Cohere's models computes embeddings when the `embedding_provider` is
+/// `cohere`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum CohereEmbeddingModelSetting {
+    /// `embed-english-v3.0`, for code and comments written in English.
+    #[default]
+    #[serde(rename = "embed-english-v3.0")]
+    EmbedEnglishV3,
+    /// `embed-multilingual-v3.0`, for comments written in other languages.
+    #[serde(rename = "embed-multilingual-v3.0")]
+    EmbedMultilingualV3,
 }
 
 #[derive(Default, Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct AssistantSettingsContent {
     pub enabled: Option<bool>,
+    /// The service that computes the embeddings of the semantic index. Changing it
+    /// takes effect after a restart.
+    ///
+    /// Default: zed_dot_dev
+    pub embedding_provider: Option<EmbeddingProviderSetting>,
+    /// The URL of the Ollama server that computes embeddings when the
+    /// `embedding_provider` is `ollama`.
+    ///
+    /// Default: http://localhost:11434
+    pub ollama_api_url: Option<String>,
+    /// The name of the model, pulled into Ollama, that computes embeddings when the
+    /// `embedding_provider` is `ollama`, like `mxbai-embed-large` or
+    /// `snowflake-arctic-embed`. Each model has its own index, since their
+    /// embeddings can't be compared. Changing it takes effect after a restart.
+    ///
+    /// Default: nomic-embed-text
+    pub ollama_embedding_model: Option<String>,
+    /// The number of dimensions of the embeddings of `ollama_embedding_model`,
+    /// which is checked against those that Ollama returns.
+    ///
+    /// Default: null, which expects 768 dimensions from `nomic-embed-text` and 1024
+    /// from `mxbai-embed-large`, and accepts embeddings of any size from other models
+    pub ollama_embedding_dimensions: Option<usize>,
+    /// The URL of the llama.cpp server that computes embeddings when the
+    /// `embedding_provider` is `llama_cpp`. Each URL has its own index, since the
+    /// model that a server runs can't be told, so serve different models at
+    /// different URLs.
+    ///
+    /// Default: http://localhost:8080
+    pub llama_cpp_api_url: Option<String>,
+    /// The URL of the LM Studio server that computes embeddings when the
+    /// `embedding_provider` is `lm_studio`.
+    ///
+    /// Default: http://localhost:1234
+    pub lm_studio_api_url: Option<String>,
+    /// The id of the model, loaded in LM Studio, that computes embeddings when the
+    /// `embedding_provider` is `lm_studio`. Each model has its own index, since their
+    /// embeddings can't be compared. Changing it takes effect after a restart.
+    ///
+    /// Default: text-embedding-nomic-embed-text-v1.5
+    pub lm_studio_embedding_model: Option<String>,
+    /// The base URL of the OpenAI-compatible API that computes embeddings when the
+    /// `embedding_provider` is `open_ai`, like OpenRouter, a LiteLLM proxy or a
+    /// self-hosted server. Each URL has its own index.
+    ///
+    /// Default: https://api.openai.com/v1
+    pub open_ai_api_url: Option<String>,
+    /// The number of dimensions to shorten OpenAI's embeddings to when the
+    /// `embedding_provider` is `open_ai`, at most 1536. Fewer dimensions make the
+    /// index smaller, at a small cost in the quality of searches. Each number of
+    /// dimensions has its own index. Changing it takes effect after a restart.
+    ///
+    /// Default: null, which keeps all of the dimensions
+    pub open_ai_embedding_dimensions: Option<usize>,
+    /// The model that computes embeddings when the `embedding_provider` is `cohere`.
+    /// Each model has its own index, since their embeddings can't be compared.
+    /// Changing it takes effect after a restart.
+    ///
+    /// Default: embed-english-v3.0
+    pub cohere_embedding_model: Option<CohereEmbeddingModelSetting>,
+    /// The AWS region whose Bedrock endpoint computes embeddings when the
+    /// `embedding_provider` is `bedrock`.
+    ///
+    /// Default: the `AWS_REGION` environment variable, or us-east-1
+    pub bedrock_region: Option<String>,
+    /// The id of the model on Hugging Face that computes embeddings when the
+    /// `embedding_provider` is `hugging_face`, which has to support the
+    /// feature-extraction pipeline. Each model has its own index, since their
+    /// embeddings can't be compared. Changing it takes effect after a restart.
+    ///
+    /// Default: sentence-transformers/all-MiniLM-L6-v2
+    pub hugging_face_embedding_model: Option<String>,
+    /// The endpoint of the Azure OpenAI resource that computes embeddings when the
+    /// `embedding_provider` is `azure_open_ai`, like
+    /// `https://my-resource.openai.azure.com`.
+    ///
+    /// Default: null
+    pub azure_open_ai_endpoint: Option<String>,
+    /// The name of the deployment of an embedding model in the Azure OpenAI resource.
+    /// Each deployment has its own index. Changing it takes effect after a restart.
+    ///
+    /// Default: null
+    pub azure_open_ai_deployment: Option<String>,
+    /// The version of the Azure OpenAI API to use.
+    ///
+    /// Default: 2024-02-01
+    pub azure_open_ai_api_version: Option<String>,
 }
 
 impl Settings for AssistantSettings {
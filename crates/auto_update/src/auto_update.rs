@@ -1,10 +1,13 @@
 mod update_notification;
 
 use anyhow::{anyhow, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
 use client::{Client, TelemetrySettings, ZED_APP_PATH};
 use db::kvp::KEY_VALUE_STORE;
 use db::RELEASE_CHANNEL;
 use editor::{Editor, MultiBuffer};
+use futures::io::BufReader;
 use gpui::{
     actions, AppContext, AsyncAppContext, Context as _, Global, Model, ModelContext,
     SemanticVersion, SharedString, Task, View, ViewContext, VisualContext, WindowContext,
@@ -12,6 +15,7 @@ use gpui::{
 use isahc::AsyncBody;
 
 use markdown_preview::markdown_preview_view::{MarkdownPreviewMode, MarkdownPreviewView};
+use notifications::{NotificationCenter, NotificationSource};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
@@ -23,7 +27,8 @@ use smol::{fs::File, process::Command};
 use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
 use std::{
     env::consts::{ARCH, OS},
-    ffi::OsString,
+    ffi::{OsStr, OsString},
+    path::{Component, Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -37,6 +42,12 @@ use workspace::Workspace;
 
 const SHOULD_SHOW_UPDATE_NOTIFICATION_KEY: &str = "auto-updater-should-show-updated-notification";
 const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The path of the file in a delta update that lists the files it removes.
+const DELTA_REMOVED_FILES_PATH: &str = ".zed-delta-removed-files";
+/// The code signing requirement that a patched app bundle must satisfy: it must be
+/// signed with a Developer ID certificate issued to Zed's team, like the full updates.
+const CODE_SIGNING_REQUIREMENT: &str =
+    "anchor apple generic and certificate leaf[subject.OU] = \"MQ55VZLNZQ\"";
 
 actions!(
     auto_update,
@@ -76,6 +87,11 @@ pub struct AutoUpdater {
 struct JsonRelease {
     version: String,
     url: String,
+    /// The url of a delta update from the running version, when one is available.
+    /// Servers that don't serve delta updates leave it out, and the full update is
+    /// downloaded instead.
+    #[serde(default)]
+    delta_url: Option<String>,
 }
 
 struct AutoUpdateSetting(bool);
@@ -102,6 +118,52 @@ impl Settings for AutoUpdateSetting {
     }
 }
 
+/// The release channel to install updates from. Switching to another channel
+/// installs that channel's latest release over the running app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UpdateChannel {
+    Stable,
+    Preview,
+    Nightly,
+}
+
+impl UpdateChannel {
+    fn release_channel(self) -> ReleaseChannel {
+        match self {
+            Self::Stable => ReleaseChannel::Stable,
+            Self::Preview => ReleaseChannel::Preview,
+            Self::Nightly => ReleaseChannel::Nightly,
+        }
+    }
+}
+
+struct UpdateChannelSetting(Option<UpdateChannel>);
+
+/// The release channel to install updates from, or null for the channel of the
+/// running app.
+///
+/// Default: null
+#[derive(Clone, Copy, Default, JsonSchema, Deserialize, Serialize)]
+#[serde(transparent)]
+struct UpdateChannelSettingContent(Option<UpdateChannel>);
+
+impl Settings for UpdateChannelSetting {
+    const KEY: Option<&'static str> = Some("update_channel");
+
+    type FileContent = Option<UpdateChannelSettingContent>;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        let update_channel = [sources.release_channel, sources.user]
+            .into_iter()
+            .find_map(|value| value.copied().flatten())
+            .or(*sources.default)
+            .unwrap_or_default();
+
+        Ok(Self(update_channel.0))
+    }
+}
+
 #[derive(Default)]
 struct GlobalAutoUpdate(Option<Model<AutoUpdater>>);
 
@@ -115,6 +177,7 @@ struct ReleaseNotesBody {
 
 pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
     AutoUpdateSetting::register(cx);
+    UpdateChannelSetting::register(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         workspace.register_action(|_, action: &Check, cx| check(action, cx));
@@ -124,7 +187,7 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
         });
 
         workspace.register_action(|workspace, _: &ViewReleaseNotesLocally, cx| {
-            view_release_notes_locally(workspace, cx);
+            view_release_notes_locally(workspace, None, cx);
         });
     })
     .detach();
@@ -136,6 +199,7 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
         let mut update_subscription = AutoUpdateSetting::get_global(cx)
             .0
             .then(|| updater.start_polling(cx));
+        let mut update_channel = UpdateChannelSetting::get_global(cx).0;
 
         cx.observe_global::<SettingsStore>(move |updater, cx| {
             if AutoUpdateSetting::get_global(cx).0 {
@@ -145,6 +209,14 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
             } else {
                 update_subscription.take();
             }
+
+            let new_update_channel = UpdateChannelSetting::get_global(cx).0;
+            if new_update_channel != update_channel {
+                update_channel = new_update_channel;
+                if update_subscription.is_some() {
+                    updater.poll(cx);
+                }
+            }
         })
         .detach();
 
@@ -186,16 +258,85 @@ pub fn view_release_notes(_: &ViewReleaseNotes, cx: &mut AppContext) -> Option<(
     None
 }
 
-fn view_release_notes_locally(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
-    let release_channel = ReleaseChannel::global(cx);
-    let version = AppVersion::global(cx).to_string();
+/// Returns the url of the latest release of `channel`.
+///
+/// The version the update is installed `from` lets the server offer a delta update.
+/// Servers that don't serve delta updates ignore it.
+fn latest_release_url(
+    client: &HttpClientWithUrl,
+    channel: ReleaseChannel,
+    from: Option<&str>,
+) -> String {
+    let mut url = client.build_url(&format!(
+        "/api/releases/latest?asset=Zed.dmg&os={}&arch={}",
+        OS, ARCH
+    ));
+    if let Some(param) = channel.release_query_param() {
+        url += "&";
+        url += param;
+    }
+    if let Some(from) = from {
+        url += "&from=";
+        url += from;
+    }
+    url
+}
 
-    let client = client::Client::global(cx).http_client();
-    let url = client.build_url(&format!(
+/// Returns the paths in the patched app bundle of the files that a delta update
+/// removes, given the contents of its [`DELTA_REMOVED_FILES_PATH`] file.
+fn delta_removed_paths(patched_app_path: &Path, removed_files: &str) -> Result<Vec<PathBuf>> {
+    removed_files
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|removed_file| -> Result<PathBuf> {
+            let removed_file = Path::new(removed_file);
+            if removed_file.is_absolute()
+                || removed_file
+                    .components()
+                    .any(|component| component == Component::ParentDir)
+            {
+                Err(anyhow!("invalid removed file in delta: {removed_file:?}"))?;
+            }
+            Ok(patched_app_path.join(removed_file))
+        })
+        .collect()
+}
+
+/// Returns the url of the release notes of `version`.
+///
+/// With `since`, the notes of all versions after it up to `version` are asked for.
+/// Servers that don't support that ignore the parameter and return the notes of
+/// `version` alone, which are still worth showing.
+fn release_notes_url(
+    client: &HttpClientWithUrl,
+    release_channel: ReleaseChannel,
+    version: &str,
+    since: Option<&str>,
+) -> String {
+    let mut url = client.build_url(&format!(
         "/api/release_notes/{}/{}",
         release_channel.dev_name(),
         version
     ));
+    if let Some(since) = since {
+        url += "?since=";
+        url += since;
+    }
+    url
+}
+
+/// Opens the release notes of the running version, or those of all versions after
+/// `since` up to the running one.
+pub(crate) fn view_release_notes_locally(
+    workspace: &mut Workspace,
+    since: Option<String>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let release_channel = ReleaseChannel::global(cx);
+    let version = AppVersion::global(cx).to_string();
+
+    let client = client::Client::global(cx).http_client();
+    let url = release_notes_url(&client, release_channel, &version, since.as_deref());
 
     let markdown = workspace
         .app_state()
@@ -257,20 +398,26 @@ fn view_release_notes_locally(workspace: &mut Workspace, cx: &mut ViewContext<Wo
 pub fn notify_of_any_new_update(cx: &mut ViewContext<Workspace>) -> Option<()> {
     let updater = AutoUpdater::get(cx)?;
     let version = updater.read(cx).current_version;
-    let should_show_notification = updater.read(cx).should_show_update_notification(cx);
+    let updated_from_version = updater.read(cx).updated_from_version(cx);
 
     cx.spawn(|workspace, mut cx| async move {
-        let should_show_notification = should_show_notification.await?;
-        if should_show_notification {
+        if let Some(previous_version) = updated_from_version.await? {
+            // Older versions didn't record the version they were updated from.
+            let previous_version = Some(previous_version).filter(|version| !version.is_empty());
             workspace.update(&mut cx, |workspace, cx| {
+                let workspace_handle = cx.view().downgrade();
                 workspace.show_notification(
                     NotificationId::unique::<UpdateNotification>(),
                     cx,
-                    |cx| cx.new_view(|_| UpdateNotification::new(version)),
+                    |cx| {
+                        cx.new_view(|_| {
+                            UpdateNotification::new(version, previous_version, workspace_handle)
+                        })
+                    },
                 );
                 updater
                     .read(cx)
-                    .set_should_show_update_notification(false, cx)
+                    .set_updated_from_version(None, cx)
                     .detach_and_log_err(cx);
             })?;
         }
@@ -305,7 +452,10 @@ impl AutoUpdater {
     }
 
     pub fn poll(&mut self, cx: &mut ModelContext<Self>) {
-        if self.pending_poll.is_some() || self.status == AutoUpdateStatus::Updated {
+        if self.pending_poll.is_some()
+            || self.status == AutoUpdateStatus::Updated
+            || self.http_client.is_offline()
+        {
             return;
         }
 
@@ -340,19 +490,27 @@ impl AutoUpdater {
             (this.http_client.clone(), this.current_version)
         })?;
 
-        let mut url_string = client.build_url(&format!(
-            "/api/releases/latest?asset=Zed.dmg&os={}&arch={}",
-            OS, ARCH
-        ));
-        cx.update(|cx| {
-            if let Some(param) = ReleaseChannel::try_global(cx)
-                .and_then(|release_channel| release_channel.release_query_param())
-            {
-                url_string += "&";
-                url_string += param;
-            }
+        let (channel, current_commit_sha) = cx.update(|cx| {
+            let channel = UpdateChannelSetting::get_global(cx)
+                .0
+                .map_or(*RELEASE_CHANNEL, UpdateChannel::release_channel);
+            (channel, AppCommitSha::try_global(cx).map(|sha| sha.0))
         })?;
+        // Switching to another channel installs that channel's latest release, even
+        // when its version is lower than the current one.
+        let is_switching_channel = channel != *RELEASE_CHANNEL;
+        let installed_version = match *RELEASE_CHANNEL {
+            ReleaseChannel::Nightly => current_commit_sha
+                .clone()
+                .unwrap_or_else(|| current_version.to_string()),
+            _ => current_version.to_string(),
+        };
 
+        let url_string = latest_release_url(
+            &client,
+            channel,
+            Some(installed_version.as_str()).filter(|_| !is_switching_channel),
+        );
         let mut response = client.get(&url_string, Default::default(), true).await?;
 
         let mut body = Vec::new();
@@ -364,13 +522,15 @@ impl AutoUpdater {
         let release: JsonRelease =
             serde_json::from_slice(body.as_slice()).context("error deserializing release")?;
 
-        let should_download = match *RELEASE_CHANNEL {
-            ReleaseChannel::Nightly => cx
-                .update(|cx| AppCommitSha::try_global(cx).map(|sha| release.version != sha.0))
-                .ok()
-                .flatten()
-                .unwrap_or(true),
-            _ => release.version.parse::<SemanticVersion>()? > current_version,
+        let should_download = if is_switching_channel {
+            true
+        } else {
+            match channel {
+                ReleaseChannel::Nightly => current_commit_sha
+                    .map(|sha| release.version != sha)
+                    .unwrap_or(true),
+                _ => release.version.parse::<SemanticVersion>()? > current_version,
+            }
         };
 
         if !should_download {
@@ -389,18 +549,9 @@ impl AutoUpdater {
         let temp_dir = tempfile::Builder::new()
             .prefix("zed-auto-update")
             .tempdir()?;
-        let dmg_path = temp_dir.path().join("Zed.dmg");
-        let mount_path = temp_dir.path().join("Zed");
         let running_app_path = ZED_APP_PATH
             .clone()
             .map_or_else(|| cx.update(|cx| cx.app_path())?, Ok)?;
-        let running_app_filename = running_app_path
-            .file_name()
-            .ok_or_else(|| anyhow!("invalid running app path"))?;
-        let mut mounted_app_path: OsString = mount_path.join(running_app_filename).into();
-        mounted_app_path.push("/");
-
-        let mut dmg_file = File::create(&dmg_path).await?;
 
         let (installation_id, release_channel, telemetry) = cx.update(|cx| {
             let installation_id = Client::global(cx).telemetry().installation_id();
@@ -410,18 +561,100 @@ impl AutoUpdater {
 
             (installation_id, release_channel, telemetry)
         })?;
-
-        let request_body = AsyncBody::from(serde_json::to_string(&UpdateRequestBody {
+        let request_body = serde_json::to_string(&UpdateRequestBody {
             installation_id,
             release_channel,
             telemetry,
-        })?);
+        })?;
+
+        let mut installed_delta = false;
+        if let Some(delta_url) = release.delta_url.as_ref().filter(|_| !is_switching_channel) {
+            match Self::install_delta(
+                &client,
+                delta_url,
+                request_body.clone(),
+                &running_app_path,
+                temp_dir.path(),
+                &this,
+                &mut cx,
+            )
+            .await
+            {
+                Ok(()) => installed_delta = true,
+                Err(error) => {
+                    log::error!("failed to apply delta update, downloading full update: {error:?}")
+                }
+            }
+        }
+
+        if !installed_delta {
+            // The disk image of another channel contains that channel's app bundle.
+            let app_filename = if is_switching_channel {
+                OsString::from(format!("{}.app", channel.display_name()))
+            } else {
+                running_app_path
+                    .file_name()
+                    .ok_or_else(|| anyhow!("invalid running app path"))?
+                    .to_owned()
+            };
+            Self::install_dmg(
+                &client,
+                &release.url,
+                request_body,
+                &app_filename,
+                &running_app_path,
+                temp_dir.path(),
+                &this,
+                &mut cx,
+            )
+            .await?;
+        }
 
-        let mut response = client.get(&release.url, request_body, true).await?;
+        this.update(&mut cx, |this, cx| {
+            this.set_updated_from_version(Some(installed_version), cx)
+                .detach_and_log_err(cx);
+            this.status = AutoUpdateStatus::Updated;
+            cx.notify();
+            if let Some(notification_center) = NotificationCenter::try_global(cx) {
+                notification_center.update(cx, |notification_center, cx| {
+                    notification_center.push(
+                        NotificationSource::Updates,
+                        format!(
+                            "{} {} is ready. Restart to update.",
+                            channel.display_name(),
+                            release.version
+                        ),
+                        cx,
+                    );
+                });
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Downloads the disk image of a release and copies its app bundle over the running one.
+    #[allow(clippy::too_many_arguments)]
+    async fn install_dmg(
+        client: &HttpClientWithUrl,
+        url: &str,
+        request_body: String,
+        app_filename: &OsStr,
+        running_app_path: &Path,
+        temp_dir: &Path,
+        this: &Model<Self>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let dmg_path = temp_dir.join("Zed.dmg");
+        let mount_path = temp_dir.join("Zed");
+        let mut mounted_app_path: OsString = mount_path.join(app_filename).into();
+        mounted_app_path.push("/");
+
+        let mut dmg_file = File::create(&dmg_path).await?;
+        let mut response = client.get(url, AsyncBody::from(request_body), true).await?;
         smol::io::copy(response.body_mut(), &mut dmg_file).await?;
         log::info!("downloaded update. path:{:?}", dmg_path);
 
-        this.update(&mut cx, |this, cx| {
+        this.update(cx, |this, cx| {
             this.status = AutoUpdateStatus::Installing;
             cx.notify();
         })?;
@@ -430,7 +663,7 @@ impl AutoUpdater {
             .args(&["attach", "-nobrowse"])
             .arg(&dmg_path)
             .arg("-mountroot")
-            .arg(&temp_dir.path())
+            .arg(temp_dir)
             .output()
             .await?;
         if !output.status.success() {
@@ -443,7 +676,7 @@ impl AutoUpdater {
         let output = Command::new("rsync")
             .args(&["-av", "--delete"])
             .arg(&mounted_app_path)
-            .arg(&running_app_path)
+            .arg(running_app_path)
             .output()
             .await?;
         if !output.status.success() {
@@ -464,28 +697,113 @@ impl AutoUpdater {
                 String::from_utf8_lossy(&output.stderr)
             ))?;
         }
+        Ok(())
+    }
 
-        this.update(&mut cx, |this, cx| {
-            this.set_should_show_update_notification(true, cx)
-                .detach_and_log_err(cx);
-            this.status = AutoUpdateStatus::Updated;
+    /// Downloads a delta update and applies it to a copy of the running app bundle,
+    /// which replaces the running one once its code signature is verified.
+    ///
+    /// A delta update is a gzipped tarball of the files in the app bundle that changed
+    /// since the running version. It lists the files that were removed in a
+    /// [`DELTA_REMOVED_FILES_PATH`] file at its root.
+    async fn install_delta(
+        client: &HttpClientWithUrl,
+        url: &str,
+        request_body: String,
+        running_app_path: &Path,
+        temp_dir: &Path,
+        this: &Model<Self>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let patched_app_path = temp_dir.join("patched").join(
+            running_app_path
+                .file_name()
+                .ok_or_else(|| anyhow!("invalid running app path"))?,
+        );
+        let mut running_app_contents: OsString = running_app_path.into();
+        running_app_contents.push("/");
+        let output = Command::new("rsync")
+            .args(&["-a", "--delete"])
+            .arg(&running_app_contents)
+            .arg(&patched_app_path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            Err(anyhow!(
+                "failed to copy app: {:?}",
+                String::from_utf8_lossy(&output.stderr)
+            ))?;
+        }
+
+        let mut response = client.get(url, AsyncBody::from(request_body), true).await?;
+        if !response.status().is_success() {
+            Err(anyhow!("failed to download delta: {}", response.status()))?;
+        }
+        let archive = Archive::new(GzipDecoder::new(BufReader::new(response.body_mut())));
+        archive.unpack(&patched_app_path).await?;
+        log::info!("downloaded delta update. path:{:?}", patched_app_path);
+
+        this.update(cx, |this, cx| {
+            this.status = AutoUpdateStatus::Installing;
             cx.notify();
         })?;
+
+        let removed_files_path = patched_app_path.join(DELTA_REMOVED_FILES_PATH);
+        if let Ok(removed_files) = smol::fs::read_to_string(&removed_files_path).await {
+            for removed_path in delta_removed_paths(&patched_app_path, &removed_files)? {
+                if removed_path.is_dir() {
+                    smol::fs::remove_dir_all(&removed_path).await?;
+                } else {
+                    smol::fs::remove_file(&removed_path).await?;
+                }
+            }
+            smol::fs::remove_file(&removed_files_path).await?;
+        }
+
+        // Checking that the signature is valid isn't enough, as any signed bundle would
+        // pass. The requirement pins it to Zed's signing identity.
+        let output = Command::new("codesign")
+            .args(&["--verify", "--deep", "--strict"])
+            .arg(format!("-R={CODE_SIGNING_REQUIREMENT}"))
+            .arg(&patched_app_path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            Err(anyhow!(
+                "patched app failed signature verification: {:?}",
+                String::from_utf8_lossy(&output.stderr)
+            ))?;
+        }
+
+        let mut patched_app_contents: OsString = patched_app_path.into();
+        patched_app_contents.push("/");
+        let output = Command::new("rsync")
+            .args(&["-av", "--delete"])
+            .arg(&patched_app_contents)
+            .arg(running_app_path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            Err(anyhow!(
+                "failed to copy app: {:?}",
+                String::from_utf8_lossy(&output.stderr)
+            ))?;
+        }
         Ok(())
     }
 
-    fn set_should_show_update_notification(
+    /// Remembers the version that was running before an update was installed, so the
+    /// update notification can be shown, along with the release notes since then, after
+    /// restarting.
+    fn set_updated_from_version(
         &self,
-        should_show: bool,
+        version: Option<String>,
         cx: &AppContext,
     ) -> Task<Result<()>> {
         cx.background_executor().spawn(async move {
-            if should_show {
+            if let Some(version) = version {
                 KEY_VALUE_STORE
-                    .write_kvp(
-                        SHOULD_SHOW_UPDATE_NOTIFICATION_KEY.to_string(),
-                        "".to_string(),
-                    )
+                    .write_kvp(SHOULD_SHOW_UPDATE_NOTIFICATION_KEY.to_string(), version)
                     .await?;
             } else {
                 KEY_VALUE_STORE
@@ -496,11 +814,101 @@ impl AutoUpdater {
         })
     }
 
-    fn should_show_update_notification(&self, cx: &AppContext) -> Task<Result<bool>> {
-        cx.background_executor().spawn(async move {
-            Ok(KEY_VALUE_STORE
-                .read_kvp(SHOULD_SHOW_UPDATE_NOTIFICATION_KEY)?
-                .is_some())
-        })
+    /// Returns the version that was running before the last update, if the update
+    /// notification hasn't been shown yet. The version is empty when it's unknown.
+    fn updated_from_version(&self, cx: &AppContext) -> Task<Result<Option<String>>> {
+        cx.background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(SHOULD_SHOW_UPDATE_NOTIFICATION_KEY) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_urls() {
+        let client = HttpClientWithUrl::new("https://zed.dev");
+        assert_eq!(
+            latest_release_url(&client, ReleaseChannel::Stable, Some("0.130.1")),
+            format!(
+                "https://zed.dev/api/releases/latest?asset=Zed.dmg&os={OS}&arch={ARCH}&from=0.130.1"
+            )
+        );
+        // Switching channels always installs the full update.
+        assert_eq!(
+            latest_release_url(&client, ReleaseChannel::Preview, None),
+            format!(
+                "https://zed.dev/api/releases/latest?asset=Zed.dmg&os={OS}&arch={ARCH}&preview=1"
+            )
+        );
+
+        assert_eq!(
+            release_notes_url(&client, ReleaseChannel::Stable, "0.131.0", None),
+            "https://zed.dev/api/release_notes/stable/0.131.0"
+        );
+        assert_eq!(
+            release_notes_url(&client, ReleaseChannel::Stable, "0.131.0", Some("0.129.2")),
+            "https://zed.dev/api/release_notes/stable/0.131.0?since=0.129.2"
+        );
+    }
+
+    #[test]
+    fn test_release_without_delta() {
+        // The releases that servers return without delta updates.
+        let release: JsonRelease = serde_json::from_str(
+            r#"{"version": "0.131.0", "url": "https://zed.dev/api/releases/stable/0.131.0/Zed.dmg"}"#,
+        )
+        .unwrap();
+        assert_eq!(release.delta_url, None);
+
+        let release: JsonRelease = serde_json::from_str(
+            r#"{
+                "version": "0.131.0",
+                "url": "https://zed.dev/Zed.dmg",
+                "delta_url": "https://zed.dev/Zed-0.130.1.delta.tar.gz"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            release.delta_url.as_deref(),
+            Some("https://zed.dev/Zed-0.130.1.delta.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_delta_removed_paths() {
+        let app_path = Path::new("/tmp/patched/Zed.app");
+        assert_eq!(
+            delta_removed_paths(
+                app_path,
+                "Contents/Resources/old.icns\n\nContents/Frameworks/Old.framework\n"
+            )
+            .unwrap(),
+            [
+                app_path.join("Contents/Resources/old.icns"),
+                app_path.join("Contents/Frameworks/Old.framework"),
+            ]
+        );
+
+        // Deltas can't remove files outside of the app bundle.
+        assert!(delta_removed_paths(app_path, "/Applications/Other.app").is_err());
+        assert!(delta_removed_paths(app_path, "Contents/../../Other.app").is_err());
+    }
+
+    #[test]
+    fn test_code_signing_requirement() {
+        // The requirement must name the team that full updates are notarized for.
+        let bundle_script = include_str!("../../../script/bundle-mac");
+        let team = bundle_script
+            .lines()
+            .find_map(|line| line.strip_prefix("APPLE_NOTORIZATION_TEAM="))
+            .unwrap()
+            .trim_matches('"');
+        assert!(
+            CODE_SIGNING_REQUIREMENT
+                .ends_with(&format!("certificate leaf[subject.OU] = \"{team}\"")),
+            "{CODE_SIGNING_REQUIREMENT}"
+        );
     }
 }
@@ -1,13 +1,18 @@
 use gpui::{
     div, DismissEvent, EventEmitter, InteractiveElement, IntoElement, ParentElement, Render,
-    SemanticVersion, StatefulInteractiveElement, Styled, ViewContext,
+    SemanticVersion, StatefulInteractiveElement, Styled, ViewContext, WeakView,
 };
 use menu::Cancel;
 use release_channel::ReleaseChannel;
-use workspace::ui::{h_flex, v_flex, Icon, IconName, Label, StyledExt};
+use workspace::{
+    ui::{h_flex, v_flex, Icon, IconName, Label, StyledExt},
+    Workspace,
+};
 
 pub struct UpdateNotification {
     version: SemanticVersion,
+    previous_version: Option<String>,
+    workspace: WeakView<Workspace>,
 }
 
 impl EventEmitter<DismissEvent> for UpdateNotification {}
@@ -23,10 +28,13 @@ impl Render for UpdateNotification {
             .child(
                 h_flex()
                     .justify_between()
-                    .child(Label::new(format!(
-                        "Updated to {app_name} {}",
-                        self.version
-                    )))
+                    .child(Label::new(match &self.previous_version {
+                        Some(previous_version) => format!(
+                            "Updated to {app_name} {} from {previous_version}",
+                            self.version
+                        ),
+                        None => format!("Updated to {app_name} {}", self.version),
+                    }))
                     .child(
                         div()
                             .id("cancel")
@@ -41,7 +49,19 @@ impl Render for UpdateNotification {
                     .child(Label::new("View the release notes"))
                     .cursor_pointer()
                     .on_click(cx.listener(|this, _, cx| {
-                        crate::view_release_notes(&Default::default(), cx);
+                        if let Some(previous_version) = this.previous_version.clone() {
+                            this.workspace
+                                .update(cx, |workspace, cx| {
+                                    crate::view_release_notes_locally(
+                                        workspace,
+                                        Some(previous_version),
+                                        cx,
+                                    )
+                                })
+                                .ok();
+                        } else {
+                            crate::view_release_notes(&Default::default(), cx);
+                        }
                         this.dismiss(&menu::Cancel, cx)
                     })),
             )
@@ -49,8 +69,16 @@ impl Render for UpdateNotification {
 }
 
 impl UpdateNotification {
-    pub fn new(version: SemanticVersion) -> Self {
-        Self { version }
+    pub fn new(
+        version: SemanticVersion,
+        previous_version: Option<String>,
+        workspace: WeakView<Workspace>,
+    ) -> Self {
+        Self {
+            version,
+            previous_version,
+            workspace,
+        }
     }
 
     pub fn dismiss(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
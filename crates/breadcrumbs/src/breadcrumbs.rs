@@ -4,11 +4,12 @@ use gpui::{
     ViewContext,
 };
 use itertools::Itertools;
+use settings::Settings;
 use std::cmp;
 use theme::ActiveTheme;
 use ui::{prelude::*, ButtonLike, ButtonStyle, Label, Tooltip};
 use workspace::{
-    item::{BreadcrumbText, ItemEvent, ItemHandle},
+    item::{BreadcrumbText, ItemEvent, ItemHandle, ItemSettings},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
 };
 
@@ -75,7 +76,12 @@ impl Render for Breadcrumbs {
             Label::new("›").color(Color::Muted).into_any_element()
         });
 
-        let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
+        let icon = ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| active_item.tab_icon(cx))
+            .flatten()
+            .map(|icon| icon.size(IconSize::Small).color(Color::Muted));
+        let breadcrumbs_stack = h_flex().gap_1().children(icon).children(breadcrumbs);
         match active_item
             .downcast::<Editor>()
             .map(|editor| editor.downgrade())
@@ -5,7 +5,10 @@ pub mod room;
 use anyhow::{anyhow, Result};
 use audio::Audio;
 use call_settings::CallSettings;
-use client::{proto, ChannelId, Client, TypedEnvelope, User, UserStore, ZED_ALWAYS_ACTIVE};
+use client::{
+    proto, ChannelId, Client, ParticipantPresence, TypedEnvelope, User, UserStore,
+    ZED_ALWAYS_ACTIVE,
+};
 use collections::HashSet;
 use futures::{channel::oneshot, future::Shared, Future, FutureExt};
 use gpui::{
@@ -16,11 +19,20 @@ use postage::watch;
 use project::Project;
 use room::Event;
 use settings::Settings;
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub use participant::ParticipantLocation;
 pub use room::Room;
 
+/// How long the user can go without interacting with Zed before they're shown as idle.
+pub const IDLE_AFTER: Duration = Duration::from_secs(2 * 60);
+/// How long the user can go without interacting with Zed before they're shown as away.
+pub const AWAY_AFTER: Duration = Duration::from_secs(10 * 60);
+const PRESENCE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
 struct GlobalActiveCall(Model<ActiveCall>);
 
 impl Global for GlobalActiveCall {}
@@ -76,6 +88,9 @@ pub struct ActiveCall {
     room: Option<(Model<Room>, Vec<Subscription>)>,
     pending_room_creation: Option<Shared<Task<Result<Model<Room>, Arc<anyhow::Error>>>>>,
     location: Option<WeakModel<Project>>,
+    presence: ParticipantPresence,
+    last_activity: Instant,
+    maintain_presence: Option<Task<()>>,
     _join_debouncer: OneAtATime,
     pending_invites: HashSet<u64>,
     incoming_call: (
@@ -95,6 +110,9 @@ impl ActiveCall {
             room: None,
             pending_room_creation: None,
             location: None,
+            presence: ParticipantPresence::Active,
+            last_activity: Instant::now(),
+            maintain_presence: None,
             pending_invites: Default::default(),
             incoming_call: watch::channel(),
             _join_debouncer: OneAtATime { cancel: None },
@@ -427,6 +445,54 @@ impl ActiveCall {
         Task::ready(Ok(()))
     }
 
+    pub fn presence(&self) -> ParticipantPresence {
+        self.presence
+    }
+
+    /// Records that the user has just interacted with Zed, so that other participants
+    /// see them as active.
+    pub fn report_activity(&mut self, cx: &mut ModelContext<Self>) {
+        self.last_activity = Instant::now();
+        if self.presence != ParticipantPresence::Active {
+            self.update_presence(cx);
+        }
+    }
+
+    fn update_presence(&mut self, cx: &mut ModelContext<Self>) {
+        let inactive_for = self.last_activity.elapsed();
+        let presence = if inactive_for >= AWAY_AFTER {
+            ParticipantPresence::Away
+        } else if inactive_for >= IDLE_AFTER {
+            ParticipantPresence::Idle
+        } else {
+            ParticipantPresence::Active
+        };
+        if presence != self.presence {
+            self.presence = presence;
+            if let Some((room, _)) = self.room.as_ref() {
+                room.update(cx, |room, cx| room.set_presence(presence, cx))
+                    .detach_and_log_err(cx);
+            }
+            cx.notify();
+        }
+    }
+
+    fn maintain_presence(cx: &mut ModelContext<Self>) -> Task<()> {
+        cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(PRESENCE_CHECK_INTERVAL)
+                    .await;
+                if this
+                    .update(&mut cx, |this, cx| this.update_presence(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
     fn set_room(
         &mut self,
         room: Option<Model<Room>>,
@@ -458,10 +524,16 @@ impl ActiveCall {
                         .and_then(|location| location.upgrade());
                     let channel_id = room.read(cx).channel_id();
                     cx.emit(Event::RoomJoined { channel_id });
-                    room.update(cx, |room, cx| room.set_location(location.as_ref(), cx))
+                    self.maintain_presence = Some(Self::maintain_presence(cx));
+                    let presence = self.presence;
+                    room.update(cx, |room, cx| {
+                        room.set_presence(presence, cx).detach_and_log_err(cx);
+                        room.set_location(location.as_ref(), cx)
+                    })
                 }
             } else {
                 self.room = None;
+                self.maintain_presence = None;
                 Task::ready(Ok(()))
             }
         }
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
-use client::ParticipantIndex;
 use client::{proto, User};
+use client::{ParticipantIndex, ParticipantPresence};
 use collections::HashMap;
 use gpui::WeakModel;
 pub use live_kit_client::Frame;
@@ -37,6 +37,7 @@ pub struct LocalParticipant {
     pub projects: Vec<proto::ParticipantProject>,
     pub active_project: Option<WeakModel<Project>>,
     pub role: proto::ChannelRole,
+    pub presence: ParticipantPresence,
 }
 
 #[derive(Clone, Debug)]
@@ -46,6 +47,7 @@ pub struct RemoteParticipant {
     pub role: proto::ChannelRole,
     pub projects: Vec<proto::ParticipantProject>,
     pub location: ParticipantLocation,
+    pub presence: ParticipantPresence,
     pub participant_index: ParticipantIndex,
     pub muted: bool,
     pub speaking: bool,
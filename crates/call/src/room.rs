@@ -6,7 +6,7 @@ use anyhow::{anyhow, Result};
 use audio::{Audio, Sound};
 use client::{
     proto::{self, PeerId},
-    ChannelId, Client, ParticipantIndex, TypedEnvelope, User, UserStore,
+    ChannelId, Client, ParticipantIndex, ParticipantPresence, TypedEnvelope, User, UserStore,
 };
 use collections::{BTreeMap, HashMap, HashSet};
 use fs::Fs;
@@ -829,6 +829,7 @@ impl Room {
                         }
 
                         let role = participant.role();
+                        let presence = ParticipantPresence::from_proto(participant.presence());
                         let location = ParticipantLocation::from_proto(participant.location)
                             .unwrap_or(ParticipantLocation::External);
                         if let Some(remote_participant) =
@@ -837,6 +838,7 @@ impl Room {
                             remote_participant.peer_id = peer_id;
                             remote_participant.projects = participant.projects;
                             remote_participant.participant_index = participant_index;
+                            remote_participant.presence = presence;
                             if location != remote_participant.location
                                 || role != remote_participant.role
                             {
@@ -855,6 +857,7 @@ impl Room {
                                     peer_id,
                                     projects: participant.projects,
                                     location,
+                                    presence,
                                     role,
                                     muted: true,
                                     speaking: false,
@@ -953,6 +956,12 @@ impl Room {
                         .map(|(user_id, participant)| (*user_id, participant.participant_index))
                         .collect();
                     user_store.set_participant_indices(participant_indices_by_user_id, cx);
+                    let participant_presences_by_user_id = this
+                        .remote_participants
+                        .iter()
+                        .map(|(user_id, participant)| (*user_id, participant.presence))
+                        .collect();
+                    user_store.set_participant_presences(participant_presences_by_user_id, cx);
                 });
 
                 this.check_invariants();
@@ -1304,6 +1313,33 @@ impl Room {
         })
     }
 
+    pub fn set_presence(
+        &mut self,
+        presence: ParticipantPresence,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.status.is_offline() {
+            return Task::ready(Err(anyhow!("room is offline")));
+        }
+        if self.local_participant.presence == presence {
+            return Task::ready(Ok(()));
+        }
+
+        self.local_participant.presence = presence;
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.notify();
+        cx.background_executor().spawn(async move {
+            client
+                .request(proto::UpdateParticipantPresence {
+                    room_id,
+                    presence: presence.to_proto().into(),
+                })
+                .await?;
+            Ok(())
+        })
+    }
+
     pub fn is_screen_sharing(&self) -> bool {
         self.live_kit.as_ref().map_or(false, |live_kit| {
             !matches!(live_kit.screen_track, LocalTrack::None)
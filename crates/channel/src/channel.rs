@@ -6,10 +6,12 @@ use client::{Client, UserStore};
 use gpui::{AppContext, Model};
 use std::sync::Arc;
 
-pub use channel_buffer::{ChannelBuffer, ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL};
+pub use channel_buffer::{
+    ChannelBuffer, ChannelBufferEvent, Comment, CommentThread, ACKNOWLEDGE_DEBOUNCE_INTERVAL,
+};
 pub use channel_chat::{
     mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId,
-    MessageParams,
+    ChannelMessageReaction, MessageParams,
 };
 pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
 
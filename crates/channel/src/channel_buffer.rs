@@ -1,15 +1,19 @@
 use crate::{Channel, ChannelStore};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use client::{ChannelId, Client, Collaborator, UserStore, ZED_ALWAYS_ACTIVE};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
-use language::proto::serialize_version;
+use language::{
+    proto::{deserialize_anchor, serialize_anchor, serialize_version},
+    Anchor,
+};
 use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, ops::Range, sync::Arc, time::Duration};
 use text::BufferId;
+use time::OffsetDateTime;
 use util::ResultExt;
 
 pub const ACKNOWLEDGE_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
@@ -17,6 +21,7 @@ pub const ACKNOWLEDGE_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
 pub(crate) fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelBuffer::handle_update_channel_buffer);
     client.add_model_message_handler(ChannelBuffer::handle_update_channel_buffer_collaborators);
+    client.add_model_message_handler(ChannelBuffer::handle_update_channel_buffer_comment_thread);
 }
 
 pub struct ChannelBuffer {
@@ -30,6 +35,24 @@ pub struct ChannelBuffer {
     client: Arc<Client>,
     subscription: Option<client::Subscription>,
     acknowledge_task: Option<Task<Result<()>>>,
+    comment_threads: Vec<CommentThread>,
+}
+
+/// A thread of review comments on a range of a channel buffer.
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    pub id: u64,
+    pub range: Range<Anchor>,
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub id: u64,
+    pub sender_id: u64,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
 }
 
 pub enum ChannelBufferEvent {
@@ -37,6 +60,7 @@ pub enum ChannelBufferEvent {
     Disconnected,
     BufferEdited,
     ChannelChanged,
+    CommentThreadsChanged,
 }
 
 impl EventEmitter<ChannelBufferEvent> for ChannelBuffer {}
@@ -84,8 +108,10 @@ impl ChannelBuffer {
                 subscription: Some(subscription.set_model(&cx.handle(), &mut cx.to_async())),
                 user_store,
                 channel_store,
+                comment_threads: Vec::new(),
             };
             this.replace_collaborators(response.collaborators, cx);
+            this.replace_comment_threads(response.comment_threads, cx);
             this
         })?)
     }
@@ -135,6 +161,131 @@ impl ChannelBuffer {
         cx.notify();
     }
 
+    pub(crate) fn replace_comment_threads(
+        &mut self,
+        threads: Vec<proto::ChannelBufferCommentThread>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.comment_threads = threads
+            .into_iter()
+            .filter_map(|thread| CommentThread::from_proto(thread).log_err())
+            .collect();
+        self.comment_threads.sort_by_key(|thread| thread.id);
+        self.fetch_comment_senders(cx);
+        cx.emit(ChannelBufferEvent::CommentThreadsChanged);
+        cx.notify();
+    }
+
+    fn insert_comment_thread(
+        &mut self,
+        thread: proto::ChannelBufferCommentThread,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let thread = CommentThread::from_proto(thread)?;
+        match self
+            .comment_threads
+            .binary_search_by_key(&thread.id, |thread| thread.id)
+        {
+            Ok(ix) => self.comment_threads[ix] = thread,
+            Err(ix) => self.comment_threads.insert(ix, thread),
+        }
+        self.fetch_comment_senders(cx);
+        cx.emit(ChannelBufferEvent::CommentThreadsChanged);
+        cx.notify();
+        Ok(())
+    }
+
+    fn fetch_comment_senders(&mut self, cx: &mut ModelContext<Self>) {
+        let sender_ids = self
+            .comment_threads
+            .iter()
+            .flat_map(|thread| thread.comments.iter().map(|comment| comment.sender_id))
+            .collect::<HashSet<_>>();
+        if sender_ids.is_empty() {
+            return;
+        }
+        self.user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(sender_ids.into_iter().collect(), cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    pub fn comment_threads(&self) -> &[CommentThread] {
+        &self.comment_threads
+    }
+
+    pub fn unresolved_comment_thread_count(&self) -> usize {
+        self.comment_threads
+            .iter()
+            .filter(|thread| !thread.resolved)
+            .count()
+    }
+
+    /// Starts a new comment thread on the given range of the buffer.
+    pub fn create_comment_thread(
+        &mut self,
+        range: Range<Anchor>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self
+            .client
+            .request(proto::CreateChannelBufferCommentThread {
+                channel_id: self.channel_id.0,
+                start: Some(serialize_anchor(&range.start)),
+                end: Some(serialize_anchor(&range.end)),
+                body,
+            });
+        self.handle_comment_thread_response(request, cx)
+    }
+
+    pub fn reply_to_comment_thread(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self
+            .client
+            .request(proto::ReplyToChannelBufferCommentThread {
+                channel_id: self.channel_id.0,
+                thread_id,
+                body,
+            });
+        self.handle_comment_thread_response(request, cx)
+    }
+
+    pub fn set_comment_thread_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self
+            .client
+            .request(proto::ResolveChannelBufferCommentThread {
+                channel_id: self.channel_id.0,
+                thread_id,
+                resolved,
+            });
+        self.handle_comment_thread_response(request, cx)
+    }
+
+    fn handle_comment_thread_response(
+        &mut self,
+        request: impl 'static + Future<Output = Result<proto::ChannelBufferCommentThreadResponse>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("missing comment thread"))?;
+            this.update(&mut cx, |this, cx| this.insert_comment_thread(thread, cx))?
+        })
+    }
+
     async fn handle_update_channel_buffer(
         this: Model<Self>,
         update_channel_buffer: TypedEnvelope<proto::UpdateChannelBuffer>,
@@ -170,6 +321,19 @@ impl ChannelBuffer {
         })
     }
 
+    async fn handle_update_channel_buffer_comment_thread(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::UpdateChannelBufferCommentThread>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = message
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("missing comment thread"))?;
+        this.update(&mut cx, |this, cx| this.insert_comment_thread(thread, cx))?
+    }
+
     fn on_buffer_update(
         &mut self,
         _: Model<language::Buffer>,
@@ -267,3 +431,33 @@ impl ChannelBuffer {
         self.buffer.read(cx).replica_id()
     }
 }
+
+impl CommentThread {
+    fn from_proto(thread: proto::ChannelBufferCommentThread) -> Result<Self> {
+        let start = thread
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid comment thread start"))?;
+        let end = thread
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid comment thread end"))?;
+        Ok(Self {
+            id: thread.id,
+            range: start..end,
+            comments: thread
+                .comments
+                .into_iter()
+                .map(|comment| {
+                    Ok(Comment {
+                        id: comment.id,
+                        sender_id: comment.sender_id,
+                        body: comment.body,
+                        timestamp: OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            resolved: thread.resolved,
+        })
+    }
+}
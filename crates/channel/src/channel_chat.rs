@@ -5,7 +5,7 @@ use client::{
     user::{User, UserStore},
     ChannelId, Client, Subscription, TypedEnvelope, UserId,
 };
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use futures::lock::Mutex;
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
@@ -22,6 +22,7 @@ use util::{post_inc, ResultExt as _, TryFutureExt};
 pub struct ChannelChat {
     pub channel_id: ChannelId,
     messages: SumTree<ChannelMessage>,
+    threads: HashMap<u64, Vec<ChannelMessage>>,
     acknowledged_message_ids: HashSet<u64>,
     channel_store: Model<ChannelStore>,
     loaded_all_messages: bool,
@@ -52,6 +53,15 @@ pub struct ChannelMessage {
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
     pub edited_at: Option<OffsetDateTime>,
+    pub thread_root_id: Option<u64>,
+    pub thread_reply_count: usize,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -99,6 +109,7 @@ pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
     client.add_model_message_handler(ChannelChat::handle_message_updated);
+    client.add_model_message_handler(ChannelChat::handle_reactions_updated);
 }
 
 impl ChannelChat {
@@ -127,6 +138,7 @@ impl ChannelChat {
                 rpc: client.clone(),
                 outgoing_messages_lock: Default::default(),
                 messages: Default::default(),
+                threads: Default::default(),
                 acknowledged_message_ids: Default::default(),
                 loaded_all_messages: false,
                 next_pending_message_id: 0,
@@ -196,6 +208,9 @@ impl ChannelChat {
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
                     edited_at: None,
+                    thread_root_id: None,
+                    thread_reply_count: 0,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -214,6 +229,7 @@ impl ChannelChat {
                 nonce: Some(nonce.into()),
                 mentions: mentions_to_proto(&message.mentions),
                 reply_to_message_id: message.reply_to_message_id,
+                thread_root_id: None,
             });
             let response = request.await?;
             drop(outgoing_message_guard);
@@ -230,6 +246,99 @@ impl ChannelChat {
         }))
     }
 
+    /// Sends a reply in the thread started by the given message.
+    pub fn send_thread_reply(
+        &mut self,
+        thread_root_id: u64,
+        message: MessageParams,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Task<Result<u64>>> {
+        if message.text.trim().is_empty() {
+            Err(anyhow!("message body can't be empty"))?;
+        }
+
+        let nonce: u128 = self.rng.gen();
+        let request = self.rpc.request(proto::SendChannelMessage {
+            channel_id: self.channel_id.0,
+            body: message.text,
+            nonce: Some(nonce.into()),
+            mentions: mentions_to_proto(&message.mentions),
+            reply_to_message_id: message.reply_to_message_id,
+            thread_root_id: Some(thread_root_id),
+        });
+        let user_store = self.user_store.clone();
+        Ok(cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let response = response.message.ok_or_else(|| anyhow!("invalid message"))?;
+            let id = response.id;
+            let message = ChannelMessage::from_proto(response, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.insert_thread_reply(thread_root_id, message, cx)
+            })?;
+            Ok(id)
+        }))
+    }
+
+    /// Loads the replies in the thread started by the given message.
+    pub fn load_thread(
+        &mut self,
+        thread_root_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::GetChannelMessageThread {
+            channel_id: self.channel_id.0,
+            thread_root_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let replies =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                let reply_count = replies.len();
+                this.threads.insert(thread_root_id, replies);
+                this.update_message_in_place(ChannelMessageId::Saved(thread_root_id), cx, |root| {
+                    root.thread_reply_count = reply_count
+                });
+            })
+        })
+    }
+
+    /// The replies in the thread started by the given message, if they've been loaded.
+    pub fn thread_replies(&self, thread_root_id: u64) -> Option<&[ChannelMessage]> {
+        self.threads.get(&thread_root_id).map(Vec::as_slice)
+    }
+
+    /// Adds the current user's reaction to a message, or removes it if they've
+    /// already reacted with the same emoji.
+    pub fn toggle_reaction(
+        &mut self,
+        message_id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(user_id) = self.rpc.user_id() else {
+            return Task::ready(Err(anyhow!("not signed in")));
+        };
+        let reacted = !self
+            .find_loaded_message(message_id)
+            .map_or(false, |message| message.has_reacted(user_id, &emoji));
+        self.update_message_in_place(ChannelMessageId::Saved(message_id), cx, |message| {
+            message.set_reaction(user_id, &emoji, reacted)
+        });
+
+        let request = self.rpc.request(proto::SetChannelMessageReaction {
+            channel_id: self.channel_id.0,
+            message_id,
+            emoji,
+            reacted,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
     pub fn remove_message(&mut self, id: u64, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
         let response = self.rpc.request(proto::RemoveChannelMessage {
             channel_id: self.channel_id.0,
@@ -312,10 +421,13 @@ impl ChannelChat {
 
     /// Load a message by its id, if it's already stored locally.
     pub fn find_loaded_message(&self, id: u64) -> Option<&ChannelMessage> {
-        self.messages.iter().find(|message| match message.id {
-            ChannelMessageId::Saved(message_id) => message_id == id,
-            ChannelMessageId::Pending(_) => false,
-        })
+        self.messages
+            .iter()
+            .chain(self.threads.values().flatten())
+            .find(|message| match message.id {
+                ChannelMessageId::Saved(message_id) => message_id == id,
+                ChannelMessageId::Pending(_) => false,
+            })
     }
 
     /// Load all of the chat messages since a certain message id.
@@ -468,6 +580,7 @@ impl ChannelChat {
                         mentions: mentions_to_proto(&pending_message.mentions),
                         nonce: Some(pending_message.nonce.into()),
                         reply_to_message_id: pending_message.reply_to_message_id,
+                        thread_root_id: None,
                     });
                     let response = request.await?;
                     let message = ChannelMessage::from_proto(
@@ -481,6 +594,14 @@ impl ChannelChat {
                     })?;
                 }
 
+                let thread_root_ids = this.update(&mut cx, |this, _| {
+                    this.threads.keys().copied().collect::<Vec<_>>()
+                })?;
+                for thread_root_id in thread_root_ids {
+                    this.update(&mut cx, |this, cx| this.load_thread(thread_root_id, cx))?
+                        .await?;
+                }
+
                 anyhow::Ok(())
             }
             .log_err()
@@ -502,6 +623,16 @@ impl ChannelChat {
         cursor.item().unwrap()
     }
 
+    /// The position of the given message in the channel's timeline, if it's loaded.
+    pub fn message_ix(&self, id: ChannelMessageId) -> Option<usize> {
+        let mut cursor = self.messages.cursor::<(ChannelMessageId, Count)>();
+        cursor.seek(&id, Bias::Left, &());
+        cursor
+            .item()
+            .filter(|message| message.id == id)
+            .map(|_| cursor.start().1 .0)
+    }
+
     pub fn acknowledge_message(&mut self, id: u64) {
         if self.acknowledged_message_ids.insert(id) {
             self.rpc
@@ -540,11 +671,15 @@ impl ChannelChat {
 
         let message = ChannelMessage::from_proto(message, &user_store, &mut cx).await?;
         this.update(&mut cx, |this, cx| {
-            this.insert_messages(SumTree::from_item(message, &()), cx);
-            cx.emit(ChannelChatEvent::NewMessage {
-                channel_id: this.channel_id,
-                message_id,
-            })
+            if let Some(thread_root_id) = message.thread_root_id {
+                this.insert_thread_reply(thread_root_id, message, cx);
+            } else {
+                this.insert_messages(SumTree::from_item(message, &()), cx);
+                cx.emit(ChannelChatEvent::NewMessage {
+                    channel_id: this.channel_id,
+                    message_id,
+                })
+            }
         })?;
 
         Ok(())
@@ -588,6 +723,94 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_reactions_updated(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ChannelMessageReactionsUpdated>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let reactions = reactions_from_proto(envelope.payload.reactions);
+        this.update(&mut cx, |this, cx| {
+            this.update_message_in_place(
+                ChannelMessageId::Saved(envelope.payload.message_id),
+                cx,
+                |message| message.reactions = reactions,
+            )
+        })?;
+        Ok(())
+    }
+
+    fn insert_thread_reply(
+        &mut self,
+        thread_root_id: u64,
+        reply: ChannelMessage,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(replies) = self.threads.get_mut(&thread_root_id) {
+            if replies.iter().any(|existing| existing.id == reply.id) {
+                return;
+            }
+            let ix = replies.partition_point(|existing| existing.id < reply.id);
+            replies.insert(ix, reply);
+        }
+        self.update_message_in_place(ChannelMessageId::Saved(thread_root_id), cx, |root| {
+            root.thread_reply_count += 1
+        });
+    }
+
+    fn thread_root_for_reply(&self, reply_id: u64) -> Option<u64> {
+        self.threads.iter().find_map(|(thread_root_id, replies)| {
+            replies
+                .iter()
+                .any(|reply| reply.id == ChannelMessageId::Saved(reply_id))
+                .then_some(*thread_root_id)
+        })
+    }
+
+    /// Applies an update to a loaded message, whether it's in the timeline or in a
+    /// thread. Changes to thread replies are reported as changes to their thread root.
+    fn update_message_in_place(
+        &mut self,
+        id: ChannelMessageId,
+        cx: &mut ModelContext<Self>,
+        update: impl FnOnce(&mut ChannelMessage),
+    ) {
+        let thread_root_id = match id {
+            ChannelMessageId::Saved(id) => self.thread_root_for_reply(id),
+            ChannelMessageId::Pending(_) => None,
+        };
+        let list_item_id = if let Some(thread_root_id) = thread_root_id {
+            if let Some(reply) = self
+                .threads
+                .get_mut(&thread_root_id)
+                .and_then(|replies| replies.iter_mut().find(|reply| reply.id == id))
+            {
+                update(reply);
+            }
+            ChannelMessageId::Saved(thread_root_id)
+        } else {
+            let mut cursor = self.messages.cursor::<ChannelMessageId>();
+            let mut messages = cursor.slice(&id, Bias::Left, &());
+            if let Some(mut message) = cursor.item().filter(|m| m.id == id).cloned() {
+                update(&mut message);
+                messages.push(message, &());
+                cursor.next(&());
+            }
+            messages.append(cursor.suffix(&()), &());
+            drop(cursor);
+            self.messages = messages;
+            id
+        };
+
+        if let Some(message_ix) = self.message_ix(list_item_id) {
+            cx.emit(ChannelChatEvent::UpdateMessage {
+                message_id: id,
+                message_ix,
+            });
+        }
+        cx.notify();
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
@@ -648,6 +871,17 @@ impl ChannelChat {
     }
 
     fn message_removed(&mut self, id: u64, cx: &mut ModelContext<Self>) {
+        self.threads.remove(&id);
+        if let Some(thread_root_id) = self.thread_root_for_reply(id) {
+            if let Some(replies) = self.threads.get_mut(&thread_root_id) {
+                replies.retain(|reply| reply.id != ChannelMessageId::Saved(id));
+            }
+            self.update_message_in_place(ChannelMessageId::Saved(thread_root_id), cx, |root| {
+                root.thread_reply_count = root.thread_reply_count.saturating_sub(1)
+            });
+            return;
+        }
+
         let mut cursor = self.messages.cursor::<ChannelMessageId>();
         let mut messages = cursor.slice(&ChannelMessageId::Saved(id), Bias::Left, &());
         if let Some(item) = cursor.item() {
@@ -687,28 +921,11 @@ impl ChannelChat {
         edited_at: Option<OffsetDateTime>,
         cx: &mut ModelContext<Self>,
     ) {
-        let mut cursor = self.messages.cursor::<ChannelMessageId>();
-        let mut messages = cursor.slice(&id, Bias::Left, &());
-        let ix = messages.summary().count;
-
-        if let Some(mut message_to_update) = cursor.item().cloned() {
-            message_to_update.body = body;
-            message_to_update.mentions = mentions;
-            message_to_update.edited_at = edited_at;
-            messages.push(message_to_update, &());
-            cursor.next(&());
-        }
-
-        messages.append(cursor.suffix(&()), &());
-        drop(cursor);
-        self.messages = messages;
-
-        cx.emit(ChannelChatEvent::UpdateMessage {
-            message_ix: ix,
-            message_id: id,
+        self.update_message_in_place(id, cx, |message| {
+            message.body = body;
+            message.mentions = mentions;
+            message.edited_at = edited_at;
         });
-
-        cx.notify();
     }
 }
 
@@ -762,6 +979,9 @@ impl ChannelMessage {
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
             edited_at,
+            thread_root_id: message.thread_root_id,
+            thread_reply_count: message.thread_reply_count as usize,
+            reactions: reactions_from_proto(message.reactions),
         })
     }
 
@@ -769,6 +989,29 @@ impl ChannelMessage {
         matches!(self.id, ChannelMessageId::Pending(_))
     }
 
+    pub fn has_reacted(&self, user_id: UserId, emoji: &str) -> bool {
+        self.reactions
+            .iter()
+            .any(|reaction| reaction.emoji == emoji && reaction.user_ids.contains(&user_id))
+    }
+
+    fn set_reaction(&mut self, user_id: UserId, emoji: &str, reacted: bool) {
+        if let Some(ix) = self.reactions.iter().position(|r| r.emoji == emoji) {
+            let reaction = &mut self.reactions[ix];
+            reaction.user_ids.retain(|id| *id != user_id);
+            if reacted {
+                reaction.user_ids.push(user_id);
+            } else if reaction.user_ids.is_empty() {
+                self.reactions.remove(ix);
+            }
+        } else if reacted {
+            self.reactions.push(ChannelMessageReaction {
+                emoji: emoji.to_string(),
+                user_ids: vec![user_id],
+            });
+        }
+    }
+
     pub async fn from_proto_vec(
         proto_messages: Vec<proto::ChannelMessage>,
         user_store: &Model<UserStore>,
@@ -776,7 +1019,12 @@ impl ChannelMessage {
     ) -> Result<Vec<Self>> {
         let unique_user_ids = proto_messages
             .iter()
-            .map(|m| m.sender_id)
+            .flat_map(|m| {
+                m.reactions
+                    .iter()
+                    .flat_map(|reaction| reaction.user_ids.iter().copied())
+                    .chain([m.sender_id])
+            })
             .collect::<HashSet<_>>()
             .into_iter()
             .collect();
@@ -807,6 +1055,16 @@ pub fn mentions_to_proto(mentions: &[(Range<usize>, UserId)]) -> Vec<proto::Chat
         .collect()
 }
 
+fn reactions_from_proto(reactions: Vec<proto::ChatReaction>) -> Vec<ChannelMessageReaction> {
+    reactions
+        .into_iter()
+        .map(|reaction| ChannelMessageReaction {
+            emoji: reaction.emoji,
+            user_ids: reaction.user_ids,
+        })
+        .collect()
+}
+
 impl sum_tree::Item for ChannelMessage {
     type Summary = ChannelMessageSummary;
 
@@ -983,6 +983,10 @@ impl ChannelStore {
                                     mem::take(&mut remote_buffer.collaborators),
                                     cx,
                                 );
+                                channel_buffer.replace_comment_threads(
+                                    mem::take(&mut remote_buffer.comment_threads),
+                                    cx,
+                                );
 
                                 let operations = channel_buffer
                                     .buffer()
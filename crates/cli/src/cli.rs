@@ -11,8 +11,21 @@ pub struct IpcHandshake {
 pub enum CliRequest {
     Open {
         paths: Vec<String>,
+        /// Pairs of `[old, new]` paths to open in a diff view.
+        #[serde(default)]
+        diff_paths: Vec<[String; 2]>,
         wait: bool,
         open_new_workspace: Option<bool>,
+        /// Whether to open a new buffer holding the text piped into the CLI's stdin,
+        /// which is sent afterwards in [`CliRequest::StdinChunk`]s.
+        #[serde(default)]
+        open_stdin: bool,
+        /// The name or file extension of the language of the stdin buffer.
+        #[serde(default)]
+        stdin_language: Option<String>,
+    },
+    StdinChunk {
+        text: String,
     },
 }
 
@@ -8,14 +8,22 @@ use std::{
     env,
     ffi::OsStr,
     fs,
+    io::{self, Read},
     path::{Path, PathBuf},
+    thread,
 };
 use util::paths::PathLikeWithPosition;
 
+/// The path argument that stands for stdin.
+const STDIN_PATH: &str = "-";
+const STDIN_CHUNK_SIZE: usize = 64 * 1024;
+
 #[derive(Parser, Debug)]
 #[command(name = "zed", disable_version_flag = true)]
 struct Args {
     /// Wait for all of the given paths to be opened/closed before exiting.
+    ///
+    /// This allows using Zed as `$EDITOR`, e.g. for writing git commit messages.
     #[arg(short, long)]
     wait: bool,
     /// Add files to the currently open workspace
@@ -28,8 +36,18 @@ struct Args {
     ///
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
+    ///
+    /// Pass `-` to open the text piped into stdin in a new buffer.
     #[arg(value_parser = parse_path_with_position)]
     paths_with_position: Vec<PathLikeWithPosition<PathBuf>>,
+    /// Open a view showing the changes between two files.
+    ///
+    /// Can be passed multiple times to compare several pairs of files.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
+    diff: Vec<PathBuf>,
+    /// The language of the buffer opened for stdin, as a language name or file extension.
+    #[arg(short, long)]
+    language: Option<String>,
     /// Print Zed's version and the app path.
     #[arg(short, long)]
     version: bool,
@@ -74,26 +92,18 @@ fn main() -> Result<()> {
 
     let curdir = env::current_dir()?;
     let mut paths = vec![];
+    let mut open_stdin = false;
     for path in args.paths_with_position {
-        let canonicalized = path.map_path_like(|path| match fs::canonicalize(&path) {
-            Ok(path) => Ok(path),
-            Err(e) => {
-                if let Some(mut parent) = path.parent() {
-                    if parent == Path::new("") {
-                        parent = &curdir;
-                    }
-                    match fs::canonicalize(parent) {
-                        Ok(parent) => Ok(parent.join(path.file_name().unwrap())),
-                        Err(_) => Err(e),
-                    }
-                } else {
-                    Err(e)
-                }
-            }
-        })?;
+        if path.path_like == Path::new(STDIN_PATH) {
+            open_stdin = true;
+            continue;
+        }
+        let canonicalized = path.map_path_like(|path| canonicalize(&path, &curdir))?;
         paths.push(canonicalized.to_string(|path| path.display().to_string()))
     }
 
+    let diff_paths = diff_paths(&args.diff, &curdir)?;
+
     let (tx, rx) = bundle.launch()?;
     let open_new_workspace = if args.new {
         Some(true)
@@ -105,22 +115,112 @@ fn main() -> Result<()> {
 
     tx.send(CliRequest::Open {
         paths,
+        diff_paths,
         wait: args.wait,
         open_new_workspace,
+        open_stdin,
+        stdin_language: args.language,
     })?;
 
+    let stdin_thread = open_stdin.then(|| {
+        thread::spawn(move || {
+            let result = forward_stdin(io::stdin().lock(), |text| {
+                Ok(tx.send(CliRequest::StdinChunk { text })?)
+            });
+            if let Err(error) = result {
+                eprintln!("error reading stdin: {error:#}");
+            }
+        })
+    });
+
     while let Ok(response) = rx.recv() {
         match response {
             CliResponse::Ping => {}
             CliResponse::Stdout { message } => println!("{message}"),
             CliResponse::Stderr { message } => eprintln!("{message}"),
-            CliResponse::Exit { status } => std::process::exit(status),
+            CliResponse::Exit { status } => {
+                // Exiting kills the forwarding thread, so let it send the rest of stdin first.
+                if let Some(stdin_thread) = stdin_thread {
+                    stdin_thread.join().ok();
+                }
+                std::process::exit(status)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads `reader` to its end, passing its contents to `send` in chunks of valid UTF-8
+/// so that large inputs show up in the editor while they're still being written.
+fn forward_stdin(mut reader: impl Read, mut send: impl FnMut(String) -> Result<()>) -> Result<()> {
+    let mut buffer = vec![0; STDIN_CHUNK_SIZE];
+    let mut pending = Vec::new();
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        pending.extend_from_slice(&buffer[..len]);
+
+        // Hold back a character that was split across reads, until the rest of it arrives.
+        let complete_len = match std::str::from_utf8(&pending) {
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            _ => pending.len(),
+        };
+        let incomplete = pending.split_off(complete_len);
+        if !pending.is_empty() {
+            let text = String::from_utf8_lossy(&pending).into_owned();
+            send(text)?;
         }
+        pending = incomplete;
     }
 
+    if !pending.is_empty() {
+        let text = String::from_utf8_lossy(&pending).into_owned();
+        send(text)?;
+    }
     Ok(())
 }
 
+/// Groups the paths passed to `--diff` into `[old, new]` pairs of canonical paths.
+fn diff_paths(paths: &[PathBuf], curdir: &Path) -> Result<Vec<[String; 2]>> {
+    let mut diff_paths = vec![];
+    for pair in paths.chunks(2) {
+        let [old_path, new_path] = pair else {
+            anyhow::bail!("--diff expects exactly two paths");
+        };
+        diff_paths.push([
+            canonicalize(old_path, curdir)?.display().to_string(),
+            canonicalize(new_path, curdir)?.display().to_string(),
+        ]);
+    }
+    Ok(diff_paths)
+}
+
+/// Canonicalizes `path`, falling back to canonicalizing its parent directory for paths
+/// that don't exist yet.
+fn canonicalize(path: &Path, curdir: &Path) -> std::io::Result<PathBuf> {
+    match fs::canonicalize(path) {
+        Ok(path) => Ok(path),
+        Err(e) => {
+            if let Some(mut parent) = path.parent() {
+                if parent == Path::new("") {
+                    parent = curdir;
+                }
+                match fs::canonicalize(parent) {
+                    Ok(parent) => Ok(parent.join(path.file_name().unwrap())),
+                    Err(_) => Err(e),
+                }
+            } else {
+                Err(e)
+            }
+        }
+    }
+}
+
 enum Bundle {
     App {
         app_bundle: PathBuf,
@@ -388,3 +488,63 @@ mod mac_os {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_stdin_in_chunks() {
+        // Offset the multi-byte characters by one byte, so that they're split across reads.
+        let input = format!("a{}", "é€".repeat(STDIN_CHUNK_SIZE));
+        let mut chunks = Vec::new();
+        forward_stdin(input.as_bytes(), |text| {
+            chunks.push(text);
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(chunks.len() > 1);
+        assert!(chunks
+            .iter()
+            .all(|chunk| !chunk.contains(char::REPLACEMENT_CHARACTER)));
+        assert_eq!(chunks.concat(), input);
+    }
+
+    #[test]
+    fn test_diff_args() {
+        let args = Args::try_parse_from(["zed", "--diff", "a.txt", "b.txt", "c.txt"]).unwrap();
+        assert_eq!(args.diff, [PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+        assert_eq!(args.paths_with_position.len(), 1);
+
+        let args = Args::try_parse_from([
+            "zed", "--diff", "a.txt", "b.txt", "--diff", "c.txt", "d.txt",
+        ])
+        .unwrap();
+        assert_eq!(args.diff.len(), 4);
+        assert!(Args::try_parse_from(["zed", "--diff", "a.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_diff_paths() {
+        // Paths that don't exist yet are resolved relative to their parent directory.
+        let curdir = fs::canonicalize(env::temp_dir()).unwrap();
+        let pairs = diff_paths(
+            &[
+                PathBuf::from("missing-old.txt"),
+                curdir.join("missing-new.txt"),
+            ],
+            &curdir,
+        )
+        .unwrap();
+        assert_eq!(
+            pairs,
+            [[
+                curdir.join("missing-old.txt").display().to_string(),
+                curdir.join("missing-new.txt").display().to_string(),
+            ]]
+        );
+
+        assert!(diff_paths(&[PathBuf::from("a.txt")], &curdir).is_err());
+    }
+}
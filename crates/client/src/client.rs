@@ -36,6 +36,7 @@ use std::{
     fmt::Write as _,
     future::Future,
     marker::PhantomData,
+    mem,
     path::PathBuf,
     sync::{
         atomic::{AtomicU64, Ordering},
@@ -46,7 +47,7 @@ use std::{
 use telemetry::Telemetry;
 use thiserror::Error;
 use url::Url;
-use util::http::{HttpClient, HttpClientWithUrl};
+use util::http::{HttpClient, HttpClientConfig, HttpClientWithUrl, Uri};
 use util::{ResultExt, TryFutureExt};
 
 pub use rpc::*;
@@ -80,7 +81,7 @@ lazy_static! {
 pub const INITIAL_RECONNECTION_DELAY: Duration = Duration::from_millis(100);
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
-actions!(client, [SignIn, SignOut, Reconnect]);
+actions!(client, [SignIn, SignOut, Reconnect, ToggleOffline]);
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ClientSettingsContent {
@@ -106,10 +107,74 @@ impl Settings for ClientSettings {
     }
 }
 
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkSettingsContent {
+    /// The proxy to send requests through, like `http://proxy.example.com:8080` or
+    /// `socks5://localhost:1080`. When unset, the `ALL_PROXY`, `HTTPS_PROXY` and
+    /// `HTTP_PROXY` environment variables are used.
+    ///
+    /// Default: null
+    pub proxy: Option<String>,
+    /// The hosts to connect to directly instead of through the proxy. When unset,
+    /// the `NO_PROXY` environment variable is used.
+    ///
+    /// Default: null
+    pub no_proxy: Option<Vec<String>>,
+    /// A PEM file of certificate authorities that replaces the system's trust
+    /// store, for networks that intercept TLS connections. Servers whose
+    /// certificates aren't signed by one of them are no longer trusted.
+    ///
+    /// Default: null
+    pub replace_ca_certificates: Option<PathBuf>,
+    /// Whether to work offline, which stops Zed from making any requests to
+    /// other machines, while services on this machine like Ollama keep working.
+    ///
+    /// Default: false
+    pub offline: Option<bool>,
+}
+
+/// How Zed connects to the network, which applies to every HTTP request it makes.
+#[derive(Clone, Default, Deserialize)]
+pub struct NetworkSettings {
+    pub proxy: Option<String>,
+    pub no_proxy: Option<Vec<String>>,
+    pub replace_ca_certificates: Option<PathBuf>,
+    pub offline: bool,
+}
+
+impl NetworkSettings {
+    pub fn http_client_config(&self) -> HttpClientConfig {
+        let mut config = HttpClientConfig::from_env();
+        if let Some(proxy) = &self.proxy {
+            match proxy.parse::<Uri>() {
+                Ok(proxy) => config.proxy = Some(proxy),
+                Err(error) => log::error!("invalid proxy {proxy:?}: {error}"),
+            }
+        }
+        if let Some(no_proxy) = &self.no_proxy {
+            config.no_proxy = no_proxy.clone();
+        }
+        config.replace_ca_certificates = self.replace_ca_certificates.clone();
+        config.offline = self.offline;
+        config
+    }
+}
+
+impl Settings for NetworkSettings {
+    const KEY: Option<&'static str> = Some("network");
+
+    type FileContent = NetworkSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
 pub fn init_settings(cx: &mut AppContext) {
     TelemetrySettings::register(cx);
     cx.update_global(|store: &mut SettingsStore, cx| {
         store.register_setting::<ClientSettings>(cx);
+        store.register_setting::<NetworkSettings>(cx);
     });
 }
 
@@ -150,6 +215,38 @@ pub fn init(client: &Arc<Client>, cx: &mut AppContext) {
             }
         }
     });
+
+    // Whether the connection was closed when going offline, so that it can be
+    // reopened when going back online.
+    let mut disconnected_by_offline = false;
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let Some(client) = client.upgrade() else {
+            return;
+        };
+        let was_offline = client.http.is_offline();
+        client
+            .http
+            .set_config(NetworkSettings::get_global(cx).http_client_config());
+        match (was_offline, client.http.is_offline()) {
+            (false, true) => {
+                disconnected_by_offline = !matches!(
+                    *client.status().borrow(),
+                    Status::SignedOut | Status::UpgradeRequired
+                );
+                if disconnected_by_offline {
+                    client.disconnect(&cx.to_async());
+                }
+            }
+            (true, false) if mem::take(&mut disconnected_by_offline) => {
+                cx.spawn(
+                    |cx| async move { client.authenticate_and_connect(true, &cx).log_err().await },
+                )
+                .detach();
+            }
+            _ => {}
+        }
+    })
+    .detach();
 }
 
 struct GlobalClient(Arc<Client>);
@@ -459,8 +556,9 @@ impl Client {
 
     pub fn production(cx: &mut AppContext) -> Arc<Self> {
         let clock = Arc::new(clock::RealSystemClock);
-        let http = Arc::new(HttpClientWithUrl::new(
+        let http = Arc::new(HttpClientWithUrl::with_config(
             &ClientSettings::get_global(cx).server_url,
+            NetworkSettings::get_global(cx).http_client_config(),
         ));
         Self::new(clock, http.clone(), cx)
     }
@@ -790,6 +888,9 @@ impl Client {
             }
             Status::UpgradeRequired => return Err(EstablishConnectionError::UpgradeRequired)?,
         };
+        if self.http.is_offline() {
+            return Err(anyhow!("can't connect to Zed while offline"));
+        }
         if was_disconnected {
             self.set_status(Status::Authenticating, cx);
         } else {
@@ -1829,6 +1930,32 @@ mod tests {
         done_rx.next().await.unwrap();
     }
 
+    #[test]
+    fn test_network_settings_http_client_config() {
+        let settings = NetworkSettings {
+            proxy: Some("socks5://localhost:1080".into()),
+            no_proxy: Some(vec!["internal.example.com".into()]),
+            replace_ca_certificates: Some("/etc/ssl/corporate.pem".into()),
+            offline: true,
+        };
+        assert_eq!(
+            settings.http_client_config(),
+            HttpClientConfig {
+                proxy: Some("socks5://localhost:1080".parse().unwrap()),
+                no_proxy: vec!["internal.example.com".into()],
+                replace_ca_certificates: Some("/etc/ssl/corporate.pem".into()),
+                offline: true,
+            }
+        );
+
+        // Unset and invalid settings fall back to the environment.
+        let settings = NetworkSettings {
+            proxy: Some("not a proxy".into()),
+            ..Default::default()
+        };
+        assert_eq!(settings.http_client_config(), HttpClientConfig::from_env());
+    }
+
     #[derive(Default)]
     struct TestModel {
         id: usize,
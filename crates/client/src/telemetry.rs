@@ -417,7 +417,9 @@ impl Telemetry {
         let mut events = mem::take(&mut state.events_queue);
         state.flush_events_task.take();
         drop(state);
-        if events.is_empty() {
+        // Events aren't kept for later while offline, to avoid sending a backlog of
+        // them all at once when the user goes back online.
+        if events.is_empty() || self.http_client.is_offline() {
             return;
         }
 
@@ -38,6 +38,33 @@ pub struct RemoteProjectId(pub u64);
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ParticipantIndex(pub u32);
 
+/// Whether a call participant has recently been using Zed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParticipantPresence {
+    #[default]
+    Active,
+    Idle,
+    Away,
+}
+
+impl ParticipantPresence {
+    pub fn from_proto(presence: proto::ParticipantPresence) -> Self {
+        match presence {
+            proto::ParticipantPresence::Active => Self::Active,
+            proto::ParticipantPresence::Idle => Self::Idle,
+            proto::ParticipantPresence::Away => Self::Away,
+        }
+    }
+
+    pub fn to_proto(self) -> proto::ParticipantPresence {
+        match self {
+            Self::Active => proto::ParticipantPresence::Active,
+            Self::Idle => proto::ParticipantPresence::Idle,
+            Self::Away => proto::ParticipantPresence::Away,
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct User {
     pub id: UserId,
@@ -90,6 +117,7 @@ pub enum ContactRequestStatus {
 pub struct UserStore {
     users: HashMap<u64, Arc<User>>,
     participant_indices: HashMap<u64, ParticipantIndex>,
+    participant_presences: HashMap<u64, ParticipantPresence>,
     update_contacts_tx: mpsc::UnboundedSender<UpdateContacts>,
     current_user: watch::Receiver<Option<Arc<User>>>,
     contacts: Vec<Arc<Contact>>,
@@ -116,6 +144,7 @@ pub enum Event {
     },
     ShowContacts,
     ParticipantIndicesChanged,
+    ParticipantPresencesChanged,
 }
 
 #[derive(Clone, Copy)]
@@ -148,6 +177,7 @@ impl UserStore {
             contacts: Default::default(),
             incoming_contact_requests: Default::default(),
             participant_indices: Default::default(),
+            participant_presences: Default::default(),
             outgoing_contact_requests: Default::default(),
             invite_info: None,
             client: Arc::downgrade(&client),
@@ -696,6 +726,21 @@ impl UserStore {
         &self.participant_indices
     }
 
+    pub fn set_participant_presences(
+        &mut self,
+        participant_presences: HashMap<u64, ParticipantPresence>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if participant_presences != self.participant_presences {
+            self.participant_presences = participant_presences;
+            cx.emit(Event::ParticipantPresencesChanged);
+        }
+    }
+
+    pub fn participant_presences(&self) -> &HashMap<u64, ParticipantPresence> {
+        &self.participant_presences
+    }
+
     pub fn participant_names(
         &self,
         user_ids: impl Iterator<Item = u64>,
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS "buffer_comment_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "start_anchor" BYTEA NOT NULL,
+    "end_anchor" BYTEA NOT NULL,
+    "created_by" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    "resolved_at" TIMESTAMP WITHOUT TIME ZONE
+);
+
+CREATE INDEX "index_buffer_comment_threads_on_buffer_id" ON "buffer_comment_threads" ("buffer_id");
+
+CREATE TABLE IF NOT EXISTS "buffer_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES buffer_comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "index_buffer_comments_on_thread_id" ON "buffer_comments" ("thread_id");
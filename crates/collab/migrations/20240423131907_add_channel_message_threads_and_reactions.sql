@@ -0,0 +1,10 @@
+ALTER TABLE "channel_messages" ADD COLUMN "thread_root_id" INTEGER REFERENCES channel_messages (id) ON DELETE CASCADE;
+CREATE INDEX "index_channel_messages_on_thread_root_id" ON "channel_messages" ("thread_root_id");
+
+CREATE TABLE IF NOT EXISTS "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    PRIMARY KEY(message_id, user_id, emoji)
+);
@@ -0,0 +1 @@
+ALTER TABLE "room_participants" ADD COLUMN "presence" INTEGER NOT NULL DEFAULT 0;
@@ -519,11 +519,17 @@ pub struct UpdatedChannelMessage {
     pub participant_connection_ids: Vec<ConnectionId>,
     pub notifications: NotificationBatch,
     pub reply_to_message_id: Option<MessageId>,
+    pub thread_root_id: Option<MessageId>,
     pub timestamp: PrimitiveDateTime,
     pub deleted_mention_notification_ids: Vec<NotificationId>,
     pub updated_mention_notifications: Vec<rpc::proto::Notification>,
 }
 
+pub struct UpdatedChannelMessageReactions {
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub reactions: Vec<proto::ChatReaction>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
 pub struct Invite {
     pub email_address: String,
@@ -668,6 +674,12 @@ pub struct RejoinedChannelBuffer {
     pub old_connection_id: ConnectionId,
 }
 
+#[derive(Debug)]
+pub struct UpdatedBufferCommentThread {
+    pub thread: proto::ChannelBufferCommentThread,
+    pub connection_ids: Vec<ConnectionId>,
+}
+
 #[derive(Clone)]
 pub struct JoinRoom {
     pub room: proto::Room,
@@ -68,6 +68,8 @@ macro_rules! id_type {
 }
 
 id_type!(AccessTokenId);
+id_type!(BufferCommentId);
+id_type!(BufferCommentThreadId);
 id_type!(BufferId);
 id_type!(ChannelBufferCollaboratorId);
 id_type!(ChannelChatParticipantId);
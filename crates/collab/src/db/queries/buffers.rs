@@ -1,6 +1,7 @@
 use super::*;
 use prost::Message;
-use text::{EditOperation, UndoOperation};
+use text::{EditOperation, ToOffset, UndoOperation};
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 pub struct LeftChannelBuffer {
     pub channel_id: ChannelId,
@@ -79,6 +80,7 @@ impl Database {
 
             let (base_text, operations, max_operation) =
                 self.get_buffer_state(&buffer, &tx).await?;
+            let comment_threads = self.get_buffer_comment_threads(&buffer, &tx).await?;
 
             // Save the last observed operation
             if let Some(op) = max_operation {
@@ -118,6 +120,7 @@ impl Database {
                         replica_id: collaborator.replica_id.0 as u32,
                     })
                     .collect(),
+                comment_threads,
             })
         })
         .await
@@ -210,6 +213,9 @@ impl Database {
                     }
                 }
 
+                drop(rows);
+                let comment_threads = self.get_buffer_comment_threads(&buffer, &tx).await?;
+
                 results.push(RejoinedChannelBuffer {
                     old_connection_id,
                     buffer: proto::RejoinedChannelBuffer {
@@ -224,6 +230,7 @@ impl Database {
                                 replica_id: collaborator.replica_id.0 as u32,
                             })
                             .collect(),
+                        comment_threads,
                     },
                 });
             }
@@ -701,11 +708,15 @@ impl Database {
             return Ok(());
         }
 
-        let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
+        let mut text_buffer =
+            text::Buffer::new(0, text::BufferId::new(buffer.id.to_proto())?, base_text);
         text_buffer
             .apply_ops(operations.into_iter().filter_map(operation_from_wire))
             .unwrap();
 
+        self.rebase_buffer_comment_threads(&buffer, &text_buffer, tx)
+            .await?;
+
         let base_text = text_buffer.text();
         let epoch = buffer.epoch + 1;
 
@@ -733,6 +744,298 @@ impl Database {
         Ok(())
     }
 
+    /// Starts a new comment thread on a range of a channel buffer. The anchors
+    /// must be valid in the buffer's current epoch, so the commenter has to be
+    /// editing the buffer.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_buffer_comment_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+        start: &proto::Anchor,
+        end: &proto::Anchor,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedBufferCommentThread> {
+        self.transaction(|tx| async move {
+            let buffer = self
+                .check_user_is_channel_buffer_collaborator(channel_id, user_id, connection, &tx)
+                .await?;
+            let start = anchor_from_wire(start).ok_or_else(|| anyhow!("invalid anchor"))?;
+            let end = anchor_from_wire(end).ok_or_else(|| anyhow!("invalid anchor"))?;
+            let timestamp = timestamp_to_storage(timestamp);
+
+            let thread = buffer_comment_thread::ActiveModel {
+                id: ActiveValue::NotSet,
+                buffer_id: ActiveValue::Set(buffer.id),
+                start_anchor: ActiveValue::Set(anchor_to_storage(&start)),
+                end_anchor: ActiveValue::Set(anchor_to_storage(&end)),
+                created_by: ActiveValue::Set(user_id),
+                created_at: ActiveValue::Set(timestamp),
+                resolved_at: ActiveValue::Set(None),
+            }
+            .insert(&*tx)
+            .await?;
+            self.insert_buffer_comment(thread.id, user_id, body, timestamp, &tx)
+                .await?;
+
+            self.updated_buffer_comment_thread(&buffer, thread, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Adds a comment to an existing comment thread.
+    pub async fn reply_to_buffer_comment_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+        thread_id: BufferCommentThreadId,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedBufferCommentThread> {
+        self.transaction(|tx| async move {
+            let buffer = self
+                .check_user_is_channel_buffer_collaborator(channel_id, user_id, connection, &tx)
+                .await?;
+            let thread = self
+                .get_buffer_comment_thread(&buffer, thread_id, &tx)
+                .await?;
+            self.insert_buffer_comment(
+                thread.id,
+                user_id,
+                body,
+                timestamp_to_storage(timestamp),
+                &tx,
+            )
+            .await?;
+
+            self.updated_buffer_comment_thread(&buffer, thread, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Marks a comment thread as resolved, or reopens it.
+    pub async fn set_buffer_comment_thread_resolved(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+        thread_id: BufferCommentThreadId,
+        resolved: bool,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedBufferCommentThread> {
+        self.transaction(|tx| async move {
+            let buffer = self
+                .check_user_is_channel_buffer_collaborator(channel_id, user_id, connection, &tx)
+                .await?;
+            let thread = self
+                .get_buffer_comment_thread(&buffer, thread_id, &tx)
+                .await?;
+            let thread = buffer_comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                resolved_at: ActiveValue::Set(resolved.then(|| timestamp_to_storage(timestamp))),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+
+            self.updated_buffer_comment_thread(&buffer, thread, &tx)
+                .await
+        })
+        .await
+    }
+
+    async fn check_user_is_channel_buffer_collaborator(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+        tx: &DatabaseTransaction,
+    ) -> Result<buffer::Model> {
+        let channel = self.get_channel_internal(channel_id, tx).await?;
+        self.check_user_is_channel_participant(&channel, user_id, tx)
+            .await?;
+
+        channel_buffer_collaborator::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(channel_buffer_collaborator::Column::ChannelId.eq(channel_id))
+                    .add(channel_buffer_collaborator::Column::ConnectionId.eq(connection.id as i32))
+                    .add(
+                        channel_buffer_collaborator::Column::ConnectionServerId
+                            .eq(connection.owner_id as i32),
+                    ),
+            )
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("not a collaborator on this channel buffer"))?;
+
+        self.get_channel_buffer(channel_id, tx).await
+    }
+
+    async fn get_buffer_comment_thread(
+        &self,
+        buffer: &buffer::Model,
+        thread_id: BufferCommentThreadId,
+        tx: &DatabaseTransaction,
+    ) -> Result<buffer_comment_thread::Model> {
+        Ok(buffer_comment_thread::Entity::find_by_id(thread_id)
+            .filter(buffer_comment_thread::Column::BufferId.eq(buffer.id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such comment thread"))?)
+    }
+
+    async fn insert_buffer_comment(
+        &self,
+        thread_id: BufferCommentThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: PrimitiveDateTime,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        buffer_comment::ActiveModel {
+            id: ActiveValue::NotSet,
+            thread_id: ActiveValue::Set(thread_id),
+            sender_id: ActiveValue::Set(user_id),
+            body: ActiveValue::Set(body.to_string()),
+            sent_at: ActiveValue::Set(timestamp),
+        }
+        .insert(tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn updated_buffer_comment_thread(
+        &self,
+        buffer: &buffer::Model,
+        thread: buffer_comment_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<UpdatedBufferCommentThread> {
+        let thread = self
+            .load_buffer_comment_threads(buffer, vec![thread], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("no such comment thread"))?;
+        let connection_ids = channel_buffer_collaborator::Entity::find()
+            .filter(channel_buffer_collaborator::Column::ChannelId.eq(buffer.channel_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|collaborator| collaborator.connection())
+            .collect();
+        Ok(UpdatedBufferCommentThread {
+            thread,
+            connection_ids,
+        })
+    }
+
+    async fn get_buffer_comment_threads(
+        &self,
+        buffer: &buffer::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelBufferCommentThread>> {
+        let threads = buffer_comment_thread::Entity::find()
+            .filter(buffer_comment_thread::Column::BufferId.eq(buffer.id))
+            .order_by_asc(buffer_comment_thread::Column::Id)
+            .all(tx)
+            .await?;
+        self.load_buffer_comment_threads(buffer, threads, tx).await
+    }
+
+    async fn load_buffer_comment_threads(
+        &self,
+        buffer: &buffer::Model,
+        threads: Vec<buffer_comment_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelBufferCommentThread>> {
+        let buffer_id = text::BufferId::new(buffer.id.to_proto())?;
+        let mut comments =
+            HashMap::<BufferCommentThreadId, Vec<proto::ChannelBufferComment>>::default();
+        let mut rows = buffer_comment::Entity::find()
+            .filter(buffer_comment::Column::ThreadId.is_in(threads.iter().map(|thread| thread.id)))
+            .order_by_asc(buffer_comment::Column::Id)
+            .stream(tx)
+            .await?;
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            comments
+                .entry(row.thread_id)
+                .or_default()
+                .push(proto::ChannelBufferComment {
+                    id: row.id.to_proto(),
+                    sender_id: row.sender_id.to_proto(),
+                    body: row.body,
+                    timestamp: row.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+        }
+        drop(rows);
+
+        threads
+            .into_iter()
+            .map(|thread| {
+                Ok(proto::ChannelBufferCommentThread {
+                    id: thread.id.to_proto(),
+                    start: Some(anchor_to_wire(&anchor_from_storage(
+                        &thread.start_anchor,
+                        buffer_id,
+                    )?)),
+                    end: Some(anchor_to_wire(&anchor_from_storage(
+                        &thread.end_anchor,
+                        buffer_id,
+                    )?)),
+                    comments: comments.remove(&thread.id).unwrap_or_default(),
+                    resolved: thread.resolved_at.is_some(),
+                })
+            })
+            .collect()
+    }
+
+    /// Moves the anchors of the buffer's comment threads into the next epoch,
+    /// whose base text is the given buffer's current text.
+    async fn rebase_buffer_comment_threads(
+        &self,
+        buffer: &buffer::Model,
+        text_buffer: &text::Buffer,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let threads = buffer_comment_thread::Entity::find()
+            .filter(buffer_comment_thread::Column::BufferId.eq(buffer.id))
+            .all(tx)
+            .await?;
+        if threads.is_empty() {
+            return Ok(());
+        }
+
+        let buffer_id = text_buffer.remote_id();
+        let next_text_buffer = text::Buffer::new(0, buffer_id, text_buffer.text());
+        let rebase_anchor = |anchor: &[u8]| {
+            let anchor = anchor_from_storage(anchor, buffer_id)
+                .ok()
+                .filter(|anchor| text_buffer.can_resolve(anchor))
+                .unwrap_or(text::Anchor::MIN);
+            let offset = anchor.to_offset(text_buffer);
+            anchor_to_storage(&next_text_buffer.anchor_at(offset, anchor.bias))
+        };
+
+        for thread in threads {
+            buffer_comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                start_anchor: ActiveValue::Set(rebase_anchor(&thread.start_anchor)),
+                end_anchor: ActiveValue::Set(rebase_anchor(&thread.end_anchor)),
+                ..Default::default()
+            }
+            .update(tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn observe_buffer_version(
         &self,
         buffer_id: BufferId,
@@ -947,6 +1250,72 @@ pub fn operation_from_wire(operation: proto::Operation) -> Option<text::Operatio
     }
 }
 
+fn anchor_from_wire(anchor: &proto::Anchor) -> Option<text::Anchor> {
+    Some(text::Anchor {
+        timestamp: clock::Lamport {
+            replica_id: anchor.replica_id as text::ReplicaId,
+            value: anchor.timestamp,
+        },
+        offset: anchor.offset as usize,
+        bias: match proto::Bias::from_i32(anchor.bias)? {
+            proto::Bias::Left => text::Bias::Left,
+            proto::Bias::Right => text::Bias::Right,
+        },
+        buffer_id: None,
+    })
+}
+
+fn anchor_to_wire(anchor: &text::Anchor) -> proto::Anchor {
+    proto::Anchor {
+        replica_id: anchor.timestamp.replica_id as u32,
+        timestamp: anchor.timestamp.value,
+        offset: anchor.offset as u64,
+        bias: match anchor.bias {
+            text::Bias::Left => proto::Bias::Left as i32,
+            text::Bias::Right => proto::Bias::Right as i32,
+        },
+        buffer_id: anchor.buffer_id.map(Into::into),
+    }
+}
+
+fn anchor_to_storage(anchor: &text::Anchor) -> Vec<u8> {
+    storage::Anchor {
+        replica_id: anchor.timestamp.replica_id as u32,
+        lamport_timestamp: anchor.timestamp.value,
+        offset: anchor.offset as u64,
+        bias_right: anchor.bias == text::Bias::Right,
+    }
+    .encode_to_vec()
+}
+
+/// Decodes a stored anchor. Anchors other than the start and end of the buffer
+/// are given the buffer's id, so that they can be resolved against it.
+fn anchor_from_storage(value: &[u8], buffer_id: text::BufferId) -> Result<text::Anchor> {
+    let anchor = storage::Anchor::decode(value).map_err(|error| anyhow!("{}", error))?;
+    let mut anchor = text::Anchor {
+        timestamp: clock::Lamport {
+            replica_id: anchor.replica_id as text::ReplicaId,
+            value: anchor.lamport_timestamp,
+        },
+        offset: anchor.offset as usize,
+        bias: if anchor.bias_right {
+            text::Bias::Right
+        } else {
+            text::Bias::Left
+        },
+        buffer_id: None,
+    };
+    if anchor != text::Anchor::MIN && anchor != text::Anchor::MAX {
+        anchor.buffer_id = Some(buffer_id);
+    }
+    Ok(anchor)
+}
+
+fn timestamp_to_storage(timestamp: OffsetDateTime) -> PrimitiveDateTime {
+    let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+    PrimitiveDateTime::new(timestamp.date(), timestamp.time())
+}
+
 fn version_from_wire(message: &[proto::VectorClockEntry]) -> clock::Global {
     let mut version = clock::Global::new();
     for entry in message {
@@ -1009,6 +1378,18 @@ mod storage {
         pub end: u64,
     }
 
+    #[derive(Message)]
+    pub struct Anchor {
+        #[prost(uint32, tag = "1")]
+        pub replica_id: u32,
+        #[prost(uint32, tag = "2")]
+        pub lamport_timestamp: u32,
+        #[prost(uint64, tag = "3")]
+        pub offset: u64,
+        #[prost(bool, tag = "4")]
+        pub bias_right: bool,
+    }
+
     #[derive(Message)]
     pub struct UndoCount {
         #[prost(uint32, tag = "1")]
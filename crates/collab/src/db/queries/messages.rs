@@ -92,8 +92,9 @@ impl Database {
             self.check_user_is_channel_participant(&channel, user_id, &tx)
                 .await?;
 
-            let mut condition =
-                Condition::all().add(channel_message::Column::ChannelId.eq(channel_id));
+            let mut condition = Condition::all()
+                .add(channel_message::Column::ChannelId.eq(channel_id))
+                .add(channel_message::Column::ThreadRootId.is_null());
 
             if let Some(before_message_id) = before_message_id {
                 condition = condition.add(channel_message::Column::Id.lt(before_message_id));
@@ -143,6 +144,30 @@ impl Database {
         .await
     }
 
+    /// Returns the replies in the thread started by the given message.
+    pub async fn get_channel_message_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        thread_root_id: MessageId,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let rows = channel_message::Entity::find()
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .filter(channel_message::Column::ThreadRootId.eq(thread_root_id))
+                .order_by_desc(channel_message::Column::Id)
+                .all(&*tx)
+                .await?;
+
+            self.load_channel_messages(rows, &tx).await
+        })
+        .await
+    }
+
     async fn load_channel_messages(
         &self,
         rows: Vec<channel_message::Model>,
@@ -166,11 +191,61 @@ impl Database {
                     edited_at: row
                         .edited_at
                         .map(|t| t.assume_utc().unix_timestamp() as u64),
+                    thread_root_id: row.thread_root_id.map(|id| id.to_proto()),
+                    thread_reply_count: 0,
+                    reactions: Vec::new(),
                 }
             })
             .collect::<Vec<_>>();
         messages.reverse();
 
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        enum QueryThreadReplyCounts {
+            ThreadRootId,
+            Count,
+        }
+
+        let mut reply_counts = channel_message::Entity::find()
+            .select_only()
+            .column(channel_message::Column::ThreadRootId)
+            .column_as(
+                channel_message::Column::Id.count(),
+                QueryThreadReplyCounts::Count,
+            )
+            .filter(channel_message::Column::ThreadRootId.is_in(messages.iter().map(|m| m.id)))
+            .group_by(channel_message::Column::ThreadRootId)
+            .into_values::<_, QueryThreadReplyCounts>()
+            .stream(tx)
+            .await?;
+        while let Some(row) = reply_counts.next().await {
+            let (thread_root_id, count): (MessageId, i64) = row?;
+            if let Ok(ix) = messages.binary_search_by_key(&thread_root_id.to_proto(), |m| m.id) {
+                messages[ix].thread_reply_count = count as u32;
+            }
+        }
+        drop(reply_counts);
+
+        let mut reactions_by_message_id = HashMap::<MessageId, Vec<_>>::default();
+        for reaction in channel_message_reaction::Entity::find()
+            .filter(
+                channel_message_reaction::Column::MessageId.is_in(messages.iter().map(|m| m.id)),
+            )
+            .order_by_asc(channel_message_reaction::Column::CreatedAt)
+            .order_by_asc(channel_message_reaction::Column::UserId)
+            .all(tx)
+            .await?
+        {
+            reactions_by_message_id
+                .entry(reaction.message_id)
+                .or_default()
+                .push(reaction);
+        }
+        for (message_id, reactions) in reactions_by_message_id {
+            if let Ok(ix) = messages.binary_search_by_key(&message_id.to_proto(), |m| m.id) {
+                messages[ix].reactions = reactions_to_proto(reactions);
+            }
+        }
+
         let mut mentions = channel_message_mention::Entity::find()
             .filter(channel_message_mention::Column::MessageId.is_in(messages.iter().map(|m| m.id)))
             .order_by_asc(channel_message_mention::Column::MessageId)
@@ -239,12 +314,24 @@ impl Database {
         timestamp: OffsetDateTime,
         nonce: u128,
         reply_to_message_id: Option<MessageId>,
+        thread_root_id: Option<MessageId>,
     ) -> Result<CreatedChannelMessage> {
         self.transaction(|tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
             self.check_user_is_channel_participant(&channel, user_id, &tx)
                 .await?;
 
+            if let Some(thread_root_id) = thread_root_id {
+                let thread_root = channel_message::Entity::find_by_id(thread_root_id)
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .one(&*tx)
+                    .await?
+                    .ok_or_else(|| anyhow!("no such message"))?;
+                if thread_root.thread_root_id.is_some() {
+                    Err(anyhow!("threads can't be nested"))?;
+                }
+            }
+
             let mut rows = channel_chat_participant::Entity::find()
                 .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
                 .stream(&*tx)
@@ -278,6 +365,7 @@ impl Database {
                 nonce: ActiveValue::Set(Uuid::from_u128(nonce)),
                 id: ActiveValue::NotSet,
                 reply_to_message_id: ActiveValue::Set(reply_to_message_id),
+                thread_root_id: ActiveValue::Set(thread_root_id),
                 edited_at: ActiveValue::NotSet,
             })
             .on_conflict(
@@ -307,6 +395,34 @@ impl Database {
                             .await?;
                     }
 
+                    // Everyone who took part in the thread hears about new replies,
+                    // unless they're already being notified about a mention.
+                    if let Some(thread_root_id) = thread_root_id {
+                        for participant_id in
+                            self.get_thread_participant_ids(thread_root_id, &tx).await?
+                        {
+                            if participant_id == user_id
+                                || mentioned_user_ids.contains(&participant_id.to_proto())
+                            {
+                                continue;
+                            }
+                            notifications.extend(
+                                self.create_notification(
+                                    participant_id,
+                                    rpc::Notification::ChannelMessageThreadReply {
+                                        message_id: message_id.to_proto(),
+                                        sender_id: user_id.to_proto(),
+                                        channel_id: channel_id.to_proto(),
+                                        thread_root_id: thread_root_id.to_proto(),
+                                    },
+                                    false,
+                                    &tx,
+                                )
+                                .await?,
+                            );
+                        }
+                    }
+
                     for mentioned_user in mentioned_user_ids {
                         notifications.extend(
                             self.create_notification(
@@ -323,8 +439,12 @@ impl Database {
                         );
                     }
 
-                    self.observe_channel_message_internal(channel_id, user_id, message_id, &tx)
-                        .await?;
+                    // Thread replies aren't part of the channel's timeline, so they don't
+                    // count towards the messages the user has seen.
+                    if thread_root_id.is_none() {
+                        self.observe_channel_message_internal(channel_id, user_id, message_id, &tx)
+                            .await?;
+                    }
                 }
                 _ => {
                     message_id = channel_message::Entity::find()
@@ -349,6 +469,30 @@ impl Database {
         .await
     }
 
+    async fn get_thread_participant_ids(
+        &self,
+        thread_root_id: MessageId,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<UserId>> {
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        enum QueryUserIds {
+            SenderId,
+        }
+
+        Ok(channel_message::Entity::find()
+            .select_only()
+            .column(channel_message::Column::SenderId)
+            .distinct()
+            .filter(
+                Condition::any()
+                    .add(channel_message::Column::Id.eq(thread_root_id))
+                    .add(channel_message::Column::ThreadRootId.eq(thread_root_id)),
+            )
+            .into_values::<_, QueryUserIds>()
+            .all(tx)
+            .await?)
+    }
+
     pub async fn observe_channel_message(
         &self,
         channel_id: ChannelId,
@@ -458,6 +602,7 @@ impl Database {
                 FROM channel_messages
                 WHERE
                     channel_id in ({values})
+                    AND thread_root_id IS NULL
             ) AS messages
             WHERE
                 row_number = 1
@@ -539,12 +684,14 @@ impl Database {
                 }
             }
 
-            let notification_kind_id =
-                self.get_notification_kind_id_by_name("ChannelMessageMention");
+            let notification_kind_ids = ["ChannelMessageMention", "ChannelMessageThreadReply"]
+                .into_iter()
+                .filter_map(|kind| self.get_notification_kind_id_by_name(kind))
+                .collect::<Vec<_>>();
 
             let existing_notifications = notification::Entity::find()
                 .filter(notification::Column::EntityId.eq(message_id))
-                .filter(notification::Column::Kind.eq(notification_kind_id))
+                .filter(notification::Column::Kind.is_in(notification_kind_ids.iter().copied()))
                 .select_column(notification::Column::Id)
                 .all(&*tx)
                 .await?;
@@ -554,10 +701,10 @@ impl Database {
                 .map(|notification| notification.id)
                 .collect();
 
-            // remove all the mention notifications for this message
+            // remove all the mention and thread notifications for this message
             notification::Entity::delete_many()
                 .filter(notification::Column::EntityId.eq(message_id))
-                .filter(notification::Column::Kind.eq(notification_kind_id))
+                .filter(notification::Column::Kind.is_in(notification_kind_ids))
                 .exec(&*tx)
                 .await?;
 
@@ -619,6 +766,7 @@ impl Database {
                 body: ActiveValue::Set(body.to_string()),
                 edited_at: ActiveValue::Set(Some(edited_at)),
                 reply_to_message_id: ActiveValue::Unchanged(channel_message.reply_to_message_id),
+                thread_root_id: ActiveValue::Unchanged(channel_message.thread_root_id),
                 id: ActiveValue::Unchanged(message_id),
                 channel_id: ActiveValue::Unchanged(channel_id),
                 sender_id: ActiveValue::Unchanged(user_id),
@@ -717,6 +865,7 @@ impl Database {
                 participant_connection_ids,
                 notifications,
                 reply_to_message_id: channel_message.reply_to_message_id,
+                thread_root_id: channel_message.thread_root_id,
                 timestamp: channel_message.sent_at,
                 deleted_mention_notification_ids: deleted_notification_ids
                     .into_iter()
@@ -726,4 +875,106 @@ impl Database {
         })
         .await
     }
+
+    /// Adds or removes the user's reaction to the given channel message.
+    pub async fn set_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+        reacted: bool,
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        self.transaction(|tx| async move {
+            let mut rows = channel_chat_participant::Entity::find()
+                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+                .stream(&*tx)
+                .await?;
+
+            let mut is_participant = false;
+            let mut participant_connection_ids = Vec::new();
+            while let Some(row) = rows.next().await {
+                let row = row?;
+                if row.user_id == user_id {
+                    is_participant = true;
+                }
+                participant_connection_ids.push(row.connection());
+            }
+            drop(rows);
+
+            if !is_participant {
+                Err(anyhow!("not a chat participant"))?;
+            }
+
+            channel_message::Entity::find_by_id(message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+
+            if reacted {
+                let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+                channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                    message_id: ActiveValue::Set(message_id),
+                    user_id: ActiveValue::Set(user_id),
+                    emoji: ActiveValue::Set(emoji.to_string()),
+                    created_at: ActiveValue::Set(time::PrimitiveDateTime::new(
+                        timestamp.date(),
+                        timestamp.time(),
+                    )),
+                })
+                .on_conflict(
+                    OnConflict::columns([
+                        channel_message_reaction::Column::MessageId,
+                        channel_message_reaction::Column::UserId,
+                        channel_message_reaction::Column::Emoji,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&*tx)
+                .await?;
+            } else {
+                channel_message_reaction::Entity::delete_many()
+                    .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                    .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                    .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                    .exec(&*tx)
+                    .await?;
+            }
+
+            let reactions = channel_message_reaction::Entity::find()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .order_by_asc(channel_message_reaction::Column::CreatedAt)
+                .order_by_asc(channel_message_reaction::Column::UserId)
+                .all(&*tx)
+                .await?;
+
+            Ok(UpdatedChannelMessageReactions {
+                participant_connection_ids,
+                reactions: reactions_to_proto(reactions),
+            })
+        })
+        .await
+    }
+}
+
+/// Groups a message's reactions by emoji, in the order each emoji was first used.
+fn reactions_to_proto(
+    reactions: impl IntoIterator<Item = channel_message_reaction::Model>,
+) -> Vec<proto::ChatReaction> {
+    let mut result = Vec::<proto::ChatReaction>::new();
+    for reaction in reactions {
+        let user_id = reaction.user_id.to_proto();
+        if let Some(existing) = result.iter_mut().find(|r| r.emoji == reaction.emoji) {
+            existing.user_ids.push(user_id);
+        } else {
+            result.push(proto::ChatReaction {
+                emoji: reaction.emoji,
+                user_ids: vec![user_id],
+            });
+        }
+    }
+    result
 }
@@ -131,6 +131,7 @@ impl Database {
                 id: ActiveValue::NotSet,
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
+                presence: ActiveValue::NotSet,
                 initial_project_id: ActiveValue::NotSet,
             }
             .insert(&*tx)
@@ -185,6 +186,7 @@ impl Database {
                 answering_connection_server_id: ActiveValue::NotSet,
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
+                presence: ActiveValue::NotSet,
             }
             .insert(&*tx)
             .await?;
@@ -332,6 +334,7 @@ impl Database {
                         connection.owner_id as i32,
                     ))),
                     answering_connection_lost: ActiveValue::set(false),
+                    presence: ActiveValue::set(proto::ParticipantPresence::Active as i32),
                     ..Default::default()
                 })
                 .exec(&*tx)
@@ -429,6 +432,7 @@ impl Database {
                     connection.owner_id as i32,
                 ))),
                 answering_connection_lost: ActiveValue::set(false),
+                presence: ActiveValue::set(proto::ParticipantPresence::Active as i32),
                 ..Default::default()
             })
             .exec(tx)
@@ -453,6 +457,7 @@ impl Database {
                 id: ActiveValue::NotSet,
                 location_kind: ActiveValue::NotSet,
                 location_project_id: ActiveValue::NotSet,
+                presence: ActiveValue::NotSet,
                 initial_project_id: ActiveValue::NotSet,
             })
             .exec(tx)
@@ -1036,6 +1041,44 @@ impl Database {
         .await
     }
 
+    /// Updates whether a participant in the given room is active, idle or away.
+    pub async fn update_room_participant_presence(
+        &self,
+        room_id: RoomId,
+        connection: ConnectionId,
+        presence: proto::ParticipantPresence,
+    ) -> Result<TransactionGuard<proto::Room>> {
+        self.room_transaction(room_id, |tx| async move {
+            let result = room_participant::Entity::update_many()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(
+                            room_participant::Column::AnsweringConnectionId
+                                .eq(connection.id as i32),
+                        )
+                        .add(
+                            room_participant::Column::AnsweringConnectionServerId
+                                .eq(connection.owner_id as i32),
+                        ),
+                )
+                .set(room_participant::ActiveModel {
+                    presence: ActiveValue::set(presence as i32),
+                    ..Default::default()
+                })
+                .exec(&*tx)
+                .await?;
+
+            if result.rows_affected == 1 {
+                let room = self.get_room(room_id, &tx).await?;
+                Ok(room)
+            } else {
+                Err(anyhow!("could not update room participant presence"))?
+            }
+        })
+        .await
+    }
+
     /// Sets the role of a participant in the given room.
     pub async fn set_room_participant_role(
         &self,
@@ -1284,6 +1327,7 @@ impl Database {
                         location: Some(proto::ParticipantLocation { variant: location }),
                         participant_index: participant_index as u32,
                         role: db_participant.role.unwrap_or(ChannelRole::Member).into(),
+                        presence: db_participant.presence,
                     },
                 );
             } else {
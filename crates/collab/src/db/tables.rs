@@ -1,5 +1,7 @@
 pub mod access_token;
 pub mod buffer;
+pub mod buffer_comment;
+pub mod buffer_comment_thread;
 pub mod buffer_operation;
 pub mod buffer_snapshot;
 pub mod channel;
@@ -8,6 +10,7 @@ pub mod channel_chat_participant;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_message_reaction;
 pub mod contact;
 pub mod contributor;
 pub mod dev_server;
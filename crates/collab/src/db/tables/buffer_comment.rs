@@ -0,0 +1,32 @@
+use crate::db::{BufferCommentId, BufferCommentThreadId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "buffer_comments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: BufferCommentId,
+    pub thread_id: BufferCommentThreadId,
+    pub sender_id: UserId,
+    pub body: String,
+    pub sent_at: PrimitiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::buffer_comment_thread::Entity",
+        from = "Column::ThreadId",
+        to = "super::buffer_comment_thread::Column::Id"
+    )]
+    Thread,
+}
+
+impl Related<super::buffer_comment_thread::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Thread.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,47 @@
+use crate::db::{BufferCommentThreadId, BufferId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// A thread of review comments anchored to a range of a channel buffer.
+///
+/// The anchors are serialized `proto::Anchor`s that are valid in the
+/// buffer's current epoch. They are rewritten whenever the buffer is
+/// snapshotted into a new epoch.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "buffer_comment_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: BufferCommentThreadId,
+    pub buffer_id: BufferId,
+    pub start_anchor: Vec<u8>,
+    pub end_anchor: Vec<u8>,
+    pub created_by: UserId,
+    pub created_at: PrimitiveDateTime,
+    pub resolved_at: Option<PrimitiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::buffer::Entity",
+        from = "Column::BufferId",
+        to = "super::buffer::Column::Id"
+    )]
+    Buffer,
+    #[sea_orm(has_many = "super::buffer_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::buffer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Buffer.def()
+    }
+}
+
+impl Related<super::buffer_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -20,6 +20,7 @@ pub struct Model {
     pub calling_connection_server_id: Option<ServerId>,
     pub participant_index: Option<i32>,
     pub role: Option<ChannelRole>,
+    pub presence: i32,
 }
 
 impl Model {
@@ -1,7 +1,8 @@
 use super::*;
 use crate::test_both_dbs;
 use language::proto::{self, serialize_version};
-use text::Buffer;
+use text::{Buffer, ToOffset};
+use time::OffsetDateTime;
 
 test_both_dbs!(
     test_channel_buffers,
@@ -313,6 +314,136 @@ async fn test_channel_buffers_last_operations(db: &Database) {
     );
 }
 
+test_both_dbs!(
+    test_channel_buffer_comment_threads,
+    test_channel_buffer_comment_threads_postgres,
+    test_channel_buffer_comment_threads_sqlite
+);
+
+async fn test_channel_buffer_comment_threads(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let owner_id = db.create_server("production").await.unwrap().0 as u32;
+    let connection_id_a = ConnectionId { owner_id, id: 1 };
+    let connection_id_b = ConnectionId { owner_id, id: 2 };
+
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(zed_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(zed_id, b_id, true)
+        .await
+        .unwrap();
+
+    let response_a = db
+        .join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    let buffer_id = text::BufferId::new(response_a.buffer_id).unwrap();
+    let mut buffer_a = Buffer::new(0, buffer_id, "".to_string());
+    let operation = buffer_a.edit([(0..0, "hello world")]);
+    update_buffer(zed_id, a_id, db, vec![operation]).await;
+
+    let start = proto::serialize_anchor(&buffer_a.anchor_before(6));
+    let end = proto::serialize_anchor(&buffer_a.anchor_after(11));
+    let created = db
+        .create_buffer_comment_thread(
+            zed_id,
+            a_id,
+            connection_id_a,
+            &start,
+            &end,
+            "Which world?",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.connection_ids, &[connection_id_a]);
+    let thread_id = BufferCommentThreadId::from_proto(created.thread.id);
+
+    // Only users who are editing the buffer can comment on it.
+    assert!(db
+        .reply_to_buffer_comment_thread(
+            zed_id,
+            b_id,
+            connection_id_b,
+            thread_id,
+            "This one",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .is_err());
+
+    let response_b = db
+        .join_channel_buffer(zed_id, b_id, connection_id_b)
+        .await
+        .unwrap();
+    assert_eq!(response_b.comment_threads, &[created.thread]);
+
+    let replied = db
+        .reply_to_buffer_comment_thread(
+            zed_id,
+            b_id,
+            connection_id_b,
+            thread_id,
+            "This one",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(replied.connection_ids, &[connection_id_a, connection_id_b]);
+    assert_eq!(
+        replied
+            .thread
+            .comments
+            .iter()
+            .map(|comment| (UserId::from_proto(comment.sender_id), comment.body.as_str()))
+            .collect::<Vec<_>>(),
+        &[(a_id, "Which world?"), (b_id, "This one")]
+    );
+
+    let resolved = db
+        .set_buffer_comment_thread_resolved(
+            zed_id,
+            a_id,
+            connection_id_a,
+            thread_id,
+            true,
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert!(resolved.thread.resolved);
+
+    // Edits made before the buffer is snapshotted are reflected in the
+    // thread's anchors once everyone has left.
+    let operation = buffer_a.edit([(0..0, "goodbye, ")]);
+    update_buffer(zed_id, a_id, db, vec![operation]).await;
+    db.leave_channel_buffer(zed_id, connection_id_a)
+        .await
+        .unwrap();
+    db.leave_channel_buffer(zed_id, connection_id_b)
+        .await
+        .unwrap();
+
+    let response_a = db
+        .join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    assert_eq!(response_a.epoch, 1);
+    let buffer_a = Buffer::new(0, buffer_id, response_a.base_text);
+    let thread = &response_a.comment_threads[0];
+    let start = proto::deserialize_anchor(thread.start.clone().unwrap()).unwrap();
+    let end = proto::deserialize_anchor(thread.end.clone().unwrap()).unwrap();
+    assert_eq!(
+        buffer_a
+            .text_for_range(start.to_offset(&buffer_a)..end.to_offset(&buffer_a))
+            .collect::<String>(),
+        "world"
+    );
+    assert!(thread.resolved);
+}
+
 async fn update_buffer(
     channel_id: ChannelId,
     user_id: UserId,
@@ -1,9 +1,10 @@
 use super::new_test_user;
 use crate::{
-    db::{ChannelRole, Database, MessageId},
+    db::{ChannelRole, Database, MessageId, NotificationBatch, UserId},
     test_both_dbs,
 };
 use channel::mentions_to_proto;
+use rpc::Notification;
 use std::sync::Arc;
 use time::OffsetDateTime;
 
@@ -33,6 +34,7 @@ async fn test_channel_message_retrieval(db: &Arc<Database>) {
                 OffsetDateTime::now_utc(),
                 i,
                 None,
+                None,
             )
             .await
             .unwrap()
@@ -108,6 +110,7 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -121,6 +124,7 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             200,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -134,6 +138,7 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -147,6 +152,7 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             200,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -163,6 +169,7 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -245,6 +252,7 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             1,
             None,
+            None,
         )
         .await
         .unwrap();
@@ -258,6 +266,7 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             2,
             None,
+            None,
         )
         .await
         .unwrap();
@@ -271,6 +280,7 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             3,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -289,6 +299,7 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             4,
             None,
+            None,
         )
         .await
         .unwrap()
@@ -356,6 +367,7 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         1,
         None,
+        None,
     )
     .await
     .unwrap();
@@ -367,6 +379,7 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         2,
         None,
+        None,
     )
     .await
     .unwrap();
@@ -378,6 +391,7 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         3,
         None,
+        None,
     )
     .await
     .unwrap();
@@ -389,6 +403,7 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         4,
         None,
+        None,
     )
     .await
     .unwrap();
@@ -419,3 +434,218 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_threads_and_reactions,
+    test_channel_message_threads_and_reactions_postgres,
+    test_channel_message_threads_and_reactions_sqlite
+);
+
+async fn test_channel_message_threads_and_reactions(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let user_c = new_test_user(db, "user_c@example.com").await;
+
+    let channel = db
+        .create_channel("channel", None, user_a)
+        .await
+        .unwrap()
+        .0
+        .id;
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    for (i, user) in [user_a, user_b, user_c].into_iter().enumerate() {
+        if user != user_a {
+            db.invite_channel_member(channel, user, user_a, ChannelRole::Member)
+                .await
+                .unwrap();
+            db.respond_to_channel_invite(channel, user, true)
+                .await
+                .unwrap();
+        }
+        db.join_channel_chat(
+            channel,
+            rpc::ConnectionId {
+                owner_id,
+                id: i as u32,
+            },
+            user,
+        )
+        .await
+        .unwrap();
+    }
+
+    let root_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "shall we ship it?",
+            &[],
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .message_id;
+
+    // The author of the thread root hears about replies.
+    let reply = db
+        .create_channel_message(
+            channel,
+            user_b,
+            "yes",
+            &[],
+            OffsetDateTime::now_utc(),
+            2,
+            None,
+            Some(root_id),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        notifications_by_user(&reply.notifications),
+        &[(
+            user_a,
+            Notification::ChannelMessageThreadReply {
+                message_id: reply.message_id.to_proto(),
+                sender_id: user_b.to_proto(),
+                channel_id: channel.to_proto(),
+                thread_root_id: root_id.to_proto(),
+            }
+        )]
+    );
+
+    // Other repliers hear about replies too, unless they're mentioned.
+    let reply = db
+        .create_channel_message(
+            channel,
+            user_c,
+            "not yet @user_b",
+            &mentions_to_proto(&[(8..15, user_b.to_proto())]),
+            OffsetDateTime::now_utc(),
+            3,
+            None,
+            Some(root_id),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        notifications_by_user(&reply.notifications),
+        &[
+            (
+                user_a,
+                Notification::ChannelMessageThreadReply {
+                    message_id: reply.message_id.to_proto(),
+                    sender_id: user_c.to_proto(),
+                    channel_id: channel.to_proto(),
+                    thread_root_id: root_id.to_proto(),
+                }
+            ),
+            (
+                user_b,
+                Notification::ChannelMessageMention {
+                    message_id: reply.message_id.to_proto(),
+                    sender_id: user_c.to_proto(),
+                    channel_id: channel.to_proto(),
+                }
+            ),
+        ]
+    );
+
+    // Threads can't be nested.
+    db.create_channel_message(
+        channel,
+        user_a,
+        "ok",
+        &[],
+        OffsetDateTime::now_utc(),
+        4,
+        None,
+        Some(reply.message_id),
+    )
+    .await
+    .unwrap_err();
+
+    // Replies are collapsed into their thread root.
+    let messages = db
+        .get_channel_messages(channel, user_a, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        messages
+            .iter()
+            .map(|m| (m.id, m.thread_reply_count))
+            .collect::<Vec<_>>(),
+        &[(root_id.to_proto(), 2)]
+    );
+    let latest_messages = db
+        .transaction(|tx| async move { db.latest_channel_messages(&[channel], &tx).await })
+        .await
+        .unwrap();
+    assert_eq!(latest_messages[0].message_id, root_id.to_proto());
+
+    let thread = db
+        .get_channel_message_thread(channel, user_b, root_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        thread
+            .iter()
+            .map(|m| (m.body.as_str(), m.thread_root_id))
+            .collect::<Vec<_>>(),
+        &[
+            ("yes", Some(root_id.to_proto())),
+            ("not yet @user_b", Some(root_id.to_proto())),
+        ]
+    );
+
+    // Reactions are grouped by emoji, in the order they were first used.
+    let now = OffsetDateTime::now_utc();
+    for (i, (user, emoji, reacted)) in [
+        (user_c, "👍", true),
+        (user_c, "🎉", true),
+        (user_b, "👍", true),
+        (user_b, "🎉", true),
+        (user_b, "🎉", true),
+        (user_b, "👍", false),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        db.set_channel_message_reaction(
+            channel,
+            root_id,
+            user,
+            emoji,
+            reacted,
+            now + time::Duration::seconds(i as i64),
+        )
+        .await
+        .unwrap();
+    }
+    let messages = db
+        .get_channel_messages(channel, user_a, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        messages[0]
+            .reactions
+            .iter()
+            .map(|r| (r.emoji.as_str(), r.user_ids.clone()))
+            .collect::<Vec<_>>(),
+        &[
+            ("👍", vec![user_c.to_proto()]),
+            ("🎉", vec![user_c.to_proto(), user_b.to_proto()]),
+        ]
+    );
+}
+
+fn notifications_by_user(notifications: &NotificationBatch) -> Vec<(UserId, Notification)> {
+    let mut result = notifications
+        .iter()
+        .map(|(user_id, notification)| (*user_id, Notification::from_proto(notification).unwrap()))
+        .collect::<Vec<_>>();
+    result.sort_by_key(|(user_id, _)| *user_id);
+    result
+}
@@ -3,11 +3,12 @@ mod connection_pool;
 use crate::{
     auth,
     db::{
-        self, dev_server, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
-        CreatedChannelMessage, Database, DevServerId, InviteMemberResult, MembershipUpdated,
-        MessageId, NotificationId, PrincipalId, Project, ProjectId, RejoinedProject,
-        RemoteProjectId, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite, RoomId,
-        ServerId, UpdatedChannelMessage, User, UserId,
+        self, dev_server, BufferCommentThreadId, BufferId, Capability, Channel, ChannelId,
+        ChannelRole, ChannelsForUser, CreatedChannelMessage, Database, DevServerId,
+        InviteMemberResult, MembershipUpdated, MessageId, NotificationId, PrincipalId, Project,
+        ProjectId, RejoinedProject, RemoteProjectId, RemoveChannelMemberResult, ReplicaId,
+        RespondToChannelInvite, RoomId, ServerId, UpdatedBufferCommentThread,
+        UpdatedChannelMessage, UpdatedChannelMessageReactions, User, UserId,
     },
     executor::Executor,
     AppState, Error, RateLimit, RateLimiter, Result,
@@ -81,6 +82,7 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_REACTION_LEN: usize = 32;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -405,6 +407,7 @@ impl Server {
             .add_request_handler(user_handler(cancel_call))
             .add_message_handler(user_message_handler(decline_call))
             .add_request_handler(user_handler(update_participant_location))
+            .add_request_handler(user_handler(update_participant_presence))
             .add_request_handler(user_handler(share_project))
             .add_message_handler(unshare_project)
             .add_request_handler(user_handler(join_project))
@@ -520,6 +523,14 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_shared_terminal_message::<proto::UpdateSharedTerminal>)
+            .add_message_handler(broadcast_shared_terminal_message::<proto::UnshareTerminal>)
+            .add_message_handler(user_message_handler(
+                forward_shared_terminal_message::<proto::RequestTerminalControl>,
+            ))
+            .add_message_handler(user_message_handler(
+                forward_shared_terminal_message::<proto::SharedTerminalInput>,
+            ))
             .add_request_handler(get_users)
             .add_request_handler(user_handler(fuzzy_search_users))
             .add_request_handler(user_handler(request_contact))
@@ -536,6 +547,9 @@ impl Server {
             .add_request_handler(user_handler(leave_channel_buffer))
             .add_message_handler(user_message_handler(update_channel_buffer))
             .add_request_handler(user_handler(rejoin_channel_buffers))
+            .add_request_handler(user_handler(create_channel_buffer_comment_thread))
+            .add_request_handler(user_handler(reply_to_channel_buffer_comment_thread))
+            .add_request_handler(user_handler(resolve_channel_buffer_comment_thread))
             .add_request_handler(user_handler(get_channel_members))
             .add_request_handler(user_handler(respond_to_channel_invite))
             .add_request_handler(user_handler(join_channel))
@@ -546,11 +560,14 @@ impl Server {
             .add_request_handler(user_handler(update_channel_message))
             .add_request_handler(user_handler(get_channel_messages))
             .add_request_handler(user_handler(get_channel_messages_by_id))
+            .add_request_handler(user_handler(get_channel_message_thread))
+            .add_request_handler(user_handler(set_channel_message_reaction))
             .add_request_handler(user_handler(get_notifications))
             .add_request_handler(user_handler(mark_notification_as_read))
             .add_request_handler(user_handler(move_channel))
             .add_request_handler(user_handler(follow))
             .add_message_handler(user_message_handler(unfollow))
+            .add_message_handler(user_message_handler(invite_to_follow))
             .add_message_handler(user_message_handler(update_followers))
             .add_request_handler(user_handler(get_private_user_info))
             .add_message_handler(user_message_handler(acknowledge_channel_message))
@@ -1951,6 +1968,25 @@ async fn update_participant_location(
     Ok(())
 }
 
+/// Updates other participants in the room with whether you're active, idle or away.
+async fn update_participant_presence(
+    request: proto::UpdateParticipantPresence,
+    response: Response<proto::UpdateParticipantPresence>,
+    session: UserSession,
+) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let presence = request.presence();
+
+    let db = session.db().await;
+    let room = db
+        .update_room_participant_presence(room_id, session.connection_id, presence)
+        .await?;
+
+    room_updated(&room, &session.peer);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Share a project into the room.
 async fn share_project(
     request: proto::ShareProject,
@@ -2789,6 +2825,40 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     Ok(())
 }
 
+/// Notify the guests of a project about a terminal that its host has shared.
+async fn broadcast_shared_terminal_message<T: EntityMessage<Entity = ShareProject>>(
+    request: T,
+    session: Session,
+) -> Result<()> {
+    session
+        .db()
+        .await
+        .check_user_is_project_host(
+            ProjectId::from_proto(request.remote_entity_id()),
+            session.connection_id,
+        )
+        .await?;
+    broadcast_project_message_from_host(request, session).await
+}
+
+/// Forward a message about a shared terminal from a guest to the host. The host
+/// decides whether the guest is allowed to control the terminal.
+async fn forward_shared_terminal_message<T: EntityMessage<Entity = ShareProject>>(
+    request: T,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.remote_entity_id());
+    let host_connection_id = session
+        .db()
+        .await
+        .host_for_read_only_project_request(project_id, session.connection_id, session.user_id())
+        .await?;
+    session
+        .peer
+        .forward_send(session.connection_id, host_connection_id, request)?;
+    Ok(())
+}
+
 /// Start following another user in a call.
 async fn follow(
     request: proto::Follow,
@@ -2859,6 +2929,27 @@ async fn unfollow(request: proto::Unfollow, session: UserSession) -> Result<()>
     Ok(())
 }
 
+/// Invite another user in a call to follow you.
+async fn invite_to_follow(request: proto::InviteToFollow, session: UserSession) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let follower_id = request
+        .follower_id
+        .ok_or_else(|| anyhow!("invalid follower id"))?
+        .into();
+
+    session
+        .db()
+        .await
+        .check_room_participants(room_id, session.connection_id, follower_id)
+        .await?;
+
+    session
+        .peer
+        .forward_send(session.connection_id, follower_id, request)?;
+
+    Ok(())
+}
+
 /// Notify everyone following you of your current location.
 async fn update_followers(request: proto::UpdateFollowers, session: UserSession) -> Result<()> {
     let room_id = RoomId::from_proto(request.room_id);
@@ -3750,6 +3841,113 @@ async fn leave_channel_buffer(
     Ok(())
 }
 
+/// Start a comment thread on a range of the channel notes
+async fn create_channel_buffer_comment_thread(
+    request: proto::CreateChannelBufferCommentThread,
+    response: Response<proto::CreateChannelBufferCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let start = request
+        .start
+        .ok_or_else(|| anyhow!("missing start anchor"))?;
+    let end = request.end.ok_or_else(|| anyhow!("missing end anchor"))?;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_thread = session
+        .db()
+        .await
+        .create_buffer_comment_thread(
+            channel_id,
+            session.user_id(),
+            session.connection_id,
+            &start,
+            &end,
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    channel_buffer_comment_thread_updated(channel_id, updated_thread, response, &session)
+}
+
+/// Reply to a comment thread in the channel notes
+async fn reply_to_channel_buffer_comment_thread(
+    request: proto::ReplyToChannelBufferCommentThread,
+    response: Response<proto::ReplyToChannelBufferCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_thread = session
+        .db()
+        .await
+        .reply_to_buffer_comment_thread(
+            channel_id,
+            session.user_id(),
+            session.connection_id,
+            BufferCommentThreadId::from_proto(request.thread_id),
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    channel_buffer_comment_thread_updated(channel_id, updated_thread, response, &session)
+}
+
+/// Resolve or reopen a comment thread in the channel notes
+async fn resolve_channel_buffer_comment_thread(
+    request: proto::ResolveChannelBufferCommentThread,
+    response: Response<proto::ResolveChannelBufferCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_thread = session
+        .db()
+        .await
+        .set_buffer_comment_thread_resolved(
+            channel_id,
+            session.user_id(),
+            session.connection_id,
+            BufferCommentThreadId::from_proto(request.thread_id),
+            request.resolved,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    channel_buffer_comment_thread_updated(channel_id, updated_thread, response, &session)
+}
+
+fn validate_comment_body(body: &str) -> Result<String> {
+    let body = body.trim().to_string();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body)
+}
+
+fn channel_buffer_comment_thread_updated<
+    T: RequestMessage<Response = proto::ChannelBufferCommentThreadResponse>,
+>(
+    channel_id: ChannelId,
+    updated_thread: UpdatedBufferCommentThread,
+    response: Response<T>,
+    session: &UserSession,
+) -> Result<()> {
+    channel_buffer_updated(
+        session.connection_id,
+        updated_thread.connection_ids,
+        &proto::UpdateChannelBufferCommentThread {
+            channel_id: channel_id.to_proto(),
+            thread: Some(updated_thread.thread.clone()),
+        },
+        &session.peer,
+    );
+    response.send(proto::ChannelBufferCommentThreadResponse {
+        thread: Some(updated_thread.thread),
+    })?;
+    Ok(())
+}
+
 fn channel_buffer_updated<T: EnvelopedMessage>(
     sender_id: ConnectionId,
     collaborators: impl IntoIterator<Item = ConnectionId>,
@@ -3826,6 +4024,7 @@ async fn send_channel_message(
                 Some(reply_to_message_id) => Some(MessageId::from_proto(reply_to_message_id)),
                 None => None,
             },
+            request.thread_root_id.map(MessageId::from_proto),
         )
         .await?;
 
@@ -3838,6 +4037,9 @@ async fn send_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
         edited_at: None,
+        thread_root_id: request.thread_root_id,
+        thread_reply_count: 0,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -3857,24 +4059,28 @@ async fn send_channel_message(
     })?;
 
     let pool = &*session.connection_pool().await;
-    broadcast(
-        None,
-        channel_members
-            .iter()
-            .flat_map(|user_id| pool.user_connection_ids(*user_id)),
-        |peer_id| {
-            session.peer.send(
-                peer_id,
-                proto::UpdateChannels {
-                    latest_channel_message_ids: vec![proto::ChannelMessageId {
-                        channel_id: channel_id.to_proto(),
-                        message_id: message_id.to_proto(),
-                    }],
-                    ..Default::default()
-                },
-            )
-        },
-    );
+    // Thread replies don't make the channel unread; thread participants are
+    // notified about them instead.
+    if request.thread_root_id.is_none() {
+        broadcast(
+            None,
+            channel_members
+                .iter()
+                .flat_map(|user_id| pool.user_connection_ids(*user_id)),
+            |peer_id| {
+                session.peer.send(
+                    peer_id,
+                    proto::UpdateChannels {
+                        latest_channel_message_ids: vec![proto::ChannelMessageId {
+                            channel_id: channel_id.to_proto(),
+                            message_id: message_id.to_proto(),
+                        }],
+                        ..Default::default()
+                    },
+                )
+            },
+        );
+    }
     send_notifications(pool, &session.peer, notifications);
 
     Ok(())
@@ -3929,6 +4135,7 @@ async fn update_channel_message(
         participant_connection_ids,
         notifications,
         reply_to_message_id,
+        thread_root_id,
         timestamp,
         deleted_mention_notification_ids,
         updated_mention_notifications,
@@ -3959,6 +4166,9 @@ async fn update_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
         edited_at: Some(updated_at.unix_timestamp() as u64),
+        thread_root_id: thread_root_id.map(|id| id.to_proto()),
+        thread_reply_count: 0,
+        reactions: Vec::new(),
     };
 
     response.send(proto::Ack {})?;
@@ -4425,8 +4635,9 @@ async fn compute_embeddings(
                 &session.http_client,
                 OPEN_AI_API_URL,
                 &api_key,
-                OpenAiEmbeddingModel::TextEmbedding3Small,
+                OpenAiEmbeddingModel::TextEmbedding3Small.id(),
                 request.texts.iter().map(|text| text.as_str()),
+                None,
             )
             .await?
         }
@@ -4574,6 +4785,68 @@ async fn get_channel_messages(
     Ok(())
 }
 
+/// Retrieve the replies in a chat message thread
+async fn get_channel_message_thread(
+    request: proto::GetChannelMessageThread,
+    response: Response<proto::GetChannelMessageThread>,
+    session: UserSession,
+) -> Result<()> {
+    let messages = session
+        .db()
+        .await
+        .get_channel_message_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            MessageId::from_proto(request.thread_root_id),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        done: true,
+        messages,
+    })?;
+    Ok(())
+}
+
+/// Add or remove a reaction to a chat message
+async fn set_channel_message_reaction(
+    request: proto::SetChannelMessageReaction,
+    response: Response<proto::SetChannelMessageReaction>,
+    session: UserSession,
+) -> Result<()> {
+    let emoji = request.emoji.trim();
+    if emoji.is_empty() || emoji.len() > MAX_REACTION_LEN || emoji.contains(char::is_whitespace) {
+        return Err(anyhow!("invalid reaction"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let UpdatedChannelMessageReactions {
+        participant_connection_ids,
+        reactions,
+    } = session
+        .db()
+        .await
+        .set_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id(),
+            emoji,
+            request.reacted,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+
+    let update = proto::ChannelMessageReactionsUpdated {
+        channel_id: channel_id.to_proto(),
+        message_id: request.message_id,
+        reactions,
+    };
+    broadcast(None, participant_connection_ids, |connection| {
+        session.peer.send(connection, update.clone())
+    });
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Retrieve specific chat messages
 async fn get_channel_messages_by_id(
     request: proto::GetChannelMessagesById,
@@ -13,6 +13,7 @@ use gpui::{BackgroundExecutor, Model, TestAppContext, ViewContext};
 use rpc::{proto::PeerId, RECEIVE_TIMEOUT};
 use serde_json::json;
 use std::ops::Range;
+use text::OffsetRangeExt;
 
 #[gpui::test]
 async fn test_core_channel_buffers(
@@ -119,6 +120,106 @@ async fn test_core_channel_buffers(
     // - Test interaction with channel deletion while buffer is open
 }
 
+#[gpui::test]
+async fn test_channel_buffer_comment_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(0..0, "hello world")], None, cx)
+    });
+    executor.run_until_parked();
+
+    // Client A comments on a range of the buffer.
+    let range = buffer_a.read_with(cx_a, |buffer, _| {
+        buffer.anchor_before(6)..buffer.anchor_after(11)
+    });
+    channel_buffer_a
+        .update(cx_a, |buffer, cx| {
+            buffer.create_comment_thread(range, "Which world?".into(), cx)
+        })
+        .await
+        .unwrap();
+
+    // Client B sees the thread when joining, anchored to the same text.
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let thread_id = channel_buffer_b.read_with(cx_b, |channel_buffer, cx| {
+        assert_eq!(channel_buffer.unresolved_comment_thread_count(), 1);
+        let thread = &channel_buffer.comment_threads()[0];
+        let buffer = channel_buffer.buffer().read(cx);
+        assert_eq!(
+            buffer
+                .text_for_range(thread.range.clone())
+                .collect::<String>(),
+            "world"
+        );
+        thread.id
+    });
+
+    // Edits move the thread along with the text.
+    let buffer_b = channel_buffer_b.read_with(cx_b, |buffer, _| buffer.buffer());
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..0, "goodbye, ")], None, cx)
+    });
+
+    // Client B replies, and client A sees the reply.
+    channel_buffer_b
+        .update(cx_b, |buffer, cx| {
+            buffer.reply_to_comment_thread(thread_id, "This one".into(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |channel_buffer, cx| {
+        let thread = &channel_buffer.comment_threads()[0];
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            &["Which world?", "This one"]
+        );
+        let buffer = channel_buffer.buffer().read(cx);
+        assert_eq!(
+            thread.range.to_offset(buffer),
+            "goodbye, hello ".len().."goodbye, hello world".len()
+        );
+    });
+
+    // Client A resolves the thread.
+    channel_buffer_a
+        .update(cx_a, |buffer, cx| {
+            buffer.set_comment_thread_resolved(thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    channel_buffer_b.read_with(cx_b, |channel_buffer, _| {
+        assert!(channel_buffer.comment_threads()[0].resolved);
+        assert_eq!(channel_buffer.unresolved_comment_thread_count(), 0);
+    });
+}
+
 #[gpui::test]
 async fn test_channel_notes_participant_indices(
     executor: BackgroundExecutor,
@@ -723,3 +723,93 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         assert_eq!(store.notification_count(), 1);
     });
 }
+
+#[gpui::test]
+async fn test_chat_threads_and_reactions(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let root_id = channel_chat_a
+        .update(cx_a, |c, cx| c.send_message("root".into(), cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let reply_id = channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_thread_reply(root_id, "in the thread".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Thread replies stay out of the main timeline, and are counted on their root.
+    channel_chat_a.update(cx_a, |c, _| {
+        assert_eq!(c.message_count(), 1);
+        assert_eq!(c.message(0).thread_reply_count, 1);
+        assert!(c.thread_replies(root_id).is_none());
+    });
+    assert_messages(&channel_chat_b, &["root"], cx_b);
+
+    channel_chat_a
+        .update(cx_a, |c, cx| c.load_thread(root_id, cx))
+        .await
+        .unwrap();
+    channel_chat_a.update(cx_a, |c, _| {
+        let replies = c.thread_replies(root_id).unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].id, ChannelMessageId::Saved(reply_id));
+        assert_eq!(replies[0].body, "in the thread");
+    });
+
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(reply_id, "👍".into(), cx))
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(root_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_a.update(cx_a, |c, _| {
+        let reply = &c.thread_replies(root_id).unwrap()[0];
+        assert!(reply.has_reacted(client_a.id(), "👍"));
+        let root = c.message(0);
+        assert_eq!(root.reactions.len(), 1);
+        assert_eq!(root.reactions[0].emoji, "🎉");
+        assert_eq!(root.reactions[0].user_ids, vec![client_b.id()]);
+    });
+
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(root_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_a.update(cx_a, |c, _| {
+        assert!(c.message(0).reactions.is_empty());
+    });
+}
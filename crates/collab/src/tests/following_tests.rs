@@ -20,7 +20,7 @@ use workspace::{
     dock::{test::TestPanel, DockPosition},
     item::{test::TestItem, ItemHandle as _},
     shared_screen::SharedScreen,
-    SplitDirection, Workspace,
+    FollowMode, SplitDirection, ToggleFollowScrollOnly, Workspace,
 };
 
 use super::TestClient;
@@ -2148,3 +2148,61 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_follow_trail_and_scroll_only(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+
+    // b follows a through 1.txt and 2.js
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+    cx_a.simulate_keystrokes("cmd-p 2 enter");
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
+        assert_eq!(
+            workspace.follow_trail(workspace.active_pane()),
+            vec![SharedString::from("1.txt"), SharedString::from("2.js")]
+        );
+    });
+
+    // b only syncs its position with a, so it stays on 2.js
+    workspace_b.update(cx_b, |workspace, cx| {
+        workspace.toggle_follow_scroll_only(&ToggleFollowScrollOnly, cx)
+    });
+    cx_a.simulate_keystrokes("cmd-p 3 enter");
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.follow_mode(workspace.active_pane()),
+            Some(FollowMode::ScrollOnly)
+        );
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
+    });
+
+    // b catches up with a once it follows fully again
+    workspace_b.update(cx_b, |workspace, cx| {
+        workspace.toggle_follow_scroll_only(&ToggleFollowScrollOnly, cx)
+    });
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "3.rs");
+        assert_eq!(
+            workspace.follow_trail(workspace.active_pane()),
+            vec![
+                SharedString::from("1.txt"),
+                SharedString::from("2.js"),
+                SharedString::from("3.rs")
+            ]
+        );
+    });
+}
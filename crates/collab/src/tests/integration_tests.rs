@@ -7,7 +7,7 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use call::{room, ActiveCall, ParticipantLocation, Room};
-use client::{User, RECEIVE_TIMEOUT};
+use client::{ParticipantPresence, User, RECEIVE_TIMEOUT};
 use collections::{HashMap, HashSet};
 use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
@@ -29,6 +29,7 @@ use project::{
     SearchResult,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -1436,6 +1437,108 @@ async fn test_unshare_project(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_merging_offline_edits(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                "a.txt": "one two three",
+                "b.txt": "b-contents",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // When client B leaves the room, it can keep editing its copy of the project.
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, cx| {
+        assert!(project.is_disconnected());
+        assert!(project.is_editing_offline());
+        assert!(!project.has_offline_edits(cx));
+    });
+
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..3, "ONE"), (8..13, "3")], None, cx)
+    });
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(8..13, "THREE")], None, cx));
+    executor.run_until_parked();
+    assert_eq!(
+        buffer_a.read_with(cx_a, |buffer, _| buffer.text()),
+        "one two THREE"
+    );
+    assert!(project_b.read_with(cx_b, |project, cx| project.has_offline_edits(cx)));
+
+    // Client B rejoins, and its offline edits are merged into the host's buffer.
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let project_b2 = client_b.build_remote_project(project_id, cx_b).await;
+    let summary = project_b2
+        .update(cx_b, |project, cx| {
+            project.merge_offline_edits(&project_b, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    assert_eq!(summary.buffers.len(), 1);
+    assert_eq!(summary.merged_edit_count(), 2);
+    assert_eq!(summary.conflict_count(), 1);
+    assert_eq!(summary.skipped_edit_count(), 0);
+    assert!(!summary.is_clean());
+    assert_eq!(
+        buffer_a.read_with(cx_a, |buffer, _| buffer.text()),
+        "ONE two THREE3"
+    );
+    summary.buffers[0].buffer.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.text(), "ONE two THREE3");
+        let conflict = summary.buffers[0].conflicts[0].to_offset(buffer);
+        assert_eq!(&buffer.text()[conflict], "3");
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_project_reconnect(
     executor: BackgroundExecutor,
@@ -2251,6 +2354,101 @@ async fn test_room_location(
     }
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_room_presence(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    let user_b_id = client_b.user_id().unwrap();
+
+    executor.run_until_parked();
+    assert_eq!(
+        participant_presences(&room_a, cx_a),
+        vec![("user_b".to_string(), ParticipantPresence::Active)]
+    );
+
+    room_b
+        .update(cx_b, |room, cx| {
+            room.set_presence(ParticipantPresence::Idle, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        participant_presences(&room_a, cx_a),
+        vec![("user_b".to_string(), ParticipantPresence::Idle)]
+    );
+    client_a.user_store().read_with(cx_a, |user_store, _| {
+        assert_eq!(
+            user_store.participant_presences().get(&user_b_id),
+            Some(&ParticipantPresence::Idle)
+        );
+    });
+
+    room_b
+        .update(cx_b, |room, cx| {
+            room.set_presence(ParticipantPresence::Away, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        participant_presences(&room_a, cx_a),
+        vec![("user_b".to_string(), ParticipantPresence::Away)]
+    );
+
+    // Rejoining the call makes a participant active again.
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        participant_presences(&room_a, cx_a),
+        vec![("user_b".to_string(), ParticipantPresence::Active)]
+    );
+
+    fn participant_presences(
+        room: &Model<Room>,
+        cx: &TestAppContext,
+    ) -> Vec<(String, ParticipantPresence)> {
+        room.read_with(cx, |room, _| {
+            room.remote_participants()
+                .values()
+                .map(|participant| {
+                    (
+                        participant.user.github_login.to_string(),
+                        participant.presence,
+                    )
+                })
+                .collect()
+        })
+    }
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_propagate_saves_and_fs_changes(
     executor: BackgroundExecutor,
@@ -6399,3 +6597,120 @@ async fn test_preview_tabs(cx: &mut TestAppContext) {
         assert!(!pane.can_navigate_forward());
     });
 }
+
+#[gpui::test]
+async fn test_shared_terminal_input_control(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    cx_a.update(terminal::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let peer_id_b = client_b.peer_id().unwrap();
+
+    let received_input = Rc::new(RefCell::new(Vec::new()));
+    cx_a.update(|cx| {
+        let received_input = received_input.clone();
+        cx.subscribe(&project_a, move |_, event, _| {
+            if let project::Event::SharedTerminalInput {
+                terminal_id,
+                peer_id,
+            } = event
+            {
+                received_input.borrow_mut().push((*terminal_id, *peer_id));
+            }
+        })
+        .detach();
+    });
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let window = workspace_a.update(cx_a, |_, cx| cx.window_handle());
+    let terminal = project_a.update(cx_a, |project, cx| {
+        project.create_terminal(None, None, window, cx).unwrap()
+    });
+    let terminal_id = project_a.update(cx_a, |project, cx| {
+        project.share_terminal(&terminal, cx).unwrap()
+    });
+    executor.run_until_parked();
+
+    // Shared terminals are read-only for guests by default, and the host drops
+    // any input that a guest sends anyway.
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.shared_terminal(terminal_id).is_some());
+        assert!(!project.controls_shared_terminal(terminal_id));
+        assert!(project
+            .send_shared_terminal_input(
+                terminal_id,
+                proto::shared_terminal_input::Variant::Text("ls\n".into()),
+            )
+            .is_err());
+    });
+    send_terminal_input(&client_b, project_id, terminal_id);
+    executor.run_until_parked();
+    assert!(received_input.borrow().is_empty());
+
+    // Once the host grants control, the guest's input reaches the terminal.
+    project_a.update(cx_a, |project, cx| {
+        project.grant_terminal_control(terminal_id, peer_id_b, cx)
+    });
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.controls_shared_terminal(terminal_id));
+        project
+            .send_shared_terminal_input(
+                terminal_id,
+                proto::shared_terminal_input::Variant::Text("ls\n".into()),
+            )
+            .unwrap();
+    });
+    executor.run_until_parked();
+    assert_eq!(*received_input.borrow(), [(terminal_id, peer_id_b)]);
+
+    // After the host revokes control, the guest's input is dropped again.
+    project_a.update(cx_a, |project, cx| {
+        project.revoke_terminal_control(terminal_id, cx)
+    });
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert!(!project.controls_shared_terminal(terminal_id));
+    });
+    send_terminal_input(&client_b, project_id, terminal_id);
+    executor.run_until_parked();
+    assert_eq!(*received_input.borrow(), [(terminal_id, peer_id_b)]);
+
+    // Control is revoked when the guest leaves the project.
+    project_a.update(cx_a, |project, cx| {
+        project.grant_terminal_control(terminal_id, peer_id_b, cx)
+    });
+    executor.run_until_parked();
+    cx_b.update(|_| drop(project_b));
+    executor.run_until_parked();
+    project_a.read_with(cx_a, |project, _| {
+        let shared_terminal = project.shared_terminal(terminal_id).unwrap();
+        assert_eq!(shared_terminal.controller, None);
+    });
+
+    fn send_terminal_input(client: &TestClient, project_id: u64, terminal_id: u64) {
+        client
+            .send(proto::SharedTerminalInput {
+                project_id,
+                terminal_id,
+                variant: Some(proto::shared_terminal_input::Variant::Text("ls\n".into())),
+            })
+            .unwrap();
+    }
+}
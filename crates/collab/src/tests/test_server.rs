@@ -274,6 +274,7 @@ impl TestServer {
         cx.update(|cx| {
             theme::init(theme::LoadThemes::JustBase, cx);
             Project::init(&client, cx);
+            client::init_settings(cx);
             client::init(&client, cx);
             language::init(cx);
             editor::init(cx);
@@ -397,6 +398,7 @@ impl TestServer {
         cx.update(|cx| {
             theme::init(theme::LoadThemes::JustBase, cx);
             Project::init(&client, cx);
+            client::init_settings(cx);
             client::init(&client, cx);
             language::init(cx);
             editor::init(cx);
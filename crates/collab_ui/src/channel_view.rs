@@ -1,14 +1,15 @@
+use crate::review_panel::ReviewPanel;
 use anyhow::Result;
 use call::report_call_event_for_channel;
 use channel::{Channel, ChannelBuffer, ChannelBufferEvent, ChannelStore};
 use client::{
     proto::{self, PeerId},
-    ChannelId, Collaborator, ParticipantIndex,
+    ChannelId, Collaborator, ParticipantIndex, ParticipantPresence,
 };
 use collections::HashMap;
 use editor::{
     display_map::ToDisplayPoint, scroll::Autoscroll, CollaborationHub, DisplayPoint, Editor,
-    EditorEvent,
+    EditorEvent, ToPoint as _,
 };
 use gpui::{
     actions, AnyElement, AnyView, AppContext, ClipboardItem, Entity as _, EventEmitter,
@@ -30,7 +31,7 @@ use workspace::{
     ItemNavHistory, Pane, SaveIntent, Toast, ViewId, Workspace, WorkspaceId,
 };
 
-actions!(collab, [CopyLink]);
+actions!(collab, [CopyLink, AddComment, ToggleReviewPanel]);
 
 pub fn init(cx: &mut AppContext) {
     register_followable_item::<ChannelView>(cx)
@@ -43,10 +44,14 @@ pub struct ChannelView {
     channel_store: Model<ChannelStore>,
     channel_buffer: Model<ChannelBuffer>,
     remote_id: Option<ViewId>,
+    review_panel: View<ReviewPanel>,
+    review_panel_open: bool,
     _editor_event_subscription: Subscription,
     _reparse_subscription: Option<Subscription>,
 }
 
+enum CommentHighlight {}
+
 impl ChannelView {
     pub fn open(
         channel_id: ChannelId,
@@ -175,6 +180,8 @@ impl ChannelView {
                         this.update(cx, |this, cx| this.copy_link_for_position(position, cx))
                             .ok();
                     })
+                    .action("Add Comment", Box::new(AddComment))
+                    .action("Toggle Review Panel", Box::new(ToggleReviewPanel))
                 }))
             });
             editor
@@ -185,16 +192,72 @@ impl ChannelView {
         cx.subscribe(&channel_buffer, Self::handle_channel_buffer_event)
             .detach();
 
-        Self {
+        let review_panel =
+            cx.new_view(|cx| ReviewPanel::new(channel_buffer.clone(), editor.clone(), cx));
+
+        let mut this = Self {
             editor,
             workspace,
             project,
             channel_store,
             channel_buffer,
             remote_id: None,
+            review_panel,
+            review_panel_open: false,
             _editor_event_subscription,
             _reparse_subscription: None,
-        }
+        };
+        this.refresh_comment_highlights(cx);
+        this
+    }
+
+    fn add_comment(&mut self, _: &AddComment, cx: &mut ViewContext<Self>) {
+        let range = self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let selection = editor.selections.newest_anchor();
+            if selection.is_empty() {
+                let row = selection.head().to_point(&buffer).row;
+                buffer.anchor_before(language::Point::new(row, 0))
+                    ..buffer.anchor_after(language::Point::new(row, buffer.line_len(row)))
+            } else {
+                selection.start..selection.end
+            }
+        });
+        self.review_panel_open = true;
+        self.review_panel.update(cx, |review_panel, cx| {
+            review_panel.start_thread(range.start.text_anchor..range.end.text_anchor, cx)
+        });
+        cx.notify();
+    }
+
+    fn toggle_review_panel(&mut self, _: &ToggleReviewPanel, cx: &mut ViewContext<Self>) {
+        self.review_panel_open = !self.review_panel_open;
+        cx.notify();
+    }
+
+    fn refresh_comment_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let threads = self.channel_buffer.read(cx).comment_threads().to_vec();
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let Some((excerpt_id, _, _)) = buffer.as_singleton() else {
+                return;
+            };
+            let ranges = threads
+                .iter()
+                .filter(|thread| !thread.resolved)
+                .filter_map(|thread| {
+                    Some(
+                        buffer.anchor_in_excerpt(*excerpt_id, thread.range.start)?
+                            ..buffer.anchor_in_excerpt(*excerpt_id, thread.range.end)?,
+                    )
+                })
+                .collect::<Vec<_>>();
+            editor.highlight_background::<CommentHighlight>(
+                &ranges,
+                |colors| colors.editor_document_highlight_read_background,
+                cx,
+            );
+        });
     }
 
     fn focus_position_from_link(
@@ -319,6 +382,10 @@ impl ChannelView {
                     });
                 }
             }
+            ChannelBufferEvent::CommentThreadsChanged => {
+                self.refresh_comment_highlights(cx);
+                cx.emit(editor::EditorEvent::TitleChanged);
+            }
             ChannelBufferEvent::CollaboratorsChanged => {}
         }
     }
@@ -343,10 +410,22 @@ impl EventEmitter<EditorEvent> for ChannelView {}
 
 impl Render for ChannelView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        div()
+        h_flex()
             .size_full()
             .on_action(cx.listener(Self::copy_link))
-            .child(self.editor.clone())
+            .on_action(cx.listener(Self::add_comment))
+            .on_action(cx.listener(Self::toggle_review_panel))
+            .child(div().flex_1().h_full().child(self.editor.clone()))
+            .when(self.review_panel_open, |this| {
+                this.child(
+                    div()
+                        .w(rems(20.))
+                        .h_full()
+                        .border_l_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(self.review_panel.clone()),
+                )
+            })
     }
 }
 
@@ -387,11 +466,22 @@ impl Item for ChannelView {
         } else {
             "channel notes (disconnected)".to_string()
         };
-        Label::new(label)
-            .color(if params.selected {
+        let unresolved_comment_count = self
+            .channel_buffer
+            .read(cx)
+            .unresolved_comment_thread_count();
+        h_flex()
+            .gap_1()
+            .child(Label::new(label).color(if params.selected {
                 Color::Default
             } else {
                 Color::Muted
+            }))
+            .when(unresolved_comment_count > 0, |this| {
+                this.child(
+                    Label::new(format!("({unresolved_comment_count} unresolved)"))
+                        .color(Color::Muted),
+                )
             })
             .into_any_element()
     }
@@ -575,6 +665,14 @@ impl CollaborationHub for ChannelBufferCollaborationHub {
         self.0.read(cx).user_store().read(cx).participant_indices()
     }
 
+    fn user_presences<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, ParticipantPresence> {
+        self.0
+            .read(cx)
+            .user_store()
+            .read(cx)
+            .participant_presences()
+    }
+
     fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString> {
         let user_ids = self.collaborators(cx).values().map(|c| c.user_id);
         self.0
@@ -2,8 +2,8 @@ use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
 use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
-use client::{ChannelId, Client};
-use collections::HashMap;
+use client::{ChannelId, Client, UserStore};
+use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
 use editor::{actions, Editor};
 use gpui::{
@@ -34,6 +34,7 @@ use workspace::{
 mod message_editor;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
+const QUICK_REACTIONS: &[&str] = &["👍", "❤️", "😄", "🎉", "👀", "🚀"];
 const CHAT_PANEL_KEY: &str = "ChatPanel";
 
 pub fn init(cx: &mut AppContext) {
@@ -48,6 +49,7 @@ pub fn init(cx: &mut AppContext) {
 pub struct ChatPanel {
     client: Arc<Client>,
     channel_store: Model<ChannelStore>,
+    user_store: Model<UserStore>,
     languages: Arc<LanguageRegistry>,
     message_list: ListState,
     active_chat: Option<(Model<ChannelChat>, Subscription)>,
@@ -64,6 +66,7 @@ pub struct ChatPanel {
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
+    expanded_threads: HashSet<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -77,6 +80,7 @@ impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
         let fs = workspace.app_state().fs.clone();
         let client = workspace.app_state().client.clone();
+        let user_store = workspace.app_state().user_store.clone();
         let channel_store = ChannelStore::global(cx);
         let languages = workspace.app_state().languages.clone();
 
@@ -113,6 +117,7 @@ impl ChatPanel {
                 fs,
                 client,
                 channel_store,
+                user_store,
                 languages,
                 message_list,
                 active_chat: Default::default(),
@@ -128,6 +133,7 @@ impl ChatPanel {
                 open_context_menu: None,
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
+                expanded_threads: HashSet::default(),
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -234,6 +240,7 @@ impl ChatPanel {
             let channel_id = chat.read(cx).channel_id;
             {
                 self.markdown_data.clear();
+                self.expanded_threads.clear();
 
                 let chat = chat.read(cx);
                 let channel_name = chat.channel(cx).map(|channel| channel.name.clone());
@@ -242,6 +249,7 @@ impl ChatPanel {
                 self.message_editor.update(cx, |editor, cx| {
                     editor.set_channel(channel_id, channel_name, cx);
                     editor.clear_reply_to_message_id();
+                    editor.clear_thread_root_id();
                 });
             };
             let subscription = cx.subscribe(&chat, Self::channel_did_change);
@@ -546,6 +554,14 @@ impl ChatPanel {
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
+                    })
+                    .when_some(message_id, |el, message_id| {
+                        el.when(!message.reactions.is_empty(), |el| {
+                            el.child(self.render_reactions(message_id, &message, cx))
+                        })
+                        .when(message.thread_reply_count > 0, |el| {
+                            el.child(self.render_thread(message_id, message.thread_reply_count, cx))
+                        })
                     }),
             )
             .when(
@@ -621,6 +637,7 @@ impl ChatPanel {
                                         this.cancel_edit_message(cx);
 
                                         this.message_editor.update(cx, |editor, cx| {
+                                            editor.clear_thread_root_id();
                                             editor.set_reply_to_message_id(message_id);
                                             editor.focus_handle(cx).focus(cx);
                                         })
@@ -629,6 +646,24 @@ impl ChatPanel {
                             .tooltip(|cx| Tooltip::text("Reply", cx)),
                     ),
                 )
+                .child(
+                    self.render_popover_button(
+                        cx,
+                        div()
+                            .id("reply-in-thread")
+                            .child(
+                                IconButton::new(
+                                    ("reply-in-thread", message_id),
+                                    IconName::MessageBubbles,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, cx| this.reply_in_thread(message_id, cx),
+                                )),
+                            )
+                            .tooltip(|cx| Tooltip::text("Reply in Thread", cx)),
+                    ),
+                )
+                .child(self.render_popover_button(cx, self.render_reaction_picker(message_id, cx)))
             })
             .when_some(message_id, |el, message_id| {
                 el.when(can_edit_message, |el| {
@@ -743,6 +778,202 @@ impl ChatPanel {
         menu
     }
 
+    fn render_reaction_picker(&self, message_id: u64, cx: &ViewContext<Self>) -> Stateful<Div> {
+        let this = cx.view().clone();
+        div()
+            .id(("react", message_id))
+            .child(
+                popover_menu(("reaction-menu", message_id))
+                    .trigger(IconButton::new(
+                        ("reaction-trigger", message_id),
+                        IconName::Plus,
+                    ))
+                    .menu(move |cx| Some(Self::render_reaction_menu(&this, message_id, cx))),
+            )
+            .tooltip(|cx| Tooltip::text("Add Reaction", cx))
+    }
+
+    fn render_reaction_menu(
+        this: &View<Self>,
+        message_id: u64,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let menu = ContextMenu::build(cx, move |menu, cx| {
+            QUICK_REACTIONS.iter().fold(menu, |menu, emoji| {
+                let emoji = *emoji;
+                menu.entry(
+                    emoji,
+                    None,
+                    cx.handler_for(&this, move |this, cx| {
+                        this.toggle_reaction(message_id, emoji.to_string(), cx)
+                    }),
+                )
+            })
+        });
+        this.update(cx, |this, cx| {
+            let subscription = cx.subscribe(&menu, |this: &mut Self, _, _: &DismissEvent, _| {
+                this.open_context_menu = None;
+            });
+            this.open_context_menu = Some((message_id, subscription));
+        });
+        menu
+    }
+
+    fn render_reactions(
+        &self,
+        message_id: u64,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let current_user_id = self.client.user_id();
+        let user_store = self.user_store.read(cx);
+        h_flex()
+            .flex_wrap()
+            .gap_1()
+            .pt_1()
+            .children(message.reactions.iter().map(|reaction| {
+                let emoji = reaction.emoji.clone();
+                let reacted_by = reaction
+                    .user_ids
+                    .iter()
+                    .filter_map(|user_id| user_store.get_cached_user(*user_id))
+                    .map(|user| user.github_login.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Button::new(
+                    ElementId::Name(format!("reaction-{message_id}-{emoji}").into()),
+                    format!("{} {}", reaction.emoji, reaction.user_ids.len()),
+                )
+                .style(ButtonStyle::Filled)
+                .label_size(LabelSize::Small)
+                .selected(
+                    current_user_id.map_or(false, |user_id| reaction.user_ids.contains(&user_id)),
+                )
+                .tooltip(move |cx| Tooltip::text(reacted_by.clone(), cx))
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.toggle_reaction(message_id, emoji.clone(), cx)
+                }))
+            }))
+    }
+
+    fn render_thread(
+        &mut self,
+        thread_root_id: u64,
+        reply_count: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let is_expanded = self.expanded_threads.contains(&thread_root_id);
+        let replies = self
+            .active_chat()
+            .filter(|_| is_expanded)
+            .and_then(|chat| Some(chat.read(cx).thread_replies(thread_root_id)?.to_vec()));
+        let label = if is_expanded {
+            "Hide replies".to_string()
+        } else if reply_count == 1 {
+            "1 reply".to_string()
+        } else {
+            format!("{reply_count} replies")
+        };
+
+        v_flex()
+            .pt_0p5()
+            .child(
+                h_flex().child(
+                    Button::new(("toggle-thread", thread_root_id), label)
+                        .icon(if is_expanded {
+                            IconName::ChevronDown
+                        } else {
+                            IconName::ChevronRight
+                        })
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .label_size(LabelSize::Small)
+                        .color(Color::Accent)
+                        .on_click(
+                            cx.listener(move |this, _, cx| this.toggle_thread(thread_root_id, cx)),
+                        ),
+                ),
+            )
+            .when_some(replies, |el, replies| {
+                el.child(
+                    v_flex()
+                        .ml_1()
+                        .pl_2()
+                        .gap_1()
+                        .border_l_2()
+                        .border_color(cx.theme().colors().border)
+                        .children(replies.iter().filter_map(|reply| {
+                            let reply_id: Option<u64> = reply.id.into();
+                            Some(self.render_thread_reply(reply_id?, reply, cx))
+                        }))
+                        .child(
+                            h_flex().child(
+                                Button::new(("reply-to-thread", thread_root_id), "Reply")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(move |this, _, cx| {
+                                        this.reply_in_thread(thread_root_id, cx)
+                                    })),
+                            ),
+                        ),
+                )
+            })
+    }
+
+    fn render_thread_reply(
+        &mut self,
+        reply_id: u64,
+        reply: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let body = self
+            .markdown_data
+            .entry(reply.id)
+            .or_insert_with(|| {
+                Self::render_markdown_with_mentions(
+                    &self.languages,
+                    self.client.id(),
+                    reply,
+                    self.local_timezone,
+                    cx,
+                )
+            })
+            .element(("thread-reply-body", reply_id).into(), cx);
+
+        v_flex()
+            .id(("thread-reply", reply_id))
+            .group("thread-reply")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm()
+                    .child(Avatar::new(reply.sender.avatar_uri.clone()).size(rems(0.8)))
+                    .child(div().font_weight(FontWeight::BOLD).child(
+                        Label::new(reply.sender.github_login.clone()).size(LabelSize::Small),
+                    ))
+                    .child(
+                        Label::new(time_format::format_localized_timestamp(
+                            reply.timestamp,
+                            OffsetDateTime::now_utc(),
+                            self.local_timezone,
+                            time_format::TimestampFormat::EnhancedAbsolute,
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        div()
+                            .ml_auto()
+                            .visible_on_hover("thread-reply")
+                            .child(self.render_reaction_picker(reply_id, cx)),
+                    ),
+            )
+            .child(div().w_full().text_ui_sm().child(body))
+            .when(!reply.reactions.is_empty(), |el| {
+                el.child(self.render_reactions(reply_id, reply, cx))
+            })
+    }
+
     fn render_markdown_with_mentions(
         language_registry: &Arc<LanguageRegistry>,
         current_user_id: u64,
@@ -813,6 +1044,15 @@ impl ChatPanel {
                 {
                     task.detach();
                 }
+            } else if let Some(thread_root_id) = self.message_editor.read(cx).thread_root_id() {
+                if let Some(task) = chat
+                    .update(cx, |chat, cx| {
+                        chat.send_thread_reply(thread_root_id, message, cx)
+                    })
+                    .log_err()
+                {
+                    task.detach();
+                }
             } else {
                 if let Some(task) = chat
                     .update(cx, |chat, cx| chat.send_message(message, cx))
@@ -824,6 +1064,64 @@ impl ChatPanel {
         }
     }
 
+    fn toggle_reaction(&mut self, message_id: u64, emoji: String, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            chat.update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn toggle_thread(&mut self, thread_root_id: u64, cx: &mut ViewContext<Self>) {
+        let Some((chat, _)) = self.active_chat.as_ref() else {
+            return;
+        };
+        if !self.expanded_threads.remove(&thread_root_id) {
+            self.expanded_threads.insert(thread_root_id);
+            if chat.read(cx).thread_replies(thread_root_id).is_none() {
+                chat.update(cx, |chat, cx| chat.load_thread(thread_root_id, cx))
+                    .detach_and_log_err(cx);
+            }
+        }
+        if let Some(ix) = chat
+            .read(cx)
+            .message_ix(ChannelMessageId::Saved(thread_root_id))
+        {
+            self.message_list.splice(ix..ix + 1, 1);
+        }
+        cx.notify();
+    }
+
+    fn reply_in_thread(&mut self, thread_root_id: u64, cx: &mut ViewContext<Self>) {
+        self.cancel_edit_message(cx);
+        if !self.expanded_threads.contains(&thread_root_id) {
+            self.toggle_thread(thread_root_id, cx);
+        }
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.set_thread_root_id(thread_root_id);
+            editor.focus_handle(cx).focus(cx);
+        });
+        cx.notify();
+    }
+
+    /// Opens the chat for the given channel, with the given thread expanded.
+    pub fn select_thread(
+        &mut self,
+        channel_id: ChannelId,
+        thread_root_id: u64,
+        cx: &mut ViewContext<ChatPanel>,
+    ) -> Task<Result<()>> {
+        let select_channel = self.select_channel(channel_id, Some(thread_root_id), cx);
+        cx.spawn(|this, mut cx| async move {
+            select_channel.await?;
+            this.update(&mut cx, |this, cx| {
+                if !this.expanded_threads.contains(&thread_root_id) {
+                    this.toggle_thread(thread_root_id, cx);
+                }
+            })
+        })
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -904,6 +1202,12 @@ impl ChatPanel {
         })
     }
 
+    fn close_thread_reply(&mut self, cx: &mut ViewContext<Self>) {
+        self.message_editor
+            .update(cx, |editor, _| editor.clear_thread_root_id());
+        cx.notify();
+    }
+
     fn close_reply_preview(&mut self, cx: &mut ViewContext<Self>) {
         self.message_editor
             .update(cx, |editor, _| editor.clear_reply_to_message_id());
@@ -941,6 +1245,7 @@ impl Render for ChatPanel {
 
         let reply_to_message_id = message_editor.reply_to_message_id();
         let edit_message_id = message_editor.edit_message_id();
+        let thread_root_id = message_editor.thread_root_id();
 
         v_flex()
             .key_context("ChatPanel")
@@ -1083,6 +1388,54 @@ impl Render for ChatPanel {
                     )
                 })
             })
+            .when_some(thread_root_id, |el, thread_root_id| {
+                let thread_root = self
+                    .active_chat()
+                    .and_then(|active_chat| {
+                        active_chat.read(cx).find_loaded_message(thread_root_id)
+                    })
+                    .cloned();
+
+                el.when_some(thread_root, |el, thread_root| {
+                    el.child(
+                        h_flex()
+                            .when(!self.is_scrolled_to_bottom, |el| {
+                                el.border_t_1().border_color(cx.theme().colors().border)
+                            })
+                            .justify_between()
+                            .overflow_hidden()
+                            .items_start()
+                            .py_1()
+                            .px_2()
+                            .bg(cx.theme().colors().background)
+                            .child(
+                                h_flex()
+                                    .flex_shrink()
+                                    .overflow_hidden()
+                                    .child(
+                                        Label::new("Replying in thread to ").size(LabelSize::Small),
+                                    )
+                                    .child(
+                                        div().font_weight(FontWeight::BOLD).child(
+                                            Label::new(format!(
+                                                "@{}",
+                                                thread_root.sender.github_login
+                                            ))
+                                            .size(LabelSize::Small),
+                                        ),
+                                    ),
+                            )
+                            .child(
+                                IconButton::new("close-thread-reply", IconName::Close)
+                                    .shape(ui::IconButtonShape::Square)
+                                    .tooltip(|cx| Tooltip::text("Leave thread", cx))
+                                    .on_click(cx.listener(move |this, _, cx| {
+                                        this.close_thread_reply(cx);
+                                    })),
+                            ),
+                    )
+                })
+            })
             .children(
                 Some(
                     h_flex()
@@ -1090,6 +1443,7 @@ impl Render for ChatPanel {
                         .on_action(cx.listener(|this, _: &actions::Cancel, cx| {
                             this.cancel_edit_message(cx);
                             this.close_reply_preview(cx);
+                            this.close_thread_reply(cx);
                         }))
                         .map(|el| el.child(self.message_editor.clone())),
                 )
@@ -1194,6 +1548,9 @@ mod tests {
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
             edited_at: None,
+            thread_root_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1248,6 +1605,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_root_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1295,6 +1655,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_root_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
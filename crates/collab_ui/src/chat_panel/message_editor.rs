@@ -38,6 +38,7 @@ pub struct MessageEditor {
     channel_id: Option<ChannelId>,
     reply_to_message_id: Option<u64>,
     edit_message_id: Option<u64>,
+    thread_root_id: Option<u64>,
 }
 
 struct MessageEditorCompletionProvider(WeakView<MessageEditor>);
@@ -133,6 +134,7 @@ impl MessageEditor {
             mentions_task: None,
             reply_to_message_id: None,
             edit_message_id: None,
+            thread_root_id: None,
         }
     }
 
@@ -160,6 +162,18 @@ impl MessageEditor {
         self.edit_message_id = None;
     }
 
+    pub fn thread_root_id(&self) -> Option<u64> {
+        self.thread_root_id
+    }
+
+    pub fn set_thread_root_id(&mut self, thread_root_id: u64) {
+        self.thread_root_id = Some(thread_root_id);
+    }
+
+    pub fn clear_thread_root_id(&mut self) {
+        self.thread_root_id = None;
+    }
+
     pub fn set_channel(
         &mut self,
         channel_id: ChannelId,
@@ -634,6 +648,7 @@ mod tests {
             Project::init_settings(cx);
             language::init(cx);
             editor::init(cx);
+            client::init_settings(cx);
             client::init(&client, cx);
             channel::init(&client, user_store, cx);
 
@@ -6,6 +6,7 @@ mod face_pile;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+mod review_panel;
 
 use std::{rc::Rc, sync::Arc};
 
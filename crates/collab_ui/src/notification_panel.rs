@@ -12,19 +12,25 @@ use gpui::{
     StatefulInteractiveElement, Styled, Task, View, ViewContext, VisualContext, WeakView,
     WindowContext,
 };
-use notifications::{NotificationEntry, NotificationEvent, NotificationStore};
+use notifications::{
+    LocalNotification, NotificationCenter, NotificationCenterEvent, NotificationCenterSettings,
+    NotificationEntry, NotificationEvent, NotificationSource, NotificationStore,
+};
 use project::Fs;
 use rpc::proto;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::{sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
-use ui::{h_flex, prelude::*, v_flex, Avatar, Button, Icon, IconButton, IconName, Label, Tooltip};
+use ui::{
+    h_flex, popover_menu, prelude::*, v_flex, Avatar, Button, ContextMenu, Icon, IconButton,
+    IconName, Label, Tooltip,
+};
 use util::{ResultExt, TryFutureExt};
 use workspace::notifications::NotificationId;
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
-    Workspace,
+    Toast, Workspace,
 };
 
 const LOADING_THRESHOLD: usize = 30;
@@ -37,6 +43,7 @@ pub struct NotificationPanel {
     user_store: Model<UserStore>,
     channel_store: Model<ChannelStore>,
     notification_store: Model<NotificationStore>,
+    notification_center: Model<NotificationCenter>,
     fs: Arc<dyn Fs>,
     width: Option<Pixels>,
     active: bool,
@@ -45,6 +52,7 @@ pub struct NotificationPanel {
     subscriptions: Vec<gpui::Subscription>,
     workspace: WeakView<Workspace>,
     current_notification_toast: Option<(u64, Task<()>)>,
+    local_notification_toast: Option<Task<()>>,
     local_timezone: UtcOffset,
     focus_handle: FocusHandle,
     mark_as_read_tasks: HashMap<u64, Task<Result<()>>>,
@@ -134,11 +142,13 @@ impl NotificationPanel {
                 local_timezone: cx.local_timezone(),
                 channel_store: ChannelStore::global(cx),
                 notification_store: NotificationStore::global(cx),
+                notification_center: NotificationCenter::global(cx),
                 notification_list,
                 pending_serialization: Task::ready(None),
                 workspace: workspace_handle,
                 focus_handle: cx.focus_handle(),
                 current_notification_toast: None,
+                local_notification_toast: None,
                 subscriptions: Vec::new(),
                 active: false,
                 mark_as_read_tasks: HashMap::default(),
@@ -150,6 +160,11 @@ impl NotificationPanel {
             this.subscriptions.extend([
                 cx.observe(&this.notification_store, |_, _, cx| cx.notify()),
                 cx.subscribe(&this.notification_store, Self::on_notification_event),
+                cx.observe(&this.notification_center, |_, _, cx| cx.notify()),
+                cx.subscribe(
+                    &this.notification_center,
+                    Self::on_notification_center_event,
+                ),
                 cx.observe_global::<SettingsStore>(move |this: &mut Self, cx| {
                     let new_dock_position = this.position(cx);
                     if new_dock_position != old_dock_position {
@@ -405,6 +420,29 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::ChannelMessageThreadReply {
+                sender_id,
+                channel_id,
+                message_id,
+                ..
+            } => {
+                let sender = user_store.get_cached_user(sender_id)?;
+                let channel = channel_store.channel_for_id(ChannelId(channel_id))?;
+                let message = self
+                    .notification_store
+                    .read(cx)
+                    .channel_message_for_id(message_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/conversations.svg",
+                    text: format!(
+                        "{} replied in a thread in #{}:\n{}",
+                        sender.github_login, channel.name, message.body,
+                    ),
+                    needs_response: false,
+                    actor: Some(sender),
+                    can_navigate: true,
+                })
+            }
         }
     }
 
@@ -415,7 +453,8 @@ impl NotificationPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. }
+            | Notification::ChannelMessageThreadReply { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
             | Notification::ChannelMessageMention { .. } => false,
@@ -441,25 +480,46 @@ impl NotificationPanel {
     }
 
     fn did_click_notification(&mut self, notification: &Notification, cx: &mut ViewContext<Self>) {
-        if let Notification::ChannelMessageMention {
-            message_id,
-            channel_id,
-            ..
-        } = notification.clone()
-        {
-            if let Some(workspace) = self.workspace.upgrade() {
-                cx.window_context().defer(move |cx| {
-                    workspace.update(cx, |workspace, cx| {
-                        if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
-                            panel.update(cx, |panel, cx| {
-                                panel
-                                    .select_channel(ChannelId(channel_id), Some(message_id), cx)
-                                    .detach_and_log_err(cx);
-                            });
-                        }
+        match notification.clone() {
+            Notification::ChannelMessageMention {
+                message_id,
+                channel_id,
+                ..
+            } => {
+                if let Some(workspace) = self.workspace.upgrade() {
+                    cx.window_context().defer(move |cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                                panel.update(cx, |panel, cx| {
+                                    panel
+                                        .select_channel(ChannelId(channel_id), Some(message_id), cx)
+                                        .detach_and_log_err(cx);
+                                });
+                            }
+                        });
                     });
-                });
+                }
+            }
+            Notification::ChannelMessageThreadReply {
+                channel_id,
+                thread_root_id,
+                ..
+            } => {
+                if let Some(workspace) = self.workspace.upgrade() {
+                    cx.window_context().defer(move |cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                                panel.update(cx, |panel, cx| {
+                                    panel
+                                        .select_thread(ChannelId(channel_id), thread_root_id, cx)
+                                        .detach_and_log_err(cx);
+                                });
+                            }
+                        });
+                    });
+                }
             }
+            _ => {}
         }
     }
 
@@ -524,6 +584,13 @@ impl NotificationPanel {
         };
 
         let notification_id = entry.id;
+        let should_alert = self.notification_center.update(cx, |center, cx| {
+            center.alert_for_collab_notification(notification_id, &entry.notification, &text, cx)
+        });
+        if !should_alert {
+            return;
+        }
+
         self.current_notification_toast = Some((
             notification_id,
             cx.spawn(|this, mut cx| async move {
@@ -565,6 +632,160 @@ impl NotificationPanel {
         }
     }
 
+    fn on_notification_center_event(
+        &mut self,
+        _: Model<NotificationCenter>,
+        event: &NotificationCenterEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let NotificationCenterEvent::NotificationAdded { id, alert } = *event;
+        if self.active {
+            self.notification_center
+                .update(cx, |center, cx| center.mark_all_as_read(cx));
+            return;
+        }
+        if !alert {
+            return;
+        }
+        let Some(text) = self
+            .notification_center
+            .read(cx)
+            .notifications()
+            .find(|notification| notification.id == id)
+            .map(|notification| notification.text.clone())
+        else {
+            return;
+        };
+
+        let toast_id = NotificationId::unique::<LocalNotificationToast>();
+        self.local_notification_toast = Some(cx.spawn({
+            let toast_id = toast_id.clone();
+            |this, mut cx| async move {
+                cx.background_executor().timer(TOAST_DURATION).await;
+                this.update(&mut cx, |this, cx| {
+                    this.workspace
+                        .update(cx, |workspace, cx| workspace.dismiss_toast(&toast_id, cx))
+                        .ok();
+                })
+                .ok();
+            }
+        }));
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let workspace_handle = cx.view().downgrade();
+                workspace.show_toast(
+                    Toast::new(toast_id, text).on_click("View", move |cx| {
+                        workspace_handle
+                            .update(cx, |workspace, cx| {
+                                workspace.focus_panel::<NotificationPanel>(cx);
+                            })
+                            .ok();
+                    }),
+                    cx,
+                )
+            })
+            .ok();
+    }
+
+    fn render_local_notification(
+        &self,
+        notification: &LocalNotification,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let relative_timestamp = time_format::format_localized_timestamp(
+            notification.timestamp,
+            now,
+            self.local_timezone,
+            time_format::TimestampFormat::Relative,
+        );
+        let icon = match notification.source {
+            NotificationSource::Collaboration => IconName::Person,
+            NotificationSource::Mentions => IconName::AtSign,
+            NotificationSource::Updates => IconName::Update,
+            NotificationSource::Tasks => IconName::Play,
+        };
+        let id = notification.id;
+
+        h_flex()
+            .id(("local-notification", id))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .items_start()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(Icon::new(icon).color(Color::Muted))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .overflow_hidden()
+                    .child(Label::new(notification.text.clone()))
+                    .child(Label::new(relative_timestamp).color(Color::Muted)),
+            )
+            .child(
+                IconButton::new(("dismiss-local-notification", id), IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Dismiss", cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.notification_center
+                            .update(cx, |center, cx| center.dismiss(id, cx));
+                    })),
+            )
+    }
+
+    fn render_settings_menu(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let this = cx.view().clone();
+        let fs = self.fs.clone();
+        popover_menu("notification-settings")
+            .trigger(
+                IconButton::new("notification-settings-trigger", IconName::Sliders)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Notification Settings", cx)),
+            )
+            .menu(move |cx| {
+                let muted_sources = NotificationCenterSettings::get_global(cx)
+                    .muted_sources
+                    .clone();
+                let this = this.clone();
+                let fs = fs.clone();
+                Some(ContextMenu::build(cx, move |mut menu, cx| {
+                    menu = menu.header("Show Alerts For");
+                    for source in NotificationSource::ALL {
+                        let is_muted = muted_sources.contains(&source);
+                        let fs = fs.clone();
+                        menu = menu.toggleable_entry(source.label(), !is_muted, None, {
+                            move |cx| {
+                                settings::update_settings_file::<NotificationCenterSettings>(
+                                    fs.clone(),
+                                    cx,
+                                    move |settings| {
+                                        let muted_sources =
+                                            settings.muted_sources.get_or_insert_with(Vec::new);
+                                        if is_muted {
+                                            muted_sources.retain(|muted| *muted != source);
+                                        } else {
+                                            muted_sources.push(source);
+                                        }
+                                    },
+                                );
+                            }
+                        });
+                    }
+                    menu.separator().entry(
+                        "Clear Local Notifications",
+                        None,
+                        cx.handler_for(&this, |this, cx| {
+                            this.notification_center
+                                .update(cx, |center, cx| center.clear(cx));
+                        }),
+                    )
+                }))
+            })
+    }
+
     fn respond_to_notification(
         &mut self,
         notification: Notification,
@@ -579,6 +800,13 @@ impl NotificationPanel {
 
 impl Render for NotificationPanel {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let local_notifications = self
+            .notification_center
+            .read(cx)
+            .notifications()
+            .cloned()
+            .collect::<Vec<_>>();
+
         v_flex()
             .size_full()
             .child(
@@ -591,8 +819,38 @@ impl Render for NotificationPanel {
                     .border_b_1()
                     .border_color(cx.theme().colors().border)
                     .child(Label::new("Notifications"))
-                    .child(Icon::new(IconName::Envelope)),
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(notifications::is_do_not_disturb_active(cx), |this| {
+                                this.child(
+                                    div()
+                                        .id("do-not-disturb")
+                                        .child(Icon::new(IconName::BellOff).color(Color::Muted))
+                                        .tooltip(|cx| {
+                                            Tooltip::text("Do Not Disturb is active", cx)
+                                        }),
+                                )
+                            })
+                            .child(self.render_settings_menu(cx)),
+                    ),
             )
+            .when(!local_notifications.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .id("local-notifications")
+                        .flex_none()
+                        .max_h_1_2()
+                        .overflow_y_scroll()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border)
+                        .children(
+                            local_notifications.iter().map(|notification| {
+                                self.render_local_notification(notification, cx)
+                            }),
+                        ),
+                )
+            })
             .map(|this| {
                 if self.client.user_id().is_none() {
                     this.child(
@@ -629,6 +887,9 @@ impl Render for NotificationPanel {
                             ),
                     )
                 } else if self.notification_list.item_count() == 0 {
+                    if !local_notifications.is_empty() {
+                        return this;
+                    }
                     this.child(
                         v_flex().p_4().child(
                             div().flex().w_full().items_center().child(
@@ -691,10 +952,14 @@ impl Panel for NotificationPanel {
 
         if self.active {
             self.unseen_notifications = Vec::new();
+            self.notification_center
+                .update(cx, |center, cx| center.mark_all_as_read(cx));
             cx.notify();
         }
 
-        if self.notification_store.read(cx).notification_count() == 0 {
+        if self.notification_store.read(cx).notification_count() == 0
+            && self.notification_center.read(cx).notification_count() == 0
+        {
             cx.emit(Event::Dismissed);
         }
     }
@@ -705,7 +970,13 @@ impl Panel for NotificationPanel {
             return None;
         }
 
-        if self.unseen_notifications.is_empty() {
+        if self.unseen_notifications.is_empty()
+            && self
+                .notification_center
+                .read(cx)
+                .unread_notification_count()
+                == 0
+        {
             return Some(IconName::Bell);
         }
 
@@ -717,7 +988,11 @@ impl Panel for NotificationPanel {
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.notification_store.read(cx).unread_notification_count();
+        let count = self.notification_store.read(cx).unread_notification_count()
+            + self
+                .notification_center
+                .read(cx)
+                .unread_notification_count();
         if count == 0 {
             None
         } else {
@@ -730,6 +1005,8 @@ impl Panel for NotificationPanel {
     }
 }
 
+struct LocalNotificationToast;
+
 pub struct NotificationToast {
     notification_id: u64,
     actor: Option<Arc<User>>,
@@ -0,0 +1,348 @@
+use channel::{ChannelBuffer, ChannelBufferEvent, CommentThread};
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    AppContext, FocusHandle, FocusableView, Model, Render, Subscription, View, ViewContext,
+    VisualContext as _,
+};
+use language::{Anchor, ToOffset};
+use std::ops::Range;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Tooltip};
+
+const EXCERPT_LEN: usize = 80;
+
+enum Draft {
+    NewThread(Range<Anchor>),
+    Reply(u64),
+}
+
+/// Lists the comment threads of a channel buffer next to its notes, and lets
+/// the user start, reply to, and resolve threads.
+pub struct ReviewPanel {
+    channel_buffer: Model<ChannelBuffer>,
+    notes_editor: View<Editor>,
+    composer: View<Editor>,
+    draft: Option<Draft>,
+    show_resolved: bool,
+    local_timezone: UtcOffset,
+    _subscription: Subscription,
+}
+
+impl ReviewPanel {
+    pub fn new(
+        channel_buffer: Model<ChannelBuffer>,
+        notes_editor: View<Editor>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let composer = cx.new_view(|cx| Editor::single_line(cx));
+        let subscription = cx.subscribe(&channel_buffer, |_, _, event, cx| match event {
+            ChannelBufferEvent::CommentThreadsChanged | ChannelBufferEvent::Disconnected => {
+                cx.notify()
+            }
+            _ => {}
+        });
+
+        Self {
+            channel_buffer,
+            notes_editor,
+            composer,
+            draft: None,
+            show_resolved: false,
+            local_timezone: cx.local_timezone(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn start_thread(&mut self, range: Range<Anchor>, cx: &mut ViewContext<Self>) {
+        self.start_draft(Draft::NewThread(range), "Leave a comment…", cx);
+    }
+
+    pub fn start_reply(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        self.start_draft(Draft::Reply(thread_id), "Reply…", cx);
+    }
+
+    fn start_draft(&mut self, draft: Draft, placeholder: &str, cx: &mut ViewContext<Self>) {
+        self.draft = Some(draft);
+        self.composer.update(cx, |composer, cx| {
+            composer.set_placeholder_text(placeholder, cx);
+            composer.clear(cx);
+        });
+        cx.focus_view(&self.composer);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(draft) = self.draft.as_ref() else {
+            return;
+        };
+        let body = self.composer.read(cx).text(cx).trim().to_string();
+        if body.is_empty() {
+            return;
+        }
+
+        let task = self
+            .channel_buffer
+            .update(cx, |channel_buffer, cx| match draft {
+                Draft::NewThread(range) => {
+                    channel_buffer.create_comment_thread(range.clone(), body, cx)
+                }
+                Draft::Reply(thread_id) => {
+                    channel_buffer.reply_to_comment_thread(*thread_id, body, cx)
+                }
+            });
+        cx.spawn(|this, mut cx| async move {
+            task.await?;
+            this.update(&mut cx, |this, cx| {
+                this.draft = None;
+                this.composer.update(cx, |composer, cx| composer.clear(cx));
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.draft.take().is_some() {
+            self.composer.update(cx, |composer, cx| composer.clear(cx));
+            cx.focus_view(&self.notes_editor);
+            cx.notify();
+        }
+    }
+
+    fn set_thread_resolved(&mut self, thread_id: u64, resolved: bool, cx: &mut ViewContext<Self>) {
+        self.channel_buffer
+            .update(cx, |channel_buffer, cx| {
+                channel_buffer.set_comment_thread_resolved(thread_id, resolved, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn jump_to_range(&mut self, range: Range<usize>, cx: &mut ViewContext<Self>) {
+        self.notes_editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                selections.select_ranges([range])
+            });
+        });
+        cx.focus_view(&self.notes_editor);
+    }
+
+    fn render_composer(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = match self.draft {
+            Some(Draft::NewThread(_)) => "New comment",
+            Some(Draft::Reply(_)) => "Reply",
+            None => "",
+        };
+        v_flex()
+            .gap_1()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                div()
+                    .px_1()
+                    .py_0p5()
+                    .rounded_md()
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.composer.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .child(
+                        Button::new("cancel-comment", "Cancel")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| this.cancel(&menu::Cancel, cx))),
+                    )
+                    .child(
+                        Button::new("submit-comment", "Comment")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(|this, _, cx| this.confirm(&menu::Confirm, cx))),
+                    ),
+            )
+    }
+
+    fn render_thread(
+        &self,
+        thread: &CommentThread,
+        range: Range<usize>,
+        excerpt: String,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let thread_id = thread.id;
+        let resolved = thread.resolved;
+        let user_store = self.channel_buffer.read(cx).user_store().read(cx);
+
+        v_flex()
+            .gap_1()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .when(resolved, |this| this.opacity(0.6))
+            .child(
+                div()
+                    .id(("comment-thread-excerpt", thread_id as usize))
+                    .px_1()
+                    .border_l_2()
+                    .border_color(cx.theme().colors().border)
+                    .cursor_pointer()
+                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                    .tooltip(|cx| Tooltip::text("Go to commented text", cx))
+                    .on_click(cx.listener(move |this, _, cx| this.jump_to_range(range.clone(), cx)))
+                    .child(
+                        Label::new(excerpt)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .children(thread.comments.iter().map(|comment| {
+                let sender = user_store.get_cached_user(comment.sender_id);
+                v_flex()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(sender.as_ref().map(|sender| {
+                                Avatar::new(sender.avatar_uri.clone()).size(rems(1.))
+                            }))
+                            .child(
+                                Label::new(
+                                    sender
+                                        .map(|sender| sender.github_login.clone())
+                                        .unwrap_or_default(),
+                                )
+                                .size(LabelSize::Small),
+                            )
+                            .child(
+                                Label::new(time_format::format_localized_timestamp(
+                                    comment.timestamp,
+                                    OffsetDateTime::now_utc(),
+                                    self.local_timezone,
+                                    time_format::TimestampFormat::Relative,
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            ),
+                    )
+                    .child(div().text_ui_sm().child(comment.body.clone()))
+            }))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .when(!resolved, |this| {
+                        this.child(
+                            Button::new(("reply-to-comment-thread", thread_id as usize), "Reply")
+                                .label_size(LabelSize::Small)
+                                .on_click(
+                                    cx.listener(move |this, _, cx| this.start_reply(thread_id, cx)),
+                                ),
+                        )
+                    })
+                    .child(
+                        Button::new(
+                            ("resolve-comment-thread", thread_id as usize),
+                            if resolved { "Reopen" } else { "Resolve" },
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.set_thread_resolved(thread_id, !resolved, cx)
+                        })),
+                    ),
+            )
+    }
+}
+
+impl Render for ReviewPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let channel_buffer = self.channel_buffer.read(cx);
+        let unresolved_count = channel_buffer.unresolved_comment_thread_count();
+        let buffer = channel_buffer.buffer().read(cx).snapshot();
+        let mut threads = channel_buffer
+            .comment_threads()
+            .iter()
+            .filter(|thread| self.show_resolved || !thread.resolved)
+            .filter(|thread| {
+                buffer.can_resolve(&thread.range.start) && buffer.can_resolve(&thread.range.end)
+            })
+            .map(|thread| {
+                let range =
+                    thread.range.start.to_offset(&buffer)..thread.range.end.to_offset(&buffer);
+                let excerpt = buffer
+                    .text_for_range(range.clone())
+                    .collect::<String>()
+                    .lines()
+                    .find(|line| !line.trim().is_empty())
+                    .map(|line| util::truncate_and_trailoff(line.trim(), EXCERPT_LEN))
+                    .unwrap_or_default();
+                (thread.clone(), range, excerpt)
+            })
+            .collect::<Vec<_>>();
+        threads.sort_by_key(|(thread, range, _)| (range.start, thread.id));
+
+        v_flex()
+            .key_context("ReviewPanel")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex().gap_1().child(Label::new("Review")).child(
+                            Label::new(format!("{unresolved_count} unresolved"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        Button::new(
+                            "toggle-resolved-comment-threads",
+                            if self.show_resolved {
+                                "Hide Resolved"
+                            } else {
+                                "Show Resolved"
+                            },
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|this, _, cx| {
+                            this.show_resolved = !this.show_resolved;
+                            cx.notify();
+                        })),
+                    ),
+            )
+            .when(self.draft.is_some(), |this| {
+                this.child(self.render_composer(cx))
+            })
+            .child(
+                v_flex()
+                    .id("comment-threads")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(threads.is_empty(), |this| {
+                        this.child(
+                            div().p_2().child(
+                                Label::new("Select some text and add a comment to start a review.")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                    })
+                    .children(threads.into_iter().map(|(thread, range, excerpt)| {
+                        self.render_thread(&thread, range, excerpt, cx)
+                    })),
+            )
+    }
+}
+
+impl FocusableView for ReviewPanel {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.composer.read(cx).focus_handle(cx)
+    }
+}
@@ -371,6 +371,10 @@ impl PickerDelegate for CommandPaletteDelegate {
         cx.dispatch_action(action);
     }
 
+    fn accessible_label(&self, ix: usize, _: &AppContext) -> Option<String> {
+        Some(self.matches.get(ix)?.string.clone())
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -535,6 +539,46 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_command_palette_announcements(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        let palette = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<CommandPalette>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+
+        cx.simulate_input("editor");
+        let (match_count, first_match, second_match) = palette.update(cx, |palette, _| {
+            let matches = &palette.delegate.matches;
+            (
+                matches.len(),
+                matches[0].string.clone(),
+                matches[1].string.clone(),
+            )
+        });
+        assert_eq!(
+            cx.take_announcements().last().unwrap(),
+            &format!("{first_match}, 1 of {match_count}")
+        );
+
+        palette.update(cx, |palette, cx| palette.select_next(&menu::SelectNext, cx));
+        assert_eq!(
+            cx.take_announcements(),
+            [format!("{second_match}, 2 of {match_count}")]
+        );
+
+        cx.simulate_input("zzzz");
+        assert_eq!(cx.take_announcements().last().unwrap(), "No matches");
+    }
+
     #[gpui::test]
     async fn test_go_to_line(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
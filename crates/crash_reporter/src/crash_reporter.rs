@@ -0,0 +1,166 @@
+mod minidump;
+mod reports;
+mod reports_view;
+mod watchdog;
+
+use anyhow::Result;
+use futures::{channel::mpsc, StreamExt};
+use gpui::{actions, AppContext, VisualContext};
+use release_channel::{AppVersion, RELEASE_CHANNEL};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources, SettingsStore};
+use std::{env, time::Duration};
+use util::ResultExt;
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    Workspace,
+};
+
+pub use reports::{Report, ReportKind};
+pub use reports_view::CrashReportsView;
+
+actions!(crash_reporter, [OpenReports]);
+
+#[derive(Serialize, Deserialize)]
+pub struct LocationData {
+    pub file: String,
+    pub line: u32,
+}
+
+/// A report of a panic or of the main thread becoming unresponsive, as it is
+/// written to the logs directory and sent to `zed.dev`.
+#[derive(Serialize, Deserialize)]
+pub struct Panic {
+    pub thread: String,
+    pub payload: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_data: Option<LocationData>,
+    pub backtrace: Vec<String>,
+    pub app_version: String,
+    pub release_channel: String,
+    pub os_name: String,
+    pub os_version: Option<String>,
+    pub architecture: String,
+    pub panicked_on: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installation_id: Option<String>,
+    pub session_id: String,
+}
+
+#[derive(Serialize)]
+pub struct PanicRequest {
+    pub panic: Panic,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CrashReporterSettings {
+    pub detect_hangs: bool,
+    pub hang_threshold_ms: u64,
+}
+
+/// Settings for detecting when Zed stops responding.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CrashReporterSettingsContent {
+    /// Whether to write a report, including a backtrace and a minidump of the
+    /// main thread, when Zed stops responding.
+    ///
+    /// Default: true
+    detect_hangs: Option<bool>,
+    /// How long the main thread has to be blocked before it's considered
+    /// unresponsive, in milliseconds.
+    ///
+    /// Default: 2000
+    hang_threshold_ms: Option<u64>,
+}
+
+impl Settings for CrashReporterSettings {
+    const KEY: Option<&'static str> = Some("crash_reporter");
+
+    type FileContent = CrashReporterSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+pub fn init(installation_id: Option<String>, session_id: String, cx: &mut AppContext) {
+    CrashReporterSettings::register(cx);
+
+    let metadata = cx.app_metadata();
+    let template = Panic {
+        thread: "main".into(),
+        payload: String::new(),
+        location_data: None,
+        backtrace: Vec::new(),
+        app_version: AppVersion::global(cx).to_string(),
+        release_channel: RELEASE_CHANNEL.display_name().into(),
+        os_name: metadata.os_name.into(),
+        os_version: metadata.os_version.as_ref().map(ToString::to_string),
+        architecture: env::consts::ARCH.into(),
+        panicked_on: 0,
+        installation_id,
+        session_id,
+    };
+
+    let (hangs_tx, mut hangs_rx) = mpsc::unbounded();
+    let watchdog = watchdog::start(template, hangs_tx, cx);
+    let mut threshold = hang_threshold(cx);
+    watchdog.set_threshold(threshold);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_threshold = hang_threshold(cx);
+        if new_threshold != threshold {
+            threshold = new_threshold;
+            watchdog.set_threshold(threshold);
+        }
+    })
+    .detach();
+
+    cx.spawn(|mut cx| async move {
+        while let Some(blocked_for) = hangs_rx.next().await {
+            cx.update(|cx| notify_hang(blocked_for, cx)).log_err();
+        }
+    })
+    .detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenReports, cx| {
+            CrashReportsView::open(workspace, cx);
+        });
+    })
+    .detach();
+}
+
+fn hang_threshold(cx: &AppContext) -> Option<Duration> {
+    let settings = CrashReporterSettings::get_global(cx);
+    settings
+        .detect_hangs
+        .then(|| Duration::from_millis(settings.hang_threshold_ms))
+}
+
+/// Lets the user know that Zed was unresponsive, and where to find the report.
+fn notify_hang(blocked_for: Duration, cx: &mut AppContext) {
+    let Some(window) = cx
+        .active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+    else {
+        return;
+    };
+
+    struct HangNotification;
+
+    window
+        .update(cx, |workspace, cx| {
+            workspace.show_notification(NotificationId::unique::<HangNotification>(), cx, |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(format!(
+                        "Zed was unresponsive for {:.1}s. A report was saved locally.",
+                        blocked_for.as_secs_f32()
+                    ))
+                    .with_click_message("View Report")
+                    .on_click(|cx| cx.dispatch_action(Box::new(OpenReports)))
+                })
+            });
+        })
+        .log_err();
+}
@@ -0,0 +1,585 @@
+//! Writes minidumps of a single thread, in the format that Breakpad, Crashpad and
+//! `rust-minidump` read, so that a hang can be inspected with the usual tools.
+
+// Threads are only captured on Linux and macOS.
+#![cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+
+use std::path::PathBuf;
+
+/// The registers of a thread, in the order of the minidump's context for the
+/// current architecture.
+pub(crate) type Registers = [u64; arch::REGISTER_COUNT];
+
+/// The state of a thread when it was captured.
+pub(crate) struct ThreadState {
+    pub thread_id: u32,
+    pub registers: Registers,
+    /// The address of the first byte of `stack`, which is the stack pointer.
+    pub stack_start: u64,
+    pub stack: Vec<u8>,
+}
+
+/// An executable or library loaded into the process.
+pub(crate) struct Module {
+    pub base: u64,
+    pub size: u32,
+    pub path: PathBuf,
+    /// The identifier that the module's debug information is looked up by, which is
+    /// the GNU build id on Linux and the Mach-O UUID on macOS.
+    pub debug_id: Option<Vec<u8>>,
+}
+
+const SIGNATURE: u32 = 0x504d444d; // "MDMP"
+const VERSION: u32 = 0xa793;
+const THREAD_LIST_STREAM: u32 = 3;
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const SYSTEM_INFO_STREAM: u32 = 7;
+const STREAM_COUNT: u32 = 4;
+const HEADER_SIZE: u32 = 32;
+const DIRECTORY_ENTRY_SIZE: u32 = 12;
+
+#[cfg(target_os = "macos")]
+const PLATFORM_ID: u32 = 0x8101;
+#[cfg(not(target_os = "macos"))]
+const PLATFORM_ID: u32 = 0x8201;
+
+/// The signature of a CodeView record holding an ELF build id.
+const CV_SIGNATURE_ELF: u32 = 0x4270454c; // "BpEL"
+/// The signature of a CodeView record holding a PDB 7.0 GUID, which holds the
+/// Mach-O UUID on macOS.
+const CV_SIGNATURE_PDB70: u32 = 0x53445352; // "RSDS"
+
+/// Writes a minidump of the thread, with the modules that its stack frames may
+/// point into.
+pub(crate) fn write(thread: &ThreadState, modules: &[Module], timestamp: u32) -> Vec<u8> {
+    let mut writer = Writer::default();
+
+    writer.put_u32(SIGNATURE);
+    writer.put_u32(VERSION);
+    writer.put_u32(STREAM_COUNT);
+    writer.put_u32(HEADER_SIZE);
+    writer.put_u32(0); // CheckSum
+    writer.put_u32(timestamp);
+    writer.put_u64(0); // Flags
+    let directory = writer.reserve(STREAM_COUNT * DIRECTORY_ENTRY_SIZE);
+
+    let system_info = writer.rva();
+    writer.put_u16(arch::PROCESSOR_ARCHITECTURE);
+    writer.put_u16(0); // ProcessorLevel
+    writer.put_u16(0); // ProcessorRevision
+    writer
+        .put_u8(std::thread::available_parallelism().map_or(1, |count| count.get().min(255) as u8));
+    writer.put_u8(0); // ProductType
+    writer.put_u32(0); // MajorVersion
+    writer.put_u32(0); // MinorVersion
+    writer.put_u32(0); // BuildNumber
+    writer.put_u32(PLATFORM_ID);
+    let csd_version = writer.reserve(4);
+    writer.put_u16(0); // SuiteMask
+    writer.put_u16(0); // Reserved2
+    writer.put_bytes(&[0; 24]); // Cpu
+    let system_info_size = writer.rva() - system_info;
+    let csd_version_rva = writer.put_string("");
+    writer.set_u32(csd_version, csd_version_rva);
+
+    writer.align();
+    let context = writer.rva();
+    writer.put_bytes(&arch::context(&thread.registers));
+    let context_size = writer.rva() - context;
+
+    let stack = writer.rva();
+    writer.put_bytes(&thread.stack);
+    let stack_size = thread.stack.len() as u32;
+
+    writer.align();
+    let thread_list = writer.rva();
+    writer.put_u32(1);
+    writer.put_u32(thread.thread_id);
+    writer.put_u32(0); // SuspendCount
+    writer.put_u32(0); // PriorityClass
+    writer.put_u32(0); // Priority
+    writer.put_u64(0); // Teb
+    writer.put_u64(thread.stack_start);
+    writer.put_u32(stack_size);
+    writer.put_u32(stack);
+    writer.put_u32(context_size);
+    writer.put_u32(context);
+    let thread_list_size = writer.rva() - thread_list;
+
+    let memory_list = writer.rva();
+    writer.put_u32(1);
+    writer.put_u64(thread.stack_start);
+    writer.put_u32(stack_size);
+    writer.put_u32(stack);
+    let memory_list_size = writer.rva() - memory_list;
+
+    let module_records = modules
+        .iter()
+        .map(|module| {
+            let name = writer.put_string(&module.path.to_string_lossy());
+            let code_view = module
+                .debug_id
+                .as_ref()
+                .map(|debug_id| writer.put_code_view(debug_id, &module.path.to_string_lossy()));
+            (name, code_view)
+        })
+        .collect::<Vec<_>>();
+    writer.align();
+    let module_list = writer.rva();
+    writer.put_u32(modules.len() as u32);
+    for (module, (name, code_view)) in modules.iter().zip(module_records) {
+        writer.put_u64(module.base);
+        writer.put_u32(module.size);
+        writer.put_u32(0); // CheckSum
+        writer.put_u32(0); // TimeDateStamp
+        writer.put_u32(name);
+        writer.put_bytes(&[0; 52]); // VersionInfo
+        let (code_view_size, code_view) = code_view.unwrap_or_default();
+        writer.put_u32(code_view_size);
+        writer.put_u32(code_view);
+        writer.put_u64(0); // MiscRecord
+        writer.put_u64(0); // Reserved0
+        writer.put_u64(0); // Reserved1
+    }
+    let module_list_size = writer.rva() - module_list;
+
+    for (ix, (stream_type, size, rva)) in [
+        (SYSTEM_INFO_STREAM, system_info_size, system_info),
+        (THREAD_LIST_STREAM, thread_list_size, thread_list),
+        (MEMORY_LIST_STREAM, memory_list_size, memory_list),
+        (MODULE_LIST_STREAM, module_list_size, module_list),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let entry = directory + ix as u32 * DIRECTORY_ENTRY_SIZE;
+        writer.set_u32(entry, stream_type);
+        writer.set_u32(entry + 4, size);
+        writer.set_u32(entry + 8, rva);
+    }
+
+    writer.bytes
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    /// The offset of the next byte that's written.
+    fn rva(&self) -> u32 {
+        self.bytes.len() as u32
+    }
+
+    fn put_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn put_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Reserves zeroed bytes to be set later, returning their offset.
+    fn reserve(&mut self, len: u32) -> u32 {
+        let rva = self.rva();
+        self.bytes.resize(self.bytes.len() + len as usize, 0);
+        rva
+    }
+
+    fn set_u32(&mut self, rva: u32, value: u32) {
+        let rva = rva as usize;
+        self.bytes[rva..rva + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn align(&mut self) {
+        while self.bytes.len() % 4 != 0 {
+            self.bytes.push(0);
+        }
+    }
+
+    /// Writes a `MINIDUMP_STRING`, which is the length of the string in bytes
+    /// followed by the string in null-terminated UTF-16, returning its offset.
+    fn put_string(&mut self, string: &str) -> u32 {
+        self.align();
+        let rva = self.rva();
+        let units = string.encode_utf16().collect::<Vec<_>>();
+        self.put_u32(units.len() as u32 * 2);
+        for unit in units {
+            self.put_u16(unit);
+        }
+        self.put_u16(0);
+        rva
+    }
+
+    /// Writes the CodeView record that identifies a module's debug information,
+    /// returning its size and offset.
+    fn put_code_view(&mut self, debug_id: &[u8], path: &str) -> (u32, u32) {
+        self.align();
+        let rva = self.rva();
+        if cfg!(target_os = "macos") && debug_id.len() == 16 {
+            self.put_u32(CV_SIGNATURE_PDB70);
+            self.put_bytes(debug_id);
+            self.put_u32(0); // Age
+            self.put_bytes(path.as_bytes());
+            self.put_u8(0);
+        } else {
+            self.put_u32(CV_SIGNATURE_ELF);
+            self.put_bytes(debug_id);
+        }
+        (self.rva() - rva, rva)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod arch {
+    pub const PROCESSOR_ARCHITECTURE: u16 = 9; // AMD64
+    /// `rax`, `rcx`, `rdx`, `rbx`, `rsp`, `rbp`, `rsi`, `rdi`, `r8` to `r15`, `rip`
+    /// and `eflags`.
+    pub const REGISTER_COUNT: usize = 18;
+    pub const SP: usize = 4;
+    pub const FP: usize = 5;
+    pub const PC: usize = 16;
+    const EFLAGS: usize = 17;
+
+    const CONTEXT_SIZE: usize = 1232;
+    const CONTEXT_FLAGS: u32 = 0x0010_0003; // CONTEXT_AMD64 | CONTROL | INTEGER
+
+    /// Lays out the registers as a `CONTEXT_AMD64`.
+    pub fn context(registers: &super::Registers) -> Vec<u8> {
+        let mut context = vec![0; CONTEXT_SIZE];
+        context[0x30..0x34].copy_from_slice(&CONTEXT_FLAGS.to_le_bytes());
+        context[0x44..0x48].copy_from_slice(&(registers[EFLAGS] as u32).to_le_bytes());
+        for (ix, register) in registers[..=PC].iter().enumerate() {
+            let offset = 0x78 + ix * 8;
+            context[offset..offset + 8].copy_from_slice(&register.to_le_bytes());
+        }
+        context
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) mod arch {
+    pub const PROCESSOR_ARCHITECTURE: u16 = 12; // ARM64
+    /// `x0` to `x28`, `fp`, `lr`, `sp`, `pc` and `cpsr`.
+    pub const REGISTER_COUNT: usize = 34;
+    pub const FP: usize = 29;
+    pub const SP: usize = 31;
+    pub const PC: usize = 32;
+    pub const CPSR: usize = 33;
+
+    const CONTEXT_SIZE: usize = 912;
+    const CONTEXT_FLAGS: u32 = 0x0040_0003; // CONTEXT_ARM64 | CONTROL | INTEGER
+
+    /// Lays out the registers as a `CONTEXT_ARM64`.
+    pub fn context(registers: &super::Registers) -> Vec<u8> {
+        let mut context = vec![0; CONTEXT_SIZE];
+        context[0..4].copy_from_slice(&CONTEXT_FLAGS.to_le_bytes());
+        context[4..8].copy_from_slice(&(registers[CPSR] as u32).to_le_bytes());
+        for (ix, register) in registers[..=PC].iter().enumerate() {
+            let offset = 8 + ix * 8;
+            context[offset..offset + 8].copy_from_slice(&register.to_le_bytes());
+        }
+        context
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) mod arch {
+    pub const PROCESSOR_ARCHITECTURE: u16 = 0xffff; // Unknown
+    /// Threads can't be captured on other architectures, so no registers are known.
+    pub const REGISTER_COUNT: usize = 0;
+    pub const SP: usize = 0;
+    pub const FP: usize = 0;
+    pub const PC: usize = 0;
+
+    pub fn context(_registers: &super::Registers) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/// Returns the modules loaded into the process.
+#[cfg(target_os = "linux")]
+pub(crate) fn loaded_modules() -> Vec<Module> {
+    use std::{
+        ffi::{c_int, c_void, CStr},
+        os::unix::ffi::OsStrExt as _,
+        path::Path,
+    };
+
+    const NT_GNU_BUILD_ID: u32 = 3;
+
+    unsafe extern "C" fn add_module(
+        info: *mut libc::dl_phdr_info,
+        _size: libc::size_t,
+        modules: *mut c_void,
+    ) -> c_int {
+        let modules = &mut *(modules as *mut Vec<Module>);
+        let info = &*info;
+        let headers = std::slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize);
+
+        let mut start = u64::MAX;
+        let mut end = 0;
+        let mut debug_id = None;
+        for header in headers {
+            match header.p_type {
+                libc::PT_LOAD => {
+                    start = start.min(header.p_vaddr);
+                    end = end.max(header.p_vaddr + header.p_memsz);
+                }
+                libc::PT_NOTE if debug_id.is_none() => {
+                    let notes = std::slice::from_raw_parts(
+                        (info.dlpi_addr + header.p_vaddr) as *const u8,
+                        header.p_memsz as usize,
+                    );
+                    debug_id = find_note(notes, b"GNU\0", NT_GNU_BUILD_ID).map(<[u8]>::to_vec);
+                }
+                _ => {}
+            }
+        }
+        if start >= end {
+            return 0;
+        }
+
+        // The executable itself has no name.
+        let name = CStr::from_ptr(info.dlpi_name).to_bytes();
+        let path = if name.is_empty() {
+            std::env::current_exe().unwrap_or_default()
+        } else {
+            Path::new(std::ffi::OsStr::from_bytes(name)).to_path_buf()
+        };
+        modules.push(Module {
+            base: info.dlpi_addr + start,
+            size: (end - start) as u32,
+            path,
+            debug_id,
+        });
+        0
+    }
+
+    let mut modules = Vec::new();
+    unsafe {
+        libc::dl_iterate_phdr(Some(add_module), &mut modules as *mut _ as *mut c_void);
+    }
+    modules
+}
+
+/// Returns the description of the first ELF note with the given name and type.
+#[cfg(any(target_os = "linux", test))]
+fn find_note<'a>(mut notes: &'a [u8], name: &[u8], note_type: u32) -> Option<&'a [u8]> {
+    fn aligned(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    while notes.len() >= 12 {
+        let field =
+            |ix: usize| u32::from_ne_bytes(notes[ix * 4..ix * 4 + 4].try_into().unwrap()) as usize;
+        let (name_size, description_size, this_type) = (field(0), field(1), field(2));
+        let name_start = 12;
+        let description_start = name_start + aligned(name_size);
+        let next = description_start + aligned(description_size);
+        if notes.len() < description_start + description_size {
+            return None;
+        }
+        if this_type as u32 == note_type && &notes[name_start..name_start + name_size] == name {
+            return Some(&notes[description_start..description_start + description_size]);
+        }
+        notes = notes.get(next..)?;
+    }
+    None
+}
+
+/// Returns the modules loaded into the process.
+#[cfg(target_os = "macos")]
+pub(crate) fn loaded_modules() -> Vec<Module> {
+    use std::{ffi::CStr, os::unix::ffi::OsStrExt as _, path::Path};
+
+    const LC_SEGMENT_64: u32 = 0x19;
+    const LC_UUID: u32 = 0x1b;
+
+    let mut modules = Vec::new();
+    unsafe {
+        for ix in 0..libc::_dyld_image_count() {
+            let header = libc::_dyld_get_image_header(ix) as *const libc::mach_header_64;
+            let name = libc::_dyld_get_image_name(ix);
+            if header.is_null() || name.is_null() {
+                continue;
+            }
+            let slide = libc::_dyld_get_image_vmaddr_slide(ix) as u64;
+
+            let mut start = u64::MAX;
+            let mut end = 0;
+            let mut debug_id = None;
+            let mut command = header.add(1) as *const u8;
+            for _ in 0..(*header).ncmds {
+                let command_type = (command as *const u32).read_unaligned();
+                let command_size = (command as *const u32).add(1).read_unaligned();
+                match command_type {
+                    LC_SEGMENT_64 => {
+                        let segment = (command as *const libc::segment_command_64).read_unaligned();
+                        // Skip the __PAGEZERO segment, which maps nothing.
+                        if segment.filesize > 0 || segment.initprot != 0 {
+                            start = start.min(segment.vmaddr);
+                            end = end.max(segment.vmaddr + segment.vmsize);
+                        }
+                    }
+                    LC_UUID => {
+                        let uuid = std::slice::from_raw_parts(command.add(8), 16);
+                        debug_id = Some(uuid.to_vec());
+                    }
+                    _ => {}
+                }
+                command = command.add(command_size as usize);
+            }
+            if start >= end {
+                continue;
+            }
+
+            modules.push(Module {
+                base: start + slide,
+                size: (end - start) as u32,
+                path: Path::new(std::ffi::OsStr::from_bytes(CStr::from_ptr(name).to_bytes()))
+                    .to_path_buf(),
+                debug_id,
+            });
+        }
+    }
+    modules
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub(crate) fn loaded_modules() -> Vec<Module> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(bytes: &[u8], rva: u32) -> u32 {
+        let rva = rva as usize;
+        u32::from_le_bytes(bytes[rva..rva + 4].try_into().unwrap())
+    }
+
+    fn read_u64(bytes: &[u8], rva: u32) -> u64 {
+        let rva = rva as usize;
+        u64::from_le_bytes(bytes[rva..rva + 8].try_into().unwrap())
+    }
+
+    fn read_string(bytes: &[u8], rva: u32) -> String {
+        let len = read_u32(bytes, rva) as usize;
+        let start = rva as usize + 4;
+        let units = bytes[start..start + len]
+            .chunks(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect::<Vec<_>>();
+        String::from_utf16(&units).unwrap()
+    }
+
+    #[test]
+    fn test_write_minidump() {
+        let thread = ThreadState {
+            thread_id: 42,
+            registers: [7; arch::REGISTER_COUNT],
+            stack_start: 0x7fff_0000,
+            stack: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let modules = [Module {
+            base: 0x1000,
+            size: 0x2000,
+            path: PathBuf::from("/usr/lib/zed"),
+            debug_id: Some(vec![0xab; 16]),
+        }];
+        let minidump = write(&thread, &modules, 1234);
+
+        assert_eq!(read_u32(&minidump, 0), SIGNATURE);
+        assert_eq!(read_u32(&minidump, 8), STREAM_COUNT);
+        assert_eq!(read_u32(&minidump, 20), 1234);
+
+        let directory = read_u32(&minidump, 12);
+        let stream = |stream_type| {
+            (0..STREAM_COUNT)
+                .map(|ix| directory + ix * DIRECTORY_ENTRY_SIZE)
+                .find(|&entry| read_u32(&minidump, entry) == stream_type)
+                .map(|entry| read_u32(&minidump, entry + 8))
+                .unwrap()
+        };
+
+        let system_info = stream(SYSTEM_INFO_STREAM);
+        assert_eq!(
+            minidump[system_info as usize..system_info as usize + 2],
+            arch::PROCESSOR_ARCHITECTURE.to_le_bytes()
+        );
+        assert_eq!(read_u32(&minidump, system_info + 20), PLATFORM_ID);
+
+        let thread_list = stream(THREAD_LIST_STREAM);
+        assert_eq!(read_u32(&minidump, thread_list), 1);
+        assert_eq!(read_u32(&minidump, thread_list + 4), 42);
+        assert_eq!(read_u64(&minidump, thread_list + 28), 0x7fff_0000);
+        let stack_size = read_u32(&minidump, thread_list + 36);
+        let stack = read_u32(&minidump, thread_list + 40) as usize;
+        assert_eq!(
+            &minidump[stack..stack + stack_size as usize],
+            &thread.stack[..]
+        );
+        let context_size = read_u32(&minidump, thread_list + 44) as usize;
+        let context = read_u32(&minidump, thread_list + 48) as usize;
+        assert_eq!(
+            &minidump[context..context + context_size],
+            &arch::context(&thread.registers)[..]
+        );
+
+        let memory_list = stream(MEMORY_LIST_STREAM);
+        assert_eq!(read_u32(&minidump, memory_list), 1);
+        assert_eq!(read_u64(&minidump, memory_list + 4), 0x7fff_0000);
+
+        let module_list = stream(MODULE_LIST_STREAM);
+        assert_eq!(read_u32(&minidump, module_list), 1);
+        assert_eq!(read_u64(&minidump, module_list + 4), 0x1000);
+        assert_eq!(read_u32(&minidump, module_list + 12), 0x2000);
+        let name = read_u32(&minidump, module_list + 24);
+        assert_eq!(read_string(&minidump, name), "/usr/lib/zed");
+        let code_view_size = read_u32(&minidump, module_list + 80);
+        let code_view = read_u32(&minidump, module_list + 84);
+        assert!(code_view_size > 0);
+        assert!(matches!(
+            read_u32(&minidump, code_view),
+            CV_SIGNATURE_ELF | CV_SIGNATURE_PDB70
+        ));
+    }
+
+    #[test]
+    fn test_find_note() {
+        let mut notes = Vec::new();
+        for (name, note_type, description) in [
+            (&b"Other\0"[..], 3, &b"skipped"[..]),
+            (b"GNU\0", 1, b"abi"),
+            (b"GNU\0", 3, b"\x01\x02\x03\x04\x05"),
+        ] {
+            notes.extend_from_slice(&(name.len() as u32).to_ne_bytes());
+            notes.extend_from_slice(&(description.len() as u32).to_ne_bytes());
+            notes.extend_from_slice(&(note_type as u32).to_ne_bytes());
+            notes.extend_from_slice(name);
+            notes.resize((notes.len() + 3) & !3, 0);
+            notes.extend_from_slice(description);
+            notes.resize((notes.len() + 3) & !3, 0);
+        }
+
+        assert_eq!(find_note(&notes, b"GNU\0", 3), Some(&[1, 2, 3, 4, 5][..]));
+        assert_eq!(find_note(&notes, b"GNU\0", 4), None);
+        assert_eq!(find_note(&notes[..20], b"GNU\0", 3), None);
+    }
+}
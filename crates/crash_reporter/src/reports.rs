@@ -0,0 +1,223 @@
+use crate::{Panic, PanicRequest};
+use anyhow::{anyhow, Context as _, Result};
+use chrono::{DateTime, Local};
+use futures::StreamExt;
+use isahc::{prelude::Configurable, Request};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{
+    http::{HttpClient, HttpClientWithUrl},
+    paths::{self, CRASHES_DIR, CRASHES_RETIRED_DIR},
+    ResultExt,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportKind {
+    Panic,
+    Hang,
+    /// A crash report written by the operating system.
+    Crash,
+}
+
+impl ReportKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportKind::Panic => "Panic",
+            ReportKind::Hang => "Hang",
+            ReportKind::Crash => "Crash",
+        }
+    }
+}
+
+/// A panic, hang or crash report stored on this machine.
+pub struct Report {
+    pub path: PathBuf,
+    pub kind: ReportKind,
+    pub created_at: Option<DateTime<Local>>,
+    pub contents: String,
+    /// The parsed contents of panic and hang reports.
+    pub panic: Option<Panic>,
+    /// The minidump of the main thread that was written along with a hang report.
+    pub minidump_path: Option<PathBuf>,
+}
+
+impl Report {
+    pub fn title(&self) -> String {
+        match &self.panic {
+            Some(panic) => panic.payload.clone(),
+            None => self
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The report formatted for reading, with the backtrace on separate lines.
+    pub fn details(&self) -> String {
+        let Some(panic) = &self.panic else {
+            return self.contents.clone();
+        };
+
+        let mut details = format!(
+            "{}\n\nThread: {}\nVersion: {} ({})\nOS: {} {} ({})\n",
+            panic.payload,
+            panic.thread,
+            panic.app_version,
+            panic.release_channel,
+            panic.os_name,
+            panic.os_version.as_deref().unwrap_or_default(),
+            panic.architecture,
+        );
+        if let Some(location) = &panic.location_data {
+            details.push_str(&format!("Location: {}:{}\n", location.file, location.line));
+        }
+        if let Some(minidump_path) = &self.minidump_path {
+            details.push_str(&format!("Minidump: {}\n", minidump_path.display()));
+        }
+        details.push_str("\nBacktrace:\n");
+        if panic.backtrace.is_empty() {
+            details.push_str("  <unavailable>\n");
+        }
+        for frame in &panic.backtrace {
+            details.push_str(&format!("  {frame}\n"));
+        }
+        details
+    }
+}
+
+/// Loads all of the panic, hang and crash reports, from newest to oldest.
+pub async fn load_reports() -> Result<Vec<Report>> {
+    let mut reports = Vec::new();
+    for dir in [
+        Some(&*paths::LOGS_DIR),
+        CRASHES_DIR.as_ref(),
+        CRASHES_RETIRED_DIR.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let Some(mut children) = smol::fs::read_dir(dir).await.log_err() else {
+            continue;
+        };
+        while let Some(child) = children.next().await {
+            let path = child?.path();
+            if let Some(report) = load_report(path).await.log_err().flatten() {
+                reports.push(report);
+            }
+        }
+    }
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(reports)
+}
+
+async fn load_report(path: PathBuf) -> Result<Option<Report>> {
+    let Some(kind) = report_kind(&path) else {
+        return Ok(None);
+    };
+
+    let contents = smol::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("error reading report {path:?}"))?;
+    let panic = match kind {
+        // Older panic files may contain several panics, one per line.
+        ReportKind::Panic | ReportKind::Hang => contents
+            .lines()
+            .next()
+            .and_then(|line| serde_json::from_str::<Panic>(line).ok()),
+        ReportKind::Crash => None,
+    };
+    let created_at = smol::fs::metadata(&path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(DateTime::<Local>::from);
+    let minidump_path = match kind {
+        ReportKind::Hang => {
+            Some(path.with_extension("dmp")).filter(|minidump_path| minidump_path.exists())
+        }
+        ReportKind::Panic | ReportKind::Crash => None,
+    };
+
+    Ok(Some(Report {
+        path,
+        kind,
+        created_at,
+        contents,
+        panic,
+        minidump_path,
+    }))
+}
+
+fn report_kind(path: &Path) -> Option<ReportKind> {
+    let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+    if !file_name.starts_with("zed") {
+        return None;
+    }
+    match path.extension().and_then(OsStr::to_str)? {
+        "panic" => Some(ReportKind::Panic),
+        "hang" => Some(ReportKind::Hang),
+        "ips" => Some(ReportKind::Crash),
+        _ => None,
+    }
+}
+
+/// Sends the report to `zed.dev`, the same way reports are sent automatically
+/// when diagnostics are enabled.
+pub async fn submit_report(http: Arc<HttpClientWithUrl>, report: &Report) -> Result<()> {
+    let request = match report.kind {
+        ReportKind::Panic | ReportKind::Hang => {
+            let panic: Panic = serde_json::from_str(
+                report
+                    .contents
+                    .lines()
+                    .next()
+                    .ok_or_else(|| anyhow!("empty report"))?,
+            )?;
+            Request::post(&http.build_url("/api/panic"))
+                .redirect_policy(isahc::config::RedirectPolicy::Follow)
+                .header("Content-Type", "application/json")
+                .body(serde_json::to_string(&PanicRequest { panic })?.into())?
+        }
+        ReportKind::Crash => Request::post(
+            &http
+                .build_zed_api_url("/telemetry/crashes", &[])?
+                .to_string(),
+        )
+        .redirect_policy(isahc::config::RedirectPolicy::Follow)
+        .header("Content-Type", "text/plain")
+        .body(report.contents.clone().into())?,
+    };
+
+    let response = http.send(request).await.context("error sending report")?;
+    if !response.status().is_success() {
+        return Err(anyhow!("error uploading report: {}", response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_kind() {
+        assert_eq!(
+            report_kind(Path::new("/logs/zed-2024_05_01 10_00_00.panic")),
+            Some(ReportKind::Panic)
+        );
+        assert_eq!(
+            report_kind(Path::new("/logs/zed-2024_05_01 10_00_00.hang")),
+            Some(ReportKind::Hang)
+        );
+        assert_eq!(
+            report_kind(Path::new("/reports/Zed-2024-05-01-100000.ips")),
+            Some(ReportKind::Crash)
+        );
+        assert_eq!(report_kind(Path::new("/logs/Zed.log")), None);
+        assert_eq!(report_kind(Path::new("/reports/other-app.ips")), None);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{
+    reports::{load_reports, submit_report},
+    Report, ReportKind,
+};
+use anyhow::anyhow;
+use client::Client;
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, SharedString, Task, View,
+    ViewContext, VisualContext,
+};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::{paths, ResultExt};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+/// Lists the panic, hang and crash reports stored on this machine, and lets the
+/// user inspect, save, submit or delete them.
+pub struct CrashReportsView {
+    focus_handle: FocusHandle,
+    reports: Vec<Report>,
+    selected_ix: usize,
+    status: Option<SharedString>,
+    pending_task: Task<()>,
+}
+
+impl CrashReportsView {
+    pub(crate) fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .find_map(|item| item.downcast::<Self>());
+
+        if let Some(existing) = existing {
+            existing.update(cx, |view, cx| view.load_reports(cx));
+            workspace.activate_item(&existing, cx);
+        } else {
+            let view = cx.new_view(Self::new);
+            workspace.add_item_to_active_pane(Box::new(view), cx);
+        }
+    }
+
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            reports: Vec::new(),
+            selected_ix: 0,
+            status: None,
+            pending_task: Task::ready(()),
+        };
+        this.load_reports(cx);
+        this
+    }
+
+    fn load_reports(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_task = cx.spawn(|this, mut cx| async move {
+            let reports = load_reports().await.log_err().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.reports = reports;
+                this.selected_ix = this.selected_ix.min(this.reports.len().saturating_sub(1));
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn selected_report(&self) -> Option<&Report> {
+        self.reports.get(self.selected_ix)
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_ix = ix;
+        self.status = None;
+        cx.notify();
+    }
+
+    fn save_selected(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(report) = self.selected_report() else {
+            return;
+        };
+        let contents = report.contents.clone();
+        let minidump_path = report.minidump_path.clone();
+        let path = cx.prompt_for_new_path(&paths::HOME);
+        cx.spawn(|_, _| async move {
+            let Some(path) = path.await.ok().flatten() else {
+                return Ok(());
+            };
+            // The minidump is saved next to the report.
+            if let Some(minidump_path) = minidump_path {
+                smol::fs::copy(minidump_path, path.with_extension("dmp")).await?;
+            }
+            smol::fs::write(path, contents).await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn submit_selected(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(report) = self.selected_report() else {
+            return;
+        };
+        let path = report.path.clone();
+        let minidump_path = report.minidump_path.clone();
+        let kind = report.kind;
+        let http = Client::global(cx).http_client();
+        self.status = Some("Submitting…".into());
+        cx.notify();
+
+        self.pending_task = cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let reports = load_reports().await?;
+                let report = reports
+                    .iter()
+                    .find(|report| report.path == path)
+                    .ok_or_else(|| anyhow!("report no longer exists"))?;
+                submit_report(http, report).await
+            }
+            .await;
+
+            // Crash reports are owned by the operating system, so only Zed's own
+            // reports are removed once they've been submitted.
+            if result.is_ok() && kind != ReportKind::Crash {
+                smol::fs::remove_file(&path).await.log_err();
+                if let Some(minidump_path) = minidump_path {
+                    smol::fs::remove_file(minidump_path).await.log_err();
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.status = Some(match result {
+                    Ok(()) => "Report submitted. Thank you!".into(),
+                    Err(error) => format!("Failed to submit report: {error}").into(),
+                });
+                this.load_reports(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn delete_selected(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(report) = self.selected_report() else {
+            return;
+        };
+        let path = report.path.clone();
+        let minidump_path = report.minidump_path.clone();
+        self.status = None;
+        self.pending_task = cx.spawn(|this, mut cx| async move {
+            smol::fs::remove_file(&path).await.log_err();
+            if let Some(minidump_path) = minidump_path {
+                smol::fs::remove_file(minidump_path).await.log_err();
+            }
+            this.update(&mut cx, |this, cx| this.load_reports(cx)).ok();
+        });
+    }
+
+    fn render_report_list(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("crash-reports-list")
+            .w(rems(20.))
+            .h_full()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(self.reports.iter().enumerate().map(|(ix, report)| {
+                let created_at = report
+                    .created_at
+                    .map(|created_at| created_at.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+
+                v_flex()
+                    .id(("crash-report", ix))
+                    .px_2()
+                    .py_1()
+                    .w_full()
+                    .cursor_pointer()
+                    .when(ix == self.selected_ix, |this| {
+                        this.bg(cx.theme().colors().element_selected)
+                    })
+                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .justify_between()
+                            .child(Label::new(report.kind.label()).size(LabelSize::Small))
+                            .child(
+                                Label::new(created_at)
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(
+                        div().overflow_hidden().child(
+                            Label::new(report.title())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| this.select(ix, cx)))
+            }))
+    }
+
+    fn render_details(&self, report: &Report, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let buffer_font = ThemeSettings::get_global(cx).buffer_font.family.clone();
+
+        v_flex()
+            .flex_1()
+            .h_full()
+            .min_w_0()
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(report.path.to_string_lossy().into_owned())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("save-report", "Save…")
+                                    .on_click(cx.listener(|this, _, cx| this.save_selected(cx))),
+                            )
+                            .child(
+                                Button::new("submit-report", "Submit")
+                                    .style(ButtonStyle::Filled)
+                                    .on_click(cx.listener(|this, _, cx| this.submit_selected(cx))),
+                            )
+                            .child(
+                                Button::new("delete-report", "Delete")
+                                    .on_click(cx.listener(|this, _, cx| this.delete_selected(cx))),
+                            ),
+                    ),
+            )
+            .children(self.status.clone().map(|status| {
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(status).size(LabelSize::Small))
+            }))
+            .child(
+                v_flex()
+                    .id("crash-report-details")
+                    .flex_1()
+                    .p_2()
+                    .overflow_y_scroll()
+                    .font_family(buffer_font)
+                    .children(
+                        report
+                            .details()
+                            .lines()
+                            .map(|line| Label::new(line.to_string()).size(LabelSize::Small)),
+                    ),
+            )
+    }
+}
+
+impl Render for CrashReportsView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if let Some(report) = self.selected_report() {
+            h_flex()
+                .flex_1()
+                .min_h_0()
+                .items_start()
+                .child(self.render_report_list(cx))
+                .child(self.render_details(report, cx))
+                .into_any_element()
+        } else {
+            v_flex()
+                .p_4()
+                .child(Label::new("There are no crash or hang reports.").color(Color::Muted))
+                .into_any_element()
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_4()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Headline::new("Crash Reports").size(HeadlineSize::Large))
+                    .child(
+                        Button::new("refresh", "Refresh")
+                            .on_click(cx.listener(|this, _, cx| this.load_reports(cx))),
+                    ),
+            )
+            .child(content)
+    }
+}
+
+impl EventEmitter<ItemEvent> for CrashReportsView {}
+
+impl FocusableView for CrashReportsView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CrashReportsView {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Crash Reports")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(Self::new))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -0,0 +1,508 @@
+use crate::{minidump, Panic};
+use chrono::Utc;
+use futures::channel::mpsc::UnboundedSender;
+use gpui::AppContext;
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use util::{paths, ResultExt};
+
+/// How often the main thread signals that it's still responsive.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the watchdog thread checks the main thread's heartbeat.
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long to wait for the main thread to capture its own state.
+const BACKTRACE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Watches the main thread from a separate thread, and writes a report when
+/// the main thread stops responding for longer than the configured threshold.
+pub(crate) struct Watchdog {
+    /// The hang threshold in milliseconds, or zero if hangs aren't detected.
+    threshold: Arc<AtomicU64>,
+}
+
+impl Watchdog {
+    pub fn set_threshold(&self, threshold: Option<Duration>) {
+        let threshold = threshold.map_or(0, |threshold| threshold.as_millis().max(1) as u64);
+        self.threshold.store(threshold, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn start(
+    template: Panic,
+    hangs_tx: UnboundedSender<Duration>,
+    cx: &mut AppContext,
+) -> Watchdog {
+    main_thread::register();
+
+    let epoch = Instant::now();
+    let heartbeat = Arc::new(AtomicU64::new(0));
+    let threshold = Arc::new(AtomicU64::new(0));
+
+    cx.spawn({
+        let heartbeat = heartbeat.clone();
+        |cx| async move {
+            loop {
+                cx.background_executor().timer(HEARTBEAT_INTERVAL).await;
+                heartbeat.store(epoch.elapsed().as_millis() as u64, Ordering::Relaxed);
+            }
+        }
+    })
+    .detach();
+
+    thread::Builder::new()
+        .name("Watchdog".into())
+        .spawn({
+            let threshold = threshold.clone();
+            move || watch(epoch, heartbeat, threshold, template, hangs_tx)
+        })
+        .log_err();
+
+    Watchdog { threshold }
+}
+
+/// A hang that the main thread hasn't recovered from yet.
+struct Hang {
+    report_path: PathBuf,
+    last_heartbeat: u64,
+}
+
+fn watch(
+    epoch: Instant,
+    heartbeat: Arc<AtomicU64>,
+    threshold: Arc<AtomicU64>,
+    mut report: Panic,
+    hangs_tx: UnboundedSender<Duration>,
+) {
+    let mut last_check = Instant::now();
+    let mut ignore_before = 0;
+    let mut hang: Option<Hang> = None;
+    loop {
+        thread::sleep(CHECK_INTERVAL);
+
+        let now = epoch.elapsed().as_millis() as u64;
+        let last_heartbeat = heartbeat.load(Ordering::Relaxed);
+
+        // When the system wakes up from sleep, neither thread has run in a while,
+        // so don't mistake the missed heartbeats for a hang.
+        if last_check.elapsed() > CHECK_INTERVAL * 4 {
+            ignore_before = now;
+        }
+        last_check = Instant::now();
+
+        if let Some(current) = hang.as_ref() {
+            if last_heartbeat != current.last_heartbeat {
+                let blocked_for = Duration::from_millis(now - current.last_heartbeat);
+                report.payload = hang_message(blocked_for);
+                write_report(&current.report_path, &report);
+                hangs_tx.unbounded_send(blocked_for).ok();
+                hang = None;
+            }
+            continue;
+        }
+
+        let threshold = threshold.load(Ordering::Relaxed);
+        let blocked_for = now.saturating_sub(last_heartbeat.max(ignore_before));
+        if threshold == 0 || blocked_for < threshold {
+            continue;
+        }
+
+        log::error!("main thread has been unresponsive for {blocked_for}ms");
+        let timestamp = Utc::now();
+        report.payload = hang_message(Duration::from_millis(blocked_for));
+        let capture = main_thread::capture(BACKTRACE_TIMEOUT);
+        report.backtrace = capture
+            .as_ref()
+            .map(|capture| capture.backtrace.clone())
+            .unwrap_or_default();
+        report.panicked_on = timestamp.timestamp_millis();
+        let report_path = paths::LOGS_DIR.join(format!(
+            "zed-{}.hang",
+            timestamp.format("%Y_%m_%d %H_%M_%S")
+        ));
+        if let Some(capture) = capture {
+            let minidump = minidump::write(
+                &capture.thread,
+                &minidump::loaded_modules(),
+                timestamp.timestamp() as u32,
+            );
+            std::fs::write(report_path.with_extension("dmp"), minidump).log_err();
+        }
+        // Write the report right away, in case the main thread never recovers.
+        write_report(&report_path, &report);
+        hang = Some(Hang {
+            report_path,
+            last_heartbeat,
+        });
+    }
+}
+
+fn hang_message(blocked_for: Duration) -> String {
+    format!(
+        "Main thread was unresponsive for {}ms",
+        blocked_for.as_millis()
+    )
+}
+
+fn write_report(path: &Path, report: &Panic) {
+    if let Some(json) = serde_json::to_string(report).log_err() {
+        std::fs::write(path, json).log_err();
+    }
+}
+
+/// The state of the main thread while it was unresponsive.
+#[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+struct Capture {
+    backtrace: Vec<String>,
+    thread: minidump::ThreadState,
+}
+
+/// Captures the main thread from another thread, by sending the main thread a
+/// signal whose handler copies its registers and the top of its stack, and walks
+/// its frame pointers. The handler only reads memory between the stack pointer and
+/// the base of the main thread's stack, and only writes to atomics, which is safe
+/// to do in a signal handler, unlike unwinding with the unwinder's tables. Frames
+/// are symbolicated on the watchdog thread afterwards.
+///
+/// Code built without frame pointers, which is most code on x86_64 Linux, cuts the
+/// walk short, so the backtrace stops at the first frame that isn't in any loaded
+/// code. The minidump has the registers and the top of the stack, so the rest of
+/// the frames can still be recovered by unwinding it with the binaries' unwind
+/// tables, like minidump tools do.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod main_thread {
+    use super::Capture;
+    use crate::minidump::{arch, Registers, ThreadState};
+    use std::{
+        ffi::c_void,
+        mem,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+            OnceLock,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    const MAX_FRAMES: usize = 256;
+    /// The most words of the main thread's stack that are copied into a minidump.
+    const MAX_STACK_WORDS: usize = 8 * 1024;
+    const WORD: usize = mem::size_of::<u64>();
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+
+    struct MainThread {
+        pthread: libc::pthread_t,
+        thread_id: u32,
+    }
+
+    static MAIN_THREAD: OnceLock<MainThread> = OnceLock::new();
+    static STACK_LOW: AtomicUsize = AtomicUsize::new(0);
+    static STACK_HIGH: AtomicUsize = AtomicUsize::new(0);
+
+    static REGISTERS: [AtomicU64; arch::REGISTER_COUNT] = [ZERO; arch::REGISTER_COUNT];
+    static FRAMES: [AtomicU64; MAX_FRAMES] = [ZERO; MAX_FRAMES];
+    static FRAME_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static STACK: [AtomicU64; MAX_STACK_WORDS] = [ZERO; MAX_STACK_WORDS];
+    static STACK_START: AtomicU64 = AtomicU64::new(0);
+    static STACK_WORD_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static CAPTURED: AtomicBool = AtomicBool::new(false);
+
+    /// Must be called on the main thread.
+    pub fn register() {
+        MAIN_THREAD.get_or_init(|| unsafe {
+            if let Some((low, high)) = stack_bounds() {
+                STACK_LOW.store(low, Ordering::Relaxed);
+                STACK_HIGH.store(high, Ordering::Relaxed);
+            }
+
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = handle_signal as usize;
+            action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(libc::SIGUSR2, &action, std::ptr::null_mut());
+            MainThread {
+                pthread: libc::pthread_self(),
+                thread_id: thread_id(),
+            }
+        });
+    }
+
+    extern "C" fn handle_signal(_: libc::c_int, _: *mut libc::siginfo_t, context: *mut c_void) {
+        let registers = unsafe { read_registers(&*(context as *const libc::ucontext_t)) };
+        match registers {
+            Some(registers) => capture_registers(&registers),
+            None => FRAME_COUNT.store(0, Ordering::Relaxed),
+        }
+        CAPTURED.store(true, Ordering::Release);
+    }
+
+    fn capture_registers(registers: &Registers) {
+        for (slot, register) in REGISTERS.iter().zip(registers) {
+            slot.store(*register, Ordering::Relaxed);
+        }
+
+        // Everything from the stack pointer to the base of the stack is mapped.
+        let sp = registers[arch::SP] as usize & !(WORD - 1);
+        let high = STACK_HIGH.load(Ordering::Relaxed);
+        if sp < STACK_LOW.load(Ordering::Relaxed) || sp >= high {
+            STACK_WORD_COUNT.store(0, Ordering::Relaxed);
+            FRAMES[0].store(registers[arch::PC], Ordering::Relaxed);
+            FRAME_COUNT.store(1, Ordering::Relaxed);
+            return;
+        }
+        let in_stack = |address: usize, len: usize| {
+            address >= sp && address % WORD == 0 && address.saturating_add(len) <= high
+        };
+
+        let word_count = ((high - sp) / WORD).min(MAX_STACK_WORDS);
+        for (ix, slot) in STACK[..word_count].iter().enumerate() {
+            let word = unsafe { ((sp + ix * WORD) as *const u64).read_volatile() };
+            slot.store(word, Ordering::Relaxed);
+        }
+        STACK_START.store(sp as u64, Ordering::Relaxed);
+        STACK_WORD_COUNT.store(word_count, Ordering::Relaxed);
+
+        // Each frame pointer points to the previous frame pointer, followed by the
+        // return address into the previous frame.
+        FRAMES[0].store(registers[arch::PC], Ordering::Relaxed);
+        let mut count = 1;
+        let mut fp = registers[arch::FP] as usize;
+        while count < MAX_FRAMES && in_stack(fp, 2 * WORD) {
+            let (next_fp, return_address) = unsafe {
+                let frame = fp as *const u64;
+                (frame.read_volatile(), frame.add(1).read_volatile())
+            };
+            if return_address == 0 {
+                break;
+            }
+            FRAMES[count].store(return_address, Ordering::Relaxed);
+            count += 1;
+            if next_fp as usize <= fp {
+                break;
+            }
+            fp = next_fp as usize;
+        }
+        FRAME_COUNT.store(count, Ordering::Relaxed);
+    }
+
+    pub fn capture(timeout: Duration) -> Option<Capture> {
+        let main_thread = MAIN_THREAD.get()?;
+        CAPTURED.store(false, Ordering::Release);
+        if unsafe { libc::pthread_kill(main_thread.pthread, libc::SIGUSR2) } != 0 {
+            return None;
+        }
+
+        let start = Instant::now();
+        while !CAPTURED.load(Ordering::Acquire) {
+            if start.elapsed() > timeout {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let frame_count = FRAME_COUNT.load(Ordering::Relaxed);
+        if frame_count == 0 {
+            return None;
+        }
+        let mut backtrace = Vec::new();
+        for (ix, frame) in FRAMES[..frame_count].iter().enumerate() {
+            let ip = frame.load(Ordering::Relaxed) as *mut c_void;
+            let mut resolved = false;
+            backtrace::resolve(ip, |symbol| {
+                resolved = true;
+                if let Some(name) = symbol.name() {
+                    backtrace.push(format!("{:#}", name));
+                }
+            });
+            // A frame without a frame pointer makes the walk continue from whatever
+            // its caller kept in that register, which rarely points into code.
+            if !resolved && ix > 0 {
+                break;
+            }
+        }
+
+        let mut registers = [0; arch::REGISTER_COUNT];
+        for (register, slot) in registers.iter_mut().zip(&REGISTERS) {
+            *register = slot.load(Ordering::Relaxed);
+        }
+        let stack = STACK[..STACK_WORD_COUNT.load(Ordering::Relaxed)]
+            .iter()
+            .flat_map(|word| word.load(Ordering::Relaxed).to_le_bytes())
+            .collect();
+        Some(Capture {
+            backtrace,
+            thread: ThreadState {
+                thread_id: main_thread.thread_id,
+                registers,
+                stack_start: STACK_START.load(Ordering::Relaxed),
+                stack,
+            },
+        })
+    }
+
+    /// Returns the lowest and highest addresses of the current thread's stack.
+    #[cfg(target_os = "linux")]
+    unsafe fn stack_bounds() -> Option<(usize, usize)> {
+        let mut attr: libc::pthread_attr_t = mem::zeroed();
+        if libc::pthread_getattr_np(libc::pthread_self(), &mut attr) != 0 {
+            return None;
+        }
+        let mut low = std::ptr::null_mut();
+        let mut size = 0;
+        let result = libc::pthread_attr_getstack(&attr, &mut low, &mut size);
+        libc::pthread_attr_destroy(&mut attr);
+        (result == 0).then(|| (low as usize, low as usize + size))
+    }
+
+    /// Returns the lowest and highest addresses of the current thread's stack.
+    #[cfg(target_os = "macos")]
+    unsafe fn stack_bounds() -> Option<(usize, usize)> {
+        let high = libc::pthread_get_stackaddr_np(libc::pthread_self()) as usize;
+        let size = libc::pthread_get_stacksize_np(libc::pthread_self());
+        Some((high.checked_sub(size)?, high))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn thread_id() -> u32 {
+        unsafe { libc::syscall(libc::SYS_gettid) as u32 }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn thread_id() -> u32 {
+        let mut thread_id = 0;
+        unsafe { libc::pthread_threadid_np(libc::pthread_self(), &mut thread_id) };
+        thread_id as u32
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    unsafe fn read_registers(context: &libc::ucontext_t) -> Option<Registers> {
+        let register = |ix: libc::c_int| context.uc_mcontext.gregs[ix as usize] as u64;
+        Some([
+            register(libc::REG_RAX),
+            register(libc::REG_RCX),
+            register(libc::REG_RDX),
+            register(libc::REG_RBX),
+            register(libc::REG_RSP),
+            register(libc::REG_RBP),
+            register(libc::REG_RSI),
+            register(libc::REG_RDI),
+            register(libc::REG_R8),
+            register(libc::REG_R9),
+            register(libc::REG_R10),
+            register(libc::REG_R11),
+            register(libc::REG_R12),
+            register(libc::REG_R13),
+            register(libc::REG_R14),
+            register(libc::REG_R15),
+            register(libc::REG_RIP),
+            register(libc::REG_EFL),
+        ])
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    unsafe fn read_registers(context: &libc::ucontext_t) -> Option<Registers> {
+        let mcontext = &context.uc_mcontext;
+        let mut registers = [0; arch::REGISTER_COUNT];
+        registers[..31].copy_from_slice(&mcontext.regs);
+        registers[arch::SP] = mcontext.sp;
+        registers[arch::PC] = mcontext.pc;
+        registers[arch::CPSR] = mcontext.pstate;
+        Some(registers)
+    }
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    unsafe fn read_registers(context: &libc::ucontext_t) -> Option<Registers> {
+        let state = &(*context.uc_mcontext).__ss;
+        Some([
+            state.__rax,
+            state.__rcx,
+            state.__rdx,
+            state.__rbx,
+            state.__rsp,
+            state.__rbp,
+            state.__rsi,
+            state.__rdi,
+            state.__r8,
+            state.__r9,
+            state.__r10,
+            state.__r11,
+            state.__r12,
+            state.__r13,
+            state.__r14,
+            state.__r15,
+            state.__rip,
+            state.__rflags,
+        ])
+    }
+
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    unsafe fn read_registers(context: &libc::ucontext_t) -> Option<Registers> {
+        let state = &(*context.uc_mcontext).__ss;
+        let mut registers = [0; arch::REGISTER_COUNT];
+        registers[..29].copy_from_slice(&state.__x);
+        registers[arch::FP] = state.__fp;
+        registers[arch::FP + 1] = state.__lr;
+        registers[arch::SP] = state.__sp;
+        registers[arch::PC] = state.__pc;
+        registers[arch::CPSR] = state.__cpsr as u64;
+        Some(registers)
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    unsafe fn read_registers(_context: &libc::ucontext_t) -> Option<Registers> {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod main_thread {
+    use super::Capture;
+    use std::time::Duration;
+
+    pub fn register() {}
+
+    pub fn capture(_timeout: Duration) -> Option<Capture> {
+        None
+    }
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "macos")))]
+mod tests {
+    use super::*;
+    use crate::minidump::arch;
+
+    #[test]
+    fn test_capture_main_thread() {
+        // This thread stands in for the main thread, and is blocked while another
+        // thread captures it.
+        main_thread::register();
+        let capture = thread::spawn(|| main_thread::capture(Duration::from_secs(5)))
+            .join()
+            .unwrap()
+            .expect("the thread wasn't captured");
+
+        let pc = capture.thread.registers[arch::PC];
+        assert_ne!(pc, 0);
+        assert!(!capture.thread.stack.is_empty());
+        let sp = capture.thread.registers[arch::SP];
+        assert!(capture.thread.stack_start <= sp && sp < capture.thread.stack_start + 8);
+
+        // The thread was stopped in one of the loaded modules, which a minidump
+        // lists so that its frames can be symbolicated.
+        let modules = minidump::loaded_modules();
+        assert!(modules
+            .iter()
+            .any(|module| (module.base..module.base + module.size as u64).contains(&pc)));
+        assert!(!minidump::write(&capture.thread, &modules, 0).is_empty());
+    }
+}
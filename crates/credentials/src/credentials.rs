@@ -0,0 +1,230 @@
+mod credentials_modal;
+
+use anyhow::Result;
+use collections::HashSet;
+use fs::Fs;
+use gpui::{actions, AppContext, Global, PromptLevel, SharedString, Task};
+use std::{env, sync::Arc};
+use workspace::Workspace;
+
+pub use credentials_modal::CredentialsModal;
+
+actions!(credentials, [Manage]);
+
+/// The username that secrets are stored under in the keychain.
+const KEYCHAIN_USERNAME: &str = "Bearer";
+
+/// A secret, like an API key, that's stored in the OS keychain (Keychain on macOS,
+/// the Secret Service on Linux and the Credential Manager on Windows).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credential {
+    /// What the secret is for, as shown to the user.
+    pub label: SharedString,
+    /// The URL that the secret is stored under in the keychain.
+    pub url: SharedString,
+    /// An environment variable that, when set, is used instead of the keychain.
+    pub env_var: Option<&'static str>,
+}
+
+/// Where a credential's secret was found.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CredentialSource {
+    Environment,
+    Keychain,
+}
+
+impl Credential {
+    pub fn new(label: impl Into<SharedString>, url: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            url: url.into(),
+            env_var: None,
+        }
+    }
+
+    pub fn with_env_var(mut self, env_var: &'static str) -> Self {
+        self.env_var = Some(env_var);
+        self
+    }
+
+    /// Reads the secret, preferring the environment variable over the keychain.
+    pub fn read(&self, cx: &AppContext) -> Task<Result<Option<(String, CredentialSource)>>> {
+        if let Some(secret) = self
+            .env_var
+            .and_then(|env_var| env::var(env_var).ok())
+            .filter(|secret| !secret.is_empty())
+        {
+            return Task::ready(Ok(Some((secret, CredentialSource::Environment))));
+        }
+
+        let read_credentials = cx.read_credentials(&self.url);
+        cx.background_executor().spawn(async move {
+            let Some((_, secret)) = read_credentials.await? else {
+                return Ok(None);
+            };
+            Ok(Some((
+                String::from_utf8(secret)?,
+                CredentialSource::Keychain,
+            )))
+        })
+    }
+
+    /// Stores the secret in the keychain, replacing any secret already stored there.
+    pub fn write(&self, secret: &str, cx: &AppContext) -> Task<Result<()>> {
+        cx.write_credentials(&self.url, KEYCHAIN_USERNAME, secret.as_bytes())
+    }
+
+    /// Removes the secret from the keychain.
+    pub fn delete(&self, cx: &AppContext) -> Task<Result<()>> {
+        cx.delete_credentials(&self.url)
+    }
+}
+
+/// The OpenAI API key, stored in the keychain under the API's URL. It's shared by
+/// the assistant's completions and the semantic index's embeddings, so that a key
+/// stored for one of them is used by the other.
+pub fn open_ai_credential(api_url: &str) -> Credential {
+    Credential::new("OpenAI API Key", api_url.to_string()).with_env_var("OPENAI_API_KEY")
+}
+
+/// The credentials in use, which can be managed with the `credentials: manage` action.
+#[derive(Default)]
+struct CredentialRegistry {
+    credentials: Vec<Box<dyn Fn(&AppContext) -> Option<Credential>>>,
+    /// The settings that the user has already been asked to move into the keychain
+    /// during this session.
+    prompted_settings: HashSet<SharedString>,
+}
+
+impl Global for CredentialRegistry {}
+
+pub fn init(cx: &mut AppContext) {
+    cx.default_global::<CredentialRegistry>();
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Manage, cx| {
+            workspace.toggle_modal(cx, CredentialsModal::new);
+        });
+    })
+    .detach();
+}
+
+/// Registers a credential so that it can be managed by the user. The credential is
+/// computed whenever it's needed, as it can depend on settings like an API's URL,
+/// and is skipped when `None` is returned, e.g. because its feature is disabled.
+pub fn register(
+    cx: &mut AppContext,
+    credential: impl 'static + Fn(&AppContext) -> Option<Credential>,
+) {
+    cx.default_global::<CredentialRegistry>()
+        .credentials
+        .push(Box::new(credential));
+}
+
+/// Returns the credentials that are currently in use.
+pub fn registered(cx: &AppContext) -> Vec<Credential> {
+    let Some(registry) = cx.try_global::<CredentialRegistry>() else {
+        return Vec::new();
+    };
+    let mut credentials = Vec::<Credential>::new();
+    for credential in registry
+        .credentials
+        .iter()
+        .filter_map(|credential| credential(cx))
+    {
+        if !credentials
+            .iter()
+            .any(|existing| existing.url == credential.url)
+        {
+            credentials.push(credential);
+        }
+    }
+    credentials
+}
+
+/// Asks the user whether a secret that's written in plain text in their settings
+/// should be moved into the keychain. Once the secret has been stored,
+/// `remove_from_settings` is called to take it out of the settings file.
+///
+/// The user is only asked once per session for each setting.
+pub fn prompt_to_move_into_keychain(
+    credential: Credential,
+    setting: impl Into<SharedString>,
+    secret: String,
+    remove_from_settings: impl 'static + FnOnce(Arc<dyn Fs>, &mut AppContext),
+    cx: &mut AppContext,
+) {
+    let setting = setting.into();
+    if !cx
+        .default_global::<CredentialRegistry>()
+        .prompted_settings
+        .insert(setting.clone())
+    {
+        return;
+    }
+
+    cx.spawn(|mut cx| async move {
+        let workspace = cx.update(|cx| {
+            cx.active_window()
+                .and_then(|window| window.downcast::<Workspace>())
+                .or_else(|| {
+                    cx.windows()
+                        .into_iter()
+                        .find_map(|window| window.downcast::<Workspace>())
+                })
+        })?;
+        let Some(workspace) = workspace else {
+            // Ask again once there's a window to ask in.
+            return cx.update(|cx| {
+                cx.global_mut::<CredentialRegistry>()
+                    .prompted_settings
+                    .remove(&setting);
+            });
+        };
+
+        let (fs, answer) = workspace.update(&mut cx, |workspace, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let answer = cx.prompt(
+                PromptLevel::Info,
+                &format!("`{setting}` is stored in plain text in your settings"),
+                Some("Move it into the system keychain? It will be removed from your settings."),
+                &["Move to Keychain", "Not Now"],
+            );
+            (fs, answer)
+        })?;
+        if !matches!(answer.await, Ok(0)) {
+            return Ok(());
+        }
+        cx.update(|cx| credential.write(&secret, cx))?.await?;
+        cx.update(|cx| remove_from_settings(fs, cx))
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    fn test_registered_credentials(cx: &mut AppContext) {
+        assert_eq!(registered(cx), Vec::new());
+
+        register(cx, |_| {
+            Some(Credential::new("First", "https://first.example.com"))
+        });
+        register(cx, |_| None);
+        register(cx, |_| {
+            Some(Credential::new("Second", "https://second.example.com").with_env_var("SECOND"))
+        });
+        register(cx, |_| {
+            Some(Credential::new("First Again", "https://first.example.com"))
+        });
+
+        assert_eq!(
+            registered(cx),
+            vec![
+                Credential::new("First", "https://first.example.com"),
+                Credential::new("Second", "https://second.example.com").with_env_var("SECOND"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{registered, Credential, CredentialSource};
+use editor::Editor;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, View, ViewContext,
+};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::ModalView;
+
+/// Lists the credentials in use and where their secrets come from, letting the
+/// user store new secrets in the keychain or remove them from it.
+pub struct CredentialsModal {
+    entries: Vec<CredentialEntry>,
+    /// The index of the entry whose secret is being entered.
+    editing: Option<usize>,
+    secret_editor: View<Editor>,
+    focus_handle: FocusHandle,
+}
+
+struct CredentialEntry {
+    credential: Credential,
+    /// Where the secret was found, or `None` if it isn't set. Unknown until
+    /// the keychain has been read.
+    source: Option<Option<CredentialSource>>,
+}
+
+impl ModalView for CredentialsModal {}
+
+impl EventEmitter<DismissEvent> for CredentialsModal {}
+
+impl FocusableView for CredentialsModal {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl CredentialsModal {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        let entries = registered(cx)
+            .into_iter()
+            .map(|credential| CredentialEntry {
+                credential,
+                source: None,
+            })
+            .collect::<Vec<_>>();
+        for (ix, entry) in entries.iter().enumerate() {
+            Self::refresh_entry(ix, &entry.credential, cx);
+        }
+
+        Self {
+            entries,
+            editing: None,
+            secret_editor: cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Paste the secret and press enter", cx);
+                editor
+            }),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn refresh_entry(ix: usize, credential: &Credential, cx: &mut ViewContext<Self>) {
+        let read = credential.read(cx);
+        cx.spawn(|this, mut cx| async move {
+            let source = read.await.log_err().flatten().map(|(_, source)| source);
+            this.update(&mut cx, |this, cx| {
+                if let Some(entry) = this.entries.get_mut(ix) {
+                    entry.source = Some(source);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn edit_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.editing = Some(ix);
+        self.secret_editor
+            .update(cx, |editor, cx| editor.set_text("", cx));
+        cx.focus_view(&self.secret_editor);
+        cx.notify();
+    }
+
+    fn forget_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let credential = self.entries[ix].credential.clone();
+        let delete = credential.delete(cx);
+        cx.spawn(|this, mut cx| async move {
+            delete.await?;
+            this.update(&mut cx, |_, cx| Self::refresh_entry(ix, &credential, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.editing else {
+            return;
+        };
+        let secret = self.secret_editor.read(cx).text(cx);
+        if secret.is_empty() {
+            return;
+        }
+
+        let credential = self.entries[ix].credential.clone();
+        let write = credential.write(&secret, cx);
+        cx.spawn(|this, mut cx| async move {
+            write.await?;
+            this.update(&mut cx, |_, cx| Self::refresh_entry(ix, &credential, cx))
+        })
+        .detach_and_log_err(cx);
+
+        self.editing = None;
+        cx.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.editing.take().is_some() {
+            cx.focus(&self.focus_handle);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entry = &self.entries[ix];
+        let status = match entry.source {
+            None => "Checking…".to_string(),
+            Some(None) => "Not set".to_string(),
+            Some(Some(CredentialSource::Keychain)) => "Stored in the keychain".to_string(),
+            Some(Some(CredentialSource::Environment)) => {
+                format!("Set by ${}", entry.credential.env_var.unwrap_or_default())
+            }
+        };
+
+        v_flex()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        v_flex()
+                            .child(Label::new(entry.credential.label.clone()))
+                            .child(
+                                Label::new(status)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(
+                                entry.source == Some(Some(CredentialSource::Keychain)),
+                                |this| {
+                                    this.child(
+                                        Button::new(("forget-credential", ix), "Forget").on_click(
+                                            cx.listener(move |this, _, cx| {
+                                                this.forget_entry(ix, cx)
+                                            }),
+                                        ),
+                                    )
+                                },
+                            )
+                            .child(
+                                Button::new(("set-credential", ix), "Set…")
+                                    .style(ButtonStyle::Filled)
+                                    .on_click(
+                                        cx.listener(move |this, _, cx| this.edit_entry(ix, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+            .when(self.editing == Some(ix), |this| {
+                this.child(
+                    div()
+                        .px_1()
+                        .py_0p5()
+                        .rounded_md()
+                        .bg(cx.theme().colors().editor_background)
+                        .child(self.secret_editor.clone()),
+                )
+            })
+    }
+}
+
+impl Render for CredentialsModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("CredentialsModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .py_1()
+            .child(
+                div()
+                    .px_2()
+                    .pb_1()
+                    .child(Label::new("Credentials").size(LabelSize::Large)),
+            )
+            .when(self.entries.is_empty(), |this| {
+                this.child(
+                    div().px_2().py_1().child(
+                        Label::new("No credentials are in use.")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .children((0..self.entries.len()).map(|ix| self.render_entry(ix, cx)))
+    }
+}
@@ -0,0 +1,211 @@
+mod external_edit_buffer;
+mod server;
+
+use anyhow::Result;
+use credentials::Credential;
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+use gpui::{AppContext, Global, Task, VisualContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{update_settings_file, Settings, SettingsSources, SettingsStore};
+use std::sync::Arc;
+use util::ResultExt;
+use workspace::Workspace;
+
+pub use external_edit_buffer::ExternalEditBuffer;
+
+use server::EditRequest;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct EditServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+/// Settings for the local server that lets other tools, like browser extensions,
+/// open text in Zed and receive the edited text back when it's saved.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EditServerSettingsContent {
+    /// Whether to run the edit server.
+    ///
+    /// Default: false
+    enabled: Option<bool>,
+    /// The port on localhost that the edit server listens on.
+    ///
+    /// Default: 43790
+    port: Option<u16>,
+    /// The token that requests must send in their `Authorization: Bearer <token>` header.
+    /// Prefer storing the token in the keychain with `credentials: manage`, which is
+    /// where it's read from when this isn't set. The edit server doesn't start unless
+    /// a token is set in either place.
+    ///
+    /// Default: null
+    token: Option<String>,
+}
+
+impl Settings for EditServerSettings {
+    const KEY: Option<&'static str> = Some("edit_server");
+
+    type FileContent = EditServerSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// The token that requests to the edit server must send, stored in the keychain.
+fn edit_server_credential() -> Credential {
+    Credential::new("Edit Server Token", "zed://edit-server")
+}
+
+/// The running edit server, restarted whenever its settings change.
+struct EditServer {
+    settings: Option<EditServerSettings>,
+    _server: Option<Task<()>>,
+}
+
+impl Global for EditServer {}
+
+pub fn init(cx: &mut AppContext) {
+    EditServerSettings::register(cx);
+    credentials::register(cx, |cx| {
+        EditServerSettings::get_global(cx)
+            .enabled
+            .then(edit_server_credential)
+    });
+
+    let (request_tx, mut request_rx) = mpsc::unbounded::<EditRequest>();
+    cx.set_global(EditServer {
+        settings: None,
+        _server: None,
+    });
+    update_server(request_tx.clone(), cx);
+    cx.observe_global::<SettingsStore>(move |cx| update_server(request_tx.clone(), cx))
+        .detach();
+    cx.observe_new_views(|_: &mut Workspace, cx| prompt_to_move_token_into_keychain(cx))
+        .detach();
+
+    cx.spawn(|mut cx| async move {
+        while let Some(request) = request_rx.next().await {
+            cx.update(|cx| open_edit_request(request, cx)).log_err();
+        }
+    })
+    .detach();
+}
+
+fn update_server(request_tx: mpsc::UnboundedSender<EditRequest>, cx: &mut AppContext) {
+    let settings = EditServerSettings::get_global(cx).clone();
+    let settings = Some(settings).filter(|settings| settings.enabled);
+    if cx.global::<EditServer>().settings == settings {
+        return;
+    }
+
+    let server = settings.as_ref().map(|settings| {
+        let token = settings.token.clone().filter(|token| !token.is_empty());
+        let port = settings.port;
+        let executor = cx.background_executor().clone();
+        cx.spawn(|cx| async move {
+            let token = match token {
+                Some(token) => token,
+                None => {
+                    let read_token = cx.update(|cx| edit_server_credential().read(cx));
+                    let token = match read_token {
+                        Ok(read_token) => read_token.await.log_err().flatten(),
+                        Err(_) => return,
+                    };
+                    let Some((token, _)) = token else {
+                        log::error!(
+                            "not starting the edit server, as no token is set in edit_server.token or the keychain"
+                        );
+                        return;
+                    };
+                    token
+                }
+            };
+
+            executor
+                .spawn({
+                    let executor = executor.clone();
+                    async move {
+                        server::run(port, Arc::from(token), request_tx, executor)
+                            .await
+                            .log_err();
+                    }
+                })
+                .await;
+        })
+    });
+    cx.set_global(EditServer {
+        settings,
+        _server: server,
+    });
+    prompt_to_move_token_into_keychain(cx);
+}
+
+/// Offers to move a token that's written in the settings into the keychain.
+fn prompt_to_move_token_into_keychain(cx: &mut AppContext) {
+    let settings = EditServerSettings::get_global(cx);
+    let Some(token) = settings.token.clone().filter(|token| !token.is_empty()) else {
+        return;
+    };
+    if !settings.enabled {
+        return;
+    }
+
+    credentials::prompt_to_move_into_keychain(
+        edit_server_credential(),
+        "edit_server.token",
+        token,
+        |fs, cx| {
+            update_settings_file::<EditServerSettings>(fs, cx, |settings| settings.token = None);
+        },
+        cx,
+    );
+}
+
+/// Opens the text of an edit request in the active workspace.
+fn open_edit_request(request: EditRequest, cx: &mut AppContext) {
+    let workspace = cx
+        .active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .or_else(|| {
+            cx.windows()
+                .into_iter()
+                .find_map(|window| window.downcast::<Workspace>())
+        });
+    let Some(workspace) = workspace else {
+        request
+            .response
+            .send(Err("no Zed window is open".into()))
+            .ok();
+        return;
+    };
+
+    workspace
+        .update(cx, |workspace, cx| {
+            cx.activate_window();
+            let project = workspace.project().clone();
+            let buffer = ExternalEditBuffer::build_buffer(
+                request.text,
+                request.language.as_deref(),
+                &project,
+                cx,
+            );
+            let title = request.title.unwrap_or_else(|| "External Edit".into());
+            let item = cx.new_view(|cx| {
+                ExternalEditBuffer::new(title.into(), buffer, project, request.response, cx)
+            });
+            workspace.add_item_to_active_pane(Box::new(item), cx);
+        })
+        .log_err();
+}
+
+/// The result of an edit request: the edited text once it's saved, `None` if the
+/// buffer was closed without saving, or an error message.
+pub(crate) type EditResponse = Result<Option<String>, String>;
+
+pub(crate) type EditResponseSender = oneshot::Sender<EditResponse>;
@@ -0,0 +1,214 @@
+use crate::EditResponseSender;
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyElement, AnyView, AnyWindowHandle, AppContext, EventEmitter, FocusHandle, FocusableView,
+    Model, Pixels, Point, Render, SharedString, Subscription, Task, View, ViewContext,
+    VisualContext,
+};
+use language::Buffer;
+use project::Project;
+use std::{
+    any::{Any, TypeId},
+    path::PathBuf,
+};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+    ItemNavHistory,
+};
+
+/// An editor for text sent by an external tool through the edit server. Saving it
+/// sends the edited text back to the tool and closes it.
+pub struct ExternalEditBuffer {
+    title: SharedString,
+    buffer: Model<Buffer>,
+    editor: View<Editor>,
+    response: Option<EditResponseSender>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ExternalEditBuffer {
+    pub(crate) fn new(
+        title: SharedString,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        response: EditResponseSender,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let editor =
+            cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+        let _subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.on_release(Self::release),
+        ];
+        Self {
+            title,
+            buffer,
+            editor,
+            response: Some(response),
+            _subscriptions,
+        }
+    }
+
+    /// Creates a buffer holding the text to edit, highlighted as the language with the
+    /// given name or file extension.
+    pub(crate) fn build_buffer(
+        text: String,
+        language: Option<&str>,
+        project: &Model<Project>,
+        cx: &mut WindowContext,
+    ) -> Model<Buffer> {
+        let language_registry = project.read(cx).languages().clone();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_language_registry(language_registry.clone());
+            buffer
+        });
+
+        if let Some(language) = language {
+            let language = language_registry.language_for_name_or_extension(language);
+            let buffer = buffer.downgrade();
+            cx.spawn(|mut cx| async move {
+                let language = language.await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_language(Some(language), cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        buffer
+    }
+
+    /// Tells the external tool that the text was closed without being saved.
+    fn release(&mut self, _: AnyWindowHandle, _: &mut AppContext) {
+        if let Some(response) = self.response.take() {
+            response.send(Ok(None)).ok();
+        }
+    }
+}
+
+impl EventEmitter<EditorEvent> for ExternalEditBuffer {}
+
+impl FocusableView for ExternalEditBuffer {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for ExternalEditBuffer {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().size_full().child(self.editor.clone())
+    }
+}
+
+impl Item for ExternalEditBuffer {
+    type Event = EditorEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::ExternalLink))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(format!("{} (save to send it back)", self.title).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("external edit buffer")
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let text = self.buffer.read(cx).text();
+        if let Some(response) = self.response.take() {
+            response.send(Ok(Some(text))).ok();
+        }
+        self.buffer.update(cx, |buffer, cx| {
+            let version = buffer.version();
+            buffer.did_save(version, None, cx);
+        });
+        cx.emit(EditorEvent::Saved);
+        cx.emit(EditorEvent::Closed);
+        Task::ready(Ok(()))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        _abs_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.save(false, project, cx)
+    }
+
+    fn reload(&mut self, _: Model<Project>, _: &mut ViewContext<Self>) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| Item::deactivated(editor, cx))
+    }
+
+    fn set_nav_history(&mut self, history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| Item::set_nav_history(editor, history, cx))
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>> {
+        self.editor.read(cx).pixel_position_of_cursor(cx)
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{EditResponse, EditResponseSender};
+use anyhow::{anyhow, Context as _, Result};
+use futures::{
+    channel::{mpsc, oneshot},
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+};
+use gpui::BackgroundExecutor;
+use serde::{Deserialize, Serialize};
+use smol::{
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+};
+use std::{net::Ipv4Addr, sync::Arc};
+use util::ResultExt;
+
+const EDIT_PATH: &str = "/edit";
+const MAX_HEADER_LINE_LEN: usize = 8 * 1024;
+const MAX_HEADER_LINES: usize = 100;
+const MAX_BODY_LEN: usize = 64 * 1024 * 1024;
+
+/// A request to edit some text in Zed, sent by an external tool.
+pub(crate) struct EditRequest {
+    pub text: String,
+    /// The title of the tab to open the text in.
+    pub title: Option<String>,
+    /// The name or file extension of the text's language.
+    pub language: Option<String>,
+    pub response: EditResponseSender,
+}
+
+#[derive(Deserialize)]
+struct EditRequestBody {
+    text: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EditResponseBody {
+    /// The edited text, or `None` if the buffer was closed without saving.
+    text: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct RequestHead {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    content_length: usize,
+}
+
+/// Accepts connections on localhost until the returned future is dropped.
+///
+/// Every request must be a `POST /edit` carrying the configured token, with a JSON
+/// body like `{"text": "...", "title": "...", "language": "markdown"}`. The response
+/// is sent once the user saves or closes the opened buffer, as `{"text": "..."}` or
+/// `{"text": null}` respectively.
+///
+/// CORS preflight requests (`OPTIONS /edit`) are answered for any origin, so that
+/// scripts in web pages can send requests too. They still need the token.
+pub(crate) async fn run(
+    port: u16,
+    token: Arc<str>,
+    requests: mpsc::UnboundedSender<EditRequest>,
+    executor: BackgroundExecutor,
+) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .with_context(|| format!("binding the edit server to port {port}"))?;
+    log::info!("edit server listening on port {port}");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let token = token.clone();
+        let requests = requests.clone();
+        executor
+            .spawn(async move {
+                handle_connection(stream, &token, requests).await.log_err();
+            })
+            .detach();
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    token: &str,
+    requests: mpsc::UnboundedSender<EditRequest>,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream.clone());
+    let mut stream = stream;
+
+    let head = match read_request_head(&mut reader).await {
+        Ok(head) => head,
+        Err(error) => {
+            return write_error(&mut stream, "400 Bad Request", error.to_string()).await;
+        }
+    };
+    if head.path != EDIT_PATH {
+        return write_error(&mut stream, "404 Not Found", "not found".into()).await;
+    }
+    if head.method == "OPTIONS" {
+        return write_preflight_response(&mut stream).await;
+    }
+    if head.method != "POST" {
+        return write_error(&mut stream, "405 Method Not Allowed", "use POST".into()).await;
+    }
+    let authorized = head
+        .authorization
+        .as_deref()
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .map_or(false, |request_token| tokens_match(request_token, token));
+    if !authorized {
+        return write_error(&mut stream, "401 Unauthorized", "invalid token".into()).await;
+    }
+    if head.content_length > MAX_BODY_LEN {
+        return write_error(
+            &mut stream,
+            "413 Payload Too Large",
+            "text is too long".into(),
+        )
+        .await;
+    }
+
+    let mut body = vec![0; head.content_length];
+    reader.read_exact(&mut body).await?;
+    let body = match serde_json::from_slice::<EditRequestBody>(&body) {
+        Ok(body) => body,
+        Err(error) => {
+            return write_error(&mut stream, "400 Bad Request", error.to_string()).await;
+        }
+    };
+
+    let (response_tx, response_rx) = oneshot::channel::<EditResponse>();
+    requests
+        .unbounded_send(EditRequest {
+            text: body.text,
+            title: body.title,
+            language: body.language,
+            response: response_tx,
+        })
+        .map_err(|_| anyhow!("edit server was shut down"))?;
+
+    match response_rx.await {
+        Ok(Ok(text)) => {
+            let body = serde_json::to_string(&EditResponseBody { text })?;
+            write_response(&mut stream, "200 OK", &body).await
+        }
+        Ok(Err(error)) => write_error(&mut stream, "503 Service Unavailable", error).await,
+        // The buffer was dropped without being saved, e.g. because Zed quit.
+        Err(_) => {
+            let body = serde_json::to_string(&EditResponseBody { text: None })?;
+            write_response(&mut stream, "200 OK", &body).await
+        }
+    }
+}
+
+async fn read_request_head(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<RequestHead> {
+    let request_line = read_line(reader).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().context("missing request method")?.to_string();
+    let path = parts.next().context("missing request path")?.to_string();
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    for header_count in 0.. {
+        let line = read_line(reader).await?;
+        if line.is_empty() {
+            break;
+        }
+        if header_count == MAX_HEADER_LINES {
+            return Err(anyhow!("too many headers"));
+        }
+        let (name, value) = line
+            .split_once(':')
+            .with_context(|| format!("invalid header {line:?}"))?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().context("invalid content-length")?;
+        }
+    }
+
+    Ok(RequestHead {
+        method,
+        path,
+        authorization,
+        content_length,
+    })
+}
+
+async fn read_line(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<String> {
+    let mut line = String::new();
+    let mut limited = (&mut *reader).take(MAX_HEADER_LINE_LEN as u64);
+    limited.read_line(&mut line).await?;
+    if !line.ends_with('\n') {
+        return Err(anyhow!("unterminated or overlong request line"));
+    }
+    Ok(line.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+/// Compares the tokens in constant time, to not leak how much of a guess was correct.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+async fn write_error(
+    stream: &mut (impl AsyncWrite + Unpin),
+    status: &str,
+    error: String,
+) -> Result<()> {
+    let body = serde_json::to_string(&ErrorBody { error })?;
+    write_response(stream, status, &body).await
+}
+
+async fn write_response(
+    stream: &mut (impl AsyncWrite + Unpin),
+    status: &str,
+    body: &str,
+) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Allows requests with the headers of an edit request from any origin.
+async fn write_preflight_response(stream: &mut (impl AsyncWrite + Unpin)) -> Result<()> {
+    let response = "HTTP/1.1 204 No Content\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\nAccess-Control-Max-Age: 86400\r\nConnection: close\r\n\r\n";
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::Cursor;
+
+    #[test]
+    fn test_read_request_head() {
+        let mut request = Cursor::new(
+            "POST /edit HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer secret\r\nContent-Length: 11\r\n\r\n{\"text\":\"\"}",
+        );
+        let head = smol::block_on(read_request_head(&mut request)).unwrap();
+        assert_eq!(
+            head,
+            RequestHead {
+                method: "POST".into(),
+                path: "/edit".into(),
+                authorization: Some("Bearer secret".into()),
+                content_length: 11,
+            }
+        );
+
+        let mut request = Cursor::new("POST /edit HTTP/1.1\r\nContent-Length: twelve\r\n\r\n");
+        assert!(smol::block_on(read_request_head(&mut request)).is_err());
+
+        let mut request = Cursor::new("POST /edit HTTP/1.1\r\nHost: localhost");
+        assert!(smol::block_on(read_request_head(&mut request)).is_err());
+
+        let headers = "X-Header: value\r\n".repeat(MAX_HEADER_LINES);
+        let mut request = Cursor::new(format!("POST /edit HTTP/1.1\r\n{headers}\r\n"));
+        assert!(smol::block_on(read_request_head(&mut request)).is_ok());
+        let mut request = Cursor::new(format!(
+            "POST /edit HTTP/1.1\r\n{headers}X-Header: value\r\n\r\n"
+        ));
+        assert!(smol::block_on(read_request_head(&mut request)).is_err());
+    }
+
+    #[test]
+    fn test_preflight_request() {
+        smol::block_on(async {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let mut client = TcpStream::connect(listener.local_addr().unwrap())
+                .await
+                .unwrap();
+            let (server, _) = listener.accept().await.unwrap();
+            let (requests, _) = mpsc::unbounded();
+
+            client
+                .write_all(
+                    b"OPTIONS /edit HTTP/1.1\r\nOrigin: https://example.com\r\nAccess-Control-Request-Method: POST\r\n\r\n",
+                )
+                .await
+                .unwrap();
+            handle_connection(server, "secret", requests).await.unwrap();
+
+            let mut response = String::new();
+            client.read_to_string(&mut response).await.unwrap();
+            assert!(
+                response.starts_with("HTTP/1.1 204 No Content\r\n"),
+                "{response}"
+            );
+            assert!(
+                response.contains("Access-Control-Allow-Origin: *\r\n"),
+                "{response}"
+            );
+            assert!(
+                response.contains("Access-Control-Allow-Headers: Authorization, Content-Type\r\n"),
+                "{response}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret", "secret2"));
+        assert!(!tokens_match("", "secret"));
+    }
+}
@@ -100,6 +100,55 @@ pub struct ExpandExcerpts {
     pub(super) lines: u32,
 }
 
+/// Surrounds the selections with a pair, like `(` or `<div>`, which is asked for
+/// when it isn't given.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct SurroundSelections {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Replaces the pair around the selections, or only the pair named by `from`, with
+/// the pair named by `to`, which is asked for when it isn't given.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ChangeSurroundingPair {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+/// Removes the pair around the selections, or only the pair named by `text`.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct DeleteSurroundingPair {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Adds `count`, or 1, to the number or date under each cursor, or switches the
+/// word under it to the next of its `toggle_words`.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct Increment {
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+/// Subtracts `count`, or 1, from the number or date under each cursor, or switches
+/// the word under it to the previous of its `toggle_words`.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct Decrement {
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+/// Aligns the selected lines on `delimiter`, which is a regex when written between
+/// slashes, like `/=>?/`. Prompts for it when it's not given.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct AlignSelections {
+    #[serde(default)]
+    pub delimiter: Option<String>,
+}
+
 impl_actions!(
     editor,
     [
@@ -120,6 +169,12 @@ impl_actions!(
         MoveDownByLines,
         SelectUpByLines,
         SelectDownByLines,
+        SurroundSelections,
+        ChangeSurroundingPair,
+        DeleteSurroundingPair,
+        Increment,
+        Decrement,
+        AlignSelections,
     ]
 );
 
@@ -128,6 +183,7 @@ gpui::actions!(
     [
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
+        AddMissingImports,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
@@ -140,6 +196,7 @@ gpui::actions!(
         ConvertToKebabCase,
         ConvertToLowerCamelCase,
         ConvertToLowerCase,
+        ConvertToScreamingSnakeCase,
         ConvertToSnakeCase,
         ConvertToTitleCase,
         ConvertToUpperCamelCase,
@@ -213,11 +270,14 @@ gpui::actions!(
         Outdent,
         PageDown,
         PageUp,
+        PeekDefinition,
+        PeekReferences,
         Paste,
         PreviousInlineCompletion,
         Redo,
         RedoSelection,
         Rename,
+        ReselectPrevious,
         RestartLanguageServer,
         RevealInFinder,
         ReverseLines,
@@ -242,12 +302,15 @@ gpui::actions!(
         SelectToPreviousWordStart,
         SelectToStartOfParagraph,
         SelectUp,
+        ShowCharacterFont,
         ShowCharacterPalette,
         ShowCompletions,
         ShowInlineCompletion,
+        ShrinkSelectionToExpansionStart,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SortLinesNumerically,
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
@@ -0,0 +1,361 @@
+use crate::{actions::AlignSelections, Anchor, Editor, EditorEvent};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, View,
+    ViewContext, WeakView,
+};
+use language::{Point, TransactionId};
+use multi_buffer::ToPoint;
+use regex::Regex;
+use std::ops::Range;
+use ui::prelude::*;
+use workspace::{DismissDecision, ModalView};
+
+/// What the lines are aligned on: either some text, or a regex when it's written
+/// between slashes, like `/=>?/`.
+enum Delimiter {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Delimiter {
+    fn parse(text: &str) -> Option<Self> {
+        if text.is_empty() {
+            return None;
+        }
+        match text
+            .strip_prefix('/')
+            .and_then(|text| text.strip_suffix('/'))
+        {
+            Some(pattern) if !pattern.is_empty() => Regex::new(pattern).ok().map(Self::Regex),
+            _ => Some(Self::Text(text.to_string())),
+        }
+    }
+
+    fn ranges_in(&self, line: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Text(text) => line
+                .match_indices(text.as_str())
+                .map(|(start, text)| start..start + text.len())
+                .collect(),
+            Self::Regex(regex) => regex
+                .find_iter(line)
+                .filter(|mat| !mat.is_empty())
+                .map(|mat| mat.range())
+                .collect(),
+        }
+    }
+}
+
+/// Aligns every occurrence of the delimiter in the lines, so that the first ones
+/// line up, then the second ones, and so on. The whitespace around the delimiters
+/// is normalized to a single space, or to none before a delimiter that none of
+/// the lines had whitespace before, like the `:` in `key: value`. Returns the new
+/// text of the lines that changed.
+fn align_lines(lines: &[String], delimiter: &Delimiter) -> Vec<Option<String>> {
+    // Split each line into the text between its delimiters, and the delimiters.
+    let lines = lines
+        .iter()
+        .map(|line| {
+            let mut cells = Vec::new();
+            let mut delimiters = Vec::new();
+            let mut cell_start = 0;
+            for range in delimiter.ranges_in(line) {
+                cells.push(&line[cell_start..range.start]);
+                delimiters.push(&line[range.clone()]);
+                cell_start = range.end;
+            }
+            cells.push(&line[cell_start..]);
+            (cells, delimiters)
+        })
+        .collect::<Vec<_>>();
+    let stop_count = lines
+        .iter()
+        .map(|(_, delimiters)| delimiters.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut aligned_lines = lines
+        .iter()
+        .map(|(cells, _)| cells[0].trim_end().to_string())
+        .collect::<Vec<_>>();
+    for stop in 0..stop_count {
+        let lines_with_stop = || (0..lines.len()).filter(|ix| lines[*ix].1.len() > stop);
+        let space_before = lines_with_stop().any(|ix| {
+            lines[ix].0[stop].ends_with(char::is_whitespace) || lines[ix].0[stop].trim().is_empty()
+        });
+
+        if space_before {
+            let width = lines_with_stop()
+                .map(|ix| aligned_lines[ix].chars().count())
+                .max()
+                .unwrap_or(0);
+            for ix in lines_with_stop() {
+                let line = &mut aligned_lines[ix];
+                let padding = width - line.chars().count();
+                line.extend(std::iter::repeat(' ').take(padding));
+                if !line.trim().is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(lines[ix].1[stop]);
+            }
+        } else {
+            for ix in lines_with_stop() {
+                aligned_lines[ix].push_str(lines[ix].1[stop]);
+            }
+        }
+
+        let width = lines_with_stop()
+            .map(|ix| aligned_lines[ix].chars().count())
+            .max()
+            .unwrap_or(0);
+        for ix in lines_with_stop() {
+            let cell = lines[ix].0[stop + 1].trim();
+            if cell.is_empty() {
+                continue;
+            }
+            let line = &mut aligned_lines[ix];
+            let padding = width - line.chars().count();
+            line.extend(std::iter::repeat(' ').take(padding + 1));
+            line.push_str(cell);
+        }
+    }
+
+    lines
+        .iter()
+        .zip(aligned_lines)
+        .map(|((cells, delimiters), aligned_line)| {
+            let line = cells.iter().zip(delimiters.iter().chain([&""])).fold(
+                String::new(),
+                |mut line, (cell, delimiter)| {
+                    line.push_str(cell);
+                    line.push_str(delimiter);
+                    line
+                },
+            );
+            (!delimiters.is_empty() && aligned_line != line).then_some(aligned_line)
+        })
+        .collect()
+}
+
+impl Editor {
+    pub fn align_selections(&mut self, action: &AlignSelections, cx: &mut ViewContext<Self>) {
+        let ranges = self.selected_line_ranges(cx);
+        match &action.delimiter {
+            Some(delimiter) => {
+                self.align_lines_in_ranges(&ranges, delimiter, cx);
+            }
+            None => self.prompt_for_delimiter(ranges, cx),
+        }
+    }
+
+    /// Returns the ranges of the lines that the selections are on, joining those of
+    /// selections on adjacent lines, like the cursors of a column selection.
+    fn selected_line_ranges(&self, cx: &mut ViewContext<Self>) -> Vec<Range<Anchor>> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut row_ranges = Vec::<Range<u32>>::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let mut rows = selection.start.row..selection.end.row + 1;
+            if selection.end.column == 0 && selection.end.row > selection.start.row {
+                rows.end -= 1;
+            }
+            match row_ranges.last_mut() {
+                Some(last_rows) if rows.start <= last_rows.end => {
+                    last_rows.end = last_rows.end.max(rows.end)
+                }
+                _ => row_ranges.push(rows),
+            }
+        }
+
+        row_ranges
+            .into_iter()
+            .map(|rows| {
+                let end_row = rows.end - 1;
+                snapshot.anchor_before(Point::new(rows.start, 0))
+                    ..snapshot.anchor_after(Point::new(end_row, snapshot.line_len(end_row)))
+            })
+            .collect()
+    }
+
+    /// Aligns the lines in each of the ranges on the delimiter, returning the
+    /// transaction that did so.
+    pub fn align_lines_in_ranges(
+        &mut self,
+        ranges: &[Range<Anchor>],
+        delimiter: &str,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<TransactionId> {
+        if self.read_only(cx) {
+            return None;
+        }
+        let delimiter = Delimiter::parse(delimiter)?;
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        for range in ranges {
+            let rows = range.start.to_point(&snapshot).row..range.end.to_point(&snapshot).row + 1;
+            let lines = rows
+                .clone()
+                .map(|row| {
+                    snapshot
+                        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            for ((row, line), aligned_line) in rows.zip(&lines).zip(align_lines(&lines, &delimiter))
+            {
+                if let Some(aligned_line) = aligned_line {
+                    // Only edit the text that changed, so the cursors and selections
+                    // around it stay put.
+                    let mut prefix_len = line
+                        .bytes()
+                        .zip(aligned_line.bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    while !line.is_char_boundary(prefix_len) {
+                        prefix_len -= 1;
+                    }
+                    let mut suffix_len = line[prefix_len..]
+                        .bytes()
+                        .rev()
+                        .zip(aligned_line[prefix_len..].bytes().rev())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    while !line.is_char_boundary(line.len() - suffix_len) {
+                        suffix_len -= 1;
+                    }
+                    edits.push((
+                        Point::new(row, prefix_len as u32)
+                            ..Point::new(row, (line.len() - suffix_len) as u32),
+                        aligned_line[prefix_len..aligned_line.len() - suffix_len].to_string(),
+                    ));
+                }
+            }
+        }
+        if edits.is_empty() {
+            return None;
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        })
+    }
+
+    fn prompt_for_delimiter(&mut self, ranges: Vec<Range<Anchor>>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let editor = cx.view().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| AlignPrompt::new(editor, ranges, cx));
+        });
+    }
+}
+
+/// Asks for the delimiter to align the selected lines on, aligning them as it's
+/// typed. The lines are restored when the prompt is dismissed without confirming.
+struct AlignPrompt {
+    editor: WeakView<Editor>,
+    ranges: Vec<Range<Anchor>>,
+    prompt_editor: View<Editor>,
+    preview: Option<TransactionId>,
+    confirmed: bool,
+    _subscription: Subscription,
+}
+
+impl ModalView for AlignPrompt {
+    fn on_before_dismiss(&mut self, cx: &mut ViewContext<Self>) -> DismissDecision {
+        if !self.confirmed {
+            self.revert_preview(cx);
+        }
+        DismissDecision::Dismiss(true)
+    }
+}
+
+impl EventEmitter<DismissEvent> for AlignPrompt {}
+
+impl FocusableView for AlignPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.prompt_editor.focus_handle(cx)
+    }
+}
+
+impl AlignPrompt {
+    fn new(
+        editor: WeakView<Editor>,
+        ranges: Vec<Range<Anchor>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let prompt_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("A delimiter like = or :, or a regex like /=>?/", cx);
+            editor
+        });
+        let subscription = cx.subscribe(&prompt_editor, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.update_preview(cx);
+            }
+        });
+        Self {
+            editor,
+            ranges,
+            prompt_editor,
+            preview: None,
+            confirmed: false,
+            _subscription: subscription,
+        }
+    }
+
+    fn update_preview(&mut self, cx: &mut ViewContext<Self>) {
+        self.revert_preview(cx);
+        let delimiter = self.prompt_editor.read(cx).text(cx);
+        let delimiter = delimiter.trim();
+        self.preview = self
+            .editor
+            .update(cx, |editor, cx| {
+                editor.align_lines_in_ranges(&self.ranges, delimiter, cx)
+            })
+            .ok()
+            .flatten();
+    }
+
+    fn revert_preview(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(transaction_id) = self.preview.take() {
+            self.editor
+                .update(cx, |editor, cx| {
+                    editor
+                        .buffer
+                        .update(cx, |buffer, cx| buffer.undo_transaction(transaction_id, cx));
+                })
+                .ok();
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        self.confirmed = true;
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for AlignPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("AlignPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(24.))
+            .p_2()
+            .gap_1()
+            .child(
+                Label::new("Align On")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.prompt_editor.clone())
+    }
+}
@@ -0,0 +1,39 @@
+use ui::prelude::*;
+
+/// The maximum number of lines of the dragged text shown in its preview.
+const MAX_PREVIEW_LINES: usize = 4;
+
+/// Text dragged from somewhere in the UI, such as a message in the assistant panel,
+/// which is inserted at the drop position when dropped onto an editor.
+#[derive(Clone)]
+pub struct DraggedText {
+    pub text: SharedString,
+}
+
+impl DraggedText {
+    pub fn new(text: impl Into<SharedString>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+impl Render for DraggedText {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut lines = self.text.lines();
+        let mut preview = lines
+            .by_ref()
+            .take(MAX_PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if lines.next().is_some() {
+            preview.push_str("\n…");
+        }
+
+        div()
+            .max_w(px(320.))
+            .px_2()
+            .py_1()
+            .overflow_hidden()
+            .elevation_2(cx)
+            .child(Label::new(preview).size(LabelSize::Small))
+    }
+}
@@ -13,26 +13,35 @@
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behaviour.
 pub mod actions;
+mod align;
 mod blame_entry_tooltip;
 mod blink_manager;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod formatter_line_length;
 mod inlay_hint_cache;
 
 mod debounced_delay;
+mod dragged_text;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
+mod increment;
 mod inline_completion_provider;
 pub mod items;
 mod mouse_context_menu;
 pub mod movement;
+mod peek_view;
 mod persistence;
+mod persistent_undo;
+mod rainbow_brackets;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod surround;
+mod tag_editing;
 
 #[cfg(test)]
 mod editor_tests;
@@ -44,14 +53,16 @@ pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
-use client::{Collaborator, ParticipantIndex};
+use client::{Collaborator, ParticipantIndex, ParticipantPresence};
 use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
 pub use display_map::DisplayPoint;
 use display_map::*;
+pub use dragged_text::DraggedText;
 pub use editor_settings::EditorSettings;
+use editor_settings::ShowCursorNames;
 use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
@@ -61,11 +72,12 @@ use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
 use git::diff_hunk_to_display;
 use gpui::{
-    div, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action, AnyElement,
-    AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardItem,
-    Context, DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
-    FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton, PaintQuad,
-    ParentElement, Pixels, Render, SharedString, Size, StrikethroughStyle, Styled, StyledText,
+    div, img, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action, Animation,
+    AnimationHandle, AnyElement, AppContext, AsyncWindowContext, AvailableSpace,
+    BackgroundExecutor, Bounds, ClipboardItem, Context, DispatchPhase, Div, ElementId,
+    EventEmitter, FocusHandle, FocusableView, FontId, FontStyle, FontWeight, HighlightStyle, Hsla,
+    ImageSource, Img, InteractiveText, KeyContext, Model, MouseButton, PaintQuad, ParentElement,
+    Pixels, Render, SharedString, Size, Stateful, StrikethroughStyle, Styled, StyledText,
     Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View, ViewContext,
     ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
@@ -98,6 +110,7 @@ use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
     CodeAction, Completion, FormatTrigger, Item, Location, Project, ProjectPath, ProjectTransaction,
 };
+use rainbow_brackets::refresh_rainbow_bracket_highlights;
 use rand::prelude::*;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
@@ -120,6 +133,7 @@ use std::{
 };
 pub use sum_tree::Bias;
 use sum_tree::TreeMap;
+use tag_editing::linked_tag_edits;
 use text::{BufferId, OffsetUtf16, Rope};
 use theme::{
     observe_buffer_font_size_adjustment, ActiveTheme, PlayerColor, StatusColors, SyntaxTheme,
@@ -134,6 +148,7 @@ use workspace::item::ItemHandle;
 use workspace::notifications::NotificationId;
 use workspace::{
     searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
+    ZenModeSettings,
 };
 use workspace::{OpenInTerminal, OpenTerminal, Toast};
 
@@ -144,7 +159,10 @@ const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
 const MIN_NAVIGATION_HISTORY_ROW_DELTA: i64 = 10;
 const MAX_SELECTION_HISTORY_LEN: usize = 1024;
+/// The largest width and height that images in documentation are displayed at.
+const MAX_MARKDOWN_IMAGE_SIZE: Pixels = px(320.);
 pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
+const CURSOR_NAMES_FADE_OUT: Duration = Duration::from_millis(400);
 #[doc(hidden)]
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 #[doc(hidden)]
@@ -158,7 +176,55 @@ pub fn render_parsed_markdown(
     editor_style: &EditorStyle,
     workspace: Option<WeakView<Workspace>>,
     cx: &mut WindowContext,
-) -> InteractiveText {
+) -> Stateful<Div> {
+    let mut container = div().id(element_id).flex().flex_col();
+    let mut text_start = 0;
+    for (ix, image) in parsed.images.iter().enumerate() {
+        container = container
+            .children(render_markdown_text(
+                ix,
+                parsed,
+                text_start..image.offset,
+                editor_style,
+                workspace.clone(),
+                cx,
+            ))
+            .child(render_markdown_image(image));
+        text_start = image.offset;
+    }
+    container.children(render_markdown_text(
+        parsed.images.len(),
+        parsed,
+        text_start..parsed.text.len(),
+        editor_style,
+        workspace,
+        cx,
+    ))
+}
+
+/// Renders the text of a parsed Markdown document in the given range, which is
+/// between two of its images.
+fn render_markdown_text(
+    element_id: impl Into<ElementId>,
+    parsed: &language::ParsedMarkdown,
+    range: Range<usize>,
+    editor_style: &EditorStyle,
+    workspace: Option<WeakView<Workspace>>,
+    cx: &mut WindowContext,
+) -> Option<InteractiveText> {
+    // Images are displayed on their own lines, so the newlines around them are
+    // dropped.
+    let text = &parsed.text[range.clone()];
+    let start = range.start + (text.len() - text.trim_start_matches('\n').len());
+    let end = (range.end - (text.len() - text.trim_end_matches('\n').len())).max(start);
+    if start == end && !parsed.images.is_empty() {
+        return None;
+    }
+    let to_local = |range: &Range<usize>| {
+        let range = range.start.max(start)..range.end.min(end);
+        (range.start < range.end).then(|| range.start - start..range.end - start)
+    };
+
     let code_span_background_color = cx
         .theme()
         .colors()
@@ -166,8 +232,9 @@ pub fn render_parsed_markdown(
 
     let highlights = gpui::combine_highlights(
         parsed.highlights.iter().filter_map(|(range, highlight)| {
+            let range = to_local(range)?;
             let highlight = highlight.to_highlight_style(&editor_style.syntax)?;
-            Some((range.clone(), highlight))
+            Some((range, highlight))
         }),
         parsed
             .regions
@@ -176,7 +243,7 @@ pub fn render_parsed_markdown(
             .filter_map(|(region, range)| {
                 if region.code {
                     Some((
-                        range.clone(),
+                        to_local(range)?,
                         HighlightStyle {
                             background_color: Some(code_span_background_color),
                             ..Default::default()
@@ -191,15 +258,16 @@ pub fn render_parsed_markdown(
     let mut links = Vec::new();
     let mut link_ranges = Vec::new();
     for (range, region) in parsed.region_ranges.iter().zip(&parsed.regions) {
-        if let Some(link) = region.link.clone() {
+        if let Some((link, range)) = region.link.clone().zip(to_local(range)) {
             links.push(link);
-            link_ranges.push(range.clone());
+            link_ranges.push(range);
         }
     }
 
-    InteractiveText::new(
+    let text = InteractiveText::new(
         element_id,
-        StyledText::new(parsed.text.clone()).with_highlights(&editor_style.text, highlights),
+        StyledText::new(parsed.text[start..end].to_string())
+            .with_highlights(&editor_style.text, highlights),
     )
     .on_click(link_ranges, move |clicked_range_ix, cx| {
         match &links[clicked_range_ix] {
@@ -212,7 +280,19 @@ pub fn render_parsed_markdown(
                 }
             }
         }
-    })
+    });
+    Some(text)
+}
+
+fn render_markdown_image(image: &markdown::ParsedImage) -> Img {
+    let source: ImageSource = match &image.source {
+        markdown::Link::Web { url } => url.clone().into(),
+        markdown::Link::Path { path } => path.clone().into(),
+    };
+    img(source)
+        .max_w(MAX_MARKDOWN_IMAGE_SIZE)
+        .max_h(MAX_MARKDOWN_IMAGE_SIZE)
+        .my_1()
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -367,6 +447,9 @@ type BackgroundHighlight = (fn(&ThemeColors) -> Hsla, Arc<[Range<Anchor>]>);
 struct ScrollbarMarkerState {
     scrollbar_size: Size<Pixels>,
     dirty: bool,
+    /// The selected text whose matches are marked, so that the markers are only
+    /// refreshed when a new selection has different text.
+    selected_text: Option<String>,
     markers: Arc<[PaintQuad]>,
     pending_refresh: Option<Task<Result<()>>>,
 }
@@ -382,6 +465,7 @@ impl Default for ScrollbarMarkerState {
         Self {
             scrollbar_size: Size::default(),
             dirty: false,
+            selected_text: None,
             markers: Arc::from([]),
             pending_refresh: None,
         }
@@ -417,11 +501,15 @@ pub struct Editor {
     blink_manager: Model<BlinkManager>,
     show_cursor_names: bool,
     hovered_cursors: HashMap<HoveredCursor, Task<()>>,
+    active_remote_cursors: HashMap<ReplicaId, RemoteCursorName>,
     pub show_local_selections: bool,
     mode: EditorMode,
     show_breadcrumbs: bool,
     show_gutter: bool,
+    show_line_numbers: Option<bool>,
     show_wrap_guides: Option<bool>,
+    formatter_line_length: Option<usize>,
+    formatter_line_length_task: Option<Task<()>>,
     placeholder_text: Option<Arc<str>>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<(usize, Range<Anchor>, Hsla)>>,
@@ -432,6 +520,7 @@ pub struct Editor {
     mouse_context_menu: Option<MouseContextMenu>,
     completion_tasks: Vec<(CompletionId, Task<Option<()>>)>,
     find_all_references_task_sources: Vec<Anchor>,
+    peek: Option<peek_view::PeekState>,
     next_completion_id: CompletionId,
     completion_documentation_pre_resolve_debounce: DebouncedDelay,
     available_code_actions: Option<(Model<Buffer>, Arc<[CodeAction]>)>,
@@ -443,6 +532,9 @@ pub struct Editor {
     collapse_matches: bool,
     autoindent_mode: Option<AutoindentMode>,
     workspace: Option<(WeakView<Workspace>, WorkspaceId)>,
+    /// Applies the workspace's zen mode settings when they change, replaced each
+    /// time the editor is added to a workspace.
+    zen_mode_subscription: Option<Subscription>,
     keymap_context_layers: BTreeMap<TypeId, KeyContext>,
     input_enabled: bool,
     use_modal_editing: bool,
@@ -485,6 +577,7 @@ pub struct Editor {
 pub struct EditorSnapshot {
     pub mode: EditorMode,
     show_gutter: bool,
+    show_line_numbers: Option<bool>,
     render_git_blame_gutter: bool,
     pub display_snapshot: DisplaySnapshot,
     pub placeholder_text: Option<Arc<str>>,
@@ -524,6 +617,7 @@ pub struct RemoteSelection {
     pub line_mode: bool,
     pub participant_index: Option<ParticipantIndex>,
     pub user_name: Option<SharedString>,
+    pub presence: ParticipantPresence,
 }
 
 #[derive(Clone, Debug)]
@@ -546,6 +640,14 @@ struct HoveredCursor {
     selection_id: usize,
 }
 
+/// The name label of a collaborator's cursor that recently moved, which stays
+/// visible for a while and then fades out.
+struct RemoteCursorName {
+    opacity: f32,
+    _show: Task<()>,
+    _fade_out: Option<AnimationHandle>,
+}
+
 impl Default for SelectionHistoryMode {
     fn default() -> Self {
         Self::Normal
@@ -791,6 +893,7 @@ impl CompletionsMenu {
         self.selected_item = 0;
         self.scroll_handle.scroll_to_item(self.selected_item);
         self.attempt_resolve_selected_completion_documentation(project, cx);
+        self.announce_selected_completion(cx);
         cx.notify();
     }
 
@@ -802,6 +905,7 @@ impl CompletionsMenu {
         }
         self.scroll_handle.scroll_to_item(self.selected_item);
         self.attempt_resolve_selected_completion_documentation(project, cx);
+        self.announce_selected_completion(cx);
         cx.notify();
     }
 
@@ -813,6 +917,7 @@ impl CompletionsMenu {
         }
         self.scroll_handle.scroll_to_item(self.selected_item);
         self.attempt_resolve_selected_completion_documentation(project, cx);
+        self.announce_selected_completion(cx);
         cx.notify();
     }
 
@@ -820,9 +925,23 @@ impl CompletionsMenu {
         self.selected_item = self.matches.len() - 1;
         self.scroll_handle.scroll_to_item(self.selected_item);
         self.attempt_resolve_selected_completion_documentation(project, cx);
+        self.announce_selected_completion(cx);
         cx.notify();
     }
 
+    fn announce_selected_completion(&self, cx: &mut ViewContext<Editor>) {
+        let Some(mat) = self.matches.get(self.selected_item) else {
+            return;
+        };
+        let completions = self.completions.read();
+        cx.announce(&format!(
+            "{}, {} of {}",
+            completions[mat.candidate_id].label.text,
+            self.selected_item + 1,
+            self.matches.len()
+        ));
+    }
+
     fn pre_resolve_completion_documentation(
         completions: Arc<RwLock<Box<[Completion]>>>,
         matches: Arc<[StringMatch]>,
@@ -1417,6 +1536,12 @@ impl Editor {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                     };
                 }));
+                let user_store = project.read(cx).user_store();
+                project_subscriptions.push(cx.subscribe(&user_store, |_, _, event, cx| {
+                    if let client::user::Event::ParticipantPresencesChanged = event {
+                        cx.notify();
+                    }
+                }));
             }
         }
 
@@ -1455,7 +1580,10 @@ impl Editor {
             mode,
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
+            show_line_numbers: None,
             show_wrap_guides: None,
+            formatter_line_length: None,
+            formatter_line_length_task: None,
             placeholder_text: None,
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
@@ -1466,6 +1594,7 @@ impl Editor {
             mouse_context_menu: None,
             completion_tasks: Default::default(),
             find_all_references_task_sources: Vec::new(),
+            peek: None,
             next_completion_id: 0,
             completion_documentation_pre_resolve_debounce: DebouncedDelay::new(),
             next_inlay_id: 0,
@@ -1478,6 +1607,7 @@ impl Editor {
             autoindent_mode: Some(AutoindentMode::EachLine),
             collapse_matches: false,
             workspace: None,
+            zen_mode_subscription: None,
             keymap_context_layers: Default::default(),
             input_enabled: true,
             use_modal_editing: mode == EditorMode::Full,
@@ -1499,6 +1629,7 @@ impl Editor {
             style: None,
             show_cursor_names: false,
             hovered_cursors: Default::default(),
+            active_remote_cursors: Default::default(),
             editor_actions: Default::default(),
             vim_replace_map: Default::default(),
             show_inline_completions: mode == EditorMode::Full,
@@ -1534,6 +1665,7 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        this.refresh_formatter_line_length(cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1659,6 +1791,7 @@ impl Editor {
         EditorSnapshot {
             mode: self.mode,
             show_gutter: self.show_gutter,
+            show_line_numbers: self.show_line_numbers,
             render_git_blame_gutter: self.render_git_blame_gutter(cx),
             display_snapshot: self.display_map.update(cx, |map, cx| map.snapshot(cx)),
             scroll_anchor: self.scroll_manager.anchor(),
@@ -1875,6 +2008,13 @@ impl Editor {
         self.snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
         self.take_rename(false, cx);
+        if EditorSettings::get_global(cx).scrollbar.selection_matches {
+            let selected_text = self.selected_text_for_scrollbar(buffer, cx);
+            if selected_text != self.scrollbar_marker_state.selected_text {
+                self.scrollbar_marker_state.selected_text = selected_text;
+                self.scrollbar_marker_state.dirty = true;
+            }
+        }
 
         let new_cursor_position = self.selections.newest_anchor().head();
 
@@ -1963,6 +2103,21 @@ impl Editor {
         cx.notify();
     }
 
+    /// Returns the text of the newest selection if its matches are marked in the
+    /// scrollbar, which needs it to be a single line that isn't blank.
+    fn selected_text_for_scrollbar(
+        &self,
+        buffer: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Option<String> {
+        let selection = self.selections.newest::<usize>(cx);
+        if selection.is_empty() || selection.end - selection.start > MAX_LINE_LEN {
+            return None;
+        }
+        let text = buffer.text_for_range(selection.range()).collect::<String>();
+        (!text.contains('\n') && !text.trim().is_empty()).then_some(text)
+    }
+
     pub fn change_selections<R>(
         &mut self,
         autoscroll: Option<Autoscroll>,
@@ -2391,6 +2546,10 @@ impl Editor {
             }
         }
 
+        if self.dismiss_peek(cx) {
+            return true;
+        }
+
         false
     }
 
@@ -2584,6 +2743,11 @@ impl Editor {
             edits.push((selection.start..selection.end, text.clone()));
         }
 
+        let linked_edits = linked_tag_edits(&edits, &snapshot);
+        if !linked_edits.is_empty() {
+            edits.extend(linked_edits);
+            edits.sort_by_key(|(range, _)| range.start);
+        }
         drop(snapshot);
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
@@ -2633,6 +2797,9 @@ impl Editor {
             drop(snapshot);
             let had_active_copilot_completion = this.has_active_inline_completion(cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+            if text.as_ref() == ">" {
+                this.close_tags_after_input(cx);
+            }
 
             if brace_inserted {
                 // If we inserted a brace while composing text (i.e. typing `"` on a
@@ -2968,6 +3135,24 @@ impl Editor {
         self.insert_with_autoindent_mode(text, autoindent, cx);
     }
 
+    /// Inserts text that was dropped onto the editor at the given position.
+    pub fn insert_dropped_text(
+        &mut self,
+        position: DisplayPoint,
+        text: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_display_ranges([position..position])
+        });
+        self.insert(text, cx);
+        cx.focus(&self.focus_handle);
+    }
+
     fn insert_with_autoindent_mode(
         &mut self,
         text: &str,
@@ -2982,23 +3167,27 @@ impl Editor {
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
-                let anchors = {
+                let (anchors, edits) = {
                     let snapshot = buffer.read(cx);
-                    old_selections
+                    let anchors = old_selections
                         .iter()
                         .map(|s| {
                             let anchor = snapshot.anchor_after(s.head());
                             s.map(|_| anchor)
                         })
-                        .collect::<Vec<_>>()
-                };
-                buffer.edit(
-                    old_selections
+                        .collect::<Vec<_>>();
+                    let mut edits = old_selections
                         .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                        .map(|s| (s.start..s.end, text.clone()))
+                        .collect::<Vec<_>>();
+                    let linked_edits = linked_tag_edits(&edits, &snapshot);
+                    if !linked_edits.is_empty() {
+                        edits.extend(linked_edits);
+                        edits.sort_by_key(|(range, _)| range.start);
+                    }
+                    (anchors, edits)
+                };
+                buffer.edit(edits, autoindent_mode, cx);
                 anchors
             });
 
@@ -3485,6 +3674,7 @@ impl Editor {
 
                     if this.focus_handle.is_focused(cx) && menu.is_some() {
                         let menu = menu.unwrap();
+                        menu.announce_selected_completion(cx);
                         *context_menu = Some(ContextMenu::Completions(menu));
                         drop(context_menu);
                         this.discard_inline_completion(cx);
@@ -4016,6 +4206,50 @@ impl Editor {
         .detach();
     }
 
+    fn remote_cursor_moved(&mut self, replica_id: ReplicaId, cx: &mut ViewContext<Self>) {
+        if EditorSettings::get_global(cx).show_cursor_names != ShowCursorNames::Auto {
+            return;
+        }
+
+        let show = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(CURSORS_VISIBLE_FOR).await;
+            this.update(&mut cx, |this, cx| {
+                let fade_out = cx.animate(
+                    Animation::new(CURSOR_NAMES_FADE_OUT),
+                    move |this, delta, cx| {
+                        if delta >= 1. {
+                            this.active_remote_cursors.remove(&replica_id);
+                        } else if let Some(name) = this.active_remote_cursors.get_mut(&replica_id) {
+                            name.opacity = 1. - delta;
+                        }
+                        cx.notify();
+                    },
+                );
+                if let Some(name) = this.active_remote_cursors.get_mut(&replica_id) {
+                    name._fade_out = Some(fade_out);
+                }
+            })
+            .ok();
+        });
+        self.active_remote_cursors.insert(
+            replica_id,
+            RemoteCursorName {
+                opacity: 1.,
+                _show: show,
+                _fade_out: None,
+            },
+        );
+        cx.notify();
+    }
+
+    /// The opacity of the name label of a collaborator's cursor that recently moved,
+    /// which fades out once the collaborator stops moving it.
+    pub(crate) fn remote_cursor_name_opacity(&self, replica_id: ReplicaId) -> Option<f32> {
+        self.active_remote_cursors
+            .get(&replica_id)
+            .map(|name| name.opacity)
+    }
+
     pub fn next_inline_completion(&mut self, _: &NextInlineCompletion, cx: &mut ViewContext<Self>) {
         if self.has_active_inline_completion(cx) {
             self.cycle_inline_completion(Direction::Next, cx);
@@ -4866,19 +5100,9 @@ impl Editor {
         self.transact(cx, |this, cx| {
             for row_range in row_ranges.into_iter().rev() {
                 for row in row_range.rev() {
-                    let end_of_line = Point::new(row, snapshot.line_len(row));
-                    let indent = snapshot.indent_size_for_line(row + 1);
-                    let start_of_next_line = Point::new(row + 1, indent.len);
-
-                    let replace = if snapshot.line_len(row + 1) > indent.len {
-                        " "
-                    } else {
-                        ""
-                    };
-
-                    this.buffer.update(cx, |buffer, cx| {
-                        buffer.edit([(end_of_line..start_of_next_line, replace)], None, cx)
-                    });
+                    let (range, replace) = join_line_edit(&snapshot, row);
+                    this.buffer
+                        .update(cx, |buffer, cx| buffer.edit([(range, replace)], None, cx));
                 }
             }
 
@@ -4904,6 +5128,19 @@ impl Editor {
         self.manipulate_lines(cx, |lines| lines.sort_by_key(|line| line.to_lowercase()))
     }
 
+    /// Sorts the selected lines by the first number in each of them. Lines without
+    /// a number are moved to the end.
+    pub fn sort_lines_numerically(&mut self, _: &SortLinesNumerically, cx: &mut ViewContext<Self>) {
+        self.manipulate_lines(cx, |lines| {
+            lines.sort_by(|a, b| match (first_number(a), first_number(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+        })
+    }
+
     pub fn unique_lines_case_insensitive(
         &mut self,
         _: &UniqueLinesCaseInsensitive,
@@ -5160,6 +5397,14 @@ impl Editor {
         self.manipulate_text(cx, |text| text.to_case(Case::Snake))
     }
 
+    pub fn convert_to_screaming_snake_case(
+        &mut self,
+        _: &ConvertToScreamingSnakeCase,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.manipulate_text(cx, |text| text.to_case(Case::ScreamingSnake))
+    }
+
     pub fn convert_to_kebab_case(&mut self, _: &ConvertToKebabCase, cx: &mut ViewContext<Self>) {
         self.manipulate_text(cx, |text| text.to_case(Case::Kebab))
     }
@@ -5302,6 +5547,7 @@ impl Editor {
         let mut edits = Vec::new();
         let mut unfold_ranges = Vec::new();
         let mut refold_ranges = Vec::new();
+        let mut moved_rows = Vec::new();
 
         let selections = self.selections.all::<Point>(cx);
         let mut selections = selections.iter().peekable();
@@ -5350,6 +5596,7 @@ impl Editor {
                     edits.push((insertion_anchor..insertion_anchor, text));
 
                     let row_delta = range_to_move.start.row - insertion_point.row + 1;
+                    moved_rows.push(start_row - row_delta..end_row - row_delta);
 
                     // Move selections up
                     new_selections.extend(contiguous_row_selections.drain(..).map(
@@ -5389,7 +5636,8 @@ impl Editor {
             this.fold_ranges(refold_ranges, true, cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select(new_selections);
-            })
+            });
+            this.reindent_moved_rows(moved_rows, cx);
         });
     }
 
@@ -5400,6 +5648,7 @@ impl Editor {
         let mut edits = Vec::new();
         let mut unfold_ranges = Vec::new();
         let mut refold_ranges = Vec::new();
+        let mut moved_rows = Vec::new();
 
         let selections = self.selections.all::<Point>(cx);
         let mut selections = selections.iter().peekable();
@@ -5441,6 +5690,7 @@ impl Editor {
                     edits.push((insertion_anchor..insertion_anchor, text));
 
                     let row_delta = insertion_point.row - range_to_move.end.row + 1;
+                    moved_rows.push(start_row + row_delta..end_row + row_delta);
 
                     // Move selections down
                     new_selections.extend(contiguous_row_selections.drain(..).map(
@@ -5479,9 +5729,55 @@ impl Editor {
             });
             this.fold_ranges(refold_ranges, true, cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+            this.reindent_moved_rows(moved_rows, cx);
         });
     }
 
+    /// Shifts each block of moved rows so that its first non-blank line gets the
+    /// indentation suggested by its new surroundings, keeping the relative
+    /// indentation of the lines within the block.
+    fn reindent_moved_rows(&mut self, moved_rows: Vec<Range<u32>>, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let first_rows = moved_rows
+            .iter()
+            .filter_map(|rows| rows.clone().find(|row| !snapshot.is_line_blank(*row)))
+            .collect::<Vec<_>>();
+        let suggested_indents = snapshot.suggested_indents(first_rows.iter().copied(), cx);
+
+        let mut edits = Vec::new();
+        for (rows, first_row) in moved_rows.into_iter().zip(first_rows) {
+            let Some(suggested_indent) = suggested_indents.get(&first_row) else {
+                continue;
+            };
+            let current_indent = snapshot.indent_size_for_line(first_row);
+            if suggested_indent.kind != current_indent.kind
+                || suggested_indent.len == current_indent.len
+            {
+                continue;
+            }
+
+            let delta = suggested_indent.len as i64 - current_indent.len as i64;
+            for row in rows {
+                if snapshot.is_line_blank(row) {
+                    continue;
+                }
+                let indent = snapshot.indent_size_for_line(row);
+                let new_indent = IndentSize {
+                    len: (indent.len as i64 + delta).max(0) as u32,
+                    kind: indent.kind,
+                };
+                edits.extend(Buffer::edit_for_indent_size_adjustment(
+                    row, indent, new_indent,
+                ));
+            }
+        }
+
+        if !edits.is_empty() {
+            self.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        }
+    }
+
     pub fn transpose(&mut self, _: &Transpose, cx: &mut ViewContext<Self>) {
         let text_layout_details = &self.text_layout_details(cx);
         self.transact(cx, |this, cx| {
@@ -6832,13 +7128,7 @@ impl Editor {
         let mut new_selections = self.selections.all::<usize>(cx);
 
         let buffer = &display_map.buffer_snapshot;
-        let query_matches = select_next_state
-            .query
-            .stream_find_iter(buffer.bytes_in_range(0..buffer.len()));
-
-        for query_match in query_matches {
-            let query_match = query_match.unwrap(); // can only fail due to I/O
-            let offset_range = query_match.start()..query_match.end();
+        for offset_range in buffer_matches(&select_next_state.query, buffer) {
             let display_range = offset_range.start.to_display_point(&display_map)
                 ..offset_range.end.to_display_point(&display_map);
 
@@ -7375,6 +7665,21 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    /// Goes back to the selections from before the syntax nodes were first expanded,
+    /// however many times that happened since.
+    pub fn shrink_selection_to_expansion_start(
+        &mut self,
+        _: &ShrinkSelectionToExpansionStart,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let stack = mem::take(&mut self.select_larger_syntax_node_stack);
+        if let Some(selections) = stack.into_iter().next() {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(selections.to_vec());
+            });
+        }
+    }
+
     pub fn move_to_enclosing_bracket(
         &mut self,
         _: &MoveToEnclosingBracket,
@@ -7462,6 +7767,43 @@ impl Editor {
         self.selection_history.mode = SelectionHistoryMode::Normal;
     }
 
+    /// Restores the most recent selections that either selected some text or had
+    /// several cursors, skipping over the cursor movements made since then.
+    pub fn reselect_previous(&mut self, _: &ReselectPrevious, cx: &mut ViewContext<Self>) {
+        self.end_selection(cx);
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let offset_ranges = |selections: &[Selection<Anchor>]| {
+            selections
+                .iter()
+                .map(|selection| {
+                    selection.start.to_offset(&buffer)..selection.end.to_offset(&buffer)
+                })
+                .collect::<Vec<_>>()
+        };
+        let current_ranges = offset_ranges(&self.selections.disjoint_anchors());
+        let Some(entry) = self
+            .selection_history
+            .undo_stack
+            .iter()
+            .rev()
+            .find(|entry| {
+                let ranges = offset_ranges(&entry.selections);
+                ranges != current_ranges
+                    && (ranges.len() > 1 || ranges.iter().any(|range| !range.is_empty()))
+            })
+            .cloned()
+        else {
+            return;
+        };
+
+        self.change_selections(Some(Autoscroll::newest()), cx, |s| {
+            s.select_anchors(entry.selections.to_vec())
+        });
+        self.select_next_state = entry.select_next_state;
+        self.select_prev_state = entry.select_prev_state;
+        self.add_selections_state = entry.add_selections_state;
+    }
+
     pub fn expand_excerpts(&mut self, action: &ExpandExcerpts, cx: &mut ViewContext<Self>) {
         let selections = self.selections.disjoint_anchors();
 
@@ -7557,6 +7899,9 @@ impl Editor {
                             goal: SelectionGoal::None,
                         }]);
                     });
+                    if let Some(active_diagnostics) = self.active_diagnostics.as_ref() {
+                        cx.announce(&active_diagnostics.primary_message);
+                    }
                 }
                 break;
             } else {
@@ -8429,6 +8774,22 @@ impl Editor {
         })
     }
 
+    fn add_missing_imports(
+        &mut self,
+        _: &AddMissingImports,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let project = self.project.clone()?;
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        let add_missing_imports = project.update(cx, |project, cx| {
+            project.add_missing_imports(buffer, true, cx)
+        });
+        Some(cx.spawn(|_, _| async move {
+            add_missing_imports.await?;
+            Ok(())
+        }))
+    }
+
     fn restart_language_server(&mut self, _: &RestartLanguageServer, cx: &mut ViewContext<Self>) {
         if let Some(project) = self.project.clone() {
             self.buffer.update(cx, |multi_buffer, cx| {
@@ -8443,6 +8804,37 @@ impl Editor {
         cx.show_character_palette();
     }
 
+    /// Shows which font renders the character after the newest cursor, which
+    /// is useful to tell whether font fallbacks are configured as intended.
+    fn show_character_font(&mut self, _: &ShowCharacterFont, cx: &mut ViewContext<Self>) {
+        let Some(font) = self.style.as_ref().map(|style| style.text.font()) else {
+            return;
+        };
+        let cursor = self.selections.newest::<usize>(cx).head();
+        let Some(ch) = self.buffer.read(cx).snapshot(cx).chars_at(cursor).next() else {
+            return;
+        };
+
+        let message = match cx.text_system().font_for_char(&font, ch) {
+            Some(char_font) => format!(
+                "{ch:?} (U+{:04X}) is rendered with \"{}\"",
+                ch as u32, char_font.family
+            ),
+            None => format!(
+                "Neither \"{}\" nor its fallbacks have a glyph for {ch:?} (U+{:04X})",
+                font.family, ch as u32
+            ),
+        };
+        if let Some(workspace) = self.workspace() {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<ShowCharacterFont>(), message),
+                    cx,
+                )
+            })
+        }
+    }
+
     fn refresh_active_diagnostics(&mut self, cx: &mut ViewContext<Editor>) {
         if let Some(active_diagnostics) = self.active_diagnostics.as_mut() {
             let buffer = self.buffer.read(cx).snapshot(cx);
@@ -8844,6 +9236,13 @@ impl Editor {
             if let SoftWrap::Column(soft_wrap) = self.soft_wrap_mode(cx) {
                 wrap_guides.push((soft_wrap as usize, true));
             }
+            if settings.wrap_guide_from_formatter_config {
+                if let Some(line_length) = self.formatter_line_length {
+                    if !wrap_guides.iter().any(|(guide, _)| *guide == line_length) {
+                        wrap_guides.push((line_length, true));
+                    }
+                }
+            }
             wrap_guides.extend(settings.wrap_guides.iter().map(|guide| (*guide, false)))
         }
 
@@ -8920,6 +9319,17 @@ impl Editor {
         cx.notify();
     }
 
+    fn apply_zen_mode_settings(
+        &mut self,
+        zen_mode: Option<ZenModeSettings>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.show_line_numbers = zen_mode
+            .filter(|zen_mode| !zen_mode.show_line_numbers)
+            .map(|_| false);
+        cx.notify();
+    }
+
     pub fn set_show_wrap_guides(&mut self, show_gutter: bool, cx: &mut ViewContext<Self>) {
         self.show_wrap_guides = Some(show_gutter);
         cx.notify();
@@ -9454,7 +9864,26 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         self.display_map.update(cx, |map, _| {
-            map.highlight_text(TypeId::of::<T>(), ranges, style)
+            map.highlight_text(HighlightKey::Type(TypeId::of::<T>()), ranges, style)
+        });
+        cx.notify();
+    }
+
+    /// Like [`Editor::highlight_text`], but allows a single type to own several
+    /// differently styled sets of ranges, distinguished by `key`.
+    pub fn highlight_text_key<T: 'static>(
+        &mut self,
+        key: usize,
+        ranges: Vec<Range<Anchor>>,
+        style: HighlightStyle,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.display_map.update(cx, |map, _| {
+            map.highlight_text(
+                HighlightKey::TypePlus(TypeId::of::<T>(), key),
+                ranges,
+                style,
+            )
         });
         cx.notify();
     }
@@ -9512,6 +9941,7 @@ impl Editor {
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
                 }
+                refresh_rainbow_bracket_highlights(self, cx);
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
 
@@ -9559,19 +9989,26 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_rainbow_bracket_highlights(self, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                refresh_rainbow_bracket_highlights(self, cx);
+                cx.emit(EditorEvent::Reparsed);
+            }
             multi_buffer::Event::LanguageChanged => {
+                refresh_rainbow_bracket_highlights(self, cx);
+                self.refresh_formatter_line_length(cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
+                self.refresh_formatter_line_length(cx);
                 cx.emit(EditorEvent::TitleChanged)
             }
             multi_buffer::Event::DiffBaseChanged => {
@@ -9580,6 +10017,9 @@ impl Editor {
                 cx.notify();
             }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
+            multi_buffer::Event::RemoteSelectionsChanged { replica_id } => {
+                self.remote_cursor_moved(*replica_id, cx)
+            }
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
                 self.scrollbar_marker_state.dirty = true;
@@ -9606,6 +10046,8 @@ impl Editor {
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        refresh_rainbow_bracket_highlights(self, cx);
+        self.refresh_formatter_line_length(cx);
 
         if self.mode == EditorMode::Full {
             let inline_blame_enabled = ProjectSettings::get_global(cx).git.inline_blame_enabled();
@@ -10024,6 +10466,7 @@ pub trait CollaborationHub {
         &self,
         cx: &'a AppContext,
     ) -> &'a HashMap<u64, ParticipantIndex>;
+    fn user_presences<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, ParticipantPresence>;
     fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString>;
 }
 
@@ -10039,6 +10482,10 @@ impl CollaborationHub for Model<Project> {
         self.read(cx).user_store().read(cx).participant_indices()
     }
 
+    fn user_presences<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, ParticipantPresence> {
+        self.read(cx).user_store().read(cx).participant_presences()
+    }
+
     fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString> {
         let this = self.read(cx);
         let user_ids = this.collaborators().values().map(|c| c.user_id);
@@ -10108,6 +10555,20 @@ impl CompletionProvider for Model<Project> {
     }
 }
 
+/// Returns the ranges of the query's matches in the buffer, which is searched
+/// without copying its text.
+pub(crate) fn buffer_matches<'a>(
+    query: &'a AhoCorasick,
+    buffer: &'a MultiBufferSnapshot,
+) -> impl 'a + Iterator<Item = Range<usize>> {
+    query
+        .stream_find_iter(buffer.bytes_in_range(0..buffer.len()))
+        .map(|query_match| {
+            let query_match = query_match.unwrap(); // can only fail due to I/O
+            query_match.start()..query_match.end()
+        })
+}
+
 fn inlay_hint_settings(
     location: Anchor,
     snapshot: &MultiBufferSnapshot,
@@ -10121,6 +10582,27 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Returns the first number in the line, like `-1.5` in `x = -1.5;`.
+fn first_number(line: &str) -> Option<f64> {
+    let digits_len = |text: &str| {
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len())
+    };
+
+    let mut start = line.find(|c: char| c.is_ascii_digit())?;
+    let mut end = start + digits_len(&line[start..]);
+    if line[end..].starts_with('.') {
+        let fraction_len = digits_len(&line[end + 1..]);
+        if fraction_len > 0 {
+            end += 1 + fraction_len;
+        }
+    }
+    if line[..start].ends_with('-') {
+        start -= 1;
+    }
+    line[start..end].parse().ok()
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -10150,6 +10632,88 @@ fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot)
     }
 }
 
+/// Returns the edit that joins the given row with the next one. The whitespace
+/// between the lines is collapsed into a single space. When both lines are line
+/// comments, the next line's comment prefix is removed, and when the lines end and
+/// start with string literals, the literals are merged into one.
+fn join_line_edit(snapshot: &MultiBufferSnapshot, row: u32) -> (Range<Point>, &'static str) {
+    let line_len = snapshot.line_len(row);
+    if row >= snapshot.max_point().row {
+        let end_of_line = Point::new(row, line_len);
+        return (end_of_line..end_of_line, "");
+    }
+
+    let trailing_whitespace = snapshot
+        .reversed_chars_at(Point::new(row, line_len))
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count() as u32;
+    let mut end_of_line = Point::new(row, line_len - trailing_whitespace);
+    let indent = snapshot.indent_size_for_line(row + 1);
+    let mut start_of_next_line = Point::new(row + 1, indent.len);
+    let next_line_len = snapshot.line_len(row + 1);
+    if next_line_len <= indent.len {
+        return (end_of_line..start_of_next_line, "");
+    }
+    if end_of_line.column == 0 {
+        return (end_of_line..start_of_next_line, " ");
+    }
+
+    let line_text = snapshot
+        .text_for_range(Point::new(row, 0)..end_of_line)
+        .collect::<String>();
+    let next_line_text = snapshot
+        .text_for_range(start_of_next_line..Point::new(row + 1, next_line_len))
+        .collect::<String>();
+    let line_end = end_of_line.to_offset(snapshot);
+    let next_line_start = start_of_next_line.to_offset(snapshot);
+    let last_char = line_text.chars().next_back().unwrap_or_default();
+    let last_char_offset = line_end - last_char.len_utf8();
+    let is_in = |offset: usize, override_name: &str| {
+        snapshot
+            .language_scope_at(offset)
+            .map_or(false, |scope| scope.override_name() == Some(override_name))
+    };
+
+    let scope = snapshot.language_scope_at(next_line_start);
+    let comment_prefix = scope.as_ref().and_then(|scope| {
+        let prefix = scope
+            .line_comment_prefixes()?
+            .iter()
+            .map(|prefix| prefix.trim_end())
+            .filter(|prefix| !prefix.is_empty() && next_line_text.starts_with(prefix))
+            .max_by_key(|prefix| prefix.len())?;
+        let ends_block_comment = scope
+            .block_comment_delimiters()
+            .map_or(false, |(_, end)| line_text.ends_with(end.trim()));
+        (line_text.contains(prefix) && !ends_block_comment).then_some(prefix.len())
+    });
+    if let Some(prefix_len) = comment_prefix {
+        if is_in(last_char_offset, "comment") && is_in(next_line_start + 1, "comment") {
+            let comment = next_line_text[prefix_len..].trim_start();
+            start_of_next_line.column += (next_line_text.len() - comment.len()) as u32;
+            let replace = if comment.is_empty() { "" } else { " " };
+            return (end_of_line..start_of_next_line, replace);
+        }
+    }
+
+    // Merge adjacent literals like `"a"` and `"b"`, but not the delimiters of
+    // strings like `"""a"""`.
+    if matches!(last_char, '"' | '\'' | '`')
+        && next_line_text.starts_with(last_char)
+        && !line_text[..line_text.len() - 1].ends_with(last_char)
+        && !next_line_text[1..].starts_with(last_char)
+        && is_in(last_char_offset, "string")
+        && !is_in(line_end, "string")
+        && is_in(next_line_start + 1, "string")
+    {
+        end_of_line.column -= 1;
+        start_of_next_line.column += 1;
+        return (end_of_line..start_of_next_line, "");
+    }
+
+    (end_of_line..start_of_next_line, " ")
+}
+
 impl EditorSnapshot {
     pub fn remote_selections_in_range<'a>(
         &'a self,
@@ -10159,6 +10723,7 @@ impl EditorSnapshot {
     ) -> impl 'a + Iterator<Item = RemoteSelection> {
         let participant_names = collaboration_hub.user_names(cx);
         let participant_indices = collaboration_hub.user_participant_indices(cx);
+        let participant_presences = collaboration_hub.user_presences(cx);
         let collaborators_by_peer_id = collaboration_hub.collaborators(cx);
         let collaborators_by_replica_id = collaborators_by_peer_id
             .iter()
@@ -10170,6 +10735,10 @@ impl EditorSnapshot {
                 let collaborator = collaborators_by_replica_id.get(&replica_id)?;
                 let participant_index = participant_indices.get(&collaborator.user_id).copied();
                 let user_name = participant_names.get(&collaborator.user_id).cloned();
+                let presence = participant_presences
+                    .get(&collaborator.user_id)
+                    .copied()
+                    .unwrap_or_default();
                 Some(RemoteSelection {
                     replica_id,
                     selection,
@@ -10178,6 +10747,7 @@ impl EditorSnapshot {
                     participant_index,
                     peer_id: collaborator.peer_id,
                     user_name,
+                    presence,
                 })
             })
     }
@@ -10198,6 +10768,11 @@ impl EditorSnapshot {
         self.scroll_anchor.scroll_position(&self.display_snapshot)
     }
 
+    pub fn show_line_numbers(&self, cx: &AppContext) -> bool {
+        self.show_line_numbers
+            .unwrap_or_else(|| EditorSettings::get_global(cx).gutter.line_numbers)
+    }
+
     pub fn gutter_dimensions(
         &self,
         font_id: FontId,
@@ -10215,7 +10790,8 @@ impl EditorSnapshot {
             ProjectSettings::get_global(cx).git.git_gutter,
             Some(GitGutterSetting::TrackedFiles)
         );
-        let gutter_settings = EditorSettings::get_global(cx).gutter;
+        let mut gutter_settings = EditorSettings::get_global(cx).gutter;
+        gutter_settings.line_numbers = self.show_line_numbers(cx);
 
         let line_gutter_width = if gutter_settings.line_numbers {
             // Avoid flicker-like gutter resizes when the line number gains another digit and only resize the gutter on files with N*10^5 lines.
@@ -10324,19 +10900,37 @@ impl Render for Editor {
 
         let text_style = match self.mode {
             EditorMode::SingleLine | EditorMode::AutoHeight { .. } => cx.text_style(),
-            EditorMode::Full => TextStyle {
-                color: cx.theme().colors().editor_foreground,
-                font_family: settings.buffer_font.family.clone(),
-                font_features: settings.buffer_font.features,
-                font_size: settings.buffer_font_size(cx).into(),
-                font_weight: FontWeight::NORMAL,
-                font_style: FontStyle::Normal,
-                line_height: relative(settings.buffer_line_height.value()),
-                background_color: None,
-                underline: None,
-                strikethrough: None,
-                white_space: WhiteSpace::Normal,
-            },
+            EditorMode::Full => {
+                // Buffers in some languages may use a different font than the
+                // one in the theme settings.
+                let language_settings = self.buffer.read(cx).settings_at(0, cx);
+                TextStyle {
+                    color: cx.theme().colors().editor_foreground,
+                    font_family: language_settings
+                        .buffer_font_family
+                        .clone()
+                        .map_or_else(|| settings.buffer_font.family.clone(), Into::into),
+                    font_features: language_settings
+                        .buffer_font_features
+                        .unwrap_or(settings.buffer_font.features),
+                    font_size: language_settings
+                        .buffer_font_size
+                        .map_or_else(
+                            || settings.buffer_font_size(cx),
+                            |font_size| theme::adjusted_font_size(px(font_size), cx),
+                        )
+                        .into(),
+                    font_weight: language_settings
+                        .buffer_font_weight
+                        .map_or(FontWeight::NORMAL, FontWeight),
+                    font_style: FontStyle::Normal,
+                    line_height: relative(settings.buffer_line_height.value()),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                    white_space: WhiteSpace::Normal,
+                }
+            }
         };
 
         let background = match self.mode {
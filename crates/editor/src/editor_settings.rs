@@ -13,6 +13,7 @@ pub struct EditorSettings {
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
     pub gutter: Gutter,
     pub vertical_scroll_margin: f32,
     pub scroll_sensitivity: f32,
@@ -22,6 +23,9 @@ pub struct EditorSettings {
     pub redact_private_values: bool,
     #[serde(default)]
     pub double_click_in_multibuffer: DoubleClickInMultibuffer,
+    #[serde(default)]
+    pub show_cursor_names: ShowCursorNames,
+    pub persistent_undo: PersistentUndo,
 }
 
 /// When to populate a new search's query based on the text under the cursor.
@@ -48,6 +52,22 @@ pub enum DoubleClickInMultibuffer {
     Open,
 }
 
+/// When to show collaborators' names next to their cursors.
+///
+/// Default: auto
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowCursorNames {
+    /// Show a collaborator's name while they're moving their cursor or typing,
+    /// and when their cursor is hovered.
+    #[default]
+    Auto,
+    /// Always show collaborators' names.
+    Always,
+    /// Never show collaborators' names.
+    Never,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Toolbar {
     pub breadcrumbs: bool,
@@ -60,9 +80,27 @@ pub struct Scrollbar {
     pub git_diff: bool,
     pub selected_symbol: bool,
     pub search_results: bool,
+    pub selection_matches: bool,
+    pub diagnostics: bool,
+    pub diagnostics_severity: ScrollbarDiagnosticSeverity,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+    pub width: f32,
+    pub git_diff: bool,
+    pub search_results: bool,
     pub diagnostics: bool,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PersistentUndo {
+    pub enabled: bool,
+    pub max_age_days: u64,
+    pub max_size_kb: usize,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -87,6 +125,50 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// The least severe diagnostics to show in the scrollbar.
+///
+/// Default: hint
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollbarDiagnosticSeverity {
+    /// Show all of the diagnostics, down to hints.
+    Hint,
+    /// Show informational diagnostics, warnings and errors.
+    Information,
+    /// Show warnings and errors.
+    Warning,
+    /// Show only errors.
+    Error,
+}
+
+impl ScrollbarDiagnosticSeverity {
+    /// Returns whether diagnostics of the given severity are shown.
+    pub fn includes(&self, severity: lsp::DiagnosticSeverity) -> bool {
+        let least_severe = match self {
+            Self::Hint => lsp::DiagnosticSeverity::HINT,
+            Self::Information => lsp::DiagnosticSeverity::INFORMATION,
+            Self::Warning => lsp::DiagnosticSeverity::WARNING,
+            Self::Error => lsp::DiagnosticSeverity::ERROR,
+        };
+        // More severe diagnostics have lower values.
+        severity <= least_severe
+    }
+}
+
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Always show the minimap.
+    Always,
+    /// Only show the minimap in editors for a single buffer.
+    Singleton,
+    /// Never show the minimap.
+    Never,
+}
+
 /// The key to use for adding multiple cursors
 ///
 /// Default: alt
@@ -133,6 +215,8 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// The number of lines to keep above/below the cursor when auto-scrolling.
@@ -168,6 +252,14 @@ pub struct EditorSettingsContent {
     ///
     /// Default: select
     pub double_click_in_multibuffer: Option<DoubleClickInMultibuffer>,
+
+    /// When to show collaborators' names next to their cursors.
+    ///
+    /// Default: auto
+    pub show_cursor_names: Option<ShowCursorNames>,
+
+    /// Keeping the undo history of files across restarts.
+    pub persistent_undo: Option<PersistentUndoContent>,
 }
 
 // Toolbar related settings
@@ -202,10 +294,44 @@ pub struct ScrollbarContent {
     ///
     /// Default: true
     pub selected_symbol: Option<bool>,
+    /// Whether to show the occurrences of the selected text in the scrollbar,
+    /// when it's within a single line.
+    ///
+    /// Default: true
+    pub selection_matches: Option<bool>,
     /// Whether to show diagnostic indicators in the scrollbar.
     ///
     /// Default: true
     pub diagnostics: Option<bool>,
+    /// The least severe diagnostics to show in the scrollbar.
+    ///
+    /// Default: hint
+    pub diagnostics_severity: Option<ScrollbarDiagnosticSeverity>,
+}
+
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+    /// The width of the minimap, in pixels.
+    ///
+    /// Default: 100.0
+    pub width: Option<f32>,
+    /// Whether to show git diff indicators in the minimap.
+    ///
+    /// Default: true
+    pub git_diff: Option<bool>,
+    /// Whether to show buffer search result indicators in the minimap.
+    ///
+    /// Default: true
+    pub search_results: Option<bool>,
+    /// Whether to show diagnostic indicators in the minimap.
+    ///
+    /// Default: true
+    pub diagnostics: Option<bool>,
 }
 
 /// Gutter related settings
@@ -225,6 +351,28 @@ pub struct GutterContent {
     pub folds: Option<bool>,
 }
 
+/// Persistent undo related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PersistentUndoContent {
+    /// Whether to keep the undo history of files when they're saved, so that
+    /// changes from before a restart can still be undone after reopening them.
+    /// The history is kept in Zed's database, so this can be disabled in the
+    /// settings of projects with sensitive files.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The number of days after which the history of a file that hasn't been
+    /// saved since is forgotten.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// The maximum size of the text kept to undo the changes to a file, in
+    /// kilobytes. The oldest changes are forgotten first.
+    ///
+    /// Default: 1024
+    pub max_size_kb: Option<usize>,
+}
+
 impl Settings for EditorSettings {
     const KEY: Option<&'static str> = None;
 
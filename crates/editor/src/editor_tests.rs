@@ -8,7 +8,7 @@ use crate::{
     JoinLines,
 };
 use futures::StreamExt;
-use gpui::{div, TestAppContext, VisualTestContext, WindowOptions};
+use gpui::{div, AccessibleRole, AccessibleText, TestAppContext, VisualTestContext, WindowOptions};
 use indoc::indoc;
 use language::{
     language_settings::{AllLanguageSettings, AllLanguageSettingsContent, LanguageSettingsContent},
@@ -322,6 +322,41 @@ fn test_ime_composition(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_ime_preedit_and_commit(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("one ˇtwo");
+
+    // Composed text is inserted at the cursor and marked until it's committed.
+    cx.simulate_ime_preedit("´");
+    cx.assert_editor_state("one ´ˇtwo");
+    cx.simulate_ime_preedit("にほ");
+    cx.assert_editor_state("one にほˇtwo");
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.marked_text_ranges(cx),
+            Some(vec![OffsetUtf16(4)..OffsetUtf16(6)])
+        );
+    });
+
+    // Committing replaces the composition, including with text outside the BMP.
+    cx.simulate_ime_commit("😀");
+    cx.assert_editor_state("one 😀ˇtwo");
+    cx.update_editor(|editor, cx| assert_eq!(editor.marked_text_ranges(cx), None));
+
+    // Cancelling a composition removes its text.
+    cx.simulate_ime_preedit("é");
+    cx.simulate_ime_preedit("");
+    cx.assert_editor_state("one 😀ˇtwo");
+    cx.update_editor(|editor, cx| assert_eq!(editor.marked_text_ranges(cx), None));
+
+    // Cancelling when nothing is being composed leaves the selection alone.
+    cx.set_state("one «twoˇ»");
+    cx.simulate_ime_preedit("");
+    cx.assert_editor_state("one «twoˇ»");
+}
+
 #[gpui::test]
 fn test_selection_with_mouse(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -542,7 +577,7 @@ fn test_clone(cx: &mut TestAppContext) {
 async fn test_navigation_history(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
 
-    use workspace::item::Item;
+    use workspace::item::{Item, NavigationPosition};
 
     let fs = FakeFs::new(cx.executor());
     let project = Project::test(fs, [], cx).await;
@@ -651,6 +686,18 @@ async fn test_navigation_history(cx: &mut TestAppContext) {
                 gpui::Point::new(0., editor.max_point(cx).row() as f32)
             );
 
+            // Entries on the cursor's line are skipped, while positions restored from
+            // a previous session are navigated to like any other entry.
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(2, 0)..DisplayPoint::new(2, 0)])
+            });
+            assert!(!editor.navigate(Box::new(NavigationPosition { row: 2, column: 3 }), cx));
+            assert!(editor.navigate(Box::new(NavigationPosition { row: 7, column: 2 }), cx));
+            assert_eq!(
+                editor.selections.display_ranges(cx),
+                &[DisplayPoint::new(7, 2)..DisplayPoint::new(7, 2)]
+            );
+
             editor
         })
     });
@@ -2010,6 +2057,32 @@ fn test_insert_with_old_selections(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_insert_dropped_text(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("one\ntwo\n", cx);
+        let mut editor = build_editor(buffer.clone(), cx);
+        editor.change_selections(None, cx, |s| s.select_ranges([0..3]));
+        editor
+    });
+
+    // The text is inserted at the drop position rather than over the selections.
+    _ = editor.update(cx, |editor, cx| {
+        editor.insert_dropped_text(DisplayPoint::new(1, 1), "dropped ", cx);
+        assert_eq!(editor.text(cx), "one\ntdropped wo\n");
+        assert_eq!(editor.selections.ranges(cx), &[13..13]);
+    });
+
+    // Read-only editors ignore drops.
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_read_only(true);
+        editor.insert_dropped_text(DisplayPoint::new(0, 0), "ignored", cx);
+        assert_eq!(editor.text(cx), "one\ntdropped wo\n");
+    });
+}
+
 #[gpui::test]
 async fn test_tab(cx: &mut gpui::TestAppContext) {
     init_test(cx, |settings| {
@@ -2729,6 +2802,61 @@ async fn test_join_lines_with_git_diff_base(
     );
 }
 
+#[gpui::test]
+async fn test_join_lines_with_comments_and_strings(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                line_comments: vec!["// ".into()],
+                ..Default::default()
+            },
+            Some(tree_sitter_typescript::language_tsx()),
+        )
+        .with_override_query("(comment) @comment (string) @string")
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The comment prefix of the next line is removed.
+    cx.set_state(indoc! {"
+        // ˇThe first line
+        //   and the second.
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        // The first lineˇ and the second.
+    "});
+
+    // Adjacent string literals are merged.
+    cx.set_state(indoc! {"
+        const s = \"oneˇ\"
+            \"two\";
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        const s = \"oneˇtwo\";
+    "});
+
+    // Comment prefixes are only removed when joining two comments.
+    cx.set_state(indoc! {"
+        let url = \"http://example.com\";ˇ
+        // comment
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        let url = \"http://example.com\";ˇ // comment
+    "});
+
+    // Trailing whitespace is collapsed along with the indentation.
+    cx.set_state("let x = 1;ˇ   \n    let y = 2;\n");
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state("let x = 1;ˇ let y = 2;\n");
+}
+
 #[gpui::test]
 async fn test_manipulate_lines_with_single_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2771,6 +2899,23 @@ async fn test_manipulate_lines_with_single_selection(cx: &mut TestAppContext) {
         5ˇ»
     "});
 
+    // Test sort_lines_numerically()
+    cx.set_state(indoc! {"
+        «item 10
+        item 9
+        no number
+        -3 degrees
+        item 1.5ˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_numerically(&SortLinesNumerically, cx));
+    cx.assert_editor_state(indoc! {"
+        «-3 degrees
+        item 1.5
+        item 9
+        item 10
+        no numberˇ»
+    "});
+
     // Skip testing shuffle_line()
 
     // From here on out, test more complex cases of manipulate_lines() with a single driver method: sort_lines_case_sensitive()
@@ -3132,6 +3277,14 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
         «aaaBbbˇ»
     "});
 
+    cx.set_state(indoc! {"
+        «helloWorldˇ» «foo-barˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_screaming_snake_case(&ConvertToScreamingSnakeCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «HELLO_WORLDˇ» «FOO_BARˇ»
+    "});
+
     // Test to make sure we all aware of the fact that each word can grow and shrink
     // Final selections should be aware of this fact
     cx.set_state(indoc! {"
@@ -3143,6 +3296,89 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_increment_and_decrement(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Numbers under or after each cursor are changed by the count.
+    cx.set_state("let x = ˇ9;\nlet y = 1ˇ;\n");
+    cx.update_editor(|e, cx| e.increment(&Increment::default(), cx));
+    cx.assert_editor_state("let x = 10ˇ;\nlet y = 2ˇ;\n");
+    cx.update_editor(|e, cx| e.decrement(&Decrement { count: Some(20) }, cx));
+    cx.assert_editor_state("let x = -10ˇ;\nlet y = -18ˇ;\n");
+
+    // Ordinals and zero-padded numbers keep their format.
+    cx.set_state("ˇthe 1st, ˇ009 and ˇ11th\n");
+    cx.update_editor(|e, cx| e.increment(&Increment::default(), cx));
+    cx.assert_editor_state("the 2ndˇ, 010ˇ and 12thˇ\n");
+
+    // Dates change by the component under the cursor.
+    cx.set_state("ˇ2024-01-31 2024-0ˇ1-31 2024-12-3ˇ1\n");
+    cx.update_editor(|e, cx| e.increment(&Increment::default(), cx));
+    cx.assert_editor_state("2025-01-31ˇ 2024-02-29ˇ 2025-01-01ˇ\n");
+
+    // Toggle words are cycled through, but only whole words are.
+    cx.set_state("if ˇtrue && ˇuntrue {\n");
+    cx.update_editor(|e, cx| e.increment(&Increment::default(), cx));
+    cx.assert_editor_state("if falseˇ && ˇuntrue {\n");
+    cx.update_editor(|e, cx| e.decrement(&Decrement::default(), cx));
+    cx.assert_editor_state("if trueˇ && ˇuntrue {\n");
+
+    cx.set_state("a ˇ&& b\n");
+    cx.update_editor(|e, cx| e.increment(&Increment::default(), cx));
+    cx.assert_editor_state("a ||ˇ b\n");
+}
+
+#[gpui::test]
+async fn test_align_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let align_on = |delimiter: &str| AlignSelections {
+        delimiter: Some(delimiter.to_string()),
+    };
+
+    // Cursors on adjacent lines align those lines together.
+    cx.set_state(indoc! {"
+        ˇlet a = 1;
+        ˇlet bcd = 2;
+        ˇlet ef=3;
+        let unaligned = 4;
+    "});
+    cx.update_editor(|e, cx| e.align_selections(&align_on("="), cx));
+    cx.assert_editor_state(indoc! {"
+        ˇlet a   = 1;
+        ˇlet bcd = 2;
+        ˇlet ef  = 3;
+        let unaligned = 4;
+    "});
+
+    // Every occurrence of the delimiter is a stop, and lines without it are left alone.
+    cx.set_state(indoc! {"
+        «a: 1, bb: 2
+        no delimiter
+        ccc: 3, d: 4ˇ»
+    "});
+    cx.update_editor(|e, cx| e.align_selections(&align_on(":"), cx));
+    cx.assert_editor_state(indoc! {"
+        «a:   1, bb: 2
+        no delimiter
+        ccc: 3, d:  4ˇ»
+    "});
+
+    // Delimiters between slashes are regexes.
+    cx.set_state(indoc! {"
+        «x => 1
+        longer = 2ˇ»
+    "});
+    cx.update_editor(|e, cx| e.align_selections(&align_on("/=>?/"), cx));
+    cx.assert_editor_state(indoc! {"
+        «x      => 1
+        longer =  2ˇ»
+    "});
+}
+
 #[gpui::test]
 fn test_duplicate_line(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3373,6 +3609,65 @@ fn test_move_line_up_down_with_blocks(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_move_line_up_down_reindents(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_indents_query(r#"(_ "{" "}" @end) @indent"#)
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        fn a() {
+            b();
+        }
+        c(ˇ);
+    "});
+
+    // Moving a line into a block indents it.
+    cx.update_editor(|editor, cx| editor.move_line_up(&MoveLineUp, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            b();
+            c(ˇ);
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.move_line_up(&MoveLineUp, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            c(ˇ);
+            b();
+        }
+    "});
+
+    // Moving it out of the block outdents it.
+    cx.update_editor(|editor, cx| editor.move_line_up(&MoveLineUp, cx));
+    cx.assert_editor_state(indoc! {"
+        c(ˇ);
+        fn a() {
+            b();
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.move_line_down(&MoveLineDown, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            c(ˇ);
+            b();
+        }
+    "});
+}
+
 #[gpui::test]
 fn test_transpose(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4096,6 +4391,32 @@ async fn test_select_all_matches(cx: &mut gpui::TestAppContext) {
     cx.assert_editor_state("«abcˇ»\n«abcˇ» «abcˇ»\ndefabc\n«abcˇ»");
 }
 
+#[gpui::test]
+async fn test_scrollbar_selected_text(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇabc abc\n  abc\nabc def");
+    let mut select = |range: Range<usize>| {
+        cx.update_editor(|editor, cx| {
+            editor.scrollbar_marker_state.dirty = false;
+            editor.change_selections(None, cx, |s| s.select_ranges([range]));
+            (
+                editor.scrollbar_marker_state.selected_text.clone(),
+                editor.scrollbar_marker_state.dirty,
+            )
+        })
+    };
+
+    assert_eq!(select(0..3), (Some("abc".to_string()), true));
+    // Selecting another occurrence of the same text keeps the markers.
+    assert_eq!(select(4..7), (Some("abc".to_string()), false));
+    assert_eq!(select(18..21), (Some("def".to_string()), true));
+    // Blank and multi-line selections have no markers.
+    assert_eq!(select(8..10), (None, true));
+    assert_eq!(select(4..12), (None, false));
+}
+
 #[gpui::test]
 async fn test_select_next_with_multiple_carets(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4391,6 +4712,21 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
         ]
     );
 
+    // Shrinking back to where the expansion started skips the intermediate nodes.
+    _ = view.update(cx, |view, cx| {
+        view.select_larger_syntax_node(&SelectLargerSyntaxNode, cx);
+        view.select_larger_syntax_node(&SelectLargerSyntaxNode, cx);
+        view.shrink_selection_to_expansion_start(&ShrinkSelectionToExpansionStart, cx);
+    });
+    assert_eq!(
+        view.update(cx, |view, cx| view.selections.display_ranges(cx)),
+        &[
+            DisplayPoint::new(0, 25)..DisplayPoint::new(0, 25),
+            DisplayPoint::new(2, 24)..DisplayPoint::new(2, 12),
+            DisplayPoint::new(3, 18)..DisplayPoint::new(3, 18),
+        ]
+    );
+
     // Ensure that we keep expanding the selection if the larger selection starts or ends within
     // a fold.
     _ = view.update(cx, |view, cx| {
@@ -4414,6 +4750,38 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_reselect_previous(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Cursor movements since the last selection are skipped over.
+    cx.set_state("«oneˇ» two three");
+    cx.update_editor(|editor, cx| {
+        editor.move_right(&MoveRight, cx);
+        editor.move_right(&MoveRight, cx);
+        editor.move_right(&MoveRight, cx);
+    });
+    cx.assert_editor_state("one twˇo three");
+    cx.update_editor(|editor, cx| editor.reselect_previous(&ReselectPrevious, cx));
+    cx.assert_editor_state("«oneˇ» two three");
+
+    // Reselecting again has no effect when there's no other selection to go back to.
+    cx.update_editor(|editor, cx| editor.reselect_previous(&ReselectPrevious, cx));
+    cx.assert_editor_state("«oneˇ» two three");
+
+    // Multiple cursors are restored too.
+    cx.set_state("ˇone ˇtwo three");
+    cx.update_editor(|editor, cx| {
+        editor.cancel(&Cancel, cx);
+        editor.move_right(&MoveRight, cx);
+    });
+    cx.assert_editor_state("one tˇwo three");
+    cx.update_editor(|editor, cx| editor.reselect_previous(&ReselectPrevious, cx));
+    cx.assert_editor_state("ˇone ˇtwo three");
+}
+
 #[gpui::test]
 async fn test_autoindent_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4977,6 +5345,71 @@ async fn test_autoclose_with_embedded_language(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_auto_close_and_rename_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let html_language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "HTML".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_html::language()),
+        )
+        .with_tags_query(
+            r#"
+            (start_tag
+              (tag_name) @name
+              (#not-match? @name "^(br|img)$")) @open
+
+            (_
+              (start_tag (tag_name) @open_name)
+              (end_tag (tag_name) @close_name))
+            "#,
+        )
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(html_language), cx));
+
+    // Typing the `>` of an opening tag closes it, once the buffer has been reparsed.
+    cx.set_state("<div>\n    <pˇ\n</div>");
+    cx.update_editor(|editor, cx| editor.handle_input(">", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<div>\n    <p>ˇ</p>\n</div>");
+
+    // Void elements and tags that are closed already are left alone.
+    cx.set_state("<div>\n    <brˇ\n</div>");
+    cx.update_editor(|editor, cx| editor.handle_input(">", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<div>\n    <br>ˇ\n</div>");
+
+    // Renaming an opening tag renames its closing tag.
+    cx.set_state("<«divˇ»>\n    <p>a</p>\n</div>");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.handle_input("section", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<sectionˇ>\n    <p>a</p>\n</section>");
+
+    // And the other way around, including deletions.
+    cx.set_state("<div>\n    <p>a</pˇ>\n</div>");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.handle_input("re", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<div>\n    <pre>a</preˇ>\n</div>");
+    cx.update_editor(|editor, cx| editor.backspace(&Backspace, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<div>\n    <pr>a</prˇ>\n</div>");
+
+    // Typing a space after a tag's name starts its attributes instead.
+    cx.update_editor(|editor, cx| editor.change_selections(None, cx, |s| s.select_ranges([4..4])));
+    cx.update_editor(|editor, cx| editor.handle_input(" ", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("<div ˇ>\n    <pr>a</pr>\n</div>");
+}
+
 #[gpui::test]
 async fn test_autoclose_with_overrides(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7775,6 +8208,99 @@ async fn go_to_prev_overlapping_diagnostic(
     "});
 }
 
+#[gpui::test]
+async fn test_go_to_diagnostic_announces_message(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let project = cx.update_editor(|editor, _| editor.project.clone().unwrap());
+
+    cx.set_state(indoc! {"
+        ˇfn func(abc def: i32) -> u32 {
+        }
+    "});
+
+    _ = cx.update(|cx| {
+        _ = project.update(cx, |project, cx| {
+            project
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                        version: None,
+                        diagnostics: vec![lsp::Diagnostic {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 12),
+                                lsp::Position::new(0, 15),
+                            ),
+                            severity: Some(lsp::DiagnosticSeverity::ERROR),
+                            message: "expected `,`".to_string(),
+                            ..Default::default()
+                        }],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap()
+        });
+    });
+
+    executor.run_until_parked();
+    cx.take_announcements();
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_diagnostic(&GoToDiagnostic, cx);
+    });
+
+    cx.assert_editor_state(indoc! {"
+        fn func(abc ˇdef: i32) -> u32 {
+        }
+    "});
+    assert_eq!(cx.take_announcements(), vec!["expected `,`".to_string()]);
+}
+
+#[gpui::test]
+async fn test_accessible_text_of_focused_editor(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        one
+        t«woˇ»
+        thrée
+    "});
+    cx.run_until_parked();
+    assert_eq!(
+        cx.accessible_text(),
+        Some(AccessibleText {
+            role: AccessibleRole::TextArea,
+            label: "file".into(),
+            value: "one\ntwo\nthrée\n".into(),
+            selected_range_utf16: 5..7,
+            insertion_line: 1,
+        })
+    );
+
+    // Offsets are reported in UTF-16 code units.
+    cx.set_state(indoc! {"
+        one
+        two
+        thréˇe
+    "});
+    cx.run_until_parked();
+    let accessible_text = cx.accessible_text().unwrap();
+    assert_eq!(accessible_text.selected_range_utf16, 12..12);
+    assert_eq!(accessible_text.insertion_line, 2);
+
+    // Nothing is reported once the editor loses focus.
+    cx.update_editor(|_, cx| cx.blur());
+    cx.run_until_parked();
+    assert_eq!(cx.accessible_text(), None);
+}
+
 #[gpui::test]
 async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7988,6 +8514,66 @@ async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_surround_selections(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+
+    // Selections are surrounded, and cursors surround the word they're in.
+    cx.set_state(indoc! {"
+        let a = «b + cˇ»;
+        let d = eˇf;
+    "});
+    cx.update_editor(|editor, cx| editor.surround_selections_with(")", cx));
+    cx.assert_editor_state(indoc! {"
+        let a = («b + cˇ»);
+        let d = («efˇ»);
+    "});
+
+    // Tags are closed with their name.
+    cx.update_editor(|editor, cx| editor.surround_selections_with("<b class=\"x\">", cx));
+    cx.assert_editor_state(indoc! {r#"
+        let a = (<b class="x">«b + cˇ»</b>);
+        let d = (<b class="x">«efˇ»</b>);
+    "#});
+
+    // Other text is used on both sides.
+    cx.set_state("«aˇ»");
+    cx.update_editor(|editor, cx| editor.surround_selections_with("**", cx));
+    cx.assert_editor_state("**«aˇ»**");
+}
+
+#[gpui::test]
+async fn test_change_and_delete_surrounding_pairs(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+
+    // The innermost pair around each cursor is changed.
+    cx.set_state(indoc! {r#"
+        let a = (b + [cˇ]);
+        let d = "eˇf";
+    "#});
+    cx.update_editor(|editor, cx| editor.replace_surrounding_pairs(None, "{", cx));
+    cx.assert_editor_state(indoc! {"
+        let a = (b + {cˇ});
+        let d = {eˇf};
+    "});
+
+    // Only pairs named by `from` are changed.
+    cx.set_state("let a = (b + [cˇ]);");
+    cx.update_editor(|editor, cx| editor.replace_surrounding_pairs(Some("("), "[", cx));
+    cx.assert_editor_state("let a = [b + [cˇ]];");
+
+    // Cursors in the same pair delete it once.
+    cx.set_state("let a = (bˇ + [cˇ] + dˇ);");
+    cx.update_editor(|editor, cx| {
+        editor.delete_surrounding_pair(&DeleteSurroundingPair { text: None }, cx)
+    });
+    cx.assert_editor_state("let a = bˇ + cˇ + dˇ;");
+}
+
 #[gpui::test]
 async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -1,37 +1,43 @@
 use crate::{
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
+    buffer_matches,
     display_map::{
         BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
         TransformBlock,
     },
-    editor_settings::{DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar},
+    editor_settings::{
+        DoubleClickInMultibuffer, MultiCursorModifier, ShowCursorNames, ShowMinimap, ShowScrollbar,
+    },
     git::{blame::GitBlame, diff_hunk_to_display, DisplayDiffHunk},
+    highlight_matching_bracket::{offscreen_matching_bracket, OffscreenMatchingBracket},
     hover_popover::{
         self, hover_at, HOVER_POPOVER_GAP, MIN_POPOVER_CHARACTER_WIDTH, MIN_POPOVER_LINE_HEIGHT,
     },
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
-    scroll::scroll_amount::ScrollAmount,
-    CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode,
-    EditorSettings, EditorSnapshot, EditorStyle, ExpandExcerpts, GutterDimensions, HalfPageDown,
-    HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point,
-    SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    scroll::{scroll_amount::ScrollAmount, Autoscroll},
+    CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, DraggedText, Editor,
+    EditorMode, EditorSettings, EditorSnapshot, EditorStyle, ExpandExcerpts, GutterDimensions,
+    HalfPageDown, HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp,
+    Point, SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
+use aho_corasick::AhoCorasick;
 use anyhow::Result;
+use client::ParticipantPresence;
 use collections::{BTreeMap, HashMap};
 use git::{blame::BlameEntry, diff::DiffHunkStatus, Oid};
 use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
-    transparent_black, Action, AnchorCorner, AnyElement, AvailableSpace, Bounds, ClipboardItem,
-    ContentMask, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementContext,
-    ElementInputHandler, Entity, Hitbox, Hsla, InteractiveElement, IntoElement,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
-    ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size, Stateful,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    transparent_black, AccessibleRole, AccessibleText, Action, AnchorCorner, AnyElement,
+    AvailableSpace, Bounds, ClipboardItem, ContentMask, Corners, CursorStyle, DispatchPhase, Edges,
+    Element, ElementContext, ElementInputHandler, Entity, Hitbox, Hsla, InteractiveElement,
+    IntoElement, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString,
+    Size, Stateful, StatefulInteractiveElement, Style, Styled, TextRun, TextStyle,
+    TextStyleRefinement, View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use language::language_settings::ShowWhitespaceSetting;
+use language::{is_invisible_control_character, language_settings::ShowWhitespaceSetting};
 use lsp::DiagnosticSeverity;
 use multi_buffer::Anchor;
 use project::{
@@ -64,6 +70,7 @@ struct SelectionLayout {
     range: Range<DisplayPoint>,
     active_rows: Range<u32>,
     user_name: Option<SharedString>,
+    user_name_opacity: f32,
 }
 
 impl SelectionLayout {
@@ -115,6 +122,7 @@ impl SelectionLayout {
             range,
             active_rows,
             user_name,
+            user_name_opacity: 1.,
         }
     }
 }
@@ -163,12 +171,14 @@ impl EditorElement {
         register_action(view, cx, Editor::join_lines);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
         register_action(view, cx, Editor::sort_lines_case_insensitive);
+        register_action(view, cx, Editor::sort_lines_numerically);
         register_action(view, cx, Editor::reverse_lines);
         register_action(view, cx, Editor::shuffle_lines);
         register_action(view, cx, Editor::convert_to_upper_case);
         register_action(view, cx, Editor::convert_to_lower_case);
         register_action(view, cx, Editor::convert_to_title_case);
         register_action(view, cx, Editor::convert_to_snake_case);
+        register_action(view, cx, Editor::convert_to_screaming_snake_case);
         register_action(view, cx, Editor::convert_to_kebab_case);
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
@@ -254,9 +264,17 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_comments);
         register_action(view, cx, Editor::select_larger_syntax_node);
         register_action(view, cx, Editor::select_smaller_syntax_node);
+        register_action(view, cx, Editor::shrink_selection_to_expansion_start);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
+        register_action(view, cx, Editor::surround_selections);
+        register_action(view, cx, Editor::change_surrounding_pair);
+        register_action(view, cx, Editor::delete_surrounding_pair);
+        register_action(view, cx, Editor::increment);
+        register_action(view, cx, Editor::decrement);
+        register_action(view, cx, Editor::align_selections);
         register_action(view, cx, Editor::undo_selection);
         register_action(view, cx, Editor::redo_selection);
+        register_action(view, cx, Editor::reselect_previous);
         if !view.read(cx).is_singleton(cx) {
             register_action(view, cx, Editor::expand_excerpts);
         }
@@ -273,6 +291,12 @@ impl EditorElement {
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_implementation(a, cx).detach_and_log_err(cx);
         });
+        register_action(view, cx, |editor, a, cx| {
+            editor.peek_definition(a, cx).detach_and_log_err(cx);
+        });
+        register_action(view, cx, |editor, a, cx| {
+            editor.peek_references(a, cx).detach_and_log_err(cx);
+        });
         register_action(view, cx, |editor, a, cx| {
             editor
                 .go_to_implementation_split(a, cx)
@@ -315,7 +339,15 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.add_missing_imports(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, Editor::restart_language_server);
+        register_action(view, cx, Editor::show_character_font);
         register_action(view, cx, Editor::show_character_palette);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.confirm_completion(action, cx) {
@@ -539,6 +571,16 @@ impl EditorElement {
         text_hitbox: &Hitbox,
         cx: &mut ViewContext<Editor>,
     ) {
+        if let Some(dragged_text) = cx.active_drag::<DraggedText>() {
+            if text_hitbox.is_hovered(cx) {
+                let text = dragged_text.text.clone();
+                let point = position_map.point_for_position(text_hitbox.bounds, event.position);
+                editor.insert_dropped_text(point.previous_valid, &text, cx);
+                cx.stop_propagation();
+            }
+            return;
+        }
+
         let end_selection = editor.has_pending_selection();
         let pending_nonempty_selections = editor.has_pending_nonempty_selection();
 
@@ -762,6 +804,7 @@ impl EditorElement {
                 }
             }
 
+            let show_cursor_names = EditorSettings::get_global(cx).show_cursor_names;
             let mut remote_selections = HashMap::default();
             for selection in snapshot.remote_selections_in_range(
                 &(start_anchor..end_anchor),
@@ -786,22 +829,45 @@ impl EditorElement {
                     selection_id: selection.selection.id,
                 };
 
-                let is_shown =
-                    editor.show_cursor_names || editor.hovered_cursors.contains_key(&key);
+                let name_opacity = match show_cursor_names {
+                    ShowCursorNames::Always => Some(1.),
+                    ShowCursorNames::Never => None,
+                    ShowCursorNames::Auto => {
+                        if editor.show_cursor_names || editor.hovered_cursors.contains_key(&key) {
+                            Some(1.)
+                        } else {
+                            editor.remote_cursor_name_opacity(selection.replica_id)
+                        }
+                    }
+                };
+                let (user_name, name_opacity) = match (selection.user_name, name_opacity) {
+                    (Some(name), Some(opacity)) if opacity > 0. => match selection.presence {
+                        ParticipantPresence::Active => (Some(name), opacity),
+                        ParticipantPresence::Idle => {
+                            (Some(format!("{name} (idle)").into()), opacity * 0.6)
+                        }
+                        ParticipantPresence::Away => {
+                            (Some(format!("{name} (away)").into()), opacity * 0.4)
+                        }
+                    },
+                    _ => (None, 0.),
+                };
 
+                let mut layout = SelectionLayout::new(
+                    selection.selection,
+                    selection.line_mode,
+                    selection.cursor_shape,
+                    &snapshot.display_snapshot,
+                    false,
+                    false,
+                    user_name,
+                );
+                layout.user_name_opacity = name_opacity;
                 remote_selections
                     .entry(selection.replica_id)
                     .or_insert((selection_style, Vec::new()))
                     .1
-                    .push(SelectionLayout::new(
-                        selection.selection,
-                        selection.line_mode,
-                        selection.cursor_shape,
-                        &snapshot.display_snapshot,
-                        false,
-                        false,
-                        if is_shown { selection.user_name } else { None },
-                    ));
+                    .push(layout);
             }
 
             selections.extend(remote_selections.into_values());
@@ -991,6 +1057,7 @@ impl EditorElement {
                     let cursor_name = selection.user_name.clone().map(|name| CursorName {
                         string: name,
                         color: self.style.background,
+                        opacity: selection.user_name_opacity,
                         is_top_row: cursor_position.row() == 0,
                     });
                     cx.with_element_context(|cx| cursor.layout(content_origin, cursor_name, cx));
@@ -1073,6 +1140,201 @@ impl EditorElement {
         })
     }
 
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        scroll_position: gpui::Point<f32>,
+        rows_per_page: f32,
+        cx: &mut ElementContext,
+    ) -> Option<MinimapLayout> {
+        if self.minimap_width(snapshot, cx) == Pixels::ZERO {
+            return None;
+        }
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+
+        let minimap_right = self.scrollbar_left(&bounds);
+        let minimap_bounds = Bounds::from_corners(
+            point(minimap_right - px(minimap_settings.width), bounds.origin.y),
+            point(minimap_right, bounds.lower_left().y),
+        );
+
+        // Scroll the minimap proportionally to the editor, so that both of them
+        // reach the end of the buffer at the same time.
+        let max_row = snapshot.max_point().row();
+        let total_rows = max_row + 1;
+        let minimap_rows = (minimap_bounds.size.height / MinimapLayout::ROW_HEIGHT).floor() as u32;
+        let scroll_fraction = if max_row == 0 {
+            0.
+        } else {
+            (scroll_position.y / max_row as f32).clamp(0., 1.)
+        };
+        let start_row =
+            (total_rows.saturating_sub(minimap_rows) as f32 * scroll_fraction).round() as u32;
+        let end_row = cmp::min(start_row + minimap_rows, total_rows);
+
+        let max_column = (minimap_bounds.size.width / MinimapLayout::COLUMN_WIDTH).floor() as u32;
+        let mut line_quads = Vec::new();
+        let mut row = start_row;
+        let mut column = 0;
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            let color = Hsla {
+                a: color.a * 0.6,
+                ..color
+            };
+            for (ix, line) in chunk.chunk.split('\n').enumerate() {
+                if ix > 0 {
+                    row += 1;
+                    column = 0;
+                }
+
+                let mut run_start = None;
+                for character in line.chars().chain(iter::once(' ')) {
+                    if character.is_whitespace() {
+                        if let Some(run_start) = run_start.take() {
+                            line_quads.extend(MinimapLayout::text_quad(
+                                row - start_row,
+                                run_start..column,
+                                max_column,
+                                color,
+                            ));
+                        }
+                    } else if run_start.is_none() {
+                        run_start = Some(column);
+                    }
+                    column += 1;
+                }
+                // Account for the trailing space chained above.
+                column -= 1;
+            }
+        }
+
+        let mut markers = Vec::new();
+        let buffer_start = DisplayPoint::new(start_row, 0).to_point(snapshot);
+        let buffer_end = DisplayPoint::new(end_row, 0).to_point(snapshot);
+        if minimap_settings.git_diff {
+            markers.extend(
+                snapshot
+                    .buffer_snapshot
+                    .git_diff_hunks_in_range(buffer_start.row..buffer_end.row + 1)
+                    .map(|hunk| {
+                        let start = Point::new(hunk.associated_range.start, 0)
+                            .to_display_point(snapshot)
+                            .row();
+                        let end = Point::new(hunk.associated_range.end, 0)
+                            .to_display_point(snapshot)
+                            .row();
+                        let color = match hunk.status() {
+                            DiffHunkStatus::Added => cx.theme().status().created,
+                            DiffHunkStatus::Modified => cx.theme().status().modified,
+                            DiffHunkStatus::Removed => cx.theme().status().deleted,
+                        };
+                        (
+                            MinimapMarkerColumn::Left,
+                            ColoredRange {
+                                start,
+                                end: cmp::max(start + 1, end),
+                                color,
+                            },
+                        )
+                    }),
+            );
+        }
+        if minimap_settings.search_results {
+            let editor = self.editor.read(cx);
+            if let Some((_, ranges)) = editor
+                .background_highlights
+                .get(&TypeId::of::<BufferSearchHighlights>())
+            {
+                let window_start = snapshot.buffer_snapshot.anchor_before(buffer_start);
+                let window_end = snapshot.buffer_snapshot.anchor_after(buffer_end);
+                markers.extend(
+                    ranges
+                        .iter()
+                        .filter(|range| {
+                            range
+                                .end
+                                .cmp(&window_start, &snapshot.buffer_snapshot)
+                                .is_ge()
+                                && range
+                                    .start
+                                    .cmp(&window_end, &snapshot.buffer_snapshot)
+                                    .is_le()
+                        })
+                        .map(|range| {
+                            let start = range.start.to_display_point(snapshot).row();
+                            let end = range.end.to_display_point(snapshot).row();
+                            (
+                                MinimapMarkerColumn::Right,
+                                ColoredRange {
+                                    start,
+                                    end: end + 1,
+                                    color: cx.theme().status().info,
+                                },
+                            )
+                        }),
+                );
+            }
+        }
+        if minimap_settings.diagnostics {
+            markers.extend(
+                snapshot
+                    .buffer_snapshot
+                    .diagnostics_in_range::<_, Point>(buffer_start..buffer_end, false)
+                    // Paint the most severe diagnostics last.
+                    .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity))
+                    .map(|diagnostic| {
+                        let start = diagnostic.range.start.to_display_point(snapshot).row();
+                        let end = diagnostic.range.end.to_display_point(snapshot).row();
+                        let color = match diagnostic.diagnostic.severity {
+                            DiagnosticSeverity::ERROR => cx.theme().status().error,
+                            DiagnosticSeverity::WARNING => cx.theme().status().warning,
+                            DiagnosticSeverity::INFORMATION => cx.theme().status().info,
+                            _ => cx.theme().status().hint,
+                        };
+                        (
+                            MinimapMarkerColumn::Right,
+                            ColoredRange {
+                                start,
+                                end: end + 1,
+                                color,
+                            },
+                        )
+                    }),
+            );
+        }
+        let marker_quads = markers
+            .into_iter()
+            .filter_map(|(column, range)| {
+                MinimapLayout::marker_quad(column, range, start_row..end_row, minimap_bounds.size)
+            })
+            .collect();
+
+        let viewport_bounds = Bounds {
+            origin: point(
+                Pixels::ZERO,
+                (scroll_position.y - start_row as f32) * MinimapLayout::ROW_HEIGHT,
+            ),
+            size: size(
+                minimap_bounds.size.width,
+                rows_per_page * MinimapLayout::ROW_HEIGHT,
+            ),
+        };
+
+        Some(MinimapLayout {
+            hitbox: cx.insert_hitbox(minimap_bounds, false),
+            start_row,
+            rows_per_page,
+            line_quads,
+            marker_quads,
+            viewport_bounds,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_gutter_fold_indicators(
         &self,
@@ -1321,6 +1583,82 @@ impl EditorElement {
         Some(button)
     }
 
+    fn layout_offscreen_bracket_hint(
+        &self,
+        snapshot: &EditorSnapshot,
+        visible_rows: Range<u32>,
+        line_height: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut ElementContext,
+    ) -> Option<AnyElement> {
+        let OffscreenMatchingBracket { position, above } =
+            offscreen_matching_bracket(self.editor.read(cx), snapshot, visible_rows)?;
+
+        let bracket_point = position.to_point(&snapshot.display_snapshot);
+        let line_text = snapshot
+            .buffer_snapshot
+            .text_for_range(
+                Point::new(bracket_point.row, 0)
+                    ..Point::new(
+                        bracket_point.row,
+                        snapshot.buffer_snapshot.line_len(bracket_point.row),
+                    ),
+            )
+            .collect::<String>();
+        let line_text = SharedString::from(line_text.trim().to_string());
+        let bracket_offset = position.to_offset(&snapshot.display_snapshot, Bias::Left);
+
+        let editor = self.editor.clone();
+        let mut hint = ButtonLike::new("offscreen-matching-bracket")
+            .style(ButtonStyle::Transparent)
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        Icon::new(if above {
+                            IconName::ArrowUp
+                        } else {
+                            IconName::ArrowDown
+                        })
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new((bracket_point.row + 1).to_string())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    ),
+            )
+            .tooltip(move |cx| Tooltip::text(line_text.clone(), cx))
+            .on_click(move |_, cx| {
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([bracket_offset..bracket_offset])
+                    });
+                });
+            })
+            .into_any_element();
+
+        let available_space = size(
+            AvailableSpace::MinContent,
+            AvailableSpace::Definite(line_height),
+        );
+        let hint_size = hint.layout_as_root(available_space, cx);
+
+        let x = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or(Pixels::ZERO);
+        let y = if above {
+            Pixels::ZERO
+        } else {
+            gutter_hitbox.size.height - line_height
+        } + (line_height - hint_size.height) / 2.;
+
+        hint.prepaint_as_root(gutter_hitbox.origin + point(x, y), available_space, cx);
+        Some(hint)
+    }
+
     fn calculate_relative_line_numbers(
         &self,
         buffer_rows: Vec<Option<u32>>,
@@ -1394,7 +1732,7 @@ impl EditorElement {
         });
         let font_size = self.style.text.font_size.to_pixels(cx.rem_size());
         let include_line_numbers =
-            EditorSettings::get_global(cx).gutter.line_numbers && snapshot.mode == EditorMode::Full;
+            snapshot.show_line_numbers(cx) && snapshot.mode == EditorMode::Full;
         let include_fold_statuses =
             EditorSettings::get_global(cx).gutter.folds && snapshot.mode == EditorMode::Full;
         let mut shaped_line_numbers = Vec::with_capacity(rows.len());
@@ -2233,6 +2571,10 @@ impl EditorElement {
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
+
+            if let Some(hint) = layout.offscreen_bracket_hint.as_mut() {
+                hint.paint(cx);
+            }
         })
     }
 
@@ -2481,6 +2823,40 @@ impl EditorElement {
         }
     }
 
+    /// Shows where text that's being dragged over the editor will be inserted if it's dropped.
+    fn paint_drop_indicator(&mut self, layout: &EditorLayout, cx: &mut ElementContext) {
+        if cx.active_drag::<DraggedText>().is_none() || !layout.text_hitbox.is_hovered(cx) {
+            return;
+        }
+        if self.editor.read(cx).read_only(cx) {
+            return;
+        }
+
+        let position_map = &layout.position_map;
+        let position = position_map
+            .point_for_position(layout.text_hitbox.bounds, cx.mouse_position())
+            .previous_valid;
+        let Some(line) = position
+            .row()
+            .checked_sub(layout.visible_display_row_range.start)
+            .and_then(|ix| position_map.line_layouts.get(ix as usize))
+        else {
+            return;
+        };
+
+        let x = line.line.x_for_index(position.column() as usize)
+            - position_map.scroll_pixel_position.x;
+        let y =
+            position.row() as f32 * position_map.line_height - position_map.scroll_pixel_position.y;
+        cx.paint_quad(fill(
+            Bounds::new(
+                layout.content_origin + point(x, y),
+                size(px(2.), position_map.line_height),
+            ),
+            cx.theme().players().local().cursor,
+        ));
+    }
+
     fn paint_scrollbar(&mut self, layout: &mut EditorLayout, cx: &mut ElementContext) {
         let Some(scrollbar_layout) = layout.scrollbar_layout.as_ref() else {
             return;
@@ -2592,10 +2968,26 @@ impl EditorElement {
                     }
 
                     editor.update(cx, |editor, cx| {
-                        editor.scroll_manager.set_is_dragging_scrollbar(true, cx);
-
                         let y = event.position.y;
-                        if y < thumb_bounds.top() || thumb_bounds.bottom() < y {
+                        let is_outside_thumb = y < thumb_bounds.top() || thumb_bounds.bottom() < y;
+
+                        // Clicking a mark moves the cursor to the line that it marks.
+                        if is_outside_thumb {
+                            let markers = &editor.scrollbar_marker_state.markers;
+                            if let Some(row) =
+                                marker_row_at_y(markers, y - hitbox.top(), row_height)
+                            {
+                                let point = DisplayPoint::new(row, 0);
+                                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                    s.select_display_ranges([point..point])
+                                });
+                                cx.stop_propagation();
+                                return;
+                            }
+                        }
+
+                        editor.scroll_manager.set_is_dragging_scrollbar(true, cx);
+                        if is_outside_thumb {
                             let center_row = ((y - hitbox.top()) / row_height).round() as u32;
                             let top_row = center_row
                                 .saturating_sub((row_range.end - row_range.start) as u32 / 2);
@@ -2613,6 +3005,116 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut ElementContext) {
+        let Some(minimap_layout) = layout.minimap_layout.as_ref() else {
+            return;
+        };
+
+        let origin = minimap_layout.hitbox.origin;
+        cx.paint_layer(minimap_layout.hitbox.bounds, |cx| {
+            cx.paint_quad(fill(
+                minimap_layout.hitbox.bounds,
+                cx.theme().colors().editor_background,
+            ));
+
+            for line_quad in &minimap_layout.line_quads {
+                let mut line_quad = line_quad.clone();
+                line_quad.bounds.origin += origin;
+                cx.paint_quad(line_quad);
+            }
+
+            let mut viewport_bounds = minimap_layout.viewport_bounds;
+            viewport_bounds.origin += origin;
+            cx.paint_quad(quad(
+                viewport_bounds,
+                Corners::default(),
+                theme::color_alpha(cx.theme().colors().scrollbar_thumb_background, 0.3),
+                Edges::all(ScrollbarLayout::BORDER_WIDTH),
+                cx.theme().colors().scrollbar_thumb_border,
+            ));
+
+            for marker_quad in &minimap_layout.marker_quads {
+                let mut marker_quad = marker_quad.clone();
+                marker_quad.bounds.origin += origin;
+                cx.paint_quad(marker_quad);
+            }
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap_layout.hitbox);
+
+        let start_row = minimap_layout.start_row;
+        let rows_per_page = minimap_layout.rows_per_page;
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = minimap_layout.hitbox.clone();
+            move |event: &MouseDownEvent, phase, cx| {
+                if phase == DispatchPhase::Capture
+                    || event.button != MouseButton::Left
+                    || !hitbox.is_hovered(cx)
+                {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    editor.scroll_manager.set_is_dragging_minimap(true, cx);
+                    let mut position = editor.scroll_position(cx);
+                    position.y = MinimapLayout::scroll_top_for_y(
+                        event.position.y - origin.y,
+                        start_row,
+                        rows_per_page,
+                    );
+                    editor.set_scroll_position(position, cx);
+                });
+                cx.stop_propagation();
+            }
+        });
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if !editor.scroll_manager.is_dragging_minimap() {
+                        return;
+                    }
+
+                    if event.pressed_button == Some(MouseButton::Left) {
+                        let mut position = editor.scroll_position(cx);
+                        position.y = MinimapLayout::scroll_top_for_y(
+                            event.position.y - origin.y,
+                            start_row,
+                            rows_per_page,
+                        );
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                    }
+                });
+            }
+        });
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn refresh_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -2634,6 +3136,15 @@ impl EditorElement {
             let theme = cx.theme().clone();
             let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
             let max_row = layout.max_row;
+            let selection_query = if scrollbar_settings.selection_matches {
+                editor
+                    .scrollbar_marker_state
+                    .selected_text
+                    .as_deref()
+                    .and_then(|text| AhoCorasick::new(&[text]).log_err())
+            } else {
+                None
+            };
 
             editor.scrollbar_marker_state.dirty = false;
             editor.scrollbar_marker_state.pending_refresh =
@@ -2710,6 +3221,30 @@ impl EditorElement {
                                 }
                             }
 
+                            if let Some(query) = selection_query {
+                                // Selecting a short, common word in a large file
+                                // would otherwise mark nearly every row.
+                                const MAX_SELECTION_MATCHES: usize = 1000;
+
+                                let marker_row_ranges =
+                                    buffer_matches(&query, &snapshot.buffer_snapshot)
+                                        .take(MAX_SELECTION_MATCHES)
+                                        .map(|range| ColoredRange {
+                                            start: range
+                                                .start
+                                                .to_display_point(&snapshot.display_snapshot)
+                                                .row(),
+                                            end: range
+                                                .end
+                                                .to_display_point(&snapshot.display_snapshot)
+                                                .row(),
+                                            color: theme.players().local().cursor,
+                                        });
+                                marker_quads.extend(
+                                    scrollbar_layout.marker_quads_for_ranges(marker_row_ranges, 1),
+                                );
+                            }
+
                             if scrollbar_settings.diagnostics {
                                 let max_point =
                                     snapshot.display_snapshot.buffer_snapshot.max_point();
@@ -2720,6 +3255,11 @@ impl EditorElement {
                                         Point::zero()..max_point,
                                         false,
                                     )
+                                    .filter(|diagnostic| {
+                                        scrollbar_settings
+                                            .diagnostics_severity
+                                            .includes(diagnostic.diagnostic.severity)
+                                    })
                                     // We want to sort by severity, in order to paint the most severe diagnostics last.
                                     .sorted_by_key(|diagnostic| {
                                         std::cmp::Reverse(diagnostic.diagnostic.severity)
@@ -3005,6 +3545,26 @@ impl EditorElement {
         });
     }
 
+    /// Returns the horizontal space taken away from the text by the minimap,
+    /// which also includes the scrollbar to its right.
+    fn minimap_width(&self, snapshot: &EditorSnapshot, cx: &WindowContext) -> Pixels {
+        if snapshot.mode != EditorMode::Full {
+            return Pixels::ZERO;
+        }
+
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let show_minimap = match minimap_settings.show {
+            ShowMinimap::Always => true,
+            ShowMinimap::Singleton => self.editor.read(cx).is_singleton(cx),
+            ShowMinimap::Never => false,
+        };
+        if show_minimap {
+            px(minimap_settings.width) + self.style.scrollbar_width
+        } else {
+            Pixels::ZERO
+        }
+    }
+
     fn scrollbar_left(&self, bounds: &Bounds<Pixels>) -> Pixels {
         bounds.upper_right().x - self.style.scrollbar_width
     }
@@ -3035,6 +3595,52 @@ impl EditorElement {
         let digit_count = (snapshot.max_buffer_row() as f32 + 1.).log10().floor() as usize + 1;
         self.column_pixels(digit_count, cx)
     }
+
+    /// Describes the visible text and the newest selection for assistive technologies.
+    fn accessible_text(&self, layout: &EditorLayout, cx: &mut ElementContext) -> AccessibleText {
+        let snapshot = &layout.position_map.snapshot;
+        let buffer = &snapshot.buffer_snapshot;
+        let visible_start = DisplayPoint::new(layout.visible_display_row_range.start, 0)
+            .to_point(&snapshot.display_snapshot);
+        let visible_end = snapshot
+            .clip_point(
+                DisplayPoint::new(layout.visible_display_row_range.end, 0),
+                Bias::Left,
+            )
+            .to_point(&snapshot.display_snapshot);
+        let visible_range = buffer.point_to_offset(Point::new(visible_start.row, 0))
+            ..buffer.point_to_offset(visible_end);
+
+        let editor = self.editor.read(cx);
+        let selection = editor.selections.newest::<usize>(cx);
+        let visible_start_utf16 = buffer.offset_to_offset_utf16(visible_range.start);
+        let to_visible_utf16 = |offset: usize| {
+            let offset = offset.clamp(visible_range.start, visible_range.end);
+            (buffer.offset_to_offset_utf16(offset) - visible_start_utf16).0
+        };
+        let cursor_row = buffer.offset_to_point(selection.head()).row;
+
+        AccessibleText {
+            role: match layout.mode {
+                EditorMode::SingleLine => AccessibleRole::TextField,
+                EditorMode::AutoHeight { .. } | EditorMode::Full => AccessibleRole::TextArea,
+            },
+            label: match layout.mode {
+                EditorMode::SingleLine => snapshot
+                    .placeholder_text
+                    .as_deref()
+                    .map(|placeholder| SharedString::from(placeholder.to_string()))
+                    .unwrap_or_default(),
+                EditorMode::AutoHeight { .. } | EditorMode::Full => {
+                    editor.title(cx).to_string().into()
+                }
+            },
+            value: buffer.text_for_range(visible_range.clone()).collect(),
+            selected_range_utf16: to_visible_utf16(selection.start)
+                ..to_visible_utf16(selection.end),
+            insertion_line: cursor_row.saturating_sub(visible_start.row) as usize,
+        }
+    }
 }
 
 fn render_inline_blame_entry(
@@ -3254,20 +3860,26 @@ impl LineWithInvisibles {
                                 });
                             }
                         } else {
-                            invisibles.extend(
-                                line_chunk
-                                    .chars()
-                                    .enumerate()
-                                    .filter(|(_, line_char)| {
-                                        let is_whitespace = line_char.is_whitespace();
-                                        non_whitespace_added |= !is_whitespace;
-                                        is_whitespace
-                                            && (non_whitespace_added || !inside_wrapped_string)
-                                    })
-                                    .map(|(whitespace_index, _)| Invisible::Whitespace {
-                                        line_offset: line.len() + whitespace_index,
-                                    }),
-                            )
+                            for (char_ix, line_char) in line_chunk.char_indices() {
+                                let line_offset = line.len() + char_ix;
+                                if is_invisible_control_character(line_char) {
+                                    invisibles.push(Invisible::ControlCharacter { line_offset });
+                                    continue;
+                                }
+
+                                let is_whitespace = line_char.is_whitespace();
+                                non_whitespace_added |= !is_whitespace;
+                                if is_whitespace && (non_whitespace_added || !inside_wrapped_string)
+                                {
+                                    let is_non_breaking =
+                                        matches!(line_char, '\u{00A0}' | '\u{2007}' | '\u{202F}');
+                                    invisibles.push(if is_non_breaking {
+                                        Invisible::NonBreakingSpace { line_offset }
+                                    } else {
+                                        Invisible::Whitespace { line_offset }
+                                    });
+                                }
+                            }
                         }
                     }
 
@@ -3320,17 +3932,47 @@ impl LineWithInvisibles {
         whitespace_setting: ShowWhitespaceSetting,
         cx: &mut ElementContext,
     ) {
-        let allowed_invisibles_regions = match whitespace_setting {
-            ShowWhitespaceSetting::None => return,
-            ShowWhitespaceSetting::Selection => Some(selection_ranges),
-            ShowWhitespaceSetting::All => None,
-        };
-
         for invisible in &self.invisibles {
             let (&token_offset, invisible_symbol) = match invisible {
                 Invisible::Tab { line_start_offset } => (line_start_offset, &layout.tab_invisible),
                 Invisible::Whitespace { line_offset } => (line_offset, &layout.space_invisible),
+                Invisible::NonBreakingSpace { line_offset } => {
+                    (line_offset, &layout.non_breaking_space_invisible)
+                }
+                // These take up no space, so they're marked with a bar wherever
+                // they are, whatever the whitespace setting.
+                Invisible::ControlCharacter { line_offset } => {
+                    let x_offset = self.line.x_for_index(*line_offset)
+                        - layout.position_map.scroll_pixel_position.x;
+                    let origin = content_origin + gpui::point(x_offset, line_y);
+                    cx.paint_quad(fill(
+                        Bounds {
+                            origin,
+                            size: size(px(2.), line_height),
+                        },
+                        cx.theme().status().error,
+                    ));
+                    continue;
+                }
+            };
+
+            let is_visible = match whitespace_setting {
+                ShowWhitespaceSetting::None => false,
+                ShowWhitespaceSetting::All => true,
+                ShowWhitespaceSetting::Selection => {
+                    let invisible_point = DisplayPoint::new(row, token_offset as u32);
+                    selection_ranges.iter().any(|region| {
+                        region.start <= invisible_point && invisible_point < region.end
+                    })
+                }
+                ShowWhitespaceSetting::Boundary => match invisible {
+                    Invisible::Whitespace { .. } => !self.is_space_between_words(token_offset),
+                    _ => true,
+                },
             };
+            if !is_visible {
+                continue;
+            }
 
             let x_offset = self.line.x_for_index(token_offset);
             let invisible_offset =
@@ -3340,25 +3982,36 @@ impl LineWithInvisibles {
                     x_offset + invisible_offset - layout.position_map.scroll_pixel_position.x,
                     line_y,
                 );
-
-            if let Some(allowed_regions) = allowed_invisibles_regions {
-                let invisible_point = DisplayPoint::new(row, token_offset as u32);
-                if !allowed_regions
-                    .iter()
-                    .any(|region| region.start <= invisible_point && invisible_point < region.end)
-                {
-                    continue;
-                }
-            }
             invisible_symbol.paint(origin, line_height, cx).log_err();
         }
     }
+
+    /// Whether the whitespace at the given offset is a single space with words on
+    /// both sides, which isn't drawn with [`ShowWhitespaceSetting::Boundary`].
+    fn is_space_between_words(&self, line_offset: usize) -> bool {
+        let text: &str = self.line.text.as_ref();
+        let is_word_boundary = |c: Option<char>| c.map_or(false, |c| !c.is_whitespace());
+        text[line_offset..].starts_with(' ')
+            && is_word_boundary(text[..line_offset].chars().next_back())
+            && is_word_boundary(text[line_offset + 1..].chars().next())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Invisible {
-    Tab { line_start_offset: usize },
-    Whitespace { line_offset: usize },
+    Tab {
+        line_start_offset: usize,
+    },
+    Whitespace {
+        line_offset: usize,
+    },
+    NonBreakingSpace {
+        line_offset: usize,
+    },
+    /// A zero-width or bidirectional control character, which is always shown.
+    ControlCharacter {
+        line_offset: usize,
+    },
 }
 
 impl Element for EditorElement {
@@ -3450,7 +4103,8 @@ impl Element for EditorElement {
                     self.max_line_number_width(&snapshot, cx),
                     cx,
                 );
-                let text_width = bounds.size.width - gutter_dimensions.width;
+                let text_width =
+                    bounds.size.width - gutter_dimensions.width - self.minimap_width(&snapshot, cx);
                 let overscroll = size(em_width, px(0.));
 
                 snapshot = self.editor.update(cx, |editor, cx| {
@@ -3689,6 +4343,8 @@ impl Element for EditorElement {
 
                 let scrollbar_layout =
                     self.layout_scrollbar(&snapshot, bounds, scroll_position, height_in_lines, cx);
+                let minimap_layout =
+                    self.layout_minimap(&snapshot, bounds, scroll_position, height_in_lines, cx);
 
                 let folds = cx.with_element_id(Some("folds"), |cx| {
                     self.layout_folds(
@@ -3750,6 +4406,19 @@ impl Element for EditorElement {
 
                 let mouse_context_menu = self.layout_mouse_context_menu(cx);
 
+                let offscreen_bracket_hint = if snapshot.mode == EditorMode::Full {
+                    self.layout_offscreen_bracket_hint(
+                        &snapshot,
+                        start_row..end_row,
+                        line_height,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        cx,
+                    )
+                } else {
+                    None
+                };
+
                 let fold_indicators = if gutter_settings.folds {
                     cx.with_element_id(Some("gutter_fold_indicators"), |cx| {
                         self.layout_gutter_fold_indicators(
@@ -3797,6 +4466,23 @@ impl Element for EditorElement {
                         }],
                     )
                     .unwrap();
+                // Non-breaking spaces look like regular ones but behave differently,
+                // so they stand out from the other invisibles.
+                let non_breaking_space_invisible = cx
+                    .text_system()
+                    .shape_line(
+                        "␣".into(),
+                        invisible_symbol_font_size,
+                        &[TextRun {
+                            len: "␣".len(),
+                            font: self.style.text.font(),
+                            color: cx.theme().status().warning,
+                            background_color: None,
+                            underline: None,
+                            strikethrough: None,
+                        }],
+                    )
+                    .unwrap();
 
                 EditorLayout {
                     mode: snapshot.mode,
@@ -3818,6 +4504,7 @@ impl Element for EditorElement {
                     gutter_dimensions,
                     content_origin,
                     scrollbar_layout,
+                    minimap_layout,
                     max_row,
                     active_rows,
                     highlighted_rows,
@@ -3833,9 +4520,11 @@ impl Element for EditorElement {
                     selections,
                     mouse_context_menu,
                     code_actions_indicator,
+                    offscreen_bracket_hint,
                     fold_indicators,
                     tab_invisible,
                     space_invisible,
+                    non_breaking_space_invisible,
                 }
             })
         })
@@ -3857,6 +4546,10 @@ impl Element for EditorElement {
             &focus_handle,
             ElementInputHandler::new(bounds, self.editor.clone()),
         );
+        if focus_handle.is_focused(cx) {
+            let accessible_text = self.accessible_text(layout, cx);
+            cx.set_accessible_text(&focus_handle, accessible_text);
+        }
         self.register_actions(cx);
         self.register_key_listeners(cx, layout);
 
@@ -3874,6 +4567,7 @@ impl Element for EditorElement {
                     self.paint_gutter(layout, cx);
                 }
                 self.paint_text(layout, cx);
+                self.paint_drop_indicator(layout, cx);
 
                 if !layout.blocks.is_empty() {
                     cx.with_element_id(Some("blocks"), |cx| {
@@ -3881,6 +4575,7 @@ impl Element for EditorElement {
                     });
                 }
 
+                self.paint_minimap(layout, cx);
                 self.paint_scrollbar(layout, cx);
                 self.paint_mouse_context_menu(layout, cx);
             });
@@ -3906,6 +4601,7 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    minimap_layout: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     visible_display_row_range: Range<u32>,
@@ -3923,10 +4619,12 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     max_row: u32,
     code_actions_indicator: Option<AnyElement>,
+    offscreen_bracket_hint: Option<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
+    non_breaking_space_invisible: ShapedLine,
 }
 
 impl EditorLayout {
@@ -4025,6 +4723,94 @@ impl ScrollbarLayout {
     }
 }
 
+/// Returns the display row marked by the scrollbar marker at the given height,
+/// relative to the top of the scrollbar, preferring the markers painted last.
+fn marker_row_at_y(markers: &[PaintQuad], y: Pixels, row_height: Pixels) -> Option<u32> {
+    markers
+        .iter()
+        .rev()
+        .find(|marker| marker.bounds.top() <= y && y <= marker.bounds.bottom())
+        .map(|marker| (marker.bounds.top() / row_height).round() as u32)
+}
+
+struct MinimapLayout {
+    hitbox: Hitbox,
+    start_row: u32,
+    rows_per_page: f32,
+    /// Quads for the text, relative to the origin of the minimap.
+    line_quads: Vec<PaintQuad>,
+    /// Quads for the markers, relative to the origin of the minimap.
+    marker_quads: Vec<PaintQuad>,
+    /// Bounds of the visible part of the editor, relative to the origin of the minimap.
+    viewport_bounds: Bounds<Pixels>,
+}
+
+#[derive(Clone, Copy)]
+enum MinimapMarkerColumn {
+    Left,
+    Right,
+}
+
+impl MinimapLayout {
+    const ROW_HEIGHT: Pixels = px(3.);
+    const GLYPH_HEIGHT: Pixels = px(2.);
+    const COLUMN_WIDTH: Pixels = px(1.5);
+    const MARKER_WIDTH: Pixels = px(3.);
+
+    /// Returns the scroll position that centers the editor on the row at the
+    /// given vertical offset from the top of the minimap.
+    fn scroll_top_for_y(y: Pixels, start_row: u32, rows_per_page: f32) -> f32 {
+        let row = start_row as f32 + y / Self::ROW_HEIGHT;
+        (row - rows_per_page / 2.).max(0.)
+    }
+
+    fn text_quad(row: u32, columns: Range<u32>, max_column: u32, color: Hsla) -> Option<PaintQuad> {
+        let end_column = cmp::min(columns.end, max_column);
+        if columns.start >= end_column {
+            return None;
+        }
+
+        Some(fill(
+            Bounds {
+                origin: point(
+                    columns.start as f32 * Self::COLUMN_WIDTH,
+                    row as f32 * Self::ROW_HEIGHT,
+                ),
+                size: size(
+                    (end_column - columns.start) as f32 * Self::COLUMN_WIDTH,
+                    Self::GLYPH_HEIGHT,
+                ),
+            },
+            color,
+        ))
+    }
+
+    fn marker_quad(
+        column: MinimapMarkerColumn,
+        range: ColoredRange<u32>,
+        visible_rows: Range<u32>,
+        minimap_size: Size<Pixels>,
+    ) -> Option<PaintQuad> {
+        let start = cmp::max(range.start, visible_rows.start);
+        let end = cmp::min(range.end, visible_rows.end);
+        if start >= end {
+            return None;
+        }
+
+        let x = match column {
+            MinimapMarkerColumn::Left => Pixels::ZERO,
+            MinimapMarkerColumn::Right => minimap_size.width - Self::MARKER_WIDTH,
+        };
+        Some(fill(
+            Bounds {
+                origin: point(x, (start - visible_rows.start) as f32 * Self::ROW_HEIGHT),
+                size: size(Self::MARKER_WIDTH, (end - start) as f32 * Self::ROW_HEIGHT),
+            },
+            range.color,
+        ))
+    }
+}
+
 struct FoldLayout {
     display_range: Range<DisplayPoint>,
     hover_element: AnyElement,
@@ -4152,6 +4938,7 @@ pub struct CursorLayout {
 pub struct CursorName {
     string: SharedString,
     color: Hsla,
+    opacity: f32,
     is_top_row: bool,
 }
 
@@ -4216,12 +5003,16 @@ impl CursorLayout {
             } else {
                 point(bounds.left(), bounds.top() - text_size / 2. - px(1.))
             };
+            let mut background = self.color;
+            background.fade_out(1. - cursor_name.opacity);
+            let mut text_color = cursor_name.color;
+            text_color.fade_out(1. - cursor_name.opacity);
             let mut name_element = div()
-                .bg(self.color)
+                .bg(background)
                 .text_size(text_size)
                 .px_0p5()
                 .line_height(text_size + px(2.))
-                .text_color(cursor_name.color)
+                .text_color(text_color)
                 .child(cursor_name.string.clone())
                 .into_any_element();
 
@@ -4733,6 +5524,28 @@ mod tests {
         assert_eq!(expected_invisibles, actual_invisibles);
     }
 
+    #[gpui::test]
+    fn test_non_breaking_space_and_control_character_invisibles(cx: &mut TestAppContext) {
+        init_test(cx, |s| {
+            s.defaults.show_whitespaces = Some(ShowWhitespaceSetting::None);
+        });
+
+        let actual_invisibles = collect_invisibles_from_new_editor(
+            cx,
+            EditorMode::Full,
+            "a\u{00A0}b\u{200B}c d",
+            px(500.0),
+        );
+        assert_eq!(
+            actual_invisibles,
+            vec![
+                Invisible::NonBreakingSpace { line_offset: 1 },
+                Invisible::ControlCharacter { line_offset: 4 },
+                Invisible::Whitespace { line_offset: 8 },
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_invisibles_dont_appear_in_certain_editors(cx: &mut TestAppContext) {
         init_test(cx, |s| {
@@ -4881,6 +5694,86 @@ mod tests {
             .cloned()
             .collect()
     }
+
+    #[test]
+    fn test_marker_row_at_y() {
+        let marker = |top: f32, bottom: f32, color: Hsla| {
+            quad(
+                Bounds::from_corners(point(px(1.), px(top)), point(px(4.), px(bottom))),
+                Corners::default(),
+                color,
+                Edges::default(),
+                Hsla::transparent_black(),
+            )
+        };
+        let row_height = px(0.5);
+        let markers = [
+            marker(5., 7., gpui::red()),
+            marker(6., 8., gpui::blue()),
+            marker(20., 22., gpui::red()),
+        ];
+
+        assert_eq!(marker_row_at_y(&markers, px(4.), row_height), None);
+        assert_eq!(marker_row_at_y(&markers, px(5.5), row_height), Some(10));
+        // Overlapping markers resolve to the one painted last.
+        assert_eq!(marker_row_at_y(&markers, px(6.5), row_height), Some(12));
+        assert_eq!(marker_row_at_y(&markers, px(21.), row_height), Some(40));
+        assert_eq!(marker_row_at_y(&markers, px(30.), row_height), None);
+    }
+
+    #[test]
+    fn test_minimap_scroll_top_for_y() {
+        // Each row of the minimap is 3px high, and the clicked row is centered.
+        assert_eq!(MinimapLayout::scroll_top_for_y(px(30.), 100, 20.), 100.);
+        assert_eq!(MinimapLayout::scroll_top_for_y(px(4.5), 0, 0.), 1.5);
+        // Rows near the top of the buffer scroll to its start.
+        assert_eq!(MinimapLayout::scroll_top_for_y(px(9.), 0, 20.), 0.);
+    }
+
+    #[test]
+    fn test_minimap_marker_quad() {
+        let minimap_size = size(px(80.), px(300.));
+        let marker_bounds = |column, start, end| {
+            let range = ColoredRange {
+                start,
+                end,
+                color: gpui::red(),
+            };
+            MinimapLayout::marker_quad(column, range, 5..50, minimap_size).map(|quad| quad.bounds)
+        };
+
+        assert_eq!(
+            marker_bounds(MinimapMarkerColumn::Left, 10, 20),
+            Some(Bounds {
+                origin: point(px(0.), px(15.)),
+                size: size(px(3.), px(30.)),
+            })
+        );
+        assert_eq!(
+            marker_bounds(MinimapMarkerColumn::Right, 10, 20),
+            Some(Bounds {
+                origin: point(px(77.), px(15.)),
+                size: size(px(3.), px(30.)),
+            })
+        );
+        // Markers are clipped to the rows shown in the minimap.
+        assert_eq!(
+            marker_bounds(MinimapMarkerColumn::Left, 0, 10),
+            Some(Bounds {
+                origin: point(px(0.), px(0.)),
+                size: size(px(3.), px(15.)),
+            })
+        );
+        assert_eq!(
+            marker_bounds(MinimapMarkerColumn::Left, 40, 60),
+            Some(Bounds {
+                origin: point(px(0.), px(105.)),
+                size: size(px(3.), px(30.)),
+            })
+        );
+        assert_eq!(marker_bounds(MinimapMarkerColumn::Left, 0, 5), None);
+        assert_eq!(marker_bounds(MinimapMarkerColumn::Left, 60, 70), None);
+    }
 }
 
 pub fn register_action<T: Action>(
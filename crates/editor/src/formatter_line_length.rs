@@ -0,0 +1,166 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use gpui::{AppContext, ViewContext};
+use language::{language_settings::language_settings, LocalFile};
+use project::Fs;
+use serde_json::Value;
+
+use crate::{Editor, EditorMode};
+
+/// The line length rustfmt formats to when its config doesn't set `max_width`.
+const RUSTFMT_DEFAULT_MAX_WIDTH: usize = 100;
+/// The line length prettier formats to when its config doesn't set `printWidth`.
+const PRETTIER_DEFAULT_PRINT_WIDTH: usize = 80;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Formatter {
+    Rustfmt,
+    Prettier,
+}
+
+impl Formatter {
+    /// The names of the files the formatter reads its config from, in the order it
+    /// looks for them in each directory.
+    fn config_file_names(&self) -> &'static [&'static str] {
+        match self {
+            Formatter::Rustfmt => &["rustfmt.toml", ".rustfmt.toml"],
+            Formatter::Prettier => &[".prettierrc", ".prettierrc.json", "package.json"],
+        }
+    }
+
+    /// Returns the line length set by a config file, the formatter's default if the
+    /// config doesn't set one, or `None` if the file doesn't configure the formatter.
+    fn line_length(&self, file_name: &str, contents: &str) -> Option<usize> {
+        match self {
+            Formatter::Rustfmt => {
+                let max_width = toml::from_str::<toml::Table>(contents)
+                    .ok()
+                    .and_then(|config| config.get("max_width")?.as_integer());
+                Some(max_width.map_or(RUSTFMT_DEFAULT_MAX_WIDTH, |width| width as usize))
+            }
+            Formatter::Prettier => {
+                if file_name == "package.json" {
+                    let package = serde_json::from_str::<Value>(contents).ok()?;
+                    return Some(prettier_print_width(package.get("prettier")?));
+                }
+                match serde_json::from_str::<Value>(contents) {
+                    Ok(config) => Some(prettier_print_width(&config)),
+                    // `.prettierrc` can be written in YAML too.
+                    Err(_) => Some(
+                        contents
+                            .lines()
+                            .find_map(|line| {
+                                line.trim().strip_prefix("printWidth:")?.trim().parse().ok()
+                            })
+                            .unwrap_or(PRETTIER_DEFAULT_PRINT_WIDTH),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Finds the config the formatter would use for the file at the given path, the
+    /// same way it does, by looking in each of its ancestor directories in turn.
+    async fn find_line_length(&self, fs: &dyn Fs, abs_path: &Path) -> Option<usize> {
+        for dir in abs_path.ancestors().skip(1) {
+            for file_name in self.config_file_names() {
+                if let Ok(contents) = fs.load(&dir.join(file_name)).await {
+                    if let Some(line_length) = self.line_length(file_name, &contents) {
+                        return Some(line_length);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn prettier_print_width(config: &Value) -> usize {
+    config
+        .get("printWidth")
+        .and_then(Value::as_u64)
+        .map_or(PRETTIER_DEFAULT_PRINT_WIDTH, |width| width as usize)
+}
+
+impl Editor {
+    /// Looks up the line length configured for the formatter of the buffer, which is
+    /// shown as a wrap guide when `wrap_guide_from_formatter_config` is enabled.
+    pub(crate) fn refresh_formatter_line_length(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((formatter, fs, abs_path)) = self.formatter_for_buffer(cx) else {
+            self.formatter_line_length = None;
+            self.formatter_line_length_task = None;
+            return;
+        };
+
+        self.formatter_line_length_task = Some(cx.spawn(|editor, mut cx| async move {
+            let line_length = formatter.find_line_length(fs.as_ref(), &abs_path).await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.formatter_line_length = line_length;
+                    cx.notify();
+                })
+                .ok();
+        }));
+    }
+
+    fn formatter_for_buffer(&self, cx: &AppContext) -> Option<(Formatter, Arc<dyn Fs>, PathBuf)> {
+        if self.mode != EditorMode::Full {
+            return None;
+        }
+        let fs = self.project.as_ref()?.read(cx).fs().clone();
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        let buffer = buffer.read(cx);
+        let language = buffer.language()?;
+        if !language_settings(Some(language), buffer.file(), cx).wrap_guide_from_formatter_config {
+            return None;
+        }
+
+        let formatter = if language.name().as_ref() == "Rust" {
+            Formatter::Rustfmt
+        } else if language.prettier_parser_name().is_some() {
+            Formatter::Prettier
+        } else {
+            return None;
+        };
+        let abs_path = buffer.file()?.as_local()?.abs_path(cx);
+        Some((formatter, fs, abs_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatter_line_length() {
+        assert_eq!(
+            Formatter::Rustfmt.line_length("rustfmt.toml", "max_width = 120\n"),
+            Some(120)
+        );
+        assert_eq!(
+            Formatter::Rustfmt.line_length(".rustfmt.toml", "edition = \"2021\"\n"),
+            Some(RUSTFMT_DEFAULT_MAX_WIDTH)
+        );
+
+        assert_eq!(
+            Formatter::Prettier.line_length(".prettierrc", r#"{ "printWidth": 100 }"#),
+            Some(100)
+        );
+        assert_eq!(
+            Formatter::Prettier.line_length(".prettierrc", "semi: false\nprintWidth: 90\n"),
+            Some(90)
+        );
+        assert_eq!(
+            Formatter::Prettier.line_length("package.json", r#"{ "prettier": {} }"#),
+            Some(PRETTIER_DEFAULT_PRINT_WIDTH)
+        );
+        // A `package.json` without a `prettier` field doesn't configure it.
+        assert_eq!(
+            Formatter::Prettier.line_length("package.json", r#"{ "name": "zed" }"#),
+            None
+        );
+    }
+}
@@ -470,6 +470,7 @@ async fn parse_markdown(text: &str, language_registry: &Arc<LanguageRegistry>) -
         &mut parsed_message.highlights,
         &mut parsed_message.region_ranges,
         &mut parsed_message.regions,
+        &mut parsed_message.images,
     )
     .await;
 
@@ -1,9 +1,49 @@
+use std::{any::TypeId, ops::Range};
+
 use gpui::ViewContext;
 
-use crate::{Editor, RangeToAnchorExt};
+use crate::{DisplayPoint, Editor, EditorSnapshot, RangeToAnchorExt, ToDisplayPoint};
 
 enum MatchingBracketHighlight {}
 
+/// The bracket matching the one under the cursor, when it is scrolled out of view.
+pub(crate) struct OffscreenMatchingBracket {
+    pub position: DisplayPoint,
+    /// Whether the bracket is located above the visible rows.
+    pub above: bool,
+}
+
+/// Returns the bracket matching the one next to the newest cursor if exactly one
+/// of the two brackets is within `visible_rows`.
+pub(crate) fn offscreen_matching_bracket(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    visible_rows: Range<u32>,
+) -> Option<OffscreenMatchingBracket> {
+    let (_, ranges) = editor
+        .background_highlights
+        .get(&TypeId::of::<MatchingBracketHighlight>())?;
+    let [opening_range, closing_range] = ranges.as_ref() else {
+        return None;
+    };
+
+    let opening = opening_range.start.to_display_point(snapshot);
+    let closing = closing_range.start.to_display_point(snapshot);
+    let opening_visible = visible_rows.contains(&opening.row());
+    let closing_visible = visible_rows.contains(&closing.row());
+    match (opening_visible, closing_visible) {
+        (true, false) => Some(OffscreenMatchingBracket {
+            position: closing,
+            above: false,
+        }),
+        (false, true) => Some(OffscreenMatchingBracket {
+            position: opening,
+            above: true,
+        }),
+        _ => None,
+    }
+}
+
 pub fn refresh_matching_bracket_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
     editor.clear_background_highlights::<MatchingBracketHighlight>(cx);
 
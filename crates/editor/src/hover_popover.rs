@@ -370,6 +370,7 @@ async fn parse_blocks(
     let mut highlights = Vec::new();
     let mut region_ranges = Vec::new();
     let mut regions = Vec::new();
+    let mut images = Vec::new();
 
     for block in blocks {
         match &block.kind {
@@ -387,6 +388,7 @@ async fn parse_blocks(
                     &mut highlights,
                     &mut region_ranges,
                     &mut regions,
+                    &mut images,
                 )
                 .await
             }
@@ -423,13 +425,22 @@ async fn parse_blocks(
                 range.start.saturating_sub(leading_space)..range.end.saturating_sub(leading_space)
             })
             .collect();
+        for image in &mut images {
+            image.offset = image.offset.saturating_sub(leading_space);
+        }
+    }
+
+    let text = text.trim().to_string();
+    for image in &mut images {
+        image.offset = image.offset.min(text.len());
     }
 
     ParsedMarkdown {
-        text: text.trim().to_string(),
+        text,
         highlights,
         region_ranges,
         regions,
+        images,
     }
 }
 
@@ -0,0 +1,269 @@
+use crate::{
+    actions::{Decrement, Increment},
+    scroll::Autoscroll,
+    Editor,
+};
+use gpui::ViewContext;
+use language::Point;
+use multi_buffer::ToPoint;
+use std::ops::Range;
+use time::{util::days_in_year_month, Date, Duration, Month};
+
+/// Something on a line that can be incremented or decremented.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    /// A date like `2024-05-31`.
+    Date,
+    /// An integer, which may be negative, zero-padded or an ordinal like `2nd`.
+    Number,
+    /// A word from the group of toggle words at the given index.
+    Word { group_ix: usize },
+}
+
+impl Editor {
+    pub fn increment(&mut self, action: &Increment, cx: &mut ViewContext<Self>) {
+        self.increment_by(action.count.unwrap_or(1) as i64, cx);
+    }
+
+    pub fn decrement(&mut self, action: &Decrement, cx: &mut ViewContext<Self>) {
+        self.increment_by(-(action.count.unwrap_or(1) as i64), cx);
+    }
+
+    /// Adds `delta` to the number or date under each cursor, or to the first one after
+    /// it on the same line, or cycles the toggle word there `delta` times. Cursors
+    /// end up after the text they changed.
+    pub fn increment_by(&mut self, delta: i64, cx: &mut ViewContext<Self>) {
+        if delta == 0 || self.read_only(cx) {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+        let mut edits = Vec::<(Range<usize>, String)>::new();
+        let mut edited_ranges = Vec::with_capacity(selections.len());
+        for selection in &selections {
+            let head = selection.head();
+            let point = head.to_point(&snapshot);
+            let line_start = head - point.column as usize;
+            let line = snapshot
+                .text_for_range(
+                    Point::new(point.row, 0)..Point::new(point.row, snapshot.line_len(point.row)),
+                )
+                .collect::<String>();
+            let toggle_words = &snapshot.settings_at(head, cx).toggle_words;
+
+            let column = point.column as usize;
+            let targets = increment_targets(&line, toggle_words);
+            let Some((range, target)) = target_at(&targets, column) else {
+                edited_ranges.push(None);
+                continue;
+            };
+            let text = &line[range.clone()];
+            let new_text = match target {
+                Target::Date => increment_date(text, column.checked_sub(range.start), delta),
+                Target::Number => increment_number(text, delta),
+                Target::Word { group_ix } => {
+                    cycle_word(text, &toggle_words[group_ix], delta).map(str::to_string)
+                }
+            };
+            let Some(new_text) = new_text else {
+                edited_ranges.push(None);
+                continue;
+            };
+
+            let range = line_start + range.start..line_start + range.end;
+            // Cursors on the same text change it once.
+            if !edits.iter().any(|(edited_range, _)| *edited_range == range) {
+                edits.push((range.clone(), new_text));
+            }
+            edited_ranges.push(Some(range));
+        }
+        if edits.is_empty() {
+            return;
+        }
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let adjust_offset = |offset: usize| {
+            edits
+                .iter()
+                .filter(|(range, _)| range.end <= offset)
+                .fold(offset, |offset, (range, text)| {
+                    offset + text.len() - range.len()
+                })
+        };
+        let new_cursors = selections
+            .iter()
+            .zip(edited_ranges)
+            .map(|(selection, edited_range)| {
+                let cursor = match edited_range {
+                    Some(range) => adjust_offset(range.end),
+                    None => adjust_offset(selection.head()),
+                };
+                cursor..cursor
+            })
+            .collect::<Vec<_>>();
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_cursors)
+            });
+        });
+    }
+}
+
+/// Returns the target that contains the column, or else the first one after it.
+/// Dates are preferred over the numbers within them.
+fn target_at(targets: &[(Range<usize>, Target)], column: usize) -> Option<(Range<usize>, Target)> {
+    targets
+        .iter()
+        .filter(|(range, _)| range.start <= column && column <= range.end)
+        .max_by_key(|(range, _)| range.len())
+        .or_else(|| {
+            targets
+                .iter()
+                .filter(|(range, _)| range.start > column)
+                .min_by_key(|(range, _)| range.start)
+        })
+        .cloned()
+}
+
+fn increment_targets(line: &str, toggle_words: &[Vec<String>]) -> Vec<(Range<usize>, Target)> {
+    let mut targets = Vec::new();
+
+    let mut ix = 0;
+    while let Some(c) = line[ix..].chars().next() {
+        if !c.is_ascii_digit() {
+            ix += c.len_utf8();
+            continue;
+        }
+
+        if is_date(&line[ix..]) {
+            targets.push((ix..ix + 10, Target::Date));
+            ix += 10;
+            continue;
+        }
+
+        let mut start = ix;
+        if line[..ix].ends_with('-') && !line[..ix - 1].ends_with(is_word_char) {
+            start -= 1;
+        }
+        let mut end = ix + digits_len(&line[ix..]);
+        if ["st", "nd", "rd", "th"]
+            .iter()
+            .any(|suffix| line[end..].starts_with(suffix))
+            && !line[end + 2..].starts_with(is_word_char)
+        {
+            end += 2;
+        }
+        targets.push((start..end, Target::Number));
+        ix = end;
+    }
+
+    for (group_ix, group) in toggle_words.iter().enumerate() {
+        for word in group.iter().filter(|word| !word.is_empty()) {
+            // Words like `true` only match whole words, unlike operators like `&&`.
+            let is_word = word.chars().all(is_word_char);
+            for (start, _) in line.match_indices(word.as_str()) {
+                let end = start + word.len();
+                if is_word
+                    && (line[..start].ends_with(is_word_char)
+                        || line[end..].starts_with(is_word_char))
+                {
+                    continue;
+                }
+                targets.push((start..end, Target::Word { group_ix }));
+            }
+        }
+    }
+
+    targets
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn digits_len(text: &str) -> usize {
+    text.find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len())
+}
+
+/// Returns whether the text starts with a date like `2024-05-31`.
+fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(ix, byte)| match ix {
+            4 | 7 => *byte == b'-',
+            _ => byte.is_ascii_digit(),
+        })
+        && !text[10..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Adds `delta` to the year, month or day that the column is in, keeping the day
+/// within the new month, or to the day when the column isn't in the date.
+fn increment_date(text: &str, column: Option<usize>, delta: i64) -> Option<String> {
+    let year: i32 = text[0..4].parse().ok()?;
+    let month: u8 = text[5..7].parse().ok()?;
+    let day: u8 = text[8..10].parse().ok()?;
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+
+    let date = match column {
+        Some(column) if column < 8 => {
+            let delta_months = if column < 5 { delta * 12 } else { delta };
+            let months = year as i64 * 12 + month as i64 - 1 + delta_months;
+            let year = i32::try_from(months.div_euclid(12)).ok()?;
+            let month = Month::try_from(months.rem_euclid(12) as u8 + 1).ok()?;
+            Date::from_calendar_date(year, month, day.min(days_in_year_month(year, month))).ok()?
+        }
+        _ => date.checked_add(Duration::days(delta))?,
+    };
+    Some(format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    ))
+}
+
+/// Adds `delta` to a number, keeping its zero padding and updating its ordinal
+/// suffix.
+fn increment_number(text: &str, delta: i64) -> Option<String> {
+    let digits_start = if text.starts_with('-') { 1 } else { 0 };
+    let digits_end = digits_start + digits_len(&text[digits_start..]);
+    let value = text[..digits_end].parse::<i64>().ok()?.checked_add(delta)?;
+
+    let digits = &text[digits_start..digits_end];
+    let width = if digits.len() > 1 && digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    let mut new_text = if value < 0 {
+        format!("-{:0width$}", value.unsigned_abs())
+    } else {
+        format!("{:0width$}", value)
+    };
+    if digits_end < text.len() {
+        new_text.push_str(ordinal_suffix(value));
+    }
+    Some(new_text)
+}
+
+fn ordinal_suffix(value: i64) -> &'static str {
+    let value = value.unsigned_abs();
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn cycle_word<'a>(word: &str, group: &'a [String], delta: i64) -> Option<&'a str> {
+    let ix = group.iter().position(|candidate| candidate == word)?;
+    let new_ix = (ix as i64 + delta).rem_euclid(group.len() as i64);
+    Some(&group[new_ix as usize])
+}
@@ -1,10 +1,14 @@
 use crate::{
-    editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
-    Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, ToPoint as _,
+    editor_settings::SeedQuerySetting,
+    persistence::DB,
+    persistent_undo::{restore_undo_history, save_undo_history},
+    scroll::ScrollAnchor,
+    Anchor, Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
+    MultiBufferSnapshot, NavigationData, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
+use file_icons::FileIcons;
 use futures::future::try_join_all;
 use git::repository::GitFileStatus;
 use gpui::{
@@ -19,7 +23,7 @@ use language::{
 use project::{search::SearchQuery, FormatTrigger, Item as _, Project, ProjectPath};
 use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
-use workspace::item::{ItemSettings, TabContentParams};
+use workspace::item::{ItemSettings, NavigationPosition, TabContentParams};
 
 use std::{
     borrow::Cow,
@@ -537,6 +541,28 @@ impl Item for Editor {
     type Event = EditorEvent;
 
     fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
+        let data = match data.downcast::<NavigationPosition>() {
+            Ok(position) => {
+                // Positions are restored from previous sessions, so they're resolved
+                // against the buffer as it is now, centering the cursor vertically.
+                let half_visible_rows = self.visible_line_count().unwrap_or(0.) as u32 / 2;
+                let buffer = self.buffer.read(cx).read(cx);
+                let point =
+                    buffer.clip_point(Point::new(position.row, position.column), Bias::Left);
+                let cursor_anchor = buffer.anchor_before(point);
+                let scroll_top_row = point.row.saturating_sub(half_visible_rows);
+                Box::new(NavigationData {
+                    cursor_anchor,
+                    cursor_position: point,
+                    scroll_anchor: ScrollAnchor {
+                        anchor: buffer.anchor_before(Point::new(scroll_top_row, 0)),
+                        offset: Default::default(),
+                    },
+                    scroll_top_row,
+                }) as Box<dyn std::any::Any>
+            }
+            Err(data) => data,
+        };
         if let Ok(data) = data.downcast::<NavigationData>() {
             let newest_selection = self.selections.newest::<Point>(cx);
             let buffer = self.buffer.read(cx).read(cx);
@@ -555,7 +581,8 @@ impl Item for Editor {
 
             drop(buffer);
 
-            if newest_selection.head() == offset {
+            // Moves within a line aren't worth stopping at when going back or forward.
+            if newest_selection.head().row == offset.row {
                 false
             } else {
                 let nav_history = self.nav_history.take();
@@ -571,6 +598,21 @@ impl Item for Editor {
         }
     }
 
+    fn navigation_position(
+        &self,
+        data: &dyn std::any::Any,
+        _: &AppContext,
+    ) -> Option<NavigationPosition> {
+        if let Some(data) = data.downcast_ref::<NavigationData>() {
+            Some(NavigationPosition {
+                row: data.cursor_position.row,
+                column: data.cursor_position.column,
+            })
+        } else {
+            data.downcast_ref::<NavigationPosition>().copied()
+        }
+    }
+
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
         let file_path = self
             .buffer()
@@ -586,6 +628,13 @@ impl Item for Editor {
         Some(file_path.into())
     }
 
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        let buffer = self.buffer().read(cx).as_singleton()?.read(cx);
+        let path = buffer.file()?.path();
+        let language_name = buffer.language().map(|language| language.name());
+        FileIcons::get_icon_for_file(path, language_name.as_deref(), cx).map(Icon::from_path)
+    }
+
     fn telemetry_event_text(&self) -> Option<&'static str> {
         None
     }
@@ -702,6 +751,7 @@ impl Item for Editor {
     ) -> Task<Result<()>> {
         self.report_editor_event("save", None, cx);
         let buffers = self.buffer().clone().read(cx).all_buffers();
+        let saved_buffers = buffers.clone();
         cx.spawn(|this, mut cx| async move {
             if format {
                 this.update(&mut cx, |editor, cx| {
@@ -743,6 +793,12 @@ impl Item for Editor {
                 }
             }
 
+            this.update(&mut cx, |_, cx| {
+                for buffer in &saved_buffers {
+                    save_undo_history(buffer, cx);
+                }
+            })?;
+
             Ok(())
         })
     }
@@ -848,6 +904,15 @@ impl Item for Editor {
         let workspace_id = workspace.database_id();
         let item_id = cx.view().item_id().as_u64() as ItemId;
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
+        self.apply_zen_mode_settings(workspace.zen_mode_settings(cx), cx);
+        self.zen_mode_subscription = workspace.weak_handle().upgrade().map(|workspace| {
+            cx.subscribe(&workspace, |this, workspace, event, cx| {
+                if let workspace::Event::ZenModeChanged = event {
+                    let zen_mode = workspace.read(cx).zen_mode_settings(cx);
+                    this.apply_zen_mode_settings(zen_mode, cx);
+                }
+            })
+        });
 
         fn serialize(
             buffer: Model<Buffer>,
@@ -978,6 +1043,7 @@ impl ProjectItem for Editor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
+        restore_undo_history(buffer.clone(), cx);
         Self::for_buffer(buffer, Some(project), cx)
     }
 }
@@ -1137,7 +1203,11 @@ impl SearchableItem for Editor {
                         }),
                 );
             } else {
+                let max_matches = query.max_matches().unwrap_or(usize::MAX);
                 for excerpt in buffer.excerpt_boundaries_in_range(0..buffer.len()) {
+                    if ranges.len() >= max_matches {
+                        break;
+                    }
                     let excerpt_range = excerpt.range.context.to_offset(&excerpt.buffer);
                     ranges.extend(
                         query
@@ -1156,6 +1226,7 @@ impl SearchableItem for Editor {
                             }),
                     );
                 }
+                ranges.truncate(max_matches);
             }
             ranges
         })
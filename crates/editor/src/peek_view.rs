@@ -0,0 +1,404 @@
+use crate::{
+    actions::{Cancel, PeekDefinition, PeekReferences},
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Editor,
+};
+use anyhow::Result;
+use collections::HashSet;
+use gpui::{
+    DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render, SharedString,
+    Subscription, Task, View, ViewContext,
+};
+use language::{Location, ToPoint};
+use project::Project;
+use ui::{prelude::*, IconButton, IconName, ListItem, ListItemSpacing, Tooltip};
+
+/// How many lines of the target are visible in a peek view.
+const PEEK_EDITOR_LINES: u8 = 12;
+
+/// An embedded editor that's displayed below a line, showing the definitions or
+/// references of the symbol on it without switching tabs. When there are several
+/// locations, they're listed beside the editor.
+pub struct PeekView {
+    title: SharedString,
+    locations: Vec<Location>,
+    labels: Vec<SharedString>,
+    selected_ix: usize,
+    editor: View<Editor>,
+    project: Model<Project>,
+}
+
+pub(crate) struct PeekState {
+    block_id: BlockId,
+    view: View<PeekView>,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<DismissEvent> for PeekView {}
+
+impl FocusableView for PeekView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl PeekView {
+    fn new(
+        title: SharedString,
+        locations: Vec<Location>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let labels = locations
+            .iter()
+            .map(|location| {
+                let buffer = location.buffer.read(cx);
+                let row = location.range.start.to_point(buffer).row + 1;
+                let path = buffer
+                    .file()
+                    .map(|file| file.path().to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "untitled".to_string());
+                SharedString::from(format!("{path}:{row}"))
+            })
+            .collect();
+        let editor = Self::editor_for_location(&locations[0], &project, cx);
+        Self {
+            title,
+            locations,
+            labels,
+            selected_ix: 0,
+            editor,
+            project,
+        }
+    }
+
+    fn editor_for_location(
+        location: &Location,
+        project: &Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Editor> {
+        let range = location.range.clone();
+        cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(location.buffer.clone(), Some(project.clone()), cx);
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let range = snapshot
+                .as_singleton()
+                .map(|(excerpt_id, _, _)| *excerpt_id)
+                .and_then(|excerpt_id| {
+                    let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                    let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                    Some(start..end)
+                });
+            if let Some(range) = range {
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_anchor_ranges([range.start..range.start])
+                });
+                editor.highlight_background::<Self>(
+                    &[range],
+                    |theme| theme.editor_document_highlight_read_background,
+                    cx,
+                );
+            }
+            editor
+        })
+    }
+
+    fn select_location(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix == self.selected_ix {
+            return;
+        }
+        self.selected_ix = ix;
+        self.editor = Self::editor_for_location(&self.locations[ix], &self.project, cx);
+        cx.focus_view(&self.editor);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for PeekView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        let header = h_flex()
+            .justify_between()
+            .px_2()
+            .bg(cx.theme().colors().editor_subheader_background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(self.title.clone()))
+                    .child(
+                        Label::new(self.labels[self.selected_ix].clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                IconButton::new("dismiss-peek", IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Close", &Cancel, cx))
+                    .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+            );
+
+        v_flex()
+            .key_context("PeekView")
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .border_y_1()
+            .border_color(border_color)
+            .child(header)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .items_start()
+                    .child(div().size_full().child(self.editor.clone()))
+                    .when(self.locations.len() > 1, |this| {
+                        this.child(
+                            v_flex()
+                                .id("peek-locations")
+                                .w(rems(16.))
+                                .h_full()
+                                .flex_shrink_0()
+                                .overflow_y_scroll()
+                                .border_l_1()
+                                .border_color(border_color)
+                                .bg(cx.theme().colors().panel_background)
+                                .children(self.labels.iter().enumerate().map(|(ix, label)| {
+                                    ListItem::new(ix)
+                                        .spacing(ListItemSpacing::Sparse)
+                                        .selected(ix == self.selected_ix)
+                                        .on_click(cx.listener(move |this, _, cx| {
+                                            this.select_location(ix, cx)
+                                        }))
+                                        .child(Label::new(label.clone()).size(LabelSize::Small))
+                                })),
+                        )
+                    }),
+            )
+    }
+}
+
+impl Editor {
+    pub fn peek_definition(
+        &mut self,
+        _: &PeekDefinition,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Ok(()));
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let definitions =
+            project.update(cx, |project, cx| project.definition(&buffer, position, cx));
+        cx.spawn(|editor, mut cx| async move {
+            let locations = definitions
+                .await?
+                .into_iter()
+                .map(|definition| definition.target)
+                .collect();
+            editor.update(&mut cx, |editor, cx| {
+                editor.show_peek("Definition", locations, cx)
+            })
+        })
+    }
+
+    pub fn peek_references(
+        &mut self,
+        _: &PeekReferences,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Ok(()));
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let references =
+            project.update(cx, |project, cx| project.references(&buffer, position, cx));
+        cx.spawn(|editor, mut cx| async move {
+            let locations = references.await?;
+            editor.update(&mut cx, |editor, cx| {
+                editor.show_peek("References", locations, cx)
+            })
+        })
+    }
+
+    /// Shows the given locations in a peek view below the newest cursor, replacing
+    /// the one that's already open.
+    pub(crate) fn show_peek(
+        &mut self,
+        title: impl Into<SharedString>,
+        locations: Vec<Location>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.dismiss_peek(cx);
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if locations.is_empty() {
+            return;
+        }
+
+        let title = title.into();
+        let view = cx.new_view(|cx| PeekView::new(title, locations, project, cx));
+        let position = self.selections.newest_anchor().head();
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                position,
+                height: PEEK_EDITOR_LINES + 1,
+                style: BlockStyle::Flex,
+                render: Box::new({
+                    let view = view.clone();
+                    move |cx: &mut BlockContext| {
+                        div()
+                            .pl(cx.gutter_dimensions.width)
+                            .w(cx.max_width + cx.gutter_dimensions.width)
+                            .h(cx.line_height * (PEEK_EDITOR_LINES + 1) as f32)
+                            .child(view.clone())
+                            .into_any_element()
+                    }
+                }),
+                disposition: BlockDisposition::Below,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        let subscription = cx.subscribe(&view, |editor, _, _: &DismissEvent, cx| {
+            editor.dismiss_peek(cx);
+        });
+        cx.focus_view(&view);
+        self.peek = Some(PeekState {
+            block_id,
+            view,
+            _subscription: subscription,
+        });
+    }
+
+    /// Closes the peek view, returning whether one was open.
+    pub(crate) fn dismiss_peek(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(peek) = self.peek.take() else {
+            return false;
+        };
+        let was_focused = peek.view.focus_handle(cx).contains_focused(cx);
+        self.remove_blocks(HashSet::from_iter([peek.block_id]), None, cx);
+        if was_focused {
+            cx.focus(&self.focus_handle);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt;
+    use gpui::KeyBinding;
+    use indoc::indoc;
+    use lsp::request::GotoDefinition;
+    use text::Point;
+
+    #[gpui::test]
+    async fn test_peek_definitions(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.update(|cx| cx.bind_keys([KeyBinding::new("escape", Cancel, None)]));
+
+        cx.set_state(indoc! {"
+            fn test() { do_ˇwork(); }
+            fn do_work() {}
+            fn do_work(x: u32) {}
+        "});
+        let first_range = cx.lsp_range(indoc! {"
+            fn test() { do_work(); }
+            fn «do_work»() {}
+            fn do_work(x: u32) {}
+        "});
+        let second_range = cx.lsp_range(indoc! {"
+            fn test() { do_work(); }
+            fn do_work() {}
+            fn «do_work»(x: u32) {}
+        "});
+        let mut requests = cx.handle_request::<GotoDefinition, _, _>(move |url, _, _| async move {
+            Ok(Some(lsp::GotoDefinitionResponse::Array(vec![
+                lsp::Location::new(url.clone(), first_range),
+                lsp::Location::new(url, second_range),
+            ])))
+        });
+
+        // All definitions are listed, and the first one is shown and focused.
+        let peek = cx.update_editor(|editor, cx| editor.peek_definition(&PeekDefinition, cx));
+        requests.next().await;
+        peek.await.unwrap();
+        cx.run_until_parked();
+        let peek_view = cx.update_editor(|editor, _| editor.peek.as_ref().unwrap().view.clone());
+        cx.update(|cx| {
+            let peek_view = peek_view.read(cx);
+            let labels = peek_view.labels.iter().map(|label| label.to_string());
+            assert_eq!(
+                labels.collect::<Vec<_>>(),
+                ["dir/file.rs:2", "dir/file.rs:3"]
+            );
+            assert_eq!(peek_view.selected_ix, 0);
+            assert_eq!(
+                peek_view
+                    .editor
+                    .read(cx)
+                    .selections
+                    .newest::<Point>(cx)
+                    .head(),
+                Point::new(1, 3)
+            );
+            assert!(peek_view.focus_handle(cx).is_focused(cx));
+        });
+
+        // Selecting another location shows it in a new editor, which keeps focus.
+        cx.update(|cx| peek_view.update(cx, |peek_view, cx| peek_view.select_location(1, cx)));
+        cx.run_until_parked();
+        cx.update(|cx| {
+            let peek_view = peek_view.read(cx);
+            assert_eq!(peek_view.selected_ix, 1);
+            assert_eq!(
+                peek_view
+                    .editor
+                    .read(cx)
+                    .selections
+                    .newest::<Point>(cx)
+                    .head(),
+                Point::new(2, 3)
+            );
+            assert!(peek_view.focus_handle(cx).is_focused(cx));
+        });
+
+        // Escape closes the peek view and focuses the editor again.
+        cx.simulate_keystroke("escape");
+        cx.update_editor(|editor, cx| {
+            assert!(editor.peek.is_none());
+            assert!(editor.focus_handle.is_focused(cx));
+        });
+        cx.assert_editor_state(indoc! {"
+            fn test() { do_ˇwork(); }
+            fn do_work() {}
+            fn do_work(x: u32) {}
+        "});
+    }
+}
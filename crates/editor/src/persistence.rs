@@ -15,6 +15,12 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    // undo_histories(
+    //   path: PathBuf,
+    //   content_hash: String,
+    //   transactions: String,
+    //   saved_at: i64,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +37,14 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE undo_histories(
+                path BLOB NOT NULL PRIMARY KEY,
+                content_hash TEXT NOT NULL,
+                transactions TEXT NOT NULL,
+                saved_at INTEGER NOT NULL
+            ) STRICT;
         )];
 );
 
@@ -80,4 +94,39 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    // Returns the hash of the text the undo history was saved for, the history, and
+    // when it was saved, in seconds since the Unix epoch.
+    query! {
+        pub async fn get_undo_history(path: PathBuf) -> Result<Option<(String, String, i64)>> {
+            SELECT content_hash, transactions, saved_at
+            FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_undo_history(
+            path: PathBuf,
+            content_hash: String,
+            transactions: String,
+            saved_at: i64
+        ) -> Result<()> {
+            INSERT INTO undo_histories
+                (path, content_hash, transactions, saved_at)
+            VALUES
+                (?1, ?2, ?3, ?4)
+            ON CONFLICT DO UPDATE SET
+                content_hash = ?2,
+                transactions = ?3,
+                saved_at = ?4
+        }
+    }
+
+    query! {
+        pub async fn delete_undo_histories_saved_before(saved_at: i64) -> Result<()> {
+            DELETE FROM undo_histories
+            WHERE saved_at < ?
+        }
+    }
 }
@@ -0,0 +1,322 @@
+//! Keeps the undo history of files across restarts.
+//!
+//! When a file is saved, the changes on its undo stack are stored in the editor's
+//! database along with a hash of the saved text. When the file is next opened and
+//! its text still has that hash, the changes are replayed onto the new buffer so
+//! they can be undone again.
+//!
+//! Each change is stored as the single edit that undoes it, spanning from the
+//! first to the last character it touched, so that the history doesn't depend on
+//! the buffer's replica or clock.
+
+use crate::{persistence::DB, EditorSettings};
+use gpui::{AppContext, Model};
+use language::Buffer;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsLocation};
+use sha2::{Digest, Sha256};
+use std::{
+    ops::Range,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use util::ResultExt;
+
+/// The edit that undoes a transaction: replacing `range` of the text after the
+/// transaction with `text` restores the text from before it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct StoredTransaction {
+    range: Range<usize>,
+    text: String,
+}
+
+/// Stores the undo history of the buffer's file, which should have just been saved.
+pub(crate) fn save_undo_history(buffer: &Model<Buffer>, cx: &mut AppContext) {
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    let settings = EditorSettings::get(
+        Some(SettingsLocation {
+            worktree_id: file.worktree_id(),
+            path: file.path(),
+        }),
+        cx,
+    )
+    .persistent_undo;
+    if !settings.enabled || buffer.is_dirty() {
+        return;
+    }
+
+    let path = file.abs_path(cx);
+    let snapshot = buffer.text_snapshot();
+    let starts = buffer
+        .undo_history()
+        .map(|transaction| transaction.start.clone())
+        .collect::<Vec<_>>();
+    cx.background_executor()
+        .spawn(async move {
+            let text = snapshot.text();
+            let mut after = text.clone();
+            let mut transactions = Vec::new();
+            let mut size = 0;
+            for start in starts.iter().rev() {
+                let before = snapshot.rope_for_version(start).to_string();
+                let (range, undo_text) = diff_hunk(&after, &before);
+                if range.is_empty() && undo_text.is_empty() {
+                    continue;
+                }
+                size += undo_text.len();
+                if size > settings.max_size_kb.saturating_mul(1024) {
+                    break;
+                }
+                transactions.push(StoredTransaction {
+                    range,
+                    text: undo_text.to_string(),
+                });
+                after = before;
+            }
+            transactions.reverse();
+
+            let now = unix_seconds(SystemTime::now());
+            DB.delete_undo_histories_saved_before(oldest_kept(now, settings.max_age_days))
+                .await
+                .log_err();
+            DB.save_undo_history(
+                path,
+                content_hash(&text),
+                serde_json::to_string(&transactions)?,
+                now,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Restores the undo history of the buffer's file if it was stored when the file
+/// was last saved, and the buffer hasn't been edited since it was opened.
+pub(crate) fn restore_undo_history(buffer: Model<Buffer>, cx: &mut AppContext) {
+    let buffer_ref = buffer.read(cx);
+    let Some(file) = buffer_ref.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    let settings = EditorSettings::get(
+        Some(SettingsLocation {
+            worktree_id: file.worktree_id(),
+            path: file.path(),
+        }),
+        cx,
+    )
+    .persistent_undo;
+    if !settings.enabled || buffer_ref.is_dirty() || buffer_ref.peek_undo_stack().is_some() {
+        return;
+    }
+
+    let path = file.abs_path(cx);
+    let snapshot = buffer_ref.text_snapshot();
+    cx.spawn(|mut cx| async move {
+        let Some((hash, transactions, saved_at)) = DB.get_undo_history(path).await? else {
+            return anyhow::Ok(());
+        };
+        let text = snapshot.text();
+        if hash != content_hash(&text)
+            || saved_at < oldest_kept(unix_seconds(SystemTime::now()), settings.max_age_days)
+        {
+            return Ok(());
+        }
+        let transactions: Vec<StoredTransaction> = serde_json::from_str(&transactions)?;
+        let Some((base_text, redo_edits)) = replay_backwards(&text, &transactions) else {
+            return Ok(());
+        };
+
+        buffer.update(&mut cx, |buffer, cx| {
+            if buffer.version() != snapshot.version || buffer.peek_undo_stack().is_some() {
+                return;
+            }
+
+            // Go back to the text before the oldest transaction without recording it...
+            let (range, base_edit_text) = diff_hunk(&text, &base_text);
+            buffer.start_transaction();
+            buffer.edit([(range, base_edit_text)], None, cx);
+            if let Some(transaction_id) = buffer.end_transaction(cx) {
+                buffer.forget_transaction(transaction_id);
+            }
+
+            // ...and redo every transaction after it, so they can be undone again.
+            for (range, text) in redo_edits.into_iter().rev() {
+                buffer.start_transaction();
+                buffer.edit([(range, text)], None, cx);
+                buffer.end_transaction(cx);
+                buffer.finalize_last_transaction();
+            }
+
+            // The text is back to what's on disk.
+            let mtime = buffer.saved_mtime();
+            buffer.did_reload(buffer.version(), buffer.line_ending(), mtime, cx);
+        })?;
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Undoes the transactions from the most recent to the oldest, returning the text
+/// from before all of them, and the edits that redo each of them in that order.
+/// Returns `None` if the transactions don't fit the text.
+fn replay_backwards(
+    text: &str,
+    transactions: &[StoredTransaction],
+) -> Option<(String, Vec<(Range<usize>, String)>)> {
+    let mut text = text.to_string();
+    let mut redo_edits = Vec::new();
+    for transaction in transactions.iter().rev() {
+        let range = transaction.range.clone();
+        if range.start > range.end
+            || range.end > text.len()
+            || !text.is_char_boundary(range.start)
+            || !text.is_char_boundary(range.end)
+        {
+            return None;
+        }
+        let redo_text = text[range.clone()].to_string();
+        text.replace_range(range.clone(), &transaction.text);
+        redo_edits.push((range.start..range.start + transaction.text.len(), redo_text));
+    }
+    Some((text, redo_edits))
+}
+
+/// Returns the range of `old` that differs from `new`, and the text of `new` it's
+/// replaced by, leaving out the text they start and end with.
+fn diff_hunk<'a>(old: &str, new: &'a str) -> (Range<usize>, &'a str) {
+    let mut prefix_len = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix_len) {
+        prefix_len -= 1;
+    }
+    let mut suffix_len = old[prefix_len..]
+        .bytes()
+        .rev()
+        .zip(new[prefix_len..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix_len) {
+        suffix_len -= 1;
+    }
+    (
+        prefix_len..old.len() - suffix_len,
+        &new[prefix_len..new.len() - suffix_len],
+    )
+}
+
+fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Returns the time, in seconds since the Unix epoch, before which histories are
+/// forgotten when they're kept for the given number of days.
+fn oldest_kept(now: i64, max_age_days: u64) -> i64 {
+    let max_age = Duration::from_secs(max_age_days.saturating_mul(24 * 60 * 60));
+    now.saturating_sub(i64::try_from(max_age.as_secs()).unwrap_or(i64::MAX))
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{actions::Undo, editor_tests::init_test, Editor};
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::item::{Item, ProjectItem};
+
+    #[gpui::test]
+    async fn test_undo_after_reopening(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/persistent_undo", json!({ "main.rs": "fn main() {}" }))
+            .await;
+
+        // Edit the file in two steps and save it...
+        let project = Project::test(fs.clone(), ["/persistent_undo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/persistent_undo/main.rs", cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) =
+            cx.add_window_view(|cx| Editor::for_project_item(project.clone(), buffer.clone(), cx));
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(11..11, "\n")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.edit([(12..12, "    a();\n")], None, cx);
+            buffer.finalize_last_transaction();
+        });
+        editor
+            .update(cx, |editor, cx| editor.save(false, project.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // ...then open it again in another project, as after a restart.
+        let project = Project::test(fs.clone(), ["/persistent_undo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/persistent_undo/main.rs", cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) =
+            cx.add_window_view(|cx| Editor::for_project_item(project.clone(), buffer, cx));
+        cx.run_until_parked();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "fn main() {\n    a();\n}");
+            assert!(!editor.is_dirty(cx));
+
+            editor.undo(&Undo, cx);
+            assert_eq!(editor.text(cx), "fn main() {\n}");
+            editor.undo(&Undo, cx);
+            assert_eq!(editor.text(cx), "fn main() {}");
+            editor.undo(&Undo, cx);
+            assert_eq!(editor.text(cx), "fn main() {}");
+        });
+    }
+
+    #[test]
+    fn test_oldest_kept() {
+        assert_eq!(oldest_kept(1_000_000, 1), 1_000_000 - 86_400);
+        // Settings too large to be represented keep histories forever.
+        assert_eq!(oldest_kept(0, u64::MAX), -i64::MAX);
+        assert_eq!(oldest_kept(i64::MIN + 1, u64::MAX), i64::MIN);
+    }
+
+    #[test]
+    fn test_replay_backwards() {
+        let versions = ["fn main() {}", "fn main() {\n}", "fn main() {\n    a();\n}"];
+        let mut transactions = Vec::new();
+        for pair in versions.windows(2) {
+            let (range, text) = diff_hunk(pair[1], pair[0]);
+            transactions.push(StoredTransaction {
+                range,
+                text: text.to_string(),
+            });
+        }
+
+        let (base_text, redo_edits) = replay_backwards(versions[2], &transactions).unwrap();
+        assert_eq!(base_text, versions[0]);
+
+        let mut text = base_text;
+        for (range, redo_text) in redo_edits.into_iter().rev() {
+            text.replace_range(range, &redo_text);
+        }
+        assert_eq!(text, versions[2]);
+
+        // A history that was stored for other text isn't replayed.
+        assert_eq!(replay_backwards("fn", &transactions), None);
+    }
+}
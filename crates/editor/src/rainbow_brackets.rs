@@ -0,0 +1,264 @@
+use std::{cmp::Reverse, ops::Range};
+
+use gpui::{HighlightStyle, ViewContext};
+use language::language_settings::language_settings;
+use multi_buffer::{Anchor, ToPoint};
+use text::{Bias, Point};
+use theme::ActiveTheme;
+
+use crate::{Editor, EditorMode};
+
+enum RainbowBracketHighlight {}
+
+/// Colors the brackets in the visible part of the editor according to their
+/// nesting depth, using the accent colors of the active theme.
+///
+/// Brackets are only colored for languages that have `rainbow_brackets` enabled.
+pub fn refresh_rainbow_bracket_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_highlights::<RainbowBracketHighlight>(cx);
+
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(visible_line_count) = editor.visible_line_count() else {
+        return;
+    };
+    let accents = cx.theme().accents().clone();
+    if accents.is_empty() {
+        return;
+    }
+
+    let multi_buffer = editor.buffer().read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let visible_start = editor
+        .scroll_manager
+        .anchor()
+        .anchor
+        .to_point(&multi_buffer_snapshot);
+    let visible_end = multi_buffer_snapshot.clip_point(
+        visible_start + Point::new(visible_line_count.ceil() as u32, 0),
+        Bias::Left,
+    );
+
+    let mut ranges_by_depth = vec![Vec::<Range<Anchor>>::new(); accents.len()];
+    for (buffer, visible_range, excerpt_id) in
+        multi_buffer.range_to_buffer_ranges(visible_start..visible_end, cx)
+    {
+        let buffer = buffer.read(cx);
+        if !language_settings(buffer.language(), buffer.file(), cx).rainbow_brackets {
+            continue;
+        }
+
+        let buffer_snapshot = buffer.snapshot();
+        let mut bracket_pairs = buffer_snapshot
+            .bracket_ranges(visible_range.clone())
+            .filter(|(open, close)| {
+                // Quotes are reported as bracket pairs by some languages, but
+                // coloring them by depth is not useful.
+                buffer_snapshot
+                    .text_for_range(open.clone())
+                    .collect::<String>()
+                    != buffer_snapshot
+                        .text_for_range(close.clone())
+                        .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        bracket_pairs.sort_by_key(|(open, close)| (open.start, Reverse(close.end)));
+        bracket_pairs.dedup();
+
+        // Every pair enclosing the visible range overlaps it as well, so the
+        // depth of each pair is the number of still open pairs preceding it.
+        let mut open_pair_ends = Vec::<usize>::new();
+        for (open, close) in bracket_pairs {
+            while open_pair_ends
+                .last()
+                .map_or(false, |&end| end <= open.start)
+            {
+                open_pair_ends.pop();
+            }
+            let depth = open_pair_ends.len();
+            open_pair_ends.push(close.end);
+
+            for bracket_range in [open, close] {
+                if bracket_range.end < visible_range.start
+                    || bracket_range.start > visible_range.end
+                {
+                    continue;
+                }
+                let start = multi_buffer_snapshot.anchor_in_excerpt(
+                    excerpt_id,
+                    buffer_snapshot.anchor_after(bracket_range.start),
+                );
+                let end = multi_buffer_snapshot.anchor_in_excerpt(
+                    excerpt_id,
+                    buffer_snapshot.anchor_before(bracket_range.end),
+                );
+                if let Some((start, end)) = start.zip(end) {
+                    ranges_by_depth[depth % accents.len()].push(start..end);
+                }
+            }
+        }
+    }
+
+    for (depth, mut ranges) in ranges_by_depth.into_iter().enumerate() {
+        if ranges.is_empty() {
+            continue;
+        }
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, &multi_buffer_snapshot));
+        editor.highlight_text_key::<RainbowBracketHighlight>(
+            depth,
+            ranges,
+            HighlightStyle {
+                color: Some(accents.color_for_index(depth as u32)),
+                ..HighlightStyle::default()
+            },
+            cx,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        display_map::HighlightKey, editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use indoc::indoc;
+    use language::{BracketPair, BracketPairConfig, Language, LanguageConfig, LanguageMatcher};
+    use multi_buffer::ToOffset;
+    use std::any::TypeId;
+    use util::test::generate_marked_text;
+
+    #[gpui::test]
+    async fn test_rainbow_bracket_highlights(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.rainbow_brackets = Some(true)
+        });
+
+        let mut cx = EditorLspTestContext::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["rs".to_string()],
+                        ..Default::default()
+                    },
+                    brackets: BracketPairConfig {
+                        pairs: vec![
+                            BracketPair {
+                                start: "{".to_string(),
+                                end: "}".to_string(),
+                                close: false,
+                                newline: true,
+                            },
+                            BracketPair {
+                                start: "(".to_string(),
+                                end: ")".to_string(),
+                                close: false,
+                                newline: true,
+                            },
+                            BracketPair {
+                                start: "[".to_string(),
+                                end: "]".to_string(),
+                                close: false,
+                                newline: true,
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::language()),
+            )
+            .with_brackets_query(indoc! {r#"
+                ("{" @open "}" @close)
+                ("(" @open ")" @close)
+                ("[" @open "]" @close)
+                ("\"" @open "\"" @close)
+                "#})
+            .unwrap(),
+            Default::default(),
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {r#"
+            fn main() {
+                foo(bar("1"), [2]);ˇ
+            }
+        "#});
+
+        let marked_depths = cx.update_editor(|editor, cx| {
+            editor.set_visible_line_count(10., cx);
+            refresh_rainbow_bracket_highlights(editor, cx);
+
+            let snapshot = editor.snapshot(cx);
+            let text = snapshot.buffer_snapshot.text();
+            (0..3)
+                .map(|depth| {
+                    let ranges = snapshot
+                        .display_snapshot
+                        .text_highlight_ranges_for_key(HighlightKey::TypePlus(
+                            TypeId::of::<RainbowBracketHighlight>(),
+                            depth,
+                        ))
+                        .map(|highlights| {
+                            highlights
+                                .1
+                                .iter()
+                                .map(|range| {
+                                    range.start.to_offset(&snapshot.buffer_snapshot)
+                                        ..range.end.to_offset(&snapshot.buffer_snapshot)
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    generate_marked_text(&text, &ranges, false)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(
+            marked_depths,
+            [
+                indoc! {r#"
+                    fn main«(»«)» «{»
+                        foo(bar("1"), [2]);
+                    «}»
+                "#},
+                indoc! {r#"
+                    fn main() {
+                        foo«(»bar("1"), [2]«)»;
+                    }
+                "#},
+                indoc! {r#"
+                    fn main() {
+                        foo(bar«(»"1"«)», «[»2«]»);
+                    }
+                "#},
+            ]
+        );
+
+        // Disabling the setting removes the highlights.
+        cx.update(|cx| {
+            cx.update_global::<settings::SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<language::language_settings::AllLanguageSettings>(
+                    cx,
+                    |settings| settings.defaults.rainbow_brackets = Some(false),
+                );
+            });
+        });
+        cx.update_editor(|editor, cx| {
+            refresh_rainbow_bracket_highlights(editor, cx);
+            let snapshot = editor.snapshot(cx);
+            assert!(snapshot
+                .display_snapshot
+                .text_highlight_ranges_for_key(HighlightKey::TypePlus(
+                    TypeId::of::<RainbowBracketHighlight>(),
+                    0,
+                ))
+                .is_none());
+        });
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{
+    actions::{ChangeSurroundingPair, DeleteSurroundingPair, SurroundSelections},
+    scroll::Autoscroll,
+    Editor,
+};
+use collections::HashSet;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, View, ViewContext,
+    WeakView,
+};
+use language::{LanguageScope, Selection, SelectionGoal};
+use std::ops::Range;
+use ui::prelude::*;
+use workspace::ModalView;
+
+/// The pairs that can be named by either side when the language doesn't define them.
+const COMMON_PAIRS: &[(&str, &str)] = &[
+    ("(", ")"),
+    ("[", "]"),
+    ("{", "}"),
+    ("<", ">"),
+    ("\"", "\""),
+    ("'", "'"),
+    ("`", "`"),
+];
+
+/// Returns the opening and closing text of the pair that `text` names: either side
+/// of one of the language's brackets, an opening tag like `<div class="a">`, or any
+/// other text, which is used on both sides.
+fn surround_pair(text: &str, scope: Option<&LanguageScope>) -> (String, String) {
+    if let Some(tag) = text.strip_prefix('<').and_then(|tag| tag.strip_suffix('>')) {
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if !name.is_empty() && !name.starts_with('/') {
+            return (text.to_string(), format!("</{name}>"));
+        }
+    }
+
+    scope
+        .into_iter()
+        .flat_map(|scope| scope.brackets())
+        .map(|(pair, _)| (pair.start.clone(), pair.end.clone()))
+        .chain(
+            COMMON_PAIRS
+                .iter()
+                .map(|(start, end)| (start.to_string(), end.to_string())),
+        )
+        .find(|(start, end)| start == text || end == text)
+        .unwrap_or_else(|| (text.to_string(), text.to_string()))
+}
+
+#[derive(Clone)]
+enum SurroundOperation {
+    Surround,
+    Change { from: Option<String> },
+}
+
+impl Editor {
+    pub fn surround_selections(&mut self, action: &SurroundSelections, cx: &mut ViewContext<Self>) {
+        match &action.text {
+            Some(text) => self.surround_selections_with(text, cx),
+            None => self.prompt_for_pair(SurroundOperation::Surround, cx),
+        }
+    }
+
+    pub fn change_surrounding_pair(
+        &mut self,
+        action: &ChangeSurroundingPair,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match &action.to {
+            Some(to) => self.replace_surrounding_pairs(action.from.as_deref(), to, cx),
+            None => {
+                let from = action.from.clone();
+                self.prompt_for_pair(SurroundOperation::Change { from }, cx)
+            }
+        }
+    }
+
+    pub fn delete_surrounding_pair(
+        &mut self,
+        action: &DeleteSurroundingPair,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.replace_surrounding_pairs(action.text.as_deref(), "", cx);
+    }
+
+    /// Surrounds each selection with the pair that `text` names. Empty selections
+    /// surround the word they're in.
+    pub fn surround_selections_with(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        if text.is_empty() || self.read_only(cx) {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut last_range: Option<Range<usize>> = None;
+        let mut delta = 0;
+        for selection in self.selections.all::<usize>(cx) {
+            let mut range = selection.range();
+            if range.is_empty() {
+                range = snapshot.surrounding_word(range.start).0;
+            }
+            // Cursors in the same word surround it once.
+            if last_range.as_ref() == Some(&range) {
+                continue;
+            }
+
+            let (start, end) =
+                surround_pair(text, snapshot.language_scope_at(range.start).as_ref());
+            let new_start = range.start + delta + start.len();
+            delta += start.len();
+            let new_end = range.end + delta;
+            delta += end.len();
+            edits.push((range.start..range.start, start));
+            edits.push((range.end..range.end, end));
+            new_selections.push(Selection {
+                id: selection.id,
+                start: new_start,
+                end: new_end,
+                reversed: selection.reversed,
+                goal: SelectionGoal::None,
+            });
+            last_range = Some(range);
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
+    /// Replaces the innermost pair of brackets or quotes around each selection with
+    /// the pair that `to` names, or removes it when `to` is empty. When `from` is
+    /// given, only pairs that it names are replaced.
+    pub fn replace_surrounding_pairs(
+        &mut self,
+        from: Option<&str>,
+        to: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+        let mut edits = Vec::new();
+        let mut replaced_pairs = HashSet::default();
+        for selection in &selections {
+            let scope = snapshot.language_scope_at(selection.start);
+            let from_pair = from.map(|from| surround_pair(from, scope.as_ref()));
+            let is_from_pair = |open: Range<usize>, close: Range<usize>| {
+                from_pair.as_ref().map_or(true, |(start, end)| {
+                    snapshot.text_for_range(open).collect::<String>() == *start
+                        && snapshot.text_for_range(close).collect::<String>() == *end
+                })
+            };
+            let Some((open, close)) =
+                snapshot.innermost_enclosing_bracket_ranges(selection.range(), Some(&is_from_pair))
+            else {
+                continue;
+            };
+            if !replaced_pairs.insert(open.start) {
+                continue;
+            }
+
+            let (start, end) = if to.is_empty() {
+                (String::new(), String::new())
+            } else {
+                surround_pair(to, scope.as_ref())
+            };
+            edits.push((open, start));
+            edits.push((close, end));
+        }
+        if edits.is_empty() {
+            return;
+        }
+        edits.sort_by_key(|(range, _)| range.start);
+
+        // The selections are inside the pairs, so they only move by the edits before them.
+        let adjust_offset = |offset: usize| {
+            edits
+                .iter()
+                .filter(|(range, _)| range.end <= offset)
+                .fold(offset, |offset, (range, text)| {
+                    offset + text.len() - range.len()
+                })
+        };
+        let new_selections = selections
+            .iter()
+            .map(|selection| selection.map(adjust_offset))
+            .collect::<Vec<_>>();
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
+    fn prompt_for_pair(&mut self, operation: SurroundOperation, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let editor = cx.view().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| SurroundPrompt::new(editor, operation, cx));
+        });
+    }
+}
+
+/// Asks for the pair to surround the selections with, or to replace their
+/// surrounding pairs with, like `(`, `"` or `<div>`.
+struct SurroundPrompt {
+    editor: WeakView<Editor>,
+    operation: SurroundOperation,
+    prompt_editor: View<Editor>,
+}
+
+impl ModalView for SurroundPrompt {}
+
+impl EventEmitter<DismissEvent> for SurroundPrompt {}
+
+impl FocusableView for SurroundPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.prompt_editor.focus_handle(cx)
+    }
+}
+
+impl SurroundPrompt {
+    fn new(
+        editor: WeakView<Editor>,
+        operation: SurroundOperation,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self {
+            editor,
+            operation,
+            prompt_editor: cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("A bracket, quote or tag, like ( or <div>", cx);
+                editor
+            }),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let text = self.prompt_editor.read(cx).text(cx);
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        self.editor
+            .update(cx, |editor, cx| match &self.operation {
+                SurroundOperation::Surround => editor.surround_selections_with(text, cx),
+                SurroundOperation::Change { from } => {
+                    editor.replace_surrounding_pairs(from.as_deref(), text, cx)
+                }
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SurroundPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = match self.operation {
+            SurroundOperation::Surround => "Surround With",
+            SurroundOperation::Change { .. } => "Change Surrounding Pair To",
+        };
+        v_flex()
+            .key_context("SurroundPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(24.))
+            .p_2()
+            .gap_1()
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(self.prompt_editor.clone())
+    }
+}
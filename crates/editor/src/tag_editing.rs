@@ -0,0 +1,122 @@
+use crate::Editor;
+use futures::future::join_all;
+use gpui::ViewContext;
+use language::Point;
+use multi_buffer::{MultiBufferSnapshot, ToOffset, ToPoint};
+use std::{ops::Range, sync::Arc};
+
+/// Returns the edits that keep the names of an element's opening and closing tags
+/// the same, by repeating the given edits of one tag's name on the other's.
+pub(crate) fn linked_tag_edits(
+    edits: &[(Range<Point>, Arc<str>)],
+    snapshot: &MultiBufferSnapshot,
+) -> Vec<(Range<Point>, Arc<str>)> {
+    let mut linked_edits = Vec::<(Range<Point>, Arc<str>)>::new();
+    for (range, text) in edits {
+        // Typing a space after a tag's name starts its attributes, rather than
+        // renaming it.
+        if !text.chars().all(is_tag_name_char) {
+            continue;
+        }
+
+        let range = range.start.to_offset(snapshot)..range.end.to_offset(snapshot);
+        let Some(excerpt) = snapshot.excerpt_containing(range.clone()) else {
+            continue;
+        };
+        let buffer_range = excerpt.map_range_to_buffer(range);
+        let Some((name, linked_name)) = excerpt.buffer().linked_tag_names(buffer_range.clone())
+        else {
+            continue;
+        };
+        if !excerpt.contains_buffer_range(linked_name.clone()) {
+            continue;
+        }
+
+        let linked_range = excerpt.map_range_from_buffer(
+            linked_name.start + (buffer_range.start - name.start)
+                ..linked_name.start + (buffer_range.end - name.start),
+        );
+        let linked_range =
+            linked_range.start.to_point(snapshot)..linked_range.end.to_point(snapshot);
+        // Leave tags alone that are being edited already, e.g. with multiple cursors.
+        let is_edited = edits
+            .iter()
+            .chain(&linked_edits)
+            .any(|(range, _)| range.start <= linked_range.end && linked_range.start <= range.end);
+        if !is_edited {
+            linked_edits.push((linked_range, text.clone()));
+        }
+    }
+    linked_edits
+}
+
+fn is_tag_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
+}
+
+impl Editor {
+    /// Inserts closing tags after the opening tags that were just finished by typing
+    /// `>`, once the buffers have been reparsed. Nothing is inserted if the editor
+    /// was edited again in the meantime.
+    pub(crate) fn close_tags_after_input(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.use_autoclose {
+            return;
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+        let parsing_idle = join_all(
+            self.selections
+                .disjoint_anchors()
+                .iter()
+                .filter_map(|selection| multi_buffer.text_anchor_for_position(selection.head(), cx))
+                .map(|(buffer, _)| buffer.read(cx).parsing_idle())
+                .collect::<Vec<_>>(),
+        );
+        let edit_count = multi_buffer.snapshot(cx).edit_count();
+
+        cx.spawn(|editor, mut cx| async move {
+            parsing_idle.await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer.read(cx).snapshot(cx);
+                    if snapshot.edit_count() != edit_count {
+                        return;
+                    }
+
+                    let selections = editor.selections.all::<usize>(cx);
+                    let mut edits = Vec::new();
+                    for selection in &selections {
+                        let offset = selection.head();
+                        if !selection.is_empty() || !snapshot.settings_at(offset, cx).use_autoclose
+                        {
+                            continue;
+                        }
+                        let Some(excerpt) = snapshot.excerpt_containing(offset..offset) else {
+                            continue;
+                        };
+                        let buffer_offset = excerpt.map_offset_to_buffer(offset);
+                        if let Some(name) = excerpt.buffer().unclosed_tag_ending_at(buffer_offset) {
+                            edits.push((offset..offset, format!("</{name}>")));
+                        }
+                    }
+                    if edits.is_empty() {
+                        return;
+                    }
+
+                    // Keep the cursors in front of the closing tags.
+                    let selections = selections
+                        .into_iter()
+                        .map(|selection| selection.map(|offset| snapshot.anchor_before(offset)))
+                        .collect::<Vec<_>>();
+                    editor.transact(cx, |editor, cx| {
+                        editor
+                            .buffer
+                            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+                        editor.change_selections(None, cx, |s| s.select_anchors(selections));
+                    });
+                })
+                .ok();
+        })
+        .detach();
+    }
+}
@@ -0,0 +1,139 @@
+use crate::compare_versions;
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use fs::{Fs, RemoveOptions};
+use futures::StreamExt;
+use std::{io::Read as _, path::PathBuf, sync::Arc};
+
+/// The number of archives kept for each extension, including the archive of
+/// the installed version.
+const MAX_ARCHIVES_PER_EXTENSION: usize = 3;
+
+const ARCHIVE_EXTENSION: &str = ".tar.gz";
+
+/// A local store of the archives downloaded when installing extensions.
+///
+/// Keeping the archives around allows rolling back to a previously installed
+/// version of an extension without downloading it again.
+#[derive(Clone)]
+pub(crate) struct ExtensionArchives {
+    dir: PathBuf,
+    fs: Arc<dyn Fs>,
+}
+
+impl ExtensionArchives {
+    pub fn new(dir: PathBuf, fs: Arc<dyn Fs>) -> Self {
+        Self { dir, fs }
+    }
+
+    fn archive_path(&self, extension_id: &str, version: &str) -> PathBuf {
+        self.dir
+            .join(extension_id)
+            .join(format!("{version}{ARCHIVE_EXTENSION}"))
+    }
+
+    /// Stores the archive of the given extension version, and removes the
+    /// archives of the oldest versions of the extension.
+    ///
+    /// Returns the versions of the extension that remain archived.
+    pub async fn save(
+        &self,
+        extension_id: &str,
+        version: &str,
+        tar_gz_bytes: &[u8],
+    ) -> Result<Vec<Arc<str>>> {
+        self.fs.create_dir(&self.dir.join(extension_id)).await?;
+        let content = tar_gz_bytes;
+        futures::pin_mut!(content);
+        self.fs
+            .create_file_with(&self.archive_path(extension_id, version), content)
+            .await
+            .with_context(|| format!("failed to archive extension {extension_id} {version}"))?;
+
+        let mut versions = self.versions(extension_id).await?;
+        while versions.len() > MAX_ARCHIVES_PER_EXTENSION {
+            let oldest_version = versions.remove(0);
+            self.fs
+                .remove_file(
+                    &self.archive_path(extension_id, &oldest_version),
+                    RemoveOptions {
+                        recursive: false,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await?;
+        }
+        Ok(versions)
+    }
+
+    /// Returns the contents of the archive of the given extension version.
+    pub async fn load(&self, extension_id: &str, version: &str) -> Result<Vec<u8>> {
+        let path = self.archive_path(extension_id, version);
+        let mut file =
+            self.fs.open_sync(&path).await.with_context(|| {
+                format!("no archive found for extension {extension_id} {version}")
+            })?;
+        let mut tar_gz_bytes = Vec::new();
+        file.read_to_end(&mut tar_gz_bytes)?;
+        Ok(tar_gz_bytes)
+    }
+
+    /// Returns the archived versions of the given extension, from oldest to newest.
+    pub async fn versions(&self, extension_id: &str) -> Result<Vec<Arc<str>>> {
+        let extension_dir = self.dir.join(extension_id);
+        if !self.fs.is_dir(&extension_dir).await {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        let mut archive_paths = self.fs.read_dir(&extension_dir).await?;
+        while let Some(archive_path) = archive_paths.next().await {
+            let archive_path = archive_path?;
+            let Some(version) = archive_path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(|file_name| file_name.strip_suffix(ARCHIVE_EXTENSION))
+            else {
+                continue;
+            };
+            versions.push(Arc::<str>::from(version));
+        }
+        versions.sort_by(|a, b| compare_versions(a, b));
+        Ok(versions)
+    }
+
+    /// Returns the archived versions of all extensions.
+    pub async fn all_versions(&self) -> Result<BTreeMap<Arc<str>, Vec<Arc<str>>>> {
+        let mut all_versions = BTreeMap::default();
+        if !self.fs.is_dir(&self.dir).await {
+            return Ok(all_versions);
+        }
+
+        let mut extension_dirs = self.fs.read_dir(&self.dir).await?;
+        while let Some(extension_dir) = extension_dirs.next().await {
+            let extension_dir = extension_dir?;
+            let Some(extension_id) = extension_dir.file_name().and_then(|name| name.to_str())
+            else {
+                continue;
+            };
+            let versions = self.versions(extension_id).await?;
+            if !versions.is_empty() {
+                all_versions.insert(extension_id.into(), versions);
+            }
+        }
+        Ok(all_versions)
+    }
+
+    /// Removes the archives of all versions of the given extension.
+    pub async fn remove(&self, extension_id: &str) -> Result<()> {
+        self.fs
+            .remove_dir(
+                &self.dir.join(extension_id),
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await
+    }
+}
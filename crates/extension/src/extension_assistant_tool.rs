@@ -0,0 +1,209 @@
+use crate::{
+    extension_manifest::AssistantToolManifestEntry, wasm_host::WasmExtension, ExtensionManifest,
+};
+use anyhow::{anyhow, bail, Result};
+use assistant_tooling::{LanguageModelTool, ToolFunctionDefinition};
+use futures::FutureExt;
+use gpui::{div, AnyElement, AppContext, Element, Model, ParentElement, Task, WindowContext};
+use project::{Project, Worktree};
+use schemars::schema_for;
+use serde_json::json;
+use std::sync::Arc;
+use wasmtime_wasi::WasiView as _;
+
+/// The input property through which the language model picks the worktree
+/// that a tool runs in.
+const WORKTREE_PROPERTY: &str = "worktree_root_name";
+
+/// An assistant tool provided by an extension.
+///
+/// Calls to the tool are forwarded to the extension, which is given access
+/// to one visible worktree of the project. When several worktrees are open,
+/// the language model picks one by its root name.
+pub struct ExtensionAssistantTool {
+    pub(crate) extension: WasmExtension,
+    pub(crate) tool_id: Arc<str>,
+    pub(crate) manifest_entry: AssistantToolManifestEntry,
+    pub(crate) project: Model<Project>,
+}
+
+impl LanguageModelTool for ExtensionAssistantTool {
+    type Input = serde_json::Value;
+    type Output = String;
+
+    fn name(&self) -> String {
+        self.tool_id.to_string()
+    }
+
+    fn description(&self) -> String {
+        self.manifest_entry.description.clone()
+    }
+
+    fn definition(&self) -> ToolFunctionDefinition {
+        ToolFunctionDefinition {
+            name: self.name(),
+            description: self.description(),
+            parameters: serde_json::from_value(input_schema_with_worktree(
+                &self.manifest_entry.input_schema,
+            ))
+            .unwrap_or_else(|_| schema_for!(serde_json::Value)),
+        }
+    }
+
+    fn execute(&self, input: &Self::Input, cx: &AppContext) -> Task<Result<Self::Output>> {
+        let extension = self.extension.clone();
+        let tool_id = self.tool_id.clone();
+        let project = self.project.clone();
+        let mut input = input.clone();
+        let worktree_root_name = input
+            .as_object_mut()
+            .and_then(|input| input.remove(WORKTREE_PROPERTY))
+            .and_then(|name| name.as_str().map(str::to_string));
+        let input = input.to_string();
+
+        cx.spawn(|mut cx| async move {
+            let delegate = project.update(&mut cx, |project, cx| {
+                let worktree = tool_worktree(project, worktree_root_name.as_deref(), cx)?;
+                anyhow::Ok(project.worktree_delegate(&worktree, cx))
+            })??;
+
+            extension
+                .call(|extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(delegate)?;
+                        store.data_mut().running_assistant_tool = Some(tool_id.clone());
+                        let result = extension
+                            .call_run_assistant_tool(store, &tool_id, &input, resource)
+                            .await;
+                        store.data_mut().running_assistant_tool = None;
+                        result?.map_err(|error| anyhow!("{}", error))
+                    }
+                    .boxed()
+                })
+                .await
+        })
+    }
+
+    fn render(
+        _tool_call_id: &str,
+        _input: &Self::Input,
+        output: &Self::Output,
+        _cx: &mut WindowContext,
+    ) -> AnyElement {
+        div().child(output.clone()).into_any()
+    }
+
+    fn format(_input: &Self::Input, output: &Self::Output) -> String {
+        output.clone()
+    }
+}
+
+/// Adds the property for picking a worktree to the input schema of a tool.
+fn input_schema_with_worktree(input_schema: &serde_json::Value) -> serde_json::Value {
+    let mut input_schema = input_schema.clone();
+    if let Some(properties) = input_schema
+        .get_mut("properties")
+        .and_then(|properties| properties.as_object_mut())
+    {
+        properties.insert(
+            WORKTREE_PROPERTY.into(),
+            json!({
+                "type": "string",
+                "description": "The root name of the worktree to run the tool in. \
+                    May be omitted when the project has a single worktree.",
+            }),
+        );
+    }
+    input_schema
+}
+
+/// Returns the visible worktree with the given root name, or the only visible
+/// worktree when no name is given.
+fn tool_worktree(
+    project: &Project,
+    root_name: Option<&str>,
+    cx: &AppContext,
+) -> Result<Model<Worktree>> {
+    if let Some(root_name) = root_name {
+        return project
+            .visible_worktrees(cx)
+            .find(|worktree| worktree.read(cx).root_name() == root_name)
+            .ok_or_else(|| anyhow!("no worktree named {root_name:?} is open"));
+    }
+
+    let mut worktrees = project.visible_worktrees(cx);
+    let worktree = worktrees
+        .next()
+        .ok_or_else(|| anyhow!("no worktree is open"))?;
+    if worktrees.next().is_some() {
+        bail!("several worktrees are open, pick one with the {WORKTREE_PROPERTY} property");
+    }
+    Ok(worktree)
+}
+
+/// Checks whether an extension may run the given command.
+///
+/// Commands can only be run while one of the extension's assistant tools is
+/// running, and only when that tool declares the command in its manifest and
+/// the user has granted the command to the extension.
+pub(crate) fn check_command_allowed(
+    manifest: &ExtensionManifest,
+    running_tool: Option<&str>,
+    granted_commands: &[String],
+    command: &str,
+) -> Result<()> {
+    let Some(tool_id) = running_tool else {
+        bail!(
+            "extension {} can only run commands from an assistant tool",
+            manifest.id
+        );
+    };
+    let tool = manifest
+        .assistant_tools
+        .get(tool_id)
+        .ok_or_else(|| anyhow!("extension {} has no tool {tool_id}", manifest.id))?;
+    if !tool.allowed_commands.iter().any(|allowed| allowed == command) {
+        bail!("tool {tool_id} does not declare the command {command:?}");
+    }
+    if !granted_commands.iter().any(|granted| granted == command) {
+        bail!(
+            "the command {command:?} has not been granted to extension {}",
+            manifest.id
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_command_allowed() {
+    let manifest: ExtensionManifest = toml::from_str(
+        r#"
+        id = "my-extension"
+        name = "My Extension"
+        version = "1.0.0"
+        schema_version = 1
+
+        [assistant_tools.run-tests]
+        description = "Runs the tests"
+        input_schema = { type = "object" }
+        allowed_commands = ["cargo"]
+
+        [assistant_tools.list-files]
+        description = "Lists files"
+        input_schema = { type = "object" }
+        "#,
+    )
+    .unwrap();
+    let granted = ["cargo".to_string(), "sh".to_string()];
+
+    assert!(check_command_allowed(&manifest, Some("run-tests"), &granted, "cargo").is_ok());
+
+    // Commands can't be run outside of a tool call.
+    assert!(check_command_allowed(&manifest, None, &granted, "cargo").is_err());
+    // Each tool may only run the commands it declares.
+    assert!(check_command_allowed(&manifest, Some("list-files"), &granted, "cargo").is_err());
+    assert!(check_command_allowed(&manifest, Some("run-tests"), &granted, "sh").is_err());
+    assert!(check_command_allowed(&manifest, Some("unknown"), &granted, "cargo").is_err());
+    // Declared commands still need to be granted by the user.
+    assert!(check_command_allowed(&manifest, Some("run-tests"), &[], "cargo").is_err());
+}
@@ -13,7 +13,7 @@ use std::{
     process::{Command, Stdio},
     sync::Arc,
 };
-use util::http::{self, AsyncBody, HttpClient};
+use util::http::{AsyncBody, HttpClient};
 use wasm_encoder::{ComponentSectionId, Encode as _, RawSection, Section as _};
 use wasmparser::Parser;
 use wit_component::ComponentEncoder;
@@ -64,11 +64,10 @@ struct CargoTomlPackage {
 }
 
 impl ExtensionBuilder {
-    pub fn new(cache_dir: PathBuf) -> Self {
-        Self {
-            cache_dir,
-            http: http::client(),
-        }
+    /// Creates a builder that downloads its toolchains with the given client,
+    /// which should follow the user's network settings.
+    pub fn new(cache_dir: PathBuf, http: Arc<dyn HttpClient>) -> Self {
+        Self { cache_dir, http }
     }
 
     pub async fn compile_extension(
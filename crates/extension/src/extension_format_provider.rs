@@ -0,0 +1,54 @@
+use crate::wasm_host::{wit, WasmExtension};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::FutureExt;
+use language::{FormatProvider, LspAdapterDelegate, PROVIDER_TIMEOUT};
+use std::{ops::Range, path::Path, sync::Arc};
+use wasmtime_wasi::WasiView as _;
+
+/// A formatter provided by an extension.
+pub struct ExtensionFormatProvider {
+    pub(crate) extension: WasmExtension,
+    pub(crate) formatter_id: Arc<str>,
+}
+
+#[async_trait]
+impl FormatProvider for ExtensionFormatProvider {
+    fn name(&self) -> Arc<str> {
+        self.formatter_id.clone()
+    }
+
+    async fn format(
+        &self,
+        path: Arc<Path>,
+        text: String,
+        range: Option<Range<usize>>,
+        delegate: Arc<dyn LspAdapterDelegate>,
+    ) -> Result<String> {
+        let formatter_id = self.formatter_id.clone();
+        let range = range.map(|range| wit::Range {
+            start: range.start as u32,
+            end: range.end as u32,
+        });
+
+        self.extension
+            .call_with_timeout(PROVIDER_TIMEOUT, |extension, store| {
+                async move {
+                    let resource = store.data_mut().table().push(delegate)?;
+                    extension
+                        .call_format_document(
+                            store,
+                            &formatter_id,
+                            &path.to_string_lossy(),
+                            &text,
+                            range,
+                            resource,
+                        )
+                        .await?
+                        .map_err(|error| anyhow!("{}", error))
+                }
+                .boxed()
+            })
+            .await
+    }
+}
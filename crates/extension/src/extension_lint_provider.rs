@@ -0,0 +1,64 @@
+use crate::wasm_host::{wit, WasmExtension};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::FutureExt;
+use language::{LintDiagnostic, LintProvider, LspAdapterDelegate, PROVIDER_TIMEOUT};
+use std::{path::Path, sync::Arc};
+use wasmtime_wasi::WasiView as _;
+
+/// A linter provided by an extension.
+pub struct ExtensionLintProvider {
+    pub(crate) extension: WasmExtension,
+    pub(crate) linter_id: Arc<str>,
+}
+
+#[async_trait]
+impl LintProvider for ExtensionLintProvider {
+    fn name(&self) -> Arc<str> {
+        self.linter_id.clone()
+    }
+
+    async fn lint(
+        &self,
+        path: Arc<Path>,
+        text: String,
+        delegate: Arc<dyn LspAdapterDelegate>,
+    ) -> Result<Vec<LintDiagnostic>> {
+        let linter_id = self.linter_id.clone();
+
+        let diagnostics = self
+            .extension
+            .call_with_timeout(PROVIDER_TIMEOUT, |extension, store| {
+                async move {
+                    let resource = store.data_mut().table().push(delegate)?;
+                    extension
+                        .call_lint_document(
+                            store,
+                            &linter_id,
+                            &path.to_string_lossy(),
+                            &text,
+                            resource,
+                        )
+                        .await?
+                        .map_err(|error| anyhow!("{}", error))
+                }
+                .boxed()
+            })
+            .await?;
+
+        Ok(diagnostics
+            .into_iter()
+            .map(|diagnostic| LintDiagnostic {
+                range: diagnostic.range.start as usize..diagnostic.range.end as usize,
+                severity: match diagnostic.severity {
+                    wit::DiagnosticSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+                    wit::DiagnosticSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+                    wit::DiagnosticSeverity::Information => lsp::DiagnosticSeverity::INFORMATION,
+                    wit::DiagnosticSeverity::Hint => lsp::DiagnosticSeverity::HINT,
+                },
+                message: diagnostic.message,
+                code: diagnostic.code,
+            })
+            .collect())
+    }
+}
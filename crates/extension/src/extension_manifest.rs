@@ -69,11 +69,23 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub themes: Vec<PathBuf>,
     #[serde(default)]
+    pub icon_themes: Vec<PathBuf>,
+    #[serde(default)]
     pub languages: Vec<PathBuf>,
     #[serde(default)]
     pub grammars: BTreeMap<Arc<str>, GrammarManifestEntry>,
     #[serde(default)]
     pub language_servers: BTreeMap<LanguageServerName, LanguageServerManifestEntry>,
+    #[serde(default)]
+    pub panels: BTreeMap<Arc<str>, PanelManifestEntry>,
+    #[serde(default)]
+    pub assistant_tools: BTreeMap<Arc<str>, AssistantToolManifestEntry>,
+    #[serde(default)]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default)]
+    pub linters: BTreeMap<Arc<str>, LinterManifestEntry>,
+    #[serde(default)]
+    pub project_templates: Vec<PathBuf>,
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -110,6 +122,51 @@ pub struct LanguageServerManifestEntry {
     pub code_action_kinds: Option<Vec<lsp::CodeActionKind>>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct PanelManifestEntry {
+    /// The name of the panel, displayed in its tooltip.
+    pub name: String,
+    /// The name of the icon displayed in the status bar, e.g. `"github"`.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The dock in which the panel is initially displayed.
+    #[serde(default)]
+    pub position: PanelPosition,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelPosition {
+    Left,
+    #[default]
+    Right,
+    Bottom,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct AssistantToolManifestEntry {
+    /// The description of the tool, used to prompt the language model.
+    pub description: String,
+    /// The JSON schema of the input of the tool.
+    pub input_schema: serde_json::Value,
+    /// The commands the tool may run. Each of them must also be granted to the
+    /// extension by the user in the `granted_extension_commands` setting.
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {
+    /// The list of languages this formatter should be used for.
+    pub languages: Vec<Arc<str>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LinterManifestEntry {
+    /// The list of languages this linter should be run for.
+    pub languages: Vec<Arc<str>>,
+}
+
 impl LanguageServerManifestEntry {
     /// Returns the list of languages for the language server.
     ///
@@ -178,6 +235,7 @@ fn manifest_from_old_manifest(
             themes.dedup();
             themes
         },
+        icon_themes: Vec::new(),
         languages: {
             let mut languages = manifest_json.languages.into_values().collect::<Vec<_>>();
             languages.sort();
@@ -190,5 +248,10 @@ fn manifest_from_old_manifest(
             .map(|grammar_name| (grammar_name, Default::default()))
             .collect(),
         language_servers: Default::default(),
+        panels: Default::default(),
+        assistant_tools: Default::default(),
+        formatters: Default::default(),
+        linters: Default::default(),
+        project_templates: Vec::new(),
     }
 }
@@ -16,6 +16,22 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// The extensions that should be kept at a specific version.
+    ///
+    /// Pinned extensions are never updated automatically, and are reinstalled
+    /// at the pinned version if a different version is installed.
+    #[serde(default)]
+    pub pinned_extensions: HashMap<Arc<str>, Arc<str>>,
+    /// The extensions for which pre-release versions should be installed
+    /// when updating.
+    #[serde(default)]
+    pub pre_release_extensions: HashMap<Arc<str>, bool>,
+    /// The commands that the assistant tools of each extension may run.
+    ///
+    /// A tool can only run a command that it declares in its manifest once
+    /// the user has granted that command to the extension here.
+    #[serde(default)]
+    pub granted_extension_commands: HashMap<Arc<str>, Vec<String>>,
 }
 
 impl ExtensionSettings {
@@ -28,11 +44,35 @@ impl ExtensionSettings {
     }
 
     pub fn should_auto_update(&self, extension_id: &str) -> bool {
+        if self.pinned_version(extension_id).is_some() {
+            return false;
+        }
+
         self.auto_update_extensions
             .get(extension_id)
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns the version the given extension is pinned to, if any.
+    pub fn pinned_version(&self, extension_id: &str) -> Option<&Arc<str>> {
+        self.pinned_extensions.get(extension_id)
+    }
+
+    /// Returns whether pre-release versions of the given extension should be installed.
+    pub fn wants_pre_releases(&self, extension_id: &str) -> bool {
+        self.pre_release_extensions
+            .get(extension_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Returns the commands the user has granted to the given extension.
+    pub fn granted_commands(&self, extension_id: &str) -> &[String] {
+        self.granted_extension_commands
+            .get(extension_id)
+            .map_or(&[], Vec::as_slice)
+    }
 }
 
 impl Settings for ExtensionSettings {
@@ -1,4 +1,8 @@
+mod extension_archives;
+mod extension_assistant_tool;
 pub mod extension_builder;
+mod extension_format_provider;
+mod extension_lint_provider;
 mod extension_lsp_adapter;
 mod extension_manifest;
 mod extension_settings;
@@ -8,31 +12,38 @@ mod wasm_host;
 mod extension_store_test;
 
 use crate::extension_manifest::SchemaVersion;
-use crate::{extension_lsp_adapter::ExtensionLspAdapter, wasm_host::wit};
+use crate::{
+    extension_archives::ExtensionArchives, extension_format_provider::ExtensionFormatProvider,
+    extension_lint_provider::ExtensionLintProvider, extension_lsp_adapter::ExtensionLspAdapter,
+    wasm_host::wit,
+};
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
 use collections::{btree_map, BTreeMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+use file_icons::{FileIcons, IconTheme};
 use fs::{Fs, RemoveOptions};
 use futures::{
     channel::{
         mpsc::{unbounded, UnboundedSender},
         oneshot,
     },
+    future::join_all,
     io::BufReader,
     select_biased, AsyncReadExt as _, Future, FutureExt as _, StreamExt as _,
 };
 use gpui::{
-    actions, AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Task,
-    WeakModel,
+    actions, AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext,
+    SharedString, Task, WeakModel,
 };
 use language::{
     ContextProviderWithTasks, LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry,
     QUERY_FILENAME_PREFIXES,
 };
 use node_runtime::NodeRuntime;
+use project::Project;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -57,10 +68,16 @@ use wasm_host::{
     WasmExtension, WasmHost,
 };
 
+pub use extension_assistant_tool::ExtensionAssistantTool;
 pub use extension_manifest::{
-    ExtensionLibraryKind, ExtensionManifest, GrammarManifestEntry, OldExtensionManifest,
+    AssistantToolManifestEntry, ExtensionLibraryKind, ExtensionManifest, FormatterManifestEntry,
+    GrammarManifestEntry, LinterManifestEntry, OldExtensionManifest, PanelManifestEntry,
+    PanelPosition,
 };
 pub use extension_settings::ExtensionSettings;
+pub use wasm_host::wit::{
+    InputChanged, UiButton, UiElement, UiEvent, UiLabel, UiLabelColor, UiListItem, UiTextInput,
+};
 
 const RELOAD_DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
 const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
@@ -94,6 +111,31 @@ pub fn is_version_compatible(extension_version: &ExtensionMetadata) -> bool {
     true
 }
 
+/// Returns whether the given extension version is a pre-release, e.g. `1.2.0-beta.1`.
+pub fn is_pre_release_version(version: &str) -> bool {
+    version.contains('-')
+}
+
+/// Compares two extension versions.
+///
+/// A pre-release is ordered before the release with the same version number.
+/// Versions that cannot be parsed are ordered before all other versions.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn sort_key(version: &str) -> Option<(SemanticVersion, bool, &str)> {
+        let (release, pre_release) = match version.split_once('-') {
+            Some((release, pre_release)) => (release, Some(pre_release)),
+            None => (version, None),
+        };
+        Some((
+            SemanticVersion::from_str(release).ok()?,
+            pre_release.is_none(),
+            pre_release.unwrap_or_default(),
+        ))
+    }
+
+    sort_key(a).cmp(&sort_key(b))
+}
+
 pub struct ExtensionStore {
     builder: Arc<ExtensionBuilder>,
     extension_index: ExtensionIndex,
@@ -103,10 +145,15 @@ pub struct ExtensionStore {
     reload_tx: UnboundedSender<Option<Arc<str>>>,
     reload_complete_senders: Vec<oneshot::Sender<()>>,
     installed_dir: PathBuf,
+    archives: ExtensionArchives,
+    archived_versions: BTreeMap<Arc<str>, Vec<Arc<str>>>,
+    archived_versions_loaded: bool,
     outstanding_operations: BTreeMap<Arc<str>, ExtensionOperation>,
     index_path: PathBuf,
     language_registry: Arc<LanguageRegistry>,
     theme_registry: Arc<ThemeRegistry>,
+    /// The names of the icon themes loaded from each extension.
+    icon_themes: BTreeMap<Arc<str>, Vec<SharedString>>,
     modified_extensions: HashSet<Arc<str>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
@@ -126,6 +173,7 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    PanelsUpdated,
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -161,7 +209,7 @@ pub struct ExtensionIndexLanguageEntry {
     grammar: Option<Arc<str>>,
 }
 
-actions!(zed, [ReloadExtensions]);
+actions!(zed, [ReloadExtensions, UpdateAllExtensions]);
 
 pub fn init(
     fs: Arc<fs::RealFs>,
@@ -192,6 +240,11 @@ pub fn init(
         store.update(cx, |store, cx| drop(store.reload(None, cx)));
     });
 
+    cx.on_action(|_: &UpdateAllExtensions, cx| {
+        let store = cx.global::<GlobalExtensionStore>().0.clone();
+        store.update(cx, |store, cx| store.update_all_extensions(cx));
+    });
+
     cx.set_global(GlobalExtensionStore(store));
 }
 
@@ -220,15 +273,20 @@ impl ExtensionStore {
         let work_dir = extensions_dir.join("work");
         let build_dir = build_dir.unwrap_or_else(|| extensions_dir.join("build"));
         let installed_dir = extensions_dir.join("installed");
+        let archives_dir = extensions_dir.join("archives");
         let index_path = extensions_dir.join("index.json");
 
         let (reload_tx, mut reload_rx) = unbounded();
         let mut this = Self {
             extension_index: Default::default(),
             installed_dir,
+            archives: ExtensionArchives::new(archives_dir, fs.clone()),
+            archived_versions: Default::default(),
+            archived_versions_loaded: false,
             index_path,
-            builder: Arc::new(ExtensionBuilder::new(build_dir)),
+            builder: Arc::new(ExtensionBuilder::new(build_dir, http_client.clone())),
             outstanding_operations: Default::default(),
+            icon_themes: Default::default(),
             modified_extensions: Default::default(),
             reload_complete_senders: Vec::new(),
             wasm_host: WasmHost::new(
@@ -413,6 +471,115 @@ impl ExtensionStore {
             .filter_map(|(name, theme)| theme.extension.as_ref().eq(extension_id).then_some(name))
     }
 
+    /// Returns the directories of the project templates provided by installed extensions.
+    pub fn project_template_dirs(&self) -> Vec<PathBuf> {
+        self.extension_index
+            .extensions
+            .iter()
+            .flat_map(|(extension_id, extension)| {
+                extension
+                    .manifest
+                    .project_templates
+                    .iter()
+                    .map(|template_path| {
+                        let mut path = self.installed_dir.clone();
+                        path.extend([Path::new(extension_id.as_ref()), template_path.as_path()]);
+                        path
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the panels provided by the loaded extensions, as
+    /// `(extension_id, panel_id, panel)` tuples.
+    pub fn extension_panels(
+        &self,
+    ) -> impl Iterator<Item = (&Arc<str>, &Arc<str>, &PanelManifestEntry)> {
+        self.wasm_extensions.iter().flat_map(|(manifest, _)| {
+            manifest
+                .panels
+                .iter()
+                .map(move |(panel_id, panel)| (&manifest.id, panel_id, panel))
+        })
+    }
+
+    /// Returns the assistant tools provided by the loaded extensions, operating
+    /// on the given project.
+    pub fn assistant_tools(&self, project: &Model<Project>) -> Vec<ExtensionAssistantTool> {
+        self.wasm_extensions
+            .iter()
+            .flat_map(|(manifest, wasm_extension)| {
+                manifest
+                    .assistant_tools
+                    .iter()
+                    .map(|(tool_id, manifest_entry)| ExtensionAssistantTool {
+                        extension: wasm_extension.clone(),
+                        tool_id: tool_id.clone(),
+                        manifest_entry: manifest_entry.clone(),
+                        project: project.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Asks the given extension for the elements to display in one of its panels.
+    pub fn render_extension_panel(
+        &self,
+        extension_id: &str,
+        panel_id: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<UiElement>>> {
+        let Some(wasm_extension) = self.wasm_extension(extension_id) else {
+            return Task::ready(Err(anyhow!("extension {extension_id} is not loaded")));
+        };
+        cx.spawn(|_, _| async move {
+            wasm_extension
+                .call(|extension, store| {
+                    async move {
+                        extension
+                            .call_render_panel(store, &panel_id)
+                            .await?
+                            .map_err(|error| anyhow!("{}", error))
+                    }
+                    .boxed()
+                })
+                .await
+        })
+    }
+
+    /// Forwards an interaction with one of its panels to the given extension.
+    pub fn handle_extension_panel_event(
+        &self,
+        extension_id: &str,
+        panel_id: Arc<str>,
+        event: UiEvent,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(wasm_extension) = self.wasm_extension(extension_id) else {
+            return Task::ready(Err(anyhow!("extension {extension_id} is not loaded")));
+        };
+        cx.spawn(|_, _| async move {
+            wasm_extension
+                .call(|extension, store| {
+                    async move {
+                        extension
+                            .call_handle_panel_event(store, &panel_id, &event)
+                            .await?
+                            .map_err(|error| anyhow!("{}", error))
+                    }
+                    .boxed()
+                })
+                .await
+        })
+    }
+
+    fn wasm_extension(&self, extension_id: &str) -> Option<WasmExtension> {
+        self.wasm_extensions
+            .iter()
+            .find(|(manifest, _)| manifest.id.as_ref() == extension_id)
+            .map(|(_, wasm_extension)| wasm_extension.clone())
+    }
+
     pub fn fetch_extensions(
         &self,
         search: Option<&str>,
@@ -431,15 +598,33 @@ impl ExtensionStore {
         &mut self,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
-        let schema_versions = schema_version_range();
-        let wasm_api_versions = wasm_api_version_range();
         let extension_settings = ExtensionSettings::get_global(cx);
         let extension_ids = self
             .extension_index
             .extensions
             .keys()
-            .map(|id| id.as_ref())
             .filter(|id| extension_settings.should_auto_update(id))
+            .cloned()
+            .collect();
+        self.fetch_updates(extension_ids, cx)
+    }
+
+    /// Fetches the newest versions of the given extensions that differ from
+    /// their installed versions.
+    fn fetch_updates(
+        &mut self,
+        extension_ids: Vec<Arc<str>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ExtensionMetadata>>> {
+        let schema_versions = schema_version_range();
+        let wasm_api_versions = wasm_api_version_range();
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let (pre_release_ids, release_ids): (Vec<_>, Vec<_>) = extension_ids
+            .into_iter()
+            .partition(|id| extension_settings.wants_pre_releases(id));
+        let release_ids = release_ids
+            .iter()
+            .map(|id| id.as_ref())
             .collect::<Vec<_>>()
             .join(",");
         let task = self.fetch_extensions_from_api(
@@ -452,12 +637,31 @@ impl ExtensionStore {
                     &wasm_api_versions.start().to_string(),
                 ),
                 ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
-                ("ids", &extension_ids),
+                ("ids", &release_ids),
             ],
             cx,
         );
+        // The updates endpoint only knows about the latest version of each extension,
+        // so the newest pre-release is picked from all of the versions of an extension.
+        let pre_release_tasks = pre_release_ids
+            .iter()
+            .map(|extension_id| self.fetch_extension_versions(extension_id, cx))
+            .collect::<Vec<_>>();
         cx.spawn(move |this, mut cx| async move {
-            let extensions = task.await?;
+            let mut extensions = task.await?;
+            extensions.retain(|extension| !is_pre_release_version(&extension.manifest.version));
+            for versions in join_all(pre_release_tasks).await {
+                let Some(versions) = versions.log_err() else {
+                    continue;
+                };
+                extensions.extend(
+                    versions
+                        .into_iter()
+                        .filter(is_version_compatible)
+                        .max_by(|a, b| compare_versions(&a.manifest.version, &b.manifest.version)),
+                );
+            }
+
             this.update(&mut cx, |this, _cx| {
                 extensions
                     .into_iter()
@@ -515,6 +719,8 @@ impl ExtensionStore {
     }
 
     pub fn check_for_updates(&mut self, cx: &mut ModelContext<Self>) {
+        self.install_pinned_versions(cx);
+
         let task = self.fetch_extensions_with_update_available(cx);
         cx.spawn(move |this, mut cx| async move {
             Self::upgrade_extensions(this, task.await?, &mut cx).await
@@ -522,6 +728,54 @@ impl ExtensionStore {
         .detach();
     }
 
+    /// Updates all installed extensions that are not pinned to a specific version,
+    /// including the ones that are not updated automatically.
+    pub fn update_all_extensions(&mut self, cx: &mut ModelContext<Self>) {
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let extension_ids = self
+            .extension_index
+            .extensions
+            .iter()
+            .filter(|(id, extension)| {
+                !extension.dev && extension_settings.pinned_version(id).is_none()
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let task = self.fetch_updates(extension_ids, cx);
+        cx.spawn(move |this, mut cx| async move {
+            Self::upgrade_extensions(this, task.await?, &mut cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Installs the pinned version of every extension for which a different
+    /// version is installed.
+    fn install_pinned_versions(&mut self, cx: &mut ModelContext<Self>) {
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let extensions_to_install = self
+            .extension_index
+            .extensions
+            .iter()
+            .filter(|(_, extension)| !extension.dev)
+            .filter_map(|(id, extension)| {
+                let pinned_version = extension_settings.pinned_version(id)?;
+                (*pinned_version != extension.manifest.version)
+                    .then(|| (id.clone(), pinned_version.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        for (extension_id, version) in extensions_to_install {
+            self.install_or_upgrade_extension(
+                extension_id,
+                version,
+                ExtensionOperation::Upgrade,
+                cx,
+            )
+            .detach_and_log_err(cx);
+        }
+    }
+
     async fn upgrade_extensions(
         this: WeakModel<Self>,
         extensions: Vec<ExtensionMetadata>,
@@ -532,12 +786,11 @@ impl ExtensionStore {
                 if let Some(installed_extension) =
                     this.extension_index.extensions.get(&extension.id)
                 {
-                    let installed_version =
-                        SemanticVersion::from_str(&installed_extension.manifest.version).ok()?;
-                    let latest_version =
-                        SemanticVersion::from_str(&extension.manifest.version).ok()?;
-
-                    if installed_version >= latest_version {
+                    let ordering = compare_versions(
+                        &installed_extension.manifest.version,
+                        &extension.manifest.version,
+                    );
+                    if ordering.is_ge() {
                         return None;
                     }
                 }
@@ -602,9 +855,7 @@ impl ExtensionStore {
         operation: ExtensionOperation,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
-        let extension_dir = self.installed_dir.join(extension_id.as_ref());
         let http_client = self.http_client.clone();
-        let fs = self.fs.clone();
 
         match self.outstanding_operations.entry(extension_id.clone()) {
             btree_map::Entry::Occupied(_) => return Task::ready(Ok(())),
@@ -631,15 +882,6 @@ impl ExtensionStore {
                 .await
                 .map_err(|err| anyhow!("error downloading extension: {}", err))?;
 
-            fs.remove_dir(
-                &extension_dir,
-                RemoveOptions {
-                    recursive: true,
-                    ignore_if_not_exists: true,
-                },
-            )
-            .await?;
-
             let content_length = response
                 .headers()
                 .get(isahc::http::header::CONTENT_LENGTH)
@@ -655,28 +897,115 @@ impl ExtensionStore {
                     bail!("downloaded extension size {actual_len} does not match content length {content_length}");
                 }
             }
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(extension_dir).await?;
-            this.update(&mut cx, |this, cx| {
-                this.reload(Some(extension_id.clone()), cx)
-            })?
-            .await;
 
-            match operation {
-                ExtensionOperation::Install => {
-                    this.update(&mut cx, |_, cx| {
-                        cx.emit(Event::ExtensionInstalled(extension_id));
+            Self::install_extension_archive(&this, extension_id, tar_gz_bytes, operation, &mut cx)
+                .await
+        })
+    }
+
+    /// Installs the given version of an extension from its local archive.
+    fn install_archived_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        version: Arc<str>,
+        operation: ExtensionOperation,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        log::info!("installing extension {extension_id} {version} from archive");
+        let archives = self.archives.clone();
+
+        match self.outstanding_operations.entry(extension_id.clone()) {
+            btree_map::Entry::Occupied(_) => return Task::ready(Ok(())),
+            btree_map::Entry::Vacant(e) => e.insert(operation),
+        };
+        cx.notify();
+
+        cx.spawn(move |this, mut cx| async move {
+            let _finish = util::defer({
+                let this = this.clone();
+                let mut cx = cx.clone();
+                let extension_id = extension_id.clone();
+                move || {
+                    this.update(&mut cx, |this, cx| {
+                        this.outstanding_operations.remove(extension_id.as_ref());
+                        cx.notify();
                     })
                     .ok();
                 }
-                _ => {}
-            }
+            });
 
-            anyhow::Ok(())
+            let tar_gz_bytes = archives.load(&extension_id, &version).await?;
+            Self::install_extension_archive(&this, extension_id, tar_gz_bytes, operation, &mut cx)
+                .await
         })
     }
 
+    /// Replaces the installed extension with the contents of the given archive,
+    /// and keeps the archive around so that the extension can be rolled back to
+    /// this version later.
+    async fn install_extension_archive(
+        this: &WeakModel<Self>,
+        extension_id: Arc<str>,
+        tar_gz_bytes: Vec<u8>,
+        operation: ExtensionOperation,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let (fs, extension_dir, archives) = this.update(cx, |this, _| {
+            (
+                this.fs.clone(),
+                this.installed_dir.join(extension_id.as_ref()),
+                this.archives.clone(),
+            )
+        })?;
+
+        fs.remove_dir(
+            &extension_dir,
+            RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: true,
+            },
+        )
+        .await?;
+
+        let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
+        let archive = Archive::new(decompressed_bytes);
+        archive.unpack(extension_dir).await?;
+        this.update(cx, |this, cx| this.reload(Some(extension_id.clone()), cx))?
+            .await;
+
+        let installed_version = this.update(cx, |this, _| {
+            this.extension_index
+                .extensions
+                .get(&extension_id)
+                .map(|extension| extension.manifest.version.clone())
+        })?;
+        if let Some(installed_version) = installed_version {
+            if let Some(archived_versions) = archives
+                .save(&extension_id, &installed_version, &tar_gz_bytes)
+                .await
+                .log_err()
+            {
+                this.update(cx, |this, cx| {
+                    this.archived_versions
+                        .insert(extension_id.clone(), archived_versions);
+                    cx.notify();
+                })?;
+            }
+        }
+
+        match operation {
+            ExtensionOperation::Install => {
+                this.update(cx, |_, cx| {
+                    cx.emit(Event::ExtensionInstalled(extension_id));
+                })
+                .ok();
+            }
+            _ => {}
+        }
+
+        anyhow::Ok(())
+    }
+
     pub fn install_latest_extension(
         &mut self,
         extension_id: Arc<str>,
@@ -731,6 +1060,14 @@ impl ExtensionStore {
         operation: ExtensionOperation,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
+        let is_archived = self
+            .archived_versions
+            .get(&extension_id)
+            .map_or(false, |versions| versions.contains(&version));
+        if is_archived {
+            return self.install_archived_extension(extension_id, version, operation, cx);
+        }
+
         log::info!("installing extension {extension_id} {version}");
         let Some(url) = self
             .http_client
@@ -746,9 +1083,67 @@ impl ExtensionStore {
         self.install_or_upgrade_extension_at_endpoint(extension_id, url, operation, cx)
     }
 
+    /// Loads the versions of the extensions that are archived locally.
+    ///
+    /// The archives aren't read on startup, to keep the number of file system
+    /// calls down, so this must be called before rolling extensions back.
+    pub fn load_archived_versions(&mut self, cx: &mut ModelContext<Self>) {
+        if self.archived_versions_loaded {
+            return;
+        }
+        self.archived_versions_loaded = true;
+
+        let archives = self.archives.clone();
+        cx.spawn(|this, mut cx| async move {
+            let archived_versions = archives.all_versions().await?;
+            this.update(&mut cx, |this, cx| {
+                for (extension_id, versions) in archived_versions {
+                    // Versions archived in the meantime are already up-to-date.
+                    this.archived_versions
+                        .entry(extension_id)
+                        .or_insert(versions);
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Returns the newest archived version of the given extension that is older
+    /// than its installed version.
+    pub fn previous_version(&self, extension_id: &str) -> Option<Arc<str>> {
+        let installed_version = &self
+            .extension_index
+            .extensions
+            .get(extension_id)?
+            .manifest
+            .version;
+        self.archived_versions
+            .get(extension_id)?
+            .iter()
+            .rev()
+            .find(|version| compare_versions(version, installed_version).is_lt())
+            .cloned()
+    }
+
+    /// Reinstalls the previous version of the given extension from its local archive.
+    pub fn rollback_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(version) = self.previous_version(&extension_id) else {
+            return Task::ready(Err(anyhow!(
+                "no previous version of extension {extension_id} is available"
+            )));
+        };
+        self.install_archived_extension(extension_id, version, ExtensionOperation::Upgrade, cx)
+    }
+
     pub fn uninstall_extension(&mut self, extension_id: Arc<str>, cx: &mut ModelContext<Self>) {
         let extension_dir = self.installed_dir.join(extension_id.as_ref());
         let fs = self.fs.clone();
+        let archives = self.archives.clone();
 
         match self.outstanding_operations.entry(extension_id.clone()) {
             btree_map::Entry::Occupied(_) => return,
@@ -777,9 +1172,13 @@ impl ExtensionStore {
                 },
             )
             .await?;
+            archives.remove(&extension_id).await?;
 
-            this.update(&mut cx, |this, cx| this.reload(None, cx))?
-                .await;
+            this.update(&mut cx, |this, cx| {
+                this.archived_versions.remove(&extension_id);
+                this.reload(None, cx)
+            })?
+            .await;
             anyhow::Ok(())
         })
         .detach_and_log_err(cx)
@@ -1005,7 +1404,9 @@ impl ExtensionStore {
             })
             .collect::<Vec<_>>();
         let mut grammars_to_remove = Vec::new();
+        let mut icon_themes_to_remove = Vec::new();
         for extension_id in &extensions_to_unload {
+            icon_themes_to_remove.extend(self.icon_themes.remove(extension_id).unwrap_or_default());
             let Some(extension) = old_index.extensions.get(extension_id) else {
                 continue;
             };
@@ -1016,11 +1417,24 @@ impl ExtensionStore {
                         .remove_lsp_adapter(&language, language_server_name);
                 }
             }
+            for (formatter_id, formatter) in extension.manifest.formatters.iter() {
+                for language in &formatter.languages {
+                    self.language_registry
+                        .remove_format_provider(language, formatter_id);
+                }
+            }
+            for (linter_id, linter) in extension.manifest.linters.iter() {
+                for language in &linter.languages {
+                    self.language_registry
+                        .remove_lint_provider(language, linter_id);
+                }
+            }
         }
 
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.theme_registry.remove_user_themes(&themes_to_remove);
+        FileIcons::remove_icon_themes(&icon_themes_to_remove, cx);
         self.language_registry
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -1031,6 +1445,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
+        let mut icon_themes_to_add = Vec::new();
         for extension_id in &extensions_to_load {
             let Some(extension) = new_index.extensions.get(extension_id) else {
                 continue;
@@ -1048,6 +1463,13 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+            icon_themes_to_add.extend(extension.manifest.icon_themes.iter().map(
+                |icon_theme_path| {
+                    let mut path = self.installed_dir.clone();
+                    path.extend([Path::new(extension_id.as_ref()), icon_theme_path.as_path()]);
+                    (extension_id.clone(), path)
+                },
+            ));
         }
 
         self.language_registry
@@ -1091,9 +1513,11 @@ impl ExtensionStore {
         self.extension_index = new_index;
         cx.notify();
         cx.emit(Event::ExtensionsUpdated);
+        cx.emit(Event::PanelsUpdated);
 
         cx.spawn(|this, mut cx| async move {
-            cx.background_executor()
+            let icon_themes = cx
+                .background_executor()
                 .spawn({
                     let fs = fs.clone();
                     async move {
@@ -1103,6 +1527,16 @@ impl ExtensionStore {
                                 .await
                                 .log_err();
                         }
+
+                        let mut icon_themes = Vec::new();
+                        for (extension_id, icon_theme_path) in &icon_themes_to_add {
+                            if let Some(icon_theme) =
+                                IconTheme::load(icon_theme_path, fs.clone()).await.log_err()
+                            {
+                                icon_themes.push((extension_id.clone(), icon_theme));
+                            }
+                        }
+                        icon_themes
                     }
                 })
                 .await;
@@ -1152,6 +1586,17 @@ impl ExtensionStore {
             this.update(&mut cx, |this, cx| {
                 this.reload_complete_senders.clear();
 
+                for (extension_id, icon_theme) in &icon_themes {
+                    this.icon_themes
+                        .entry(extension_id.clone())
+                        .or_default()
+                        .push(icon_theme.name.clone());
+                }
+                FileIcons::insert_icon_themes(
+                    icon_themes.into_iter().map(|(_, icon_theme)| icon_theme),
+                    cx,
+                );
+
                 for (manifest, wasm_extension) in &wasm_extensions {
                     for (language_server_id, language_server_config) in &manifest.language_servers {
                         for language in language_server_config.languages() {
@@ -1169,8 +1614,31 @@ impl ExtensionStore {
                             );
                         }
                     }
+                    for (formatter_id, formatter) in &manifest.formatters {
+                        for language in &formatter.languages {
+                            this.language_registry.register_format_provider(
+                                language.clone(),
+                                Arc::new(ExtensionFormatProvider {
+                                    extension: wasm_extension.clone(),
+                                    formatter_id: formatter_id.clone(),
+                                }),
+                            );
+                        }
+                    }
+                    for (linter_id, linter) in &manifest.linters {
+                        for language in &linter.languages {
+                            this.language_registry.register_lint_provider(
+                                language.clone(),
+                                Arc::new(ExtensionLintProvider {
+                                    extension: wasm_extension.clone(),
+                                    linter_id: linter_id.clone(),
+                                }),
+                            );
+                        }
+                    }
                 }
                 this.wasm_extensions.extend(wasm_extensions);
+                cx.emit(Event::PanelsUpdated);
                 ThemeSettings::reload_current_theme(cx)
             })
             .ok();
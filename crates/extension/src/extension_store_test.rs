@@ -1,9 +1,9 @@
 use crate::extension_manifest::SchemaVersion;
 use crate::extension_settings::ExtensionSettings;
 use crate::{
-    Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
-    ExtensionIndexThemeEntry, ExtensionManifest, ExtensionStore, GrammarManifestEntry,
-    RELOAD_DEBOUNCE_DURATION,
+    compare_versions, is_pre_release_version, Event, ExtensionIndex, ExtensionIndexEntry,
+    ExtensionIndexLanguageEntry, ExtensionIndexThemeEntry, ExtensionManifest, ExtensionStore,
+    GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION,
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::BTreeMap;
@@ -150,6 +150,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         repository: None,
                         themes: Default::default(),
                         lib: Default::default(),
+                        icon_themes: Vec::new(),
                         languages: vec!["languages/erb".into(), "languages/ruby".into()],
                         grammars: [
                             ("embedded_template".into(), GrammarManifestEntry::default()),
@@ -158,6 +159,11 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         .into_iter()
                         .collect(),
                         language_servers: BTreeMap::default(),
+                        panels: BTreeMap::default(),
+                        assistant_tools: BTreeMap::default(),
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        project_templates: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -178,9 +184,15 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                             "themes/monokai.json".into(),
                         ],
                         lib: Default::default(),
+                        icon_themes: Vec::new(),
                         languages: Default::default(),
                         grammars: BTreeMap::default(),
                         language_servers: BTreeMap::default(),
+                        panels: BTreeMap::default(),
+                        assistant_tools: BTreeMap::default(),
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        project_templates: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -332,9 +344,15 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 repository: None,
                 themes: vec!["themes/gruvbox.json".into()],
                 lib: Default::default(),
+                icon_themes: Vec::new(),
                 languages: Default::default(),
                 grammars: BTreeMap::default(),
                 language_servers: BTreeMap::default(),
+                panels: BTreeMap::default(),
+                assistant_tools: BTreeMap::default(),
+                formatters: BTreeMap::default(),
+                linters: BTreeMap::default(),
+                project_templates: Vec::new(),
             }),
             dev: false,
         },
@@ -433,6 +451,19 @@ async fn test_extension_store(cx: &mut TestAppContext) {
     });
 }
 
+#[test]
+fn test_compare_versions() {
+    let mut versions = vec!["1.10.0", "1.2.0", "1.2.0-beta.1", "invalid", "0.9.1"];
+    versions.sort_by(|a, b| compare_versions(a, b));
+    assert_eq!(
+        versions,
+        ["invalid", "0.9.1", "1.2.0-beta.1", "1.2.0", "1.10.0"]
+    );
+
+    assert!(is_pre_release_version("1.2.0-beta.1"));
+    assert!(!is_pre_release_version("1.2.0"));
+}
+
 #[gpui::test]
 async fn test_extension_store_with_gleam_extension(cx: &mut TestAppContext) {
     init_test(cx);
@@ -19,11 +19,13 @@ use semantic_version::SemanticVersion;
 use std::{
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
+    thread,
+    time::{Duration, Instant},
 };
 use util::http::HttpClient;
 use wasmtime::{
     component::{Component, ResourceTable},
-    Engine, Store,
+    Engine, Store, UpdateDeadline,
 };
 use wasmtime_wasi as wasi;
 use wit::Extension;
@@ -35,6 +37,7 @@ pub(crate) struct WasmHost {
     pub(crate) language_registry: Arc<LanguageRegistry>,
     fs: Arc<dyn Fs>,
     pub(crate) work_dir: PathBuf,
+    pub(crate) executor: BackgroundExecutor,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
 }
@@ -52,6 +55,10 @@ pub(crate) struct WasmState {
     pub(crate) table: ResourceTable,
     ctx: wasi::WasiCtx,
     pub(crate) host: Arc<WasmHost>,
+    /// The ID of the assistant tool the extension is currently running, if any.
+    pub(crate) running_assistant_tool: Option<Arc<str>>,
+    /// When the current call into the extension is interrupted, if it has a timeout.
+    deadline: Option<Instant>,
 }
 
 type MainThreadCall =
@@ -61,6 +68,10 @@ type ExtensionCall = Box<
     dyn Send + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, ()>,
 >;
 
+/// How often the engine's epoch advances, which is how often running extensions
+/// yield to the executor and have their deadline checked.
+const EPOCH_INTERVAL: Duration = Duration::from_millis(100);
+
 fn wasm_engine() -> wasmtime::Engine {
     static WASM_ENGINE: OnceLock<wasmtime::Engine> = OnceLock::new();
 
@@ -69,11 +80,41 @@ fn wasm_engine() -> wasmtime::Engine {
             let mut config = wasmtime::Config::new();
             config.wasm_component_model(true);
             config.async_support(true);
-            wasmtime::Engine::new(&config).unwrap()
+            config.epoch_interruption(true);
+            let engine = wasmtime::Engine::new(&config).unwrap();
+
+            let ticker = engine.clone();
+            thread::Builder::new()
+                .name("wasm-epoch".into())
+                .spawn(move || loop {
+                    thread::sleep(EPOCH_INTERVAL);
+                    ticker.increment_epoch();
+                })
+                .unwrap();
+
+            engine
         })
         .clone()
 }
 
+/// Makes the wasm running in the store yield to the executor on every epoch,
+/// and trap once the deadline returned by `deadline` has passed, so that an
+/// extension that loops forever can't block its thread or outlive its timeout.
+fn interrupt_on_epoch<T: 'static>(
+    store: &mut Store<T>,
+    deadline: impl 'static + Send + Sync + Fn(&T) -> Option<Instant>,
+) {
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(move |store| {
+        if let Some(deadline) = deadline(store.data()) {
+            if Instant::now() >= deadline {
+                bail!("extension timed out");
+            }
+        }
+        Ok(UpdateDeadline::Yield(1))
+    });
+}
+
 impl WasmHost {
     pub fn new(
         fs: Arc<dyn Fs>,
@@ -96,6 +137,7 @@ impl WasmHost {
             http_client,
             node_runtime,
             language_registry,
+            executor: cx.background_executor().clone(),
             _main_thread_message_task: task,
             main_thread_message_tx: tx,
         })
@@ -121,8 +163,11 @@ impl WasmHost {
                     manifest: manifest.clone(),
                     table: ResourceTable::new(),
                     host: this.clone(),
+                    running_assistant_tool: None,
+                    deadline: None,
                 },
             );
+            interrupt_on_epoch(&mut store, |state| state.deadline);
 
             let (mut extension, instance) =
                 Extension::instantiate_async(&mut store, zed_api_version, &component).await?;
@@ -258,6 +303,29 @@ impl WasmExtension {
             .expect("wasm extension channel should not be closed yet");
         return_rx.await.expect("wasm extension channel")
     }
+
+    /// Calls into the extension like [`Self::call`], trapping the extension's code
+    /// if it's still running after the given timeout.
+    ///
+    /// An extension that traps can't be called again until it's reloaded.
+    pub async fn call_with_timeout<T, Fn>(&self, timeout: Duration, f: Fn) -> Result<T>
+    where
+        T: 'static + Send,
+        Fn: 'static
+            + Send
+            + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, Result<T>>,
+    {
+        self.call(move |extension, store| {
+            async move {
+                store.data_mut().deadline = Some(Instant::now() + timeout);
+                let result = f(extension, store).await;
+                store.data_mut().deadline = None;
+                result
+            }
+            .boxed()
+        })
+        .await
+    }
 }
 
 impl WasmState {
@@ -295,3 +363,43 @@ impl wasi::WasiView for WasmState {
         &mut self.ctx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::{Module, TypedFunc};
+
+    /// `(module (func (export "spin") (loop (br 0))))`
+    const SPIN_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+        0x03, 0x02, 0x01, 0x00, // function section
+        0x07, 0x08, 0x01, 0x04, b's', b'p', b'i', b'n', 0x00, 0x00, // export section
+        0x0a, 0x09, 0x01, 0x07, 0x00, 0x03, 0x40, 0x0c, 0x00, 0x0b, 0x0b, // code section
+    ];
+
+    #[test]
+    fn test_interrupt_on_epoch() {
+        let engine = wasm_engine();
+        let module = Module::new(&engine, SPIN_MODULE).unwrap();
+        let mut store = Store::new(&engine, None);
+        interrupt_on_epoch(&mut store, |deadline: &Option<Instant>| *deadline);
+
+        futures::executor::block_on(async {
+            let instance = wasmtime::Instance::new_async(&mut store, &module, &[])
+                .await
+                .unwrap();
+            let spin: TypedFunc<(), ()> = instance.get_typed_func(&mut store, "spin").unwrap();
+
+            let timeout = Duration::from_millis(300);
+            let start = Instant::now();
+            *store.data_mut() = Some(start + timeout);
+            let error = spin.call_async(&mut store, ()).await.unwrap_err();
+            assert!(
+                format!("{error:?}").contains("extension timed out"),
+                "{error:?}"
+            );
+            assert!(start.elapsed() >= timeout);
+        });
+    }
+}
@@ -1,7 +1,8 @@
 mod since_v0_0_1;
 mod since_v0_0_4;
 mod since_v0_0_6;
-use since_v0_0_6 as latest;
+mod since_v0_0_7;
+use since_v0_0_7 as latest;
 
 use super::{wasm_engine, WasmState};
 use anyhow::{Context, Result};
@@ -17,7 +18,11 @@ use wasmtime::{
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
     zed::extension::lsp::{Completion, CompletionKind, InsertTextFormat, Symbol, SymbolKind},
-    CodeLabel, CodeLabelSpan, Command, Range,
+    zed::extension::ui::{
+        Button as UiButton, InputChanged, Label as UiLabel, LabelColor as UiLabelColor,
+        ListItem as UiListItem, TextInput as UiTextInput, UiElement, UiEvent,
+    },
+    CodeLabel, CodeLabelSpan, Command, DiagnosticSeverity, LintDiagnostic, Range,
 };
 pub use since_v0_0_4::LanguageServerConfig;
 
@@ -46,6 +51,7 @@ pub fn wasm_api_version_range() -> RangeInclusive<SemanticVersion> {
 }
 
 pub enum Extension {
+    V007(since_v0_0_7::Extension),
     V006(since_v0_0_6::Extension),
     V004(since_v0_0_4::Extension),
     V001(since_v0_0_1::Extension),
@@ -62,6 +68,15 @@ impl Extension {
                 latest::Extension::instantiate_async(store, &component, latest::linker())
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok((Self::V007(extension), instance))
+        } else if version >= since_v0_0_6::MIN_VERSION {
+            let (extension, instance) = since_v0_0_6::Extension::instantiate_async(
+                store,
+                &component,
+                since_v0_0_6::linker(),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok((Self::V006(extension), instance))
         } else if version >= since_v0_0_4::MIN_VERSION {
             let (extension, instance) = since_v0_0_4::Extension::instantiate_async(
@@ -86,6 +101,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V007(ext) => ext.call_init_extension(store).await,
             Extension::V006(ext) => ext.call_init_extension(store).await,
             Extension::V004(ext) => ext.call_init_extension(store).await,
             Extension::V001(ext) => ext.call_init_extension(store).await,
@@ -100,10 +116,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_language_server_command(store, &language_server_id.0, resource)
+                .await?
+                .map(|command| command.into())),
             Extension::V004(ext) => Ok(ext
                 .call_language_server_command(store, config, resource)
                 .await?
@@ -123,6 +143,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V006(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -153,6 +181,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V007(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V006(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -173,10 +209,19 @@ impl Extension {
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
             Extension::V001(_) | Extension::V004(_) => Ok(Ok(Vec::new())),
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
         }
     }
 
@@ -188,10 +233,101 @@ impl Extension {
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
             Extension::V001(_) | Extension::V004(_) => Ok(Ok(Vec::new())),
-            Extension::V006(ext) => {
+            Extension::V007(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V006(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
+        }
+    }
+
+    pub async fn call_run_assistant_tool(
+        &self,
+        store: &mut Store<WasmState>,
+        tool_id: &str,
+        input: &str,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_run_assistant_tool(store, tool_id, input, resource)
+                    .await
+            }
+            Extension::V006(_) | Extension::V004(_) | Extension::V001(_) => Ok(Err(
+                "assistant tools are not supported by this extension's API version".into(),
+            )),
+        }
+    }
+
+    pub async fn call_render_panel(
+        &self,
+        store: &mut Store<WasmState>,
+        panel_id: &str,
+    ) -> Result<Result<Vec<UiElement>, String>> {
+        match self {
+            Extension::V007(ext) => ext.call_render_panel(store, panel_id).await,
+            Extension::V006(_) | Extension::V004(_) | Extension::V001(_) => Ok(Err(
+                "panels are not supported by this extension's API version".into(),
+            )),
+        }
+    }
+
+    pub async fn call_handle_panel_event(
+        &self,
+        store: &mut Store<WasmState>,
+        panel_id: &str,
+        event: &UiEvent,
+    ) -> Result<Result<(), String>> {
+        match self {
+            Extension::V007(ext) => ext.call_handle_panel_event(store, panel_id, event).await,
+            Extension::V006(_) | Extension::V004(_) | Extension::V001(_) => Ok(Ok(())),
+        }
+    }
+
+    pub async fn call_format_document(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_id: &str,
+        path: &str,
+        text: &str,
+        range: Option<Range>,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_format_document(store, formatter_id, path, text, range, resource)
+                    .await
+            }
+            Extension::V006(_) | Extension::V004(_) | Extension::V001(_) => Ok(Err(
+                "formatters are not supported by this extension's API version".into(),
+            )),
+        }
+    }
+
+    pub async fn call_lint_document(
+        &self,
+        store: &mut Store<WasmState>,
+        linter_id: &str,
+        path: &str,
+        text: &str,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Vec<LintDiagnostic>, String>> {
+        match self {
+            Extension::V007(ext) => {
+                ext.call_lint_document(store, linter_id, path, text, resource)
+                    .await
+            }
+            Extension::V006(_) | Extension::V004(_) | Extension::V001(_) => Ok(Err(
+                "linters are not supported by this extension's API version".into(),
+            )),
         }
     }
 }
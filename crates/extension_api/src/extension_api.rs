@@ -14,7 +14,7 @@ pub use serde_json;
 // We explicitly enumerate the symbols we want to re-export, as there are some
 // that we may want to shadow to provide a cleaner Rust API.
 pub use wit::{
-    download_file, make_file_executable,
+    download_file, make_file_executable, run_command,
     zed::extension::github::{
         latest_github_release, GithubRelease, GithubReleaseAsset, GithubReleaseOptions,
     },
@@ -23,8 +23,8 @@ pub use wit::{
         npm_package_latest_version,
     },
     zed::extension::platform::{current_platform, Architecture, Os},
-    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
-    LanguageServerInstallationStatus, Range, Worktree,
+    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, CommandOutput, DiagnosticSeverity,
+    DownloadedFileType, EnvVars, LanguageServerInstallationStatus, LintDiagnostic, Range, Worktree,
 };
 
 // Undocumented WIT re-exports.
@@ -42,6 +42,13 @@ pub mod lsp {
     };
 }
 
+/// Constructs for describing the contents of panels provided by an extension.
+pub mod ui {
+    pub use crate::wit::zed::extension::ui::{
+        Button, InputChanged, Label, LabelColor, ListItem, TextInput, UiElement, UiEvent,
+    };
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -103,6 +110,65 @@ pub trait Extension: Send + Sync {
     ) -> Option<CodeLabel> {
         None
     }
+
+    /// Runs the assistant tool with the given ID and returns the output to pass
+    /// back to the language model.
+    ///
+    /// Assistant tools are declared in the `assistant_tools` section of the
+    /// extension manifest, together with the JSON schema of their input.
+    fn run_assistant_tool(
+        &mut self,
+        tool_id: &str,
+        _input: serde_json::Value,
+        _worktree: &Worktree,
+    ) -> Result<String> {
+        Err(format!("unknown assistant tool: {tool_id}"))
+    }
+
+    /// Returns the elements to display in the panel with the given ID.
+    ///
+    /// Panels are declared in the `panels` section of the extension manifest.
+    fn render_panel(&mut self, panel_id: &str) -> Result<Vec<ui::UiElement>> {
+        Err(format!("unknown panel: {panel_id}"))
+    }
+
+    /// Handles an interaction of the user with the panel with the given ID.
+    ///
+    /// The panel is rendered again after the event has been handled.
+    fn handle_panel_event(&mut self, _panel_id: &str, _event: ui::UiEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Formats the given text of the file at the given worktree-relative path,
+    /// using the formatter with the given ID.
+    ///
+    /// Formatters are declared in the `formatters` section of the extension
+    /// manifest. If a byte range is given, only the text within that range
+    /// should be formatted, and the returned text replaces it.
+    fn format_document(
+        &mut self,
+        formatter_id: &str,
+        _path: &str,
+        _text: &str,
+        _range: Option<Range>,
+        _worktree: &Worktree,
+    ) -> Result<String> {
+        Err(format!("unknown formatter: {formatter_id}"))
+    }
+
+    /// Returns the diagnostics for the given text of the file at the given
+    /// worktree-relative path, using the linter with the given ID.
+    ///
+    /// Linters are declared in the `linters` section of the extension manifest.
+    fn lint_document(
+        &mut self,
+        linter_id: &str,
+        _path: &str,
+        _text: &str,
+        _worktree: &Worktree,
+    ) -> Result<Vec<LintDiagnostic>> {
+        Err(format!("unknown linter: {linter_id}"))
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -140,7 +206,7 @@ pub static ZED_API_VERSION: [u8; 6] = *include_bytes!(concat!(env!("OUT_DIR"), "
 mod wit {
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.0.6",
+        path: "./wit/since_v0.0.7",
     });
 }
 
@@ -208,6 +274,42 @@ impl wit::Guest for Component {
         }
         Ok(labels)
     }
+
+    fn run_assistant_tool(
+        tool_id: String,
+        input: String,
+        worktree: &Worktree,
+    ) -> Result<String, String> {
+        let input = serde_json::from_str(&input).map_err(|error| error.to_string())?;
+        extension().run_assistant_tool(&tool_id, input, worktree)
+    }
+
+    fn render_panel(panel_id: String) -> Result<Vec<ui::UiElement>, String> {
+        extension().render_panel(&panel_id)
+    }
+
+    fn handle_panel_event(panel_id: String, event: ui::UiEvent) -> Result<(), String> {
+        extension().handle_panel_event(&panel_id, event)
+    }
+
+    fn format_document(
+        formatter_id: String,
+        path: String,
+        text: String,
+        range: Option<Range>,
+        worktree: &Worktree,
+    ) -> Result<String, String> {
+        extension().format_document(&formatter_id, &path, &text, range, worktree)
+    }
+
+    fn lint_document(
+        linter_id: String,
+        path: String,
+        text: String,
+        worktree: &Worktree,
+    ) -> Result<Vec<LintDiagnostic>, String> {
+        extension().lint_document(&linter_id, &path, &text, worktree)
+    }
 }
 
 /// The ID of a language server.
@@ -16,6 +16,7 @@ use extension::{
 use language::LanguageConfig;
 use theme::ThemeRegistry;
 use tree_sitter::{Language, Query, WasmStore};
+use util::http;
 
 #[derive(Parser, Debug)]
 #[command(name = "zed-extension")]
@@ -58,7 +59,7 @@ async fn main() -> Result<()> {
     let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
 
     log::info!("compiling extension");
-    let builder = ExtensionBuilder::new(scratch_dir);
+    let builder = ExtensionBuilder::new(scratch_dir, http::client());
     builder
         .compile_extension(
             &extension_path,
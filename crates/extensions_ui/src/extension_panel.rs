@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use extension::{
+    ExtensionStore, InputChanged, PanelManifestEntry, PanelPosition, UiElement, UiEvent,
+    UiLabelColor,
+};
+use gpui::{
+    impl_actions, AnyElement, AppContext, Empty, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View,
+};
+use serde::Deserialize;
+use strum::IntoEnumIterator as _;
+use ui::{prelude::*, Divider, ListItem};
+use util::ResultExt as _;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const DEFAULT_WIDTH: Pixels = px(240.);
+const DEFAULT_HEIGHT: Pixels = px(320.);
+
+/// Toggles focus on a panel provided by an extension.
+#[derive(Clone, Default, Deserialize, PartialEq)]
+pub struct ToggleExtensionPanel {
+    pub extension_id: Arc<str>,
+    pub panel_id: Arc<str>,
+}
+
+impl_actions!(zed, [ToggleExtensionPanel]);
+
+pub(crate) fn init(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, action: &ToggleExtensionPanel, cx| {
+        let panel = extension_panels(workspace, cx).into_iter().find(|panel| {
+            let panel = panel.read(cx);
+            panel.extension_id == action.extension_id && panel.panel_id == action.panel_id
+        });
+        if let Some(panel) = panel {
+            workspace.toggle_panel_focus_for_id(panel.entity_id(), cx);
+        }
+    });
+
+    let Some(store) = ExtensionStore::try_global(cx) else {
+        return;
+    };
+    cx.subscribe(&store, |workspace, store, event, cx| {
+        if let extension::Event::PanelsUpdated = event {
+            update_extension_panels(&store, workspace, cx);
+        }
+    })
+    .detach();
+    update_extension_panels(&store, workspace, cx);
+}
+
+fn extension_panels(workspace: &Workspace, cx: &AppContext) -> Vec<View<ExtensionPanel>> {
+    [
+        workspace.left_dock(),
+        workspace.bottom_dock(),
+        workspace.right_dock(),
+    ]
+    .into_iter()
+    .flat_map(|dock| dock.read(cx).panels::<ExtensionPanel>())
+    .collect()
+}
+
+/// Adds a panel to the workspace for every panel provided by a loaded
+/// extension, and removes the panels of extensions that were unloaded.
+fn update_extension_panels(
+    store: &Model<ExtensionStore>,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let provided_panels = store
+        .read(cx)
+        .extension_panels()
+        .map(|(extension_id, panel_id, panel)| {
+            (extension_id.clone(), panel_id.clone(), panel.clone())
+        })
+        .collect::<Vec<_>>();
+    let existing_panels = extension_panels(workspace, cx);
+
+    for panel in &existing_panels {
+        let is_provided = provided_panels.iter().any(|(extension_id, panel_id, _)| {
+            let panel = panel.read(cx);
+            &panel.extension_id == extension_id && &panel.panel_id == panel_id
+        });
+        if !is_provided {
+            for dock in [
+                workspace.left_dock(),
+                workspace.bottom_dock(),
+                workspace.right_dock(),
+            ] {
+                dock.update(cx, |dock, cx| dock.remove_panel(panel, cx));
+            }
+        }
+    }
+
+    for (extension_id, panel_id, manifest_entry) in provided_panels {
+        let exists = existing_panels.iter().any(|panel| {
+            let panel = panel.read(cx);
+            panel.extension_id == extension_id && panel.panel_id == panel_id
+        });
+        if !exists {
+            let panel = ExtensionPanel::new(extension_id, panel_id, &manifest_entry, cx);
+            workspace.add_panel(panel, cx);
+        }
+    }
+}
+
+/// A panel whose contents are provided by an extension.
+///
+/// The extension describes the contents as a list of [`UiElement`]s, which
+/// are requested again whenever the user interacts with the panel.
+pub struct ExtensionPanel {
+    extension_id: Arc<str>,
+    panel_id: Arc<str>,
+    name: SharedString,
+    icon: IconName,
+    position: DockPosition,
+    size: Option<Pixels>,
+    focus_handle: FocusHandle,
+    elements: Vec<UiElement>,
+    error: Option<SharedString>,
+    inputs: HashMap<String, (View<Editor>, Subscription)>,
+    render_task: Option<Task<()>>,
+}
+
+impl ExtensionPanel {
+    fn new(
+        extension_id: Arc<str>,
+        panel_id: Arc<str>,
+        manifest_entry: &PanelManifestEntry,
+        cx: &mut WindowContext,
+    ) -> View<Self> {
+        let icon = panel_icon(manifest_entry.icon.as_deref());
+
+        cx.new_view(|cx| {
+            let mut this = Self {
+                extension_id,
+                panel_id,
+                name: manifest_entry.name.clone().into(),
+                icon,
+                position: match manifest_entry.position {
+                    PanelPosition::Left => DockPosition::Left,
+                    PanelPosition::Right => DockPosition::Right,
+                    PanelPosition::Bottom => DockPosition::Bottom,
+                },
+                size: None,
+                focus_handle: cx.focus_handle(),
+                elements: Vec::new(),
+                error: None,
+                inputs: HashMap::default(),
+                render_task: None,
+            };
+            this.refresh(cx);
+            this
+        })
+    }
+
+    /// Requests the contents of the panel from the extension.
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(store) = ExtensionStore::try_global(cx) else {
+            return;
+        };
+        let task = store.update(cx, |store, cx| {
+            store.render_extension_panel(&self.extension_id, self.panel_id.clone(), cx)
+        });
+        self.render_task = Some(cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| {
+                match result {
+                    Ok(elements) => {
+                        this.error = None;
+                        this.set_elements(elements, cx);
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn set_elements(&mut self, elements: Vec<UiElement>, cx: &mut ViewContext<Self>) {
+        let mut inputs = HashMap::default();
+        for element in &elements {
+            let UiElement::TextInput(input) = element else {
+                continue;
+            };
+            // Keep the editors of existing inputs, so that their contents and
+            // selections are not reset while the user is typing.
+            let input_state = self.inputs.remove(&input.id).unwrap_or_else(|| {
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::single_line(cx);
+                    editor.set_placeholder_text(input.placeholder.clone(), cx);
+                    editor.set_text(input.value.clone(), cx);
+                    editor
+                });
+                let id = input.id.clone();
+                let subscription = cx.subscribe(&editor, move |this, editor, event, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        let value = editor.read(cx).text(cx);
+                        this.handle_event(
+                            UiEvent::InputChanged(InputChanged {
+                                id: id.clone(),
+                                value,
+                            }),
+                            cx,
+                        );
+                    }
+                });
+                (editor, subscription)
+            });
+            inputs.insert(input.id.clone(), input_state);
+        }
+        self.inputs = inputs;
+        self.elements = elements;
+    }
+
+    /// Forwards an interaction to the extension and renders the panel again
+    /// once the extension has handled it.
+    fn handle_event(&mut self, event: UiEvent, cx: &mut ViewContext<Self>) {
+        let Some(store) = ExtensionStore::try_global(cx) else {
+            return;
+        };
+        let task = store.update(cx, |store, cx| {
+            store.handle_extension_panel_event(&self.extension_id, self.panel_id.clone(), event, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            task.await.log_err();
+            this.update(&mut cx, |this, cx| this.refresh(cx)).ok();
+        })
+        .detach();
+    }
+
+    fn render_element(&self, element: &UiElement, cx: &mut ViewContext<Self>) -> AnyElement {
+        match element {
+            UiElement::Label(label) => Label::new(label.text.clone())
+                .color(match label.color {
+                    UiLabelColor::Default => Color::Default,
+                    UiLabelColor::Muted => Color::Muted,
+                    UiLabelColor::Accent => Color::Accent,
+                    UiLabelColor::Success => Color::Success,
+                    UiLabelColor::Warning => Color::Warning,
+                    UiLabelColor::Error => Color::Error,
+                })
+                .into_any_element(),
+            UiElement::Button(button) => {
+                let id = button.id.clone();
+                Button::new(SharedString::from(button.id.clone()), button.label.clone())
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.handle_event(UiEvent::ButtonClicked(id.clone()), cx)
+                    }))
+                    .into_any_element()
+            }
+            UiElement::TextInput(input) => match self.inputs.get(&input.id) {
+                Some((editor, _)) => div()
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_md()
+                    .child(editor.clone())
+                    .into_any_element(),
+                None => Empty.into_any_element(),
+            },
+            UiElement::ListItem(item) => {
+                let id = item.id.clone();
+                ListItem::new(SharedString::from(item.id.clone()))
+                    .indent_level(item.indent as usize)
+                    .selected(item.selected)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(item.label.clone()))
+                            .children(item.detail.clone().map(|detail| {
+                                Label::new(detail)
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small)
+                            })),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.handle_event(UiEvent::ListItemClicked(id.clone()), cx)
+                    }))
+                    .into_any_element()
+            }
+            UiElement::Divider => Divider::horizontal().into_any_element(),
+        }
+    }
+}
+
+/// Returns the icon with the given name, e.g. `"github"`, falling back to
+/// a generic icon for names that don't exist.
+fn panel_icon(name: Option<&str>) -> IconName {
+    name.and_then(|name| {
+        let path = format!("icons/{name}.svg");
+        IconName::iter().find(|icon| icon.path() == path)
+    })
+    .unwrap_or(IconName::Menu)
+}
+
+impl Render for ExtensionPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("extension-panel")
+            .key_context("ExtensionPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_2()
+            .gap_1()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).color(Color::Error))
+            })
+            .children(
+                self.elements
+                    .iter()
+                    .map(|element| self.render_element(element, cx)),
+            )
+    }
+}
+
+impl FocusableView for ExtensionPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ExtensionPanel {}
+
+impl Panel for ExtensionPanel {
+    fn persistent_name() -> &'static str {
+        "ExtensionPanel"
+    }
+
+    fn persistent_id(&self) -> SharedString {
+        format!(
+            "{}:{}:{}",
+            Self::persistent_name(),
+            self.extension_id,
+            self.panel_id
+        )
+        .into()
+    }
+
+    fn position(&self, _: &WindowContext) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        self.position = position;
+        self.size = None;
+        cx.notify();
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        self.size.unwrap_or(match self.position {
+            DockPosition::Left | DockPosition::Right => DEFAULT_WIDTH,
+            DockPosition::Bottom => DEFAULT_HEIGHT,
+        })
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.size = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &WindowContext) -> Option<IconName> {
+        Some(self.icon)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleExtensionPanel {
+            extension_id: self.extension_id.clone(),
+            panel_id: self.panel_id.clone(),
+        })
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active {
+            self.refresh(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use workspace::dock::PanelHandle as _;
+
+    #[test]
+    fn test_panel_icon() {
+        assert_eq!(panel_icon(Some("github")), IconName::Github);
+        assert_eq!(panel_icon(Some("no_such_icon")), IconName::Menu);
+        assert_eq!(panel_icon(None), IconName::Menu);
+    }
+
+    #[gpui::test]
+    fn test_extension_panel_names(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let manifest_entry = |name: &str| PanelManifestEntry {
+            name: name.into(),
+            icon: None,
+            position: PanelPosition::Right,
+        };
+        let (first, second, other) = cx.update(|cx| {
+            (
+                ExtensionPanel::new("first".into(), "todos".into(), &manifest_entry("TODOs"), cx),
+                ExtensionPanel::new(
+                    "second".into(),
+                    "todos".into(),
+                    &manifest_entry("Tasks"),
+                    cx,
+                ),
+                ExtensionPanel::new("first".into(), "ci".into(), &manifest_entry("CI"), cx),
+            )
+        });
+
+        cx.update(|cx| {
+            // Panels are persisted separately, even when several extensions
+            // provide panels with the same ID.
+            assert_eq!(first.persistent_name(cx), "ExtensionPanel:first:todos");
+            assert_eq!(second.persistent_name(cx), "ExtensionPanel:second:todos");
+            assert_eq!(other.persistent_name(cx), "ExtensionPanel:first:ci");
+
+            assert_eq!(first.icon_tooltip(cx), Some("TODOs".into()));
+            assert_eq!(second.icon_tooltip(cx), Some("Tasks".into()));
+            assert_eq!(first.position(cx), DockPosition::Right);
+        });
+    }
+}
@@ -177,8 +177,9 @@ impl PickerDelegate for ExtensionVersionSelectorDelegate {
 
             update_settings_file::<ExtensionSettings>(self.fs.clone(), cx, {
                 let extension_id = extension_id.clone();
+                let version = version.clone();
                 move |settings| {
-                    settings.auto_update_extensions.insert(extension_id, false);
+                    settings.pinned_extensions.insert(extension_id, version);
                 }
             });
 
@@ -1,4 +1,5 @@
 mod components;
+mod extension_panel;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -9,14 +10,16 @@ use crate::extension_version_selector::{
 use client::telemetry::Telemetry;
 use client::ExtensionMetadata;
 use editor::{Editor, EditorElement, EditorStyle};
-use extension::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension::{
+    ExtensionManifest, ExtensionOperation, ExtensionSettings, ExtensionStore, UpdateAllExtensions,
+};
 use fuzzy::{match_strings, StringMatchCandidate};
 use gpui::{
     actions, canvas, uniform_list, AnyElement, AppContext, EventEmitter, FocusableView, FontStyle,
     FontWeight, InteractiveElement, KeyContext, ParentElement, Render, Styled, Task, TextStyle,
     UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
-use settings::Settings;
+use settings::{update_settings_file, Settings};
 use std::ops::DerefMut;
 use std::time::Duration;
 use std::{ops::Range, sync::Arc};
@@ -29,6 +32,8 @@ use workspace::{
     Workspace, WorkspaceId,
 };
 
+pub use extension_panel::{ExtensionPanel, ToggleExtensionPanel};
+
 actions!(zed, [Extensions, InstallDevExtension]);
 
 pub fn init(cx: &mut AppContext) {
@@ -71,6 +76,8 @@ pub fn init(cx: &mut AppContext) {
                     .detach();
             });
 
+        extension_panel::init(workspace, cx);
+
         cx.subscribe(workspace.project(), |_, _, event, cx| match event {
             project::Event::LanguageNotFound(buffer) => {
                 extension_suggest::suggest(buffer.clone(), cx);
@@ -126,6 +133,7 @@ impl ExtensionsPage {
     pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
         cx.new_view(|cx: &mut ViewContext<Self>| {
             let store = ExtensionStore::global(cx);
+            store.update(cx, |store, cx| store.load_archived_versions(cx));
             let workspace_handle = workspace.weak_handle();
             let subscriptions = [
                 cx.observe(&store, |_, _, cx| cx.notify()),
@@ -548,19 +556,126 @@ impl ExtensionsPage {
         extension_id: Arc<str>,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
+        let extension_store = ExtensionStore::global(cx).read(cx);
+        let installed_version = extension_store
+            .installed_extensions()
+            .get(&extension_id)
+            .filter(|extension| !extension.dev)
+            .map(|extension| extension.manifest.version.clone());
+        let previous_version = extension_store.previous_version(&extension_id);
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let pinned_version = extension_settings.pinned_version(&extension_id).cloned();
+        let wants_pre_releases = extension_settings.wants_pre_releases(&extension_id);
+
         let context_menu = ContextMenu::build(cx, |context_menu, cx| {
-            context_menu.entry(
-                "Install Another Version...",
-                None,
-                cx.handler_for(&this, move |this, cx| {
-                    this.show_extension_version_list(extension_id.clone(), cx)
-                }),
-            )
+            context_menu
+                .entry(
+                    "Install Another Version...",
+                    None,
+                    cx.handler_for(&this, {
+                        let extension_id = extension_id.clone();
+                        move |this, cx| this.show_extension_version_list(extension_id.clone(), cx)
+                    }),
+                )
+                .when_some(installed_version, |context_menu, installed_version| {
+                    context_menu
+                        .toggleable_entry(
+                            format!("Pin v{installed_version}"),
+                            pinned_version.as_ref() == Some(&installed_version),
+                            None,
+                            cx.handler_for(&this, {
+                                let extension_id = extension_id.clone();
+                                move |this, cx| {
+                                    let extension_id = extension_id.clone();
+                                    let version = match pinned_version.clone() {
+                                        Some(_) => None,
+                                        None => Some(installed_version.clone()),
+                                    };
+                                    this.update_extension_settings(cx, move |settings| {
+                                        match version {
+                                            Some(version) => settings
+                                                .pinned_extensions
+                                                .insert(extension_id, version),
+                                            None => {
+                                                settings.pinned_extensions.remove(&extension_id)
+                                            }
+                                        };
+                                    });
+                                }
+                            }),
+                        )
+                        .toggleable_entry(
+                            "Install Pre-Releases",
+                            wants_pre_releases,
+                            None,
+                            cx.handler_for(&this, {
+                                let extension_id = extension_id.clone();
+                                move |this, cx| {
+                                    let extension_id = extension_id.clone();
+                                    this.update_extension_settings(cx, move |settings| {
+                                        settings
+                                            .pre_release_extensions
+                                            .insert(extension_id, !wants_pre_releases);
+                                    });
+                                }
+                            }),
+                        )
+                        .when_some(previous_version, |context_menu, previous_version| {
+                            context_menu.entry(
+                                format!("Roll Back to v{previous_version}"),
+                                None,
+                                cx.handler_for(&this, move |this, cx| {
+                                    this.rollback_extension(
+                                        extension_id.clone(),
+                                        previous_version.clone(),
+                                        cx,
+                                    )
+                                }),
+                            )
+                        })
+                })
         });
 
         context_menu
     }
 
+    fn update_extension_settings(
+        &self,
+        cx: &mut ViewContext<Self>,
+        update: impl 'static + Send + FnOnce(&mut ExtensionSettings),
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let fs = workspace.read(cx).project().read(cx).fs().clone();
+        update_settings_file::<ExtensionSettings>(fs, cx, update);
+    }
+
+    /// Rolls the extension back to the given previously installed version, and
+    /// pins it to that version so that it isn't updated again automatically.
+    fn rollback_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        previous_version: Arc<str>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.telemetry
+            .report_app_event("extensions: roll back extension".to_string());
+        self.update_extension_settings(cx, {
+            let extension_id = extension_id.clone();
+            move |settings| {
+                settings
+                    .pinned_extensions
+                    .insert(extension_id, previous_version);
+            }
+        });
+        ExtensionStore::global(cx).update(cx, |store, cx| {
+            store
+                .rollback_extension(extension_id, cx)
+                .detach_and_log_err(cx)
+        });
+    }
+
     fn show_extension_version_list(&mut self, extension_id: Arc<str>, cx: &mut ViewContext<Self>) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
@@ -862,12 +977,29 @@ impl Render for ExtensionsPage {
                             .justify_between()
                             .child(Headline::new("Extensions").size(HeadlineSize::XLarge))
                             .child(
-                                Button::new("install-dev-extension", "Install Dev Extension")
-                                    .style(ButtonStyle::Filled)
-                                    .size(ButtonSize::Large)
-                                    .on_click(|_event, cx| {
-                                        cx.dispatch_action(Box::new(InstallDevExtension))
-                                    }),
+                                h_flex()
+                                    .gap_2()
+                                    .child(
+                                        Button::new("update-all-extensions", "Update All")
+                                            .style(ButtonStyle::Filled)
+                                            .size(ButtonSize::Large)
+                                            .on_click(|_event, cx| {
+                                                cx.dispatch_action(Box::new(UpdateAllExtensions))
+                                            }),
+                                    )
+                                    .child(
+                                        Button::new(
+                                            "install-dev-extension",
+                                            "Install Dev Extension",
+                                        )
+                                        .style(ButtonStyle::Filled)
+                                        .size(ButtonSize::Large)
+                                        .on_click(
+                                            |_event, cx| {
+                                                cx.dispatch_action(Box::new(InstallDevExtension))
+                                            },
+                                        ),
+                                    ),
                             ),
                     )
                     .child(
@@ -1,8 +1,12 @@
 #[cfg(test)]
 mod file_finder_tests;
 
+mod file_finder_settings;
+
 use collections::{HashMap, HashSet};
 use editor::{scroll::Autoscroll, Bias, Editor};
+use file_finder_settings::FileFinderSettings;
+use file_icons::FileIcons;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
     actions, rems, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
@@ -36,6 +40,7 @@ pub struct FileFinder {
 }
 
 pub fn init(cx: &mut AppContext) {
+    FileFinderSettings::register(cx);
     cx.observe_new_views(FileFinder::register).detach();
 }
 
@@ -903,6 +908,16 @@ impl PickerDelegate for FileFinderDelegate {
             .log_err();
     }
 
+    fn accessible_label(&self, ix: usize, cx: &AppContext) -> Option<String> {
+        let path_match = self.matches.get(ix)?;
+        let (file_name, _, full_path, _) = self.labels_for_match(path_match, cx, ix);
+        if full_path.is_empty() {
+            Some(file_name)
+        } else {
+            Some(format!("{file_name}, {full_path}"))
+        }
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -917,11 +932,19 @@ impl PickerDelegate for FileFinderDelegate {
         let (file_name, file_name_positions, full_path, full_path_positions) =
             self.labels_for_match(path_match, cx, ix);
 
+        let file_icon = if FileFinderSettings::get_global(cx).file_icons {
+            FileIcons::get_icon(Path::new(&file_name), cx)
+                .map(|icon_path| Icon::from_path(icon_path).color(Color::Muted))
+        } else {
+            None
+        };
+
         Some(
             ListItem::new(ix)
                 .spacing(ListItemSpacing::Sparse)
                 .inset(true)
                 .selected(selected)
+                .start_slot::<Icon>(file_icon)
                 .child(
                     h_flex()
                         .gap_2()
@@ -1,25 +1,99 @@
-use std::{path::Path, str, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str,
+    sync::Arc,
+};
 
+use anyhow::{Context, Result};
 use collections::HashMap;
 
-use gpui::{AppContext, AssetSource, Global};
-use serde_derive::Deserialize;
-use util::{maybe, paths::PathExt};
+use fs::Fs;
+use gpui::{AppContext, AssetSource, Global, SharedString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use util::paths::PathExt;
 
 #[derive(Deserialize, Debug)]
 struct TypeConfig {
     icon: Arc<str>,
 }
 
+/// A set of icons for files and directories, and the mapping from file names,
+/// file suffixes, and languages to those icons.
 #[derive(Deserialize, Debug)]
-pub struct FileIcons {
+pub struct IconTheme {
+    #[serde(default)]
+    pub name: SharedString,
+    #[serde(default)]
     stems: HashMap<String, String>,
+    #[serde(default)]
     suffixes: HashMap<String, String>,
+    #[serde(default)]
+    languages: HashMap<String, String>,
     types: HashMap<String, TypeConfig>,
 }
 
+impl IconTheme {
+    /// Loads the icon theme at the given path.
+    ///
+    /// The icon paths in the theme are relative to the directory containing it.
+    pub async fn load(path: &Path, fs: Arc<dyn Fs>) -> Result<Self> {
+        let content = fs
+            .load(path)
+            .await
+            .with_context(|| format!("failed to load icon theme {path:?}"))?;
+        let mut icon_theme = serde_json::from_str::<IconTheme>(&content)
+            .with_context(|| format!("invalid icon theme {path:?}"))?;
+        if icon_theme.name.is_empty() {
+            anyhow::bail!("icon theme {path:?} has no name");
+        }
+
+        let theme_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for type_config in icon_theme.types.values_mut() {
+            let icon_path: PathBuf = theme_dir.join(&*type_config.icon);
+            type_config.icon = icon_path.to_string_lossy().into();
+        }
+
+        Ok(icon_theme)
+    }
+
+    fn get_type_icon(&self, typ: &str) -> Option<Arc<str>> {
+        self.types
+            .get(typ)
+            .map(|type_config| type_config.icon.clone())
+    }
+
+    fn get_path_icon(&self, path: &Path) -> Option<Arc<str>> {
+        let suffix = path.icon_stem_or_suffix()?;
+
+        if let Some(type_str) = self.stems.get(suffix) {
+            return self.get_type_icon(type_str);
+        }
+
+        self.suffixes
+            .get(suffix)
+            .and_then(|type_str| self.get_type_icon(type_str))
+    }
+
+    fn get_language_icon(&self, language_name: &str) -> Option<Arc<str>> {
+        match self.languages.get(language_name) {
+            Some(type_str) => self.get_type_icon(type_str),
+            None => self.get_type_icon(&language_name.to_lowercase()),
+        }
+    }
+}
+
+/// The icon themes available to the application.
+pub struct FileIcons {
+    icon_themes: HashMap<SharedString, Arc<IconTheme>>,
+}
+
 impl Global for FileIcons {}
 
+/// The name of the icon theme bundled with Zed.
+pub const DEFAULT_ICON_THEME: &str = "Zed (Default)";
+
 const COLLAPSED_DIRECTORY_TYPE: &str = "collapsed_folder";
 const EXPANDED_DIRECTORY_TYPE: &str = "expanded_folder";
 const COLLAPSED_CHEVRON_TYPE: &str = "collapsed_chevron";
@@ -27,70 +101,297 @@ const EXPANDED_CHEVRON_TYPE: &str = "expanded_chevron";
 pub const FILE_TYPES_ASSET: &str = "icons/file_icons/file_types.json";
 
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
+    IconThemeSettings::register(cx);
     cx.set_global(FileIcons::new(assets))
 }
 
 impl FileIcons {
     pub fn new(assets: impl AssetSource) -> Self {
-        assets
-            .load("icons/file_icons/file_types.json")
+        let mut this = Self {
+            icon_themes: HashMap::default(),
+        };
+        this.reload_default_icon_theme(assets);
+        this
+    }
+
+    /// Reloads the bundled icon theme from the given assets, keeping any other icon themes.
+    pub fn reload_default_icon_theme(&mut self, assets: impl AssetSource) {
+        let default_icon_theme = assets
+            .load(FILE_TYPES_ASSET)
             .and_then(|file| {
-                serde_json::from_str::<FileIcons>(str::from_utf8(&file).unwrap())
+                serde_json::from_str::<IconTheme>(str::from_utf8(&file).unwrap())
                     .map_err(Into::into)
             })
-            .unwrap_or_else(|_| FileIcons {
+            .unwrap_or_else(|_| IconTheme {
+                name: SharedString::default(),
                 stems: HashMap::default(),
                 suffixes: HashMap::default(),
+                languages: HashMap::default(),
                 types: HashMap::default(),
-            })
+            });
+
+        self.icon_themes.insert(
+            SharedString::from(DEFAULT_ICON_THEME),
+            Arc::new(IconTheme {
+                name: DEFAULT_ICON_THEME.into(),
+                ..default_icon_theme
+            }),
+        );
     }
 
-    pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
-        let this = cx.try_global::<Self>()?;
+    /// Adds the given icon themes, replacing any existing themes with the same names.
+    pub fn insert_icon_themes(
+        icon_themes: impl IntoIterator<Item = IconTheme>,
+        cx: &mut AppContext,
+    ) {
+        let this = cx.default_global::<Self>();
+        for icon_theme in icon_themes {
+            this.icon_themes
+                .insert(icon_theme.name.clone(), Arc::new(icon_theme));
+        }
+    }
 
-        // FIXME: Associate a type with the languages and have the file's language
-        //        override these associations
-        maybe!({
-            let suffix = path.icon_stem_or_suffix()?;
+    /// Removes the icon themes with the given names. The bundled icon theme can't be removed.
+    pub fn remove_icon_themes(names: &[SharedString], cx: &mut AppContext) {
+        let this = cx.default_global::<Self>();
+        this.icon_themes
+            .retain(|name, _| name.as_ref() == DEFAULT_ICON_THEME || !names.contains(name));
+    }
 
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
-            }
+    /// Returns the names of the available icon themes.
+    pub fn icon_theme_names(&self) -> Vec<SharedString> {
+        let mut names = self.icon_themes.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
 
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
-        })
-        .or_else(|| this.get_type_icon("default"))
+    /// Returns the icon themes to look icons up in: the selected icon theme,
+    /// followed by the bundled one, which provides icons the selected theme lacks.
+    fn active_icon_themes(cx: &AppContext) -> impl Iterator<Item = &IconTheme> {
+        let this = cx.try_global::<Self>();
+        let selected_theme = this.map(|_| IconThemeSettings::get_global(cx).icon_theme.clone());
+        [selected_theme, Some(SharedString::from(DEFAULT_ICON_THEME))]
+            .into_iter()
+            .flatten()
+            .filter_map(move |name| this?.icon_themes.get(&name).map(|theme| theme.as_ref()))
     }
 
-    pub fn get_type_icon(&self, typ: &str) -> Option<Arc<str>> {
-        self.types
-            .get(typ)
-            .map(|type_config| type_config.icon.clone())
+    pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
+        Self::get_icon_for_file(path, None, cx)
     }
 
-    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
-        let this = cx.try_global::<Self>()?;
+    /// Returns the icon for the file at the given path, falling back to the
+    /// icon of the file's language when there's none for the path itself.
+    pub fn get_icon_for_file(
+        path: &Path,
+        language_name: Option<&str>,
+        cx: &AppContext,
+    ) -> Option<Arc<str>> {
+        Self::active_icon_themes(cx)
+            .find_map(|icon_theme| icon_theme.get_path_icon(path))
+            .or_else(|| language_name.and_then(|name| Self::get_language_icon(name, cx)))
+            .or_else(|| Self::get_type_icon("default", cx))
+    }
 
+    /// Returns the icon for files in the language with the given name.
+    pub fn get_language_icon(language_name: &str, cx: &AppContext) -> Option<Arc<str>> {
+        Self::active_icon_themes(cx)
+            .find_map(|icon_theme| icon_theme.get_language_icon(language_name))
+    }
+
+    pub fn get_type_icon(typ: &str, cx: &AppContext) -> Option<Arc<str>> {
+        Self::active_icon_themes(cx).find_map(|icon_theme| icon_theme.get_type_icon(typ))
+    }
+
+    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
         let key = if expanded {
             EXPANDED_DIRECTORY_TYPE
         } else {
             COLLAPSED_DIRECTORY_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
-        let this = cx.try_global::<Self>()?;
-
         let key = if expanded {
             EXPANDED_CHEVRON_TYPE
         } else {
             COLLAPSED_CHEVRON_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IconThemeSettings {
+    pub icon_theme: SharedString,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IconThemeSettingsContent {
+    /// The name of the icon theme to use for files and directories.
+    ///
+    /// Default: "Zed (Default)"
+    pub icon_theme: Option<String>,
+}
+
+impl Settings for IconThemeSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = IconThemeSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::borrow::Cow;
+
+    struct TestAssets;
+
+    impl AssetSource for TestAssets {
+        fn load(&self, path: &str) -> Result<Cow<'static, [u8]>> {
+            anyhow::ensure!(path == FILE_TYPES_ASSET, "no asset at {path}");
+            Ok(json!({
+                "stems": { "Makefile": "make" },
+                "suffixes": { "rs": "rust", "md": "document" },
+                "types": {
+                    "default": { "icon": "icons/file_icons/file.svg" },
+                    "document": { "icon": "icons/file_icons/book.svg" },
+                    "make": { "icon": "icons/file_icons/hammer.svg" },
+                    "rust": { "icon": "icons/file_icons/rust.svg" },
+                    "collapsed_folder": { "icon": "icons/file_icons/folder.svg" },
+                    "expanded_folder": { "icon": "icons/file_icons/folder_open.svg" }
+                }
+            })
+            .to_string()
+            .into_bytes()
+            .into())
+        }
+
+        fn list(&self, _path: &str) -> Result<Vec<SharedString>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[gpui::test]
+    async fn test_icon_themes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            init(TestAssets, cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/icon-themes/fancy",
+            json!({
+                "fancy.json": json!({
+                    "name": "Fancy",
+                    "suffixes": { "rs": "rust", "py": "python" },
+                    "languages": { "Shell Script": "shell" },
+                    "types": {
+                        "rust": { "icon": "icons/crab.svg" },
+                        "python": { "icon": "icons/snake.svg" },
+                        "shell": { "icon": "icons/shell.svg" },
+                        "expanded_folder": { "icon": "icons/open.svg" }
+                    }
+                })
+                .to_string(),
+            }),
+        )
+        .await;
+        let icon_theme = IconTheme::load(Path::new("/icon-themes/fancy/fancy.json"), fs.clone())
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            FileIcons::insert_icon_themes([icon_theme], cx);
+            assert_eq!(
+                cx.global::<FileIcons>().icon_theme_names(),
+                [SharedString::from("Fancy"), DEFAULT_ICON_THEME.into()]
+            );
+
+            // The bundled icon theme is used until another one is selected.
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/file_icons/rust.svg")
+            );
+
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<IconThemeSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Fancy".into());
+                });
+            });
+
+            // Icon paths are relative to the icon theme's directory.
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("/icon-themes/fancy/icons/crab.svg")
+            );
+            assert_eq!(
+                FileIcons::get_folder_icon(true, cx).as_deref(),
+                Some("/icon-themes/fancy/icons/open.svg")
+            );
+            // Icons the selected theme lacks come from the bundled one.
+            assert_eq!(
+                FileIcons::get_icon(Path::new("Makefile"), cx).as_deref(),
+                Some("icons/file_icons/hammer.svg")
+            );
+            assert_eq!(
+                FileIcons::get_folder_icon(false, cx).as_deref(),
+                Some("icons/file_icons/folder.svg")
+            );
+            assert_eq!(
+                FileIcons::get_icon(Path::new("notes.txt"), cx).as_deref(),
+                Some("icons/file_icons/file.svg")
+            );
+            // Files without an icon of their own get the icon of their language.
+            assert_eq!(
+                FileIcons::get_icon_for_file(Path::new("install"), Some("Shell Script"), cx)
+                    .as_deref(),
+                Some("/icon-themes/fancy/icons/shell.svg")
+            );
+
+            // Removing the selected icon theme falls back to the bundled one,
+            // which can't be removed.
+            FileIcons::remove_icon_themes(
+                &[SharedString::from("Fancy"), DEFAULT_ICON_THEME.into()],
+                cx,
+            );
+            assert_eq!(
+                cx.global::<FileIcons>().icon_theme_names(),
+                [SharedString::from(DEFAULT_ICON_THEME)]
+            );
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/file_icons/rust.svg")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_load_icon_theme_without_name(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/icon-themes",
+            json!({
+                "nameless.json": r#"{ "types": {} }"#,
+                "invalid.json": "{",
+            }),
+        )
+        .await;
+        for path in ["/icon-themes/nameless.json", "/icon-themes/invalid.json"] {
+            assert!(IconTheme::load(Path::new(path), fs.clone()).await.is_err());
+        }
     }
 }
@@ -12,7 +12,7 @@ use rope::Rope;
 #[cfg(any(test, feature = "test-support"))]
 use smol::io::AsyncReadExt;
 use smol::io::AsyncWriteExt;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::sync::Arc;
 use std::{
     io,
@@ -21,7 +21,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 use tempfile::{NamedTempFile, TempDir};
-use text::LineEnding;
+use text::{LineEnding, LoadedText, TextLoader, LOAD_CHUNK_SIZE};
 use util::{paths, ResultExt};
 
 #[cfg(any(test, feature = "test-support"))]
@@ -52,6 +52,9 @@ pub trait Fs: Send + Sync {
     async fn remove_file(&self, path: &Path, options: RemoveOptions) -> Result<()>;
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
     async fn load(&self, path: &Path) -> Result<String>;
+    /// Load a file as text in chunks, normalizing its line endings and replacing
+    /// any contents that aren't valid UTF-8.
+    async fn load_text(&self, path: &Path) -> Result<LoadedText>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
@@ -247,6 +250,25 @@ impl Fs for RealFs {
         Ok(text)
     }
 
+    async fn load_text(&self, path: &Path) -> Result<LoadedText> {
+        let path = path.to_path_buf();
+        smol::unblock(move || {
+            let mut file = std::fs::File::open(path)?;
+            let mut loader = TextLoader::new();
+            let mut chunk = vec![0; LOAD_CHUNK_SIZE];
+            loop {
+                match file.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(len) => loader.push(&chunk[..len]),
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            Ok(loader.finish())
+        })
+        .await
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = if cfg!(target_os = "linux") {
@@ -1271,6 +1293,15 @@ impl Fs for FakeFs {
         Ok(String::from_utf8(content.clone())?)
     }
 
+    async fn load_text(&self, path: &Path) -> Result<LoadedText> {
+        let content = self.load_internal(path).await?;
+        let mut loader = TextLoader::new();
+        for chunk in content.chunks(LOAD_CHUNK_SIZE) {
+            loader.push(chunk);
+        }
+        Ok(loader.finish())
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -1,6 +1,6 @@
 use crate::blame::Blame;
 use anyhow::{Context, Result};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use git2::{BranchType, StatusShow};
 use parking_lot::Mutex;
 use rope::Rope;
@@ -8,12 +8,16 @@ use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     path::{Component, Path, PathBuf},
+    process::{Command, Output},
     sync::Arc,
     time::SystemTime,
 };
 use sum_tree::{MapSeekTarget, TreeMap};
 use util::{paths::PathExt, ResultExt};
 
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
 pub use git2::Repository as LibGitRepository;
 
 #[derive(Clone, Debug, Hash, PartialEq)]
@@ -21,6 +25,30 @@ pub struct Branch {
     pub name: Box<str>,
     /// Timestamp of most recent commit, normalized to Unix Epoch format.
     pub unix_timestamp: Option<i64>,
+    /// Whether this branch is currently checked out.
+    pub is_head: bool,
+    /// Whether every commit on this branch is also reachable from HEAD.
+    pub is_merged: bool,
+}
+
+/// How a file differs from the HEAD commit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatusEntry {
+    pub repo_path: RepoPath,
+    /// How the file in the index differs from the HEAD commit.
+    pub staged: Option<GitFileStatus>,
+    /// How the file in the working directory differs from the index.
+    pub unstaged: Option<GitFileStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub sha: String,
+    pub short_sha: String,
+    pub subject: String,
+    pub author_name: String,
+    /// Commit time, as seconds since the Unix Epoch.
+    pub commit_timestamp: i64,
 }
 
 pub trait GitRepository: Send {
@@ -58,6 +86,37 @@ pub trait GitRepository: Send {
     fn branches(&self) -> Result<Vec<Branch>>;
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
+    /// Creates a branch pointing at the tip of the `base` branch, without checking it out.
+    fn create_branch_from(&self, name: &str, base: &str) -> Result<()>;
+    fn rename_branch(&self, name: &str, new_name: &str) -> Result<()>;
+    fn delete_branch(&self, name: &str) -> Result<()>;
+    /// Fetches `remote_ref` from the given remote into the local branch `branch`,
+    /// creating the branch if it doesn't exist yet, and resetting it to the fetched
+    /// commit otherwise, even if the remote ref was force-pushed.
+    fn fetch_branch(&self, remote: &str, remote_ref: &str, branch: &str) -> Result<()>;
+
+    /// Whether any tracked file differs from the HEAD commit.
+    fn has_uncommitted_changes(&self) -> Result<bool>;
+    /// Stashes all uncommitted changes to tracked files under the given message.
+    fn stash_changes(&self, message: &str) -> Result<()>;
+    /// Reapplies and drops the most recent stash with the given message, returning
+    /// whether there was such a stash.
+    fn pop_stash(&self, message: &str) -> Result<bool>;
+
+    /// Returns every file whose content in the index or the working directory differs
+    /// from the HEAD commit, sorted by path.
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>>;
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+    /// Returns a patch with the staged changes to the given file, or with its
+    /// unstaged changes if `staged` is false.
+    fn diff(&self, path: &RepoPath, staged: bool) -> Result<String>;
+    /// Commits the staged changes, signing the commit if `sign` is true.
+    fn commit(&self, message: &str, sign: bool) -> Result<()>;
+    /// Returns up to `limit` of the most recent commits reachable from HEAD.
+    fn log(&self, limit: usize) -> Result<Vec<CommitSummary>>;
+    /// Returns the message and patch of the given commit.
+    fn show_commit(&self, sha: &str) -> Result<String>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
 }
@@ -80,6 +139,39 @@ impl RealGitRepository {
             git_binary_path: git_binary_path.unwrap_or_else(|| PathBuf::from("git")),
         }
     }
+
+    fn run_git(&self, args: &[&str]) -> Result<String> {
+        let output = self.git_output(args)?;
+        anyhow::ensure!(
+            output.status.success(),
+            "'git {}' failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn git_output(&self, args: &[&str]) -> Result<Output> {
+        let working_directory = self
+            .repository
+            .workdir()
+            .context("failed to get git working directory")?;
+
+        let mut command = Command::new(&self.git_binary_path);
+        command.current_dir(working_directory).args(args);
+
+        #[cfg(windows)]
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+
+        command
+            .output()
+            .with_context(|| format!("failed to start 'git {}'", args.join(" ")))
+    }
+}
+
+fn repo_path_arg(path: &RepoPath) -> Result<&str> {
+    path.to_str()
+        .with_context(|| format!("path {:?} is not valid UTF-8", path))
 }
 
 impl GitRepository for RealGitRepository {
@@ -191,12 +283,21 @@ impl GitRepository for RealGitRepository {
     }
 
     fn branches(&self) -> Result<Vec<Branch>> {
+        let head_oid = self.repository.head().ok().and_then(|head| head.target());
         let local_branches = self.repository.branches(Some(BranchType::Local))?;
         let valid_branches = local_branches
             .filter_map(|branch| {
                 branch.ok().and_then(|(branch, _)| {
                     let name = branch.name().ok().flatten().map(Box::from)?;
-                    let timestamp = branch.get().peel_to_commit().ok()?.time();
+                    let commit = branch.get().peel_to_commit().ok()?;
+                    let is_merged = head_oid.map_or(false, |head_oid| {
+                        head_oid == commit.id()
+                            || self
+                                .repository
+                                .graph_descendant_of(head_oid, commit.id())
+                                .unwrap_or(false)
+                    });
+                    let timestamp = commit.time();
                     let unix_timestamp = timestamp.seconds();
                     let timezone_offset = timestamp.offset_minutes();
                     let utc_offset =
@@ -206,6 +307,8 @@ impl GitRepository for RealGitRepository {
                     Some(Branch {
                         name,
                         unix_timestamp: Some(unix_timestamp.to_offset(utc_offset).unix_timestamp()),
+                        is_head: branch.is_head(),
+                        is_merged,
                     })
                 })
             })
@@ -230,6 +333,214 @@ impl GitRepository for RealGitRepository {
 
         Ok(())
     }
+    fn create_branch_from(&self, name: &str, base: &str) -> Result<()> {
+        let base_commit = self
+            .repository
+            .find_branch(base, BranchType::Local)?
+            .get()
+            .peel_to_commit()?;
+        self.repository.branch(name, &base_commit, false)?;
+
+        Ok(())
+    }
+    fn rename_branch(&self, name: &str, new_name: &str) -> Result<()> {
+        let mut branch = self.repository.find_branch(name, BranchType::Local)?;
+        branch.rename(new_name, false)?;
+        Ok(())
+    }
+    fn delete_branch(&self, name: &str) -> Result<()> {
+        let mut branch = self.repository.find_branch(name, BranchType::Local)?;
+        anyhow::ensure!(!branch.is_head(), "cannot delete the current branch");
+        branch.delete()?;
+        Ok(())
+    }
+
+    fn fetch_branch(&self, remote: &str, remote_ref: &str, branch: &str) -> Result<()> {
+        self.run_git(&[
+            "fetch",
+            "--quiet",
+            remote,
+            &format!("+{remote_ref}:refs/heads/{branch}"),
+        ])?;
+        Ok(())
+    }
+
+    fn has_uncommitted_changes(&self) -> Result<bool> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(false);
+        options.include_ignored(false);
+        let statuses = self.repository.statuses(Some(&mut options))?;
+        Ok(!statuses.is_empty())
+    }
+
+    fn stash_changes(&self, message: &str) -> Result<()> {
+        self.run_git(&["stash", "push", "--message", message])?;
+        Ok(())
+    }
+
+    fn pop_stash(&self, message: &str) -> Result<bool> {
+        // Stash subjects look like "On <branch>: <message>".
+        let suffix = format!(": {message}");
+        let stashes = self.run_git(&["stash", "list", "--format=%gd %gs"])?;
+        let Some(stash) = stashes.lines().find_map(|line| {
+            let (stash, subject) = line.split_once(' ')?;
+            subject.ends_with(&suffix).then_some(stash)
+        }) else {
+            return Ok(false);
+        };
+        self.run_git(&["stash", "pop", stash])?;
+        Ok(true)
+    }
+
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true);
+        options.recurse_untracked_dirs(true);
+        options.include_ignored(false);
+        options.renames_head_to_index(false);
+
+        let statuses = self.repository.statuses(Some(&mut options))?;
+        let mut entries = statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = RepoPath(PathBuf::try_from_bytes(entry.path_bytes()).ok()?);
+                let status = entry.status();
+                if status.contains(git2::Status::CONFLICTED) {
+                    return Some(GitStatusEntry {
+                        repo_path: path,
+                        staged: None,
+                        unstaged: Some(GitFileStatus::Conflict),
+                    });
+                }
+                let staged = if status.contains(git2::Status::INDEX_NEW) {
+                    Some(GitFileStatus::Added)
+                } else if status.intersects(
+                    git2::Status::INDEX_MODIFIED
+                        | git2::Status::INDEX_RENAMED
+                        | git2::Status::INDEX_TYPECHANGE
+                        | git2::Status::INDEX_DELETED,
+                ) {
+                    Some(GitFileStatus::Modified)
+                } else {
+                    None
+                };
+                let unstaged = if status.contains(git2::Status::WT_NEW) {
+                    Some(GitFileStatus::Added)
+                } else if status.intersects(
+                    git2::Status::WT_MODIFIED
+                        | git2::Status::WT_RENAMED
+                        | git2::Status::WT_TYPECHANGE
+                        | git2::Status::WT_DELETED,
+                ) {
+                    Some(GitFileStatus::Modified)
+                } else {
+                    None
+                };
+                (staged.is_some() || unstaged.is_some()).then_some(GitStatusEntry {
+                    repo_path: path,
+                    staged,
+                    unstaged,
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+        Ok(entries)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["add", "--all", "--"];
+        for path in paths {
+            args.push(repo_path_arg(path)?);
+        }
+        self.run_git(&args)?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        // Before the first commit there's no HEAD to reset the index to.
+        let mut args = if self.repository.head().is_ok() {
+            vec!["reset", "--quiet", "HEAD", "--"]
+        } else {
+            vec!["rm", "--cached", "--quiet", "--"]
+        };
+        for path in paths {
+            args.push(repo_path_arg(path)?);
+        }
+        self.run_git(&args)?;
+        Ok(())
+    }
+
+    fn diff(&self, path: &RepoPath, staged: bool) -> Result<String> {
+        let path_arg = repo_path_arg(path)?;
+        if staged {
+            return self.run_git(&["diff", "--cached", "--", path_arg]);
+        }
+
+        let is_untracked = self
+            .repository
+            .status_file(path)
+            .map_or(false, |status| status.contains(git2::Status::WT_NEW));
+        if is_untracked {
+            // `git diff --no-index` exits with 1 when the files differ.
+            let args = ["diff", "--no-index", "--", "/dev/null", path_arg];
+            let output = self.git_output(&args)?;
+            anyhow::ensure!(
+                matches!(output.status.code(), Some(0) | Some(1)),
+                "'git {}' failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+        self.run_git(&["diff", "--", path_arg])
+    }
+
+    fn commit(&self, message: &str, sign: bool) -> Result<()> {
+        let mut args = vec!["commit", "--quiet", "--message", message];
+        if sign {
+            args.push("--gpg-sign");
+        }
+        self.run_git(&args)?;
+        Ok(())
+    }
+
+    fn log(&self, limit: usize) -> Result<Vec<CommitSummary>> {
+        if self.repository.head().is_err() {
+            return Ok(Vec::new());
+        }
+
+        const SEPARATOR: char = '\x1f';
+        let limit = limit.to_string();
+        let output = self.run_git(&[
+            "log",
+            "--max-count",
+            &limit,
+            "--format=%H%x1f%h%x1f%an%x1f%ct%x1f%s",
+        ])?;
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(5, SEPARATOR);
+                Some(CommitSummary {
+                    sha: fields.next()?.to_string(),
+                    short_sha: fields.next()?.to_string(),
+                    author_name: fields.next()?.to_string(),
+                    commit_timestamp: fields.next()?.parse().ok()?,
+                    subject: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<String> {
+        self.run_git(&["show", "--format=fuller", sha])
+    }
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame> {
         let working_directory = self
@@ -293,6 +604,19 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    pub branches: HashSet<String>,
+    /// The status of each staged file. Files that are only changed in the working
+    /// directory are in `worktree_statuses`.
+    pub index_statuses: HashMap<RepoPath, GitFileStatus>,
+    /// Every commit that was made, with the most recent last.
+    pub commits: Vec<CommitSummary>,
+    /// The message and file statuses of each stash, with the most recent last.
+    pub stashes: Vec<(String, HashMap<RepoPath, GitFileStatus>)>,
+    /// The paths whose stashed changes conflict with the checked out branch when
+    /// they're reapplied.
+    pub stash_conflicts: HashSet<RepoPath>,
+    /// The remote ref that each branch was last fetched from.
+    pub fetched_refs: HashMap<String, String>,
 }
 
 impl FakeGitRepository {
@@ -343,21 +667,204 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn branches(&self) -> Result<Vec<Branch>> {
-        Ok(vec![])
+        let state = self.state.lock();
+        Ok(state
+            .branches
+            .iter()
+            .map(|name| Branch {
+                name: name.as_str().into(),
+                unix_timestamp: None,
+                is_head: state.branch_name.as_ref() == Some(name),
+                is_merged: true,
+            })
+            .collect())
     }
 
     fn change_branch(&self, name: &str) -> Result<()> {
         let mut state = self.state.lock();
+        anyhow::ensure!(state.branches.contains(name), "no branch named {name:?}");
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
 
     fn create_branch(&self, name: &str) -> Result<()> {
         let mut state = self.state.lock();
+        state.branches.insert(name.to_owned());
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
 
+    fn create_branch_from(&self, name: &str, base: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(state.branches.contains(base), "no branch named {base:?}");
+        state.branches.insert(name.to_owned());
+        Ok(())
+    }
+
+    fn rename_branch(&self, name: &str, new_name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(state.branches.remove(name), "no branch named {name:?}");
+        state.branches.insert(new_name.to_owned());
+        if state.branch_name.as_deref() == Some(name) {
+            state.branch_name = Some(new_name.to_owned());
+        }
+        Ok(())
+    }
+
+    fn delete_branch(&self, name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            state.branch_name.as_deref() != Some(name),
+            "cannot delete the current branch"
+        );
+        anyhow::ensure!(state.branches.remove(name), "no branch named {name:?}");
+        Ok(())
+    }
+
+    fn fetch_branch(&self, _remote: &str, remote_ref: &str, branch: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            state.branch_name.as_deref() != Some(branch),
+            "refusing to fetch into checked out branch {branch:?}"
+        );
+        state.branches.insert(branch.to_owned());
+        state
+            .fetched_refs
+            .insert(branch.to_owned(), remote_ref.to_owned());
+        Ok(())
+    }
+
+    fn has_uncommitted_changes(&self) -> Result<bool> {
+        let state = self.state.lock();
+        Ok(!state.worktree_statuses.is_empty() || !state.index_statuses.is_empty())
+    }
+
+    fn stash_changes(&self, message: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let statuses = std::mem::take(&mut state.worktree_statuses);
+        state.stashes.push((message.to_owned(), statuses));
+        Ok(())
+    }
+
+    fn pop_stash(&self, message: &str) -> Result<bool> {
+        let mut state = self.state.lock();
+        let Some(ix) = state
+            .stashes
+            .iter()
+            .rposition(|(stash, _)| stash == message)
+        else {
+            return Ok(false);
+        };
+        let conflicts = state.stashes[ix]
+            .1
+            .keys()
+            .filter(|path| state.stash_conflicts.contains(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !conflicts.is_empty() {
+            // Like git, apply the changes with conflict markers and keep the stash.
+            let statuses = state.stashes[ix].1.clone();
+            state.worktree_statuses.extend(statuses);
+            for path in conflicts {
+                state
+                    .worktree_statuses
+                    .insert(path, GitFileStatus::Conflict);
+            }
+            anyhow::bail!("the stashed changes conflict with the checked out branch");
+        }
+        let (_, statuses) = state.stashes.remove(ix);
+        state.worktree_statuses.extend(statuses);
+        Ok(true)
+    }
+
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>> {
+        let state = self.state.lock();
+        let mut entries = state
+            .index_statuses
+            .keys()
+            .chain(state.worktree_statuses.keys())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|repo_path| GitStatusEntry {
+                repo_path: repo_path.clone(),
+                staged: state.index_statuses.get(repo_path).copied(),
+                unstaged: state.worktree_statuses.get(repo_path).copied(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+        Ok(entries)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if let Some(status) = state.worktree_statuses.remove(path) {
+                let status = match state.index_statuses.get(path) {
+                    Some(GitFileStatus::Added) => GitFileStatus::Added,
+                    _ => status,
+                };
+                state.index_statuses.insert(path.clone(), status);
+            }
+        }
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if let Some(status) = state.index_statuses.remove(path) {
+                state
+                    .worktree_statuses
+                    .entry(path.clone())
+                    .or_insert(status);
+            }
+        }
+        Ok(())
+    }
+
+    fn diff(&self, path: &RepoPath, staged: bool) -> Result<String> {
+        let state = self.state.lock();
+        let statuses = if staged {
+            &state.index_statuses
+        } else {
+            &state.worktree_statuses
+        };
+        let status = statuses
+            .get(path)
+            .with_context(|| format!("no changes to {:?}", path))?;
+        Ok(format!("{:?} {}\n", status, path.display()))
+    }
+
+    fn commit(&self, message: &str, _sign: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(!state.index_statuses.is_empty(), "nothing to commit");
+        state.index_statuses.clear();
+        let sha = format!("{:040x}", state.commits.len() + 1);
+        state.commits.push(CommitSummary {
+            short_sha: sha[..7].to_string(),
+            sha,
+            subject: message.lines().next().unwrap_or_default().to_string(),
+            author_name: "Fake Author".to_string(),
+            commit_timestamp: 0,
+        });
+        Ok(())
+    }
+
+    fn log(&self, limit: usize) -> Result<Vec<CommitSummary>> {
+        let state = self.state.lock();
+        Ok(state.commits.iter().rev().take(limit).cloned().collect())
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<String> {
+        let state = self.state.lock();
+        let commit = state
+            .commits
+            .iter()
+            .find(|commit| commit.sha == sha)
+            .with_context(|| format!("no commit {sha}"))?;
+        Ok(format!("commit {}\n\n    {}\n", commit.sha, commit.subject))
+    }
+
     fn blame(&self, path: &Path, _content: Rope) -> Result<crate::blame::Blame> {
         let state = self.state.lock();
         state
@@ -0,0 +1,190 @@
+//! Hints about the conventional shape of a commit message: a short subject line,
+//! then a blank line, then a body wrapped at a fixed width.
+//! Also builds the prompt used to have a language model draft such a message.
+
+/// The length above which a commit's subject line gets truncated by most tools.
+pub const SUBJECT_MAX_LEN: usize = 50;
+/// The width at which a commit's body is conventionally wrapped.
+pub const BODY_MAX_LINE_LEN: usize = 72;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitMessageHint {
+    /// The subject line is longer than [`SUBJECT_MAX_LEN`] characters.
+    LongSubject { len: usize },
+    /// The subject line is directly followed by the body.
+    MissingBlankLine,
+    /// The body has lines longer than [`BODY_MAX_LINE_LEN`] characters,
+    /// given as (zero-based) rows of the message.
+    LongBodyLines { rows: Vec<u32> },
+}
+
+impl CommitMessageHint {
+    pub fn message(&self) -> String {
+        match self {
+            CommitMessageHint::LongSubject { len } => {
+                format!("Subject is {len} characters long, try to keep it under {SUBJECT_MAX_LEN}")
+            }
+            CommitMessageHint::MissingBlankLine => {
+                "Separate the subject from the body with a blank line".to_string()
+            }
+            CommitMessageHint::LongBodyLines { rows } => {
+                let lines = if rows.len() == 1 { "line" } else { "lines" };
+                format!(
+                    "{} body {lines} longer than {BODY_MAX_LINE_LEN} characters",
+                    rows.len()
+                )
+            }
+        }
+    }
+}
+
+/// Returns the subject line of the given commit message.
+pub fn subject(message: &str) -> &str {
+    message.lines().next().unwrap_or_default()
+}
+
+/// Whether the given commit message has nothing but whitespace.
+pub fn is_blank(message: &str) -> bool {
+    message.trim().is_empty()
+}
+
+pub fn commit_message_hints(message: &str) -> Vec<CommitMessageHint> {
+    let mut hints = Vec::new();
+    let mut lines = message.lines();
+
+    let subject_len = lines.next().unwrap_or_default().chars().count();
+    if subject_len > SUBJECT_MAX_LEN {
+        hints.push(CommitMessageHint::LongSubject { len: subject_len });
+    }
+
+    if lines.next().map_or(false, |line| !line.trim().is_empty()) {
+        hints.push(CommitMessageHint::MissingBlankLine);
+    }
+
+    let long_rows = message
+        .lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| line.chars().count() > BODY_MAX_LINE_LEN)
+        .map(|(row, _)| row as u32)
+        .collect::<Vec<_>>();
+    if !long_rows.is_empty() {
+        hints.push(CommitMessageHint::LongBodyLines { rows: long_rows });
+    }
+
+    hints
+}
+
+/// How much of the staged diff is included when asking a model to draft a commit message.
+const DRAFT_DIFF_MAX_LEN: usize = 16 * 1024;
+
+/// Returns the prompt asking a language model for a commit message describing the given diff.
+pub fn draft_prompt(staged_diff: &str) -> String {
+    let mut diff_len = staged_diff.len().min(DRAFT_DIFF_MAX_LEN);
+    while !staged_diff.is_char_boundary(diff_len) {
+        diff_len -= 1;
+    }
+    let truncation_note = if diff_len < staged_diff.len() {
+        "\n(The rest of the diff was truncated.)"
+    } else {
+        ""
+    };
+
+    format!(
+        "Write a commit message for the following staged changes.\n\
+        Use the conventional commit format: `<type>(<optional scope>): <description>`, \
+        where the type is one of feat, fix, docs, style, refactor, perf, test, build, ci or chore.\n\
+        Keep the subject line under {SUBJECT_MAX_LEN} characters and use the imperative mood. \
+        If the change needs explaining, add a body after a blank line, \
+        wrapped at {BODY_MAX_LINE_LEN} characters.\n\
+        Respond with the commit message only, without any surrounding formatting.\n\n\
+        ```diff\n{}\n```{truncation_note}",
+        &staged_diff[..diff_len]
+    )
+}
+
+/// Cleans up a (possibly partial) commit message drafted by a language model,
+/// stripping the code fence models sometimes wrap it in.
+pub fn clean_draft(draft: &str) -> String {
+    let mut draft = draft.trim();
+    if let Some(fenced) = draft.strip_prefix("```") {
+        // Skip the fence's info string, if any.
+        draft = fenced.split_once('\n').map_or("", |(_, rest)| rest);
+        draft = draft.trim_end().trim_end_matches('`');
+    }
+    draft.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_formed_message() {
+        assert_eq!(commit_message_hints(""), vec![]);
+        assert_eq!(commit_message_hints("Fix a crash"), vec![]);
+        assert_eq!(
+            commit_message_hints("Fix a crash\n\nThe panel no longer panics when closed.\n"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_long_subject() {
+        let subject = "a".repeat(SUBJECT_MAX_LEN + 1);
+        assert_eq!(
+            commit_message_hints(&subject),
+            vec![CommitMessageHint::LongSubject {
+                len: SUBJECT_MAX_LEN + 1
+            }]
+        );
+        // Characters are counted, not bytes.
+        assert_eq!(commit_message_hints(&"é".repeat(SUBJECT_MAX_LEN)), vec![]);
+    }
+
+    #[test]
+    fn test_body_hints() {
+        let long_line = "b".repeat(BODY_MAX_LINE_LEN + 1);
+        assert_eq!(
+            commit_message_hints(&format!("Subject\nBody\n{long_line}\nshort\n{long_line}")),
+            vec![
+                CommitMessageHint::MissingBlankLine,
+                CommitMessageHint::LongBodyLines { rows: vec![2, 4] },
+            ]
+        );
+        assert_eq!(commit_message_hints("Subject\n   \nBody"), vec![]);
+    }
+
+    #[test]
+    fn test_subject() {
+        assert_eq!(subject(""), "");
+        assert_eq!(subject("Subject\n\nBody"), "Subject");
+        assert!(is_blank(" \n\t\n"));
+        assert!(!is_blank("\nBody"));
+    }
+
+    #[test]
+    fn test_draft_prompt() {
+        let prompt = draft_prompt("+fn main() {}");
+        assert!(prompt.contains("```diff\n+fn main() {}\n```"));
+        assert!(!prompt.contains("truncated"));
+
+        // Long diffs are truncated on a character boundary.
+        let prompt = draft_prompt(&format!("a{}", "é".repeat(DRAFT_DIFF_MAX_LEN)));
+        let kept = format!("a{}", "é".repeat(DRAFT_DIFF_MAX_LEN / 2 - 1));
+        assert!(prompt.contains(&format!("\n{kept}\n```")));
+        assert!(prompt.contains("truncated"));
+    }
+
+    #[test]
+    fn test_clean_draft() {
+        assert_eq!(clean_draft("  feat: add panel\n"), "feat: add panel");
+        assert_eq!(
+            clean_draft("```text\nfix(editor): wrap lines\n\nBody\n```"),
+            "fix(editor): wrap lines\n\nBody"
+        );
+        // A draft that is still streaming in.
+        assert_eq!(clean_draft("```\nfeat: add"), "feat: add");
+        assert_eq!(clean_draft("```"), "");
+    }
+}
@@ -0,0 +1,834 @@
+mod commit_message;
+mod git_panel_settings;
+
+use anyhow::{anyhow, Result};
+use assistant::{CompletionProvider, LanguageModelRequest, LanguageModelRequestMessage, Role};
+use commit_message::{commit_message_hints, SUBJECT_MAX_LEN};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use futures::StreamExt;
+use git::repository::{CommitSummary, GitFileStatus, GitRepository, GitStatusEntry, RepoPath};
+use git_panel_settings::GitPanelSettings;
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Pixels, Render, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use parking_lot::Mutex;
+use project::{Fs, Project};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use theme::ActiveTheme;
+use ui::{prelude::*, Checkbox, Selection, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+const HISTORY_LIMIT: usize = 50;
+
+actions!(git_panel, [ToggleFocus, Commit, StageAll, UnstageAll, DraftCommitMessage]);
+
+pub fn init(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+}
+
+/// Lists the changes in the project's git repository, lets them be staged and
+/// committed, and shows the repository's recent history.
+pub struct GitPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    repository: Option<Arc<Mutex<dyn GitRepository>>>,
+    branch_name: Option<String>,
+    status_entries: Vec<GitStatusEntry>,
+    commits: Vec<CommitSummary>,
+    commit_editor: View<Editor>,
+    sign_commit: bool,
+    committing: bool,
+    /// Streams a commit message drafted by the assistant's language model into the commit editor.
+    pending_draft: Option<Task<()>>,
+    error: Option<SharedString>,
+    pending_refresh: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+}
+
+impl GitPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let weak_workspace = cx.view().downgrade();
+        cx.new_view(|cx| {
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::auto_height(8, cx);
+                editor.set_placeholder_text("Commit message", cx);
+                editor
+            });
+            let subscriptions = vec![
+                cx.subscribe(&commit_editor, |_, _, event, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        cx.notify();
+                    }
+                }),
+                cx.subscribe(&project, |this, _, event, cx| match event {
+                    project::Event::WorktreeAdded
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeUpdatedGitRepositories => this.refresh(cx),
+                    _ => {}
+                }),
+            ];
+
+            let mut this = Self {
+                workspace: weak_workspace,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                repository: None,
+                branch_name: None,
+                status_entries: Vec::new(),
+                commits: Vec::new(),
+                commit_editor,
+                sign_commit: GitPanelSettings::get_global(cx).sign_commits,
+                committing: false,
+                pending_draft: None,
+                error: None,
+                pending_refresh: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            this.set_commit_message_language(cx);
+            this.refresh(cx);
+            this
+        })
+    }
+
+    /// Highlights the commit message editor as a git commit message.
+    fn set_commit_message_language(&mut self, cx: &mut ViewContext<Self>) {
+        let languages = self.project.read(cx).languages().clone();
+        let Some(buffer) = self.commit_editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let language = languages.language_for_name("Git Commit").await?;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_language(Some(language), cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedGitPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn open_repository(&self, cx: &AppContext) -> Option<Arc<Mutex<dyn GitRepository>>> {
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let worktree = project.visible_worktrees(cx).next()?;
+        let dot_git = worktree.read(cx).abs_path().join(".git");
+        project.fs().open_repo(&dot_git)
+    }
+
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.repository = self.open_repository(cx);
+        let Some(repository) = self.repository.clone() else {
+            self.branch_name = None;
+            self.status_entries.clear();
+            self.commits.clear();
+            cx.notify();
+            return;
+        };
+
+        self.pending_refresh = cx.spawn(|this, mut cx| async move {
+            let state = cx
+                .background_executor()
+                .spawn(async move {
+                    let repository = repository.lock();
+                    anyhow::Ok((
+                        repository.branch_name(),
+                        repository.status_entries()?,
+                        repository.log(HISTORY_LIMIT)?,
+                    ))
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                match state {
+                    Ok((branch_name, status_entries, commits)) => {
+                        this.branch_name = branch_name;
+                        this.status_entries = status_entries;
+                        this.commits = commits;
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Runs the given operation on the repository in the background, then refreshes the panel.
+    fn update_repository(
+        &mut self,
+        operation: impl 'static + Send + FnOnce(&dyn GitRepository) -> Result<()>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(repository) = self.repository.clone() else {
+            return Task::ready(Err(anyhow!("no git repository")));
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            let result = cx
+                .background_executor()
+                .spawn(async move { operation(&*repository.lock()) })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.error = result.as_ref().err().map(|error| error.to_string().into());
+                this.refresh(cx);
+            })?;
+            result
+        })
+    }
+
+    fn staged_entries(&self) -> impl Iterator<Item = (&RepoPath, GitFileStatus)> {
+        self.status_entries
+            .iter()
+            .filter_map(|entry| Some((&entry.repo_path, entry.staged?)))
+    }
+
+    fn unstaged_entries(&self) -> impl Iterator<Item = (&RepoPath, GitFileStatus)> {
+        self.status_entries
+            .iter()
+            .filter_map(|entry| Some((&entry.repo_path, entry.unstaged?)))
+    }
+
+    fn set_staged(&mut self, paths: Vec<RepoPath>, staged: bool, cx: &mut ViewContext<Self>) {
+        self.update_repository(
+            move |repository| {
+                if staged {
+                    repository.stage_paths(&paths)
+                } else {
+                    repository.unstage_paths(&paths)
+                }
+            },
+            cx,
+        )
+        .detach();
+    }
+
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .unstaged_entries()
+            .map(|(path, _)| path.clone())
+            .collect();
+        self.set_staged(paths, true, cx);
+    }
+
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .staged_entries()
+            .map(|(path, _)| path.clone())
+            .collect();
+        self.set_staged(paths, false, cx);
+    }
+
+    fn commit(&mut self, _: &Commit, cx: &mut ViewContext<Self>) {
+        let message = self.commit_editor.read(cx).text(cx);
+        if self.committing
+            || commit_message::is_blank(&message)
+            || self.staged_entries().next().is_none()
+        {
+            return;
+        }
+
+        let sign = self.sign_commit;
+        let commit = self.update_repository(
+            move |repository| repository.commit(message.trim_end(), sign),
+            cx,
+        );
+        self.committing = true;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = commit.await;
+            this.update(&mut cx, |this, cx| {
+                this.committing = false;
+                if result.is_ok() {
+                    this.commit_editor.update(cx, |editor, cx| editor.clear(cx));
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Asks the assistant's language model to draft a message for the staged changes,
+    /// replacing the contents of the commit editor so the draft can be edited before committing.
+    fn draft_commit_message(&mut self, _: &DraftCommitMessage, cx: &mut ViewContext<Self>) {
+        if self.pending_draft.is_some() {
+            return;
+        }
+        let Some(repository) = self.repository.clone() else {
+            return;
+        };
+        let staged_paths = self
+            .staged_entries()
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        if staged_paths.is_empty() {
+            return;
+        }
+
+        let provider = CompletionProvider::global(cx);
+        if !provider.is_authenticated() {
+            self.error = Some("Configure a language model in the assistant panel first".into());
+            cx.notify();
+            return;
+        }
+        let model = provider.default_model();
+        self.error = None;
+
+        self.pending_draft = Some(cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let diffs = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let repository = repository.lock();
+                        staged_paths
+                            .iter()
+                            .map(|path| repository.diff(path, true))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .await?;
+                let request = LanguageModelRequest {
+                    model,
+                    messages: vec![LanguageModelRequestMessage {
+                        role: Role::User,
+                        content: commit_message::draft_prompt(&diffs.join("\n")),
+                    }],
+                    stop: Vec::new(),
+                    temperature: 1.0,
+                };
+
+                let mut chunks = cx
+                    .update(|cx| CompletionProvider::global(cx).complete(request))?
+                    .await?;
+                let mut draft = String::new();
+                while let Some(chunk) = chunks.next().await {
+                    draft.push_str(&chunk?);
+                    let message = commit_message::clean_draft(&draft);
+                    this.update(&mut cx, |this, cx| {
+                        this.commit_editor
+                            .update(cx, |editor, cx| editor.set_text(message, cx));
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.pending_draft = None;
+                if let Err(error) = result {
+                    this.error = Some(format!("Failed to draft commit message: {error}").into());
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn open_file_diff(&mut self, path: RepoPath, staged: bool, cx: &mut ViewContext<Self>) {
+        let title = if staged {
+            format!("{} (Staged)", path.display())
+        } else {
+            format!("{} (Unstaged)", path.display())
+        };
+        self.open_diff(title, move |repository| repository.diff(&path, staged), cx);
+    }
+
+    fn open_commit_diff(&mut self, commit: &CommitSummary, cx: &mut ViewContext<Self>) {
+        let sha = commit.sha.clone();
+        self.open_diff(
+            format!("{} {}", commit.short_sha, commit.subject),
+            move |repository| repository.show_commit(&sha),
+            cx,
+        );
+    }
+
+    /// Opens a read-only editor with the patch returned by `diff`.
+    fn open_diff(
+        &mut self,
+        title: String,
+        diff: impl 'static + Send + FnOnce(&dyn GitRepository) -> Result<String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(repository) = self.repository.clone() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        let project = self.project.clone();
+
+        cx.spawn(|this, mut cx| async move {
+            let diff = cx
+                .background_executor()
+                .spawn(async move { diff(&*repository.lock()) })
+                .await;
+            let diff = match diff {
+                Ok(diff) => diff,
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| {
+                        this.error = Some(error.to_string().into());
+                        cx.notify();
+                    })?;
+                    return Ok(());
+                }
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let buffer =
+                    project.update(cx, |project, cx| project.create_buffer(&diff, None, cx))?;
+                let buffer =
+                    cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::for_multibuffer(buffer, Some(project), cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item_to_active_pane(Box::new(editor), cx);
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Icon::new(IconName::FileGit).color(Color::Muted))
+                    .child(Label::new(
+                        self.branch_name.clone().unwrap_or_else(|| "Git".into()),
+                    )),
+            )
+            .child(
+                IconButton::new("refresh", IconName::ArrowCircle)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Refresh", cx))
+                    .on_click(cx.listener(|this, _, cx| this.refresh(cx))),
+            )
+    }
+
+    fn render_commit_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let message = self.commit_editor.read(cx).text(cx);
+        let subject_len = commit_message::subject(&message).chars().count();
+        let has_staged_entries = self.staged_entries().next().is_some();
+        let can_commit =
+            !self.committing && !commit_message::is_blank(&message) && has_staged_entries;
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .child(
+                div()
+                    .p_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.commit_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(format!("{subject_len}/{SUBJECT_MAX_LEN}"))
+                            .size(LabelSize::Small)
+                            .color(if subject_len > SUBJECT_MAX_LEN {
+                                Color::Warning
+                            } else {
+                                Color::Muted
+                            }),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                IconButton::new("draft-commit-message", IconName::Ai)
+                                    .icon_size(IconSize::Small)
+                                    .selected(self.pending_draft.is_some())
+                                    .disabled(!has_staged_entries || self.pending_draft.is_some())
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action(
+                                            "Draft Commit Message",
+                                            &DraftCommitMessage,
+                                            cx,
+                                        )
+                                    })
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.draft_commit_message(&DraftCommitMessage, cx)
+                                    })),
+                            )
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .child(
+                                        Checkbox::new(
+                                            "sign-commit",
+                                            if self.sign_commit {
+                                                Selection::Selected
+                                            } else {
+                                                Selection::Unselected
+                                            },
+                                        )
+                                        .on_click(
+                                            cx.listener(|this, selection, cx| {
+                                                this.sign_commit =
+                                                    *selection == Selection::Selected;
+                                                cx.notify();
+                                            }),
+                                        ),
+                                    )
+                                    .child(Label::new("Sign").size(LabelSize::Small)),
+                            )
+                            .child(
+                                Button::new(
+                                    "commit",
+                                    if self.committing {
+                                        "Committing…"
+                                    } else {
+                                        "Commit"
+                                    },
+                                )
+                                .style(ButtonStyle::Filled)
+                                .disabled(!can_commit)
+                                .on_click(cx.listener(|this, _, cx| this.commit(&Commit, cx))),
+                            ),
+                    ),
+            )
+            .children(commit_message_hints(&message).into_iter().map(|hint| {
+                Label::new(hint.message())
+                    .size(LabelSize::Small)
+                    .color(Color::Warning)
+            }))
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
+    }
+
+    fn render_section_header(
+        &self,
+        title: &'static str,
+        count: usize,
+        button: Button,
+    ) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .pt_2()
+            .justify_between()
+            .child(
+                Label::new(format!("{title} ({count})"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when(count > 0, |this| this.child(button))
+    }
+
+    fn render_status_entry(
+        &self,
+        ix: usize,
+        repo_path: &RepoPath,
+        status: GitFileStatus,
+        staged: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let (status_label, status_color) = match status {
+            GitFileStatus::Added => ("A", Color::Created),
+            GitFileStatus::Modified => ("M", Color::Modified),
+            GitFileStatus::Conflict => ("!", Color::Conflict),
+        };
+        let (entry_id, diff_id) = if staged {
+            ("staged-entry", "staged-diff")
+        } else {
+            ("unstaged-entry", "unstaged-diff")
+        };
+
+        h_flex()
+            .id((entry_id, ix))
+            .px_2()
+            .gap_2()
+            .w_full()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                Label::new(status_label)
+                    .size(LabelSize::Small)
+                    .color(status_color),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    Label::new(repo_path.to_string_lossy().to_string()).size(LabelSize::Small),
+                ),
+            )
+            .child(
+                IconButton::new((diff_id, ix), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Open Diff", cx))
+                    .on_click(cx.listener({
+                        let repo_path = repo_path.clone();
+                        move |this, _, cx| this.open_file_diff(repo_path.clone(), staged, cx)
+                    })),
+            )
+            .tooltip(move |cx| Tooltip::text(if staged { "Unstage" } else { "Stage" }, cx))
+            .on_click(cx.listener({
+                let repo_path = repo_path.clone();
+                move |this, _, cx| this.set_staged(vec![repo_path.clone()], !staged, cx)
+            }))
+    }
+
+    fn render_commit(
+        &self,
+        ix: usize,
+        commit: &CommitSummary,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let timestamp = time::OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+            .ok()
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    time::OffsetDateTime::now_utc(),
+                    cx.local_timezone(),
+                    time_format::TimestampFormat::Relative,
+                )
+            });
+        let author_name = SharedString::from(commit.author_name.clone());
+
+        h_flex()
+            .id(("commit", ix))
+            .px_2()
+            .gap_2()
+            .w_full()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                Label::new(commit.short_sha.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(Label::new(commit.subject.clone()).size(LabelSize::Small)),
+            )
+            .children(timestamp.map(|timestamp| {
+                Label::new(timestamp)
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+            }))
+            .tooltip(move |cx| Tooltip::text(author_name.clone(), cx))
+            .on_click(cx.listener({
+                let commit = commit.clone();
+                move |this, _, cx| this.open_commit_diff(&commit, cx)
+            }))
+    }
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let root = v_flex()
+            .key_context("GitPanel")
+            .on_action(cx.listener(Self::commit))
+            .on_action(cx.listener(Self::stage_all))
+            .on_action(cx.listener(Self::unstage_all))
+            .on_action(cx.listener(Self::draft_commit_message))
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(self.render_header(cx));
+
+        if self.repository.is_none() {
+            return root.child(v_flex().p_4().child(
+                Label::new("This project doesn't have a git repository.").color(Color::Muted),
+            ));
+        }
+
+        let staged_count = self.staged_entries().count();
+        let unstaged_count = self.unstaged_entries().count();
+        let staged_entries = self
+            .staged_entries()
+            .enumerate()
+            .map(|(ix, (path, status))| (ix, path.clone(), status))
+            .collect::<Vec<_>>();
+        let unstaged_entries = self
+            .unstaged_entries()
+            .enumerate()
+            .map(|(ix, (path, status))| (ix, path.clone(), status))
+            .collect::<Vec<_>>();
+
+        root.child(self.render_commit_editor(cx)).child(
+            v_flex()
+                .id("git-panel-entries")
+                .flex_1()
+                .overflow_y_scroll()
+                .child(
+                    self.render_section_header(
+                        "Staged Changes",
+                        staged_count,
+                        Button::new("unstage-all", "Unstage All")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| this.unstage_all(&UnstageAll, cx))),
+                    ),
+                )
+                .children(staged_entries.into_iter().map(|(ix, path, status)| {
+                    self.render_status_entry(ix, &path, status, true, cx)
+                }))
+                .child(
+                    self.render_section_header(
+                        "Changes",
+                        unstaged_count,
+                        Button::new("stage-all", "Stage All")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| this.stage_all(&StageAll, cx))),
+                    ),
+                )
+                .children(unstaged_entries.into_iter().map(|(ix, path, status)| {
+                    self.render_status_entry(ix, &path, status, false, cx)
+                }))
+                .child(
+                    h_flex().px_2().pt_2().child(
+                        Label::new("History")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+                .children(
+                    self.commits
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, commit)| self.render_commit(ix, commit, cx)),
+                ),
+        )
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "GitPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        GitPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(self.fs.clone(), cx, move |settings| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn icon_label(&self, _: &WindowContext) -> Option<String> {
+        let count = self.status_entries.len();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
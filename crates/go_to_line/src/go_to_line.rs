@@ -3,9 +3,11 @@ pub mod cursor_position;
 use cursor_position::LineIndicatorFormat;
 use editor::{scroll::Autoscroll, Editor};
 use gpui::{
-    actions, div, prelude::*, AnyWindowHandle, AppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, Render, SharedString, Styled, Subscription, View, ViewContext, VisualContext,
+    actions, div, impl_actions, prelude::*, AnyWindowHandle, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, Render, SharedString, Styled, Subscription, View,
+    ViewContext, VisualContext,
 };
+use serde::Deserialize;
 use settings::Settings;
 use text::{Bias, Point};
 use theme::ActiveTheme;
@@ -15,6 +17,16 @@ use workspace::ModalView;
 
 actions!(go_to_line, [Toggle]);
 
+/// Moves the cursor to the given line and column, both 1-based.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct GoToLineNumber {
+    pub line: u32,
+    #[serde(default)]
+    pub column: Option<u32>,
+}
+
+impl_actions!(go_to_line, [GoToLineNumber]);
+
 pub fn init(cx: &mut AppContext) {
     LineIndicatorFormat::register(cx);
     cx.observe_new_views(GoToLine::register).detach();
@@ -53,6 +65,23 @@ impl GoToLine {
                 workspace.toggle_modal(cx, move |cx| GoToLine::new(editor, cx));
             })
         });
+        let handle = cx.view().downgrade();
+        editor.register_action(move |action: &GoToLineNumber, cx| {
+            let Some(editor) = handle.upgrade() else {
+                return;
+            };
+            let point = Point::new(
+                action.line.saturating_sub(1),
+                action.column.unwrap_or(0).saturating_sub(1),
+            );
+            editor.update(cx, |editor, cx| {
+                let snapshot = editor.snapshot(cx).display_snapshot;
+                let point = snapshot.buffer_snapshot.clip_point(point, Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point])
+                });
+            });
+        });
     }
 
     pub fn new(active_editor: View<Editor>, cx: &mut ViewContext<Self>) -> Self {
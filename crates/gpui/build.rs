@@ -77,6 +77,9 @@ mod macos {
             "Pixels".into(),
             "PointF".into(),
             "Hsla".into(),
+            "ColorStop".into(),
+            "BackgroundTag".into(),
+            "Background".into(),
             "ContentMask".into(),
             "Uniforms".into(),
             "AtlasTile".into(),
@@ -94,6 +97,8 @@ mod macos {
             "PathSprite".into(),
             "SurfaceInputIndex".into(),
             "SurfaceBounds".into(),
+            "BackdropBlurInputIndex".into(),
+            "BackdropBlur".into(),
             "TransformationMatrix".into(),
         ]);
         config.no_includes = true;
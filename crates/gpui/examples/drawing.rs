@@ -0,0 +1,106 @@
+use std::{f32::consts::PI, rc::Rc};
+
+use gpui::*;
+
+struct DrawingExample {
+    drawing: Rc<Drawing>,
+}
+
+impl DrawingExample {
+    fn new() -> Self {
+        let mut drawing = Drawing::new();
+
+        // A line chart with its area shaded underneath.
+        let values = [0.2, 0.5, 0.35, 0.8, 0.6, 0.9, 0.4];
+        let chart_bounds = Bounds::new(point(px(20.), px(20.)), size(px(360.), px(160.)));
+        let points = values
+            .iter()
+            .enumerate()
+            .map(|(ix, value)| {
+                point(
+                    chart_bounds.origin.x
+                        + chart_bounds.size.width * (ix as f32 / (values.len() - 1) as f32),
+                    chart_bounds.lower_left().y - chart_bounds.size.height * *value,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut line = PathBuilder::new();
+        line.move_to(points[0]);
+        for point in &points[1..] {
+            line.line_to(*point);
+        }
+        let mut area = line.clone();
+        area.line_to(chart_bounds.lower_right())
+            .line_to(chart_bounds.lower_left())
+            .close();
+
+        drawing
+            .quad(outline(chart_bounds, rgb(0x444444)))
+            .fill(&area, rgba(0x4f8ff733))
+            .stroke(&line, px(2.), rgb(0x4f8ff7));
+
+        // A donut made of two overlapping circles, and a curve.
+        let mut donut = PathBuilder::new();
+        donut
+            .circle(point(px(80.), px(260.)), px(50.))
+            .circle(point(px(80.), px(260.)), px(25.));
+        let mut arc = PathBuilder::new();
+        arc.arc(
+            point(px(80.), px(260.)),
+            px(60.),
+            radians(-PI / 2.),
+            radians(PI / 4.),
+        );
+        let mut curve = PathBuilder::new();
+        curve.move_to(point(px(180.), px(300.))).cubic_bezier_to(
+            point(px(220.), px(180.)),
+            point(px(300.), px(340.)),
+            point(px(380.), px(220.)),
+        );
+
+        drawing
+            .fill(&donut, rgb(0xe0a030))
+            .stroke(&arc, px(4.), rgb(0xd04040))
+            .stroke(&curve, px(3.), rgb(0x40c070))
+            .text(
+                point(px(20.), px(340.)),
+                "Drawn with a retained drawing",
+                px(14.),
+                vec![TextRun {
+                    len: "Drawn with a retained drawing".len(),
+                    font: font(".SystemUIFont"),
+                    color: rgb(0xffffff).into(),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                }],
+            );
+
+        Self {
+            drawing: Rc::new(drawing),
+        }
+    }
+}
+
+impl Render for DrawingExample {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .bg(rgb(0x1e1e1e))
+            .child(gpui::drawing(self.drawing.clone()).size_full())
+    }
+}
+
+fn main() {
+    App::new().run(|cx: &mut AppContext| {
+        let bounds = Bounds::centered(None, size(px(400.), px(380.)), cx);
+        cx.open_window(
+            WindowOptions {
+                bounds: Some(bounds),
+                ..Default::default()
+            },
+            |cx| cx.new_view(|_cx| DrawingExample::new()),
+        );
+    });
+}
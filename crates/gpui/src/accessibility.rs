@@ -0,0 +1,30 @@
+use crate::SharedString;
+use std::ops::Range;
+
+/// The role an accessible element plays, which determines how assistive technologies
+/// such as screen readers present it to the user.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccessibleRole {
+    /// A multi-line text editing surface.
+    #[default]
+    TextArea,
+    /// A single-line text input.
+    TextField,
+}
+
+/// A description of the focused text surface, reported to the platform's accessibility APIs.
+///
+/// Register it during paint by calling [`ElementContext::set_accessible_text`](crate::ElementContext::set_accessible_text).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessibleText {
+    /// The role of the element.
+    pub role: AccessibleRole,
+    /// A short, human-readable name for the element, e.g. the title of the file being edited.
+    pub label: SharedString,
+    /// The text content that is exposed to assistive technologies.
+    pub value: String,
+    /// The selected range in `value`, in UTF-16 code units. Empty when there's just a cursor.
+    pub selected_range_utf16: Range<usize>,
+    /// The zero-based line in `value` that contains the cursor.
+    pub insertion_line: usize,
+}
@@ -109,6 +109,9 @@ pub struct App(Rc<AppCell>);
 /// configured, you'll start the app with `App::run`.
 impl App {
     /// Builds an app with the given asset source.
+    ///
+    /// Its HTTP client only follows the `*_PROXY` environment variables, so applications
+    /// with their own network settings should replace it with [`AppContext::set_http_client`].
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         #[cfg(any(test, feature = "test-support"))]
@@ -121,6 +124,17 @@ impl App {
         ))
     }
 
+    /// Builds an app that runs without a display server, whose windows are never shown and
+    /// can only be rasterized with [`WindowContext::render_to_image`].
+    #[cfg(target_os = "linux")]
+    pub fn headless() -> Self {
+        Self(AppContext::new(
+            crate::headless_platform(),
+            Arc::new(()),
+            http::client(),
+        ))
+    }
+
     /// Assign
     pub fn with_assets(self, asset_source: impl AssetSource) -> Self {
         let mut context_lock = self.0.borrow_mut();
@@ -243,6 +257,7 @@ pub struct AppContext {
     pub(crate) layout_id_buffer: Vec<LayoutId>, // We recycle this memory across layout requests.
     pub(crate) propagate_event: bool,
     pub(crate) prompt_builder: Option<PromptBuilder>,
+    reduce_motion: bool,
 }
 
 impl AppContext {
@@ -304,6 +319,7 @@ impl AppContext {
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
                 prompt_builder: Some(PromptBuilder::Default),
+                reduce_motion: false,
             }),
         });
 
@@ -352,12 +368,31 @@ impl AppContext {
         self.app_metadata.clone()
     }
 
+    /// The number of models and views that are currently alive.
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
     /// Schedules all windows in the application to be redrawn. This can be called
     /// multiple times in an update cycle and still result in a single redraw.
     pub fn refresh(&mut self) {
         self.pending_effects.push_back(Effect::Refresh);
     }
 
+    /// Returns whether animations should be skipped, jumping straight to their final state.
+    pub fn reduce_motion(&self) -> bool {
+        self.reduce_motion
+    }
+
+    /// Sets whether animations should be skipped, for users who are sensitive to motion.
+    /// Animations run with [`Animation`]s respect this automatically.
+    pub fn set_reduce_motion(&mut self, reduce_motion: bool) {
+        if self.reduce_motion != reduce_motion {
+            self.reduce_motion = reduce_motion;
+            self.refresh();
+        }
+    }
+
     pub(crate) fn update<R>(&mut self, update: impl FnOnce(&mut Self) -> R) -> R {
         self.pending_updates += 1;
         let result = update(self);
@@ -656,6 +691,12 @@ impl AppContext {
         self.http_client.clone()
     }
 
+    /// Replaces the http client used by GPUI, e.g. so that images are loaded
+    /// through the same proxy as the rest of the application's requests.
+    pub fn set_http_client(&mut self, http_client: Arc<dyn HttpClient>) {
+        self.http_client = http_client;
+    }
+
     /// Returns the SVG renderer GPUI uses
     pub(crate) fn svg_renderer(&self) -> SvgRenderer {
         self.svg_renderer.clone()
@@ -1163,6 +1204,12 @@ impl AppContext {
         self.platform.add_recent_document(path);
     }
 
+    /// Shows a notification through the operating system's notification center,
+    /// where it's visible even when the application isn't focused.
+    pub fn show_notification(&self, title: &str, body: &str) {
+        self.platform.show_notification(title, body);
+    }
+
     /// Dispatch an action to the currently active window or global action handler
     /// See [action::Action] for more information on how actions work
     pub fn dispatch_action(&mut self, action: &dyn Action) {
@@ -1228,6 +1275,13 @@ impl AppContext {
         self.active_drag.is_some()
     }
 
+    /// Returns the value being dragged, if there is an active drag of the given type.
+    pub fn active_drag<T: 'static>(&self) -> Option<&T> {
+        self.active_drag
+            .as_ref()
+            .and_then(|drag| drag.value.downcast_ref())
+    }
+
     /// Set the prompt renderer for GPUI. This will replace the default or platform specific
     /// prompts with this custom implementation.
     pub fn set_prompt_builder(
@@ -77,6 +77,11 @@ impl EntityMap {
         }
     }
 
+    /// The number of entities that are currently alive.
+    pub fn len(&self) -> usize {
+        self.ref_counts.read().counts.len()
+    }
+
     /// Reserve a slot for an entity, which you can subsequently use with `insert`.
     pub fn reserve<T: 'static>(&self) -> Slot<T> {
         let id = self.ref_counts.write().counts.insert(1.into());
@@ -1,13 +1,15 @@
 use crate::{
-    Action, AnyElement, AnyView, AnyWindowHandle, AppCell, AppContext, AsyncAppContext,
-    AvailableSpace, BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, Context, Empty,
-    Entity, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Model, ModelContext,
-    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform, TestWindow,
-    TextSystem, View, ViewContext, VisualContext, WindowContext, WindowHandle, WindowOptions,
+    AccessibleText, Action, AnyElement, AnyView, AnyWindowHandle, AppCell, AppContext,
+    AsyncAppContext, AvailableSpace, BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem,
+    Context, Empty, Entity, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Model,
+    ModelContext, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, Pixels, Platform, Point, Render, Result, Size, Task, TestDispatcher,
+    TestPlatform, TestWindow, TextSystem, View, ViewContext, VisualContext, WindowContext,
+    WindowHandle, WindowOptions,
 };
 use anyhow::{anyhow, bail};
 use futures::{channel::oneshot, Stream, StreamExt};
+use image::RgbaImage;
 use std::{cell::RefCell, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration};
 
 /// A TestAppContext is provided to tests created with `#[gpui::test]`, it provides
@@ -390,6 +392,35 @@ impl TestAppContext {
         self.background_executor.run_until_parked()
     }
 
+    /// simulate_ime_preedit shows text that an input method is composing at the cursor,
+    /// where it stays marked until it is committed. Empty text cancels the composition.
+    /// This will also run the background executor until it's parked.
+    pub fn simulate_ime_preedit(&mut self, window: AnyWindowHandle, text: &str) {
+        self.update_window(window, |_, cx| {
+            if let Some(mut input_handler) = cx.window.platform_window.take_input_handler() {
+                input_handler.dispatch_ime_preedit(text, cx);
+                cx.window.platform_window.set_input_handler(input_handler);
+            }
+        })
+        .unwrap();
+
+        self.background_executor.run_until_parked()
+    }
+
+    /// simulate_ime_commit inserts text from an input method, replacing the text it was composing.
+    /// This will also run the background executor until it's parked.
+    pub fn simulate_ime_commit(&mut self, window: AnyWindowHandle, text: &str) {
+        self.update_window(window, |_, cx| {
+            if let Some(mut input_handler) = cx.window.platform_window.take_input_handler() {
+                input_handler.dispatch_input(text, cx);
+                cx.window.platform_window.set_input_handler(input_handler);
+            }
+        })
+        .unwrap();
+
+        self.background_executor.run_until_parked()
+    }
+
     /// dispatches a single Keystroke (see also `simulate_keystrokes` and `simulate_input`)
     pub fn dispatch_keystroke(&mut self, window: AnyWindowHandle, keystroke: Keystroke) {
         self.update_window(window, |_, cx| cx.dispatch_keystroke(keystroke))
@@ -671,6 +702,21 @@ impl VisualTestContext {
         self.cx.test_window(self.window).0.lock().title.clone()
     }
 
+    /// Read the text reported to assistive technologies (set by `ElementContext#set_accessible_text`)
+    pub fn accessible_text(&mut self) -> Option<AccessibleText> {
+        self.cx
+            .test_window(self.window)
+            .0
+            .lock()
+            .accessible_text
+            .clone()
+    }
+
+    /// Take the messages announced to assistive technologies (via `WindowContext#announce`)
+    pub fn take_announcements(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cx.test_window(self.window).0.lock().announcements)
+    }
+
     /// Simulate a sequence of keystrokes `cx.simulate_keystrokes("cmd-p escape")`
     /// Automatically runs until parked.
     pub fn simulate_keystrokes(&mut self, keystrokes: &str) {
@@ -683,6 +729,18 @@ impl VisualTestContext {
         self.cx.simulate_input(self.window, input)
     }
 
+    /// Simulate an input method composing text `cx.simulate_ime_preedit("にほ")`
+    /// Automatically runs until parked.
+    pub fn simulate_ime_preedit(&mut self, text: &str) {
+        self.cx.simulate_ime_preedit(self.window, text)
+    }
+
+    /// Simulate an input method committing text `cx.simulate_ime_commit("日本")`
+    /// Automatically runs until parked.
+    pub fn simulate_ime_commit(&mut self, text: &str) {
+        self.cx.simulate_ime_commit(self.window, text)
+    }
+
     /// Simulate a mouse move event to the given point
     pub fn simulate_mouse_move(&mut self, position: Point<Pixels>, modifiers: Modifiers) {
         self.simulate_event(MouseMoveEvent {
@@ -719,11 +777,30 @@ impl VisualTestContext {
         self.simulate_window_resize(self.window, size)
     }
 
+    /// Simulates the platform asking the window for its next frame, which runs any animation
+    /// frame callbacks that have been scheduled.
+    pub fn simulate_frame(&self) {
+        self.cx.test_window(self.window).simulate_frame()
+    }
+
+    /// Simulate the window moving to a display with a different scale factor.
+    pub fn simulate_scale_factor_change(&self, scale_factor: f32) {
+        self.cx
+            .test_window(self.window)
+            .simulate_scale_factor_change(scale_factor)
+    }
+
     /// debug_bounds returns the bounds of the element with the given selector.
     pub fn debug_bounds(&mut self, selector: &'static str) -> Option<Bounds<Pixels>> {
         self.update(|cx| cx.window.rendered_frame.debug_bounds.get(selector).copied())
     }
 
+    /// Rasterizes the window's current frame into an image, so tests can assert on what it
+    /// looks like. Draws the window first if it's dirty.
+    pub fn render_to_image(&mut self) -> RgbaImage {
+        self.update(|cx| cx.render_to_image()).unwrap()
+    }
+
     /// Draw an element to the window. Useful for simulating events or actions
     pub fn draw(
         &mut self,
@@ -442,6 +442,113 @@ impl<'de> Deserialize<'de> for Hsla {
     }
 }
 
+/// A color placed at a position along a gradient.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct ColorStop {
+    /// The color at this stop.
+    pub color: Hsla,
+    /// Where this stop is placed along the gradient, in a range from 0 to 1.
+    pub percentage: f32,
+}
+
+impl Eq for ColorStop {}
+
+/// Construct a [`ColorStop`] placing the given color at the given percentage of a gradient.
+pub fn color_stop(color: impl Into<Hsla>, percentage: f32) -> ColorStop {
+    ColorStop {
+        color: color.into(),
+        percentage: percentage.clamp(0., 1.),
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub(crate) enum BackgroundTag {
+    #[default]
+    Solid = 0,
+    LinearGradient = 1,
+    RadialGradient = 2,
+}
+
+/// A solid color or a gradient, used to fill a quad.
+///
+/// Gradients interpolate between two [`ColorStop`]s. Construct them with
+/// [`linear_gradient`] and [`radial_gradient`].
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct Background {
+    pub(crate) tag: BackgroundTag,
+    pub(crate) solid: Hsla,
+    /// The angle of a linear gradient, in degrees.
+    pub(crate) angle: f32,
+    pub(crate) colors: [ColorStop; 2],
+}
+
+impl Eq for Background {}
+
+impl Background {
+    /// Returns the color of this background if it's a solid color.
+    pub fn solid_color(&self) -> Option<Hsla> {
+        match self.tag {
+            BackgroundTag::Solid => Some(self.solid),
+            BackgroundTag::LinearGradient | BackgroundTag::RadialGradient => None,
+        }
+    }
+
+    /// Returns true if no part of this background is visible.
+    pub fn is_transparent(&self) -> bool {
+        match self.tag {
+            BackgroundTag::Solid => self.solid.is_transparent(),
+            BackgroundTag::LinearGradient | BackgroundTag::RadialGradient => {
+                self.colors.iter().all(|stop| stop.color.is_transparent())
+            }
+        }
+    }
+}
+
+impl From<Hsla> for Background {
+    fn from(color: Hsla) -> Self {
+        Background {
+            solid: color,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Rgba> for Background {
+    fn from(color: Rgba) -> Self {
+        Hsla::from(color).into()
+    }
+}
+
+/// Construct a linear gradient between two color stops.
+///
+/// Like in CSS, the angle is in degrees, where 0 points from the bottom towards
+/// the top of the filled shape and angles increase clockwise.
+pub fn linear_gradient(
+    angle: f32,
+    from: impl Into<ColorStop>,
+    to: impl Into<ColorStop>,
+) -> Background {
+    Background {
+        tag: BackgroundTag::LinearGradient,
+        angle,
+        colors: [from.into(), to.into()],
+        ..Default::default()
+    }
+}
+
+/// Construct a radial gradient between two color stops, going from the center
+/// of the filled shape (at 0) outwards to its edges (at 1).
+pub fn radial_gradient(from: impl Into<ColorStop>, to: impl Into<ColorStop>) -> Background {
+    Background {
+        tag: BackgroundTag::RadialGradient,
+        colors: [from.into(), to.into()],
+        ..Default::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -489,4 +596,29 @@ mod tests {
 
         assert_eq!(actual, rgba(0xdeadbeef))
     }
+
+    #[test]
+    fn test_gradients() {
+        let red = hsla(0., 1., 0.5, 1.);
+        let clear = hsla(0., 1., 0.5, 0.);
+
+        // Stops are kept within the gradient.
+        assert_eq!(color_stop(red, 1.5).percentage, 1.);
+        assert_eq!(color_stop(red, -0.5).percentage, 0.);
+
+        let solid = Background::from(red);
+        assert_eq!(solid.solid_color(), Some(red));
+        assert!(!solid.is_transparent());
+        assert!(Background::from(clear).is_transparent());
+
+        let linear = linear_gradient(90., color_stop(red, 0.), color_stop(clear, 1.));
+        assert_eq!(linear.solid_color(), None);
+        assert_eq!(linear.angle, 90.);
+        assert!(!linear.is_transparent());
+
+        // A gradient is only transparent if all of its colors are.
+        let radial = radial_gradient(color_stop(clear, 0.), color_stop(clear, 1.));
+        assert_eq!(radial.solid_color(), None);
+        assert!(radial.is_transparent());
+    }
 }
@@ -1,6 +1,13 @@
-use std::time::{Duration, Instant};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crate::{AnyElement, Element, ElementId, IntoElement};
+use crate::{
+    AnyElement, Bounds, Corners, Edges, Element, ElementId, Hsla, IntoElement, Pixels, Point, Rems,
+    Size, WindowContext,
+};
 
 pub use easing::*;
 
@@ -39,6 +46,404 @@ impl Animation {
         self.easing = Box::new(easing);
         self
     }
+
+    /// Create an animation that follows the given spring, running for as long as it takes the
+    /// spring to settle. Underdamped springs overshoot, so the animation's delta can leave the
+    /// range between 0 and 1 before it ends at 1.
+    pub fn spring(spring: Spring) -> Self {
+        let duration = spring.settling_duration();
+        Self {
+            duration,
+            oneshot: true,
+            easing: Box::new(move |delta| {
+                if delta >= 1.0 {
+                    1.0
+                } else {
+                    spring.value(duration.mul_f32(delta))
+                }
+            }),
+        }
+    }
+
+    /// Returns the eased delta of this animation after it has been running for the given
+    /// amount of time, along with whether it has finished. When motion is reduced, animations
+    /// that run once skip straight to their end and repeating ones stay at their start.
+    pub fn progress(&self, elapsed: Duration, reduce_motion: bool) -> (f32, bool) {
+        let (delta, done) = if reduce_motion {
+            (if self.oneshot { 1.0 } else { 0.0 }, true)
+        } else if self.duration.is_zero() {
+            (1.0, self.oneshot)
+        } else {
+            let delta = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+            if delta < 1.0 {
+                (delta, false)
+            } else if self.oneshot {
+                (1.0, true)
+            } else {
+                (delta % 1.0, false)
+            }
+        };
+        ((self.easing)(delta), done)
+    }
+}
+
+/// A damped spring pulling an animated value from 0 towards 1, which tends to feel more
+/// natural than a fixed easing curve for interactions like dragging and dismissing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    /// How strongly the spring pulls towards its target.
+    pub stiffness: f32,
+    /// How strongly the spring's motion is slowed down. Springs with little damping oscillate
+    /// around their target before settling.
+    pub damping: f32,
+    /// The mass attached to the spring, where heavier masses move more slowly.
+    pub mass: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+        }
+    }
+}
+
+impl Spring {
+    /// The distance from the target within which a spring is considered to have settled.
+    const SETTLED_THRESHOLD: f32 = 0.001;
+    /// The longest a spring is allowed to run for, so weak springs still end.
+    const MAX_SETTLING_DURATION: Duration = Duration::from_secs(10);
+
+    /// Create a spring with the given stiffness and damping, and a mass of 1.
+    pub fn new(stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.0,
+        }
+    }
+
+    /// Set the mass attached to this spring.
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Returns the spring's position after the given amount of time, starting at rest at 0.
+    pub fn value(&self, elapsed: Duration) -> f32 {
+        1.0 - self.displacement(elapsed.as_secs_f32())
+    }
+
+    /// Returns how long it takes for the spring to stay within a small distance of its target.
+    pub fn settling_duration(&self) -> Duration {
+        let step = Duration::from_millis(1);
+        let mut settled_at = Duration::ZERO;
+        let mut elapsed = Duration::ZERO;
+        while elapsed < Self::MAX_SETTLING_DURATION {
+            if self.displacement(elapsed.as_secs_f32()).abs() >= Self::SETTLED_THRESHOLD {
+                settled_at = elapsed + step;
+            }
+            elapsed += step;
+        }
+        settled_at
+    }
+
+    /// The distance from the target after `t` seconds, found by solving the damped harmonic
+    /// oscillator for a displacement of 1 and no initial velocity.
+    fn displacement(&self, t: f32) -> f32 {
+        if self.stiffness <= 0.0 || self.mass <= 0.0 {
+            return 0.0;
+        }
+
+        let natural_frequency = (self.stiffness / self.mass).sqrt();
+        let damping_ratio = self.damping / (2.0 * (self.stiffness * self.mass).sqrt());
+        if (damping_ratio - 1.0).abs() < 1e-4 {
+            (-natural_frequency * t).exp() * (1.0 + natural_frequency * t)
+        } else if damping_ratio < 1.0 {
+            let decay = damping_ratio * natural_frequency;
+            let damped_frequency = natural_frequency * (1.0 - damping_ratio * damping_ratio).sqrt();
+            (-decay * t).exp()
+                * ((damped_frequency * t).cos()
+                    + decay / damped_frequency * (damped_frequency * t).sin())
+        } else {
+            let root = (damping_ratio * damping_ratio - 1.0).sqrt();
+            let slow = -natural_frequency * (damping_ratio - root);
+            let fast = -natural_frequency * (damping_ratio + root);
+            (fast * (slow * t).exp() - slow * (fast * t).exp()) / (fast - slow)
+        }
+    }
+}
+
+/// A value that can be animated by blending between two of its instances.
+pub trait Interpolate {
+    /// Returns the value the given fraction of the way from `self` to `to`, where 0 returns
+    /// `self` and 1 returns `to`. Fractions outside of that range extrapolate.
+    fn interpolate(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Interpolate for Pixels {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Pixels(self.0.interpolate(&to.0, t))
+    }
+}
+
+impl Interpolate for Rems {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Rems(self.0.interpolate(&to.0, t))
+    }
+}
+
+impl Interpolate for Hsla {
+    /// Blends each component separately, turning the hue along the shorter way around the
+    /// color wheel.
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        let mut hue_distance = to.h - self.h;
+        if hue_distance > 0.5 {
+            hue_distance -= 1.0;
+        } else if hue_distance < -0.5 {
+            hue_distance += 1.0;
+        }
+        Hsla {
+            h: (self.h + hue_distance * t).rem_euclid(1.0),
+            s: self.s.interpolate(&to.s, t).clamp(0.0, 1.0),
+            l: self.l.interpolate(&to.l, t).clamp(0.0, 1.0),
+            a: self.a.interpolate(&to.a, t).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<T> Interpolate for Point<T>
+where
+    T: Interpolate + Clone + Default + std::fmt::Debug,
+{
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Point {
+            x: self.x.interpolate(&to.x, t),
+            y: self.y.interpolate(&to.y, t),
+        }
+    }
+}
+
+impl<T> Interpolate for Size<T>
+where
+    T: Interpolate + Clone + Default + std::fmt::Debug,
+{
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Size {
+            width: self.width.interpolate(&to.width, t),
+            height: self.height.interpolate(&to.height, t),
+        }
+    }
+}
+
+impl<T> Interpolate for Bounds<T>
+where
+    T: Interpolate + Clone + Default + std::fmt::Debug,
+{
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Bounds {
+            origin: self.origin.interpolate(&to.origin, t),
+            size: self.size.interpolate(&to.size, t),
+        }
+    }
+}
+
+impl<T> Interpolate for Edges<T>
+where
+    T: Interpolate + Clone + Default + std::fmt::Debug,
+{
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Edges {
+            top: self.top.interpolate(&to.top, t),
+            right: self.right.interpolate(&to.right, t),
+            bottom: self.bottom.interpolate(&to.bottom, t),
+            left: self.left.interpolate(&to.left, t),
+        }
+    }
+}
+
+impl<T> Interpolate for Corners<T>
+where
+    T: Interpolate + Clone + Default + std::fmt::Debug,
+{
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Corners {
+            top_left: self.top_left.interpolate(&to.top_left, t),
+            top_right: self.top_right.interpolate(&to.top_right, t),
+            bottom_right: self.bottom_right.interpolate(&to.bottom_right, t),
+            bottom_left: self.bottom_left.interpolate(&to.bottom_left, t),
+        }
+    }
+}
+
+/// The values a property passes through over the course of an animation, so a single
+/// animation can drive several properties that change at different times, e.g.
+///
+/// ```ignore
+/// let opacity = Keyframes::new(0.).then(0.25, 1.).then(0.75, 1.).then(1., 0.);
+/// label.with_animation("toast", animation, move |label, delta| {
+///     label.opacity(opacity.sample(delta))
+/// })
+/// ```
+#[derive(Clone)]
+pub struct Keyframes<T> {
+    frames: Vec<Keyframe<T>>,
+}
+
+#[derive(Clone)]
+struct Keyframe<T> {
+    offset: f32,
+    value: T,
+    easing: Rc<dyn Fn(f32) -> f32>,
+}
+
+impl<T: Interpolate + Clone> Keyframes<T> {
+    /// Start from the given value at the beginning of the animation.
+    pub fn new(initial: T) -> Self {
+        Self {
+            frames: vec![Keyframe {
+                offset: 0.0,
+                value: initial,
+                easing: Rc::new(linear),
+            }],
+        }
+    }
+
+    /// Reach the given value at the given offset into the animation, between 0 and 1,
+    /// interpolating linearly from the previous keyframe.
+    pub fn then(self, offset: f32, value: T) -> Self {
+        self.then_with_easing(offset, value, linear)
+    }
+
+    /// Reach the given value at the given offset into the animation, between 0 and 1, easing
+    /// from the previous keyframe with the given function. Offsets before the previous
+    /// keyframe's are moved up to it.
+    pub fn then_with_easing(
+        mut self,
+        offset: f32,
+        value: T,
+        easing: impl Fn(f32) -> f32 + 'static,
+    ) -> Self {
+        let previous_offset = self.frames.last().map_or(0.0, |frame| frame.offset);
+        self.frames.push(Keyframe {
+            offset: offset.max(previous_offset),
+            value,
+            easing: Rc::new(easing),
+        });
+        self
+    }
+
+    /// Returns the value at the given animation delta. Deltas before the first keyframe or
+    /// after the last one, like those of an overshooting spring, extrapolate from the
+    /// nearest pair of keyframes.
+    pub fn sample(&self, delta: f32) -> T {
+        if self.frames.len() == 1 {
+            return self.frames[0].value.clone();
+        }
+
+        let ix = self
+            .frames
+            .partition_point(|frame| frame.offset < delta)
+            .clamp(1, self.frames.len() - 1);
+        let (from, to) = (&self.frames[ix - 1], &self.frames[ix]);
+        let span = to.offset - from.offset;
+        if span <= 0.0 {
+            return to.value.clone();
+        }
+
+        let t = (delta - from.offset) / span;
+        let t = if (0.0..=1.0).contains(&t) {
+            (to.easing)(t)
+        } else {
+            t
+        };
+        from.value.interpolate(&to.value, t)
+    }
+}
+
+/// A handle to an animation or animation frame callback, which is cancelled when the handle
+/// is dropped unless it has been detached.
+#[must_use]
+pub struct AnimationHandle {
+    cancelled: Rc<Cell<bool>>,
+    detached: bool,
+}
+
+impl AnimationHandle {
+    pub(crate) fn new() -> Self {
+        Self {
+            cancelled: Rc::new(Cell::new(false)),
+            detached: false,
+        }
+    }
+
+    pub(crate) fn cancelled(&self) -> Rc<Cell<bool>> {
+        self.cancelled.clone()
+    }
+
+    /// Stop the animation before its next frame.
+    pub fn cancel(self) {
+        self.cancelled.set(true);
+    }
+
+    /// Let the animation run to completion, even after this handle is dropped.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+
+    /// Returns whether the animation has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+impl Drop for AnimationHandle {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.cancelled.set(true);
+        }
+    }
+}
+
+/// An animation started with [`WindowContext::animate`], which advances once per frame.
+pub(crate) struct RunningAnimation {
+    pub(crate) animation: Animation,
+    pub(crate) start: Instant,
+    pub(crate) on_frame: Box<dyn FnMut(f32, &mut WindowContext)>,
+    pub(crate) cancelled: Rc<Cell<bool>>,
+}
+
+impl RunningAnimation {
+    pub(crate) fn schedule(self, cx: &mut WindowContext) {
+        let cancelled = self.cancelled.clone();
+        cx.on_next_frame(move |cx| {
+            if !cancelled.get() {
+                self.tick(cx);
+            }
+        });
+    }
+
+    fn tick(mut self, cx: &mut WindowContext) {
+        let elapsed = cx
+            .background_executor()
+            .now()
+            .saturating_duration_since(self.start);
+        let (delta, done) = self.animation.progress(elapsed, cx.reduce_motion());
+        (self.on_frame)(delta, cx);
+        if done {
+            self.cancelled.set(true);
+        } else {
+            self.schedule(cx);
+        }
+    }
 }
 
 /// An extension trait for adding the animation wrapper to both Elements and Components
@@ -94,26 +499,13 @@ impl<E: IntoElement + 'static> Element for AnimationElement<E> {
         cx: &mut crate::ElementContext,
     ) -> (crate::LayoutId, Self::RequestLayoutState) {
         cx.with_element_state(Some(self.id.clone()), |state, cx| {
-            let state = state.unwrap().unwrap_or_else(|| AnimationState {
-                start: Instant::now(),
-            });
-            let mut delta =
-                state.start.elapsed().as_secs_f32() / self.animation.duration.as_secs_f32();
-
-            let mut done = false;
-            if delta > 1.0 {
-                if self.animation.oneshot {
-                    done = true;
-                    delta = 1.0;
-                } else {
-                    delta = delta % 1.0;
-                }
-            }
-            let delta = (self.animation.easing)(delta);
-
-            debug_assert!(
-                delta >= 0.0 && delta <= 1.0,
-                "delta should always be between 0 and 1"
+            let now = cx.background_executor().now();
+            let state = state
+                .unwrap()
+                .unwrap_or_else(|| AnimationState { start: now });
+            let (delta, done) = self.animation.progress(
+                now.saturating_duration_since(state.start),
+                cx.reduce_motion(),
             );
 
             let element = self.element.take().expect("should only be called once");
@@ -186,3 +578,84 @@ mod easing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, px, TestAppContext};
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_spring() {
+        for spring in [
+            Spring::default(),
+            Spring::new(100., 5.),
+            Spring::new(100., 20.),
+            Spring::new(100., 60.).mass(2.),
+        ] {
+            let duration = spring.settling_duration();
+            assert!(duration > Duration::ZERO && duration < Duration::from_secs(10));
+            assert_eq!(spring.value(Duration::ZERO), 0.);
+            assert!((spring.value(duration) - 1.).abs() < 0.001);
+        }
+
+        let bouncy = Spring::new(100., 5.);
+        assert!((0..1000).any(|ms| bouncy.value(Duration::from_millis(ms)) > 1.));
+    }
+
+    #[test]
+    fn test_keyframes() {
+        let keyframes = Keyframes::new(px(0.)).then(0.5, px(100.)).then(1., px(50.));
+        assert_eq!(keyframes.sample(0.), px(0.));
+        assert_eq!(keyframes.sample(0.25), px(50.));
+        assert_eq!(keyframes.sample(0.5), px(100.));
+        assert_eq!(keyframes.sample(0.75), px(75.));
+        assert_eq!(keyframes.sample(1.), px(50.));
+        assert_eq!(keyframes.sample(1.5), px(0.));
+
+        let red = Hsla {
+            h: 0.95,
+            s: 1.,
+            l: 0.5,
+            a: 1.,
+        };
+        let orange = Hsla { h: 0.05, ..red };
+        let hue = red.interpolate(&orange, 0.5).h;
+        assert!(hue < 0.001 || hue > 0.999);
+    }
+
+    #[gpui::test]
+    fn test_animate(cx: &mut TestAppContext) {
+        fn animate(deltas: &Rc<RefCell<Vec<f32>>>, cx: &mut WindowContext) -> AnimationHandle {
+            let deltas = deltas.clone();
+            cx.animate(Animation::new(Duration::from_secs(1)), move |delta, _| {
+                deltas.borrow_mut().push(delta)
+            })
+        }
+
+        let cx = cx.add_empty_window();
+        let deltas = Rc::new(RefCell::new(Vec::new()));
+
+        let handle = cx.update(|cx| animate(&deltas, cx));
+        cx.simulate_frame();
+        cx.executor().advance_clock(Duration::from_millis(500));
+        cx.simulate_frame();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.simulate_frame();
+        cx.simulate_frame();
+        assert_eq!(deltas.take(), [0., 0.5, 1.]);
+        drop(handle);
+
+        let handle = cx.update(|cx| animate(&deltas, cx));
+        cx.simulate_frame();
+        handle.cancel();
+        cx.simulate_frame();
+        assert_eq!(deltas.take(), [0.]);
+
+        cx.update(|cx| cx.set_reduce_motion(true));
+        cx.update(|cx| animate(&deltas, cx)).detach();
+        cx.simulate_frame();
+        cx.simulate_frame();
+        assert_eq!(deltas.take(), [1.]);
+    }
+}
@@ -916,6 +916,13 @@ pub trait StatefulInteractiveElement: InteractiveElement {
         self
     }
 
+    /// Scroll this element while something is being dragged near its edges, so that
+    /// drop targets outside of the visible area can be reached.
+    fn auto_scroll_on_drag(mut self) -> Self {
+        self.interactivity().auto_scroll_on_drag = true;
+        self
+    }
+
     /// Set the given styles to be applied when this element is active.
     fn active(mut self, f: impl FnOnce(StyleRefinement) -> StyleRefinement) -> Self
     where
@@ -1234,6 +1241,8 @@ pub struct Interactivity {
     pub(crate) tracked_focus_handle: Option<FocusHandle>,
     pub(crate) tracked_scroll_handle: Option<ScrollHandle>,
     pub(crate) scroll_offset: Option<Rc<RefCell<Point<Pixels>>>>,
+    pub(crate) scroll_max: Size<Pixels>,
+    pub(crate) auto_scroll_on_drag: bool,
     pub(crate) group: Option<SharedString>,
     /// The base style of the element, before any modifications are applied
     /// by focus, active, etc.
@@ -1433,6 +1442,7 @@ impl Interactivity {
                         .to_pixels(bounds.size.height.into(), rem_size),
             );
             let scroll_max = (self.content_size + padding_size - bounds.size).max(&Size::default());
+            self.scroll_max = scroll_max;
             // Clamp scroll offset in case scroll max is smaller now (e.g., if children
             // were removed or the bounds became larger).
             let mut scroll_offset = scroll_offset.borrow_mut();
@@ -2060,6 +2070,21 @@ impl Interactivity {
                     }
                 }
             });
+
+            if self.auto_scroll_on_drag && cx.has_active_drag() && hitbox.is_hovered(cx) {
+                let delta = auto_scroll_delta(hitbox.bounds, cx.mouse_position(), style.overflow);
+                let mut scroll_offset = scroll_offset.borrow_mut();
+                let old_scroll_offset = *scroll_offset;
+                scroll_offset.x = (scroll_offset.x + delta.x).clamp(-self.scroll_max.width, px(0.));
+                scroll_offset.y =
+                    (scroll_offset.y + delta.y).clamp(-self.scroll_max.height, px(0.));
+
+                // Keep scrolling for as long as the drag stays near the edge, even if
+                // the mouse isn't moving.
+                if *scroll_offset != old_scroll_offset {
+                    cx.on_next_frame(|cx| cx.refresh());
+                }
+            }
         }
     }
 
@@ -2168,6 +2193,41 @@ impl Interactivity {
     }
 }
 
+/// How close to the edge of a scrollable element a drag has to be for the element
+/// to start scrolling.
+const AUTO_SCROLL_MARGIN: Pixels = px(32.);
+
+/// How far a scrollable element scrolls per frame when a drag is right at its edge.
+const AUTO_SCROLL_MAX_SPEED: Pixels = px(16.);
+
+/// Returns how far to scroll an element with the given bounds this frame, given the
+/// position of an active drag. The closer the drag is to an edge, the faster it scrolls.
+fn auto_scroll_delta(
+    bounds: Bounds<Pixels>,
+    position: Point<Pixels>,
+    overflow: Point<Overflow>,
+) -> Point<Pixels> {
+    fn axis_delta(position: Pixels, start: Pixels, end: Pixels) -> Pixels {
+        let margin = AUTO_SCROLL_MARGIN.min((end - start) * 0.25);
+        if position < start + margin {
+            AUTO_SCROLL_MAX_SPEED * ((start + margin - position) / margin).min(1.)
+        } else if position > end - margin {
+            -AUTO_SCROLL_MAX_SPEED * ((position - (end - margin)) / margin).min(1.)
+        } else {
+            Pixels::ZERO
+        }
+    }
+
+    let mut delta = Point::default();
+    if overflow.x == Overflow::Scroll {
+        delta.x = axis_delta(position.x, bounds.left(), bounds.right());
+    }
+    if overflow.y == Overflow::Scroll {
+        delta.y = axis_delta(position.y, bounds.top(), bounds.bottom());
+    }
+    delta
+}
+
 /// The per-frame state of an interactive element. Used for tracking stateful interactions like clicks
 /// and scroll offsets.
 #[derive(Default)]
@@ -2500,3 +2560,41 @@ impl ScrollHandle {
         self.0.borrow_mut().requested_scroll_top = Some((ix, px));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_scroll_delta() {
+        let bounds = Bounds::new(point(px(0.), px(0.)), size(px(100.), px(200.)));
+        let scroll_y = Point {
+            x: Overflow::Visible,
+            y: Overflow::Scroll,
+        };
+        let delta_y = |y: f32, bounds: Bounds<Pixels>| {
+            auto_scroll_delta(bounds, point(px(50.), px(y)), scroll_y).y
+        };
+
+        // The closer the drag is to an edge, the faster the element scrolls towards it.
+        assert_eq!(delta_y(0., bounds), px(16.));
+        assert_eq!(delta_y(16., bounds), px(8.));
+        assert_eq!(delta_y(100., bounds), px(0.));
+        assert_eq!(delta_y(184., bounds), px(-8.));
+        assert_eq!(delta_y(200., bounds), px(-16.));
+
+        // Drags outside of the element scroll at most at full speed.
+        assert_eq!(delta_y(-50., bounds), px(16.));
+
+        // The margin shrinks for small elements, so that their middle doesn't scroll.
+        let small_bounds = Bounds::new(point(px(0.), px(0.)), size(px(100.), px(40.)));
+        assert_eq!(delta_y(5., small_bounds), px(8.));
+        assert_eq!(delta_y(20., small_bounds), px(0.));
+
+        // Only axes that can scroll are scrolled.
+        assert_eq!(
+            auto_scroll_delta(bounds, point(px(0.), px(0.)), scroll_y).x,
+            px(0.)
+        );
+    }
+}
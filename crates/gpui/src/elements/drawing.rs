@@ -0,0 +1,197 @@
+use std::rc::Rc;
+
+use refineable::Refineable as _;
+use util::ResultExt;
+
+use crate::{
+    Bounds, ContentMask, Element, ElementContext, Hsla, IntoElement, LayoutId, PaintQuad, Path,
+    PathBuilder, Pixels, Point, SharedString, Style, StyleRefinement, Styled, TextRun,
+};
+
+/// Construct an element that paints the given drawing, relative to its own origin
+/// and clipped to its bounds.
+pub fn drawing(drawing: impl Into<Rc<Drawing>>) -> DrawingElement {
+    DrawingElement {
+        drawing: drawing.into(),
+        style: StyleRefinement::default(),
+    }
+}
+
+enum DrawCommand {
+    Path {
+        path: Path<Pixels>,
+        color: Hsla,
+    },
+    Quad(PaintQuad),
+    Text {
+        origin: Point<Pixels>,
+        text: SharedString,
+        font_size: Pixels,
+        runs: Vec<TextRun>,
+    },
+}
+
+/// A retained list of filled and stroked paths, quads and text, which can be
+/// painted as many times as needed without rebuilding it.
+///
+/// Paths are tessellated once, when they're added, so a view can build a drawing
+/// when its data changes and then cheaply paint it every frame with [`drawing`].
+/// Coordinates are relative to the origin the drawing is painted at.
+#[derive(Default)]
+pub struct Drawing {
+    commands: Vec<DrawCommand>,
+}
+
+impl Drawing {
+    /// Create an empty drawing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether nothing has been added to this drawing.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Remove everything from this drawing.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Fill the inside of the given shape.
+    pub fn fill(&mut self, shape: &PathBuilder, color: impl Into<Hsla>) -> &mut Self {
+        if let Some(path) = shape.fill() {
+            self.commands.push(DrawCommand::Path {
+                path,
+                color: color.into(),
+            });
+        }
+        self
+    }
+
+    /// Stroke the outline of the given shape with lines of the given width.
+    pub fn stroke(
+        &mut self,
+        shape: &PathBuilder,
+        width: Pixels,
+        color: impl Into<Hsla>,
+    ) -> &mut Self {
+        if let Some(path) = shape.stroke(width) {
+            self.commands.push(DrawCommand::Path {
+                path,
+                color: color.into(),
+            });
+        }
+        self
+    }
+
+    /// Paint a quad, which is cheaper than a path for axis aligned rectangles.
+    pub fn quad(&mut self, quad: PaintQuad) -> &mut Self {
+        self.commands.push(DrawCommand::Quad(quad));
+        self
+    }
+
+    /// Paint a single line of text whose line box is as tall as the font size,
+    /// with its top left corner at the given origin.
+    pub fn text(
+        &mut self,
+        origin: Point<Pixels>,
+        text: impl Into<SharedString>,
+        font_size: Pixels,
+        runs: Vec<TextRun>,
+    ) -> &mut Self {
+        self.commands.push(DrawCommand::Text {
+            origin,
+            text: text.into(),
+            font_size,
+            runs,
+        });
+        self
+    }
+
+    /// Paint this drawing, offsetting everything in it by the given origin.
+    pub fn paint(&self, origin: Point<Pixels>, cx: &mut ElementContext) {
+        for command in &self.commands {
+            match command {
+                DrawCommand::Path { path, color } => {
+                    let mut path = path.clone();
+                    path.translate(origin);
+                    cx.paint_path(path, *color);
+                }
+                DrawCommand::Quad(quad) => {
+                    let mut quad = quad.clone();
+                    quad.bounds.origin += origin;
+                    cx.paint_quad(quad);
+                }
+                DrawCommand::Text {
+                    origin: text_origin,
+                    text,
+                    font_size,
+                    runs,
+                } => {
+                    if let Some(line) = cx
+                        .text_system()
+                        .shape_line(text.clone(), *font_size, runs)
+                        .log_err()
+                    {
+                        line.paint(origin + *text_origin, *font_size, cx).log_err();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An element that paints a [`Drawing`].
+pub struct DrawingElement {
+    drawing: Rc<Drawing>,
+    style: StyleRefinement,
+}
+
+impl IntoElement for DrawingElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for DrawingElement {
+    type RequestLayoutState = Style;
+    type PrepaintState = ();
+
+    fn request_layout(&mut self, cx: &mut ElementContext) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.refine(&self.style);
+        let layout_id = cx.request_layout(&style, []);
+        (layout_id, style)
+    }
+
+    fn prepaint(
+        &mut self,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Style,
+        _cx: &mut ElementContext,
+    ) {
+    }
+
+    fn paint(
+        &mut self,
+        bounds: Bounds<Pixels>,
+        style: &mut Style,
+        _prepaint: &mut Self::PrepaintState,
+        cx: &mut ElementContext,
+    ) {
+        style.paint(bounds, cx, |cx| {
+            cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                self.drawing.paint(bounds.origin, cx);
+            });
+        });
+    }
+}
+
+impl Styled for DrawingElement {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
@@ -3,6 +3,7 @@ mod animation;
 mod canvas;
 mod deferred;
 mod div;
+mod drawing;
 mod img;
 mod list;
 mod svg;
@@ -14,6 +15,7 @@ pub use animation::*;
 pub use canvas::*;
 pub use deferred::*;
 pub use div::*;
+pub use drawing::*;
 pub use img::*;
 pub use list::*;
 pub use svg::*;
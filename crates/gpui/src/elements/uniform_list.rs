@@ -288,6 +288,11 @@ impl UniformList {
         self.scroll_handle = Some(handle);
         self
     }
+    /// Scroll this list while something is being dragged near its top or bottom edge.
+    pub fn auto_scroll_on_drag(mut self) -> Self {
+        self.interactivity.auto_scroll_on_drag = true;
+        self
+    }
 }
 
 impl InteractiveElement for UniformList {
@@ -0,0 +1,373 @@
+use crate::{point, px, Bounds, Path, Pixels, Point, Radians};
+use std::f32::consts::PI;
+
+/// The length of the line segments that curves and arcs are flattened into.
+const CURVE_SEGMENT_LENGTH: f32 = 2.;
+
+/// The maximum number of line segments a single curve or arc is flattened into.
+const MAX_CURVE_SEGMENTS: usize = 64;
+
+/// How far, relative to the stroke width, the outer corner of a join can extend
+/// before it's beveled instead of mitered.
+const MITER_LIMIT: f32 = 4.;
+
+#[derive(Clone, Debug, Default)]
+struct Contour {
+    points: Vec<Point<Pixels>>,
+    closed: bool,
+}
+
+/// Builds a shape out of lines, curves and arcs, which can then be turned into
+/// a [`Path`] that fills or strokes it.
+///
+/// Shapes are filled with the even-odd rule, so overlapping contours cut holes
+/// into each other.
+#[derive(Clone, Debug, Default)]
+pub struct PathBuilder {
+    contours: Vec<Contour>,
+}
+
+impl PathBuilder {
+    /// Create an empty path builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether nothing has been added to this builder.
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// Start a new contour at the given point.
+    pub fn move_to(&mut self, to: Point<Pixels>) -> &mut Self {
+        self.contours.push(Contour {
+            points: vec![to],
+            closed: false,
+        });
+        self
+    }
+
+    /// Draw a straight line from the current point to the given point.
+    pub fn line_to(&mut self, to: Point<Pixels>) -> &mut Self {
+        self.current_contour(to).points.push(to);
+        self
+    }
+
+    /// Draw a quadratic Bézier curve from the current point to the given point.
+    pub fn quadratic_bezier_to(&mut self, ctrl: Point<Pixels>, to: Point<Pixels>) -> &mut Self {
+        let from = self.current_point().unwrap_or(ctrl);
+        let segments = curve_segments(&[from, ctrl, to]);
+        let contour = self.current_contour(from);
+        for ix in 1..=segments {
+            let t = ix as f32 / segments as f32;
+            let s = 1. - t;
+            contour
+                .points
+                .push(from * (s * s) + ctrl * (2. * s * t) + to * (t * t));
+        }
+        self
+    }
+
+    /// Draw a cubic Bézier curve from the current point to the given point.
+    pub fn cubic_bezier_to(
+        &mut self,
+        ctrl_a: Point<Pixels>,
+        ctrl_b: Point<Pixels>,
+        to: Point<Pixels>,
+    ) -> &mut Self {
+        let from = self.current_point().unwrap_or(ctrl_a);
+        let segments = curve_segments(&[from, ctrl_a, ctrl_b, to]);
+        let contour = self.current_contour(from);
+        for ix in 1..=segments {
+            let t = ix as f32 / segments as f32;
+            let s = 1. - t;
+            contour.points.push(
+                from * (s * s * s)
+                    + ctrl_a * (3. * s * s * t)
+                    + ctrl_b * (3. * s * t * t)
+                    + to * (t * t * t),
+            );
+        }
+        self
+    }
+
+    /// Draw an arc around the given center, from the start angle to the end angle.
+    /// Angles are measured clockwise from the positive x axis.
+    ///
+    /// If there's a current point, a line is drawn from it to the start of the arc.
+    pub fn arc(
+        &mut self,
+        center: Point<Pixels>,
+        radius: Pixels,
+        start_angle: Radians,
+        end_angle: Radians,
+    ) -> &mut Self {
+        let sweep = end_angle.0 - start_angle.0;
+        let segments = ((sweep.abs() * radius.0 / CURVE_SEGMENT_LENGTH).ceil() as usize)
+            .clamp(1, MAX_CURVE_SEGMENTS);
+        let point_at = |angle: f32| {
+            point(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        };
+
+        let start = point_at(start_angle.0);
+        let contour = self.current_contour(start);
+        if contour.points.last() != Some(&start) {
+            contour.points.push(start);
+        }
+        for ix in 1..=segments {
+            let angle = start_angle.0 + sweep * ix as f32 / segments as f32;
+            contour.points.push(point_at(angle));
+        }
+        self
+    }
+
+    /// Close the current contour by connecting its last point to its first one.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.closed = true;
+        }
+        self
+    }
+
+    /// Add a closed rectangle with the given bounds.
+    pub fn rect(&mut self, bounds: Bounds<Pixels>) -> &mut Self {
+        self.move_to(bounds.origin)
+            .line_to(bounds.upper_right())
+            .line_to(bounds.lower_right())
+            .line_to(bounds.lower_left())
+            .close()
+    }
+
+    /// Add a closed circle with the given center and radius.
+    pub fn circle(&mut self, center: Point<Pixels>, radius: Pixels) -> &mut Self {
+        self.move_to(point(center.x + radius, center.y))
+            .arc(center, radius, Radians(0.), Radians(2. * PI))
+            .close()
+    }
+
+    /// Build a path that fills the inside of this shape. Open contours are
+    /// implicitly closed. Returns `None` if the shape doesn't cover any area.
+    pub fn fill(&self) -> Option<Path<Pixels>> {
+        let mut path = None;
+        for contour in &self.contours {
+            if contour.points.len() >= 3 {
+                push_polygon(&mut path, &contour.points);
+            }
+        }
+        path
+    }
+
+    /// Build a path that strokes the outline of this shape with lines of the
+    /// given width. Returns `None` if the shape doesn't have any line segments.
+    pub fn stroke(&self, width: Pixels) -> Option<Path<Pixels>> {
+        let half_width = width.0 / 2.;
+        if half_width <= 0. {
+            return None;
+        }
+
+        let mut path = None;
+        for contour in &self.contours {
+            let mut points = contour.points.clone();
+            points.dedup();
+            if contour.closed && points.len() > 2 && points.first() == points.last() {
+                points.pop();
+            }
+            if points.len() < 2 {
+                continue;
+            }
+
+            let left = offset_points(&points, contour.closed, half_width);
+            let right = offset_points(&points, contour.closed, -half_width);
+            if contour.closed {
+                // The stroke of a closed contour is the ring between its two sides.
+                push_polygon(&mut path, &left);
+                push_polygon(&mut path, &right);
+            } else {
+                let mut outline = left;
+                outline.extend(right.into_iter().rev());
+                push_polygon(&mut path, &outline);
+            }
+        }
+        path
+    }
+
+    fn current_point(&self) -> Option<Point<Pixels>> {
+        let contour = self.contours.last()?;
+        if contour.closed {
+            contour.points.first().copied()
+        } else {
+            contour.points.last().copied()
+        }
+    }
+
+    /// Returns the contour new points should be added to, starting a new one at the
+    /// given point if there's no current contour or it has been closed.
+    fn current_contour(&mut self, start: Point<Pixels>) -> &mut Contour {
+        match self.contours.last() {
+            None => {
+                self.move_to(start);
+            }
+            Some(contour) if contour.closed => {
+                let start = contour.points[0];
+                self.move_to(start);
+            }
+            Some(_) => {}
+        }
+        self.contours.last_mut().unwrap()
+    }
+}
+
+fn push_polygon(path: &mut Option<Path<Pixels>>, points: &[Point<Pixels>]) {
+    let path = path.get_or_insert_with(|| Path::new(points[0]));
+    path.move_to(points[0]);
+    for point in &points[1..] {
+        path.line_to(*point);
+    }
+}
+
+fn curve_segments(control_points: &[Point<Pixels>]) -> usize {
+    let length = control_points
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum::<f32>();
+    ((length / CURVE_SEGMENT_LENGTH).ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+/// Offsets each point of a polyline perpendicularly to its segments, mitering or
+/// beveling the joins between them.
+fn offset_points(points: &[Point<Pixels>], closed: bool, offset: f32) -> Vec<Point<Pixels>> {
+    let len = points.len();
+    let normal = |from: usize, to: usize| {
+        let dx = points[to].x.0 - points[from].x.0;
+        let dy = points[to].y.0 - points[from].y.0;
+        let length = (dx * dx + dy * dy).sqrt();
+        (dy / length, -dx / length)
+    };
+    let offset_by = |ix: usize, (nx, ny): (f32, f32), distance: f32| {
+        point(
+            points[ix].x + px(nx * distance),
+            points[ix].y + px(ny * distance),
+        )
+    };
+
+    let mut offset_points = Vec::with_capacity(len);
+    for ix in 0..len {
+        let prev_normal = (ix > 0 || closed).then(|| normal((ix + len - 1) % len, ix));
+        let next_normal = (ix + 1 < len || closed).then(|| normal(ix, (ix + 1) % len));
+        match (prev_normal, next_normal) {
+            (Some(prev_normal), Some(next_normal)) => {
+                let miter = (prev_normal.0 + next_normal.0, prev_normal.1 + next_normal.1);
+                let miter_length = (miter.0 * miter.0 + miter.1 * miter.1).sqrt();
+                // The join is on the outside of the turn when the path turns away from the
+                // side being offset.
+                let turn = prev_normal.1 * next_normal.0 - prev_normal.0 * next_normal.1;
+                let is_outer = turn * offset < 0.;
+                // `miter_length / 2` is the cosine of half the angle between the normals.
+                let miter_distance = if miter_length > f32::EPSILON {
+                    offset.abs() * 2. / miter_length
+                } else {
+                    f32::INFINITY
+                };
+
+                if is_outer && miter_distance > offset.abs() * MITER_LIMIT {
+                    offset_points.push(offset_by(ix, prev_normal, offset));
+                    offset_points.push(offset_by(ix, next_normal, offset));
+                } else if miter_length > f32::EPSILON {
+                    let direction = (miter.0 / miter_length, miter.1 / miter_length);
+                    let distance = miter_distance.min(offset.abs() * MITER_LIMIT);
+                    offset_points.push(offset_by(ix, direction, distance * offset.signum()));
+                } else {
+                    offset_points.push(offset_by(ix, prev_normal, offset));
+                }
+            }
+            (Some(normal), None) | (None, Some(normal)) => {
+                offset_points.push(offset_by(ix, normal, offset));
+            }
+            (None, None) => {}
+        }
+    }
+    offset_points
+}
+
+fn distance(a: Point<Pixels>, b: Point<Pixels>) -> f32 {
+    let dx = b.x.0 - a.x.0;
+    let dy = b.y.0 - a.y.0;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{size, Size};
+
+    #[test]
+    fn test_fill() {
+        assert!(PathBuilder::new().fill().is_none());
+        assert!(PathBuilder::new()
+            .move_to(point(px(0.), px(0.)))
+            .line_to(point(px(10.), px(0.)))
+            .fill()
+            .is_none());
+
+        let path = PathBuilder::new()
+            .rect(Bounds::new(point(px(10.), px(20.)), size(px(30.), px(40.))))
+            .fill()
+            .unwrap();
+        // A rectangle is made up of two triangles.
+        assert_eq!(path.vertices.len(), 6);
+        assert_eq!(
+            path.bounds,
+            Bounds::new(point(px(10.), px(20.)), size(px(30.), px(40.)))
+        );
+    }
+
+    #[test]
+    fn test_stroke() {
+        assert!(PathBuilder::new()
+            .move_to(point(px(0.), px(0.)))
+            .stroke(px(2.))
+            .is_none());
+
+        let path = PathBuilder::new()
+            .move_to(point(px(0.), px(10.)))
+            .line_to(point(px(20.), px(10.)))
+            .stroke(px(2.))
+            .unwrap();
+        assert_eq!(
+            path.bounds,
+            Bounds::new(point(px(0.), px(9.)), size(px(20.), px(2.)))
+        );
+
+        // The outer corners of a closed square's stroke are mitered.
+        let path = PathBuilder::new()
+            .rect(Bounds::new(point(px(0.), px(0.)), size(px(10.), px(10.))))
+            .stroke(px(2.))
+            .unwrap();
+        assert_eq!(
+            path.bounds,
+            Bounds::new(point(px(-1.), px(-1.)), size(px(12.), px(12.)))
+        );
+    }
+
+    #[test]
+    fn test_curves_are_flattened() {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(point(px(0.), px(0.)))
+            .quadratic_bezier_to(point(px(50.), px(100.)), point(px(100.), px(0.)));
+        let points = &builder.contours[0].points;
+        assert!(points.len() > 3);
+        assert_eq!(*points.last().unwrap(), point(px(100.), px(0.)));
+
+        let path = PathBuilder::new()
+            .circle(point(px(50.), px(50.)), px(10.))
+            .fill()
+            .unwrap();
+        let Size { width, height } = path.bounds.size;
+        assert!((width.0 - 20.).abs() < 0.01);
+        assert!((height.0 - 20.).abs() < 0.01);
+    }
+}
@@ -9,6 +9,9 @@ mod keystroke;
 #[cfg(not(target_os = "macos"))]
 mod cosmic_text;
 
+#[cfg(any(target_os = "linux", test, feature = "test-support"))]
+mod headless_renderer;
+
 #[cfg(target_os = "linux")]
 mod linux;
 
@@ -25,21 +28,22 @@ mod test;
 mod windows;
 
 use crate::{
-    Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels,
-    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, Keymap,
-    LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImageParams,
-    RenderSvgParams, Scene, SharedString, Size, Task, TaskLabel, WindowContext,
+    AccessibleText, Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds,
+    DevicePixels, DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor,
+    GlyphId, Keymap, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams,
+    RenderImageParams, RenderSvgParams, Scene, SharedString, Size, Task, TaskLabel, WindowContext,
 };
 use anyhow::Result;
 use async_task::Runnable;
 use futures::channel::oneshot;
+use image::RgbaImage;
 use parking::Unparker;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use seahash::SeaHasher;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{
     any::Any,
     fmt::{self, Debug},
@@ -55,6 +59,8 @@ pub use keystroke::*;
 
 #[cfg(not(target_os = "macos"))]
 pub(crate) use cosmic_text::*;
+#[cfg(any(target_os = "linux", test, feature = "test-support"))]
+pub(crate) use headless_renderer::*;
 #[cfg(target_os = "linux")]
 pub(crate) use linux::*;
 #[cfg(target_os = "macos")]
@@ -86,6 +92,10 @@ pub(crate) fn current_platform() -> Rc<dyn Platform> {
         Rc::new(HeadlessClient::new())
     }
 }
+#[cfg(target_os = "linux")]
+pub(crate) fn headless_platform() -> Rc<dyn Platform> {
+    Rc::new(HeadlessClient::new())
+}
 // todo("windows")
 #[cfg(target_os = "windows")]
 pub(crate) fn current_platform() -> Rc<dyn Platform> {
@@ -137,6 +147,7 @@ pub(crate) trait Platform: 'static {
 
     fn set_menus(&self, menus: Vec<Menu>, keymap: &Keymap);
     fn add_recent_document(&self, _path: &Path) {}
+    fn show_notification(&self, _title: &str, _body: &str) {}
     fn on_app_menu_action(&self, callback: Box<dyn FnMut(&dyn Action)>);
     fn on_will_open_app_menu(&self, callback: Box<dyn FnMut()>);
     fn on_validate_app_menu_command(&self, callback: Box<dyn FnMut(&dyn Action) -> bool>);
@@ -229,6 +240,17 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn draw(&self, scene: &Scene);
     fn completed_frame(&self) {}
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
+    /// Rasterizes the given scene into an image on the CPU. Only supported by windows that
+    /// aren't presented on a display.
+    fn render_to_image(&self, _scene: &Scene) -> Option<RgbaImage> {
+        None
+    }
+    /// Reports the focused text surface to assistive technologies. Implemented on macOS, on
+    /// Linux over AT-SPI and on Windows over UI Automation.
+    fn set_accessible_text(&self, _text: Option<AccessibleText>) {}
+    fn set_always_on_top(&self, _always_on_top: bool) {}
+    /// Asks assistive technologies to read out the given message.
+    fn announce(&self, _message: &str) {}
 
     #[cfg(target_os = "windows")]
     fn get_raw_handle(&self) -> windows::HWND;
@@ -247,6 +269,10 @@ pub trait PlatformDispatcher: Send + Sync {
     fn dispatch(&self, runnable: Runnable, label: Option<TaskLabel>);
     fn dispatch_on_main_thread(&self, runnable: Runnable);
     fn dispatch_after(&self, duration: Duration, runnable: Runnable);
+    /// The current time, which only moves forward when the clock is advanced in tests.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
     fn tick(&self, background_only: bool) -> bool;
     fn park(&self);
     fn unparker(&self) -> Unparker;
@@ -316,6 +342,17 @@ impl AtlasKey {
             AtlasKey::Image(_) => AtlasTextureKind::Polychrome,
         }
     }
+
+    /// Returns whether the sprite for this key can still be used after the window's scale
+    /// factor changes to the given one.
+    pub(crate) fn is_valid_at_scale_factor(&self, scale_factor: f32) -> bool {
+        match self {
+            AtlasKey::Glyph(params) => params.scale_factor == scale_factor,
+            // SVGs are rasterized at their size in device pixels for the previous scale factor.
+            AtlasKey::Svg(_) => false,
+            AtlasKey::Image(_) => true,
+        }
+    }
 }
 
 impl From<RenderGlyphParams> for AtlasKey {
@@ -342,6 +379,9 @@ pub(crate) trait PlatformAtlas: Send + Sync {
         key: &AtlasKey,
         build: &mut dyn FnMut() -> Result<(Size<DevicePixels>, Cow<'a, [u8]>)>,
     ) -> Result<AtlasTile>;
+
+    /// Free the tiles whose keys don't satisfy the predicate, so their space can be reused.
+    fn retain(&self, predicate: &mut dyn FnMut(&AtlasKey) -> bool);
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -454,9 +494,42 @@ impl PlatformInputHandler {
             .flatten()
     }
 
+    /// Show the given text as the composition in progress, replacing the previous one.
+    /// Clears the composition when the text is empty.
+    #[cfg(target_os = "linux")]
+    fn set_ime_preedit(&mut self, text: &str, selected_range_utf16: Option<Range<usize>>) {
+        self.cx
+            .update(|cx| set_ime_preedit(self.handler.as_mut(), text, selected_range_utf16, cx))
+            .ok();
+    }
+
     pub(crate) fn dispatch_input(&mut self, input: &str, cx: &mut WindowContext) {
         self.handler.replace_text_in_range(None, input, cx);
     }
+
+    pub(crate) fn dispatch_ime_preedit(&mut self, text: &str, cx: &mut WindowContext) {
+        set_ime_preedit(self.handler.as_mut(), text, None, cx);
+    }
+}
+
+fn set_ime_preedit(
+    handler: &mut dyn InputHandler,
+    text: &str,
+    selected_range_utf16: Option<Range<usize>>,
+    cx: &mut WindowContext,
+) {
+    if text.is_empty() {
+        // Replacing with empty text would otherwise delete the selection.
+        if handler.marked_text_range(cx).is_some() {
+            handler.replace_and_mark_text_in_range(None, "", None, cx);
+        }
+    } else {
+        let selected_range_utf16 = selected_range_utf16.unwrap_or_else(|| {
+            let len = text.encode_utf16().count();
+            len..len
+        });
+        handler.replace_and_mark_text_in_range(None, text, Some(selected_range_utf16), cx);
+    }
 }
 
 /// Zed's interface for handling text input from the platform's IME system
@@ -128,6 +128,18 @@ impl PlatformAtlas for BladeAtlas {
             Ok(tile)
         }
     }
+
+    fn retain(&self, predicate: &mut dyn FnMut(&AtlasKey) -> bool) {
+        let mut lock = self.0.lock();
+        let lock = &mut *lock;
+        lock.tiles_by_key.retain(|key, tile| {
+            let keep = predicate(key);
+            if !keep {
+                lock.storage[tile.texture_id].deallocate(tile);
+            }
+            keep
+        });
+    }
 }
 
 impl BladeAtlasState {
@@ -292,6 +304,17 @@ impl ops::Index<AtlasTextureId> for BladeAtlasStorage {
     }
 }
 
+impl ops::IndexMut<AtlasTextureId> for BladeAtlasStorage {
+    fn index_mut(&mut self, id: AtlasTextureId) -> &mut Self::Output {
+        let textures = match id.kind {
+            crate::AtlasTextureKind::Monochrome => &mut self.monochrome_textures,
+            crate::AtlasTextureKind::Polychrome => &mut self.polychrome_textures,
+            crate::AtlasTextureKind::Path => &mut self.path_textures,
+        };
+        &mut textures[id.index as usize]
+    }
+}
+
 impl BladeAtlasStorage {
     fn destroy(&mut self, gpu: &gpu::Context) {
         for mut texture in self.monochrome_textures.drain(..) {
@@ -319,6 +342,10 @@ impl BladeAtlasTexture {
         self.allocator.clear();
     }
 
+    fn deallocate(&mut self, tile: &AtlasTile) {
+        self.allocator.deallocate(tile.tile_id.into());
+    }
+
     fn allocate(&mut self, size: Size<DevicePixels>) -> Option<AtlasTile> {
         let allocation = self.allocator.allocate(size.into())?;
         let tile = AtlasTile {
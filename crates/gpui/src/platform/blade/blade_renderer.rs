@@ -3,8 +3,8 @@
 
 use super::{BladeAtlas, BladeBelt, BladeBeltDescriptor, PATH_TEXTURE_FORMAT};
 use crate::{
-    AtlasTextureKind, AtlasTile, Bounds, ContentMask, Hsla, MonochromeSprite, Path, PathId,
-    PathVertex, PolychromeSprite, PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size,
+    AtlasTextureKind, AtlasTile, BackdropBlur, Bounds, ContentMask, Hsla, MonochromeSprite, Path,
+    PathId, PathVertex, PolychromeSprite, PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size,
     Underline,
 };
 use bytemuck::{Pod, Zeroable};
@@ -119,6 +119,14 @@ struct ShaderShadowsData {
     b_shadows: gpu::BufferPiece,
 }
 
+#[derive(blade_macros::ShaderData)]
+struct ShaderBackdropBlursData {
+    globals: GlobalParams,
+    t_backdrop: gpu::TextureView,
+    s_backdrop: gpu::Sampler,
+    b_backdrop_blurs: gpu::BufferPiece,
+}
+
 #[derive(blade_macros::ShaderData)]
 struct ShaderPathRasterizationData {
     globals: GlobalParams,
@@ -175,6 +183,7 @@ struct PathSprite {
 struct BladePipelines {
     quads: gpu::RenderPipeline,
     shadows: gpu::RenderPipeline,
+    backdrop_blurs: gpu::RenderPipeline,
     path_rasterization: gpu::RenderPipeline,
     paths: gpu::RenderPipeline,
     underlines: gpu::RenderPipeline,
@@ -194,6 +203,7 @@ impl BladePipelines {
         shader.check_struct_size::<SurfaceParams>();
         shader.check_struct_size::<Quad>();
         shader.check_struct_size::<Shadow>();
+        shader.check_struct_size::<BackdropBlur>();
         assert_eq!(
             mem::size_of::<PathVertex<ScaledPixels>>(),
             shader.get_struct_size("PathVertex") as usize,
@@ -238,6 +248,23 @@ impl BladePipelines {
                     write_mask: gpu::ColorWrites::default(),
                 }],
             }),
+            backdrop_blurs: gpu.create_render_pipeline(gpu::RenderPipelineDesc {
+                name: "backdrop-blurs",
+                data_layouts: &[&ShaderBackdropBlursData::layout()],
+                vertex: shader.at("vs_backdrop_blur"),
+                vertex_fetches: &[],
+                primitive: gpu::PrimitiveState {
+                    topology: gpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                fragment: shader.at("fs_backdrop_blur"),
+                color_targets: &[gpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(gpu::BlendState::ALPHA_BLENDING),
+                    write_mask: gpu::ColorWrites::default(),
+                }],
+            }),
             path_rasterization: gpu.create_render_pipeline(gpu::RenderPipelineDesc {
                 name: "path_rasterization",
                 data_layouts: &[&ShaderPathRasterizationData::layout()],
@@ -344,8 +371,16 @@ impl BladePipelines {
     }
 }
 
+/// A copy of what was drawn before a batch of backdrop blurs, which they sample from.
+#[derive(Clone, Copy)]
+struct BackdropTexture {
+    raw: gpu::Texture,
+    raw_view: gpu::TextureView,
+}
+
 pub struct BladeRenderer {
     gpu: Arc<gpu::Context>,
+    surface_format: gpu::TextureFormat,
     command_encoder: gpu::CommandEncoder,
     last_sync_point: Option<gpu::SyncPoint>,
     pipelines: BladePipelines,
@@ -354,6 +389,7 @@ pub struct BladeRenderer {
     path_tiles: HashMap<PathId, AtlasTile>,
     atlas: Arc<BladeAtlas>,
     atlas_sampler: gpu::Sampler,
+    backdrop_texture: Option<BackdropTexture>,
     #[cfg(target_os = "macos")]
     core_video_texture_cache: CVMetalTextureCache,
 }
@@ -362,7 +398,8 @@ impl BladeRenderer {
     fn make_surface_config(size: gpu::Extent) -> gpu::SurfaceConfig {
         gpu::SurfaceConfig {
             size,
-            usage: gpu::TextureUsage::TARGET,
+            // Backdrop blurs copy from the frame's texture.
+            usage: gpu::TextureUsage::TARGET | gpu::TextureUsage::COPY,
             display_sync: gpu::DisplaySync::Recent,
             //Note: this matches the original logic of the Metal backend,
             // but ultimaterly we need to switch to `Linear`.
@@ -398,6 +435,7 @@ impl BladeRenderer {
 
         Self {
             gpu,
+            surface_format,
             command_encoder,
             last_sync_point: None,
             pipelines,
@@ -406,6 +444,7 @@ impl BladeRenderer {
             path_tiles: HashMap::default(),
             atlas,
             atlas_sampler,
+            backdrop_texture: None,
             #[cfg(target_os = "macos")]
             core_video_texture_cache,
         }
@@ -430,6 +469,7 @@ impl BladeRenderer {
             self.wait_for_gpu();
             self.gpu.resize(Self::make_surface_config(gpu_size));
             self.viewport_size = gpu_size;
+            self.destroy_backdrop_texture();
         }
     }
 
@@ -507,8 +547,64 @@ impl BladeRenderer {
         }
     }
 
+    /// Copies what has been drawn to the frame so far into the backdrop texture,
+    /// allocating it first if needed.
+    fn copy_backdrop_texture(&mut self, frame: &gpu::Frame) -> gpu::TextureView {
+        let backdrop_texture = match self.backdrop_texture {
+            Some(backdrop_texture) => backdrop_texture,
+            None => {
+                let raw = self.gpu.create_texture(gpu::TextureDesc {
+                    name: "backdrop",
+                    format: self.surface_format,
+                    size: self.viewport_size,
+                    array_layer_count: 1,
+                    mip_level_count: 1,
+                    dimension: gpu::TextureDimension::D2,
+                    usage: gpu::TextureUsage::COPY | gpu::TextureUsage::RESOURCE,
+                });
+                let raw_view = self.gpu.create_texture_view(gpu::TextureViewDesc {
+                    name: "backdrop",
+                    texture: raw,
+                    format: self.surface_format,
+                    dimension: gpu::ViewDimension::D2,
+                    subresources: &Default::default(),
+                });
+                self.command_encoder.init_texture(raw);
+                let backdrop_texture = BackdropTexture { raw, raw_view };
+                self.backdrop_texture = Some(backdrop_texture);
+                backdrop_texture
+            }
+        };
+
+        let mut transfers = self.command_encoder.transfer();
+        transfers.copy_texture_to_texture(
+            gpu::TexturePiece {
+                texture: frame.texture(),
+                mip_level: 0,
+                array_layer: 0,
+                origin: [0, 0, 0],
+            },
+            gpu::TexturePiece {
+                texture: backdrop_texture.raw,
+                mip_level: 0,
+                array_layer: 0,
+                origin: [0, 0, 0],
+            },
+            self.viewport_size,
+        );
+        backdrop_texture.raw_view
+    }
+
+    fn destroy_backdrop_texture(&mut self) {
+        if let Some(backdrop_texture) = self.backdrop_texture.take() {
+            self.gpu.destroy_texture_view(backdrop_texture.raw_view);
+            self.gpu.destroy_texture(backdrop_texture.raw);
+        }
+    }
+
     pub fn destroy(&mut self) {
         self.wait_for_gpu();
+        self.destroy_backdrop_texture();
         self.atlas.destroy();
         self.instance_belt.destroy(&self.gpu);
         self.gpu.destroy_command_encoder(&mut self.command_encoder);
@@ -533,199 +629,235 @@ impl BladeRenderer {
             pad: [0; 2],
         };
 
-        if let mut pass = self.command_encoder.render(gpu::RenderTargetSet {
-            colors: &[gpu::RenderTarget {
-                view: frame.texture_view(),
-                init_op: gpu::InitOp::Clear(gpu::TextureColor::TransparentBlack),
-                finish_op: gpu::FinishOp::Store,
-            }],
-            depth_stencil: None,
-        }) {
-            profiling::scope!("render pass");
-            for batch in scene.batches() {
-                match batch {
-                    PrimitiveBatch::Quads(quads) => {
-                        let instance_buf =
-                            unsafe { self.instance_belt.alloc_data(quads, &self.gpu) };
-                        let mut encoder = pass.with(&self.pipelines.quads);
-                        encoder.bind(
-                            0,
-                            &ShaderQuadsData {
-                                globals,
-                                b_quads: instance_buf,
-                            },
-                        );
-                        encoder.draw(0, 4, 0, quads.len() as u32);
-                    }
-                    PrimitiveBatch::Shadows(shadows) => {
-                        let instance_buf =
-                            unsafe { self.instance_belt.alloc_data(shadows, &self.gpu) };
-                        let mut encoder = pass.with(&self.pipelines.shadows);
-                        encoder.bind(
-                            0,
-                            &ShaderShadowsData {
-                                globals,
-                                b_shadows: instance_buf,
-                            },
-                        );
-                        encoder.draw(0, 4, 0, shadows.len() as u32);
-                    }
-                    PrimitiveBatch::Paths(paths) => {
-                        let mut encoder = pass.with(&self.pipelines.paths);
-                        // todo(linux): group by texture ID
-                        for path in paths {
-                            let tile = &self.path_tiles[&path.id];
-                            let tex_info = self.atlas.get_texture_info(tile.texture_id);
-                            let origin = path.bounds.intersect(&path.content_mask.bounds).origin;
-                            let sprites = [PathSprite {
-                                bounds: Bounds {
-                                    origin: origin.map(|p| p.floor()),
-                                    size: tile.bounds.size.map(Into::into),
+        let mut batches = scene.batches();
+        let mut init_op = gpu::InitOp::Clear(gpu::TextureColor::TransparentBlack);
+        let mut backdrop_blurs: Option<(&[BackdropBlur], gpu::TextureView)> = None;
+        loop {
+            // Backdrop blurs sample what has been drawn before them, so the render pass
+            // ends at each batch of them to copy the frame, and then resumes on top of it.
+            let mut next_backdrop_blurs = None;
+            if let mut pass = self.command_encoder.render(gpu::RenderTargetSet {
+                colors: &[gpu::RenderTarget {
+                    view: frame.texture_view(),
+                    init_op,
+                    finish_op: gpu::FinishOp::Store,
+                }],
+                depth_stencil: None,
+            }) {
+                profiling::scope!("render pass");
+                if let Some((backdrop_blurs, t_backdrop)) = backdrop_blurs.take() {
+                    let instance_buf =
+                        unsafe { self.instance_belt.alloc_data(backdrop_blurs, &self.gpu) };
+                    let mut encoder = pass.with(&self.pipelines.backdrop_blurs);
+                    encoder.bind(
+                        0,
+                        &ShaderBackdropBlursData {
+                            globals,
+                            t_backdrop,
+                            s_backdrop: self.atlas_sampler,
+                            b_backdrop_blurs: instance_buf,
+                        },
+                    );
+                    encoder.draw(0, 4, 0, backdrop_blurs.len() as u32);
+                }
+
+                for batch in batches.by_ref() {
+                    match batch {
+                        PrimitiveBatch::BackdropBlurs(backdrop_blurs) => {
+                            next_backdrop_blurs = Some(backdrop_blurs);
+                            break;
+                        }
+                        PrimitiveBatch::Quads(quads) => {
+                            let instance_buf =
+                                unsafe { self.instance_belt.alloc_data(quads, &self.gpu) };
+                            let mut encoder = pass.with(&self.pipelines.quads);
+                            encoder.bind(
+                                0,
+                                &ShaderQuadsData {
+                                    globals,
+                                    b_quads: instance_buf,
+                                },
+                            );
+                            encoder.draw(0, 4, 0, quads.len() as u32);
+                        }
+                        PrimitiveBatch::Shadows(shadows) => {
+                            let instance_buf =
+                                unsafe { self.instance_belt.alloc_data(shadows, &self.gpu) };
+                            let mut encoder = pass.with(&self.pipelines.shadows);
+                            encoder.bind(
+                                0,
+                                &ShaderShadowsData {
+                                    globals,
+                                    b_shadows: instance_buf,
                                 },
-                                color: path.color,
-                                tile: (*tile).clone(),
-                            }];
+                            );
+                            encoder.draw(0, 4, 0, shadows.len() as u32);
+                        }
+                        PrimitiveBatch::Paths(paths) => {
+                            let mut encoder = pass.with(&self.pipelines.paths);
+                            // todo(linux): group by texture ID
+                            for path in paths {
+                                let tile = &self.path_tiles[&path.id];
+                                let tex_info = self.atlas.get_texture_info(tile.texture_id);
+                                let origin =
+                                    path.bounds.intersect(&path.content_mask.bounds).origin;
+                                let sprites = [PathSprite {
+                                    bounds: Bounds {
+                                        origin: origin.map(|p| p.floor()),
+                                        size: tile.bounds.size.map(Into::into),
+                                    },
+                                    color: path.color,
+                                    tile: (*tile).clone(),
+                                }];
 
+                                let instance_buf =
+                                    unsafe { self.instance_belt.alloc_data(&sprites, &self.gpu) };
+                                encoder.bind(
+                                    0,
+                                    &ShaderPathsData {
+                                        globals,
+                                        t_sprite: tex_info.raw_view,
+                                        s_sprite: self.atlas_sampler,
+                                        b_path_sprites: instance_buf,
+                                    },
+                                );
+                                encoder.draw(0, 4, 0, sprites.len() as u32);
+                            }
+                        }
+                        PrimitiveBatch::Underlines(underlines) => {
                             let instance_buf =
-                                unsafe { self.instance_belt.alloc_data(&sprites, &self.gpu) };
+                                unsafe { self.instance_belt.alloc_data(underlines, &self.gpu) };
+                            let mut encoder = pass.with(&self.pipelines.underlines);
                             encoder.bind(
                                 0,
-                                &ShaderPathsData {
+                                &ShaderUnderlinesData {
+                                    globals,
+                                    b_underlines: instance_buf,
+                                },
+                            );
+                            encoder.draw(0, 4, 0, underlines.len() as u32);
+                        }
+                        PrimitiveBatch::MonochromeSprites {
+                            texture_id,
+                            sprites,
+                        } => {
+                            let tex_info = self.atlas.get_texture_info(texture_id);
+                            let instance_buf =
+                                unsafe { self.instance_belt.alloc_data(sprites, &self.gpu) };
+                            let mut encoder = pass.with(&self.pipelines.mono_sprites);
+                            encoder.bind(
+                                0,
+                                &ShaderMonoSpritesData {
                                     globals,
                                     t_sprite: tex_info.raw_view,
                                     s_sprite: self.atlas_sampler,
-                                    b_path_sprites: instance_buf,
+                                    b_mono_sprites: instance_buf,
                                 },
                             );
                             encoder.draw(0, 4, 0, sprites.len() as u32);
                         }
-                    }
-                    PrimitiveBatch::Underlines(underlines) => {
-                        let instance_buf =
-                            unsafe { self.instance_belt.alloc_data(underlines, &self.gpu) };
-                        let mut encoder = pass.with(&self.pipelines.underlines);
-                        encoder.bind(
-                            0,
-                            &ShaderUnderlinesData {
-                                globals,
-                                b_underlines: instance_buf,
-                            },
-                        );
-                        encoder.draw(0, 4, 0, underlines.len() as u32);
-                    }
-                    PrimitiveBatch::MonochromeSprites {
-                        texture_id,
-                        sprites,
-                    } => {
-                        let tex_info = self.atlas.get_texture_info(texture_id);
-                        let instance_buf =
-                            unsafe { self.instance_belt.alloc_data(sprites, &self.gpu) };
-                        let mut encoder = pass.with(&self.pipelines.mono_sprites);
-                        encoder.bind(
-                            0,
-                            &ShaderMonoSpritesData {
-                                globals,
-                                t_sprite: tex_info.raw_view,
-                                s_sprite: self.atlas_sampler,
-                                b_mono_sprites: instance_buf,
-                            },
-                        );
-                        encoder.draw(0, 4, 0, sprites.len() as u32);
-                    }
-                    PrimitiveBatch::PolychromeSprites {
-                        texture_id,
-                        sprites,
-                    } => {
-                        let tex_info = self.atlas.get_texture_info(texture_id);
-                        let instance_buf =
-                            unsafe { self.instance_belt.alloc_data(sprites, &self.gpu) };
-                        let mut encoder = pass.with(&self.pipelines.poly_sprites);
-                        encoder.bind(
-                            0,
-                            &ShaderPolySpritesData {
-                                globals,
-                                t_sprite: tex_info.raw_view,
-                                s_sprite: self.atlas_sampler,
-                                b_poly_sprites: instance_buf,
-                            },
-                        );
-                        encoder.draw(0, 4, 0, sprites.len() as u32);
-                    }
-                    PrimitiveBatch::Surfaces(surfaces) => {
-                        let mut _encoder = pass.with(&self.pipelines.surfaces);
-
-                        for surface in surfaces {
-                            #[cfg(not(target_os = "macos"))]
-                            {
-                                let _ = surface;
-                                continue;
-                            };
-
-                            #[cfg(target_os = "macos")]
-                            {
-                                let (t_y, t_cb_cr) = {
-                                    use core_foundation::base::TCFType as _;
-                                    use std::ptr;
-
-                                    assert_eq!(
+                        PrimitiveBatch::PolychromeSprites {
+                            texture_id,
+                            sprites,
+                        } => {
+                            let tex_info = self.atlas.get_texture_info(texture_id);
+                            let instance_buf =
+                                unsafe { self.instance_belt.alloc_data(sprites, &self.gpu) };
+                            let mut encoder = pass.with(&self.pipelines.poly_sprites);
+                            encoder.bind(
+                                0,
+                                &ShaderPolySpritesData {
+                                    globals,
+                                    t_sprite: tex_info.raw_view,
+                                    s_sprite: self.atlas_sampler,
+                                    b_poly_sprites: instance_buf,
+                                },
+                            );
+                            encoder.draw(0, 4, 0, sprites.len() as u32);
+                        }
+                        PrimitiveBatch::Surfaces(surfaces) => {
+                            let mut _encoder = pass.with(&self.pipelines.surfaces);
+
+                            for surface in surfaces {
+                                #[cfg(not(target_os = "macos"))]
+                                {
+                                    let _ = surface;
+                                    continue;
+                                };
+
+                                #[cfg(target_os = "macos")]
+                                {
+                                    let (t_y, t_cb_cr) = {
+                                        use core_foundation::base::TCFType as _;
+                                        use std::ptr;
+
+                                        assert_eq!(
                                     surface.image_buffer.pixel_format_type(),
                                     media::core_video::kCVPixelFormatType_420YpCbCr8BiPlanarFullRange
                                 );
 
-                                    let y_texture = unsafe {
-                                        self.core_video_texture_cache
-                                            .create_texture_from_image(
-                                                surface.image_buffer.as_concrete_TypeRef(),
-                                                ptr::null(),
-                                                metal::MTLPixelFormat::R8Unorm,
-                                                surface.image_buffer.plane_width(0),
-                                                surface.image_buffer.plane_height(0),
-                                                0,
-                                            )
-                                            .unwrap()
+                                        let y_texture = unsafe {
+                                            self.core_video_texture_cache
+                                                .create_texture_from_image(
+                                                    surface.image_buffer.as_concrete_TypeRef(),
+                                                    ptr::null(),
+                                                    metal::MTLPixelFormat::R8Unorm,
+                                                    surface.image_buffer.plane_width(0),
+                                                    surface.image_buffer.plane_height(0),
+                                                    0,
+                                                )
+                                                .unwrap()
+                                        };
+                                        let cb_cr_texture = unsafe {
+                                            self.core_video_texture_cache
+                                                .create_texture_from_image(
+                                                    surface.image_buffer.as_concrete_TypeRef(),
+                                                    ptr::null(),
+                                                    metal::MTLPixelFormat::RG8Unorm,
+                                                    surface.image_buffer.plane_width(1),
+                                                    surface.image_buffer.plane_height(1),
+                                                    1,
+                                                )
+                                                .unwrap()
+                                        };
+                                        (
+                                            gpu::TextureView::from_metal_texture(
+                                                y_texture.as_texture_ref(),
+                                            ),
+                                            gpu::TextureView::from_metal_texture(
+                                                cb_cr_texture.as_texture_ref(),
+                                            ),
+                                        )
                                     };
-                                    let cb_cr_texture = unsafe {
-                                        self.core_video_texture_cache
-                                            .create_texture_from_image(
-                                                surface.image_buffer.as_concrete_TypeRef(),
-                                                ptr::null(),
-                                                metal::MTLPixelFormat::RG8Unorm,
-                                                surface.image_buffer.plane_width(1),
-                                                surface.image_buffer.plane_height(1),
-                                                1,
-                                            )
-                                            .unwrap()
-                                    };
-                                    (
-                                        gpu::TextureView::from_metal_texture(
-                                            y_texture.as_texture_ref(),
-                                        ),
-                                        gpu::TextureView::from_metal_texture(
-                                            cb_cr_texture.as_texture_ref(),
-                                        ),
-                                    )
-                                };
 
-                                _encoder.bind(
-                                    0,
-                                    &ShaderSurfacesData {
-                                        globals,
-                                        surface_locals: SurfaceParams {
-                                            bounds: surface.bounds.into(),
-                                            content_mask: surface.content_mask.bounds.into(),
+                                    _encoder.bind(
+                                        0,
+                                        &ShaderSurfacesData {
+                                            globals,
+                                            surface_locals: SurfaceParams {
+                                                bounds: surface.bounds.into(),
+                                                content_mask: surface.content_mask.bounds.into(),
+                                            },
+                                            t_y,
+                                            t_cb_cr,
+                                            s_surface: self.atlas_sampler,
                                         },
-                                        t_y,
-                                        t_cb_cr,
-                                        s_surface: self.atlas_sampler,
-                                    },
-                                );
+                                    );
 
-                                _encoder.draw(0, 4, 0, 1);
+                                    _encoder.draw(0, 4, 0, 1);
+                                }
                             }
                         }
                     }
                 }
             }
+
+            let Some(next_backdrop_blurs) = next_backdrop_blurs else {
+                break;
+            };
+            let t_backdrop = self.copy_backdrop_texture(&frame);
+            backdrop_blurs = Some((next_backdrop_blurs, t_backdrop));
+            init_op = gpu::InitOp::Load;
         }
 
         self.command_encoder.present(frame);
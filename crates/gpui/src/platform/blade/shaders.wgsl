@@ -32,6 +32,17 @@ struct Hsla {
     l: f32,
     a: f32,
 }
+struct ColorStop {
+    color: Hsla,
+    percentage: f32,
+}
+struct Background {
+    // 0 - solid, 1 - linear gradient, 2 - radial gradient
+    tag: u32,
+    solid: Hsla,
+    angle: f32,
+    colors: array<ColorStop, 2>,
+}
 
 struct AtlasTextureId {
     index: u32,
@@ -122,6 +133,33 @@ fn hsla_to_rgba(hsla: Hsla) -> vec4<f32> {
     return color;
 }
 
+// Returns the color of a linear or radial gradient at the given position
+// within its bounds, or the solid color if the background isn't a gradient.
+fn gradient_color(background: Background, position: vec2<f32>, bounds: Bounds, solid_color: vec4<f32>) -> vec4<f32> {
+    if (background.tag == 0u) {
+        return solid_color;
+    }
+
+    let half_size = bounds.size / 2.0;
+    let center = bounds.origin + half_size;
+    let center_to_point = position - center;
+    var t = 0.0;
+    if (background.tag == 1u) {
+        // Angles are in CSS convention: 0 degrees points up, increasing clockwise.
+        let angle = background.angle * M_PI_F / 180.0;
+        let direction = vec2<f32>(sin(angle), -cos(angle));
+        let half_length = abs(half_size.x * direction.x) + abs(half_size.y * direction.y);
+        t = dot(center_to_point, direction) / (2.0 * max(half_length, 0.0001)) + 0.5;
+    } else {
+        t = length(center_to_point / max(half_size, vec2<f32>(0.0001)));
+    }
+
+    let first_stop = background.colors[0];
+    let second_stop = background.colors[1];
+    t = saturate((t - first_stop.percentage) / max(second_stop.percentage - first_stop.percentage, 0.0001));
+    return mix(hsla_to_rgba(first_stop.color), hsla_to_rgba(second_stop.color), t);
+}
+
 fn over(below: vec4<f32>, above: vec4<f32>) -> vec4<f32> {
     let alpha = above.a + below.a * (1.0 - above.a);
     let color = (above.rgb * above.a + below.rgb * below.a * (1.0 - above.a)) / alpha;
@@ -183,7 +221,7 @@ struct Quad {
     pad: u32,
     bounds: Bounds,
     content_mask: Bounds,
-    background: Hsla,
+    background: Background,
     border_color: Hsla,
     corner_radii: Corners,
     border_widths: Edges,
@@ -206,7 +244,7 @@ fn vs_quad(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) insta
 
     var out = QuadVarying();
     out.position = to_device_position(unit_vertex, quad.bounds);
-    out.background_color = hsla_to_rgba(quad.background);
+    out.background_color = hsla_to_rgba(quad.background.solid);
     out.border_color = hsla_to_rgba(quad.border_color);
     out.quad_id = instance_id;
     out.clip_distances = distance_from_clip_rect(unit_vertex, quad.bounds, quad.content_mask);
@@ -221,13 +259,14 @@ fn fs_quad(input: QuadVarying) -> @location(0) vec4<f32> {
     }
 
     let quad = b_quads[input.quad_id];
+    let background_color = gradient_color(quad.background, input.position.xy, quad.bounds, input.background_color);
     // Fast path when the quad is not rounded and doesn't have any border.
     if (quad.corner_radii.top_left == 0.0 && quad.corner_radii.bottom_left == 0.0 &&
         quad.corner_radii.top_right == 0.0 &&
         quad.corner_radii.bottom_right == 0.0 && quad.border_widths.top == 0.0 &&
         quad.border_widths.left == 0.0 && quad.border_widths.right == 0.0 &&
         quad.border_widths.bottom == 0.0) {
-        return input.background_color;
+        return background_color;
     }
 
     let half_size = quad.bounds.size / 2.0;
@@ -256,19 +295,81 @@ fn fs_quad(input: QuadVarying) -> @location(0) vec4<f32> {
         border_width = vertical_border;
     }
 
-    var color = input.background_color;
+    var color = background_color;
     if (border_width > 0.0) {
         let inset_distance = distance + border_width;
         // Blend the border on top of the background and then linearly interpolate
         // between the two as we slide inside the background.
-        let blended_border = over(input.background_color, input.border_color);
-        color = mix(blended_border, input.background_color,
+        let blended_border = over(background_color, input.border_color);
+        color = mix(blended_border, background_color,
                     saturate(0.5 - inset_distance));
     }
 
     return color * vec4<f32>(1.0, 1.0, 1.0, saturate(0.5 - distance));
 }
 
+// --- backdrop blurs --- //
+
+struct BackdropBlur {
+    order: u32,
+    blur_radius: f32,
+    bounds: Bounds,
+    corner_radii: Corners,
+    content_mask: Bounds,
+}
+var<storage, read> b_backdrop_blurs: array<BackdropBlur>;
+var t_backdrop: texture_2d<f32>;
+var s_backdrop: sampler;
+
+struct BackdropBlurVarying {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) backdrop_blur_id: u32,
+    @location(1) clip_distances: vec4<f32>,
+}
+
+@vertex
+fn vs_backdrop_blur(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) instance_id: u32) -> BackdropBlurVarying {
+    let unit_vertex = vec2<f32>(f32(vertex_id & 1u), 0.5 * f32(vertex_id & 2u));
+    let backdrop_blur = b_backdrop_blurs[instance_id];
+
+    var out = BackdropBlurVarying();
+    out.position = to_device_position(unit_vertex, backdrop_blur.bounds);
+    out.backdrop_blur_id = instance_id;
+    out.clip_distances = distance_from_clip_rect(unit_vertex, backdrop_blur.bounds, backdrop_blur.content_mask);
+    return out;
+}
+
+@fragment
+fn fs_backdrop_blur(input: BackdropBlurVarying) -> @location(0) vec4<f32> {
+    // Alpha clip first, since we don't have `clip_distance`.
+    if (any(input.clip_distances < vec4<f32>(0.0))) {
+        return vec4<f32>(0.0);
+    }
+
+    let backdrop_blur = b_backdrop_blurs[input.backdrop_blur_id];
+
+    // Sample a grid spanning the blur radius, weighting each sample by its
+    // distance from the fragment.
+    let steps = 4;
+    let radius = backdrop_blur.blur_radius;
+    let sigma = max(radius / 2.0, 0.5);
+    var color = vec4<f32>(0.0);
+    var total_weight = 0.0;
+    for (var y = -steps; y <= steps; y += 1) {
+        for (var x = -steps; x <= steps; x += 1) {
+            let offset = vec2<f32>(f32(x), f32(y)) * (radius / f32(steps));
+            let weight = gaussian(offset.x, sigma) * gaussian(offset.y, sigma);
+            let uv = (input.position.xy + offset) / globals.viewport_size;
+            color += textureSampleLevel(t_backdrop, s_backdrop, uv, 0.0) * weight;
+            total_weight += weight;
+        }
+    }
+    color /= total_weight;
+
+    let distance = quad_sdf(input.position.xy, backdrop_blur.bounds, backdrop_blur.corner_radii);
+    return vec4<f32>(color.rgb, saturate(0.5 - distance));
+}
+
 // --- shadows --- //
 
 struct Shadow {
@@ -0,0 +1,781 @@
+//! A software renderer that rasterizes scenes on the CPU, for windows that are never presented
+//! on a display, such as those of the headless and test platforms.
+
+use crate::{
+    point, AtlasKey, AtlasTextureId, AtlasTextureKind, AtlasTile, BackdropBlur, Background,
+    BackgroundTag, Bounds, Corners, DevicePixels, Hsla, MonochromeSprite, Path, PlatformAtlas,
+    PolychromeSprite, PrimitiveBatch, Quad, Rgba, ScaledPixels, Scene, Shadow, Size, TileId,
+    Underline,
+};
+use anyhow::Result;
+use collections::HashMap;
+use image::RgbaImage;
+use parking_lot::Mutex;
+use std::{borrow::Cow, f32::consts::PI, ops::Range};
+
+/// Rasterizes the given scene into an image of the given size, approximating what the
+/// GPU renderers draw.
+///
+/// Edges are anti-aliased the same way as in the shaders, but paths are sampled once per
+/// pixel and sprites are sampled without filtering, so the output won't match a GPU
+/// renderer pixel for pixel.
+pub(crate) fn render_scene(
+    scene: &Scene,
+    viewport_size: Size<DevicePixels>,
+    atlas: &HeadlessAtlas,
+) -> RgbaImage {
+    let atlas = atlas.0.lock();
+    let mut canvas = Canvas::new(viewport_size);
+    for batch in scene.batches() {
+        match batch {
+            PrimitiveBatch::BackdropBlurs(blurs) => {
+                for blur in blurs {
+                    canvas.draw_backdrop_blur(blur);
+                }
+            }
+            PrimitiveBatch::Shadows(shadows) => {
+                for shadow in shadows {
+                    canvas.draw_shadow(shadow);
+                }
+            }
+            PrimitiveBatch::Quads(quads) => {
+                for quad in quads {
+                    canvas.draw_quad(quad);
+                }
+            }
+            PrimitiveBatch::Paths(paths) => {
+                for path in paths {
+                    canvas.draw_path(path);
+                }
+            }
+            PrimitiveBatch::Underlines(underlines) => {
+                for underline in underlines {
+                    canvas.draw_underline(underline);
+                }
+            }
+            PrimitiveBatch::MonochromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                if let Some(texture) = atlas.textures.get(&texture_id) {
+                    for sprite in sprites {
+                        canvas.draw_monochrome_sprite(sprite, texture);
+                    }
+                }
+            }
+            PrimitiveBatch::PolychromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                if let Some(texture) = atlas.textures.get(&texture_id) {
+                    for sprite in sprites {
+                        canvas.draw_polychrome_sprite(sprite, texture);
+                    }
+                }
+            }
+            // Surfaces are only produced on macOS, from video frames we can't read back.
+            PrimitiveBatch::Surfaces(_) => {}
+        }
+    }
+    canvas.into_image()
+}
+
+struct HeadlessTexture {
+    kind: AtlasTextureKind,
+    size: Size<DevicePixels>,
+    bytes: Vec<u8>,
+}
+
+impl HeadlessTexture {
+    /// Returns the texel under the given position of a sprite with the given bounds,
+    /// if the position is inside of them.
+    fn sample(&self, bounds: Bounds<ScaledPixels>, x: f32, y: f32) -> Option<&[u8]> {
+        let u = (x - bounds.origin.x.0) / bounds.size.width.0;
+        let v = (y - bounds.origin.y.0) / bounds.size.height.0;
+        if !(0. ..1.).contains(&u) || !(0. ..1.).contains(&v) {
+            return None;
+        }
+
+        let width = self.size.width.0 as usize;
+        let height = self.size.height.0 as usize;
+        let bytes_per_texel = match self.kind {
+            AtlasTextureKind::Polychrome => 4,
+            AtlasTextureKind::Monochrome | AtlasTextureKind::Path => 1,
+        };
+        let column = ((u * width as f32) as usize).min(width.saturating_sub(1));
+        let row = ((v * height as f32) as usize).min(height.saturating_sub(1));
+        let offset = (row * width + column) * bytes_per_texel;
+        self.bytes.get(offset..offset + bytes_per_texel)
+    }
+}
+
+struct HeadlessAtlasState {
+    next_id: u32,
+    tiles: HashMap<AtlasKey, AtlasTile>,
+    textures: HashMap<AtlasTextureId, HeadlessTexture>,
+}
+
+/// An atlas that keeps each tile in a texture of its own in main memory, so that
+/// [`render_scene`] can sample them.
+pub(crate) struct HeadlessAtlas(Mutex<HeadlessAtlasState>);
+
+impl HeadlessAtlas {
+    pub fn new() -> Self {
+        HeadlessAtlas(Mutex::new(HeadlessAtlasState {
+            next_id: 0,
+            tiles: HashMap::default(),
+            textures: HashMap::default(),
+        }))
+    }
+}
+
+impl PlatformAtlas for HeadlessAtlas {
+    fn get_or_insert_with<'a>(
+        &self,
+        key: &AtlasKey,
+        build: &mut dyn FnMut() -> Result<(Size<DevicePixels>, Cow<'a, [u8]>)>,
+    ) -> Result<AtlasTile> {
+        let mut state = self.0.lock();
+        if let Some(tile) = state.tiles.get(key) {
+            return Ok(tile.clone());
+        }
+
+        state.next_id += 1;
+        let texture_id = AtlasTextureId {
+            index: state.next_id,
+            kind: key.texture_kind(),
+        };
+        state.next_id += 1;
+        let tile_id = state.next_id;
+
+        drop(state);
+        let (size, bytes) = build()?;
+        let mut state = self.0.lock();
+
+        let tile = AtlasTile {
+            texture_id,
+            tile_id: TileId(tile_id),
+            padding: 0,
+            bounds: Bounds {
+                origin: Default::default(),
+                size,
+            },
+        };
+        state.textures.insert(
+            texture_id,
+            HeadlessTexture {
+                kind: texture_id.kind,
+                size,
+                bytes: bytes.into_owned(),
+            },
+        );
+        state.tiles.insert(key.clone(), tile.clone());
+        Ok(tile)
+    }
+
+    fn retain(&self, predicate: &mut dyn FnMut(&AtlasKey) -> bool) {
+        let state = &mut *self.0.lock();
+        let textures = &mut state.textures;
+        state.tiles.retain(|key, tile| {
+            let retain = predicate(key);
+            if !retain {
+                textures.remove(&tile.texture_id);
+            }
+            retain
+        });
+    }
+}
+
+/// An RGBA color with straight alpha, with each component in a range from 0 to 1.
+type Color = [f32; 4];
+
+struct Canvas {
+    width: i32,
+    height: i32,
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    fn new(size: Size<DevicePixels>) -> Self {
+        let width = size.width.0.max(0);
+        let height = size.height.0.max(0);
+        Self {
+            width,
+            height,
+            pixels: vec![[0.; 4]; (width * height) as usize],
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        RgbaImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let color = self.pixels[(y * self.width as u32 + x) as usize];
+            image::Rgba(color.map(|component| (component.clamp(0., 1.) * 255.).round() as u8))
+        })
+    }
+
+    /// Returns the columns and rows of the pixels whose centers are inside of the given bounds.
+    fn pixel_range(&self, bounds: Bounds<ScaledPixels>) -> Option<(Range<i32>, Range<i32>)> {
+        let columns = ((bounds.origin.x.0 - 0.5).ceil() as i32).max(0)
+            ..((bounds.origin.x.0 + bounds.size.width.0 - 0.5).ceil() as i32).min(self.width);
+        let rows = ((bounds.origin.y.0 - 0.5).ceil() as i32).max(0)
+            ..((bounds.origin.y.0 + bounds.size.height.0 - 0.5).ceil() as i32).min(self.height);
+        (!columns.is_empty() && !rows.is_empty()).then_some((columns, rows))
+    }
+
+    /// Calls the given function with the center of each pixel inside of the given bounds,
+    /// blending the color it returns on top of the pixel.
+    fn fill(&mut self, bounds: Bounds<ScaledPixels>, mut shade: impl FnMut(f32, f32) -> Color) {
+        let Some((columns, rows)) = self.pixel_range(bounds) else {
+            return;
+        };
+        for y in rows {
+            for x in columns.clone() {
+                let color = shade(x as f32 + 0.5, y as f32 + 0.5);
+                self.blend(x, y, color);
+            }
+        }
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Color) {
+        if color[3] > 0. {
+            let pixel = &mut self.pixels[(y * self.width + x) as usize];
+            *pixel = over(*pixel, color);
+        }
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let bounds = quad.bounds;
+        let border_color = to_color(quad.border_color);
+        let half_size = (bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+        let center = (
+            bounds.origin.x.0 + half_size.0,
+            bounds.origin.y.0 + half_size.1,
+        );
+        self.fill(bounds.intersect(&quad.content_mask.bounds), |x, y| {
+            let background_color = background_color(&quad.background, bounds, x, y);
+            let center_to_point = (x - center.0, y - center.1);
+            let corner_radius = pick_corner_radius(center_to_point, &quad.corner_radii);
+            let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+
+            let vertical_border = if center_to_point.0 > 0. {
+                quad.border_widths.right.0
+            } else {
+                quad.border_widths.left.0
+            };
+            let horizontal_border = if center_to_point.1 > 0. {
+                quad.border_widths.bottom.0
+            } else {
+                quad.border_widths.top.0
+            };
+            let point_to_inset_corner = (
+                center_to_point.0.abs() - (half_size.0 - corner_radius - vertical_border),
+                center_to_point.1.abs() - (half_size.1 - corner_radius - horizontal_border),
+            );
+            let border_width = if point_to_inset_corner.0 < 0. && point_to_inset_corner.1 < 0. {
+                0.
+            } else if point_to_inset_corner.1 > point_to_inset_corner.0 {
+                horizontal_border
+            } else {
+                vertical_border
+            };
+
+            let mut color = background_color;
+            if border_width > 0. {
+                // Blend the border on top of the background and then linearly interpolate
+                // between the two as we slide inside the background.
+                let inset_distance = distance + border_width;
+                color = mix(
+                    over(background_color, border_color),
+                    background_color,
+                    saturate(0.5 - inset_distance),
+                );
+            }
+            with_coverage(color, saturate(0.5 - distance))
+        });
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let blur_radius = shadow.blur_radius.0;
+        let bounds = shadow.bounds;
+        let color = to_color(shadow.color);
+        let half_size = (bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+        let center = (
+            bounds.origin.x.0 + half_size.0,
+            bounds.origin.y.0 + half_size.1,
+        );
+        let margin = ScaledPixels(3. * blur_radius);
+        let shadow_bounds = Bounds {
+            origin: point(bounds.origin.x - margin, bounds.origin.y - margin),
+            size: Size {
+                width: bounds.size.width + margin + margin,
+                height: bounds.size.height + margin + margin,
+            },
+        };
+
+        self.fill(
+            shadow_bounds.intersect(&shadow.content_mask.bounds),
+            |x, y| {
+                let center_to_point = (x - center.0, y - center.1);
+                let corner_radius = pick_corner_radius(center_to_point, &shadow.corner_radii);
+                if blur_radius <= 0. {
+                    let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+                    return with_coverage(color, saturate(0.5 - distance));
+                }
+
+                // The signal is only non-zero in a limited range, so don't waste samples.
+                let low = center_to_point.1 - half_size.1;
+                let high = center_to_point.1 + half_size.1;
+                let start = (-3. * blur_radius).clamp(low, high);
+                let end = (3. * blur_radius).clamp(low, high);
+
+                let step = (end - start) / 4.;
+                let mut y = start + step * 0.5;
+                let mut alpha = 0.;
+                for _ in 0..4 {
+                    let blur = blur_along_x(
+                        center_to_point.0,
+                        center_to_point.1 - y,
+                        blur_radius,
+                        corner_radius,
+                        half_size,
+                    );
+                    alpha += blur * gaussian(y, blur_radius) * step;
+                    y += step;
+                }
+                with_coverage(color, alpha)
+            },
+        );
+    }
+
+    fn draw_backdrop_blur(&mut self, blur: &BackdropBlur) {
+        const STEPS: i32 = 4;
+
+        let backdrop = self.pixels.clone();
+        let (width, height) = (self.width, self.height);
+        let sample = |x: f32, y: f32| {
+            let column = (x.floor() as i32).clamp(0, width - 1);
+            let row = (y.floor() as i32).clamp(0, height - 1);
+            backdrop[(row * width + column) as usize]
+        };
+
+        let bounds = blur.bounds;
+        let half_size = (bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+        let center = (
+            bounds.origin.x.0 + half_size.0,
+            bounds.origin.y.0 + half_size.1,
+        );
+        let radius = blur.blur_radius.0;
+        let sigma = (radius / 2.).max(0.5);
+        self.fill(bounds.intersect(&blur.content_mask.bounds), |x, y| {
+            // Sample a grid spanning the blur radius, weighting each sample by its
+            // distance from the pixel.
+            let mut color = [0.; 4];
+            let mut total_weight = 0.;
+            for row in -STEPS..=STEPS {
+                for column in -STEPS..=STEPS {
+                    let offset = (
+                        column as f32 * radius / STEPS as f32,
+                        row as f32 * radius / STEPS as f32,
+                    );
+                    let weight = gaussian(offset.0, sigma) * gaussian(offset.1, sigma);
+                    let texel = sample(x + offset.0, y + offset.1);
+                    for (component, texel_component) in color.iter_mut().zip(texel) {
+                        *component += texel_component * weight;
+                    }
+                    total_weight += weight;
+                }
+            }
+
+            let center_to_point = (x - center.0, y - center.1);
+            let corner_radius = pick_corner_radius(center_to_point, &blur.corner_radii);
+            let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+            [
+                color[0] / total_weight,
+                color[1] / total_weight,
+                color[2] / total_weight,
+                saturate(0.5 - distance),
+            ]
+        });
+    }
+
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let clip_bounds = path.bounds.intersect(&path.content_mask.bounds);
+        let Some((columns, rows)) = self.pixel_range(clip_bounds) else {
+            return;
+        };
+
+        // Count how many triangles cover each pixel, so that overlapping contours can be
+        // filled with the even-odd rule.
+        let mut coverage = vec![0_u32; columns.len() * rows.len()];
+        for triangle in path.vertices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|ix| {
+                let vertex = &triangle[ix];
+                (
+                    vertex.xy_position.x.0,
+                    vertex.xy_position.y.0,
+                    vertex.st_position.x,
+                    vertex.st_position.y,
+                )
+            });
+            let area = edge_function(a, b, (c.0, c.1));
+            if area == 0. {
+                continue;
+            }
+
+            let triangle_bounds = Bounds::from_corners(
+                point(
+                    ScaledPixels(a.0.min(b.0).min(c.0)),
+                    ScaledPixels(a.1.min(b.1).min(c.1)),
+                ),
+                point(
+                    ScaledPixels(a.0.max(b.0).max(c.0)),
+                    ScaledPixels(a.1.max(b.1).max(c.1)),
+                ),
+            );
+            let Some((triangle_columns, triangle_rows)) =
+                self.pixel_range(triangle_bounds.intersect(&clip_bounds))
+            else {
+                continue;
+            };
+
+            for y in triangle_rows {
+                for x in triangle_columns.clone() {
+                    let position = (x as f32 + 0.5, y as f32 + 0.5);
+                    let weights = [
+                        edge_function(b, c, position) / area,
+                        edge_function(c, a, position) / area,
+                        edge_function(a, b, position) / area,
+                    ];
+                    if weights.iter().any(|weight| *weight < 0.) {
+                        continue;
+                    }
+
+                    // Curves are drawn as triangles in which the inside of the curve is
+                    // where s² - t is negative.
+                    let s = weights[0] * a.2 + weights[1] * b.2 + weights[2] * c.2;
+                    let t = weights[0] * a.3 + weights[1] * b.3 + weights[2] * c.3;
+                    if s * s - t <= 0. {
+                        let ix = (y - rows.start) as usize * columns.len()
+                            + (x - columns.start) as usize;
+                        coverage[ix] += 1;
+                    }
+                }
+            }
+        }
+
+        let color = to_color(path.color);
+        for y in rows.clone() {
+            for x in columns.clone() {
+                let ix = (y - rows.start) as usize * columns.len() + (x - columns.start) as usize;
+                if coverage[ix] % 2 == 1 {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        let color = to_color(underline.color);
+        let bounds = underline.bounds;
+        let clip_bounds = bounds.intersect(&underline.content_mask.bounds);
+        if !underline.wavy {
+            self.fill(clip_bounds, |_, _| color);
+            return;
+        }
+
+        let thickness = underline.thickness.0;
+        let height = bounds.size.height.0;
+        let frequency = PI * 3. * thickness / 8.;
+        let amplitude = 1. / (2. * thickness);
+        self.fill(clip_bounds, |x, y| {
+            let s = (x - bounds.origin.x.0) / height;
+            let t = (y - bounds.origin.y.0) / height - 0.5;
+            let sine = (s * frequency).sin() * amplitude;
+            let sine_slope = (s * frequency).cos() * amplitude * frequency;
+            let distance = (t - sine) / (1. + sine_slope * sine_slope).sqrt() * height;
+            let distance_from_top_border = distance - thickness / 2.;
+            let distance_from_bottom_border = distance + thickness / 2.;
+            with_coverage(
+                color,
+                saturate(0.5 - (-distance_from_bottom_border).max(distance_from_top_border)),
+            )
+        });
+    }
+
+    fn draw_monochrome_sprite(&mut self, sprite: &MonochromeSprite, texture: &HeadlessTexture) {
+        let [[a, b], [c, d]] = sprite.transformation.rotation_scale;
+        let [tx, ty] = sprite.transformation.translation;
+        let determinant = a * d - b * c;
+        if determinant == 0. {
+            return;
+        }
+
+        // Fill the bounding box of the transformed sprite, mapping each pixel back to the
+        // untransformed sprite to sample it and test it against the content mask.
+        let bounds = sprite.bounds;
+        let corners = [
+            bounds.origin,
+            bounds.upper_right(),
+            bounds.lower_right(),
+            bounds.lower_left(),
+        ]
+        .map(|corner| {
+            (
+                a * corner.x.0 + b * corner.y.0 + tx,
+                c * corner.x.0 + d * corner.y.0 + ty,
+            )
+        });
+        let transformed_bounds = Bounds::from_corners(
+            point(
+                ScaledPixels(corners.iter().map(|p| p.0).fold(f32::INFINITY, f32::min)),
+                ScaledPixels(corners.iter().map(|p| p.1).fold(f32::INFINITY, f32::min)),
+            ),
+            point(
+                ScaledPixels(
+                    corners
+                        .iter()
+                        .map(|p| p.0)
+                        .fold(f32::NEG_INFINITY, f32::max),
+                ),
+                ScaledPixels(
+                    corners
+                        .iter()
+                        .map(|p| p.1)
+                        .fold(f32::NEG_INFINITY, f32::max),
+                ),
+            ),
+        );
+
+        let color = to_color(sprite.color);
+        let content_mask = sprite.content_mask.bounds;
+        self.fill(transformed_bounds, |x, y| {
+            let (dx, dy) = (x - tx, y - ty);
+            let x = (d * dx - b * dy) / determinant;
+            let y = (a * dy - c * dx) / determinant;
+            let position = point(ScaledPixels(x), ScaledPixels(y));
+            if !content_mask.contains(&position) {
+                return [0.; 4];
+            }
+            match texture.sample(bounds, x, y) {
+                Some(texel) => with_coverage(color, texel[0] as f32 / 255.),
+                None => [0.; 4],
+            }
+        });
+    }
+
+    fn draw_polychrome_sprite(&mut self, sprite: &PolychromeSprite, texture: &HeadlessTexture) {
+        let bounds = sprite.bounds;
+        let half_size = (bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+        let center = (
+            bounds.origin.x.0 + half_size.0,
+            bounds.origin.y.0 + half_size.1,
+        );
+        self.fill(bounds.intersect(&sprite.content_mask.bounds), |x, y| {
+            let Some(texel) = texture.sample(bounds, x, y) else {
+                return [0.; 4];
+            };
+            // Polychrome tiles are stored as BGRA.
+            let mut color = [texel[2], texel[1], texel[0], texel[3]].map(|c| c as f32 / 255.);
+            if sprite.grayscale {
+                let grayscale = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+                color = [grayscale, grayscale, grayscale, color[3]];
+            }
+
+            let center_to_point = (x - center.0, y - center.1);
+            let corner_radius = pick_corner_radius(center_to_point, &sprite.corner_radii);
+            let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+            with_coverage(color, saturate(0.5 - distance))
+        });
+    }
+}
+
+fn to_color(color: Hsla) -> Color {
+    let color = Rgba::from(color);
+    [color.r, color.g, color.b, color.a]
+}
+
+fn with_coverage(color: Color, coverage: f32) -> Color {
+    [color[0], color[1], color[2], color[3] * coverage]
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0., 1.)
+}
+
+fn mix(from: Color, to: Color, t: f32) -> Color {
+    [0, 1, 2, 3].map(|ix| from[ix] + (to[ix] - from[ix]) * t)
+}
+
+fn over(below: Color, above: Color) -> Color {
+    let alpha = above[3] + below[3] * (1. - above[3]);
+    if alpha <= 0. {
+        return [0.; 4];
+    }
+    let [r, g, b] =
+        [0, 1, 2].map(|ix| (above[ix] * above[3] + below[ix] * below[3] * (1. - above[3])) / alpha);
+    [r, g, b, alpha]
+}
+
+fn background_color(
+    background: &Background,
+    bounds: Bounds<ScaledPixels>,
+    x: f32,
+    y: f32,
+) -> Color {
+    let half_size = (bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+    let center_to_point = (
+        x - (bounds.origin.x.0 + half_size.0),
+        y - (bounds.origin.y.0 + half_size.1),
+    );
+    let t = match background.tag {
+        BackgroundTag::Solid => return to_color(background.solid),
+        BackgroundTag::LinearGradient => {
+            // Angles are in CSS convention: 0 degrees points up, increasing clockwise.
+            let angle = background.angle.to_radians();
+            let direction = (angle.sin(), -angle.cos());
+            let half_length = (half_size.0 * direction.0).abs() + (half_size.1 * direction.1).abs();
+            (center_to_point.0 * direction.0 + center_to_point.1 * direction.1)
+                / (2. * half_length.max(0.0001))
+                + 0.5
+        }
+        BackgroundTag::RadialGradient => {
+            let x = center_to_point.0 / half_size.0.max(0.0001);
+            let y = center_to_point.1 / half_size.1.max(0.0001);
+            (x * x + y * y).sqrt()
+        }
+    };
+
+    let [first_stop, second_stop] = background.colors;
+    let t = saturate(
+        (t - first_stop.percentage) / (second_stop.percentage - first_stop.percentage).max(0.0001),
+    );
+    mix(to_color(first_stop.color), to_color(second_stop.color), t)
+}
+
+fn pick_corner_radius(center_to_point: (f32, f32), radii: &Corners<ScaledPixels>) -> f32 {
+    match (center_to_point.0 < 0., center_to_point.1 < 0.) {
+        (true, true) => radii.top_left.0,
+        (true, false) => radii.bottom_left.0,
+        (false, true) => radii.top_right.0,
+        (false, false) => radii.bottom_right.0,
+    }
+}
+
+/// The signed distance from the given point, relative to the center of a rounded rectangle,
+/// to its edge.
+fn rounded_rect_sdf(center_to_point: (f32, f32), half_size: (f32, f32), corner_radius: f32) -> f32 {
+    let rounded_edge_to_point = (
+        center_to_point.0.abs() - half_size.0 + corner_radius,
+        center_to_point.1.abs() - half_size.1 + corner_radius,
+    );
+    let outside = (
+        rounded_edge_to_point.0.max(0.),
+        rounded_edge_to_point.1.max(0.),
+    );
+    (outside.0 * outside.0 + outside.1 * outside.1).sqrt()
+        + rounded_edge_to_point.0.max(rounded_edge_to_point.1).min(0.)
+        - corner_radius
+}
+
+/// A standard gaussian function, used for weighting samples.
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+/// Approximates the error function, needed for the gaussian integral.
+fn erf(x: f32) -> f32 {
+    let sign = if x < 0. { -1. } else { 1. };
+    let x = x.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + 0.078108 * (x * x)) * x) * x;
+    let r2 = r1 * r1;
+    sign - sign / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_size: (f32, f32)) -> f32 {
+    let delta = (half_size.1 - corner - y.abs()).min(0.);
+    let curved = half_size.0 - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let start = 0.5 + 0.5 * erf((x - curved) * (0.5_f32.sqrt() / sigma));
+    let end = 0.5 + 0.5 * erf((x + curved) * (0.5_f32.sqrt() / sigma));
+    end - start
+}
+
+/// Twice the signed area of the triangle made of the given points, which tells which side
+/// of the line from `a` to `b` the point `p` is on.
+fn edge_function(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{
+        self as gpui, div, drawing, point, px, rgb, size, Drawing, IntoElement, ParentElement,
+        PathBuilder, Render, Styled, TestAppContext, ViewContext,
+    };
+
+    const WHITE: image::Rgba<u8> = image::Rgba([255, 255, 255, 255]);
+    const RED: image::Rgba<u8> = image::Rgba([255, 0, 0, 255]);
+
+    struct Swatches {
+        drawing: Rc<Drawing>,
+    }
+
+    impl Render for Swatches {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .bg(rgb(0xffffff))
+                .child(
+                    div()
+                        .absolute()
+                        .top(px(10.))
+                        .left(px(10.))
+                        .size(px(20.))
+                        .rounded(px(10.))
+                        .bg(rgb(0xff0000)),
+                )
+                .child(
+                    drawing(self.drawing.clone())
+                        .absolute()
+                        .top(px(50.))
+                        .left(px(0.))
+                        .size(px(50.)),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_render_to_image(cx: &mut TestAppContext) {
+        // A ring, made of two circles filled with the even-odd rule.
+        let mut ring = PathBuilder::new();
+        ring.circle(point(px(25.), px(25.)), px(20.))
+            .circle(point(px(25.), px(25.)), px(10.));
+        let mut drawing = Drawing::new();
+        drawing.fill(&ring, rgb(0xff0000));
+
+        let (_, cx) = cx.add_window_view(|_| Swatches {
+            drawing: Rc::new(drawing),
+        });
+        cx.simulate_resize(size(px(100.), px(100.)));
+        let scale_factor = cx.update(|cx| cx.scale_factor());
+
+        let image = cx.render_to_image();
+        assert_eq!(
+            image.dimensions(),
+            ((100. * scale_factor) as u32, (100. * scale_factor) as u32)
+        );
+        let pixel =
+            |x: f32, y: f32| *image.get_pixel((x * scale_factor) as u32, (y * scale_factor) as u32);
+
+        // The rounded quad is a circle, so its bounds' corners are left uncovered.
+        assert_eq!(pixel(20., 20.), RED);
+        assert_eq!(pixel(11., 11.), WHITE);
+        assert_eq!(pixel(60., 60.), WHITE);
+
+        // The inner circle cuts a hole into the outer one.
+        assert_eq!(pixel(10., 75.), RED);
+        assert_eq!(pixel(25., 75.), WHITE);
+        assert_eq!(pixel(1., 51.), WHITE);
+    }
+}
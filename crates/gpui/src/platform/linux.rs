@@ -1,12 +1,14 @@
 // todo(linux): remove
 #![allow(unused)]
 
+mod accessibility;
 mod dispatcher;
 mod headless;
 mod platform;
 mod wayland;
 mod x11;
 
+pub(crate) use accessibility::*;
 pub(crate) use dispatcher::*;
 pub(crate) use headless::*;
 pub(crate) use platform::*;
@@ -0,0 +1,1029 @@
+//! Exposes the focused text of each window to assistive technologies, such as Orca, over AT-SPI.
+//!
+//! Every window is published as a frame under the application root, and the text reported by
+//! `PlatformWindow::set_accessible_text` as a text node inside of it. The interfaces are
+//! described in https://gitlab.gnome.org/GNOME/at-spi2-core/-/tree/main/xml.
+
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use util::ResultExt;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, Value};
+use zbus::{interface, Connection, ConnectionBuilder};
+
+use crate::{AccessibleRole, AccessibleText, BackgroundExecutor};
+
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+const REGISTRY_NAME: &str = "org.a11y.atspi.Registry";
+const OBJECT_EVENT_INTERFACE: &str = "org.a11y.atspi.Event.Object";
+
+// https://gitlab.gnome.org/GNOME/at-spi2-core/-/blob/main/atspi/atspi-constants.h
+const ROLE_FRAME: u32 = 23;
+const ROLE_TEXT: u32 = 61;
+const ROLE_APPLICATION: u32 = 75;
+const ROLE_ENTRY: u32 = 79;
+
+const STATE_ACTIVE: u32 = 1;
+const STATE_EDITABLE: u32 = 7;
+const STATE_ENABLED: u32 = 8;
+const STATE_FOCUSABLE: u32 = 11;
+const STATE_FOCUSED: u32 = 12;
+const STATE_MULTI_LINE: u32 = 17;
+const STATE_SENSITIVE: u32 = 24;
+const STATE_SHOWING: u32 = 25;
+const STATE_SINGLE_LINE: u32 = 26;
+const STATE_VISIBLE: u32 = 30;
+
+const LIVE_ASSERTIVE: i32 = 2;
+
+/// A reference to an accessible object: the bus name of its application and its object path.
+type ObjectRef = (String, OwnedObjectPath);
+
+/// Publishes the accessible objects of this application on the AT-SPI bus.
+///
+/// The bus is only connected once the first window is opened, so headless clients never
+/// register with the accessibility registry.
+#[derive(Clone)]
+pub(crate) struct AccessibilityBridge(Arc<BridgeInner>);
+
+struct BridgeInner {
+    state: Arc<Mutex<BridgeState>>,
+    requests: UnboundedSender<Request>,
+    pending_requests: Mutex<Option<UnboundedReceiver<Request>>>,
+    next_window_id: AtomicU64,
+    executor: BackgroundExecutor,
+}
+
+#[derive(Default)]
+struct BridgeState {
+    bus_name: String,
+    parent: Option<ObjectRef>,
+    windows: BTreeMap<u64, WindowState>,
+}
+
+#[derive(Default)]
+struct WindowState {
+    title: String,
+    text: Option<AccessibleText>,
+}
+
+/// Requests are handled in order on a single task, so that events reach the screen reader
+/// in the order they happened, and objects are never published after being removed.
+enum Request {
+    AddWindow(u64),
+    RemoveWindow(u64),
+    Emit(Node, Event),
+}
+
+struct Event {
+    member: &'static str,
+    detail: &'static str,
+    detail1: i32,
+    detail2: i32,
+    data: Value<'static>,
+}
+
+impl Event {
+    fn new(member: &'static str, detail: &'static str, detail1: i32, detail2: i32) -> Self {
+        Self {
+            member,
+            detail,
+            detail1,
+            detail2,
+            data: Value::from(0i32),
+        }
+    }
+
+    fn with_data(mut self, data: impl Into<Value<'static>>) -> Self {
+        self.data = data.into();
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Node {
+    Application,
+    Frame(u64),
+    Text(u64),
+}
+
+impl Node {
+    fn path(&self) -> String {
+        match self {
+            Node::Application => ROOT_PATH.to_string(),
+            Node::Frame(id) => format!("/org/a11y/atspi/accessible/{id}"),
+            Node::Text(id) => format!("/org/a11y/atspi/accessible/{id}/text"),
+        }
+    }
+}
+
+impl BridgeState {
+    fn object_ref(&self, node: Node) -> ObjectRef {
+        (
+            self.bus_name.clone(),
+            ObjectPath::from_string_unchecked(node.path()).into(),
+        )
+    }
+
+    fn text(&self, id: u64) -> Option<&AccessibleText> {
+        self.windows.get(&id)?.text.as_ref()
+    }
+
+    fn children(&self, node: Node) -> Vec<Node> {
+        match node {
+            Node::Application => self.windows.keys().map(|id| Node::Frame(*id)).collect(),
+            Node::Frame(id) if self.text(id).is_some() => vec![Node::Text(id)],
+            Node::Frame(_) | Node::Text(_) => Vec::new(),
+        }
+    }
+
+    fn parent(&self, node: Node) -> ObjectRef {
+        match node {
+            Node::Application => self.parent.clone().unwrap_or_else(|| {
+                (
+                    String::new(),
+                    ObjectPath::from_static_str_unchecked(NULL_PATH).into(),
+                )
+            }),
+            Node::Frame(_) => self.object_ref(Node::Application),
+            Node::Text(id) => self.object_ref(Node::Frame(id)),
+        }
+    }
+}
+
+impl AccessibilityBridge {
+    pub fn new(executor: BackgroundExecutor) -> Self {
+        let (requests, pending_requests) = mpsc::unbounded();
+        Self(Arc::new(BridgeInner {
+            state: Arc::default(),
+            requests,
+            pending_requests: Mutex::new(Some(pending_requests)),
+            next_window_id: AtomicU64::new(0),
+            executor,
+        }))
+    }
+
+    /// Publishes a new window, connecting to the accessibility bus if needed.
+    pub fn open_window(&self, title: &str) -> AccessibleWindow {
+        if let Some(requests) = self.0.pending_requests.lock().take() {
+            let state = self.0.state.clone();
+            self.0
+                .executor
+                .spawn(async move {
+                    if let Err(error) = run(state, requests).await {
+                        log::info!("accessibility bus is unavailable: {error}");
+                    }
+                })
+                .detach();
+        }
+
+        let id = self.0.next_window_id.fetch_add(1, SeqCst);
+        self.0.state.lock().windows.insert(
+            id,
+            WindowState {
+                title: title.to_string(),
+                text: None,
+            },
+        );
+        self.send(Request::AddWindow(id));
+        AccessibleWindow {
+            id,
+            bridge: self.clone(),
+        }
+    }
+
+    fn send(&self, request: Request) {
+        // The receiver is gone when the accessibility bus is unavailable.
+        self.0.requests.unbounded_send(request).ok();
+    }
+}
+
+/// The accessible objects of a single window, which are unpublished when this is dropped.
+pub(crate) struct AccessibleWindow {
+    id: u64,
+    bridge: AccessibilityBridge,
+}
+
+impl AccessibleWindow {
+    pub fn set_title(&self, title: &str) {
+        let mut state = self.bridge.0.state.lock();
+        let Some(window) = state.windows.get_mut(&self.id) else {
+            return;
+        };
+        window.title = title.to_string();
+        drop(state);
+
+        self.bridge.send(Request::Emit(
+            Node::Frame(self.id),
+            Event::new("PropertyChange", "accessible-name", 0, 0).with_data(title.to_string()),
+        ));
+    }
+
+    pub fn set_text(&self, text: Option<AccessibleText>) {
+        let mut state = self.bridge.0.state.lock();
+        let Some(window) = state.windows.get_mut(&self.id) else {
+            return;
+        };
+        let old_text = mem::replace(&mut window.text, text);
+        let events = text_events(self.id, &state, old_text.as_ref());
+        drop(state);
+
+        for (node, event) in events {
+            self.bridge.send(Request::Emit(node, event));
+        }
+    }
+
+    pub fn announce(&self, message: &str) {
+        self.bridge.send(Request::Emit(
+            Node::Frame(self.id),
+            Event::new("Announcement", "", LIVE_ASSERTIVE, 0).with_data(message.to_string()),
+        ));
+    }
+}
+
+impl Drop for AccessibleWindow {
+    fn drop(&mut self) {
+        self.bridge.0.state.lock().windows.remove(&self.id);
+        self.bridge.send(Request::RemoveWindow(self.id));
+    }
+}
+
+/// Describes the change from `old_text` to the window's current text as AT-SPI events.
+fn text_events(
+    id: u64,
+    state: &BridgeState,
+    old_text: Option<&AccessibleText>,
+) -> Vec<(Node, Event)> {
+    let frame = Node::Frame(id);
+    let node = Node::Text(id);
+    let mut events = Vec::new();
+    match (old_text, state.text(id)) {
+        (None, None) => {}
+        (None, Some(_)) => {
+            let child = Value::from(state.object_ref(node));
+            events.push((
+                frame,
+                Event::new("ChildrenChanged", "add", 0, 0).with_data(child),
+            ));
+            events.push((node, Event::new("StateChanged", "focused", 1, 0)));
+        }
+        (Some(_), None) => {
+            let child = Value::from(state.object_ref(node));
+            events.push((node, Event::new("StateChanged", "focused", 0, 0)));
+            events.push((
+                frame,
+                Event::new("ChildrenChanged", "remove", 0, 0).with_data(child),
+            ));
+        }
+        (Some(old), Some(new)) => {
+            if old.label != new.label {
+                events.push((
+                    node,
+                    Event::new("PropertyChange", "accessible-name", 0, 0)
+                        .with_data(new.label.to_string()),
+                ));
+            }
+
+            if old.value != new.value {
+                let (start, removed, inserted) = text_change(&old.value, &new.value);
+                if !removed.is_empty() {
+                    let len = removed.chars().count() as i32;
+                    events.push((
+                        node,
+                        Event::new("TextChanged", "delete", start as i32, len).with_data(removed),
+                    ));
+                }
+                if !inserted.is_empty() {
+                    let len = inserted.chars().count() as i32;
+                    events.push((
+                        node,
+                        Event::new("TextChanged", "insert", start as i32, len).with_data(inserted),
+                    ));
+                }
+            }
+
+            let old_selection = selected_char_range(old);
+            let new_selection = selected_char_range(new);
+            if old_selection.1 != new_selection.1 {
+                events.push((
+                    node,
+                    Event::new("TextCaretMoved", "", new_selection.1 as i32, 0),
+                ));
+            }
+            if old_selection != new_selection
+                && (old_selection.0 != old_selection.1 || new_selection.0 != new_selection.1)
+            {
+                events.push((node, Event::new("TextSelectionChanged", "", 0, 0)));
+            }
+        }
+    }
+    events
+}
+
+/// Returns the character offset at which `old` and `new` differ, along with the removed and
+/// inserted text.
+fn text_change(old: &str, new: &str) -> (usize, String, String) {
+    let prefix_len = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old_rest = old.chars().skip(prefix_len).collect::<Vec<_>>();
+    let new_rest = new.chars().skip(prefix_len).collect::<Vec<_>>();
+    let suffix_len = old_rest
+        .iter()
+        .rev()
+        .zip(new_rest.iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let removed = old_rest[..old_rest.len() - suffix_len].iter().collect();
+    let inserted = new_rest[..new_rest.len() - suffix_len].iter().collect();
+    (prefix_len, removed, inserted)
+}
+
+/// AT-SPI offsets count characters, while gpui reports selections in UTF-16 code units.
+fn char_offset(text: &str, offset_utf16: usize) -> usize {
+    let mut utf16_count = 0;
+    for (char_count, c) in text.chars().enumerate() {
+        if utf16_count >= offset_utf16 {
+            return char_count;
+        }
+        utf16_count += c.len_utf16();
+    }
+    text.chars().count()
+}
+
+fn selected_char_range(text: &AccessibleText) -> (usize, usize) {
+    (
+        char_offset(&text.value, text.selected_range_utf16.start),
+        char_offset(&text.value, text.selected_range_utf16.end),
+    )
+}
+
+#[derive(Clone, Copy)]
+enum Granularity {
+    Char,
+    Word,
+    Line,
+}
+
+impl Granularity {
+    /// Sentences and paragraphs are approximated by lines.
+    fn from_granularity(granularity: u32) -> Self {
+        match granularity {
+            0 => Granularity::Char,
+            1 => Granularity::Word,
+            _ => Granularity::Line,
+        }
+    }
+
+    fn from_boundary_type(boundary_type: u32) -> Self {
+        match boundary_type {
+            0 => Granularity::Char,
+            1 | 2 => Granularity::Word,
+            _ => Granularity::Line,
+        }
+    }
+
+    fn range_at(self, chars: &[char], offset: usize) -> (usize, usize) {
+        if chars.is_empty() {
+            return (0, 0);
+        }
+        let offset = offset.min(chars.len());
+        match self {
+            Granularity::Char => (offset, (offset + 1).min(chars.len())),
+            Granularity::Word => {
+                let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+                let is_word_start = |ix: usize| {
+                    is_word_char(chars[ix]) && (ix == 0 || !is_word_char(chars[ix - 1]))
+                };
+                let start = (0..=offset.min(chars.len() - 1))
+                    .rev()
+                    .find(|ix| is_word_start(*ix))
+                    .unwrap_or(0);
+                let end = (offset + 1..chars.len())
+                    .find(|ix| is_word_start(*ix))
+                    .unwrap_or(chars.len());
+                (start, end)
+            }
+            Granularity::Line => {
+                let start = chars[..offset]
+                    .iter()
+                    .rposition(|c| *c == '\n')
+                    .map_or(0, |ix| ix + 1);
+                let end = chars[offset..]
+                    .iter()
+                    .position(|c| *c == '\n')
+                    .map_or(chars.len(), |ix| offset + ix + 1);
+                (start, end)
+            }
+        }
+    }
+
+    fn range_before(self, chars: &[char], offset: usize) -> (usize, usize) {
+        let (start, _) = self.range_at(chars, offset);
+        if start == 0 {
+            (0, 0)
+        } else {
+            self.range_at(chars, start - 1)
+        }
+    }
+
+    fn range_after(self, chars: &[char], offset: usize) -> (usize, usize) {
+        let (_, end) = self.range_at(chars, offset);
+        if end >= chars.len() {
+            (chars.len(), chars.len())
+        } else {
+            self.range_at(chars, end)
+        }
+    }
+}
+
+fn text_in_range(chars: &[char], (start, end): (usize, usize)) -> (String, i32, i32) {
+    (chars[start..end].iter().collect(), start as i32, end as i32)
+}
+
+async fn run(
+    state: Arc<Mutex<BridgeState>>,
+    mut requests: UnboundedReceiver<Request>,
+) -> zbus::Result<()> {
+    let connection = connect(&state).await?;
+    let object_server = connection.object_server();
+    while let Some(request) = requests.next().await {
+        match request {
+            Request::AddWindow(id) => {
+                let frame = AccessibleNode {
+                    node: Node::Frame(id),
+                    state: state.clone(),
+                };
+                let text = AccessibleNode {
+                    node: Node::Text(id),
+                    state: state.clone(),
+                };
+                let text_interface = TextInterface {
+                    id,
+                    state: state.clone(),
+                };
+                object_server
+                    .at(Node::Frame(id).path(), frame)
+                    .await
+                    .log_err();
+                object_server
+                    .at(Node::Text(id).path(), text)
+                    .await
+                    .log_err();
+                object_server
+                    .at(Node::Text(id).path(), text_interface)
+                    .await
+                    .log_err();
+            }
+            Request::RemoveWindow(id) => {
+                object_server
+                    .remove::<AccessibleNode, _>(Node::Frame(id).path())
+                    .await
+                    .log_err();
+                object_server
+                    .remove::<AccessibleNode, _>(Node::Text(id).path())
+                    .await
+                    .log_err();
+                object_server
+                    .remove::<TextInterface, _>(Node::Text(id).path())
+                    .await
+                    .log_err();
+            }
+            Request::Emit(node, event) => {
+                connection
+                    .emit_signal(
+                        None::<&str>,
+                        node.path().as_str(),
+                        OBJECT_EVENT_INTERFACE,
+                        event.member,
+                        &(
+                            event.detail,
+                            event.detail1,
+                            event.detail2,
+                            event.data,
+                            HashMap::<&str, Value>::new(),
+                        ),
+                    )
+                    .await
+                    .log_err();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Connects to the accessibility bus and embeds the application root into the registry.
+async fn connect(state: &Arc<Mutex<BridgeState>>) -> zbus::Result<Connection> {
+    let session = Connection::session().await?;
+    let reply = session
+        .call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )
+        .await?;
+    let address: String = reply.body().deserialize()?;
+
+    let root = AccessibleNode {
+        node: Node::Application,
+        state: state.clone(),
+    };
+    let connection = ConnectionBuilder::address(address.as_str())?
+        .serve_at(ROOT_PATH, root)?
+        .serve_at(ROOT_PATH, ApplicationInterface { id: 0 })?
+        .build()
+        .await?;
+    state.lock().bus_name = connection
+        .unique_name()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    let root = state.lock().object_ref(Node::Application);
+    let reply = connection
+        .call_method(
+            Some(REGISTRY_NAME),
+            ROOT_PATH,
+            Some("org.a11y.atspi.Socket"),
+            "Embed",
+            &(root,),
+        )
+        .await?;
+    let parent: ObjectRef = reply.body().deserialize()?;
+    state.lock().parent = Some(parent);
+
+    Ok(connection)
+}
+
+struct AccessibleNode {
+    node: Node,
+    state: Arc<Mutex<BridgeState>>,
+}
+
+#[interface(name = "org.a11y.atspi.Accessible")]
+impl AccessibleNode {
+    #[zbus(property)]
+    fn name(&self) -> String {
+        let state = self.state.lock();
+        match self.node {
+            Node::Application => std::env::current_exe()
+                .ok()
+                .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+                .unwrap_or_default(),
+            Node::Frame(id) => state
+                .windows
+                .get(&id)
+                .map(|window| window.title.clone())
+                .unwrap_or_default(),
+            Node::Text(id) => state
+                .text(id)
+                .map(|text| text.label.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    #[zbus(property)]
+    fn description(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn parent(&self) -> ObjectRef {
+        self.state.lock().parent(self.node)
+    }
+
+    #[zbus(property)]
+    fn child_count(&self) -> i32 {
+        self.state.lock().children(self.node).len() as i32
+    }
+
+    #[zbus(property)]
+    fn locale(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn accessible_id(&self) -> String {
+        String::new()
+    }
+
+    fn get_child_at_index(&self, index: i32) -> (ObjectRef,) {
+        let state = self.state.lock();
+        let child = usize::try_from(index)
+            .ok()
+            .and_then(|index| state.children(self.node).get(index).copied());
+        match child {
+            Some(child) => (state.object_ref(child),),
+            None => ((
+                String::new(),
+                ObjectPath::from_static_str_unchecked(NULL_PATH).into(),
+            ),),
+        }
+    }
+
+    fn get_children(&self) -> Vec<ObjectRef> {
+        let state = self.state.lock();
+        state
+            .children(self.node)
+            .into_iter()
+            .map(|child| state.object_ref(child))
+            .collect()
+    }
+
+    fn get_index_in_parent(&self) -> i32 {
+        let state = self.state.lock();
+        match self.node {
+            Node::Application => -1,
+            Node::Frame(id) => state
+                .windows
+                .keys()
+                .position(|window_id| *window_id == id)
+                .map_or(-1, |ix| ix as i32),
+            Node::Text(_) => 0,
+        }
+    }
+
+    fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+        Vec::new()
+    }
+
+    fn get_role(&self) -> u32 {
+        match self.node {
+            Node::Application => ROLE_APPLICATION,
+            Node::Frame(_) => ROLE_FRAME,
+            Node::Text(id) => match self.state.lock().text(id).map(|text| text.role) {
+                Some(AccessibleRole::TextField) => ROLE_ENTRY,
+                Some(AccessibleRole::TextArea) | None => ROLE_TEXT,
+            },
+        }
+    }
+
+    fn get_role_name(&self) -> String {
+        match self.get_role() {
+            ROLE_APPLICATION => "application",
+            ROLE_FRAME => "frame",
+            ROLE_ENTRY => "entry",
+            _ => "text",
+        }
+        .to_string()
+    }
+
+    fn get_localized_role_name(&self) -> String {
+        self.get_role_name()
+    }
+
+    fn get_state(&self) -> Vec<u32> {
+        let mut states = vec![STATE_ENABLED, STATE_SENSITIVE, STATE_SHOWING, STATE_VISIBLE];
+        if let Node::Text(id) = self.node {
+            if let Some(text) = self.state.lock().text(id) {
+                states.extend([STATE_EDITABLE, STATE_FOCUSABLE, STATE_FOCUSED]);
+                states.push(match text.role {
+                    AccessibleRole::TextArea => STATE_MULTI_LINE,
+                    AccessibleRole::TextField => STATE_SINGLE_LINE,
+                });
+            }
+        } else if let Node::Frame(id) = self.node {
+            if self.state.lock().text(id).is_some() {
+                states.push(STATE_ACTIVE);
+            }
+        }
+        let bits = states
+            .into_iter()
+            .fold(0u32, |bits, state| bits | (1 << state));
+        vec![bits, 0]
+    }
+
+    fn get_attributes(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn get_application(&self) -> (ObjectRef,) {
+        (self.state.lock().object_ref(Node::Application),)
+    }
+
+    fn get_interfaces(&self) -> Vec<String> {
+        let mut interfaces = vec!["org.a11y.atspi.Accessible".to_string()];
+        match self.node {
+            Node::Application => interfaces.push("org.a11y.atspi.Application".to_string()),
+            Node::Frame(_) => {}
+            Node::Text(_) => interfaces.push("org.a11y.atspi.Text".to_string()),
+        }
+        interfaces
+    }
+}
+
+struct ApplicationInterface {
+    id: i32,
+}
+
+#[interface(name = "org.a11y.atspi.Application")]
+impl ApplicationInterface {
+    #[zbus(property)]
+    fn toolkit_name(&self) -> String {
+        "GPUI".to_string()
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    #[zbus(property)]
+    fn atspi_version(&self) -> String {
+        "2.1".to_string()
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    #[zbus(property)]
+    fn set_id(&mut self, id: i32) {
+        self.id = id;
+    }
+}
+
+struct TextInterface {
+    id: u64,
+    state: Arc<Mutex<BridgeState>>,
+}
+
+impl TextInterface {
+    fn with_text<R: Default>(&self, f: impl FnOnce(&AccessibleText) -> R) -> R {
+        self.state.lock().text(self.id).map(f).unwrap_or_default()
+    }
+
+    fn with_chars<R: Default>(&self, f: impl FnOnce(&[char]) -> R) -> R {
+        self.with_text(|text| f(&text.value.chars().collect::<Vec<_>>()))
+    }
+}
+
+#[interface(name = "org.a11y.atspi.Text")]
+impl TextInterface {
+    #[zbus(property)]
+    fn character_count(&self) -> i32 {
+        self.with_text(|text| text.value.chars().count() as i32)
+    }
+
+    #[zbus(property)]
+    fn caret_offset(&self) -> i32 {
+        self.with_text(|text| selected_char_range(text).1 as i32)
+    }
+
+    fn get_text(&self, start_offset: i32, end_offset: i32) -> String {
+        self.with_chars(|chars| {
+            let start = (start_offset.max(0) as usize).min(chars.len());
+            // An end offset of -1 means the end of the text.
+            let end = if end_offset < 0 {
+                chars.len()
+            } else {
+                (end_offset as usize).clamp(start, chars.len())
+            };
+            chars[start..end].iter().collect()
+        })
+    }
+
+    fn get_string_at_offset(&self, offset: i32, granularity: u32) -> (String, i32, i32) {
+        let granularity = Granularity::from_granularity(granularity);
+        self.with_chars(|chars| {
+            text_in_range(chars, granularity.range_at(chars, offset.max(0) as usize))
+        })
+    }
+
+    fn get_text_at_offset(&self, offset: i32, boundary_type: u32) -> (String, i32, i32) {
+        let granularity = Granularity::from_boundary_type(boundary_type);
+        self.with_chars(|chars| {
+            text_in_range(chars, granularity.range_at(chars, offset.max(0) as usize))
+        })
+    }
+
+    fn get_text_before_offset(&self, offset: i32, boundary_type: u32) -> (String, i32, i32) {
+        let granularity = Granularity::from_boundary_type(boundary_type);
+        self.with_chars(|chars| {
+            text_in_range(
+                chars,
+                granularity.range_before(chars, offset.max(0) as usize),
+            )
+        })
+    }
+
+    fn get_text_after_offset(&self, offset: i32, boundary_type: u32) -> (String, i32, i32) {
+        let granularity = Granularity::from_boundary_type(boundary_type);
+        self.with_chars(|chars| {
+            text_in_range(
+                chars,
+                granularity.range_after(chars, offset.max(0) as usize),
+            )
+        })
+    }
+
+    fn get_character_at_offset(&self, offset: i32) -> i32 {
+        self.with_chars(|chars| {
+            usize::try_from(offset)
+                .ok()
+                .and_then(|offset| chars.get(offset))
+                .map_or(0, |c| *c as i32)
+        })
+    }
+
+    fn get_n_selections(&self) -> i32 {
+        self.with_text(|text| {
+            let (start, end) = selected_char_range(text);
+            i32::from(start != end)
+        })
+    }
+
+    fn get_selection(&self, selection_num: i32) -> (i32, i32) {
+        self.with_text(|text| {
+            let (start, end) = selected_char_range(text);
+            if selection_num == 0 && start != end {
+                (start as i32, end as i32)
+            } else {
+                (0, 0)
+            }
+        })
+    }
+
+    fn set_caret_offset(&self, _offset: i32) -> bool {
+        false
+    }
+
+    fn set_selection(&self, _selection_num: i32, _start_offset: i32, _end_offset: i32) -> bool {
+        false
+    }
+
+    fn get_attributes(&self, _offset: i32) -> (HashMap<String, String>, i32, i32) {
+        let len = self.character_count();
+        (HashMap::new(), 0, len)
+    }
+
+    fn get_attribute_run(
+        &self,
+        _offset: i32,
+        _include_defaults: bool,
+    ) -> (HashMap<String, String>, i32, i32) {
+        let len = self.character_count();
+        (HashMap::new(), 0, len)
+    }
+
+    fn get_attribute_value(&self, _offset: i32, _attribute_name: String) -> String {
+        String::new()
+    }
+
+    fn get_default_attributes(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn get_default_attribute_set(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    // The element bounds aren't reported, so screen magnifiers can't follow the caret yet.
+    fn get_character_extents(&self, _offset: i32, _coord_type: u32) -> (i32, i32, i32, i32) {
+        (0, 0, 0, 0)
+    }
+
+    fn get_range_extents(
+        &self,
+        _start_offset: i32,
+        _end_offset: i32,
+        _coord_type: u32,
+    ) -> (i32, i32, i32, i32) {
+        (0, 0, 0, 0)
+    }
+
+    fn get_offset_at_point(&self, _x: i32, _y: i32, _coord_type: u32) -> i32 {
+        -1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_events() {
+        let text = |value: &str, cursor: usize| AccessibleText {
+            value: value.into(),
+            selected_range_utf16: cursor..cursor,
+            ..Default::default()
+        };
+        let mut state = BridgeState::default();
+        state.windows.insert(
+            1,
+            WindowState {
+                title: String::new(),
+                text: Some(text("hello world", 11)),
+            },
+        );
+        let events = |old_text: Option<&AccessibleText>| {
+            text_events(1, &state, old_text)
+                .into_iter()
+                .map(|(node, event)| {
+                    (
+                        node,
+                        event.member,
+                        event.detail,
+                        event.detail1,
+                        event.detail2,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            events(None),
+            [
+                (Node::Frame(1), "ChildrenChanged", "add", 0, 0),
+                (Node::Text(1), "StateChanged", "focused", 1, 0),
+            ]
+        );
+        assert_eq!(
+            events(Some(&text("hello", 5))),
+            [
+                (Node::Text(1), "TextChanged", "insert", 5, 6),
+                (Node::Text(1), "TextCaretMoved", "", 11, 0),
+            ]
+        );
+        assert_eq!(
+            events(Some(&text("hello there", 11))),
+            [
+                (Node::Text(1), "TextChanged", "delete", 6, 5),
+                (Node::Text(1), "TextChanged", "insert", 6, 5),
+            ]
+        );
+        assert!(events(Some(&text("hello world", 11))).is_empty());
+    }
+
+    #[test]
+    fn test_text_change() {
+        assert_eq!(
+            text_change("hello world", "hello there world"),
+            (6, String::new(), "there ".into())
+        );
+        assert_eq!(
+            text_change("let x = 1;", "let y = 1;"),
+            (4, "x".into(), "y".into())
+        );
+        assert_eq!(text_change("añb", "ab"), (1, "ñ".into(), String::new()));
+        assert_eq!(text_change("aaa", "aa"), (2, "a".into(), String::new()));
+        assert_eq!(
+            text_change("same", "same"),
+            (4, String::new(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_char_offset() {
+        // The emoji is two UTF-16 code units long.
+        let text = "a😀b";
+        assert_eq!(char_offset(text, 0), 0);
+        assert_eq!(char_offset(text, 1), 1);
+        assert_eq!(char_offset(text, 3), 2);
+        assert_eq!(char_offset(text, 4), 3);
+        assert_eq!(char_offset(text, 10), 3);
+    }
+
+    #[test]
+    fn test_granularity_ranges() {
+        let chars = "foo bar_baz\nqux".chars().collect::<Vec<_>>();
+
+        assert_eq!(Granularity::Char.range_at(&chars, 4), (4, 5));
+        assert_eq!(Granularity::Char.range_at(&chars, 15), (15, 15));
+        assert_eq!(Granularity::Char.range_before(&chars, 4), (3, 4));
+        assert_eq!(Granularity::Char.range_after(&chars, 4), (5, 6));
+
+        // Words include the separators that follow them.
+        assert_eq!(Granularity::Word.range_at(&chars, 0), (0, 4));
+        assert_eq!(Granularity::Word.range_at(&chars, 3), (0, 4));
+        assert_eq!(Granularity::Word.range_at(&chars, 5), (4, 12));
+        assert_eq!(Granularity::Word.range_before(&chars, 5), (0, 4));
+        assert_eq!(Granularity::Word.range_before(&chars, 2), (0, 0));
+        assert_eq!(Granularity::Word.range_after(&chars, 0), (4, 12));
+        assert_eq!(Granularity::Word.range_after(&chars, 13), (15, 15));
+
+        // Lines include their newline.
+        assert_eq!(Granularity::Line.range_at(&chars, 5), (0, 12));
+        assert_eq!(Granularity::Line.range_at(&chars, 13), (12, 15));
+        assert_eq!(Granularity::Line.range_before(&chars, 13), (0, 12));
+        assert_eq!(Granularity::Line.range_before(&chars, 5), (0, 0));
+        assert_eq!(Granularity::Line.range_after(&chars, 5), (12, 15));
+        assert_eq!(Granularity::Line.range_after(&chars, 13), (15, 15));
+
+        for granularity in [Granularity::Char, Granularity::Word, Granularity::Line] {
+            assert_eq!(granularity.range_at(&[], 0), (0, 0));
+            assert_eq!(granularity.range_before(&[], 0), (0, 0));
+            assert_eq!(granularity.range_after(&[], 0), (0, 0));
+        }
+    }
+}
@@ -1,3 +1,7 @@
 mod client;
+mod display;
+mod window;
 
 pub(crate) use client::*;
+pub(crate) use display::*;
+pub(crate) use window::*;
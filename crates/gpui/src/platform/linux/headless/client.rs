@@ -3,11 +3,13 @@ use std::ops::Deref;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+use calloop::timer::{TimeoutAction, Timer};
 use calloop::{EventLoop, LoopHandle};
 use collections::HashMap;
 
 use util::ResultExt;
 
+use crate::platform::linux::headless::{HeadlessDisplay, HeadlessWindow};
 use crate::platform::linux::LinuxClient;
 use crate::platform::{LinuxCommon, PlatformWindow};
 use crate::{
@@ -20,10 +22,15 @@ use calloop::{
     RegistrationToken,
 };
 
+/// How often headless windows are asked to draw a frame.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
 pub struct HeadlessClientState {
     pub(crate) loop_handle: LoopHandle<'static, HeadlessClient>,
     pub(crate) event_loop: Option<calloop::EventLoop<'static, HeadlessClient>>,
     pub(crate) common: LinuxCommon,
+    pub(crate) display: Rc<dyn PlatformDisplay>,
+    pub(crate) scale_factor: f32,
 }
 
 #[derive(Clone)]
@@ -43,10 +50,20 @@ impl HeadlessClient {
             }
         });
 
+        // Headless windows aren't shown on any monitor, so allow picking the scale factor
+        // they're rendered at, e.g. to take high resolution screenshots.
+        let scale_factor = std::env::var("GPUI_HEADLESS_SCALE_FACTOR")
+            .ok()
+            .and_then(|scale_factor| scale_factor.parse::<f32>().ok())
+            .filter(|scale_factor| *scale_factor > 0.)
+            .unwrap_or(1.);
+
         HeadlessClient(Rc::new(RefCell::new(HeadlessClientState {
             event_loop: Some(event_loop),
             loop_handle: handle,
             common,
+            display: Rc::new(HeadlessDisplay::new()),
+            scale_factor,
         })))
     }
 }
@@ -57,15 +74,16 @@ impl LinuxClient for HeadlessClient {
     }
 
     fn displays(&self) -> Vec<Rc<dyn PlatformDisplay>> {
-        vec![]
+        vec![self.0.borrow().display.clone()]
     }
 
     fn primary_display(&self) -> Option<Rc<dyn PlatformDisplay>> {
-        None
+        Some(self.0.borrow().display.clone())
     }
 
     fn display(&self, id: DisplayId) -> Option<Rc<dyn PlatformDisplay>> {
-        None
+        let display = self.0.borrow().display.clone();
+        (display.id() == id).then_some(display)
     }
 
     fn open_window(
@@ -73,7 +91,27 @@ impl LinuxClient for HeadlessClient {
         _handle: AnyWindowHandle,
         params: WindowParams,
     ) -> Box<dyn PlatformWindow> {
-        unimplemented!()
+        let state = self.0.borrow();
+        let window = HeadlessWindow::new(params, state.scale_factor, state.display.clone());
+
+        // There's no compositor to tell us when to draw, so draw at a steady rate until
+        // the window is closed.
+        let weak_window = window.downgrade();
+        state
+            .loop_handle
+            .insert_source(
+                Timer::immediate(),
+                move |_, _, _| match HeadlessWindow::upgrade(&weak_window) {
+                    Some(window) => {
+                        window.request_frame();
+                        TimeoutAction::ToDuration(FRAME_INTERVAL)
+                    }
+                    None => TimeoutAction::Drop,
+                },
+            )
+            .expect("Failed to initialize frame timer");
+
+        Box::new(window)
     }
 
     //todo(linux)
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::{point, size, Bounds, DevicePixels, DisplayId, PlatformDisplay};
+
+/// The size of the display that headless windows pretend to be shown on.
+const DISPLAY_SIZE: (i32, i32) = (1920, 1080);
+
+#[derive(Debug)]
+pub(crate) struct HeadlessDisplay {
+    bounds: Bounds<DevicePixels>,
+}
+
+impl HeadlessDisplay {
+    pub(crate) fn new() -> Self {
+        Self {
+            bounds: Bounds {
+                origin: point(DevicePixels(0), DevicePixels(0)),
+                size: size(DevicePixels(DISPLAY_SIZE.0), DevicePixels(DISPLAY_SIZE.1)),
+            },
+        }
+    }
+}
+
+impl PlatformDisplay for HeadlessDisplay {
+    fn id(&self) -> DisplayId {
+        DisplayId(0)
+    }
+
+    fn uuid(&self) -> Result<Uuid> {
+        Ok(Uuid::from_bytes([0; 16]))
+    }
+
+    fn bounds(&self) -> Bounds<DevicePixels> {
+        self.bounds
+    }
+}
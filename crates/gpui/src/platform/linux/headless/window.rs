@@ -0,0 +1,211 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    rc::{Rc, Weak},
+    sync::Arc,
+};
+
+use futures::channel::oneshot;
+use image::RgbaImage;
+use raw_window_handle as rwh;
+
+use crate::{
+    px, render_scene, size, Bounds, DevicePixels, DispatchEventResult, HeadlessAtlas, Modifiers,
+    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
+    Point, PromptLevel, Scene, Size, WindowAppearance, WindowBackgroundAppearance, WindowParams,
+};
+
+struct HeadlessWindowState {
+    bounds: Bounds<DevicePixels>,
+    scale_factor: f32,
+    display: Rc<dyn PlatformDisplay>,
+    sprite_atlas: Arc<HeadlessAtlas>,
+    input_handler: Option<PlatformInputHandler>,
+    fullscreen: bool,
+}
+
+/// A window that's never shown, whose frames are rasterized on the CPU on request.
+#[derive(Clone)]
+pub(crate) struct HeadlessWindow(Rc<HeadlessWindowInner>);
+
+pub(crate) struct HeadlessWindowInner {
+    state: RefCell<HeadlessWindowState>,
+    request_frame: RefCell<Option<Box<dyn FnMut()>>>,
+}
+
+impl HeadlessWindow {
+    pub(crate) fn new(
+        params: WindowParams,
+        scale_factor: f32,
+        display: Rc<dyn PlatformDisplay>,
+    ) -> Self {
+        Self(Rc::new(HeadlessWindowInner {
+            state: RefCell::new(HeadlessWindowState {
+                bounds: params.bounds,
+                scale_factor,
+                display,
+                sprite_atlas: Arc::new(HeadlessAtlas::new()),
+                input_handler: None,
+                fullscreen: false,
+            }),
+            request_frame: RefCell::new(None),
+        }))
+    }
+
+    /// Returns a reference that stops upgrading once the window has been closed.
+    pub(crate) fn downgrade(&self) -> Weak<HeadlessWindowInner> {
+        Rc::downgrade(&self.0)
+    }
+
+    pub(crate) fn upgrade(window: &Weak<HeadlessWindowInner>) -> Option<Self> {
+        window.upgrade().map(Self)
+    }
+
+    pub(crate) fn request_frame(&self) {
+        let callback = self.0.request_frame.borrow_mut().take();
+        if let Some(mut callback) = callback {
+            callback();
+            self.0.request_frame.borrow_mut().get_or_insert(callback);
+        }
+    }
+}
+
+impl rwh::HasWindowHandle for HeadlessWindow {
+    fn window_handle(&self) -> Result<rwh::WindowHandle<'_>, rwh::HandleError> {
+        Err(rwh::HandleError::Unavailable)
+    }
+}
+
+impl rwh::HasDisplayHandle for HeadlessWindow {
+    fn display_handle(&self) -> Result<rwh::DisplayHandle<'_>, rwh::HandleError> {
+        Err(rwh::HandleError::Unavailable)
+    }
+}
+
+impl PlatformWindow for HeadlessWindow {
+    fn bounds(&self) -> Bounds<DevicePixels> {
+        self.0.state.borrow().bounds
+    }
+
+    fn is_maximized(&self) -> bool {
+        false
+    }
+
+    fn is_minimized(&self) -> bool {
+        false
+    }
+
+    fn content_size(&self) -> Size<Pixels> {
+        let state = self.0.state.borrow();
+        size(
+            px(state.bounds.size.width.0 as f32 / state.scale_factor),
+            px(state.bounds.size.height.0 as f32 / state.scale_factor),
+        )
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.0.state.borrow().scale_factor
+    }
+
+    fn appearance(&self) -> WindowAppearance {
+        WindowAppearance::Light
+    }
+
+    fn display(&self) -> Rc<dyn PlatformDisplay> {
+        self.0.state.borrow().display.clone()
+    }
+
+    fn mouse_position(&self) -> Point<Pixels> {
+        Point::default()
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        Modifiers::default()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_input_handler(&mut self, input_handler: PlatformInputHandler) {
+        self.0.state.borrow_mut().input_handler = Some(input_handler);
+    }
+
+    fn take_input_handler(&mut self) -> Option<PlatformInputHandler> {
+        self.0.state.borrow_mut().input_handler.take()
+    }
+
+    fn prompt(
+        &self,
+        _level: PromptLevel,
+        _msg: &str,
+        _detail: Option<&str>,
+        _answers: &[&str],
+    ) -> Option<oneshot::Receiver<usize>> {
+        None
+    }
+
+    fn activate(&self) {}
+
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn set_background_appearance(&mut self, _background_appearance: WindowBackgroundAppearance) {}
+
+    fn set_edited(&mut self, _edited: bool) {}
+
+    fn show_character_palette(&self) {}
+
+    fn minimize(&self) {}
+
+    fn zoom(&self) {}
+
+    fn toggle_fullscreen(&self) {
+        let mut state = self.0.state.borrow_mut();
+        state.fullscreen = !state.fullscreen;
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.0.state.borrow().fullscreen
+    }
+
+    fn on_request_frame(&self, callback: Box<dyn FnMut()>) {
+        *self.0.request_frame.borrow_mut() = Some(callback);
+    }
+
+    // Headless windows don't receive any input.
+    fn on_input(&self, _callback: Box<dyn FnMut(PlatformInput) -> DispatchEventResult>) {}
+
+    fn on_active_status_change(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_resize(&self, _callback: Box<dyn FnMut(Size<Pixels>, f32)>) {}
+
+    fn on_fullscreen(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_moved(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_should_close(&self, _callback: Box<dyn FnMut() -> bool>) {}
+
+    fn on_close(&self, _callback: Box<dyn FnOnce()>) {}
+
+    fn on_appearance_changed(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn is_topmost_for_position(&self, _position: Point<Pixels>) -> bool {
+        true
+    }
+
+    // Frames are only rasterized when they're asked for with `render_to_image`.
+    fn draw(&self, _scene: &Scene) {}
+
+    fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas> {
+        self.0.state.borrow().sprite_atlas.clone()
+    }
+
+    fn render_to_image(&self, scene: &Scene) -> Option<RgbaImage> {
+        let state = self.0.state.borrow();
+        Some(render_scene(scene, state.bounds.size, &state.sprite_atlas))
+    }
+}
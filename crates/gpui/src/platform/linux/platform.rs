@@ -5,7 +5,7 @@ use std::cell::{self, RefCell};
 use std::env;
 use std::fs::File;
 use std::io::Read;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 use std::os::fd::{AsRawFd, FromRawFd};
 use std::panic::Location;
 use std::{
@@ -32,11 +32,11 @@ use xkbcommon::xkb::{self, Keycode, Keysym, State};
 
 use crate::platform::linux::wayland::WaylandClient;
 use crate::{
-    px, Action, AnyWindowHandle, BackgroundExecutor, ClipboardItem, CosmicTextSystem, CursorStyle,
-    DisplayId, ForegroundExecutor, Keymap, Keystroke, LinuxDispatcher, Menu, Modifiers,
-    PathPromptOptions, Pixels, Platform, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformTextSystem, PlatformWindow, Point, PromptLevel, Result, SemanticVersion, Size, Task,
-    WindowAppearance, WindowOptions, WindowParams,
+    px, AccessibilityBridge, Action, AnyWindowHandle, BackgroundExecutor, ClipboardItem,
+    CosmicTextSystem, CursorStyle, DisplayId, ForegroundExecutor, Keymap, Keystroke,
+    LinuxDispatcher, Menu, Modifiers, PathPromptOptions, Pixels, Platform, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformTextSystem, PlatformWindow, Point, PromptLevel,
+    Result, SemanticVersion, Size, Task, WindowAppearance, WindowOptions, WindowParams,
 };
 
 use super::x11::X11Client;
@@ -84,6 +84,7 @@ pub(crate) struct LinuxCommon {
     pub(crate) background_executor: BackgroundExecutor,
     pub(crate) foreground_executor: ForegroundExecutor,
     pub(crate) text_system: Arc<CosmicTextSystem>,
+    pub(crate) accessibility: AccessibilityBridge,
     pub(crate) callbacks: PlatformHandlers,
     pub(crate) signal: LoopSignal,
 }
@@ -96,8 +97,11 @@ impl LinuxCommon {
 
         let dispatcher = Arc::new(LinuxDispatcher::new(main_sender));
 
+        let background_executor = BackgroundExecutor::new(dispatcher.clone());
+
         let common = LinuxCommon {
-            background_executor: BackgroundExecutor::new(dispatcher.clone()),
+            accessibility: AccessibilityBridge::new(background_executor.clone()),
+            background_executor,
             foreground_executor: ForegroundExecutor::new(dispatcher.clone()),
             text_system,
             callbacks,
@@ -304,6 +308,21 @@ impl<P: LinuxClient + 'static> Platform for P {
         open::that(dir);
     }
 
+    fn show_notification(&self, title: &str, body: &str) {
+        let result = std::process::Command::new("notify-send")
+            .arg("--app-name=Zed")
+            .arg(title)
+            .arg(body)
+            .spawn();
+        match result {
+            // Reap the process once it exits, so it doesn't linger as a zombie.
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            }
+            Err(e) => log::error!("failed to show notification: {:?}", e),
+        }
+    }
+
     fn on_become_active(&self, callback: Box<dyn FnMut()>) {
         self.with_common(|common| {
             common.callbacks.become_active = Some(callback);
@@ -593,6 +612,123 @@ impl Keystroke {
     }
 }
 
+/// Returns the character to show while a dead key is waiting to be combined with the next key.
+pub(super) fn dead_key_text(keysym: Keysym) -> Option<&'static str> {
+    Some(match keysym {
+        Keysym::dead_grave => "`",
+        Keysym::dead_acute => "´",
+        Keysym::dead_circumflex => "^",
+        Keysym::dead_tilde => "~",
+        Keysym::dead_macron => "¯",
+        Keysym::dead_breve => "˘",
+        Keysym::dead_abovedot => "˙",
+        Keysym::dead_diaeresis => "¨",
+        Keysym::dead_abovering => "˚",
+        Keysym::dead_doubleacute => "˝",
+        Keysym::dead_caron => "ˇ",
+        Keysym::dead_cedilla => "¸",
+        Keysym::dead_ogonek => "˛",
+        _ => return None,
+    })
+}
+
+/// Converts a range of byte offsets into `text` to the UTF-16 offsets used by input handlers.
+/// Returns `None` if the range doesn't fall on character boundaries.
+pub(super) fn byte_range_to_utf16(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let start = text.get(..range.start)?.encode_utf16().count();
+    let end = start + text.get(range)?.encode_utf16().count();
+    Some(start..end)
+}
+
+/// What to do with a key press after feeding it to a [`KeyComposer`].
+pub(super) enum Composition {
+    /// The key isn't part of a compose sequence, and should be handled as usual.
+    None,
+    /// The key started or continued a compose sequence, whose text so far should be
+    /// shown as the composition in progress.
+    Preedit(String),
+    /// The key completed a compose sequence, which produced the given text.
+    Composed { text: String, key: Option<String> },
+    /// The key cancelled the compose sequence in progress, and should be ignored.
+    Cancelled,
+}
+
+/// Combines dead keys and compose key sequences, such as `´` followed by `e` into `é`,
+/// using the compose table of the user's locale.
+pub(super) struct KeyComposer {
+    state: Option<xkb::compose::State>,
+    preedit: String,
+}
+
+impl KeyComposer {
+    pub(super) fn new(context: &xkb::Context) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env::var_os(name).filter(|value| !value.is_empty()))
+            .unwrap_or_else(|| "C".into());
+        let state =
+            xkb::compose::Table::new_from_locale(context, &locale, xkb::compose::COMPILE_NO_FLAGS)
+                .ok()
+                .map(|table| xkb::compose::State::new(&table, xkb::compose::STATE_NO_FLAGS));
+        Self {
+            state,
+            preedit: String::new(),
+        }
+    }
+
+    pub(super) fn is_composing(&self) -> bool {
+        self.state.as_ref().map_or(false, |state| {
+            matches!(state.status(), xkb::Status::Composing)
+        })
+    }
+
+    pub(super) fn feed(&mut self, keysym: Keysym) -> Composition {
+        let Some(state) = self.state.as_mut() else {
+            return Composition::None;
+        };
+
+        state.feed(keysym);
+        match state.status() {
+            xkb::Status::Composing => {
+                if let Some(text) = dead_key_text(keysym) {
+                    self.preedit.push_str(text);
+                } else if let Some(character) = char::from_u32(xkb::keysym_to_utf32(keysym))
+                    .filter(|character| *character != '\0' && !character.is_control())
+                {
+                    self.preedit.push(character);
+                }
+                Composition::Preedit(self.preedit.clone())
+            }
+            xkb::Status::Composed => {
+                self.preedit.clear();
+                let text = state.utf8();
+                let key = state
+                    .keysym()
+                    .map(|keysym| xkb::keysym_get_name(keysym).to_lowercase());
+                state.reset();
+                match text {
+                    Some(text) => Composition::Composed { text, key },
+                    None => Composition::Cancelled,
+                }
+            }
+            xkb::Status::Cancelled => {
+                self.preedit.clear();
+                state.reset();
+                Composition::Cancelled
+            }
+            _ => Composition::None,
+        }
+    }
+
+    /// Abandon the compose sequence in progress, e.g. when the window loses focus.
+    pub(super) fn reset(&mut self) {
+        self.preedit.clear();
+        if let Some(state) = self.state.as_mut() {
+            state.reset();
+        }
+    }
+}
+
 impl Modifiers {
     pub(super) fn from_xkb(keymap_state: &State) -> Self {
         let shift = keymap_state.mod_name_is_active(xkb::MOD_NAME_SHIFT, xkb::STATE_MODS_EFFECTIVE);
@@ -636,4 +772,22 @@ mod tests {
             false
         );
     }
+
+    #[test]
+    fn test_dead_key_text() {
+        assert_eq!(dead_key_text(Keysym::dead_acute), Some("´"));
+        assert_eq!(dead_key_text(Keysym::dead_diaeresis), Some("¨"));
+        assert_eq!(dead_key_text(Keysym::a), None);
+    }
+
+    #[test]
+    fn test_byte_range_to_utf16() {
+        let text = "a😀é";
+        assert_eq!(byte_range_to_utf16(text, 0..1), Some(0..1));
+        assert_eq!(byte_range_to_utf16(text, 1..5), Some(1..3));
+        assert_eq!(byte_range_to_utf16(text, 5..7), Some(3..4));
+        assert_eq!(byte_range_to_utf16(text, 7..7), Some(4..4));
+        assert_eq!(byte_range_to_utf16(text, 2..5), None);
+        assert_eq!(byte_range_to_utf16(text, 0..8), None);
+    }
 }
@@ -1,5 +1,6 @@
 use core::hash;
 use std::cell::{RefCell, RefMut};
+use std::ops::Range;
 use std::os::fd::{AsRawFd, BorrowedFd};
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
@@ -37,6 +38,9 @@ use wayland_client::{
 use wayland_protocols::wp::fractional_scale::v1::client::{
     wp_fractional_scale_manager_v1, wp_fractional_scale_v1,
 };
+use wayland_protocols::wp::text_input::zv3::client::{
+    zwp_text_input_manager_v3, zwp_text_input_v3,
+};
 use wayland_protocols::wp::viewporter::client::{wp_viewport, wp_viewporter};
 use wayland_protocols::xdg::decoration::zv1::client::{
     zxdg_decoration_manager_v1, zxdg_toplevel_decoration_v1,
@@ -45,7 +49,7 @@ use wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_ba
 use xkbcommon::xkb::ffi::XKB_KEYMAP_FORMAT_TEXT_V1;
 use xkbcommon::xkb::{self, Keycode, KEYMAP_COMPILE_NO_FLAGS};
 
-use super::super::{read_fd, DOUBLE_CLICK_INTERVAL};
+use super::super::{byte_range_to_utf16, read_fd, Composition, KeyComposer, DOUBLE_CLICK_INTERVAL};
 use super::window::{WaylandWindowState, WaylandWindowStatePtr};
 use crate::platform::linux::is_within_click_distance;
 use crate::platform::linux::wayland::cursor::Cursor;
@@ -75,6 +79,7 @@ pub struct Globals {
     pub fractional_scale_manager:
         Option<wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1>,
     pub decoration_manager: Option<zxdg_decoration_manager_v1::ZxdgDecorationManagerV1>,
+    pub text_input_manager: Option<zwp_text_input_manager_v3::ZwpTextInputManagerV3>,
     pub executor: ForegroundExecutor,
 }
 
@@ -105,6 +110,7 @@ impl Globals {
             viewporter: globals.bind(&qh, 1..=1, ()).ok(),
             fractional_scale_manager: globals.bind(&qh, 1..=1, ()).ok(),
             decoration_manager: globals.bind(&qh, 1..=1, ()).ok(),
+            text_input_manager: globals.bind(&qh, 1..=1, ()).ok(),
             executor,
             qh,
         }
@@ -116,11 +122,14 @@ pub(crate) struct WaylandClientState {
     globals: Globals,
     wl_pointer: Option<wl_pointer::WlPointer>,
     data_device: Option<wl_data_device::WlDataDevice>,
+    text_input: Option<zwp_text_input_v3::ZwpTextInputV3>,
     // Surface to Window mapping
     windows: HashMap<ObjectId, WaylandWindowStatePtr>,
     // Output to scale mapping
     output_scales: HashMap<ObjectId, i32>,
     keymap_state: Option<xkb::State>,
+    composer: KeyComposer,
+    ime: ImeState,
     drag: DragState,
     click: ClickState,
     repeat: KeyRepeat,
@@ -151,6 +160,13 @@ pub struct DragState {
     position: Point<Pixels>,
 }
 
+/// Input method changes received since the last `done` event, which are applied together.
+#[derive(Default)]
+pub struct ImeState {
+    preedit: Option<(String, Option<Range<usize>>)>,
+    commit: Option<String>,
+}
+
 pub struct ClickState {
     last_click: Instant,
     last_location: Point<Pixels>,
@@ -283,6 +299,11 @@ impl WaylandClient {
             .as_ref()
             .map(|data_device_manager| data_device_manager.get_data_device(&seat, &qh, ()));
 
+        let text_input = globals
+            .text_input_manager
+            .as_ref()
+            .map(|text_input_manager| text_input_manager.get_text_input(&seat, &qh, ()));
+
         let (primary, clipboard) = unsafe { create_clipboards_from_external(display) };
 
         let cursor = Cursor::new(&conn, &globals, 24);
@@ -292,10 +313,13 @@ impl WaylandClient {
             globals,
             wl_pointer: None,
             data_device,
+            text_input,
             output_scales: outputs,
             windows: HashMap::default(),
             common,
             keymap_state: None,
+            composer: KeyComposer::new(&xkb::Context::new(xkb::CONTEXT_NO_FLAGS)),
+            ime: ImeState::default(),
             drag: DragState {
                 data_offer: None,
                 window: None,
@@ -364,9 +388,18 @@ impl LinuxClient for WaylandClient {
     ) -> Box<dyn PlatformWindow> {
         let mut state = self.0.borrow_mut();
 
+        let title = params
+            .titlebar
+            .as_ref()
+            .and_then(|titlebar| titlebar.title.as_ref());
+        let accessibility = state
+            .common
+            .accessibility
+            .open_window(title.map_or("", AsRef::as_ref));
         let (window, surface_id) = WaylandWindow::new(
             state.globals.clone(),
             WaylandClientStatePtr(Rc::downgrade(&self.0)),
+            accessibility,
             params,
         );
         state.windows.insert(surface_id, window.0.clone());
@@ -524,6 +557,7 @@ delegate_noop!(WaylandClientStatePtr: ignore wp_fractional_scale_manager_v1::WpF
 delegate_noop!(WaylandClientStatePtr: ignore zxdg_decoration_manager_v1::ZxdgDecorationManagerV1);
 delegate_noop!(WaylandClientStatePtr: ignore wp_viewporter::WpViewporter);
 delegate_noop!(WaylandClientStatePtr: ignore wp_viewport::WpViewport);
+delegate_noop!(WaylandClientStatePtr: ignore zwp_text_input_manager_v3::ZwpTextInputManagerV3);
 
 impl Dispatch<WlCallback, ObjectId> for WaylandClientStatePtr {
     fn event(
@@ -575,7 +609,7 @@ impl Dispatch<wl_surface::WlSurface, ()> for WaylandClientStatePtr {
         let scales = state.output_scales.clone();
         drop(state);
 
-        window.handle_surface_event(event, scales);
+        window.handle_surface_event(event, &scales);
     }
 }
 
@@ -599,6 +633,15 @@ impl Dispatch<wl_output::WlOutput, ()> for WaylandClientStatePtr {
             wl_output::Event::Scale { factor } => {
                 *output_scale = factor;
             }
+            wl_output::Event::Done => {
+                // The scale of an output the windows are on may have changed.
+                let scales = state.output_scales.clone();
+                let windows = state.windows.values().cloned().collect::<Vec<_>>();
+                drop(state);
+                for window in windows {
+                    window.update_scale_from_outputs(&scales);
+                }
+            }
             _ => {}
         }
     }
@@ -748,9 +791,14 @@ impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandClientStatePtr {
                 state.serial = serial;
                 let keyboard_focused_window = get_window(&mut state, &surface.id());
                 state.keyboard_focused_window = None;
+                let was_composing = state.composer.is_composing();
+                state.composer.reset();
 
                 if let Some(window) = keyboard_focused_window {
                     drop(state);
+                    if was_composing {
+                        window.handle_ime_preedit("", None);
+                    }
                     window.set_focused(false);
                 }
             }
@@ -799,8 +847,40 @@ impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandClientStatePtr {
 
                 match key_state {
                     wl_keyboard::KeyState::Pressed if !keysym.is_modifier_key() => {
+                        let mut keystroke =
+                            Keystroke::from_xkb(keymap_state, state.modifiers, keycode);
+                        let text_input = state.text_input.clone();
+
+                        // Keys that are part of a compose sequence aren't repeated.
+                        match state.composer.feed(keysym) {
+                            Composition::None => {}
+                            Composition::Preedit(text) => {
+                                drop(state);
+                                focused_window.handle_ime_preedit(&text, None);
+                                return;
+                            }
+                            Composition::Composed { text, key } => {
+                                drop(state);
+                                focused_window.handle_ime_preedit("", None);
+                                keystroke.ime_key = Some(text);
+                                if let Some(key) = key {
+                                    keystroke.key = key;
+                                }
+                                focused_window.handle_input(PlatformInput::KeyDown(KeyDownEvent {
+                                    keystroke,
+                                    is_held: false,
+                                }));
+                                return;
+                            }
+                            Composition::Cancelled => {
+                                drop(state);
+                                focused_window.handle_ime_preedit("", None);
+                                return;
+                            }
+                        }
+
                         let input = PlatformInput::KeyDown(KeyDownEvent {
-                            keystroke: Keystroke::from_xkb(keymap_state, state.modifiers, keycode),
+                            keystroke,
                             is_held: false, // todo(linux)
                         });
 
@@ -837,6 +917,9 @@ impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandClientStatePtr {
 
                         drop(state);
                         focused_window.handle_input(input);
+                        if let Some(text_input) = text_input {
+                            update_ime_position(&text_input, &focused_window);
+                        }
                     }
                     wl_keyboard::KeyState::Released if !keysym.is_modifier_key() => {
                         let input = PlatformInput::KeyUp(KeyUpEvent {
@@ -856,6 +939,89 @@ impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandClientStatePtr {
     }
 }
 
+impl Dispatch<zwp_text_input_v3::ZwpTextInputV3, ()> for WaylandClientStatePtr {
+    fn event(
+        this: &mut Self,
+        text_input: &zwp_text_input_v3::ZwpTextInputV3,
+        event: zwp_text_input_v3::Event,
+        data: &(),
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let mut client = this.get_client();
+        let mut state = client.borrow_mut();
+        match event {
+            zwp_text_input_v3::Event::Enter { .. } => {
+                text_input.enable();
+                text_input.set_content_type(
+                    zwp_text_input_v3::ContentHint::None,
+                    zwp_text_input_v3::ContentPurpose::Normal,
+                );
+                text_input.commit();
+
+                if let Some(window) = state.keyboard_focused_window.clone() {
+                    drop(state);
+                    update_ime_position(text_input, &window);
+                }
+            }
+            zwp_text_input_v3::Event::Leave { .. } => {
+                state.ime = ImeState::default();
+                text_input.disable();
+                text_input.commit();
+            }
+            zwp_text_input_v3::Event::PreeditString {
+                text,
+                cursor_begin,
+                cursor_end,
+            } => {
+                let text = text.unwrap_or_default();
+                // Negative offsets mean the cursor should be hidden.
+                let selected_range = (cursor_begin >= 0 && cursor_end >= 0)
+                    .then(|| {
+                        let range = cursor_begin as usize..cursor_end as usize;
+                        byte_range_to_utf16(&text, range)
+                    })
+                    .flatten();
+                state.ime.preedit = Some((text, selected_range));
+            }
+            zwp_text_input_v3::Event::CommitString { text } => {
+                state.ime.commit = text;
+            }
+            zwp_text_input_v3::Event::Done { .. } => {
+                let ime = std::mem::take(&mut state.ime);
+                let Some(window) = state.keyboard_focused_window.clone() else {
+                    return;
+                };
+                drop(state);
+
+                if let Some(text) = ime.commit {
+                    window.handle_ime_commit(&text);
+                }
+                let (preedit, selected_range) = ime.preedit.unwrap_or_default();
+                window.handle_ime_preedit(&preedit, selected_range);
+                update_ime_position(text_input, &window);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Tells the input method where the cursor is, so it can show its candidates next to it.
+fn update_ime_position(
+    text_input: &zwp_text_input_v3::ZwpTextInputV3,
+    window: &WaylandWindowStatePtr,
+) {
+    if let Some(bounds) = window.get_ime_area() {
+        text_input.set_cursor_rectangle(
+            bounds.origin.x.0 as i32,
+            bounds.origin.y.0 as i32,
+            bounds.size.width.0 as i32,
+            bounds.size.height.0 as i32,
+        );
+        text_input.commit();
+    }
+}
+
 fn linux_button_to_gpui(button: u32) -> Option<MouseButton> {
     // These values are coming from <linux/input-event-codes.h>.
     const BTN_LEFT: u32 = 0x110;
@@ -2,6 +2,7 @@ use std::any::Any;
 use std::cell::{Ref, RefCell, RefMut};
 use std::ffi::c_void;
 use std::num::NonZeroU32;
+use std::ops::Range;
 use std::rc::{Rc, Weak};
 use std::sync::Arc;
 
@@ -26,9 +27,9 @@ use crate::platform::linux::wayland::display::WaylandDisplay;
 use crate::platform::{PlatformAtlas, PlatformInputHandler, PlatformWindow};
 use crate::scene::Scene;
 use crate::{
-    px, size, Bounds, DevicePixels, Globals, Modifiers, Pixels, PlatformDisplay, PlatformInput,
-    Point, PromptLevel, Size, WaylandClientState, WaylandClientStatePtr, WindowAppearance,
-    WindowBackgroundAppearance, WindowParams,
+    px, size, AccessibleText, AccessibleWindow, Bounds, DevicePixels, Globals, Modifiers, Pixels,
+    PlatformDisplay, PlatformInput, Point, PromptLevel, Size, WaylandClientState,
+    WaylandClientStatePtr, WindowAppearance, WindowBackgroundAppearance, WindowParams,
 };
 
 #[derive(Default)]
@@ -71,6 +72,7 @@ pub struct WaylandWindowState {
     decoration: Option<zxdg_toplevel_decoration_v1::ZxdgToplevelDecorationV1>,
     toplevel: xdg_toplevel::XdgToplevel,
     viewport: Option<wp_viewport::WpViewport>,
+    fractional_scale: Option<wp_fractional_scale_v1::WpFractionalScaleV1>,
     outputs: HashSet<ObjectId>,
     globals: Globals,
     renderer: BladeRenderer,
@@ -82,6 +84,7 @@ pub struct WaylandWindowState {
     maximized: bool,
     client: WaylandClientStatePtr,
     callbacks: Callbacks,
+    accessibility: AccessibleWindow,
 }
 
 #[derive(Clone)]
@@ -98,8 +101,10 @@ impl WaylandWindowState {
         toplevel: xdg_toplevel::XdgToplevel,
         decoration: Option<zxdg_toplevel_decoration_v1::ZxdgToplevelDecorationV1>,
         viewport: Option<wp_viewport::WpViewport>,
+        fractional_scale: Option<wp_fractional_scale_v1::WpFractionalScaleV1>,
         client: WaylandClientStatePtr,
         globals: Globals,
+        accessibility: AccessibleWindow,
         options: WindowParams,
     ) -> Self {
         let bounds = options.bounds.map(|p| p.0 as u32);
@@ -138,6 +143,7 @@ impl WaylandWindowState {
             decoration,
             toplevel,
             viewport,
+            fractional_scale,
             globals,
 
             outputs: HashSet::default(),
@@ -151,6 +157,7 @@ impl WaylandWindowState {
             maximized: false,
             callbacks: Callbacks::default(),
             client,
+            accessibility,
         }
     }
 }
@@ -171,6 +178,9 @@ impl Drop for WaylandWindow {
         if let Some(viewport) = &state.viewport {
             viewport.destroy();
         }
+        if let Some(fractional_scale) = &state.fractional_scale {
+            fractional_scale.destroy();
+        }
         state.xdg_surface.destroy();
         state.surface.destroy();
 
@@ -199,6 +209,7 @@ impl WaylandWindow {
     pub fn new(
         globals: Globals,
         client: WaylandClientStatePtr,
+        accessibility: AccessibleWindow,
         params: WindowParams,
     ) -> (Self, ObjectId) {
         let surface = globals.compositor.create_surface(&globals.qh, ());
@@ -207,10 +218,6 @@ impl WaylandWindow {
             .get_xdg_surface(&surface, &globals.qh, surface.id());
         let toplevel = xdg_surface.get_toplevel(&globals.qh, surface.id());
 
-        if let Some(fractional_scale_manager) = globals.fractional_scale_manager.as_ref() {
-            fractional_scale_manager.get_fractional_scale(&surface, &globals.qh, surface.id());
-        }
-
         // Attempt to set up window decorations based on the requested configuration
         let decoration = globals
             .decoration_manager
@@ -230,6 +237,16 @@ impl WaylandWindow {
             .as_ref()
             .map(|viewporter| viewporter.get_viewport(&surface, &globals.qh, ()));
 
+        // Fractionally scaled buffers are mapped onto the surface with the viewport, so the
+        // compositor's preferred fractional scale can only be used when we have one.
+        let fractional_scale = globals
+            .fractional_scale_manager
+            .as_ref()
+            .filter(|_| viewport.is_some())
+            .map(|fractional_scale_manager| {
+                fractional_scale_manager.get_fractional_scale(&surface, &globals.qh, surface.id())
+            });
+
         surface.frame(&globals.qh, surface.id());
 
         let this = Self(WaylandWindowStatePtr {
@@ -239,8 +256,10 @@ impl WaylandWindow {
                 toplevel,
                 decoration,
                 viewport,
+                fractional_scale,
                 client,
                 globals,
+                accessibility,
                 params,
             ))),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
@@ -350,55 +369,25 @@ impl WaylandWindowStatePtr {
     pub fn handle_surface_event(
         &self,
         event: wl_surface::Event,
-        output_scales: HashMap<ObjectId, i32>,
+        output_scales: &HashMap<ObjectId, i32>,
     ) {
         let mut state = self.state.borrow_mut();
-
-        // We use `WpFractionalScale` instead to set the scale if it's available
-        if state.globals.fractional_scale_manager.is_some() {
-            return;
-        }
-
         match event {
             wl_surface::Event::Enter { output } => {
-                // We use `PreferredBufferScale` instead to set the scale if it's available
-                if state.surface.version() >= wl_surface::EVT_PREFERRED_BUFFER_SCALE_SINCE {
-                    return;
-                }
-
                 state.outputs.insert(output.id());
-
-                let mut scale = 1;
-                for output in state.outputs.iter() {
-                    if let Some(s) = output_scales.get(output) {
-                        scale = scale.max(*s)
-                    }
-                }
-
-                state.surface.set_buffer_scale(scale);
                 drop(state);
-                self.rescale(scale as f32);
+                self.update_scale_from_outputs(output_scales);
             }
             wl_surface::Event::Leave { output } => {
-                // We use `PreferredBufferScale` instead to set the scale if it's available
-                if state.surface.version() >= wl_surface::EVT_PREFERRED_BUFFER_SCALE_SINCE {
-                    return;
-                }
-
                 state.outputs.remove(&output.id());
-
-                let mut scale = 1;
-                for output in state.outputs.iter() {
-                    if let Some(s) = output_scales.get(output) {
-                        scale = scale.max(*s)
-                    }
-                }
-
-                state.surface.set_buffer_scale(scale);
                 drop(state);
-                self.rescale(scale as f32);
+                self.update_scale_from_outputs(output_scales);
             }
             wl_surface::Event::PreferredBufferScale { factor } => {
+                // We use `WpFractionalScale` instead to set the scale if it's available
+                if state.fractional_scale.is_some() {
+                    return;
+                }
                 state.surface.set_buffer_scale(factor);
                 drop(state);
                 self.rescale(factor as f32);
@@ -407,6 +396,29 @@ impl WaylandWindowStatePtr {
         }
     }
 
+    /// Use the largest scale of the outputs the window is on, for compositors that tell us
+    /// neither the fractional nor the preferred buffer scale. Called when the window enters
+    /// or leaves an output, and when the scale of an output changes.
+    pub fn update_scale_from_outputs(&self, output_scales: &HashMap<ObjectId, i32>) {
+        let state = self.state.borrow();
+        if state.fractional_scale.is_some()
+            || state.surface.version() >= wl_surface::EVT_PREFERRED_BUFFER_SCALE_SINCE
+        {
+            return;
+        }
+
+        let scale = state
+            .outputs
+            .iter()
+            .filter_map(|output| output_scales.get(output))
+            .copied()
+            .max()
+            .unwrap_or(1);
+        state.surface.set_buffer_scale(scale);
+        drop(state);
+        self.rescale(scale as f32);
+    }
+
     pub fn set_size_and_scale(
         &self,
         width: Option<NonZeroU32>,
@@ -433,9 +445,11 @@ impl WaylandWindowStatePtr {
             let width = state.bounds.size.width;
             let height = state.bounds.size.height;
             let scale = state.scale;
+            // Round rather than truncate, so that fractionally scaled buffers cover the whole
+            // surface instead of being stretched by the viewport and looking blurry.
             state.renderer.update_drawable_size(size(
-                width as f64 * scale as f64,
-                height as f64 * scale as f64,
+                (width as f64 * scale as f64).round(),
+                (height as f64 * scale as f64).round(),
             ));
             (width, height, scale)
         };
@@ -513,6 +527,36 @@ impl WaylandWindowStatePtr {
         }
     }
 
+    pub fn handle_ime_preedit(&self, text: &str, selected_range_utf16: Option<Range<usize>>) {
+        let mut state = self.state.borrow_mut();
+        if let Some(mut input_handler) = state.input_handler.take() {
+            drop(state);
+            input_handler.set_ime_preedit(text, selected_range_utf16);
+            self.state.borrow_mut().input_handler = Some(input_handler);
+        }
+    }
+
+    pub fn handle_ime_commit(&self, text: &str) {
+        let mut state = self.state.borrow_mut();
+        if let Some(mut input_handler) = state.input_handler.take() {
+            drop(state);
+            input_handler.replace_text_in_range(None, text);
+            self.state.borrow_mut().input_handler = Some(input_handler);
+        }
+    }
+
+    /// Returns the bounds of the selected text, which input methods show their candidates next to.
+    pub fn get_ime_area(&self) -> Option<Bounds<Pixels>> {
+        let mut state = self.state.borrow_mut();
+        let mut input_handler = state.input_handler.take()?;
+        drop(state);
+        let bounds = input_handler
+            .selected_text_range()
+            .and_then(|range| input_handler.bounds_for_range(range));
+        self.state.borrow_mut().input_handler = Some(input_handler);
+        bounds
+    }
+
     pub fn set_focused(&self, focus: bool) {
         if let Some(ref mut fun) = self.callbacks.borrow_mut().active_status_change {
             fun(focus);
@@ -610,7 +654,9 @@ impl PlatformWindow for WaylandWindow {
     }
 
     fn set_title(&mut self, title: &str) {
-        self.borrow_mut().toplevel.set_title(title.to_string());
+        let state = self.borrow();
+        state.toplevel.set_title(title.to_string());
+        state.accessibility.set_title(title);
     }
 
     fn set_background_appearance(&mut self, _background_appearance: WindowBackgroundAppearance) {
@@ -701,6 +747,14 @@ impl PlatformWindow for WaylandWindow {
         let state = self.borrow();
         state.renderer.sprite_atlas().clone()
     }
+
+    fn set_accessible_text(&self, text: Option<AccessibleText>) {
+        self.borrow().accessibility.set_text(text);
+    }
+
+    fn announce(&self, message: &str) {
+        self.borrow().accessibility.announce(message);
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
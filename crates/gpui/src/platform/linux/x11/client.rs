@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::env;
 use std::ops::Deref;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
@@ -19,7 +20,7 @@ use x11rb::xcb_ffi::XCBConnection;
 use xkbc::x11::ffi::{XKB_X11_MIN_MAJOR_XKB_VERSION, XKB_X11_MIN_MINOR_XKB_VERSION};
 use xkbcommon::xkb as xkbc;
 
-use crate::platform::linux::LinuxClient;
+use crate::platform::linux::{Composition, KeyComposer, LinuxClient};
 use crate::platform::{LinuxCommon, PlatformWindow};
 use crate::{
     px, AnyWindowHandle, Bounds, CursorStyle, DisplayId, Modifiers, ModifiersChangedEvent, Pixels,
@@ -62,12 +63,118 @@ pub struct X11ClientState {
     pub(crate) windows: HashMap<xproto::Window, WindowRef>,
     pub(crate) focused_window: Option<xproto::Window>,
     pub(crate) xkb: xkbc::State,
+    pub(crate) composer: KeyComposer,
+    pub(crate) scale_factor: X11ScaleFactor,
 
     pub(crate) common: LinuxCommon,
     pub(crate) clipboard: X11ClipboardContext<Clipboard>,
     pub(crate) primary: X11ClipboardContext<Primary>,
 }
 
+/// How the scale factor of windows is chosen, which can be overridden with the
+/// `GPUI_X11_SCALE_FACTOR` environment variable.
+pub(crate) enum X11ScaleFactor {
+    /// Every window uses the same scale factor, which is either given by the environment
+    /// variable or derived from the `Xft.dpi` resource.
+    Fixed(f32),
+    /// Each window uses the scale factor of the monitor it's on, derived from the physical
+    /// size RandR reports for the monitor. Enabled with `GPUI_X11_SCALE_FACTOR=randr`.
+    PerMonitor,
+}
+
+impl X11ScaleFactor {
+    fn new(xcb_connection: &XCBConnection, root: xproto::Window) -> Self {
+        match env::var("GPUI_X11_SCALE_FACTOR").ok().as_deref() {
+            Some("randr") => return X11ScaleFactor::PerMonitor,
+            Some(value) => match value.parse::<f32>() {
+                Ok(scale_factor) if scale_factor > 0. => {
+                    return X11ScaleFactor::Fixed(scale_factor);
+                }
+                _ => log::warn!("invalid GPUI_X11_SCALE_FACTOR {value:?}"),
+            },
+            None => {}
+        }
+
+        let dpi = xcb_connection
+            .get_property(
+                false,
+                root,
+                xproto::AtomEnum::RESOURCE_MANAGER,
+                xproto::AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| parse_xft_dpi(&String::from_utf8_lossy(&reply.value)));
+        X11ScaleFactor::Fixed(dpi.map_or(1., |dpi| dpi / DEFAULT_DPI))
+    }
+
+    /// Returns the scale factor for a window with the given bounds, in device pixels.
+    fn for_bounds(
+        &self,
+        xcb_connection: &XCBConnection,
+        root: xproto::Window,
+        bounds: Bounds<i32>,
+    ) -> f32 {
+        match self {
+            X11ScaleFactor::Fixed(scale_factor) => *scale_factor,
+            X11ScaleFactor::PerMonitor => {
+                monitor_scale_factor(xcb_connection, root, bounds).unwrap_or(1.)
+            }
+        }
+    }
+}
+
+/// The DPI at which the scale factor is 1.
+const DEFAULT_DPI: f32 = 96.;
+
+fn parse_xft_dpi(resources: &str) -> Option<f32> {
+    resources
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim() == "Xft.dpi" {
+                value.trim().parse::<f32>().ok()
+            } else {
+                None
+            }
+        })
+        .filter(|dpi| *dpi > 0.)
+}
+
+/// Returns the scale factor of the monitor containing the center of the given bounds, rounded
+/// to a quarter so that slightly inaccurate physical sizes don't produce odd scale factors.
+fn monitor_scale_factor(
+    xcb_connection: &XCBConnection,
+    root: xproto::Window,
+    bounds: Bounds<i32>,
+) -> Option<f32> {
+    let center_x = bounds.origin.x + bounds.size.width / 2;
+    let center_y = bounds.origin.y + bounds.size.height / 2;
+    let monitors = xcb_connection
+        .randr_get_monitors(root, true)
+        .ok()?
+        .reply()
+        .ok()?
+        .monitors;
+    let monitor = monitors.iter().find(|monitor| {
+        let x = monitor.x as i32;
+        let y = monitor.y as i32;
+        (x..x + monitor.width as i32).contains(&center_x)
+            && (y..y + monitor.height as i32).contains(&center_y)
+    })?;
+    if monitor.width_in_millimeters == 0 {
+        return None;
+    }
+    let dpi = monitor.width as f32 * 25.4 / monitor.width_in_millimeters as f32;
+    Some(round_scale_factor(dpi / DEFAULT_DPI))
+}
+
+fn round_scale_factor(scale_factor: f32) -> f32 {
+    ((scale_factor * 4.).round() / 4.).clamp(1., 4.)
+}
+
 #[derive(Clone)]
 pub(crate) struct X11Client(Rc<RefCell<X11ClientState>>);
 
@@ -113,8 +220,8 @@ impl X11Client {
             .unwrap();
         assert!(xkb.supported);
 
+        let xkb_context = xkbc::Context::new(xkbc::CONTEXT_NO_FLAGS);
         let xkb_state = {
-            let xkb_context = xkbc::Context::new(xkbc::CONTEXT_NO_FLAGS);
             let xkb_device_id = xkbc::x11::get_core_keyboard_device_id(&xcb_connection);
             let xkb_keymap = xkbc::x11::keymap_new_from_device(
                 &xkb_context,
@@ -124,6 +231,11 @@ impl X11Client {
             );
             xkbc::x11::state_new_from_device(&xkb_keymap, &xcb_connection, xkb_device_id)
         };
+        let composer = KeyComposer::new(&xkb_context);
+        let scale_factor = X11ScaleFactor::new(
+            &xcb_connection,
+            xcb_connection.setup().roots[x_root_index].root,
+        );
 
         let clipboard = X11ClipboardContext::<Clipboard>::new().unwrap();
         let primary = X11ClipboardContext::<Primary>::new().unwrap();
@@ -166,11 +278,21 @@ impl X11Client {
             windows: HashMap::default(),
             focused_window: None,
             xkb: xkb_state,
+            composer,
+            scale_factor,
             clipboard,
             primary,
         })))
     }
 
+    fn scale_factor_for(&self, bounds: Bounds<i32>) -> f32 {
+        let state = self.0.borrow();
+        let root = state.xcb_connection.setup().roots[state.x_root_index].root;
+        state
+            .scale_factor
+            .for_bounds(&state.xcb_connection, root, bounds)
+    }
+
     fn get_window(&self, win: xproto::Window) -> Option<X11Window> {
         let state = self.0.borrow();
         state
@@ -209,7 +331,8 @@ impl X11Client {
                     },
                 };
                 let window = self.get_window(event.window)?;
-                window.configure(bounds);
+                let scale_factor = self.scale_factor_for(bounds);
+                window.configure(bounds, scale_factor);
             }
             Event::Expose(event) => {
                 let window = self.get_window(event.window)?;
@@ -223,7 +346,13 @@ impl X11Client {
             Event::FocusOut(event) => {
                 let window = self.get_window(event.event)?;
                 window.set_focused(false);
-                self.0.borrow_mut().focused_window = None;
+                let mut state = self.0.borrow_mut();
+                state.focused_window = None;
+                if state.composer.is_composing() {
+                    state.composer.reset();
+                    drop(state);
+                    window.handle_ime_preedit("");
+                }
             }
             Event::XkbStateNotify(event) => {
                 let mut state = self.0.borrow_mut();
@@ -249,18 +378,34 @@ impl X11Client {
                 let mut state = self.0.borrow_mut();
 
                 let modifiers = modifiers_from_state(event.state);
-                let keystroke = {
-                    let code = event.detail.into();
-                    let keystroke = crate::Keystroke::from_xkb(&state.xkb, modifiers, code);
-                    state.xkb.update_key(code, xkbc::KeyDirection::Down);
-                    let keysym = state.xkb.key_get_one_sym(code);
-                    if keysym.is_modifier_key() {
-                        return Some(());
-                    }
-                    keystroke
-                };
+                let code = event.detail.into();
+                let mut keystroke = crate::Keystroke::from_xkb(&state.xkb, modifiers, code);
+                state.xkb.update_key(code, xkbc::KeyDirection::Down);
+                let keysym = state.xkb.key_get_one_sym(code);
+                if keysym.is_modifier_key() {
+                    return Some(());
+                }
+                let composition = state.composer.feed(keysym);
 
                 drop(state);
+                match composition {
+                    Composition::None => {}
+                    Composition::Preedit(text) => {
+                        window.handle_ime_preedit(&text);
+                        return Some(());
+                    }
+                    Composition::Composed { text, key } => {
+                        window.handle_ime_preedit("");
+                        keystroke.ime_key = Some(text);
+                        if let Some(key) = key {
+                            keystroke.key = key;
+                        }
+                    }
+                    Composition::Cancelled => {
+                        window.handle_ime_preedit("");
+                        return Some(());
+                    }
+                }
                 window.handle_input(PlatformInput::KeyDown(crate::KeyDownEvent {
                     keystroke,
                     is_held: false,
@@ -289,8 +434,7 @@ impl X11Client {
                 let mut state = self.0.borrow_mut();
 
                 let modifiers = modifiers_from_state(event.state);
-                let position =
-                    Point::new((event.event_x as f32).into(), (event.event_y as f32).into());
+                let position = logical_position(&window, event.event_x, event.event_y);
                 if let Some(button) = button_of_key(event.detail) {
                     let click_elapsed = state.last_click.elapsed();
 
@@ -334,8 +478,7 @@ impl X11Client {
                 let window = self.get_window(event.event)?;
                 let state = self.0.borrow();
                 let modifiers = modifiers_from_state(event.state);
-                let position =
-                    Point::new((event.event_x as f32).into(), (event.event_y as f32).into());
+                let position = logical_position(&window, event.event_x, event.event_y);
                 if let Some(button) = button_of_key(event.detail) {
                     let click_count = state.current_count;
                     drop(state);
@@ -350,8 +493,7 @@ impl X11Client {
             Event::MotionNotify(event) => {
                 let window = self.get_window(event.event)?;
                 let pressed_button = super::button_from_state(event.state);
-                let position =
-                    Point::new((event.event_x as f32).into(), (event.event_y as f32).into());
+                let position = logical_position(&window, event.event_x, event.event_y);
                 let modifiers = modifiers_from_state(event.state);
                 window.handle_input(PlatformInput::MouseMove(crate::MouseMoveEvent {
                     pressed_button,
@@ -362,8 +504,7 @@ impl X11Client {
             Event::LeaveNotify(event) => {
                 let window = self.get_window(event.event)?;
                 let pressed_button = super::button_from_state(event.state);
-                let position =
-                    Point::new((event.event_x as f32).into(), (event.event_y as f32).into());
+                let position = logical_position(&window, event.event_x, event.event_y);
                 let modifiers = modifiers_from_state(event.state);
                 window.handle_input(PlatformInput::MouseExited(crate::MouseExitEvent {
                     pressed_button,
@@ -420,15 +561,26 @@ impl LinuxClient for X11Client {
         _handle: AnyWindowHandle,
         params: WindowParams,
     ) -> Box<dyn PlatformWindow> {
+        let scale_factor = self.scale_factor_for(params.bounds.map(|pixels| pixels.0));
         let mut state = self.0.borrow_mut();
         let x_window = state.xcb_connection.generate_id().unwrap();
 
+        let title = params
+            .titlebar
+            .as_ref()
+            .and_then(|titlebar| titlebar.title.as_ref());
+        let accessibility = state
+            .common
+            .accessibility
+            .open_window(title.map_or("", AsRef::as_ref));
         let window = X11Window::new(
             params,
             &state.xcb_connection,
             state.x_root_index,
             x_window,
             &state.atoms,
+            scale_factor,
+            accessibility,
         );
 
         let screen_resources = state
@@ -547,6 +699,12 @@ impl LinuxClient for X11Client {
     }
 }
 
+/// Converts a position in device pixels relative to the window into logical pixels.
+fn logical_position(window: &X11Window, x: i16, y: i16) -> Point<Pixels> {
+    let scale_factor = window.scale_factor();
+    Point::new(px(x as f32 / scale_factor), px(y as f32 / scale_factor))
+}
+
 // Adatpted from:
 // https://docs.rs/winit/0.29.11/src/winit/platform_impl/linux/x11/monitor.rs.html#103-111
 pub fn mode_refresh_rate(mode: &randr::ModeInfo) -> Duration {
@@ -555,3 +713,25 @@ pub fn mode_refresh_rate(mode: &randr::ModeInfo) -> Duration {
     log::info!("Refreshing at {} micros", micros);
     Duration::from_micros(micros)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xft_dpi() {
+        assert_eq!(
+            parse_xft_dpi("Xft.antialias:\t1\nXft.dpi:\t144\n"),
+            Some(144.)
+        );
+        assert_eq!(parse_xft_dpi("Xft.antialias:\t1\n"), None);
+        assert_eq!(parse_xft_dpi("Xft.dpi:\tlarge\n"), None);
+    }
+
+    #[test]
+    fn test_round_scale_factor() {
+        assert_eq!(round_scale_factor(1.04), 1.);
+        assert_eq!(round_scale_factor(1.46), 1.5);
+        assert_eq!(round_scale_factor(0.8), 1.);
+    }
+}
@@ -2,10 +2,10 @@
 #![allow(unused)]
 
 use crate::{
-    platform::blade::BladeRenderer, size, Bounds, DevicePixels, Modifiers, Pixels, PlatformAtlas,
-    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel,
-    Scene, Size, WindowAppearance, WindowBackgroundAppearance, WindowOptions, WindowParams,
-    X11Client, X11ClientState,
+    platform::blade::BladeRenderer, px, size, AccessibleText, AccessibleWindow, Bounds,
+    DevicePixels, Modifiers, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
+    PlatformInputHandler, PlatformWindow, Point, PromptLevel, Scene, Size, WindowAppearance,
+    WindowBackgroundAppearance, WindowOptions, WindowParams, X11Client, X11ClientState,
 };
 use blade_graphics as gpu;
 use parking_lot::Mutex;
@@ -83,6 +83,7 @@ pub(crate) struct X11WindowState {
     scale_factor: f32,
     renderer: BladeRenderer,
     display: Rc<dyn PlatformDisplay>,
+    accessibility: AccessibleWindow,
 
     input_handler: Option<PlatformInputHandler>,
 }
@@ -140,6 +141,8 @@ impl X11WindowState {
         x_main_screen_index: usize,
         x_window: xproto::Window,
         atoms: &XcbAtoms,
+        scale_factor: f32,
+        accessibility: AccessibleWindow,
     ) -> Self {
         let x_screen_index = params
             .display_id
@@ -238,9 +241,10 @@ impl X11WindowState {
             display: Rc::new(X11Display::new(xcb_connection, x_screen_index).unwrap()),
             raw,
             bounds: params.bounds.map(|v| v.0),
-            scale_factor: 1.0,
+            scale_factor,
             renderer: BladeRenderer::new(gpu, gpu_extent),
             atoms: *atoms,
+            accessibility,
 
             input_handler: None,
         }
@@ -249,8 +253,8 @@ impl X11WindowState {
     fn content_size(&self) -> Size<Pixels> {
         let size = self.renderer.viewport_size();
         Size {
-            width: size.width.into(),
-            height: size.height.into(),
+            width: px(size.width as f32 / self.scale_factor),
+            height: px(size.height as f32 / self.scale_factor),
         }
     }
 }
@@ -262,6 +266,8 @@ impl X11Window {
         x_main_screen_index: usize,
         x_window: xproto::Window,
         atoms: &XcbAtoms,
+        scale_factor: f32,
+        accessibility: AccessibleWindow,
     ) -> Self {
         X11Window {
             state: Rc::new(RefCell::new(X11WindowState::new(
@@ -270,6 +276,8 @@ impl X11Window {
                 x_main_screen_index,
                 x_window,
                 atoms,
+                scale_factor,
+                accessibility,
             ))),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
             xcb_connection: xcb_connection.clone(),
@@ -316,20 +324,44 @@ impl X11Window {
         }
     }
 
-    pub fn configure(&self, bounds: Bounds<i32>) {
+    pub fn handle_ime_preedit(&self, text: &str) {
+        let mut state = self.state.borrow_mut();
+        if let Some(mut input_handler) = state.input_handler.take() {
+            drop(state);
+            input_handler.set_ime_preedit(text, None);
+            self.state.borrow_mut().input_handler = Some(input_handler);
+        }
+    }
+
+    pub fn handle_ime_commit(&self, text: &str) {
+        let mut state = self.state.borrow_mut();
+        if let Some(mut input_handler) = state.input_handler.take() {
+            drop(state);
+            input_handler.replace_text_in_range(None, text);
+            self.state.borrow_mut().input_handler = Some(input_handler);
+        }
+    }
+
+    /// Handles the window being moved or resized, possibly onto a monitor with a different
+    /// scale factor.
+    pub fn configure(&self, bounds: Bounds<i32>, scale_factor: f32) {
         let mut resize_args = None;
         let do_move;
         {
             let mut state = self.state.borrow_mut();
             let old_bounds = mem::replace(&mut state.bounds, bounds);
             do_move = old_bounds.origin != bounds.origin;
+            let rescaled = mem::replace(&mut state.scale_factor, scale_factor) != scale_factor;
             // todo(linux): use normal GPUI types here, refactor out the double
             // viewport check and extra casts ( )
             let gpu_size = query_render_extent(&self.xcb_connection, self.x_window);
-            if state.renderer.viewport_size() != gpu_size {
+            let resized = state.renderer.viewport_size() != gpu_size;
+            if resized {
                 state
                     .renderer
                     .update_drawable_size(size(gpu_size.width as f64, gpu_size.height as f64));
+            }
+            if resized || rescaled {
                 resize_args = Some((state.content_size(), state.scale_factor));
             }
         }
@@ -393,7 +425,11 @@ impl PlatformWindow for X11Window {
             .unwrap()
             .reply()
             .unwrap();
-        Point::new((reply.root_x as u32).into(), (reply.root_y as u32).into())
+        let scale_factor = self.state.borrow().scale_factor;
+        Point::new(
+            px(reply.win_x as f32 / scale_factor),
+            px(reply.win_y as f32 / scale_factor),
+        )
     }
 
     // todo(linux)
@@ -455,6 +491,8 @@ impl PlatformWindow for X11Window {
                 title.as_bytes(),
             )
             .unwrap();
+
+        self.state.borrow().accessibility.set_title(title);
     }
 
     // todo(linux)
@@ -544,4 +582,12 @@ impl PlatformWindow for X11Window {
         let inner = self.state.borrow();
         inner.renderer.sprite_atlas().clone()
     }
+
+    fn set_accessible_text(&self, text: Option<AccessibleText>) {
+        self.state.borrow().accessibility.set_text(text);
+    }
+
+    fn announce(&self, message: &str) {
+        self.state.borrow().accessibility.announce(message);
+    }
 }
@@ -74,6 +74,21 @@ impl PlatformAtlas for MetalAtlas {
             Ok(tile)
         }
     }
+
+    fn retain(&self, predicate: &mut dyn FnMut(&AtlasKey) -> bool) {
+        let mut lock = self.0.lock();
+        let mut removed_tiles = Vec::new();
+        lock.tiles_by_key.retain(|key, tile| {
+            let keep = predicate(key);
+            if !keep {
+                removed_tiles.push(tile.clone());
+            }
+            keep
+        });
+        for tile in removed_tiles {
+            lock.texture_mut(tile.texture_id).deallocate(&tile);
+        }
+    }
 }
 
 impl MetalAtlasState {
@@ -157,6 +172,15 @@ impl MetalAtlasState {
         };
         &textures[id.index as usize]
     }
+
+    fn texture_mut(&mut self, id: AtlasTextureId) -> &mut MetalAtlasTexture {
+        let textures = match id.kind {
+            crate::AtlasTextureKind::Monochrome => &mut self.monochrome_textures,
+            crate::AtlasTextureKind::Polychrome => &mut self.polychrome_textures,
+            crate::AtlasTextureKind::Path => &mut self.path_textures,
+        };
+        &mut textures[id.index as usize]
+    }
 }
 
 struct MetalAtlasTexture {
@@ -170,6 +194,10 @@ impl MetalAtlasTexture {
         self.allocator.clear();
     }
 
+    fn deallocate(&mut self, tile: &AtlasTile) {
+        self.allocator.deallocate(tile.tile_id.into());
+    }
+
     fn allocate(&mut self, size: Size<DevicePixels>) -> Option<AtlasTile> {
         let allocation = self.allocator.allocate(size.into())?;
         let tile = AtlasTile {
@@ -1,8 +1,8 @@
 use super::metal_atlas::MetalAtlas;
 use crate::{
-    point, size, AtlasTextureId, AtlasTextureKind, AtlasTile, Bounds, ContentMask, DevicePixels,
-    Hsla, MonochromeSprite, Path, PathId, PathVertex, PolychromeSprite, PrimitiveBatch, Quad,
-    ScaledPixels, Scene, Shadow, Size, Surface, Underline,
+    point, size, AtlasTextureId, AtlasTextureKind, AtlasTile, BackdropBlur, Bounds, ContentMask,
+    DevicePixels, Hsla, MonochromeSprite, Path, PathId, PathVertex, PolychromeSprite,
+    PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size, Surface, Underline,
 };
 use block::ConcreteBlock;
 use cocoa::{
@@ -54,6 +54,10 @@ pub(crate) struct MetalRenderer {
     monochrome_sprites_pipeline_state: metal::RenderPipelineState,
     polychrome_sprites_pipeline_state: metal::RenderPipelineState,
     surfaces_pipeline_state: metal::RenderPipelineState,
+    backdrop_blurs_pipeline_state: metal::RenderPipelineState,
+    /// A copy of what was drawn before the current batch of backdrop blurs,
+    /// which they sample from. Reallocated whenever the drawable is resized.
+    backdrop_texture: Option<metal::Texture>,
     unit_vertices: metal::Buffer,
     #[allow(clippy::arc_with_non_send_sync)]
     instance_buffer_pool: Arc<Mutex<Vec<metal::Buffer>>>,
@@ -75,6 +79,8 @@ impl MetalRenderer {
         layer.set_pixel_format(MTLPixelFormat::BGRA8Unorm);
         layer.set_opaque(false);
         layer.set_maximum_drawable_count(3);
+        // Backdrop blurs copy from the drawable's texture.
+        layer.set_framebuffer_only(false);
         unsafe {
             let _: () = msg_send![&*layer, setAllowsNextDrawableTimeout: NO];
             let _: () = msg_send![&*layer, setNeedsDisplayOnBoundsChange: YES];
@@ -178,6 +184,14 @@ impl MetalRenderer {
             "surface_fragment",
             MTLPixelFormat::BGRA8Unorm,
         );
+        let backdrop_blurs_pipeline_state = build_pipeline_state(
+            &device,
+            &library,
+            "backdrop_blurs",
+            "backdrop_blur_vertex",
+            "backdrop_blur_fragment",
+            MTLPixelFormat::BGRA8Unorm,
+        );
 
         let command_queue = device.new_command_queue();
         let sprite_atlas = Arc::new(MetalAtlas::new(device.clone()));
@@ -197,6 +211,8 @@ impl MetalRenderer {
             monochrome_sprites_pipeline_state,
             polychrome_sprites_pipeline_state,
             surfaces_pipeline_state,
+            backdrop_blurs_pipeline_state,
+            backdrop_texture: None,
             unit_vertices,
             instance_buffer_pool,
             sprite_atlas,
@@ -271,27 +287,14 @@ impl MetalRenderer {
             return;
         };
 
-        let render_pass_descriptor = metal::RenderPassDescriptor::new();
-        let color_attachment = render_pass_descriptor
-            .color_attachments()
-            .object_at(0)
-            .unwrap();
-
-        color_attachment.set_texture(Some(drawable.texture()));
-        color_attachment.set_load_action(metal::MTLLoadAction::Clear);
-        color_attachment.set_store_action(metal::MTLStoreAction::Store);
         let alpha = if self.layer.is_opaque() { 1. } else { 0. };
-        color_attachment.set_clear_color(metal::MTLClearColor::new(0., 0., 0., alpha));
-        let command_encoder = command_buffer.new_render_command_encoder(render_pass_descriptor);
-
-        command_encoder.set_viewport(metal::MTLViewport {
-            originX: 0.0,
-            originY: 0.0,
-            width: i32::from(viewport_size.width) as f64,
-            height: i32::from(viewport_size.height) as f64,
-            znear: 0.0,
-            zfar: 1.0,
-        });
+        let mut command_encoder = begin_render_pass(
+            command_buffer,
+            drawable.texture(),
+            metal::MTLLoadAction::Clear,
+            metal::MTLClearColor::new(0., 0., 0., alpha),
+            viewport_size,
+        );
 
         for batch in scene.batches() {
             let ok = match batch {
@@ -353,10 +356,32 @@ impl MetalRenderer {
                     viewport_size,
                     command_encoder,
                 ),
+                PrimitiveBatch::BackdropBlurs(backdrop_blurs) => {
+                    // The blurs sample what has been drawn so far, so the pass has to be
+                    // ended to copy it, and then resumed on top of what was drawn.
+                    command_encoder.end_encoding();
+                    let backdrop_texture =
+                        self.copy_backdrop_texture(drawable.texture(), command_buffer);
+                    command_encoder = begin_render_pass(
+                        command_buffer,
+                        drawable.texture(),
+                        metal::MTLLoadAction::Load,
+                        metal::MTLClearColor::new(0., 0., 0., alpha),
+                        viewport_size,
+                    );
+                    self.draw_backdrop_blurs(
+                        backdrop_blurs,
+                        &backdrop_texture,
+                        &mut instance_buffer,
+                        &mut instance_offset,
+                        viewport_size,
+                        command_encoder,
+                    )
+                }
             };
 
             if !ok {
-                log::error!("scene too large: {} paths, {} shadows, {} quads, {} underlines, {} mono, {} poly, {} surfaces",
+                log::error!("scene too large: {} paths, {} shadows, {} quads, {} underlines, {} mono, {} poly, {} surfaces, {} backdrop blurs",
                     scene.paths.len(),
                     scene.shadows.len(),
                     scene.quads.len(),
@@ -364,6 +389,7 @@ impl MetalRenderer {
                     scene.monochrome_sprites.len(),
                     scene.polychrome_sprites.len(),
                     scene.surfaces.len(),
+                    scene.backdrop_blurs.len(),
                 );
                 break;
             }
@@ -1025,6 +1051,146 @@ impl MetalRenderer {
         }
         true
     }
+
+    /// Copies what has been drawn to `source` so far into the backdrop texture.
+    fn copy_backdrop_texture(
+        &mut self,
+        source: &metal::TextureRef,
+        command_buffer: &metal::CommandBufferRef,
+    ) -> metal::Texture {
+        let width = source.width();
+        let height = source.height();
+        let backdrop_texture = match self.backdrop_texture.take() {
+            Some(texture) if texture.width() == width && texture.height() == height => texture,
+            _ => {
+                let texture_descriptor = metal::TextureDescriptor::new();
+                texture_descriptor.set_width(width);
+                texture_descriptor.set_height(height);
+                texture_descriptor.set_pixel_format(source.pixel_format());
+                texture_descriptor.set_usage(metal::MTLTextureUsage::ShaderRead);
+                texture_descriptor.set_storage_mode(metal::MTLStorageMode::Private);
+                self.device.new_texture(&texture_descriptor)
+            }
+        };
+
+        let blit_encoder = command_buffer.new_blit_command_encoder();
+        blit_encoder.copy_from_texture(
+            source,
+            0,
+            0,
+            metal::MTLOrigin { x: 0, y: 0, z: 0 },
+            metal::MTLSize {
+                width,
+                height,
+                depth: 1,
+            },
+            &backdrop_texture,
+            0,
+            0,
+            metal::MTLOrigin { x: 0, y: 0, z: 0 },
+        );
+        blit_encoder.end_encoding();
+
+        self.backdrop_texture = Some(backdrop_texture.clone());
+        backdrop_texture
+    }
+
+    fn draw_backdrop_blurs(
+        &mut self,
+        backdrop_blurs: &[BackdropBlur],
+        backdrop_texture: &metal::TextureRef,
+        instance_buffer: &mut metal::Buffer,
+        instance_offset: &mut usize,
+        viewport_size: Size<DevicePixels>,
+        command_encoder: &metal::RenderCommandEncoderRef,
+    ) -> bool {
+        if backdrop_blurs.is_empty() {
+            return true;
+        }
+        align_offset(instance_offset);
+
+        command_encoder.set_render_pipeline_state(&self.backdrop_blurs_pipeline_state);
+        command_encoder.set_vertex_buffer(
+            BackdropBlurInputIndex::Vertices as u64,
+            Some(&self.unit_vertices),
+            0,
+        );
+        command_encoder.set_vertex_buffer(
+            BackdropBlurInputIndex::BackdropBlurs as u64,
+            Some(instance_buffer),
+            *instance_offset as u64,
+        );
+        command_encoder.set_fragment_buffer(
+            BackdropBlurInputIndex::BackdropBlurs as u64,
+            Some(instance_buffer),
+            *instance_offset as u64,
+        );
+        command_encoder.set_vertex_bytes(
+            BackdropBlurInputIndex::ViewportSize as u64,
+            mem::size_of_val(&viewport_size) as u64,
+            &viewport_size as *const Size<DevicePixels> as *const _,
+        );
+        command_encoder.set_fragment_texture(
+            BackdropBlurInputIndex::BackdropTexture as u64,
+            Some(backdrop_texture),
+        );
+
+        let backdrop_blur_bytes_len = mem::size_of_val(backdrop_blurs);
+        let buffer_contents =
+            unsafe { (instance_buffer.contents() as *mut u8).add(*instance_offset) };
+
+        let next_offset = *instance_offset + backdrop_blur_bytes_len;
+        if next_offset > INSTANCE_BUFFER_SIZE {
+            return false;
+        }
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                backdrop_blurs.as_ptr() as *const u8,
+                buffer_contents,
+                backdrop_blur_bytes_len,
+            );
+        }
+
+        command_encoder.draw_primitives_instanced(
+            metal::MTLPrimitiveType::Triangle,
+            0,
+            6,
+            backdrop_blurs.len() as u64,
+        );
+        *instance_offset = next_offset;
+        true
+    }
+}
+
+fn begin_render_pass<'a>(
+    command_buffer: &'a metal::CommandBufferRef,
+    texture: &metal::TextureRef,
+    load_action: metal::MTLLoadAction,
+    clear_color: metal::MTLClearColor,
+    viewport_size: Size<DevicePixels>,
+) -> &'a metal::RenderCommandEncoderRef {
+    let render_pass_descriptor = metal::RenderPassDescriptor::new();
+    let color_attachment = render_pass_descriptor
+        .color_attachments()
+        .object_at(0)
+        .unwrap();
+
+    color_attachment.set_texture(Some(texture));
+    color_attachment.set_load_action(load_action);
+    color_attachment.set_store_action(metal::MTLStoreAction::Store);
+    color_attachment.set_clear_color(clear_color);
+    let command_encoder = command_buffer.new_render_command_encoder(render_pass_descriptor);
+
+    command_encoder.set_viewport(metal::MTLViewport {
+        originX: 0.0,
+        originY: 0.0,
+        width: i32::from(viewport_size.width) as f64,
+        height: i32::from(viewport_size.height) as f64,
+        znear: 0.0,
+        zfar: 1.0,
+    });
+    command_encoder
 }
 
 fn build_pipeline_state(
@@ -1140,6 +1306,14 @@ enum SurfaceInputIndex {
     CbCrTexture = 5,
 }
 
+#[repr(C)]
+enum BackdropBlurInputIndex {
+    Vertices = 0,
+    BackdropBlurs = 1,
+    ViewportSize = 2,
+    BackdropTexture = 3,
+}
+
 #[repr(C)]
 enum PathRasterizationInputIndex {
     Vertices = 0,
@@ -776,6 +776,20 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn show_notification(&self, title: &str, body: &str) {
+        unsafe {
+            let notification: id = msg_send![class!(NSUserNotification), new];
+            let _: () = msg_send![notification, setTitle: ns_string(title)];
+            let _: () = msg_send![notification, setInformativeText: ns_string(body)];
+            let center: id = msg_send![
+                class!(NSUserNotificationCenter),
+                defaultUserNotificationCenter
+            ];
+            let _: () = msg_send![center, deliverNotification: notification];
+            let _: () = msg_send![notification, release];
+        }
+    }
+
     fn local_timezone(&self) -> UtcOffset {
         unsafe {
             let local_timezone: id = msg_send![class!(NSTimeZone), localTimeZone];
@@ -21,6 +21,8 @@ float2 erf(float2 x);
 float blur_along_x(float x, float y, float sigma, float corner,
                    float2 half_size);
 float4 over(float4 below, float4 above);
+float4 gradient_color(Background background, float2 position,
+                      Bounds_ScaledPixels bounds, float4 solid_color);
 
 struct QuadVertexOutput {
   float4 position [[position]];
@@ -51,7 +53,7 @@ vertex QuadVertexOutput quad_vertex(uint unit_vertex_id [[vertex_id]],
       to_device_position(unit_vertex, quad.bounds, viewport_size);
   float4 clip_distance = distance_from_clip_rect(unit_vertex, quad.bounds,
                                                  quad.content_mask.bounds);
-  float4 background_color = hsla_to_rgba(quad.background);
+  float4 background_color = hsla_to_rgba(quad.background.solid);
   float4 border_color = hsla_to_rgba(quad.border_color);
   return QuadVertexOutput{
       device_position,
@@ -65,6 +67,8 @@ fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
                               constant Quad *quads
                               [[buffer(QuadInputIndex_Quads)]]) {
   Quad quad = quads[input.quad_id];
+  float4 background_color = gradient_color(quad.background, input.position.xy,
+                                           quad.bounds, input.background_color);
 
   // Fast path when the quad is not rounded and doesn't have any border.
   if (quad.corner_radii.top_left == 0. && quad.corner_radii.bottom_left == 0. &&
@@ -72,7 +76,7 @@ fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
       quad.corner_radii.bottom_right == 0. && quad.border_widths.top == 0. &&
       quad.border_widths.left == 0. && quad.border_widths.right == 0. &&
       quad.border_widths.bottom == 0.) {
-    return input.background_color;
+    return background_color;
   }
 
   float2 half_size =
@@ -120,19 +124,83 @@ fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
 
   float4 color;
   if (border_width == 0.) {
-    color = input.background_color;
+    color = background_color;
   } else {
     float inset_distance = distance + border_width;
     // Blend the border on top of the background and then linearly interpolate
     // between the two as we slide inside the background.
-    float4 blended_border = over(input.background_color, input.border_color);
-    color = mix(blended_border, input.background_color,
+    float4 blended_border = over(background_color, input.border_color);
+    color = mix(blended_border, background_color,
                 saturate(0.5 - inset_distance));
   }
 
   return color * float4(1., 1., 1., saturate(0.5 - distance));
 }
 
+struct BackdropBlurVertexOutput {
+  float4 position [[position]];
+  uint backdrop_blur_id [[flat]];
+  float clip_distance [[clip_distance]][4];
+};
+
+struct BackdropBlurFragmentInput {
+  float4 position [[position]];
+  uint backdrop_blur_id [[flat]];
+};
+
+vertex BackdropBlurVertexOutput backdrop_blur_vertex(
+    uint unit_vertex_id [[vertex_id]], uint backdrop_blur_id [[instance_id]],
+    constant float2 *unit_vertices [[buffer(BackdropBlurInputIndex_Vertices)]],
+    constant BackdropBlur *backdrop_blurs
+    [[buffer(BackdropBlurInputIndex_BackdropBlurs)]],
+    constant Size_DevicePixels *viewport_size
+    [[buffer(BackdropBlurInputIndex_ViewportSize)]]) {
+  float2 unit_vertex = unit_vertices[unit_vertex_id];
+  BackdropBlur backdrop_blur = backdrop_blurs[backdrop_blur_id];
+  float4 device_position =
+      to_device_position(unit_vertex, backdrop_blur.bounds, viewport_size);
+  float4 clip_distance = distance_from_clip_rect(
+      unit_vertex, backdrop_blur.bounds, backdrop_blur.content_mask.bounds);
+  return BackdropBlurVertexOutput{
+      device_position,
+      backdrop_blur_id,
+      {clip_distance.x, clip_distance.y, clip_distance.z, clip_distance.w}};
+}
+
+fragment float4 backdrop_blur_fragment(
+    BackdropBlurFragmentInput input [[stage_in]],
+    constant BackdropBlur *backdrop_blurs
+    [[buffer(BackdropBlurInputIndex_BackdropBlurs)]],
+    texture2d<float> backdrop_texture
+    [[texture(BackdropBlurInputIndex_BackdropTexture)]]) {
+  BackdropBlur backdrop_blur = backdrop_blurs[input.backdrop_blur_id];
+  constexpr sampler backdrop_sampler(mag_filter::linear, min_filter::linear,
+                                     address::clamp_to_edge, coord::pixel);
+
+  // Sample a grid spanning the blur radius, weighting each sample by its
+  // distance from the fragment.
+  const int steps = 4;
+  float radius = backdrop_blur.blur_radius;
+  float sigma = max(radius / 2., 0.5);
+  float4 color = float4(0.);
+  float total_weight = 0.;
+  for (int y = -steps; y <= steps; y++) {
+    for (int x = -steps; x <= steps; x++) {
+      float2 offset = float2(x, y) * (radius / steps);
+      float weight = gaussian(offset.x, sigma) * gaussian(offset.y, sigma);
+      color += backdrop_texture.sample(backdrop_sampler,
+                                       input.position.xy + offset) *
+               weight;
+      total_weight += weight;
+    }
+  }
+  color /= total_weight;
+
+  float distance = quad_sdf(input.position.xy, backdrop_blur.bounds,
+                            backdrop_blur.corner_radii);
+  return float4(color.rgb, saturate(0.5 - distance));
+}
+
 struct ShadowVertexOutput {
   float4 position [[position]];
   float4 color [[flat]];
@@ -691,3 +759,33 @@ float4 over(float4 below, float4 above) {
   result.a = alpha;
   return result;
 }
+
+// Returns the color of a linear or radial gradient at the given position
+// within its bounds, or the solid color if the background isn't a gradient.
+float4 gradient_color(Background background, float2 position,
+                      Bounds_ScaledPixels bounds, float4 solid_color) {
+  if (background.tag == BackgroundTag_Solid) {
+    return solid_color;
+  }
+
+  float2 half_size = float2(bounds.size.width, bounds.size.height) / 2.;
+  float2 center = float2(bounds.origin.x, bounds.origin.y) + half_size;
+  float2 center_to_point = position - center;
+  float t;
+  if (background.tag == BackgroundTag_LinearGradient) {
+    // Angles are in CSS convention: 0 degrees points up, increasing clockwise.
+    float angle = background.angle * M_PI_F / 180.;
+    float2 direction = float2(sin(angle), -cos(angle));
+    float half_length = abs(half_size.x * direction.x) +
+                        abs(half_size.y * direction.y);
+    t = dot(center_to_point, direction) / (2. * max(half_length, 0.0001)) + 0.5;
+  } else {
+    t = length(center_to_point / max(half_size, float2(0.0001)));
+  }
+
+  ColorStop first_stop = background.colors[0];
+  ColorStop second_stop = background.colors[1];
+  t = saturate((t - first_stop.percentage) /
+               max(second_stop.percentage - first_stop.percentage, 0.0001));
+  return mix(hsla_to_rgba(first_stop.color), hsla_to_rgba(second_stop.color), t);
+}
@@ -1,10 +1,11 @@
 use super::{ns_string, renderer, MacDisplay, NSRange};
 use crate::{
-    platform::PlatformInputHandler, point, px, size, AnyWindowHandle, Bounds, DevicePixels,
-    DisplayLink, ExternalPaths, FileDropEvent, ForegroundExecutor, KeyDownEvent, Keystroke,
-    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformWindow, Point, PromptLevel,
-    Size, Timer, WindowAppearance, WindowBackgroundAppearance, WindowKind, WindowParams,
+    platform::PlatformInputHandler, point, px, size, AccessibleRole, AccessibleText,
+    AnyWindowHandle, Bounds, DevicePixels, DisplayLink, ExternalPaths, FileDropEvent,
+    ForegroundExecutor, KeyDownEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformWindow, Point, PromptLevel, Size, Timer, WindowAppearance,
+    WindowBackgroundAppearance, WindowKind, WindowParams,
 };
 use block::ConcreteBlock;
 use cocoa::{
@@ -42,7 +43,6 @@ use std::{
     ffi::{c_void, CStr},
     mem,
     ops::Range,
-    os::raw::c_char,
     path::PathBuf,
     ptr::{self, NonNull},
     rc::Rc,
@@ -63,6 +63,8 @@ const NSWindowStyleMaskNonactivatingPanel: NSWindowStyleMask =
 #[allow(non_upper_case_globals)]
 const NSNormalWindowLevel: NSInteger = 0;
 #[allow(non_upper_case_globals)]
+const NSFloatingWindowLevel: NSInteger = 3;
+#[allow(non_upper_case_globals)]
 const NSPopUpWindowLevel: NSInteger = 101;
 #[allow(non_upper_case_globals)]
 const NSTrackingMouseEnteredAndExited: NSUInteger = 0x01;
@@ -94,6 +96,21 @@ extern "C" {
     ) -> i32;
 }
 
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    static NSAccessibilityValueChangedNotification: id;
+    static NSAccessibilitySelectedTextChangedNotification: id;
+    static NSAccessibilityAnnouncementRequestedNotification: id;
+    static NSAccessibilityAnnouncementKey: id;
+    static NSAccessibilityPriorityKey: id;
+    fn NSAccessibilityPostNotification(element: id, notification: id);
+    fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, user_info: id);
+}
+
+// https://developer.apple.com/documentation/appkit/nsaccessibilitypriority
+#[allow(non_upper_case_globals)]
+const NSAccessibilityPriorityHigh: NSInteger = 90;
+
 #[ctor]
 unsafe fn build_classes() {
     WINDOW_CLASS = build_window_class("GPUIWindow", class!(NSWindow));
@@ -231,6 +248,36 @@ unsafe fn build_classes() {
             accepts_first_mouse as extern "C" fn(&Object, Sel, id) -> BOOL,
         );
 
+        // NSAccessibility
+        decl.add_method(
+            sel!(isAccessibilityElement),
+            yes as extern "C" fn(&Object, Sel) -> BOOL,
+        );
+        decl.add_method(
+            sel!(accessibilityRole),
+            accessibility_role as extern "C" fn(&Object, Sel) -> id,
+        );
+        decl.add_method(
+            sel!(accessibilityLabel),
+            accessibility_label as extern "C" fn(&Object, Sel) -> id,
+        );
+        decl.add_method(
+            sel!(accessibilityValue),
+            accessibility_value as extern "C" fn(&Object, Sel) -> id,
+        );
+        decl.add_method(
+            sel!(accessibilityNumberOfCharacters),
+            accessibility_number_of_characters as extern "C" fn(&Object, Sel) -> NSInteger,
+        );
+        decl.add_method(
+            sel!(accessibilitySelectedTextRange),
+            accessibility_selected_text_range as extern "C" fn(&Object, Sel) -> NSRange,
+        );
+        decl.add_method(
+            sel!(accessibilityInsertionPointLineNumber),
+            accessibility_insertion_point_line_number as extern "C" fn(&Object, Sel) -> NSInteger,
+        );
+
         decl.register()
     };
 }
@@ -362,6 +409,7 @@ struct MacWindowState {
     // Whether the next left-mouse click is also the focusing click.
     first_mouse: bool,
     minimized: bool,
+    accessible_text: Option<AccessibleText>,
 }
 
 impl MacWindowState {
@@ -654,6 +702,7 @@ impl MacWindow {
                 external_files_dragged: false,
                 first_mouse: false,
                 minimized: false,
+                accessible_text: None,
             })));
 
             (*native_window).set_ivar(
@@ -1015,6 +1064,17 @@ impl PlatformWindow for MacWindow {
         self.0.lock().move_traffic_light();
     }
 
+    fn set_always_on_top(&self, always_on_top: bool) {
+        let level = if always_on_top {
+            NSFloatingWindowLevel
+        } else {
+            NSNormalWindowLevel
+        };
+        unsafe {
+            self.0.lock().native_window.setLevel_(level);
+        }
+    }
+
     fn show_character_palette(&self) {
         let this = self.0.lock();
         let window = this.native_window;
@@ -1139,6 +1199,58 @@ impl PlatformWindow for MacWindow {
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas> {
         self.0.lock().renderer.sprite_atlas().clone()
     }
+
+    fn set_accessible_text(&self, text: Option<AccessibleText>) {
+        let mut this = self.0.lock();
+        let value_changed = this.accessible_text.as_ref().map(|text| &text.value)
+            != text.as_ref().map(|text| &text.value);
+        this.accessible_text = text;
+        let native_view = this.native_view.as_ptr() as id;
+        // Assistive technologies query the view synchronously when notified,
+        // so post the notifications after the window state is unlocked.
+        this.executor
+            .spawn(async move {
+                unsafe {
+                    if value_changed {
+                        NSAccessibilityPostNotification(
+                            native_view,
+                            NSAccessibilityValueChangedNotification,
+                        );
+                    }
+                    NSAccessibilityPostNotification(
+                        native_view,
+                        NSAccessibilitySelectedTextChangedNotification,
+                    );
+                }
+            })
+            .detach();
+    }
+
+    fn announce(&self, message: &str) {
+        let this = self.0.lock();
+        let window = this.native_window;
+        let message = message.to_owned();
+        this.executor
+            .spawn(async move {
+                unsafe {
+                    let priority: id =
+                        msg_send![class!(NSNumber), numberWithInteger: NSAccessibilityPriorityHigh];
+                    let objects = NSArray::arrayWithObjects(nil, &[ns_string(&message), priority]);
+                    let keys = NSArray::arrayWithObjects(
+                        nil,
+                        &[NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey],
+                    );
+                    let user_info: id =
+                        msg_send![class!(NSDictionary), dictionaryWithObjects: objects forKeys: keys];
+                    NSAccessibilityPostNotificationWithUserInfo(
+                        window,
+                        NSAccessibilityAnnouncementRequestedNotification,
+                        user_info,
+                    );
+                }
+            })
+            .detach();
+    }
 }
 
 impl HasWindowHandle for MacWindow {
@@ -1715,6 +1827,41 @@ extern "C" fn selected_range(this: &Object, _: Sel) -> NSRange {
         .map_or(NSRange::invalid(), |range| range.into())
 }
 
+fn with_accessible_text<R>(this: &Object, f: impl FnOnce(&AccessibleText) -> R) -> Option<R> {
+    let window_state = unsafe { get_window_state(this) };
+    let lock = window_state.lock();
+    lock.accessible_text.as_ref().map(f)
+}
+
+extern "C" fn accessibility_role(this: &Object, _: Sel) -> id {
+    let role = with_accessible_text(this, |text| match text.role {
+        AccessibleRole::TextArea => "AXTextArea",
+        AccessibleRole::TextField => "AXTextField",
+    });
+    unsafe { ns_string(role.unwrap_or("AXGroup")) }
+}
+
+extern "C" fn accessibility_label(this: &Object, _: Sel) -> id {
+    with_accessible_text(this, |text| unsafe { ns_string(&text.label) }).unwrap_or(nil)
+}
+
+extern "C" fn accessibility_value(this: &Object, _: Sel) -> id {
+    with_accessible_text(this, |text| unsafe { ns_string(&text.value) }).unwrap_or(nil)
+}
+
+extern "C" fn accessibility_number_of_characters(this: &Object, _: Sel) -> NSInteger {
+    with_accessible_text(this, |text| text.value.encode_utf16().count() as NSInteger).unwrap_or(0)
+}
+
+extern "C" fn accessibility_selected_text_range(this: &Object, _: Sel) -> NSRange {
+    with_accessible_text(this, |text| text.selected_range_utf16.clone().into())
+        .unwrap_or_else(NSRange::invalid)
+}
+
+extern "C" fn accessibility_insertion_point_line_number(this: &Object, _: Sel) -> NSInteger {
+    with_accessible_text(this, |text| text.insertion_line as NSInteger).unwrap_or(0)
+}
+
 extern "C" fn first_rect_for_character_range(
     this: &Object,
     _: Sel,
@@ -1754,14 +1901,9 @@ extern "C" fn insert_text(this: &Object, _: Sel, text: id, replacement_range: NS
         } else {
             text
         };
-        let text = CStr::from_ptr(text.UTF8String() as *mut c_char)
-            .to_str()
-            .unwrap();
+        let text = string_from_ns_string(text);
         let replacement_range = replacement_range.to_range();
-        send_to_input_handler(
-            this,
-            ImeInput::InsertText(text.to_string(), replacement_range),
-        );
+        send_to_input_handler(this, ImeInput::InsertText(text, replacement_range));
     }
 }
 
@@ -1782,16 +1924,31 @@ extern "C" fn set_marked_text(
         };
         let selected_range = selected_range.to_range();
         let replacement_range = replacement_range.to_range();
-        let text = CStr::from_ptr(text.UTF8String() as *mut c_char)
-            .to_str()
-            .unwrap();
+        let text = string_from_ns_string(text);
 
         send_to_input_handler(
             this,
-            ImeInput::SetMarkedText(text.to_string(), replacement_range, selected_range),
+            ImeInput::SetMarkedText(text, replacement_range, selected_range),
         );
     }
 }
+
+/// Reads a string through its UTF-16 contents rather than `UTF8String`, which returns null when
+/// the string contains a lone surrogate. Lone surrogates are replaced with U+FFFD, which keeps
+/// the UTF-16 offsets that input methods refer to unchanged.
+unsafe fn string_from_ns_string(string: id) -> String {
+    if string == nil {
+        return String::new();
+    }
+    let length: NSUInteger = msg_send![string, length];
+    let mut characters = vec![0u16; length as usize];
+    let _: () = msg_send![
+        string,
+        getCharacters: characters.as_mut_ptr()
+        range: NSRange::from(0..length as usize)
+    ];
+    String::from_utf16_lossy(&characters)
+}
 extern "C" fn unmark_text(this: &Object, _: Sel) {
     send_to_input_handler(this, ImeInput::UnmarkText);
 }
@@ -1,10 +1,9 @@
 use crate::{
-    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DevicePixels,
-    DispatchEventResult, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
-    PlatformInputHandler, PlatformWindow, Point, Size, TestPlatform, TileId, WindowAppearance,
-    WindowBackgroundAppearance, WindowParams,
+    render_scene, AccessibleText, AnyWindowHandle, Bounds, DevicePixels, DispatchEventResult,
+    HeadlessAtlas, Pixels, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
+    Point, Scene, Size, TestPlatform, WindowAppearance, WindowBackgroundAppearance, WindowParams,
 };
-use collections::HashMap;
+use image::RgbaImage;
 use parking_lot::Mutex;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use std::{
@@ -14,17 +13,21 @@ use std::{
 
 pub(crate) struct TestWindowState {
     pub(crate) bounds: Bounds<DevicePixels>,
+    scale_factor: f32,
     pub(crate) handle: AnyWindowHandle,
     display: Rc<dyn PlatformDisplay>,
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
+    pub(crate) accessible_text: Option<AccessibleText>,
+    pub(crate) announcements: Vec<String>,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas: Arc<HeadlessAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
     active_status_change_callback: Option<Box<dyn FnMut(bool)>>,
     resize_callback: Option<Box<dyn FnMut(Size<Pixels>, f32)>>,
     moved_callback: Option<Box<dyn FnMut()>>,
+    request_frame_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
 }
@@ -57,17 +60,21 @@ impl TestWindow {
     ) -> Self {
         Self(Arc::new(Mutex::new(TestWindowState {
             bounds: params.bounds,
+            scale_factor: 2.0,
             display,
             platform,
             handle,
-            sprite_atlas: Arc::new(TestAtlas::new()),
+            sprite_atlas: Arc::new(HeadlessAtlas::new()),
             title: Default::default(),
             edited: false,
+            accessible_text: None,
+            announcements: Vec::new(),
             should_close_handler: None,
             input_callback: None,
             active_status_change_callback: None,
             resize_callback: None,
             moved_callback: None,
+            request_frame_callback: None,
             input_handler: None,
             is_fullscreen: false,
         })))
@@ -85,6 +92,26 @@ impl TestWindow {
         self.0.lock().resize_callback = Some(callback);
     }
 
+    pub fn simulate_frame(&mut self) {
+        let Some(mut callback) = self.0.lock().request_frame_callback.take() else {
+            return;
+        };
+        callback();
+        self.0.lock().request_frame_callback = Some(callback);
+    }
+
+    pub fn simulate_scale_factor_change(&mut self, scale_factor: f32) {
+        let mut lock = self.0.lock();
+        let Some(mut callback) = lock.resize_callback.take() else {
+            return;
+        };
+        lock.scale_factor = scale_factor;
+        let size = lock.bounds.size.map(|pixels| Pixels(pixels.0 as f32));
+        drop(lock);
+        callback(size, scale_factor);
+        self.0.lock().resize_callback = Some(callback);
+    }
+
     pub(crate) fn simulate_active_status_change(&self, active: bool) {
         let mut lock = self.0.lock();
         let Some(mut callback) = lock.active_status_change_callback.take() else {
@@ -125,7 +152,7 @@ impl PlatformWindow for TestWindow {
     }
 
     fn scale_factor(&self) -> f32 {
-        2.0
+        self.0.lock().scale_factor
     }
 
     fn appearance(&self) -> WindowAppearance {
@@ -219,7 +246,9 @@ impl PlatformWindow for TestWindow {
         self.0.lock().is_fullscreen
     }
 
-    fn on_request_frame(&self, _callback: Box<dyn FnMut()>) {}
+    fn on_request_frame(&self, callback: Box<dyn FnMut()>) {
+        self.0.lock().request_frame_callback = Some(callback)
+    }
 
     fn on_input(&self, callback: Box<dyn FnMut(crate::PlatformInput) -> DispatchEventResult>) {
         self.0.lock().input_callback = Some(callback)
@@ -259,71 +288,29 @@ impl PlatformWindow for TestWindow {
         self.0.lock().sprite_atlas.clone()
     }
 
-    fn as_test(&mut self) -> Option<&mut TestWindow> {
-        Some(self)
+    fn render_to_image(&self, scene: &Scene) -> Option<RgbaImage> {
+        let state = self.0.lock();
+        let viewport_size = state
+            .bounds
+            .size
+            .map(|size| DevicePixels((size.0 as f32 * state.scale_factor).round() as i32));
+        Some(render_scene(scene, viewport_size, &state.sprite_atlas))
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_raw_handle(&self) -> windows::Win32::Foundation::HWND {
-        unimplemented!()
+    fn set_accessible_text(&self, text: Option<AccessibleText>) {
+        self.0.lock().accessible_text = text;
     }
-}
-
-pub(crate) struct TestAtlasState {
-    next_id: u32,
-    tiles: HashMap<AtlasKey, AtlasTile>,
-}
 
-pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
+    fn announce(&self, message: &str) {
+        self.0.lock().announcements.push(message.to_owned());
+    }
 
-impl TestAtlas {
-    pub fn new() -> Self {
-        TestAtlas(Mutex::new(TestAtlasState {
-            next_id: 0,
-            tiles: HashMap::default(),
-        }))
+    fn as_test(&mut self) -> Option<&mut TestWindow> {
+        Some(self)
     }
-}
 
-impl PlatformAtlas for TestAtlas {
-    fn get_or_insert_with<'a>(
-        &self,
-        key: &crate::AtlasKey,
-        build: &mut dyn FnMut() -> anyhow::Result<(
-            Size<crate::DevicePixels>,
-            std::borrow::Cow<'a, [u8]>,
-        )>,
-    ) -> anyhow::Result<crate::AtlasTile> {
-        let mut state = self.0.lock();
-        if let Some(tile) = state.tiles.get(key) {
-            return Ok(tile.clone());
-        }
-
-        state.next_id += 1;
-        let texture_id = state.next_id;
-        state.next_id += 1;
-        let tile_id = state.next_id;
-
-        drop(state);
-        let (size, _) = build()?;
-        let mut state = self.0.lock();
-
-        state.tiles.insert(
-            key.clone(),
-            crate::AtlasTile {
-                texture_id: AtlasTextureId {
-                    index: texture_id,
-                    kind: crate::AtlasTextureKind::Path,
-                },
-                tile_id: TileId(tile_id),
-                padding: 0,
-                bounds: crate::Bounds {
-                    origin: Point::default(),
-                    size,
-                },
-            },
-        );
-
-        Ok(state.tiles[key].clone())
+    #[cfg(target_os = "windows")]
+    fn get_raw_handle(&self) -> windows::Win32::Foundation::HWND {
+        unimplemented!()
     }
 }
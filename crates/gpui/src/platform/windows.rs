@@ -1,3 +1,4 @@
+mod accessibility;
 mod direct_write;
 mod dispatcher;
 mod display;
@@ -5,6 +6,7 @@ mod platform;
 mod util;
 mod window;
 
+pub(crate) use accessibility::*;
 pub(crate) use direct_write::*;
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
@@ -0,0 +1,153 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ::util::ResultExt;
+use windows::{
+    core::*,
+    Win32::{Foundation::*, UI::Accessibility::*},
+};
+
+use crate::{AccessibleRole, AccessibleText};
+
+/// Exposes the focused text of a window to UI Automation clients, such as Narrator.
+///
+/// The window's root element takes on the role and value of the text, which is enough
+/// for screen readers to read it and to follow edits. Selections aren't exposed yet,
+/// since that needs the text pattern.
+pub(crate) struct AccessibleWindow {
+    text: Rc<RefCell<Option<AccessibleText>>>,
+    provider: IRawElementProviderSimple,
+}
+
+impl AccessibleWindow {
+    pub fn new(hwnd: HWND) -> Self {
+        let text = Rc::new(RefCell::new(None));
+        let provider = TextProvider {
+            hwnd,
+            text: text.clone(),
+        }
+        .into();
+        Self { text, provider }
+    }
+
+    /// Answers a `WM_GETOBJECT` message, if it asks for the UI Automation provider.
+    pub fn handle_get_object(&self, hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> Option<isize> {
+        if lparam.0 as i32 != UiaRootObjectId {
+            return None;
+        }
+        Some(unsafe { UiaReturnRawElementProvider(hwnd, wparam, lparam, &self.provider) }.0)
+    }
+
+    pub fn set_text(&self, text: Option<AccessibleText>) {
+        let old_text = self.text.replace(text);
+        if !unsafe { UiaClientsAreListening() }.as_bool() {
+            return;
+        }
+
+        let new_text = self.text.borrow();
+        match (old_text.as_ref(), new_text.as_ref()) {
+            (None, None) => {}
+            (Some(old), Some(new)) => {
+                if old.value != new.value {
+                    unsafe {
+                        UiaRaiseAutomationPropertyChangedEvent(
+                            &self.provider,
+                            UIA_ValueValuePropertyId,
+                            &VARIANT::from(old.value.as_str()),
+                            &VARIANT::from(new.value.as_str()),
+                        )
+                    }
+                    .log_err();
+                }
+            }
+            _ => {
+                unsafe {
+                    UiaRaiseAutomationEvent(&self.provider, UIA_AutomationFocusChangedEventId)
+                }
+                .log_err();
+            }
+        }
+    }
+
+    pub fn announce(&self, message: &str) {
+        unsafe {
+            UiaRaiseNotificationEvent(
+                &self.provider,
+                NotificationKind_Other,
+                NotificationProcessing_ImportantAll,
+                &BSTR::from(message),
+                &BSTR::new(),
+            )
+        }
+        .log_err();
+    }
+}
+
+impl Drop for AccessibleWindow {
+    fn drop(&mut self) {
+        unsafe { UiaDisconnectProvider(&self.provider) }.ok();
+    }
+}
+
+#[implement(IRawElementProviderSimple, IValueProvider)]
+struct TextProvider {
+    hwnd: HWND,
+    text: Rc<RefCell<Option<AccessibleText>>>,
+}
+
+impl IRawElementProviderSimple_Impl for TextProvider {
+    fn ProviderOptions(&self) -> Result<ProviderOptions> {
+        Ok(ProviderOptions_ServerSideProvider | ProviderOptions_UseComThreading)
+    }
+
+    fn GetPatternProvider(&self, pattern_id: UIA_PATTERN_ID) -> Result<IUnknown> {
+        if pattern_id == UIA_ValuePatternId && self.text.borrow().is_some() {
+            unsafe { self.cast() }
+        } else {
+            // A null pattern tells the client that the pattern isn't supported.
+            Err(Error::empty())
+        }
+    }
+
+    fn GetPropertyValue(&self, property_id: UIA_PROPERTY_ID) -> Result<VARIANT> {
+        let text = self.text.borrow();
+        let Some(text) = text.as_ref() else {
+            // Without any text, the window is described by its host provider.
+            return Ok(VARIANT::default());
+        };
+        let value = match property_id {
+            UIA_NamePropertyId => VARIANT::from(text.label.as_ref()),
+            UIA_ControlTypePropertyId => VARIANT::from(match text.role {
+                AccessibleRole::TextArea => UIA_DocumentControlTypeId.0,
+                AccessibleRole::TextField => UIA_EditControlTypeId.0,
+            }),
+            UIA_HasKeyboardFocusPropertyId | UIA_IsKeyboardFocusablePropertyId => {
+                VARIANT::from(true)
+            }
+            UIA_IsValuePatternAvailablePropertyId => VARIANT::from(true),
+            UIA_ValueValuePropertyId => VARIANT::from(text.value.as_str()),
+            _ => VARIANT::default(),
+        };
+        Ok(value)
+    }
+
+    fn HostRawElementProvider(&self) -> Result<IRawElementProviderSimple> {
+        unsafe { UiaHostProviderFromHwnd(self.hwnd) }
+    }
+}
+
+impl IValueProvider_Impl for TextProvider {
+    fn SetValue(&self, _value: &PCWSTR) -> Result<()> {
+        Err(E_NOTIMPL.into())
+    }
+
+    fn Value(&self) -> Result<BSTR> {
+        let text = self.text.borrow();
+        Ok(BSTR::from(
+            text.as_ref().map_or("", |text| text.value.as_str()),
+        ))
+    }
+
+    fn IsReadOnly(&self) -> Result<BOOL> {
+        Ok(FALSE)
+    }
+}
@@ -28,6 +28,7 @@ use windows::{
         Graphics::Gdi::*,
         System::{Com::*, Ole::*, SystemServices::*},
         UI::{
+            Accessibility::{IRawElementProviderSimple, UiaReturnRawElementProvider},
             Controls::*,
             HiDpi::*,
             Input::{Ime::*, KeyboardAndMouse::*},
@@ -54,6 +55,7 @@ pub(crate) struct WindowsWindowInner {
     display: RefCell<Rc<WindowsDisplay>>,
     click_state: RefCell<ClickState>,
     fullscreen: Cell<Option<StyleAndBounds>>,
+    accessibility: AccessibleWindow,
 }
 
 impl WindowsWindowInner {
@@ -115,6 +117,7 @@ impl WindowsWindowInner {
         let display = RefCell::new(display);
         let click_state = RefCell::new(ClickState::new());
         let fullscreen = Cell::new(None);
+        let accessibility = AccessibleWindow::new(hwnd);
         Self {
             hwnd,
             origin,
@@ -288,6 +291,9 @@ impl WindowsWindowInner {
             WM_IME_STARTCOMPOSITION => self.handle_ime_position(),
             WM_IME_COMPOSITION => self.handle_ime_composition(lparam),
             WM_SETCURSOR => self.handle_set_cursor(lparam),
+            WM_GETOBJECT => self
+                .accessibility
+                .handle_get_object(self.hwnd, wparam, lparam),
             _ => None,
         };
         if let Some(n) = handled {
@@ -399,6 +405,15 @@ impl WindowsWindowInner {
     }
 
     fn handle_destroy_msg(&self) -> Option<isize> {
+        // Tells UI Automation to release the providers it got from this window.
+        unsafe {
+            UiaReturnRawElementProvider(
+                self.hwnd,
+                WPARAM(0),
+                LPARAM(0),
+                None::<&IRawElementProviderSimple>,
+            )
+        };
         let mut callbacks = self.callbacks.borrow_mut();
         if let Some(callback) = callbacks.close.take() {
             callback()
@@ -1520,9 +1535,38 @@ impl PlatformWindow for WindowsWindow {
     // todo(windows)
     fn set_edited(&mut self, _edited: bool) {}
 
+    fn set_accessible_text(&self, text: Option<AccessibleText>) {
+        self.inner.accessibility.set_text(text);
+    }
+
+    fn announce(&self, message: &str) {
+        self.inner.accessibility.announce(message);
+    }
+
     // todo(windows)
     fn show_character_palette(&self) {}
 
+    fn set_always_on_top(&self, always_on_top: bool) {
+        let insert_after = if always_on_top {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        unsafe {
+            SetWindowPos(
+                self.inner.hwnd,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+            .context("unable to change whether the window is always on top")
+            .log_err();
+        }
+    }
+
     fn minimize(&self) {
         unsafe { ShowWindowAsync(self.inner.hwnd, SW_MINIMIZE) };
     }
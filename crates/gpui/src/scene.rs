@@ -2,8 +2,8 @@
 #![cfg_attr(windows, allow(dead_code))]
 
 use crate::{
-    bounds_tree::BoundsTree, point, AtlasTextureId, AtlasTile, Bounds, ContentMask, Corners, Edges,
-    Hsla, Pixels, Point, Radians, ScaledPixels, Size,
+    bounds_tree::BoundsTree, point, AtlasTextureId, AtlasTile, Background, Bounds, ContentMask,
+    Corners, Edges, Hsla, Pixels, Point, Radians, ScaledPixels, Size,
 };
 use std::{fmt::Debug, iter::Peekable, ops::Range, slice};
 
@@ -17,6 +17,7 @@ pub(crate) struct Scene {
     pub(crate) paint_operations: Vec<PaintOperation>,
     primitive_bounds: BoundsTree<ScaledPixels>,
     layer_stack: Vec<DrawOrder>,
+    pub(crate) backdrop_blurs: Vec<BackdropBlur>,
     pub(crate) shadows: Vec<Shadow>,
     pub(crate) quads: Vec<Quad>,
     pub(crate) paths: Vec<Path<ScaledPixels>>,
@@ -32,6 +33,7 @@ impl Scene {
         self.primitive_bounds.clear();
         self.layer_stack.clear();
         self.paths.clear();
+        self.backdrop_blurs.clear();
         self.shadows.clear();
         self.quads.clear();
         self.underlines.clear();
@@ -76,6 +78,10 @@ impl Scene {
             .copied()
             .unwrap_or_else(|| self.primitive_bounds.insert(clipped_bounds));
         match &mut primitive {
+            Primitive::BackdropBlur(blur) => {
+                blur.order = order;
+                self.backdrop_blurs.push(blur.clone());
+            }
             Primitive::Shadow(shadow) => {
                 shadow.order = order;
                 self.shadows.push(shadow.clone());
@@ -121,6 +127,7 @@ impl Scene {
     }
 
     pub fn finish(&mut self) {
+        self.backdrop_blurs.sort();
         self.shadows.sort();
         self.quads.sort();
         self.paths.sort();
@@ -132,6 +139,9 @@ impl Scene {
 
     pub(crate) fn batches(&self) -> impl Iterator<Item = PrimitiveBatch> {
         BatchIterator {
+            backdrop_blurs: &self.backdrop_blurs,
+            backdrop_blurs_start: 0,
+            backdrop_blurs_iter: self.backdrop_blurs.iter().peekable(),
             shadows: &self.shadows,
             shadows_start: 0,
             shadows_iter: self.shadows.iter().peekable(),
@@ -159,6 +169,7 @@ impl Scene {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Default)]
 pub(crate) enum PrimitiveKind {
+    BackdropBlur,
     Shadow,
     #[default]
     Quad,
@@ -177,6 +188,7 @@ pub(crate) enum PaintOperation {
 
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub(crate) enum Primitive {
+    BackdropBlur(BackdropBlur),
     Shadow(Shadow),
     Quad(Quad),
     Path(Path<ScaledPixels>),
@@ -189,6 +201,7 @@ pub(crate) enum Primitive {
 impl Primitive {
     pub fn bounds(&self) -> &Bounds<ScaledPixels> {
         match self {
+            Primitive::BackdropBlur(blur) => &blur.bounds,
             Primitive::Shadow(shadow) => &shadow.bounds,
             Primitive::Quad(quad) => &quad.bounds,
             Primitive::Path(path) => &path.bounds,
@@ -201,6 +214,7 @@ impl Primitive {
 
     pub fn content_mask(&self) -> &ContentMask<ScaledPixels> {
         match self {
+            Primitive::BackdropBlur(blur) => &blur.content_mask,
             Primitive::Shadow(shadow) => &shadow.content_mask,
             Primitive::Quad(quad) => &quad.content_mask,
             Primitive::Path(path) => &path.content_mask,
@@ -213,6 +227,9 @@ impl Primitive {
 }
 
 struct BatchIterator<'a> {
+    backdrop_blurs: &'a [BackdropBlur],
+    backdrop_blurs_start: usize,
+    backdrop_blurs_iter: Peekable<slice::Iter<'a, BackdropBlur>>,
     shadows: &'a [Shadow],
     shadows_start: usize,
     shadows_iter: Peekable<slice::Iter<'a, Shadow>>,
@@ -241,6 +258,10 @@ impl<'a> Iterator for BatchIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut orders_and_kinds = [
+            (
+                self.backdrop_blurs_iter.peek().map(|b| b.order),
+                PrimitiveKind::BackdropBlur,
+            ),
             (
                 self.shadows_iter.peek().map(|s| s.order),
                 PrimitiveKind::Shadow,
@@ -275,6 +296,22 @@ impl<'a> Iterator for BatchIterator<'a> {
         };
 
         match batch_kind {
+            PrimitiveKind::BackdropBlur => {
+                let blurs_start = self.backdrop_blurs_start;
+                let mut blurs_end = blurs_start + 1;
+                self.backdrop_blurs_iter.next();
+                while self
+                    .backdrop_blurs_iter
+                    .next_if(|blur| (blur.order, batch_kind) < max_order_and_kind)
+                    .is_some()
+                {
+                    blurs_end += 1;
+                }
+                self.backdrop_blurs_start = blurs_end;
+                Some(PrimitiveBatch::BackdropBlurs(
+                    &self.backdrop_blurs[blurs_start..blurs_end],
+                ))
+            }
             PrimitiveKind::Shadow => {
                 let shadows_start = self.shadows_start;
                 let mut shadows_end = shadows_start + 1;
@@ -399,6 +436,9 @@ impl<'a> Iterator for BatchIterator<'a> {
 
 #[derive(Debug)]
 pub(crate) enum PrimitiveBatch<'a> {
+    /// Blurs of everything drawn before them. Renderers need to end their render pass and
+    /// copy what's been drawn so far so it can be sampled when drawing these.
+    BackdropBlurs(&'a [BackdropBlur]),
     Shadows(&'a [Shadow]),
     Quads(&'a [Quad]),
     Paths(&'a [Path<ScaledPixels>]),
@@ -421,7 +461,7 @@ pub(crate) struct Quad {
     pub pad: u32, // align to 8 bytes
     pub bounds: Bounds<ScaledPixels>,
     pub content_mask: ContentMask<ScaledPixels>,
-    pub background: Hsla,
+    pub background: Background,
     pub border_color: Hsla,
     pub corner_radii: Corners<ScaledPixels>,
     pub border_widths: Edges<ScaledPixels>,
@@ -504,6 +544,35 @@ impl From<Shadow> for Primitive {
     }
 }
 
+/// A region in which everything drawn before it is blurred.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[repr(C)]
+pub(crate) struct BackdropBlur {
+    pub order: DrawOrder,
+    pub blur_radius: ScaledPixels,
+    pub bounds: Bounds<ScaledPixels>,
+    pub corner_radii: Corners<ScaledPixels>,
+    pub content_mask: ContentMask<ScaledPixels>,
+}
+
+impl Ord for BackdropBlur {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.order.cmp(&other.order)
+    }
+}
+
+impl PartialOrd for BackdropBlur {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<BackdropBlur> for Primitive {
+    fn from(blur: BackdropBlur) -> Self {
+        Primitive::BackdropBlur(blur)
+    }
+}
+
 /// A data type representing a 2 dimensional transformation that can be applied to an element.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
@@ -754,6 +823,24 @@ impl Path<Pixels> {
         }
     }
 
+    /// Start a new contour at the given point. Contours are filled with the even-odd
+    /// rule, so overlapping contours cut holes into each other.
+    pub fn move_to(&mut self, to: Point<Pixels>) {
+        self.contour_count = 0;
+        self.start = to;
+        self.current = to;
+    }
+
+    /// Move every point of this path by the given offset.
+    pub fn translate(&mut self, offset: Point<Pixels>) {
+        self.bounds.origin += offset;
+        self.start += offset;
+        self.current += offset;
+        for vertex in &mut self.vertices {
+            vertex.xy_position += offset;
+        }
+    }
+
     /// Draw a straight line from the current point to the given point.
     pub fn line_to(&mut self, to: Point<Pixels>) {
         self.contour_count += 1;
@@ -864,3 +951,43 @@ impl PathVertex<Pixels> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{px, size};
+
+    #[test]
+    fn test_backdrop_blur_batches() {
+        let bounds = Bounds::new(point(px(0.), px(0.)), size(px(10.), px(10.))).scale(1.);
+        let content_mask = ContentMask { bounds };
+        let quad = || Quad {
+            bounds,
+            content_mask: content_mask.clone(),
+            ..Default::default()
+        };
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(quad());
+        scene.insert_primitive(BackdropBlur {
+            order: 0,
+            blur_radius: px(8.).scale(1.),
+            bounds,
+            corner_radii: Corners::default(),
+            content_mask: content_mask.clone(),
+        });
+        scene.insert_primitive(quad());
+        scene.finish();
+
+        // The blur ends the batch of quads painted below it, so that only those get blurred.
+        let batches = scene
+            .batches()
+            .map(|batch| match batch {
+                PrimitiveBatch::BackdropBlurs(blurs) => ("blurs", blurs.len()),
+                PrimitiveBatch::Quads(quads) => ("quads", quads.len()),
+                batch => panic!("unexpected batch {batch:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(batches, [("quads", 1), ("blurs", 1), ("quads", 1)]);
+    }
+}
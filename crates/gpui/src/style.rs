@@ -1,10 +1,10 @@
 use std::{iter, mem, ops::Range};
 
 use crate::{
-    black, phi, point, quad, rems, AbsoluteLength, Bounds, ContentMask, Corners, CornersRefinement,
-    CursorStyle, DefiniteLength, Edges, EdgesRefinement, ElementContext, Font, FontFeatures,
-    FontStyle, FontWeight, Hsla, Length, Pixels, Point, PointRefinement, Rgba, SharedString, Size,
-    SizeRefinement, Styled, TextRun,
+    black, phi, point, quad, rems, AbsoluteLength, Background, Bounds, ContentMask, Corners,
+    CornersRefinement, CursorStyle, DefiniteLength, Edges, EdgesRefinement, ElementContext, Font,
+    FontFeatures, FontStyle, FontWeight, Hsla, Length, Pixels, Point, PointRefinement, Rgba,
+    SharedString, Size, SizeRefinement, Styled, TextRun,
 };
 use collections::HashSet;
 use refineable::Refineable;
@@ -109,6 +109,9 @@ pub struct Style {
     /// Box Shadow of the element
     pub box_shadow: SmallVec<[BoxShadow; 2]>,
 
+    /// How much to blur whatever is painted behind this element
+    pub backdrop_blur: Option<Pixels>,
+
     /// The text style of this element
     pub text: TextStyleRefinement,
 
@@ -406,20 +409,28 @@ impl Style {
 
         let rem_size = cx.rem_size();
 
+        if let Some(blur_radius) = self.backdrop_blur {
+            cx.paint_backdrop_blur(
+                bounds,
+                self.corner_radii.to_pixels(bounds.size, rem_size),
+                blur_radius,
+            );
+        }
+
         cx.paint_shadows(
             bounds,
             self.corner_radii.to_pixels(bounds.size, rem_size),
             &self.box_shadow,
         );
 
-        let background_color = self.background.as_ref().and_then(Fill::color);
-        if background_color.map_or(false, |color| !color.is_transparent()) {
-            let mut border_color = background_color.unwrap_or_default();
+        let background = self.background.as_ref().map(Fill::background);
+        if let Some(background) = background.filter(|background| !background.is_transparent()) {
+            let mut border_color = background.solid_color().unwrap_or_default();
             border_color.a = 0.;
             cx.paint_quad(quad(
                 bounds,
                 self.corner_radii.to_pixels(bounds.size, rem_size),
-                background_color.unwrap_or_default(),
+                background,
                 Edges::default(),
                 border_color,
             ));
@@ -537,6 +548,7 @@ impl Default for Style {
             border_color: None,
             corner_radii: Corners::default(),
             box_shadow: Default::default(),
+            backdrop_blur: None,
             text: TextStyleRefinement::default(),
             mouse_cursor: None,
 
@@ -578,6 +590,9 @@ pub struct StrikethroughStyle {
 pub enum Fill {
     /// A solid color fill.
     Color(Hsla),
+    /// A linear or radial gradient fill, see [`linear_gradient`](crate::linear_gradient)
+    /// and [`radial_gradient`](crate::radial_gradient).
+    Gradient(Background),
 }
 
 impl Fill {
@@ -585,6 +600,15 @@ impl Fill {
     pub fn color(&self) -> Option<Hsla> {
         match self {
             Fill::Color(color) => Some(*color),
+            Fill::Gradient(_) => None,
+        }
+    }
+
+    /// Convert this fill into the background of a quad.
+    pub fn background(&self) -> Background {
+        match self {
+            Fill::Color(color) => (*color).into(),
+            Fill::Gradient(gradient) => *gradient,
         }
     }
 }
@@ -607,6 +631,15 @@ impl From<Rgba> for Fill {
     }
 }
 
+impl From<Background> for Fill {
+    fn from(background: Background) -> Self {
+        match background.solid_color() {
+            Some(color) => Self::Color(color),
+            None => Self::Gradient(background),
+        }
+    }
+}
+
 impl From<TextStyle> for HighlightStyle {
     fn from(other: TextStyle) -> Self {
         Self::from(&other)
@@ -843,4 +876,20 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_fill_from_background() {
+        let solid = Fill::from(Background::from(red()));
+        assert_eq!(solid.color(), Some(red()));
+        assert_eq!(solid.background(), Background::from(red()));
+
+        let gradient = crate::linear_gradient(
+            45.,
+            crate::color_stop(red(), 0.),
+            crate::color_stop(blue(), 1.),
+        );
+        let fill = Fill::from(gradient);
+        assert_eq!(fill.color(), None);
+        assert_eq!(fill.background(), gradient);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
     self as gpui, hsla, point, px, relative, rems, AbsoluteLength, AlignItems, CursorStyle,
     DefiniteLength, Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, Hsla,
-    JustifyContent, Length, Position, SharedString, StyleRefinement, Visibility, WhiteSpace,
+    JustifyContent, Length, Pixels, Position, SharedString, StyleRefinement, Visibility,
+    WhiteSpace,
 };
 use crate::{BoxShadow, TextStyleRefinement};
 use smallvec::{smallvec, SmallVec};
@@ -499,6 +500,14 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Blurs whatever is painted behind the element, like frosted glass. Combine it with
+    /// a translucent background to tint the blurred backdrop.
+    /// [Docs](https://tailwindcss.com/docs/backdrop-blur)
+    fn backdrop_blur(mut self, radius: impl Into<Pixels>) -> Self {
+        self.style().backdrop_blur = Some(radius.into());
+        self
+    }
+
     /// Sets the box shadow of the element.
     /// [Docs](https://tailwindcss.com/docs/box-shadow)
     fn shadow_sm(mut self) -> Self {
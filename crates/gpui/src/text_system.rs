@@ -39,6 +39,30 @@ pub struct FontFamilyId(pub usize);
 
 pub(crate) const SUBPIXEL_VARIANTS: u8 = 4;
 
+/// The fonts to fall back to for characters that neither a font nor its
+/// configured fallbacks have a glyph for, covering emoji and CJK text.
+#[cfg(target_os = "macos")]
+const DEFAULT_GLYPH_FALLBACKS: &[&str] = &[
+    "Apple Color Emoji",
+    "PingFang SC",
+    "Hiragino Sans",
+    "Apple SD Gothic Neo",
+];
+#[cfg(target_os = "windows")]
+const DEFAULT_GLYPH_FALLBACKS: &[&str] = &[
+    "Segoe UI Emoji",
+    "Segoe UI Symbol",
+    "Microsoft YaHei",
+    "Yu Gothic",
+    "Malgun Gothic",
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_GLYPH_FALLBACKS: &[&str] = &[
+    "Noto Color Emoji",
+    "Noto Sans Mono CJK SC",
+    "Noto Sans CJK SC",
+];
+
 /// The GPUI text rendering sub system.
 pub struct TextSystem {
     platform_text_system: Arc<dyn PlatformTextSystem>,
@@ -48,6 +72,8 @@ pub struct TextSystem {
     wrapper_pool: Mutex<FxHashMap<FontIdWithSize, Vec<LineWrapper>>>,
     font_runs_pool: Mutex<Vec<Vec<FontRun>>>,
     fallback_font_stack: SmallVec<[Font; 2]>,
+    glyph_fallbacks: RwLock<FxHashMap<SharedString, Arc<[SharedString]>>>,
+    glyph_coverage: RwLock<FxHashMap<(FontId, char), bool>>,
 }
 
 impl TextSystem {
@@ -68,6 +94,8 @@ impl TextSystem {
                 font("Ubuntu"),    // Gnome (Ubuntu)
                 font("Noto Sans"), // KDE
             ],
+            glyph_fallbacks: RwLock::default(),
+            glyph_coverage: RwLock::default(),
         }
     }
 
@@ -154,6 +182,104 @@ impl TextSystem {
         );
     }
 
+    /// Sets the font families to fall back to, in order, for characters that
+    /// the given font family has no glyph for, such as emoji, CJK text, or
+    /// icons from a Nerd Font.
+    pub fn set_font_fallbacks(
+        &self,
+        family: impl Into<SharedString>,
+        fallbacks: impl IntoIterator<Item = SharedString>,
+    ) {
+        let fallbacks = fallbacks.into_iter().collect::<Arc<[_]>>();
+        let mut glyph_fallbacks = self.glyph_fallbacks.write();
+        if fallbacks.is_empty() {
+            glyph_fallbacks.remove(&family.into());
+        } else {
+            glyph_fallbacks.insert(family.into(), fallbacks);
+        }
+    }
+
+    /// Returns the font families that the given font family falls back to, if any.
+    pub fn font_fallbacks(&self, family: &str) -> Option<Arc<[SharedString]>> {
+        self.glyph_fallbacks.read().get(family).cloned()
+    }
+
+    /// Returns the font whose glyph is used to render the given character in
+    /// the given font: either the font itself, or one of its fallbacks.
+    ///
+    /// Returns `None` when none of them has a glyph for the character, in which
+    /// case the platform picks the font to render it with, if any.
+    pub fn font_for_char(&self, font: &Font, ch: char) -> Option<Font> {
+        let font_id = self.glyph_font_id(font, self.resolve_font(font), ch);
+        if self.has_glyph(font_id, ch) {
+            self.get_font_for_id(font_id)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ID of the font to render the given character with, when
+    /// rendering it in the given font, which resolved to `font_id`.
+    fn glyph_font_id(&self, font: &Font, font_id: FontId, ch: char) -> FontId {
+        // Every font is expected to cover ASCII, which keeps the common case fast.
+        if ch.is_ascii() || self.has_glyph(font_id, ch) {
+            return font_id;
+        }
+
+        let fallbacks = self.glyph_fallbacks.read().get(&font.family).cloned();
+        let fallback_families = fallbacks
+            .iter()
+            .flat_map(|fallbacks| fallbacks.iter().cloned())
+            .chain(
+                DEFAULT_GLYPH_FALLBACKS
+                    .iter()
+                    .copied()
+                    .map(SharedString::from),
+            );
+        for family in fallback_families {
+            let fallback = Font {
+                family,
+                ..font.clone()
+            };
+            if let Ok(fallback_id) = self.font_id(&fallback) {
+                if self.has_glyph(fallback_id, ch) {
+                    return fallback_id;
+                }
+            }
+        }
+
+        font_id
+    }
+
+    fn has_glyph(&self, font_id: FontId, ch: char) -> bool {
+        if let Some(has_glyph) = self.glyph_coverage.read().get(&(font_id, ch)) {
+            return *has_glyph;
+        }
+
+        let has_glyph = self
+            .platform_text_system
+            .glyph_for_char(font_id, ch)
+            .is_some();
+        self.glyph_coverage.write().insert((font_id, ch), has_glyph);
+        has_glyph
+    }
+
+    /// Appends the font runs for the given text in the given font, switching
+    /// to the font's fallbacks for the characters it has no glyph for.
+    fn push_font_runs(&self, font_runs: &mut Vec<FontRun>, text: &str, font: &Font) {
+        let font_id = self.resolve_font(font);
+        for ch in text.chars() {
+            let font_id = self.glyph_font_id(font, font_id, ch);
+            match font_runs.last_mut() {
+                Some(last_run) if last_run.font_id == font_id => last_run.len += ch.len_utf8(),
+                _ => font_runs.push(FontRun {
+                    len: ch.len_utf8(),
+                    font_id,
+                }),
+            }
+        }
+    }
+
     /// Get the bounding box for the given font and font size.
     /// A font's bounding box is the smallest rectangle that could enclose all glyphs
     /// in the font. superimposed over one another.
@@ -381,7 +507,6 @@ impl WindowTextSystem {
         let mut process_line = |line_text: SharedString| {
             let line_end = line_start + line_text.len();
 
-            let mut last_font: Option<Font> = None;
             let mut decoration_runs = SmallVec::<[DecorationRun; 32]>::new();
             let mut run_start = line_start;
             while run_start < line_end {
@@ -391,15 +516,9 @@ impl WindowTextSystem {
 
                 let run_len_within_line = cmp::min(line_end, run_start + run.len) - run_start;
 
-                if last_font == Some(run.font.clone()) {
-                    font_runs.last_mut().unwrap().len += run_len_within_line;
-                } else {
-                    last_font = Some(run.font.clone());
-                    font_runs.push(FontRun {
-                        len: run_len_within_line,
-                        font_id: self.resolve_font(&run.font),
-                    });
-                }
+                let run_text = &line_text
+                    [run_start - line_start..run_start - line_start + run_len_within_line];
+                self.push_font_runs(&mut font_runs, run_text, &run.font);
 
                 if decoration_runs.last().map_or(false, |last_run| {
                     last_run.color == run.color
@@ -487,18 +606,11 @@ impl WindowTextSystem {
         runs: &[TextRun],
     ) -> Result<Arc<LineLayout>> {
         let mut font_runs = self.font_runs_pool.lock().pop().unwrap_or_default();
+        let mut run_start = 0;
         for run in runs.iter() {
-            let font_id = self.resolve_font(&run.font);
-            if let Some(last_run) = font_runs.last_mut() {
-                if last_run.font_id == font_id {
-                    last_run.len += run.len;
-                    continue;
-                }
-            }
-            font_runs.push(FontRun {
-                len: run.len,
-                font_id,
-            });
+            let run_end = cmp::min(run_start + run.len, text.len());
+            self.push_font_runs(&mut font_runs, &text[run_start..run_end], &run.font);
+            run_start = run_end;
         }
 
         let layout = self
@@ -792,3 +904,56 @@ impl FontMetrics {
         (self.bounding_box / self.units_per_em as f32 * font_size.0).map(px)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, TestAppContext};
+
+    #[gpui::test]
+    fn test_font_fallbacks(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let text_system = cx.text_system().clone();
+            let courier = font("Courier");
+
+            text_system.set_font_fallbacks(
+                "Courier",
+                ["Missing Font".into(), "Other Missing Font".into()],
+            );
+            assert_eq!(
+                text_system.font_fallbacks("Courier").as_deref(),
+                Some(&["Missing Font".into(), "Other Missing Font".into()][..])
+            );
+            assert_eq!(text_system.font_fallbacks("Helvetica"), None);
+
+            // Characters the font has a glyph for are rendered with the font itself,
+            // skipping fallbacks that aren't installed.
+            assert_eq!(
+                text_system
+                    .font_for_char(&courier, 'a')
+                    .map(|font| font.family),
+                Some("Courier".into())
+            );
+
+            // Adjacent text in the same font is merged into a single run.
+            let mut font_runs = Vec::new();
+            text_system.push_font_runs(&mut font_runs, "abc", &courier);
+            text_system.push_font_runs(&mut font_runs, "de", &courier);
+            text_system.push_font_runs(&mut font_runs, "fg", &courier.clone().bold());
+            assert_eq!(
+                font_runs
+                    .iter()
+                    .map(|run| (run.len, run.font_id))
+                    .collect::<Vec<_>>(),
+                [
+                    (5, text_system.resolve_font(&courier)),
+                    (2, text_system.resolve_font(&courier.bold())),
+                ]
+            );
+
+            // Setting no fallbacks clears them.
+            text_system.set_font_fallbacks("Courier", []);
+            assert_eq!(text_system.font_fallbacks("Courier"), None);
+        });
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
     seal::Sealed, AnyElement, AnyModel, AnyWeakModel, AppContext, Bounds, ContentMask, Element,
     ElementContext, ElementId, Entity, EntityId, Flatten, FocusHandle, FocusableView, IntoElement,
-    LayoutId, Model, PaintIndex, Pixels, PrepaintStateIndex, Render, Style, StyleRefinement,
-    TextStyle, ViewContext, VisualContext, WeakModel,
+    LayoutId, Model, PaintIndex, Pixels, PrepaintStateIndex, ProfilePhase, Render, Style,
+    StyleRefinement, TextStyle, ViewContext, VisualContext, WeakModel,
 };
 use anyhow::{Context, Result};
 use refineable::Refineable;
@@ -94,10 +94,12 @@ impl<V: Render> Element for View<V> {
     type PrepaintState = ();
 
     fn request_layout(&mut self, cx: &mut ElementContext) -> (LayoutId, Self::RequestLayoutState) {
-        cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-            let mut element = self.update(cx, |view, cx| view.render(cx).into_any_element());
-            let layout_id = element.request_layout(cx);
-            (layout_id, element)
+        cx.with_profiled_view(type_name::<V>(), ProfilePhase::Layout, |cx| {
+            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                let mut element = self.update(cx, |view, cx| view.render(cx).into_any_element());
+                let layout_id = element.request_layout(cx);
+                (layout_id, element)
+            })
         })
     }
 
@@ -108,8 +110,10 @@ impl<V: Render> Element for View<V> {
         cx: &mut ElementContext,
     ) {
         cx.set_view_id(self.entity_id());
-        cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-            element.prepaint(cx)
+        cx.with_profiled_view(type_name::<V>(), ProfilePhase::Layout, |cx| {
+            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                element.prepaint(cx)
+            })
         })
     }
 
@@ -120,8 +124,10 @@ impl<V: Render> Element for View<V> {
         _: &mut Self::PrepaintState,
         cx: &mut ElementContext,
     ) {
-        cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-            element.paint(cx)
+        cx.with_profiled_view(type_name::<V>(), ProfilePhase::Paint, |cx| {
+            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                element.paint(cx)
+            })
         })
     }
 }
@@ -221,6 +227,7 @@ impl<V> Eq for WeakView<V> {}
 pub struct AnyView {
     model: AnyModel,
     render: fn(&AnyView, &mut ElementContext) -> AnyElement,
+    type_name: &'static str,
     cached_style: Option<StyleRefinement>,
 }
 
@@ -238,6 +245,7 @@ impl AnyView {
         AnyWeakView {
             model: self.model.downgrade(),
             render: self.render,
+            type_name: self.type_name,
         }
     }
 
@@ -249,6 +257,7 @@ impl AnyView {
             Err(model) => Err(Self {
                 model,
                 render: self.render,
+                type_name: self.type_name,
                 cached_style: self.cached_style,
             }),
         }
@@ -270,6 +279,7 @@ impl<V: Render> From<View<V>> for AnyView {
         AnyView {
             model: value.model.into_any(),
             render: any_view::render::<V>,
+            type_name: type_name::<V>(),
             cached_style: None,
         }
     }
@@ -286,10 +296,12 @@ impl Element for AnyView {
             let layout_id = cx.request_layout(&root_style, None);
             (layout_id, None)
         } else {
-            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-                let mut element = (self.render)(self, cx);
-                let layout_id = element.request_layout(cx);
-                (layout_id, Some(element))
+            cx.with_profiled_view(self.type_name, ProfilePhase::Layout, |cx| {
+                cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                    let mut element = (self.render)(self, cx);
+                    let layout_id = element.request_layout(cx);
+                    (layout_id, Some(element))
+                })
             })
         }
     }
@@ -326,9 +338,13 @@ impl Element for AnyView {
                     }
 
                     let prepaint_start = cx.prepaint_index();
-                    let mut element = (self.render)(self, cx);
-                    element.layout_as_root(bounds.size.into(), cx);
-                    element.prepaint_at(bounds.origin, cx);
+                    let element =
+                        cx.with_profiled_view(self.type_name, ProfilePhase::Layout, |cx| {
+                            let mut element = (self.render)(self, cx);
+                            element.layout_as_root(bounds.size.into(), cx);
+                            element.prepaint_at(bounds.origin, cx);
+                            element
+                        });
                     let prepaint_end = cx.prepaint_index();
 
                     (
@@ -346,10 +362,12 @@ impl Element for AnyView {
                 },
             )
         } else {
-            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-                let mut element = element.take().unwrap();
-                element.prepaint(cx);
-                Some(element)
+            cx.with_profiled_view(self.type_name, ProfilePhase::Layout, |cx| {
+                cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                    let mut element = element.take().unwrap();
+                    element.prepaint(cx);
+                    Some(element)
+                })
             })
         }
     }
@@ -370,7 +388,9 @@ impl Element for AnyView {
                     let paint_start = cx.paint_index();
 
                     if let Some(element) = element {
-                        element.paint(cx);
+                        cx.with_profiled_view(self.type_name, ProfilePhase::Paint, |cx| {
+                            element.paint(cx)
+                        });
                     } else {
                         cx.reuse_paint(element_state.paint_range.clone());
                     }
@@ -382,8 +402,10 @@ impl Element for AnyView {
                 },
             )
         } else {
-            cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
-                element.as_mut().unwrap().paint(cx);
+            cx.with_profiled_view(self.type_name, ProfilePhase::Paint, |cx| {
+                cx.with_element_id(Some(ElementId::View(self.entity_id())), |cx| {
+                    element.as_mut().unwrap().paint(cx);
+                })
             })
         }
     }
@@ -409,6 +431,7 @@ impl IntoElement for AnyView {
 pub struct AnyWeakView {
     model: AnyWeakModel,
     render: fn(&AnyView, &mut ElementContext) -> AnyElement,
+    type_name: &'static str,
 }
 
 impl AnyWeakView {
@@ -418,6 +441,7 @@ impl AnyWeakView {
         Some(AnyView {
             model,
             render: self.render,
+            type_name: self.type_name,
             cached_style: None,
         })
     }
@@ -428,6 +452,7 @@ impl<V: 'static + Render> From<WeakView<V>> for AnyWeakView {
         Self {
             model: view.model.into(),
             render: any_view::render::<V>,
+            type_name: type_name::<V>(),
         }
     }
 }
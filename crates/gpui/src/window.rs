@@ -1,19 +1,20 @@
 use crate::{
-    point, px, size, transparent_black, Action, AnyDrag, AnyView, AppContext, Arena,
-    AsyncWindowContext, Bounds, Context, Corners, CursorStyle, DevicePixels,
-    DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity,
-    EntityId, EventEmitter, FileDropEvent, Flatten, Global, GlobalElementId, Hsla, KeyBinding,
-    KeyDownEvent, KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent, Model, ModelContext,
-    Modifiers, ModifiersChangedEvent, MouseButton, MouseMoveEvent, MouseUpEvent, Pixels,
-    PlatformAtlas, PlatformDisplay, PlatformInput, PlatformWindow, Point, PromptLevel, Render,
-    ScaledPixels, SharedString, Size, SubscriberSet, Subscription, TaffyLayoutEngine, Task,
-    TextStyle, TextStyleRefinement, View, VisualContext, WeakView, WindowAppearance,
-    WindowBackgroundAppearance, WindowOptions, WindowParams, WindowTextSystem,
+    point, px, size, transparent_black, Action, Animation, AnimationHandle, AnyDrag, AnyView,
+    AppContext, Arena, AsyncWindowContext, Background, Bounds, Context, Corners, CursorStyle,
+    DevicePixels, DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect,
+    Entity, EntityId, EventEmitter, FileDropEvent, Flatten, Global, GlobalElementId, Hsla,
+    KeyBinding, KeyDownEvent, KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent, Model,
+    ModelContext, Modifiers, ModifiersChangedEvent, MouseButton, MouseMoveEvent, MouseUpEvent,
+    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformWindow, Point, PromptLevel,
+    Render, RunningAnimation, ScaledPixels, SharedString, Size, SubscriberSet, Subscription,
+    TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement, View, VisualContext, WeakView,
+    WindowAppearance, WindowBackgroundAppearance, WindowOptions, WindowParams, WindowTextSystem,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::FxHashSet;
 use derive_more::{Deref, DerefMut};
 use futures::channel::oneshot;
+use image::RgbaImage;
 use parking_lot::RwLock;
 use refineable::Refineable;
 use slotmap::SlotMap;
@@ -37,9 +38,11 @@ use std::{
 use util::{measure, ResultExt};
 
 mod element_cx;
+mod frame_profiler;
 mod prompts;
 
 pub use element_cx::*;
+pub use frame_profiler::*;
 pub use prompts::*;
 
 /// Represents the two different phases when dispatching events.
@@ -277,6 +280,7 @@ pub struct Window {
     pub(crate) platform_window: Box<dyn PlatformWindow>,
     display_id: DisplayId,
     sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas_scale_factor: f32,
     text_system: Arc<WindowTextSystem>,
     pub(crate) rem_size: Pixels,
     pub(crate) viewport_size: Size<Pixels>,
@@ -316,6 +320,7 @@ pub struct Window {
     focus_enabled: bool,
     pending_input: Option<PendingInput>,
     prompt: Option<RenderablePromptHandle>,
+    pub(crate) frame_profiler: Option<FrameProfiler>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -545,6 +550,7 @@ impl Window {
             platform_window,
             display_id,
             sprite_atlas,
+            sprite_atlas_scale_factor: scale_factor,
             text_system,
             rem_size: px(16.),
             viewport_size: content_size,
@@ -584,6 +590,7 @@ impl Window {
             focus_enabled: true,
             pending_input: None,
             prompt: None,
+            frame_profiler: None,
         }
     }
     fn new_focus_listener(
@@ -651,6 +658,33 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Start or stop recording how long each frame, and each view within it, takes to lay out and paint.
+    pub fn set_frame_profiling(&mut self, enabled: bool) {
+        if enabled != self.window.frame_profiler.is_some() {
+            self.window.frame_profiler = enabled.then(FrameProfiler::new);
+            self.refresh();
+        }
+    }
+
+    /// Returns whether frames are being profiled. See [`Self::set_frame_profiling`].
+    pub fn frame_profiling(&self) -> bool {
+        self.window.frame_profiler.is_some()
+    }
+
+    /// The most recently drawn frames, from oldest to newest, when frames are being profiled.
+    pub fn frame_profiles(&self) -> impl Iterator<Item = &FrameProfile> {
+        self.window
+            .frame_profiler
+            .iter()
+            .flat_map(|profiler| profiler.frames())
+    }
+
+    /// The most recently drawn frames in the Chrome trace event format, or `None`
+    /// if frames aren't being profiled.
+    pub fn frame_profile_chrome_trace(&self) -> Option<String> {
+        Some(self.window.frame_profiler.as_ref()?.chrome_trace())
+    }
+
     /// Indicate that this view has changed, which will invoke any observers and also mark the window as dirty.
     /// If this view or any of its ancestors are *cached*, notifying it will cause it or its ancestors to be redrawn.
     pub fn notify(&mut self, view_id: EntityId) {
@@ -861,6 +895,43 @@ impl<'a> WindowContext<'a> {
         RefCell::borrow_mut(&self.window.next_frame_callbacks).push(Box::new(callback));
     }
 
+    /// Schedule the given closure to be called with the current time right before the window
+    /// draws its next frame, like `requestAnimationFrame` on the web. The closure isn't called
+    /// if the returned handle is dropped or cancelled first.
+    pub fn request_animation_frame(
+        &mut self,
+        callback: impl FnOnce(Instant, &mut WindowContext) + 'static,
+    ) -> AnimationHandle {
+        let handle = AnimationHandle::new();
+        let cancelled = handle.cancelled();
+        self.on_next_frame(move |cx| {
+            if !cancelled.get() {
+                let now = cx.background_executor().now();
+                callback(now, cx);
+            }
+        });
+        handle
+    }
+
+    /// Run the given animation, calling `on_frame` with its eased delta once per frame until
+    /// it finishes, or until the returned handle is dropped or cancelled. When the user has
+    /// asked for reduced motion, `on_frame` is only called once, with the animation's final delta.
+    pub fn animate(
+        &mut self,
+        animation: Animation,
+        on_frame: impl FnMut(f32, &mut WindowContext) + 'static,
+    ) -> AnimationHandle {
+        let handle = AnimationHandle::new();
+        RunningAnimation {
+            animation,
+            start: self.background_executor().now(),
+            on_frame: Box::new(on_frame),
+            cancelled: handle.cancelled(),
+        }
+        .schedule(self);
+        handle
+    }
+
     /// Spawn the future returned by the given closure on the application thread pool.
     /// The closure is provided a handle to the current window and an `AsyncWindowContext` for
     /// use within your future.
@@ -941,6 +1012,12 @@ impl<'a> WindowContext<'a> {
         self.window.platform_window.set_edited(edited);
     }
 
+    /// Keep the window above other windows, even when it isn't focused.
+    /// This isn't supported on every platform.
+    pub fn set_window_always_on_top(&mut self, always_on_top: bool) {
+        self.window.platform_window.set_always_on_top(always_on_top);
+    }
+
     /// Determine the display on which the window is visible.
     pub fn display(&self) -> Option<Rc<dyn PlatformDisplay>> {
         self.platform
@@ -954,6 +1031,11 @@ impl<'a> WindowContext<'a> {
         self.window.platform_window.show_character_palette();
     }
 
+    /// Asks the platform's assistive technologies, such as a screen reader, to announce the given message.
+    pub fn announce(&self, message: &str) {
+        self.window.platform_window.announce(message);
+    }
+
     /// The scale factor of the display associated with the window. For example, it could
     /// return 2.0 for a "retina" display, indicating that each logical pixel should actually
     /// be rendered as two pixels on screen.
@@ -1039,7 +1121,23 @@ impl<'a> WindowContext<'a> {
                 .push(Some(input_handler));
         }
 
+        // Sprites rasterized for the previous scale factor would look blurry or jagged, so
+        // evict them rather than letting them pile up in the atlas.
+        if self.window.sprite_atlas_scale_factor != self.window.scale_factor {
+            let scale_factor = self.window.scale_factor;
+            self.window
+                .sprite_atlas
+                .retain(&mut |key| key.is_valid_at_scale_factor(scale_factor));
+            self.window.sprite_atlas_scale_factor = scale_factor;
+        }
+
+        if let Some(profiler) = self.window.frame_profiler.as_mut() {
+            profiler.begin_frame();
+        }
         self.with_element_context(|cx| cx.draw_roots());
+        if let Some(profiler) = self.window.frame_profiler.as_mut() {
+            profiler.end_frame();
+        }
         self.window.dirty_views.clear();
 
         self.window
@@ -1059,6 +1157,14 @@ impl<'a> WindowContext<'a> {
                 .set_input_handler(input_handler.unwrap());
         }
 
+        // Report the focused element's text to assistive technologies, if it changed.
+        let accessible_text = self.window.next_frame.accessible_texts.last();
+        if accessible_text != self.window.rendered_frame.accessible_texts.last() {
+            self.window
+                .platform_window
+                .set_accessible_text(accessible_text.cloned());
+        }
+
         self.window.layout_engine.as_mut().unwrap().clear();
         self.text_system().finish_frame();
         self.window
@@ -1114,6 +1220,19 @@ impl<'a> WindowContext<'a> {
         self.window.needs_present.set(true);
     }
 
+    /// Rasterizes the window's current frame into an image on the CPU, drawing a new frame
+    /// first if the window is dirty. This is only supported by windows that aren't presented
+    /// on a display, such as those of headless apps on Linux and of tests.
+    pub fn render_to_image(&mut self) -> Result<RgbaImage> {
+        if self.window.dirty.get() {
+            self.draw();
+        }
+        self.window
+            .platform_window
+            .render_to_image(&self.window.rendered_frame.scene)
+            .ok_or_else(|| anyhow!("this window can't be rendered to an image"))
+    }
+
     #[profiling::function]
     fn present(&self) {
         self.window
@@ -2116,6 +2235,37 @@ impl<'a, V: 'static> ViewContext<'a, V> {
         self.window_cx.on_next_frame(move |cx| view.update(cx, f));
     }
 
+    /// Schedule the given closure to be called with the current time right before the window
+    /// draws its next frame. See [`WindowContext::request_animation_frame`].
+    pub fn request_animation_frame(
+        &mut self,
+        callback: impl FnOnce(&mut V, Instant, &mut ViewContext<V>) + 'static,
+    ) -> AnimationHandle
+    where
+        V: 'static,
+    {
+        let view = self.view().downgrade();
+        self.window_cx.request_animation_frame(move |now, cx| {
+            view.update(cx, |view, cx| callback(view, now, cx)).ok();
+        })
+    }
+
+    /// Run the given animation, calling `on_frame` with the view and the animation's eased
+    /// delta once per frame. See [`WindowContext::animate`].
+    pub fn animate(
+        &mut self,
+        animation: Animation,
+        mut on_frame: impl FnMut(&mut V, f32, &mut ViewContext<V>) + 'static,
+    ) -> AnimationHandle
+    where
+        V: 'static,
+    {
+        let view = self.view().downgrade();
+        self.window_cx.animate(animation, move |delta, cx| {
+            view.update(cx, |view, cx| on_frame(view, delta, cx)).ok();
+        })
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer(&mut self, f: impl FnOnce(&mut V, &mut ViewContext<V>) + 'static) {
@@ -2902,8 +3052,8 @@ pub struct PaintQuad {
     pub bounds: Bounds<Pixels>,
     /// The radii of the quad's corners.
     pub corner_radii: Corners<Pixels>,
-    /// The background color of the quad.
-    pub background: Hsla,
+    /// The background of the quad, either a solid color or a gradient.
+    pub background: Background,
     /// The widths of the quad's borders.
     pub border_widths: Edges<Pixels>,
     /// The color of the quad's borders.
@@ -2935,8 +3085,8 @@ impl PaintQuad {
         }
     }
 
-    /// Sets the background color of the quad.
-    pub fn background(self, background: impl Into<Hsla>) -> Self {
+    /// Sets the background of the quad.
+    pub fn background(self, background: impl Into<Background>) -> Self {
         PaintQuad {
             background: background.into(),
             ..self
@@ -2948,7 +3098,7 @@ impl PaintQuad {
 pub fn quad(
     bounds: Bounds<Pixels>,
     corner_radii: impl Into<Corners<Pixels>>,
-    background: impl Into<Hsla>,
+    background: impl Into<Background>,
     border_widths: impl Into<Edges<Pixels>>,
     border_color: impl Into<Hsla>,
 ) -> PaintQuad {
@@ -2961,8 +3111,8 @@ pub fn quad(
     }
 }
 
-/// Creates a filled quad with the given bounds and background color.
-pub fn fill(bounds: impl Into<Bounds<Pixels>>, background: impl Into<Hsla>) -> PaintQuad {
+/// Creates a filled quad with the given bounds and background.
+pub fn fill(bounds: impl Into<Bounds<Pixels>>, background: impl Into<Background>) -> PaintQuad {
     PaintQuad {
         bounds: bounds.into(),
         corner_radii: (0.).into(),
@@ -2977,8 +3127,64 @@ pub fn outline(bounds: impl Into<Bounds<Pixels>>, border_color: impl Into<Hsla>)
     PaintQuad {
         bounds: bounds.into(),
         corner_radii: (0.).into(),
-        background: transparent_black(),
+        background: transparent_black().into(),
         border_widths: (1.).into(),
         border_color: border_color.into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{
+        self as gpui, size, AtlasKey, DevicePixels, FontId, GlyphId, RenderGlyphParams,
+        TestAppContext, VisualTestContext,
+    };
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_scale_factor_change_evicts_stale_glyphs(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let glyph = |scale_factor| {
+            AtlasKey::Glyph(RenderGlyphParams {
+                font_id: FontId(0),
+                glyph_id: GlyphId(1),
+                font_size: px(12.),
+                subpixel_variant: Point::default(),
+                scale_factor,
+                is_emoji: false,
+            })
+        };
+        // Returns whether the sprite had to be rasterized.
+        let insert = |cx: &mut VisualTestContext, key: AtlasKey| {
+            cx.update(|cx| {
+                let mut rasterized = false;
+                cx.window
+                    .sprite_atlas
+                    .get_or_insert_with(&key, &mut || {
+                        rasterized = true;
+                        Ok((
+                            size(DevicePixels(1), DevicePixels(1)),
+                            Cow::Borrowed(&[0u8][..]),
+                        ))
+                    })
+                    .unwrap();
+                rasterized
+            })
+        };
+
+        assert!(insert(cx, glyph(2.)));
+        assert!(!insert(cx, glyph(2.)));
+
+        cx.simulate_scale_factor_change(1.5);
+        assert_eq!(cx.update(|cx| cx.scale_factor()), 1.5);
+        assert!(insert(cx, glyph(2.)));
+
+        // Glyphs for the current scale factor survive later frames.
+        assert!(insert(cx, glyph(1.5)));
+        cx.update(|cx| cx.refresh());
+        assert!(!insert(cx, glyph(1.5)));
+    }
+}
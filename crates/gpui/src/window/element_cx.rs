@@ -31,15 +31,16 @@ use smallvec::SmallVec;
 use util::post_inc;
 
 use crate::{
-    hash, point, prelude::*, px, size, AnyElement, AnyTooltip, AppContext, Asset, AvailableSpace,
-    Bounds, BoxShadow, ContentMask, Corners, CursorStyle, DevicePixels, DispatchNodeId,
-    DispatchPhase, DispatchTree, DrawPhase, ElementId, ElementStateBox, EntityId, FocusHandle,
-    FocusId, FontId, GlobalElementId, GlyphId, Hsla, ImageData, InputHandler, IsZero, KeyContext,
-    KeyEvent, LayoutId, LineLayoutIndex, ModifiersChangedEvent, MonochromeSprite, MouseEvent,
-    PaintQuad, Path, Pixels, PlatformInputHandler, Point, PolychromeSprite, Quad,
-    RenderGlyphParams, RenderImageParams, RenderSvgParams, Scene, Shadow, SharedString, Size,
-    StrikethroughStyle, Style, Task, TextStyleRefinement, TransformationMatrix, Underline,
-    UnderlineStyle, Window, WindowContext, SUBPIXEL_VARIANTS,
+    hash, point, prelude::*, px, size, AccessibleText, AnyElement, AnyTooltip, AppContext, Asset,
+    AvailableSpace, BackdropBlur, Bounds, BoxShadow, ContentMask, Corners, CursorStyle,
+    DevicePixels, DispatchNodeId, DispatchPhase, DispatchTree, DrawPhase, ElementId,
+    ElementStateBox, EntityId, FocusHandle, FocusId, FontId, GlobalElementId, GlyphId, Hsla,
+    ImageData, InputHandler, IsZero, KeyContext, KeyEvent, LayoutId, LineLayoutIndex,
+    ModifiersChangedEvent, MonochromeSprite, MouseEvent, PaintQuad, Path, Pixels,
+    PlatformInputHandler, Point, PolychromeSprite, ProfilePhase, Quad, RenderGlyphParams,
+    RenderImageParams, RenderSvgParams, Scene, Shadow, SharedString, Size, StrikethroughStyle,
+    Style, Task, TextStyleRefinement, TransformationMatrix, Underline, UnderlineStyle, Window,
+    WindowContext, SUBPIXEL_VARIANTS,
 };
 
 pub(crate) type AnyMouseListener =
@@ -136,6 +137,7 @@ pub(crate) struct Frame {
     pub(crate) hitboxes: Vec<Hitbox>,
     pub(crate) deferred_draws: Vec<DeferredDraw>,
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
+    pub(crate) accessible_texts: Vec<AccessibleText>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
     #[cfg(any(test, feature = "test-support"))]
@@ -157,6 +159,7 @@ pub(crate) struct PaintIndex {
     scene_index: usize,
     mouse_listeners_index: usize,
     input_handlers_index: usize,
+    accessible_texts_index: usize,
     cursor_styles_index: usize,
     accessed_element_states_index: usize,
     line_layout_index: LineLayoutIndex,
@@ -175,6 +178,7 @@ impl Frame {
             hitboxes: Vec::new(),
             deferred_draws: Vec::new(),
             input_handlers: Vec::new(),
+            accessible_texts: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
 
@@ -190,6 +194,7 @@ impl Frame {
         self.dispatch_tree.clear();
         self.scene.clear();
         self.input_handlers.clear();
+        self.accessible_texts.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
         self.hitboxes.clear();
@@ -424,6 +429,9 @@ impl<'a> ElementContext<'a> {
 
         // Now actually paint the elements.
         self.window.draw_phase = DrawPhase::Paint;
+        if let Some(profiler) = self.window.frame_profiler.as_mut() {
+            profiler.begin_paint();
+        }
         root_element.paint(self);
 
         self.paint_deferred_draws(&sorted_deferred_draws);
@@ -602,6 +610,7 @@ impl<'a> ElementContext<'a> {
             scene_index: self.window.next_frame.scene.len(),
             mouse_listeners_index: self.window.next_frame.mouse_listeners.len(),
             input_handlers_index: self.window.next_frame.input_handlers.len(),
+            accessible_texts_index: self.window.next_frame.accessible_texts.len(),
             cursor_styles_index: self.window.next_frame.cursor_styles.len(),
             accessed_element_states_index: self.window.next_frame.accessed_element_states.len(),
             line_layout_index: self.window.text_system.layout_index(),
@@ -623,6 +632,12 @@ impl<'a> ElementContext<'a> {
                 .iter_mut()
                 .map(|handler| handler.take()),
         );
+        window.next_frame.accessible_texts.extend(
+            window.rendered_frame.accessible_texts
+                [range.start.accessible_texts_index..range.end.accessible_texts_index]
+                .iter()
+                .cloned(),
+        );
         window.next_frame.mouse_listeners.extend(
             window.rendered_frame.mouse_listeners
                 [range.start.mouse_listeners_index..range.end.mouse_listeners_index]
@@ -702,6 +717,24 @@ impl<'a> ElementContext<'a> {
         }
     }
 
+    /// Invokes the given closure, attributing the time it takes to the given view
+    /// when frames are being profiled.
+    pub(crate) fn with_profiled_view<R>(
+        &mut self,
+        name: &'static str,
+        phase: ProfilePhase,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        if let Some(profiler) = self.window.frame_profiler.as_mut() {
+            profiler.begin_span(name, phase);
+        }
+        let result = f(self);
+        if let Some(profiler) = self.window.frame_profiler.as_mut() {
+            profiler.end_span();
+        }
+        result
+    }
+
     /// Invoke the given function with the given content mask after intersecting it
     /// with the current mask.
     pub fn with_content_mask<R>(
@@ -1065,6 +1098,15 @@ impl<'a> ElementContext<'a> {
             let mut shadow_bounds = bounds;
             shadow_bounds.origin += shadow.offset;
             shadow_bounds.dilate(shadow.spread_radius);
+            shadow_bounds.size = shadow_bounds.size.max(&Size::default());
+            // Like in CSS, spreading a shadow grows or shrinks its corners along with it.
+            let corner_radii = corner_radii.map(|radius| {
+                if radius.is_zero() {
+                    *radius
+                } else {
+                    (*radius + shadow.spread_radius).max(Pixels::ZERO)
+                }
+            });
             self.window.next_frame.scene.insert_primitive(Shadow {
                 order: 0,
                 blur_radius: shadow.blur_radius.scale(scale_factor),
@@ -1076,6 +1118,29 @@ impl<'a> ElementContext<'a> {
         }
     }
 
+    /// Blur whatever has been painted behind the given bounds so far, for frosted glass
+    /// effects on overlays. Paint a translucent background on top of it to tint it.
+    pub fn paint_backdrop_blur(
+        &mut self,
+        bounds: Bounds<Pixels>,
+        corner_radii: Corners<Pixels>,
+        blur_radius: Pixels,
+    ) {
+        if blur_radius <= Pixels::ZERO {
+            return;
+        }
+
+        let scale_factor = self.scale_factor();
+        let content_mask = self.content_mask();
+        self.window.next_frame.scene.insert_primitive(BackdropBlur {
+            order: 0,
+            blur_radius: blur_radius.scale(scale_factor),
+            bounds: bounds.scale(scale_factor),
+            corner_radii: corner_radii.scale(scale_factor),
+            content_mask: content_mask.scale(scale_factor),
+        });
+    }
+
     /// Paint one or more quads into the scene for the next frame at the current stacking context.
     /// Quads are colored rectangular regions with an optional background, border, and corner radius.
     /// see [`fill`](crate::fill), [`outline`](crate::outline), and [`quad`](crate::quad) to construct this type.
@@ -1499,6 +1564,15 @@ impl<'a> ElementContext<'a> {
         }
     }
 
+    /// Describes the text content of the focused element to the platform's assistive technologies,
+    /// such as screen readers. Like [`Self::handle_input`], this only has an effect if the given
+    /// focus handle is focused, and applies until the following frame is rendered.
+    pub fn set_accessible_text(&mut self, focus_handle: &FocusHandle, text: AccessibleText) {
+        if focus_handle.is_focused(self) {
+            self.window.next_frame.accessible_texts.push(text);
+        }
+    }
+
     /// Register a mouse event listener on the window for the next frame. The type of event
     /// is determined by the first parameter of the given listener. When the next frame is rendered
     /// the listener will be cleared.
@@ -1552,3 +1626,79 @@ impl<'a> ElementContext<'a> {
             ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{self as gpui, hsla, TestAppContext};
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_paint_shadows_and_backdrop_blur(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        cx.update(|cx| {
+            cx.with_element_context(|cx| {
+                let bounds = Bounds::new(point(px(10.), px(10.)), size(px(100.), px(100.)));
+                let corner_radii = Corners {
+                    top_left: px(8.),
+                    top_right: px(0.),
+                    bottom_right: px(2.),
+                    bottom_left: px(8.),
+                };
+                let shadow = |spread_radius: f32| BoxShadow {
+                    color: hsla(0., 0., 0., 0.5),
+                    offset: point(px(0.), px(0.)),
+                    blur_radius: px(4.),
+                    spread_radius: px(spread_radius),
+                };
+                cx.paint_shadows(
+                    bounds,
+                    corner_radii,
+                    &[shadow(4.), shadow(-4.), shadow(-60.)],
+                );
+                cx.paint_backdrop_blur(bounds, corner_radii, px(0.));
+                cx.paint_backdrop_blur(bounds, corner_radii, px(12.));
+
+                let scale_factor = cx.scale_factor();
+                let scene = &cx.window.next_frame.scene;
+
+                // Spreading a shadow grows or shrinks its rounded corners along with it,
+                // and shadows that shrink away entirely aren't painted.
+                assert_eq!(scene.shadows.len(), 2);
+                assert_eq!(
+                    scene.shadows[0].bounds,
+                    Bounds::new(point(px(6.), px(6.)), size(px(108.), px(108.)))
+                        .scale(scale_factor)
+                );
+                assert_eq!(
+                    scene.shadows[0].corner_radii,
+                    Corners {
+                        top_left: px(12.),
+                        top_right: px(0.),
+                        bottom_right: px(6.),
+                        bottom_left: px(12.),
+                    }
+                    .scale(scale_factor)
+                );
+                assert_eq!(
+                    scene.shadows[1].corner_radii,
+                    Corners {
+                        top_left: px(4.),
+                        top_right: px(0.),
+                        bottom_right: px(0.),
+                        bottom_left: px(4.),
+                    }
+                    .scale(scale_factor)
+                );
+
+                // Blurs without a radius aren't painted.
+                assert_eq!(scene.backdrop_blurs.len(), 1);
+                assert_eq!(
+                    scene.backdrop_blurs[0].blur_radius,
+                    px(12.).scale(scale_factor)
+                );
+                assert_eq!(scene.backdrop_blurs[0].bounds, bounds.scale(scale_factor));
+            })
+        });
+    }
+}
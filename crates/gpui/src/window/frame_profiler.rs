@@ -0,0 +1,293 @@
+use collections::HashMap;
+use serde_json::{json, Value};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The number of most recent frames kept by the profiler.
+const MAX_FRAMES: usize = 240;
+
+/// The phase of drawing a frame that a [ProfileSpan] was recorded in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfilePhase {
+    /// Rendering views, and requesting and computing their layout.
+    Layout,
+    /// Painting the laid out elements into the scene.
+    Paint,
+}
+
+impl ProfilePhase {
+    fn name(&self) -> &'static str {
+        match self {
+            ProfilePhase::Layout => "layout",
+            ProfilePhase::Paint => "paint",
+        }
+    }
+}
+
+/// The time spent laying out or painting a single view, including its children.
+#[derive(Clone, Debug)]
+pub struct ProfileSpan {
+    /// The type name of the view.
+    pub name: &'static str,
+    /// The phase of the frame this span was recorded in.
+    pub phase: ProfilePhase,
+    /// When the span started, relative to when profiling started.
+    pub start: Duration,
+    /// How long the view took, including its child views.
+    pub duration: Duration,
+    /// How long the view took, excluding its child views.
+    pub self_duration: Duration,
+    /// How many views this span is nested in.
+    pub depth: usize,
+}
+
+/// The time spent in views of a given type during a frame, excluding their child views.
+#[derive(Clone, Debug, Default)]
+pub struct ViewProfile {
+    /// The type name of the views.
+    pub name: &'static str,
+    /// The number of times views of this type were laid out or painted.
+    pub count: usize,
+    /// The time spent laying out views of this type.
+    pub layout_time: Duration,
+    /// The time spent painting views of this type.
+    pub paint_time: Duration,
+}
+
+/// Timing information about a single frame.
+#[derive(Clone, Debug)]
+pub struct FrameProfile {
+    /// When the frame started drawing, relative to when profiling started.
+    pub start: Duration,
+    /// The time spent laying out the frame.
+    pub layout_time: Duration,
+    /// The time spent painting the frame.
+    pub paint_time: Duration,
+    /// The total time spent drawing the frame.
+    pub frame_time: Duration,
+    /// The spans recorded for each view, in the order they started.
+    pub spans: Vec<ProfileSpan>,
+}
+
+impl FrameProfile {
+    /// Sums up the time spent in each type of view, from most to least expensive.
+    pub fn view_profiles(&self) -> Vec<ViewProfile> {
+        let mut profiles = HashMap::<&'static str, ViewProfile>::default();
+        for span in &self.spans {
+            let profile = profiles.entry(span.name).or_insert_with(|| ViewProfile {
+                name: span.name,
+                ..Default::default()
+            });
+            profile.count += 1;
+            match span.phase {
+                ProfilePhase::Layout => profile.layout_time += span.self_duration,
+                ProfilePhase::Paint => profile.paint_time += span.self_duration,
+            }
+        }
+        let mut profiles = profiles.into_values().collect::<Vec<_>>();
+        profiles.sort_by(|a, b| {
+            (b.layout_time + b.paint_time)
+                .cmp(&(a.layout_time + a.paint_time))
+                .then_with(|| a.name.cmp(b.name))
+        });
+        profiles
+    }
+}
+
+struct OpenSpan {
+    ix: usize,
+    start: Instant,
+    child_duration: Duration,
+}
+
+struct CurrentFrame {
+    start: Instant,
+    paint_start: Option<Instant>,
+    spans: Vec<ProfileSpan>,
+    stack: Vec<OpenSpan>,
+}
+
+/// Records how long each frame, and each view within it, takes to lay out and paint.
+pub(crate) struct FrameProfiler {
+    epoch: Instant,
+    frames: VecDeque<FrameProfile>,
+    current: Option<CurrentFrame>,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            frames: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    pub fn frames(&self) -> &VecDeque<FrameProfile> {
+        &self.frames
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.current = Some(CurrentFrame {
+            start: Instant::now(),
+            paint_start: None,
+            spans: Vec::new(),
+            stack: Vec::new(),
+        });
+    }
+
+    pub fn begin_paint(&mut self) {
+        if let Some(current) = self.current.as_mut() {
+            current.paint_start = Some(Instant::now());
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        let Some(current) = self.current.take() else {
+            return;
+        };
+        let now = Instant::now();
+        let paint_start = current.paint_start.unwrap_or(now);
+        if self.frames.len() == MAX_FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(FrameProfile {
+            start: current.start - self.epoch,
+            layout_time: paint_start - current.start,
+            paint_time: now - paint_start,
+            frame_time: now - current.start,
+            spans: current.spans,
+        });
+    }
+
+    pub fn begin_span(&mut self, name: &'static str, phase: ProfilePhase) {
+        let Some(current) = self.current.as_mut() else {
+            return;
+        };
+        let start = Instant::now();
+        current.stack.push(OpenSpan {
+            ix: current.spans.len(),
+            start,
+            child_duration: Duration::ZERO,
+        });
+        current.spans.push(ProfileSpan {
+            name,
+            phase,
+            start: start - self.epoch,
+            duration: Duration::ZERO,
+            self_duration: Duration::ZERO,
+            depth: current.stack.len() - 1,
+        });
+    }
+
+    pub fn end_span(&mut self) {
+        let Some(current) = self.current.as_mut() else {
+            return;
+        };
+        let Some(open_span) = current.stack.pop() else {
+            return;
+        };
+        let duration = open_span.start.elapsed();
+        let span = &mut current.spans[open_span.ix];
+        span.duration = duration;
+        span.self_duration = duration.saturating_sub(open_span.child_duration);
+        if let Some(parent) = current.stack.last_mut() {
+            parent.child_duration += duration;
+        }
+    }
+
+    /// Serializes the recorded frames in the Chrome trace event format, which can be
+    /// loaded into `chrome://tracing` or Perfetto.
+    pub fn chrome_trace(&self) -> String {
+        let mut events = Vec::new();
+        for (frame_ix, frame) in self.frames.iter().enumerate() {
+            events.push(trace_event(
+                &format!("Frame {frame_ix}"),
+                "frame",
+                frame.start,
+                frame.frame_time,
+            ));
+            for span in &frame.spans {
+                events.push(trace_event(
+                    span.name,
+                    span.phase.name(),
+                    span.start,
+                    span.duration,
+                ));
+            }
+        }
+        json!({ "traceEvents": events }).to_string()
+    }
+}
+
+fn trace_event(name: &str, category: &str, start: Duration, duration: Duration) -> Value {
+    json!({
+        "name": name,
+        "cat": category,
+        "ph": "X",
+        "ts": start.as_micros() as u64,
+        "dur": duration.as_micros() as u64,
+        "pid": 1,
+        "tid": 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_duration_excludes_children() {
+        let mut profiler = FrameProfiler::new();
+        profiler.begin_frame();
+        profiler.begin_span("Workspace", ProfilePhase::Layout);
+        profiler.begin_span("Editor", ProfilePhase::Layout);
+        std::thread::sleep(Duration::from_millis(5));
+        profiler.end_span();
+        profiler.end_span();
+        profiler.begin_paint();
+        profiler.end_frame();
+
+        let frame = profiler.frames().back().unwrap();
+        assert_eq!(frame.spans.len(), 2);
+        assert_eq!(frame.spans[0].depth, 0);
+        assert_eq!(frame.spans[1].depth, 1);
+        assert!(frame.spans[0].duration >= frame.spans[1].duration);
+        assert!(frame.spans[0].self_duration < frame.spans[1].duration);
+
+        let views = frame.view_profiles();
+        assert_eq!(views[0].name, "Editor");
+        assert_eq!(views[0].count, 1);
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let mut profiler = FrameProfiler::new();
+        profiler.begin_frame();
+        profiler.begin_paint();
+        profiler.begin_span("Pane<\"a\">", ProfilePhase::Paint);
+        profiler.end_span();
+        profiler.end_frame();
+
+        let trace: Value = serde_json::from_str(&profiler.chrome_trace()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["name"], "Frame 0");
+        assert_eq!(events[0]["cat"], "frame");
+        assert_eq!(events[1]["name"], "Pane<\"a\">");
+        assert_eq!(events[1]["cat"], "paint");
+        assert_eq!(events[1]["ph"], "X");
+    }
+
+    #[test]
+    fn test_keeps_most_recent_frames() {
+        let mut profiler = FrameProfiler::new();
+        for _ in 0..MAX_FRAMES + 10 {
+            profiler.begin_frame();
+            profiler.end_frame();
+        }
+        assert_eq!(profiler.frames().len(), MAX_FRAMES);
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Context as _, Result};
+use editor::Editor;
+use gpui::{actions, AppContext, Model, ModelContext, ViewContext};
+use language::{Anchor, Buffer, OffsetRangeExt};
+use std::ops::Range;
+use util::{paths::TEMP_DIR, ResultExt};
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+actions!(injection_editor, [EditInjection]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(edit_injection);
+    })
+    .detach();
+}
+
+struct NoInjection;
+
+/// Opens the injected region under the cursor, like SQL in a string or a code
+/// block in Markdown, in a buffer of its own. The region is written to a temporary
+/// file so that it gets its language's language server and formatting, and edits
+/// to either buffer are copied to the other one.
+fn edit_injection(workspace: &mut Workspace, _: &EditInjection, cx: &mut ViewContext<Workspace>) {
+    let Some(editor) = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+    else {
+        return;
+    };
+
+    let injection = editor.update(cx, |editor, cx| {
+        let cursor = editor.selections.newest_anchor().head();
+        let (host_buffer, position) = editor
+            .buffer()
+            .read(cx)
+            .text_anchor_for_position(cursor, cx)?;
+        let snapshot = host_buffer.read(cx).snapshot();
+        let (language, range) = snapshot.injection_at(position)?;
+        let suffix = language.path_suffixes().first()?.clone();
+        let host_name = host_buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned())
+            .unwrap_or_else(|| "untitled".to_string());
+        let row = snapshot.offset_to_point(range.start).row + 1;
+        let path = TEMP_DIR
+            .join("injections")
+            .join(format!("{host_name}-{row}.{suffix}"));
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        // Insertions at either end of the region are kept inside it.
+        let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+        Some((host_buffer, range, path, text))
+    });
+    let Some((host_buffer, range, path, text)) = injection else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoInjection>(),
+                "The cursor isn't inside an embedded language",
+            ),
+            cx,
+        );
+        return;
+    };
+
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        fs.create_dir(path.parent().context("no parent directory")?)
+            .await?;
+        fs.atomic_write(path.clone(), text).await?;
+        let item = workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.open_abs_path(path.clone(), false, cx)
+            })?
+            .await?;
+        let injection_buffer = cx
+            .update(|cx| {
+                item.act_as::<Editor>(cx)
+                    .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            })?
+            .ok_or_else(|| anyhow!("{path:?} wasn't opened in an editor"))?;
+        workspace.update(&mut cx, |_, cx| {
+            synchronize(host_buffer, range, injection_buffer, cx)
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Copies edits between an injected region of a host buffer and the buffer that
+/// it's being edited in, until the latter is released.
+fn synchronize(
+    host_buffer: Model<Buffer>,
+    range: Range<Anchor>,
+    injection_buffer: Model<Buffer>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let weak_host_buffer = host_buffer.downgrade();
+    let host_range = range.clone();
+    cx.subscribe(&injection_buffer, move |_, injection_buffer, event, cx| {
+        if let language::Event::Edited = event {
+            let text = injection_buffer.read(cx).text();
+            weak_host_buffer
+                .update(cx, |host_buffer, cx| {
+                    let range = host_range.to_offset(host_buffer);
+                    replace_changed_text(host_buffer, range, &text, cx);
+                })
+                .log_err();
+        }
+    })
+    .detach();
+
+    let weak_injection_buffer = injection_buffer.downgrade();
+    let mut host_subscription = Some(cx.subscribe(
+        &host_buffer,
+        move |_, host_buffer, event, cx| {
+            if let language::Event::Edited = event {
+                let text = {
+                    let host_buffer = host_buffer.read(cx);
+                    let range = range.to_offset(host_buffer);
+                    host_buffer.text_for_range(range).collect::<String>()
+                };
+                weak_injection_buffer
+                    .update(cx, |injection_buffer, cx| {
+                        let range = 0..injection_buffer.len();
+                        replace_changed_text(injection_buffer, range, &text, cx);
+                    })
+                    .ok();
+            }
+        },
+    ));
+    cx.observe_release(&injection_buffer, move |_, _, _| {
+        host_subscription.take();
+    })
+    .detach();
+}
+
+/// Replaces the given range of the buffer with `new_text`, only editing the part
+/// that differs so that anchors and selections elsewhere in the range are kept.
+fn replace_changed_text(
+    buffer: &mut Buffer,
+    range: Range<usize>,
+    new_text: &str,
+    cx: &mut ModelContext<Buffer>,
+) {
+    let old_text = buffer.text_for_range(range.clone()).collect::<String>();
+    if let Some((old_range, new_range)) = changed_ranges(&old_text, new_text) {
+        let start = range.start + old_range.start;
+        let end = range.start + old_range.end;
+        buffer.edit([(start..end, &new_text[new_range])], None, cx);
+    }
+}
+
+/// Returns the ranges of `old_text` and `new_text` that remain after removing
+/// their common prefix and suffix, or `None` if the texts are equal.
+fn changed_ranges(old_text: &str, new_text: &str) -> Option<(Range<usize>, Range<usize>)> {
+    if old_text == new_text {
+        return None;
+    }
+
+    let prefix_len = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, old), new)| old != new)
+        .map_or(old_text.len().min(new_text.len()), |((ix, _), _)| ix);
+
+    let max_suffix_len = old_text.len().min(new_text.len()) - prefix_len;
+    let suffix_len = old_text
+        .chars()
+        .rev()
+        .zip(new_text.chars().rev())
+        .take_while(|(old, new)| old == new)
+        .map(|(old, _)| old.len_utf8())
+        .scan(0, |len, char_len| {
+            *len += char_len;
+            Some(*len)
+        })
+        .take_while(|len| *len <= max_suffix_len)
+        .last()
+        .unwrap_or(0);
+
+    Some((
+        prefix_len..old_text.len() - suffix_len,
+        prefix_len..new_text.len() - suffix_len,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_ranges() {
+        assert_eq!(changed_ranges("select 1", "select 1"), None);
+        assert_eq!(
+            changed_ranges("select 1 from t", "select 2 from t"),
+            Some((7..8, 7..8))
+        );
+        assert_eq!(changed_ranges("aaa", "aaaa"), Some((3..3, 3..4)));
+        assert_eq!(changed_ranges("abc", "ac"), Some((1..2, 1..1)));
+        assert_eq!(changed_ranges("", "new"), Some((0..0, 0..3)));
+        assert_eq!(changed_ranges("é", "è"), Some((0..2, 0..2)));
+    }
+}
@@ -17,11 +17,12 @@ use crate::{
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
-use futures::channel::oneshot;
-use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
+use futures::{channel::oneshot, StreamExt};
+use gpui::{AppContext, EventEmitter, Global, HighlightStyle, ModelContext, Task, TaskLabel};
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
+use postage::watch;
 use similar::{ChangeTag, TextDiff};
 use smallvec::SmallVec;
 use smol::future::yield_now;
@@ -95,7 +96,7 @@ pub struct Buffer {
     pending_autoindent: Option<Task<()>>,
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
-    parsing_in_background: bool,
+    parse_status: (watch::Sender<ParseStatus>, watch::Receiver<ParseStatus>),
     parse_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -109,6 +110,15 @@ pub struct Buffer {
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
     has_conflict: bool,
+    /// Whether invalid UTF-8 was replaced when the file was loaded, in which
+    /// case the buffer's text no longer matches the bytes on disk.
+    is_lossy: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ParseStatus {
+    Idle,
+    Parsing,
 }
 
 /// An immutable, cheaply cloneable representation of a fixed
@@ -312,6 +322,8 @@ pub enum Event {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// Another replica of the buffer changed its selections.
+    RemoteSelectionsChanged { replica_id: ReplicaId },
     /// The buffer was explicitly requested to close.
     Closed,
 }
@@ -432,6 +444,34 @@ struct IndentSuggestion {
     within_error: bool,
 }
 
+/// How long all buffers combined may block the main thread waiting on parses
+/// within a single frame, so that edits to many buffers at once (e.g. a
+/// project-wide replace) can't add up to a dropped frame.
+const SYNC_PARSE_FRAME_BUDGET: Duration = Duration::from_millis(4);
+const FRAME_DURATION: Duration = Duration::from_millis(16);
+
+#[derive(Default)]
+struct SyncParseBudget {
+    frame_start: Option<Instant>,
+    spent: Duration,
+}
+
+impl Global for SyncParseBudget {}
+
+impl SyncParseBudget {
+    /// Returns how much longer parses can block during the frame containing `now`.
+    fn remaining(&mut self, now: Instant) -> Duration {
+        let frame_elapsed = self
+            .frame_start
+            .map(|frame_start| now.saturating_duration_since(frame_start));
+        if frame_elapsed.map_or(true, |elapsed| elapsed >= FRAME_DURATION) {
+            self.frame_start = Some(now);
+            self.spent = Duration::ZERO;
+        }
+        SYNC_PARSE_FRAME_BUDGET.saturating_sub(self.spent)
+    }
+}
+
 struct BufferChunkHighlights<'a> {
     captures: SyntaxMapCaptures<'a>,
     next_capture: Option<SyntaxMapCapture<'a>>,
@@ -451,6 +491,9 @@ pub struct BufferChunks<'a> {
     hint_depth: usize,
     unnecessary_depth: usize,
     highlights: Option<BufferChunkHighlights<'a>>,
+    /// Sorted ranges that are still waiting to be parsed, which are left unhighlighted
+    /// rather than showing highlights from a syntax tree that doesn't match them.
+    stale_syntax_ranges: Vec<Range<usize>>,
 }
 
 /// A chunk of a buffer's text, along with its syntax highlight and
@@ -647,7 +690,7 @@ impl Buffer {
             file,
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
-            parsing_in_background: false,
+            parse_status: watch::channel_with(ParseStatus::Idle),
             parse_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -664,6 +707,7 @@ impl Buffer {
             completion_triggers_timestamp: Default::default(),
             deferred_ops: OperationQueue::new(),
             has_conflict: false,
+            is_lossy: false,
         }
     }
 
@@ -749,6 +793,7 @@ impl Buffer {
     ) {
         self.saved_version = version;
         self.has_conflict = false;
+        self.is_lossy = false;
         self.saved_mtime = mtime;
         cx.emit(Event::Saved);
         cx.notify();
@@ -780,6 +825,7 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
+                    this.is_lossy = false;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
@@ -950,7 +996,19 @@ impl Buffer {
     /// Whether the buffer is being parsed in the background.
     #[cfg(any(test, feature = "test-support"))]
     pub fn is_parsing(&self) -> bool {
-        self.parsing_in_background
+        *self.parse_status.1.borrow() == ParseStatus::Parsing
+    }
+
+    /// Waits until the buffer's syntax tree reflects its latest edits.
+    pub fn parsing_idle(&self) -> impl Future<Output = ()> {
+        let mut parse_status = self.parse_status.1.clone();
+        async move {
+            while *parse_status.borrow() != ParseStatus::Idle {
+                if parse_status.next().await.is_none() {
+                    break;
+                }
+            }
+        }
     }
 
     /// Indicates whether the buffer contains any regions that may be
@@ -976,7 +1034,12 @@ impl Buffer {
     ///
     /// Meanwhile, in the foreground, we block the main thread for up to 1ms
     /// waiting on the parse to complete. As soon as it completes, we proceed
-    /// synchronously, unless a 1ms timeout elapses.
+    /// synchronously, unless a 1ms timeout elapses. All buffers share a budget
+    /// for this blocking within each frame, and once it's spent we don't block
+    /// at all until the next frame.
+    ///
+    /// Text inserted since the last completed parse is left unhighlighted by
+    /// [`BufferSnapshot::chunks`] until the background parse catches up.
     ///
     /// If we time out waiting on the parse, we spawn a second task waiting
     /// until the parse does complete and return with the interpolated tree still
@@ -988,7 +1051,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if *self.parse_status.1.borrow() == ParseStatus::Parsing {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1015,16 +1078,26 @@ impl Buffer {
             }
         });
 
-        match cx
+        let started_at = cx.background_executor().now();
+        let timeout = self
+            .sync_parse_timeout
+            .min(cx.default_global::<SyncParseBudget>().remaining(started_at));
+        let result = cx
             .background_executor()
-            .block_with_timeout(self.sync_parse_timeout, parse_task)
-        {
+            .block_with_timeout(timeout, parse_task);
+        let blocked_for = cx
+            .background_executor()
+            .now()
+            .saturating_duration_since(started_at);
+        cx.default_global::<SyncParseBudget>().spent += blocked_for;
+
+        match result {
             Ok(new_syntax_snapshot) => {
                 self.did_finish_parsing(new_syntax_snapshot, cx);
                 return;
             }
             Err(parse_task) => {
-                self.parsing_in_background = true;
+                *self.parse_status.0.borrow_mut() = ParseStatus::Parsing;
                 cx.spawn(move |this, mut cx| async move {
                     let new_syntax_map = parse_task.await;
                     this.update(&mut cx, move |this, cx| {
@@ -1041,7 +1114,7 @@ impl Buffer {
                             || grammar_changed
                             || this.version.changed_since(&parsed_version);
                         this.did_finish_parsing(new_syntax_map, cx);
-                        this.parsing_in_background = false;
+                        *this.parse_status.0.borrow_mut() = ParseStatus::Idle;
                         if parse_again {
                             this.reparse(cx);
                         }
@@ -1495,6 +1568,17 @@ impl Buffer {
                 .map_or(false, |file| file.is_deleted() || !file.is_created())
     }
 
+    /// Checks if invalid UTF-8 was replaced when the buffer's file was loaded,
+    /// so that saving the buffer would overwrite the file's original bytes.
+    pub fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+
+    /// Records whether invalid UTF-8 was replaced when the buffer's file was loaded.
+    pub fn set_lossy(&mut self, is_lossy: bool) {
+        self.is_lossy = is_lossy;
+    }
+
     /// Checks if the buffer and its file have both changed since the buffer
     /// was last saved or reloaded.
     pub fn has_conflict(&self) -> bool {
@@ -1907,6 +1991,9 @@ impl Buffer {
                 );
                 self.text.lamport_clock.observe(lamport_timestamp);
                 self.selections_update_count += 1;
+                cx.emit(Event::RemoteSelectionsChanged {
+                    replica_id: lamport_timestamp.replica_id,
+                });
             }
             Operation::UpdateCompletionTriggers {
                 triggers,
@@ -2434,7 +2521,12 @@ impl BufferSnapshot {
                 .sort_unstable_by_key(|endpoint| (endpoint.offset, !endpoint.is_start));
         }
 
-        BufferChunks::new(self.text.as_rope(), range, syntax, diagnostic_endpoints)
+        let mut chunks =
+            BufferChunks::new(self.text.as_rope(), range, syntax, diagnostic_endpoints);
+        if language_aware {
+            chunks.stale_syntax_ranges = self.syntax.stale_ranges(&self.text);
+        }
+        chunks
     }
 
     /// Invokes the given callback for each line of text in the given range of the buffer.
@@ -2471,6 +2563,22 @@ impl BufferSnapshot {
             .last()
     }
 
+    /// Returns the language and byte range of the innermost injected region, like a
+    /// code block in Markdown, that contains the given position.
+    pub fn injection_at<D: ToOffset>(&self, position: D) -> Option<(Arc<Language>, Range<usize>)> {
+        let offset = position.to_offset(self);
+        self.syntax
+            .layers_for_range(offset..offset, &self.text)
+            .filter(|layer| layer.depth() > 0)
+            .filter_map(|layer| {
+                let range = layer
+                    .included_byte_ranges()
+                    .find(|range| range.start <= offset && offset <= range.end)?;
+                Some((layer.language.clone(), range))
+            })
+            .last()
+    }
+
     /// Returns the main [Language]
     pub fn language(&self) -> Option<&Arc<Language>> {
         self.language.as_ref()
@@ -2948,6 +3056,139 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the range that the given row can be folded to according to the
+    /// language's `folds` query, which runs from the end of the row to the end of
+    /// the largest `@fold` capture that starts on it.
+    pub fn syntax_fold_range(&self, row: u32) -> Option<Range<Point>> {
+        let row_range = Point::new(row, 0)..Point::new(row, self.line_len(row));
+        let mut syntax_matches = self.syntax.matches(row_range.clone(), self, |grammar| {
+            grammar.folds_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut fold_end: Option<Point> = None;
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    if capture.index != config.fold_capture_ix
+                        || capture.node.start_position().row as u32 != row
+                    {
+                        continue;
+                    }
+
+                    // Nodes that end at the start of a line, like sections that own
+                    // their trailing newline, are folded up to the end of the line before.
+                    let mut end = Point::from_ts_point(capture.node.end_position());
+                    if end.column == 0 && end.row > row {
+                        end = Point::new(end.row - 1, self.line_len(end.row - 1));
+                    }
+                    if end.row > row && fold_end.map_or(true, |fold_end| end > fold_end) {
+                        fold_end = Some(end);
+                    }
+                }
+            }
+            syntax_matches.advance();
+        }
+
+        Some(row_range.end..fold_end?)
+    }
+
+    /// Returns the name of the opening tag that ends at the given offset according
+    /// to the language's `tags` query, unless the tag already has a closing tag.
+    pub fn unclosed_tag_ending_at(&self, offset: usize) -> Option<String> {
+        let mut syntax_matches =
+            self.syntax
+                .matches(offset.saturating_sub(1)..offset, self, |grammar| {
+                    grammar.tags_config.as_ref().map(|config| &config.query)
+                });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.tags_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut name_range = None;
+        let mut closed_name_ranges = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let capture_range = |capture_ix: Option<u32>| {
+                    mat.captures
+                        .iter()
+                        .find(|capture| Some(capture.index) == capture_ix)
+                        .map(|capture| capture.node.byte_range())
+                };
+                if let Some((open, name)) =
+                    capture_range(config.open_capture_ix).zip(capture_range(config.name_capture_ix))
+                {
+                    if open.end == offset {
+                        name_range = Some(name);
+                    }
+                }
+                if let Some((open_name, _)) = capture_range(config.open_name_capture_ix)
+                    .zip(capture_range(config.close_name_capture_ix))
+                {
+                    closed_name_ranges.push(open_name);
+                }
+            }
+            syntax_matches.advance();
+        }
+
+        let name_range = name_range?;
+        if closed_name_ranges.contains(&name_range) {
+            return None;
+        }
+        Some(self.text_for_range(name_range).collect())
+    }
+
+    /// Returns the range of the tag name containing the given range, along with the
+    /// range of the other tag's name, when it's the name of an element's opening or
+    /// closing tag according to the language's `tags` query and both names match.
+    pub fn linked_tag_names(&self, range: Range<usize>) -> Option<(Range<usize>, Range<usize>)> {
+        let search_range = range.start.saturating_sub(1)..(range.end + 1).min(self.len());
+        let mut syntax_matches = self.syntax.matches(search_range, self, |grammar| {
+            grammar.tags_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.tags_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut linked_names = None;
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let capture_range = |capture_ix: Option<u32>| {
+                    mat.captures
+                        .iter()
+                        .find(|capture| Some(capture.index) == capture_ix)
+                        .map(|capture| capture.node.byte_range())
+                };
+                if let Some((open_name, close_name)) = capture_range(config.open_name_capture_ix)
+                    .zip(capture_range(config.close_name_capture_ix))
+                {
+                    if open_name.start <= range.start && range.end <= open_name.end {
+                        linked_names = Some((open_name, close_name));
+                    } else if close_name.start <= range.start && range.end <= close_name.end {
+                        linked_names = Some((close_name, open_name));
+                    }
+                }
+            }
+            syntax_matches.advance();
+        }
+
+        let (name, linked_name) = linked_names?;
+        let name_text = self.text_for_range(name.clone()).collect::<String>();
+        let linked_name_text = self.text_for_range(linked_name.clone()).collect::<String>();
+        (name_text == linked_name_text).then_some((name, linked_name))
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
@@ -3019,6 +3260,23 @@ impl BufferSnapshot {
         self.git_diff.hunks_intersecting_range_rev(range, self)
     }
 
+    /// Returns the ranges of the [invisible control characters](is_invisible_control_character)
+    /// in the buffer, skipping a byte order mark at its start.
+    pub fn invisible_control_characters(&self) -> Vec<(Range<usize>, char)> {
+        let mut characters = Vec::new();
+        let mut offset = 0;
+        for chunk in self.as_rope().chunks() {
+            for (ix, c) in chunk.char_indices() {
+                let start = offset + ix;
+                if is_invisible_control_character(c) && !(start == 0 && c == '\u{FEFF}') {
+                    characters.push((start..start + c.len_utf8(), c));
+                }
+            }
+            offset += chunk.len();
+        }
+        characters
+    }
+
     /// Returns if the buffer contains any diagnostics.
     pub fn has_diagnostics(&self) -> bool {
         !self.diagnostics.is_empty()
@@ -3223,6 +3481,7 @@ impl<'a> BufferChunks<'a> {
             hint_depth: 0,
             unnecessary_depth: 0,
             highlights,
+            stale_syntax_ranges: Vec::new(),
         }
     }
 
@@ -3355,6 +3614,18 @@ impl<'a> Iterator for BufferChunks<'a> {
                 }
             }
 
+            let stale_ix = self
+                .stale_syntax_ranges
+                .partition_point(|range| range.end <= chunk_start);
+            if let Some(stale_range) = self.stale_syntax_ranges.get(stale_ix) {
+                if stale_range.start <= chunk_start {
+                    chunk_end = chunk_end.min(stale_range.end);
+                    highlight_id = None;
+                } else {
+                    chunk_end = chunk_end.min(stale_range.start);
+                }
+            }
+
             let slice =
                 &chunk[chunk_start - self.chunks.offset()..chunk_end - self.chunks.offset()];
             self.range.start = chunk_end;
@@ -3536,6 +3807,22 @@ pub(crate) fn contiguous_ranges(
     })
 }
 
+/// Returns whether the character affects how the text around it is displayed while
+/// being invisible itself, like zero-width spaces and bidirectional overrides. These
+/// can make code read differently than it's interpreted.
+pub fn is_invisible_control_character(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{061C}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
 /// Returns the [CharKind] for the given character. When a scope is provided,
 /// the function checks if the character is considered a word character
 /// based on the language scope's word character settings.
@@ -560,6 +560,40 @@ async fn test_reparse(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_stale_syntax_is_unhighlighted(cx: &mut gpui::TestAppContext) {
+    let language = rust_lang()
+        .with_highlights_query("(identifier) @variable")
+        .unwrap();
+    let buffer =
+        cx.new_model(|cx| Buffer::local("fn a() {}", cx).with_language(Arc::new(language), cx));
+    let highlighted_text = |buffer: &Buffer| {
+        let snapshot = buffer.snapshot();
+        snapshot
+            .chunks(0..snapshot.len(), true)
+            .filter(|chunk| chunk.syntax_highlight_id.is_some())
+            .map(|chunk| chunk.text)
+            .collect::<String>()
+    };
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| assert_eq!(highlighted_text(buffer), "a"));
+
+    // While the edit is being parsed in the background, the inserted text isn't
+    // highlighted using the stale syntax tree.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_sync_parse_timeout(Duration::ZERO);
+        buffer.edit([(4..4, "bcd")], None, cx);
+        assert!(buffer.is_parsing());
+        assert!(!highlighted_text(buffer).contains("bcd"));
+    });
+
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_parsing());
+        assert_eq!(highlighted_text(buffer), "abcd");
+    });
+}
+
 #[gpui::test]
 async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     let buffer = cx.new_model(|cx| {
@@ -712,6 +746,71 @@ async fn test_outline_nodes_with_newlines(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+fn test_syntax_fold_range(cx: &mut AppContext) {
+    let text = r#"
+        mod x {
+            fn a() {
+                b();
+            }
+        }
+        fn c() {}
+    "#
+    .unindent();
+
+    let language = rust_lang()
+        .with_folds_query(
+            r#"
+            (mod_item) @fold
+            (function_item) @fold
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    assert_eq!(
+        snapshot.syntax_fold_range(0),
+        Some(Point::new(0, 7)..Point::new(4, 1))
+    );
+    assert_eq!(
+        snapshot.syntax_fold_range(1),
+        Some(Point::new(1, 12)..Point::new(3, 5))
+    );
+    assert_eq!(snapshot.syntax_fold_range(2), None);
+    // Nodes that fit on a single line can't be folded.
+    assert_eq!(snapshot.syntax_fold_range(5), None);
+}
+
+#[gpui::test]
+fn test_tags_query(cx: &mut AppContext) {
+    let text = "<div><p>a</p><br><span>";
+    let language = html_lang()
+        .with_tags_query(
+            r#"
+            (start_tag
+              (tag_name) @name
+              (#not-match? @name "^(br|img)$")) @open
+
+            (_
+              (start_tag (tag_name) @open_name)
+              (end_tag (tag_name) @close_name))
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    assert_eq!(snapshot.unclosed_tag_ending_at(5).as_deref(), Some("div"));
+    assert_eq!(snapshot.unclosed_tag_ending_at(8), None);
+    assert_eq!(snapshot.unclosed_tag_ending_at(17), None);
+    assert_eq!(snapshot.unclosed_tag_ending_at(23).as_deref(), Some("span"));
+
+    assert_eq!(snapshot.linked_tag_names(6..7), Some((6..7, 11..12)));
+    assert_eq!(snapshot.linked_tag_names(12..12), Some((11..12, 6..7)));
+    assert_eq!(snapshot.linked_tag_names(2..2), None);
+}
+
 #[gpui::test]
 async fn test_outline_with_extra_context(cx: &mut gpui::TestAppContext) {
     let language = javascript_lang()
@@ -2005,6 +2104,56 @@ fn test_language_scope_at_with_combined_injections(cx: &mut AppContext) {
     });
 }
 
+#[gpui::test]
+fn test_injection_at(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    cx.new_model(|cx| {
+        let text = r#"
+            <ol>
+            <% people.each do |person| %>
+                <li><%= person.name %></li>
+            <% end %>
+            </ol>
+        "#
+        .unindent();
+
+        let language_registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+        language_registry.add(Arc::new(ruby_lang()));
+        language_registry.add(Arc::new(html_lang()));
+        language_registry.add(Arc::new(erb_lang()));
+
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language_registry(language_registry.clone());
+        buffer.set_language(
+            language_registry
+                .language_for_name("ERB")
+                .now_or_never()
+                .unwrap()
+                .ok(),
+            cx,
+        );
+
+        let snapshot = buffer.snapshot();
+        // Combined injections are split back into the regions they came from.
+        let (language, range) = snapshot.injection_at(Point::new(2, 15)).unwrap();
+        assert_eq!(language.name().as_ref(), "Ruby");
+        assert_eq!(
+            snapshot.text_for_range(range).collect::<String>().trim(),
+            "person.name"
+        );
+
+        let (language, range) = snapshot.injection_at(Point::new(1, 5)).unwrap();
+        assert_eq!(language.name().as_ref(), "Ruby");
+        assert_eq!(
+            snapshot.text_for_range(range).collect::<String>().trim(),
+            "people.each do |person|"
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::AppContext) {
     let mut now = Instant::now();
@@ -2355,6 +2504,21 @@ fn test_contiguous_ranges() {
     );
 }
 
+#[gpui::test]
+fn test_invisible_control_characters(cx: &mut AppContext) {
+    let text = "\u{FEFF}let access = \"user\u{202E} \u{2066}// admin\u{2069}\u{2066}\";\nlet a\u{200B}b = 1;\u{00A0}\n";
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let characters = snapshot.invisible_control_characters();
+    assert_eq!(
+        characters.iter().map(|(_, c)| *c).collect::<Vec<_>>(),
+        ['\u{202E}', '\u{2066}', '\u{2069}', '\u{2066}', '\u{200B}']
+    );
+    for (range, c) in characters {
+        assert_eq!(&text[range], c.to_string());
+    }
+}
+
 #[gpui::test(iterations = 500)]
 fn test_trailing_whitespace_ranges(mut rng: StdRng) {
     // Generate a random multi-line string containing
@@ -0,0 +1,31 @@
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::LspAdapterDelegate;
+
+/// How long a [`FormatProvider`] or [`LintProvider`](crate::LintProvider) may run
+/// before it's interrupted and its result is discarded.
+pub const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Formats the buffers of a language without a language server, e.g. on behalf
+/// of an extension.
+#[async_trait]
+pub trait FormatProvider: Send + Sync {
+    /// The name of the formatter, used to identify it in the [`LanguageRegistry`](crate::LanguageRegistry)
+    /// and in error messages.
+    fn name(&self) -> Arc<str>;
+
+    /// Formats the given text of the file at the given worktree-relative path.
+    ///
+    /// If a byte range is given, only the text within that range should be formatted,
+    /// and the returned text replaces it. Otherwise, the returned text replaces the whole file.
+    async fn format(
+        &self,
+        path: Arc<Path>,
+        text: String,
+        range: Option<Range<usize>>,
+        delegate: Arc<dyn LspAdapterDelegate>,
+    ) -> Result<String>;
+}
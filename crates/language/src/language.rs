@@ -8,9 +8,11 @@
 //! Notably we do *not* assign a single language to a single file; in real world a single file can consist of multiple programming languages - HTML is a good example of that - and `language` crate tends to reflect that status quo in its API.
 mod buffer;
 mod diagnostic_set;
+mod format_provider;
 mod highlight_map;
 mod language_registry;
 pub mod language_settings;
+mod lint_provider;
 mod outline;
 pub mod proto;
 mod syntax_map;
@@ -64,10 +66,12 @@ use util::http::HttpClient;
 pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
+pub use format_provider::{FormatProvider, PROVIDER_TIMEOUT};
 pub use language_registry::{
     LanguageNotFound, LanguageQueries, LanguageRegistry, LanguageServerBinaryStatus,
     PendingLanguageServer, QUERY_FILENAME_PREFIXES,
 };
+pub use lint_provider::{LintDiagnostic, LintProvider};
 pub use lsp::LanguageServerId;
 pub use outline::{Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
@@ -828,6 +832,8 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) folds_config: Option<FoldConfig>,
+    pub(crate) tags_config: Option<TagConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -874,6 +880,22 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct FoldConfig {
+    pub query: Query,
+    pub fold_capture_ix: u32,
+}
+
+struct TagConfig {
+    pub query: Query,
+    /// An opening tag, which is closed when typing the `>` that ends it.
+    pub open_capture_ix: Option<u32>,
+    /// The name of an `open` tag.
+    pub name_capture_ix: Option<u32>,
+    /// The names of an element's opening and closing tags, which are renamed together.
+    pub open_name_capture_ix: Option<u32>,
+    pub close_name_capture_ix: Option<u32>,
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -915,6 +937,8 @@ impl Language {
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    folds_config: None,
+                    tags_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -970,6 +994,16 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.tags {
+            self = self
+                .with_tags_query(query.as_ref())
+                .context("Error loading tags query")?;
+        }
         Ok(self)
     }
 
@@ -1241,6 +1275,55 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_folds_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        get_capture_indices(&query, &mut [("fold", &mut fold_capture_ix)]);
+
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.folds_config = Some(FoldConfig {
+                query,
+                fold_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_tags_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut open_capture_ix = None;
+        let mut name_capture_ix = None;
+        let mut open_name_capture_ix = None;
+        let mut close_name_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("open", &mut open_capture_ix),
+                ("name", &mut name_capture_ix),
+                ("open_name", &mut open_name_capture_ix),
+                ("close_name", &mut close_name_capture_ix),
+            ],
+        );
+
+        grammar.tags_config = Some(TagConfig {
+            query,
+            open_capture_ix,
+            name_capture_ix,
+            open_name_capture_ix,
+            close_name_capture_ix,
+        });
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
@@ -1329,6 +1412,15 @@ impl LanguageScope {
         self.language.config.collapsed_placeholder.as_ref()
     }
 
+    /// Returns the name of the override this scope is in, like `string` or `comment`,
+    /// as captured by the language's overrides query.
+    pub fn override_name(&self) -> Option<&str> {
+        let id = self.override_id?;
+        let grammar = self.language.grammar.as_ref()?;
+        let override_config = grammar.override_config.as_ref()?;
+        override_config.values.get(&id).map(|e| e.0.as_str())
+    }
+
     /// Returns line prefix that is inserted in e.g. line continuations or
     /// in `toggle comments` action.
     pub fn line_comment_prefixes(&self) -> Option<&Vec<Arc<str>>> {
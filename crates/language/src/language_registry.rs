@@ -3,8 +3,8 @@ use crate::{
         all_language_settings, AllLanguageSettingsContent, LanguageSettingsContent,
     },
     task_context::ContextProvider,
-    CachedLspAdapter, File, Language, LanguageConfig, LanguageId, LanguageMatcher,
-    LanguageServerName, LspAdapter, LspAdapterDelegate, PARSER, PLAIN_TEXT,
+    CachedLspAdapter, File, FormatProvider, Language, LanguageConfig, LanguageId, LanguageMatcher,
+    LanguageServerName, LintProvider, LspAdapter, LspAdapterDelegate, PARSER, PLAIN_TEXT,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{hash_map, HashMap};
@@ -46,6 +46,8 @@ struct LanguageRegistryState {
     available_languages: Vec<AvailableLanguage>,
     grammars: HashMap<Arc<str>, AvailableGrammar>,
     lsp_adapters: HashMap<Arc<str>, Vec<Arc<CachedLspAdapter>>>,
+    format_providers: HashMap<Arc<str>, Vec<Arc<dyn FormatProvider>>>,
+    lint_providers: HashMap<Arc<str>, Vec<Arc<dyn LintProvider>>>,
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
@@ -122,6 +124,8 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("injections", |q| &mut q.injections),
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
+    ("folds", |q| &mut q.folds),
+    ("tags", |q| &mut q.tags),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -135,6 +139,8 @@ pub struct LanguageQueries {
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
+    pub folds: Option<Cow<'static, str>>,
+    pub tags: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -153,6 +159,8 @@ impl LanguageRegistry {
                 language_settings: Default::default(),
                 loading_languages: Default::default(),
                 lsp_adapters: Default::default(),
+                format_providers: Default::default(),
+                lint_providers: Default::default(),
                 subscription: watch::channel(),
                 theme: Default::default(),
                 version: 0,
@@ -203,6 +211,59 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    pub fn register_format_provider(
+        &self,
+        language_name: Arc<str>,
+        provider: Arc<dyn FormatProvider>,
+    ) {
+        self.state
+            .write()
+            .format_providers
+            .entry(language_name)
+            .or_default()
+            .push(provider);
+    }
+
+    pub fn remove_format_provider(&self, language_name: &str, name: &str) {
+        if let Some(providers) = self.state.write().format_providers.get_mut(language_name) {
+            providers.retain(|provider| provider.name().as_ref() != name);
+        }
+    }
+
+    /// Returns the most recently registered format provider for the given language.
+    pub fn format_provider(&self, language_name: &str) -> Option<Arc<dyn FormatProvider>> {
+        self.state
+            .read()
+            .format_providers
+            .get(language_name)?
+            .last()
+            .cloned()
+    }
+
+    pub fn register_lint_provider(&self, language_name: Arc<str>, provider: Arc<dyn LintProvider>) {
+        self.state
+            .write()
+            .lint_providers
+            .entry(language_name)
+            .or_default()
+            .push(provider);
+    }
+
+    pub fn remove_lint_provider(&self, language_name: &str, name: &str) {
+        if let Some(providers) = self.state.write().lint_providers.get_mut(language_name) {
+            providers.retain(|provider| provider.name().as_ref() != name);
+        }
+    }
+
+    pub fn lint_providers(&self, language_name: &str) -> Vec<Arc<dyn LintProvider>> {
+        self.state
+            .read()
+            .lint_providers
+            .get(language_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     #[cfg(any(feature = "test-support", test))]
     pub fn register_test_language(&self, config: LanguageConfig) {
         self.register_language(
@@ -703,6 +764,7 @@ impl LanguageRegistry {
         adapter: Arc<CachedLspAdapter>,
         root_path: Arc<Path>,
         delegate: Arc<dyn LspAdapterDelegate>,
+        environment: HashMap<String, String>,
         cx: &mut AppContext,
     ) -> Option<PendingLanguageServer> {
         let server_id = self.state.write().next_language_server_id();
@@ -741,7 +803,14 @@ impl LanguageRegistry {
 
                 delegate.update_status(adapter.name.clone(), LanguageServerBinaryStatus::None);
 
-                let binary = binary_result?;
+                let mut binary = binary_result?;
+                if !environment.is_empty() {
+                    // The project's environment overrides the one the adapter computed.
+                    binary
+                        .env
+                        .get_or_insert_with(HashMap::default)
+                        .extend(environment);
+                }
                 let options = adapter
                     .adapter
                     .clone()
@@ -4,7 +4,7 @@ use crate::{File, Language, LanguageServerName};
 use anyhow::Result;
 use collections::{HashMap, HashSet};
 use globset::GlobMatcher;
-use gpui::AppContext;
+use gpui::{AppContext, FontFeatures};
 use itertools::{Either, Itertools};
 use schemars::{
     schema::{InstanceType, ObjectValidation, Schema, SchemaObject},
@@ -77,6 +77,9 @@ pub struct LanguageSettings {
     pub show_wrap_guides: bool,
     /// Character counts at which to show wrap guides in the editor.
     pub wrap_guides: Vec<usize>,
+    /// Whether to show a wrap guide at the line length configured for the
+    /// buffer's formatter, like `max_width` in `rustfmt.toml`.
+    pub wrap_guide_from_formatter_config: bool,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
@@ -103,7 +106,8 @@ pub struct LanguageSettings {
     /// Controls whether Copilot provides suggestion immediately (true)
     /// or waits for a `copilot::Toggle` (false).
     pub show_copilot_suggestions: bool,
-    /// Whether to show tabs and spaces in the editor.
+    /// Whether to show tabs and spaces in the editor. Zero-width and bidirectional
+    /// control characters are shown regardless.
     pub show_whitespaces: ShowWhitespaceSetting,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     pub extend_comment_on_newline: bool,
@@ -113,8 +117,24 @@ pub struct LanguageSettings {
     pub use_autoclose: bool,
     // Controls how the editor handles the autoclosed characters.
     pub always_treat_brackets_as_autoclosed: bool,
+    /// Whether to color nested brackets by their depth.
+    pub rainbow_brackets: bool,
+    /// The groups of words that `editor::Increment` and `editor::Decrement` cycle
+    /// through, like `true` and `false`.
+    pub toggle_words: Vec<Vec<String>>,
     /// Which code actions to run on save
     pub code_actions_on_format: HashMap<String, bool>,
+    /// The font family to use for buffers in this language, instead of the
+    /// `buffer_font_family`.
+    pub buffer_font_family: Option<String>,
+    /// The font size to use for buffers in this language, instead of the
+    /// `buffer_font_size`.
+    pub buffer_font_size: Option<f32>,
+    /// The font weight to use for buffers in this language.
+    pub buffer_font_weight: Option<f32>,
+    /// The OpenType features to enable for buffers in this language, instead
+    /// of the `buffer_font_features`.
+    pub buffer_font_features: Option<FontFeatures>,
 }
 
 impl LanguageSettings {
@@ -230,8 +250,16 @@ pub struct LanguageSettingsContent {
     /// Character counts at which to show wrap guides in the editor.
     ///
     /// Default: []
-    #[serde(default)]
+    #[serde(default, alias = "rulers")]
     pub wrap_guides: Option<Vec<usize>>,
+    /// Whether to show a wrap guide at the maximum line length configured for
+    /// the formatter of the buffer, read from the nearest `rustfmt.toml` or
+    /// `.rustfmt.toml` for Rust and the nearest prettier config for languages
+    /// formatted with prettier.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub wrap_guide_from_formatter_config: Option<bool>,
     /// Whether or not to perform a buffer format before saving.
     ///
     /// Default: on
@@ -295,6 +323,7 @@ pub struct LanguageSettingsContent {
     pub inlay_hints: Option<InlayHintSettings>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
+    /// Tags are closed as well in languages that have a `tags` query.
     ///
     /// Default: true
     pub use_autoclose: Option<bool>,
@@ -306,11 +335,46 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: false
     pub always_treat_brackets_as_autoclosed: Option<bool>,
+    /// Whether to color nested brackets, parentheses and braces according to
+    /// their nesting depth, cycling through the theme's accent colors.
+    ///
+    /// Default: false
+    pub rainbow_brackets: Option<bool>,
+    /// The groups of words that incrementing or decrementing the word under the
+    /// cursor cycles through, like `["true", "false"]` or `["&&", "||"]`.
+    ///
+    /// Default: [["true", "false"], ["&&", "||"]]
+    pub toggle_words: Option<Vec<Vec<String>>>,
     /// Which code actions to run on save after the formatter.
     /// These are not run if formatting is off.
     ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
     pub code_actions_on_format: Option<HashMap<String, bool>>,
+    /// The name of a font to use for rendering buffers in this language,
+    /// overriding `buffer_font_family`.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub buffer_font_family: Option<String>,
+    /// The font size for rendering buffers in this language, overriding
+    /// `buffer_font_size`.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub buffer_font_size: Option<f32>,
+    /// The font weight for rendering buffers in this language, from 100 to
+    /// 900, where 400 is normal.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub buffer_font_weight: Option<f32>,
+    /// The OpenType features to enable for rendering buffers in this
+    /// language, overriding `buffer_font_features`. For example,
+    /// `{"calt": false}` disables ligatures.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub buffer_font_features: Option<FontFeatures>,
 }
 
 /// The contents of the GitHub Copilot settings.
@@ -371,19 +435,25 @@ pub enum ShowWhitespaceSetting {
     /// Do not draw any tabs or spaces.
     None,
     /// Draw all invisible symbols.
+    #[serde(alias = "always")]
     All,
+    /// Draw all invisible symbols except for single spaces between words.
+    Boundary,
 }
 
 /// Controls which formatter should be used when formatting code.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Formatter {
-    /// Format files using Zed's Prettier integration (if applicable),
-    /// or falling back to formatting via language server.
+    /// Format files using a formatter provided by an extension or Zed's
+    /// Prettier integration (if applicable), or falling back to formatting
+    /// via language server.
     #[default]
     Auto,
     /// Format code using the current language server.
     LanguageServer,
+    /// Format code using a formatter provided by an extension.
+    Extension,
     /// Format code using Zed's Prettier integration.
     Prettier,
     /// Format code using an external command.
@@ -687,8 +757,14 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.always_treat_brackets_as_autoclosed,
         src.always_treat_brackets_as_autoclosed,
     );
+    merge(&mut settings.rainbow_brackets, src.rainbow_brackets);
+    merge(&mut settings.toggle_words, src.toggle_words.clone());
     merge(&mut settings.show_wrap_guides, src.show_wrap_guides);
     merge(&mut settings.wrap_guides, src.wrap_guides.clone());
+    merge(
+        &mut settings.wrap_guide_from_formatter_config,
+        src.wrap_guide_from_formatter_config,
+    );
     merge(
         &mut settings.code_actions_on_format,
         src.code_actions_on_format.clone(),
@@ -724,6 +800,22 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(
+        &mut settings.buffer_font_family,
+        src.buffer_font_family.clone().map(Some),
+    );
+    merge(
+        &mut settings.buffer_font_size,
+        src.buffer_font_size.map(Some),
+    );
+    merge(
+        &mut settings.buffer_font_weight,
+        src.buffer_font_weight.map(Some),
+    );
+    merge(
+        &mut settings.buffer_font_features,
+        src.buffer_font_features.map(Some),
+    );
 }
 
 #[cfg(test)]
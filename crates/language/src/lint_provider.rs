@@ -0,0 +1,34 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use lsp::DiagnosticSeverity;
+
+use crate::LspAdapterDelegate;
+
+/// A diagnostic reported by a [`LintProvider`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintDiagnostic {
+    /// The byte range of the diagnostic within the linted text.
+    pub range: Range<usize>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub code: Option<String>,
+}
+
+/// Reports diagnostics for the buffers of a language without a language server,
+/// e.g. on behalf of an extension.
+#[async_trait]
+pub trait LintProvider: Send + Sync {
+    /// The name of the linter, used to identify it in the [`LanguageRegistry`](crate::LanguageRegistry)
+    /// and as the source of its diagnostics.
+    fn name(&self) -> Arc<str>;
+
+    /// Lints the given text of the file at the given worktree-relative path.
+    async fn lint(
+        &self,
+        path: Arc<Path>,
+        text: String,
+        delegate: Arc<dyn LspAdapterDelegate>,
+    ) -> Result<Vec<LintDiagnostic>>;
+}
@@ -5,7 +5,7 @@ use std::{ops::Range, path::PathBuf};
 
 use crate::{HighlightId, Language, LanguageRegistry};
 use gpui::{px, FontStyle, FontWeight, HighlightStyle, StrikethroughStyle, UnderlineStyle};
-use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Parser, Tag, TagEnd};
 
 /// Parsed Markdown content.
 #[derive(Debug, Clone, Default)]
@@ -18,6 +18,8 @@ pub struct ParsedMarkdown {
     pub region_ranges: Vec<Range<usize>>,
     /// The regions of the Markdown document.
     pub regions: Vec<ParsedRegion>,
+    /// The images in the Markdown document, which are displayed between its lines.
+    pub images: Vec<ParsedImage>,
 }
 
 /// A run of highlighted Markdown text.
@@ -88,8 +90,20 @@ pub struct ParsedRegion {
     pub link: Option<Link>,
 }
 
-/// A Markdown link.
+/// An image in a Markdown document.
 #[derive(Debug, Clone)]
+pub struct ParsedImage {
+    /// The offset in the text where the image is displayed, which is always at
+    /// the start of a line.
+    pub offset: usize,
+    /// Where the image is loaded from.
+    pub source: Link,
+    /// The image's alternative text.
+    pub alt_text: String,
+}
+
+/// A Markdown link.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Link {
     /// A link to a webpage.
     Web {
@@ -105,11 +119,11 @@ pub enum Link {
 
 impl Link {
     fn identify(text: String) -> Option<Link> {
-        if text.starts_with("http") {
+        if text.starts_with("http") || text.starts_with("mailto:") {
             return Some(Link::Web { url: text });
         }
 
-        let path = PathBuf::from(text);
+        let path = PathBuf::from(text.strip_prefix("file://").unwrap_or(&text));
         if path.is_absolute() {
             return Some(Link::Path { path });
         }
@@ -128,6 +142,7 @@ pub async fn parse_markdown(
     let mut highlights = Vec::new();
     let mut region_ranges = Vec::new();
     let mut regions = Vec::new();
+    let mut images = Vec::new();
 
     parse_markdown_block(
         markdown,
@@ -137,6 +152,7 @@ pub async fn parse_markdown(
         &mut highlights,
         &mut region_ranges,
         &mut regions,
+        &mut images,
     )
     .await;
 
@@ -145,6 +161,7 @@ pub async fn parse_markdown(
         highlights,
         region_ranges,
         regions,
+        images,
     }
 }
 
@@ -157,6 +174,7 @@ pub async fn parse_markdown_block(
     highlights: &mut Vec<(Range<usize>, MarkdownHighlight)>,
     region_ranges: &mut Vec<Range<usize>>,
     regions: &mut Vec<ParsedRegion>,
+    images: &mut Vec<ParsedImage>,
 ) {
     let mut bold_depth = 0;
     let mut italic_depth = 0;
@@ -164,12 +182,65 @@ pub async fn parse_markdown_block(
     let mut link_url = None;
     let mut current_language = None;
     let mut list_stack = Vec::new();
+    let mut table: Option<ParsedTable> = None;
+    // The source and alternative text of the image being parsed.
+    let mut image: Option<(String, String)> = None;
 
     let mut options = pulldown_cmark::Options::all();
     options.remove(pulldown_cmark::Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
 
     for event in Parser::new_ext(markdown, options) {
         let prev_len = text.len();
+        if let Some(parsed_table) = &mut table {
+            // Tables are laid out once all of their cells are known, and their
+            // cells are displayed without styling.
+            match event {
+                Event::Text(t) | Event::Code(t) => parsed_table.push_str(t.as_ref()),
+                Event::SoftBreak | Event::HardBreak => parsed_table.push_str(" "),
+                Event::Start(Tag::TableRow) => parsed_table.rows.push(Vec::new()),
+                Event::Start(Tag::TableCell) => parsed_table.push_cell(),
+                Event::End(TagEnd::TableHead) => parsed_table.header_rows = parsed_table.rows.len(),
+                Event::End(TagEnd::Table) => {
+                    let layout = table_layout(table.take().unwrap());
+                    text.push_str(&layout.text);
+                    if let Some(header) = layout.header {
+                        highlights.push((
+                            prev_len + header.start..prev_len + header.end,
+                            MarkdownHighlight::Style(MarkdownHighlightStyle {
+                                weight: FontWeight::BOLD,
+                                ..Default::default()
+                            }),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some((_, alt_text)) = &mut image {
+            match event {
+                Event::Text(t) | Event::Code(t) => alt_text.push_str(t.as_ref()),
+                Event::End(TagEnd::Image) => {
+                    let (url, alt_text) = image.take().unwrap();
+                    if let Some(source) = Link::identify(url) {
+                        if !text.is_empty() && !text.ends_with('\n') {
+                            text.push('\n');
+                        }
+                        images.push(ParsedImage {
+                            offset: text.len(),
+                            source,
+                            alt_text,
+                        });
+                    } else {
+                        text.push_str(&alt_text);
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
         match event {
             Event::Text(t) => {
                 if let Some(language) = &current_language {
@@ -250,16 +321,40 @@ pub async fn parse_markdown_block(
 
                 Tag::CodeBlock(kind) => {
                     new_paragraph(text, &mut list_stack);
-                    current_language = if let CodeBlockKind::Fenced(language) = kind {
+                    // Info strings can have attributes after the language, like
+                    // `rust,ignore` or `python title="example.py"`.
+                    let fence_language = match &kind {
+                        CodeBlockKind::Fenced(info) => info
+                            .split(|c: char| c == ',' || c.is_whitespace())
+                            .next()
+                            .unwrap_or_default(),
+                        CodeBlockKind::Indented => "",
+                    };
+                    current_language = if fence_language.is_empty() {
+                        language.clone()
+                    } else {
                         language_registry
-                            .language_for_name_or_extension(language.as_ref())
+                            .language_for_name_or_extension(fence_language)
                             .await
                             .ok()
-                    } else {
-                        language.clone()
                     }
                 }
 
+                Tag::Table(alignments) => {
+                    new_paragraph(text, &mut list_stack);
+                    table = Some(ParsedTable {
+                        alignments,
+                        ..Default::default()
+                    });
+                }
+
+                Tag::Image {
+                    link_type: _,
+                    dest_url,
+                    title: _,
+                    id: _,
+                } => image = Some((dest_url.to_string(), String::new())),
+
                 Tag::Emphasis => italic_depth += 1,
 
                 Tag::Strong => bold_depth += 1,
@@ -320,6 +415,85 @@ pub async fn parse_markdown_block(
     }
 }
 
+/// The cells of a Markdown table, which is displayed as aligned columns of text.
+#[derive(Default)]
+struct ParsedTable {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    /// How many of the rows are part of the header.
+    header_rows: usize,
+}
+
+impl ParsedTable {
+    fn push_cell(&mut self) {
+        if self.rows.is_empty() {
+            // The header's cells aren't wrapped in a row.
+            self.rows.push(Vec::new());
+        }
+        self.rows.last_mut().unwrap().push(String::new());
+    }
+
+    fn push_str(&mut self, text: &str) {
+        if let Some(cell) = self.rows.last_mut().and_then(|row| row.last_mut()) {
+            cell.push_str(text);
+        }
+    }
+}
+
+struct TableLayout {
+    text: String,
+    /// The range of the text containing the header's rows.
+    header: Option<Range<usize>>,
+}
+
+fn table_layout(table: ParsedTable) -> TableLayout {
+    let column_count = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut widths = vec![0; column_count];
+    for row in &table.rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.trim().chars().count());
+        }
+    }
+
+    let mut text = String::new();
+    let mut header = None;
+    for (row_ix, row) in table.rows.iter().enumerate() {
+        if row_ix > 0 {
+            text.push('\n');
+        }
+        if row_ix == table.header_rows && row_ix > 0 {
+            header = Some(0..text.len() - 1);
+            let separator = widths
+                .iter()
+                .map(|width| "─".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("─┼─");
+            text.push_str(&separator);
+            text.push('\n');
+        }
+
+        let mut line = String::new();
+        for (column_ix, width) in widths.iter().enumerate() {
+            if column_ix > 0 {
+                line.push_str(" │ ");
+            }
+            let cell = row.get(column_ix).map_or("", |cell| cell.trim());
+            let padding = width - cell.chars().count();
+            let (left_padding, right_padding) = match table.alignments.get(column_ix) {
+                Some(Alignment::Right) => (padding, 0),
+                Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                _ => (0, padding),
+            };
+            line.extend(std::iter::repeat(' ').take(left_padding));
+            line.push_str(cell);
+            line.extend(std::iter::repeat(' ').take(right_padding));
+        }
+        text.push_str(line.trim_end());
+    }
+
+    TableLayout { text, header }
+}
+
 /// Appends a highlighted run of text to the provided `text` buffer.
 pub fn highlight_code(
     text: &mut String,
@@ -363,6 +537,61 @@ pub fn new_paragraph(text: &mut String, list_stack: &mut Vec<(Option<u64>, bool)
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_tables(cx: &mut TestAppContext) {
+        let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        let input = "
+| Name | Size |
+|------|-----:|
+| a | 1 |
+| long `name` | 100 |
+";
+
+        let parsed = parse_markdown(input, &language_registry, None).await;
+        let header = "Name      │ Size";
+        assert_eq!(
+            parsed.text,
+            [
+                header,
+                "──────────┼─────",
+                "a         │    1",
+                "long name │  100",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            parsed.highlights,
+            [(
+                0..header.len(),
+                MarkdownHighlight::Style(MarkdownHighlightStyle {
+                    weight: FontWeight::BOLD,
+                    ..Default::default()
+                })
+            )]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_images(cx: &mut TestAppContext) {
+        let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        let input = "See ![the logo](https://example.com/logo.png) and ![a diagram](diagram.png).";
+
+        let parsed = parse_markdown(input, &language_registry, None).await;
+        // Images that can't be loaded are replaced with their alternative text.
+        assert_eq!(parsed.text, "See \n and a diagram.");
+        assert_eq!(parsed.images.len(), 1);
+        assert_eq!(parsed.images[0].offset, "See \n".len());
+        assert_eq!(parsed.images[0].alt_text, "the logo");
+        assert_eq!(
+            parsed.images[0].source,
+            Link::Web {
+                url: "https://example.com/logo.png".into()
+            }
+        );
+    }
 
     #[test]
     fn test_dividers() {
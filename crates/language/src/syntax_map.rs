@@ -844,6 +844,26 @@ impl SyntaxSnapshot {
     pub fn language_registry_version(&self) -> usize {
         self.language_registry_version
     }
+
+    /// The ranges of text inserted since this snapshot was last parsed, whose syntax
+    /// trees were only interpolated and so may not match the text.
+    pub fn stale_ranges(&self, text: &BufferSnapshot) -> Vec<Range<usize>> {
+        if !text.version().changed_since(&self.parsed_version) {
+            return Vec::new();
+        }
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for edit in text.edits_since::<usize>(&self.parsed_version) {
+            if edit.new.is_empty() {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end >= edit.new.start => last.end = last.end.max(edit.new.end),
+                _ => ranges.push(edit.new),
+            }
+        }
+        ranges
+    }
 }
 
 impl<'a> SyntaxMapCaptures<'a> {
@@ -1184,6 +1204,16 @@ fn parse_text(
 }
 
 #[allow(clippy::too_many_arguments)]
+/// Returns the language named by an injection's `@language` capture, ignoring the
+/// braces and options that surround it in code cells like ```` ```{r, echo=FALSE} ````.
+fn injection_language_name(name: &str) -> &str {
+    let name = name.trim();
+    let name = name.strip_prefix('{').unwrap_or(name);
+    name.split(|c: char| c == ',' || c == '}' || c.is_whitespace())
+        .next()
+        .unwrap_or(name)
+}
+
 fn get_injections(
     config: &InjectionConfig,
     text: &BufferSnapshot,
@@ -1247,9 +1277,10 @@ fn get_injections(
             {
                 step_range.start = cmp::min(content_range.start, language_node.start_byte());
                 step_range.end = cmp::max(content_range.end, language_node.end_byte());
-                language_name = Some(Cow::Owned(
-                    text.text_for_range(language_node.byte_range()).collect(),
-                ))
+                let name = text
+                    .text_for_range(language_node.byte_range())
+                    .collect::<String>();
+                language_name = Some(Cow::Owned(injection_language_name(&name).to_string()))
             };
 
             if let Some(language_name) = language_name {
@@ -1469,6 +1500,23 @@ impl<'a> SyntaxLayer<'a> {
             .root_node_with_offset(self.offset.0, self.offset.1)
     }
 
+    /// Returns how deeply this layer is nested in injections, which is zero for the
+    /// buffer's own language.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the byte ranges of the buffer that this layer was parsed from. These
+    /// are disjoint when an injection is interrupted, like a template literal by its
+    /// interpolations, or is combined from several nodes.
+    pub fn included_byte_ranges(&self) -> impl Iterator<Item = Range<usize>> + 'a {
+        let offset = self.offset.0;
+        self.tree
+            .included_ranges()
+            .into_iter()
+            .map(move |range| offset + range.start_byte..offset + range.end_byte)
+    }
+
     pub(crate) fn override_id(&self, offset: usize, text: &text::BufferSnapshot) -> Option<u32> {
         let text = TextProvider(text.as_rope());
         let config = self.language.grammar.as_ref()?.override_config.as_ref()?;
@@ -0,0 +1,28 @@
+(comment) @comment
+(generated_comment) @comment
+(title) @title
+(branch) @link_uri
+(change) @keyword
+(filepath) @string.special
+(arrow) @punctuation.delimiter
+
+(subject) @title
+
+; Conventional commit prefixes, such as `feat(editor)!:`
+(prefix
+  (type) @keyword)
+(prefix
+  (scope) @variable.special)
+(prefix
+  [
+    "("
+    ")"
+    ":"
+  ] @punctuation.delimiter)
+(prefix
+  "!" @punctuation.special)
+
+(trailer
+  (token) @label)
+(breaking_change
+  (token) @emphasis.strong)
@@ -0,0 +1,6 @@
+(jsx_opening_element
+  name: (_) @name) @open
+
+(jsx_element
+  (jsx_opening_element name: (_) @open_name)
+  (jsx_closing_element name: (_) @close_name))
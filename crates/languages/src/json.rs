@@ -1,3 +1,4 @@
+use crate::json_schemas::{json_schema_associations, CatalogSchema, SchemaRegistry};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use collections::HashMap;
@@ -27,19 +28,31 @@ fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
 pub struct JsonLspAdapter {
     node: Arc<dyn NodeRuntime>,
     languages: Arc<LanguageRegistry>,
+    schema_registry: Arc<SchemaRegistry>,
     workspace_config: OnceLock<Value>,
 }
 
 impl JsonLspAdapter {
-    pub fn new(node: Arc<dyn NodeRuntime>, languages: Arc<LanguageRegistry>) -> Self {
+    pub fn new(
+        node: Arc<dyn NodeRuntime>,
+        languages: Arc<LanguageRegistry>,
+        schema_registry: Arc<SchemaRegistry>,
+    ) -> Self {
         Self {
             node,
             languages,
+            schema_registry,
             workspace_config: Default::default(),
         }
     }
 
-    fn get_workspace_config(language_names: Vec<String>, cx: &mut AppContext) -> Value {
+    /// Returns the configuration with the schemas of Zed's settings, keymap and
+    /// tasks files, followed by those of the JSON Schema Store catalog.
+    fn get_workspace_config(
+        language_names: Vec<String>,
+        catalog: &[CatalogSchema],
+        cx: &mut AppContext,
+    ) -> Value {
         let action_names = cx.all_action_names();
         let staff_mode = cx.is_staff();
 
@@ -53,31 +66,33 @@ impl JsonLspAdapter {
             cx,
         );
         let tasks_schema = task::TaskTemplates::generate_json_schema();
-        serde_json::json!({
+        let mut schemas = vec![
+            json!({
+                "fileMatch": [
+                    schema_file_match(&paths::SETTINGS),
+                    &*paths::LOCAL_SETTINGS_RELATIVE_PATH,
+                ],
+                "schema": settings_schema,
+            }),
+            json!({
+                "fileMatch": [schema_file_match(&paths::KEYMAP)],
+                "schema": KeymapFile::generate_json_schema(&action_names),
+            }),
+            json!({
+                "fileMatch": [
+                    schema_file_match(&paths::TASKS),
+                    &*paths::LOCAL_TASKS_RELATIVE_PATH,
+                ],
+                "schema": tasks_schema,
+            }),
+        ];
+        schemas.extend(json_schema_associations(catalog));
+        json!({
             "json": {
                 "format": {
                     "enable": true,
                 },
-                "schemas": [
-                    {
-                        "fileMatch": [
-                            schema_file_match(&paths::SETTINGS),
-                            &*paths::LOCAL_SETTINGS_RELATIVE_PATH,
-                        ],
-                        "schema": settings_schema,
-                    },
-                    {
-                        "fileMatch": [schema_file_match(&paths::KEYMAP)],
-                        "schema": KeymapFile::generate_json_schema(&action_names),
-                    },
-                    {
-                        "fileMatch": [
-                            schema_file_match(&paths::TASKS),
-                            &*paths::LOCAL_TASKS_RELATIVE_PATH,
-                        ],
-                        "schema": tasks_schema,
-                    }
-                ]
+                "schemas": schemas,
             }
         })
     }
@@ -154,12 +169,18 @@ impl LspAdapter for JsonLspAdapter {
 
     async fn workspace_configuration(
         self: Arc<Self>,
-        _: &Arc<dyn LspAdapterDelegate>,
+        delegate: &Arc<dyn LspAdapterDelegate>,
         cx: &mut AsyncAppContext,
     ) -> Result<Value> {
+        if let Some(config) = self.workspace_config.get() {
+            return Ok(config.clone());
+        }
+        let catalog = self.schema_registry.catalog(delegate.http_client()).await;
         cx.update(|cx| {
             self.workspace_config
-                .get_or_init(|| Self::get_workspace_config(self.languages.language_names(), cx))
+                .get_or_init(|| {
+                    Self::get_workspace_config(self.languages.language_names(), &catalog, cx)
+                })
                 .clone()
         })
     }
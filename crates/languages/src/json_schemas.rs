@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Context, Result};
+use futures::{lock::Mutex, AsyncReadExt};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use smol::fs;
+use std::sync::Arc;
+use util::{http::HttpClient, paths, ResultExt};
+
+/// The catalog of the schemas that the JSON Schema Store knows about, along with
+/// the names of the files that each applies to.
+const SCHEMA_STORE_CATALOG_URL: &str = "https://www.schemastore.org/api/json/catalog.json";
+
+/// A schema of the JSON Schema Store catalog.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CatalogSchema {
+    pub name: String,
+    pub url: String,
+    /// Globs of the files that the schema applies to, like `package.json` or
+    /// `.github/workflows/*.yml`.
+    #[serde(default, rename = "fileMatch")]
+    pub file_match: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SchemaStoreCatalog {
+    schemas: Vec<CatalogSchema>,
+}
+
+/// The schemas that the JSON and YAML language servers are configured with, on
+/// top of those of Zed's own settings files.
+#[derive(Default)]
+pub struct SchemaRegistry {
+    catalog: Mutex<Option<Arc<[CatalogSchema]>>>,
+}
+
+impl SchemaRegistry {
+    /// Returns the JSON Schema Store catalog, which is loaded the first time
+    /// that it's needed.
+    pub async fn catalog(&self, http: Arc<dyn HttpClient>) -> Arc<[CatalogSchema]> {
+        let mut catalog = self.catalog.lock().await;
+        if let Some(catalog) = catalog.as_ref() {
+            return catalog.clone();
+        }
+        let loaded: Arc<[CatalogSchema]> = load_schema_store_catalog(http).await.into();
+        *catalog = Some(loaded.clone());
+        loaded
+    }
+}
+
+/// Downloads the JSON Schema Store catalog, falling back to the copy saved by
+/// the last successful download when offline.
+async fn load_schema_store_catalog(http: Arc<dyn HttpClient>) -> Vec<CatalogSchema> {
+    let cache_path = paths::JSON_SCHEMAS_DIR.join("catalog.json");
+    let catalog = match download_catalog(&http).await {
+        Ok(catalog) => {
+            async {
+                fs::create_dir_all(&*paths::JSON_SCHEMAS_DIR).await?;
+                fs::write(&cache_path, &catalog).await
+            }
+            .await
+            .context("failed to cache the JSON Schema Store catalog")
+            .log_err();
+            catalog
+        }
+        Err(error) => {
+            log::warn!("failed to download the JSON Schema Store catalog: {error:#}");
+            let Some(catalog) = fs::read_to_string(&cache_path).await.log_err() else {
+                return Vec::new();
+            };
+            catalog
+        }
+    };
+
+    serde_json::from_str::<SchemaStoreCatalog>(&catalog)
+        .context("failed to parse the JSON Schema Store catalog")
+        .log_err()
+        .map_or_else(Vec::new, |catalog| catalog.schemas)
+}
+
+async fn download_catalog(http: &Arc<dyn HttpClient>) -> Result<String> {
+    let mut response = http
+        .get(SCHEMA_STORE_CATALOG_URL, Default::default(), true)
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("request failed with status {}", response.status()));
+    }
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    Ok(body)
+}
+
+fn is_yaml_file_match(file_match: &str) -> bool {
+    file_match.ends_with(".yml") || file_match.ends_with(".yaml")
+}
+
+/// Returns whether the glob may match a JSON file, which those of the catalog
+/// do unless they're for YAML or TOML files.
+fn is_json_file_match(file_match: &str) -> bool {
+    !is_yaml_file_match(file_match) && !file_match.ends_with(".toml")
+}
+
+/// Returns the associations of the catalog's schemas with JSON files, in the
+/// `json.schemas` format of the JSON language server.
+pub fn json_schema_associations(catalog: &[CatalogSchema]) -> Vec<Value> {
+    catalog
+        .iter()
+        .filter_map(|schema| {
+            let file_match = schema
+                .file_match
+                .iter()
+                .filter(|file_match| is_json_file_match(file_match))
+                .collect::<Vec<_>>();
+            if file_match.is_empty() {
+                return None;
+            }
+            Some(json!({
+                "fileMatch": file_match,
+                "url": schema.url,
+            }))
+        })
+        .collect()
+}
+
+/// Returns the associations of the catalog's schemas with YAML files, in the
+/// `yaml.schemas` format of the YAML language server.
+pub fn yaml_schema_associations(catalog: &[CatalogSchema]) -> Value {
+    let mut associations = Map::new();
+    for schema in catalog {
+        let file_match = schema
+            .file_match
+            .iter()
+            .filter(|file_match| is_yaml_file_match(file_match))
+            .collect::<Vec<_>>();
+        if !file_match.is_empty() {
+            associations.insert(schema.url.clone(), json!(file_match));
+        }
+    }
+    Value::Object(associations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_associations() {
+        let catalog = serde_json::from_value::<SchemaStoreCatalog>(json!({
+            "schemas": [
+                {
+                    "name": "package.json",
+                    "url": "https://json.schemastore.org/package.json",
+                    "fileMatch": ["package.json"],
+                },
+                {
+                    "name": "GitHub Workflow",
+                    "url": "https://json.schemastore.org/github-workflow.json",
+                    "fileMatch": [".github/workflows/*.yml", ".github/workflows/*.yaml"],
+                },
+                {
+                    "name": "prettierrc",
+                    "url": "https://json.schemastore.org/prettierrc.json",
+                    "fileMatch": [
+                        ".prettierrc",
+                        ".prettierrc.json",
+                        ".prettierrc.yaml",
+                        ".prettierrc.toml",
+                    ],
+                },
+                {
+                    "name": "No files",
+                    "url": "https://json.schemastore.org/none.json",
+                },
+            ]
+        }))
+        .unwrap()
+        .schemas;
+
+        assert_eq!(
+            json_schema_associations(&catalog),
+            [
+                json!({
+                    "fileMatch": ["package.json"],
+                    "url": "https://json.schemastore.org/package.json",
+                }),
+                json!({
+                    "fileMatch": [".prettierrc", ".prettierrc.json"],
+                    "url": "https://json.schemastore.org/prettierrc.json",
+                }),
+            ]
+        );
+        assert_eq!(
+            yaml_schema_associations(&catalog),
+            json!({
+                "https://json.schemastore.org/github-workflow.json": [
+                    ".github/workflows/*.yml",
+                    ".github/workflows/*.yaml",
+                ],
+                "https://json.schemastore.org/prettierrc.json": [".prettierrc.yaml"],
+            })
+        );
+    }
+}
@@ -10,7 +10,7 @@ use util::{asset_str, ResultExt};
 
 use crate::{
     bash::bash_task_context, elixir::elixir_task_context, python::python_task_context,
-    rust::RustContextProvider,
+    quarto::QuartoContextProvider, rust::RustContextProvider,
 };
 
 use self::elixir::ElixirSettings;
@@ -21,7 +21,9 @@ mod css;
 mod elixir;
 mod go;
 mod json;
+mod json_schemas;
 mod python;
+mod quarto;
 mod ruby;
 mod rust;
 mod tailwind;
@@ -48,6 +50,7 @@ pub fn init(
     cx: &mut AppContext,
 ) {
     ElixirSettings::register(cx);
+    let schema_registry = Arc::new(json_schemas::SchemaRegistry::default());
 
     languages.register_native_grammars([
         ("bash", tree_sitter_bash::language()),
@@ -59,6 +62,7 @@ pub fn init(
             "embedded_template",
             tree_sitter_embedded_template::language(),
         ),
+        ("gitcommit", tree_sitter_gitcommit::language()),
         ("go", tree_sitter_go::language()),
         ("gomod", tree_sitter_gomod::language()),
         ("gowork", tree_sitter_gowork::language()),
@@ -66,6 +70,7 @@ pub fn init(
         ("jsdoc", tree_sitter_jsdoc::language()),
         ("json", tree_sitter_json::language()),
         ("markdown", tree_sitter_markdown::language()),
+        ("org", tree_sitter_org::language()),
         ("proto", tree_sitter_proto::language()),
         ("python", tree_sitter_python::language()),
         ("regex", tree_sitter_regex::language()),
@@ -161,6 +166,7 @@ pub fn init(
             );
         }
     }
+    language!("gitcommit");
     language!("go", vec![Arc::new(go::GoLspAdapter)]);
     language!("gomod");
     language!("gowork");
@@ -176,9 +182,12 @@ pub fn init(
         vec![Arc::new(json::JsonLspAdapter::new(
             node_runtime.clone(),
             languages.clone(),
+            schema_registry.clone(),
         ))]
     );
     language!("markdown");
+    language!("org");
+    language!("quarto", Vec::new(), QuartoContextProvider);
     language!(
         "python",
         vec![Arc::new(python::PythonLspAdapter::new(
@@ -229,7 +238,10 @@ pub fn init(
     language!("regex");
     language!(
         "yaml",
-        vec![Arc::new(yaml::YamlLspAdapter::new(node_runtime.clone()))]
+        vec![Arc::new(yaml::YamlLspAdapter::new(
+            node_runtime.clone(),
+            schema_registry.clone(),
+        ))]
     );
     language!("proto");
 
@@ -0,0 +1,4 @@
+(section) @fold
+(drawer) @fold
+(property_drawer) @fold
+(block) @fold
@@ -0,0 +1,17 @@
+(headline (stars) @punctuation.special)
+(headline (item) @title)
+(tag_list) @tag
+
+(plan) @comment
+(timestamp) @constant
+
+(property_drawer) @comment
+(drawer) @comment
+(directive) @keyword
+(comment) @comment
+
+(bullet) @punctuation.list_marker
+(checkbox) @punctuation.special
+
+(block) @text.literal
+(latex_env) @text.literal
@@ -0,0 +1,4 @@
+(section
+    (headline
+        (stars) @context
+        (item) @name)) @item
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use gpui::AppContext;
+use language::{ContextProvider, Location, ToOffset};
+use std::{borrow::Cow, ops::Range, path::Path};
+use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+
+const CELL_LANGUAGE_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("CELL_LANGUAGE"));
+const CELL_CODE_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("CELL_CODE"));
+
+/// Provides the code cell under the cursor in Quarto and R Markdown documents to
+/// tasks, so that it can be run on its own.
+pub(super) struct QuartoContextProvider;
+
+impl ContextProvider for QuartoContextProvider {
+    fn build_context(
+        &self,
+        _: Option<&Path>,
+        location: &Location,
+        cx: &mut AppContext,
+    ) -> Result<TaskVariables> {
+        let buffer = location.buffer.read(cx);
+        let offset = location.range.start.to_offset(buffer);
+        let text = buffer.text();
+        Ok(code_cells(&text)
+            .into_iter()
+            .find(|cell| cell.range.contains(&offset))
+            .map(|cell| {
+                TaskVariables::from_iter([
+                    (CELL_LANGUAGE_TASK_VARIABLE.clone(), cell.language),
+                    (CELL_CODE_TASK_VARIABLE.clone(), cell.code),
+                ])
+            })
+            .unwrap_or_default())
+    }
+
+    fn associated_tasks(&self) -> Option<TaskTemplates> {
+        Some(TaskTemplates(vec![
+            TaskTemplate {
+                label: "run python cell".to_owned(),
+                command: "python3".to_owned(),
+                args: vec!["-c".to_owned(), CELL_CODE_TASK_VARIABLE.template_value()],
+                ignore_previously_resolved: true,
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "run R cell".to_owned(),
+                command: "Rscript".to_owned(),
+                args: vec!["-e".to_owned(), CELL_CODE_TASK_VARIABLE.template_value()],
+                ignore_previously_resolved: true,
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!("quarto render {}", VariableName::File.template_value()),
+                command: "quarto".to_owned(),
+                args: vec!["render".to_owned(), VariableName::File.template_value()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!("quarto preview {}", VariableName::File.template_value()),
+                command: "quarto".to_owned(),
+                args: vec!["preview".to_owned(), VariableName::File.template_value()],
+                ..TaskTemplate::default()
+            },
+        ]))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CodeCell {
+    language: String,
+    code: String,
+    /// The range of the whole cell, including its fences.
+    range: Range<usize>,
+}
+
+/// Finds the executable code cells of a document, whose opening fence names their
+/// language in braces, like ```` ```{python} ````. Fenced blocks without braces are
+/// only displayed, so they're skipped.
+fn code_cells(text: &str) -> Vec<CodeCell> {
+    let mut cells = Vec::new();
+    let mut open_cell: Option<(String, usize, usize, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        let fence_len = trimmed.len() - trimmed.trim_start_matches('`').len();
+
+        if let Some((_, _, _, open_fence_len)) = &open_cell {
+            if fence_len >= *open_fence_len && trimmed[fence_len..].is_empty() {
+                let (language, cell_start, code_start, _) = open_cell.take().unwrap();
+                cells.push(CodeCell {
+                    language,
+                    code: text[code_start..line_start].to_string(),
+                    range: cell_start..offset,
+                });
+            }
+        } else if fence_len >= 3 {
+            let info = trimmed[fence_len..].trim_start();
+            if let Some(info) = info.strip_prefix('{') {
+                let language = info
+                    .split(|c: char| c == ',' || c == '}' || c.is_whitespace())
+                    .next()
+                    .unwrap_or_default();
+                if !language.is_empty() {
+                    open_cell = Some((language.to_string(), line_start, offset, fence_len));
+                }
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_code_cells() {
+        let text = "
+            # Analysis
+
+            ```{python}
+            import math
+            print(math.pi)
+            ```
+
+            ```python
+            not_executed()
+            ```
+
+            ````{r, echo=FALSE}
+            summary(cars)
+            ```
+            plot(cars)
+            ````
+        "
+        .unindent();
+
+        let cells = code_cells(&text);
+        assert_eq!(
+            cells
+                .iter()
+                .map(|cell| (cell.language.as_str(), cell.code.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("python", "import math\nprint(math.pi)\n"),
+                ("r", "summary(cars)\n```\nplot(cars)\n"),
+            ]
+        );
+        assert_eq!(
+            &text[cells[0].range.clone()],
+            "```{python}\nimport math\nprint(math.pi)\n```\n"
+        );
+    }
+}
@@ -0,0 +1,4 @@
+; Code cells name their language in braces, e.g. ```{python} or ```{r, echo=FALSE}.
+(fenced_code_block
+  (info_string) @language
+  (code_fence_content) @content)
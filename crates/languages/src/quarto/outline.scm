@@ -0,0 +1,9 @@
+(atx_heading
+    .
+    (_) @context
+    .
+    (_) @name ) @item
+
+(fenced_code_block
+    (fenced_code_block_delimiter) @context
+    (info_string) @name) @item
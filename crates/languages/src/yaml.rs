@@ -1,3 +1,4 @@
+use crate::json_schemas::{yaml_schema_associations, SchemaRegistry};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -25,11 +26,15 @@ fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
 
 pub struct YamlLspAdapter {
     node: Arc<dyn NodeRuntime>,
+    schema_registry: Arc<SchemaRegistry>,
 }
 
 impl YamlLspAdapter {
-    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
-        YamlLspAdapter { node }
+    pub fn new(node: Arc<dyn NodeRuntime>, schema_registry: Arc<SchemaRegistry>) -> Self {
+        YamlLspAdapter {
+            node,
+            schema_registry,
+        }
     }
 }
 
@@ -95,9 +100,10 @@ impl LspAdapter for YamlLspAdapter {
 
     async fn workspace_configuration(
         self: Arc<Self>,
-        _: &Arc<dyn LspAdapterDelegate>,
+        delegate: &Arc<dyn LspAdapterDelegate>,
         cx: &mut AsyncAppContext,
     ) -> Result<Value> {
+        let catalog = self.schema_registry.catalog(delegate.http_client()).await;
         let tab_size = cx.update(|cx| {
             all_language_settings(None, cx)
                 .language(Some("YAML"))
@@ -106,7 +112,13 @@ impl LspAdapter for YamlLspAdapter {
 
         Ok(serde_json::json!({
             "yaml": {
-                "keyOrdering": false
+                "keyOrdering": false,
+                // The schemas of the JSON Schema Store are provided by the registry
+                // shared with the JSON language server, rather than downloaded again.
+                "schemaStore": {
+                    "enable": false
+                },
+                "schemas": yaml_schema_associations(&catalog)
             },
             "[yaml]": {
                 "editor.tabSize": tab_size
@@ -0,0 +1,243 @@
+use fuzzy::{match_strings, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, ParentElement,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{LayoutProfileScope, ModalView, Workspace};
+
+actions!(layout_profile_selector, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LayoutProfileSelector::register)
+        .detach();
+}
+
+/// Switches between the saved layout profiles, and saves the current layout as
+/// a new one under the name typed into it.
+pub struct LayoutProfileSelector {
+    picker: View<Picker<LayoutProfileSelectorDelegate>>,
+}
+
+impl LayoutProfileSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            let profiles = workspace.layout_profiles(cx);
+            cx.spawn(|workspace, mut cx| async move {
+                let profiles = profiles.await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    let weak_workspace = cx.view().downgrade();
+                    workspace.toggle_modal(cx, move |cx| {
+                        LayoutProfileSelector::new(weak_workspace, profiles, cx)
+                    });
+                })
+            })
+            .detach_and_log_err(cx);
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        profiles: Vec<(String, LayoutProfileScope)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            LayoutProfileSelectorDelegate::new(cx.view().downgrade(), workspace, profiles);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for LayoutProfileSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for LayoutProfileSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutProfileSelector {}
+impl ModalView for LayoutProfileSelector {}
+
+enum LayoutProfileEntry {
+    Profile {
+        name: String,
+        scope: LayoutProfileScope,
+        positions: Vec<usize>,
+    },
+    Save {
+        name: String,
+        scope: LayoutProfileScope,
+    },
+}
+
+pub struct LayoutProfileSelectorDelegate {
+    layout_profile_selector: WeakView<LayoutProfileSelector>,
+    workspace: WeakView<Workspace>,
+    profiles: Vec<(String, LayoutProfileScope)>,
+    entries: Vec<LayoutProfileEntry>,
+    selected_index: usize,
+}
+
+impl LayoutProfileSelectorDelegate {
+    fn new(
+        layout_profile_selector: WeakView<LayoutProfileSelector>,
+        workspace: WeakView<Workspace>,
+        profiles: Vec<(String, LayoutProfileScope)>,
+    ) -> Self {
+        Self {
+            layout_profile_selector,
+            workspace,
+            profiles,
+            entries: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for LayoutProfileSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Switch to a layout, or type a name to save the current one...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Switches to the selected profile, or saves the current layout under the typed
+    /// name. With the secondary modifier, the selected profile is replaced by the
+    /// current layout instead of being switched to.
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(entry) = self.entries.get(self.selected_index) {
+            self.workspace
+                .update(cx, |workspace, cx| match entry {
+                    LayoutProfileEntry::Profile { name, scope, .. } if secondary => workspace
+                        .save_layout_profile_as(name.clone(), *scope, cx)
+                        .detach_and_log_err(cx),
+                    LayoutProfileEntry::Profile { name, .. } => workspace
+                        .switch_to_layout_profile(name.clone(), cx)
+                        .detach_and_log_err(cx),
+                    LayoutProfileEntry::Save { name, scope } => workspace
+                        .save_layout_profile_as(name.clone(), *scope, cx)
+                        .detach_and_log_err(cx),
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.layout_profile_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, (name, _))| StringMatchCandidate::new(id, name.clone()))
+            .collect::<Vec<_>>();
+        let name = query.trim().to_string();
+        cx.spawn(|this, mut cx| async move {
+            let matches = match_strings(
+                &candidates,
+                &name,
+                false,
+                100,
+                &Default::default(),
+                background,
+            )
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                let mut entries = matches
+                    .into_iter()
+                    .map(|mat| {
+                        let (name, scope) = &delegate.profiles[mat.candidate_id];
+                        LayoutProfileEntry::Profile {
+                            name: name.clone(),
+                            scope: *scope,
+                            positions: mat.positions,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if !name.is_empty()
+                    && !delegate
+                        .profiles
+                        .iter()
+                        .any(|(profile_name, _)| *profile_name == name)
+                {
+                    for scope in [LayoutProfileScope::Project, LayoutProfileScope::Global] {
+                        entries.push(LayoutProfileEntry::Save {
+                            name: name.clone(),
+                            scope,
+                        });
+                    }
+                }
+                delegate.entries = entries;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.entries.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let scope_label = |scope| match scope {
+            LayoutProfileScope::Project => "This Project",
+            LayoutProfileScope::Global => "All Projects",
+        };
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+        Some(match &self.entries[ix] {
+            LayoutProfileEntry::Profile {
+                name,
+                scope,
+                positions,
+            } => item
+                .child(HighlightedLabel::new(name.clone(), positions.clone()))
+                .end_slot(
+                    Label::new(scope_label(*scope))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            LayoutProfileEntry::Save { name, scope } => item
+                .child(Label::new(format!("Save Current Layout as “{name}”")))
+                .end_slot(
+                    Label::new(scope_label(*scope))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use log::{Level, LevelFilter};
+
+/// An entry of Zed's log file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: Level,
+    /// The module that logged the entry, which older log files don't include.
+    pub target: Option<String>,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Parses a line that starts a new entry, which looks like
+    /// `2024-05-02T10:00:00+02:00 [INFO] project::terminals: message`.
+    fn parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let (level, rest) = rest.strip_prefix('[')?.split_once("] ")?;
+        let level = level.parse().ok()?;
+        let (target, message) = match rest.split_once(": ") {
+            Some((target, message)) if is_module_path(target) => {
+                (Some(target.to_string()), message)
+            }
+            _ => (None, rest),
+        };
+        Some(Self {
+            timestamp: timestamp.to_string(),
+            level,
+            target,
+            message: message.to_string(),
+        })
+    }
+
+    /// Returns whether the entry is at least as severe as the level filter, was
+    /// logged by a module whose path contains `module_query`, and has a message
+    /// that contains `search_query`. The queries must be lowercase.
+    pub fn matches(
+        &self,
+        level_filter: LevelFilter,
+        module_query: &str,
+        search_query: &str,
+    ) -> bool {
+        self.level <= level_filter
+            && (module_query.is_empty()
+                || self
+                    .target
+                    .as_ref()
+                    .map_or(false, |target| target.to_lowercase().contains(module_query)))
+            && (search_query.is_empty() || self.message.to_lowercase().contains(search_query))
+    }
+}
+
+fn is_module_path(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+/// Parses the lines of the log, appending them to the entries. Lines that don't
+/// start an entry, like those of multi-line messages, are added to the last one.
+pub fn parse_log(text: &str, entries: &mut Vec<LogEntry>) {
+    for line in text.lines() {
+        if let Some(entry) = LogEntry::parse(line) {
+            entries.push(entry);
+        } else if let Some(last_entry) = entries.last_mut() {
+            last_entry.message.push('\n');
+            last_entry.message.push_str(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let mut entries = Vec::new();
+        parse_log(
+            concat!(
+                "2024-05-02T10:00:00+02:00 [INFO] zed: ========== starting zed ==========\n",
+                "2024-05-02T10:00:01+02:00 [ERROR] lsp: oops: server crashed\n",
+                "stack backtrace:\n",
+                "  0: main\n",
+                "2024-05-02T10:00:02+02:00 [WARN] Unable to read settings: invalid JSON\n",
+            ),
+            &mut entries,
+        );
+
+        assert_eq!(
+            entries,
+            [
+                LogEntry {
+                    timestamp: "2024-05-02T10:00:00+02:00".into(),
+                    level: Level::Info,
+                    target: Some("zed".into()),
+                    message: "========== starting zed ==========".into(),
+                },
+                LogEntry {
+                    timestamp: "2024-05-02T10:00:01+02:00".into(),
+                    level: Level::Error,
+                    target: Some("lsp".into()),
+                    message: "oops: server crashed\nstack backtrace:\n  0: main".into(),
+                },
+                LogEntry {
+                    timestamp: "2024-05-02T10:00:02+02:00".into(),
+                    level: Level::Warn,
+                    target: None,
+                    message: "Unable to read settings: invalid JSON".into(),
+                },
+            ]
+        );
+
+        assert!(entries[1].matches(LevelFilter::Warn, "lsp", "crashed"));
+        assert!(!entries[1].matches(LevelFilter::Warn, "project", ""));
+        assert!(!entries[0].matches(LevelFilter::Warn, "", ""));
+        assert!(!entries[2].matches(LevelFilter::Trace, "zed", ""));
+    }
+}
@@ -0,0 +1,382 @@
+mod log_entry;
+
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, ParentElement, Render, Styled, Task, UniformListScrollHandle, View,
+    ViewContext, VisualContext, WindowContext,
+};
+use log::{Level, LevelFilter};
+use std::{ops::Range, sync::Arc, time::Duration};
+use ui::{prelude::*, ToggleButton, Tooltip};
+use util::paths;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    OpenLog, OpenLogViewer, Workspace, WorkspaceId,
+};
+
+pub use log_entry::{parse_log, LogEntry};
+
+/// How many entries the viewer keeps, dropping the oldest ones beyond that.
+const MAX_ENTRIES: usize = 10_000;
+
+/// The levels that the viewer can show entries of, along with all those that
+/// are more severe.
+const LEVEL_FILTERS: [(LevelFilter, &str); 4] = [
+    (LevelFilter::Trace, "All"),
+    (LevelFilter::Info, "Info"),
+    (LevelFilter::Warn, "Warnings"),
+    (LevelFilter::Error, "Errors"),
+];
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenLogViewer, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<LogViewer>());
+
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, cx);
+            } else {
+                let fs = workspace.app_state().fs.clone();
+                let log_viewer = cx.new_view(|cx| LogViewer::new(fs, cx));
+                workspace.add_item_to_active_pane(Box::new(log_viewer), cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Shows the entries of Zed's log as they're written, filtered by level, module
+/// and message.
+pub struct LogViewer {
+    entries: Vec<LogEntry>,
+    filtered_entry_indices: Vec<usize>,
+    level_filter: LevelFilter,
+    module_editor: View<Editor>,
+    search_editor: View<Editor>,
+    /// Whether to scroll to the newest entry as entries are added.
+    follow: bool,
+    list: UniformListScrollHandle,
+    _tail_log: Task<()>,
+}
+
+impl LogViewer {
+    fn new(fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
+        let module_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by module...", cx);
+            editor
+        });
+        let search_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search messages...", cx);
+            editor
+        });
+        for editor in [&module_editor, &search_editor] {
+            cx.subscribe(editor, |this, _, event, cx| {
+                if let EditorEvent::Edited = event {
+                    this.filter_entries(cx);
+                }
+            })
+            .detach();
+        }
+
+        Self {
+            entries: Vec::new(),
+            filtered_entry_indices: Vec::new(),
+            level_filter: LevelFilter::Trace,
+            module_editor,
+            search_editor,
+            follow: true,
+            list: UniformListScrollHandle::new(),
+            _tail_log: Self::tail_log(fs, cx),
+        }
+    }
+
+    /// Loads the previous and current log files, then appends the lines that are
+    /// written to the current one.
+    fn tail_log(fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Task<()> {
+        cx.spawn(|this, mut cx| async move {
+            if let Ok(old_log) = fs.load(&paths::OLD_LOG).await {
+                if this
+                    .update(&mut cx, |this, cx| this.append_log(&old_log, cx))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+
+            let mut events = fs.watch(&paths::LOG, Duration::from_millis(100)).await;
+            let mut read_len = 0;
+            loop {
+                if let Ok(log) = fs.load(&paths::LOG).await {
+                    // The log is moved aside when it grows too large, and a new one started.
+                    if log.len() < read_len || !log.is_char_boundary(read_len) {
+                        read_len = 0;
+                    }
+                    // Only read complete lines, as the last one may still be being written.
+                    let end = log[read_len..]
+                        .rfind('\n')
+                        .map_or(read_len, |ix| read_len + ix + 1);
+                    let new_lines = &log[read_len..end];
+                    read_len = end;
+                    if this
+                        .update(&mut cx, |this, cx| this.append_log(new_lines, cx))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+
+                if events.next().await.is_none() {
+                    return;
+                }
+            }
+        })
+    }
+
+    fn append_log(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        if text.is_empty() {
+            return;
+        }
+        parse_log(text, &mut self.entries);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+        self.filter_entries(cx);
+    }
+
+    fn filter_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let module_query = self.module_editor.read(cx).text(cx).trim().to_lowercase();
+        let search_query = self.search_editor.read(cx).text(cx).trim().to_lowercase();
+        self.filtered_entry_indices = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches(self.level_filter, &module_query, &search_query))
+            .map(|(ix, _)| ix)
+            .collect();
+        if self.follow {
+            if let Some(last_ix) = self.filtered_entry_indices.len().checked_sub(1) {
+                self.list.scroll_to_item(last_ix);
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_level_filter(&mut self, level_filter: LevelFilter, cx: &mut ViewContext<Self>) {
+        self.level_filter = level_filter;
+        self.filter_entries(cx);
+    }
+
+    fn render_entries(&mut self, range: Range<usize>, _: &mut ViewContext<Self>) -> Vec<Div> {
+        range
+            .map(|ix| {
+                let entry = &self.entries[self.filtered_entry_indices[ix]];
+                let level_color = match entry.level {
+                    Level::Error => Color::Error,
+                    Level::Warn => Color::Warning,
+                    Level::Info => Color::Default,
+                    Level::Debug | Level::Trace => Color::Muted,
+                };
+                let (first_line, more_lines) = match entry.message.split_once('\n') {
+                    Some((first_line, _)) => (first_line.to_string(), true),
+                    None => (entry.message.clone(), false),
+                };
+                let message = entry.message.clone();
+
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .px_2()
+                    .child(
+                        Label::new(entry.timestamp.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(entry.level.as_str())
+                            .size(LabelSize::Small)
+                            .color(level_color),
+                    )
+                    .when_some(entry.target.clone(), |row, target| {
+                        row.child(
+                            Label::new(target)
+                                .size(LabelSize::Small)
+                                .color(Color::Accent),
+                        )
+                    })
+                    .child(
+                        div()
+                            .id(("log-entry-message", ix))
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(Label::new(first_line).size(LabelSize::Small).single_line())
+                            .when(more_lines, |this| {
+                                this.tooltip(move |cx| Tooltip::text(message.clone(), cx))
+                            }),
+                    )
+                    .when_some(related_action(entry), |row, (label, action_name)| {
+                        row.child(
+                            Button::new(("log-entry-action", ix), label)
+                                .label_size(LabelSize::Small)
+                                .on_click(move |_, cx| {
+                                    if let Ok(action) = cx.build_action(action_name, None) {
+                                        cx.dispatch_action(action);
+                                    }
+                                }),
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Returns the label and name of an action that leads to where the cause of the
+/// entry can be looked into further, or fixed.
+fn related_action(entry: &LogEntry) -> Option<(&'static str, &'static str)> {
+    let target = entry.target.as_deref().unwrap_or_default();
+    if target.starts_with("lsp") || entry.message.contains("language server") {
+        Some(("Server Logs", "debug::OpenLanguageServerLogs"))
+    } else if target.starts_with("settings") {
+        Some(("Settings", "zed::OpenSettings"))
+    } else if target.starts_with("extension") {
+        Some(("Extensions", "zed::Extensions"))
+    } else {
+        None
+    }
+}
+
+impl Render for LogViewer {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entry_count = self.filtered_entry_indices.len();
+        let level_filter_count = LEVEL_FILTERS.len();
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(h_flex().children(LEVEL_FILTERS.iter().enumerate().map(
+                        |(ix, (level_filter, label))| {
+                            let level_filter = *level_filter;
+                            let button = ToggleButton::new(("level-filter", ix), *label)
+                                .style(ButtonStyle::Filled)
+                                .selected(self.level_filter == level_filter)
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.set_level_filter(level_filter, cx)
+                                }));
+                            if ix == 0 {
+                                button.first()
+                            } else if ix == level_filter_count - 1 {
+                                button.last()
+                            } else {
+                                button.middle()
+                            }
+                        },
+                    )))
+                    .child(
+                        div()
+                            .w_48()
+                            .px_2()
+                            .py_1()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .rounded_md()
+                            .child(self.module_editor.clone()),
+                    )
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .rounded_md()
+                            .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                            .child(self.search_editor.clone()),
+                    )
+                    .child(
+                        ToggleButton::new("follow", "Follow")
+                            .style(ButtonStyle::Filled)
+                            .selected(self.follow)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.follow = !this.follow;
+                                this.filter_entries(cx);
+                            }))
+                            .tooltip(|cx| Tooltip::text("Scroll to new entries", cx)),
+                    )
+                    .child(
+                        Button::new("open-raw-log", "Open Raw Log")
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(OpenLog))),
+                    ),
+            )
+            .child(if entry_count == 0 {
+                div()
+                    .p_4()
+                    .child(Label::new("No log entries match the filters.").color(Color::Muted))
+                    .into_any_element()
+            } else {
+                uniform_list(
+                    cx.view().clone(),
+                    "log-entries",
+                    entry_count,
+                    Self::render_entries,
+                )
+                .size_full()
+                .py_1()
+                .track_scroll(self.list.clone())
+                .into_any_element()
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for LogViewer {}
+
+impl FocusableView for LogViewer {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.search_editor.focus_handle(cx)
+    }
+}
+
+impl Item for LogViewer {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Log")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("log viewer")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        _: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
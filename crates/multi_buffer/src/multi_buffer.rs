@@ -95,6 +95,9 @@ pub enum Event {
     Closed,
     DirtyChanged,
     DiagnosticsUpdated,
+    RemoteSelectionsChanged {
+        replica_id: ReplicaId,
+    },
 }
 
 #[derive(Clone)]
@@ -1474,6 +1477,11 @@ impl MultiBuffer {
                 self.capability = buffer.read(cx).capability();
                 Event::CapabilityChanged
             }
+            language::Event::RemoteSelectionsChanged { replica_id } => {
+                Event::RemoteSelectionsChanged {
+                    replica_id: *replica_id,
+                }
+            }
 
             //
             language::Event::Operation(_) => return,
@@ -2579,6 +2587,28 @@ impl MultiBufferSnapshot {
         None
     }
 
+    /// Returns the range that the given row can be folded to according to its
+    /// language's `folds` query. See [`BufferSnapshot::syntax_fold_range`].
+    pub fn syntax_fold_range(&self, row: u32) -> Option<Range<Point>> {
+        let mut cursor = self.excerpts.cursor::<Point>();
+        let point = Point::new(row, 0);
+        cursor.seek(&point, Bias::Right, &());
+        if cursor.item().is_none() && *cursor.start() == point {
+            cursor.prev(&());
+        }
+        let excerpt = cursor.item()?;
+        let excerpt_start = excerpt.range.context.start.to_point(&excerpt.buffer);
+        let excerpt_end = excerpt.range.context.end.to_point(&excerpt.buffer);
+        let buffer_row = excerpt_start.row + (row - cursor.start().row);
+        let range = excerpt.buffer.syntax_fold_range(buffer_row)?;
+        let end = range.end.min(excerpt_end);
+        if end.row <= buffer_row {
+            return None;
+        }
+        let start = range.start.max(excerpt_start);
+        Some(*cursor.start() + (start - excerpt_start)..*cursor.start() + (end - excerpt_start))
+    }
+
     pub fn max_point(&self) -> Point {
         self.text_summary().lines
     }
@@ -0,0 +1,347 @@
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use gpui::{AppContext, Context as _, EventEmitter, Global, Model, ModelContext};
+use rpc::Notification;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::{collections::VecDeque, fmt};
+use time::OffsetDateTime;
+
+/// The maximum number of notifications kept by the notification center.
+const MAX_NOTIFICATIONS: usize = 100;
+
+pub(crate) fn init(cx: &mut AppContext) {
+    NotificationCenterSettings::register(cx);
+    let notification_center = cx.new_model(|_| NotificationCenter::default());
+    cx.set_global(GlobalNotificationCenter(notification_center));
+}
+
+struct GlobalNotificationCenter(Model<NotificationCenter>);
+
+impl Global for GlobalNotificationCenter {}
+
+/// Where a notification comes from. Each source can be muted separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSource {
+    /// Contact requests and channel invitations.
+    Collaboration,
+    /// Mentions and thread replies in channel chats.
+    Mentions,
+    /// Updates that are ready to be installed.
+    Updates,
+    /// Long-running tasks that finished.
+    Tasks,
+}
+
+impl NotificationSource {
+    pub const ALL: [Self; 4] = [
+        Self::Collaboration,
+        Self::Mentions,
+        Self::Updates,
+        Self::Tasks,
+    ];
+
+    pub fn for_collab_notification(notification: &Notification) -> Self {
+        match notification {
+            Notification::ContactRequest { .. }
+            | Notification::ContactRequestAccepted { .. }
+            | Notification::ChannelInvitation { .. } => Self::Collaboration,
+            Notification::ChannelMessageMention { .. }
+            | Notification::ChannelMessageThreadReply { .. } => Self::Mentions,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Collaboration => "Collaboration",
+            Self::Mentions => "Mentions",
+            Self::Updates => "Updates",
+            Self::Tasks => "Tasks",
+        }
+    }
+}
+
+/// A notification that originates in this instance of Zed, rather than on the
+/// collaboration server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalNotification {
+    pub id: usize,
+    pub source: NotificationSource,
+    pub text: String,
+    pub timestamp: OffsetDateTime,
+    pub is_read: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationCenterEvent {
+    NotificationAdded {
+        id: usize,
+        /// Whether the notification should be shown as a toast.
+        alert: bool,
+    },
+}
+
+/// Collects the notifications from all of Zed's local sources, like finished
+/// tasks and downloaded updates, and decides which of them to alert about.
+#[derive(Default)]
+pub struct NotificationCenter {
+    notifications: VecDeque<LocalNotification>,
+    next_id: usize,
+    mirrored_collab_notifications: HashSet<u64>,
+}
+
+impl EventEmitter<NotificationCenterEvent> for NotificationCenter {}
+
+impl NotificationCenter {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalNotificationCenter>().0.clone()
+    }
+
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalNotificationCenter>()
+            .map(|global| global.0.clone())
+    }
+
+    /// Adds a notification, mirroring it to the operating system when enabled.
+    pub fn push(
+        &mut self,
+        source: NotificationSource,
+        text: impl Into<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let notification = LocalNotification {
+            id,
+            source,
+            text: text.into(),
+            timestamp: OffsetDateTime::now_utc(),
+            is_read: false,
+        };
+        let alert = should_alert(source, cx);
+        if alert {
+            mirror_to_os(source, &notification.text, cx);
+        }
+
+        self.notifications.push_front(notification);
+        self.notifications.truncate(MAX_NOTIFICATIONS);
+        cx.emit(NotificationCenterEvent::NotificationAdded { id, alert });
+        cx.notify();
+        id
+    }
+
+    /// The notifications, newest first.
+    pub fn notifications(&self) -> impl Iterator<Item = &LocalNotification> {
+        self.notifications.iter()
+    }
+
+    pub fn notification_count(&self) -> usize {
+        self.notifications.len()
+    }
+
+    pub fn unread_notification_count(&self) -> usize {
+        self.notifications
+            .iter()
+            .filter(|notification| !notification.is_read)
+            .count()
+    }
+
+    pub fn mark_all_as_read(&mut self, cx: &mut ModelContext<Self>) {
+        for notification in &mut self.notifications {
+            notification.is_read = true;
+        }
+        cx.notify();
+    }
+
+    pub fn dismiss(&mut self, id: usize, cx: &mut ModelContext<Self>) {
+        self.notifications
+            .retain(|notification| notification.id != id);
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        self.notifications.clear();
+        cx.notify();
+    }
+
+    /// Returns whether a notification from the collaboration server should be shown
+    /// as a toast. The notification panel of every window asks, but the notification
+    /// is only mirrored to the operating system once.
+    pub fn alert_for_collab_notification(
+        &mut self,
+        notification_id: u64,
+        notification: &Notification,
+        text: &str,
+        cx: &AppContext,
+    ) -> bool {
+        let source = NotificationSource::for_collab_notification(notification);
+        if !should_alert(source, cx) {
+            return false;
+        }
+        if self.mirrored_collab_notifications.insert(notification_id) {
+            mirror_to_os(source, text, cx);
+        }
+        true
+    }
+}
+
+/// Returns whether a notification from the given source should be shown, which it
+/// isn't when the source is muted or do not disturb is active.
+pub fn should_alert(source: NotificationSource, cx: &AppContext) -> bool {
+    let settings = NotificationCenterSettings::get_global(cx);
+    if settings.muted_sources.contains(&source) {
+        return false;
+    }
+    !is_do_not_disturb_active(cx)
+}
+
+pub fn is_do_not_disturb_active(cx: &AppContext) -> bool {
+    let Some(schedule) = NotificationCenterSettings::get_global(cx).do_not_disturb else {
+        return false;
+    };
+    let now = OffsetDateTime::now_utc().to_offset(cx.local_timezone());
+    schedule.contains(TimeOfDay::from_time(now.time()))
+}
+
+fn mirror_to_os(source: NotificationSource, text: &str, cx: &AppContext) {
+    if NotificationCenterSettings::get_global(cx).os_notifications {
+        cx.show_notification(source.label(), text);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NotificationCenterSettings {
+    pub muted_sources: Vec<NotificationSource>,
+    pub do_not_disturb: Option<DoNotDisturbSchedule>,
+    pub os_notifications: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct NotificationCenterSettingsContent {
+    /// The sources whose notifications are listed in the notification panel,
+    /// without being shown as toasts or mirrored to the operating system.
+    ///
+    /// Default: []
+    pub muted_sources: Option<Vec<NotificationSource>>,
+    /// A daily period, in local time, during which no notifications are shown
+    /// as toasts or mirrored to the operating system.
+    ///
+    /// Default: null
+    pub do_not_disturb: Option<DoNotDisturbSchedule>,
+    /// Whether to also show notifications through the operating system's
+    /// notification center.
+    ///
+    /// Default: false
+    pub os_notifications: Option<bool>,
+}
+
+impl Settings for NotificationCenterSettings {
+    const KEY: Option<&'static str> = Some("notifications");
+
+    type FileContent = NotificationCenterSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// A daily period of time, which may wrap around midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DoNotDisturbSchedule {
+    /// When the period starts, as `HH:MM`.
+    #[schemars(with = "String")]
+    pub start: TimeOfDay,
+    /// When the period ends, as `HH:MM`.
+    #[schemars(with = "String")]
+    pub end: TimeOfDay,
+}
+
+impl DoNotDisturbSchedule {
+    pub fn contains(&self, time: TimeOfDay) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// A time of day with minute precision, written as `HH:MM`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        (hour < 24 && minute < 60).then(|| Self {
+            minutes: hour as u16 * 60 + minute as u16,
+        })
+    }
+
+    fn from_time(time: time::Time) -> Self {
+        Self {
+            minutes: time.hour() as u16 * 60 + time.minute() as u16,
+        }
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        let (hour, minute) = value
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected a time as HH:MM, got {value:?}"))?;
+        Self::new(hour.trim().parse()?, minute.trim().parse()?)
+            .ok_or_else(|| anyhow!("invalid time of day {value:?}"))
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_do_not_disturb_schedule() {
+        let time = |value: &str| TimeOfDay::try_from(value.to_string()).unwrap();
+
+        let overnight = DoNotDisturbSchedule {
+            start: time("22:00"),
+            end: time("7:30"),
+        };
+        assert!(overnight.contains(time("22:00")));
+        assert!(overnight.contains(time("23:59")));
+        assert!(overnight.contains(time("00:00")));
+        assert!(overnight.contains(time("07:29")));
+        assert!(!overnight.contains(time("07:30")));
+        assert!(!overnight.contains(time("12:00")));
+
+        let lunch = DoNotDisturbSchedule {
+            start: time("12:00"),
+            end: time("13:00"),
+        };
+        assert!(lunch.contains(time("12:30")));
+        assert!(!lunch.contains(time("13:00")));
+        assert!(!lunch.contains(time("11:59")));
+
+        assert!(TimeOfDay::try_from("24:00".to_string()).is_err());
+        assert!(TimeOfDay::try_from("noon".to_string()).is_err());
+        assert_eq!(time("7:05").to_string(), "07:05");
+    }
+}
@@ -1,3 +1,5 @@
+mod notification_center;
+
 use anyhow::{Context, Result};
 use channel::{ChannelMessage, ChannelMessageId, ChannelStore};
 use client::{ChannelId, Client, UserStore};
@@ -12,7 +14,10 @@ use sum_tree::{Bias, SumTree};
 use time::OffsetDateTime;
 use util::ResultExt;
 
+pub use notification_center::*;
+
 pub fn init(client: Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
+    notification_center::init(cx);
     let notification_store = cx.new_model(|cx| NotificationStore::new(client, user_store, cx));
     cx.set_global(GlobalNotificationStore(notification_store));
 }
@@ -245,11 +250,10 @@ impl NotificationStore {
     ) -> Result<()> {
         this.update(&mut cx, |this, cx| {
             if let Some(notification) = envelope.payload.notification {
-                if let Some(rpc::Notification::ChannelMessageMention {
-                    message_id,
-                    sender_id: _,
-                    channel_id: _,
-                }) = Notification::from_proto(&notification)
+                if let Some(
+                    rpc::Notification::ChannelMessageMention { message_id, .. }
+                    | rpc::Notification::ChannelMessageThreadReply { message_id, .. },
+                ) = Notification::from_proto(&notification)
                 {
                     let fetch_message_task = this.channel_store.update(cx, |this, cx| {
                         this.fetch_channel_messages(vec![message_id], cx)
@@ -316,6 +320,11 @@ impl NotificationStore {
                     sender_id,
                     message_id,
                     ..
+                }
+                | Notification::ChannelMessageThreadReply {
+                    sender_id,
+                    message_id,
+                    ..
                 } => {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
@@ -273,10 +273,30 @@ pub enum OpenAiEmbeddingModel {
     TextEmbedding3Large,
 }
 
+impl OpenAiEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TextEmbedding3Small => "text-embedding-3-small",
+            Self::TextEmbedding3Large => "text-embedding-3-large",
+        }
+    }
+
+    /// The number of dimensions of the model's embeddings, which is the most that
+    /// they can be shortened to.
+    pub fn max_dimensions(&self) -> usize {
+        match self {
+            Self::TextEmbedding3Small => 1536,
+            Self::TextEmbedding3Large => 3072,
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct OpenAiEmbeddingRequest<'a> {
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     input: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -284,23 +304,44 @@ pub struct OpenAiEmbeddingResponse {
     pub data: Vec<OpenAiEmbedding>,
 }
 
+impl OpenAiEmbeddingResponse {
+    /// Parses the body of a response, ordering its embeddings like the texts of
+    /// the request. Azure OpenAI's responses have the same format.
+    pub fn parse(body: &str) -> Result<Self> {
+        let mut response: Self = serde_json::from_str(body)?;
+        // The embeddings aren't guaranteed to be listed in the order of the input.
+        response.data.sort_by_key(|embedding| embedding.index);
+        Ok(response)
+    }
+}
+
 #[derive(Deserialize)]
 pub struct OpenAiEmbedding {
     pub embedding: Vec<f32>,
+    /// The position of the embedded text in the request's `input`.
+    pub index: usize,
 }
 
+/// Embeds all of the texts in a single request, returning their embeddings in the
+/// same order as the texts. The `text-embedding-3` models can return embeddings
+/// with fewer `dimensions` than they have, which are shortened the same way.
+///
+/// The `model` is the id of a model like [`OpenAiEmbeddingModel::id`], or that of a
+/// model served by another API that's compatible with OpenAI's.
 pub fn embed<'a>(
     client: &dyn HttpClient,
     api_url: &str,
     api_key: &str,
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     texts: impl IntoIterator<Item = &'a str>,
+    dimensions: Option<usize>,
 ) -> impl 'static + Future<Output = Result<OpenAiEmbeddingResponse>> {
     let uri = format!("{api_url}/embeddings");
 
     let request = OpenAiEmbeddingRequest {
         model,
         input: texts.into_iter().collect(),
+        dimensions,
     };
     let body = AsyncBody::from(serde_json::to_string(&request).unwrap());
     let request = HttpRequest::builder()
@@ -317,9 +358,8 @@ pub fn embed<'a>(
         response.body_mut().read_to_string(&mut body).await?;
 
         if response.status().is_success() {
-            let response: OpenAiEmbeddingResponse =
-                serde_json::from_str(&body).context("failed to parse OpenAI embedding response")?;
-            Ok(response)
+            OpenAiEmbeddingResponse::parse(&body)
+                .context("failed to parse OpenAI embedding response")
         } else {
             Err(anyhow!(
                 "error during embedding, status: {:?}, body: {:?}",
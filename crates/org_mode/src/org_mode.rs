@@ -0,0 +1,484 @@
+use editor::{actions::Tab, scroll::Autoscroll, Editor, EditorMode, MultiBufferSnapshot};
+use gpui::{actions, AppContext, ViewContext};
+use language::Point;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+use util::paths::HOME;
+
+actions!(
+    org_mode,
+    [CycleTodo, ToggleCheckbox, AlignTable, FollowLink]
+);
+
+lazy_static! {
+    static ref HEADLINE_REGEX: Regex =
+        Regex::new(r"^(\*+)(?:[ \t]+|$)(?:(TODO|DONE)(?:[ \t]+|$))?").unwrap();
+    static ref CHECKBOX_REGEX: Regex =
+        Regex::new(r"^(?:\s*[-+]|\s*\d+[.)]|\s+\*)\s+\[([ xX-])\]").unwrap();
+    static ref LINK_REGEX: Regex = Regex::new(r"\[\[([^\]]+)\](?:\[[^\]]*\])?\]").unwrap();
+    static ref TAGS_REGEX: Regex = Regex::new(r"\s+:[\w@#%:]+:\s*$").unwrap();
+}
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+
+        let handle = cx.view().downgrade();
+        editor
+            .register_action({
+                let handle = handle.clone();
+                move |_: &CycleTodo, cx| {
+                    handle.update(cx, cycle_todo).ok();
+                }
+            })
+            .register_action({
+                let handle = handle.clone();
+                move |_: &ToggleCheckbox, cx| {
+                    handle.update(cx, toggle_checkbox).ok();
+                }
+            })
+            .register_action({
+                let handle = handle.clone();
+                move |_: &AlignTable, cx| {
+                    handle.update(cx, align_table).ok();
+                }
+            })
+            .register_action(move |_: &FollowLink, cx| {
+                handle.update(cx, follow_link).ok();
+            });
+    })
+    .detach();
+}
+
+/// Cycles the headline containing each cursor between `TODO`, `DONE` and no keyword.
+fn cycle_todo(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut headline_rows = editor
+        .selections
+        .all::<Point>(cx)
+        .into_iter()
+        .filter_map(|selection| {
+            (0..=selection.head().row)
+                .rev()
+                .find(|row| HEADLINE_REGEX.is_match(&line_text(&snapshot, *row)))
+        })
+        .collect::<Vec<_>>();
+    headline_rows.dedup();
+
+    let edits = headline_rows
+        .into_iter()
+        .filter_map(|row| {
+            let (range, text) = todo_keyword_edit(&line_text(&snapshot, row))?;
+            Some((row_range(row, range), text))
+        })
+        .collect::<Vec<_>>();
+    editor.transact(cx, |editor, cx| editor.edit(edits, cx));
+}
+
+/// Checks or unchecks the checkbox of the list item on each cursor's line.
+fn toggle_checkbox(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut rows = editor
+        .selections
+        .all::<Point>(cx)
+        .into_iter()
+        .map(|selection| selection.head().row)
+        .collect::<Vec<_>>();
+    rows.dedup();
+
+    let edits = rows
+        .into_iter()
+        .filter_map(|row| {
+            let (range, text) = checkbox_edit(&line_text(&snapshot, row))?;
+            Some((row_range(row, range), text))
+        })
+        .collect::<Vec<_>>();
+    editor.transact(cx, |editor, cx| editor.edit(edits, cx));
+}
+
+/// Aligns the columns of the table around the cursor and moves it to the next
+/// cell. Outside of tables, this inserts a tab as usual.
+fn align_table(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let cursor = editor.selections.newest::<Point>(cx).head();
+    let cursor_line = line_text(&snapshot, cursor.row);
+    if !is_table_row(&cursor_line) {
+        editor.tab(&Tab, cx);
+        return;
+    }
+
+    let mut start_row = cursor.row;
+    while start_row > 0 && is_table_row(&line_text(&snapshot, start_row - 1)) {
+        start_row -= 1;
+    }
+    let mut end_row = cursor.row;
+    while end_row < snapshot.max_point().row && is_table_row(&line_text(&snapshot, end_row + 1)) {
+        end_row += 1;
+    }
+
+    let lines = (start_row..=end_row)
+        .map(|row| line_text(&snapshot, row))
+        .collect::<Vec<_>>();
+    let aligned = format_table(&lines);
+    let cell_ix = cursor_line[..cursor.column as usize]
+        .matches('|')
+        .count()
+        .saturating_sub(1);
+    let next_cell = next_cell_position(&aligned, (cursor.row - start_row) as usize, cell_ix);
+
+    let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+    editor.transact(cx, |editor, cx| {
+        editor.edit([(range, aligned.join("\n"))], cx);
+        if let Some((row, column)) = next_cell {
+            let point = Point::new(start_row + row as u32, column as u32);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([point..point])
+            });
+        }
+    });
+}
+
+/// Follows the `[[target][description]]` link under the cursor, which can be a
+/// URL, a file or a headline in the same file.
+fn follow_link(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let cursor = editor.selections.newest::<Point>(cx).head();
+    let Some(target) = link_target_at(&line_text(&snapshot, cursor.row), cursor.column as usize)
+    else {
+        return;
+    };
+
+    match parse_link(&target) {
+        Link::Url(url) => cx.open_url(url),
+        Link::File(path) => {
+            let Some(workspace) = editor.workspace() else {
+                return;
+            };
+            let path = if let Some(path) = path.strip_prefix("~/") {
+                HOME.join(path)
+            } else {
+                let directory = editor
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()
+                    .and_then(|buffer| {
+                        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+                        Some(abs_path.parent()?.to_path_buf())
+                    })
+                    .unwrap_or_default();
+                directory.join(path)
+            };
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(path, true, cx)
+                    .detach_and_log_err(cx);
+            });
+        }
+        Link::Headline(title) => {
+            let row = (0..=snapshot.max_point().row)
+                .find(|row| headline_title(&line_text(&snapshot, *row)) == Some(title));
+            if let Some(row) = row {
+                select_row(editor, row, cx);
+            }
+        }
+        Link::Target(target) => {
+            let dedicated_target = format!("<<{target}>>");
+            let row = (0..=snapshot.max_point().row).find(|row| {
+                let line = line_text(&snapshot, *row);
+                line.contains(&dedicated_target) || headline_title(&line) == Some(target)
+            });
+            if let Some(row) = row {
+                select_row(editor, row, cx);
+            }
+        }
+    }
+}
+
+fn select_row(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let point = Point::new(row, 0);
+    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+        selections.select_ranges([point..point])
+    });
+}
+
+fn line_text(snapshot: &MultiBufferSnapshot, row: u32) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect()
+}
+
+fn row_range(row: u32, range: Range<usize>) -> Range<Point> {
+    Point::new(row, range.start as u32)..Point::new(row, range.end as u32)
+}
+
+/// Returns the edit that moves a headline on to its next `TODO` keyword.
+fn todo_keyword_edit(line: &str) -> Option<(Range<usize>, &'static str)> {
+    let captures = HEADLINE_REGEX.captures(line)?;
+    let headline_prefix = captures.get(0)?;
+    match captures.get(2) {
+        Some(keyword) if keyword.as_str() == "TODO" => Some((keyword.range(), "DONE")),
+        Some(keyword) => Some((keyword.start()..headline_prefix.end(), "")),
+        None if headline_prefix.as_str().ends_with('*') => {
+            Some((headline_prefix.end()..headline_prefix.end(), " TODO"))
+        }
+        None => Some((headline_prefix.end()..headline_prefix.end(), "TODO ")),
+    }
+}
+
+/// Returns the edit that checks an unchecked checkbox, or unchecks a checked or
+/// partially checked one.
+fn checkbox_edit(line: &str) -> Option<(Range<usize>, &'static str)> {
+    let status = CHECKBOX_REGEX.captures(line)?.get(1)?;
+    let new_status = if status.as_str() == " " { "X" } else { " " };
+    Some((status.range(), new_status))
+}
+
+/// Returns a headline's title, without its stars, `TODO` keyword or tags.
+fn headline_title(line: &str) -> Option<&str> {
+    let prefix = HEADLINE_REGEX.find(line)?;
+    let title = &line[prefix.end()..];
+    let title = match TAGS_REGEX.find(title) {
+        Some(tags) => &title[..tags.start()],
+        None => title,
+    };
+    Some(title.trim())
+}
+
+fn is_table_row(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+fn is_table_separator(line: &str) -> bool {
+    line.trim_start().starts_with("|-")
+}
+
+fn table_cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|').map(str::trim).collect()
+}
+
+/// Pads every cell of a table to the width of its column, keeping the table's
+/// indentation and turning separator lines into `|---+---|` rules.
+fn format_table(lines: &[String]) -> Vec<String> {
+    let indent = lines
+        .first()
+        .map_or("", |line| &line[..line.len() - line.trim_start().len()]);
+
+    let rows = lines
+        .iter()
+        .map(|line| (!is_table_separator(line)).then(|| table_cells(line)))
+        .collect::<Vec<_>>();
+    let mut widths = Vec::<usize>::new();
+    for cells in rows.iter().flatten() {
+        for (ix, cell) in cells.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(ix) {
+                Some(column_width) => *column_width = (*column_width).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    rows.into_iter()
+        .map(|cells| match cells {
+            Some(cells) => {
+                let cells = widths
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, width)| {
+                        let cell = cells.get(ix).copied().unwrap_or_default();
+                        format!(" {cell:<width$} ")
+                    })
+                    .collect::<Vec<_>>();
+                format!("{indent}|{}|", cells.join("|"))
+            }
+            None => {
+                let rules = widths
+                    .iter()
+                    .map(|width| "-".repeat(width + 2))
+                    .collect::<Vec<_>>();
+                format!("{indent}|{}|", rules.join("+"))
+            }
+        })
+        .collect()
+}
+
+/// Returns the row and column of the start of the cell after the given one,
+/// continuing on the next row that isn't a separator.
+fn next_cell_position(lines: &[String], row: usize, cell_ix: usize) -> Option<(usize, usize)> {
+    let cell_starts = |line: &str| -> Vec<usize> {
+        if is_table_separator(line) {
+            return Vec::new();
+        }
+        let mut starts = line
+            .match_indices('|')
+            .map(|(ix, _)| ix + 2)
+            .collect::<Vec<_>>();
+        starts.pop();
+        starts
+    };
+
+    if let Some(start) = cell_starts(lines.get(row)?).get(cell_ix + 1) {
+        return Some((row, *start));
+    }
+    lines
+        .iter()
+        .enumerate()
+        .skip(row + 1)
+        .find_map(|(ix, line)| Some((ix, *cell_starts(line).first()?)))
+}
+
+fn link_target_at(line: &str, column: usize) -> Option<String> {
+    LINK_REGEX
+        .captures_iter(line)
+        .find(|captures| {
+            captures
+                .get(0)
+                .map_or(false, |link| link.range().contains(&column))
+        })
+        .and_then(|captures| Some(captures.get(1)?.as_str().to_string()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Link<'a> {
+    Url(&'a str),
+    File(&'a str),
+    Headline(&'a str),
+    /// A `<<target>>` or, failing that, a headline with the same title.
+    Target(&'a str),
+}
+
+fn parse_link(target: &str) -> Link {
+    if ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| target.starts_with(scheme))
+    {
+        Link::Url(target)
+    } else if let Some(path) = target.strip_prefix("file:") {
+        Link::File(path)
+    } else if ["/", "./", "../", "~/"]
+        .iter()
+        .any(|prefix| target.starts_with(prefix))
+    {
+        Link::File(target)
+    } else if let Some(title) = target.strip_prefix('*') {
+        Link::Headline(title.trim())
+    } else {
+        Link::Target(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(line: &str, edit: Option<(Range<usize>, &str)>) -> String {
+        let (range, text) = edit.unwrap();
+        let mut line = line.to_string();
+        line.replace_range(range, text);
+        line
+    }
+
+    #[test]
+    fn test_todo_keyword_edit() {
+        assert_eq!(
+            apply("** Write docs", todo_keyword_edit("** Write docs")),
+            "** TODO Write docs"
+        );
+        assert_eq!(
+            apply(
+                "** TODO Write docs",
+                todo_keyword_edit("** TODO Write docs")
+            ),
+            "** DONE Write docs"
+        );
+        assert_eq!(
+            apply(
+                "** DONE Write docs",
+                todo_keyword_edit("** DONE Write docs")
+            ),
+            "** Write docs"
+        );
+        assert_eq!(apply("*", todo_keyword_edit("*")), "* TODO");
+        assert_eq!(
+            apply("* TODOS", todo_keyword_edit("* TODOS")),
+            "* TODO TODOS"
+        );
+        assert_eq!(todo_keyword_edit("*bold* text"), None);
+        assert_eq!(todo_keyword_edit("  - item"), None);
+    }
+
+    #[test]
+    fn test_checkbox_edit() {
+        assert_eq!(
+            apply("- [ ] milk", checkbox_edit("- [ ] milk")),
+            "- [X] milk"
+        );
+        assert_eq!(
+            apply("  + [x] eggs", checkbox_edit("  + [x] eggs")),
+            "  + [ ] eggs"
+        );
+        assert_eq!(
+            apply("1. [-] bread", checkbox_edit("1. [-] bread")),
+            "1. [ ] bread"
+        );
+        assert_eq!(checkbox_edit("* [ ] headline"), None);
+        assert_eq!(checkbox_edit("- no checkbox"), None);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let lines = [
+            "  | Name | Qty |",
+            "  |-",
+            "  |apples|3|",
+            "  | pears |  12",
+        ]
+        .map(String::from);
+        let aligned = format_table(&lines);
+        assert_eq!(
+            aligned,
+            [
+                "  | Name   | Qty |",
+                "  |--------+-----|",
+                "  | apples | 3   |",
+                "  | pears  | 12  |",
+            ]
+        );
+
+        assert_eq!(next_cell_position(&aligned, 0, 0), Some((0, 13)));
+        assert_eq!(next_cell_position(&aligned, 0, 1), Some((2, 4)));
+        assert_eq!(next_cell_position(&aligned, 3, 1), None);
+    }
+
+    #[test]
+    fn test_links() {
+        let line = "See [[https://orgmode.org][Org]] and [[*Next steps]].";
+        assert_eq!(
+            link_target_at(line, 10).as_deref(),
+            Some("https://orgmode.org")
+        );
+        assert_eq!(link_target_at(line, 40).as_deref(), Some("*Next steps"));
+        assert_eq!(link_target_at(line, 2), None);
+
+        assert_eq!(
+            parse_link("https://orgmode.org"),
+            Link::Url("https://orgmode.org")
+        );
+        assert_eq!(parse_link("file:notes.org"), Link::File("notes.org"));
+        assert_eq!(parse_link("../todo.org"), Link::File("../todo.org"));
+        assert_eq!(parse_link("*Next steps"), Link::Headline("Next steps"));
+        assert_eq!(parse_link("meeting"), Link::Target("meeting"));
+
+        assert_eq!(
+            headline_title("** TODO Next steps   :work:urgent:"),
+            Some("Next steps")
+        );
+        assert_eq!(headline_title("Next steps"), None);
+    }
+}
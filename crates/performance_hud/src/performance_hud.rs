@@ -0,0 +1,364 @@
+use gpui::{
+    actions, anchored, deferred, point, px, AnchorCorner, AppContext, FrameProfile, Render, Task,
+    ViewContext,
+};
+use std::time::Duration;
+use sysinfo::{Pid, System};
+use ui::{prelude::*, Tooltip};
+use util::paths;
+use workspace::{
+    item::ItemHandle, notifications::NotificationId, StatusItemView, Toast, Workspace,
+};
+
+actions!(performance_hud, [Toggle, ExportChromeTrace]);
+
+/// How often the HUD is updated while it's shown.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The number of most recent frames summarized by the HUD.
+const RECENT_FRAMES: usize = 60;
+
+/// The number of views listed in the breakdown of the slowest recent frame.
+const MAX_LISTED_VIEWS: usize = 8;
+
+/// Frames that take longer than this miss a refresh at 60Hz.
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|_, _: &Toggle, cx| {
+                let enabled = !cx.frame_profiling();
+                cx.set_frame_profiling(enabled);
+            })
+            .register_action(|workspace, _: &ExportChromeTrace, cx| {
+                export_chrome_trace(workspace, cx);
+            });
+    })
+    .detach();
+}
+
+/// Prompts for a file and writes the recently profiled frames to it, in the Chrome
+/// trace event format.
+fn export_chrome_trace(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some(trace) = cx.frame_profile_chrome_trace() else {
+        struct ExportChromeTraceToast;
+
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<ExportChromeTraceToast>(),
+                "Show the performance HUD to start profiling frames before exporting a trace",
+            ),
+            cx,
+        );
+        return;
+    };
+    let fs = workspace.app_state().fs.clone();
+    let path = cx.prompt_for_new_path(&paths::HOME);
+    cx.spawn(|_, _| async move {
+        let Some(path) = path.await.ok().flatten() else {
+            return Ok(());
+        };
+        fs.atomic_write(path, trace).await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// An overlay showing how long recent frames took to draw, which views they
+/// spent that time in, and how many entities and how much memory Zed is using.
+///
+/// It's added to the status bar so there's one per window, but only renders
+/// anything while the window's frames are being profiled.
+pub struct PerformanceHud {
+    memory_usage: Option<u64>,
+    refresh_task: Option<Task<()>>,
+}
+
+impl PerformanceHud {
+    pub fn new(_: &mut ViewContext<Self>) -> Self {
+        Self {
+            memory_usage: None,
+            refresh_task: None,
+        }
+    }
+
+    /// Re-renders the HUD periodically for as long as frames are being profiled,
+    /// instead of on every frame, so the HUD itself doesn't cause frames to be drawn.
+    fn refresh_periodically(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_task = Some(cx.spawn(|this, mut cx| async move {
+            let pid = Pid::from_u32(std::process::id());
+            loop {
+                let memory_usage = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let mut system = System::new();
+                        system.refresh_process(pid);
+                        system.process(pid).map(|process| process.memory())
+                    })
+                    .await;
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+
+                let still_profiling = this
+                    .update(&mut cx, |this, cx| {
+                        this.memory_usage = memory_usage;
+                        cx.notify();
+                        cx.frame_profiling()
+                    })
+                    .unwrap_or(false);
+                if !still_profiling {
+                    this.update(&mut cx, |this, _| this.refresh_task = None)
+                        .ok();
+                    break;
+                }
+            }
+        }));
+    }
+
+    fn render_frame_times(&self, frames: &[&FrameProfile], cx: &ViewContext<Self>) -> Div {
+        let max_frame_time = frames
+            .iter()
+            .map(|frame| frame.frame_time)
+            .max()
+            .unwrap_or_default()
+            .max(FRAME_BUDGET);
+        let chart_height = 32.;
+
+        h_flex()
+            .h(px(chart_height))
+            .items_end()
+            .gap_px()
+            .children(frames.iter().map(|frame| {
+                let height = frame.frame_time.as_secs_f32() / max_frame_time.as_secs_f32();
+                div().w(px(3.)).h(px((height * chart_height).max(1.))).bg(
+                    if frame.frame_time > FRAME_BUDGET {
+                        cx.theme().status().error
+                    } else {
+                        cx.theme().status().success
+                    },
+                )
+            }))
+    }
+
+    fn render_views(&self, frame: &FrameProfile) -> Div {
+        v_flex()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(stat_label("View")))
+                    .child(div().w(px(60.)).child(stat_label("Layout")))
+                    .child(div().w(px(60.)).child(stat_label("Paint")))
+                    .child(div().w(px(32.)).child(stat_label("#"))),
+            )
+            .children(
+                frame
+                    .view_profiles()
+                    .into_iter()
+                    .take(MAX_LISTED_VIEWS)
+                    .map(|view| {
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .overflow_hidden()
+                                    .child(value_label(short_type_name(view.name))),
+                            )
+                            .child(
+                                div()
+                                    .w(px(60.))
+                                    .child(value_label(format_duration(view.layout_time))),
+                            )
+                            .child(
+                                div()
+                                    .w(px(60.))
+                                    .child(value_label(format_duration(view.paint_time))),
+                            )
+                            .child(div().w(px(32.)).child(value_label(view.count.to_string())))
+                    }),
+            )
+    }
+}
+
+impl Render for PerformanceHud {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        if !cx.frame_profiling() {
+            return div().into_any_element();
+        }
+        if self.refresh_task.is_none() {
+            self.refresh_periodically(cx);
+        }
+
+        let viewport_size = cx.viewport_size();
+        let hud = v_flex()
+            .id("performance-hud")
+            .w(px(360.))
+            .p_2()
+            .gap_2()
+            .elevation_3(cx);
+
+        let frames = cx.frame_profiles().collect::<Vec<_>>();
+        let frames = &frames[frames.len().saturating_sub(RECENT_FRAMES)..];
+        let last_frame = frames.last().copied();
+        let slowest_frame = frames.iter().copied().max_by_key(|frame| frame.frame_time);
+        let average_frame_time = if frames.is_empty() {
+            Duration::ZERO
+        } else {
+            frames
+                .iter()
+                .map(|frame| frame.frame_time)
+                .sum::<Duration>()
+                / frames.len() as u32
+        };
+
+        let hud = hud
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Performance").size(LabelSize::Small))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new("export-chrome-trace", IconName::ArrowDown)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action(
+                                            "Export Chrome Trace",
+                                            &ExportChromeTrace,
+                                            cx,
+                                        )
+                                    })
+                                    .on_click(|_, cx| {
+                                        cx.dispatch_action(Box::new(ExportChromeTrace))
+                                    }),
+                            )
+                            .child(
+                                IconButton::new("close-performance-hud", IconName::Close)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(|cx| Tooltip::for_action("Close", &Toggle, cx))
+                                    .on_click(|_, cx| cx.dispatch_action(Box::new(Toggle))),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(stat(
+                        "Frame",
+                        last_frame.map_or("-".into(), |frame| format_duration(frame.frame_time)),
+                    ))
+                    .child(stat(
+                        "Layout",
+                        last_frame.map_or("-".into(), |frame| format_duration(frame.layout_time)),
+                    ))
+                    .child(stat(
+                        "Paint",
+                        last_frame.map_or("-".into(), |frame| format_duration(frame.paint_time)),
+                    ))
+                    .child(stat("Average", format_duration(average_frame_time)))
+                    .child(stat(
+                        "Slowest",
+                        slowest_frame.map_or("-".into(), |frame| format_duration(frame.frame_time)),
+                    )),
+            )
+            .child(self.render_frame_times(frames, cx))
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(stat("Entities", cx.entity_count().to_string()))
+                    .child(stat(
+                        "Memory",
+                        self.memory_usage.map_or("-".into(), format_bytes),
+                    )),
+            )
+            .children(slowest_frame.map(|frame| {
+                v_flex()
+                    .gap_1()
+                    .child(stat_label(
+                        "Slowest recent frame, by time spent in each view",
+                    ))
+                    .child(self.render_views(frame))
+            }));
+
+        deferred(
+            anchored()
+                .anchor(AnchorCorner::TopRight)
+                .position(point(viewport_size.width - px(16.), px(48.)))
+                .child(hud),
+        )
+        .with_priority(1)
+        .into_any_element()
+    }
+}
+
+impl StatusItemView for PerformanceHud {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _cx: &mut ViewContext<Self>,
+    ) {
+    }
+}
+
+fn stat(label: &'static str, value: String) -> Div {
+    v_flex().child(stat_label(label)).child(value_label(value))
+}
+
+fn stat_label(label: &'static str) -> Label {
+    Label::new(label)
+        .size(LabelSize::XSmall)
+        .color(Color::Muted)
+}
+
+fn value_label(value: impl Into<SharedString>) -> Label {
+    Label::new(value).size(LabelSize::Small)
+}
+
+/// Strips the module path from a view's type name, including from its type parameters.
+fn short_type_name(name: &str) -> String {
+    let mut short_name = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (ix, char) in name.char_indices() {
+        if matches!(char, '<' | '>' | ',' | ' ' | '&' | '(' | ')') {
+            short_name.push_str(last_path_segment(&name[segment_start..ix]));
+            short_name.push(char);
+            segment_start = ix + 1;
+        }
+    }
+    short_name.push_str(last_path_segment(&name[segment_start..]));
+    short_name
+}
+
+fn last_path_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024. * 1024.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(short_type_name("editor::Editor"), "Editor");
+        assert_eq!(
+            short_type_name("workspace::item::ItemView<project_panel::ProjectPanel>"),
+            "ItemView<ProjectPanel>"
+        );
+        assert_eq!(short_type_name("gpui::Foo<a::B, c::D>"), "Foo<B, D>");
+        assert_eq!(short_type_name("Workspace"), "Workspace");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_duration(Duration::ZERO), "0.00ms");
+    }
+}
@@ -48,6 +48,8 @@ pub struct Picker<D: PickerDelegate> {
     ///
     /// Set this to `false` when rendering the `Picker` as part of a larger modal.
     is_modal: bool,
+    /// The last message announced to screen readers about the selected match.
+    announced_selection: Option<String>,
 }
 
 pub trait PickerDelegate: Sized + 'static {
@@ -87,6 +89,11 @@ pub trait PickerDelegate: Sized + 'static {
     fn selected_as_query(&self) -> Option<String> {
         None
     }
+    /// A plain-text description of the match at the given index, which is announced
+    /// to screen readers when the match is selected.
+    fn accessible_label(&self, _ix: usize, _cx: &AppContext) -> Option<String> {
+        None
+    }
 
     fn render_match(
         &self,
@@ -162,6 +169,7 @@ impl<D: PickerDelegate> Picker<D> {
             width: None,
             max_height: None,
             is_modal: true,
+            announced_selection: None,
         };
         this.update_matches("".to_string(), cx);
         // give the delegate 4ms to render the first set of suggestions.
@@ -224,6 +232,7 @@ impl<D: PickerDelegate> Picker<D> {
             let ix = if index == count - 1 { 0 } else { index + 1 };
             self.delegate.set_selected_index(ix, cx);
             self.scroll_to_item_index(ix);
+            self.announce_selection(cx);
             cx.notify();
         }
     }
@@ -235,6 +244,7 @@ impl<D: PickerDelegate> Picker<D> {
             let ix = if index == 0 { count - 1 } else { index - 1 };
             self.delegate.set_selected_index(ix, cx);
             self.scroll_to_item_index(ix);
+            self.announce_selection(cx);
             cx.notify();
         }
     }
@@ -244,6 +254,7 @@ impl<D: PickerDelegate> Picker<D> {
         if count > 0 {
             self.delegate.set_selected_index(0, cx);
             self.scroll_to_item_index(0);
+            self.announce_selection(cx);
             cx.notify();
         }
     }
@@ -253,6 +264,7 @@ impl<D: PickerDelegate> Picker<D> {
         if count > 0 {
             self.delegate.set_selected_index(count - 1, cx);
             self.scroll_to_item_index(count - 1);
+            self.announce_selection(cx);
             cx.notify();
         }
     }
@@ -263,6 +275,7 @@ impl<D: PickerDelegate> Picker<D> {
         let new_index = if index + 1 == count { 0 } else { index + 1 };
         self.delegate.set_selected_index(new_index, cx);
         self.scroll_to_item_index(new_index);
+        self.announce_selection(cx);
         cx.notify();
     }
 
@@ -383,6 +396,7 @@ impl<D: PickerDelegate> Picker<D> {
 
         let index = self.delegate.selected_index();
         self.scroll_to_item_index(index);
+        self.announce_selection(cx);
         self.pending_update_matches = None;
         if let Some(secondary) = self.confirm_on_update.take() {
             self.delegate.confirm(secondary, cx);
@@ -409,6 +423,27 @@ impl<D: PickerDelegate> Picker<D> {
         }
     }
 
+    /// Tells screen readers which match is selected, or that nothing matches the query.
+    fn announce_selection(&mut self, cx: &mut ViewContext<Self>) {
+        let count = self.delegate.match_count();
+        let message = if count == 0 {
+            if self.query(cx).is_empty() {
+                return;
+            }
+            self.delegate.no_matches_text(cx).to_string()
+        } else {
+            let ix = self.delegate.selected_index();
+            let Some(label) = self.delegate.accessible_label(ix, cx) else {
+                return;
+            };
+            format!("{label}, {} of {count}", ix + 1)
+        };
+        if self.announced_selection.as_ref() != Some(&message) {
+            cx.announce(&message);
+            self.announced_selection = Some(message);
+        }
+    }
+
     fn scroll_to_item_index(&mut self, ix: usize) {
         match &mut self.element_container {
             ElementContainer::List(state) => state.scroll_to_reveal_item(ix),
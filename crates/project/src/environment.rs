@@ -0,0 +1,255 @@
+use crate::{project_settings::ProjectSettings, Project};
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use gpui::{AppContext, Model, ModelContext};
+use settings::{Settings, SettingsLocation};
+use std::{fmt, path::Path};
+use util::{paths::LOCAL_ENV_RELATIVE_PATH, ResultExt};
+use worktree::{Worktree, WorktreeId};
+
+/// Where a variable of a [`WorktreeEnvironment`] is defined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvironmentSource {
+    /// The `.envrc` file of the worktree, loaded with `direnv`.
+    Direnv,
+    /// The `env` setting.
+    Settings,
+    /// The `.zed/env` file of the worktree.
+    EnvFile,
+}
+
+impl fmt::Display for EnvironmentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Direnv => write!(f, "direnv"),
+            Self::Settings => write!(f, "settings"),
+            Self::EnvFile => write!(f, "{}", LOCAL_ENV_RELATIVE_PATH.display()),
+        }
+    }
+}
+
+/// The environment variables that a project defines for the terminals, tasks and
+/// language servers that it starts in a worktree.
+#[derive(Clone, Debug, Default)]
+pub struct WorktreeEnvironment {
+    variables: BTreeMap<String, (String, EnvironmentSource)>,
+}
+
+impl WorktreeEnvironment {
+    /// Adds the variables, replacing those of the same name from earlier sources.
+    fn extend(
+        &mut self,
+        source: EnvironmentSource,
+        variables: impl IntoIterator<Item = (String, String)>,
+    ) {
+        for (name, value) in variables {
+            self.variables.insert(name, (value, source));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    /// Iterates over the variables, sorted by name, along with where they come from.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, EnvironmentSource)> {
+        self.variables
+            .iter()
+            .map(|(name, (value, source))| (name.as_str(), value.as_str(), *source))
+    }
+
+    pub fn to_env(&self) -> HashMap<String, String> {
+        self.iter()
+            .map(|(name, value, _)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+impl Project {
+    /// Returns the environment of the worktree, which is made of the variables
+    /// that `direnv` loaded for it if enabled, then the `env` setting, then those
+    /// in its `.zed/env` file, each overriding the previous ones.
+    pub fn worktree_environment(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> WorktreeEnvironment {
+        let mut environment = WorktreeEnvironment::default();
+        if let Some(direnv_environment) = self.direnv_environments.get(&worktree_id) {
+            environment.extend(EnvironmentSource::Direnv, direnv_environment.clone());
+        }
+
+        let settings = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree_id.to_proto() as usize,
+                path: Path::new(""),
+            }),
+            cx,
+        );
+        environment.extend(EnvironmentSource::Settings, settings.env.clone());
+
+        let worktree_abs_path = self
+            .worktree_for_id(worktree_id, cx)
+            .and_then(|worktree| Some(worktree.read(cx).as_local()?.abs_path().clone()));
+        if let Some(worktree_abs_path) = worktree_abs_path {
+            let env_file_path = worktree_abs_path.join(&*LOCAL_ENV_RELATIVE_PATH);
+            if let Ok(contents) = std::fs::read_to_string(&env_file_path) {
+                environment.extend(EnvironmentSource::EnvFile, parse_env_file(&contents));
+            }
+        }
+
+        environment
+    }
+
+    /// Returns the environment of the local worktree that contains the path.
+    pub fn environment_for_path(&self, abs_path: &Path, cx: &AppContext) -> WorktreeEnvironment {
+        self.find_local_worktree(abs_path, cx)
+            .map(|(worktree, _)| self.worktree_environment(worktree.read(cx).id(), cx))
+            .unwrap_or_default()
+    }
+
+    /// Loads the environment that `direnv` sets up for the worktree's `.envrc`,
+    /// if `load_direnv` is enabled.
+    pub(crate) fn load_direnv_environment(
+        &mut self,
+        worktree: &Model<Worktree>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local_worktree) = worktree.read(cx).as_local() else {
+            return;
+        };
+        let worktree_id = local_worktree.id();
+        if !load_direnv_enabled(worktree_id, cx) {
+            self.direnv_environments.remove(&worktree_id);
+            return;
+        }
+
+        let worktree_abs_path = local_worktree.abs_path().clone();
+        cx.spawn(|this, mut cx| async move {
+            let environment = load_direnv_environment(&worktree_abs_path)
+                .await
+                .with_context(|| format!("failed to load direnv in {worktree_abs_path:?}"))
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, |this, _| {
+                this.direnv_environments.insert(worktree_id, environment);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Loads or drops the `direnv` environments of the worktrees for which the
+    /// `load_direnv` setting has changed.
+    pub(crate) fn refresh_direnv_environments(&mut self, cx: &mut ModelContext<Self>) {
+        let worktrees = self
+            .worktrees()
+            .filter(|worktree| {
+                let worktree_id = worktree.read(cx).id();
+                load_direnv_enabled(worktree_id, cx)
+                    != self.direnv_environments.contains_key(&worktree_id)
+            })
+            .collect::<Vec<_>>();
+        for worktree in worktrees {
+            self.load_direnv_environment(&worktree, cx);
+        }
+    }
+}
+
+fn load_direnv_enabled(worktree_id: WorktreeId, cx: &AppContext) -> bool {
+    ProjectSettings::get(
+        Some(SettingsLocation {
+            worktree_id: worktree_id.to_proto() as usize,
+            path: Path::new(""),
+        }),
+        cx,
+    )
+    .load_direnv
+}
+
+async fn load_direnv_environment(dir: &Path) -> Result<HashMap<String, String>> {
+    let output = smol::process::Command::new("direnv")
+        .args(["export", "json"])
+        .current_dir(dir)
+        .output()
+        .await
+        .context("failed to spawn direnv")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "direnv exited with error {:?}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // direnv prints nothing when the directory has no `.envrc`, and `null` for
+    // the variables that it unsets.
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(HashMap::default());
+    }
+    let variables: HashMap<String, Option<String>> = serde_json::from_slice(&output.stdout)?;
+    Ok(variables
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect())
+}
+
+/// Parses the `NAME=value` lines of a dotenv file. Empty lines and those starting
+/// with `#` are skipped, a leading `export` is allowed, and values can be quoted.
+pub fn parse_env_file(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|quote| {
+                    value
+                        .strip_prefix(*quote)
+                        .and_then(|value| value.strip_suffix(*quote))
+                })
+                .unwrap_or(value);
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let contents = r#"
+            # Database settings
+            DATABASE_URL=postgres://localhost/dev
+            export RUST_LOG = debug
+            GREETING="hello world"
+            QUOTE='single'
+            EMPTY=
+            not a variable
+            =missing name
+        "#;
+        assert_eq!(
+            parse_env_file(contents),
+            [
+                ("DATABASE_URL", "postgres://localhost/dev"),
+                ("RUST_LOG", "debug"),
+                ("GREETING", "hello world"),
+                ("QUOTE", "single"),
+                ("EMPTY", ""),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+}
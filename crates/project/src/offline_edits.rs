@@ -0,0 +1,197 @@
+//! Merging the edits that a guest made while disconnected from a remote project
+//! back into the host's copy, once the project has been rejoined.
+
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::{Anchor, Buffer, TextBufferSnapshot, ToOffset};
+
+use crate::{File, Project, ProjectPath};
+
+/// The outcome of merging the offline edits that were made to a single buffer.
+#[derive(Clone, Debug)]
+pub struct OfflineBufferMerge {
+    pub project_path: ProjectPath,
+    /// The buffer in the rejoined project that the edits were merged into.
+    pub buffer: Model<Buffer>,
+    pub merged_edit_count: usize,
+    /// Offline edits that touched text that someone else also changed while this guest
+    /// was disconnected. They were merged anyway, but should be reviewed.
+    pub conflicts: Vec<Range<Anchor>>,
+    /// Offline edits that couldn't be merged, because the text they were made against
+    /// is no longer part of the host's copy of the buffer.
+    pub skipped_edit_count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OfflineMergeSummary {
+    pub buffers: Vec<OfflineBufferMerge>,
+}
+
+impl OfflineMergeSummary {
+    pub fn merged_edit_count(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|buffer| buffer.merged_edit_count)
+            .sum()
+    }
+
+    pub fn conflict_count(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|buffer| buffer.conflicts.len())
+            .sum()
+    }
+
+    pub fn skipped_edit_count(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|buffer| buffer.skipped_edit_count)
+            .sum()
+    }
+
+    /// Whether every offline edit was merged without touching anyone else's changes.
+    pub fn is_clean(&self) -> bool {
+        self.conflict_count() == 0 && self.skipped_edit_count() == 0
+    }
+}
+
+impl Project {
+    /// Whether any buffer was edited after this project lost its connection to the host.
+    pub fn has_offline_edits(&self, cx: &AppContext) -> bool {
+        !self.offline_edited_buffers(cx).is_empty()
+    }
+
+    /// Applies the edits that were made to `offline_project`'s buffers while it was
+    /// disconnected to the corresponding buffers of this project, which must be a fresh
+    /// replica of the same remote project.
+    ///
+    /// Both projects share the history of the host's buffers up to the point where the
+    /// connection was lost, so the offline edits are anchored in that shared history and
+    /// resolved against whatever the other collaborators have done since.
+    pub fn merge_offline_edits(
+        &mut self,
+        offline_project: &Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<OfflineMergeSummary>> {
+        if !self.is_remote() || self.is_disconnected() {
+            return Task::ready(Err(anyhow!(
+                "offline edits can only be merged into a connected remote project"
+            )));
+        }
+
+        let edited_buffers = offline_project.read(cx).offline_edited_buffers(cx);
+        cx.spawn(move |this, mut cx| async move {
+            let mut summary = OfflineMergeSummary::default();
+            for (project_path, base, offline) in edited_buffers {
+                let buffer = this
+                    .update(&mut cx, |this, cx| {
+                        this.open_buffer(project_path.clone(), cx)
+                    })?
+                    .await?;
+                let (merged_edit_count, conflicts, skipped_edit_count) = buffer
+                    .update(&mut cx, |buffer, cx| {
+                        apply_offline_edits(buffer, &base, &offline, cx)
+                    })?;
+                summary.buffers.push(OfflineBufferMerge {
+                    project_path,
+                    buffer,
+                    merged_edit_count,
+                    conflicts,
+                    skipped_edit_count,
+                });
+            }
+            Ok(summary)
+        })
+    }
+
+    /// Returns the path of each buffer that has been edited since the connection to the host
+    /// was lost, along with its state at the time of the disconnect and its current state.
+    fn offline_edited_buffers(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(ProjectPath, TextBufferSnapshot, TextBufferSnapshot)> {
+        self.offline_buffer_bases
+            .iter()
+            .filter_map(|(buffer_id, base)| {
+                let buffer = self.buffer_for_id(*buffer_id)?.read(cx);
+                if !buffer.version().changed_since(&base.version) {
+                    return None;
+                }
+                let file = File::from_dyn(buffer.file())?;
+                let project_path = ProjectPath {
+                    worktree_id: file.worktree_id(cx),
+                    path: file.path.clone(),
+                };
+                Some((project_path, base.clone(), buffer.text_snapshot()))
+            })
+            .collect()
+    }
+}
+
+/// Replays the edits that turned `base` into `offline` on `buffer`, returning the number of
+/// merged edits, the ranges of the merged edits that conflict with concurrent changes, and
+/// the number of edits that had to be skipped.
+fn apply_offline_edits(
+    buffer: &mut Buffer,
+    base: &TextBufferSnapshot,
+    offline: &TextBufferSnapshot,
+    cx: &mut ModelContext<Buffer>,
+) -> (usize, Vec<Range<Anchor>>, usize) {
+    // If the host still has the buffer that was disconnected from, offline edits can be
+    // anchored in their shared history. Otherwise, offsets are only meaningful if nobody
+    // has changed the text in the meantime.
+    let shares_history = buffer.remote_id() == base.remote_id();
+    let text_is_unchanged = !shares_history && buffer.text() == base.text();
+    let concurrent_edits = if shares_history {
+        buffer
+            .edits_since::<usize>(&base.version)
+            .map(|edit| edit.new)
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
+    let mut edits = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut skipped_edit_count = 0;
+    for edit in offline.edits_since::<usize>(&base.version) {
+        let range = if shares_history {
+            // Anchor replaced text to its own first and last characters, so that text
+            // that others inserted next to it concurrently is left alone.
+            let (start, end) = if edit.old.is_empty() {
+                let position = base.anchor_before(edit.old.start);
+                (position, position)
+            } else {
+                (
+                    base.anchor_after(edit.old.start),
+                    base.anchor_before(edit.old.end),
+                )
+            };
+            if !buffer.can_resolve(&start) || !buffer.can_resolve(&end) {
+                skipped_edit_count += 1;
+                continue;
+            }
+            start.to_offset(buffer)..end.to_offset(buffer)
+        } else if text_is_unchanged {
+            edit.old
+        } else {
+            skipped_edit_count += 1;
+            continue;
+        };
+
+        if concurrent_edits
+            .iter()
+            .any(|concurrent| concurrent.start <= range.end && range.start <= concurrent.end)
+        {
+            conflicts.push(buffer.anchor_before(range.start)..buffer.anchor_after(range.end));
+        }
+        let new_text = offline.text_for_range(edit.new).collect::<String>();
+        edits.push((range, new_text));
+    }
+
+    let merged_edit_count = edits.len();
+    buffer.edit(edits, None, cx);
+    (merged_edit_count, conflicts, skipped_edit_count)
+}
@@ -1,9 +1,12 @@
 pub mod connection_manager;
 pub mod debounced_delay;
+pub mod environment;
 pub mod lsp_command;
 pub mod lsp_ext_command;
+mod offline_edits;
 mod prettier_support;
 pub mod project_settings;
+mod provider_support;
 pub mod search;
 mod task_inventory;
 pub mod terminals;
@@ -109,8 +112,10 @@ use util::{
 };
 use worktree::{Snapshot, Traversal};
 
+pub use environment::{EnvironmentSource, WorktreeEnvironment};
 pub use fs::*;
 pub use language::Location;
+pub use offline_edits::{OfflineBufferMerge, OfflineMergeSummary};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{Inventory, TaskSourceKind};
@@ -126,6 +131,11 @@ const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5
 pub const SERVER_PROGRESS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
 
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
+const INVISIBLE_CHARACTER_SCAN_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The id that warnings about invisible control characters are reported under. No
+/// language server is ever assigned it.
+pub const INVISIBLE_CHARACTERS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
 
 pub trait Item {
     fn try_open(
@@ -171,6 +181,7 @@ pub struct Project {
     next_entry_id: Arc<AtomicUsize>,
     join_project_response_message_id: u32,
     next_diagnostic_group_id: usize,
+    lint_provider_server_ids: HashMap<Arc<str>, LanguageServerId>,
     user_store: Model<UserStore>,
     fs: Arc<dyn Fs>,
     client_state: ProjectClientState,
@@ -194,11 +205,17 @@ pub struct Project {
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
+    invisible_character_scans: HashMap<BufferId, Task<()>>,
+    /// The state of each buffer at the moment a remote project lost its connection to the host,
+    /// used to find and merge the edits that were made to it while offline.
+    offline_buffer_bases: HashMap<BufferId, TextBufferSnapshot>,
     git_diff_debouncer: DebouncedDelay,
     nonce: u128,
     _maintain_buffer_languages: Task<()>,
     _maintain_workspace_config: Task<Result<()>>,
     terminals: Terminals,
+    /// The environment that direnv loaded for each local worktree, if enabled.
+    direnv_environments: HashMap<WorktreeId, HashMap<String, String>>,
     copilot_lsp_subscription: Option<gpui::Subscription>,
     copilot_log_subscription: Option<lsp::Subscription>,
     current_lsp_settings: HashMap<Arc<str>, LspSettings>,
@@ -335,6 +352,17 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    TerminalShared(u64),
+    TerminalUnshared(u64),
+    SharedTerminalUpdated(u64),
+    TerminalControlRequested {
+        terminal_id: u64,
+        peer_id: proto::PeerId,
+    },
+    SharedTerminalInput {
+        terminal_id: u64,
+        peer_id: proto::PeerId,
+    },
 }
 
 pub enum LanguageServerState {
@@ -541,6 +569,7 @@ enum FormatOperation {
     Lsp(Vec<(Range<Anchor>, String)>),
     External(Diff),
     Prettier(Diff),
+    Provider(Diff),
 }
 
 impl FormatTrigger {
@@ -650,6 +679,10 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_message_handler(Self::handle_request_terminal_control);
+        client.add_model_message_handler(Self::handle_shared_terminal_input);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_blame_buffer);
         client.add_model_request_handler(Self::handle_multi_lsp_query);
@@ -703,6 +736,7 @@ impl Project {
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                lint_provider_server_ids: Default::default(),
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
@@ -712,11 +746,15 @@ impl Project {
                 language_server_watched_paths: HashMap::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
+                invisible_character_scans: Default::default(),
+                offline_buffer_bases: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    shared: HashMap::default(),
                 },
+                direnv_environments: HashMap::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
@@ -829,6 +867,7 @@ impl Project {
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                lint_provider_server_ids: Default::default(),
                 client_subscriptions: Default::default(),
                 _subscriptions: vec![
                     cx.on_release(Self::release),
@@ -867,12 +906,16 @@ impl Project {
                 opened_buffers: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
+                invisible_character_scans: Default::default(),
+                offline_buffer_bases: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    shared: HashMap::default(),
                 },
+                direnv_environments: HashMap::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
@@ -1166,6 +1209,8 @@ impl Project {
             self.restart_language_servers(worktree, language, cx);
         }
 
+        self.refresh_direnv_environments(cx);
+
         if self.copilot_lsp_subscription.is_none() {
             if let Some(copilot) = Copilot::global(cx) {
                 for buffer in self.opened_buffers.values() {
@@ -1800,6 +1845,7 @@ impl Project {
             self.collaborators.clear();
             self.shared_buffers.clear();
             self.client_subscriptions.clear();
+            self.terminals.shared.clear();
 
             for worktree_handle in self.worktrees.iter_mut() {
                 if let WorktreeHandle::Strong(worktree) = worktree_handle {
@@ -1862,10 +1908,12 @@ impl Project {
     fn disconnected_from_host_internal(&mut self, cx: &mut AppContext) {
         if let ProjectClientState::Remote {
             sharing_has_stopped,
+            capability,
             ..
         } = &mut self.client_state
         {
             *sharing_has_stopped = true;
+            let can_edit_offline = *capability == Capability::ReadWrite;
 
             self.collaborators.clear();
 
@@ -1879,10 +1927,18 @@ impl Project {
                 }
             }
 
-            for open_buffer in self.opened_buffers.values_mut() {
+            for (buffer_id, open_buffer) in self.opened_buffers.iter_mut() {
                 // Wake up any tasks waiting for peers' edits to this buffer.
                 if let Some(buffer) = open_buffer.upgrade() {
                     buffer.update(cx, |buffer, _| buffer.give_up_waiting());
+
+                    // Keep the buffers alive so that they can still be edited, and remember
+                    // where the host's copy left off so the edits can be merged back later.
+                    if can_edit_offline {
+                        self.offline_buffer_bases
+                            .insert(*buffer_id, buffer.read(cx).text_snapshot());
+                        continue;
+                    }
                 }
 
                 if let OpenBuffer::Strong(buffer) = open_buffer {
@@ -1911,6 +1967,12 @@ impl Project {
         }
     }
 
+    /// Whether this project has lost its connection to the host, but its open buffers
+    /// can still be edited locally, to be merged back in once the project is rejoined.
+    pub fn is_editing_offline(&self) -> bool {
+        self.is_disconnected() && self.capability() == Capability::ReadWrite
+    }
+
     pub fn capability(&self) -> Capability {
         match &self.client_state {
             ProjectClientState::Remote { capability, .. } => *capability,
@@ -2185,6 +2247,12 @@ impl Project {
         let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
             return Task::ready(Err(anyhow!("buffer doesn't have a file")));
         };
+        if buffer.read(cx).is_lossy() {
+            return Task::ready(Err(anyhow!(
+                "refusing to overwrite {:?}, whose invalid UTF-8 was replaced when it was opened",
+                file.path
+            )));
+        }
         let worktree = file.worktree.clone();
         let path = file.path.clone();
         worktree.update(cx, |worktree, cx| match worktree {
@@ -2250,6 +2318,7 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
         self.request_buffer_diff_recalculation(buffer, cx);
+        self.scan_for_invisible_characters(buffer, cx);
         buffer.update(cx, |buffer, _| {
             buffer.set_language_registry(self.languages.clone())
         });
@@ -2413,6 +2482,8 @@ impl Project {
                         .insert(server.server_id(), vec![snapshot]);
                 }
             }
+
+            self.lint_buffer_with_providers(buffer_handle, cx);
         }
     }
 
@@ -2583,9 +2654,16 @@ impl Project {
         ) {
             self.request_buffer_diff_recalculation(&buffer, cx);
         }
+        if matches!(event, BufferEvent::Edited { .. } | BufferEvent::Reloaded) {
+            self.scan_for_invisible_characters(&buffer, cx);
+        }
 
         match event {
             BufferEvent::Operation(operation) => {
+                // Edits made while offline are merged in when the project is rejoined.
+                if self.is_disconnected() {
+                    return None;
+                }
                 self.enqueue_buffer_ordered_message(BufferOrderedMessage::Operation {
                     buffer_id: buffer.read(cx).remote_id(),
                     operation: language::proto::serialize_operation(operation),
@@ -2690,6 +2768,8 @@ impl Project {
             }
 
             BufferEvent::Saved => {
+                self.lint_buffer_with_providers(&buffer, cx);
+
                 let file = File::from_dyn(buffer.read(cx).file())?;
                 let worktree_id = file.worktree_id(cx);
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2783,6 +2863,71 @@ impl Project {
         None
     }
 
+    /// Reports the invisible control characters in the buffer as warnings once it
+    /// hasn't been edited for a moment. The host reports them for remote projects.
+    fn scan_for_invisible_characters(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.is_remote() {
+            return;
+        }
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer = buffer.downgrade();
+        let scan = cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(INVISIBLE_CHARACTER_SCAN_DEBOUNCE)
+                .await;
+            let Ok(snapshot) = buffer.update(&mut cx, |buffer, _| buffer.snapshot()) else {
+                return;
+            };
+            let (snapshot, diagnostics) = cx
+                .background_executor()
+                .spawn(async move {
+                    let diagnostics = snapshot
+                        .invisible_control_characters()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(group_id, (range, c))| DiagnosticEntry {
+                            range: snapshot.offset_to_point_utf16(range.start)
+                                ..snapshot.offset_to_point_utf16(range.end),
+                            diagnostic: Diagnostic {
+                                source: Some("zed".to_string()),
+                                severity: DiagnosticSeverity::WARNING,
+                                message: invisible_character_message(c),
+                                group_id,
+                                is_primary: true,
+                                ..Default::default()
+                            },
+                        })
+                        .collect::<Vec<_>>();
+                    (snapshot, diagnostics)
+                })
+                .await;
+
+            buffer
+                .update(&mut cx, |buffer, cx| {
+                    if diagnostics.is_empty()
+                        && snapshot
+                            .diagnostic_groups(Some(INVISIBLE_CHARACTERS_SERVER_ID))
+                            .is_empty()
+                    {
+                        return;
+                    }
+                    let diagnostics = DiagnosticSet::new(diagnostics, &snapshot);
+                    buffer.update_diagnostics(INVISIBLE_CHARACTERS_SERVER_ID, diagnostics, cx);
+                })
+                .ok();
+            this.update(&mut cx, |this, _| {
+                this.invisible_character_scans.remove(&buffer_id);
+            })
+            .ok();
+        });
+        self.invisible_character_scans.insert(buffer_id, scan);
+    }
+
     fn request_buffer_diff_recalculation(
         &mut self,
         buffer: &Model<Buffer>,
@@ -3048,6 +3193,16 @@ impl Project {
         }
     }
 
+    /// Returns a delegate that gives restricted access to the given worktree,
+    /// as used by language server adapters and extensions.
+    pub fn worktree_delegate(
+        &self,
+        worktree: &Model<Worktree>,
+        cx: &ModelContext<Self>,
+    ) -> Arc<dyn LspAdapterDelegate> {
+        ProjectLspAdapterDelegate::new(self, worktree, cx)
+    }
+
     fn start_language_servers(
         &mut self,
         worktree: &Model<Worktree>,
@@ -3099,12 +3254,14 @@ impl Project {
 
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let lsp_adapter_delegate = ProjectLspAdapterDelegate::new(self, worktree_handle, cx);
+        let environment = self.worktree_environment(worktree_id, cx).to_env();
         let pending_server = match self.languages.create_pending_language_server(
             stderr_capture.clone(),
             language.clone(),
             adapter.clone(),
             Arc::clone(&worktree_path),
             lsp_adapter_delegate.clone(),
+            environment,
             cx,
         ) {
             Some(pending_server) => pending_server,
@@ -4793,10 +4950,19 @@ impl Project {
                     }
                 }
                 (Formatter::Auto, FormatOnSave::On | FormatOnSave::Off) => {
-                    let prettier =
-                        prettier_support::format_with_prettier(&project, buffer, &mut cx).await;
+                    // Prefer a formatter provided by an extension over prettier.
+                    let operation =
+                        match provider_support::format_with_provider(&project, buffer, &mut cx)
+                            .await
+                        {
+                            Some(operation) => Some(operation),
+                            None => {
+                                prettier_support::format_with_prettier(&project, buffer, &mut cx)
+                                    .await
+                            }
+                        };
 
-                    if let Some(operation) = prettier {
+                    if let Some(operation) = operation {
                         format_operation = Some(operation?);
                     } else if let Some((language_server, buffer_abs_path)) = server_and_buffer {
                         format_operation = Some(FormatOperation::Lsp(
@@ -4821,6 +4987,14 @@ impl Project {
                         format_operation = Some(operation?);
                     }
                 }
+                (Formatter::Extension, FormatOnSave::On | FormatOnSave::Off) => {
+                    let provider =
+                        provider_support::format_with_provider(&project, buffer, &mut cx).await;
+
+                    if let Some(operation) = provider {
+                        format_operation = Some(operation?);
+                    }
+                }
             };
 
             buffer.update(&mut cx, |b, cx| {
@@ -4848,6 +5022,9 @@ impl Project {
                         FormatOperation::Prettier(diff) => {
                             b.apply_diff(diff, cx);
                         }
+                        FormatOperation::Provider(diff) => {
+                            b.apply_diff(diff, cx);
+                        }
                     }
 
                     if let Some(transaction_id) = whitespace_transaction_id {
@@ -5709,15 +5886,42 @@ impl Project {
                     .as_ref()
                     .and_then(|options| options.resolve_provider)
                     .unwrap_or(false);
-                let additional_text_edits = if can_resolve {
+                let completion_item = if can_resolve {
                     lang_server
                         .request::<lsp::request::ResolveCompletionItem>(completion.lsp_completion)
                         .await?
-                        .additional_text_edits
                 } else {
-                    completion.lsp_completion.additional_text_edits
+                    completion.lsp_completion
                 };
-                if let Some(edits) = additional_text_edits {
+
+                // Some servers add imports with a command rather than additional edits.
+                // Commands meant for the client, like triggering signature help, are
+                // skipped since the server can't execute them.
+                if let Some(command) = completion_item.command.filter(|command| {
+                    lang_server
+                        .capabilities()
+                        .execute_command_provider
+                        .as_ref()
+                        .map_or(false, |provider| {
+                            provider.commands.contains(&command.command)
+                        })
+                }) {
+                    lang_server
+                        .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                            command: command.command,
+                            arguments: command.arguments.unwrap_or_default(),
+                            ..Default::default()
+                        })
+                        .await
+                        .context("executing the completion's command")
+                        .log_err();
+                    this.update(&mut cx, |this, _| {
+                        this.last_workspace_edits_by_language_server
+                            .remove(&lang_server.server_id());
+                    })?;
+                }
+
+                if let Some(edits) = completion_item.additional_text_edits {
                     let edits = this
                         .update(&mut cx, |this, cx| {
                             this.edits_from_lsp(
@@ -5977,6 +6181,54 @@ impl Project {
         }
     }
 
+    /// Applies a quick fix that adds an import for each diagnostic in the buffer,
+    /// like an unresolved name, using the code actions of all its language servers.
+    pub fn add_missing_imports(
+        &mut self,
+        buffer: Model<Buffer>,
+        push_to_history: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let mut ranges = snapshot
+            .diagnostics_in_range::<_, usize>(0..snapshot.len(), false)
+            .map(|entry| entry.range)
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| (range.start, range.end));
+        ranges.dedup();
+        let code_actions = ranges
+            .into_iter()
+            .map(|range| self.code_actions(&buffer, range, cx))
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            // The same import is often offered for several diagnostics.
+            let mut titles = HashSet::default();
+            let mut import_actions = Vec::new();
+            for actions in join_all(code_actions).await {
+                if let Some(action) = actions
+                    .into_iter()
+                    .find(|action| is_import_fix(&action.lsp_action))
+                {
+                    if titles.insert(action.lsp_action.title.clone()) {
+                        import_actions.push(action);
+                    }
+                }
+            }
+
+            let mut project_transaction = ProjectTransaction::default();
+            for action in import_actions {
+                let transaction = this
+                    .update(&mut cx, |this, cx| {
+                        this.apply_code_action(buffer.clone(), action, push_to_history, cx)
+                    })?
+                    .await?;
+                project_transaction.0.extend(transaction.0);
+            }
+            Ok(project_transaction)
+        })
+    }
+
     fn apply_on_type_formatting(
         &self,
         buffer: Model<Buffer>,
@@ -6633,13 +6885,19 @@ impl Project {
             matching_paths.sort_by_key(|candidate| (candidate.is_ignored(), candidate.path()));
 
             let mut range_count = 0;
-            let query = Arc::new(query);
+            // A single buffer can't report more ranges than the whole search is allowed to.
+            let query = Arc::new(query.with_max_matches(MAX_SEARCH_RESULT_RANGES + 1));
 
             // Now that we know what paths match the query, we will load at most
             // 64 buffers at a time to avoid overwhelming the main thread. For each
             // opened buffer, we will spawn a background task that retrieves all the
             // ranges in the buffer matched by the query.
             'outer: for matching_paths_chunk in matching_paths.chunks(64) {
+                // Stop opening and searching buffers once the search has been cancelled.
+                if result_tx.is_closed() {
+                    break;
+                }
+
                 let mut chunk_results = Vec::new();
                 for matching_path in matching_paths_chunk {
                     let query = query.clone();
@@ -7117,6 +7375,7 @@ impl Project {
         self.task_inventory().update(cx, |inventory, _| {
             inventory.remove_worktree_sources(id_to_remove);
         });
+        self.direnv_environments.remove(&id_to_remove);
 
         self.worktrees.retain(|worktree| {
             if let Some(worktree) = worktree.upgrade() {
@@ -7145,6 +7404,12 @@ impl Project {
                         this.update_local_worktree_language_servers(&worktree, changes, cx);
                         this.update_local_worktree_settings(&worktree, changes, cx);
                         this.update_prettier_settings(&worktree, changes, cx);
+                        if changes
+                            .iter()
+                            .any(|(path, _, _)| path.as_ref() == Path::new(".envrc"))
+                        {
+                            this.load_direnv_environment(&worktree, cx);
+                        }
                     }
 
                     cx.emit(Event::WorktreeUpdatedEntries(
@@ -7189,6 +7454,7 @@ impl Project {
         })
         .detach();
 
+        self.load_direnv_environment(worktree, cx);
         cx.emit(Event::WorktreeAdded);
         self.metadata_changed(cx);
     }
@@ -8032,6 +8298,9 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            if this.is_local() {
+                this.resend_shared_terminals(cx);
+            }
             cx.notify();
         })?;
 
@@ -8109,6 +8378,7 @@ impl Project {
                 }
             }
             this.shared_buffers.remove(&peer_id);
+            this.remove_terminal_controller(peer_id, cx);
 
             cx.emit(Event::CollaboratorLeft(peer_id));
             cx.notify();
@@ -9986,6 +10256,18 @@ impl Project {
     }
 }
 
+/// Returns whether a code action is a quick fix that adds an import. Servers don't
+/// have a kind for these, so they're recognized by titles like `Import 'Foo' from
+/// module "./foo"` or `Add import: foo`.
+fn is_import_fix(action: &lsp::CodeAction) -> bool {
+    let is_quick_fix = action.kind.as_ref().map_or(true, |kind| {
+        kind.as_str()
+            .starts_with(lsp::CodeActionKind::QUICKFIX.as_str())
+    });
+    let title = action.title.to_lowercase();
+    is_quick_fix && title.contains("import") && !title.contains("remove")
+}
+
 async fn populate_labels_for_symbols(
     symbols: Vec<CoreSymbol>,
     language_registry: &Arc<LanguageRegistry>,
@@ -10822,6 +11104,16 @@ fn deserialize_blame_buffer_response(response: proto::BlameBufferResponse) -> gi
     }
 }
 
+fn invisible_character_message(c: char) -> String {
+    let description = match c {
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => {
+            "Invisible bidirectional control character, which can make code display in a different order than it's interpreted"
+        }
+        _ => "Invisible zero-width character, which can make identical-looking text differ",
+    };
+    format!("{description} (U+{:04X})", c as u32)
+}
+
 fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     hover
         .contents
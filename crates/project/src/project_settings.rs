@@ -20,6 +20,20 @@ pub struct ProjectSettings {
     /// Configuration for Git-related features
     #[serde(default)]
     pub git: GitSettings,
+
+    /// Environment variables to set for the terminals, tasks and language
+    /// servers of the project. Variables in a `.zed/env` file take precedence.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Whether to load the environment that direnv sets up for a project's
+    /// `.envrc` and pass it to its terminals, tasks and language servers.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub load_direnv: bool,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
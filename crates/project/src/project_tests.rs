@@ -988,6 +988,54 @@ async fn test_single_file_worktrees_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_invisible_character_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "let a\u{200B} = 1;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    cx.executor()
+        .advance_clock(INVISIBLE_CHARACTER_SCAN_DEBOUNCE);
+    cx.executor().run_until_parked();
+
+    buffer.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            &[
+                ("let a", None),
+                ("\u{200B}", Some(DiagnosticSeverity::WARNING)),
+                (" = 1;", None),
+            ]
+        );
+    });
+
+    // The warning goes away along with the character.
+    buffer.update(cx, |buffer, cx| buffer.edit([(5..8, "")], None, cx));
+    cx.executor()
+        .advance_clock(INVISIBLE_CHARACTER_SCAN_DEBOUNCE);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "let a = 1;");
+        assert!(!buffer.snapshot().has_diagnostics());
+    });
+}
+
 #[gpui::test]
 async fn test_omitted_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2769,6 +2817,48 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_with_invalid_utf8(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/latin1.txt", b"caf\xe9\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/latin1.txt", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "caf\u{fffd}\n");
+        assert!(buffer.is_lossy());
+        buffer.edit([(0..0, "un ")], None, cx);
+    });
+
+    // Saving over the original file would lose its invalid bytes.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap_err();
+    let original = fs.load_text(Path::new("/dir/latin1.txt")).await.unwrap();
+    assert_eq!(original.text.to_string(), "caf\u{fffd}\n");
+    assert!(original.is_lossy());
+
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer_as(buffer.clone(), "/dir/utf8.txt".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new("/dir/utf8.txt")).await.unwrap(),
+        "un caf\u{fffd}\n"
+    );
+    buffer.update(cx, |buffer, _| assert!(!buffer.is_lossy()));
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4921,6 +5011,39 @@ async fn test_multiple_language_server_actions(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[test]
+fn test_is_import_fix() {
+    fn action(title: &str, kind: Option<lsp::CodeActionKind>) -> lsp::CodeAction {
+        lsp::CodeAction {
+            title: title.to_string(),
+            kind,
+            ..Default::default()
+        }
+    }
+
+    assert!(is_import_fix(&action(
+        "Import 'Foo' from module \"./foo\"",
+        Some(lsp::CodeActionKind::QUICKFIX)
+    )));
+    assert!(is_import_fix(&action("Add import: std::fmt", None)));
+    assert!(is_import_fix(&action(
+        "Import `HashMap`",
+        Some(lsp::CodeActionKind::new("quickfix.rust"))
+    )));
+    assert!(!is_import_fix(&action(
+        "Remove unused import",
+        Some(lsp::CodeActionKind::QUICKFIX)
+    )));
+    assert!(!is_import_fix(&action(
+        "Organize imports",
+        Some(lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS)
+    )));
+    assert!(!is_import_fix(&action(
+        "Change spelling to 'Foo'",
+        Some(lsp::CodeActionKind::QUICKFIX)
+    )));
+}
+
 async fn search(
     project: &Model<Project>,
     query: SearchQuery,
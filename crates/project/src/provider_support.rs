@@ -0,0 +1,176 @@
+//! Support for the formatters and linters that are registered in the
+//! [`LanguageRegistry`](language::LanguageRegistry) without a language server,
+//! e.g. by extensions.
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use futures::{pin_mut, select_biased, Future, FutureExt};
+use gpui::{AsyncAppContext, Model, ModelContext, WeakModel};
+use language::{
+    Buffer, Diagnostic, DiagnosticEntry, Diff, FormatProvider, LintDiagnostic, LintProvider,
+    LspAdapterDelegate, ToPointUtf16, Unclipped, PROVIDER_TIMEOUT,
+};
+use util::{post_inc, ResultExt};
+
+use crate::{File, FormatOperation, Project};
+
+pub(super) async fn format_with_provider(
+    project: &WeakModel<Project>,
+    buffer: &Model<Buffer>,
+    cx: &mut AsyncAppContext,
+) -> Option<Result<FormatOperation>> {
+    let provider = project
+        .update(cx, |project, cx| {
+            let language = buffer.read(cx).language()?;
+            project.languages.format_provider(&language.name())
+        })
+        .ok()??;
+
+    let format_result = format_via_provider(project, buffer, provider.as_ref(), cx)
+        .await
+        .map(FormatOperation::Provider)
+        .with_context(|| format!("formatter {} failed to format buffer", provider.name()));
+    Some(format_result)
+}
+
+async fn format_via_provider(
+    project: &WeakModel<Project>,
+    buffer: &Model<Buffer>,
+    provider: &dyn FormatProvider,
+    cx: &mut AsyncAppContext,
+) -> Result<Diff> {
+    let (path, text, delegate) = project.update(cx, |project, cx| {
+        let buffer = buffer.read(cx);
+        let file = File::from_dyn(buffer.file())
+            .filter(|file| file.is_local())
+            .ok_or_else(|| anyhow!("buffer is not a local file"))?;
+        let delegate = project.worktree_delegate(&file.worktree, cx);
+        anyhow::Ok((file.path.clone(), buffer.text(), delegate))
+    })??;
+
+    let formatted_text =
+        with_timeout(provider.format(path, text, None, delegate), cx.clone()).await??;
+
+    Ok(buffer
+        .update(cx, |buffer, cx| buffer.diff(formatted_text, cx))?
+        .await)
+}
+
+impl Project {
+    /// Runs the lint providers of the buffer's language, replacing the diagnostics
+    /// they previously reported for the buffer.
+    pub(super) fn lint_buffer_with_providers(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(language) = buffer.read(cx).language().cloned() else {
+            return;
+        };
+        let Some(file) = File::from_dyn(buffer.read(cx).file()).filter(|file| file.is_local())
+        else {
+            return;
+        };
+        let providers = self.languages.lint_providers(&language.name());
+        if providers.is_empty() {
+            return;
+        }
+
+        let abs_path = file.abs_path(cx);
+        let path = file.path.clone();
+        let delegate = self.worktree_delegate(&file.worktree, cx);
+        let snapshot = buffer.read(cx).snapshot();
+        let text = snapshot.text();
+
+        for provider in providers {
+            let name = provider.name();
+            let server_id = *self
+                .lint_provider_server_ids
+                .entry(name.clone())
+                .or_insert_with(|| self.languages.next_language_server_id());
+            let lint = with_timeout(
+                lint_via_provider(provider, path.clone(), text.clone(), delegate.clone()),
+                cx.to_async(),
+            );
+            let snapshot = snapshot.clone();
+            let abs_path = abs_path.clone();
+
+            cx.spawn(|this, mut cx| async move {
+                let lint_diagnostics = lint
+                    .await
+                    .and_then(|result| result)
+                    .with_context(|| format!("linter {name} failed to lint buffer"))
+                    .log_err()?;
+
+                this.update(&mut cx, |this, cx| {
+                    let diagnostics = lint_diagnostics
+                        .into_iter()
+                        .map(|lint_diagnostic| {
+                            let start = lint_diagnostic.range.start.min(snapshot.len());
+                            let end = lint_diagnostic.range.end.clamp(start, snapshot.len());
+                            DiagnosticEntry {
+                                range: Unclipped(start.to_point_utf16(&snapshot))
+                                    ..Unclipped(end.to_point_utf16(&snapshot)),
+                                diagnostic: Diagnostic {
+                                    source: Some(name.to_string()),
+                                    code: lint_diagnostic.code,
+                                    severity: lint_diagnostic.severity,
+                                    message: lint_diagnostic.message,
+                                    group_id: post_inc(&mut this.next_diagnostic_group_id),
+                                    is_primary: true,
+                                    is_disk_based: false,
+                                    is_unnecessary: false,
+                                },
+                            }
+                        })
+                        .collect();
+                    this.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)
+                })
+                .ok()?
+                .log_err()
+            })
+            .detach();
+        }
+    }
+}
+
+async fn lint_via_provider(
+    provider: Arc<dyn LintProvider>,
+    path: Arc<Path>,
+    text: String,
+    delegate: Arc<dyn LspAdapterDelegate>,
+) -> Result<Vec<LintDiagnostic>> {
+    provider.lint(path, text, delegate).await
+}
+
+/// Discards the result of a provider that takes longer than [`PROVIDER_TIMEOUT`].
+///
+/// This only stops waiting for the provider. Providers that run code of their own,
+/// like extensions, are responsible for interrupting it.
+async fn with_timeout<T>(future: impl Future<Output = T>, cx: AsyncAppContext) -> Result<T> {
+    let future = future.fuse();
+    pin_mut!(future);
+    let mut timer = cx.background_executor().timer(PROVIDER_TIMEOUT).fuse();
+    select_biased! {
+        output = future => Ok(output),
+        _ = timer => Err(anyhow!("timed out after {:?}", PROVIDER_TIMEOUT)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_provider_timeout(cx: &mut TestAppContext) {
+        let output = with_timeout(async { 42 }, cx.to_async()).await;
+        assert_eq!(output.unwrap(), 42);
+
+        let hung = cx.spawn(|cx| with_timeout(futures::future::pending::<()>(), cx));
+        cx.executor().advance_clock(PROVIDER_TIMEOUT);
+        let error = hung.await.unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{error}");
+    }
+}
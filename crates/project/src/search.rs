@@ -21,6 +21,7 @@ pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: Vec<PathMatcher>,
     files_to_exclude: Vec<PathMatcher>,
+    max_matches: Option<usize>,
 }
 
 impl SearchInputs {
@@ -33,6 +34,9 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &[PathMatcher] {
         &self.files_to_exclude
     }
+    pub fn max_matches(&self) -> Option<usize> {
+        self.max_matches
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -73,6 +77,7 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            max_matches: None,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -111,6 +116,7 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            max_matches: None,
         };
         Ok(Self::Regex {
             regex,
@@ -159,6 +165,16 @@ impl SearchQuery {
             }
         }
     }
+    /// Stop searching a buffer once this many matches have been found in it, so that
+    /// patterns matching almost everywhere don't produce an unbounded number of results.
+    pub fn with_max_matches(mut self, max_matches: usize) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. } | Self::Regex { ref mut inner, .. } => {
+                inner.max_matches = Some(max_matches);
+                self
+            }
+        }
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
@@ -250,16 +266,21 @@ impl SearchQuery {
             }
         }
     }
+    /// Find the ranges matching this query in the given buffer, or in part of it, stopping
+    /// after [`SearchInputs::max_matches`] matches if it is set.
+    ///
+    /// The search regularly yields to the executor, so dropping the task it runs on, e.g.
+    /// because the query changed, cancels it without waiting for it to scan the whole buffer.
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
         subrange: Option<Range<usize>>,
     ) -> Vec<Range<usize>> {
-        const YIELD_INTERVAL: usize = 20000;
-
         if self.as_str().is_empty() {
             return Default::default();
         }
+        let max_matches = self.max_matches().unwrap_or(usize::MAX);
+        let mut yielder = SearchYielder::default();
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
         let rope = if let Some(range) = subrange {
@@ -273,15 +294,10 @@ impl SearchQuery {
             Self::Text {
                 search, whole_word, ..
             } => {
-                for (ix, mat) in search
-                    .stream_find_iter(rope.bytes_in_range(0..rope.len()))
-                    .enumerate()
-                {
-                    if (ix + 1) % YIELD_INTERVAL == 0 {
-                        yield_now().await;
-                    }
-
+                for mat in search.stream_find_iter(rope.bytes_in_range(0..rope.len())) {
                     let mat = mat.unwrap();
+                    yielder.advance(mat.end()).await;
+
                     if *whole_word {
                         let scope = buffer.language_scope_at(range_offset + mat.start());
                         let kind = |c| char_kind(&scope, c);
@@ -294,7 +310,10 @@ impl SearchQuery {
                             continue;
                         }
                     }
-                    matches.push(mat.start()..mat.end())
+                    matches.push(mat.start()..mat.end());
+                    if matches.len() >= max_matches {
+                        break;
+                    }
                 }
             }
 
@@ -303,31 +322,31 @@ impl SearchQuery {
             } => {
                 if *multiline {
                     let text = rope.to_string();
-                    for (ix, mat) in regex.find_iter(&text).enumerate() {
-                        if (ix + 1) % YIELD_INTERVAL == 0 {
-                            yield_now().await;
-                        }
-
+                    for mat in regex.find_iter(&text) {
+                        yielder.advance(mat.end()).await;
                         matches.push(mat.start()..mat.end());
+                        if matches.len() >= max_matches {
+                            break;
+                        }
                     }
                 } else {
                     let mut line = String::new();
                     let mut line_offset = 0;
-                    for (chunk_ix, chunk) in rope.chunks().chain(["\n"]).enumerate() {
-                        if (chunk_ix + 1) % YIELD_INTERVAL == 0 {
-                            yield_now().await;
-                        }
-
+                    for chunk in rope.chunks().chain(["\n"]) {
                         for (newline_ix, text) in chunk.split('\n').enumerate() {
                             if newline_ix > 0 {
                                 for mat in regex.find_iter(&line) {
                                     let start = line_offset + mat.start();
                                     let end = line_offset + mat.end();
                                     matches.push(start..end);
+                                    if matches.len() >= max_matches {
+                                        return matches;
+                                    }
                                 }
 
                                 line_offset += line.len() + 1;
                                 line.clear();
+                                yielder.advance(line_offset).await;
                             }
                             line.push_str(text);
                         }
@@ -384,6 +403,10 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn max_matches(&self) -> Option<usize> {
+        self.as_inner().max_matches()
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -417,6 +440,30 @@ impl SearchQuery {
     }
 }
 
+/// Decides when a search should yield to the executor: after enough matches have been found
+/// or enough text has been scanned since the last time it yielded.
+#[derive(Default)]
+struct SearchYielder {
+    steps: usize,
+    last_yield_offset: usize,
+}
+
+impl SearchYielder {
+    const STEPS_PER_YIELD: usize = 10_000;
+    const BYTES_PER_YIELD: usize = 64 * 1024;
+
+    async fn advance(&mut self, offset: usize) {
+        self.steps += 1;
+        if self.steps >= Self::STEPS_PER_YIELD
+            || offset.saturating_sub(self.last_yield_offset) >= Self::BYTES_PER_YIELD
+        {
+            self.steps = 0;
+            self.last_yield_offset = offset;
+            yield_now().await;
+        }
+    }
+}
+
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>> {
     glob_set
         .split(',')
@@ -1,9 +1,18 @@
-use crate::Project;
+use crate::{Event, Project};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
 use collections::HashMap;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use gpui::{
+    AnyWindowHandle, AsyncAppContext, Context, Entity, Keystroke, Model, ModelContext,
+    Subscription, WeakModel,
+};
 use settings::Settings;
 use smol::channel::bounded;
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use task::SpawnInTerminal;
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
@@ -11,11 +20,35 @@ use terminal::{
 };
 use util::ResultExt;
 
+/// How often the host sends the output of a shared terminal to guests, at most.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) shared: HashMap<u64, SharedTerminal>,
+}
+
+/// A terminal that the host of a project shares with its guests. Guests can
+/// see its output, and send input to it once the host grants them control.
+#[derive(Default)]
+pub struct SharedTerminal {
+    pub title: String,
+    pub content: String,
+    /// The guest that is currently allowed to send input to the terminal.
+    pub controller: Option<proto::PeerId>,
+    /// The guests that have asked the host for control of the terminal.
+    pub control_requests: Vec<proto::PeerId>,
+    hosted: Option<HostedTerminal>,
+}
+
+/// The host's side of a [`SharedTerminal`].
+struct HostedTerminal {
+    terminal: WeakModel<Terminal>,
+    update_pending: bool,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Project {
@@ -36,7 +69,13 @@ impl Project {
         let python_settings = settings.detect_venv.clone();
         let (completion_tx, completion_rx) = bounded(1);
 
-        let mut env = settings.env.clone();
+        // The project's environment applies to all of its terminals, and the
+        // terminal settings and task definitions override it.
+        let mut env = working_directory
+            .as_deref()
+            .map(|working_directory| self.environment_for_path(working_directory, cx).to_env())
+            .unwrap_or_default();
+        env.extend(settings.env.clone());
         // Alacritty uses parent project's working directory when no working directory is provided
         // https://github.com/alacritty/alacritty/blob/fd1a3cc79192d1d03839f0fd8c72e1f8d0fce42e/extra/man/alacritty.5.scd?plain=1#L47-L52
 
@@ -59,6 +98,7 @@ impl Project {
                     command_label: spawn_task.command_label,
                     status: TaskStatus::Running,
                     completion_rx,
+                    started_at: Instant::now(),
                 }),
                 Shell::WithArguments {
                     program: spawn_task.command,
@@ -212,6 +252,347 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    pub fn shared_terminal(&self, terminal_id: u64) -> Option<&SharedTerminal> {
+        self.terminals.shared.get(&terminal_id)
+    }
+
+    /// Returns the id under which the given terminal is shared with guests, if it is.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        let terminal_id = terminal.entity_id().as_u64();
+        self.terminals
+            .shared
+            .contains_key(&terminal_id)
+            .then_some(terminal_id)
+    }
+
+    /// Shares a terminal with the guests of this project. Guests can only view
+    /// its output until they are granted control with [`Self::grant_terminal_control`].
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        anyhow::ensure!(self.is_local(), "only the host can share terminals");
+        anyhow::ensure!(
+            self.is_shared(),
+            "the project must be shared before sharing terminals"
+        );
+
+        let terminal_id = terminal.entity_id().as_u64();
+        if self.terminals.shared.contains_key(&terminal_id) {
+            return Ok(terminal_id);
+        }
+
+        let subscriptions = vec![
+            cx.subscribe(terminal, move |this, _, event, cx| match event {
+                terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                    this.schedule_shared_terminal_update(terminal_id, cx)
+                }
+                terminal::Event::CloseTerminal => this.unshare_terminal(terminal_id, cx),
+                _ => {}
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.unshare_terminal(terminal_id, cx)
+            }),
+        ];
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                hosted: Some(HostedTerminal {
+                    terminal: terminal.downgrade(),
+                    update_pending: false,
+                    _subscriptions: subscriptions,
+                }),
+                ..Default::default()
+            },
+        );
+        self.send_shared_terminal_update(terminal_id, cx);
+        cx.emit(Event::TerminalShared(terminal_id));
+        Ok(terminal_id)
+    }
+
+    pub fn unshare_terminal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        if self.terminals.shared.remove(&terminal_id).is_none() {
+            return;
+        }
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::UnshareTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .log_err();
+        }
+        cx.emit(Event::TerminalUnshared(terminal_id));
+    }
+
+    /// Lets the given guest send input to a shared terminal, taking control
+    /// away from any other guest.
+    pub fn grant_terminal_control(
+        &mut self,
+        terminal_id: u64,
+        peer_id: proto::PeerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(shared_terminal) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        shared_terminal.controller = Some(peer_id);
+        shared_terminal
+            .control_requests
+            .retain(|requester| *requester != peer_id);
+        self.send_shared_terminal_update(terminal_id, cx);
+        cx.emit(Event::SharedTerminalUpdated(terminal_id));
+    }
+
+    /// Makes a shared terminal read-only for all guests again.
+    pub fn revoke_terminal_control(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared_terminal) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared_terminal.controller.take().is_some() {
+            self.send_shared_terminal_update(terminal_id, cx);
+            cx.emit(Event::SharedTerminalUpdated(terminal_id));
+        }
+    }
+
+    /// Asks the host for control of a shared terminal.
+    pub fn request_terminal_control(&self, terminal_id: u64) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        self.client.send(proto::RequestTerminalControl {
+            project_id,
+            terminal_id,
+        })
+    }
+
+    /// Returns whether this guest is currently allowed to send input to a shared terminal.
+    pub fn controls_shared_terminal(&self, terminal_id: u64) -> bool {
+        let peer_id = self.client.peer_id();
+        self.terminals
+            .shared
+            .get(&terminal_id)
+            .map_or(false, |shared_terminal| {
+                peer_id.is_some() && shared_terminal.controller == peer_id
+            })
+    }
+
+    pub fn send_shared_terminal_input(
+        &self,
+        terminal_id: u64,
+        input: proto::shared_terminal_input::Variant,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.controls_shared_terminal(terminal_id),
+            "not in control of the terminal"
+        );
+        let project_id = self.remote_id().context("project is not shared")?;
+        self.client.send(proto::SharedTerminalInput {
+            project_id,
+            terminal_id,
+            variant: Some(input),
+        })
+    }
+
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_shared_terminal_update(terminal_id, cx);
+        }
+    }
+
+    /// Revokes control of any shared terminal from a guest that left the project.
+    pub(crate) fn remove_terminal_controller(
+        &mut self,
+        peer_id: proto::PeerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut revoked = Vec::new();
+        for (terminal_id, shared_terminal) in &mut self.terminals.shared {
+            shared_terminal
+                .control_requests
+                .retain(|requester| *requester != peer_id);
+            if shared_terminal.controller == Some(peer_id) {
+                revoked.push(*terminal_id);
+            }
+        }
+        for terminal_id in revoked {
+            self.revoke_terminal_control(terminal_id, cx);
+        }
+    }
+
+    fn schedule_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(hosted) = self
+            .terminals
+            .shared
+            .get_mut(&terminal_id)
+            .and_then(|shared_terminal| shared_terminal.hosted.as_mut())
+        else {
+            return;
+        };
+        if hosted.update_pending {
+            return;
+        }
+
+        hosted.update_pending = true;
+        cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(hosted) = this
+                    .terminals
+                    .shared
+                    .get_mut(&terminal_id)
+                    .and_then(|shared_terminal| shared_terminal.hosted.as_mut())
+                {
+                    hosted.update_pending = false;
+                }
+                this.send_shared_terminal_update(terminal_id, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn send_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared_terminal) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        let Some(terminal) = shared_terminal
+            .hosted
+            .as_ref()
+            .and_then(|hosted| hosted.terminal.upgrade())
+        else {
+            return;
+        };
+
+        let terminal = terminal.read(cx);
+        shared_terminal.title = terminal.title(false);
+        shared_terminal.content = terminal.viewport_text();
+        self.client
+            .send(proto::UpdateSharedTerminal {
+                project_id,
+                terminal_id,
+                title: shared_terminal.title.clone(),
+                content: shared_terminal.content.clone(),
+                controller_id: shared_terminal.controller,
+            })
+            .log_err();
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        _: Arc<client::Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let update = envelope.payload;
+            let is_new = !this.terminals.shared.contains_key(&update.terminal_id);
+            let shared_terminal = this.terminals.shared.entry(update.terminal_id).or_default();
+            shared_terminal.title = update.title;
+            shared_terminal.content = update.content;
+            shared_terminal.controller = update.controller_id;
+            if is_new {
+                cx.emit(Event::TerminalShared(update.terminal_id));
+            } else {
+                cx.emit(Event::SharedTerminalUpdated(update.terminal_id));
+            }
+        })
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        _: Arc<client::Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let terminal_id = envelope.payload.terminal_id;
+            if this.terminals.shared.remove(&terminal_id).is_some() {
+                cx.emit(Event::TerminalUnshared(terminal_id));
+            }
+        })
+    }
+
+    pub(crate) async fn handle_request_terminal_control(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::RequestTerminalControl>,
+        _: Arc<client::Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let peer_id = envelope.original_sender_id()?;
+        let terminal_id = envelope.payload.terminal_id;
+        this.update(&mut cx, |this, cx| {
+            let shared_terminal = this
+                .terminals
+                .shared
+                .get_mut(&terminal_id)
+                .ok_or_else(|| anyhow!("unknown shared terminal {terminal_id}"))?;
+            if shared_terminal.controller != Some(peer_id)
+                && !shared_terminal.control_requests.contains(&peer_id)
+            {
+                shared_terminal.control_requests.push(peer_id);
+                cx.emit(Event::TerminalControlRequested {
+                    terminal_id,
+                    peer_id,
+                });
+            }
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        _: Arc<client::Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let peer_id = envelope.original_sender_id()?;
+        let terminal_id = envelope.payload.terminal_id;
+        let input = envelope
+            .payload
+            .variant
+            .ok_or_else(|| anyhow!("missing terminal input"))?;
+        this.update(&mut cx, |this, cx| {
+            let shared_terminal = this
+                .terminals
+                .shared
+                .get(&terminal_id)
+                .ok_or_else(|| anyhow!("unknown shared terminal {terminal_id}"))?;
+            anyhow::ensure!(
+                shared_terminal.controller == Some(peer_id),
+                "peer {peer_id} is not in control of terminal {terminal_id}"
+            );
+            let terminal = shared_terminal
+                .hosted
+                .as_ref()
+                .and_then(|hosted| hosted.terminal.upgrade())
+                .ok_or_else(|| anyhow!("shared terminal {terminal_id} was closed"))?;
+
+            match input {
+                proto::shared_terminal_input::Variant::Text(text) => {
+                    terminal.update(cx, |terminal, _| terminal.input(text));
+                }
+                proto::shared_terminal_input::Variant::Keystroke(keystroke) => {
+                    let keystroke = Keystroke::parse(&keystroke)?;
+                    let alt_is_meta = TerminalSettings::get_global(cx).option_as_meta;
+                    terminal.update(cx, |terminal, _| {
+                        terminal.try_keystroke(&keystroke, alt_is_meta)
+                    });
+                }
+            }
+            cx.emit(Event::SharedTerminalInput {
+                terminal_id,
+                peer_id,
+            });
+            Ok(())
+        })?
+    }
 }
 
 // TODO: Add a few tests for adding and removing terminal tabs
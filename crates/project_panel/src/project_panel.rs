@@ -1633,10 +1633,7 @@ impl ProjectPanel {
                     entry_id: *entry_id,
                 })
             })
-            .drag_over::<ProjectEntryId>(|style, _, cx| {
-                style.bg(cx.theme().colors().drop_target_background)
-            })
-            .on_drop(cx.listener(move |this, dragged_id: &ProjectEntryId, cx| {
+            .drop_target(cx.listener(move |this, dragged_id: &ProjectEntryId, cx| {
                 this.move_entry(*dragged_id, entry_id, kind.is_file(), cx);
             }))
             .child(
@@ -1806,7 +1803,8 @@ impl Render for ProjectPanel {
                         },
                     )
                     .size_full()
-                    .track_scroll(self.scroll_handle.clone()),
+                    .track_scroll(self.scroll_handle.clone())
+                    .auto_scroll_on_drag(),
                 )
                 .children(self.context_menu.as_ref().map(|(menu, position, _)| {
                     deferred(
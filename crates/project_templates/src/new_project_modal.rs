@@ -0,0 +1,273 @@
+use crate::{ProjectTemplate, TemplatePlaceholder, PROJECT_NAME_PLACEHOLDER};
+use collections::HashMap;
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, PathPromptOptions,
+    Render, View, ViewContext, VisualContext, WeakView,
+};
+use std::{path::PathBuf, sync::Arc};
+use task::TaskContext;
+use ui::prelude::*;
+use workspace::{open_paths, ModalView, OpenOptions, Workspace};
+
+/// Asks for the values of a template's placeholders and the directory to create
+/// the new project in, then creates it and opens it in a new window.
+pub struct NewProjectModal {
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    template: ProjectTemplate,
+    project_name_editor: View<Editor>,
+    placeholder_editors: Vec<(TemplatePlaceholder, View<Editor>)>,
+    error: Option<SharedString>,
+    creating: bool,
+}
+
+impl NewProjectModal {
+    pub(crate) fn new(
+        workspace: WeakView<Workspace>,
+        fs: Arc<dyn Fs>,
+        template: ProjectTemplate,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let project_name_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("my-project", cx);
+            editor
+        });
+        let placeholder_editors = template
+            .placeholders
+            .iter()
+            .map(|placeholder| {
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::single_line(cx);
+                    if let Some(default) = &placeholder.default {
+                        editor.set_text(default.clone(), cx);
+                    }
+                    editor
+                });
+                (placeholder.clone(), editor)
+            })
+            .collect();
+
+        Self {
+            workspace,
+            fs,
+            template,
+            project_name_editor,
+            placeholder_editors,
+            error: None,
+            creating: false,
+        }
+    }
+
+    fn values(&self, cx: &AppContext) -> Result<HashMap<String, String>, SharedString> {
+        let project_name = self
+            .project_name_editor
+            .read(cx)
+            .text(cx)
+            .trim()
+            .to_string();
+        if project_name.is_empty() {
+            return Err("Enter a name for the project".into());
+        }
+        if project_name.contains(['/', '\\']) || project_name == "." || project_name == ".." {
+            return Err(format!("{project_name:?} is not a valid directory name").into());
+        }
+
+        let mut values = HashMap::default();
+        values.insert(PROJECT_NAME_PLACEHOLDER.to_string(), project_name);
+        for (placeholder, editor) in &self.placeholder_editors {
+            values.insert(placeholder.name.clone(), editor.read(cx).text(cx));
+        }
+        Ok(values)
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if self.creating {
+            return;
+        }
+        let values = match self.values(cx) {
+            Ok(values) => values,
+            Err(error) => {
+                self.error = Some(error);
+                cx.notify();
+                return;
+            }
+        };
+
+        let parent_dir = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        self.creating = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let parent_dir = parent_dir
+                .await
+                .ok()
+                .flatten()
+                .and_then(|mut paths| paths.pop());
+            let Some(parent_dir) = parent_dir else {
+                return this.update(&mut cx, |this, cx| {
+                    this.creating = false;
+                    cx.notify();
+                });
+            };
+
+            let (fs, template) =
+                this.update(&mut cx, |this, _| (this.fs.clone(), this.template.clone()))?;
+            let project_dir = parent_dir.join(&values[PROJECT_NAME_PLACEHOLDER]);
+            let result = template
+                .instantiate(fs.as_ref(), &project_dir, &values)
+                .await;
+
+            this.update(&mut cx, |this, cx| match result {
+                Ok(()) => {
+                    this.open_project(project_dir, &values, cx);
+                    cx.emit(DismissEvent);
+                }
+                Err(error) => {
+                    this.creating = false;
+                    this.error = Some(format!("Failed to create project: {error}").into());
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Runs the template's post-create tasks and opens the new project.
+    ///
+    /// The tasks are spawned in the current window's terminal, as the new window's
+    /// terminal panel is not loaded yet at this point.
+    fn open_project(
+        &self,
+        project_dir: PathBuf,
+        values: &HashMap<String, String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let task_context = TaskContext {
+            cwd: Some(project_dir.clone()),
+            task_variables: Default::default(),
+        };
+        let id_base = format!("project template {}", self.template.name);
+        let tasks = self.template.post_create_tasks(values);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                for task in tasks {
+                    if let Some(spawn_in_terminal) = task
+                        .resolve_task(&id_base, &task_context)
+                        .and_then(|task| task.resolved)
+                    {
+                        cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+                    }
+                }
+
+                open_paths(
+                    &[project_dir],
+                    workspace.app_state().clone(),
+                    OpenOptions {
+                        open_new_workspace: Some(true),
+                        ..Default::default()
+                    },
+                    cx,
+                )
+                .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_field(
+        &self,
+        label: impl Into<SharedString>,
+        editor: &View<Editor>,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(editor.clone()),
+            )
+    }
+}
+
+impl ModalView for NewProjectModal {}
+impl EventEmitter<DismissEvent> for NewProjectModal {}
+
+impl FocusableView for NewProjectModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.project_name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for NewProjectModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("NewProjectModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_4()
+            .gap_3()
+            .child(
+                v_flex()
+                    .child(
+                        Headline::new(format!("New {} Project", self.template.name))
+                            .size(HeadlineSize::Small),
+                    )
+                    .children(self.template.description.clone().map(|description| {
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    })),
+            )
+            .child(self.render_field("Project Name", &self.project_name_editor, cx))
+            .children(
+                self.placeholder_editors
+                    .iter()
+                    .map(|(placeholder, editor)| {
+                        let label = placeholder
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| placeholder.name.clone());
+                        self.render_field(label, editor, cx)
+                    }),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).color(Color::Error)),
+            )
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_1()
+                    .child(
+                        Button::new("new-project-cancel", "Cancel")
+                            .on_click(cx.listener(|this, _, cx| this.cancel(&menu::Cancel, cx))),
+                    )
+                    .child(
+                        Button::new("new-project-create", "Choose Location and Create")
+                            .style(ButtonStyle::Filled)
+                            .disabled(self.creating)
+                            .on_click(cx.listener(|this, _, cx| this.confirm(&menu::Confirm, cx))),
+                    ),
+            )
+    }
+}
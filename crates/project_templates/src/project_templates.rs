@@ -0,0 +1,21 @@
+mod new_project_modal;
+mod template;
+mod template_picker;
+
+use gpui::AppContext;
+use workspace::{NewProjectFromTemplate, Workspace};
+
+pub use new_project_modal::NewProjectModal;
+pub use template::{
+    load_templates, render_template, ProjectTemplate, TemplatePlaceholder, PROJECT_NAME_PLACEHOLDER,
+};
+pub use template_picker::ProjectTemplatePicker;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &NewProjectFromTemplate, cx| {
+            ProjectTemplatePicker::toggle(workspace, cx);
+        });
+    })
+    .detach();
+}
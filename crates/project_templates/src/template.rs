@@ -0,0 +1,283 @@
+use anyhow::{bail, Context, Result};
+use collections::HashMap;
+use fs::{CopyOptions, Fs};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use task::TaskTemplate;
+use util::ResultExt;
+
+/// The file describing a template, at the root of its directory.
+const MANIFEST_FILE_NAME: &str = "template.json";
+/// The directory holding the files a template creates.
+const FILES_DIR_NAME: &str = "files";
+/// The placeholder every template gets, holding the name of the new project.
+pub const PROJECT_NAME_PLACEHOLDER: &str = "project_name";
+
+/// A directory tree that new projects can be created from.
+///
+/// The names and contents of the files in the template's `files` directory may
+/// contain `{{placeholder}}`s, which are replaced with the values entered when
+/// creating a project.
+#[derive(Clone, Debug)]
+pub struct ProjectTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub placeholders: Vec<TemplatePlaceholder>,
+    /// Tasks run in the new project's directory once its files are created.
+    pub post_create: Vec<TaskTemplate>,
+    pub dir: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TemplatePlaceholder {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TemplateManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    placeholders: Vec<TemplatePlaceholder>,
+    #[serde(default)]
+    post_create: Vec<TaskTemplate>,
+}
+
+impl ProjectTemplate {
+    pub async fn load(fs: &dyn Fs, dir: PathBuf) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE_NAME);
+        let manifest = fs
+            .load(&manifest_path)
+            .await
+            .with_context(|| format!("loading {manifest_path:?}"))?;
+        let manifest: TemplateManifest = serde_json::from_str(&manifest)
+            .with_context(|| format!("parsing {manifest_path:?}"))?;
+
+        let name = match manifest.name {
+            Some(name) => name,
+            None => dir
+                .file_name()
+                .context("template directory has no name")?
+                .to_string_lossy()
+                .into_owned(),
+        };
+        Ok(Self {
+            name,
+            description: manifest.description,
+            placeholders: manifest
+                .placeholders
+                .into_iter()
+                .filter(|placeholder| placeholder.name != PROJECT_NAME_PLACEHOLDER)
+                .collect(),
+            post_create: manifest.post_create,
+            dir,
+        })
+    }
+
+    /// Creates the template's files in `target`, which must not exist or be empty.
+    pub async fn instantiate(
+        &self,
+        fs: &dyn Fs,
+        target: &Path,
+        values: &HashMap<String, String>,
+    ) -> Result<()> {
+        if fs.is_file(target).await {
+            bail!("{target:?} already exists");
+        }
+        if fs.is_dir(target).await && fs.read_dir(target).await?.next().await.is_some() {
+            bail!("{target:?} already exists and is not empty");
+        }
+        fs.create_dir(target).await?;
+
+        let files_dir = self.dir.join(FILES_DIR_NAME);
+        let mut dirs_to_copy = vec![files_dir.clone()];
+        while let Some(dir) = dirs_to_copy.pop() {
+            let mut entries = fs.read_dir(&dir).await?;
+            while let Some(source) = entries.next().await {
+                let source = source?;
+                let relative_path = source.strip_prefix(&files_dir)?.to_string_lossy();
+                let destination = target.join(render_template(&relative_path, values));
+
+                if fs.is_dir(&source).await {
+                    fs.create_dir(&destination).await?;
+                    dirs_to_copy.push(source);
+                } else if let Ok(text) = fs.load(&source).await {
+                    fs.atomic_write(destination, render_template(&text, values))
+                        .await?;
+                } else {
+                    // Files that aren't valid UTF-8 are copied verbatim.
+                    fs.copy_file(&source, &destination, CopyOptions::default())
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the template's post-create tasks, with placeholders substituted
+    /// in their commands and arguments.
+    pub fn post_create_tasks(&self, values: &HashMap<String, String>) -> Vec<TaskTemplate> {
+        self.post_create
+            .iter()
+            .map(|task| TaskTemplate {
+                label: render_template(&task.label, values),
+                command: render_template(&task.command, values),
+                args: task
+                    .args
+                    .iter()
+                    .map(|arg| render_template(arg, values))
+                    .collect(),
+                ..task.clone()
+            })
+            .collect()
+    }
+}
+
+/// Loads the templates from the given directories, each of which contains one
+/// template per subdirectory, along with the given individual template directories.
+pub async fn load_templates(
+    fs: &dyn Fs,
+    template_roots: &[PathBuf],
+    template_dirs: Vec<PathBuf>,
+) -> Vec<ProjectTemplate> {
+    let mut dirs = Vec::new();
+    for root in template_roots {
+        let Ok(mut entries) = fs.read_dir(root).await else {
+            continue;
+        };
+        while let Some(entry) = entries.next().await {
+            if let Some(entry) = entry.log_err() {
+                if fs.is_dir(&entry).await {
+                    dirs.push(entry);
+                }
+            }
+        }
+    }
+    dirs.extend(template_dirs);
+
+    let mut templates = Vec::new();
+    for dir in dirs {
+        if let Some(template) = ProjectTemplate::load(fs, dir).await.log_err() {
+            templates.push(template);
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+/// Replaces each `{{name}}` in `text` with the value of the placeholder of that name.
+/// Unknown placeholders are left as they are.
+pub fn render_template(text: &str, values: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}").map(|end| start + 2 + end) else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match values.get(rest[start + 2..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    fn values() -> HashMap<String, String> {
+        HashMap::from_iter([
+            (PROJECT_NAME_PLACEHOLDER.to_string(), "hello".to_string()),
+            ("author".to_string(), "Jane".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_render_template() {
+        let values = values();
+        assert_eq!(
+            render_template("name = \"{{project_name}}\"", &values),
+            "name = \"hello\""
+        );
+        assert_eq!(
+            render_template("{{ author }} wrote {{project_name}}", &values),
+            "Jane wrote hello"
+        );
+        assert_eq!(render_template("{{unknown}}", &values), "{{unknown}}");
+        assert_eq!(render_template("{{project_name", &values), "{{project_name");
+        assert_eq!(
+            render_template("no placeholders", &values),
+            "no placeholders"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_instantiate_template(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/templates",
+            json!({
+                "rust": {
+                    "template.json": r#"{
+                        "description": "A Rust binary",
+                        "placeholders": [{ "name": "author" }],
+                        "post_create": [{ "label": "init {{project_name}}", "command": "cargo", "args": ["init", "--name", "{{project_name}}"] }]
+                    }"#,
+                    "files": {
+                        "Cargo.toml": "[package]\nname = \"{{project_name}}\"\nauthors = [\"{{author}}\"]\n",
+                        "src": {
+                            "{{project_name}}.rs": "fn main() {}\n",
+                        },
+                    },
+                },
+                "not-a-template.txt": "",
+            }),
+        )
+        .await;
+        fs.insert_tree("/projects", json!({ "taken": { "file.txt": "" } }))
+            .await;
+
+        let templates = load_templates(fs.as_ref(), &[PathBuf::from("/templates")], vec![]).await;
+        assert_eq!(templates.len(), 1);
+        let template = &templates[0];
+        assert_eq!(template.name, "rust");
+        assert_eq!(template.description.as_deref(), Some("A Rust binary"));
+        assert_eq!(template.placeholders.len(), 1);
+
+        let values = values();
+        template
+            .instantiate(fs.as_ref(), Path::new("/projects/hello"), &values)
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load(Path::new("/projects/hello/Cargo.toml"))
+                .await
+                .unwrap(),
+            "[package]\nname = \"hello\"\nauthors = [\"Jane\"]\n"
+        );
+        assert!(fs.is_file(Path::new("/projects/hello/src/hello.rs")).await);
+
+        assert!(template
+            .instantiate(fs.as_ref(), Path::new("/projects/taken"), &values)
+            .await
+            .is_err());
+
+        let tasks = template.post_create_tasks(&values);
+        assert_eq!(tasks[0].label, "init hello");
+        assert_eq!(tasks[0].args, ["init", "--name", "hello"]);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{load_templates, NewProjectModal, ProjectTemplate};
+use extension::ExtensionStore;
+use fs::Fs;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription,
+    Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::{paths::TEMPLATES_DIR, ResultExt};
+use workspace::{notifications::NotificationId, ModalView, Toast, Workspace};
+
+/// Lists the user's and the installed extensions' project templates.
+pub struct ProjectTemplatePicker {
+    picker: View<Picker<ProjectTemplatePickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ProjectTemplatePicker {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let fs = workspace.app_state().fs.clone();
+        let extension_template_dirs = ExtensionStore::try_global(cx)
+            .map(|store| store.read(cx).project_template_dirs())
+            .unwrap_or_default();
+
+        cx.spawn(|workspace, mut cx| async move {
+            let templates = load_templates(
+                fs.as_ref(),
+                &[TEMPLATES_DIR.clone()],
+                extension_template_dirs,
+            )
+            .await;
+
+            workspace.update(&mut cx, |workspace, cx| {
+                if templates.is_empty() {
+                    struct NoProjectTemplates;
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<NoProjectTemplates>(),
+                            format!(
+                                "No project templates found. Add one to {} or install an extension providing them.",
+                                TEMPLATES_DIR.display()
+                            ),
+                        ),
+                        cx,
+                    );
+                    return;
+                }
+
+                let delegate = ProjectTemplatePickerDelegate {
+                    workspace: cx.view().downgrade(),
+                    fs,
+                    templates,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| Self::new(delegate, cx));
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(delegate: ProjectTemplatePickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for ProjectTemplatePicker {}
+impl EventEmitter<DismissEvent> for ProjectTemplatePicker {}
+
+impl FocusableView for ProjectTemplatePicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ProjectTemplatePicker {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct ProjectTemplatePickerDelegate {
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    templates: Vec<ProjectTemplate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for ProjectTemplatePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select a project template...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No matching templates".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .templates
+            .iter()
+            .enumerate()
+            .map(|(id, template)| StringMatchCandidate {
+                id,
+                char_bag: template.name.chars().collect(),
+                string: template.name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(template) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.templates.get(hit.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+
+        let fs = self.fs.clone();
+        let workspace = self.workspace.clone();
+        self.workspace
+            .update(cx, |workspace_view, cx| {
+                workspace_view
+                    .toggle_modal(cx, |cx| NewProjectModal::new(workspace, fs, template, cx));
+            })
+            .log_err();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let template = self.templates.get(hit.candidate_id)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                ))
+                .end_slot::<Label>(template.description.clone().map(|description| {
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                })),
+        )
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{bail, Context, Result};
+use futures::AsyncReadExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{AsyncBody, HttpClient, Method, Request};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// A repository hosted on GitHub, identified by its owner and name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitHubRepository {
+    pub owner: String,
+    pub name: String,
+}
+
+impl GitHubRepository {
+    /// Parses the URL of a git remote hosted on GitHub, in either its SSH or its HTTPS form.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let path = url
+            .strip_prefix("git@github.com:")
+            .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+            .or_else(|| url.strip_prefix("https://github.com/"))
+            .or_else(|| url.strip_prefix("http://github.com/"))?;
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        let (owner, name) = path.split_once('/')?;
+        if owner.is_empty() || name.is_empty() || name.contains('/') {
+            return None;
+        }
+        Some(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{GITHUB_API_URL}/repos/{}/{}{path}", self.owner, self.name)
+    }
+}
+
+impl std::fmt::Display for GitHubRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GitHubUser {
+    pub login: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PullRequestRef {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub sha: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub user: GitHubUser,
+    pub head: PullRequestRef,
+    pub base: PullRequestRef,
+    pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// The side of a diff a review comment is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    /// The old version of the file.
+    Left,
+    /// The new version of the file.
+    Right,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub path: String,
+    /// The line the comment is attached to, or `None` if the comment is outdated.
+    pub line: Option<u32>,
+    pub side: Option<DiffSide>,
+    pub body: String,
+    pub user: GitHubUser,
+    pub in_reply_to_id: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct NewReviewComment<'a> {
+    body: &'a str,
+    commit_id: &'a str,
+    path: &'a str,
+    line: u32,
+    side: DiffSide,
+}
+
+pub async fn list_pull_requests(
+    http: &Arc<dyn HttpClient>,
+    token: &str,
+    repository: &GitHubRepository,
+) -> Result<Vec<PullRequest>> {
+    let url = repository.api_url("/pulls?state=open&per_page=100");
+    let body = send(http, Method::GET, &url, token, None, AsyncBody::default()).await?;
+    serde_json::from_slice(&body).context("deserializing pull requests failed")
+}
+
+/// Returns the unified diff between a pull request's base and head.
+pub async fn fetch_pull_request_diff(
+    http: &Arc<dyn HttpClient>,
+    token: &str,
+    repository: &GitHubRepository,
+    number: u64,
+) -> Result<String> {
+    let url = repository.api_url(&format!("/pulls/{number}"));
+    let body = send(
+        http,
+        Method::GET,
+        &url,
+        token,
+        Some("application/vnd.github.diff"),
+        AsyncBody::default(),
+    )
+    .await?;
+    String::from_utf8(body).context("pull request diff is not valid UTF-8")
+}
+
+pub async fn list_review_comments(
+    http: &Arc<dyn HttpClient>,
+    token: &str,
+    repository: &GitHubRepository,
+    number: u64,
+) -> Result<Vec<ReviewComment>> {
+    let url = repository.api_url(&format!("/pulls/{number}/comments?per_page=100"));
+    let body = send(http, Method::GET, &url, token, None, AsyncBody::default()).await?;
+    serde_json::from_slice(&body).context("deserializing review comments failed")
+}
+
+/// Posts a review comment on a line of the given pull request's diff.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_review_comment(
+    http: &Arc<dyn HttpClient>,
+    token: &str,
+    repository: &GitHubRepository,
+    pull_request: &PullRequest,
+    path: &str,
+    line: u32,
+    side: DiffSide,
+    body: &str,
+) -> Result<ReviewComment> {
+    let url = repository.api_url(&format!("/pulls/{}/comments", pull_request.number));
+    let request_body = serde_json::to_string(&NewReviewComment {
+        body,
+        commit_id: &pull_request.head.sha,
+        path,
+        line,
+        side,
+    })?;
+    let body = send(
+        http,
+        Method::POST,
+        &url,
+        token,
+        None,
+        AsyncBody::from(request_body),
+    )
+    .await?;
+    serde_json::from_slice(&body).context("deserializing review comment failed")
+}
+
+async fn send(
+    http: &Arc<dyn HttpClient>,
+    method: Method,
+    url: &str,
+    token: &str,
+    accept: Option<&str>,
+    body: AsyncBody,
+) -> Result<Vec<u8>> {
+    let request = Request::builder()
+        .method(method)
+        .uri(url)
+        .header("Accept", accept.unwrap_or("application/vnd.github+json"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "Zed")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .body(body)?;
+
+    let mut response = http
+        .send(request)
+        .await
+        .with_context(|| format!("error sending request to {url}"))?;
+
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+
+    if !response.status().is_success() {
+        let text = String::from_utf8_lossy(body.as_slice());
+        bail!(
+            "status error {}, response: {text:?}",
+            response.status().as_u16()
+        );
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_url() {
+        let expected = Some(GitHubRepository {
+            owner: "zed-industries".into(),
+            name: "zed".into(),
+        });
+        for url in [
+            "git@github.com:zed-industries/zed.git",
+            "git@github.com:zed-industries/zed",
+            "ssh://git@github.com/zed-industries/zed.git",
+            "https://github.com/zed-industries/zed.git",
+            "https://github.com/zed-industries/zed/",
+        ] {
+            assert_eq!(GitHubRepository::from_remote_url(url), expected, "{url}");
+        }
+
+        assert_eq!(
+            GitHubRepository::from_remote_url("https://gitlab.com/zed-industries/zed.git"),
+            None
+        );
+        assert_eq!(
+            GitHubRepository::from_remote_url("https://github.com/zed-industries"),
+            None
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::github_api::DiffSide;
+
+/// A line of a file on one side of a pull request's diff, which review comments can be attached to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiffLocation {
+    pub path: String,
+    pub side: DiffSide,
+    pub line: u32,
+}
+
+/// Maps the rows of a unified diff to the file lines they show.
+#[derive(Default)]
+pub struct PullRequestDiff {
+    rows: Vec<Option<DiffLocation>>,
+    /// The rows of added and removed lines, with the side of the diff they belong to.
+    changed_rows: Vec<(u32, DiffSide)>,
+}
+
+impl PullRequestDiff {
+    pub fn parse(diff: &str) -> Self {
+        let mut rows = Vec::new();
+        let mut changed_rows = Vec::new();
+        let mut path = None;
+        let mut old_line = 0;
+        let mut new_line = 0;
+        let mut in_hunk = false;
+
+        for row in diff.lines() {
+            if row.starts_with("diff --git ") {
+                path = None;
+                in_hunk = false;
+                rows.push(None);
+                continue;
+            }
+
+            if !in_hunk {
+                if let Some(old_path) = row.strip_prefix("--- a/") {
+                    path = Some(old_path.to_string());
+                } else if let Some(new_path) = row.strip_prefix("+++ b/") {
+                    path = Some(new_path.to_string());
+                }
+            }
+
+            if let Some(hunk_header) = row.strip_prefix("@@ ") {
+                if let Some((old_start, new_start)) = parse_hunk_header(hunk_header) {
+                    old_line = old_start;
+                    new_line = new_start;
+                    in_hunk = true;
+                }
+                rows.push(None);
+                continue;
+            }
+
+            let location = match (in_hunk, &path, row.chars().next()) {
+                (true, Some(path), Some('+')) => {
+                    changed_rows.push((rows.len() as u32, DiffSide::Right));
+                    new_line += 1;
+                    Some((path, DiffSide::Right, new_line - 1))
+                }
+                (true, Some(path), Some('-')) => {
+                    changed_rows.push((rows.len() as u32, DiffSide::Left));
+                    old_line += 1;
+                    Some((path, DiffSide::Left, old_line - 1))
+                }
+                (true, Some(path), Some(' ') | None) => {
+                    old_line += 1;
+                    new_line += 1;
+                    Some((path, DiffSide::Right, new_line - 1))
+                }
+                _ => None,
+            };
+            rows.push(location.map(|(path, side, line)| DiffLocation {
+                path: path.clone(),
+                side,
+                line,
+            }));
+        }
+
+        Self { rows, changed_rows }
+    }
+
+    pub fn changed_rows(&self) -> &[(u32, DiffSide)] {
+        &self.changed_rows
+    }
+
+    pub fn location_for_row(&self, row: u32) -> Option<&DiffLocation> {
+        self.rows.get(row as usize)?.as_ref()
+    }
+
+    pub fn row_for_location(&self, location: &DiffLocation) -> Option<u32> {
+        self.rows
+            .iter()
+            .position(|row| row.as_ref() == Some(location))
+            .map(|row| row as u32)
+    }
+}
+
+/// Parses the old and new start lines out of a hunk header such as `-1,5 +1,7 @@ fn main() {`.
+fn parse_hunk_header(header: &str) -> Option<(u32, u32)> {
+    let mut ranges = header.split_whitespace();
+    let old_start = ranges.next()?.strip_prefix('-')?.split(',').next()?;
+    let new_start = ranges.next()?.strip_prefix('+')?.split(',').next()?;
+    Some((old_start.parse().ok()?, new_start.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_diff_locations() {
+        let diff = PullRequestDiff::parse(indoc! {"
+            diff --git a/src/main.rs b/src/main.rs
+            index 1111111..2222222 100644
+            --- a/src/main.rs
+            +++ b/src/main.rs
+            @@ -1,3 +1,3 @@
+             fn main() {
+            -    println!(\"hello\");
+            +    println!(\"goodbye\");
+             }
+            diff --git a/README.md b/README.md
+            new file mode 100644
+            --- /dev/null
+            +++ b/README.md
+            @@ -0,0 +1 @@
+            +# Title
+        "});
+
+        let location = |path: &str, side, line| {
+            Some(DiffLocation {
+                path: path.into(),
+                side,
+                line,
+            })
+        };
+        for row in 0..5 {
+            assert_eq!(diff.location_for_row(row), None);
+        }
+        assert_eq!(
+            diff.location_for_row(5).cloned(),
+            location("src/main.rs", DiffSide::Right, 1)
+        );
+        assert_eq!(
+            diff.location_for_row(6).cloned(),
+            location("src/main.rs", DiffSide::Left, 2)
+        );
+        assert_eq!(
+            diff.location_for_row(7).cloned(),
+            location("src/main.rs", DiffSide::Right, 2)
+        );
+        assert_eq!(
+            diff.location_for_row(8).cloned(),
+            location("src/main.rs", DiffSide::Right, 3)
+        );
+        assert_eq!(diff.location_for_row(13), None);
+        assert_eq!(
+            diff.location_for_row(14).cloned(),
+            location("README.md", DiffSide::Right, 1)
+        );
+
+        assert_eq!(
+            diff.changed_rows(),
+            &[
+                (6, DiffSide::Left),
+                (7, DiffSide::Right),
+                (14, DiffSide::Right)
+            ]
+        );
+
+        assert_eq!(
+            diff.row_for_location(&location("src/main.rs", DiffSide::Left, 2).unwrap()),
+            Some(6)
+        );
+        assert_eq!(
+            diff.row_for_location(&location("src/lib.rs", DiffSide::Left, 2).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk_header("-1,5 +1,7 @@ fn main() {"), Some((1, 1)));
+        assert_eq!(parse_hunk_header("-10 +12,2 @@"), Some((10, 12)));
+        assert_eq!(parse_hunk_header("garbage"), None);
+    }
+}
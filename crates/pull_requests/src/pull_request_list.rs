@@ -0,0 +1,464 @@
+use crate::{
+    github_api::{self, GitHubRepository, PullRequest},
+    github_remote, read_github_token, write_github_token, PullRequestView,
+};
+use anyhow::{bail, Result};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::repository::GitRepository;
+use gpui::{
+    rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, SharedString, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+};
+use parking_lot::Mutex;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::{http::HttpClient, ResultExt};
+use workspace::{notifications::NotificationId, ModalView, Toast, Workspace};
+
+/// Lists the open pull requests of the project's GitHub repository.
+pub struct PullRequestList {
+    picker: View<Picker<PullRequestListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl PullRequestList {
+    pub(crate) fn toggle_modal(
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Result<()> {
+        let delegate = PullRequestListDelegate::new(workspace, cx)?;
+        workspace.toggle_modal(cx, |cx| Self::new(delegate, cx));
+        Ok(())
+    }
+
+    fn new(delegate: PullRequestListDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| {
+            let mut picker = Picker::uniform_list(delegate, cx);
+            picker.delegate.load_pull_requests(None, cx);
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for PullRequestList {}
+impl EventEmitter<DismissEvent> for PullRequestList {}
+
+impl FocusableView for PullRequestList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for PullRequestList {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(40.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+enum ListState {
+    Loading,
+    /// No GitHub token is known, so the query editor is used to enter one.
+    NeedsToken,
+    Loaded,
+    Failed(SharedString),
+}
+
+pub struct PullRequestListDelegate {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    repo: Arc<Mutex<dyn GitRepository>>,
+    remote: String,
+    repository: GitHubRepository,
+    http: Arc<dyn HttpClient>,
+    token: Option<String>,
+    state: ListState,
+    pull_requests: Vec<PullRequest>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    last_query: String,
+}
+
+impl PullRequestListDelegate {
+    fn new(workspace: &Workspace, cx: &ViewContext<Workspace>) -> Result<Self> {
+        let project = workspace.project().read(cx);
+        let Some(worktree) = project.visible_worktrees(cx).next() else {
+            bail!("Cannot list pull requests as there are no visible worktrees")
+        };
+
+        let dot_git = worktree.read(cx).abs_path().join(".git");
+        let Some(repo) = project.fs().open_repo(&dot_git) else {
+            bail!("Project does not have associated git repository.")
+        };
+        let Some((remote, repository)) = github_remote(&*repo.lock()) else {
+            bail!("Project's git repository does not have a GitHub remote.")
+        };
+
+        Ok(Self {
+            workspace: cx.view().downgrade(),
+            project: workspace.project().clone(),
+            repo,
+            remote,
+            repository,
+            http: workspace.client().http_client(),
+            token: None,
+            state: ListState::Loading,
+            pull_requests: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            last_query: String::new(),
+        })
+    }
+
+    /// Fetches the open pull requests, reading the GitHub token from the keychain if none is given.
+    fn load_pull_requests(&mut self, token: Option<String>, cx: &mut ViewContext<Picker<Self>>) {
+        self.state = ListState::Loading;
+        let http = self.http.clone();
+        let repository = self.repository.clone();
+        cx.spawn(|picker, mut cx| async move {
+            let token = match token {
+                Some(token) => Some(token),
+                None => read_github_token(&cx).await,
+            };
+            let Some(token) = token else {
+                return picker.update(&mut cx, |picker, cx| {
+                    picker.delegate.state = ListState::NeedsToken;
+                    picker.refresh(cx);
+                });
+            };
+
+            let pull_requests = github_api::list_pull_requests(&http, &token, &repository).await;
+            picker.update(&mut cx, |picker, cx| {
+                let delegate = &mut picker.delegate;
+                delegate.token = Some(token);
+                match pull_requests {
+                    Ok(pull_requests) => {
+                        delegate.pull_requests = pull_requests;
+                        delegate.state = ListState::Loaded;
+                    }
+                    Err(error) => {
+                        delegate.state = ListState::Failed(
+                            format!("Failed to load pull requests: {error}").into(),
+                        );
+                    }
+                }
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn selected_pull_request(&self) -> Option<&PullRequest> {
+        let hit = self.matches.get(self.selected_index)?;
+        self.pull_requests.get(hit.candidate_id)
+    }
+
+    fn save_token(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let token = self.last_query.trim().to_string();
+        if token.is_empty() {
+            return;
+        }
+
+        let write_token = write_github_token(&token, cx);
+        self.state = ListState::Loading;
+        cx.spawn(|picker, mut cx| async move {
+            write_token.await.log_err();
+            picker.update(&mut cx, |picker, cx| {
+                picker.set_query("", cx);
+                picker.delegate.load_pull_requests(Some(token), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn open_pull_request(&self, pull_request: PullRequest, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        PullRequestView::open(
+            self.workspace.clone(),
+            self.project.clone(),
+            self.http.clone(),
+            token,
+            self.repository.clone(),
+            pull_request,
+            cx,
+        )
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn checkout_pull_request(&self, pull_request: PullRequest, cx: &mut ViewContext<Picker<Self>>) {
+        let repo = self.repo.clone();
+        let remote = self.remote.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    checkout_pull_request_branch(&*repo.lock(), &remote, pull_request.number)
+                })
+                .await;
+
+            if let Err(error) = result {
+                workspace.update(&mut cx, |workspace, cx| {
+                    struct PullRequestCheckoutFailure;
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<PullRequestCheckoutFailure>(),
+                            format!("Failed to check out pull request: {error}"),
+                        ),
+                        cx,
+                    )
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Returns the local branch that the given pull request is checked out into. It's
+/// separate from the pull request's own branch name, which can be anything, including
+/// `main` when the pull request comes from a fork.
+fn pull_request_branch(number: u64) -> String {
+    format!("pr/{number}")
+}
+
+/// Fetches the head of the given pull request into its own local branch and checks it
+/// out, returning the branch's name. The branch is reset to the fetched head, so
+/// pull requests can be checked out again after their author force-pushes.
+fn checkout_pull_request_branch(
+    repo: &dyn GitRepository,
+    remote: &str,
+    number: u64,
+) -> Result<String> {
+    let branch = pull_request_branch(number);
+    if repo.branch_name().as_deref() == Some(branch.as_str()) {
+        bail!("pull request #{number} is already checked out");
+    }
+    if repo.has_uncommitted_changes()? {
+        bail!("commit or stash your changes first");
+    }
+    repo.fetch_branch(remote, &format!("pull/{number}/head"), &branch)?;
+    repo.change_branch(&branch)?;
+    Ok(branch)
+}
+
+impl PickerDelegate for PullRequestListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.state {
+            ListState::NeedsToken => "Paste a GitHub personal access token...".into(),
+            _ => format!("Search pull requests of {}...", self.repository).into(),
+        }
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        match &self.state {
+            ListState::Loading => "Loading pull requests...".into(),
+            ListState::NeedsToken => {
+                "Paste a GitHub token and press enter to store it in your keychain.".into()
+            }
+            ListState::Loaded => "No open pull requests".into(),
+            ListState::Failed(error) => error.clone(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .pull_requests
+            .iter()
+            .enumerate()
+            .map(|(id, pull_request)| {
+                let string = format!("#{} {}", pull_request.number, pull_request.title);
+                StringMatchCandidate {
+                    id,
+                    char_bag: string.chars().collect(),
+                    string,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                    delegate.last_query = query;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let ListState::NeedsToken = self.state {
+            self.save_token(cx);
+            return;
+        }
+
+        let Some(pull_request) = self.selected_pull_request().cloned() else {
+            return;
+        };
+        if secondary {
+            self.checkout_pull_request(pull_request, cx);
+        } else {
+            self.open_pull_request(pull_request, cx);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let pull_request = self.pull_requests.get(hit.candidate_id)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                ))
+                .end_slot(
+                    h_flex()
+                        .gap_1()
+                        .when(pull_request.draft, |this| {
+                            this.child(
+                                Label::new("Draft")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .child(
+                            Label::new(pull_request.user.login.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.selected_pull_request().is_none() {
+            return None;
+        }
+
+        Some(
+            h_flex()
+                .mr_3()
+                .pb_2()
+                .gap_1()
+                .child(h_flex().w_full())
+                .child(
+                    Button::new("pull-request-checkout-button", "Check Out").on_click(cx.listener(
+                        |picker, _, cx| {
+                            picker.delegate.confirm(true, cx);
+                        },
+                    )),
+                )
+                .child(
+                    Button::new("pull-request-open-button", "Open")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.confirm(false, cx);
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{FakeGitRepository, FakeGitRepositoryState};
+
+    #[test]
+    fn test_checkout_fork_pull_request_named_main() {
+        let state = Arc::new(Mutex::new(FakeGitRepositoryState {
+            branch_name: Some("main".into()),
+            branches: ["main".into()].into_iter().collect(),
+            ..Default::default()
+        }));
+        let repo = FakeGitRepository::open(state.clone());
+
+        // The fork's `main` branch is fetched into a branch of its own, leaving the
+        // local `main` alone.
+        let branch = checkout_pull_request_branch(&*repo.lock(), "origin", 42).unwrap();
+        assert_eq!(branch, "pr/42");
+        {
+            let state = state.lock();
+            assert_eq!(state.branch_name.as_deref(), Some("pr/42"));
+            assert_eq!(state.fetched_refs.get("pr/42").unwrap(), "pull/42/head");
+            assert!(!state.fetched_refs.contains_key("main"));
+        }
+
+        // The pull request can be checked out again, such as after a force-push.
+        repo.lock().change_branch("main").unwrap();
+        checkout_pull_request_branch(&*repo.lock(), "origin", 42).unwrap();
+        assert_eq!(state.lock().branch_name.as_deref(), Some("pr/42"));
+        assert!(checkout_pull_request_branch(&*repo.lock(), "origin", 42).is_err());
+    }
+}
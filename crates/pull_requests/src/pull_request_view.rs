@@ -0,0 +1,407 @@
+use crate::{
+    github_api::{self, DiffSide, GitHubRepository, PullRequest, ReviewComment},
+    pull_request_diff::{DiffLocation, PullRequestDiff},
+    SubmitReviewComment,
+};
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent, MultiBuffer,
+};
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render, Subscription,
+    Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::Point;
+use project::Project;
+use std::sync::Arc;
+use ui::prelude::*;
+use util::http::HttpClient;
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace,
+};
+
+/// A pull request's diff, with its review comments shown below the lines they are attached to.
+pub struct PullRequestView {
+    http: Arc<dyn HttpClient>,
+    token: String,
+    repository: GitHubRepository,
+    pull_request: PullRequest,
+    editor: View<Editor>,
+    diff: PullRequestDiff,
+    comments: Vec<ReviewComment>,
+    comment_blocks: HashSet<BlockId>,
+    comment_editor: View<Editor>,
+    posting_comment: bool,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PullRequestView {
+    /// Fetches the diff and review comments of the given pull request, and opens them in the workspace.
+    pub fn open(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        http: Arc<dyn HttpClient>,
+        token: String,
+        repository: GitHubRepository,
+        pull_request: PullRequest,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        cx.spawn(|mut cx| async move {
+            let (diff, comments) = futures::try_join!(
+                github_api::fetch_pull_request_diff(
+                    &http,
+                    &token,
+                    &repository,
+                    pull_request.number
+                ),
+                github_api::list_review_comments(&http, &token, &repository, pull_request.number),
+            )?;
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let buffer =
+                    project.update(cx, |project, cx| project.create_buffer(&diff, None, cx))?;
+                let title = format!("#{} {}", pull_request.number, pull_request.title);
+                let buffer =
+                    cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                let view = cx.new_view(|cx| {
+                    let editor = cx.new_view(|cx| {
+                        let mut editor = Editor::for_multibuffer(buffer, Some(project), cx);
+                        editor.set_read_only(true);
+                        editor
+                    });
+                    Self::new(
+                        editor,
+                        PullRequestDiff::parse(&diff),
+                        comments,
+                        http,
+                        token,
+                        repository,
+                        pull_request,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(view), cx);
+                anyhow::Ok(())
+            })?
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        editor: View<Editor>,
+        diff: PullRequestDiff,
+        comments: Vec<ReviewComment>,
+        http: Arc<dyn HttpClient>,
+        token: String,
+        repository: GitHubRepository,
+        pull_request: PullRequest,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let comment_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(6, cx);
+            editor.set_placeholder_text("Leave a comment on the line under the cursor", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event, cx| {
+                if let EditorEvent::SelectionsChanged { .. } = event {
+                    cx.notify();
+                }
+            }),
+            cx.subscribe(&comment_editor, |_, _, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    cx.notify();
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            http,
+            token,
+            repository,
+            pull_request,
+            editor,
+            diff,
+            comments,
+            comment_blocks: HashSet::default(),
+            comment_editor,
+            posting_comment: false,
+            error: None,
+            _subscriptions: subscriptions,
+        };
+        this.highlight_changed_rows(cx);
+        this.update_comment_blocks(cx);
+        this
+    }
+
+    fn highlight_changed_rows(&mut self, cx: &mut ViewContext<Self>) {
+        let added_background = cx.theme().status().created_background;
+        let removed_background = cx.theme().status().deleted_background;
+        let changed_rows = self.diff.changed_rows().to_vec();
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            for (row, side) in changed_rows {
+                let start = snapshot.anchor_before(Point::new(row, 0));
+                let end = snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+                let color = match side {
+                    DiffSide::Left => removed_background,
+                    DiffSide::Right => added_background,
+                };
+                editor.highlight_rows::<Self>(start..end, Some(color), cx);
+            }
+        });
+    }
+
+    /// Shows each thread of review comments in a block below the line it's attached to.
+    fn update_comment_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        let mut threads = HashMap::<u64, (DiffLocation, Vec<(String, String)>)>::default();
+        let mut thread_order = Vec::new();
+        for comment in &self.comments {
+            let entry = (comment.user.login.clone(), comment.body.clone());
+            if let Some((_, replies)) = comment.in_reply_to_id.and_then(|id| threads.get_mut(&id)) {
+                replies.push(entry);
+            } else if let Some(line) = comment.line {
+                let location = DiffLocation {
+                    path: comment.path.clone(),
+                    side: comment.side.unwrap_or(DiffSide::Right),
+                    line,
+                };
+                threads.insert(comment.id, (location, vec![entry]));
+                thread_order.push(comment.id);
+            }
+        }
+
+        let threads = thread_order
+            .into_iter()
+            .filter_map(|id| {
+                let (location, comments) = threads.remove(&id)?;
+                let row = self.diff.row_for_location(&location)?;
+                Some((row, comments))
+            })
+            .collect::<Vec<_>>();
+
+        let old_blocks = std::mem::take(&mut self.comment_blocks);
+        self.comment_blocks = self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(old_blocks, None, cx);
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let blocks = threads.into_iter().map(|(row, comments)| {
+                let height = comments
+                    .iter()
+                    .map(|(_, body)| 1 + body.lines().count().max(1))
+                    .sum::<usize>();
+                BlockProperties {
+                    position: snapshot.anchor_after(Point::new(row, 0)),
+                    height: height.min(u8::MAX as usize) as u8,
+                    style: BlockStyle::Flex,
+                    render: Box::new(move |cx: &mut BlockContext| {
+                        render_comment_thread(&comments, cx.anchor_x)
+                    }),
+                    disposition: BlockDisposition::Below,
+                }
+            });
+            editor.insert_blocks(blocks, None, cx).into_iter().collect()
+        });
+    }
+
+    /// Returns the location of the diff line under the newest cursor, if comments can be attached to it.
+    fn comment_location(&self, cx: &AppContext) -> Option<&DiffLocation> {
+        let cursor = self.editor.read(cx).selections.newest::<Point>(cx).head();
+        self.diff.location_for_row(cursor.row)
+    }
+
+    fn submit_comment(&mut self, _: &SubmitReviewComment, cx: &mut ViewContext<Self>) {
+        let body = self.comment_editor.read(cx).text(cx);
+        if self.posting_comment || body.trim().is_empty() {
+            return;
+        }
+        let Some(location) = self.comment_location(cx).cloned() else {
+            return;
+        };
+
+        let http = self.http.clone();
+        let token = self.token.clone();
+        let repository = self.repository.clone();
+        let pull_request = self.pull_request.clone();
+        self.posting_comment = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let comment = github_api::create_review_comment(
+                &http,
+                &token,
+                &repository,
+                &pull_request,
+                &location.path,
+                location.line,
+                location.side,
+                body.trim(),
+            )
+            .await;
+            this.update(&mut cx, |this, cx| {
+                this.posting_comment = false;
+                match comment {
+                    Ok(comment) => {
+                        this.comments.push(comment);
+                        this.comment_editor
+                            .update(cx, |editor, cx| editor.clear(cx));
+                        this.update_comment_blocks(cx);
+                    }
+                    Err(error) => {
+                        this.error = Some(format!("Failed to post comment: {error}").into());
+                    }
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_comment_composer(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let location = self.comment_location(cx);
+        let can_comment = location.is_some()
+            && !self.posting_comment
+            && !self.comment_editor.read(cx).text(cx).trim().is_empty();
+        let target = match location {
+            Some(location) => {
+                let side = match location.side {
+                    DiffSide::Left => " (old)",
+                    DiffSide::Right => "",
+                };
+                format!("Comment on {}:{}{side}", location.path, location.line)
+            }
+            None => "Move the cursor onto a line of the diff to comment on it".to_string(),
+        };
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .p_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.comment_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(target)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new(
+                            "submit-review-comment",
+                            if self.posting_comment {
+                                "Commenting…"
+                            } else {
+                                "Comment"
+                            },
+                        )
+                        .style(ButtonStyle::Filled)
+                        .disabled(!can_comment)
+                        .on_click(
+                            cx.listener(|this, _, cx| {
+                                this.submit_comment(&SubmitReviewComment, cx)
+                            }),
+                        ),
+                    ),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
+    }
+}
+
+fn render_comment_thread(comments: &[(String, String)], padding: Pixels) -> AnyElement {
+    v_flex()
+        .pl(padding)
+        .children(comments.iter().map(|(author, body)| {
+            v_flex()
+                .child(
+                    Label::new(author.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Accent),
+                )
+                .children(body.lines().map(|line| Label::new(line.to_string())))
+        }))
+        .into_any_element()
+}
+
+impl Render for PullRequestView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("PullRequestView")
+            .on_action(cx.listener(Self::submit_comment))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(format!(
+                        "#{} {}",
+                        self.pull_request.number, self.pull_request.title
+                    )))
+                    .child(
+                        Label::new(format!(
+                            "{} wants to merge {} into {}",
+                            self.pull_request.user.login,
+                            self.pull_request.head.ref_name,
+                            self.pull_request.base.ref_name
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(div().flex_1().child(self.editor.clone()))
+            .child(self.render_comment_composer(cx))
+    }
+}
+
+impl FocusableView for PullRequestView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<()> for PullRequestView {}
+
+impl Item for PullRequestView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(format!("PR #{}", self.pull_request.number))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.pull_request.title.clone().into())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGit))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("pull request")
+    }
+}
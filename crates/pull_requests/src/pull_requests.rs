@@ -0,0 +1,69 @@
+mod github_api;
+mod pull_request_diff;
+mod pull_request_list;
+mod pull_request_view;
+
+use anyhow::Result;
+use credentials::Credential;
+use git::repository::GitRepository;
+use github_api::GitHubRepository;
+use gpui::{actions, AppContext, AsyncAppContext, Task};
+use util::ResultExt;
+use workspace::Workspace;
+
+pub use pull_request_list::PullRequestList;
+pub use pull_request_view::PullRequestView;
+
+actions!(
+    pull_requests,
+    [OpenPullRequests, ForgetGitHubToken, SubmitReviewComment]
+);
+
+/// The keychain entry under which the GitHub token is stored.
+const GITHUB_CREDENTIALS_URL: &str = "https://api.github.com";
+
+fn github_credential() -> Credential {
+    Credential::new("GitHub Token", GITHUB_CREDENTIALS_URL).with_env_var("GITHUB_TOKEN")
+}
+
+/// Remotes that are searched for a GitHub repository, in order of preference. A fork's
+/// pull requests are usually opened against its upstream.
+const REMOTE_NAMES: [&str; 2] = ["upstream", "origin"];
+
+pub fn init(cx: &mut AppContext) {
+    credentials::register(cx, |_| Some(github_credential()));
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &OpenPullRequests, cx| {
+                PullRequestList::toggle_modal(workspace, cx).log_err();
+            })
+            .register_action(|_, _: &ForgetGitHubToken, cx| {
+                github_credential().delete(cx).detach_and_log_err(cx);
+            });
+    })
+    .detach();
+}
+
+/// Returns the name of the remote that points at GitHub, along with the repository it points at.
+fn github_remote(repo: &dyn GitRepository) -> Option<(String, GitHubRepository)> {
+    REMOTE_NAMES.into_iter().find_map(|name| {
+        let url = repo.remote_url(name)?;
+        let repository = GitHubRepository::from_remote_url(&url)?;
+        Some((name.to_string(), repository))
+    })
+}
+
+/// Reads the GitHub token from the `GITHUB_TOKEN` environment variable, falling back to the keychain.
+async fn read_github_token(cx: &AsyncAppContext) -> Option<String> {
+    let (token, _) = cx
+        .update(|cx| github_credential().read(cx))
+        .log_err()?
+        .await
+        .log_err()
+        .flatten()?;
+    Some(token)
+}
+
+fn write_github_token(token: &str, cx: &AppContext) -> Task<Result<()>> {
+    github_credential().write(token, cx)
+}
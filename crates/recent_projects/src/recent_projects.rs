@@ -1,5 +1,6 @@
 mod remote_projects;
 
+use collections::HashMap;
 use feature_flags::FeatureFlagAppExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -18,13 +19,15 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use time::OffsetDateTime;
 use ui::{
     prelude::*, tooltip_container, ButtonLike, IconWithIndicator, Indicator, KeyBinding, ListItem,
     ListItemSpacing, Tooltip,
 };
 use util::{paths::PathExt, ResultExt};
 use workspace::{
-    AppState, ModalView, SerializedWorkspaceLocation, Workspace, WorkspaceId, WORKSPACE_DB,
+    AppState, ModalView, RecentWorkspaceDetails, SerializedWorkspaceLocation, Workspace,
+    WorkspaceId, WORKSPACE_DB,
 };
 
 #[derive(PartialEq, Clone, Deserialize, Default)]
@@ -38,7 +41,7 @@ fn default_create_new_window() -> bool {
 }
 
 gpui::impl_actions!(projects, [OpenRecent]);
-gpui::actions!(projects, [OpenRemote]);
+gpui::actions!(projects, [OpenRemote, RemoveSelected]);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(RecentProjects::register).detach();
@@ -73,9 +76,14 @@ impl RecentProjects {
                 .await
                 .log_err()
                 .unwrap_or_default();
+            let details = WORKSPACE_DB
+                .recent_workspace_details()
+                .log_err()
+                .unwrap_or_default();
             this.update(&mut cx, move |this, cx| {
                 this.picker.update(cx, move |picker, cx| {
                     picker.delegate.set_workspaces(workspaces);
+                    picker.delegate.details = details;
                     picker.update_matches(picker.query(cx), cx)
                 })
             })
@@ -139,7 +147,14 @@ impl FocusableView for RecentProjects {
 impl Render for RecentProjects {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         v_flex()
+            .key_context("RecentProjects")
             .w(rems(self.rem_width))
+            .on_action(cx.listener(|this, _: &RemoveSelected, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    let ix = picker.delegate.selected_index();
+                    picker.delegate.delete_recent_project(ix, cx);
+                })
+            }))
             .child(self.picker.clone())
             .on_mouse_down_out(cx.listener(|this, _, cx| {
                 this.picker.update(cx, |this, cx| {
@@ -152,6 +167,7 @@ impl Render for RecentProjects {
 pub struct RecentProjectsDelegate {
     workspace: WeakView<Workspace>,
     workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>,
+    details: HashMap<WorkspaceId, RecentWorkspaceDetails>,
     selected_match_index: usize,
     matches: Vec<StringMatch>,
     render_paths: bool,
@@ -166,6 +182,7 @@ impl RecentProjectsDelegate {
         Self {
             workspace,
             workspaces: Vec::new(),
+            details: HashMap::default(),
             selected_match_index: 0,
             matches: Default::default(),
             create_new_window,
@@ -464,8 +481,13 @@ impl PickerDelegate for RecentProjectsDelegate {
                             if !self.render_paths {
                                 highlighted.paths.clear();
                             }
-                            highlighted.render(cx)
-                        }),
+                            div().flex_1().child(highlighted.render(cx))
+                        })
+                        .children(
+                            self.details
+                                .get(workspace_id)
+                                .map(|details| render_details(details, cx)),
+                        ),
                 )
                 .when(!is_current_workspace, |el| {
                     let delete_button = div()
@@ -532,6 +554,32 @@ impl PickerDelegate for RecentProjectsDelegate {
     }
 }
 
+/// Shows when the project was last opened, and whether changes were discarded
+/// when it was closed.
+fn render_details(details: &RecentWorkspaceDetails, cx: &WindowContext) -> impl IntoElement {
+    let last_opened = time_format::format_localized_timestamp(
+        OffsetDateTime::from(details.last_opened),
+        OffsetDateTime::now_utc(),
+        cx.local_timezone(),
+        time_format::TimestampFormat::Relative,
+    );
+    h_flex()
+        .gap_1()
+        .when(details.closed_with_unsaved_changes, |this| {
+            this.child(
+                div()
+                    .id("closed-with-unsaved-changes")
+                    .child(Indicator::dot().color(Color::Modified))
+                    .tooltip(|cx| Tooltip::text("Closed with unsaved changes", cx)),
+            )
+        })
+        .child(
+            Label::new(last_opened)
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+}
+
 // Compute the highlighted text for the name and path
 fn highlights_for_path(
     path: &Path,
@@ -586,6 +634,9 @@ impl RecentProjectsDelegate {
     fn delete_recent_project(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
         if let Some(selected_match) = self.matches.get(ix) {
             let (workspace_id, _) = self.workspaces[selected_match.candidate_id];
+            if self.is_current_workspace(workspace_id, cx) {
+                return;
+            }
             cx.spawn(move |this, mut cx| async move {
                 let _ = WORKSPACE_DB.delete_workspace_by_id(workspace_id).await;
                 let workspaces = WORKSPACE_DB
@@ -594,7 +645,7 @@ impl RecentProjectsDelegate {
                     .unwrap_or_default();
                 this.update(&mut cx, move |picker, cx| {
                     picker.delegate.set_workspaces(workspaces);
-                    picker.delegate.set_selected_index(ix - 1, cx);
+                    picker.delegate.set_selected_index(ix.saturating_sub(1), cx);
                     picker.delegate.reset_selected_match_index = false;
                     picker.update_matches(picker.query(cx), cx)
                 })
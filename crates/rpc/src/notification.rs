@@ -38,6 +38,13 @@ pub enum Notification {
         sender_id: u64,
         channel_id: u64,
     },
+    ChannelMessageThreadReply {
+        #[serde(rename = "entity_id")]
+        message_id: u64,
+        sender_id: u64,
+        channel_id: u64,
+        thread_root_id: u64,
+    },
 }
 
 impl Notification {
@@ -96,6 +103,12 @@ mod tests {
                 channel_id: 30,
                 message_id: 1,
             },
+            Notification::ChannelMessageThreadReply {
+                sender_id: 200,
+                channel_id: 30,
+                message_id: 2,
+                thread_root_id: 1,
+            },
         ] {
             let message = notification.to_proto();
             let deserialized = Notification::from_proto(&message).unwrap();
@@ -148,6 +148,8 @@ messages!(
     (Call, Foreground),
     (CallCanceled, Foreground),
     (CancelCall, Foreground),
+    (ChannelBufferCommentThreadResponse, Foreground),
+    (ChannelMessageReactionsUpdated, Foreground),
     (ChannelMessageSent, Foreground),
     (ChannelMessageUpdate, Foreground),
     (CompleteWithLanguageModel, Background),
@@ -158,6 +160,7 @@ messages!(
     (CountTokensResponse, Background),
     (CreateBufferForPeer, Foreground),
     (CreateChannel, Foreground),
+    (CreateChannelBufferCommentThread, Foreground),
     (CreateChannelResponse, Foreground),
     (CreateProjectEntry, Foreground),
     (CreateRoom, Foreground),
@@ -181,6 +184,7 @@ messages!(
     (GetChannelMembers, Foreground),
     (GetChannelMembersResponse, Foreground),
     (GetChannelMessages, Background),
+    (GetChannelMessageThread, Background),
     (GetChannelMessagesById, Background),
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
@@ -211,6 +215,7 @@ messages!(
     (InlayHints, Background),
     (InlayHintsResponse, Background),
     (InviteChannelMember, Foreground),
+    (InviteToFollow, Foreground),
     (JoinChannel, Foreground),
     (JoinChannelBuffer, Foreground),
     (JoinChannelBufferResponse, Foreground),
@@ -256,7 +261,10 @@ messages!(
     (RenameChannel, Foreground),
     (RenameChannelResponse, Foreground),
     (RenameProjectEntry, Foreground),
+    (ReplyToChannelBufferCommentThread, Foreground),
     (RequestContact, Foreground),
+    (RequestTerminalControl, Foreground),
+    (ResolveChannelBufferCommentThread, Foreground),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
@@ -266,6 +274,7 @@ messages!(
     (RoomUpdated, Foreground),
     (SaveBuffer, Foreground),
     (SetChannelMemberRole, Foreground),
+    (SetChannelMessageReaction, Foreground),
     (SetChannelVisibility, Foreground),
     (SearchProject, Background),
     (SearchProjectResponse, Background),
@@ -273,6 +282,7 @@ messages!(
     (SendChannelMessageResponse, Background),
     (ShareProject, Foreground),
     (ShareProjectResponse, Foreground),
+    (SharedTerminalInput, Foreground),
     (ShowContacts, Foreground),
     (StartLanguageServer, Foreground),
     (SynchronizeBuffers, Foreground),
@@ -280,11 +290,14 @@ messages!(
     (Test, Foreground),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
+    (UnshareTerminal, Foreground),
     (UpdateBuffer, Foreground),
     (UpdateBufferFile, Foreground),
     (UpdateChannelBuffer, Foreground),
     (UpdateChannelBufferCollaborators, Foreground),
+    (UpdateChannelBufferCommentThread, Foreground),
     (UpdateChannels, Foreground),
+    (UpdateSharedTerminal, Foreground),
     (UpdateUserChannels, Foreground),
     (UpdateContacts, Foreground),
     (UpdateDiagnosticSummary, Foreground),
@@ -293,6 +306,7 @@ messages!(
     (UpdateInviteInfo, Foreground),
     (UpdateLanguageServer, Foreground),
     (UpdateParticipantLocation, Foreground),
+    (UpdateParticipantPresence, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
     (UpdateWorktree, Foreground),
@@ -335,6 +349,10 @@ request_messages!(
     (ComputeEmbeddings, ComputeEmbeddingsResponse),
     (CountTokensWithLanguageModel, CountTokensResponse),
     (CreateChannel, CreateChannelResponse),
+    (
+        CreateChannelBufferCommentThread,
+        ChannelBufferCommentThreadResponse
+    ),
     (CreateProjectEntry, ProjectEntryResponse),
     (CreateRoom, CreateRoomResponse),
     (DeclineCall, Ack),
@@ -347,6 +365,7 @@ request_messages!(
     (GetCachedEmbeddings, GetCachedEmbeddingsResponse),
     (GetChannelMembers, GetChannelMembersResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
+    (GetChannelMessageThread, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
@@ -387,15 +406,24 @@ request_messages!(
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
     (UpdateChannelMessage, Ack),
+    (SetChannelMessageReaction, Ack),
     (RemoveContact, Ack),
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
+    (
+        ReplyToChannelBufferCommentThread,
+        ChannelBufferCommentThreadResponse
+    ),
     (RequestContact, Ack),
     (
         ResolveCompletionDocumentation,
         ResolveCompletionDocumentationResponse
     ),
     (ResolveInlayHint, ResolveInlayHintResponse),
+    (
+        ResolveChannelBufferCommentThread,
+        ChannelBufferCommentThreadResponse
+    ),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
@@ -408,6 +436,7 @@ request_messages!(
     (Test, Test),
     (UpdateBuffer, Ack),
     (UpdateParticipantLocation, Ack),
+    (UpdateParticipantPresence, Ack),
     (UpdateProject, Ack),
     (UpdateWorktree, Ack),
     (LspExtExpandMacro, LspExtExpandMacroResponse),
@@ -462,13 +491,16 @@ entity_messages!(
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    RequestTerminalControl,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
     SaveBuffer,
     SearchProject,
+    SharedTerminalInput,
     StartLanguageServer,
     SynchronizeBuffers,
     UnshareProject,
+    UnshareTerminal,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -476,6 +508,7 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateSharedTerminal,
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
@@ -483,12 +516,14 @@ entity_messages!(
 
 entity_messages!(
     {channel_id, Channel},
+    ChannelMessageReactionsUpdated,
     ChannelMessageSent,
     ChannelMessageUpdate,
     RemoveChannelMessage,
     UpdateChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
+    UpdateChannelBufferCommentThread,
 );
 
 const KIB: usize = 1024;
@@ -0,0 +1,175 @@
+use crate::{add_scratch_buffer, persistence::SCRATCH_BUFFERS};
+use anyhow::Context as _;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Picks the language of a new scratch buffer.
+pub struct ScratchLanguagePicker {
+    picker: View<Picker<ScratchLanguagePickerDelegate>>,
+}
+
+impl ScratchLanguagePicker {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let language_names = workspace.app_state().languages.language_names();
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate = ScratchLanguagePickerDelegate {
+                language_picker: cx.view().downgrade(),
+                workspace: workspace_handle,
+                candidates: language_names
+                    .into_iter()
+                    .enumerate()
+                    .map(|(candidate_id, name)| StringMatchCandidate::new(candidate_id, name))
+                    .collect(),
+                matches: Vec::new(),
+                selected_index: 0,
+            };
+            Self {
+                picker: cx.new_view(|cx| Picker::uniform_list(delegate, cx)),
+            }
+        });
+    }
+}
+
+impl Render for ScratchLanguagePicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for ScratchLanguagePicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ScratchLanguagePicker {}
+impl ModalView for ScratchLanguagePicker {}
+
+pub struct ScratchLanguagePickerDelegate {
+    language_picker: WeakView<ScratchLanguagePicker>,
+    workspace: WeakView<Workspace>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for ScratchLanguagePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select the language of the scratch buffer...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let language = self.candidates[mat.candidate_id].string.clone();
+            let workspace = self.workspace.clone();
+            cx.spawn(|_, mut cx| async move {
+                let name = format!(
+                    "Scratch {}",
+                    SCRATCH_BUFFERS.list_scratch_buffers()?.len() + 1
+                );
+                let scratch_id = SCRATCH_BUFFERS
+                    .create_scratch_buffer(name.clone(), Some(language.clone()), String::new())
+                    .await?
+                    .context("failed to create scratch buffer")?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    add_scratch_buffer(
+                        workspace,
+                        scratch_id,
+                        name.into(),
+                        Some(language),
+                        String::new(),
+                        cx,
+                    );
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.language_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
@@ -0,0 +1,176 @@
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+/// Identifies a scratch buffer across restarts.
+pub type ScratchId = i64;
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // scratch_buffers(
+    //   scratch_id: i64,
+    //   name: String,
+    //   language: Option<String>,
+    //   text: String,
+    //   timestamp: String,
+    // )
+    // scratch_buffer_items(
+    //   workspace_id: usize,
+    //   item_id: usize,
+    //   scratch_id: i64,
+    // )
+    pub static ref SCRATCH_BUFFERS: ScratchBufferDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE scratch_buffers (
+                scratch_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                language TEXT,
+                text TEXT NOT NULL,
+                timestamp TEXT DEFAULT CURRENT_TIMESTAMP NOT NULL
+            ) STRICT;
+
+            CREATE TABLE scratch_buffer_items (
+                workspace_id INTEGER NOT NULL,
+                item_id INTEGER NOT NULL,
+                scratch_id INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, item_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE,
+                FOREIGN KEY(scratch_id) REFERENCES scratch_buffers(scratch_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl ScratchBufferDb {
+    query! {
+        pub async fn create_scratch_buffer(
+            name: String,
+            language: Option<String>,
+            text: String
+        ) -> Result<Option<ScratchId>> {
+            INSERT INTO scratch_buffers (name, language, text)
+            VALUES (?1, ?2, ?3)
+            RETURNING scratch_id
+        }
+    }
+
+    query! {
+        pub fn get_scratch_buffer(scratch_id: ScratchId) -> Result<Option<(String, Option<String>, String)>> {
+            SELECT name, language, text
+            FROM scratch_buffers
+            WHERE scratch_id = ?
+        }
+    }
+
+    // Returns the id, name and language of every scratch buffer, most recently edited first.
+    query! {
+        pub fn list_scratch_buffers() -> Result<Vec<(ScratchId, String, Option<String>)>> {
+            SELECT scratch_id, name, language
+            FROM scratch_buffers
+            ORDER BY timestamp DESC, scratch_id DESC
+        }
+    }
+
+    query! {
+        pub async fn save_text(scratch_id: ScratchId, text: String) -> Result<()> {
+            UPDATE scratch_buffers
+            SET text = ?2, timestamp = CURRENT_TIMESTAMP
+            WHERE scratch_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn save_language(scratch_id: ScratchId, language: Option<String>) -> Result<()> {
+            UPDATE scratch_buffers
+            SET language = ?2
+            WHERE scratch_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn rename_scratch_buffer(scratch_id: ScratchId, name: String) -> Result<()> {
+            UPDATE scratch_buffers
+            SET name = ?2
+            WHERE scratch_id = ?1
+        }
+    }
+
+    query! {
+        pub async fn delete_scratch_buffer(scratch_id: ScratchId) -> Result<()> {
+            DELETE FROM scratch_buffers
+            WHERE scratch_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_item(
+            workspace_id: WorkspaceId,
+            item_id: ItemId,
+            scratch_id: ScratchId
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO scratch_buffer_items (workspace_id, item_id, scratch_id)
+            VALUES (?1, ?2, ?3)
+        }
+    }
+
+    query! {
+        pub fn get_item_scratch_id(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<ScratchId>> {
+            SELECT scratch_id
+            FROM scratch_buffer_items
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::open_test_db;
+
+    #[gpui::test]
+    async fn test_scratch_buffers() {
+        let db = ScratchBufferDb(open_test_db("test_scratch_buffers").await);
+        assert_eq!(db.list_scratch_buffers().unwrap(), Vec::new());
+
+        let notes = db
+            .create_scratch_buffer("Notes".into(), None, String::new())
+            .await
+            .unwrap()
+            .unwrap();
+        let query = db
+            .create_scratch_buffer("Query".into(), Some("SQL".into()), String::new())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            db.list_scratch_buffers().unwrap(),
+            vec![
+                (query, "Query".to_string(), Some("SQL".to_string())),
+                (notes, "Notes".to_string(), None),
+            ]
+        );
+
+        db.save_text(notes, "remember the milk".into())
+            .await
+            .unwrap();
+        db.save_language(notes, Some("Markdown".into()))
+            .await
+            .unwrap();
+        db.rename_scratch_buffer(notes, "Groceries".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_scratch_buffer(notes).unwrap(),
+            Some((
+                "Groceries".to_string(),
+                Some("Markdown".to_string()),
+                "remember the milk".to_string()
+            ))
+        );
+
+        db.delete_scratch_buffer(query).await.unwrap();
+        assert_eq!(db.get_scratch_buffer(query).unwrap(), None);
+        assert_eq!(db.list_scratch_buffers().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    persistence::{ScratchId, SCRATCH_BUFFERS},
+    ScratchBuffer,
+};
+use editor::Editor;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, SharedString, View,
+    ViewContext, VisualContext, WeakView,
+};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Renames the active scratch buffer.
+pub struct RenameScratchBufferModal {
+    workspace: WeakView<Workspace>,
+    scratch_id: ScratchId,
+    name_editor: View<Editor>,
+}
+
+impl RenameScratchBufferModal {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(scratch_buffer) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ScratchBuffer>())
+        else {
+            return;
+        };
+        let scratch_buffer = scratch_buffer.read(cx);
+        let scratch_id = scratch_buffer.scratch_id();
+        let name = scratch_buffer.name().clone();
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let name_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_text(name, cx);
+                editor.select_all(&Default::default(), cx);
+                editor
+            });
+            Self {
+                workspace: workspace_handle,
+                scratch_id,
+                name_editor,
+            }
+        });
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let scratch_id = self.scratch_id;
+        let new_name = SharedString::from(name.clone());
+        self.workspace
+            .update(cx, |workspace, cx| {
+                for scratch_buffer in workspace
+                    .items_of_type::<ScratchBuffer>(cx)
+                    .collect::<Vec<_>>()
+                {
+                    scratch_buffer.update(cx, |scratch_buffer, cx| {
+                        if scratch_buffer.scratch_id() == scratch_id {
+                            scratch_buffer.set_name(new_name.clone(), cx);
+                        }
+                    });
+                }
+            })
+            .log_err();
+        cx.background_executor()
+            .spawn(async move {
+                SCRATCH_BUFFERS
+                    .rename_scratch_buffer(scratch_id, name)
+                    .await
+                    .log_err();
+            })
+            .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for RenameScratchBufferModal {}
+impl EventEmitter<DismissEvent> for RenameScratchBufferModal {}
+
+impl FocusableView for RenameScratchBufferModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for RenameScratchBufferModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RenameScratchBuffer")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w_96()
+            .child(div().px_2().py_1().child(self.name_editor.clone()))
+            .child(
+                div()
+                    .h_px()
+                    .w_full()
+                    .bg(cx.theme().colors().element_background),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Rename scratch buffer")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
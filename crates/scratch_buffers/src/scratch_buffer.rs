@@ -0,0 +1,301 @@
+use crate::persistence::{ScratchId, SCRATCH_BUFFERS};
+use anyhow::{anyhow, Result};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyElement, AnyView, AnyWindowHandle, AppContext, EventEmitter, FocusHandle, FocusableView,
+    Model, Pixels, Point, Render, SharedString, Subscription, Task, View, ViewContext,
+    VisualContext, WeakView,
+};
+use language::Buffer;
+use project::Project;
+use std::{
+    any::{Any, TypeId},
+    time::Duration,
+};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+    ItemId, ItemNavHistory, Pane, Workspace, WorkspaceId,
+};
+
+const SCRATCH_BUFFER_KIND: &str = "ScratchBuffer";
+
+/// How long to wait after an edit before writing the scratch buffer's text to the database.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// An editor for text that isn't backed by a file. Its contents are persisted to
+/// the database as they change, so they survive restarts.
+pub struct ScratchBuffer {
+    scratch_id: ScratchId,
+    name: SharedString,
+    buffer: Model<Buffer>,
+    project: Model<Project>,
+    editor: View<Editor>,
+    pending_save: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ScratchBuffer {
+    pub(crate) fn new(
+        scratch_id: ScratchId,
+        name: SharedString,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let editor =
+            cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+        let _subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.subscribe(&buffer, Self::handle_buffer_event),
+            cx.on_release(Self::release),
+        ];
+        Self {
+            scratch_id,
+            name,
+            buffer,
+            project,
+            editor,
+            pending_save: None,
+            _subscriptions,
+        }
+    }
+
+    /// Creates a buffer holding a scratch buffer's text, highlighted as the given language.
+    pub(crate) fn build_buffer(
+        text: String,
+        language_name: Option<&str>,
+        project: &Model<Project>,
+        cx: &mut WindowContext,
+    ) -> Model<Buffer> {
+        let language_registry = project.read(cx).languages().clone();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_language_registry(language_registry.clone());
+            buffer
+        });
+
+        if let Some(language_name) = language_name {
+            let language = language_registry.language_for_name(language_name);
+            let buffer = buffer.downgrade();
+            cx.spawn(|mut cx| async move {
+                let language = language.await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_language(Some(language), cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        buffer
+    }
+
+    pub fn scratch_id(&self) -> ScratchId {
+        self.scratch_id
+    }
+
+    pub fn name(&self) -> &SharedString {
+        &self.name
+    }
+
+    pub(crate) fn set_name(&mut self, name: SharedString, cx: &mut ViewContext<Self>) {
+        self.name = name;
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
+    }
+
+    fn handle_buffer_event(
+        &mut self,
+        _: Model<Buffer>,
+        event: &language::Event,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            language::Event::Edited => self.schedule_save(cx),
+            language::Event::LanguageChanged => {
+                let scratch_id = self.scratch_id;
+                let language = self
+                    .buffer
+                    .read(cx)
+                    .language()
+                    .map(|language| language.name().to_string());
+                cx.background_executor()
+                    .spawn(async move {
+                        SCRATCH_BUFFERS
+                            .save_language(scratch_id, language)
+                            .await
+                            .log_err();
+                    })
+                    .detach();
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes out an edit that is still waiting for its debounced save.
+    fn release(&mut self, _: AnyWindowHandle, cx: &mut AppContext) {
+        if self.pending_save.take().is_some() {
+            let scratch_id = self.scratch_id;
+            let text = self.buffer.read(cx).text();
+            cx.background_executor()
+                .spawn(async move {
+                    SCRATCH_BUFFERS.save_text(scratch_id, text).await.log_err();
+                })
+                .detach();
+        }
+    }
+
+    fn schedule_save(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_save = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            let Some((scratch_id, text)) = this
+                .update(&mut cx, |this, cx| {
+                    (this.scratch_id, this.buffer.read(cx).text())
+                })
+                .ok()
+            else {
+                return;
+            };
+            SCRATCH_BUFFERS.save_text(scratch_id, text).await.log_err();
+            this.update(&mut cx, |this, _| this.pending_save = None)
+                .ok();
+        }));
+    }
+}
+
+impl EventEmitter<EditorEvent> for ScratchBuffer {}
+
+impl FocusableView for ScratchBuffer {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for ScratchBuffer {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().size_full().child(self.editor.clone())
+    }
+}
+
+impl Item for ScratchBuffer {
+    type Event = EditorEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.name.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::File))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(format!("Scratch buffer: {}", self.name).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("scratch buffer")
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| {
+            Self::new(
+                self.scratch_id,
+                self.name.clone(),
+                self.buffer.clone(),
+                self.project.clone(),
+                cx,
+            )
+        }))
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| Item::deactivated(editor, cx))
+    }
+
+    fn set_nav_history(&mut self, history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| Item::set_nav_history(editor, history, cx))
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>> {
+        self.editor.read(cx).pixel_position_of_cursor(cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        let item_id = cx.entity_id().as_u64();
+        let workspace_id = workspace.database_id();
+        let scratch_id = self.scratch_id;
+        cx.background_executor()
+            .spawn(async move {
+                SCRATCH_BUFFERS
+                    .save_item(workspace_id, item_id, scratch_id)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    fn serialized_item_kind() -> Option<&'static str> {
+        Some(SCRATCH_BUFFER_KIND)
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut ViewContext<Pane>,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|_, mut cx| async move {
+            let scratch_id = SCRATCH_BUFFERS
+                .get_item_scratch_id(item_id, workspace_id)?
+                .ok_or_else(|| anyhow!("no scratch buffer found for item"))?;
+            let (name, language, text) = SCRATCH_BUFFERS
+                .get_scratch_buffer(scratch_id)?
+                .ok_or_else(|| anyhow!("scratch buffer {scratch_id} was deleted"))?;
+
+            cx.update(|cx| {
+                let buffer = Self::build_buffer(text, language.as_deref(), &project, cx);
+                cx.new_view(|cx| Self::new(scratch_id, name.into(), buffer, project, cx))
+            })
+        })
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    open_scratch_buffer,
+    persistence::{ScratchId, SCRATCH_BUFFERS},
+    ScratchBuffer,
+};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, SaveIntent, Workspace};
+
+/// Lists the saved scratch buffers, to reopen or delete them.
+pub struct ScratchBufferManager {
+    picker: View<Picker<ScratchBufferManagerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ScratchBufferManager {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(scratch_buffers) = SCRATCH_BUFFERS.list_scratch_buffers().log_err() else {
+            return;
+        };
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate = ScratchBufferManagerDelegate {
+                workspace: workspace_handle,
+                scratch_buffers,
+                matches: Vec::new(),
+                selected_index: 0,
+            };
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+            Self {
+                picker,
+                _subscription,
+            }
+        });
+    }
+}
+
+impl ModalView for ScratchBufferManager {}
+impl EventEmitter<DismissEvent> for ScratchBufferManager {}
+
+impl FocusableView for ScratchBufferManager {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ScratchBufferManager {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct ScratchBufferManagerDelegate {
+    workspace: WeakView<Workspace>,
+    /// The id, name and language of every scratch buffer.
+    scratch_buffers: Vec<(ScratchId, String, Option<String>)>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl ScratchBufferManagerDelegate {
+    fn selected_scratch_id(&self) -> Option<ScratchId> {
+        let hit = self.matches.get(self.selected_index)?;
+        let (scratch_id, _, _) = self.scratch_buffers.get(hit.candidate_id)?;
+        Some(*scratch_id)
+    }
+
+    fn delete_scratch_buffer(&mut self, scratch_id: ScratchId, cx: &mut ViewContext<Picker<Self>>) {
+        self.scratch_buffers.retain(|(id, _, _)| *id != scratch_id);
+        self.matches.clear();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let open_items = workspace
+                    .items_of_type::<ScratchBuffer>(cx)
+                    .filter(|item| item.read(cx).scratch_id() == scratch_id)
+                    .collect::<Vec<_>>();
+                for item in open_items {
+                    if let Some(pane) = workspace.pane_for(&item) {
+                        pane.update(cx, |pane, cx| {
+                            pane.close_item_by_id(item.entity_id(), SaveIntent::Skip, cx)
+                                .detach_and_log_err(cx);
+                        });
+                    }
+                }
+            })
+            .log_err();
+
+        cx.background_executor()
+            .spawn(async move {
+                SCRATCH_BUFFERS
+                    .delete_scratch_buffer(scratch_id)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+impl PickerDelegate for ScratchBufferManagerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search scratch buffers...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.scratch_buffers.is_empty() {
+            "No scratch buffers yet. Create one with \"workspace: new scratch buffer\".".into()
+        } else {
+            "No matching scratch buffers".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .scratch_buffers
+            .iter()
+            .enumerate()
+            .map(|(id, (_, name, _))| StringMatchCandidate::new(id, name.clone()))
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(scratch_id) = self.selected_scratch_id() else {
+            return;
+        };
+
+        if secondary {
+            self.delete_scratch_buffer(scratch_id, cx);
+            cx.spawn(|picker, mut cx| async move {
+                picker.update(&mut cx, |picker, cx| picker.refresh(cx))
+            })
+            .detach_and_log_err(cx);
+        } else {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_scratch_buffer(workspace, scratch_id, cx).detach_and_log_err(cx);
+                })
+                .log_err();
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let (_, _, language) = self.scratch_buffers.get(hit.candidate_id)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                ))
+                .end_slot::<Label>(language.clone().map(|language| {
+                    Label::new(language)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                })),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        self.selected_scratch_id()?;
+
+        Some(
+            h_flex()
+                .mr_3()
+                .pb_2()
+                .gap_1()
+                .child(h_flex().w_full())
+                .child(
+                    Button::new("scratch-buffer-delete-button", "Delete").on_click(cx.listener(
+                        |picker, _, cx| {
+                            picker.delegate.confirm(true, cx);
+                        },
+                    )),
+                )
+                .child(
+                    Button::new("scratch-buffer-open-button", "Open")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.confirm(false, cx);
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -0,0 +1,76 @@
+mod language_picker;
+mod persistence;
+mod rename_modal;
+mod scratch_buffer;
+mod scratch_buffer_manager;
+
+use anyhow::{anyhow, Result};
+use gpui::{actions, AppContext, SharedString, Task, ViewContext, VisualContext};
+use workspace::{NewScratchBuffer, Workspace};
+
+pub use language_picker::ScratchLanguagePicker;
+pub use persistence::ScratchId;
+pub use rename_modal::RenameScratchBufferModal;
+pub use scratch_buffer::ScratchBuffer;
+pub use scratch_buffer_manager::ScratchBufferManager;
+
+use persistence::SCRATCH_BUFFERS;
+
+actions!(scratch_buffers, [OpenScratchBuffers, RenameScratchBuffer]);
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_deserializable_item::<ScratchBuffer>(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &NewScratchBuffer, cx| {
+                ScratchLanguagePicker::toggle(workspace, cx);
+            })
+            .register_action(|workspace, _: &OpenScratchBuffers, cx| {
+                ScratchBufferManager::toggle(workspace, cx);
+            })
+            .register_action(|workspace, _: &RenameScratchBuffer, cx| {
+                RenameScratchBufferModal::toggle(workspace, cx);
+            });
+    })
+    .detach();
+}
+
+/// Activates the given scratch buffer if it's already open in the workspace, and
+/// opens it in the active pane otherwise.
+fn open_scratch_buffer(
+    workspace: &mut Workspace,
+    scratch_id: ScratchId,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let existing = workspace
+        .items_of_type::<ScratchBuffer>(cx)
+        .find(|item| item.read(cx).scratch_id() == scratch_id);
+    if let Some(existing) = existing {
+        workspace.activate_item(&existing, cx);
+        return Task::ready(Ok(()));
+    }
+
+    cx.spawn(|workspace, mut cx| async move {
+        let (name, language, text) = SCRATCH_BUFFERS
+            .get_scratch_buffer(scratch_id)?
+            .ok_or_else(|| anyhow!("scratch buffer {scratch_id} was deleted"))?;
+        workspace.update(&mut cx, |workspace, cx| {
+            add_scratch_buffer(workspace, scratch_id, name.into(), language, text, cx);
+        })
+    })
+}
+
+fn add_scratch_buffer(
+    workspace: &mut Workspace,
+    scratch_id: ScratchId,
+    name: SharedString,
+    language: Option<String>,
+    text: String,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let buffer = ScratchBuffer::build_buffer(text, language.as_deref(), &project, cx);
+    let scratch_buffer =
+        cx.new_view(|cx| ScratchBuffer::new(scratch_id, name, buffer, project, cx));
+    workspace.add_item_to_active_pane(Box::new(scratch_buffer), cx);
+}
@@ -41,6 +41,8 @@ use registrar::{ForDeployed, ForDismissed, SearchActionsRegistrar, WithResults};
 const MIN_INPUT_WIDTH_REMS: f32 = 10.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 const MAX_BUFFER_SEARCH_HISTORY_SIZE: usize = 50;
+/// How many matches are found at first, and how many more each time the user asks for more.
+const MATCH_LIMIT_STEP: usize = 10_000;
 
 const fn true_value() -> bool {
     true
@@ -56,7 +58,7 @@ pub struct Deploy {
 
 impl_actions!(buffer_search, [Deploy]);
 
-actions!(buffer_search, [Dismiss, FocusEditor]);
+actions!(buffer_search, [Dismiss, FocusEditor, ShowMoreMatches]);
 
 impl Deploy {
     pub fn find() -> Self {
@@ -87,6 +89,8 @@ pub struct BufferSearchBar {
     active_search: Option<Arc<SearchQuery>>,
     searchable_items_with_matches: HashMap<Box<dyn WeakSearchableItemHandle>, AnyVec<dyn Send>>,
     pending_search: Option<Task<()>>,
+    match_limit: usize,
+    match_limit_reached: bool,
     search_options: SearchOptions,
     default_options: SearchOptions,
     query_contains_error: bool,
@@ -178,7 +182,8 @@ impl Render for BufferSearchBar {
                     .map(AnyVec::len)
                     .unwrap_or(0);
                 if let Some(match_ix) = self.active_match_index {
-                    Some(format!("{}/{}", match_ix + 1, matches_count))
+                    let more = if self.match_limit_reached { "+" } else { "" };
+                    Some(format!("{}/{}{more}", match_ix + 1, matches_count))
                 } else {
                     text_color = Color::Error; // No matches found
                     None
@@ -286,6 +291,22 @@ impl Render for BufferSearchBar {
                                 Color::Disabled
                             }),
                         ))
+                    })
+                    .when(self.match_limit_reached, |this| {
+                        this.child(
+                            Button::new("buffer-search-show-more-matches", "Show More")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.show_more_matches(&ShowMoreMatches, cx)
+                                }))
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Search for more matches",
+                                        &ShowMoreMatches,
+                                        cx,
+                                    )
+                                }),
+                        )
                     }),
             );
 
@@ -462,6 +483,9 @@ impl BufferSearchBar {
         registrar.register_handler(WithResults(|this, action: &SelectAllMatches, cx| {
             this.select_all_matches(action, cx);
         }));
+        registrar.register_handler(WithResults(|this, action: &ShowMoreMatches, cx| {
+            this.show_more_matches(action, cx);
+        }));
         registrar.register_handler(ForDeployed(|this, _: &editor::actions::Cancel, cx| {
             this.dismiss(&Dismiss, cx);
         }));
@@ -496,6 +520,8 @@ impl BufferSearchBar {
             default_options: SearchOptions::NONE,
             search_options: SearchOptions::NONE,
             pending_search: None,
+            match_limit: MATCH_LIMIT_STEP,
+            match_limit_reached: false,
             query_contains_error: false,
             dismissed: true,
             search_history: SearchHistory::new(
@@ -665,6 +691,7 @@ impl BufferSearchBar {
                 });
             });
             self.search_options = options;
+            self.match_limit = MATCH_LIMIT_STEP;
             self.clear_matches(cx);
             cx.notify();
         }
@@ -690,6 +717,7 @@ impl BufferSearchBar {
     fn toggle_search_option(&mut self, search_option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(search_option);
         self.default_options = self.search_options;
+        self.match_limit = MATCH_LIMIT_STEP;
         let _ = self.update_matches(cx);
         cx.notify();
     }
@@ -721,6 +749,14 @@ impl BufferSearchBar {
         self.select_match(Direction::Prev, 1, cx);
     }
 
+    /// Search again, finding more matches than the previous search stopped at.
+    fn show_more_matches(&mut self, _: &ShowMoreMatches, cx: &mut ViewContext<Self>) {
+        if self.match_limit_reached {
+            self.match_limit += MATCH_LIMIT_STEP;
+            let _ = self.update_matches(cx);
+        }
+    }
+
     fn select_all_matches(&mut self, _: &SelectAllMatches, cx: &mut ViewContext<Self>) {
         if !self.dismissed && self.active_match_index.is_some() {
             if let Some(searchable_item) = self.active_searchable_item.as_ref() {
@@ -778,6 +814,7 @@ impl BufferSearchBar {
             editor::EditorEvent::Focused => self.query_editor_focused = true,
             editor::EditorEvent::Blurred => self.query_editor_focused = false,
             editor::EditorEvent::Edited => {
+                self.match_limit = MATCH_LIMIT_STEP;
                 self.clear_matches(cx);
                 let search = self.update_matches(cx);
 
@@ -866,6 +903,7 @@ impl BufferSearchBar {
         let (done_tx, done_rx) = oneshot::channel();
         let query = self.query(cx);
         self.pending_search.take();
+        self.match_limit_reached = false;
 
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.query_contains_error = false;
@@ -909,6 +947,7 @@ impl BufferSearchBar {
                         }
                     }
                 }
+                .with_max_matches(self.match_limit)
                 .into();
                 self.active_search = Some(query.clone());
                 let query_text = query.as_str().to_string();
@@ -923,6 +962,7 @@ impl BufferSearchBar {
                         if let Some(active_searchable_item) =
                             WeakSearchableItemHandle::upgrade(active_searchable_item.as_ref(), cx)
                         {
+                            this.match_limit_reached = matches.len() >= this.match_limit;
                             this.searchable_items_with_matches
                                 .insert(active_searchable_item.downgrade(), matches);
 
@@ -1142,6 +1182,49 @@ mod tests {
         (editor, search_bar, cx)
     }
 
+    #[gpui::test]
+    async fn test_search_match_limit(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        editor.update(cx, |editor, cx| {
+            editor.set_text("a".repeat(MATCH_LIMIT_STEP + 5), cx)
+        });
+        let match_count = |search_bar: &BufferSearchBar| {
+            search_bar
+                .searchable_items_with_matches
+                .values()
+                .map(AnyVec::len)
+                .sum::<usize>()
+        };
+
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("a", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, _| {
+            assert_eq!(match_count(search_bar), MATCH_LIMIT_STEP);
+            assert!(search_bar.match_limit_reached);
+        });
+
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.show_more_matches(&ShowMoreMatches, cx)
+        });
+        cx.run_until_parked();
+        search_bar.update(cx, |search_bar, _| {
+            assert_eq!(match_count(search_bar), MATCH_LIMIT_STEP + 5);
+            assert!(!search_bar.match_limit_reached);
+        });
+
+        // Changing the query starts over from the initial limit.
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("aa", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, _| {
+            assert_eq!(search_bar.match_limit, MATCH_LIMIT_STEP);
+            assert!(!search_bar.match_limit_reached);
+        });
+    }
+
     #[gpui::test]
     async fn test_search_simple(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
@@ -3,7 +3,10 @@ use futures::channel::oneshot;
 use gpui::{App, Global};
 use language::language_settings::AllLanguageSettings;
 use project::Project;
-use semantic_index::{OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SemanticIndex};
+use semantic_index::{
+    EmbeddingProvider, HuggingFaceEmbeddingProvider, JinaEmbeddingModel, JinaEmbeddingProvider,
+    OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SemanticIndex, HUGGING_FACE_DEFAULT_MODEL,
+};
 use settings::SettingsStore;
 use std::{
     path::{Path, PathBuf},
@@ -40,16 +43,34 @@ fn main() {
 
         // let embedding_provider = semantic_index::FakeEmbeddingProvider;
 
-        let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY not set");
-
-        let embedding_provider = Arc::new(OpenAiEmbeddingProvider::new(
+        // Jina's code model is used when a `JINA_API_KEY` is set, a Hugging Face model
+        // when a `HUGGING_FACE_API_KEY` is set, and OpenAI's otherwise.
+        let jina_api_key = std::env::var("JINA_API_KEY").ok();
+        let hugging_face_api_key = std::env::var("HUGGING_FACE_API_KEY").ok();
+        let hugging_face_model = std::env::var("HUGGING_FACE_MODEL")
+            .unwrap_or_else(|_| HUGGING_FACE_DEFAULT_MODEL.to_string());
+        let open_ai_provider = OpenAiEmbeddingProvider::authenticate(
             http.clone(),
             OpenAiEmbeddingModel::TextEmbedding3Small,
             open_ai::OPEN_AI_API_URL.to_string(),
-            api_key,
-        ));
+            cx,
+        );
 
         cx.spawn(|mut cx| async move {
+            let embedding_provider: Arc<dyn EmbeddingProvider> =
+                match (jina_api_key, hugging_face_api_key) {
+                    (Some(api_key), _) => Arc::new(JinaEmbeddingProvider::new(
+                        http.clone(),
+                        JinaEmbeddingModel::JinaEmbeddingsV2BaseCode,
+                        api_key,
+                    )),
+                    (None, Some(api_key)) => Arc::new(HuggingFaceEmbeddingProvider::new(
+                        http.clone(),
+                        hugging_face_model,
+                        api_key,
+                    )),
+                    (None, None) => Arc::new(open_ai_provider.await.expect("no OpenAI API key")),
+                };
             let semantic_index = SemanticIndex::new(
                 PathBuf::from("/tmp/semantic-index-db.mdb"),
                 embedding_provider,
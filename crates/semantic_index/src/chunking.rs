@@ -98,16 +98,81 @@ fn chunk_lines(text: &str) -> Vec<Chunk> {
 
     chunk_ranges
         .into_iter()
-        .map(|range| {
-            let mut hasher = Sha256::new();
-            hasher.update(&text[range.clone()]);
-            let mut digest = [0u8; 32];
-            digest.copy_from_slice(hasher.finalize().as_slice());
-            Chunk { range, digest }
-        })
+        .map(|range| chunk_for_range(text, range))
         .collect()
 }
 
+fn chunk_for_range(text: &str, range: Range<usize>) -> Chunk {
+    let mut hasher = Sha256::new();
+    hasher.update(&text[range.clone()]);
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(hasher.finalize().as_slice());
+    Chunk { range, digest }
+}
+
+/// Splits the chunks that have more than `max_tokens` tokens into smaller ones,
+/// so that none of them is too long for the embedding model. Oversized chunks are
+/// halved, at a line break near their middle when there is one.
+pub fn split_oversized_chunks(
+    text: &str,
+    chunks: Vec<Chunk>,
+    max_tokens: usize,
+    count_tokens: impl Fn(&str) -> usize,
+) -> Vec<Chunk> {
+    let mut result = Vec::with_capacity(chunks.len());
+    let mut ranges_to_split = Vec::new();
+    let fits = |text: &str| fits_in_max_tokens(text, max_tokens, &count_tokens);
+    for chunk in chunks {
+        if fits(&text[chunk.range.clone()]) {
+            result.push(chunk);
+            continue;
+        }
+
+        ranges_to_split.push(chunk.range);
+        while let Some(range) = ranges_to_split.pop() {
+            if fits(&text[range.clone()]) {
+                result.push(chunk_for_range(text, range));
+            } else if let Some(split_ix) = split_point(text, range.clone()) {
+                ranges_to_split.push(split_ix..range.end);
+                ranges_to_split.push(range.start..split_ix);
+            } else {
+                // A single character can't be split any further.
+                result.push(chunk_for_range(text, range));
+            }
+        }
+    }
+    result
+}
+
+/// Returns whether the text has at most `max_tokens` tokens. Texts with no more bytes
+/// than that aren't tokenized, since no token is shorter than a byte.
+pub fn fits_in_max_tokens(
+    text: &str,
+    max_tokens: usize,
+    count_tokens: impl Fn(&str) -> usize,
+) -> bool {
+    text.len() <= max_tokens || count_tokens(text) <= max_tokens
+}
+
+fn split_point(text: &str, range: Range<usize>) -> Option<usize> {
+    let mut middle = range.start + range.len() / 2;
+    let quarter = range.start + range.len() / 4;
+    if let Some(newline_ix) = text.as_bytes()[quarter..middle]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+    {
+        return Some(quarter + newline_ix + 1);
+    }
+
+    while !text.is_char_boundary(middle) {
+        middle += 1;
+    }
+    if middle == range.start {
+        middle += text[middle..].chars().next()?.len_utf8();
+    }
+    (middle < range.end).then_some(middle)
+}
+
 fn split_text(
     text: &str,
     mut range: Range<usize>,
@@ -406,4 +471,52 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_split_oversized_chunks() {
+        let text = "one two\nthree four\nfive six\nseven eight\n";
+        let count_words = |text: &str| text.split_whitespace().count();
+
+        let chunks = split_oversized_chunks(text, chunk_lines(text), 3, count_words);
+        let chunk_texts = chunks
+            .iter()
+            .map(|chunk| &text[chunk.range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chunk_texts,
+            ["one two\n", "three four\n", "five six\n", "seven eight\n"]
+        );
+        for chunk in &chunks {
+            assert_eq!(
+                chunk.digest,
+                <[u8; 32]>::from(Sha256::digest(&text[chunk.range.clone()]))
+            );
+        }
+
+        // Chunks without line breaks are split at character boundaries.
+        let text = "αβγδεζη";
+        let chunks =
+            split_oversized_chunks(text, chunk_lines(text), 2, |text| text.chars().count());
+        let chunk_texts = chunks
+            .iter()
+            .map(|chunk| &text[chunk.range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(chunk_texts, ["αβ", "γδ", "εζ", "η"]);
+
+        // Chunks within the limit are left untouched.
+        let text = "short";
+        let chunks = split_oversized_chunks(text, chunk_lines(text), 3, count_words);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].range, 0..text.len());
+    }
+
+    #[test]
+    fn test_fits_in_max_tokens() {
+        let count_words = |text: &str| text.split_whitespace().count();
+        assert!(fits_in_max_tokens("one two", 2, count_words));
+        assert!(!fits_in_max_tokens("one two three", 2, count_words));
+
+        // Texts with no more bytes than tokens allowed aren't tokenized.
+        assert!(fits_in_max_tokens("one", 3, |_| unreachable!()));
+    }
 }
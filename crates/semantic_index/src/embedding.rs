@@ -1,43 +1,88 @@
+mod azure_open_ai;
+mod bedrock;
 mod cloud;
+mod cohere;
+mod google_ai;
+mod hugging_face;
+mod jina;
+mod llama_cpp;
+mod lm_studio;
+mod local;
+mod mistral;
 mod ollama;
 mod open_ai;
+mod voyage;
 
+pub use azure_open_ai::*;
+pub use bedrock::*;
 pub use cloud::*;
+pub use cohere::*;
+pub use google_ai::*;
+pub use hugging_face::*;
+pub use jina::*;
+pub use llama_cpp::*;
+pub use lm_studio::*;
+pub use local::*;
+pub use mistral::*;
 pub use ollama::*;
 pub use open_ai::*;
 use sha2::{Digest, Sha256};
+pub use voyage::*;
 
+use crate::chunking::fits_in_max_tokens;
 use anyhow::Result;
-use futures::{future::BoxFuture, FutureExt};
+use futures::{future::BoxFuture, AsyncReadExt as _, FutureExt};
 use serde::{Deserialize, Serialize};
-use std::{fmt, future};
+#[cfg(test)]
+use std::sync::Arc;
+use std::{fmt, future, sync::OnceLock};
+use tiktoken_rs::CoreBPE;
+use util::http::{AsyncBody, HttpClient, Request};
+#[cfg(test)]
+use util::http::{FakeHttpClient, Response};
 
+/// A normalized embedding vector of any number of dimensions.
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Embedding(Vec<f32>);
 
 impl Embedding {
+    /// Normalizes the vector to unit length. Vectors that can't be normalized,
+    /// because they're all zeros or contain values that aren't finite, are kept
+    /// as zeros, so that they aren't similar to anything.
     pub fn new(mut embedding: Vec<f32>) -> Self {
-        let len = embedding.len();
-        let mut norm = 0f32;
-
-        for i in 0..len {
-            norm += embedding[i] * embedding[i];
-        }
+        let norm = embedding
+            .iter()
+            .map(|dimension| dimension * dimension)
+            .sum::<f32>()
+            .sqrt();
 
-        norm = norm.sqrt();
-        for dimension in &mut embedding {
-            *dimension /= norm;
+        if norm.is_normal() {
+            for dimension in &mut embedding {
+                *dimension /= norm;
+            }
+        } else {
+            log::warn!(
+                "embedding of {} dimensions has no direction",
+                embedding.len()
+            );
+            embedding.fill(0.);
         }
 
         Self(embedding)
     }
 
-    fn len(&self) -> usize {
+    /// The number of dimensions of the embedding, which depends on the model that
+    /// produced it.
+    pub fn dimensions(&self) -> usize {
         self.0.len()
     }
 
+    /// Returns the cosine similarity with another embedding, which is 0 when they
+    /// come from models with different dimensions.
     pub fn similarity(self, other: &Embedding) -> f32 {
-        debug_assert_eq!(self.0.len(), other.0.len());
+        if self.dimensions() != other.dimensions() {
+            return 0.;
+        }
         self.0
             .iter()
             .copied()
@@ -52,7 +97,7 @@ impl fmt::Display for Embedding {
         let digits_to_display = 3;
 
         // Start the Embedding display format
-        write!(f, "Embedding(sized: {}; values: [", self.len())?;
+        write!(f, "Embedding(sized: {}; values: [", self.dimensions())?;
 
         for (index, value) in self.0.iter().enumerate().take(digits_to_display) {
             // Lead with comma if not the first element
@@ -61,7 +106,7 @@ impl fmt::Display for Embedding {
             }
             write!(f, "{:.3}", value)?;
         }
-        if self.len() > digits_to_display {
+        if self.dimensions() > digits_to_display {
             write!(f, "...")?;
         }
         write!(f, "])")
@@ -70,8 +115,105 @@ impl fmt::Display for Embedding {
 
 /// Trait for embedding providers. Texts in, vectors out.
 pub trait EmbeddingProvider: Sync + Send {
+    /// Embeds the given texts, returning one embedding for each of them in order.
+    /// At most [`EmbeddingProvider::batch_size`] texts are passed at once.
     fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>>;
-    fn batch_size(&self) -> usize;
+
+    /// Embeds search queries, which some models embed differently from the texts
+    /// that are searched. By default, queries are embedded like any other text.
+    fn embed_query<'a>(
+        &'a self,
+        queries: &'a [TextToEmbed<'a>],
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed(queries)
+    }
+
+    /// How many texts can be embedded with a single request. Providers that can't
+    /// batch requests are given one text at a time.
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    /// The most tokens that the model accepts in a single text, or `None` if the
+    /// provider handles texts of any length, e.g. by truncating them itself. Longer
+    /// texts are split or truncated before they're passed to [`EmbeddingProvider::embed`].
+    fn max_tokens(&self) -> Option<usize> {
+        None
+    }
+
+    /// Counts the tokens in the text the way the model's tokenizer does. By default,
+    /// this is the number of bytes, which no byte-level tokenizer produces more tokens than.
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len()
+    }
+}
+
+/// Counts the tokens of the text in the `cl100k_base` encoding that OpenAI's embedding
+/// models use. Unlike tiktoken's singleton, the encoding isn't behind a lock, so chunks
+/// can be counted on several threads at once.
+pub(crate) fn count_cl100k_base_tokens(text: &str) -> usize {
+    static CL100K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+    CL100K_BASE
+        .get_or_init(|| tiktoken_rs::cl100k_base().expect("cl100k_base is bundled"))
+        .encode_with_special_tokens(text)
+        .len()
+}
+
+/// Returns the longest prefix of the text that fits in the provider's token limit.
+pub fn truncate_to_max_tokens<'a>(provider: &dyn EmbeddingProvider, text: &'a str) -> &'a str {
+    let Some(max_tokens) = provider.max_tokens() else {
+        return text;
+    };
+    if fits_in_max_tokens(text, max_tokens, |text| provider.count_tokens(text)) {
+        return text;
+    }
+
+    let prefix_ends = text
+        .char_indices()
+        .map(|(ix, _)| ix)
+        .skip(1)
+        .chain([text.len()])
+        .collect::<Vec<_>>();
+    let fitting_prefixes =
+        prefix_ends.partition_point(|end| provider.count_tokens(&text[..*end]) <= max_tokens);
+    match fitting_prefixes.checked_sub(1) {
+        Some(ix) => &text[..prefix_ends[ix]],
+        None => "",
+    }
+}
+
+/// Sends a request to the API of an embedding provider, returning the body of the
+/// response, or an error with the body when the request didn't succeed.
+pub(crate) async fn send_embedding_request(
+    client: &dyn HttpClient,
+    provider_name: &str,
+    request: Request<AsyncBody>,
+) -> Result<String> {
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "{provider_name} failed to embed texts with status {}: {body}",
+        response.status()
+    );
+    Ok(body)
+}
+
+/// Normalizes the vectors that a provider returned for the texts, checking that
+/// it returned one for each of them.
+pub(crate) fn embeddings_for_texts(
+    provider_name: &str,
+    vectors: Vec<Vec<f32>>,
+    texts: &[TextToEmbed],
+) -> Result<Vec<Embedding>> {
+    anyhow::ensure!(
+        vectors.len() == texts.len(),
+        "{provider_name} returned {} embeddings for {} texts",
+        vectors.len(),
+        texts.len()
+    );
+    Ok(vectors.into_iter().map(Embedding::new).collect())
 }
 
 #[derive(Debug)]
@@ -112,6 +254,29 @@ impl EmbeddingProvider for FakeEmbeddingProvider {
     }
 }
 
+/// Returns an HTTP client for testing providers, which responds to each request with
+/// the JSON returned by `respond` for the request and its JSON body.
+#[cfg(test)]
+pub(crate) fn fake_embedding_api(
+    respond: impl 'static
+        + Send
+        + Sync
+        + Fn(&Request<AsyncBody>, serde_json::Value) -> serde_json::Value,
+) -> Arc<dyn HttpClient> {
+    use futures::AsyncReadExt as _;
+
+    let respond = Arc::new(respond);
+    FakeHttpClient::create(move |mut request| {
+        let respond = respond.clone();
+        async move {
+            let mut body = String::new();
+            request.body_mut().read_to_string(&mut body).await?;
+            let body = serde_json::from_str(&body).unwrap();
+            Ok(Response::new(respond(&request, body).to_string().into()))
+        }
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -121,5 +286,60 @@ mod test {
         let normalized = Embedding::new(vec![1.0, 1.0, 1.0]);
         let value: f32 = 1.0 / 3.0_f32.sqrt();
         assert_eq!(normalized, Embedding(vec![value; 3]));
+
+        assert_eq!(Embedding::new(vec![0.0; 3]), Embedding(vec![0.0; 3]));
+        assert_eq!(
+            Embedding::new(vec![1.0, f32::NAN, 1.0]),
+            Embedding(vec![0.0; 3])
+        );
+        assert_eq!(normalized.similarity(&Embedding::new(vec![1.0, 1.0])), 0.);
+    }
+
+    #[test]
+    fn test_count_cl100k_base_tokens() {
+        assert_eq!(count_cl100k_base_tokens(""), 0);
+        assert_eq!(count_cl100k_base_tokens("hello world"), 2);
+
+        // The encoding can be used on several threads at once.
+        let counts = std::thread::scope(|scope| {
+            let threads = (0..4)
+                .map(|_| scope.spawn(|| count_cl100k_base_tokens("hello world")))
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(counts, [2; 4]);
+    }
+
+    #[test]
+    fn test_truncate_to_max_tokens() {
+        struct CharTokenProvider;
+
+        impl EmbeddingProvider for CharTokenProvider {
+            fn embed<'a>(
+                &'a self,
+                _texts: &'a [TextToEmbed<'a>],
+            ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+                unimplemented!()
+            }
+
+            fn max_tokens(&self) -> Option<usize> {
+                Some(3)
+            }
+
+            fn count_tokens(&self, text: &str) -> usize {
+                text.chars().count()
+            }
+        }
+
+        assert_eq!(truncate_to_max_tokens(&CharTokenProvider, "ab"), "ab");
+        assert_eq!(truncate_to_max_tokens(&CharTokenProvider, "abc"), "abc");
+        assert_eq!(truncate_to_max_tokens(&CharTokenProvider, "αβγδε"), "αβγ");
+        assert_eq!(
+            truncate_to_max_tokens(&FakeEmbeddingProvider, "αβγδε"),
+            "αβγδε"
+        );
     }
 }
@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::Serialize;
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    count_cl100k_base_tokens, parse_open_ai_embeddings, send_embedding_request, Embedding,
+    EmbeddingProvider, TextToEmbed,
+};
+
+/// The version of the Azure OpenAI API that's used when none is given.
+pub const AZURE_OPEN_AI_API_VERSION: &str = "2024-02-01";
+
+/// The API key of an Azure OpenAI resource, stored in the keychain under the
+/// resource's endpoint.
+pub fn azure_open_ai_credential(endpoint: &str) -> Credential {
+    Credential::new(
+        "Azure OpenAI API Key",
+        endpoint.trim_end_matches('/').to_string(),
+    )
+    .with_env_var("AZURE_OPENAI_API_KEY")
+}
+
+/// Embeds texts with a model that's deployed to an Azure OpenAI resource, which is
+/// addressed by the name of its deployment rather than that of the model.
+pub struct AzureOpenaiEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    endpoint: String,
+    deployment: String,
+    api_version: String,
+    api_key: String,
+}
+
+#[derive(Serialize)]
+struct AzureOpenAiEmbeddingRequest<'a> {
+    input: Vec<&'a str>,
+}
+
+impl AzureOpenaiEmbeddingProvider {
+    /// Creates a provider for the resource at `endpoint`, like
+    /// `https://my-resource.openai.azure.com`, that embeds with the model deployed
+    /// as `deployment`, through version [`AZURE_OPEN_AI_API_VERSION`] of the API.
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        endpoint: impl Into<String>,
+        deployment: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            deployment: deployment.into(),
+            api_version: AZURE_OPEN_AI_API_VERSION.to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Uses the given version of the API, like `2024-02-01`, instead of the default one.
+    pub fn with_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Creates a provider that uses the API key from the `AZURE_OPENAI_API_KEY`
+    /// environment variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        endpoint: String,
+        deployment: String,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = azure_open_ai_credential(&endpoint).read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Azure OpenAI API key is set"))?;
+            Ok(Self::new(client, endpoint, deployment, api_key))
+        })
+    }
+}
+
+impl EmbeddingProvider for AzureOpenaiEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = AzureOpenAiEmbeddingRequest {
+            input: texts.iter().map(|to_embed| to_embed.text).collect(),
+        };
+        let request = serde_json::to_string(&request).unwrap();
+        let url = format!(
+            "{}/openai/deployments/{}/embeddings?api-version={}",
+            self.endpoint, self.deployment, self.api_version
+        );
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(url)
+                .header("Content-Type", "application/json")
+                .header("api-key", self.api_key.as_str())
+                .body(request.into())?;
+            let body =
+                send_embedding_request(self.client.as_ref(), "Azure OpenAI", request).await?;
+            parse_open_ai_embeddings("Azure OpenAI", &body, texts)
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // From https://learn.microsoft.com/en-us/azure/ai-services/openai/reference#embeddings
+        2048
+    }
+
+    fn max_tokens(&self) -> Option<usize> {
+        // Azure deploys OpenAI's embedding models, which accept up to 8191 tokens.
+        Some(8191)
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        count_cl100k_base_tokens(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_azure_open_ai_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://my-resource.openai.azure.com/openai/deployments/my-deployment/embeddings?api-version=2024-02-01"
+            );
+            assert_eq!(request.headers()["api-key"], "the-api-key");
+            assert_eq!(body, json!({ "input": ["first", "second"] }));
+
+            // The embeddings aren't listed in the order of the texts.
+            json!({
+                "data": [
+                    { "embedding": [0.0, 2.0], "index": 1 },
+                    { "embedding": [3.0, 0.0], "index": 0 },
+                ]
+            })
+        });
+        let provider = AzureOpenaiEmbeddingProvider::new(
+            client,
+            "https://my-resource.openai.azure.com/",
+            "my-deployment",
+            "the-api-key",
+        );
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        let embeddings = provider.embed(&texts).await.unwrap();
+        assert_eq!(
+            embeddings,
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
+    }
+}
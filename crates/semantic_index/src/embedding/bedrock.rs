@@ -0,0 +1,246 @@
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, AsyncReadExt, FutureExt};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+
+/// The region that Bedrock is used in when none is configured.
+pub const BEDROCK_DEFAULT_REGION: &str = "us-east-1";
+
+const BEDROCK_SERVICE: &str = "bedrock";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BedrockEmbeddingModel {
+    /// Titan Embeddings G1 - Text, which has 1536 dimensions.
+    TitanEmbedTextV1,
+}
+
+impl BedrockEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TitanEmbedTextV1 => "amazon.titan-embed-text-v1",
+        }
+    }
+}
+
+/// The AWS credentials that requests to Bedrock are signed with.
+#[derive(Clone)]
+pub struct BedrockCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// The token of temporary credentials, like those of an assumed role or SSO.
+    pub session_token: Option<String>,
+}
+
+impl BedrockCredentials {
+    /// Reads the credentials from the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`
+    /// and `AWS_SESSION_TOKEN` environment variables, like the AWS CLI does.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID")
+                .context("AWS_ACCESS_KEY_ID is not set")?,
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY")
+                .context("AWS_SECRET_ACCESS_KEY is not set")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// Embeds texts with an Amazon Titan model through AWS Bedrock, signing each
+/// request with [Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_aws-signing.html).
+pub struct BedrockEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: BedrockEmbeddingModel,
+    region: String,
+    credentials: BedrockCredentials,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TitanEmbeddingRequest<'a> {
+    input_text: &'a str,
+}
+
+#[derive(Deserialize)]
+struct TitanEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+impl BedrockEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: BedrockEmbeddingModel,
+        region: impl Into<String>,
+        credentials: BedrockCredentials,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            region: region.into(),
+            credentials,
+        }
+    }
+
+    async fn embed_text(&self, text: &str) -> Result<Embedding> {
+        let body = serde_json::to_string(&TitanEmbeddingRequest { input_text: text })?;
+        let host = format!("bedrock-runtime.{}.amazonaws.com", self.region);
+        let path = format!("/model/{}/invoke", self.model.id());
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut headers = vec![
+            ("accept", "application/json"),
+            ("content-type", "application/json"),
+            ("host", host.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", session_token.as_str()));
+        }
+        let authorization = CanonicalRequest {
+            method: "POST",
+            path: &path,
+            headers: &headers,
+            body: body.as_bytes(),
+        }
+        .authorization(&self.credentials, &self.region, BEDROCK_SERVICE, now);
+
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("https://{host}{path}"));
+        for (name, value) in &headers {
+            if *name != "host" {
+                request = request.header(*name, *value);
+            }
+        }
+        let request = request
+            .header("Authorization", authorization)
+            .body(body.into())?;
+        let mut response = self.client.send(request).await?;
+
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+        anyhow::ensure!(
+            response.status().is_success(),
+            "Bedrock failed to embed texts with status {}: {body}",
+            response.status()
+        );
+
+        let response: TitanEmbeddingResponse =
+            serde_json::from_str(&body).context("failed to parse Bedrock embedding response")?;
+        Ok(Embedding::new(response.embedding))
+    }
+}
+
+impl EmbeddingProvider for BedrockEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        // Titan embeds a single text per request, so a batch is sent concurrently.
+        futures::future::try_join_all(texts.iter().map(|to_embed| self.embed_text(to_embed.text)))
+            .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        16
+    }
+}
+
+/// The parts of a request that its Signature Version 4 covers. The headers must
+/// have lowercase names and be sorted by them, and include `host` and `x-amz-date`.
+struct CanonicalRequest<'a> {
+    method: &'a str,
+    path: &'a str,
+    headers: &'a [(&'a str, &'a str)],
+    body: &'a [u8],
+}
+
+impl CanonicalRequest<'_> {
+    /// Returns the `Authorization` header that signs the request at the given time.
+    fn authorization(
+        &self,
+        credentials: &BedrockCredentials,
+        region: &str,
+        service: &str,
+        time: DateTime<Utc>,
+    ) -> String {
+        let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = time.format("%Y%m%d").to_string();
+        let scope = format!("{date}/{region}/{service}/aws4_request");
+
+        let signed_headers = self
+            .headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let mut canonical_request = format!("{}\n{}\n\n", self.method, self.path);
+        for (name, value) in self.headers {
+            canonical_request.push_str(&format!("{name}:{}\n", value.trim()));
+        }
+        canonical_request.push_str(&format!(
+            "\n{signed_headers}\n{}",
+            hex::encode(Sha256::digest(self.body))
+        ));
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [region, service, "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_signature() {
+        // The `get-vanilla` case of AWS's Signature Version 4 test suite.
+        let credentials = BedrockCredentials {
+            access_key_id: "AKIDEXAMPLE".into(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+            session_token: None,
+        };
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        let authorization = CanonicalRequest {
+            method: "GET",
+            path: "/",
+            headers: &[
+                ("host", "example.amazonaws.com"),
+                ("x-amz-date", "20150830T123600Z"),
+            ],
+            body: b"",
+        }
+        .authorization(&credentials, "us-east-1", "service", time);
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+}
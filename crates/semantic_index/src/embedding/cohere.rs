@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Context as _, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    embeddings_for_texts, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+pub const COHERE_API_URL: &str = "https://api.cohere.com";
+
+/// The Cohere API key, stored in the keychain under the API's URL.
+pub fn cohere_credential() -> Credential {
+    Credential::new("Cohere API Key", COHERE_API_URL).with_env_var("COHERE_API_KEY")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CohereEmbeddingModel {
+    EmbedEnglishV3,
+    EmbedMultilingualV3,
+}
+
+impl CohereEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::EmbedEnglishV3 => "embed-english-v3.0",
+            Self::EmbedMultilingualV3 => "embed-multilingual-v3.0",
+        }
+    }
+}
+
+pub struct CohereEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: CohereEmbeddingModel,
+    api_key: String,
+}
+
+/// What the embedded texts are used for. Cohere's v3 models embed the documents
+/// being searched differently from the queries they're searched with.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CohereInputType {
+    SearchDocument,
+    SearchQuery,
+}
+
+#[derive(Serialize)]
+struct CohereEmbeddingRequest<'a> {
+    model: &'a str,
+    texts: Vec<&'a str>,
+    input_type: CohereInputType,
+}
+
+#[derive(Deserialize)]
+struct CohereEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl CohereEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: CohereEmbeddingModel,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a provider that uses the API key from the `COHERE_API_KEY` environment
+    /// variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: CohereEmbeddingModel,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = cohere_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Cohere API key is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+
+    fn embed_as<'a>(
+        &'a self,
+        texts: &'a [TextToEmbed<'a>],
+        input_type: CohereInputType,
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = CohereEmbeddingRequest {
+            model: self.model.id(),
+            texts: texts.iter().map(|to_embed| to_embed.text).collect(),
+            input_type,
+        };
+        let request = serde_json::to_string(&request).unwrap();
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!("{COHERE_API_URL}/v1/embed"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "Cohere", request).await?;
+            let response: CohereEmbeddingResponse =
+                serde_json::from_str(&body).context("failed to parse Cohere embedding response")?;
+            embeddings_for_texts("Cohere", response.embeddings, texts)
+        }
+        .boxed()
+    }
+}
+
+impl EmbeddingProvider for CohereEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(texts, CohereInputType::SearchDocument)
+    }
+
+    fn embed_query<'a>(
+        &'a self,
+        queries: &'a [TextToEmbed<'a>],
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(queries, CohereInputType::SearchQuery)
+    }
+
+    fn batch_size(&self) -> usize {
+        // From https://docs.cohere.com/reference/embed
+        96
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_cohere_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(request.uri().to_string(), "https://api.cohere.com/v1/embed");
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["model"], "embed-english-v3.0");
+
+            // Documents and queries are embedded differently.
+            let embedding = match body["input_type"].as_str().unwrap() {
+                "search_document" => [1.0, 0.0],
+                "search_query" => [0.0, 1.0],
+                input_type => panic!("unexpected input type {input_type}"),
+            };
+            let texts = body["texts"].as_array().unwrap();
+            json!({ "embeddings": vec![embedding; texts.len()] })
+        });
+        let provider = CohereEmbeddingProvider::new(
+            client,
+            CohereEmbeddingModel::EmbedEnglishV3,
+            "the-api-key",
+        );
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![1.0, 0.0])
+            ]
+        );
+        let queries = [TextToEmbed::new("query")];
+        assert_eq!(
+            provider.embed_query(&queries).await.unwrap(),
+            [Embedding::new(vec![0.0, 1.0])]
+        );
+    }
+}
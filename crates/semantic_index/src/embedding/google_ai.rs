@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Context as _, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    embeddings_for_texts, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+pub const GOOGLE_AI_API_URL: &str = "https://generativelanguage.googleapis.com";
+
+/// The Google AI API key, stored in the keychain under the API's URL.
+pub fn google_ai_credential() -> Credential {
+    Credential::new("Google AI API Key", GOOGLE_AI_API_URL).with_env_var("GOOGLE_AI_API_KEY")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoogleAiEmbeddingModel {
+    TextEmbedding004,
+}
+
+impl GoogleAiEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TextEmbedding004 => "text-embedding-004",
+        }
+    }
+}
+
+/// A provider for the embedding models of the Generative Language API, which
+/// authenticates with an API key from Google AI Studio.
+pub struct GoogleAiEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: GoogleAiEmbeddingModel,
+    api_key: String,
+}
+
+/// What the embedded texts are used for, which the model optimizes the
+/// embeddings for.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum GoogleAiTaskType {
+    RetrievalDocument,
+    RetrievalQuery,
+}
+
+#[derive(Serialize)]
+struct GoogleAiBatchEmbedRequest<'a> {
+    requests: Vec<GoogleAiEmbedRequest<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleAiEmbedRequest<'a> {
+    model: String,
+    content: GoogleAiContent<'a>,
+    task_type: GoogleAiTaskType,
+}
+
+#[derive(Serialize)]
+struct GoogleAiContent<'a> {
+    parts: [GoogleAiPart<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct GoogleAiPart<'a> {
+    text: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GoogleAiBatchEmbedResponse {
+    embeddings: Vec<GoogleAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct GoogleAiEmbedding {
+    values: Vec<f32>,
+}
+
+impl GoogleAiEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: GoogleAiEmbeddingModel,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a provider that uses the API key from the `GOOGLE_AI_API_KEY`
+    /// environment variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: GoogleAiEmbeddingModel,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = google_ai_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Google AI API key is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+
+    fn embed_as<'a>(
+        &'a self,
+        texts: &'a [TextToEmbed<'a>],
+        task_type: GoogleAiTaskType,
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let model = format!("models/{}", self.model.id());
+        let request = GoogleAiBatchEmbedRequest {
+            requests: texts
+                .iter()
+                .map(|to_embed| GoogleAiEmbedRequest {
+                    model: model.clone(),
+                    content: GoogleAiContent {
+                        parts: [GoogleAiPart {
+                            text: to_embed.text,
+                        }],
+                    },
+                    task_type,
+                })
+                .collect(),
+        };
+        let request = serde_json::to_string(&request).unwrap();
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!(
+                    "{GOOGLE_AI_API_URL}/v1beta/{model}:batchEmbedContents"
+                ))
+                .header("Content-Type", "application/json")
+                .header("x-goog-api-key", &self.api_key)
+                .body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "Google AI", request).await?;
+            let response: GoogleAiBatchEmbedResponse = serde_json::from_str(&body)
+                .context("failed to parse Google AI embedding response")?;
+            embeddings_for_texts(
+                "Google AI",
+                response
+                    .embeddings
+                    .into_iter()
+                    .map(|embedding| embedding.values)
+                    .collect(),
+                texts,
+            )
+        }
+        .boxed()
+    }
+}
+
+impl EmbeddingProvider for GoogleAiEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(texts, GoogleAiTaskType::RetrievalDocument)
+    }
+
+    fn embed_query<'a>(
+        &'a self,
+        queries: &'a [TextToEmbed<'a>],
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(queries, GoogleAiTaskType::RetrievalQuery)
+    }
+
+    fn batch_size(&self) -> usize {
+        // From https://ai.google.dev/api/embeddings#method:-models.batchembedcontents
+        100
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_google_ai_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://generativelanguage.googleapis.com/v1beta/models/text-embedding-004:batchEmbedContents"
+            );
+            assert_eq!(request.headers()["x-goog-api-key"], "the-api-key");
+
+            let embeddings = body["requests"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    assert_eq!(request["model"], "models/text-embedding-004");
+                    // Documents and queries are embedded for different tasks.
+                    let values = match request["taskType"].as_str().unwrap() {
+                        "RETRIEVAL_DOCUMENT" => [1.0, 0.0],
+                        "RETRIEVAL_QUERY" => [0.0, 1.0],
+                        task_type => panic!("unexpected task type {task_type}"),
+                    };
+                    json!({ "values": values })
+                })
+                .collect::<Vec<_>>();
+            json!({ "embeddings": embeddings })
+        });
+        let provider = GoogleAiEmbeddingProvider::new(
+            client,
+            GoogleAiEmbeddingModel::TextEmbedding004,
+            "the-api-key",
+        );
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![1.0, 0.0])
+            ]
+        );
+        let queries = [TextToEmbed::new("query")];
+        assert_eq!(
+            provider.embed_query(&queries).await.unwrap(),
+            [Embedding::new(vec![0.0, 1.0])]
+        );
+    }
+}
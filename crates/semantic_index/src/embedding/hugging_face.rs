@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Context as _, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    embeddings_for_texts, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+pub const HUGGING_FACE_API_URL: &str = "https://api-inference.huggingface.co";
+
+/// The Hugging Face access token, stored in the keychain under the API's URL.
+pub fn hugging_face_credential() -> Credential {
+    Credential::new("Hugging Face Access Token", HUGGING_FACE_API_URL)
+        .with_env_var("HUGGING_FACE_API_KEY")
+}
+
+/// A small, general-purpose sentence-transformers model, for when no other model
+/// is picked.
+pub const HUGGING_FACE_DEFAULT_MODEL: &str = "sentence-transformers/all-MiniLM-L6-v2";
+
+/// Embeds texts with any model hosted on Hugging Face that supports the
+/// feature-extraction pipeline of its Inference API.
+pub struct HuggingFaceEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: String,
+    api_key: String,
+}
+
+#[derive(Serialize)]
+struct HuggingFaceEmbeddingRequest<'a> {
+    inputs: Vec<&'a str>,
+    options: HuggingFaceRequestOptions,
+}
+
+#[derive(Serialize)]
+struct HuggingFaceRequestOptions {
+    /// Waits for the model to be loaded instead of failing while it's loading,
+    /// which happens on the first requests to models that aren't used much.
+    wait_for_model: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HuggingFaceEmbeddingResponse {
+    /// One embedding per text, from models like sentence-transformers that pool
+    /// the embeddings of the tokens themselves.
+    Pooled(Vec<Vec<f32>>),
+    /// One embedding per token of each text, from models that don't.
+    Tokens(Vec<Vec<Vec<f32>>>),
+}
+
+impl HuggingFaceEmbeddingProvider {
+    /// Creates a provider for the model with the given id on Hugging Face, like
+    /// [`HUGGING_FACE_DEFAULT_MODEL`].
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model: model.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a provider that uses the access token from the `HUGGING_FACE_API_KEY`
+    /// environment variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: String,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = hugging_face_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Hugging Face access token is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+}
+
+impl EmbeddingProvider for HuggingFaceEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = HuggingFaceEmbeddingRequest {
+            inputs: texts.iter().map(|to_embed| to_embed.text).collect(),
+            options: HuggingFaceRequestOptions {
+                wait_for_model: true,
+            },
+        };
+        let request = serde_json::to_string(&request).unwrap();
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!(
+                    "{HUGGING_FACE_API_URL}/pipeline/feature-extraction/{}",
+                    self.model
+                ))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .body(request.into())?;
+            let body =
+                send_embedding_request(self.client.as_ref(), "Hugging Face", request).await?;
+            let response: HuggingFaceEmbeddingResponse = serde_json::from_str(&body)
+                .context("failed to parse Hugging Face embedding response")?;
+            let embeddings = match response {
+                HuggingFaceEmbeddingResponse::Pooled(embeddings) => embeddings,
+                HuggingFaceEmbeddingResponse::Tokens(embeddings) => embeddings
+                    .into_iter()
+                    .map(|tokens| mean_pool(&tokens))
+                    .collect(),
+            };
+            embeddings_for_texts("Hugging Face", embeddings, texts)
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // The Inference API doesn't document a limit, but large batches of long
+        // texts time out on the shared infrastructure.
+        32
+    }
+}
+
+/// Averages the embeddings of the tokens of a text into one for the whole text.
+fn mean_pool(tokens: &[Vec<f32>]) -> Vec<f32> {
+    let Some(first) = tokens.first() else {
+        return Vec::new();
+    };
+    let mut pooled = vec![0.; first.len()];
+    for token in tokens {
+        for (sum, value) in pooled.iter_mut().zip(token) {
+            *sum += value;
+        }
+    }
+    for sum in &mut pooled {
+        *sum /= tokens.len() as f32;
+    }
+    pooled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_hugging_face_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["options"]["wait_for_model"], true);
+            assert_eq!(body["inputs"], json!(["first", "second"]));
+
+            match request.uri().path() {
+                "/pipeline/feature-extraction/sentence-transformers/all-MiniLM-L6-v2" => {
+                    json!([[1.0, 0.0], [0.0, 1.0]])
+                }
+                // Models that don't pool respond with an embedding per token.
+                "/pipeline/feature-extraction/bert-base-uncased" => {
+                    json!([[[1.0, 0.0], [1.0, 2.0]], [[0.0, 3.0]]])
+                }
+                path => panic!("unexpected path {path}"),
+            }
+        });
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+
+        let provider = HuggingFaceEmbeddingProvider::new(
+            client.clone(),
+            HUGGING_FACE_DEFAULT_MODEL,
+            "the-api-key",
+        );
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
+
+        let provider =
+            HuggingFaceEmbeddingProvider::new(client, "bert-base-uncased", "the-api-key");
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 1.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{anyhow, Result};
+use credentials::Credential;
+use futures::future::BoxFuture;
+use gpui::{AppContext, Task};
+use std::sync::Arc;
+use util::http::HttpClient;
+
+use crate::{Embedding, EmbeddingProvider, OpenAiEmbeddingProvider, TextToEmbed};
+
+pub const JINA_API_URL: &str = "https://api.jina.ai";
+
+/// The Jina API key, stored in the keychain under the API's URL.
+pub fn jina_credential() -> Credential {
+    Credential::new("Jina API Key", JINA_API_URL).with_env_var("JINA_API_KEY")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JinaEmbeddingModel {
+    JinaEmbeddingsV2BaseCode,
+    JinaEmbeddingsV2BaseEn,
+}
+
+impl JinaEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::JinaEmbeddingsV2BaseCode => "jina-embeddings-v2-base-code",
+            Self::JinaEmbeddingsV2BaseEn => "jina-embeddings-v2-base-en",
+        }
+    }
+}
+
+/// Jina's embeddings API has the same format as OpenAI's.
+pub struct JinaEmbeddingProvider(OpenAiEmbeddingProvider);
+
+impl JinaEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: JinaEmbeddingModel,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self(OpenAiEmbeddingProvider::compatible(
+            client,
+            model.id(),
+            format!("{JINA_API_URL}/v1"),
+            api_key,
+        ))
+    }
+
+    /// Creates a provider that uses the API key from the `JINA_API_KEY` environment
+    /// variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: JinaEmbeddingModel,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = jina_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Jina API key is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+}
+
+impl EmbeddingProvider for JinaEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.0.embed(texts)
+    }
+
+    fn batch_size(&self) -> usize {
+        // From https://jina.ai/embeddings/
+        2048
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_jina_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://api.jina.ai/v1/embeddings"
+            );
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["model"], "jina-embeddings-v2-base-code");
+            assert_eq!(body["input"], json!(["first", "second"]));
+            json!({
+                "data": [
+                    { "index": 0, "embedding": [1.0, 0.0] },
+                    { "index": 1, "embedding": [0.0, 1.0] },
+                ]
+            })
+        });
+        let provider = JinaEmbeddingProvider::new(
+            client,
+            JinaEmbeddingModel::JinaEmbeddingsV2BaseCode,
+            "the-api-key",
+        );
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
+    }
+}
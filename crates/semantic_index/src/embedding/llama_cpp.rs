@@ -0,0 +1,104 @@
+use anyhow::{Context as _, Result};
+use futures::{future::BoxFuture, AsyncReadExt, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{is_local_uri, Configurable, HttpClient, Method, Request};
+
+use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+
+/// The URL that the llama.cpp server listens on by default.
+pub const LLAMA_CPP_API_URL: &str = "http://localhost:8080";
+
+/// Embeds texts with a llama.cpp server started with `--embedding`, using
+/// whichever model it was started with.
+pub struct LlamaCppEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+}
+
+#[derive(Serialize)]
+struct LlamaCppEmbeddingRequest<'a> {
+    content: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct LlamaCppEmbedding {
+    embedding: Vec<f32>,
+}
+
+/// The shapes that different versions of the server respond to several texts with.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LlamaCppEmbeddingResponse {
+    Results { results: Vec<LlamaCppEmbedding> },
+    List(Vec<LlamaCppEmbedding>),
+    Single(LlamaCppEmbedding),
+}
+
+impl LlamaCppEmbeddingProvider {
+    /// Creates a provider for the llama.cpp server at `api_url`, like [`LLAMA_CPP_API_URL`].
+    pub fn new(client: Arc<dyn HttpClient>, api_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            api_url: api_url.into(),
+        }
+    }
+}
+
+impl EmbeddingProvider for LlamaCppEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = LlamaCppEmbeddingRequest {
+            content: texts.iter().map(|to_embed| to_embed.text).collect(),
+        };
+        let request = serde_json::to_string(&request).unwrap();
+        let url = format!("{}/embedding", self.api_url.trim_end_matches('/'));
+
+        async move {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri(url)
+                .header("Content-Type", "application/json");
+            // A configured proxy usually can't reach the server on this machine, but it
+            // may be needed to reach the server on another one.
+            if builder.uri_ref().map_or(false, is_local_uri) {
+                builder = builder.proxy(None);
+            }
+            let request = builder.body(request.into())?;
+            let mut response = self.client.send(request).await?;
+
+            let mut body = String::new();
+            response.body_mut().read_to_string(&mut body).await?;
+            anyhow::ensure!(
+                response.status().is_success(),
+                "llama.cpp failed to embed texts with status {}: {body}",
+                response.status()
+            );
+
+            let response: LlamaCppEmbeddingResponse = serde_json::from_str(&body)
+                .context("failed to parse llama.cpp embedding response")?;
+            let embeddings = match response {
+                LlamaCppEmbeddingResponse::Results { results } => results,
+                LlamaCppEmbeddingResponse::List(embeddings) => embeddings,
+                LlamaCppEmbeddingResponse::Single(embedding) => vec![embedding],
+            };
+            anyhow::ensure!(
+                embeddings.len() == texts.len(),
+                "llama.cpp returned {} embeddings for {} texts",
+                embeddings.len(),
+                texts.len()
+            );
+
+            Ok(embeddings
+                .into_iter()
+                .map(|embedding| Embedding::new(embedding.embedding))
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // The server processes a batch within its context size, which is 512
+        // tokens per slot by default, so larger batches just queue up.
+        16
+    }
+}
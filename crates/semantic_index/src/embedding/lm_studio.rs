@@ -0,0 +1,79 @@
+use anyhow::Result;
+use futures::{future::BoxFuture, FutureExt};
+use serde::Serialize;
+use std::sync::Arc;
+use util::http::{is_local_uri, Configurable, HttpClient, Method, Request};
+
+use crate::{
+    parse_open_ai_embeddings, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+/// The URL that LM Studio's local server listens on by default.
+pub const LM_STUDIO_API_URL: &str = "http://localhost:1234";
+
+/// The embedding model that LM Studio comes with.
+pub const LM_STUDIO_DEFAULT_MODEL: &str = "text-embedding-nomic-embed-text-v1.5";
+
+/// Embeds texts with a model loaded in LM Studio, through its OpenAI-compatible
+/// embeddings route.
+pub struct LmStudioEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: String,
+    api_url: String,
+}
+
+#[derive(Serialize)]
+struct LmStudioEmbeddingRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+impl LmStudioEmbeddingProvider {
+    /// Creates a provider for the model with the given id, like
+    /// [`LM_STUDIO_DEFAULT_MODEL`], in the LM Studio server at `api_url`, like
+    /// [`LM_STUDIO_API_URL`].
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: impl Into<String>,
+        api_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model: model.into(),
+            api_url: api_url.into(),
+        }
+    }
+}
+
+impl EmbeddingProvider for LmStudioEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = LmStudioEmbeddingRequest {
+            model: &self.model,
+            input: texts.iter().map(|to_embed| to_embed.text).collect(),
+        };
+        let request = serde_json::to_string(&request).unwrap();
+        let url = format!("{}/v1/embeddings", self.api_url.trim_end_matches('/'));
+
+        async move {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri(url)
+                .header("Content-Type", "application/json");
+            // A configured proxy usually can't reach LM Studio on this machine, but it
+            // may be needed to reach LM Studio on another one.
+            if builder.uri_ref().map_or(false, is_local_uri) {
+                builder = builder.proxy(None);
+            }
+            let request = builder.body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "LM Studio", request).await?;
+            parse_open_ai_embeddings("LM Studio", &body, texts)
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // Like Ollama, large batches take long enough to embed on a CPU that the
+        // request could time out.
+        32
+    }
+}
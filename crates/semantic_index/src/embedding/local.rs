@@ -0,0 +1,338 @@
+use anyhow::{Context as _, Result};
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use futures::{future::BoxFuture, lock::Mutex, AsyncReadExt, FutureExt};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use util::http::{AsyncBody, HttpClient};
+
+use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+
+const HUGGING_FACE_URL: &str = "https://huggingface.co";
+
+/// A file that a model is made of, downloaded from its repository on Hugging Face,
+/// with the SHA-256 digest of its contents at the model's revision.
+struct ModelFile {
+    name: &'static str,
+    sha256: &'static str,
+}
+
+/// The number of dimensions of the embeddings of `all-MiniLM-L6-v2`, from
+/// https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2
+pub const ALL_MINI_LM_L6_V2_DIMENSIONS: usize = 384;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalEmbeddingModel {
+    AllMiniLmL6V2,
+}
+
+impl LocalEmbeddingModel {
+    /// The repository of the model on Hugging Face.
+    pub fn repo_id(&self) -> &'static str {
+        match self {
+            Self::AllMiniLmL6V2 => "sentence-transformers/all-MiniLM-L6-v2",
+        }
+    }
+
+    /// The commit of the model's repository that its files are downloaded from, so
+    /// that they can't change from under their digests.
+    fn revision(&self) -> &'static str {
+        match self {
+            Self::AllMiniLmL6V2 => "c9745ed1d9f207416be6d2e6f8de32d1f16199bf",
+        }
+    }
+
+    /// The files of the model, which are checked against their digests before the
+    /// model is loaded.
+    fn files(&self) -> &'static [ModelFile] {
+        match self {
+            Self::AllMiniLmL6V2 => &[
+                ModelFile {
+                    name: "config.json",
+                    sha256: "953f9c0d463486b10a6871cc2fd59f223b2c70184f49815e7efbcab5d8908b41",
+                },
+                ModelFile {
+                    name: "tokenizer.json",
+                    sha256: "be50c3628f2bf5bb5e3a7f17b1f74611b2561a3a27eeab05e5aa30f411572037",
+                },
+                ModelFile {
+                    name: "model.safetensors",
+                    sha256: "53aa51172d142c89d9012cce15ae4d6cc0ca6895895114379cacb4fab128d9db",
+                },
+            ],
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Self::AllMiniLmL6V2 => ALL_MINI_LM_L6_V2_DIMENSIONS,
+        }
+    }
+
+    /// The most tokens the model was trained on, beyond which texts are truncated.
+    fn max_tokens(&self) -> usize {
+        match self {
+            Self::AllMiniLmL6V2 => 256,
+        }
+    }
+}
+
+/// Embeds texts with a small model that runs in the editor's own process, so no
+/// server or API key is needed. The model's weights are downloaded on first use.
+pub struct LocalEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: LocalEmbeddingModel,
+    models_dir: PathBuf,
+    loaded_model: Mutex<Option<Arc<LoadedModel>>>,
+}
+
+struct LoadedModel {
+    bert: BertModel,
+    tokenizer: Tokenizer,
+}
+
+impl LocalEmbeddingProvider {
+    /// Creates a provider that keeps the weights of its model in `models_dir`, like
+    /// a directory in [`util::paths::EMBEDDINGS_DIR`].
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: LocalEmbeddingModel,
+        models_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            models_dir: models_dir.into(),
+            loaded_model: Mutex::new(None),
+        }
+    }
+
+    fn model_dir(&self) -> PathBuf {
+        self.models_dir
+            .join(self.model.repo_id().replace('/', "--"))
+    }
+
+    /// Returns the model, downloading and loading it if it's the first time it's used.
+    async fn load(&self) -> Result<Arc<LoadedModel>> {
+        let mut loaded_model = self.loaded_model.lock().await;
+        if let Some(model) = loaded_model.as_ref() {
+            return Ok(model.clone());
+        }
+
+        let model_dir = self.model_dir();
+        download_model(
+            self.client.as_ref(),
+            self.model.repo_id(),
+            self.model.revision(),
+            self.model.files(),
+            &model_dir,
+        )
+        .await
+        .with_context(|| format!("failed to download {}", self.model.repo_id()))?;
+
+        let config = smol::fs::read_to_string(model_dir.join("config.json")).await?;
+        let config: BertConfig = serde_json::from_str(&config)?;
+        let mut tokenizer =
+            Tokenizer::from_file(model_dir.join("tokenizer.json")).map_err(anyhow::Error::msg)?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: self.model.max_tokens(),
+                ..Default::default()
+            }))
+            .map_err(anyhow::Error::msg)?;
+        // Safety: the weights were just checked against their digest, and are only
+        // ever written before being moved into place.
+        let weights = unsafe {
+            VarBuilder::from_mmaped_safetensors(
+                &[model_dir.join("model.safetensors")],
+                DTYPE,
+                &Device::Cpu,
+            )?
+        };
+        let bert = BertModel::load(weights, &config)?;
+
+        let model = Arc::new(LoadedModel { bert, tokenizer });
+        *loaded_model = Some(model.clone());
+        Ok(model)
+    }
+}
+
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let model = self.load().await?;
+            let embeddings = model.embed(texts.iter().map(|to_embed| to_embed.text).collect())?;
+            anyhow::ensure!(
+                embeddings.len() == texts.len(),
+                "the local model returned {} embeddings for {} texts",
+                embeddings.len(),
+                texts.len()
+            );
+            Ok(embeddings.into_iter().map(Embedding::new).collect())
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // Texts are padded to the longest one in their batch, so small batches waste
+        // less work on padding while still making use of several cores.
+        16
+    }
+}
+
+impl LoadedModel {
+    /// Embeds the texts by averaging the embeddings of their tokens, ignoring the
+    /// padding, the way sentence-transformers pools them.
+    fn embed(&self, texts: Vec<&str>) -> Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(anyhow::Error::msg)?;
+        let device = &self.bert.device;
+        let input_ids = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_ids(), device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let attention_mask = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_attention_mask(), device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let input_ids = Tensor::stack(&input_ids, 0)?;
+        let attention_mask = Tensor::stack(&attention_mask, 0)?;
+        let token_type_ids = input_ids.zeros_like()?;
+
+        let token_embeddings =
+            self.bert
+                .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+        let mask = attention_mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+        let summed = token_embeddings.broadcast_mul(&mask)?.sum(1)?;
+        let token_counts = mask.sum(1)?;
+        Ok(summed.broadcast_div(&token_counts)?.to_vec2()?)
+    }
+}
+
+/// Downloads the files of the model at the given revision that aren't in `model_dir`
+/// yet, or whose contents don't match their digests, checking each one that's
+/// downloaded against its digest too.
+async fn download_model(
+    client: &dyn HttpClient,
+    repo_id: &str,
+    revision: &str,
+    files: &[ModelFile],
+    model_dir: &Path,
+) -> Result<()> {
+    smol::fs::create_dir_all(model_dir).await?;
+    for file in files {
+        let path = model_dir.join(file.name);
+        if let Ok(contents) = smol::fs::read(&path).await {
+            if sha256_hex(&contents) == file.sha256 {
+                continue;
+            }
+            log::warn!("{} of {repo_id} is corrupted", file.name);
+        }
+
+        log::info!("downloading {} of {repo_id}", file.name);
+        let url = format!(
+            "{HUGGING_FACE_URL}/{repo_id}/resolve/{revision}/{}",
+            file.name
+        );
+        let mut response = client.get(&url, AsyncBody::default(), true).await?;
+        let mut body = Vec::new();
+        response.body_mut().read_to_end(&mut body).await?;
+        anyhow::ensure!(
+            response.status().is_success(),
+            "failed to download {url} with status {}",
+            response.status()
+        );
+        let digest = sha256_hex(&body);
+        anyhow::ensure!(
+            digest == file.sha256,
+            "{url} has the SHA-256 digest {digest} instead of {}",
+            file.sha256
+        );
+
+        // Write the file next to its final path first, so that an interrupted download
+        // isn't mistaken for a complete one.
+        let download_path = path.with_extension("download");
+        smol::fs::write(&download_path, body).await?;
+        smol::fs::rename(&download_path, &path).await?;
+    }
+    Ok(())
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use util::http::{FakeHttpClient, Response};
+
+    #[test]
+    fn test_download_model() {
+        const FILES: &[ModelFile] = &[ModelFile {
+            name: "config.json",
+            // The digest of `{}`.
+            sha256: "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+        }];
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                requests.fetch_add(1, SeqCst);
+                let body = match request.uri().path() {
+                    "/org/model/resolve/abc123/config.json" => "{}",
+                    _ => "{ \"tampered\": true }",
+                };
+                async move { Ok(Response::new(body.to_string().into())) }
+            }
+        });
+        let temp_dir = tempfile::tempdir().unwrap();
+        let model_dir = temp_dir.path().join("model");
+
+        smol::block_on(async {
+            download_model(client.as_ref(), "org/model", "abc123", FILES, &model_dir)
+                .await
+                .unwrap();
+            assert_eq!(
+                std::fs::read_to_string(model_dir.join("config.json")).unwrap(),
+                "{}"
+            );
+            assert_eq!(requests.load(SeqCst), 1);
+
+            // Files that are already downloaded aren't downloaded again.
+            download_model(client.as_ref(), "org/model", "abc123", FILES, &model_dir)
+                .await
+                .unwrap();
+            assert_eq!(requests.load(SeqCst), 1);
+
+            // Corrupted files are downloaded again.
+            std::fs::write(model_dir.join("config.json"), "{").unwrap();
+            download_model(client.as_ref(), "org/model", "abc123", FILES, &model_dir)
+                .await
+                .unwrap();
+            assert_eq!(requests.load(SeqCst), 2);
+            assert_eq!(
+                std::fs::read_to_string(model_dir.join("config.json")).unwrap(),
+                "{}"
+            );
+
+            // Files whose contents don't match their digest are rejected.
+            std::fs::remove_file(model_dir.join("config.json")).unwrap();
+            let error = download_model(client.as_ref(), "org/model", "def456", FILES, &model_dir)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("SHA-256"), "{error}");
+            assert!(!model_dir.join("config.json").exists());
+        });
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Context as _, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    embeddings_for_texts, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+pub const MISTRAL_API_URL: &str = "https://api.mistral.ai";
+
+/// The number of dimensions of the embeddings of `mistral-embed`, from
+/// https://docs.mistral.ai/capabilities/embeddings/
+pub const MISTRAL_EMBED_DIMENSIONS: usize = 1024;
+
+/// The Mistral API key, stored in the keychain under the API's URL.
+pub fn mistral_credential() -> Credential {
+    Credential::new("Mistral API Key", MISTRAL_API_URL).with_env_var("MISTRAL_API_KEY")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MistralEmbeddingModel {
+    MistralEmbed,
+}
+
+impl MistralEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::MistralEmbed => "mistral-embed",
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Self::MistralEmbed => MISTRAL_EMBED_DIMENSIONS,
+        }
+    }
+}
+
+pub struct MistralEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: MistralEmbeddingModel,
+    api_key: String,
+}
+
+#[derive(Serialize)]
+struct MistralEmbeddingRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+    encoding_format: &'a str,
+}
+
+#[derive(Deserialize)]
+struct MistralEmbeddingResponse {
+    data: Vec<MistralEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct MistralEmbedding {
+    embedding: Vec<f32>,
+    /// The position of the embedded text in the request's `input`.
+    index: usize,
+}
+
+impl MistralEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: MistralEmbeddingModel,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a provider that uses the API key from the `MISTRAL_API_KEY` environment
+    /// variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: MistralEmbeddingModel,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = mistral_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Mistral API key is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+}
+
+impl EmbeddingProvider for MistralEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = MistralEmbeddingRequest {
+            model: self.model.id(),
+            input: texts.iter().map(|to_embed| to_embed.text).collect(),
+            encoding_format: "float",
+        };
+        let request = serde_json::to_string(&request).unwrap();
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!("{MISTRAL_API_URL}/v1/embeddings"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "Mistral", request).await?;
+            let mut response: MistralEmbeddingResponse = serde_json::from_str(&body)
+                .context("failed to parse Mistral embedding response")?;
+            // The embeddings aren't guaranteed to be listed in the order of the input.
+            response.data.sort_by_key(|embedding| embedding.index);
+            embeddings_for_texts(
+                "Mistral",
+                response
+                    .data
+                    .into_iter()
+                    .map(|data| data.embedding)
+                    .collect(),
+                texts,
+            )
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // Mistral limits requests to 16384 tokens rather than to a number of texts,
+        // and chunks are up to 1500 bytes, so this keeps batches well under that.
+        16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_mistral_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://api.mistral.ai/v1/embeddings"
+            );
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["model"], "mistral-embed");
+            assert_eq!(body["input"], json!(["first", "second"]));
+            assert_eq!(body["encoding_format"], "float");
+
+            // Embeddings may be returned in any order.
+            json!({
+                "data": [
+                    { "index": 1, "embedding": [0.0, 1.0] },
+                    { "index": 0, "embedding": [1.0, 0.0] },
+                ]
+            })
+        });
+        let provider = MistralEmbeddingProvider::new(
+            client,
+            MistralEmbeddingModel::MistralEmbed,
+            "the-api-key",
+        );
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
+    }
+}
@@ -1,74 +1,207 @@
 use anyhow::{Context as _, Result};
-use futures::{future::BoxFuture, AsyncReadExt, FutureExt};
+use futures::{future::BoxFuture, FutureExt};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use util::http::HttpClient;
+use util::http::{is_local_uri, Configurable, HttpClient, Method, Request};
 
-use crate::{Embedding, EmbeddingProvider, TextToEmbed};
+use crate::{
+    embeddings_for_texts, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
 
+/// The URL that Ollama listens on by default.
+pub const OLLAMA_API_URL: &str = "http://localhost:11434";
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum OllamaEmbeddingModel {
     NomicEmbedText,
     MxbaiEmbedLarge,
+    /// Any other model that has been pulled into Ollama, like `snowflake-arctic-embed`.
+    /// When the number of dimensions is given, embeddings of any other size are
+    /// rejected, which catches a model that isn't meant for embeddings.
+    Custom {
+        name: String,
+        dimensions: Option<usize>,
+    },
+}
+
+impl OllamaEmbeddingModel {
+    /// Returns the model with the name it was pulled with, like `mxbai-embed-large`.
+    /// The given number of dimensions takes precedence over that of a known model,
+    /// since a model can be pulled under the name of another.
+    pub fn from_name(name: &str, dimensions: Option<usize>) -> Self {
+        let model = match name {
+            "nomic-embed-text" => Self::NomicEmbedText,
+            "mxbai-embed-large" => Self::MxbaiEmbedLarge,
+            _ => Self::Custom {
+                name: name.to_string(),
+                dimensions,
+            },
+        };
+        match dimensions {
+            Some(dimensions) if model.dimensions() != Some(dimensions) => Self::Custom {
+                name: name.to_string(),
+                dimensions: Some(dimensions),
+            },
+            _ => model,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::NomicEmbedText => "nomic-embed-text",
+            Self::MxbaiEmbedLarge => "mxbai-embed-large",
+            Self::Custom { name, .. } => name,
+        }
+    }
+
+    /// The number of dimensions of the model's embeddings, if known.
+    pub fn dimensions(&self) -> Option<usize> {
+        match self {
+            Self::NomicEmbedText => Some(768),
+            Self::MxbaiEmbedLarge => Some(1024),
+            Self::Custom { dimensions, .. } => *dimensions,
+        }
+    }
 }
 
 pub struct OllamaEmbeddingProvider {
     client: Arc<dyn HttpClient>,
     model: OllamaEmbeddingModel,
+    api_url: String,
 }
 
 #[derive(Serialize)]
-struct OllamaEmbeddingRequest {
-    model: String,
-    prompt: String,
+struct OllamaEmbeddingRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
 }
 
 #[derive(Deserialize)]
 struct OllamaEmbeddingResponse {
-    embedding: Vec<f32>,
+    embeddings: Vec<Vec<f32>>,
 }
 
 impl OllamaEmbeddingProvider {
-    pub fn new(client: Arc<dyn HttpClient>, model: OllamaEmbeddingModel) -> Self {
-        Self { client, model }
+    /// Creates a provider for the Ollama server at `api_url`, like [`OLLAMA_API_URL`].
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: OllamaEmbeddingModel,
+        api_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_url: api_url.into(),
+        }
     }
 }
 
 impl EmbeddingProvider for OllamaEmbeddingProvider {
     fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
-        //
-        let model = match self.model {
-            OllamaEmbeddingModel::NomicEmbedText => "nomic-embed-text",
-            OllamaEmbeddingModel::MxbaiEmbedLarge => "mxbai-embed-large",
+        let request = OllamaEmbeddingRequest {
+            model: self.model.name(),
+            input: texts.iter().map(|to_embed| to_embed.text).collect(),
         };
+        let request = serde_json::to_string(&request).unwrap();
+        let url = format!("{}/api/embed", self.api_url.trim_end_matches('/'));
 
-        futures::future::try_join_all(texts.into_iter().map(|to_embed| {
-            let request = OllamaEmbeddingRequest {
-                model: model.to_string(),
-                prompt: to_embed.text.to_string(),
-            };
+        async move {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri(url)
+                .header("Content-Type", "application/json");
+            // A configured proxy usually can't reach Ollama on this machine, but it
+            // may be needed to reach Ollama on another one.
+            if builder.uri_ref().map_or(false, is_local_uri) {
+                builder = builder.proxy(None);
+            }
+            let request = builder.body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "Ollama", request).await?;
+            let response: OllamaEmbeddingResponse =
+                serde_json::from_str(&body).context("failed to parse Ollama embedding response")?;
+            if let Some(dimensions) = self.model.dimensions() {
+                if let Some(embedding) = response
+                    .embeddings
+                    .iter()
+                    .find(|embedding| embedding.len() != dimensions)
+                {
+                    anyhow::bail!(
+                        "Ollama's {} model returned {} dimensions instead of {dimensions}",
+                        self.model.name(),
+                        embedding.len()
+                    );
+                }
+            }
+            embeddings_for_texts("Ollama", response.embeddings, texts)
+        }
+        .boxed()
+    }
 
-            let request = serde_json::to_string(&request).unwrap();
+    fn batch_size(&self) -> usize {
+        // Ollama has no limit, but large batches take long enough to embed on a
+        // CPU that the request could time out.
+        32
+    }
+}
 
-            async {
-                let response = self
-                    .client
-                    .post_json("http://localhost:11434/api/embeddings", request.into())
-                    .await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
 
-                let mut body = String::new();
-                response.into_body().read_to_string(&mut body).await?;
+    #[test]
+    fn test_model_from_name() {
+        assert_eq!(
+            OllamaEmbeddingModel::from_name("nomic-embed-text", None),
+            OllamaEmbeddingModel::NomicEmbedText
+        );
+        assert_eq!(
+            OllamaEmbeddingModel::from_name("mxbai-embed-large", Some(1024)),
+            OllamaEmbeddingModel::MxbaiEmbedLarge
+        );
+        assert_eq!(
+            OllamaEmbeddingModel::from_name("nomic-embed-text", Some(256)).dimensions(),
+            Some(256)
+        );
+        assert_eq!(
+            OllamaEmbeddingModel::from_name("snowflake-arctic-embed", None).dimensions(),
+            None
+        );
+    }
 
-                let response: OllamaEmbeddingResponse =
-                    serde_json::from_str(&body).context("Unable to pull response")?;
+    #[gpui::test]
+    async fn test_ollama_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "http://localhost:11434/api/embed"
+            );
+            assert_eq!(body["model"], "all-minilm");
+            assert_eq!(body["input"], json!(["first", "second"]));
+            json!({ "embeddings": [[1.0, 0.0], [0.0, 1.0]] })
+        });
 
-                Ok(Embedding::new(response.embedding))
-            }
-        }))
-        .boxed()
-    }
+        let provider = OllamaEmbeddingProvider::new(
+            client.clone(),
+            OllamaEmbeddingModel::from_name("all-minilm", Some(2)),
+            OLLAMA_API_URL,
+        );
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![0.0, 1.0])
+            ]
+        );
 
-    fn batch_size(&self) -> usize {
-        // TODO: Figure out decent value
-        10
+        // Embeddings of another size than the model's are rejected.
+        let provider = OllamaEmbeddingProvider::new(
+            client,
+            OllamaEmbeddingModel::from_name("all-minilm", Some(384)),
+            OLLAMA_API_URL,
+        );
+        assert!(provider.embed(&texts).await.is_err());
     }
 }
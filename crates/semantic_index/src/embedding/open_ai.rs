@@ -1,18 +1,49 @@
-use crate::{Embedding, EmbeddingProvider, TextToEmbed};
-use anyhow::Result;
+use crate::{
+    count_cl100k_base_tokens, embeddings_for_texts, Embedding, EmbeddingProvider, TextToEmbed,
+};
+use anyhow::{anyhow, Context as _, Result};
+use credentials::open_ai_credential;
 use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
 pub use open_ai::OpenAiEmbeddingModel;
+use open_ai::OpenAiEmbeddingResponse;
 use std::sync::Arc;
 use util::http::HttpClient;
 
+/// Parses a response in the format of OpenAI's embeddings API, which other
+/// providers use too, returning the embeddings in the order of the texts.
+pub(crate) fn parse_open_ai_embeddings(
+    provider_name: &str,
+    body: &str,
+    texts: &[TextToEmbed],
+) -> Result<Vec<Embedding>> {
+    let response = OpenAiEmbeddingResponse::parse(body)
+        .with_context(|| format!("failed to parse {provider_name} embedding response"))?;
+    embeddings_for_texts(
+        provider_name,
+        response
+            .data
+            .into_iter()
+            .map(|data| data.embedding)
+            .collect(),
+        texts,
+    )
+}
+
 pub struct OpenAiEmbeddingProvider {
     client: Arc<dyn HttpClient>,
-    model: OpenAiEmbeddingModel,
+    model: String,
+    /// The OpenAI model that `model` is the id of, unless it's a model that's
+    /// served by another API.
+    open_ai_model: Option<OpenAiEmbeddingModel>,
     api_url: String,
     api_key: String,
+    dimensions: Option<usize>,
 }
 
 impl OpenAiEmbeddingProvider {
+    /// Creates a provider for the API at `api_url`, which can be OpenAI's own, like
+    /// [`open_ai::OPEN_AI_API_URL`], or that of any server that's compatible with it.
     pub fn new(
         client: Arc<dyn HttpClient>,
         model: OpenAiEmbeddingModel,
@@ -21,11 +52,60 @@ impl OpenAiEmbeddingProvider {
     ) -> Self {
         Self {
             client,
-            model,
-            api_url,
+            model: model.id().to_string(),
+            open_ai_model: Some(model),
+            api_url: api_url.trim_end_matches('/').to_string(),
             api_key,
+            dimensions: None,
+        }
+    }
+
+    /// Creates a provider for a model served by an API that's compatible with
+    /// OpenAI's, like Mistral's, whose tokenizer and dimensions aren't known.
+    pub fn compatible(
+        client: Arc<dyn HttpClient>,
+        model: impl Into<String>,
+        api_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model: model.into(),
+            open_ai_model: None,
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            dimensions: None,
         }
     }
+
+    /// Shortens the embeddings to the given number of dimensions, which makes the
+    /// index several times smaller while barely affecting the quality of searches.
+    /// Embeddings are cut to the first `dimensions` values and normalized again,
+    /// the way OpenAI shortens them, in case a compatible API ignores the parameter.
+    pub fn with_dimensions(mut self, dimensions: Option<usize>) -> Self {
+        let max_dimensions = self
+            .open_ai_model
+            .map_or(usize::MAX, |model| model.max_dimensions());
+        self.dimensions = dimensions.map(|dimensions| dimensions.clamp(1, max_dimensions));
+        self
+    }
+
+    /// Creates a provider that uses the API key from the `OPENAI_API_KEY` environment
+    /// variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: OpenAiEmbeddingModel,
+        api_url: String,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = open_ai_credential(&api_url).read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no OpenAI API key is set"))?;
+            Ok(Self::new(client, model, api_url, api_key))
+        })
+    }
 }
 
 impl EmbeddingProvider for OpenAiEmbeddingProvider {
@@ -34,15 +114,28 @@ impl EmbeddingProvider for OpenAiEmbeddingProvider {
             self.client.as_ref(),
             &self.api_url,
             &self.api_key,
-            self.model,
+            &self.model,
             texts.iter().map(|to_embed| to_embed.text),
+            self.dimensions,
         );
         async move {
             let response = embed.await?;
+            anyhow::ensure!(
+                response.data.len() == texts.len(),
+                "OpenAI returned {} embeddings for {} texts",
+                response.data.len(),
+                texts.len()
+            );
             Ok(response
                 .data
                 .into_iter()
-                .map(|data| Embedding::new(data.embedding))
+                .map(|data| {
+                    let mut embedding = data.embedding;
+                    if let Some(dimensions) = self.dimensions {
+                        embedding.truncate(dimensions);
+                    }
+                    Embedding::new(embedding)
+                })
                 .collect())
         }
         .boxed()
@@ -52,4 +145,86 @@ impl EmbeddingProvider for OpenAiEmbeddingProvider {
         // From https://platform.openai.com/docs/api-reference/embeddings/create
         2048
     }
+
+    fn max_tokens(&self) -> Option<usize> {
+        // From https://platform.openai.com/docs/guides/embeddings/embedding-models
+        self.open_ai_model.map(|_| 8191)
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        if self.open_ai_model.is_none() {
+            return text.len();
+        }
+        // Both embedding models use the `cl100k_base` encoding.
+        count_cl100k_base_tokens(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_open_ai_dimensions() {
+        // An API that ignores the number of dimensions it's asked for.
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://api.openai.com/v1/embeddings"
+            );
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["model"], "text-embedding-3-small");
+            assert_eq!(body["dimensions"], 2);
+            json!({
+                "data": [
+                    { "index": 0, "embedding": [3.0, 4.0, 12.0] },
+                    { "index": 1, "embedding": [0.0, -2.0, 5.0] },
+                ]
+            })
+        });
+        let provider = OpenAiEmbeddingProvider::new(
+            client,
+            OpenAiEmbeddingModel::TextEmbedding3Small,
+            open_ai::OPEN_AI_API_URL.to_string(),
+            "the-api-key".to_string(),
+        )
+        .with_dimensions(Some(2));
+
+        // The embeddings are shortened and normalized again.
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        let embeddings = provider.embed(&texts).await.unwrap();
+        assert_eq!(
+            embeddings,
+            [
+                Embedding::new(vec![3.0, 4.0]),
+                Embedding::new(vec![0.0, -2.0])
+            ]
+        );
+        for embedding in &embeddings {
+            let norm = embedding.0.iter().map(|value| value * value).sum::<f32>();
+            assert!((norm - 1.0).abs() < 1e-6);
+        }
+        assert!((embeddings[0].0[0] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_open_ai_dimensions_are_clamped() {
+        let client = fake_embedding_api(|_, _| json!({}));
+        let provider = |dimensions| {
+            OpenAiEmbeddingProvider::new(
+                client.clone(),
+                OpenAiEmbeddingModel::TextEmbedding3Small,
+                open_ai::OPEN_AI_API_URL.to_string(),
+                String::new(),
+            )
+            .with_dimensions(dimensions)
+            .dimensions
+        };
+        assert_eq!(provider(None), None);
+        assert_eq!(provider(Some(0)), Some(1));
+        assert_eq!(provider(Some(256)), Some(256));
+        assert_eq!(provider(Some(5000)), Some(1536));
+    }
 }
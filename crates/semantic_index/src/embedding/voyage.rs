@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use credentials::Credential;
+use futures::{future::BoxFuture, FutureExt};
+use gpui::{AppContext, Task};
+use serde::Serialize;
+use std::sync::Arc;
+use util::http::{HttpClient, Method, Request};
+
+use crate::{
+    parse_open_ai_embeddings, send_embedding_request, Embedding, EmbeddingProvider, TextToEmbed,
+};
+
+pub const VOYAGE_API_URL: &str = "https://api.voyageai.com";
+
+/// The number of dimensions of the embeddings of each model, from
+/// https://docs.voyageai.com/docs/embeddings
+pub const VOYAGE_CODE_2_DIMENSIONS: usize = 1536;
+pub const VOYAGE_LARGE_2_DIMENSIONS: usize = 1536;
+pub const VOYAGE_2_DIMENSIONS: usize = 1024;
+
+/// The Voyage API key, stored in the keychain under the API's URL.
+pub fn voyage_credential() -> Credential {
+    Credential::new("Voyage API Key", VOYAGE_API_URL).with_env_var("VOYAGE_API_KEY")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoyageEmbeddingModel {
+    VoyageCode2,
+    VoyageLarge2,
+    Voyage2,
+}
+
+impl VoyageEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::VoyageCode2 => "voyage-code-2",
+            Self::VoyageLarge2 => "voyage-large-2",
+            Self::Voyage2 => "voyage-2",
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Self::VoyageCode2 => VOYAGE_CODE_2_DIMENSIONS,
+            Self::VoyageLarge2 => VOYAGE_LARGE_2_DIMENSIONS,
+            Self::Voyage2 => VOYAGE_2_DIMENSIONS,
+        }
+    }
+}
+
+pub struct VoyageEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    model: VoyageEmbeddingModel,
+    api_key: String,
+}
+
+/// What the embedded texts are used for, which Voyage prepends a prompt for.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum VoyageInputType {
+    Document,
+    Query,
+}
+
+#[derive(Serialize)]
+struct VoyageEmbeddingRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+    input_type: VoyageInputType,
+}
+
+impl VoyageEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: VoyageEmbeddingModel,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a provider that uses the API key from the `VOYAGE_API_KEY` environment
+    /// variable or the keychain.
+    pub fn authenticate(
+        client: Arc<dyn HttpClient>,
+        model: VoyageEmbeddingModel,
+        cx: &AppContext,
+    ) -> Task<Result<Self>> {
+        let read_credential = voyage_credential().read(cx);
+        cx.background_executor().spawn(async move {
+            let (api_key, _) = read_credential
+                .await?
+                .ok_or_else(|| anyhow!("no Voyage API key is set"))?;
+            Ok(Self::new(client, model, api_key))
+        })
+    }
+
+    fn embed_as<'a>(
+        &'a self,
+        texts: &'a [TextToEmbed<'a>],
+        input_type: VoyageInputType,
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let request = VoyageEmbeddingRequest {
+            model: self.model.id(),
+            input: texts.iter().map(|to_embed| to_embed.text).collect(),
+            input_type,
+        };
+        let request = serde_json::to_string(&request).unwrap();
+
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!("{VOYAGE_API_URL}/v1/embeddings"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .body(request.into())?;
+            let body = send_embedding_request(self.client.as_ref(), "Voyage", request).await?;
+            parse_open_ai_embeddings("Voyage", &body, texts)
+        }
+        .boxed()
+    }
+}
+
+impl EmbeddingProvider for VoyageEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(texts, VoyageInputType::Document)
+    }
+
+    fn embed_query<'a>(
+        &'a self,
+        queries: &'a [TextToEmbed<'a>],
+    ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.embed_as(queries, VoyageInputType::Query)
+    }
+
+    fn batch_size(&self) -> usize {
+        // From https://docs.voyageai.com/reference/embeddings-api
+        128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_embedding_api;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_voyage_request() {
+        let client = fake_embedding_api(|request, body| {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://api.voyageai.com/v1/embeddings"
+            );
+            assert_eq!(request.headers()["Authorization"], "Bearer the-api-key");
+            assert_eq!(body["model"], "voyage-code-2");
+
+            // Documents and queries are embedded with different prompts.
+            let embedding = match body["input_type"].as_str().unwrap() {
+                "document" => [1.0, 0.0],
+                "query" => [0.0, 1.0],
+                input_type => panic!("unexpected input type {input_type}"),
+            };
+            let data = (0..body["input"].as_array().unwrap().len())
+                .rev()
+                .map(|index| json!({ "embedding": embedding, "index": index }))
+                .collect::<Vec<_>>();
+            json!({ "data": data })
+        });
+        let provider =
+            VoyageEmbeddingProvider::new(client, VoyageEmbeddingModel::VoyageCode2, "the-api-key");
+
+        let texts = [TextToEmbed::new("first"), TextToEmbed::new("second")];
+        assert_eq!(
+            provider.embed(&texts).await.unwrap(),
+            [
+                Embedding::new(vec![1.0, 0.0]),
+                Embedding::new(vec![1.0, 0.0])
+            ]
+        );
+        let queries = [TextToEmbed::new("query")];
+        assert_eq!(
+            provider.embed_query(&queries).await.unwrap(),
+            [Embedding::new(vec![0.0, 1.0])]
+        );
+    }
+}
@@ -2,7 +2,7 @@ mod chunking;
 mod embedding;
 
 use anyhow::{anyhow, Context as _, Result};
-use chunking::{chunk_text, Chunk};
+use chunking::{chunk_text, split_oversized_chunks, Chunk};
 use collections::{Bound, HashMap};
 pub use embedding::*;
 use fs::Fs;
@@ -12,7 +12,10 @@ use gpui::{
     AppContext, AsyncAppContext, Context, EntityId, EventEmitter, Global, Model, ModelContext,
     Subscription, Task, WeakModel,
 };
-use heed::types::{SerdeBincode, Str};
+use heed::{
+    byteorder::BigEndian,
+    types::{SerdeBincode, Str, Unit, U64},
+};
 use language::LanguageRegistry;
 use project::{Entry, Project, UpdatedEntriesSet, Worktree};
 use serde::{Deserialize, Serialize};
@@ -20,6 +23,7 @@ use smol::channel;
 use std::{
     cmp::Ordering,
     future::Future,
+    mem,
     ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
@@ -28,9 +32,108 @@ use std::{
 use util::ResultExt;
 use worktree::LocalSnapshot;
 
+const EMBEDDING_CACHE_DB_NAME: &str = "embedding-cache.2";
+const EMBEDDING_CACHE_LAST_USED_DB_NAME: &str = "embedding-cache-last-used.2";
+const EMBEDDING_CACHE_RECENCY_DB_NAME: &str = "embedding-cache-recency.2";
+/// The names of the caches in earlier formats, which are cleared when the semantic
+/// index is opened.
+const OLD_EMBEDDING_CACHE_DB_NAMES: &[&str] = &["embedding-cache", "embedding-cache.1"];
+/// The most embeddings that are cached, which is around 300MB of the database for
+/// embeddings of 1536 dimensions.
+const EMBEDDING_CACHE_HIGH_WATER_MARK: usize = 50_000;
+/// How many embeddings are kept when the cache is over its capacity, so that it's
+/// pruned in batches rather than every time a chunk is embedded.
+const EMBEDDING_CACHE_LOW_WATER_MARK: usize = 45_000;
+
+/// The embeddings of chunks keyed by the digest of their text, which is shared by
+/// all worktrees, so a chunk whose text is unchanged is never embedded twice, even
+/// when the file containing it is touched or moved, or the project is opened
+/// elsewhere. The cache lives in the semantic index's database, which is opened
+/// separately for each provider, model and endpoint, so that embeddings of
+/// different models are never mixed up.
+///
+/// Every use of an embedding is numbered with an increasing generation, which is
+/// stored apart from the embedding, so that using it again doesn't rewrite it. Once
+/// the cache holds more than `high_water_mark` embeddings, the least recently used
+/// ones are evicted, in order of their generations, until `low_water_mark` are left.
+#[derive(Clone, Copy)]
+struct EmbeddingCache {
+    embeddings: heed::Database<SerdeBincode<[u8; 32]>, SerdeBincode<Embedding>>,
+    /// The generation in which each embedding was last used.
+    last_used: heed::Database<SerdeBincode<[u8; 32]>, U64<BigEndian>>,
+    /// The digest of the embedding last used in each generation, from the least
+    /// to the most recently used.
+    recency: heed::Database<U64<BigEndian>, SerdeBincode<[u8; 32]>>,
+    high_water_mark: usize,
+    low_water_mark: usize,
+}
+
+impl EmbeddingCache {
+    fn create(
+        db_connection: &heed::Env,
+        txn: &mut heed::RwTxn,
+        high_water_mark: usize,
+        low_water_mark: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            embeddings: db_connection.create_database(txn, Some(EMBEDDING_CACHE_DB_NAME))?,
+            last_used: db_connection
+                .create_database(txn, Some(EMBEDDING_CACHE_LAST_USED_DB_NAME))?,
+            recency: db_connection.create_database(txn, Some(EMBEDDING_CACHE_RECENCY_DB_NAME))?,
+            high_water_mark,
+            low_water_mark,
+        })
+    }
+
+    fn get(&self, txn: &heed::RoTxn, digest: &[u8; 32]) -> Result<Option<Embedding>> {
+        Ok(self.embeddings.get(txn, digest)?)
+    }
+
+    /// Returns the generation that the next use of an embedding is numbered with.
+    fn next_generation(&self, txn: &heed::RoTxn) -> Result<u64> {
+        Ok(self
+            .recency
+            .last(txn)?
+            .map_or(0, |(generation, _)| generation + 1))
+    }
+
+    /// Marks the embedding with the given digest as used in the given generation.
+    fn touch(&self, txn: &mut heed::RwTxn, digest: &[u8; 32], generation: u64) -> Result<()> {
+        if let Some(last_used) = self.last_used.get(txn, digest)? {
+            self.recency.delete(txn, &last_used)?;
+        }
+        self.last_used.put(txn, digest, &generation)?;
+        self.recency.put(txn, &generation, digest)?;
+        Ok(())
+    }
+
+    /// Removes the least recently used embeddings once the cache holds more than
+    /// its high-water mark, until it's down to its low-water mark.
+    fn evict(&self, txn: &mut heed::RwTxn) -> Result<()> {
+        let len = self.embeddings.len(txn)? as usize;
+        if len <= self.high_water_mark {
+            return Ok(());
+        }
+
+        let evicted = self
+            .recency
+            .iter(txn)?
+            .take(len.saturating_sub(self.low_water_mark))
+            .collect::<heed::Result<Vec<_>>>()?;
+        log::debug!("evicting {} embeddings from the cache", evicted.len());
+        for (generation, digest) in evicted {
+            self.recency.delete(txn, &generation)?;
+            self.last_used.delete(txn, &digest)?;
+            self.embeddings.delete(txn, &digest)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct SemanticIndex {
     embedding_provider: Arc<dyn EmbeddingProvider>,
     db_connection: heed::Env,
+    embedding_cache: EmbeddingCache,
     project_indices: HashMap<WeakModel<Project>, Model<ProjectIndex>>,
 }
 
@@ -42,22 +145,39 @@ impl SemanticIndex {
         embedding_provider: Arc<dyn EmbeddingProvider>,
         cx: &mut AsyncAppContext,
     ) -> Result<Self> {
-        let db_connection = cx
+        let (db_connection, embedding_cache) = cx
             .background_executor()
             .spawn(async move {
                 std::fs::create_dir_all(&db_path)?;
-                unsafe {
+                let db_connection = unsafe {
                     heed::EnvOpenOptions::new()
                         .map_size(1024 * 1024 * 1024)
                         .max_dbs(3000)
                         .open(db_path)
+                }?;
+                let mut txn = db_connection.write_txn()?;
+                for old_name in OLD_EMBEDDING_CACHE_DB_NAMES {
+                    if let Some(old_embedding_cache) =
+                        db_connection.open_database::<Unit, Unit>(&txn, Some(old_name))?
+                    {
+                        old_embedding_cache.clear(&mut txn)?;
+                    }
                 }
+                let embedding_cache = EmbeddingCache::create(
+                    &db_connection,
+                    &mut txn,
+                    EMBEDDING_CACHE_HIGH_WATER_MARK,
+                    EMBEDDING_CACHE_LOW_WATER_MARK,
+                )?;
+                txn.commit()?;
+                anyhow::Ok((db_connection, embedding_cache))
             })
             .await
             .context("opening database connection")?;
 
         Ok(SemanticIndex {
             db_connection,
+            embedding_cache,
             embedding_provider,
             project_indices: HashMap::default(),
         })
@@ -75,6 +195,7 @@ impl SemanticIndex {
                     ProjectIndex::new(
                         project,
                         self.db_connection.clone(),
+                        self.embedding_cache,
                         self.embedding_provider.clone(),
                         cx,
                     )
@@ -86,6 +207,7 @@ impl SemanticIndex {
 
 pub struct ProjectIndex {
     db_connection: heed::Env,
+    embedding_cache: EmbeddingCache,
     project: Model<Project>,
     worktree_indices: HashMap<EntityId, WorktreeIndexHandle>,
     language_registry: Arc<LanguageRegistry>,
@@ -109,6 +231,7 @@ impl ProjectIndex {
     fn new(
         project: Model<Project>,
         db_connection: heed::Env,
+        embedding_cache: EmbeddingCache,
         embedding_provider: Arc<dyn EmbeddingProvider>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
@@ -116,6 +239,7 @@ impl ProjectIndex {
         let fs = project.read(cx).fs().clone();
         let mut this = ProjectIndex {
             db_connection,
+            embedding_cache,
             project: project.clone(),
             worktree_indices: HashMap::default(),
             language_registry,
@@ -163,6 +287,7 @@ impl ProjectIndex {
                 let worktree_index = WorktreeIndex::load(
                     worktree.clone(),
                     self.db_connection.clone(),
+                    self.embedding_cache,
                     self.language_registry.clone(),
                     self.fs.clone(),
                     self.embedding_provider.clone(),
@@ -269,6 +394,7 @@ struct WorktreeIndex {
     worktree: Model<Worktree>,
     db_connection: heed::Env,
     db: heed::Database<Str, SerdeBincode<EmbeddedFile>>,
+    embedding_cache: EmbeddingCache,
     language_registry: Arc<LanguageRegistry>,
     fs: Arc<dyn Fs>,
     embedding_provider: Arc<dyn EmbeddingProvider>,
@@ -281,6 +407,7 @@ impl WorktreeIndex {
     pub fn load(
         worktree: Model<Worktree>,
         db_connection: heed::Env,
+        embedding_cache: EmbeddingCache,
         language_registry: Arc<LanguageRegistry>,
         fs: Arc<dyn Fs>,
         embedding_provider: Arc<dyn EmbeddingProvider>,
@@ -306,6 +433,7 @@ impl WorktreeIndex {
                     worktree,
                     db_connection,
                     db,
+                    embedding_cache,
                     language_registry,
                     fs,
                     embedding_provider,
@@ -319,6 +447,7 @@ impl WorktreeIndex {
         worktree: Model<Worktree>,
         db_connection: heed::Env,
         db: heed::Database<Str, SerdeBincode<EmbeddedFile>>,
+        embedding_cache: EmbeddingCache,
         language_registry: Arc<LanguageRegistry>,
         fs: Arc<dyn Fs>,
         embedding_provider: Arc<dyn EmbeddingProvider>,
@@ -334,6 +463,7 @@ impl WorktreeIndex {
         Self {
             db_connection,
             db,
+            embedding_cache,
             worktree,
             language_registry,
             fs,
@@ -586,11 +716,26 @@ impl WorktreeIndex {
         cx: &AppContext,
     ) -> EmbedFiles {
         let embedding_provider = self.embedding_provider.clone();
+        let db_connection = self.db_connection.clone();
+        let embedding_cache = self.embedding_cache;
         let (embedded_files_tx, embedded_files_rx) = channel::bounded(512);
         let task = cx.background_executor().spawn(async move {
             let mut chunked_file_batches =
                 chunked_files.chunks_timeout(512, Duration::from_secs(2));
-            while let Some(chunked_files) = chunked_file_batches.next().await {
+            while let Some(mut chunked_files) = chunked_file_batches.next().await {
+                // Split the chunks that are too long for the model, so that embedding
+                // them doesn't fail.
+                if let Some(max_tokens) = embedding_provider.max_tokens() {
+                    for chunked_file in &mut chunked_files {
+                        chunked_file.chunks = split_oversized_chunks(
+                            &chunked_file.text,
+                            mem::take(&mut chunked_file.chunks),
+                            max_tokens,
+                            |text| embedding_provider.count_tokens(text),
+                        );
+                    }
+                }
+
                 // View the batch of files as a vec of chunks
                 // Flatten out to a vec of chunks that we can subdivide into batch sized pieces
                 // Once those are done, reassemble it back into which files they belong to
@@ -605,10 +750,13 @@ impl WorktreeIndex {
                     })
                     .collect::<Vec<_>>();
 
-                let mut embeddings = Vec::new();
-                for embedding_batch in chunks.chunks(embedding_provider.batch_size()) {
-                    embeddings.extend(embedding_provider.embed(embedding_batch).await?);
-                }
+                let embeddings = embed_with_cache(
+                    embedding_provider.as_ref(),
+                    &db_connection,
+                    embedding_cache,
+                    &chunks,
+                )
+                .await?;
 
                 let mut embeddings = embeddings.into_iter();
                 for chunked_file in chunked_files {
@@ -710,8 +858,9 @@ impl WorktreeIndex {
             let embedding_query_start = std::time::Instant::now();
             log::info!("Searching for {query}");
 
+            let truncated_query = truncate_to_max_tokens(embedding_provider.as_ref(), &query);
             let mut query_embeddings = embedding_provider
-                .embed(&[TextToEmbed::new(&query)])
+                .embed_query(&[TextToEmbed::new(truncated_query)])
                 .await?;
             let query_embedding = query_embeddings
                 .pop()
@@ -813,6 +962,75 @@ struct EmbeddedChunk {
     embedding: Embedding,
 }
 
+/// Embeds the chunks, reusing the embeddings of those found in the cache and adding
+/// the others to it.
+async fn embed_with_cache(
+    embedding_provider: &dyn EmbeddingProvider,
+    db_connection: &heed::Env,
+    embedding_cache: EmbeddingCache,
+    chunks: &[TextToEmbed<'_>],
+) -> Result<Vec<Embedding>> {
+    let mut embeddings = {
+        let txn = db_connection
+            .read_txn()
+            .context("failed to create read transaction")?;
+        chunks
+            .iter()
+            .map(|chunk| embedding_cache.get(&txn, &chunk.digest))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let uncached_chunks = chunks
+        .iter()
+        .zip(&embeddings)
+        .filter(|(_, embedding)| embedding.is_none())
+        .map(|(chunk, _)| TextToEmbed {
+            text: chunk.text,
+            digest: chunk.digest,
+        })
+        .collect::<Vec<_>>();
+    log::debug!(
+        "embedding {} chunks, {} of which were cached",
+        chunks.len(),
+        chunks.len() - uncached_chunks.len()
+    );
+
+    let mut new_embeddings = Vec::with_capacity(uncached_chunks.len());
+    for embedding_batch in uncached_chunks.chunks(embedding_provider.batch_size()) {
+        new_embeddings.extend(embedding_provider.embed(embedding_batch).await?);
+    }
+    anyhow::ensure!(
+        new_embeddings.len() == uncached_chunks.len(),
+        "got {} embeddings for {} chunks",
+        new_embeddings.len(),
+        uncached_chunks.len()
+    );
+
+    let mut txn = db_connection.write_txn()?;
+    let mut new_embeddings = new_embeddings.into_iter();
+    for (chunk, embedding) in chunks.iter().zip(&mut embeddings) {
+        if embedding.is_none() {
+            let new_embedding = new_embeddings.next().unwrap();
+            embedding_cache
+                .embeddings
+                .put(&mut txn, &chunk.digest, &new_embedding)?;
+            *embedding = Some(new_embedding);
+        }
+    }
+
+    // Both the new embeddings and the cached ones are marked as just used, so that
+    // the embeddings of chunks that are still around are the last to be evicted.
+    let mut generation = embedding_cache.next_generation(&txn)?;
+    for chunk in chunks {
+        embedding_cache.touch(&mut txn, &chunk.digest, generation)?;
+        generation += 1;
+    }
+    embedding_cache.evict(&mut txn)?;
+    txn.commit()?;
+
+    Ok(embeddings.into_iter().flatten().collect())
+}
+
 fn db_key_for_path(path: &Arc<Path>) -> String {
     path.to_string_lossy().replace('/', "\0")
 }
@@ -828,7 +1046,14 @@ mod tests {
     use language::language_settings::AllLanguageSettings;
     use project::Project;
     use settings::SettingsStore;
-    use std::{future, path::Path, sync::Arc};
+    use std::{
+        future,
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering::SeqCst},
+            Arc,
+        },
+    };
 
     fn init_test(cx: &mut TestAppContext) {
         _ = cx.update(|cx| {
@@ -877,6 +1102,84 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_embed_with_cache() {
+        struct CountingEmbeddingProvider(AtomicUsize);
+
+        impl EmbeddingProvider for CountingEmbeddingProvider {
+            fn embed<'a>(
+                &'a self,
+                texts: &'a [TextToEmbed<'a>],
+            ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+                self.0.fetch_add(texts.len(), SeqCst);
+                TestEmbeddingProvider.embed(texts)
+            }
+        }
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_connection = unsafe {
+            heed::EnvOpenOptions::new()
+                .max_dbs(3)
+                .open(temp_dir.path())
+                .unwrap()
+        };
+        let mut txn = db_connection.write_txn().unwrap();
+        let embedding_cache = EmbeddingCache::create(&db_connection, &mut txn, 3, 2).unwrap();
+        txn.commit().unwrap();
+
+        let provider = CountingEmbeddingProvider(AtomicUsize::new(0));
+        let chunks = [
+            TextToEmbed::new("garbage in"),
+            TextToEmbed::new("garbage out"),
+        ];
+        let embeddings = embed_with_cache(&provider, &db_connection, embedding_cache, &chunks)
+            .await
+            .unwrap();
+        assert_eq!(provider.0.load(SeqCst), 2);
+
+        // Only the chunk that wasn't embedded before is passed to the provider.
+        let chunks = [
+            TextToEmbed::new("garbage out"),
+            TextToEmbed::new("garbage in, garbage out"),
+            TextToEmbed::new("garbage in"),
+        ];
+        let cached_embeddings =
+            embed_with_cache(&provider, &db_connection, embedding_cache, &chunks)
+                .await
+                .unwrap();
+        assert_eq!(provider.0.load(SeqCst), 3);
+        assert_eq!(cached_embeddings.len(), 3);
+        assert_eq!(cached_embeddings[0], embeddings[1]);
+        assert_eq!(cached_embeddings[2], embeddings[0]);
+
+        // Once the cache is over its high-water mark, the least recently used
+        // embeddings are evicted until it's down to its low-water mark.
+        let chunks = [TextToEmbed::new("nothing in, nothing out")];
+        embed_with_cache(&provider, &db_connection, embedding_cache, &chunks)
+            .await
+            .unwrap();
+        let txn = db_connection.read_txn().unwrap();
+        let cached_texts = ["garbage in", "nothing in, nothing out"];
+        assert_eq!(embedding_cache.embeddings.len(&txn).unwrap(), 2);
+        for text in cached_texts {
+            let digest = TextToEmbed::new(text).digest;
+            assert!(embedding_cache.get(&txn, &digest).unwrap().is_some());
+        }
+
+        // Every remaining embedding was last used in exactly one generation.
+        let recency = embedding_cache
+            .recency
+            .iter(&txn)
+            .unwrap()
+            .map(|entry| entry.unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            recency,
+            cached_texts.map(|text| TextToEmbed::new(text).digest)
+        );
+        assert_eq!(embedding_cache.last_used.len(&txn).unwrap(), 2);
+    }
+
     #[gpui::test]
     async fn test_search(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -1,5 +1,6 @@
 mod keymap_file;
 mod settings_file;
+mod settings_format;
 mod settings_store;
 
 use gpui::AppContext;
@@ -9,6 +10,7 @@ use util::asset_str;
 
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
+pub use settings_format::*;
 pub use settings_store::{
     Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
 };
@@ -1,4 +1,4 @@
-use crate::{settings_store::SettingsStore, Settings};
+use crate::{settings_store::SettingsStore, Settings, SettingsFormat};
 use anyhow::{Context, Result};
 use fs::Fs;
 use futures::{channel::mpsc, StreamExt};
@@ -62,6 +62,51 @@ pub fn watch_config_file(
     rx
 }
 
+/// Watches the user's settings file, reporting its contents as JSON even when
+/// the settings are written in TOML. Switching formats takes effect immediately.
+pub fn watch_user_settings_file(
+    executor: &BackgroundExecutor,
+    fs: Arc<dyn Fs>,
+) -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded();
+    executor
+        .spawn(async move {
+            let json_events = fs.watch(&paths::SETTINGS, Duration::from_millis(100)).await;
+            let toml_events = fs
+                .watch(&paths::SETTINGS_TOML, Duration::from_millis(100))
+                .await;
+            let mut events = futures::stream::select(json_events, toml_events);
+
+            let contents = load_settings_as_json(fs.as_ref())
+                .await
+                .log_err()
+                .unwrap_or_default();
+            if tx.unbounded_send(contents).is_err() {
+                return;
+            }
+
+            loop {
+                if events.next().await.is_none() {
+                    break;
+                }
+
+                if let Some(contents) = load_settings_as_json(fs.as_ref()).await.log_err() {
+                    if tx.unbounded_send(contents).is_err() {
+                        break;
+                    }
+                }
+            }
+        })
+        .detach();
+    rx
+}
+
+async fn load_settings_as_json(fs: &dyn Fs) -> Result<String> {
+    let format = SettingsFormat::detect(fs).await;
+    let contents = fs.load(format.path()).await?;
+    format.to_json(&contents)
+}
+
 pub fn handle_settings_file_changes(
     mut user_settings_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
@@ -91,8 +136,8 @@ pub fn handle_settings_file_changes(
     .detach();
 }
 
-async fn load_settings(fs: &Arc<dyn Fs>) -> Result<String> {
-    match fs.load(&paths::SETTINGS).await {
+async fn load_settings(fs: &Arc<dyn Fs>, format: SettingsFormat) -> Result<String> {
+    match fs.load(format.path()).await {
         result @ Ok(_) => result,
         Err(err) => {
             if let Some(e) = err.downcast_ref::<std::io::Error>() {
@@ -111,11 +156,12 @@ pub fn update_settings_file<T: Settings>(
     update: impl 'static + Send + FnOnce(&mut T::FileContent),
 ) {
     cx.spawn(|cx| async move {
-        let old_text = load_settings(&fs).await?;
+        let format = SettingsFormat::detect(fs.as_ref()).await;
+        let old_text = load_settings(&fs, format).await?;
         let new_text = cx.read_global(|store: &SettingsStore, _cx| {
-            store.new_text_for_update::<T>(old_text, update)
-        })?;
-        let initial_path = paths::SETTINGS.as_path();
+            format.new_text_for_update::<T>(store, old_text, update)
+        })??;
+        let initial_path = format.path();
         if fs.is_file(initial_path).await {
             let resolved_path = fs.canonicalize(initial_path).await.with_context(|| {
                 format!("Failed to canonicalize settings path {:?}", initial_path)
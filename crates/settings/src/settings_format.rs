@@ -0,0 +1,301 @@
+use crate::{settings_store::parse_json_with_comments, Settings, SettingsStore};
+use anyhow::{anyhow, Context, Result};
+use fs::{Fs, RenameOptions};
+use serde_json::{Map, Value};
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, TableLike};
+use util::paths;
+
+/// The file format of the user's settings.
+///
+/// Settings are stored in `settings.json` (JSON with comments) unless a
+/// `settings.toml` exists next to it, in which case that file is used instead.
+/// Either way, the settings store only ever sees JSON, so TOML is converted
+/// when it's read and edits are translated back when it's written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SettingsFormat {
+    Json,
+    Toml,
+}
+
+impl SettingsFormat {
+    /// Returns the format of the user's settings, based on which file exists.
+    pub async fn detect(fs: &dyn Fs) -> Self {
+        if fs.is_file(&paths::SETTINGS_TOML).await {
+            Self::Toml
+        } else {
+            Self::Json
+        }
+    }
+
+    pub fn path(self) -> &'static Path {
+        match self {
+            Self::Json => paths::SETTINGS.as_path(),
+            Self::Toml => paths::SETTINGS_TOML.as_path(),
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Json => Self::Toml,
+            Self::Toml => Self::Json,
+        }
+    }
+
+    /// Parses settings written in this format.
+    pub fn parse(self, content: &str) -> Result<Value> {
+        match self {
+            Self::Json => parse_json_with_comments(content),
+            Self::Toml => Ok(toml::from_str(content)?),
+        }
+    }
+
+    /// Converts settings written in this format to the JSON expected by the [`SettingsStore`].
+    pub fn to_json(self, content: &str) -> Result<String> {
+        match self {
+            Self::Json => Ok(content.to_string()),
+            Self::Toml => Ok(serde_json::to_string(&self.parse(content)?)?),
+        }
+    }
+
+    /// Serializes settings in this format, for when there's no existing file to edit.
+    pub fn serialize(self, value: &Value) -> Result<String> {
+        match self {
+            Self::Json => Ok(serde_json::to_string_pretty(value)?),
+            Self::Toml => Ok(toml::to_string_pretty(&without_nulls(value))?),
+        }
+    }
+
+    /// Applies an update to settings written in this format, preserving the comments
+    /// and formatting of everything that wasn't changed.
+    pub fn new_text_for_update<T: Settings>(
+        self,
+        store: &SettingsStore,
+        old_text: String,
+        update: impl FnOnce(&mut T::FileContent),
+    ) -> Result<String> {
+        match self {
+            Self::Json => Ok(store.new_text_for_update::<T>(old_text, update)),
+            Self::Toml => {
+                let mut document = old_text
+                    .parse::<DocumentMut>()
+                    .context("failed to parse TOML settings")?;
+                let old_value = self.parse(&old_text)?;
+                let old_json = serde_json::to_string_pretty(&old_value)?;
+                let new_json = store.new_text_for_update::<T>(old_json, update);
+                let new_value: Value = parse_json_with_comments(&new_json)?;
+                update_toml_table(
+                    document.as_table_mut(),
+                    old_value.as_object().unwrap_or(&Map::new()),
+                    new_value
+                        .as_object()
+                        .ok_or_else(|| anyhow!("settings must be an object"))?,
+                )?;
+                Ok(document.to_string())
+            }
+        }
+    }
+}
+
+/// Returns the path of the user's settings file, for opening it in an editor.
+pub fn user_settings_path() -> &'static Path {
+    if paths::SETTINGS_TOML.is_file() {
+        SettingsFormat::Toml.path()
+    } else {
+        SettingsFormat::Json.path()
+    }
+}
+
+/// Rewrites the user's settings in the other format, keeping the original file
+/// next to it with a `.bak` extension. Comments aren't carried over.
+///
+/// Returns the path of the new settings file.
+pub async fn migrate_user_settings(fs: &dyn Fs) -> Result<&'static Path> {
+    let source = SettingsFormat::detect(fs).await;
+    let target = source.other();
+    if fs.is_file(target.path()).await {
+        return Err(anyhow!(
+            "cannot migrate settings to {:?} because that file already exists",
+            target.path()
+        ));
+    }
+
+    let value = if fs.is_file(source.path()).await {
+        source.parse(&fs.load(source.path()).await?)?
+    } else {
+        Value::Object(Map::new())
+    };
+    fs.atomic_write(target.path().to_path_buf(), target.serialize(&value)?)
+        .await
+        .with_context(|| format!("failed to write settings to {:?}", target.path()))?;
+
+    if fs.is_file(source.path()).await {
+        let mut backup_path = source.path().as_os_str().to_owned();
+        backup_path.push(".bak");
+        fs.rename(
+            source.path(),
+            Path::new(&backup_path),
+            RenameOptions {
+                overwrite: true,
+                ignore_if_exists: false,
+            },
+        )
+        .await?;
+    }
+
+    Ok(target.path())
+}
+
+/// Edits a TOML table in place so that it matches `new`, where `old` is the
+/// table's current contents. Entries that didn't change are left untouched.
+fn update_toml_table(
+    table: &mut dyn TableLike,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+) -> Result<()> {
+    for key in old.keys() {
+        if new.get(key).map_or(true, Value::is_null) {
+            table.remove(key);
+        }
+    }
+
+    for (key, new_value) in new {
+        let old_value = old.get(key);
+        if new_value.is_null() || old_value == Some(new_value) {
+            continue;
+        }
+
+        if let (Value::Object(new_object), Some(Value::Object(old_object))) = (new_value, old_value)
+        {
+            if let Some(child_table) = table.get_mut(key).and_then(Item::as_table_like_mut) {
+                update_toml_table(child_table, old_object, new_object)?;
+                continue;
+            }
+        }
+
+        table.insert(key, toml_item(new_value)?);
+    }
+
+    Ok(())
+}
+
+fn toml_item(value: &Value) -> Result<Item> {
+    let value = without_nulls(value);
+    if value.is_object() {
+        let document = toml::to_string(&value)?.parse::<DocumentMut>()?;
+        Ok(Item::Table(document.as_table().clone()))
+    } else {
+        let value = toml::Value::try_from(value)?;
+        Ok(Item::Value(value.to_string().parse()?))
+    }
+}
+
+/// TOML has no null, so null settings are left out, as though they weren't set.
+fn without_nulls(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key.clone(), without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(without_nulls).collect()),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SettingsSources;
+    use gpui::AppContext;
+    use schemars::JsonSchema;
+    use serde_derive::{Deserialize, Serialize};
+    use unindent::Unindent;
+
+    #[gpui::test]
+    fn test_toml_settings_update(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+
+        let old_text = r#"
+            # The settings for the current user.
+            [user]
+            name = "Max" # not their full name
+            age = 32
+
+            [other]
+            key = [1, 2]
+        "#
+        .unindent();
+
+        let new_text = SettingsFormat::Toml
+            .new_text_for_update::<UserSettings>(&store, old_text.clone(), |settings| {
+                settings.age = Some(33);
+                settings.staff = Some(true);
+            })
+            .unwrap();
+        assert_eq!(
+            new_text,
+            r#"
+                # The settings for the current user.
+                [user]
+                name = "Max" # not their full name
+                age = 33
+                staff = true
+
+                [other]
+                key = [1, 2]
+            "#
+            .unindent()
+        );
+
+        assert_eq!(
+            SettingsFormat::Toml.parse(&new_text).unwrap(),
+            serde_json::json!({
+                "user": { "name": "Max", "age": 33, "staff": true },
+                "other": { "key": [1, 2] },
+            })
+        );
+        assert_eq!(
+            SettingsFormat::Toml
+                .parse(
+                    &SettingsFormat::Toml
+                        .serialize(&serde_json::json!({
+                            "user": { "name": "Max", "age": null },
+                            "other": { "key": [1, 2] },
+                        }))
+                        .unwrap()
+                )
+                .unwrap(),
+            serde_json::json!({
+                "user": { "name": "Max" },
+                "other": { "key": [1, 2] },
+            })
+        );
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct UserSettings {
+        name: String,
+        age: u32,
+        staff: bool,
+    }
+
+    #[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
+    struct UserSettingsJson {
+        name: Option<String>,
+        age: Option<u32>,
+        staff: Option<bool>,
+    }
+
+    impl Settings for UserSettings {
+        const KEY: Option<&'static str> = Some("user");
+        type FileContent = UserSettingsJson;
+
+        fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+            sources.json_merge()
+        }
+    }
+}
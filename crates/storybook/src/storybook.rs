@@ -4,10 +4,14 @@ mod assets;
 mod stories;
 mod story_selector;
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::Parser;
 use dialoguer::FuzzySelect;
 use gpui::{
-    div, px, size, AnyView, AppContext, Bounds, Render, ViewContext, VisualContext, WindowOptions,
+    div, px, size, AnyView, AppContext, Bounds, Render, ViewContext, VisualContext, WindowHandle,
+    WindowOptions,
 };
 use log::LevelFilter;
 use project::Project;
@@ -34,8 +38,19 @@ struct Args {
     /// If not provided, the default theme will be used.
     #[arg(long)]
     theme: Option<String>,
+
+    /// Render the story offscreen and save a screenshot of it as a PNG at the given path,
+    /// instead of opening a window.
+    ///
+    /// Only supported on Linux.
+    #[arg(long, value_name = "PATH")]
+    snapshot: Option<PathBuf>,
 }
 
+/// How long to wait before taking a snapshot, so that assets loaded in the background,
+/// like images, can show up in it.
+const SNAPSHOT_DELAY: Duration = Duration::from_millis(500);
+
 fn main() {
     SimpleLogger::init(LevelFilter::Info, Default::default()).expect("could not initialize logger");
 
@@ -60,8 +75,15 @@ fn main() {
         StorySelector::Component(stories[selection])
     });
     let theme_name = args.theme.unwrap_or("One Dark".to_string());
+    let snapshot_path = args.snapshot;
 
-    gpui::App::new().with_assets(Assets).run(move |cx| {
+    let app = if snapshot_path.is_some() {
+        headless_app()
+    } else {
+        gpui::App::new()
+    };
+
+    app.with_assets(Assets).run(move |cx| {
         load_embedded_fonts(cx).unwrap();
 
         settings::init(cx);
@@ -83,7 +105,7 @@ fn main() {
 
         let size = size(px(1500.), px(780.));
         let bounds = Bounds::centered(None, size, cx);
-        let _window = cx.open_window(
+        let window = cx.open_window(
             WindowOptions {
                 bounds: Some(bounds),
                 ..Default::default()
@@ -97,9 +119,38 @@ fn main() {
         );
 
         cx.activate(true);
+
+        if let Some(snapshot_path) = snapshot_path {
+            save_snapshot(window, snapshot_path, cx);
+        }
     });
 }
 
+#[cfg(target_os = "linux")]
+fn headless_app() -> gpui::App {
+    gpui::App::headless()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn headless_app() -> gpui::App {
+    eprintln!("Snapshots are only supported on Linux");
+    std::process::exit(1);
+}
+
+fn save_snapshot(window: WindowHandle<StoryWrapper>, path: PathBuf, cx: &mut AppContext) {
+    cx.spawn(|mut cx| async move {
+        cx.background_executor().timer(SNAPSHOT_DELAY).await;
+        let image = window.update(&mut cx, |_, cx| cx.render_to_image());
+        let result = image.and_then(|image| Ok(image?.save(&path)?));
+        if let Err(error) = result {
+            eprintln!("Failed to save snapshot to {}: {error:?}", path.display());
+            std::process::exit(1);
+        }
+        cx.update(|cx| cx.quit()).ok();
+    })
+    .detach();
+}
+
 #[derive(Clone)]
 pub struct StoryWrapper {
     story: AnyView,
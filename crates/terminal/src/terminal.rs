@@ -50,7 +50,7 @@ use std::{
     ops::{Deref, Index, RangeInclusive},
     path::PathBuf,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
@@ -92,6 +92,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The terminal's task stopped running, see [`Terminal::task`] for its status.
+    TaskFinished,
 }
 
 #[derive(Clone, Debug)]
@@ -590,6 +592,7 @@ pub struct TaskState {
     pub command_label: String,
     pub status: TaskStatus,
     pub completion_rx: Receiver<()>,
+    pub started_at: Instant,
 }
 
 /// A status of the current terminal tab's task.
@@ -961,6 +964,23 @@ impl Terminal {
         }
     }
 
+    /// Returns the text of each of the search matches.
+    pub fn match_texts(&self) -> Vec<String> {
+        let term = self.term.lock();
+        self.matches
+            .iter()
+            .map(|search_match| term.bounds_to_string(*search_match.start(), *search_match.end()))
+            .collect()
+    }
+
+    /// Returns the text that is currently visible in the terminal, one line per row.
+    pub fn viewport_text(&self) -> String {
+        let term = self.term.lock();
+        let start = AlacPoint::new(Line(-(term.grid().display_offset() as i32)), Column(0));
+        let end = AlacPoint::new(start.line + term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     pub fn select_all(&mut self) {
         let term = self.term.lock();
         let start = AlacPoint::new(term.topmost_line(), Column(0));
@@ -1454,6 +1474,8 @@ impl Terminal {
             }
         };
 
+        cx.emit(Event::TaskFinished);
+
         let (task_line, command_line) = task_summary(task, error_code);
         // SAFETY: the invocation happens on non `TaskStatus::Running` tasks, once,
         // after either `AlacTermEvent::Exit` or `AlacTermEvent::ChildExit` events that are spawned
@@ -29,6 +29,7 @@ pub struct TerminalSettings {
     pub working_directory: WorkingDirectory,
     pub font_size: Option<Pixels>,
     pub font_family: Option<String>,
+    pub font_fallbacks: Option<Vec<String>>,
     pub line_height: TerminalLineHeight,
     pub font_features: Option<FontFeatures>,
     pub env: HashMap<String, String>,
@@ -109,6 +110,12 @@ pub struct TerminalSettingsContent {
     /// If this option is not included,
     /// the terminal will default to matching the buffer's font family.
     pub font_family: Option<String>,
+    /// The names of fonts to fall back to, in order, for characters that the
+    /// terminal's font family has no glyph for, such as icons from a Nerd Font.
+    ///
+    /// If this option is not included, the terminal will default to the
+    /// buffer's font fallbacks.
+    pub font_fallbacks: Option<Vec<String>>,
     /// Sets the terminal's line height.
     ///
     /// Default: comfortable
@@ -0,0 +1,314 @@
+use client::proto;
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, KeyDownEvent, Keystroke,
+    Model, Render, Subscription, ViewContext, VisualContext,
+};
+use project::Project;
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    Workspace,
+};
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        cx.subscribe(&project, handle_project_event).detach();
+    })
+    .detach();
+}
+
+fn handle_project_event(
+    workspace: &mut Workspace,
+    project: Model<Project>,
+    event: &project::Event,
+    cx: &mut ViewContext<Workspace>,
+) {
+    match event {
+        project::Event::TerminalShared(terminal_id) if project.read(cx).is_remote() => {
+            let terminal_id = *terminal_id;
+            let Some(title) = project
+                .read(cx)
+                .shared_terminal(terminal_id)
+                .map(|shared_terminal| shared_terminal.title.clone())
+            else {
+                return;
+            };
+
+            struct TerminalSharedNotification;
+
+            let workspace_handle = workspace.weak_handle();
+            workspace.show_notification(
+                NotificationId::identified::<TerminalSharedNotification>(terminal_id as usize),
+                cx,
+                |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(format!(
+                            "The host shared the terminal \"{title}\""
+                        ))
+                        .with_click_message("Open")
+                        .on_click(move |cx| {
+                            workspace_handle
+                                .update(cx, |workspace, cx| {
+                                    SharedTerminalView::open(workspace, terminal_id, cx)
+                                })
+                                .ok();
+                        })
+                    })
+                },
+            );
+        }
+        project::Event::TerminalControlRequested {
+            terminal_id,
+            peer_id,
+        } => {
+            let (terminal_id, peer_id) = (*terminal_id, *peer_id);
+            let Some(title) = project
+                .read(cx)
+                .shared_terminal(terminal_id)
+                .map(|shared_terminal| shared_terminal.title.clone())
+            else {
+                return;
+            };
+            let login = collaborator_login(workspace, peer_id, cx)
+                .unwrap_or_else(|| "A collaborator".into());
+
+            struct TerminalControlRequestNotification;
+
+            workspace.show_notification(
+                NotificationId::identified::<TerminalControlRequestNotification>(
+                    terminal_id as usize,
+                ),
+                cx,
+                |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(format!(
+                            "{login} requested control of the terminal \"{title}\""
+                        ))
+                        .with_click_message("Grant Control")
+                        .on_click(move |cx| {
+                            project.update(cx, |project, cx| {
+                                project.grant_terminal_control(terminal_id, peer_id, cx)
+                            });
+                        })
+                    })
+                },
+            );
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn collaborator_login(
+    workspace: &Workspace,
+    peer_id: proto::PeerId,
+    cx: &AppContext,
+) -> Option<SharedString> {
+    let user_id = workspace
+        .project()
+        .read(cx)
+        .collaborators()
+        .get(&peer_id)?
+        .user_id;
+    let user = workspace.user_store().read(cx).get_cached_user(user_id)?;
+    Some(user.github_login.clone().into())
+}
+
+/// A guest's view of a terminal that the host of the project shared. It is
+/// read-only unless the host granted this guest control of the terminal.
+pub struct SharedTerminalView {
+    project: Model<Project>,
+    terminal_id: u64,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl SharedTerminalView {
+    pub fn open(workspace: &mut Workspace, terminal_id: u64, cx: &mut ViewContext<Workspace>) {
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).terminal_id == terminal_id);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| Self::new(project, terminal_id, cx));
+        workspace.add_item_to_active_pane(Box::new(view), cx);
+    }
+
+    fn new(project: Model<Project>, terminal_id: u64, cx: &mut ViewContext<Self>) -> Self {
+        let subscription = cx.subscribe(&project, move |_, _, event, cx| match event {
+            project::Event::SharedTerminalUpdated(id) if *id == terminal_id => {
+                cx.emit(ItemEvent::UpdateTab);
+                cx.notify();
+            }
+            project::Event::TerminalUnshared(id) if *id == terminal_id => {
+                cx.emit(ItemEvent::CloseItem)
+            }
+            project::Event::DisconnectedFromHost => cx.emit(ItemEvent::CloseItem),
+            _ => {}
+        });
+
+        Self {
+            project,
+            terminal_id,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        if !project.controls_shared_terminal(self.terminal_id) {
+            return;
+        }
+
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.platform {
+            return;
+        }
+        let input = match &keystroke.ime_key {
+            Some(text) if !modifiers.control && !modifiers.alt => {
+                proto::shared_terminal_input::Variant::Text(text.clone())
+            }
+            _ => proto::shared_terminal_input::Variant::Keystroke(keystroke_source(keystroke)),
+        };
+        project
+            .send_shared_terminal_input(self.terminal_id, input)
+            .log_err();
+        cx.stop_propagation();
+    }
+}
+
+/// Formats a keystroke in the syntax understood by [`Keystroke::parse`].
+fn keystroke_source(keystroke: &Keystroke) -> String {
+    let mut source = String::new();
+    for (pressed, modifier) in [
+        (keystroke.modifiers.control, "ctrl-"),
+        (keystroke.modifiers.alt, "alt-"),
+        (keystroke.modifiers.shift, "shift-"),
+        (keystroke.modifiers.function, "fn-"),
+    ] {
+        if pressed {
+            source.push_str(modifier);
+        }
+    }
+    source.push_str(&keystroke.key);
+    source
+}
+
+impl EventEmitter<ItemEvent> for SharedTerminalView {}
+
+impl FocusableView for SharedTerminalView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SharedTerminalView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let in_control = project.controls_shared_terminal(self.terminal_id);
+        let content = project
+            .shared_terminal(self.terminal_id)
+            .map(|shared_terminal| shared_terminal.content.clone())
+            .unwrap_or_default();
+        let terminal_id = self.terminal_id;
+        let settings = ThemeSettings::get_global(cx);
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::key_down))
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(if in_control {
+                            "You are in control of this terminal"
+                        } else {
+                            "Read-only"
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .when(!in_control, |this| {
+                        this.child(
+                            Button::new("request-control", "Request Control")
+                                .label_size(LabelSize::Small)
+                                .tooltip(|cx| {
+                                    Tooltip::text(
+                                        "Ask the host to let you type in this terminal",
+                                        cx,
+                                    )
+                                })
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.project
+                                        .read(cx)
+                                        .request_terminal_control(terminal_id)
+                                        .log_err();
+                                })),
+                        )
+                    }),
+            )
+            .child(
+                v_flex()
+                    .id("shared-terminal-content")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .p_2()
+                    .font_family(settings.buffer_font.family.clone())
+                    .text_size(settings.buffer_font_size(cx))
+                    .text_color(cx.theme().colors().editor_foreground)
+                    .children(
+                        content
+                            .lines()
+                            .map(|line| div().min_h(rems(1.)).child(line.to_string())),
+                    ),
+            )
+    }
+}
+
+impl Item for SharedTerminalView {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let title = self
+            .project
+            .read(cx)
+            .shared_terminal(self.terminal_id)
+            .map(|shared_terminal| shared_terminal.title.clone())
+            .unwrap_or_default();
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::Terminal))
+            .child(Label::new(title).color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            }))
+            .into_any()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -1,4 +1,5 @@
 mod persistence;
+pub mod shared_terminal_view;
 pub mod terminal_element;
 pub mod terminal_panel;
 
@@ -6,13 +7,15 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, Styled, Subscription, Task, View, VisualContext, WeakView,
+    actions, anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, Styled, Subscription, Task, View, VisualContext,
+    WeakView,
 };
 use language::Bias;
+use notifications::{NotificationCenter, NotificationSource};
 use persistence::TERMINAL_DB;
-use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
+use project::{search::SearchQuery, Fs, LocalWorktree, Location, Metadata, Project};
 use settings::SettingsStore;
 use terminal::{
     alacritty_terminal::{
@@ -23,6 +26,7 @@ use terminal::{
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, TaskStatus, Terminal,
 };
 use terminal_element::TerminalElement;
+use theme::ThemeSettings;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
@@ -53,6 +57,9 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 
+/// How long a task has to run for its completion to be sent to the notification center.
+const LONG_RUNNING_TASK_DURATION: Duration = Duration::from_secs(10);
+
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
 pub struct ScrollTerminal(pub i32);
@@ -65,18 +72,51 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(
+    terminal,
+    [
+        ShareWithCollaborators,
+        StopSharing,
+        RevokeControl,
+        OpenMatchLocations
+    ]
+);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
+    shared_terminal_view::init(cx);
 
     register_deserializable_item::<TerminalView>(cx);
 
+    set_font_fallbacks(cx);
+    cx.observe_global::<SettingsStore>(set_font_fallbacks)
+        .detach();
+
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(TerminalView::deploy);
     })
     .detach();
 }
 
+/// Configures the fonts that the text system falls back to for characters
+/// missing from the terminal's font, when it differs from the buffer font.
+fn set_font_fallbacks(cx: &mut AppContext) {
+    let terminal_settings = TerminalSettings::get_global(cx);
+    let Some(font_family) = terminal_settings.font_family.clone() else {
+        return;
+    };
+    let font_fallbacks = match &terminal_settings.font_fallbacks {
+        Some(font_fallbacks) => font_fallbacks
+            .iter()
+            .map(|family| SharedString::from(family.clone()))
+            .collect(),
+        None => ThemeSettings::get_global(cx).buffer_font_fallbacks.clone(),
+    };
+    cx.text_system()
+        .set_font_fallbacks(font_family, font_fallbacks);
+}
+
 ///A terminal view, maintains the PTY's file handles and communicates with the terminal
 pub struct TerminalView {
     terminal: Model<Terminal>,
@@ -195,9 +235,38 @@ impl TerminalView {
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        let project = self.project(cx);
+        let shared_terminal = project.as_ref().and_then(|project| {
+            let project = project.read(cx);
+            let terminal_id = project.shared_terminal_id(&self.terminal)?;
+            project.shared_terminal(terminal_id)
+        });
+        let can_share = project
+            .as_ref()
+            .map_or(false, |project| project.read(cx).is_shared());
+        let is_shared = shared_terminal.is_some();
+        let has_controller =
+            shared_terminal.map_or(false, |terminal| terminal.controller.is_some());
+        let has_matches = !self.terminal.read(cx).matches.is_empty();
+
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.action("Clear", Box::new(Clear))
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
+                .when(has_matches, |menu| {
+                    menu.separator()
+                        .action("Open Matched Paths", Box::new(OpenMatchLocations))
+                })
+                .when(can_share && !is_shared, |menu| {
+                    menu.separator()
+                        .action("Share with Collaborators", Box::new(ShareWithCollaborators))
+                })
+                .when(is_shared, |menu| {
+                    menu.separator()
+                        .action("Stop Sharing", Box::new(StopSharing))
+                        .when(has_controller, |menu| {
+                            menu.action("Revoke Control", Box::new(RevokeControl))
+                        })
+                })
         });
 
         cx.focus_view(&context_menu);
@@ -589,10 +658,35 @@ fn subscribe_for_terminal_events(
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => cx.emit(SearchEvent::ActiveMatchChanged),
+            Event::TaskFinished => {
+                let notification = finished_task_notification(this.terminal.read(cx));
+                if let Some((text, notification_center)) =
+                    notification.zip(NotificationCenter::try_global(cx))
+                {
+                    notification_center.update(cx, |notification_center, cx| {
+                        notification_center.push(NotificationSource::Tasks, text, cx);
+                    });
+                }
+            }
         });
     vec![terminal_subscription, terminal_events_subscription]
 }
 
+/// The text of the notification about a long-running task that finished, if there is one.
+fn finished_task_notification(terminal: &Terminal) -> Option<String> {
+    let task = terminal.task()?;
+    if task.started_at.elapsed() < LONG_RUNNING_TASK_DURATION {
+        return None;
+    }
+
+    let outcome = match task.status {
+        TaskStatus::Completed { success: true } => "finished successfully",
+        TaskStatus::Completed { success: false } => "failed",
+        TaskStatus::Running | TaskStatus::Unknown => "finished",
+    };
+    Some(format!("Task `{}` {outcome}", task.label))
+}
+
 fn possible_open_paths_metadata(
     fs: Arc<dyn Fs>,
     row: Option<u32>,
@@ -676,6 +770,17 @@ fn possible_open_targets(
     possible_open_paths_metadata(fs, row, column, potential_abs_paths, cx)
 }
 
+/// Strips the quotes, brackets and punctuation that paths are often surrounded by
+/// in a program's output, like `'src/main.rs',` or `(src/main.rs:10)`.
+fn trim_path_punctuation(text: &str) -> &str {
+    text.trim().trim_matches(|c| {
+        matches!(
+            c,
+            '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '<' | '>' | ','
+        )
+    })
+}
+
 fn regex_to_literal(regex: &str) -> String {
     regex
         .chars()
@@ -699,6 +804,120 @@ pub fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<Re
 }
 
 impl TerminalView {
+    fn project(&self, cx: &AppContext) -> Option<Model<Project>> {
+        self.workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+    }
+
+    fn share_with_collaborators(&mut self, _: &ShareWithCollaborators, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .project()
+                    .update(cx, |project, cx| project.share_terminal(&terminal, cx))
+                    .notify_err(workspace, cx);
+            })
+            .ok();
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn stop_sharing(&mut self, _: &StopSharing, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        project.update(cx, |project, cx| {
+            if let Some(terminal_id) = project.shared_terminal_id(&self.terminal) {
+                project.unshare_terminal(terminal_id, cx);
+            }
+        });
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn revoke_control(&mut self, _: &RevokeControl, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        project.update(cx, |project, cx| {
+            if let Some(terminal_id) = project.shared_terminal_id(&self.terminal) {
+                project.revoke_terminal_control(terminal_id, cx);
+            }
+        });
+    }
+
+    /// Opens the files that the search matches refer to, like `src/main.rs:10:5` in
+    /// a compiler's output, in a multibuffer with an excerpt at each position.
+    /// Matches that aren't paths to files of the project are left out.
+    fn open_match_locations(&mut self, _: &OpenMatchLocations, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        let fs = project.read(cx).fs().clone();
+        let terminal = self.terminal.read(cx);
+        let cwd = terminal.get_cwd();
+        let mut seen = HashSet::default();
+        let match_texts = terminal
+            .match_texts()
+            .into_iter()
+            .map(|text| trim_path_punctuation(&text).to_string())
+            .filter(|text| !text.is_empty() && seen.insert(text.clone()))
+            .collect::<Vec<_>>();
+        let open_targets = match_texts
+            .iter()
+            .map(|text| possible_open_targets(fs.clone(), &self.workspace, &cwd, text, cx))
+            .collect::<Vec<_>>();
+
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let mut locations = Vec::new();
+            for open_targets in open_targets {
+                for (path, metadata) in open_targets.await {
+                    if metadata.is_dir {
+                        continue;
+                    }
+                    let Some(buffer) = project
+                        .update(&mut cx, |project, cx| {
+                            project.open_local_buffer(&path.path_like, cx)
+                        })?
+                        .await
+                        .log_err()
+                    else {
+                        continue;
+                    };
+                    let range = buffer.update(&mut cx, |buffer, _| {
+                        let point = buffer.clip_point(
+                            language::Point::new(
+                                path.row.unwrap_or(1).saturating_sub(1),
+                                path.column.unwrap_or(1).saturating_sub(1),
+                            ),
+                            Bias::Left,
+                        );
+                        let anchor = buffer.anchor_before(point);
+                        anchor..anchor
+                    })?;
+                    locations.push(Location { buffer, range });
+                }
+            }
+            if locations.is_empty() {
+                return anyhow::Ok(());
+            }
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let replica_id = workspace.project().read(cx).replica_id();
+                Editor::open_locations_in_multibuffer(
+                    workspace,
+                    locations,
+                    replica_id,
+                    "Terminal Matches".to_string(),
+                    false,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
         self.clear_bell(cx);
         self.pause_cursor_blinking(cx);
@@ -743,6 +962,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::clear))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::share_with_collaborators))
+            .on_action(cx.listener(TerminalView::stop_sharing))
+            .on_action(cx.listener(TerminalView::revoke_control))
+            .on_action(cx.listener(TerminalView::open_match_locations))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -800,6 +1023,12 @@ impl Item for TerminalView {
             },
             None => IconName::Terminal,
         };
+        let is_shared = self.project(cx).map_or(false, |project| {
+            project
+                .read(cx)
+                .shared_terminal_id(&self.terminal)
+                .is_some()
+        });
         h_flex()
             .gap_2()
             .child(Icon::new(icon))
@@ -808,6 +1037,13 @@ impl Item for TerminalView {
             } else {
                 Color::Muted
             }))
+            .when(is_shared, |this| {
+                this.child(
+                    Icon::new(IconName::Public)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+            })
             .into_any()
     }
 
@@ -1292,4 +1528,14 @@ mod tests {
     fn empty_string_stays_empty() {
         assert_eq!(regex_to_literal(""), "".to_string());
     }
+
+    #[test]
+    fn trims_punctuation_around_paths() {
+        assert_eq!(trim_path_punctuation("'src/main.rs',"), "src/main.rs");
+        assert_eq!(
+            trim_path_punctuation("(src/main.rs:10:5)"),
+            "src/main.rs:10:5"
+        );
+        assert_eq!(trim_path_punctuation("./src/main.rs"), "./src/main.rs");
+    }
 }
@@ -0,0 +1,142 @@
+use crate::{LineEnding, Rope, LINE_SEPARATORS_REGEX};
+use std::{borrow::Cow, mem, ops::Range, str};
+
+/// How many bytes of a file to read before handing them to a [`TextLoader`].
+pub const LOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The contents of a file decoded by a [`TextLoader`].
+pub struct LoadedText {
+    /// The decoded text, with every line ending normalized to `\n`.
+    pub text: Rope,
+    /// The line ending the file used, so it can be restored when saving.
+    pub line_ending: LineEnding,
+    /// The byte ranges of the file that weren't valid UTF-8, each of which was
+    /// replaced with a single U+FFFD replacement character.
+    pub invalid_ranges: Vec<Range<usize>>,
+}
+
+impl LoadedText {
+    /// Returns whether any of the file's contents were replaced while decoding it,
+    /// meaning that saving the text won't reproduce the original bytes.
+    pub fn is_lossy(&self) -> bool {
+        !self.invalid_ranges.is_empty()
+    }
+}
+
+/// Decodes a file into a [`Rope`] as its bytes are read, so large files never have
+/// to be held in memory as a single string.
+///
+/// Chunks can be split anywhere, including in the middle of a UTF-8 sequence or
+/// between the `\r` and `\n` of a line ending.
+#[derive(Default)]
+pub struct TextLoader {
+    text: Rope,
+    line_ending: Option<LineEnding>,
+    invalid_ranges: Vec<Range<usize>>,
+    /// The bytes at the end of the last chunk that start an unfinished UTF-8 sequence.
+    incomplete_sequence: Vec<u8>,
+    /// Whether the last chunk ended with a `\r`, which has already been written as
+    /// a newline, so a `\n` at the start of the next chunk needs to be skipped.
+    pending_carriage_return: bool,
+    /// The offset in the file of the next byte that will be pushed.
+    offset: usize,
+}
+
+impl TextLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the next chunk of the file.
+    pub fn push(&mut self, bytes: &[u8]) {
+        let start_offset = self.offset - self.incomplete_sequence.len();
+        self.offset += bytes.len();
+
+        let bytes = if self.incomplete_sequence.is_empty() {
+            Cow::Borrowed(bytes)
+        } else {
+            let mut incomplete_sequence = mem::take(&mut self.incomplete_sequence);
+            incomplete_sequence.extend_from_slice(bytes);
+            Cow::Owned(incomplete_sequence)
+        };
+
+        let mut ix = 0;
+        while ix < bytes.len() {
+            match str::from_utf8(&bytes[ix..]) {
+                Ok(text) => {
+                    self.push_str(text);
+                    break;
+                }
+                Err(error) => {
+                    let valid_end = ix + error.valid_up_to();
+                    if let Ok(text) = str::from_utf8(&bytes[ix..valid_end]) {
+                        self.push_str(text);
+                    }
+
+                    match error.error_len() {
+                        Some(len) => {
+                            self.push_invalid(
+                                start_offset + valid_end..start_offset + valid_end + len,
+                            );
+                            ix = valid_end + len;
+                        }
+                        None => {
+                            self.incomplete_sequence = bytes[valid_end..].to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finish decoding the file. A UTF-8 sequence that's still unfinished is
+    /// replaced, and the line ending defaults to the platform's when the file
+    /// has no newlines.
+    pub fn finish(mut self) -> LoadedText {
+        if !self.incomplete_sequence.is_empty() {
+            let start = self.offset - self.incomplete_sequence.len();
+            self.push_invalid(start..self.offset);
+        }
+
+        LoadedText {
+            text: self.text,
+            line_ending: self.line_ending.unwrap_or_default(),
+            invalid_ranges: self.invalid_ranges,
+        }
+    }
+
+    fn push_invalid(&mut self, range: Range<usize>) {
+        self.invalid_ranges.push(range);
+        self.push_str(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]));
+    }
+
+    fn push_str(&mut self, mut text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if mem::take(&mut self.pending_carriage_return) {
+            if let Some(rest) = text.strip_prefix('\n') {
+                self.line_ending.get_or_insert(LineEnding::Windows);
+                text = rest;
+            }
+        }
+
+        if self.line_ending.is_none() {
+            if let Some(ix) = text.find('\n') {
+                self.line_ending = Some(if ix > 0 && text.as_bytes()[ix - 1] == b'\r' {
+                    LineEnding::Windows
+                } else {
+                    LineEnding::Unix
+                });
+            }
+        }
+
+        self.pending_carriage_return = text.ends_with('\r');
+        match LINE_SEPARATORS_REGEX.replace_all(text, "\n") {
+            Cow::Borrowed(text) => self.text.push(text),
+            Cow::Owned(text) => self.text.push(&text),
+        }
+    }
+}
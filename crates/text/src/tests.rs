@@ -181,6 +181,36 @@ fn test_line_endings() {
     buffer.check_invariants();
 }
 
+#[test]
+fn test_text_loader() {
+    let bytes = b"one\r\ntwo\rthree\xF0\x9F\x8D\x90 \xFF four\r\n\xE2\x9C";
+    for split_ix in 0..=bytes.len() {
+        let mut loader = TextLoader::new();
+        loader.push(&bytes[..split_ix]);
+        loader.push(&bytes[split_ix..]);
+        let loaded = loader.finish();
+        assert_eq!(
+            loaded.text.to_string(),
+            "one\ntwo\nthree🍐 \u{FFFD} four\n\u{FFFD}",
+            "split at {split_ix}"
+        );
+        assert_eq!(loaded.line_ending, LineEnding::Windows);
+        assert_eq!(loaded.invalid_ranges, [19..20, 27..29]);
+    }
+
+    let mut loader = TextLoader::new();
+    loader.push(b"one\r");
+    loader.push(b"two\nthree");
+    let loaded = loader.finish();
+    assert_eq!(loaded.text.to_string(), "one\ntwo\nthree");
+    assert_eq!(loaded.line_ending, LineEnding::Unix);
+    assert!(!loaded.is_lossy());
+
+    let loaded = TextLoader::new().finish();
+    assert_eq!(loaded.text.to_string(), "");
+    assert_eq!(loaded.line_ending, LineEnding::default());
+}
+
 #[test]
 fn test_line_len() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -1,4 +1,5 @@
 mod anchor;
+mod loader;
 pub mod locator;
 #[cfg(any(test, feature = "test-support"))]
 pub mod network;
@@ -14,6 +15,7 @@ pub use anchor::*;
 use anyhow::{anyhow, Context as _, Result};
 pub use clock::ReplicaId;
 use collections::{HashMap, HashSet};
+pub use loader::*;
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
@@ -520,8 +522,23 @@ impl Buffer {
     pub fn new(replica_id: u16, remote_id: BufferId, mut base_text: String) -> Buffer {
         let line_ending = LineEnding::detect(&base_text);
         LineEnding::normalize(&mut base_text);
+        Self::new_normalized(
+            replica_id,
+            remote_id,
+            line_ending,
+            Rope::from(base_text.as_ref()),
+        )
+    }
 
-        let history = History::new(Rope::from(base_text.as_ref()));
+    /// Create a buffer from text whose line endings have already been normalized
+    /// to `\n`, such as the text produced by a [`TextLoader`].
+    pub fn new_normalized(
+        replica_id: u16,
+        remote_id: BufferId,
+        line_ending: LineEnding,
+        normalized: Rope,
+    ) -> Buffer {
+        let history = History::new(normalized);
         let mut fragments = SumTree::new();
         let mut insertions = SumTree::new();
 
@@ -1169,6 +1186,14 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// The transactions that can be undone, from the oldest to the most recent.
+    pub fn undo_history(&self) -> impl Iterator<Item = &Transaction> {
+        self.history
+            .undo_stack
+            .iter()
+            .map(|entry| &entry.transaction)
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{
+    try_parse_color, Appearance, HighlightStyleContent, PlayerColorContent, ThemeStyleContent,
+};
+
+/// The keys of a theme style whose values aren't colors.
+const NON_COLOR_KEYS: &[&str] = &["background.appearance", "accents", "players", "syntax"];
+
+/// The maximum edit distance between an unknown key and a known key for the
+/// known key to be suggested in its place.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// Overrides for the styles of the current theme, applied depending on its appearance.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeOverridesContent {
+    /// The overrides to apply when the current theme is a light theme.
+    #[serde(default)]
+    pub light: Option<ThemeStyleOverridesContent>,
+    /// The overrides to apply when the current theme is a dark theme.
+    #[serde(default)]
+    pub dark: Option<ThemeStyleOverridesContent>,
+}
+
+impl ThemeOverridesContent {
+    /// Returns the overrides for themes with the given appearance.
+    pub fn for_appearance(&self, appearance: Appearance) -> Option<&ThemeStyleOverridesContent> {
+        match appearance {
+            Appearance::Light => self.light.as_ref(),
+            Appearance::Dark => self.dark.as_ref(),
+        }
+    }
+}
+
+/// Overrides for the colors, syntax styles, and player colors of a theme.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ThemeStyleOverridesContent {
+    #[serde(flatten)]
+    pub style: ThemeStyleContent,
+
+    /// The problems found in the overrides, such as unknown keys or invalid colors.
+    #[serde(skip)]
+    pub errors: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for ThemeStyleOverridesContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let style = ThemeStyleContent::deserialize(&value).map_err(D::Error::custom)?;
+        Ok(Self {
+            style,
+            errors: validate_theme_style(&value),
+        })
+    }
+}
+
+impl JsonSchema for ThemeStyleOverridesContent {
+    fn schema_name() -> String {
+        "ThemeStyleOverridesContent".to_owned()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema: SchemaObject = ThemeStyleContent::json_schema(generator).into_object();
+        // Flag unknown keys in the settings file, as they would otherwise be silently ignored.
+        schema.object().additional_properties = Some(Box::new(Schema::Bool(false)));
+        schema.into()
+    }
+}
+
+/// Returns a description of each problem in the given theme style overrides.
+fn validate_theme_style(value: &Value) -> Vec<String> {
+    let Value::Object(style) = value else {
+        return Vec::new();
+    };
+
+    let style_keys = keys_of(ThemeStyleContent::default());
+    let highlight_style_keys = keys_of(HighlightStyleContent::default());
+    let player_color_keys = keys_of(PlayerColorContent {
+        cursor: None,
+        background: None,
+        selection: None,
+    });
+
+    let mut errors = Vec::new();
+    for (key, value) in style {
+        if !style_keys.contains(key.as_str()) {
+            errors.push(unknown_key_error(key, key, &style_keys));
+            continue;
+        }
+
+        match key.as_str() {
+            "syntax" => {
+                let Value::Object(highlight_styles) = value else {
+                    continue;
+                };
+                for (syntax_name, highlight_style) in highlight_styles {
+                    let Value::Object(highlight_style) = highlight_style else {
+                        continue;
+                    };
+                    for (key, value) in highlight_style {
+                        let path = format!("syntax.{syntax_name}.{key}");
+                        if !highlight_style_keys.contains(key.as_str()) {
+                            errors.push(unknown_key_error(&path, key, &highlight_style_keys));
+                        } else if key == "color" {
+                            errors.extend(invalid_color_error(&path, value));
+                        }
+                    }
+                }
+            }
+            "players" => {
+                let Value::Array(players) = value else {
+                    continue;
+                };
+                for (ix, player) in players.iter().enumerate() {
+                    let Value::Object(player) = player else {
+                        continue;
+                    };
+                    for (key, value) in player {
+                        let path = format!("players[{ix}].{key}");
+                        if player_color_keys.contains(key.as_str()) {
+                            errors.extend(invalid_color_error(&path, value));
+                        } else {
+                            errors.push(unknown_key_error(&path, key, &player_color_keys));
+                        }
+                    }
+                }
+            }
+            key if !NON_COLOR_KEYS.contains(&key) => {
+                errors.extend(invalid_color_error(key, value));
+            }
+            _ => {}
+        }
+    }
+    errors
+}
+
+/// Returns the keys of the given content, when serialized.
+fn keys_of(content: impl Serialize) -> HashSet<String> {
+    match serde_json::to_value(content) {
+        Ok(Value::Object(object)) => object.into_iter().map(|(key, _)| key).collect(),
+        _ => HashSet::default(),
+    }
+}
+
+fn unknown_key_error(path: &str, key: &str, known_keys: &HashSet<String>) -> String {
+    let suggestion = known_keys
+        .iter()
+        .map(|known_key| (edit_distance(key, known_key), known_key))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min();
+    match suggestion {
+        Some((_, known_key)) => format!("unknown key `{path}`, did you mean `{known_key}`?"),
+        None => format!("unknown key `{path}`"),
+    }
+}
+
+fn invalid_color_error(path: &str, value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(color) if try_parse_color(color).is_ok() => None,
+        _ => Some(format!(
+            "invalid color {value} for `{path}`, expected a hex color such as \"#rrggbbaa\""
+        )),
+    }
+}
+
+/// Returns the Levenshtein distance between the given strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous_diagonal = distances[0];
+        distances[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_diagonal + usize::from(a_char != *b_char);
+            previous_diagonal = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(distances[j + 1] + 1);
+        }
+    }
+    distances[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_theme_style_overrides_validation() {
+        let overrides: ThemeStyleOverridesContent = serde_json::from_value(json!({
+            "editor.background": "#1e1e1eff",
+            "editor.backgroud": "#1e1e1eff",
+            "text": "not a color",
+            "syntax": {
+                "comment": { "color": "#6a9955ff", "font_styel": "italic" }
+            },
+            "players": [{ "cursor": "#0000ffff", "curser": "#0000ffff" }],
+            "totally_unknown_key": true
+        }))
+        .unwrap();
+
+        assert_eq!(
+            overrides.style.colors.editor_background.as_deref(),
+            Some("#1e1e1eff")
+        );
+        assert_eq!(
+            overrides.style.syntax["comment"].color.as_deref(),
+            Some("#6a9955ff")
+        );
+
+        let mut errors = overrides.errors.clone();
+        errors.sort();
+        assert_eq!(
+            errors,
+            [
+                "invalid color \"not a color\" for `text`, expected a hex color such as \"#rrggbbaa\"",
+                "unknown key `editor.backgroud`, did you mean `editor.background`?",
+                "unknown key `players[0].curser`, did you mean `cursor`?",
+                "unknown key `syntax.comment.font_styel`, did you mean `font_style`?",
+                "unknown key `totally_unknown_key`",
+            ]
+        );
+    }
+}
@@ -81,6 +81,10 @@ pub struct ThemeStyleContent {
     #[serde(flatten, default)]
     pub status: StatusColorsContent,
 
+    /// The colors that are cycled through for nested elements, such as rainbow brackets.
+    #[serde(default)]
+    pub accents: Vec<AccentContent>,
+
     #[serde(default)]
     pub players: Vec<PlayerColorContent>,
 
@@ -1196,6 +1200,9 @@ impl StatusColorsContent {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AccentContent(pub Option<String>);
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PlayerColorContent {
     pub cursor: Option<String>,
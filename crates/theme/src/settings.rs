@@ -1,15 +1,17 @@
 use crate::one_themes::one_dark;
-use crate::{Appearance, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{
+    Appearance, SyntaxTheme, Theme, ThemeOverridesContent, ThemeRegistry, ThemeStyleContent,
+};
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use gpui::{
-    px, AppContext, Font, FontFeatures, FontStyle, FontWeight, Global, Pixels, Subscription,
-    ViewContext,
+    px, AppContext, Font, FontFeatures, FontStyle, FontWeight, Global, Pixels, SharedString,
+    Subscription, ViewContext,
 };
 use refineable::Refineable;
 use schemars::{
     gen::SchemaGenerator,
-    schema::{InstanceType, Schema, SchemaObject},
+    schema::{ArrayValidation, InstanceType, Schema, SchemaObject},
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
@@ -25,12 +27,15 @@ const MIN_LINE_HEIGHT: f32 = 1.0;
 pub struct ThemeSettings {
     pub ui_font_size: Pixels,
     pub ui_font: Font,
+    pub ui_font_fallbacks: Vec<SharedString>,
     pub buffer_font: Font,
+    pub buffer_font_fallbacks: Vec<SharedString>,
     pub buffer_font_size: Pixels,
     pub buffer_line_height: BufferLineHeight,
     pub theme_selection: Option<ThemeSelection>,
     pub active_theme: Arc<Theme>,
     pub theme_overrides: Option<ThemeStyleContent>,
+    pub appearance_theme_overrides: Option<ThemeOverridesContent>,
 }
 
 impl ThemeSettings {
@@ -167,6 +172,10 @@ pub struct ThemeSettingsContent {
     /// The OpenType features to enable for text in the UI.
     #[serde(default)]
     pub ui_font_features: Option<FontFeatures>,
+    /// The names of fonts to fall back to, in order, for characters that the
+    /// UI font has no glyph for.
+    #[serde(default)]
+    pub ui_font_fallbacks: Option<Vec<String>>,
     /// The name of a font to use for rendering in text buffers.
     #[serde(default)]
     pub buffer_font_family: Option<String>,
@@ -179,6 +188,11 @@ pub struct ThemeSettingsContent {
     /// The OpenType features to enable for rendering in text buffers.
     #[serde(default)]
     pub buffer_font_features: Option<FontFeatures>,
+    /// The names of fonts to fall back to, in order, for characters that the
+    /// buffer font has no glyph for, such as emoji, CJK text, or icons from a
+    /// Nerd Font.
+    #[serde(default)]
+    pub buffer_font_fallbacks: Option<Vec<String>>,
     /// The name of the Zed theme to use.
     #[serde(default)]
     pub theme: Option<ThemeSelection>,
@@ -188,6 +202,13 @@ pub struct ThemeSettingsContent {
     /// These values will override the ones on the current theme specified in `theme`.
     #[serde(rename = "experimental.theme_overrides", default)]
     pub theme_overrides: Option<ThemeStyleContent>,
+
+    /// Overrides for the current theme, applied depending on whether it is a
+    /// light or a dark theme.
+    ///
+    /// These values are applied after those in `experimental.theme_overrides`.
+    #[serde(rename = "theme_overrides", default)]
+    pub appearance_theme_overrides: Option<ThemeOverridesContent>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Default)]
@@ -241,9 +262,20 @@ impl ThemeSettings {
 
     /// Applies the theme overrides, if there are any, to the current theme.
     pub fn apply_theme_overrides(&mut self) {
-        if let Some(theme_overrides) = &self.theme_overrides {
-            let mut base_theme = (*self.active_theme).clone();
+        let appearance_theme_overrides = self
+            .appearance_theme_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.for_appearance(self.active_theme.appearance));
+        if self.theme_overrides.is_none() && appearance_theme_overrides.is_none() {
+            return;
+        }
 
+        let mut base_theme = (*self.active_theme).clone();
+        let theme_overrides = self
+            .theme_overrides
+            .iter()
+            .chain(appearance_theme_overrides.map(|overrides| &overrides.style));
+        for theme_overrides in theme_overrides {
             if let Some(window_background_appearance) = theme_overrides.window_background_appearance
             {
                 base_theme.styles.window_background_appearance =
@@ -259,6 +291,7 @@ impl ThemeSettings {
                 .status
                 .refine(&theme_overrides.status_colors_refinement());
             base_theme.styles.player.merge(&theme_overrides.players);
+            base_theme.styles.accents.merge(&theme_overrides.accents);
             base_theme.styles.syntax = Arc::new(SyntaxTheme {
                 highlights: {
                     let mut highlights = base_theme.styles.syntax.highlights.clone();
@@ -268,9 +301,9 @@ impl ThemeSettings {
                     highlights
                 },
             });
-
-            self.active_theme = Arc::new(base_theme);
         }
+
+        self.active_theme = Arc::new(base_theme);
     }
 }
 
@@ -281,7 +314,7 @@ pub fn observe_buffer_font_size_adjustment<V: 'static>(
     cx.observe_global::<AdjustedBufferFontSize>(f)
 }
 
-pub fn adjusted_font_size(size: Pixels, cx: &mut AppContext) -> Pixels {
+pub fn adjusted_font_size(size: Pixels, cx: &AppContext) -> Pixels {
     if let Some(AdjustedBufferFontSize(adjusted_size)) = cx.try_global::<AdjustedBufferFontSize>() {
         let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
         let delta = *adjusted_size - buffer_font_size;
@@ -329,12 +362,14 @@ impl settings::Settings for ThemeSettings {
                 weight: Default::default(),
                 style: Default::default(),
             },
+            ui_font_fallbacks: font_fallbacks(defaults.ui_font_fallbacks.as_ref()),
             buffer_font: Font {
                 family: defaults.buffer_font_family.clone().unwrap().into(),
                 features: defaults.buffer_font_features.unwrap(),
                 weight: FontWeight::default(),
                 style: FontStyle::default(),
             },
+            buffer_font_fallbacks: font_fallbacks(defaults.buffer_font_fallbacks.as_ref()),
             buffer_font_size: defaults.buffer_font_size.unwrap().into(),
             buffer_line_height: defaults.buffer_line_height.unwrap(),
             theme_selection: defaults.theme.clone(),
@@ -343,6 +378,7 @@ impl settings::Settings for ThemeSettings {
                 .or(themes.get(&one_dark().name))
                 .unwrap(),
             theme_overrides: None,
+            appearance_theme_overrides: None,
         };
 
         for value in sources.user.into_iter().chain(sources.release_channel) {
@@ -352,6 +388,9 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.buffer_font_features {
                 this.buffer_font.features = value;
             }
+            if let Some(value) = &value.buffer_font_fallbacks {
+                this.buffer_font_fallbacks = font_fallbacks(Some(value));
+            }
 
             if let Some(value) = value.ui_font_family.clone() {
                 this.ui_font.family = value.into();
@@ -359,6 +398,9 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.ui_font_features {
                 this.ui_font.features = value;
             }
+            if let Some(value) = &value.ui_font_fallbacks {
+                this.ui_font_fallbacks = font_fallbacks(Some(value));
+            }
 
             if let Some(value) = &value.theme {
                 this.theme_selection = Some(value.clone());
@@ -371,6 +413,17 @@ impl settings::Settings for ThemeSettings {
             }
 
             this.theme_overrides = value.theme_overrides.clone();
+            this.appearance_theme_overrides = value.appearance_theme_overrides.clone();
+            if let Some(appearance_theme_overrides) = &this.appearance_theme_overrides {
+                for (appearance, overrides) in [
+                    ("light", &appearance_theme_overrides.light),
+                    ("dark", &appearance_theme_overrides.dark),
+                ] {
+                    for error in overrides.iter().flat_map(|overrides| &overrides.errors) {
+                        log::error!("invalid setting in theme_overrides.{appearance}: {error}");
+                    }
+                }
+            }
             this.apply_theme_overrides();
 
             merge(&mut this.ui_font_size, value.ui_font_size.map(Into::into));
@@ -417,6 +470,14 @@ impl settings::Settings for ThemeSettings {
             ("ThemeName".into(), theme_name_schema.into()),
             ("FontFamilies".into(), fonts_schema.into()),
         ]);
+        let font_fallbacks_schema = SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(Schema::new_ref("#/definitions/FontFamilies".into()).into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
 
         root_schema
             .schema
@@ -433,12 +494,25 @@ impl settings::Settings for ThemeSettings {
                     "ui_font_family".to_owned(),
                     Schema::new_ref("#/definitions/FontFamilies".into()),
                 ),
+                (
+                    "buffer_font_fallbacks".to_owned(),
+                    font_fallbacks_schema.clone().into(),
+                ),
+                ("ui_font_fallbacks".to_owned(), font_fallbacks_schema.into()),
             ]);
 
         root_schema
     }
 }
 
+fn font_fallbacks(families: Option<&Vec<String>>) -> Vec<SharedString> {
+    families
+        .into_iter()
+        .flatten()
+        .map(|family| SharedString::from(family.clone()))
+        .collect()
+}
+
 fn merge<T: Copy>(target: &mut T, value: Option<T>) {
     if let Some(value) = value {
         *target = value;
@@ -2,7 +2,9 @@ use gpui::{Hsla, WindowBackgroundAppearance};
 use refineable::Refineable;
 use std::sync::Arc;
 
-use crate::{PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme, SystemColors};
+use crate::{
+    AccentColors, PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme, SystemColors,
+};
 
 #[derive(Refineable, Clone, Debug)]
 #[refineable(Debug, serde::Deserialize)]
@@ -242,7 +244,7 @@ pub struct ThemeStyles {
     /// An array of colors used for theme elements that iterate through a series of colors.
     ///
     /// Example: Player colors, rainbow brackets and indent guides, etc.
-    pub accents: Vec<Hsla>,
+    pub accents: AccentColors,
 
     #[refineable]
     pub colors: ThemeColors,
@@ -9,23 +9,27 @@
 mod default_colors;
 mod default_theme;
 mod one_themes;
+mod overrides;
 pub mod prelude;
 mod registry;
 mod scale;
 mod schema;
 mod settings;
 mod styles;
+mod vscode;
 
 use std::sync::Arc;
 
 use ::settings::{Settings, SettingsStore};
 pub use default_colors::*;
 pub use default_theme::*;
+pub use overrides::*;
 pub use registry::*;
 pub use scale::*;
 pub use schema::*;
 pub use settings::*;
 pub use styles::*;
+pub use vscode::*;
 
 use gpui::{
     AppContext, AssetSource, Hsla, SharedString, WindowAppearance, WindowBackgroundAppearance,
@@ -78,6 +82,7 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut AppContext) {
     }
 
     ThemeSettings::register(cx);
+    set_font_fallbacks(cx);
 
     let mut prev_buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
     cx.observe_global::<SettingsStore>(move |cx| {
@@ -86,10 +91,37 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut AppContext) {
             prev_buffer_font_size = buffer_font_size;
             reset_font_size(cx);
         }
+        set_font_fallbacks(cx);
     })
     .detach();
 }
 
+/// Configures the fonts that the text system falls back to for characters
+/// missing from the buffer and UI fonts.
+fn set_font_fallbacks(cx: &mut AppContext) {
+    let settings = ThemeSettings::get_global(cx);
+    let text_system = cx.text_system();
+    if settings.ui_font.family == settings.buffer_font.family {
+        text_system.set_font_fallbacks(
+            settings.buffer_font.family.clone(),
+            settings
+                .buffer_font_fallbacks
+                .iter()
+                .chain(&settings.ui_font_fallbacks)
+                .cloned(),
+        );
+    } else {
+        text_system.set_font_fallbacks(
+            settings.buffer_font.family.clone(),
+            settings.buffer_font_fallbacks.iter().cloned(),
+        );
+        text_system.set_font_fallbacks(
+            settings.ui_font.family.clone(),
+            settings.ui_font_fallbacks.iter().cloned(),
+        );
+    }
+}
+
 pub trait ActiveTheme {
     fn theme(&self) -> &Arc<Theme>;
 }
@@ -131,6 +163,12 @@ impl Theme {
         &self.styles.player
     }
 
+    /// Returns the [`AccentColors`] for the theme.
+    #[inline(always)]
+    pub fn accents(&self) -> &AccentColors {
+        &self.styles.accents
+    }
+
     /// Returns the [`ThemeColors`] for the theme.
     #[inline(always)]
     pub fn colors(&self) -> &ThemeColors {
@@ -173,3 +211,67 @@ pub fn color_alpha(color: Hsla, alpha: f32) -> Hsla {
     color.a = alpha;
     color
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    fn test_font_fallbacks(cx: &mut AppContext) {
+        let store = SettingsStore::test(cx);
+        cx.set_global(store);
+        init(LoadThemes::JustBase, cx);
+
+        let set_fallbacks = |cx: &mut AppContext, buffer: &[&str], ui: &[&str]| {
+            let buffer = buffer.iter().map(|family| family.to_string()).collect();
+            let ui = ui.iter().map(|family| family.to_string()).collect();
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<ThemeSettings>(cx, |settings| {
+                    settings.buffer_font_fallbacks = Some(buffer);
+                    settings.ui_font_fallbacks = Some(ui);
+                });
+            });
+        };
+        let fallbacks = |cx: &mut AppContext, family: &str| {
+            cx.text_system()
+                .font_fallbacks(family)
+                .map(|fallbacks| fallbacks.to_vec())
+        };
+        let family = |family: &'static str| SharedString::from(family);
+
+        // When the buffer and UI fonts differ, each gets its own fallbacks.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<ThemeSettings>(cx, |settings| {
+                settings.buffer_font_family = Some("Zed Mono".into());
+                settings.ui_font_family = Some("Zed Sans".into());
+            });
+        });
+        set_fallbacks(cx, &["Symbols Nerd Font Mono"], &["Noto Sans"]);
+        let settings = ThemeSettings::get_global(cx);
+        assert_eq!(
+            settings.buffer_font_fallbacks,
+            [family("Symbols Nerd Font Mono")]
+        );
+        assert_eq!(settings.ui_font_fallbacks, [family("Noto Sans")]);
+        assert_eq!(
+            fallbacks(cx, "Zed Mono"),
+            Some(vec![family("Symbols Nerd Font Mono")])
+        );
+        assert_eq!(fallbacks(cx, "Zed Sans"), Some(vec![family("Noto Sans")]));
+
+        // When they're the same font, it falls back to the buffer's fallbacks first.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<ThemeSettings>(cx, |settings| {
+                settings.ui_font_family = Some("Zed Mono".into());
+            });
+        });
+        assert_eq!(
+            fallbacks(cx, "Zed Mono"),
+            Some(vec![family("Symbols Nerd Font Mono"), family("Noto Sans")])
+        );
+
+        // Removing the fallbacks clears them.
+        set_fallbacks(cx, &[], &[]);
+        assert_eq!(fallbacks(cx, "Zed Mono"), None);
+    }
+}
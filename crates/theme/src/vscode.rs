@@ -0,0 +1,532 @@
+//! Conversion of VS Code color themes into Zed themes.
+
+mod syntax;
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::Value;
+use strum::IntoEnumIterator;
+
+use crate::{
+    try_parse_color, AppearanceContent, FontStyleContent, FontWeightContent, HighlightStyleContent,
+    PlayerColorContent, ThemeContent, ThemeFamilyContent, ThemeStyleContent,
+    WindowBackgroundContent,
+};
+
+pub use syntax::*;
+
+/// The VS Code color keys that have an equivalent in Zed, along with the Zed
+/// theme keys they are imported as.
+///
+/// When several VS Code keys map to the same Zed key, the one listed first wins.
+const COLOR_MAPPINGS: &[(&str, &[&str])] = &[
+    ("focusBorder", &["border.focused"]),
+    (
+        "panel.border",
+        &[
+            "border",
+            "border.variant",
+            "border.selected",
+            "border.transparent",
+            "border.disabled",
+            "editor.wrap_guide",
+            "editor.active_wrap_guide",
+        ],
+    ),
+    ("dropdown.background", &["elevated_surface.background"]),
+    (
+        "panel.background",
+        &["surface.background", "panel.background"],
+    ),
+    ("button.background", &["element.background"]),
+    (
+        "list.hoverBackground",
+        &["element.hover", "ghost_element.hover"],
+    ),
+    (
+        "list.activeSelectionBackground",
+        &["element.selected", "ghost_element.selected"],
+    ),
+    ("list.dropBackground", &["drop_target.background"]),
+    ("foreground", &["text"]),
+    ("disabledForeground", &["text.disabled", "icon.disabled"]),
+    ("textLink.foreground", &["text.accent", "icon.accent"]),
+    ("textLink.activeForeground", &["link_text.hover"]),
+    ("statusBar.background", &["status_bar.background"]),
+    ("titleBar.activeBackground", &["title_bar.background"]),
+    ("breadcrumb.background", &["toolbar.background"]),
+    ("editorGroupHeader.tabsBackground", &["tab_bar.background"]),
+    ("tab.activeBackground", &["tab.active_background"]),
+    (
+        "tab.inactiveBackground",
+        &["tab.inactive_background", "tab.active_background"],
+    ),
+    ("tab.inactiveForeground", &["text.muted", "hidden"]),
+    (
+        "scrollbarSlider.background",
+        &["scrollbar.thumb.background", "scrollbar.thumb.border"],
+    ),
+    (
+        "scrollbarSlider.hoverBackground",
+        &["scrollbar.thumb.hover_background"],
+    ),
+    ("editorOverviewRuler.border", &["scrollbar.track.border"]),
+    ("editorGroup.border", &["pane_group.border"]),
+    (
+        "editorLineNumber.activeForeground",
+        &["editor.active_line_number"],
+    ),
+    (
+        "editor.foreground",
+        &["editor.foreground", "editor.active_line_number"],
+    ),
+    (
+        "editor.background",
+        &[
+            "background",
+            "editor.background",
+            "editor.gutter.background",
+            "toolbar.background",
+            "scrollbar.track.background",
+        ],
+    ),
+    (
+        "editor.lineHighlightBackground",
+        &["editor.active_line.background"],
+    ),
+    ("editorLineNumber.foreground", &["editor.line_number"]),
+    ("editorWhitespace.foreground", &["editor.invisible"]),
+    (
+        "editor.findMatchHighlightBackground",
+        &["search.match_background"],
+    ),
+    (
+        "editor.wordHighlightBackground",
+        &["editor.document_highlight.read_background"],
+    ),
+    (
+        "editor.wordHighlightStrongBackground",
+        &["editor.document_highlight.write_background"],
+    ),
+    ("terminal.background", &["terminal.background"]),
+    ("terminal.foreground", &["terminal.foreground"]),
+    ("terminal.ansiBlack", &["terminal.ansi.black"]),
+    ("terminal.ansiBrightBlack", &["terminal.ansi.bright_black"]),
+    ("terminal.ansiRed", &["terminal.ansi.red"]),
+    ("terminal.ansiBrightRed", &["terminal.ansi.bright_red"]),
+    ("terminal.ansiGreen", &["terminal.ansi.green"]),
+    ("terminal.ansiBrightGreen", &["terminal.ansi.bright_green"]),
+    ("terminal.ansiYellow", &["terminal.ansi.yellow"]),
+    (
+        "terminal.ansiBrightYellow",
+        &["terminal.ansi.bright_yellow"],
+    ),
+    ("terminal.ansiBlue", &["terminal.ansi.blue"]),
+    ("terminal.ansiBrightBlue", &["terminal.ansi.bright_blue"]),
+    ("terminal.ansiMagenta", &["terminal.ansi.magenta"]),
+    (
+        "terminal.ansiBrightMagenta",
+        &["terminal.ansi.bright_magenta"],
+    ),
+    ("terminal.ansiCyan", &["terminal.ansi.cyan"]),
+    ("terminal.ansiBrightCyan", &["terminal.ansi.bright_cyan"]),
+    ("terminal.ansiWhite", &["terminal.ansi.white"]),
+    ("terminal.ansiBrightWhite", &["terminal.ansi.bright_white"]),
+    ("gitDecoration.conflictingResourceForeground", &["conflict"]),
+    ("gitDecoration.ignoredResourceForeground", &["ignored"]),
+    ("gitDecoration.renamedResourceForeground", &["renamed"]),
+    ("editorGutter.addedBackground", &["created"]),
+    ("editorGutter.deletedBackground", &["deleted"]),
+    ("editorGutter.modifiedBackground", &["modified"]),
+    ("editorError.foreground", &["error"]),
+    ("editorError.background", &["error.background"]),
+    ("editorError.border", &["error.border"]),
+    ("editorWarning.foreground", &["warning"]),
+    ("editorWarning.background", &["warning.background"]),
+    ("editorWarning.border", &["warning.border"]),
+    ("editorInfo.foreground", &["info"]),
+    ("editorInfo.background", &["info.background"]),
+    ("editorInfo.border", &["info.border"]),
+    ("editorInlayHint.foreground", &["hint"]),
+    ("editorHint.border", &["hint.border"]),
+];
+
+/// The VS Code color keys that are imported as the local player's colors.
+const CURSOR_COLOR_KEY: &str = "editorCursor.foreground";
+const SELECTION_COLOR_KEY: &str = "editor.selectionBackground";
+
+/// The colors used when the VS Code theme does not provide a value.
+const DEFAULT_COLORS: &[(&str, &str)] = &[("hint", "#969696ff")];
+
+/// The top-level keys of a VS Code theme that don't affect its appearance.
+const IGNORED_KEYS: &[&str] = &[
+    "$schema",
+    "maintainers",
+    "semanticClass",
+    "semanticHighlighting",
+];
+
+#[derive(Debug, Deserialize)]
+struct VsCodeTheme {
+    name: Option<String>,
+    author: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    colors: IndexMap<String, Option<String>>,
+    #[serde(rename = "tokenColors", default)]
+    token_colors: Option<Value>,
+    #[serde(rename = "semanticTokenColors", default)]
+    semantic_token_colors: IndexMap<String, VsCodeSemanticTokenStyle>,
+    #[serde(flatten)]
+    other: IndexMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum VsCodeSemanticTokenStyle {
+    Color(String),
+    Style {
+        foreground: Option<String>,
+        #[serde(rename = "fontStyle")]
+        font_style: Option<String>,
+        bold: Option<bool>,
+        italic: Option<bool>,
+    },
+}
+
+/// A theme imported from a VS Code color theme.
+#[derive(Debug)]
+pub struct VsCodeThemeImport {
+    pub theme: ThemeContent,
+    pub author: Option<String>,
+    /// The keys of the VS Code theme that have no equivalent in Zed, and were
+    /// therefore left out of the imported theme.
+    pub unmapped_keys: Vec<String>,
+}
+
+impl VsCodeThemeImport {
+    /// Returns a theme family containing only the imported theme.
+    pub fn into_theme_family(self) -> ThemeFamilyContent {
+        ThemeFamilyContent {
+            name: self.theme.name.clone(),
+            author: self.author.unwrap_or_default(),
+            themes: vec![self.theme],
+        }
+    }
+}
+
+/// Converts the contents of a VS Code color theme file into a Zed theme.
+///
+/// The `fallback_name` is used when the VS Code theme is unnamed.
+pub fn import_vscode_theme(content: &str, fallback_name: &str) -> Result<VsCodeThemeImport> {
+    let vscode_theme: VsCodeTheme =
+        serde_json_lenient::from_str(content).context("failed to parse VS Code theme")?;
+
+    let mut unmapped_keys = Vec::new();
+    for key in vscode_theme.other.keys() {
+        if !IGNORED_KEYS.contains(&key.as_str()) {
+            unmapped_keys.push(key.clone());
+        }
+    }
+
+    let token_colors = match vscode_theme.token_colors {
+        Some(Value::Array(token_colors)) => token_colors
+            .into_iter()
+            .filter_map(|token_color| serde_json::from_value(token_color).ok())
+            .collect(),
+        // The token colors may also refer to a separate TextMate theme file,
+        // which we can't follow.
+        Some(_) => {
+            unmapped_keys.push("tokenColors".to_string());
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+
+    let colors = vscode_theme
+        .colors
+        .into_iter()
+        .filter_map(|(key, color)| Some((key, color?)))
+        .collect::<IndexMap<_, _>>();
+
+    let mut style = convert_colors(&colors, &token_colors)?;
+    for key in colors.keys() {
+        let is_mapped = COLOR_MAPPINGS
+            .iter()
+            .any(|(vscode_key, _)| vscode_key == key)
+            || key == CURSOR_COLOR_KEY
+            || key == SELECTION_COLOR_KEY;
+        if !is_mapped {
+            unmapped_keys.push(format!("colors.{key}"));
+        }
+    }
+
+    let (syntax, unused_token_colors) = convert_token_colors(&token_colors);
+    style.syntax = syntax;
+    unmapped_keys.extend(unused_token_colors.into_iter().map(|ix| {
+        let token_color = &token_colors[ix];
+        let scope = match &token_color.scope {
+            Some(VsCodeTokenScope::One(scope)) => scope.clone(),
+            Some(VsCodeTokenScope::Many(scopes)) => scopes.join(", "),
+            None => token_color.name.clone().unwrap_or_default(),
+        };
+        format!("tokenColors.{scope}")
+    }));
+
+    for (selector, semantic_style) in &vscode_theme.semantic_token_colors {
+        let Some(token) = ZedSyntaxToken::from_semantic_token_selector(selector) else {
+            unmapped_keys.push(format!("semanticTokenColors.{selector}"));
+            continue;
+        };
+        apply_semantic_token_style(
+            style.syntax.entry(token.to_string()).or_default(),
+            semantic_style,
+        );
+    }
+
+    let appearance = match vscode_theme.kind.as_deref() {
+        Some("light") | Some("hcLight") => AppearanceContent::Light,
+        Some(_) => AppearanceContent::Dark,
+        None => colors
+            .get("editor.background")
+            .and_then(|color| try_parse_color(color).ok())
+            .filter(|color| color.l > 0.5)
+            .map_or(AppearanceContent::Dark, |_| AppearanceContent::Light),
+    };
+
+    Ok(VsCodeThemeImport {
+        theme: ThemeContent {
+            name: vscode_theme
+                .name
+                .unwrap_or_else(|| fallback_name.to_string()),
+            appearance,
+            style,
+        },
+        author: vscode_theme.author,
+        unmapped_keys,
+    })
+}
+
+fn convert_colors(
+    colors: &IndexMap<String, String>,
+    token_colors: &[VsCodeTokenColor],
+) -> Result<ThemeStyleContent> {
+    let mut zed_colors = serde_json::Map::new();
+    for (vscode_key, zed_keys) in COLOR_MAPPINGS {
+        let Some(color) = colors.get(*vscode_key) else {
+            continue;
+        };
+        for zed_key in *zed_keys {
+            zed_colors
+                .entry(zed_key.to_string())
+                .or_insert_with(|| Value::String(color.clone()));
+        }
+    }
+
+    // The foreground of the token color without a scope is the default text color.
+    let default_foreground = token_colors
+        .iter()
+        .find(|token_color| token_color.scope.is_none())
+        .and_then(|token_color| token_color.settings.foreground.clone());
+    if let Some(foreground) = default_foreground {
+        for zed_key in ["text", "editor.foreground"] {
+            zed_colors
+                .entry(zed_key.to_string())
+                .or_insert_with(|| Value::String(foreground.clone()));
+        }
+    }
+
+    for (zed_key, color) in DEFAULT_COLORS {
+        zed_colors
+            .entry(zed_key.to_string())
+            .or_insert_with(|| Value::String(color.to_string()));
+    }
+
+    let mut style: ThemeStyleContent = serde_json::from_value(Value::Object(zed_colors))?;
+    style.window_background_appearance = Some(WindowBackgroundContent::Opaque);
+
+    let cursor = colors.get(CURSOR_COLOR_KEY).cloned();
+    let selection = colors.get(SELECTION_COLOR_KEY).cloned();
+    if cursor.is_some() || selection.is_some() {
+        style.players.push(PlayerColorContent {
+            background: cursor.clone(),
+            cursor,
+            selection,
+        });
+    }
+
+    Ok(style)
+}
+
+/// Returns the syntax styles matching the token colors, along with the indices
+/// of the token colors that were not used by any syntax style.
+fn convert_token_colors(
+    token_colors: &[VsCodeTokenColor],
+) -> (IndexMap<String, HighlightStyleContent>, Vec<usize>) {
+    let mut highlight_styles = IndexMap::new();
+    let mut used_token_colors = vec![false; token_colors.len()];
+
+    for syntax_token in ZedSyntaxToken::iter() {
+        let best_match = syntax_token
+            .find_best_token_color_match(token_colors)
+            .or_else(|| {
+                syntax_token
+                    .fallbacks()
+                    .iter()
+                    .find_map(|fallback| fallback.find_best_token_color_match(token_colors))
+            });
+
+        let Some(ix) = best_match else {
+            continue;
+        };
+        let token_color = &token_colors[ix];
+
+        let font_style = token_color.settings.font_style.as_deref();
+        let highlight_style = HighlightStyleContent {
+            color: token_color.settings.foreground.clone(),
+            font_style: font_style.and_then(try_parse_font_style),
+            font_weight: font_style.and_then(try_parse_font_weight),
+        };
+
+        if highlight_style.is_empty() {
+            continue;
+        }
+
+        used_token_colors[ix] = true;
+        highlight_styles.insert(syntax_token.to_string(), highlight_style);
+    }
+
+    let unused_token_colors = token_colors
+        .iter()
+        .enumerate()
+        .filter(|(ix, token_color)| token_color.scope.is_some() && !used_token_colors[*ix])
+        .map(|(ix, _)| ix)
+        .collect();
+
+    (highlight_styles, unused_token_colors)
+}
+
+fn apply_semantic_token_style(
+    highlight_style: &mut HighlightStyleContent,
+    semantic_style: &VsCodeSemanticTokenStyle,
+) {
+    match semantic_style {
+        VsCodeSemanticTokenStyle::Color(color) => {
+            highlight_style.color = Some(color.clone());
+        }
+        VsCodeSemanticTokenStyle::Style {
+            foreground,
+            font_style,
+            bold,
+            italic,
+        } => {
+            if let Some(foreground) = foreground {
+                highlight_style.color = Some(foreground.clone());
+            }
+            if let Some(font_style) = font_style {
+                highlight_style.font_style = try_parse_font_style(font_style);
+                highlight_style.font_weight = try_parse_font_weight(font_style);
+            }
+            match bold {
+                Some(true) => highlight_style.font_weight = Some(FontWeightContent::Bold),
+                Some(false) => highlight_style.font_weight = None,
+                None => {}
+            }
+            match italic {
+                Some(true) => highlight_style.font_style = Some(FontStyleContent::Italic),
+                Some(false) => highlight_style.font_style = None,
+                None => {}
+            }
+        }
+    }
+}
+
+fn try_parse_font_weight(font_style: &str) -> Option<FontWeightContent> {
+    match font_style {
+        style if style.contains("bold") => Some(FontWeightContent::Bold),
+        _ => None,
+    }
+}
+
+fn try_parse_font_style(font_style: &str) -> Option<FontStyleContent> {
+    match font_style {
+        style if style.contains("italic") => Some(FontStyleContent::Italic),
+        style if style.contains("oblique") => Some(FontStyleContent::Oblique),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_vscode_theme() {
+        let import = import_vscode_theme(
+            r##"{
+                // VS Code themes may contain comments.
+                "name": "Test Theme",
+                "type": "light",
+                "colors": {
+                    "editor.background": "#ffffff",
+                    "editor.foreground": "#333333",
+                    "terminal.ansiRed": "#cd3131",
+                    "activityBar.background": "#eeeeee",
+                },
+                "tokenColors": [
+                    { "scope": ["comment"], "settings": { "foreground": "#008000", "fontStyle": "italic" } },
+                    { "scope": "markup.strikethrough", "settings": { "foreground": "#ff0000" } },
+                ],
+                "semanticTokenColors": {
+                    "function": "#795e26",
+                    "variable.readonly": { "foreground": "#0070c1", "bold": true },
+                    "macro": "#af00db",
+                },
+            }"##,
+            "fallback",
+        )
+        .unwrap();
+
+        let theme = &import.theme;
+        assert_eq!(theme.name, "Test Theme");
+        assert!(matches!(theme.appearance, AppearanceContent::Light));
+        assert_eq!(
+            theme.style.colors.editor_background.as_deref(),
+            Some("#ffffff")
+        );
+        assert_eq!(
+            theme.style.colors.editor_active_line_number.as_deref(),
+            Some("#333333")
+        );
+        assert_eq!(
+            theme.style.colors.terminal_ansi_red.as_deref(),
+            Some("#cd3131")
+        );
+        assert_eq!(theme.style.status.hint.as_deref(), Some("#969696ff"));
+
+        let comment = &theme.style.syntax["comment"];
+        assert_eq!(comment.color.as_deref(), Some("#008000"));
+        assert!(matches!(comment.font_style, Some(FontStyleContent::Italic)));
+        assert_eq!(
+            theme.style.syntax["function"].color.as_deref(),
+            Some("#795e26")
+        );
+        let constant = &theme.style.syntax["constant"];
+        assert_eq!(constant.color.as_deref(), Some("#0070c1"));
+        assert!(matches!(
+            constant.font_weight,
+            Some(FontWeightContent::Bold)
+        ));
+
+        assert_eq!(
+            import.unmapped_keys,
+            [
+                "colors.activityBar.background",
+                "tokenColors.markup.strikethrough",
+                "semanticTokenColors.macro",
+            ]
+        );
+    }
+}
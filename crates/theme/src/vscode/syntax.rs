@@ -2,21 +2,21 @@ use indexmap::IndexMap;
 use serde::Deserialize;
 use strum::EnumIter;
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum VsCodeTokenScope {
     One(String),
     Many(Vec<String>),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct VsCodeTokenColor {
     pub name: Option<String>,
     pub scope: Option<VsCodeTokenScope>,
     pub settings: VsCodeTokenColorSettings,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct VsCodeTokenColorSettings {
     pub foreground: Option<String>,
     pub background: Option<String>,
@@ -118,10 +118,8 @@ impl std::fmt::Display for ZedSyntaxToken {
 }
 
 impl ZedSyntaxToken {
-    pub fn find_best_token_color_match<'a>(
-        &self,
-        token_colors: &'a [VsCodeTokenColor],
-    ) -> Option<&'a VsCodeTokenColor> {
+    /// Returns the index of the token color that best matches this token.
+    pub fn find_best_token_color_match(&self, token_colors: &[VsCodeTokenColor]) -> Option<usize> {
         let mut ranked_matches = IndexMap::new();
 
         for (ix, token_color) in token_colors.iter().enumerate() {
@@ -141,7 +139,7 @@ impl ZedSyntaxToken {
         ranked_matches
             .into_iter()
             .max_by_key(|(_, rank)| *rank)
-            .map(|(ix, _)| &token_colors[ix])
+            .map(|(ix, _)| ix)
     }
 
     fn rank_match(&self, token_color: &VsCodeTokenColor) -> Option<u32> {
@@ -307,4 +305,28 @@ impl ZedSyntaxToken {
             ZedSyntaxToken::Variant => vec!["variant"],
         }
     }
+
+    /// Returns the token matching the given VS Code semantic token selector,
+    /// e.g. `function` or `variable.readonly`.
+    pub fn from_semantic_token_selector(selector: &str) -> Option<Self> {
+        let token = match selector {
+            "class" | "interface" | "struct" | "type" | "typeParameter" => ZedSyntaxToken::Type,
+            "comment" => ZedSyntaxToken::Comment,
+            "decorator" => ZedSyntaxToken::Attribute,
+            "enum" => ZedSyntaxToken::Enum,
+            "enumMember" => ZedSyntaxToken::Variant,
+            "function" | "method" => ZedSyntaxToken::Function,
+            "keyword" => ZedSyntaxToken::Keyword,
+            "label" => ZedSyntaxToken::Label,
+            "number" => ZedSyntaxToken::Number,
+            "operator" => ZedSyntaxToken::Operator,
+            "property" => ZedSyntaxToken::Property,
+            "regexp" => ZedSyntaxToken::StringRegex,
+            "string" => ZedSyntaxToken::String,
+            "variable" | "parameter" => ZedSyntaxToken::Variable,
+            "variable.readonly" => ZedSyntaxToken::Constant,
+            _ => return None,
+        };
+        Some(token)
+    }
 }
@@ -1,70 +1,17 @@
 mod assets;
 mod color;
 mod util;
-mod vscode;
 
 use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use indexmap::IndexMap;
 use log::LevelFilter;
 use schemars::schema_for;
-use serde::Deserialize;
 use simplelog::{TermLogger, TerminalMode};
-use theme::{Appearance, AppearanceContent, ThemeFamilyContent};
-
-use crate::vscode::VsCodeTheme;
-use crate::vscode::VsCodeThemeConverter;
-
-#[derive(Debug, Deserialize)]
-struct FamilyMetadata {
-    pub name: String,
-    pub author: String,
-    pub themes: Vec<ThemeMetadata>,
-
-    /// Overrides for specific syntax tokens.
-    ///
-    /// Use this to ensure certain Zed syntax tokens are matched
-    /// to an exact set of scopes when it is not otherwise possible
-    /// to rely on the default mappings in the theme importer.
-    #[serde(default)]
-    pub syntax: IndexMap<String, Vec<String>>,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ThemeAppearanceJson {
-    Light,
-    Dark,
-}
-
-impl From<ThemeAppearanceJson> for AppearanceContent {
-    fn from(value: ThemeAppearanceJson) -> Self {
-        match value {
-            ThemeAppearanceJson::Light => Self::Light,
-            ThemeAppearanceJson::Dark => Self::Dark,
-        }
-    }
-}
-
-impl From<ThemeAppearanceJson> for Appearance {
-    fn from(value: ThemeAppearanceJson) -> Self {
-        match value {
-            ThemeAppearanceJson::Light => Self::Light,
-            ThemeAppearanceJson::Dark => Self::Dark,
-        }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ThemeMetadata {
-    pub name: String,
-    pub file_name: String,
-    pub appearance: ThemeAppearanceJson,
-}
+use theme::{import_vscode_theme, ThemeFamilyContent};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -128,28 +75,28 @@ fn main() -> Result<()> {
 
     let theme_file_path = args.theme_path;
 
-    let theme_file = match File::open(&theme_file_path) {
+    let mut theme_file = match File::open(&theme_file_path) {
         Ok(file) => file,
         Err(err) => {
             log::info!("Failed to open file at path: {:?}", theme_file_path);
             return Err(err)?;
         }
     };
+    let mut theme_content = String::new();
+    theme_file.read_to_string(&mut theme_content)?;
+
+    let fallback_name = theme_file_path
+        .file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let import = import_vscode_theme(&theme_content, &fallback_name)
+        .context(format!("failed to import theme {theme_file_path:?}"))?;
+
+    for key in &import.unmapped_keys {
+        log::warn!("No equivalent found for '{key}'");
+    }
 
-    let vscode_theme: VsCodeTheme = serde_json_lenient::from_reader(theme_file)
-        .context(format!("failed to parse theme {theme_file_path:?}"))?;
-
-    let theme_metadata = ThemeMetadata {
-        name: vscode_theme.name.clone().unwrap_or("".to_string()),
-        appearance: ThemeAppearanceJson::Dark,
-        file_name: "".to_string(),
-    };
-
-    let converter = VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::new());
-
-    let theme = converter.convert()?;
-
-    let theme_json = serde_json::to_string_pretty(&theme).unwrap();
+    let theme_json = serde_json::to_string_pretty(&import.into_theme_family()).unwrap();
 
     if let Some(output) = args.output {
         let mut file = File::create(output)?;
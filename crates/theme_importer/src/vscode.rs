@@ -1,7 +0,0 @@
-mod converter;
-mod syntax;
-mod theme;
-
-pub use converter::*;
-pub use syntax::*;
-pub use theme::*;
@@ -0,0 +1,126 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use fs::Fs;
+use gpui::{actions, PathPromptOptions, ViewContext, VisualContext};
+use theme::{import_vscode_theme, ThemeFamilyContent, ThemeRegistry, VsCodeThemeImport};
+use util::paths;
+use workspace::{
+    notifications::{
+        simple_message_notification::MessageNotification, DetachAndPromptErr, NotificationId,
+    },
+    Workspace,
+};
+
+use crate::Toggle;
+
+actions!(theme, [ImportFromFile]);
+
+/// The number of unmapped keys listed in the notification shown after an import.
+const MAX_REPORTED_UNMAPPED_KEYS: usize = 10;
+
+pub(crate) fn import_from_file(
+    workspace: &mut Workspace,
+    _: &ImportFromFile,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let theme_registry = ThemeRegistry::global(cx);
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+    });
+
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = paths.await?.and_then(|paths| paths.into_iter().next()) else {
+            return Ok(());
+        };
+
+        let import = import_theme(&path, fs, theme_registry).await?;
+        let theme_name = import.theme.name.clone();
+        for key in &import.unmapped_keys {
+            log::info!("theme {theme_name:?} has no equivalent for {key:?}");
+        }
+
+        let message = import_report(&theme_name, &import.unmapped_keys);
+        workspace.update(&mut cx, |workspace, cx| {
+            struct ThemeImportNotification;
+
+            workspace.show_notification(
+                NotificationId::unique::<ThemeImportNotification>(),
+                cx,
+                |cx| {
+                    cx.new_view(move |_| {
+                        MessageNotification::new(message)
+                            .with_click_message("Select Theme")
+                            .on_click(move |cx| {
+                                cx.dispatch_action(Box::new(Toggle {
+                                    themes_filter: Some(vec![theme_name.clone()]),
+                                }))
+                            })
+                    })
+                },
+            );
+        })
+    })
+    .detach_and_prompt_err("Failed to import theme", cx, |_, _| None);
+}
+
+/// Converts the VS Code theme at the given path, and installs the result in the
+/// user's themes directory.
+async fn import_theme(
+    path: &Path,
+    fs: Arc<dyn Fs>,
+    theme_registry: Arc<ThemeRegistry>,
+) -> Result<VsCodeThemeImport> {
+    let content = fs
+        .load(path)
+        .await
+        .with_context(|| format!("failed to read theme {path:?}"))?;
+    let file_stem = path
+        .file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().to_string())
+        .context("invalid theme path")?;
+    let import = import_vscode_theme(&content, &file_stem)?;
+
+    let theme_family = ThemeFamilyContent {
+        name: import.theme.name.clone(),
+        author: import.author.clone().unwrap_or_default(),
+        themes: vec![import.theme.clone()],
+    };
+    let themes_dir = paths::THEMES_DIR.as_path();
+    fs.create_dir(themes_dir).await?;
+    let theme_path = themes_dir.join(format!("{file_stem}.json"));
+    fs.atomic_write(
+        theme_path.clone(),
+        serde_json::to_string_pretty(&theme_family)?,
+    )
+    .await?;
+    theme_registry.load_user_theme(&theme_path, fs).await?;
+
+    Ok(import)
+}
+
+fn import_report(theme_name: &str, unmapped_keys: &[String]) -> String {
+    if unmapped_keys.is_empty() {
+        return format!("Imported theme \"{theme_name}\".");
+    }
+
+    let mut report = format!(
+        "Imported theme \"{theme_name}\". These keys have no equivalent in Zed and were skipped: {}",
+        unmapped_keys
+            .iter()
+            .take(MAX_REPORTED_UNMAPPED_KEYS)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if unmapped_keys.len() > MAX_REPORTED_UNMAPPED_KEYS {
+        report.push_str(&format!(
+            ", and {} more",
+            unmapped_keys.len() - MAX_REPORTED_UNMAPPED_KEYS
+        ));
+    }
+    report
+}
@@ -1,3 +1,5 @@
+mod theme_import;
+
 use client::telemetry::Telemetry;
 use feature_flags::FeatureFlagAppExt;
 use fs::Fs;
@@ -17,6 +19,8 @@ use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
 use util::ResultExt;
 use workspace::{ui::HighlightedLabel, ModalView, Workspace};
 
+pub use theme_import::ImportFromFile;
+
 #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
 pub struct Toggle {
     /// A list of theme names to filter the theme selector down to.
@@ -30,6 +34,7 @@ pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(toggle);
+            workspace.register_action(theme_import::import_from_file);
         },
     )
     .detach();
@@ -0,0 +1,106 @@
+use db::{define_connection, query, sqlez_macros::sql};
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // time_entries(
+    //   day: String, // YYYY-MM-DD, in local time
+    //   project: String,
+    //   language: String,
+    //   path: String,
+    //   milliseconds: i64,
+    // )
+    pub static ref TIME_TRACKING: TimeTrackingDb<()> =
+        &[sql!(
+            CREATE TABLE time_entries (
+                day TEXT NOT NULL,
+                project TEXT NOT NULL,
+                language TEXT NOT NULL,
+                path TEXT NOT NULL,
+                milliseconds INTEGER NOT NULL,
+                PRIMARY KEY(day, project, language, path)
+            ) STRICT;
+        )];
+}
+
+/// The time spent on a file on a given day: `(day, project, language, path, milliseconds)`.
+pub type TimeEntry = (String, String, String, String, i64);
+
+impl TimeTrackingDb {
+    query! {
+        pub async fn add_time(
+            day: String,
+            project: String,
+            language: String,
+            path: String,
+            milliseconds: i64
+        ) -> Result<()> {
+            INSERT INTO time_entries (day, project, language, path, milliseconds)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(day, project, language, path)
+            DO UPDATE SET milliseconds = milliseconds + excluded.milliseconds
+        }
+    }
+
+    // Returns the entries of the given day and the days after it.
+    query! {
+        pub fn entries_since(day: String) -> Result<Vec<TimeEntry>> {
+            SELECT day, project, language, path, milliseconds
+            FROM time_entries
+            WHERE day >= ?
+            ORDER BY day, project, language, path
+        }
+    }
+
+    query! {
+        pub fn all_entries() -> Result<Vec<TimeEntry>> {
+            SELECT day, project, language, path, milliseconds
+            FROM time_entries
+            ORDER BY day, project, language, path
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_time_entries() {
+        let db = TimeTrackingDb(db::open_test_db("test_time_entries").await);
+
+        for (day, path, milliseconds) in [
+            ("2024-05-01", "a.rs", 1000),
+            ("2024-05-02", "a.rs", 2000),
+            ("2024-05-02", "a.rs", 500),
+            ("2024-05-02", "b.rs", 700),
+        ] {
+            db.add_time(
+                day.into(),
+                "zed".into(),
+                "Rust".into(),
+                path.into(),
+                milliseconds,
+            )
+            .await
+            .unwrap();
+        }
+
+        let entry = |day: &str, path: &str, milliseconds| {
+            (
+                day.to_string(),
+                "zed".to_string(),
+                "Rust".to_string(),
+                path.to_string(),
+                milliseconds,
+            )
+        };
+        assert_eq!(
+            db.entries_since("2024-05-02".into()).unwrap(),
+            vec![
+                entry("2024-05-02", "a.rs", 2500),
+                entry("2024-05-02", "b.rs", 700)
+            ]
+        );
+        assert_eq!(db.all_entries().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{
+    format_day, persistence::TIME_TRACKING, ExportCsv, TimeEntry, TimeTracker, TimeTrackingSettings,
+};
+use chrono::{Days, Local, NaiveDate};
+use collections::HashMap;
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Subscription, Task, View,
+    ViewContext, VisualContext,
+};
+use settings::{Settings, SettingsStore};
+use std::time::Duration;
+use ui::{prelude::*, BarChart, ChartBar, ToggleButton};
+use util::{paths, ResultExt};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+/// The number of days shown in the week summary, including today.
+const DAYS_PER_WEEK: u64 = 7;
+
+/// The number of projects, languages and files listed in the summary.
+const MAX_LISTED: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SummaryRange {
+    Today,
+    Week,
+}
+
+/// Summarizes the time spent editing today and over the past week.
+pub struct TimeTrackingSummary {
+    focus_handle: FocusHandle,
+    range: SummaryRange,
+    today: NaiveDate,
+    entries: Vec<TimeEntry>,
+    _load_entries: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+impl TimeTrackingSummary {
+    pub(crate) fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .find_map(|item| item.downcast::<Self>());
+
+        if let Some(existing) = existing {
+            existing.update(cx, |summary, cx| summary.load_entries(cx));
+            workspace.activate_item(&existing, cx);
+        } else {
+            let summary = cx.new_view(|cx| Self::new(SummaryRange::Today, cx));
+            workspace.add_item_to_active_pane(Box::new(summary), cx);
+        }
+    }
+
+    fn new(range: SummaryRange, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            range,
+            today: Local::now().date_naive(),
+            entries: Vec::new(),
+            _load_entries: Task::ready(()),
+            _settings_subscription: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        };
+        this.load_entries(cx);
+        this
+    }
+
+    /// Writes the pending time to the database and reloads the past week's entries.
+    fn load_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let flush = cx.update_global(|tracker: &mut TimeTracker, cx| tracker.flush(cx));
+        let today = Local::now().date_naive();
+        let since = today - Days::new(DAYS_PER_WEEK - 1);
+        self._load_entries = cx.spawn(|this, mut cx| async move {
+            flush.await;
+            let entries = cx
+                .background_executor()
+                .spawn(async move { TIME_TRACKING.entries_since(format_day(since)) })
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.today = today;
+                this.entries = entries;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn set_range(&mut self, range: SummaryRange, cx: &mut ViewContext<Self>) {
+        self.range = range;
+        cx.notify();
+    }
+
+    fn entries_in_range(&self) -> Vec<&TimeEntry> {
+        let today = format_day(self.today);
+        self.entries
+            .iter()
+            .filter(|entry| self.range == SummaryRange::Week || entry.0 == today)
+            .collect()
+    }
+
+    fn render_chart(&self, entries: &[&TimeEntry]) -> BarChart {
+        let bars: Vec<_> = match self.range {
+            SummaryRange::Today => totals(entries, |entry| &entry.1)
+                .into_iter()
+                .take(MAX_LISTED)
+                .map(|(project, duration)| chart_bar(project.to_string(), duration))
+                .collect(),
+            SummaryRange::Week => {
+                let by_day = totals(entries, |entry| &entry.0)
+                    .into_iter()
+                    .collect::<HashMap<_, _>>();
+                (0..DAYS_PER_WEEK)
+                    .rev()
+                    .map(|days_ago| {
+                        let day = self.today - Days::new(days_ago);
+                        let duration = by_day
+                            .get(format_day(day).as_str())
+                            .copied()
+                            .unwrap_or_default();
+                        chart_bar(day.format("%a").to_string(), duration)
+                    })
+                    .collect()
+            }
+        };
+        BarChart::new().bars(bars)
+    }
+
+    fn render_list(
+        &self,
+        title: &'static str,
+        totals: Vec<(&str, Duration)>,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .gap_1()
+            .child(Headline::new(title).size(HeadlineSize::XSmall))
+            .child(div().h_px().w_full().bg(cx.theme().colors().border_variant))
+            .children(totals.into_iter().take(MAX_LISTED).map(|(name, duration)| {
+                h_flex()
+                    .gap_2()
+                    .justify_between()
+                    .child(Label::new(name.to_string()).size(LabelSize::Small))
+                    .child(
+                        Label::new(format_duration(duration))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            }))
+    }
+}
+
+impl Render for TimeTrackingSummary {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = self.entries_in_range();
+        let total = entries
+            .iter()
+            .map(|entry| Duration::from_millis(entry.4.max(0) as u64))
+            .sum::<Duration>();
+        let enabled = TimeTrackingSettings::get_global(cx).enabled;
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_4()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(Headline::new("Time Tracking").size(HeadlineSize::Large))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                h_flex()
+                                    .child(
+                                        ToggleButton::new("range-today", "Today")
+                                            .style(ButtonStyle::Filled)
+                                            .selected(self.range == SummaryRange::Today)
+                                            .on_click(cx.listener(|this, _, cx| {
+                                                this.set_range(SummaryRange::Today, cx)
+                                            }))
+                                            .first(),
+                                    )
+                                    .child(
+                                        ToggleButton::new("range-week", "This Week")
+                                            .style(ButtonStyle::Filled)
+                                            .selected(self.range == SummaryRange::Week)
+                                            .on_click(cx.listener(|this, _, cx| {
+                                                this.set_range(SummaryRange::Week, cx)
+                                            }))
+                                            .last(),
+                                    ),
+                            )
+                            .child(
+                                Button::new("refresh", "Refresh")
+                                    .on_click(cx.listener(|this, _, cx| this.load_entries(cx))),
+                            )
+                            .child(
+                                Button::new("export-csv", "Export CSV")
+                                    .on_click(|_, cx| cx.dispatch_action(Box::new(ExportCsv))),
+                            ),
+                    ),
+            )
+            .when(!enabled, |this| {
+                this.child(
+                    Label::new(
+                        "Time tracking is disabled. Set \"time_tracking.enabled\" to true to \
+                        start tracking. The tracked time is only stored on this machine.",
+                    )
+                    .color(Color::Muted),
+                )
+            })
+            .child(Label::new(format!("Total: {}", format_duration(total))))
+            .child(self.render_chart(&entries))
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_start()
+                    .gap_4()
+                    .child(self.render_list("Projects", totals(&entries, |e| &e.1), cx))
+                    .child(self.render_list("Languages", totals(&entries, |e| &e.2), cx))
+                    .child(self.render_list("Files", totals(&entries, |e| &e.3), cx)),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for TimeTrackingSummary {}
+
+impl FocusableView for TimeTrackingSummary {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for TimeTrackingSummary {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Time Tracking")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.range, cx)))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+/// Prompts for a file and writes all of the tracked time to it as CSV.
+pub(crate) fn export_csv(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let flush = cx.update_global(|tracker: &mut TimeTracker, cx| tracker.flush(cx));
+    let path = cx.prompt_for_new_path(&paths::HOME);
+    cx.spawn(|_, cx| async move {
+        let Some(path) = path.await.ok().flatten() else {
+            return Ok(());
+        };
+        flush.await;
+        let entries = cx
+            .background_executor()
+            .spawn(async move { TIME_TRACKING.all_entries() })
+            .await?;
+        fs.atomic_write(path, to_csv(&entries)).await
+    })
+    .detach_and_log_err(cx);
+}
+
+fn chart_bar(label: String, duration: Duration) -> ChartBar {
+    ChartBar::new(label, duration.as_secs_f32()).value_label(format_duration(duration))
+}
+
+/// Sums the time of the given entries per key, sorted from most to least time spent.
+fn totals<'a>(
+    entries: &[&'a TimeEntry],
+    key: impl Fn(&'a TimeEntry) -> &'a str,
+) -> Vec<(&'a str, Duration)> {
+    let mut totals = HashMap::<&str, Duration>::default();
+    for entry in entries {
+        *totals.entry(key(entry)).or_default() += Duration::from_millis(entry.4.max(0) as u64);
+    }
+    let mut totals = totals.into_iter().collect::<Vec<_>>();
+    totals.sort_by(|(a_key, a_duration), (b_key, b_duration)| {
+        b_duration.cmp(a_duration).then_with(|| a_key.cmp(b_key))
+    });
+    totals
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) if duration.is_zero() => "0m".to_string(),
+        (0, 0) => "<1m".to_string(),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes:02}m"),
+    }
+}
+
+fn to_csv(entries: &[TimeEntry]) -> String {
+    let mut csv = "date,project,language,path,seconds\n".to_string();
+    for (day, project, language, path, milliseconds) in entries {
+        let milliseconds = (*milliseconds).max(0);
+        csv.push_str(&format!(
+            "{},{},{},{},{}.{:03}\n",
+            csv_field(day),
+            csv_field(project),
+            csv_field(language),
+            csv_field(path),
+            milliseconds / 1000,
+            milliseconds % 1000
+        ));
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let entries = vec![
+            (
+                "2024-05-01".to_string(),
+                "zed".to_string(),
+                "Rust".to_string(),
+                "src/main.rs".to_string(),
+                61_250,
+            ),
+            (
+                "2024-05-02".to_string(),
+                "notes, misc".to_string(),
+                "Markdown".to_string(),
+                "say \"hi\".md".to_string(),
+                5,
+            ),
+        ];
+        assert_eq!(
+            to_csv(&entries),
+            "date,project,language,path,seconds\n\
+             2024-05-01,zed,Rust,src/main.rs,61.250\n\
+             2024-05-02,\"notes, misc\",Markdown,\"say \"\"hi\"\".md\",0.005\n"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "0m");
+        assert_eq!(format_duration(Duration::from_secs(59)), "<1m");
+        assert_eq!(format_duration(Duration::from_secs(25 * 60)), "25m");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 3600 + 5 * 60)),
+            "2h 05m"
+        );
+    }
+}
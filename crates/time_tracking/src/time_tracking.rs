@@ -0,0 +1,177 @@
+mod persistence;
+mod summary_view;
+mod tracker;
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use editor::{Editor, EditorEvent, EditorMode};
+use gpui::{actions, AppContext, Global, Task, ViewContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::time::{Duration, Instant};
+use util::ResultExt;
+use workspace::Workspace;
+
+pub use persistence::TimeEntry;
+pub use summary_view::TimeTrackingSummary;
+pub use tracker::{Activity, Tracker};
+
+use persistence::TIME_TRACKING;
+
+actions!(time_tracking, [OpenSummary, ExportCsv]);
+
+/// How often the tracked time is written to the database.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TimeTrackingSettings {
+    pub enabled: bool,
+    pub idle_timeout_seconds: u64,
+}
+
+/// Settings for tracking the time spent editing. The tracked time is only ever
+/// stored locally.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TimeTrackingSettingsContent {
+    /// Whether to track the time spent editing each file.
+    ///
+    /// Default: false
+    enabled: Option<bool>,
+    /// How long to wait for the next edit or cursor movement before considering
+    /// the user to be away, in seconds.
+    ///
+    /// Default: 120
+    idle_timeout_seconds: Option<u64>,
+}
+
+impl Settings for TimeTrackingSettings {
+    const KEY: Option<&'static str> = Some("time_tracking");
+
+    type FileContent = TimeTrackingSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// Records activity in editors and periodically writes the tracked time to the database.
+#[derive(Default)]
+struct TimeTracker {
+    tracker: Tracker,
+    flush_task: Option<Task<()>>,
+}
+
+impl Global for TimeTracker {}
+
+impl TimeTracker {
+    fn record(&mut self, activity: Activity, idle_timeout: Duration, cx: &mut AppContext) {
+        self.tracker.record(
+            activity,
+            Local::now().date_naive(),
+            Instant::now(),
+            idle_timeout,
+        );
+        if self.flush_task.is_none() {
+            self.flush_task = Some(cx.spawn(|mut cx| async move {
+                cx.background_executor().timer(FLUSH_INTERVAL).await;
+                cx.update_global(|this: &mut TimeTracker, cx| {
+                    this.flush_task = None;
+                    this.flush(cx).detach();
+                })
+                .log_err();
+            }));
+        }
+    }
+
+    fn flush(&mut self, cx: &AppContext) -> Task<()> {
+        let pending = self.tracker.take_pending();
+        cx.background_executor().spawn(async move {
+            for (day, activity, duration) in pending {
+                TIME_TRACKING
+                    .add_time(
+                        format_day(day),
+                        activity.project,
+                        activity.language,
+                        activity.path,
+                        duration.as_millis() as i64,
+                    )
+                    .await
+                    .log_err();
+            }
+        })
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    TimeTrackingSettings::register(cx);
+    cx.set_global(TimeTracker::default());
+
+    cx.on_app_quit(|cx| cx.update_global(|tracker: &mut TimeTracker, cx| tracker.flush(cx)))
+        .detach();
+
+    cx.observe_new_views(|editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+        let editor_view = cx.view().clone();
+        cx.subscribe(&editor_view, |editor, _, event: &EditorEvent, cx| {
+            if matches!(
+                event,
+                EditorEvent::BufferEdited | EditorEvent::SelectionsChanged { local: true }
+            ) {
+                record_editor_activity(editor, cx);
+            }
+        })
+        .detach();
+    })
+    .detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &OpenSummary, cx| {
+                TimeTrackingSummary::open(workspace, cx);
+            })
+            .register_action(|workspace, _: &ExportCsv, cx| {
+                summary_view::export_csv(workspace, cx);
+            });
+    })
+    .detach();
+}
+
+/// Formats a day the way it's stored in the database.
+fn format_day(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+fn record_editor_activity(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let settings = TimeTrackingSettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+    let idle_timeout = Duration::from_secs(settings.idle_timeout_seconds);
+
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file() else {
+        return;
+    };
+    let project = file
+        .full_path(cx)
+        .components()
+        .next()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let activity = Activity {
+        project,
+        language: buffer
+            .language()
+            .map(|language| language.name().to_string())
+            .unwrap_or_else(|| "Plain Text".to_string()),
+        path: file.path().to_string_lossy().into_owned(),
+    };
+
+    cx.update_global(|tracker: &mut TimeTracker, cx| tracker.record(activity, idle_timeout, cx));
+}
@@ -0,0 +1,104 @@
+use chrono::NaiveDate;
+use collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// What the user was working on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Activity {
+    /// The name of the worktree containing the file.
+    pub project: String,
+    pub language: String,
+    /// The path of the file relative to its worktree.
+    pub path: String,
+}
+
+/// Accumulates the time spent on each activity from a stream of activity events.
+///
+/// The time between two consecutive events is attributed to the first event's activity,
+/// unless it exceeds the idle timeout, in which case the user is assumed to have been away.
+#[derive(Default)]
+pub struct Tracker {
+    last_event: Option<(Activity, NaiveDate, Instant)>,
+    pending: HashMap<(NaiveDate, Activity), Duration>,
+}
+
+impl Tracker {
+    pub fn record(
+        &mut self,
+        activity: Activity,
+        day: NaiveDate,
+        now: Instant,
+        idle_timeout: Duration,
+    ) {
+        if let Some((previous, previous_day, at)) = self.last_event.take() {
+            let elapsed = now.saturating_duration_since(at);
+            if elapsed <= idle_timeout {
+                *self.pending.entry((previous_day, previous)).or_default() += elapsed;
+            }
+        }
+        self.last_event = Some((activity, day, now));
+    }
+
+    /// Returns the time accumulated since the last call, to be persisted.
+    pub fn take_pending(&mut self) -> Vec<(NaiveDate, Activity, Duration)> {
+        self.pending
+            .drain()
+            .filter(|(_, duration)| !duration.is_zero())
+            .map(|((day, activity), duration)| (day, activity, duration))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(path: &str) -> Activity {
+        Activity {
+            project: "zed".into(),
+            language: "Rust".into(),
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn test_tracker() {
+        let idle_timeout = Duration::from_secs(60);
+        let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let start = Instant::now();
+        let mut tracker = Tracker::default();
+
+        tracker.record(activity("a.rs"), day, start, idle_timeout);
+        tracker.record(
+            activity("a.rs"),
+            day,
+            start + Duration::from_secs(10),
+            idle_timeout,
+        );
+        tracker.record(
+            activity("b.rs"),
+            day,
+            start + Duration::from_secs(30),
+            idle_timeout,
+        );
+        // The user was idle, so the time since the last event isn't counted.
+        tracker.record(
+            activity("a.rs"),
+            day,
+            start + Duration::from_secs(300),
+            idle_timeout,
+        );
+        tracker.record(
+            activity("a.rs"),
+            day,
+            start + Duration::from_secs(305),
+            idle_timeout,
+        );
+
+        assert_eq!(
+            tracker.take_pending(),
+            vec![(day, activity("a.rs"), Duration::from_secs(35))]
+        );
+        assert!(tracker.take_pending().is_empty());
+    }
+}
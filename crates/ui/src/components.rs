@@ -1,4 +1,5 @@
 mod avatar;
+mod bar_chart;
 mod button;
 mod checkbox;
 mod collapsible_container;
@@ -24,6 +25,7 @@ mod tooltip;
 mod stories;
 
 pub use avatar::*;
+pub use bar_chart::*;
 pub use button::*;
 pub use checkbox::*;
 pub use collapsible_container::*;
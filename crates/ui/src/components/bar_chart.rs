@@ -0,0 +1,110 @@
+use crate::prelude::*;
+
+/// A single bar of a [`BarChart`].
+pub struct ChartBar {
+    label: SharedString,
+    value: f32,
+    value_label: Option<SharedString>,
+}
+
+impl ChartBar {
+    pub fn new(label: impl Into<SharedString>, value: f32) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            value_label: None,
+        }
+    }
+
+    /// Sets the text shown above the bar, e.g. its formatted value.
+    pub fn value_label(mut self, value_label: impl Into<SharedString>) -> Self {
+        self.value_label = Some(value_label.into());
+        self
+    }
+}
+
+/// A chart of vertical bars, scaled relative to the largest value.
+#[derive(IntoElement)]
+pub struct BarChart {
+    bars: Vec<ChartBar>,
+    height: Rems,
+    color: Color,
+}
+
+impl BarChart {
+    pub fn new() -> Self {
+        Self {
+            bars: Vec::new(),
+            height: rems(8.),
+            color: Color::Accent,
+        }
+    }
+
+    pub fn bar(mut self, bar: ChartBar) -> Self {
+        self.bars.push(bar);
+        self
+    }
+
+    pub fn bars(mut self, bars: impl IntoIterator<Item = ChartBar>) -> Self {
+        self.bars.extend(bars);
+        self
+    }
+
+    /// Sets the height of the tallest bar.
+    pub fn height(mut self, height: Rems) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderOnce for BarChart {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let max_value = self.bars.iter().map(|bar| bar.value).fold(0., f32::max);
+        let bar_color = self.color.color(cx);
+
+        h_flex()
+            .w_full()
+            .items_end()
+            .gap_2()
+            .children(self.bars.into_iter().map(|bar| {
+                let fraction = if max_value > 0. {
+                    (bar.value / max_value).clamp(0., 1.)
+                } else {
+                    0.
+                };
+
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        Label::new(bar.value_label.unwrap_or_default())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        v_flex().w_full().h(self.height).justify_end().child(
+                            div()
+                                .w_full()
+                                .h(relative(fraction))
+                                .when(bar.value > 0., |this| this.min_h_px())
+                                .rounded_t_md()
+                                .bg(bar_color),
+                        ),
+                    )
+                    .child(Label::new(bar.label).size(LabelSize::Small))
+            }))
+    }
+}
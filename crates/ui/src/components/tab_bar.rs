@@ -130,6 +130,7 @@ impl RenderOnce for TabBar {
                             .id("tabs")
                             .flex_grow()
                             .overflow_x_scroll()
+                            .auto_scroll_on_drag()
                             .when_some(self.scroll_handle, |cx, scroll_handle| {
                                 cx.track_scroll(&scroll_handle)
                             })
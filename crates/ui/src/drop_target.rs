@@ -0,0 +1,15 @@
+use gpui::{InteractiveElement, Styled, WindowContext};
+use theme::ActiveTheme;
+
+pub trait DropTarget {
+    /// Highlights the element while a value of type `T` that it accepts is dragged over it,
+    /// and calls `listener` when that value is dropped onto it.
+    fn drop_target<T: 'static>(self, listener: impl Fn(&T, &mut WindowContext) + 'static) -> Self;
+}
+
+impl<E: InteractiveElement> DropTarget for E {
+    fn drop_target<T: 'static>(self, listener: impl Fn(&T, &mut WindowContext) + 'static) -> Self {
+        self.drag_over::<T>(|style, _, cx| style.bg(cx.theme().colors().drop_target_background))
+            .on_drop(listener)
+    }
+}
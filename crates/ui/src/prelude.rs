@@ -9,6 +9,7 @@ pub use gpui::{
 
 pub use crate::clickable::*;
 pub use crate::disableable::*;
+pub use crate::drop_target::*;
 pub use crate::fixed::*;
 pub use crate::selectable::*;
 pub use crate::styles::{rems_from_px, vh, vw, PlatformStyle};
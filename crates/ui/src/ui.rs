@@ -6,6 +6,7 @@
 mod clickable;
 mod components;
 mod disableable;
+mod drop_target;
 mod fixed;
 pub mod prelude;
 mod selectable;
@@ -17,6 +18,7 @@ mod visible_on_hover;
 pub use clickable::*;
 pub use components::*;
 pub use disableable::*;
+pub use drop_target::*;
 pub use fixed::*;
 pub use prelude::*;
 
@@ -0,0 +1,328 @@
+mod undo_history_panel_settings;
+mod undo_tree;
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorMode};
+use gpui::{
+    actions, px, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Pixels, Render, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::Buffer;
+use project::Fs;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use theme::ActiveTheme;
+use ui::{prelude::*, Tooltip};
+use undo_history_panel_settings::UndoHistoryPanelSettings;
+use undo_tree::UndoTree;
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const UNDO_HISTORY_PANEL_KEY: &str = "UndoHistoryPanel";
+
+actions!(undo_history_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    UndoHistoryPanelSettings::register(cx);
+
+    // Track the history of buffers from when they're first opened, so that it's
+    // complete whenever the panel is opened.
+    cx.observe_new_views(|editor: &mut Editor, cx| {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+        if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+            UndoTree::for_buffer(&buffer, cx);
+        }
+    })
+    .detach();
+}
+
+/// Shows every state the active buffer has been in as a tree, including those
+/// that can't be reached by undoing and redoing anymore, and jumps back to them.
+pub struct UndoHistoryPanel {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    tree: Option<Model<UndoTree>>,
+    _tree_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedUndoHistoryPanel {
+    width: Option<Pixels>,
+}
+
+/// A node of the tree as it's listed in the panel.
+struct Row {
+    ix: usize,
+    /// How many branches the node is away from the newest one.
+    column: usize,
+    is_current: bool,
+    is_on_current_path: bool,
+    preview: String,
+    changed_at: time::OffsetDateTime,
+}
+
+impl UndoHistoryPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let active_buffer = active_buffer(workspace, cx);
+        let workspace_view = cx.view().clone();
+        cx.new_view(|cx| {
+            let subscriptions =
+                vec![cx.subscribe(&workspace_view, |this, workspace, event, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        let buffer = active_buffer(workspace.read(cx), cx);
+                        this.set_buffer(buffer, cx);
+                    }
+                })];
+
+            let mut this = Self {
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                tree: None,
+                _tree_subscription: None,
+                _subscriptions: subscriptions,
+            };
+            this.set_buffer(active_buffer, cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(UNDO_HISTORY_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedUndoHistoryPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        UNDO_HISTORY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedUndoHistoryPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn set_buffer(&mut self, buffer: Option<Model<Buffer>>, cx: &mut ViewContext<Self>) {
+        self.tree = buffer.map(|buffer| UndoTree::for_buffer(&buffer, cx));
+        self._tree_subscription = self
+            .tree
+            .as_ref()
+            .map(|tree| cx.observe(tree, |_, _, cx| cx.notify()));
+        cx.notify();
+    }
+
+    /// Lists the nodes from the oldest down, following the newest branch from each
+    /// node first and the older ones after it, further to the right.
+    fn rows(tree: &UndoTree) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let mut stack = vec![(0, 0)];
+        while let Some((ix, column)) = stack.pop() {
+            let node = &tree.nodes()[ix];
+            for (branch, child) in node.children.iter().enumerate() {
+                stack.push((*child, column + node.children.len() - 1 - branch));
+            }
+            rows.push(Row {
+                ix,
+                column,
+                is_current: ix == tree.current(),
+                is_on_current_path: tree.is_on_current_path(ix),
+                preview: if node.parent.is_none() {
+                    "Opened".to_string()
+                } else {
+                    format!("{}: {}", node.row + 1, node.preview)
+                },
+                changed_at: node.changed_at,
+            });
+        }
+        rows
+    }
+
+    fn render_row(&self, row: Row, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let color = if row.is_on_current_path {
+            Color::Default
+        } else {
+            Color::Muted
+        };
+        let timestamp = time_format::format_localized_timestamp(
+            row.changed_at,
+            time::OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            time_format::TimestampFormat::Relative,
+        );
+        let ix = row.ix;
+
+        h_flex()
+            .id(("undo-state", ix))
+            .px_2()
+            .gap_2()
+            .w_full()
+            .cursor_pointer()
+            .when(row.is_current, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(div().w(px(12.) * row.column as f32))
+            .child(
+                Label::new(if row.is_current { "●" } else { "○" })
+                    .size(LabelSize::Small)
+                    .color(color),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(Label::new(row.preview).size(LabelSize::Small).color(color)),
+            )
+            .child(
+                Label::new(timestamp)
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .tooltip(|cx| Tooltip::text("Go Back to This State", cx))
+            .on_click(cx.listener(move |this, _, cx| {
+                if let Some(tree) = this.tree.clone() {
+                    tree.update(cx, |tree, cx| tree.jump_to(ix, cx));
+                }
+            }))
+    }
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+    editor.read(cx).buffer().read(cx).as_singleton()
+}
+
+impl Render for UndoHistoryPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let root = v_flex()
+            .key_context("UndoHistoryPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Undo History")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            );
+
+        let Some(tree) = self.tree.clone() else {
+            return root.child(
+                v_flex()
+                    .p_4()
+                    .child(Label::new("Open a file to see its undo history.").color(Color::Muted)),
+            );
+        };
+
+        let rows = Self::rows(tree.read(cx))
+            .into_iter()
+            .map(|row| self.render_row(row, cx).into_any_element())
+            .collect::<Vec<_>>();
+        root.child(
+            v_flex()
+                .id("undo-history-states")
+                .flex_1()
+                .overflow_y_scroll()
+                .children(rows),
+        )
+    }
+}
+
+impl FocusableView for UndoHistoryPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for UndoHistoryPanel {}
+
+impl Panel for UndoHistoryPanel {
+    fn persistent_name() -> &'static str {
+        "UndoHistoryPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        UndoHistoryPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<UndoHistoryPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| UndoHistoryPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        UndoHistoryPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::ArrowCircle)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Undo History")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,229 @@
+use collections::HashMap;
+use gpui::{AppContext, Context, EntityId, Global, Model, ModelContext, Subscription, WeakModel};
+use language::{Buffer, Point, TransactionId};
+use text::Rope;
+use time::OffsetDateTime;
+
+/// The longest preview shown for a change, in characters.
+const MAX_PREVIEW_LEN: usize = 80;
+
+/// The undo tree of every buffer that's been opened in an editor, so that their
+/// history is known from the start when the panel is opened later.
+#[derive(Default)]
+struct UndoTrees(HashMap<EntityId, Model<UndoTree>>);
+
+impl Global for UndoTrees {}
+
+/// Every state a buffer has been in, including those abandoned by making a change
+/// after undoing, which its linear undo and redo stacks forget.
+///
+/// Each edit that brings the buffer back to a known state, like undoing or
+/// redoing, moves to that state's node. Other edits either extend the current
+/// node when they're part of the same transaction, or start a new branch from it.
+pub struct UndoTree {
+    buffer: WeakModel<Buffer>,
+    nodes: Vec<UndoNode>,
+    current: usize,
+    version: clock::Global,
+    _subscription: Subscription,
+}
+
+pub struct UndoNode {
+    pub parent: Option<usize>,
+    /// The nodes branching from this one, from the oldest to the most recent.
+    pub children: Vec<usize>,
+    /// When the buffer was last changed into this state.
+    pub changed_at: OffsetDateTime,
+    /// The line of the first change that led to this state, as it reads in it.
+    pub preview: String,
+    pub row: u32,
+    transaction_id: Option<TransactionId>,
+    text: Rope,
+}
+
+impl UndoTree {
+    /// Returns the undo tree of the buffer, starting it from the buffer's current
+    /// state if it isn't tracked yet.
+    pub fn for_buffer(buffer: &Model<Buffer>, cx: &mut AppContext) -> Model<Self> {
+        let buffer_id = buffer.entity_id();
+        if let Some(tree) = cx.default_global::<UndoTrees>().0.get(&buffer_id) {
+            return tree.clone();
+        }
+
+        let tree = cx.new_model(|cx| Self::new(buffer, cx));
+        cx.observe_release(buffer, move |_, cx| {
+            cx.default_global::<UndoTrees>().0.remove(&buffer_id);
+        })
+        .detach();
+        cx.default_global::<UndoTrees>()
+            .0
+            .insert(buffer_id, tree.clone());
+        tree
+    }
+
+    fn new(buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) -> Self {
+        let subscription = cx.subscribe(buffer, |this, buffer, event, cx| {
+            if let language::Event::Edited = event {
+                this.buffer_edited(buffer, cx);
+            }
+        });
+        let buffer_ref = buffer.read(cx);
+        Self {
+            buffer: buffer.downgrade(),
+            nodes: vec![UndoNode {
+                parent: None,
+                children: Vec::new(),
+                changed_at: OffsetDateTime::now_utc(),
+                preview: String::new(),
+                row: 0,
+                transaction_id: None,
+                text: buffer_ref.as_rope().clone(),
+            }],
+            current: 0,
+            version: buffer_ref.version(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn nodes(&self) -> &[UndoNode] {
+        &self.nodes
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Returns whether undoing from the current state would pass through the node.
+    pub fn is_on_current_path(&self, ix: usize) -> bool {
+        let mut node = Some(self.current);
+        while let Some(node_ix) = node {
+            if node_ix == ix {
+                return true;
+            }
+            node = self.nodes[node_ix].parent;
+        }
+        false
+    }
+
+    /// Changes the buffer back into the node's state, as a change of its own.
+    pub fn jump_to(&mut self, ix: usize, cx: &mut ModelContext<Self>) {
+        let Some(buffer) = self.buffer.upgrade() else {
+            return;
+        };
+        if ix == self.current {
+            return;
+        }
+        let diff = buffer.read(cx).diff(self.nodes[ix].text.to_string(), cx);
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.apply_diff(diff, cx);
+                buffer.finalize_last_transaction();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn buffer_edited(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer = buffer.read(cx);
+        let text = buffer.as_rope().clone();
+        let transaction_id = buffer.peek_undo_stack().map(|entry| entry.transaction_id());
+        let row = buffer
+            .edits_since::<Point>(&self.version)
+            .next()
+            .map_or(0, |edit| edit.new.start.row);
+        let preview = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        let preview = preview.trim();
+        let preview = match preview.char_indices().nth(MAX_PREVIEW_LEN) {
+            Some((end, _)) => format!("{}…", &preview[..end]),
+            None => preview.to_string(),
+        };
+        self.version = buffer.version();
+
+        if let Some(ix) = self.node_with_text(&text) {
+            self.current = ix;
+        } else {
+            let current = &mut self.nodes[self.current];
+            if current.transaction_id.is_some()
+                && current.transaction_id == transaction_id
+                && current.children.is_empty()
+            {
+                current.text = text;
+                current.changed_at = OffsetDateTime::now_utc();
+                current.preview = preview;
+                current.row = row;
+            } else {
+                let ix = self.nodes.len();
+                self.nodes.push(UndoNode {
+                    parent: Some(self.current),
+                    children: Vec::new(),
+                    changed_at: OffsetDateTime::now_utc(),
+                    preview,
+                    row,
+                    transaction_id,
+                    text,
+                });
+                self.nodes[self.current].children.push(ix);
+                self.current = ix;
+            }
+        }
+        cx.notify();
+    }
+
+    /// Finds a node whose state has the given text, looking at the ones next to the
+    /// current one first.
+    fn node_with_text(&self, text: &Rope) -> Option<usize> {
+        let current = &self.nodes[self.current];
+        current
+            .parent
+            .into_iter()
+            .chain(current.children.iter().rev().copied())
+            .chain(0..self.nodes.len())
+            .find(|ix| {
+                let node_text = &self.nodes[*ix].text;
+                node_text.len() == text.len()
+                    && node_text
+                        .chunks()
+                        .flat_map(str::bytes)
+                        .eq(text.chunks().flat_map(str::bytes))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_undo_tree(cx: &mut TestAppContext) {
+        let buffer = cx.new_model(|cx| Buffer::local("abc", cx));
+        let tree = cx.update(|cx| UndoTree::for_buffer(&buffer, cx));
+
+        buffer.update(cx, |buffer, cx| buffer.edit([(3..3, "d")], None, cx));
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+        tree.read_with(cx, |tree, _| assert_eq!(tree.current(), 0));
+
+        // Changing the buffer after undoing starts a new branch.
+        buffer.update(cx, |buffer, cx| buffer.edit([(3..3, "e")], None, cx));
+        tree.read_with(cx, |tree, _| {
+            assert_eq!(tree.current(), 2);
+            assert_eq!(tree.nodes()[0].children, [1, 2]);
+            assert_eq!(tree.nodes()[2].preview, "abce");
+            assert!(!tree.is_on_current_path(1));
+        });
+
+        // The abandoned branch can still be jumped to, and that can be undone.
+        tree.update(cx, |tree, cx| tree.jump_to(1, cx));
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "abcd");
+        tree.read_with(cx, |tree, _| assert_eq!(tree.current(), 1));
+
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "abce");
+        tree.read_with(cx, |tree, _| assert_eq!(tree.current(), 2));
+    }
+}
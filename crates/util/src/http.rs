@@ -1,35 +1,120 @@
-use crate::http_proxy_from_env;
+use crate::{http_proxy_from_env, no_proxy_from_env};
 pub use anyhow::{anyhow, Result};
 use futures::future::BoxFuture;
 use futures_lite::FutureExt;
-use isahc::config::{Configurable, RedirectPolicy};
+use isahc::config::{CaCertificate, RedirectPolicy};
 pub use isahc::{
+    config::Configurable,
     http::{Method, StatusCode, Uri},
     AsyncBody, Error, HttpClient as IsahcHttpClient, Request, Response,
 };
 #[cfg(feature = "test-support")]
 use std::fmt;
 use std::{
+    io,
+    path::PathBuf,
     sync::{Arc, Mutex},
     time::Duration,
 };
 pub use url::Url;
 
+/// How HTTP clients connect to servers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// The proxy to send requests through, which can be an `http`, `https`, `socks5`
+    /// or `socks5h` URL.
+    pub proxy: Option<Uri>,
+    /// The hosts that are connected to directly instead of through the proxy.
+    pub no_proxy: Vec<String>,
+    /// A PEM file of certificate authorities that replaces the system's trust store,
+    /// so that only servers whose certificates they signed are trusted.
+    pub replace_ca_certificates: Option<PathBuf>,
+    /// Whether requests to other machines fail immediately instead of being sent.
+    pub offline: bool,
+}
+
+impl HttpClientConfig {
+    /// Returns the configuration given by the conventional `*_PROXY` environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            proxy: http_proxy_from_env(),
+            no_proxy: no_proxy_from_env(),
+            replace_ca_certificates: None,
+            offline: false,
+        }
+    }
+}
+
 /// An [`HttpClient`] that has a base URL.
 pub struct HttpClientWithUrl {
     base_url: Mutex<String>,
-    client: Arc<dyn HttpClient>,
+    client: Mutex<(HttpClientConfig, Arc<dyn HttpClient>)>,
+    /// Whether the client is kept when the configuration changes, like the fake
+    /// clients of tests, instead of being replaced by one built for it.
+    keep_client: bool,
 }
 
 impl HttpClientWithUrl {
     /// Returns a new [`HttpClientWithUrl`] with the given base URL.
     pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_config(base_url, HttpClientConfig::from_env())
+    }
+
+    /// Returns a new [`HttpClientWithUrl`] with the given base URL, which connects
+    /// to servers as described by the given configuration.
+    pub fn with_config(base_url: impl Into<String>, config: HttpClientConfig) -> Self {
+        let client = client_with_config(&config);
         Self {
             base_url: Mutex::new(base_url.into()),
-            client: client(),
+            client: Mutex::new((config, client)),
+            keep_client: false,
+        }
+    }
+
+    /// Changes how requests connect to servers. Requests that are already in flight
+    /// keep using the previous configuration. Fake clients only take the offline
+    /// setting into account.
+    pub fn set_config(&self, config: HttpClientConfig) {
+        if let Ok(mut client) = self.client.lock() {
+            if client.0 != config {
+                if !self.keep_client {
+                    client.1 = client_with_config(&config);
+                }
+                client.0 = config;
+            }
+        }
+    }
+
+    /// Returns whether requests to other machines are refused. Requests to
+    /// servers on this machine, like Ollama, are still sent.
+    pub fn is_offline(&self) -> bool {
+        match self.client.lock() {
+            Ok(client) => client.0.offline,
+            Err(poisoned) => poisoned.into_inner().0.offline,
         }
     }
 
+    fn send_request(
+        &self,
+        req: Request<AsyncBody>,
+    ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
+        let (offline, client) = match self.client.lock() {
+            Ok(client) => (client.0.offline, client.1.clone()),
+            Err(poisoned) => {
+                let client = poisoned.into_inner();
+                (client.0.offline, client.1.clone())
+            }
+        };
+        if offline && !is_local_uri(req.uri()) {
+            let error = io::Error::new(
+                io::ErrorKind::NotConnected,
+                format!("not sending a request to {} while offline", req.uri()),
+            );
+            return async move { Err(error.into()) }.boxed();
+        }
+        client.send(req)
+    }
+
     /// Returns the base URL.
     pub fn base_url(&self) -> String {
         self.base_url
@@ -75,7 +160,7 @@ impl HttpClient for Arc<HttpClientWithUrl> {
         &self,
         req: Request<AsyncBody>,
     ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
-        self.client.send(req)
+        self.send_request(req)
     }
 }
 
@@ -84,7 +169,7 @@ impl HttpClient for HttpClientWithUrl {
         &self,
         req: Request<AsyncBody>,
     ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
-        self.client.send(req)
+        self.send_request(req)
     }
 }
 
@@ -133,14 +218,77 @@ pub trait HttpClient: Send + Sync {
 }
 
 pub fn client() -> Arc<dyn HttpClient> {
-    Arc::new(
-        isahc::HttpClient::builder()
-            .connect_timeout(Duration::from_secs(5))
-            .low_speed_timeout(100, Duration::from_secs(5))
-            .proxy(http_proxy_from_env())
-            .build()
-            .unwrap(),
-    )
+    client_with_config(&HttpClientConfig::from_env())
+}
+
+/// The hosts that point at this machine.
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "::1"];
+
+/// Returns whether the URI points at this machine.
+pub fn is_local_uri(uri: &Uri) -> bool {
+    uri.host().map_or(false, |host| {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        LOCAL_HOSTS.contains(&host)
+    })
+}
+
+/// Returns a client that connects to servers as described by the given configuration.
+///
+/// When the client can't be created, the error is logged and a client with the default
+/// configuration is returned instead, or one whose requests fail if that can't be
+/// created either.
+pub fn client_with_config(config: &HttpClientConfig) -> Arc<dyn HttpClient> {
+    let mut builder = default_client_builder()
+        .proxy(config.proxy.clone())
+        .proxy_blacklist(proxy_blacklist(config));
+    if let Some(ca_certificates) = &config.replace_ca_certificates {
+        builder = builder.ssl_ca_certificate(CaCertificate::file(ca_certificates));
+    }
+    match builder.build() {
+        Ok(client) => return Arc::new(client),
+        Err(error) => log::error!("failed to create an HTTP client for {config:?}: {error}"),
+    }
+    match default_client_builder().build() {
+        Ok(client) => Arc::new(client),
+        Err(error) => {
+            log::error!("failed to create an HTTP client: {error}");
+            Arc::new(UnavailableHttpClient(error.to_string()))
+        }
+    }
+}
+
+/// Returns the hosts that are connected to directly, which always include this
+/// machine so that local servers like Ollama keep working behind a proxy.
+fn proxy_blacklist(config: &HttpClientConfig) -> Vec<String> {
+    let mut hosts = config.no_proxy.clone();
+    for host in LOCAL_HOSTS {
+        if !hosts.iter().any(|existing| existing == host) {
+            hosts.push(host.to_string());
+        }
+    }
+    hosts
+}
+
+fn default_client_builder() -> isahc::HttpClientBuilder {
+    isahc::HttpClient::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .low_speed_timeout(100, Duration::from_secs(5))
+}
+
+/// A client that fails every request, used when no client could be created.
+struct UnavailableHttpClient(String);
+
+impl HttpClient for UnavailableHttpClient {
+    fn send(
+        &self,
+        req: Request<AsyncBody>,
+    ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
+        let error = io::Error::new(
+            io::ErrorKind::Other,
+            format!("can't send a request to {}: {}", req.uri(), self.0),
+        );
+        async move { Err(error.into()) }.boxed()
+    }
 }
 
 impl HttpClient for isahc::HttpClient {
@@ -175,9 +323,13 @@ impl FakeHttpClient {
     {
         Arc::new(HttpClientWithUrl {
             base_url: Mutex::new("http://test.example".into()),
-            client: Arc::new(Self {
-                handler: Box::new(move |req| Box::pin(handler(req))),
-            }),
+            client: Mutex::new((
+                HttpClientConfig::default(),
+                Arc::new(Self {
+                    handler: Box::new(move |req| Box::pin(handler(req))),
+                }),
+            )),
+            keep_client: true,
         })
     }
 
@@ -217,3 +369,141 @@ impl HttpClient for FakeHttpClient {
         Box::pin(async move { future.await.map(Into::into) })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+    /// A client that counts the requests that reach it.
+    #[derive(Default)]
+    struct CountingClient(AtomicUsize);
+
+    impl HttpClient for CountingClient {
+        fn send(
+            &self,
+            _: Request<AsyncBody>,
+        ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
+            self.0.fetch_add(1, SeqCst);
+            async move {
+                Ok(Response::builder()
+                    .status(200)
+                    .body(AsyncBody::empty())
+                    .unwrap())
+            }
+            .boxed()
+        }
+    }
+
+    fn client_with_url(config: HttpClientConfig, client: Arc<CountingClient>) -> HttpClientWithUrl {
+        HttpClientWithUrl {
+            base_url: Mutex::new("http://test.example".into()),
+            client: Mutex::new((config, client)),
+            keep_client: true,
+        }
+    }
+
+    #[test]
+    fn test_offline_requests() {
+        let counting_client = Arc::new(CountingClient::default());
+        let client = client_with_url(
+            HttpClientConfig {
+                offline: true,
+                ..Default::default()
+            },
+            counting_client.clone(),
+        );
+
+        block_on(client.get("https://zed.dev/api", AsyncBody::empty(), false)).unwrap_err();
+        assert_eq!(counting_client.0.load(SeqCst), 0);
+
+        for uri in [
+            "http://localhost:11434/api/embed",
+            "http://127.0.0.1:8080/embedding",
+            "http://[::1]:1234/v1/embeddings",
+        ] {
+            block_on(client.get(uri, AsyncBody::empty(), false)).unwrap();
+        }
+        assert_eq!(counting_client.0.load(SeqCst), 3);
+
+        client.set_config(HttpClientConfig::default());
+        block_on(client.get("https://zed.dev/api", AsyncBody::empty(), false)).unwrap();
+        assert_eq!(counting_client.0.load(SeqCst), 4);
+    }
+
+    #[test]
+    fn test_set_config_keeps_fake_client() {
+        let counting_client = Arc::new(CountingClient::default());
+        let client = client_with_url(HttpClientConfig::default(), counting_client.clone());
+
+        client.set_config(HttpClientConfig {
+            proxy: Some("http://proxy.example:8080".parse().unwrap()),
+            no_proxy: vec!["localhost".into()],
+            ..Default::default()
+        });
+        block_on(client.get("https://zed.dev/api", AsyncBody::empty(), false)).unwrap();
+        assert_eq!(counting_client.0.load(SeqCst), 1);
+
+        client.set_config(HttpClientConfig {
+            offline: true,
+            ..Default::default()
+        });
+        assert!(client.is_offline());
+        block_on(client.get("https://zed.dev/api", AsyncBody::empty(), false)).unwrap_err();
+        assert_eq!(counting_client.0.load(SeqCst), 1);
+    }
+
+    #[test]
+    fn test_unavailable_client() {
+        let client = UnavailableHttpClient("no agent thread".into());
+        let error = block_on(client.get("https://zed.dev/api", AsyncBody::empty(), false))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("https://zed.dev/api"), "{error}");
+        assert!(error.contains("no agent thread"), "{error}");
+    }
+
+    #[test]
+    fn test_client_with_invalid_config() {
+        // Settings that can't be used only fail the requests that need them,
+        // instead of failing to create the client.
+        client_with_config(&HttpClientConfig {
+            proxy: Some("socks5h://proxy.invalid:1080".parse().unwrap()),
+            no_proxy: vec!["localhost".into()],
+            replace_ca_certificates: Some("/nonexistent/certificates.pem".into()),
+            offline: false,
+        });
+    }
+
+    #[test]
+    fn test_proxy_blacklist() {
+        assert_eq!(
+            proxy_blacklist(&HttpClientConfig {
+                proxy: Some("http://proxy.example.com:8080".parse().unwrap()),
+                no_proxy: vec!["internal.example.com".into(), "localhost".into()],
+                replace_ca_certificates: None,
+                offline: false,
+            }),
+            ["internal.example.com", "localhost", "127.0.0.1", "::1"]
+        );
+    }
+
+    #[test]
+    fn test_is_local_uri() {
+        for uri in [
+            "http://localhost:11434",
+            "http://127.0.0.1",
+            "http://[::1]:8080/",
+        ] {
+            assert!(is_local_uri(&uri.parse().unwrap()), "{uri}");
+        }
+        for uri in [
+            "https://zed.dev",
+            "http://192.168.1.10:11434",
+            "http://localhost.example",
+        ] {
+            assert!(!is_local_uri(&uri.parse().unwrap()), "{uri}");
+        }
+    }
+}
@@ -30,6 +30,7 @@ lazy_static::lazy_static! {
         SUPPORT_DIR.join("embeddings")
     };
     pub static ref THEMES_DIR: PathBuf = CONFIG_DIR.join("themes");
+    pub static ref TEMPLATES_DIR: PathBuf = CONFIG_DIR.join("templates");
 
     pub static ref SUPPORT_DIR: PathBuf = if cfg!(target_os = "macos") {
         HOME.join("Library/Application Support/Zed")
@@ -51,6 +52,7 @@ lazy_static::lazy_static! {
     };
     pub static ref EXTENSIONS_DIR: PathBuf = SUPPORT_DIR.join("extensions");
     pub static ref LANGUAGES_DIR: PathBuf = SUPPORT_DIR.join("languages");
+    pub static ref JSON_SCHEMAS_DIR: PathBuf = SUPPORT_DIR.join("json_schemas");
     pub static ref COPILOT_DIR: PathBuf = SUPPORT_DIR.join("copilot");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = SUPPORT_DIR.join("prettier");
     pub static ref DB_DIR: PathBuf = SUPPORT_DIR.join("db");
@@ -61,6 +63,7 @@ lazy_static::lazy_static! {
         .map(|dir| dir.join("Retired"));
 
     pub static ref SETTINGS: PathBuf = CONFIG_DIR.join("settings.json");
+    pub static ref SETTINGS_TOML: PathBuf = CONFIG_DIR.join("settings.toml");
     pub static ref KEYMAP: PathBuf = CONFIG_DIR.join("keymap.json");
     pub static ref TASKS: PathBuf = CONFIG_DIR.join("tasks.json");
     pub static ref LAST_USERNAME: PathBuf = CONFIG_DIR.join("last-username.txt");
@@ -68,6 +71,7 @@ lazy_static::lazy_static! {
     pub static ref OLD_LOG: PathBuf = LOGS_DIR.join("Zed.log.old");
     pub static ref LOCAL_SETTINGS_RELATIVE_PATH: &'static Path = Path::new(".zed/settings.json");
     pub static ref LOCAL_TASKS_RELATIVE_PATH: &'static Path = Path::new(".zed/tasks.json");
+    pub static ref LOCAL_ENV_RELATIVE_PATH: &'static Path = Path::new(".zed/env");
     pub static ref LOCAL_VSCODE_TASKS_RELATIVE_PATH: &'static Path = Path::new(".vscode/tasks.json");
     pub static ref TEMP_DIR: PathBuf = if cfg!(target_os = "widows") {
         dirs::cache_dir()
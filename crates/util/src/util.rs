@@ -64,6 +64,22 @@ pub fn http_proxy_from_env() -> Option<isahc::http::Uri> {
     None
 }
 
+/// Returns the hosts listed in the `NO_PROXY` environment variable, which are
+/// connected to directly instead of through a proxy.
+pub fn no_proxy_from_env() -> Vec<String> {
+    ["NO_PROXY", "no_proxy"]
+        .into_iter()
+        .find_map(|env| std::env::var(env).ok())
+        .map(|hosts| {
+            hosts
+                .split(',')
+                .map(|host| host.trim().to_string())
+                .filter(|host| !host.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Removes characters from the end of the string if its length is greater than `max_chars` and
 /// appends "..." to the string. Returns string unchanged if its length is smaller than max_chars.
 pub fn truncate_and_trailoff(s: &str, max_chars: usize) -> String {
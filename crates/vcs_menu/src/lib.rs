@@ -1,16 +1,18 @@
-use anyhow::{anyhow, bail, Result};
+use anyhow::{bail, Context as _, Result};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::repository::Branch;
+use git::repository::{Branch, GitRepository};
 use gpui::{
     actions, rems, AnyElement, AppContext, DismissEvent, Element, EventEmitter, FocusHandle,
-    FocusableView, InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+    FocusableView, InteractiveElement, IntoElement, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, View, ViewContext, VisualContext, WindowContext,
 };
+use parking_lot::Mutex;
 use picker::{Picker, PickerDelegate};
-use std::{ops::Not, sync::Arc};
+use std::{fmt, ops::Not, sync::Arc};
 use ui::{
-    h_flex, v_flex, Button, ButtonCommon, Clickable, Color, HighlightedLabel, Label, LabelCommon,
-    LabelSize, ListItem, ListItemSpacing, Selectable,
+    h_flex, v_flex, Button, ButtonCommon, Clickable, Color, HighlightedLabel, Icon, IconButton,
+    IconName, IconSize, Label, LabelCommon, LabelSize, ListItem, ListItemSpacing, Selectable,
+    Tooltip,
 };
 use util::ResultExt;
 use workspace::notifications::NotificationId;
@@ -87,12 +89,99 @@ pub fn build_branch_list(
     Ok(cx.new_view(move |cx| BranchList::new(delegate, 20., cx)))
 }
 
+/// What to do with uncommitted changes when switching to another branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UncommittedChanges {
+    /// Stash the changes, and reapply them the next time their branch is checked out.
+    LeaveOnBranch,
+    /// Stash the changes, and reapply them on the branch being checked out.
+    Bring,
+}
+
+const BROUGHT_CHANGES_STASH_MESSAGE: &str = "zed: changes brought from another branch";
+
+fn left_changes_stash_message(branch: &str) -> String {
+    format!("zed: changes left on branch '{branch}'")
+}
+
+/// The error returned when a branch was checked out, but the changes that were
+/// stashed for it conflicted with it when they were reapplied.
+#[derive(Debug)]
+struct StashConflict {
+    branch: String,
+    stash_message: String,
+}
+
+impl fmt::Display for StashConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Checked out '{}', but the stashed changes conflicted with it. \
+            Resolve the conflicts, then drop the stash \"{}\".",
+            self.branch, self.stash_message
+        )
+    }
+}
+
+impl std::error::Error for StashConflict {}
+
+/// Checks out `target`, first stashing any uncommitted changes as requested. Changes
+/// that were previously left on `target` are reapplied, unless other changes were brought along.
+fn checkout_branch(
+    repo: &dyn GitRepository,
+    target: &str,
+    uncommitted_changes: Option<UncommittedChanges>,
+) -> Result<()> {
+    let stash_message = match uncommitted_changes {
+        Some(UncommittedChanges::LeaveOnBranch) => {
+            let Some(current_branch) = repo.branch_name() else {
+                bail!("Cannot leave changes on a detached HEAD");
+            };
+            Some(left_changes_stash_message(&current_branch))
+        }
+        Some(UncommittedChanges::Bring) => Some(BROUGHT_CHANGES_STASH_MESSAGE.to_string()),
+        None => None,
+    };
+    if let Some(stash_message) = &stash_message {
+        repo.stash_changes(stash_message)?;
+    }
+
+    if let Err(error) = repo.change_branch(target) {
+        // Put the stashed changes back where they came from.
+        if let Some(stash_message) = &stash_message {
+            repo.pop_stash(stash_message).with_context(|| {
+                format!("{error}, and the stashed changes couldn't be reapplied")
+            })?;
+        }
+        return Err(error);
+    }
+
+    // Git keeps the stash when reapplying it conflicts, so nothing is lost.
+    let stash_message = if uncommitted_changes == Some(UncommittedChanges::Bring) {
+        BROUGHT_CHANGES_STASH_MESSAGE.to_string()
+    } else {
+        left_changes_stash_message(target)
+    };
+    if let Err(error) = repo.pop_stash(&stash_message) {
+        log::error!("failed to reapply stash {stash_message:?}: {error:?}");
+        return Err(StashConflict {
+            branch: target.to_string(),
+            stash_message,
+        }
+        .into());
+    }
+    Ok(())
+}
+
 pub struct BranchListDelegate {
     matches: Vec<StringMatch>,
     all_branches: Vec<Branch>,
+    repo: Arc<Mutex<dyn GitRepository>>,
     workspace: View<Workspace>,
     selected_index: usize,
     last_query: String,
+    /// The branch whose new name is being typed into the query editor.
+    renaming: Option<String>,
     /// Max length of branch name before we truncate it and add a trailing `...`.
     branch_name_trailoff_after: usize,
 }
@@ -119,22 +208,145 @@ impl BranchListDelegate {
             matches: vec![],
             workspace: handle,
             all_branches,
+            repo,
             selected_index: 0,
             last_query: Default::default(),
+            renaming: None,
             branch_name_trailoff_after,
         })
     }
 
     fn display_error_toast(&self, message: String, cx: &mut WindowContext<'_>) {
-        self.workspace.update(cx, |model, ctx| {
-            struct GitCheckoutFailure;
-            let id = NotificationId::unique::<GitCheckoutFailure>();
+        display_error_toast(&self.workspace, message, cx);
+    }
+
+    fn reload_branches(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        match self.repo.lock().branches() {
+            Ok(branches) => self.all_branches = branches,
+            Err(error) => self.display_error_toast(format!("Failed to load branches: {error}"), cx),
+        }
+    }
 
-            model.show_toast(Toast::new(id, message), ctx)
+    fn branch(&self, name: &str) -> Option<&Branch> {
+        self.all_branches
+            .iter()
+            .find(|branch| branch.name.as_ref() == name)
+    }
+
+    fn selected_branch_name(&self) -> Option<String> {
+        self.matches
+            .get(self.selected_index)
+            .map(|pick| pick.string.clone())
+    }
+
+    /// Checks out the given branch, asking what to do with uncommitted changes first.
+    fn switch_to_branch(&self, target: String, cx: &mut ViewContext<Picker<Self>>) {
+        let repo = self.repo.clone();
+        let workspace = self.workspace.clone();
+        let has_uncommitted_changes = repo.lock().has_uncommitted_changes().unwrap_or(false);
+        let prompt = has_uncommitted_changes.then(|| {
+            let current_branch = repo.lock().branch_name().unwrap_or_else(|| "HEAD".into());
+            cx.prompt(
+                PromptLevel::Info,
+                &format!("You have uncommitted changes on '{current_branch}'."),
+                Some(&format!(
+                    "Stash them to have them reapplied when you return to '{current_branch}', \
+                    or bring them along to '{target}'."
+                )),
+                &["Stash and Reapply Later", "Bring Changes", "Cancel"],
+            )
         });
+
+        cx.spawn(|_, mut cx| async move {
+            let uncommitted_changes = match prompt {
+                Some(prompt) => match prompt.await? {
+                    0 => Some(UncommittedChanges::LeaveOnBranch),
+                    1 => Some(UncommittedChanges::Bring),
+                    _ => return Ok(()),
+                },
+                None => None,
+            };
+            let result = checkout_branch(&*repo.lock(), &target, uncommitted_changes);
+            if let Err(error) = result {
+                let message = if error.is::<StashConflict>() {
+                    error.to_string()
+                } else {
+                    format!("Failed to checkout branch '{target}': {error}")
+                };
+                cx.update(|cx| display_error_toast(&workspace, message, cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn create_branch(&mut self, base: Option<String>, cx: &mut ViewContext<Picker<Self>>) {
+        let name = self.last_query.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        if let Some(base) = base {
+            let status = self.repo.lock().create_branch_from(&name, &base);
+            match status {
+                Ok(()) => self.switch_to_branch(name, cx),
+                Err(error) => self.display_error_toast(
+                    format!("Failed to create branch '{name}' from '{base}': {error}"),
+                    cx,
+                ),
+            }
+        } else {
+            // A branch created from the current commit can be checked out without
+            // touching the working tree, so uncommitted changes simply carry over.
+            let repo = self.repo.lock();
+            let status = repo
+                .create_branch(&name)
+                .and_then(|_| repo.change_branch(&name));
+            drop(repo);
+            match status {
+                Ok(()) => cx.emit(DismissEvent),
+                Err(error) => self
+                    .display_error_toast(format!("Failed to create branch '{name}': {error}"), cx),
+            }
+        }
+    }
+
+    fn rename_branch(&mut self, name: String, cx: &mut ViewContext<Picker<Self>>) {
+        let new_name = self.last_query.trim().to_string();
+        if new_name.is_empty() || new_name == name {
+            self.renaming = Some(name);
+            return;
+        }
+        let status = self.repo.lock().rename_branch(&name, &new_name);
+        match status {
+            Ok(()) => cx.emit(DismissEvent),
+            Err(error) => self.display_error_toast(
+                format!("Failed to rename branch '{name}' to '{new_name}': {error}"),
+                cx,
+            ),
+        }
+    }
+
+    fn delete_branch(&mut self, name: &str, cx: &mut ViewContext<Picker<Self>>) {
+        let status = self.repo.lock().delete_branch(name);
+        if let Err(error) = status {
+            self.display_error_toast(format!("Failed to delete branch '{name}': {error}"), cx);
+            return;
+        }
+        self.reload_branches(cx);
     }
 }
 
+fn display_error_toast(workspace: &View<Workspace>, message: String, cx: &mut WindowContext<'_>) {
+    workspace.update(cx, |model, ctx| {
+        struct GitCheckoutFailure;
+        let id = NotificationId::unique::<GitCheckoutFailure>();
+
+        model.show_toast(Toast::new(id, message), ctx)
+    });
+}
+
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
@@ -220,48 +432,22 @@ impl PickerDelegate for BranchListDelegate {
     }
 
     fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
-        let current_pick = self.selected_index();
-        let Some(current_pick) = self
-            .matches
-            .get(current_pick)
-            .map(|pick| pick.string.clone())
-        else {
+        if let Some(name) = self.renaming.take() {
+            self.rename_branch(name, cx);
             return;
-        };
-        cx.spawn(|picker, mut cx| async move {
-            picker
-                .update(&mut cx, |this, cx| {
-                    let project = this.delegate.workspace.read(cx).project().read(cx);
-                    let mut cwd = project
-                        .visible_worktrees(cx)
-                        .next()
-                        .ok_or_else(|| anyhow!("There are no visisible worktrees."))?
-                        .read(cx)
-                        .abs_path()
-                        .to_path_buf();
-                    cwd.push(".git");
-                    let status = project
-                        .fs()
-                        .open_repo(&cwd)
-                        .ok_or_else(|| {
-                            anyhow!(
-                                "Could not open repository at path `{}`",
-                                cwd.as_os_str().to_string_lossy()
-                            )
-                        })?
-                        .lock()
-                        .change_branch(&current_pick);
-                    if status.is_err() {
-                        this.delegate.display_error_toast(format!("Failed to checkout branch '{current_pick}', check for conflicts or unstashed files"), cx);
-                        status?;
-                    }
-                    cx.emit(DismissEvent);
+        }
 
-                    Ok::<(), anyhow::Error>(())
-                })
-                .log_err();
-        })
-        .detach();
+        let Some(current_pick) = self.selected_branch_name() else {
+            return;
+        };
+        if self
+            .branch(&current_pick)
+            .map_or(false, |branch| branch.is_head)
+        {
+            cx.emit(DismissEvent);
+            return;
+        }
+        self.switch_to_branch(current_pick, cx);
     }
 
     fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
@@ -272,9 +458,12 @@ impl PickerDelegate for BranchListDelegate {
         &self,
         ix: usize,
         selected: bool,
-        _cx: &mut ViewContext<Picker<Self>>,
+        cx: &mut ViewContext<Picker<Self>>,
     ) -> Option<Self::ListItem> {
         let hit = &self.matches[ix];
+        let branch = self.branch(&hit.string);
+        let is_head = branch.map_or(false, |branch| branch.is_head);
+        let is_merged = branch.map_or(false, |branch| branch.is_merged);
         let shortened_branch_name =
             util::truncate_and_trailoff(&hit.string, self.branch_name_trailoff_after);
         let highlights: Vec<_> = hit
@@ -283,16 +472,61 @@ impl PickerDelegate for BranchListDelegate {
             .filter(|index| index < &&self.branch_name_trailoff_after)
             .copied()
             .collect();
+
+        let rename_button = IconButton::new(("rename-branch", ix), IconName::Pencil)
+            .icon_size(IconSize::Small)
+            .tooltip(|cx| Tooltip::text("Rename Branch", cx))
+            .on_click({
+                let name = hit.string.clone();
+                cx.listener(move |picker, _, cx| {
+                    picker.delegate.renaming = Some(name.clone());
+                    picker.set_query(name.clone(), cx);
+                })
+            });
+        let delete_button = (is_merged && !is_head).then(|| {
+            IconButton::new(("delete-branch", ix), IconName::Trash)
+                .icon_size(IconSize::Small)
+                .tooltip(|cx| Tooltip::text("Delete Merged Branch", cx))
+                .on_click({
+                    let name = hit.string.clone();
+                    cx.listener(move |picker, _, cx| {
+                        picker.delegate.delete_branch(&name, cx);
+                        picker.refresh(cx);
+                    })
+                })
+        });
+
         Some(
             ListItem::new(SharedString::from(format!("vcs-menu-{ix}")))
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .selected(selected)
-                .start_slot(HighlightedLabel::new(shortened_branch_name, highlights)),
+                .start_slot(
+                    h_flex()
+                        .gap_1()
+                        .child(HighlightedLabel::new(shortened_branch_name, highlights))
+                        .children(is_head.then(|| {
+                            Icon::new(IconName::Check)
+                                .size(IconSize::Small)
+                                .color(Color::Muted)
+                        })),
+                )
+                .end_slot(
+                    h_flex()
+                        .gap_1()
+                        .child(rename_button)
+                        .children(delete_button),
+                ),
         )
     }
     fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
-        let label = if self.last_query.is_empty() {
+        let label = if let Some(name) = &self.renaming {
+            h_flex().ml_3().child(
+                Label::new(format!("Type a new name for '{name}'"))
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+        } else if self.last_query.is_empty() {
             h_flex()
                 .ml_3()
                 .child(Label::new("Recent Branches").size(LabelSize::Small))
@@ -317,46 +551,198 @@ impl PickerDelegate for BranchListDelegate {
             return None;
         }
 
+        if self.renaming.is_some() {
+            return Some(
+                h_flex()
+                    .mr_3()
+                    .pb_2()
+                    .child(h_flex().w_full())
+                    .child(
+                        Button::new("branch-picker-rename-branch-button", "Rename branch")
+                            .style(ui::ButtonStyle::Filled)
+                            .on_click(cx.listener(|picker, _, cx| {
+                                picker.delegate.confirm(false, cx);
+                            })),
+                    )
+                    .into_any_element(),
+            );
+        }
+
+        let base = self.selected_branch_name();
         Some(
-            h_flex().mr_3().pb_2().child(h_flex().w_full()).child(
-            Button::new("branch-picker-create-branch-button", "Create branch").on_click(
-                cx.listener(|_, _, cx| {
-                    cx.spawn(|picker, mut cx| async move {
-                                        picker.update(&mut cx, |this, cx| {
-                                            let project = this.delegate.workspace.read(cx).project().read(cx);
-                                            let current_pick = &this.delegate.last_query;
-                                            let mut cwd = project
-                                            .visible_worktrees(cx)
-                                            .next()
-                                            .ok_or_else(|| anyhow!("There are no visisible worktrees."))?
-                                            .read(cx)
-                                            .abs_path()
-                                            .to_path_buf();
-                                            cwd.push(".git");
-                                            let repo = project
-                                                .fs()
-                                                .open_repo(&cwd)
-                                                .ok_or_else(|| anyhow!("Could not open repository at path `{}`", cwd.as_os_str().to_string_lossy()))?;
-                                            let repo = repo
-                                                .lock();
-                                            let status = repo
-                                                .create_branch(&current_pick);
-                                            if status.is_err() {
-                                                this.delegate.display_error_toast(format!("Failed to create branch '{current_pick}', check for conflicts or unstashed files"), cx);
-                                                status?;
-                                            }
-                                            let status = repo.change_branch(&current_pick);
-                                            if status.is_err() {
-                                                this.delegate.display_error_toast(format!("Failed to check branch '{current_pick}', check for conflicts or unstashed files"), cx);
-                                                status?;
-                                            }
-                                            this.cancel(&Default::default(), cx);
-                                            Ok::<(), anyhow::Error>(())
-                                })
-
-                    }).detach_and_log_err(cx);
-                }),
-            ).style(ui::ButtonStyle::Filled)).into_any_element(),
+            h_flex()
+                .mr_3()
+                .pb_2()
+                .gap_1()
+                .child(h_flex().w_full())
+                .children(base.map(|base| {
+                    Button::new(
+                        "branch-picker-create-branch-from-button",
+                        format!(
+                            "Create from {}",
+                            util::truncate_and_trailoff(&base, self.branch_name_trailoff_after / 2)
+                        ),
+                    )
+                    .on_click(cx.listener(move |picker, _, cx| {
+                        picker.delegate.create_branch(Some(base.clone()), cx);
+                    }))
+                }))
+                .child(
+                    Button::new("branch-picker-create-branch-button", "Create branch")
+                        .style(ui::ButtonStyle::Filled)
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.create_branch(None, cx);
+                        })),
+                )
+                .into_any_element(),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{FakeGitRepository, FakeGitRepositoryState, GitFileStatus, RepoPath};
+    use std::path::Path;
+
+    fn fake_repo(
+        branch: &str,
+        changes: &[(&str, GitFileStatus)],
+    ) -> (
+        Arc<Mutex<FakeGitRepositoryState>>,
+        Arc<Mutex<dyn GitRepository>>,
+    ) {
+        let state = Arc::new(Mutex::new(FakeGitRepositoryState {
+            branch_name: Some(branch.into()),
+            branches: ["main".into(), "feature".into()].into_iter().collect(),
+            worktree_statuses: changes
+                .iter()
+                .map(|(path, status)| (RepoPath::from(Path::new(path)), *status))
+                .collect(),
+            ..Default::default()
+        }));
+        let repo = FakeGitRepository::open(state.clone());
+        (state, repo)
+    }
+
+    fn changes(state: &Mutex<FakeGitRepositoryState>) -> Vec<(String, GitFileStatus)> {
+        let mut changes = state
+            .lock()
+            .worktree_statuses
+            .iter()
+            .map(|(path, status)| (path.0.to_string_lossy().into_owned(), *status))
+            .collect::<Vec<_>>();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes
+    }
+
+    fn stash_messages(state: &Mutex<FakeGitRepositoryState>) -> Vec<String> {
+        state
+            .lock()
+            .stashes
+            .iter()
+            .map(|(message, _)| message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_checkout_leaving_changes_on_branch() {
+        let (state, repo) = fake_repo("main", &[("a.txt", GitFileStatus::Modified)]);
+        let repo = repo.lock();
+
+        checkout_branch(&*repo, "feature", Some(UncommittedChanges::LeaveOnBranch)).unwrap();
+        assert_eq!(repo.branch_name().as_deref(), Some("feature"));
+        assert!(changes(&state).is_empty());
+        assert_eq!(
+            stash_messages(&state),
+            ["zed: changes left on branch 'main'"]
+        );
+
+        // Returning to the branch reapplies the changes that were left on it.
+        checkout_branch(&*repo, "main", None).unwrap();
+        assert_eq!(repo.branch_name().as_deref(), Some("main"));
+        assert_eq!(
+            changes(&state),
+            [("a.txt".to_string(), GitFileStatus::Modified)]
+        );
+        assert!(stash_messages(&state).is_empty());
+    }
+
+    #[test]
+    fn test_checkout_bringing_changes() {
+        let (state, repo) = fake_repo("feature", &[("b.txt", GitFileStatus::Added)]);
+        let repo = repo.lock();
+
+        // Changes that were left on the target stay stashed when others are brought along.
+        state
+            .lock()
+            .stashes
+            .push((left_changes_stash_message("main"), Default::default()));
+
+        checkout_branch(&*repo, "main", Some(UncommittedChanges::Bring)).unwrap();
+        assert_eq!(repo.branch_name().as_deref(), Some("main"));
+        assert_eq!(
+            changes(&state),
+            [("b.txt".to_string(), GitFileStatus::Added)]
+        );
+        assert_eq!(
+            stash_messages(&state),
+            ["zed: changes left on branch 'main'"]
+        );
+    }
+
+    #[test]
+    fn test_checkout_missing_branch_restores_changes() {
+        let (state, repo) = fake_repo("main", &[("a.txt", GitFileStatus::Modified)]);
+        state.lock().branches.remove("feature");
+        let repo = repo.lock();
+
+        checkout_branch(&*repo, "feature", Some(UncommittedChanges::LeaveOnBranch)).unwrap_err();
+        assert_eq!(repo.branch_name().as_deref(), Some("main"));
+        assert_eq!(
+            changes(&state),
+            [("a.txt".to_string(), GitFileStatus::Modified)]
+        );
+        assert!(stash_messages(&state).is_empty());
+    }
+
+    #[test]
+    fn test_checkout_with_conflicting_stash() {
+        let (state, repo) = fake_repo(
+            "main",
+            &[
+                ("a.txt", GitFileStatus::Modified),
+                ("b.txt", GitFileStatus::Modified),
+            ],
+        );
+        let repo = repo.lock();
+        checkout_branch(&*repo, "feature", Some(UncommittedChanges::LeaveOnBranch)).unwrap();
+
+        // Meanwhile, main changed in a way that conflicts with the stashed changes.
+        state
+            .lock()
+            .stash_conflicts
+            .insert(RepoPath::from(Path::new("a.txt")));
+
+        let error = checkout_branch(&*repo, "main", None).unwrap_err();
+        assert!(error.is::<StashConflict>(), "{error:?}");
+        assert_eq!(
+            error.to_string(),
+            "Checked out 'main', but the stashed changes conflicted with it. \
+            Resolve the conflicts, then drop the stash \"zed: changes left on branch 'main'\"."
+        );
+        // The branch is checked out, and the stash is kept until the conflicts are resolved.
+        assert_eq!(repo.branch_name().as_deref(), Some("main"));
+        assert_eq!(
+            changes(&state),
+            [
+                ("a.txt".to_string(), GitFileStatus::Conflict),
+                ("b.txt".to_string(), GitFileStatus::Modified),
+            ]
+        );
+        assert_eq!(
+            stash_messages(&state),
+            ["zed: changes left on branch 'main'"]
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{motion::Motion, object::Object, Vim};
+use collections::HashMap;
+use editor::actions::AlignSelections;
+use gpui::WindowContext;
+
+pub fn align_motion(vim: &mut Vim, motion: Motion, times: Option<usize>, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        let text_layout_details = editor.text_layout_details(cx);
+        editor.set_clip_at_line_ends(false, cx);
+        let mut original_positions: HashMap<_, _> = Default::default();
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                let original_position = (selection.head(), selection.goal);
+                original_positions.insert(selection.id, original_position);
+                motion.expand_selection(map, selection, times, true, &text_layout_details);
+            });
+        });
+        // The lines to align are captured before prompting for the delimiter, so
+        // the cursors can go back to where they were right away.
+        editor.align_selections(&AlignSelections::default(), cx);
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|_, selection| {
+                let (head, goal) = original_positions.remove(&selection.id).unwrap();
+                selection.collapse_to(head, goal);
+            });
+        });
+    });
+}
+
+pub fn align_object(vim: &mut Vim, object: Object, around: bool, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        editor.set_clip_at_line_ends(false, cx);
+        let mut original_positions: HashMap<_, _> = Default::default();
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                let original_position = (selection.head(), selection.goal);
+                object.expand_selection(map, selection, around);
+                original_positions.insert(selection.id, original_position);
+            });
+        });
+        editor.align_selections(&AlignSelections::default(), cx);
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|_, selection| {
+                let (head, goal) = original_positions.remove(&selection.id).unwrap();
+                selection.collapse_to(head, goal);
+            });
+        });
+    });
+}
@@ -78,7 +78,15 @@ impl VimTestContext {
             });
             workspace.status_bar().update(cx, |status_bar, cx| {
                 let vim_mode_indicator = cx.new_view(ModeIndicator::new);
-                status_bar.add_right_item(vim_mode_indicator, cx);
+                status_bar.add_item(
+                    vim_mode_indicator,
+                    workspace::StatusItemOptions::new(
+                        "vim_mode",
+                        workspace::StatusItemSide::Right,
+                        300,
+                    ),
+                    cx,
+                );
             });
         });
 
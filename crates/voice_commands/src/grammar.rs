@@ -0,0 +1,359 @@
+use anyhow::{anyhow, Context as _, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const NUMBER_PLACEHOLDER: &str = "{number}";
+const TEXT_PLACEHOLDER: &str = "{text}";
+
+/// Maps spoken phrases to actions.
+///
+/// Phrases are sequences of words, which may contain a `{number}` placeholder that
+/// matches a number given as digits or words ("forty two"), and a `{text}` placeholder
+/// that matches any words. The placeholders' values are substituted into the action's
+/// arguments wherever the same placeholder appears in them.
+#[derive(Debug, Default)]
+pub struct Grammar {
+    commands: Vec<Command>,
+}
+
+#[derive(Debug)]
+struct Command {
+    pattern: Vec<PatternToken>,
+    action_name: String,
+    action_args: Option<Value>,
+}
+
+#[derive(Debug, PartialEq)]
+enum PatternToken {
+    Word(String),
+    Number,
+    Text,
+}
+
+/// A phrase that matched a command of the grammar.
+#[derive(Debug, PartialEq)]
+pub struct CommandMatch {
+    pub action_name: String,
+    pub action_args: Option<Value>,
+}
+
+#[derive(Default)]
+struct Captures {
+    number: Option<u32>,
+    text: Option<String>,
+}
+
+impl Grammar {
+    /// Builds a grammar from phrases mapped to actions, which are given the same way
+    /// as in keymaps: either as an action name or as an `[action name, arguments]` pair.
+    /// Phrases mapped to `null` are ignored, so that default commands can be removed.
+    pub fn new(commands: &BTreeMap<String, Value>) -> Self {
+        let mut commands = commands
+            .iter()
+            .filter(|(_, action)| !action.is_null())
+            .filter_map(|(phrase, action)| {
+                Command::new(phrase, action)
+                    .with_context(|| format!("invalid voice command {phrase:?}"))
+                    .map_err(|error| log::error!("{error:#}"))
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        // Prefer the most specific commands, so that e.g. "close all tabs" isn't
+        // matched as "close {text}".
+        commands.sort_by_key(|command| {
+            std::cmp::Reverse(
+                command
+                    .pattern
+                    .iter()
+                    .filter(|token| matches!(token, PatternToken::Word(_)))
+                    .count(),
+            )
+        });
+        Self { commands }
+    }
+
+    pub fn match_phrase(&self, phrase: &str) -> Option<CommandMatch> {
+        let words = normalize(phrase);
+        let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+        if words.is_empty() {
+            return None;
+        }
+
+        self.commands.iter().find_map(|command| {
+            let mut captures = Captures::default();
+            if !match_tokens(&command.pattern, &words, &mut captures) {
+                return None;
+            }
+            Some(CommandMatch {
+                action_name: command.action_name.clone(),
+                action_args: command
+                    .action_args
+                    .clone()
+                    .map(|args| substitute(args, &captures)),
+            })
+        })
+    }
+}
+
+impl Command {
+    fn new(phrase: &str, action: &Value) -> Result<Self> {
+        let pattern = phrase
+            .split_whitespace()
+            .flat_map(|word| match word {
+                NUMBER_PLACEHOLDER => vec![PatternToken::Number],
+                TEXT_PLACEHOLDER => vec![PatternToken::Text],
+                word => normalize(word)
+                    .into_iter()
+                    .map(PatternToken::Word)
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        if pattern.is_empty() {
+            return Err(anyhow!("phrase is empty"));
+        }
+
+        let (action_name, action_args) = match action {
+            Value::String(name) => (name.clone(), None),
+            Value::Array(items) => match items.as_slice() {
+                [Value::String(name), args] => (name.clone(), Some(args.clone())),
+                _ => return Err(anyhow!("expected an [action name, arguments] pair")),
+            },
+            _ => return Err(anyhow!("expected an action name")),
+        };
+        Ok(Self {
+            pattern,
+            action_name,
+            action_args,
+        })
+    }
+}
+
+/// Lowercases the phrase and splits it into words, dropping punctuation.
+fn normalize(phrase: &str) -> Vec<String> {
+    phrase
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn match_tokens(pattern: &[PatternToken], words: &[&str], captures: &mut Captures) -> bool {
+    let Some((token, rest)) = pattern.split_first() else {
+        return words.is_empty();
+    };
+
+    match token {
+        PatternToken::Word(expected) => {
+            words.first() == Some(&expected.as_str()) && match_tokens(rest, &words[1..], captures)
+        }
+        PatternToken::Number => (1..=words.len()).rev().any(|len| {
+            let Some(number) = parse_number(&words[..len]) else {
+                return false;
+            };
+            captures.number = Some(number);
+            match_tokens(rest, &words[len..], captures)
+        }),
+        PatternToken::Text => (1..=words.len()).any(|len| {
+            captures.text = Some(words[..len].join(" "));
+            match_tokens(rest, &words[len..], captures)
+        }),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumberWord {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Thousand,
+}
+
+/// Parses a number given as digits, or spelled out in words like "one hundred and five".
+fn parse_number(words: &[&str]) -> Option<u32> {
+    if let [word] = words {
+        if let Ok(number) = word.parse() {
+            return Some(number);
+        }
+    }
+
+    let mut total = 0u32;
+    let mut current = 0u32;
+    let mut previous = None;
+    for (ix, word) in words.iter().enumerate() {
+        if *word == "and" && previous == Some(NumberWord::Hundred) && ix + 1 < words.len() {
+            continue;
+        }
+
+        let (kind, value) = match *word {
+            "zero" => (NumberWord::Unit, 0),
+            "one" => (NumberWord::Unit, 1),
+            "two" => (NumberWord::Unit, 2),
+            "three" => (NumberWord::Unit, 3),
+            "four" => (NumberWord::Unit, 4),
+            "five" => (NumberWord::Unit, 5),
+            "six" => (NumberWord::Unit, 6),
+            "seven" => (NumberWord::Unit, 7),
+            "eight" => (NumberWord::Unit, 8),
+            "nine" => (NumberWord::Unit, 9),
+            "ten" => (NumberWord::Teen, 10),
+            "eleven" => (NumberWord::Teen, 11),
+            "twelve" => (NumberWord::Teen, 12),
+            "thirteen" => (NumberWord::Teen, 13),
+            "fourteen" => (NumberWord::Teen, 14),
+            "fifteen" => (NumberWord::Teen, 15),
+            "sixteen" => (NumberWord::Teen, 16),
+            "seventeen" => (NumberWord::Teen, 17),
+            "eighteen" => (NumberWord::Teen, 18),
+            "nineteen" => (NumberWord::Teen, 19),
+            "twenty" => (NumberWord::Tens, 20),
+            "thirty" => (NumberWord::Tens, 30),
+            "forty" => (NumberWord::Tens, 40),
+            "fifty" => (NumberWord::Tens, 50),
+            "sixty" => (NumberWord::Tens, 60),
+            "seventy" => (NumberWord::Tens, 70),
+            "eighty" => (NumberWord::Tens, 80),
+            "ninety" => (NumberWord::Tens, 90),
+            "hundred" => (NumberWord::Hundred, 100),
+            "thousand" => (NumberWord::Thousand, 1000),
+            _ => return None,
+        };
+
+        let allowed = match kind {
+            NumberWord::Unit => matches!(
+                previous,
+                None | Some(NumberWord::Tens | NumberWord::Hundred | NumberWord::Thousand)
+            ),
+            NumberWord::Teen | NumberWord::Tens => matches!(
+                previous,
+                None | Some(NumberWord::Hundred | NumberWord::Thousand)
+            ),
+            NumberWord::Hundred => matches!(previous, None | Some(NumberWord::Unit)),
+            NumberWord::Thousand => previous != Some(NumberWord::Thousand),
+        };
+        if !allowed {
+            return None;
+        }
+
+        match kind {
+            NumberWord::Unit | NumberWord::Teen | NumberWord::Tens => current += value,
+            NumberWord::Hundred => current = current.max(1) * value,
+            NumberWord::Thousand => {
+                total = total.checked_add(current.max(1).checked_mul(value)?)?;
+                current = 0;
+            }
+        }
+        previous = Some(kind);
+    }
+
+    previous.map(|_| total + current)
+}
+
+fn substitute(value: Value, captures: &Captures) -> Value {
+    match value {
+        Value::String(string) => {
+            if string == NUMBER_PLACEHOLDER {
+                if let Some(number) = captures.number {
+                    return Value::from(number);
+                }
+            }
+            let mut string = string;
+            if let Some(number) = captures.number {
+                string = string.replace(NUMBER_PLACEHOLDER, &number.to_string());
+            }
+            if let Some(text) = &captures.text {
+                string = string.replace(TEXT_PLACEHOLDER, text);
+            }
+            Value::String(string)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| substitute(item, captures))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, substitute(value, captures)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn grammar(commands: Value) -> Grammar {
+        Grammar::new(&serde_json::from_value(commands).unwrap())
+    }
+
+    #[test]
+    fn test_match_phrase() {
+        let grammar = grammar(json!({
+            "open settings": "zed::OpenSettings",
+            "go to line {number}": ["go_to_line::GoToLineNumber", { "line": "{number}" }],
+            "search for {text}": ["buffer_search::Deploy", { "query": "{text}" }],
+            "close tab": "pane::CloseActiveItem",
+            "close all tabs": "pane::CloseAllItems",
+            "undo": null,
+        }));
+
+        assert_eq!(
+            grammar.match_phrase("Open settings."),
+            Some(CommandMatch {
+                action_name: "zed::OpenSettings".into(),
+                action_args: None,
+            })
+        );
+        assert_eq!(
+            grammar.match_phrase("go to line forty-two"),
+            Some(CommandMatch {
+                action_name: "go_to_line::GoToLineNumber".into(),
+                action_args: Some(json!({ "line": 42 })),
+            })
+        );
+        assert_eq!(
+            grammar.match_phrase("go to line 7"),
+            Some(CommandMatch {
+                action_name: "go_to_line::GoToLineNumber".into(),
+                action_args: Some(json!({ "line": 7 })),
+            })
+        );
+        assert_eq!(
+            grammar.match_phrase("search for Hello World"),
+            Some(CommandMatch {
+                action_name: "buffer_search::Deploy".into(),
+                action_args: Some(json!({ "query": "hello world" })),
+            })
+        );
+        assert_eq!(
+            grammar.match_phrase("close all tabs").unwrap().action_name,
+            "pane::CloseAllItems"
+        );
+        assert_eq!(grammar.match_phrase("go to line banana"), None);
+        assert_eq!(grammar.match_phrase("open settings now"), None);
+        assert_eq!(grammar.match_phrase("undo"), None);
+        assert_eq!(grammar.match_phrase(""), None);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let parse = |phrase: &str| parse_number(&phrase.split(' ').collect::<Vec<_>>());
+        assert_eq!(parse("40"), Some(40));
+        assert_eq!(parse("forty"), Some(40));
+        assert_eq!(parse("forty two"), Some(42));
+        assert_eq!(parse("seventeen"), Some(17));
+        assert_eq!(parse("hundred"), Some(100));
+        assert_eq!(parse("one hundred and five"), Some(105));
+        assert_eq!(parse("three hundred twelve"), Some(312));
+        assert_eq!(parse("two thousand twenty four"), Some(2024));
+        assert_eq!(parse("one two"), None);
+        assert_eq!(parse("forty twelve"), None);
+        assert_eq!(parse("hundred and"), None);
+        assert_eq!(parse("line"), None);
+    }
+}
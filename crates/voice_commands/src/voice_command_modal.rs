@@ -0,0 +1,128 @@
+use crate::{CommandMatch, Grammar, VoiceCommandSettings};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, View,
+    ViewContext, VisualContext,
+};
+use settings::Settings;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+/// Takes a spoken command, dictated through the system's speech recognition, and
+/// runs the action that the voice command grammar maps it to.
+pub struct VoiceCommandModal {
+    phrase_editor: View<Editor>,
+    previous_focus_handle: FocusHandle,
+    grammar: Grammar,
+    command_match: Option<CommandMatch>,
+    _subscription: Subscription,
+}
+
+impl VoiceCommandModal {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(previous_focus_handle) = cx.focused() else {
+            return;
+        };
+        let grammar = Grammar::new(&VoiceCommandSettings::get_global(cx).commands);
+        workspace.toggle_modal(cx, move |cx| {
+            let phrase_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Say a command, like \"go to line forty\"", cx);
+                editor
+            });
+            let _subscription = cx.subscribe(&phrase_editor, Self::on_phrase_editor_event);
+            Self {
+                phrase_editor,
+                previous_focus_handle,
+                grammar,
+                command_match: None,
+                _subscription,
+            }
+        });
+    }
+
+    fn on_phrase_editor_event(
+        &mut self,
+        _: View<Editor>,
+        event: &EditorEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            EditorEvent::Blurred => cx.emit(DismissEvent),
+            EditorEvent::BufferEdited => {
+                let phrase = self.phrase_editor.read(cx).text(cx);
+                self.command_match = self.grammar.match_phrase(&phrase);
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(command_match) = self.command_match.take() else {
+            return;
+        };
+
+        match cx.build_action(&command_match.action_name, command_match.action_args) {
+            Ok(action) => {
+                cx.focus(&self.previous_focus_handle);
+                cx.emit(DismissEvent);
+                cx.dispatch_action(action);
+            }
+            Err(error) => {
+                log::error!(
+                    "invalid action {:?} in voice command: {error:#}",
+                    command_match.action_name
+                );
+                cx.emit(DismissEvent);
+            }
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for VoiceCommandModal {}
+impl EventEmitter<DismissEvent> for VoiceCommandModal {}
+
+impl FocusableView for VoiceCommandModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.phrase_editor.focus_handle(cx)
+    }
+}
+
+impl Render for VoiceCommandModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let is_empty = self.phrase_editor.read(cx).text(cx).trim().is_empty();
+        let (status, color) = match &self.command_match {
+            Some(command_match) => (format!("Run {}", command_match.action_name), Color::Muted),
+            None if is_empty => (
+                "Dictate a command using your system's speech recognition".to_string(),
+                Color::Muted,
+            ),
+            None => ("No command matches".to_string(), Color::Warning),
+        };
+
+        v_flex()
+            .key_context("VoiceCommand")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w_96()
+            .child(div().px_2().py_1().child(self.phrase_editor.clone()))
+            .child(
+                div()
+                    .h_px()
+                    .w_full()
+                    .bg(cx.theme().colors().element_background),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(status).size(LabelSize::Small).color(color)),
+            )
+    }
+}
@@ -0,0 +1,53 @@
+mod grammar;
+mod voice_command_modal;
+
+use anyhow::Result;
+use gpui::{actions, AppContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use settings::{Settings, SettingsSources};
+use std::collections::BTreeMap;
+use workspace::Workspace;
+
+pub use grammar::{CommandMatch, Grammar};
+pub use voice_command_modal::VoiceCommandModal;
+
+actions!(voice_commands, [Toggle]);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct VoiceCommandSettings {
+    pub commands: BTreeMap<String, Value>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct VoiceCommandSettingsContent {
+    /// The spoken phrases that voice commands recognize, mapped to the actions they run.
+    ///
+    /// Actions are given as in keymaps, either by name or as an `[action name, arguments]`
+    /// pair. Phrases may contain a `{number}` placeholder, which matches numbers given
+    /// as digits or words, and a `{text}` placeholder, which matches any words. These
+    /// placeholders are replaced with the words they matched in the action's arguments.
+    /// Map a phrase to `null` to remove it.
+    commands: Option<BTreeMap<String, Value>>,
+}
+
+impl Settings for VoiceCommandSettings {
+    const KEY: Option<&'static str> = Some("voice_commands");
+
+    type FileContent = VoiceCommandSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    VoiceCommandSettings::register(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            VoiceCommandModal::toggle(workspace, cx);
+        });
+    })
+    .detach();
+}
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Context as _, Result};
+use editor::Editor;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, PathPromptOptions, Render,
+    Task, View, ViewContext, VisualContext, WeakView,
+};
+use std::path::Path;
+use ui::prelude::*;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+use crate::CloneRepository;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &CloneRepository, cx| {
+            let handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| CloneRepositoryModal::new(handle, cx));
+        });
+    })
+    .detach();
+}
+
+/// Asks for the URL of a git repository, then for the folder to clone it into,
+/// and opens the clone once it's done.
+pub struct CloneRepositoryModal {
+    workspace: WeakView<Workspace>,
+    url_editor: View<Editor>,
+}
+
+impl ModalView for CloneRepositoryModal {}
+
+impl EventEmitter<DismissEvent> for CloneRepositoryModal {}
+
+impl FocusableView for CloneRepositoryModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.url_editor.focus_handle(cx)
+    }
+}
+
+impl CloneRepositoryModal {
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let url_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("https://github.com/owner/repository.git", cx);
+            editor
+        });
+        Self {
+            workspace,
+            url_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let url = self.url_editor.read(cx).text(cx).trim().to_string();
+        let Some(directory_name) = repository_directory_name(&url) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let parent = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let task: Task<Result<()>> = workspace.update(cx, |_, cx| {
+            cx.spawn(|workspace, mut cx| async move {
+                let Some(parent) = parent.await?.and_then(|paths| paths.into_iter().next()) else {
+                    return Ok(());
+                };
+                let destination = parent.join(directory_name);
+                clone(&url, &destination).await?;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_workspace_for_paths(false, vec![destination], cx)
+                    })?
+                    .await
+            })
+        });
+        task.detach_and_prompt_err("Failed to clone the repository", cx, |error, _| {
+            Some(error.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+async fn clone(url: &str, destination: &Path) -> Result<()> {
+    anyhow::ensure!(
+        !destination.exists(),
+        "{} already exists",
+        destination.display()
+    );
+    let output = smol::process::Command::new("git")
+        .arg("clone")
+        .arg("--")
+        .arg(url)
+        .arg(destination)
+        .output()
+        .await
+        .context("failed to run git")?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Returns the name of the folder that `git clone` creates for the repository
+/// at the URL, which is its last path component without the `.git` suffix.
+fn repository_directory_name(url: &str) -> Option<String> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+impl Render for CloneRepositoryModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("CloneRepository")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(div().px_2().py_1p5().child(self.url_editor.clone()))
+            .child(
+                div()
+                    .h_px()
+                    .w_full()
+                    .bg(cx.theme().colors().element_background),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Clone a git repository, then choose where to put it")
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_directory_name() {
+        assert_eq!(
+            repository_directory_name("https://github.com/zed-industries/zed.git").as_deref(),
+            Some("zed")
+        );
+        assert_eq!(
+            repository_directory_name("git@github.com:zed-industries/zed").as_deref(),
+            Some("zed")
+        );
+        assert_eq!(
+            repository_directory_name("ssh://host/repos/project/").as_deref(),
+            Some("project")
+        );
+        assert_eq!(repository_directory_name(""), None);
+    }
+}
@@ -0,0 +1,591 @@
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent};
+use feature_flags::FeatureFlagAppExt;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, PathPromptOptions,
+    Render, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use recent_projects::OpenRemote;
+use std::{
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc},
+};
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip};
+use util::{paths::PathExt, ResultExt};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    open_new, AppState, SerializedWorkspaceLocation, Workspace, WorkspaceId, WORKSPACE_DB,
+};
+
+use crate::{CloneRepository, ShowDashboard};
+
+const PINNED_FOLDERS_KEY: &str = "dashboard_pinned_folders";
+
+/// How many recent projects are listed, which are the most recently opened ones
+/// until something is searched for.
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// The actions whose key bindings are shown as tips, with what they do.
+const TIPS: &[(&str, &str)] = &[
+    ("Open a file by its name", "file_finder::Toggle"),
+    ("Run any command", "command_palette::Toggle"),
+    ("Search across the project", "pane::DeploySearch"),
+    ("Switch to a recent project", "projects::OpenRecent"),
+    ("Show the project panel", "project_panel::ToggleFocus"),
+    ("Open a terminal", "terminal_panel::ToggleFocus"),
+];
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowDashboard, cx| {
+            let dashboard = Dashboard::new(workspace, cx);
+            workspace.add_item_to_active_pane(Box::new(dashboard.clone()), cx);
+            cx.focus_view(&dashboard);
+        });
+    })
+    .detach();
+}
+
+/// Opens a window that shows the dashboard instead of an empty file.
+pub fn show_dashboard(app_state: Arc<AppState>, cx: &mut AppContext) {
+    open_new(app_state, cx, |workspace, cx| {
+        let dashboard = Dashboard::new(workspace, cx);
+        workspace.add_item_to_center(Box::new(dashboard.clone()), cx);
+        cx.focus_view(&dashboard);
+    })
+    .detach();
+}
+
+/// A starting point for a window without a project: the recent projects and
+/// pinned folders to open, searchable by name, the other ways to get a project,
+/// and the key bindings of common actions.
+///
+/// Every entry is selected with the arrow keys and used with enter, while typing
+/// searches the folders and projects.
+pub struct Dashboard {
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    query_editor: View<Editor>,
+    pinned_folders: Vec<PathBuf>,
+    recent_projects: Vec<Vec<PathBuf>>,
+    entries: Vec<DashboardEntry>,
+    selected_ix: usize,
+    update_entries_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone)]
+enum DashboardEntry {
+    PinnedFolder {
+        path: PathBuf,
+        positions: Vec<usize>,
+    },
+    RecentProject {
+        paths: Vec<PathBuf>,
+        positions: Vec<usize>,
+    },
+    Action(DashboardAction),
+    Tip {
+        description: &'static str,
+        action: Arc<dyn Action>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DashboardAction {
+    OpenFolder,
+    PinFolder,
+    CloneRepository,
+    ConnectToRemote,
+}
+
+impl DashboardAction {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::OpenFolder => "Open a Folder…",
+            Self::PinFolder => "Pin a Folder…",
+            Self::CloneRepository => "Clone a Repository…",
+            Self::ConnectToRemote => "Connect to a Remote Project…",
+        }
+    }
+
+    fn icon(&self) -> IconName {
+        match self {
+            Self::OpenFolder => IconName::FolderOpen,
+            Self::PinFolder => IconName::Folder,
+            Self::CloneRepository => IconName::Github,
+            Self::ConnectToRemote => IconName::Server,
+        }
+    }
+}
+
+impl Dashboard {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let workspace_handle = workspace.weak_handle();
+        let current_workspace_id = workspace.database_id();
+        cx.new_view(|cx| {
+            let query_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search recent projects and pinned folders…", cx);
+                editor
+            });
+            let subscriptions = vec![cx.subscribe(&query_editor, |this, _, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.selected_ix = 0;
+                    this.update_entries(cx);
+                }
+            })];
+
+            cx.spawn(|this, mut cx| async move {
+                let pinned_folders = cx
+                    .background_executor()
+                    .spawn(async { load_pinned_folders() })
+                    .await;
+                let recent_projects = WORKSPACE_DB
+                    .recent_workspaces_on_disk()
+                    .await
+                    .log_err()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(id, _)| *id != current_workspace_id)
+                    .filter_map(|(_, location)| match location {
+                        SerializedWorkspaceLocation::Local(paths) => {
+                            Some(paths.paths().as_ref().clone())
+                        }
+                        SerializedWorkspaceLocation::Remote(_) => None,
+                    })
+                    .collect();
+                this.update(&mut cx, |this, cx| {
+                    this.pinned_folders = pinned_folders;
+                    this.recent_projects = recent_projects;
+                    this.update_entries(cx);
+                })
+                .ok();
+            })
+            .detach();
+
+            let mut this = Self {
+                workspace: workspace_handle,
+                focus_handle: cx.focus_handle(),
+                query_editor,
+                pinned_folders: Vec::new(),
+                recent_projects: Vec::new(),
+                entries: Vec::new(),
+                selected_ix: 0,
+                update_entries_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            this.update_entries(cx);
+            this
+        })
+    }
+
+    /// Lists the pinned folders and recent projects matching the query, followed
+    /// by the actions and tips.
+    fn update_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let folders = self
+            .pinned_folders
+            .iter()
+            .map(|path| vec![path.clone()])
+            .chain(
+                self.recent_projects
+                    .iter()
+                    .filter(|paths| match paths.as_slice() {
+                        [path] => !self.pinned_folders.contains(path),
+                        _ => true,
+                    })
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+        let pinned_count = self.pinned_folders.len();
+        let executor = cx.background_executor().clone();
+
+        self.update_entries_task = cx.spawn(|this, mut cx| async move {
+            let mut matches = if query.trim().is_empty() {
+                (0..folders.len())
+                    .map(|candidate_id| StringMatch {
+                        candidate_id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: String::new(),
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                let candidates = folders
+                    .iter()
+                    .enumerate()
+                    .map(|(id, paths)| StringMatchCandidate::new(id, paths_label(paths)))
+                    .collect::<Vec<_>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    query.trim(),
+                    query.chars().any(|c| c.is_uppercase()),
+                    100,
+                    &AtomicBool::default(),
+                    executor,
+                )
+                .await
+            };
+            // Pinned folders stay first, and the others are in order of their score.
+            matches.sort_by_key(|string_match| string_match.candidate_id >= pinned_count);
+
+            this.update(&mut cx, |this, cx| {
+                let mut recent_count = 0;
+                this.entries = matches
+                    .into_iter()
+                    .filter_map(|string_match| {
+                        let mut paths = folders[string_match.candidate_id].clone();
+                        if string_match.candidate_id < pinned_count {
+                            Some(DashboardEntry::PinnedFolder {
+                                path: paths.remove(0),
+                                positions: string_match.positions,
+                            })
+                        } else {
+                            recent_count += 1;
+                            (recent_count <= MAX_RECENT_PROJECTS).then_some(
+                                DashboardEntry::RecentProject {
+                                    paths,
+                                    positions: string_match.positions,
+                                },
+                            )
+                        }
+                    })
+                    .collect();
+
+                let mut actions = vec![
+                    DashboardAction::OpenFolder,
+                    DashboardAction::PinFolder,
+                    DashboardAction::CloneRepository,
+                ];
+                if cx.has_flag::<feature_flags::Remoting>() {
+                    actions.push(DashboardAction::ConnectToRemote);
+                }
+                this.entries
+                    .extend(actions.into_iter().map(DashboardEntry::Action));
+                for (description, action_name) in TIPS {
+                    // Actions of crates that aren't part of this build are skipped.
+                    if let Ok(action) = cx.build_action(action_name, None) {
+                        this.entries.push(DashboardEntry::Tip {
+                            description: *description,
+                            action: action.into(),
+                        });
+                    }
+                }
+
+                this.selected_ix = this.selected_ix.min(this.entries.len().saturating_sub(1));
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_ix = (self.selected_ix + 1) % self.entries.len();
+            cx.notify();
+        }
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_ix = self
+                .selected_ix
+                .checked_sub(1)
+                .unwrap_or(self.entries.len() - 1);
+            cx.notify();
+        }
+    }
+
+    fn select_first(&mut self, _: &menu::SelectFirst, cx: &mut ViewContext<Self>) {
+        self.selected_ix = 0;
+        cx.notify();
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, cx: &mut ViewContext<Self>) {
+        self.selected_ix = self.entries.len().saturating_sub(1);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        self.confirm_entry(self.selected_ix, cx);
+    }
+
+    /// Pins or unpins the selected folder or project.
+    fn secondary_confirm(&mut self, _: &menu::SecondaryConfirm, cx: &mut ViewContext<Self>) {
+        self.toggle_pinned(self.selected_ix, cx);
+    }
+
+    fn confirm_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        match entry {
+            DashboardEntry::PinnedFolder { path, .. } => self.open_paths(vec![path], cx),
+            DashboardEntry::RecentProject { paths, .. } => self.open_paths(paths, cx),
+            DashboardEntry::Action(DashboardAction::OpenFolder) => {
+                cx.dispatch_action(workspace::Open.boxed_clone())
+            }
+            DashboardEntry::Action(DashboardAction::PinFolder) => self.pin_folder(cx),
+            DashboardEntry::Action(DashboardAction::CloneRepository) => {
+                cx.dispatch_action(CloneRepository.boxed_clone())
+            }
+            DashboardEntry::Action(DashboardAction::ConnectToRemote) => {
+                cx.dispatch_action(OpenRemote.boxed_clone())
+            }
+            DashboardEntry::Tip { action, .. } => cx.dispatch_action(action.boxed_clone()),
+        }
+    }
+
+    fn open_paths(&mut self, paths: Vec<PathBuf>, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_workspace_for_paths(false, paths, cx)
+                    .detach_and_log_err(cx)
+            })
+            .ok();
+    }
+
+    fn pin_folder(&mut self, cx: &mut ViewContext<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: true,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let Some(paths) = paths.await.log_err().flatten() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                for path in paths {
+                    if !this.pinned_folders.contains(&path) {
+                        this.pinned_folders.push(path);
+                    }
+                }
+                this.pinned_folders_changed(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_pinned(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        match self.entries.get(ix) {
+            Some(DashboardEntry::PinnedFolder { path, .. }) => {
+                let path = path.clone();
+                self.pinned_folders.retain(|pinned| *pinned != path);
+            }
+            Some(DashboardEntry::RecentProject { paths, .. }) => {
+                for path in paths {
+                    if !self.pinned_folders.contains(path) {
+                        self.pinned_folders.push(path.clone());
+                    }
+                }
+            }
+            _ => return,
+        }
+        self.pinned_folders_changed(cx);
+    }
+
+    fn pinned_folders_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let pinned_folders = self.pinned_folders.clone();
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        PINNED_FOLDERS_KEY.to_string(),
+                        serde_json::to_string(&pinned_folders)?,
+                    )
+                    .await
+            })
+            .detach_and_log_err(cx);
+        self.update_entries(cx);
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &DashboardEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let item = ListItem::new(("dashboard-entry", ix))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(ix == self.selected_ix)
+            .on_click(cx.listener(move |this, _, cx| {
+                this.selected_ix = ix;
+                this.confirm_entry(ix, cx);
+            }));
+
+        match entry {
+            DashboardEntry::PinnedFolder { path, positions } => item
+                .start_slot(Icon::new(IconName::Folder).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    path.compact().to_string_lossy().into_owned(),
+                    positions.clone(),
+                ))
+                .end_hover_slot(self.render_pin_button(ix, true, cx))
+                .into_any_element(),
+            DashboardEntry::RecentProject { paths, positions } => item
+                .start_slot(Icon::new(IconName::FileTree).color(Color::Muted))
+                .child(HighlightedLabel::new(paths_label(paths), positions.clone()))
+                .end_hover_slot(self.render_pin_button(ix, false, cx))
+                .into_any_element(),
+            DashboardEntry::Action(action) => item
+                .start_slot(Icon::new(action.icon()).color(Color::Muted))
+                .child(Label::new(action.label()))
+                .into_any_element(),
+            DashboardEntry::Tip {
+                description,
+                action,
+            } => item
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .child(Label::new(*description).color(Color::Muted))
+                        .children(KeyBinding::for_action_in(&**action, &self.focus_handle, cx)),
+                )
+                .into_any_element(),
+        }
+    }
+
+    fn render_pin_button(&self, ix: usize, pinned: bool, cx: &mut ViewContext<Self>) -> IconButton {
+        let tooltip = if pinned { "Unpin" } else { "Pin" };
+        IconButton::new(
+            ("dashboard-pin", ix),
+            if pinned {
+                IconName::Close
+            } else {
+                IconName::Plus
+            },
+        )
+        .icon_size(IconSize::Small)
+        .tooltip(move |cx| Tooltip::for_action(tooltip, &menu::SecondaryConfirm, cx))
+        .on_click(cx.listener(move |this, _, cx| this.toggle_pinned(ix, cx)))
+    }
+}
+
+fn load_pinned_folders() -> Vec<PathBuf> {
+    KEY_VALUE_STORE
+        .read_kvp(PINNED_FOLDERS_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|pinned_folders| serde_json::from_str(&pinned_folders).log_err())
+        .unwrap_or_default()
+}
+
+fn paths_label(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.compact().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Render for Dashboard {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut sections = Vec::new();
+        let mut current_section = None;
+        for (ix, entry) in self.entries.iter().enumerate() {
+            let section = match entry {
+                DashboardEntry::PinnedFolder { .. } => "Pinned",
+                DashboardEntry::RecentProject { .. } => "Recent Projects",
+                DashboardEntry::Action(_) => "Start",
+                DashboardEntry::Tip { .. } => "Tips",
+            };
+            if current_section != Some(section) {
+                current_section = Some(section);
+                sections.push(
+                    div()
+                        .pt_3()
+                        .pb_1()
+                        .px_2()
+                        .child(
+                            Label::new(section)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .into_any_element(),
+                );
+            }
+            sections.push(self.render_entry(ix, entry, cx));
+        }
+
+        h_flex()
+            .key_context("Dashboard")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::secondary_confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .id("dashboard")
+                    .w(rems(34.))
+                    .h_full()
+                    .mx_auto()
+                    .py_8()
+                    .overflow_y_scroll()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.query_editor.clone()),
+                    )
+                    .children(sections),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for Dashboard {}
+
+impl FocusableView for Dashboard {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl Item for Dashboard {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Dashboard")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("dashboard")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let workspace = self.workspace.upgrade()?;
+        Some(workspace.update(cx, |workspace, cx| Self::new(workspace, cx)))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
@@ -1,13 +1,15 @@
 mod base_keymap_picker;
 mod base_keymap_setting;
+mod clone_repository;
+mod dashboard;
 
 use client::{telemetry::Telemetry, TelemetrySettings};
 use copilot_ui;
 use db::kvp::KEY_VALUE_STORE;
 use gpui::{
-    svg, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
-    ParentElement, Render, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
-    WindowContext,
+    actions, svg, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, ParentElement, Render, Styled, Subscription, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
 use settings::{Settings, SettingsStore};
 use std::sync::Arc;
@@ -20,6 +22,9 @@ use workspace::{
 };
 
 pub use base_keymap_setting::BaseKeymap;
+pub use dashboard::{show_dashboard, Dashboard};
+
+actions!(welcome, [ShowDashboard, CloneRepository]);
 
 pub const FIRST_OPEN: &str = "first_open";
 
@@ -35,6 +40,8 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 
     base_keymap_picker::init(cx);
+    clone_repository::init(cx);
+    dashboard::init(cx);
 }
 
 pub fn show_welcome_view(app_state: Arc<AppState>, cx: &mut AppContext) {
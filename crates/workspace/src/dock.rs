@@ -1,5 +1,5 @@
 use crate::persistence::model::DockData;
-use crate::{status_bar::StatusItemView, Workspace};
+use crate::{render_key_hint, status_bar::StatusItemView, HintTarget, Workspace};
 use crate::{DraggedDock, Event};
 use gpui::{
     deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, ClickEvent, Entity,
@@ -25,13 +25,19 @@ pub enum PanelEvent {
 
 pub trait Panel: FocusableView + EventEmitter<PanelEvent> {
     fn persistent_name() -> &'static str;
+    /// The name under which the state of this panel is persisted, which only
+    /// differs from [`Panel::persistent_name`] for types of panels that can be
+    /// added to a workspace more than once.
+    fn persistent_id(&self) -> SharedString {
+        Self::persistent_name().into()
+    }
     fn position(&self, cx: &WindowContext) -> DockPosition;
     fn position_is_valid(&self, position: DockPosition) -> bool;
     fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>);
     fn size(&self, cx: &WindowContext) -> Pixels;
     fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>);
     fn icon(&self, cx: &WindowContext) -> Option<ui::IconName>;
-    fn icon_tooltip(&self, cx: &WindowContext) -> Option<&'static str>;
+    fn icon_tooltip(&self, cx: &WindowContext) -> Option<&str>;
     fn toggle_action(&self) -> Box<dyn Action>;
     fn icon_label(&self, _: &WindowContext) -> Option<String> {
         None
@@ -48,7 +54,7 @@ pub trait Panel: FocusableView + EventEmitter<PanelEvent> {
 
 pub trait PanelHandle: Send + Sync {
     fn panel_id(&self) -> EntityId;
-    fn persistent_name(&self) -> &'static str;
+    fn persistent_name(&self, cx: &AppContext) -> SharedString;
     fn position(&self, cx: &WindowContext) -> DockPosition;
     fn position_is_valid(&self, position: DockPosition, cx: &WindowContext) -> bool;
     fn set_position(&self, position: DockPosition, cx: &mut WindowContext);
@@ -58,7 +64,7 @@ pub trait PanelHandle: Send + Sync {
     fn size(&self, cx: &WindowContext) -> Pixels;
     fn set_size(&self, size: Option<Pixels>, cx: &mut WindowContext);
     fn icon(&self, cx: &WindowContext) -> Option<ui::IconName>;
-    fn icon_tooltip(&self, cx: &WindowContext) -> Option<&'static str>;
+    fn icon_tooltip(&self, cx: &WindowContext) -> Option<SharedString>;
     fn toggle_action(&self, cx: &WindowContext) -> Box<dyn Action>;
     fn icon_label(&self, cx: &WindowContext) -> Option<String>;
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle;
@@ -73,8 +79,8 @@ where
         Entity::entity_id(self)
     }
 
-    fn persistent_name(&self) -> &'static str {
-        T::persistent_name()
+    fn persistent_name(&self, cx: &AppContext) -> SharedString {
+        self.read(cx).persistent_id()
     }
 
     fn position(&self, cx: &WindowContext) -> DockPosition {
@@ -113,8 +119,10 @@ where
         self.read(cx).icon(cx)
     }
 
-    fn icon_tooltip(&self, cx: &WindowContext) -> Option<&'static str> {
-        self.read(cx).icon_tooltip(cx)
+    fn icon_tooltip(&self, cx: &WindowContext) -> Option<SharedString> {
+        self.read(cx)
+            .icon_tooltip(cx)
+            .map(|tooltip| tooltip.to_string().into())
     }
 
     fn toggle_action(&self, cx: &WindowContext) -> Box<dyn Action> {
@@ -191,6 +199,7 @@ struct PanelEntry {
 
 pub struct PanelButtons {
     dock: View<Dock>,
+    workspace: WeakView<Workspace>,
 }
 
 impl Dock {
@@ -280,20 +289,28 @@ impl Dock {
             .find_map(|entry| entry.panel.to_any().clone().downcast().ok())
     }
 
+    pub fn panels<T: Panel>(&self) -> impl Iterator<Item = View<T>> + '_ {
+        self.panel_entries
+            .iter()
+            .filter_map(|entry| entry.panel.to_any().downcast().ok())
+    }
+
     pub fn panel_index_for_type<T: Panel>(&self) -> Option<usize> {
         self.panel_entries
             .iter()
             .position(|entry| entry.panel.to_any().downcast::<T>().is_ok())
     }
 
-    pub fn panel_index_for_persistent_name(
-        &self,
-        ui_name: &str,
-        _cx: &AppContext,
-    ) -> Option<usize> {
+    pub fn panel_index_for_id(&self, panel_id: EntityId) -> Option<usize> {
+        self.panel_entries
+            .iter()
+            .position(|entry| entry.panel.panel_id() == panel_id)
+    }
+
+    pub fn panel_index_for_persistent_name(&self, ui_name: &str, cx: &AppContext) -> Option<usize> {
         self.panel_entries
             .iter()
-            .position(|entry| entry.panel.persistent_name() == ui_name)
+            .position(|entry| entry.panel.persistent_name(cx) == ui_name)
     }
 
     pub fn active_panel_index(&self) -> usize {
@@ -430,7 +447,7 @@ impl Dock {
             }),
         ];
 
-        let name = panel.persistent_name().to_string();
+        let name = panel.persistent_name(cx).to_string();
 
         self.panel_entries.push(PanelEntry {
             panel: Arc::new(panel.clone()),
@@ -473,6 +490,10 @@ impl Dock {
         }
     }
 
+    pub fn panel_handles(&self) -> impl Iterator<Item = &Arc<dyn PanelHandle>> {
+        self.panel_entries.iter().map(|entry| &entry.panel)
+    }
+
     pub fn panels_len(&self) -> usize {
         self.panel_entries.len()
     }
@@ -650,14 +671,19 @@ impl Render for Dock {
 }
 
 impl PanelButtons {
-    pub fn new(dock: View<Dock>, cx: &mut ViewContext<Self>) -> Self {
+    pub fn new(
+        dock: View<Dock>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
         cx.observe(&dock, |_, _, cx| cx.notify()).detach();
-        Self { dock }
+        Self { dock, workspace }
     }
 }
 
 impl Render for PanelButtons {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let workspace = self.workspace.upgrade();
         let dock = self.dock.read(cx);
         let active_index = dock.active_panel_index;
         let is_open = dock.is_open;
@@ -677,8 +703,13 @@ impl Render for PanelButtons {
             .filter_map(|(i, entry)| {
                 let icon = entry.panel.icon(cx)?;
                 let icon_tooltip = entry.panel.icon_tooltip(cx)?;
-                let name = entry.panel.persistent_name();
+                let name = entry.panel.persistent_name(cx);
                 let panel = entry.panel.clone();
+                let hint = workspace.as_ref().and_then(|workspace| {
+                    workspace
+                        .read(cx)
+                        .key_hint(&HintTarget::PanelButton(panel.panel_id()))
+                });
 
                 let is_active_button = i == active_index && is_open;
                 let (action, tooltip) = if is_active_button {
@@ -691,11 +722,11 @@ impl Render for PanelButtons {
                 } else {
                     let action = entry.panel.toggle_action(cx);
 
-                    (action, icon_tooltip.into())
+                    (action, icon_tooltip)
                 };
 
                 Some(
-                    right_click_menu(name)
+                    right_click_menu(name.clone())
                         .menu(move |cx| {
                             const POSITIONS: [DockPosition; 3] = [
                                 DockPosition::Left,
@@ -724,16 +755,21 @@ impl Render for PanelButtons {
                         .anchor(menu_anchor)
                         .attach(menu_attach)
                         .trigger(
-                            IconButton::new(name, icon)
-                                .icon_size(IconSize::Small)
-                                .selected(is_active_button)
-                                .on_click({
-                                    let action = action.boxed_clone();
-                                    move |_, cx| cx.dispatch_action(action.boxed_clone())
-                                })
-                                .tooltip(move |cx| {
-                                    Tooltip::for_action(tooltip.clone(), &*action, cx)
-                                }),
+                            h_flex()
+                                .gap_0p5()
+                                .child(
+                                    IconButton::new(name, icon)
+                                        .icon_size(IconSize::Small)
+                                        .selected(is_active_button)
+                                        .on_click({
+                                            let action = action.boxed_clone();
+                                            move |_, cx| cx.dispatch_action(action.boxed_clone())
+                                        })
+                                        .tooltip(move |cx| {
+                                            Tooltip::for_action(tooltip.clone(), &*action, cx)
+                                        }),
+                                )
+                                .children(hint.map(|hint| render_key_hint(hint, cx))),
                         ),
                 )
             });
@@ -0,0 +1,316 @@
+use crate::{
+    dock::Dock, notifications::NotificationId, FocusNextRegion, FocusPreviousRegion, ShowKeyHints,
+    Workspace,
+};
+use gpui::{EntityId, FocusHandle, FocusableView, KeyDownEvent, Subscription, View};
+use ui::prelude::*;
+
+/// The characters that hint labels are made of, starting with the home row.
+const HINT_CHARACTERS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// A control that can be activated by typing its hint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HintTarget {
+    /// The tab of an item in one of the center panes, which is activated.
+    Tab(EntityId),
+    /// The button of a panel in the status bar, which focuses the panel.
+    PanelButton(EntityId),
+    /// A notification, which is dismissed.
+    Notification(NotificationId),
+}
+
+/// The labels shown over the controls of the workspace while hint mode is on.
+pub(crate) struct KeyHints {
+    targets: Vec<(SharedString, HintTarget)>,
+    /// The beginning of a label that has been typed so far.
+    typed: String,
+    focus_handle: FocusHandle,
+    previous_focus: Option<FocusHandle>,
+    _blur: Subscription,
+}
+
+impl Workspace {
+    /// Shows hints over the visible tabs, panel buttons and notifications, or
+    /// hides them if they're shown.
+    pub fn show_key_hints(&mut self, _: &ShowKeyHints, cx: &mut ViewContext<Self>) {
+        if self.key_hints.is_some() {
+            self.hide_key_hints(true, cx);
+            return;
+        }
+
+        let targets = self.hint_targets(cx);
+        if targets.is_empty() {
+            return;
+        }
+        let focus_handle = cx.focus_handle();
+        let previous_focus = cx.focused();
+        let blur = cx.on_blur(&focus_handle, |this, cx| this.hide_key_hints(false, cx));
+        focus_handle.focus(cx);
+        self.key_hints = Some(KeyHints {
+            targets: hint_labels(targets.len())
+                .into_iter()
+                .zip(targets)
+                .collect(),
+            typed: String::new(),
+            focus_handle,
+            previous_focus,
+            _blur: blur,
+        });
+        self.notify_hinted_views(cx);
+    }
+
+    /// Returns the hint shown over the target, if hints are shown and the target's
+    /// hint starts with what has been typed so far.
+    pub fn key_hint(&self, target: &HintTarget) -> Option<SharedString> {
+        let key_hints = self.key_hints.as_ref()?;
+        key_hints
+            .targets
+            .iter()
+            .find(|(label, hint_target)| {
+                hint_target == target && label.starts_with(key_hints.typed.as_str())
+            })
+            .map(|(label, _)| label.clone())
+    }
+
+    fn hint_targets(&self, cx: &WindowContext) -> Vec<HintTarget> {
+        let mut targets = Vec::new();
+        for pane in self.center.panes() {
+            targets.extend(
+                pane.read(cx)
+                    .items()
+                    .map(|item| HintTarget::Tab(item.item_id())),
+            );
+        }
+        for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+            targets.extend(
+                dock.read(cx)
+                    .panel_handles()
+                    .filter(|panel| panel.icon(cx).is_some() && panel.icon_tooltip(cx).is_some())
+                    .map(|panel| HintTarget::PanelButton(panel.panel_id())),
+            );
+        }
+        targets.extend(
+            self.notifications
+                .iter()
+                .map(|(id, _)| HintTarget::Notification(id.clone())),
+        );
+        targets
+    }
+
+    fn hide_key_hints(&mut self, restore_focus: bool, cx: &mut ViewContext<Self>) {
+        let Some(key_hints) = self.key_hints.take() else {
+            return;
+        };
+        if restore_focus {
+            if let Some(previous_focus) = key_hints.previous_focus {
+                previous_focus.focus(cx);
+            }
+        }
+        self.notify_hinted_views(cx);
+    }
+
+    /// Re-renders the views that show hints, which may be cached otherwise.
+    fn notify_hinted_views(&self, cx: &mut ViewContext<Self>) {
+        for pane in self.center.panes() {
+            pane.update(cx, |_, cx| cx.notify());
+        }
+        for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+            dock.update(cx, |_, cx| cx.notify());
+        }
+        cx.notify();
+    }
+
+    fn handle_key_hint_input(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let Some(key_hints) = self.key_hints.as_mut() else {
+            return;
+        };
+        let keystroke = &event.keystroke;
+        // Let other keybindings through, after hiding the hints.
+        if keystroke.modifiers.modified() {
+            self.hide_key_hints(false, cx);
+            return;
+        }
+        cx.stop_propagation();
+
+        match keystroke.key.as_str() {
+            "escape" => self.hide_key_hints(true, cx),
+            "backspace" => {
+                key_hints.typed.pop();
+                self.notify_hinted_views(cx);
+            }
+            key if key.chars().count() == 1 && HINT_CHARACTERS.contains(key) => {
+                key_hints.typed.push_str(key);
+                let typed = key_hints.typed.as_str();
+                let target = key_hints
+                    .targets
+                    .iter()
+                    .find(|(label, _)| label == typed)
+                    .map(|(_, target)| target.clone());
+                let any_match = key_hints
+                    .targets
+                    .iter()
+                    .any(|(label, _)| label.starts_with(typed));
+
+                if let Some(target) = target {
+                    self.hide_key_hints(false, cx);
+                    self.activate_hint_target(target, cx);
+                } else if any_match {
+                    self.notify_hinted_views(cx);
+                } else {
+                    self.hide_key_hints(true, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn activate_hint_target(&mut self, target: HintTarget, cx: &mut ViewContext<Self>) {
+        match target {
+            HintTarget::Tab(item_id) => {
+                let Some(pane) = self
+                    .panes_by_item
+                    .get(&item_id)
+                    .and_then(|pane| pane.upgrade())
+                else {
+                    return;
+                };
+                pane.update(cx, |pane, cx| {
+                    let ix = pane.items().position(|item| item.item_id() == item_id);
+                    if let Some(ix) = ix {
+                        pane.activate_item(ix, true, true, cx);
+                    }
+                });
+            }
+            HintTarget::PanelButton(panel_id) => {
+                self.focus_or_unfocus_panel(
+                    cx,
+                    |dock| dock.panel_index_for_id(panel_id),
+                    |_, _| true,
+                );
+            }
+            HintTarget::Notification(id) => self.dismiss_notification(&id, cx),
+        }
+    }
+
+    /// Renders the prompt that receives the hints as they're typed.
+    pub(crate) fn render_key_hints_prompt(&self, cx: &mut ViewContext<Self>) -> Option<Div> {
+        let key_hints = self.key_hints.as_ref()?;
+        Some(
+            div()
+                .absolute()
+                .bottom_3()
+                .w_full()
+                .flex()
+                .justify_center()
+                .child(
+                    h_flex()
+                        .track_focus(&key_hints.focus_handle)
+                        .key_context("KeyHints")
+                        .on_key_down(cx.listener(Self::handle_key_hint_input))
+                        .gap_2()
+                        .px_2()
+                        .py_1()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(cx.theme().colors().elevated_surface_background)
+                        .child(Label::new(
+                            "Type a hint to activate it, or escape to cancel",
+                        ))
+                        .when(!key_hints.typed.is_empty(), |this| {
+                            this.child(Label::new(key_hints.typed.clone()).color(Color::Accent))
+                        }),
+                ),
+        )
+    }
+
+    /// Moves the focus to the next of the active center pane and the open docks,
+    /// like F6 does in other applications.
+    pub fn focus_next_region(&mut self, _: &FocusNextRegion, cx: &mut ViewContext<Self>) {
+        self.focus_region_by_offset(1, cx);
+    }
+
+    pub fn focus_previous_region(&mut self, _: &FocusPreviousRegion, cx: &mut ViewContext<Self>) {
+        self.focus_region_by_offset(-1, cx);
+    }
+
+    fn focus_region_by_offset(&mut self, offset: isize, cx: &mut ViewContext<Self>) {
+        let regions = self.focus_regions(cx);
+        // With no region focused, the center pane comes next.
+        let next_ix = match regions
+            .iter()
+            .position(|region| region.contains_focused(cx))
+        {
+            Some(ix) => (ix as isize + offset).rem_euclid(regions.len() as isize) as usize,
+            None => 0,
+        };
+        regions[next_ix].focus(cx);
+    }
+
+    /// Returns the focus handles of the active center pane and of the bottom, right
+    /// and left docks, leaving out the docks that aren't shown.
+    fn focus_regions(&self, cx: &WindowContext) -> Vec<FocusHandle> {
+        let dock_region = |dock: &View<Dock>| {
+            let dock = dock.read(cx);
+            let panel = dock.visible_panel()?;
+            (self.zoomed_position.is_none() || self.zoomed_position == Some(dock.position()))
+                .then(|| panel.focus_handle(cx))
+        };
+        let mut regions = vec![self.active_pane.focus_handle(cx)];
+        regions.extend(dock_region(&self.bottom_dock));
+        regions.extend(dock_region(&self.right_dock));
+        regions.extend(dock_region(&self.left_dock));
+        regions
+    }
+}
+
+/// Renders a hint over a control, like the tab of an item.
+pub fn render_key_hint(label: SharedString, cx: &WindowContext) -> Div {
+    div()
+        .px_1()
+        .rounded_sm()
+        .bg(cx.theme().status().info_background)
+        .border_1()
+        .border_color(cx.theme().status().info_border)
+        .child(
+            Label::new(label)
+                .size(LabelSize::XSmall)
+                .color(Color::Default),
+        )
+}
+
+/// Returns labels for the given number of hints, all of the same length so that
+/// none starts with another.
+fn hint_labels(count: usize) -> Vec<SharedString> {
+    let characters = HINT_CHARACTERS.chars().collect::<Vec<_>>();
+    let mut len = 1;
+    while characters.len().pow(len) < count {
+        len += 1;
+    }
+    (0..count)
+        .map(|mut ix| {
+            let mut label = String::new();
+            for _ in 0..len {
+                label.insert(0, characters[ix % characters.len()]);
+                ix /= characters.len();
+            }
+            label.into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_labels() {
+        assert_eq!(hint_labels(3), ["a", "s", "d"].map(SharedString::from));
+
+        let labels = hint_labels(HINT_CHARACTERS.len() + 1);
+        assert_eq!(labels.len(), HINT_CHARACTERS.len() + 1);
+        assert_eq!(labels[0], "aa");
+        assert_eq!(labels[1], "as");
+        assert_eq!(labels[HINT_CHARACTERS.len()], "sa");
+    }
+}
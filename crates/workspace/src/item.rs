@@ -32,7 +32,7 @@ use std::{
     time::Duration,
 };
 use theme::Theme;
-use ui::Element as _;
+use ui::{Element as _, Icon};
 
 pub const LEADER_UPDATE_THROTTLE: Duration = Duration::from_millis(200);
 
@@ -40,6 +40,7 @@ pub const LEADER_UPDATE_THROTTLE: Duration = Duration::from_millis(200);
 pub struct ItemSettings {
     pub git_status: bool,
     pub close_position: ClosePosition,
+    pub file_icons: bool,
 }
 
 #[derive(Deserialize)]
@@ -75,6 +76,10 @@ pub struct ItemSettingsContent {
     ///
     /// Default: right
     close_position: Option<ClosePosition>,
+    /// Whether to show the file icon of an item in its tab and in the breadcrumbs.
+    ///
+    /// Default: false
+    file_icons: Option<bool>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -125,6 +130,14 @@ pub struct BreadcrumbText {
     pub font: Option<Font>,
 }
 
+/// A position within an item that a navigation history entry points to, which
+/// outlives the item so that the history can be restored in a later session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavigationPosition {
+    pub row: u32,
+    pub column: u32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct TabContentParams {
     pub detail: Option<usize>,
@@ -144,9 +157,20 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn navigate(&mut self, _: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
         false
     }
+    /// Returns the position that the navigation data pushed by this item points
+    /// to. Items that return one can be navigated to with a `NavigationPosition`
+    /// as well, which is how their history is listed and restored.
+    fn navigation_position(&self, _: &dyn Any, _: &AppContext) -> Option<NavigationPosition> {
+        None
+    }
     fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
         None
     }
+    /// Returns the icon to show next to the item's tab content, such as the
+    /// icon of the file the item displays.
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        None
+    }
     fn tab_description(&self, _: usize, _: &AppContext) -> Option<SharedString> {
         None
     }
@@ -267,6 +291,7 @@ pub trait ItemHandle: 'static + Send {
     ) -> gpui::Subscription;
     fn focus_handle(&self, cx: &WindowContext) -> FocusHandle;
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString>;
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon>;
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString>;
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str>;
@@ -295,7 +320,9 @@ pub trait ItemHandle: 'static + Send {
     fn deactivated(&self, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool;
+    fn navigation_position(&self, data: &dyn Any, cx: &AppContext) -> Option<NavigationPosition>;
     fn item_id(&self) -> EntityId;
+    fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
     fn has_conflict(&self, cx: &AppContext) -> bool;
@@ -363,6 +390,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).tab_tooltip_text(cx)
     }
 
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        self.read(cx).tab_icon(cx)
+    }
+
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str> {
         self.read(cx).telemetry_event_text()
     }
@@ -615,10 +646,18 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.navigate(data, cx))
     }
 
+    fn navigation_position(&self, data: &dyn Any, cx: &AppContext) -> Option<NavigationPosition> {
+        self.read(cx).navigation_position(data, cx)
+    }
+
     fn item_id(&self) -> EntityId {
         self.entity_id()
     }
 
+    fn downgrade_item(&self) -> Box<dyn WeakItemHandle> {
+        Box::new(self.downgrade())
+    }
+
     fn to_any(&self) -> AnyView {
         self.clone().into()
     }
@@ -0,0 +1,260 @@
+use crate::{
+    dock::Dock, pane_group::Member, Event, SaveLayoutProfile, SwitchLayoutProfile, Workspace,
+    WorkspaceId,
+};
+use anyhow::{Context as _, Result};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{px, Axis, Task, View, ViewContext, WindowContext};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const LAYOUT_PROFILES_KEY: &str = "layout_profiles";
+
+/// A named arrangement of the docks and panes of a workspace, like "coding" or
+/// "review", that can be switched back to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutProfile {
+    pub left_dock: DockLayout,
+    pub bottom_dock: DockLayout,
+    pub right_dock: DockLayout,
+    /// The proportions of the splits between panes, which are only restored
+    /// where the panes are split the same way as when the profile was saved.
+    pub panes: PaneLayout,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    pub open: bool,
+    /// The persistent name of the active panel.
+    pub active_panel: Option<String>,
+    /// The size of the active panel, in pixels.
+    pub size: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneLayout {
+    Pane,
+    Split {
+        vertical: bool,
+        flexes: Vec<f32>,
+        members: Vec<PaneLayout>,
+    },
+}
+
+/// Where a layout profile is saved. Profiles saved for the project take
+/// precedence over global ones with the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutProfileScope {
+    Project,
+    Global,
+}
+
+impl Workspace {
+    /// Returns the current arrangement of the docks and panes.
+    pub fn layout_profile(&self, cx: &WindowContext) -> LayoutProfile {
+        LayoutProfile {
+            left_dock: dock_layout(&self.left_dock, cx),
+            bottom_dock: dock_layout(&self.bottom_dock, cx),
+            right_dock: dock_layout(&self.right_dock, cx),
+            panes: pane_layout(&self.center.root),
+        }
+    }
+
+    pub fn apply_layout_profile(&mut self, profile: &LayoutProfile, cx: &mut ViewContext<Self>) {
+        if self.zen_mode.take().is_some() {
+            cx.emit(Event::ZenModeChanged);
+        }
+        for (dock, layout) in [
+            (&self.left_dock, &profile.left_dock),
+            (&self.bottom_dock, &profile.bottom_dock),
+            (&self.right_dock, &profile.right_dock),
+        ] {
+            dock.update(cx, |dock, cx| {
+                if let Some(panel_ix) = layout
+                    .active_panel
+                    .as_deref()
+                    .and_then(|name| dock.panel_index_for_persistent_name(name, cx))
+                {
+                    dock.activate_panel(panel_ix, cx);
+                }
+                dock.set_open(layout.open, cx);
+                if let Some(size) = layout.size {
+                    dock.resize_active_panel(Some(px(size)), cx);
+                }
+            });
+        }
+        apply_pane_layout(&self.center.root, &profile.panes);
+
+        cx.notify();
+        self.serialize_workspace(cx).detach();
+    }
+
+    /// Returns the names of the profiles that can be switched to, with the
+    /// project's ones first.
+    pub fn layout_profiles(
+        &self,
+        cx: &WindowContext,
+    ) -> Task<Result<Vec<(String, LayoutProfileScope)>>> {
+        let project_key = profiles_key(LayoutProfileScope::Project, self.database_id());
+        cx.background_executor().spawn(async move {
+            let mut profiles = read_profiles(&project_key)?
+                .into_keys()
+                .map(|name| (name, LayoutProfileScope::Project))
+                .collect::<Vec<_>>();
+            for name in read_profiles(LAYOUT_PROFILES_KEY)?.into_keys() {
+                if !profiles
+                    .iter()
+                    .any(|(project_name, _)| *project_name == name)
+                {
+                    profiles.push((name, LayoutProfileScope::Global));
+                }
+            }
+            Ok(profiles)
+        })
+    }
+
+    /// Saves the current layout as a profile with the name, replacing any profile
+    /// with the same name in the scope.
+    pub fn save_layout_profile_as(
+        &self,
+        name: String,
+        scope: LayoutProfileScope,
+        cx: &WindowContext,
+    ) -> Task<Result<()>> {
+        let profile = self.layout_profile(cx);
+        let key = profiles_key(scope, self.database_id());
+        cx.background_executor().spawn(async move {
+            update_profiles(key, |profiles| {
+                profiles.insert(name, profile);
+            })
+            .await
+        })
+    }
+
+    pub fn delete_layout_profile(
+        &self,
+        name: String,
+        scope: LayoutProfileScope,
+        cx: &WindowContext,
+    ) -> Task<Result<()>> {
+        let key = profiles_key(scope, self.database_id());
+        cx.background_executor().spawn(async move {
+            update_profiles(key, |profiles| {
+                profiles.remove(&name);
+            })
+            .await
+        })
+    }
+
+    /// Switches to the profile with the name that's saved for the project, or to
+    /// the global one if there's none.
+    pub fn switch_to_layout_profile(
+        &mut self,
+        name: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let project_key = profiles_key(LayoutProfileScope::Project, self.database_id());
+        cx.spawn(|this, mut cx| async move {
+            let profile = cx
+                .background_executor()
+                .spawn(async move {
+                    let profile = match read_profiles(&project_key)?.remove(&name) {
+                        Some(profile) => Some(profile),
+                        None => read_profiles(LAYOUT_PROFILES_KEY)?.remove(&name),
+                    };
+                    profile.with_context(|| format!("no layout profile named {name:?}"))
+                })
+                .await?;
+            this.update(&mut cx, |this, cx| this.apply_layout_profile(&profile, cx))
+        })
+    }
+
+    pub(crate) fn save_layout_profile(
+        &mut self,
+        action: &SaveLayoutProfile,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let scope = if action.global {
+            LayoutProfileScope::Global
+        } else {
+            LayoutProfileScope::Project
+        };
+        self.save_layout_profile_as(action.name.clone(), scope, cx)
+            .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn switch_layout_profile(
+        &mut self,
+        action: &SwitchLayoutProfile,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.switch_to_layout_profile(action.name.clone(), cx)
+            .detach_and_log_err(cx);
+    }
+}
+
+fn dock_layout(dock: &View<Dock>, cx: &WindowContext) -> DockLayout {
+    let dock = dock.read(cx);
+    let active_panel = dock.active_panel();
+    DockLayout {
+        open: dock.is_open(),
+        active_panel: active_panel.map(|panel| panel.persistent_name(cx).to_string()),
+        size: active_panel.map(|panel| panel.size(cx).0),
+    }
+}
+
+fn pane_layout(member: &Member) -> PaneLayout {
+    match member {
+        Member::Pane(_) => PaneLayout::Pane,
+        Member::Axis(axis) => PaneLayout::Split {
+            vertical: axis.axis == Axis::Vertical,
+            flexes: axis.flexes.lock().clone(),
+            members: axis.members.iter().map(pane_layout).collect(),
+        },
+    }
+}
+
+fn apply_pane_layout(member: &Member, layout: &PaneLayout) {
+    if let (
+        Member::Axis(axis),
+        PaneLayout::Split {
+            vertical,
+            flexes,
+            members,
+        },
+    ) = (member, layout)
+    {
+        if (axis.axis == Axis::Vertical) == *vertical && axis.members.len() == members.len() {
+            *axis.flexes.lock() = flexes.clone();
+            for (member, layout) in axis.members.iter().zip(members) {
+                apply_pane_layout(member, layout);
+            }
+        }
+    }
+}
+
+fn profiles_key(scope: LayoutProfileScope, workspace_id: WorkspaceId) -> String {
+    match scope {
+        LayoutProfileScope::Project => format!("{LAYOUT_PROFILES_KEY}-{}", workspace_id.0),
+        LayoutProfileScope::Global => LAYOUT_PROFILES_KEY.to_string(),
+    }
+}
+
+fn read_profiles(key: &str) -> Result<BTreeMap<String, LayoutProfile>> {
+    match KEY_VALUE_STORE.read_kvp(key)? {
+        Some(profiles) => Ok(serde_json::from_str(&profiles)?),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+async fn update_profiles(
+    key: String,
+    update: impl FnOnce(&mut BTreeMap<String, LayoutProfile>),
+) -> Result<()> {
+    let mut profiles = read_profiles(&key)?;
+    update(&mut profiles);
+    KEY_VALUE_STORE
+        .write_kvp(key, serde_json::to_string(&profiles)?)
+        .await
+}
@@ -0,0 +1,195 @@
+use gpui::{DismissEvent, EventEmitter, ViewContext, WeakView};
+use project::OfflineMergeSummary;
+use ui::{prelude::*, Tooltip};
+
+use crate::{rejoin_offline_project, Workspace};
+
+/// Shown in the window of a remote project that lost its connection to the host
+/// while it can still be edited.
+pub(crate) struct OfflineEditingNotification {
+    workspace: WeakView<Workspace>,
+    rejoining: bool,
+    error: Option<SharedString>,
+}
+
+impl EventEmitter<DismissEvent> for OfflineEditingNotification {}
+
+impl OfflineEditingNotification {
+    pub fn new(workspace: WeakView<Workspace>) -> Self {
+        Self {
+            workspace,
+            rejoining: false,
+            error: None,
+        }
+    }
+
+    fn rejoin(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(window) = cx.window_handle().downcast::<Workspace>() else {
+            return;
+        };
+        let app_state = workspace.read(cx).app_state().clone();
+        let rejoin = rejoin_offline_project(window, app_state, cx);
+        self.rejoining = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = rejoin.await;
+            // If everything was merged, this window has been closed by now.
+            this.update(&mut cx, |this, cx| {
+                this.rejoining = false;
+                if let Err(error) = result {
+                    this.error = Some(format!("Couldn't rejoin: {error}").into());
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+impl Render for OfflineEditingNotification {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_3(cx)
+            .p_4()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(div().max_w_80().child(Label::new(
+                        "Your connection to the remote project has been lost. \
+                        You can keep editing, and your changes will be merged \
+                        when you rejoin.",
+                    )))
+                    .child(
+                        IconButton::new("dismiss", IconName::Close)
+                            .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+                    ),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).color(Color::Error)),
+            )
+            .child(
+                h_flex().child(
+                    Button::new(
+                        "rejoin",
+                        if self.rejoining {
+                            "Rejoining…"
+                        } else {
+                            "Rejoin and Merge"
+                        },
+                    )
+                    .style(ButtonStyle::Filled)
+                    .disabled(self.rejoining)
+                    .on_click(cx.listener(|this, _, cx| this.rejoin(cx))),
+                ),
+            )
+    }
+}
+
+/// Summarizes how the edits made while offline were merged into a rejoined project.
+pub(crate) struct OfflineMergeNotification {
+    summary: OfflineMergeSummary,
+    workspace: WeakView<Workspace>,
+}
+
+impl EventEmitter<DismissEvent> for OfflineMergeNotification {}
+
+impl OfflineMergeNotification {
+    pub fn new(summary: OfflineMergeSummary, workspace: WeakView<Workspace>) -> Self {
+        Self { summary, workspace }
+    }
+
+    fn title(&self) -> String {
+        let edit_count = self.summary.merged_edit_count();
+        let edits = if edit_count == 1 { "edit" } else { "edits" };
+        match self.summary.conflict_count() {
+            0 => format!("Merged {edit_count} offline {edits}."),
+            1 => format!("Merged {edit_count} offline {edits}, 1 of which conflicts with other changes."),
+            conflict_count => format!(
+                "Merged {edit_count} offline {edits}, {conflict_count} of which conflict with other changes."
+            ),
+        }
+    }
+
+    fn open_buffer(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(merge) = self.summary.buffers.get(ix) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(merge.project_path.clone(), None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+}
+
+impl Render for OfflineMergeNotification {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_3(cx)
+            .p_4()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(div().max_w_80().child(Label::new(self.title())))
+                    .child(
+                        IconButton::new("dismiss", IconName::Close)
+                            .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+                    ),
+            )
+            .child(
+                v_flex().children(self.summary.buffers.iter().enumerate().map(|(ix, merge)| {
+                    let mut details = Vec::new();
+                    if !merge.conflicts.is_empty() {
+                        details.push(
+                            Label::new(format!("{} conflicting", merge.conflicts.len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Warning),
+                        );
+                    }
+                    if merge.skipped_edit_count > 0 {
+                        details.push(
+                            Label::new(format!("{} not merged", merge.skipped_edit_count))
+                                .size(LabelSize::Small)
+                                .color(Color::Error),
+                        );
+                    }
+
+                    h_flex()
+                        .id(ix)
+                        .px_1()
+                        .gap_2()
+                        .justify_between()
+                        .rounded_md()
+                        .cursor_pointer()
+                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                        .child(Label::new(
+                            merge.project_path.path.to_string_lossy().to_string(),
+                        ))
+                        .child(h_flex().gap_1().children(details))
+                        .tooltip(|cx| Tooltip::text("Open File", cx))
+                        .on_click(cx.listener(move |this, _, cx| this.open_buffer(ix, cx)))
+                })),
+            )
+            .when(self.summary.skipped_edit_count() > 0, |this| {
+                this.child(
+                    Label::new(
+                        "Edits that couldn't be merged are still open in the disconnected window.",
+                    )
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+    }
+}
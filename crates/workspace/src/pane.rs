@@ -1,22 +1,24 @@
 use crate::{
     item::{
-        ClosePosition, Item, ItemHandle, ItemSettings, PreviewTabsSettings, TabContentParams,
-        WeakItemHandle,
+        ClosePosition, Item, ItemHandle, ItemSettings, NavigationPosition, PreviewTabsSettings,
+        TabContentParams, WeakItemHandle,
     },
+    render_key_hint,
     toolbar::Toolbar,
     workspace_settings::{AutosaveSetting, TabBarSettings, WorkspaceSettings},
-    CloseWindow, NewCenterTerminal, NewFile, NewSearch, OpenInTerminal, OpenTerminal, OpenVisible,
-    SplitDirection, ToggleZoom, Workspace,
+    CloseWindow, HintTarget, MoveItemToNewWindow, NewCenterTerminal, NewFile, NewSearch,
+    OpenInTerminal, OpenTerminal, OpenVisible, SplitDirection, ToggleZoom, Workspace,
 };
 use anyhow::Result;
 use collections::{HashMap, HashSet, VecDeque};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
     actions, anchored, deferred, impl_actions, prelude::*, Action, AnchorCorner, AnyElement,
-    AppContext, AsyncWindowContext, ClickEvent, DismissEvent, Div, DragMoveEvent, EntityId,
+    AppContext, AsyncWindowContext, Bounds, ClickEvent, DismissEvent, Div, DragMoveEvent, EntityId,
     EventEmitter, ExternalPaths, FocusHandle, FocusableView, KeyContext, Model, MouseButton,
-    MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render, ScrollHandle,
-    Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView, WindowContext,
+    MouseDownEvent, MouseUpEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
+    ScrollHandle, Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView,
+    WindowContext,
 };
 use parking_lot::Mutex;
 use project::{Project, ProjectEntryId, ProjectPath};
@@ -145,6 +147,7 @@ pub enum Event {
     RemoveItem { item_id: EntityId },
     Split(SplitDirection),
     ChangeItemTitle,
+    ChangeHistory,
     Focus,
     ZoomIn,
     ZoomOut,
@@ -171,6 +174,7 @@ impl fmt::Debug for Event {
                 .field("direction", direction)
                 .finish(),
             Event::ChangeItemTitle => f.write_str("ChangeItemTitle"),
+            Event::ChangeHistory => f.write_str("ChangeHistory"),
             Event::Focus => f.write_str("Focus"),
             Event::ZoomIn => f.write_str("ZoomIn"),
             Event::ZoomOut => f.write_str("ZoomOut"),
@@ -550,8 +554,45 @@ impl Pane {
         }
     }
 
+    /// Goes back or forward by several entries at once, as when an entry is picked
+    /// from the history menu of the navigation buttons.
+    fn navigate_by(&mut self, mode: NavigationMode, steps: usize, cx: &mut ViewContext<Self>) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let pane = cx.view().downgrade();
+            cx.window_context().defer(move |cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace
+                        .navigate_history_by(pane, mode, steps, cx)
+                        .detach_and_log_err(cx)
+                })
+            })
+        }
+    }
+
+    fn render_nav_history_menu(
+        pane: WeakView<Self>,
+        mode: NavigationMode,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        ContextMenu::build(cx, move |mut menu, cx| {
+            let Some(pane) = pane.upgrade() else {
+                return menu;
+            };
+            let labels = pane.read(cx).nav_history.entry_labels(mode, cx);
+            for (ix, label) in labels.into_iter().enumerate() {
+                menu = menu.entry(
+                    label,
+                    None,
+                    cx.handler_for(&pane, move |pane, cx| pane.navigate_by(mode, ix + 1, cx)),
+                );
+            }
+            menu
+        })
+    }
+
     fn history_updated(&mut self, cx: &mut ViewContext<Self>) {
         self.toolbar.update(cx, |_, cx| cx.notify());
+        cx.emit(Event::ChangeHistory);
     }
 
     pub fn preview_item_id(&self) -> Option<EntityId> {
@@ -1445,9 +1486,18 @@ impl Pane {
             },
             cx,
         );
-        let close_side = &ItemSettings::get_global(cx).close_position;
+        let item_settings = ItemSettings::get_global(cx);
+        let close_side = &item_settings.close_position;
+        let icon = item_settings
+            .file_icons
+            .then(|| item.tab_icon(cx))
+            .flatten();
         let indicator = render_item_indicator(item.boxed_clone(), cx);
         let item_id = item.item_id();
+        let hint = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).key_hint(&HintTarget::Tab(item_id)));
         let is_first_item = ix == 0;
         let is_last_item = ix == self.items.len() - 1;
         let position_relative_to_active_item = ix.cmp(&self.active_item_index);
@@ -1496,20 +1546,14 @@ impl Pane {
                 },
                 |tab, cx| cx.new_view(|_| tab.clone()),
             )
-            .drag_over::<DraggedTab>(|tab, _, cx| {
-                tab.bg(cx.theme().colors().drop_target_background)
-            })
-            .drag_over::<ProjectEntryId>(|tab, _, cx| {
-                tab.bg(cx.theme().colors().drop_target_background)
-            })
             .when_some(self.can_drop_predicate.clone(), |this, p| {
                 this.can_drop(move |a, cx| p(a, cx))
             })
-            .on_drop(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
+            .drop_target(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
                 this.drag_split_direction = None;
                 this.handle_tab_drop(dragged_tab, ix, cx)
             }))
-            .on_drop(cx.listener(move |this, entry_id: &ProjectEntryId, cx| {
+            .drop_target(cx.listener(move |this, entry_id: &ProjectEntryId, cx| {
                 this.drag_split_direction = None;
                 this.handle_project_entry_drop(entry_id, cx)
             }))
@@ -1532,7 +1576,13 @@ impl Pane {
                             .detach_and_log_err(cx);
                     })),
             )
-            .child(label);
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(hint.map(|hint| render_key_hint(hint, cx)))
+                    .children(icon.map(|icon| icon.size(IconSize::Small).color(Color::Muted)))
+                    .child(label),
+            );
 
         let single_entry_to_resolve = {
             let item_entries = self.items[ix].project_entry_ids(cx);
@@ -1630,6 +1680,22 @@ impl Pane {
                         let entry_id = entry.to_proto();
                         menu = menu
                             .separator()
+                            .entry(
+                                "Move to New Window",
+                                Some(Box::new(MoveItemToNewWindow)),
+                                cx.handler_for(&pane, move |pane, cx| {
+                                    let pane_view = cx.view().clone();
+                                    pane.workspace
+                                        .update(cx, |workspace, cx| {
+                                            workspace.move_items_to_new_window(
+                                                pane_view,
+                                                vec![item_id],
+                                                cx,
+                                            )
+                                        })
+                                        .log_err();
+                                }),
+                            )
                             .entry(
                                 "Reveal In Project Panel",
                                 Some(Box::new(RevealInProjectPanel {
@@ -1675,25 +1741,55 @@ impl Pane {
                         h_flex()
                             .gap_2()
                             .child(
-                                IconButton::new("navigate_backward", IconName::ArrowLeft)
-                                    .icon_size(IconSize::Small)
-                                    .on_click({
-                                        let view = cx.view().clone();
-                                        move |_, cx| view.update(cx, Self::navigate_backward)
-                                    })
-                                    .disabled(!self.can_navigate_backward())
-                                    .tooltip(|cx| Tooltip::for_action("Go Back", &GoBack, cx)),
+                                right_click_menu("navigate_backward_history")
+                                    .trigger(
+                                        IconButton::new("navigate_backward", IconName::ArrowLeft)
+                                            .icon_size(IconSize::Small)
+                                            .on_click({
+                                                let view = cx.view().clone();
+                                                move |_, cx| {
+                                                    view.update(cx, Self::navigate_backward)
+                                                }
+                                            })
+                                            .disabled(!self.can_navigate_backward())
+                                            .tooltip(|cx| {
+                                                Tooltip::for_action("Go Back", &GoBack, cx)
+                                            }),
+                                    )
+                                    .menu({
+                                        let pane = cx.view().downgrade();
+                                        move |cx| {
+                                            Self::render_nav_history_menu(
+                                                pane.clone(),
+                                                NavigationMode::GoingBack,
+                                                cx,
+                                            )
+                                        }
+                                    }),
                             )
                             .child(
-                                IconButton::new("navigate_forward", IconName::ArrowRight)
-                                    .icon_size(IconSize::Small)
-                                    .on_click({
-                                        let view = cx.view().clone();
-                                        move |_, cx| view.update(cx, Self::navigate_forward)
-                                    })
-                                    .disabled(!self.can_navigate_forward())
-                                    .tooltip(|cx| {
-                                        Tooltip::for_action("Go Forward", &GoForward, cx)
+                                right_click_menu("navigate_forward_history")
+                                    .trigger(
+                                        IconButton::new("navigate_forward", IconName::ArrowRight)
+                                            .icon_size(IconSize::Small)
+                                            .on_click({
+                                                let view = cx.view().clone();
+                                                move |_, cx| view.update(cx, Self::navigate_forward)
+                                            })
+                                            .disabled(!self.can_navigate_forward())
+                                            .tooltip(|cx| {
+                                                Tooltip::for_action("Go Forward", &GoForward, cx)
+                                            }),
+                                    )
+                                    .menu({
+                                        let pane = cx.view().downgrade();
+                                        move |cx| {
+                                            Self::render_nav_history_menu(
+                                                pane.clone(),
+                                                NavigationMode::GoingForward,
+                                                cx,
+                                            )
+                                        }
                                     }),
                             ),
                     )
@@ -1721,17 +1817,11 @@ impl Pane {
                     .child("")
                     .h_full()
                     .flex_grow()
-                    .drag_over::<DraggedTab>(|bar, _, cx| {
-                        bar.bg(cx.theme().colors().drop_target_background)
-                    })
-                    .drag_over::<ProjectEntryId>(|bar, _, cx| {
-                        bar.bg(cx.theme().colors().drop_target_background)
-                    })
-                    .on_drop(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
+                    .drop_target(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
                         this.drag_split_direction = None;
                         this.handle_tab_drop(dragged_tab, this.items.len(), cx)
                     }))
-                    .on_drop(cx.listener(move |this, entry_id: &ProjectEntryId, cx| {
+                    .drop_target(cx.listener(move |this, entry_id: &ProjectEntryId, cx| {
                         this.drag_split_direction = None;
                         this.handle_project_entry_drop(entry_id, cx)
                     }))
@@ -1810,6 +1900,29 @@ impl Pane {
         }
     }
 
+    /// Moves a tab that was dragged out of this pane and released outside of
+    /// the window into a new window.
+    fn handle_tab_drag_out_of_window(&mut self, event: &MouseUpEvent, cx: &mut ViewContext<Self>) {
+        let viewport = Bounds::new(Point::default(), cx.viewport_size());
+        if viewport.contains(&event.position) {
+            return;
+        }
+        let Some(dragged_tab) = cx.active_drag::<DraggedTab>() else {
+            return;
+        };
+        if &dragged_tab.pane != cx.view() {
+            return;
+        }
+
+        let item_id = dragged_tab.item.item_id();
+        let pane = cx.view().clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.move_items_to_new_window(pane, vec![item_id], cx)
+            })
+            .log_err();
+    }
+
     fn handle_tab_drop(
         &mut self,
         dragged_tab: &DraggedTab,
@@ -1931,6 +2044,15 @@ impl Pane {
             .log_err();
     }
 
+    fn should_display_tab_bar(&self, cx: &AppContext) -> bool {
+        self.workspace.upgrade().map_or(true, |workspace| {
+            workspace
+                .read(cx)
+                .zen_mode_settings(cx)
+                .map_or(true, |zen_mode| zen_mode.show_tab_bar)
+        })
+    }
+
     pub fn display_nav_history_buttons(&mut self, display: Option<bool>) {
         self.display_nav_history_buttons = display;
     }
@@ -1965,6 +2087,10 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &GoBack, cx| pane.navigate_backward(cx)))
             .on_action(cx.listener(|pane, _: &GoForward, cx| pane.navigate_forward(cx)))
             .on_action(cx.listener(Pane::toggle_zoom))
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(Self::handle_tab_drag_out_of_window),
+            )
             .on_action(cx.listener(|pane: &mut Pane, action: &ActivateItem, cx| {
                 pane.activate_item(action.0, true, true, cx);
             }))
@@ -2048,9 +2174,10 @@ impl Render for Pane {
                     }
                 }),
             )
-            .when(self.active_item().is_some(), |pane| {
-                pane.child(self.render_tab_bar(cx))
-            })
+            .when(
+                self.active_item().is_some() && self.should_display_tab_bar(cx),
+                |pane| pane.child(self.render_tab_bar(cx)),
+            )
             .child({
                 let has_worktrees = self.project.read(cx).worktrees().next().is_some();
                 // main content
@@ -2188,6 +2315,71 @@ impl NavHistory {
             })
     }
 
+    /// Describes the entries that going back or forward would visit, nearest first.
+    pub fn entry_labels(&self, mode: NavigationMode, cx: &AppContext) -> Vec<SharedString> {
+        let state = self.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingBack => &state.backward_stack,
+            NavigationMode::GoingForward => &state.forward_stack,
+            _ => return Vec::new(),
+        };
+        stack
+            .iter()
+            .rev()
+            .map(|entry| {
+                let item = entry.item.upgrade();
+                let name = item
+                    .as_ref()
+                    .and_then(|item| item.tab_description(0, cx))
+                    .or_else(|| {
+                        let (project_path, _) = state.paths_by_item.get(&entry.item.id())?;
+                        let file_name = project_path.path.file_name()?;
+                        Some(file_name.to_string_lossy().into_owned().into())
+                    })
+                    .unwrap_or_else(|| "untitled".into());
+                let position = item
+                    .zip(entry.data.as_ref())
+                    .and_then(|(item, data)| item.navigation_position(&**data, cx));
+                match position {
+                    Some(position) => format!("{name}:{}", position.row + 1).into(),
+                    None => name,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the items and positions of the entries that going back or forward
+    /// would visit, oldest first, skipping entries whose position is unknown.
+    pub fn entry_positions(
+        &self,
+        mode: NavigationMode,
+        cx: &AppContext,
+    ) -> Vec<(EntityId, NavigationPosition)> {
+        let state = self.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingBack => &state.backward_stack,
+            NavigationMode::GoingForward => &state.forward_stack,
+            _ => return Vec::new(),
+        };
+        stack
+            .iter()
+            .filter_map(|entry| {
+                let item = entry.item.upgrade()?;
+                let position = item.navigation_position(entry.data.as_deref()?, cx)?;
+                Some((entry.item.id(), position))
+            })
+            .collect()
+    }
+
+    /// Removes the entries that going back or forward would visit, keeping the
+    /// closed items so that they can still be reopened.
+    pub fn clear(&mut self, cx: &mut WindowContext) {
+        let mut state = self.0.lock();
+        state.backward_stack.clear();
+        state.forward_stack.clear();
+        state.did_update(cx);
+    }
+
     pub fn set_mode(&mut self, mode: NavigationMode) {
         self.0.lock().mode = mode;
     }
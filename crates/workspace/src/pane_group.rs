@@ -1,4 +1,4 @@
-use crate::{pane_group::element::pane_axis, AppState, FollowerState, Pane, Workspace};
+use crate::{pane_group::element::pane_axis, AppState, FollowMode, FollowerState, Pane, Workspace};
 use anyhow::{anyhow, Result};
 use call::{ActiveCall, ParticipantLocation};
 use collections::HashMap;
@@ -197,6 +197,7 @@ impl Member {
                 let mut leader_border = None;
                 let mut leader_status_box = None;
                 let mut leader_join_data = None;
+                let mut follow_trail = None;
                 if let Some(leader) = &leader {
                     let mut leader_color = cx
                         .theme()
@@ -236,6 +237,12 @@ impl Member {
                             leader.user.github_login
                         ))),
                     };
+
+                    follow_trail = follower_state
+                        .filter(|state| {
+                            !state.trail.is_empty() || state.mode == FollowMode::ScrollOnly
+                        })
+                        .map(|state| (state.mode, state.trail.clone()));
                 }
 
                 div()
@@ -257,6 +264,49 @@ impl Member {
                                 .border_color(color),
                         )
                     })
+                    .when_some(follow_trail, |this, (mode, trail)| {
+                        this.child(
+                            h_flex()
+                                .absolute()
+                                .bottom_3()
+                                .left_3()
+                                .gap_1()
+                                .elevation_2(cx)
+                                .p_1()
+                                .when(mode == FollowMode::ScrollOnly, |this| {
+                                    this.child(
+                                        Label::new("Scroll only")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Accent),
+                                    )
+                                })
+                                .children(trail.into_iter().enumerate().map(
+                                    |(ix, (view_id, title))| {
+                                        let pane = pane.clone();
+                                        h_flex()
+                                            .gap_1()
+                                            .when(ix > 0, |this| {
+                                                this.child(
+                                                    Label::new("›")
+                                                        .size(LabelSize::Small)
+                                                        .color(Color::Muted),
+                                                )
+                                            })
+                                            .child(
+                                                Button::new(("follow-trail", ix), title)
+                                                    .label_size(LabelSize::Small)
+                                                    .on_click(cx.listener(
+                                                        move |workspace, _, cx| {
+                                                            workspace.activate_follow_trail_entry(
+                                                                &pane, view_id, cx,
+                                                            )
+                                                        },
+                                                    )),
+                                            )
+                                    },
+                                )),
+                        )
+                    })
                     .when_some(leader_status_box, |this, status_box| {
                         this.child(
                             div()
@@ -1,9 +1,13 @@
 pub mod model;
 
-use std::path::Path;
+use std::{
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use client::RemoteProjectId;
+use collections::HashMap;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{point, size, Axis, Bounds};
 
@@ -22,7 +26,9 @@ use model::{
     SerializedWorkspace,
 };
 
-use self::model::{DockStructure, SerializedRemoteProject, SerializedWorkspaceLocation};
+use self::model::{
+    DockStructure, RecentWorkspaceDetails, SerializedRemoteProject, SerializedWorkspaceLocation,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub(crate) struct SerializedAxis(pub(crate) gpui::Axis);
@@ -140,6 +146,9 @@ define_connection! {
     //   display: Option<Uuid>, // Display id
     //   fullscreen: Option<bool>, // Is the window fullscreen?
     //   centered_layout: Option<bool>, // Is the Centered Layout mode activated?
+    //   always_on_top: Option<bool>, // Is the window kept above other windows?
+    //   window_zoom: Option<f32>, // The factor by which the window's UI is zoomed
+    //   closed_with_unsaved_changes: Option<bool>, // Were unsaved changes discarded when it was last closed?
     // )
     //
     // pane_groups(
@@ -155,6 +164,7 @@ define_connection! {
     //     pane_id: usize, // Primary key for panes
     //     workspace_id: usize, // References workspaces table
     //     active: bool,
+    //     navigation_history: Option<String>, // A JSON object of the back and forward entries
     // )
     //
     // center_panes(
@@ -299,6 +309,19 @@ define_connection! {
         ALTER TABLE workspaces ADD COLUMN remote_project_id INTEGER;
         ALTER TABLE workspaces RENAME COLUMN workspace_location TO local_paths;
     ),
+    // Add always_on_top and window_zoom fields to workspace
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN always_on_top INTEGER; //bool
+        ALTER TABLE workspaces ADD COLUMN window_zoom REAL;
+    ),
+    // Add navigation history to panes
+    sql!(
+        ALTER TABLE panes ADD COLUMN navigation_history TEXT;
+    ),
+    // Add whether unsaved changes were discarded when closing the workspace
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN closed_with_unsaved_changes INTEGER; //bool
+    ),
     ];
 }
 
@@ -322,6 +345,8 @@ impl WorkspaceDb {
             display,
             fullscreen,
             centered_layout,
+            always_on_top,
+            window_zoom,
             docks,
         ): (
             WorkspaceId,
@@ -331,6 +356,8 @@ impl WorkspaceDb {
             Option<Uuid>,
             Option<bool>,
             Option<bool>,
+            Option<bool>,
+            Option<f32>,
             DockStructure,
         ) = self
             .select_row_bound(sql! {
@@ -346,6 +373,8 @@ impl WorkspaceDb {
                     display,
                     fullscreen,
                     centered_layout,
+                    always_on_top,
+                    window_zoom,
                     left_dock_visible,
                     left_dock_active_panel,
                     left_dock_zoom,
@@ -391,6 +420,8 @@ impl WorkspaceDb {
             bounds: bounds.map(|bounds| bounds.0),
             fullscreen: fullscreen.unwrap_or(false),
             centered_layout: centered_layout.unwrap_or(false),
+            always_on_top,
+            window_zoom: window_zoom.unwrap_or(1.0),
             display,
             docks,
         })
@@ -530,6 +561,16 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        fn recent_workspace_activity() -> Result<Vec<(WorkspaceId, i64, Option<bool>)>> {
+            SELECT
+                workspace_id,
+                CAST((julianday(timestamp) - 2440587.5) * 86400 AS INTEGER),
+                closed_with_unsaved_changes
+            FROM workspaces
+        }
+    }
+
     query! {
         fn remote_projects() -> Result<Vec<SerializedRemoteProject>> {
             SELECT remote_project_id, path, dev_server_name
@@ -600,6 +641,21 @@ impl WorkspaceDb {
         Ok(result)
     }
 
+    /// Returns when each workspace was last opened, and how it was closed.
+    pub fn recent_workspace_details(&self) -> Result<HashMap<WorkspaceId, RecentWorkspaceDetails>> {
+        Ok(self
+            .recent_workspace_activity()?
+            .into_iter()
+            .map(|(id, last_opened, closed_with_unsaved_changes)| {
+                let details = RecentWorkspaceDetails {
+                    last_opened: UNIX_EPOCH + Duration::from_secs(last_opened.max(0) as u64),
+                    closed_with_unsaved_changes: closed_with_unsaved_changes.unwrap_or(false),
+                };
+                (id, details)
+            })
+            .collect())
+    }
+
     pub async fn last_workspace(&self) -> Result<Option<LocalPaths>> {
         Ok(self
             .recent_workspaces_on_disk()
@@ -617,12 +673,7 @@ impl WorkspaceDb {
             .get_pane_group(workspace_id, None)?
             .into_iter()
             .next()
-            .unwrap_or_else(|| {
-                SerializedPaneGroup::Pane(SerializedPane {
-                    active: true,
-                    children: vec![],
-                })
-            }))
+            .unwrap_or_else(|| SerializedPaneGroup::Pane(SerializedPane::new(vec![], true))))
     }
 
     fn get_pane_group(
@@ -637,9 +688,10 @@ impl WorkspaceDb {
             Option<PaneId>,
             Option<bool>,
             Option<String>,
+            Option<String>,
         );
         self.select_bound::<GroupKey, GroupOrPane>(sql!(
-            SELECT group_id, axis, pane_id, active, flexes
+            SELECT group_id, axis, pane_id, active, flexes, navigation_history
                 FROM (SELECT
                         group_id,
                         axis,
@@ -648,7 +700,8 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         workspace_id,
-                        flexes
+                        flexes,
+                        NULL as navigation_history
                       FROM pane_groups
                     UNION
                       SELECT
@@ -659,14 +712,15 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         panes.workspace_id as workspace_id,
-                        NULL
+                        NULL,
+                        panes.navigation_history as navigation_history
                       FROM center_panes
                       JOIN panes ON center_panes.pane_id = panes.pane_id)
                 WHERE parent_group_id IS ? AND workspace_id = ?
                 ORDER BY position
         ))?((group_id, workspace_id))?
         .into_iter()
-        .map(|(group_id, axis, pane_id, active, flexes)| {
+        .map(|(group_id, axis, pane_id, active, flexes, history)| {
             if let Some((group_id, axis)) = group_id.zip(axis) {
                 let flexes = flexes
                     .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
@@ -678,10 +732,15 @@ impl WorkspaceDb {
                     flexes,
                 })
             } else if let Some((pane_id, active)) = pane_id.zip(active) {
-                Ok(SerializedPaneGroup::Pane(SerializedPane::new(
-                    self.get_items(pane_id)?,
-                    active,
-                )))
+                // An unreadable history shouldn't keep the pane from being restored.
+                let navigation_history = history
+                    .and_then(|history: String| serde_json::from_str(&history).log_err())
+                    .unwrap_or_default();
+
+                Ok(SerializedPaneGroup::Pane(
+                    SerializedPane::new(self.get_items(pane_id)?, active)
+                        .with_navigation_history(navigation_history),
+                ))
             } else {
                 bail!("Pane Group Child was neither a pane group or a pane");
             }
@@ -751,11 +810,12 @@ impl WorkspaceDb {
         pane: &SerializedPane,
         parent: Option<(GroupId, usize)>,
     ) -> Result<PaneId> {
+        let navigation_history = serde_json::json!(pane.navigation_history).to_string();
         let pane_id = conn.select_row_bound::<_, i64>(sql!(
-            INSERT INTO panes(workspace_id, active)
-            VALUES (?, ?)
+            INSERT INTO panes(workspace_id, active, navigation_history)
+            VALUES (?, ?, ?)
             RETURNING pane_id
-        ))?((workspace_id, pane.active))?
+        ))?((workspace_id, pane.active, navigation_history))?
         .ok_or_else(|| anyhow!("Could not retrieve inserted pane_id"))?;
 
         let (parent_id, order) = unzip_option(parent);
@@ -793,6 +853,14 @@ impl WorkspaceDb {
         Ok(())
     }
 
+    query! {
+        pub(crate) async fn set_closed_with_unsaved_changes(workspace_id: WorkspaceId, closed_with_unsaved_changes: bool) -> Result<()> {
+            UPDATE workspaces
+            SET closed_with_unsaved_changes = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
     query! {
         pub async fn update_timestamp(workspace_id: WorkspaceId) -> Result<()> {
             UPDATE workspaces
@@ -829,6 +897,22 @@ impl WorkspaceDb {
             WHERE workspace_id = ?1
         }
     }
+
+    query! {
+        pub(crate) async fn set_always_on_top(workspace_id: WorkspaceId, always_on_top: bool) -> Result<()> {
+            UPDATE workspaces
+            SET always_on_top = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub(crate) async fn set_window_zoom(workspace_id: WorkspaceId, window_zoom: f32) -> Result<()> {
+            UPDATE workspaces
+            SET window_zoom = ?2
+            WHERE workspace_id = ?1
+        }
+    }
 }
 
 #[cfg(test)]
@@ -916,6 +1000,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         let workspace_2 = SerializedWorkspace {
@@ -927,6 +1013,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         db.save_workspace(workspace_1.clone()).await;
@@ -1027,6 +1115,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         db.save_workspace(workspace.clone()).await;
@@ -1057,6 +1147,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         let mut workspace_2 = SerializedWorkspace {
@@ -1068,6 +1160,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         db.save_workspace(workspace_1.clone()).await;
@@ -1106,6 +1200,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         };
 
         db.save_workspace(workspace_3.clone()).await;
@@ -1125,8 +1221,31 @@ mod tests {
         );
     }
 
+    use crate::item::NavigationPosition;
     use crate::persistence::model::SerializedWorkspace;
-    use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use crate::persistence::model::{
+        SerializedItem, SerializedNavigationEntry, SerializedNavigationHistory, SerializedPane,
+        SerializedPaneGroup,
+    };
+
+    #[gpui::test]
+    async fn test_recent_workspace_details() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_recent_workspace_details").await);
+        db.save_workspace(default_workspace(&["/tmp"], &Default::default()))
+            .await;
+
+        let details = db.recent_workspace_details().unwrap()[&WorkspaceId(4)];
+        assert!(!details.closed_with_unsaved_changes);
+        assert!(details.last_opened > UNIX_EPOCH);
+
+        db.set_closed_with_unsaved_changes(WorkspaceId(4), true)
+            .await
+            .unwrap();
+        let details = db.recent_workspace_details().unwrap()[&WorkspaceId(4)];
+        assert!(details.closed_with_unsaved_changes);
+    }
 
     fn default_workspace<P: AsRef<Path>>(
         workspace_id: &[P],
@@ -1141,6 +1260,8 @@ mod tests {
             docks: Default::default(),
             fullscreen: false,
             centered_layout: false,
+            always_on_top: None,
+            window_zoom: 1.0,
         }
     }
 
@@ -1266,4 +1387,35 @@ mod tests {
 
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
+
+    #[gpui::test]
+    async fn test_navigation_history_serialization() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_navigation_history_serialization").await);
+
+        let entry = |item_ix, row, column| SerializedNavigationEntry {
+            item_ix,
+            position: NavigationPosition { row, column },
+        };
+        let center_pane = SerializedPaneGroup::Pane(
+            SerializedPane::new(
+                vec![
+                    SerializedItem::new("Editor", 1, false, false),
+                    SerializedItem::new("Editor", 2, true, false),
+                ],
+                true,
+            )
+            .with_navigation_history(SerializedNavigationHistory {
+                backward: vec![entry(0, 10, 4), entry(1, 3, 0)],
+                forward: vec![entry(0, 42, 8)],
+            }),
+        );
+
+        let workspace = default_workspace(&["/tmp"], &center_pane);
+        db.save_workspace(workspace.clone()).await;
+
+        let new_workspace = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(workspace.center_group, new_workspace.center_group);
+    }
 }
@@ -1,5 +1,8 @@
 use super::SerializedAxis;
-use crate::{item::ItemHandle, ItemDeserializers, Member, Pane, PaneAxis, Workspace, WorkspaceId};
+use crate::{
+    item::{ItemHandle, NavigationPosition},
+    ItemDeserializers, Member, NavigationMode, Pane, PaneAxis, Workspace, WorkspaceId,
+};
 use anyhow::{Context, Result};
 use async_recursion::async_recursion;
 use client::RemoteProjectId;
@@ -13,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 use util::ResultExt;
 use uuid::Uuid;
@@ -105,6 +109,16 @@ pub enum SerializedWorkspaceLocation {
     Remote(SerializedRemoteProject),
 }
 
+/// What's known about a recently opened workspace, apart from where it is.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RecentWorkspaceDetails {
+    /// When the workspace was last opened or changed.
+    pub last_opened: SystemTime,
+    /// Whether the workspace had unsaved changes that were discarded when it was
+    /// last closed.
+    pub closed_with_unsaved_changes: bool,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct SerializedWorkspace {
     pub(crate) id: WorkspaceId,
@@ -113,6 +127,9 @@ pub(crate) struct SerializedWorkspace {
     pub(crate) bounds: Option<Bounds<DevicePixels>>,
     pub(crate) fullscreen: bool,
     pub(crate) centered_layout: bool,
+    /// Whether the window is kept above other windows, if it was ever toggled.
+    pub(crate) always_on_top: Option<bool>,
+    pub(crate) window_zoom: f32,
     pub(crate) display: Option<Uuid>,
     pub(crate) docks: DockStructure,
 }
@@ -195,6 +212,7 @@ impl Default for SerializedPaneGroup {
         Self::Pane(SerializedPane {
             children: vec![SerializedItem::default()],
             active: false,
+            navigation_history: SerializedNavigationHistory::default(),
         })
     }
 }
@@ -271,11 +289,38 @@ impl SerializedPaneGroup {
 pub struct SerializedPane {
     pub(crate) active: bool,
     pub(crate) children: Vec<SerializedItem>,
+    pub(crate) navigation_history: SerializedNavigationHistory,
+}
+
+/// The back and forward history of a pane, stored as JSON in the `panes` table.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct SerializedNavigationHistory {
+    pub(crate) backward: Vec<SerializedNavigationEntry>,
+    pub(crate) forward: Vec<SerializedNavigationEntry>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub(crate) struct SerializedNavigationEntry {
+    /// The index of the entry's item among the pane's children.
+    pub(crate) item_ix: usize,
+    pub(crate) position: NavigationPosition,
 }
 
 impl SerializedPane {
     pub fn new(children: Vec<SerializedItem>, active: bool) -> Self {
-        SerializedPane { children, active }
+        SerializedPane {
+            children,
+            active,
+            navigation_history: SerializedNavigationHistory::default(),
+        }
+    }
+
+    pub(crate) fn with_navigation_history(
+        mut self,
+        navigation_history: SerializedNavigationHistory,
+    ) -> Self {
+        self.navigation_history = navigation_history;
+        self
     }
 
     pub async fn deserialize_to(
@@ -335,6 +380,37 @@ impl SerializedPane {
             })?;
         }
 
+        // Restore the history after the items have been added, replacing the
+        // entries that adding and activating them pushed.
+        let history = &self.navigation_history;
+        if history.backward.is_empty() && history.forward.is_empty() {
+            return anyhow::Ok(items);
+        }
+        pane.update(cx, |pane, cx| {
+            let nav_history = pane.nav_history_mut();
+            if matches!(nav_history.mode(), NavigationMode::Disabled) {
+                return;
+            }
+            nav_history.clear(cx);
+            for (mode, entries) in [
+                (NavigationMode::Normal, &history.backward),
+                (NavigationMode::GoingBack, &history.forward),
+            ] {
+                nav_history.set_mode(mode);
+                for entry in entries {
+                    if let Some(Some(item)) = items.get(entry.item_ix) {
+                        nav_history.push(
+                            Some(entry.position),
+                            Arc::from(item.downgrade_item()),
+                            false,
+                            cx,
+                        );
+                    }
+                }
+            }
+            nav_history.set_mode(NavigationMode::Normal);
+        })?;
+
         anyhow::Ok(items)
     }
 }
@@ -1,10 +1,13 @@
-use crate::{ItemHandle, Pane};
+use crate::{workspace_settings::StatusBarSettings, ItemHandle, Pane};
 use gpui::{
-    AnyView, IntoElement, ParentElement, Render, Styled, Subscription, View, ViewContext,
-    WindowContext,
+    Action, AnchorCorner, AnyElement, AnyView, IntoElement, ParentElement, Render, SharedString,
+    Styled, Subscription, View, ViewContext, WindowContext,
 };
-use std::any::TypeId;
-use ui::{h_flex, prelude::*};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{cmp::Reverse, rc::Rc};
+use ui::{h_flex, popover_menu, prelude::*, ContextMenu, PopoverTrigger, Tooltip};
 use util::ResultExt;
 
 pub trait StatusItemView: Render {
@@ -22,14 +25,48 @@ trait StatusItemViewHandle: Send {
         active_pane_item: Option<&dyn ItemHandle>,
         cx: &mut WindowContext,
     );
-    fn item_type(&self) -> TypeId;
+}
+
+/// A side of the status bar.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusItemSide {
+    Left,
+    Right,
+}
+
+/// Where an item is shown in the status bar, unless the `status_bar` settings
+/// place it somewhere else.
+#[derive(Clone, Debug)]
+pub struct StatusItemOptions {
+    /// The id that the item is referred to by in the `status_bar` settings.
+    pub id: SharedString,
+    pub side: StatusItemSide,
+    /// Items with a higher priority are shown closer to the edge of the status
+    /// bar, and items with the same priority in the order they were added in.
+    pub priority: i32,
+}
+
+impl StatusItemOptions {
+    pub fn new(id: impl Into<SharedString>, side: StatusItemSide, priority: i32) -> Self {
+        Self {
+            id: id.into(),
+            side,
+            priority,
+        }
+    }
+}
+
+struct StatusItem {
+    handle: Box<dyn StatusItemViewHandle>,
+    options: StatusItemOptions,
 }
 
 pub struct StatusBar {
-    left_items: Vec<Box<dyn StatusItemViewHandle>>,
-    right_items: Vec<Box<dyn StatusItemViewHandle>>,
+    items: Vec<StatusItem>,
     active_pane: View<Pane>,
     _observe_active_pane: Subscription,
+    _observe_settings: Subscription,
 }
 
 impl Render for StatusBar {
@@ -48,72 +85,66 @@ impl Render for StatusBar {
 }
 
 impl StatusBar {
-    fn render_left_tools(&self, _: &mut ViewContext<Self>) -> impl IntoElement {
-        h_flex()
-            .gap_2()
-            .overflow_x_hidden()
-            .children(self.left_items.iter().map(|item| item.to_any()))
+    fn render_left_tools(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex().gap_2().overflow_x_hidden().children(
+            self.items_on_side(StatusItemSide::Left, cx)
+                .into_iter()
+                .map(|item| item.handle.to_any()),
+        )
     }
 
-    fn render_right_tools(&self, _: &mut ViewContext<Self>) -> impl IntoElement {
-        h_flex()
-            .gap_2()
-            .children(self.right_items.iter().rev().map(|item| item.to_any()))
+    fn render_right_tools(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex().gap_2().children(
+            self.items_on_side(StatusItemSide::Right, cx)
+                .into_iter()
+                .rev()
+                .map(|item| item.handle.to_any()),
+        )
+    }
+
+    /// Returns the visible items on the side, from the edge of the status bar
+    /// inwards, as placed by their options and the `status_bar` settings.
+    fn items_on_side(&self, side: StatusItemSide, cx: &WindowContext) -> Vec<&StatusItem> {
+        let settings = StatusBarSettings::get_global(cx);
+        let mut items = self
+            .items
+            .iter()
+            .filter_map(|item| {
+                let overrides = settings
+                    .items
+                    .get(item.options.id.as_ref())
+                    .copied()
+                    .unwrap_or_default();
+                let visible = overrides.visible.unwrap_or(true);
+                let item_side = overrides.side.unwrap_or(item.options.side);
+                let priority = overrides.priority.unwrap_or(item.options.priority);
+                (visible && item_side == side).then_some((priority, item))
+            })
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(priority, _)| Reverse(*priority));
+        items.into_iter().map(|(_, item)| item).collect()
     }
 }
 
 impl StatusBar {
     pub fn new(active_pane: &View<Pane>, cx: &mut ViewContext<Self>) -> Self {
         let mut this = Self {
-            left_items: Default::default(),
-            right_items: Default::default(),
+            items: Default::default(),
             active_pane: active_pane.clone(),
             _observe_active_pane: cx
                 .observe(active_pane, |this, _, cx| this.update_active_pane_item(cx)),
+            _observe_settings: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
         };
         this.update_active_pane_item(cx);
         this
     }
 
-    pub fn add_left_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
-    where
-        T: 'static + StatusItemView,
-    {
-        let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
-
-        self.left_items.push(Box::new(item));
-        cx.notify();
-    }
-
-    pub fn item_of_type<T: StatusItemView>(&self) -> Option<View<T>> {
-        self.left_items
-            .iter()
-            .chain(self.right_items.iter())
-            .find_map(|item| item.to_any().clone().downcast().log_err())
-    }
-
-    pub fn position_of_item<T>(&self) -> Option<usize>
-    where
-        T: StatusItemView,
-    {
-        for (index, item) in self.left_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
-                return Some(index);
-            }
-        }
-        for (index, item) in self.right_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
-                return Some(index + self.left_items.len());
-            }
-        }
-        return None;
-    }
-
-    pub fn insert_item_after<T>(
+    /// Adds an item to the status bar, where the options say unless the
+    /// `status_bar` settings say otherwise.
+    pub fn add_item<T>(
         &mut self,
-        position: usize,
         item: View<T>,
+        options: StatusItemOptions,
         cx: &mut ViewContext<Self>,
     ) where
         T: 'static + StatusItemView,
@@ -121,33 +152,25 @@ impl StatusBar {
         let active_pane_item = self.active_pane.read(cx).active_item();
         item.set_active_pane_item(active_pane_item.as_deref(), cx);
 
-        if position < self.left_items.len() {
-            self.left_items.insert(position + 1, Box::new(item))
-        } else {
-            self.right_items
-                .insert(position + 1 - self.left_items.len(), Box::new(item))
-        }
-        cx.notify()
-    }
-
-    pub fn remove_item_at(&mut self, position: usize, cx: &mut ViewContext<Self>) {
-        if position < self.left_items.len() {
-            self.left_items.remove(position);
-        } else {
-            self.right_items.remove(position - self.left_items.len());
-        }
+        self.items.push(StatusItem {
+            handle: Box::new(item),
+            options,
+        });
         cx.notify();
     }
 
-    pub fn add_right_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
-    where
-        T: 'static + StatusItemView,
-    {
-        let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
+    pub fn item_of_type<T: StatusItemView>(&self) -> Option<View<T>> {
+        self.items
+            .iter()
+            .find_map(|item| item.handle.to_any().clone().downcast().log_err())
+    }
 
-        self.right_items.push(Box::new(item));
+    /// Removes the item with the given id, returning whether there was one.
+    pub fn remove_item(&mut self, id: &str, cx: &mut ViewContext<Self>) -> bool {
+        let len = self.items.len();
+        self.items.retain(|item| item.options.id.as_ref() != id);
         cx.notify();
+        self.items.len() < len
     }
 
     pub fn set_active_pane(&mut self, active_pane: &View<Pane>, cx: &mut ViewContext<Self>) {
@@ -159,8 +182,9 @@ impl StatusBar {
 
     fn update_active_pane_item(&mut self, cx: &mut ViewContext<Self>) {
         let active_pane_item = self.active_pane.read(cx).active_item();
-        for item in self.left_items.iter().chain(&self.right_items) {
-            item.set_active_pane_item(active_pane_item.as_deref(), cx);
+        for item in &self.items {
+            item.handle
+                .set_active_pane_item(active_pane_item.as_deref(), cx);
         }
     }
 }
@@ -179,10 +203,6 @@ impl<T: StatusItemView> StatusItemViewHandle for View<T> {
             this.set_active_pane_item(active_pane_item, cx)
         });
     }
-
-    fn item_type(&self) -> TypeId {
-        TypeId::of::<T>()
-    }
 }
 
 impl From<&dyn StatusItemViewHandle> for AnyView {
@@ -190,3 +210,189 @@ impl From<&dyn StatusItemViewHandle> for AnyView {
         val.to_any().clone()
     }
 }
+
+/// A status bar item that's a button showing an icon, a label or both, for
+/// contributing an item without implementing a view for it. Clicking the button
+/// either dispatches an action or opens a menu.
+pub struct StatusBarButton {
+    id: SharedString,
+    icon: Option<IconName>,
+    label: Option<SharedString>,
+    tooltip: Option<SharedString>,
+    action: Option<Box<dyn Action>>,
+    menu: Option<Rc<dyn Fn(&mut WindowContext) -> View<ContextMenu>>>,
+}
+
+impl StatusBarButton {
+    pub fn new(id: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            icon: None,
+            label: None,
+            tooltip: None,
+            action: None,
+            menu: None,
+        }
+    }
+
+    pub fn icon(mut self, icon: IconName) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn tooltip(mut self, tooltip: impl Into<SharedString>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Dispatches the action when the button is clicked.
+    pub fn action(mut self, action: Box<dyn Action>) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Opens the menu built by the function when the button is clicked, instead of
+    /// dispatching an action.
+    pub fn menu(
+        mut self,
+        menu: impl Fn(&mut WindowContext) -> View<ContextMenu> + 'static,
+    ) -> Self {
+        self.menu = Some(Rc::new(menu));
+        self
+    }
+
+    pub fn set_label(&mut self, label: impl Into<SharedString>, cx: &mut ViewContext<Self>) {
+        self.label = Some(label.into());
+        cx.notify();
+    }
+
+    fn render_button<B>(&self, button: B) -> AnyElement
+    where
+        B: PopoverTrigger + ButtonCommon,
+    {
+        let button = match self.tooltip.clone() {
+            Some(tooltip) => button.tooltip(move |cx| Tooltip::text(tooltip.clone(), cx)),
+            None => button,
+        };
+
+        if let Some(menu) = self.menu.clone() {
+            popover_menu(self.id.clone())
+                .menu(move |cx| Some(menu(cx)))
+                .anchor(AnchorCorner::BottomLeft)
+                .trigger(button)
+                .into_any_element()
+        } else if let Some(action) = self.action.as_ref() {
+            let action = action.boxed_clone();
+            button
+                .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+                .into_any_element()
+        } else {
+            button.into_any_element()
+        }
+    }
+}
+
+impl Render for StatusBarButton {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        match (self.label.clone(), self.icon) {
+            (Some(label), icon) => self.render_button(
+                Button::new(self.id.clone(), label)
+                    .label_size(LabelSize::Small)
+                    .when_some(icon, |button, icon| {
+                        button
+                            .icon(icon)
+                            .icon_size(IconSize::Small)
+                            .icon_position(IconPosition::Start)
+                    }),
+            ),
+            (None, Some(icon)) => self
+                .render_button(IconButton::new(self.id.clone(), icon).icon_size(IconSize::Small)),
+            (None, None) => div().into_any_element(),
+        }
+    }
+}
+
+impl StatusItemView for StatusBarButton {
+    fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, StatusItemSettings, Workspace};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext, VisualTestContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_status_item_placement(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let status_bar = workspace.update(cx, |workspace, _| workspace.status_bar().clone());
+        status_bar.update(cx, |status_bar, cx| {
+            for (id, priority) in [("low", 1), ("high", 2), ("also_high", 2)] {
+                let button = cx.new_view(|_| StatusBarButton::new(id).label(id));
+                let options = StatusItemOptions::new(id, StatusItemSide::Left, priority);
+                status_bar.add_item(button, options, cx);
+            }
+        });
+        let item_ids = |side, cx: &mut VisualTestContext| {
+            status_bar.update(cx, |status_bar, cx| {
+                status_bar
+                    .items_on_side(side, cx)
+                    .into_iter()
+                    .map(|item| item.options.id.to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // Items with the same priority keep the order they were added in.
+        assert_eq!(
+            item_ids(StatusItemSide::Left, cx),
+            ["left_dock_buttons", "high", "also_high", "low"]
+        );
+
+        cx.update(|cx| {
+            cx.update_global(|settings: &mut SettingsStore, cx| {
+                settings.update_user_settings::<StatusBarSettings>(cx, |settings| {
+                    settings.items = Some(
+                        [
+                            (
+                                "high".to_string(),
+                                StatusItemSettings {
+                                    visible: Some(false),
+                                    ..Default::default()
+                                },
+                            ),
+                            (
+                                "low".to_string(),
+                                StatusItemSettings {
+                                    side: Some(StatusItemSide::Right),
+                                    priority: Some(1000),
+                                    ..Default::default()
+                                },
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    );
+                })
+            });
+        });
+        assert_eq!(
+            item_ids(StatusItemSide::Left, cx),
+            ["left_dock_buttons", "also_high"]
+        );
+        assert_eq!(
+            item_ids(StatusItemSide::Right, cx),
+            ["low", "right_dock_buttons", "bottom_dock_buttons"]
+        );
+    }
+}
@@ -1,7 +1,10 @@
 pub mod dock;
+mod hints;
 pub mod item;
+mod layout_profile;
 mod modal_layer;
 pub mod notifications;
+mod offline_edits;
 pub mod pane;
 pub mod pane_group;
 mod persistence;
@@ -17,7 +20,7 @@ use client::{
     proto::{self, ErrorCode, PeerId},
     ChannelId, Client, ErrorExt, ProjectId, Status, TypedEnvelope, UserStore,
 };
-use collections::{hash_map, HashMap, HashSet};
+use collections::{hash_map, HashMap, HashSet, VecDeque};
 use derive_more::{Deref, DerefMut};
 use dock::{Dock, DockPosition, Panel, PanelButtons, PanelHandle};
 use futures::{
@@ -32,34 +35,40 @@ use gpui::{
     LayoutId, ManagedView, Model, ModelContext, PathPromptOptions, Point, PromptLevel, Render,
     Size, Subscription, Task, View, WeakView, WindowHandle, WindowOptions,
 };
+pub use hints::{render_key_hint, HintTarget};
 use item::{
     FollowableItem, FollowableItemHandle, Item, ItemHandle, ItemSettings, PreviewTabsSettings,
     ProjectItem,
 };
 use itertools::Itertools;
 use language::{LanguageRegistry, Rope};
+pub use layout_profile::{DockLayout, LayoutProfile, LayoutProfileScope, PaneLayout};
 use lazy_static::lazy_static;
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
 use notifications::{simple_message_notification::MessageNotification, NotificationHandle};
+use offline_edits::{OfflineEditingNotification, OfflineMergeNotification};
 pub use pane::*;
 pub use pane_group::*;
 use persistence::{model::SerializedWorkspace, SerializedWindowsBounds, DB};
 pub use persistence::{
-    model::{ItemId, LocalPaths, SerializedRemoteProject, SerializedWorkspaceLocation},
+    model::{
+        ItemId, LocalPaths, RecentWorkspaceDetails, SerializedRemoteProject,
+        SerializedWorkspaceLocation,
+    },
     WorkspaceDb, DB as WORKSPACE_DB,
 };
 use postage::stream::Stream;
 use project::{Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
 use serde::Deserialize;
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use shared_screen::SharedScreen;
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
 };
 use status_bar::StatusBar;
-pub use status_bar::StatusItemView;
+pub use status_bar::{StatusBarButton, StatusItemOptions, StatusItemSide, StatusItemView};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -78,19 +87,23 @@ use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
 use ui::{
-    div, h_flex, Context as _, Div, Element, ElementContext, FluentBuilder,
+    div, h_flex, px, Context as _, Div, Element, ElementContext, FluentBuilder,
     InteractiveElement as _, IntoElement, Label, ParentElement as _, Pixels, SharedString,
     Styled as _, ViewContext, VisualContext as _, WindowContext,
 };
 use util::{maybe, ResultExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehaviour, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, RestoreOnStartupBehaviour, StatusBarSettings, StatusItemSettings,
+    TabBarSettings, WorkspaceSettings, ZenModeSettings,
 };
 
 use crate::notifications::NotificationId;
 use crate::persistence::{
-    model::{DockData, DockStructure, SerializedItem, SerializedPane, SerializedPaneGroup},
+    model::{
+        DockData, DockStructure, SerializedItem, SerializedNavigationEntry,
+        SerializedNavigationHistory, SerializedPane, SerializedPaneGroup,
+    },
     SerializedAxis,
 };
 
@@ -105,6 +118,10 @@ lazy_static! {
         .and_then(parse_pixel_position_env_var);
 }
 
+const MIN_WINDOW_ZOOM: f32 = 0.5;
+const MAX_WINDOW_ZOOM: f32 = 3.0;
+const WINDOW_ZOOM_STEP: f32 = 0.1;
+
 #[derive(Clone, PartialEq)]
 pub struct RemoveWorktreeFromProject(pub WorktreeId);
 
@@ -124,6 +141,8 @@ actions!(
         ActivatePreviousPane,
         ActivateNextPane,
         FollowNextCollaborator,
+        ToggleFollowScrollOnly,
+        InviteToFollow,
         NewTerminal,
         NewCenterTerminal,
         NewSearch,
@@ -135,7 +154,19 @@ actions!(
         ToggleRightDock,
         ToggleBottomDock,
         ToggleCenteredLayout,
+        ToggleZenMode,
+        ToggleAlwaysOnTop,
+        IncreaseWindowZoom,
+        DecreaseWindowZoom,
+        ResetWindowZoom,
+        MoveItemToNewWindow,
+        MovePaneToNewWindow,
         CloseAllDocks,
+        NewProjectFromTemplate,
+        NewScratchBuffer,
+        ShowKeyHints,
+        FocusNextRegion,
+        FocusPreviousRegion,
     ]
 );
 
@@ -183,6 +214,20 @@ pub struct CloseInactiveTabsAndPanes {
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SendKeystrokes(pub String);
 
+/// Saves the current layout of the docks and panes as a profile with the name,
+/// for the project or for every project.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SaveLayoutProfile {
+    pub name: String,
+    #[serde(default)]
+    pub global: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SwitchLayoutProfile {
+    pub name: String,
+}
+
 impl_actions!(
     workspace,
     [
@@ -196,6 +241,8 @@ impl_actions!(
         SaveAll,
         SwapPaneInDirection,
         SendKeystrokes,
+        SaveLayoutProfile,
+        SwitchLayoutProfile,
     ]
 );
 
@@ -268,12 +315,19 @@ pub fn init_settings(cx: &mut AppContext) {
     ItemSettings::register(cx);
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
+    StatusBarSettings::register(cx);
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
     init_settings(cx);
     notifications::init(cx);
 
+    cx.set_reduce_motion(WorkspaceSettings::get_global(cx).reduce_motion);
+    cx.observe_global::<SettingsStore>(|cx| {
+        cx.set_reduce_motion(WorkspaceSettings::get_global(cx).reduce_motion);
+    })
+    .detach();
+
     cx.on_action(Workspace::close_global);
     cx.on_action(restart);
 
@@ -462,6 +516,7 @@ impl AppState {
         let workspace_store = cx.new_model(|cx| WorkspaceStore::new(client.clone(), cx));
 
         theme::init(theme::LoadThemes::JustBase, cx);
+        client::init_settings(cx);
         client::init(&client, cx);
         crate::init_settings(cx);
 
@@ -535,6 +590,7 @@ pub enum Event {
         language: &'static str,
     },
     ZoomChanged,
+    ZenModeChanged,
 }
 
 pub enum OpenVisible {
@@ -584,9 +640,20 @@ pub struct Workspace {
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    always_on_top: bool,
+    window_zoom: f32,
+    zen_mode: Option<ZenModeState>,
+    key_hints: Option<hints::KeyHints>,
     bounds_save_task_queued: Option<Task<()>>,
 }
 
+/// The layout that is restored when leaving zen mode.
+struct ZenModeState {
+    left_dock_open: bool,
+    bottom_dock_open: bool,
+    right_dock_open: bool,
+}
+
 impl EventEmitter<Event> for Workspace {}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -595,11 +662,50 @@ pub struct ViewId {
     pub id: u64,
 }
 
+/// How a pane that follows a collaborator keeps up with them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FollowMode {
+    /// Switch to whichever item the leader is viewing, and track their position in it.
+    #[default]
+    Full,
+    /// Stay on the current item, and only track the leader's position in it.
+    ScrollOnly,
+}
+
 #[derive(Default)]
 struct FollowerState {
     leader_id: PeerId,
     active_view_id: Option<ViewId>,
     items_by_leader_view_id: HashMap<ViewId, Box<dyn FollowableItemHandle>>,
+    mode: FollowMode,
+    /// The most recent items the leader has viewed, oldest first.
+    trail: VecDeque<(ViewId, SharedString)>,
+}
+
+impl FollowerState {
+    const MAX_TRAIL_LEN: usize = 5;
+
+    fn record_trail(&mut self, cx: &AppContext) {
+        let Some(view_id) = self.active_view_id else {
+            return;
+        };
+        if self.trail.back().map(|(id, _)| *id) == Some(view_id) {
+            return;
+        }
+        let Some(title) = self
+            .items_by_leader_view_id
+            .get(&view_id)
+            .and_then(|item| item.tab_description(0, cx))
+        else {
+            return;
+        };
+
+        self.trail.retain(|(id, _)| *id != view_id);
+        self.trail.push_back((view_id, title));
+        if self.trail.len() > Self::MAX_TRAIL_LEN {
+            self.trail.pop_front();
+        }
+    }
 }
 
 impl Workspace {
@@ -635,7 +741,16 @@ impl Workspace {
                     for pane in panes_to_unfollow {
                         this.unfollow(&pane, cx);
                     }
-                    cx.disable_focus();
+                    if this.project.read(cx).is_editing_offline() {
+                        let workspace = cx.view().downgrade();
+                        this.show_notification(
+                            NotificationId::unique::<OfflineEditingNotification>(),
+                            cx,
+                            |cx| cx.new_view(|_| OfflineEditingNotification::new(workspace)),
+                        );
+                    } else {
+                        cx.disable_focus();
+                    }
                 }
 
                 project::Event::Closed => {
@@ -746,14 +861,29 @@ impl Workspace {
         let left_dock = Dock::new(DockPosition::Left, cx);
         let bottom_dock = Dock::new(DockPosition::Bottom, cx);
         let right_dock = Dock::new(DockPosition::Right, cx);
-        let left_dock_buttons = cx.new_view(|cx| PanelButtons::new(left_dock.clone(), cx));
-        let bottom_dock_buttons = cx.new_view(|cx| PanelButtons::new(bottom_dock.clone(), cx));
-        let right_dock_buttons = cx.new_view(|cx| PanelButtons::new(right_dock.clone(), cx));
+        let left_dock_buttons =
+            cx.new_view(|cx| PanelButtons::new(left_dock.clone(), weak_handle.clone(), cx));
+        let bottom_dock_buttons =
+            cx.new_view(|cx| PanelButtons::new(bottom_dock.clone(), weak_handle.clone(), cx));
+        let right_dock_buttons =
+            cx.new_view(|cx| PanelButtons::new(right_dock.clone(), weak_handle.clone(), cx));
         let status_bar = cx.new_view(|cx| {
             let mut status_bar = StatusBar::new(&center_pane.clone(), cx);
-            status_bar.add_left_item(left_dock_buttons, cx);
-            status_bar.add_right_item(right_dock_buttons, cx);
-            status_bar.add_right_item(bottom_dock_buttons, cx);
+            status_bar.add_item(
+                left_dock_buttons,
+                StatusItemOptions::new("left_dock_buttons", StatusItemSide::Left, 300),
+                cx,
+            );
+            status_bar.add_item(
+                right_dock_buttons,
+                StatusItemOptions::new("right_dock_buttons", StatusItemSide::Right, 700),
+                cx,
+            );
+            status_bar.add_item(
+                bottom_dock_buttons,
+                StatusItemOptions::new("bottom_dock_buttons", StatusItemSide::Right, 600),
+                cx,
+            );
             status_bar
         });
 
@@ -874,6 +1004,10 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            always_on_top: false,
+            window_zoom: 1.0,
+            zen_mode: None,
+            key_hints: None,
             bounds_save_task_queued: None,
         }
     }
@@ -967,6 +1101,11 @@ impl Workspace {
                     .as_ref()
                     .map(|w| w.centered_layout)
                     .unwrap_or(false);
+                let always_on_top = serialized_workspace.as_ref().and_then(|w| w.always_on_top);
+                let window_zoom = serialized_workspace
+                    .as_ref()
+                    .map(|w| w.window_zoom)
+                    .unwrap_or(1.0);
                 cx.open_window(options, {
                     let app_state = app_state.clone();
                     let project_handle = project_handle.clone();
@@ -975,6 +1114,13 @@ impl Workspace {
                             let mut workspace =
                                 Workspace::new(workspace_id, project_handle, app_state, cx);
                             workspace.centered_layout = centered_layout;
+                            workspace.window_zoom = window_zoom;
+                            workspace.set_always_on_top(
+                                always_on_top.unwrap_or_else(|| {
+                                    WorkspaceSettings::get_global(cx).always_on_top
+                                }),
+                                cx,
+                            );
                             workspace
                         })
                     }
@@ -1206,6 +1352,26 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    /// Goes back or forward by the given number of entries in the pane's history.
+    pub fn navigate_history_by(
+        &mut self,
+        pane: WeakView<Pane>,
+        mode: NavigationMode,
+        steps: usize,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|workspace, mut cx| async move {
+            for _ in 0..steps {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.navigate_history(pane.clone(), mode, cx)
+                    })?
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
         self.navigate_history(
             self.active_pane().downgrade(),
@@ -1349,10 +1515,26 @@ impl Workspace {
                 }
             }
 
-            this.update(&mut cx, |this, cx| {
-                this.save_all_internal(SaveIntent::Close, cx)
-            })?
-            .await
+            let close = this
+                .update(&mut cx, |this, cx| {
+                    this.save_all_internal(SaveIntent::Close, cx)
+                })?
+                .await?;
+            if close {
+                // Changes that are still unsaved after being asked about are discarded.
+                let (database_id, closed_with_unsaved_changes) =
+                    this.update(&mut cx, |this, cx| {
+                        (
+                            this.database_id(),
+                            this.items(cx).any(|item| item.is_dirty(cx)),
+                        )
+                    })?;
+                persistence::DB
+                    .set_closed_with_unsaved_changes(database_id, closed_with_unsaved_changes)
+                    .await
+                    .log_err();
+            }
+            Ok(close)
         })
     }
 
@@ -1406,7 +1588,17 @@ impl Workspace {
         mut save_intent: SaveIntent,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
-        if self.project.read(cx).is_disconnected() {
+        let project = self.project.read(cx);
+        if project.is_disconnected() {
+            if save_intent == SaveIntent::Close && project.has_offline_edits(cx) {
+                let answer = cx.prompt(
+                    PromptLevel::Warning,
+                    "Discard the edits you made while offline?",
+                    Some("They haven't been merged into the remote project yet."),
+                    &["Discard", "Cancel"],
+                );
+                return cx.spawn(|_, _| async move { Ok(answer.await? == 0) });
+            }
             return Task::ready(Ok(true));
         }
         let dirty_items = self
@@ -1856,26 +2048,39 @@ impl Workspace {
 
     /// Transfer focus to the panel of the given type.
     pub fn focus_panel<T: Panel>(&mut self, cx: &mut ViewContext<Self>) -> Option<View<T>> {
-        let panel = self.focus_or_unfocus_panel::<T>(cx, |_, _| true)?;
+        let panel =
+            self.focus_or_unfocus_panel(cx, Dock::panel_index_for_type::<T>, |_, _| true)?;
         panel.to_any().downcast().ok()
     }
 
     /// Focus the panel of the given type if it isn't already focused. If it is
     /// already focused, then transfer focus back to the workspace center.
     pub fn toggle_panel_focus<T: Panel>(&mut self, cx: &mut ViewContext<Self>) {
-        self.focus_or_unfocus_panel::<T>(cx, |panel, cx| {
+        self.focus_or_unfocus_panel(cx, Dock::panel_index_for_type::<T>, |panel, cx| {
             !panel.focus_handle(cx).contains_focused(cx)
         });
     }
 
-    /// Focus or unfocus the given panel type, depending on the given callback.
-    fn focus_or_unfocus_panel<T: Panel>(
+    /// Like [`Self::toggle_panel_focus`], but for a specific panel instead of
+    /// the first panel of a given type.
+    pub fn toggle_panel_focus_for_id(&mut self, panel_id: EntityId, cx: &mut ViewContext<Self>) {
+        self.focus_or_unfocus_panel(
+            cx,
+            |dock| dock.panel_index_for_id(panel_id),
+            |panel, cx| !panel.focus_handle(cx).contains_focused(cx),
+        );
+    }
+
+    /// Focus or unfocus the panel found by the given callback, depending on
+    /// the other given callback.
+    fn focus_or_unfocus_panel(
         &mut self,
         cx: &mut ViewContext<Self>,
+        panel_index: impl Fn(&Dock) -> Option<usize>,
         should_focus: impl Fn(&dyn PanelHandle, &mut ViewContext<Dock>) -> bool,
     ) -> Option<Arc<dyn PanelHandle>> {
         for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
-            if let Some(panel_index) = dock.read(cx).panel_index_for_type::<T>() {
+            if let Some(panel_index) = panel_index(dock.read(cx)) {
                 let mut focus_center = false;
                 let panel = dock.update(cx, |dock, cx| {
                     dock.activate_panel(panel_index, cx);
@@ -2447,6 +2652,11 @@ impl Workspace {
                 }
                 self.update_window_edited(cx);
             }
+            pane::Event::ChangeHistory => {
+                // The history changes with every jump, so it's saved lazily.
+                self.schedule_serialize(cx);
+                return;
+            }
             pane::Event::RemoveItem { item_id } => {
                 cx.emit(Event::ActiveItemChanged);
                 self.update_window_edited(cx);
@@ -2594,6 +2804,97 @@ impl Workspace {
         });
     }
 
+    pub fn move_item_to_new_window(&mut self, _: &MoveItemToNewWindow, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        if let Some(item) = pane.read(cx).active_item() {
+            self.move_items_to_new_window(pane, vec![item.item_id()], cx);
+        }
+    }
+
+    pub fn move_pane_to_new_window(&mut self, _: &MovePaneToNewWindow, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        let item_ids = pane.read(cx).items().map(|item| item.item_id()).collect();
+        self.move_items_to_new_window(pane, item_ids, cx);
+    }
+
+    /// Opens the given items of `pane` in a new window that shares this workspace's
+    /// project, and closes them here once they have been opened there.
+    ///
+    /// Only items with a project path can be moved, as they are reopened from the
+    /// project in the new window.
+    pub fn move_items_to_new_window(
+        &mut self,
+        pane: View<Pane>,
+        item_ids: Vec<EntityId>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let active_item_id = pane.read(cx).active_item().map(|item| item.item_id());
+        let mut moved_item_ids = Vec::new();
+        let mut project_paths = Vec::new();
+        for item in pane.read(cx).items() {
+            if !item_ids.contains(&item.item_id()) {
+                continue;
+            }
+            if let Some(project_path) = item.project_path(cx) {
+                moved_item_ids.push(item.item_id());
+                project_paths.push((project_path, Some(item.item_id()) == active_item_id));
+            }
+        }
+
+        if project_paths.is_empty() {
+            struct MoveToNewWindowNotification;
+
+            self.show_notification(
+                NotificationId::unique::<MoveToNewWindowNotification>(),
+                cx,
+                |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(
+                            "Only items that belong to a project file can be moved to a new window",
+                        )
+                    })
+                },
+            );
+            return;
+        }
+
+        // Open the active item last, so that it is the one shown in the new window.
+        project_paths.sort_by_key(|(_, is_active)| *is_active);
+
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        cx.spawn(|_, mut cx| async move {
+            let workspace_id = DB.next_id().await.unwrap_or_else(|_| Default::default());
+            let window = cx.update(|cx| {
+                let options = (app_state.build_window_options)(None, cx);
+                cx.open_window(options, |cx| {
+                    cx.new_view(|cx| Workspace::new(workspace_id, project, app_state.clone(), cx))
+                })
+            })?;
+
+            for (project_path, _) in project_paths {
+                window
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.open_path(project_path, None, true, cx)
+                    })?
+                    .await?;
+            }
+
+            // The items were reopened on the same project buffers, so closing them
+            // here doesn't lose any unsaved changes.
+            pane.update(&mut cx, |pane, cx| {
+                pane.close_items(cx, SaveIntent::Skip, move |item_id| {
+                    moved_item_ids.contains(&item_id)
+                })
+            })?
+            .await?;
+
+            window.update(&mut cx, |_, cx| cx.activate_window())?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn remove_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
         if self.center.remove(&pane).unwrap() {
             self.force_remove_pane(&pane, cx);
@@ -2659,6 +2960,8 @@ impl Workspace {
                 leader_id,
                 active_view_id: None,
                 items_by_leader_view_id: Default::default(),
+                mode: FollowMode::Full,
+                trail: Default::default(),
             },
         );
         cx.notify();
@@ -2741,6 +3044,118 @@ impl Workspace {
         }
     }
 
+    /// Switches the active pane between following the leader to every item they
+    /// view, and only following their position in the current item.
+    pub fn toggle_follow_scroll_only(
+        &mut self,
+        _: &ToggleFollowScrollOnly,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(state) = self.follower_states.get_mut(&self.active_pane) else {
+            return;
+        };
+        state.mode = match state.mode {
+            FollowMode::Full => FollowMode::ScrollOnly,
+            FollowMode::ScrollOnly => FollowMode::Full,
+        };
+        if state.mode == FollowMode::Full {
+            let leader_id = state.leader_id;
+            self.leader_updated(leader_id, cx);
+        }
+        cx.notify();
+    }
+
+    pub fn follow_mode(&self, pane: &View<Pane>) -> Option<FollowMode> {
+        self.follower_states.get(pane).map(|state| state.mode)
+    }
+
+    /// Returns the titles of the items the leader of `pane` has recently viewed,
+    /// oldest first.
+    pub fn follow_trail(&self, pane: &View<Pane>) -> Vec<SharedString> {
+        self.follower_states
+            .get(pane)
+            .map(|state| state.trail.iter().map(|(_, title)| title.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Shows an item from the follow trail of `pane`. The pane keeps following
+    /// the leader, but stops switching items along with them.
+    pub(crate) fn activate_follow_trail_entry(
+        &mut self,
+        pane: &View<Pane>,
+        view_id: ViewId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(state) = self.follower_states.get_mut(pane) else {
+            return;
+        };
+        let Some(item) = state
+            .items_by_leader_view_id
+            .get(&view_id)
+            .map(|item| item.boxed_clone())
+        else {
+            return;
+        };
+        state.mode = FollowMode::ScrollOnly;
+        pane.update(cx, |pane, cx| {
+            if let Some(index) = pane.index_for_item(item.as_ref()) {
+                pane.activate_item(index, false, false, cx);
+            } else {
+                pane.add_item(item, false, false, None, cx);
+            }
+        });
+        cx.notify();
+    }
+
+    /// Asks everyone else in the call to follow you.
+    pub fn invite_to_follow(&mut self, _: &InviteToFollow, cx: &mut ViewContext<Self>) {
+        let Some(room) = ActiveCall::global(cx).read(cx).room() else {
+            return;
+        };
+        let room = room.read(cx);
+        let project_id = self.project.read(cx).remote_id();
+        for participant in room.remote_participants().values() {
+            self.app_state
+                .client
+                .send(proto::InviteToFollow {
+                    room_id: room.id(),
+                    project_id,
+                    follower_id: Some(participant.peer_id),
+                })
+                .log_err();
+        }
+    }
+
+    fn show_follow_invitation(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) {
+        let Some(room) = ActiveCall::global(cx).read(cx).room() else {
+            return;
+        };
+        let Some(leader) = room.read(cx).remote_participant_for_peer_id(leader_id) else {
+            return;
+        };
+        let user_id = leader.user.id;
+        let message = format!("{} invited you to follow them", leader.user.github_login);
+
+        struct FollowInvitation;
+
+        let workspace = cx.view().downgrade();
+        self.show_notification(
+            NotificationId::identified::<FollowInvitation>(user_id as usize),
+            cx,
+            |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(message)
+                        .with_click_message("Follow")
+                        .on_click(move |cx| {
+                            workspace
+                                .update(cx, |workspace, cx| workspace.follow(leader_id, cx))
+                                .ok();
+                        })
+                })
+            },
+        );
+    }
+
     pub fn follow(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) {
         let Some(room) = ActiveCall::global(cx).read(cx).room() else {
             return;
@@ -2821,20 +3236,34 @@ impl Workspace {
 
     fn active_item_path_changed(&mut self, cx: &mut ViewContext<Self>) {
         cx.emit(Event::ActiveItemChanged);
-        let active_entry = self.active_project_path(cx);
-        self.project
-            .update(cx, |project, cx| project.set_active_path(active_entry, cx));
+        if !self.project_active_in_other_window(cx) {
+            let active_entry = self.active_project_path(cx);
+            self.project
+                .update(cx, |project, cx| project.set_active_path(active_entry, cx));
+        }
 
         self.update_window_title(cx);
     }
 
+    /// Returns whether this workspace's project is shared with another window,
+    /// and that window is the active one.
+    fn project_active_in_other_window(&self, cx: &WindowContext) -> bool {
+        let Some(active_window) = cx.active_window() else {
+            return false;
+        };
+        active_window != cx.window_handle()
+            && active_window
+                .downcast::<Workspace>()
+                .and_then(|workspace| workspace.read(cx).ok())
+                .map_or(false, |workspace| workspace.project == self.project)
+    }
+
     fn update_window_title(&mut self, cx: &mut WindowContext) {
         let project = self.project().read(cx);
-        let mut title = String::new();
-
-        if let Some(path) = self.active_item(cx).and_then(|item| item.project_path(cx)) {
-            let filename = path
-                .path
+        let active_item = self.active_item(cx);
+        let active_path = active_item.as_ref().and_then(|item| item.project_path(cx));
+        let filename = active_path.as_ref().and_then(|path| {
+            path.path
                 .file_name()
                 .map(|s| s.to_string_lossy())
                 .or_else(|| {
@@ -2844,20 +3273,33 @@ impl Workspace {
                             .read(cx)
                             .root_name(),
                     ))
-                });
-
-            if let Some(filename) = filename {
-                title.push_str(filename.as_ref());
-                title.push_str(" — ");
-            }
-        }
+                })
+        });
+        let project_name = project
+            .worktree_root_names(cx)
+            .collect::<Vec<_>>()
+            .join(", ");
 
-        for (i, name) in project.worktree_root_names(cx).enumerate() {
-            if i > 0 {
-                title.push_str(", ");
+        let mut title = match &WorkspaceSettings::get_global(cx).window_title {
+            Some(template) => {
+                let is_dirty = active_item.map_or(false, |item| item.is_dirty(cx));
+                let path = active_path
+                    .as_ref()
+                    .map(|path| path.path.to_string_lossy())
+                    .unwrap_or_default();
+                template
+                    .replace("{file}", filename.as_deref().unwrap_or_default())
+                    .replace("{path}", &path)
+                    .replace("{project}", &project_name)
+                    .replace("{dirty}", if is_dirty { "● " } else { "" })
+                    .trim()
+                    .to_string()
             }
-            title.push_str(name);
-        }
+            None => match filename {
+                Some(filename) => format!("{filename} — {project_name}"),
+                None => project_name,
+            },
+        };
 
         if title.is_empty() {
             title = "empty project".to_string();
@@ -2873,7 +3315,8 @@ impl Workspace {
     }
 
     fn update_window_edited(&mut self, cx: &mut WindowContext) {
-        let is_edited = !self.project.read(cx).is_disconnected()
+        let project = self.project.read(cx);
+        let is_edited = (!project.is_disconnected() || project.is_editing_offline())
             && self
                 .items(cx)
                 .any(|item| item.has_conflict(cx) || item.is_dirty(cx));
@@ -2883,7 +3326,7 @@ impl Workspace {
         }
     }
 
-    fn render_notifications(&self, _cx: &ViewContext<Self>) -> Option<Div> {
+    fn render_notifications(&self, cx: &ViewContext<Self>) -> Option<Div> {
         if self.notifications.is_empty() {
             None
         } else {
@@ -2898,11 +3341,19 @@ impl Workspace {
                     .flex_col()
                     .justify_end()
                     .gap_2()
-                    .children(
-                        self.notifications
-                            .iter()
-                            .map(|(_, notification)| notification.to_any()),
-                    ),
+                    .children(self.notifications.iter().map(|(id, notification)| {
+                        let hint = self.key_hint(&HintTarget::Notification(id.clone()));
+                        div()
+                            .relative()
+                            .child(notification.to_any())
+                            .children(hint.map(|hint| {
+                                div()
+                                    .absolute()
+                                    .top_1()
+                                    .right_1()
+                                    .child(render_key_hint(hint, cx))
+                            }))
+                    })),
             )
         }
     }
@@ -3193,6 +3644,10 @@ impl Workspace {
     pub fn update_active_view_for_followers(&mut self, cx: &mut WindowContext) {
         let mut is_project_item = true;
         let mut update = proto::UpdateActiveView::default();
+        if !cx.is_window_active() && self.project_active_in_other_window(cx) {
+            // The active window showing this project reports the active view instead.
+            return;
+        }
         if cx.is_window_active() {
             if let Some(item) = self.active_item(cx) {
                 if item.focus_handle(cx).contains_focused(cx) {
@@ -3264,6 +3719,12 @@ impl Workspace {
     fn leader_updated(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         cx.notify();
 
+        for state in self.follower_states.values_mut() {
+            if state.leader_id == leader_id {
+                state.record_trail(cx);
+            }
+        }
+
         let call = self.active_call()?;
         let room = call.read(cx).room()?.read(cx);
         let participant = room.remote_participant_for_peer_id(leader_id)?;
@@ -3287,7 +3748,7 @@ impl Workspace {
         };
 
         for (pane, state) in &self.follower_states {
-            if state.leader_id != leader_id {
+            if state.leader_id != leader_id || state.mode == FollowMode::ScrollOnly {
                 continue;
             }
             if let (Some(active_view_id), true) = (state.active_view_id, leader_in_this_app) {
@@ -3345,7 +3806,11 @@ impl Workspace {
 
     pub fn on_window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
         if cx.is_window_active() {
+            self.report_activity(cx);
             self.update_active_view_for_followers(cx);
+            let active_entry = self.active_project_path(cx);
+            self.project
+                .update(cx, |project, cx| project.set_active_path(active_entry, cx));
             cx.background_executor()
                 .spawn(persistence::DB.update_timestamp(self.database_id()))
                 .detach();
@@ -3373,6 +3838,13 @@ impl Workspace {
         self.active_call.as_ref().map(|(call, _)| call)
     }
 
+    /// Lets the other participants of the call know that this user isn't idle.
+    fn report_activity(&self, cx: &mut ViewContext<Self>) {
+        if let Some(call) = self.active_call() {
+            call.update(cx, |call, cx| call.report_activity(cx));
+        }
+    }
+
     fn on_active_call_event(
         &mut self,
         _: Model<ActiveCall>,
@@ -3444,25 +3916,42 @@ impl Workspace {
 
     fn serialize_workspace(&self, cx: &mut WindowContext) -> Task<()> {
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
-            let (items, active) = {
-                let pane = pane_handle.read(cx);
-                let active_item_id = pane.active_item().map(|item| item.item_id());
-                (
-                    pane.items()
-                        .filter_map(|item_handle| {
-                            Some(SerializedItem {
-                                kind: Arc::from(item_handle.serialized_item_kind()?),
-                                item_id: item_handle.item_id().as_u64(),
-                                active: Some(item_handle.item_id()) == active_item_id,
-                                preview: pane.is_active_preview_item(item_handle.item_id()),
-                            })
-                        })
-                        .collect::<Vec<_>>(),
-                    pane.has_focus(cx),
-                )
+            let pane = pane_handle.read(cx);
+            let active_item_id = pane.active_item().map(|item| item.item_id());
+            let serialized_items = pane
+                .items()
+                .filter_map(|item_handle| {
+                    let item = SerializedItem {
+                        kind: Arc::from(item_handle.serialized_item_kind()?),
+                        item_id: item_handle.item_id().as_u64(),
+                        active: Some(item_handle.item_id()) == active_item_id,
+                        preview: pane.is_active_preview_item(item_handle.item_id()),
+                    };
+                    Some((item_handle.item_id(), item))
+                })
+                .collect::<Vec<_>>();
+
+            let serialize_entries = |mode: NavigationMode| {
+                pane.nav_history()
+                    .entry_positions(mode, cx)
+                    .into_iter()
+                    .filter_map(|(item_id, position)| {
+                        let item_ix = serialized_items.iter().position(|(id, _)| *id == item_id)?;
+                        Some(SerializedNavigationEntry { item_ix, position })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let navigation_history = SerializedNavigationHistory {
+                backward: serialize_entries(NavigationMode::GoingBack),
+                forward: serialize_entries(NavigationMode::GoingForward),
             };
 
-            SerializedPane::new(items, active)
+            let items = serialized_items
+                .into_iter()
+                .map(|(_, item)| item)
+                .collect::<Vec<_>>();
+            SerializedPane::new(items, pane.has_focus(cx))
+                .with_navigation_history(navigation_history)
         }
 
         fn build_serialized_pane_group(
@@ -3494,7 +3983,7 @@ impl Workspace {
             let left_visible = left_dock.is_open();
             let left_active_panel = left_dock
                 .visible_panel()
-                .map(|panel| panel.persistent_name().to_string());
+                .map(|panel| panel.persistent_name(cx).to_string());
             let left_dock_zoom = left_dock
                 .visible_panel()
                 .map(|panel| panel.is_zoomed(cx))
@@ -3504,7 +3993,7 @@ impl Workspace {
             let right_visible = right_dock.is_open();
             let right_active_panel = right_dock
                 .visible_panel()
-                .map(|panel| panel.persistent_name().to_string());
+                .map(|panel| panel.persistent_name(cx).to_string());
             let right_dock_zoom = right_dock
                 .visible_panel()
                 .map(|panel| panel.is_zoomed(cx))
@@ -3514,7 +4003,7 @@ impl Workspace {
             let bottom_visible = bottom_dock.is_open();
             let bottom_active_panel = bottom_dock
                 .visible_panel()
-                .map(|panel| panel.persistent_name().to_string());
+                .map(|panel| panel.persistent_name(cx).to_string());
             let bottom_dock_zoom = bottom_dock
                 .visible_panel()
                 .map(|panel| panel.is_zoomed(cx))
@@ -3575,6 +4064,8 @@ impl Workspace {
                 docks,
                 fullscreen: cx.is_fullscreen(),
                 centered_layout: self.centered_layout,
+                always_on_top: Some(self.always_on_top),
+                window_zoom: self.window_zoom,
             };
             return cx.spawn(|_| persistence::DB.save_workspace(serialized_workspace));
         }
@@ -3678,6 +4169,8 @@ impl Workspace {
             .on_action(cx.listener(Self::send_keystrokes))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::follow_next_collaborator))
+            .on_action(cx.listener(Self::toggle_follow_scroll_only))
+            .on_action(cx.listener(Self::invite_to_follow))
             .on_action(cx.listener(|workspace, _: &Unfollow, cx| {
                 let pane = workspace.active_pane().clone();
                 workspace.unfollow(&pane, cx);
@@ -3738,6 +4231,18 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(Workspace::toggle_always_on_top))
+            .on_action(cx.listener(Workspace::increase_window_zoom))
+            .on_action(cx.listener(Workspace::decrease_window_zoom))
+            .on_action(cx.listener(Workspace::reset_window_zoom))
+            .on_action(cx.listener(Workspace::move_item_to_new_window))
+            .on_action(cx.listener(Workspace::move_pane_to_new_window))
+            .on_action(cx.listener(Workspace::toggle_zen_mode))
+            .on_action(cx.listener(Workspace::save_layout_profile))
+            .on_action(cx.listener(Workspace::switch_layout_profile))
+            .on_action(cx.listener(Workspace::show_key_hints))
+            .on_action(cx.listener(Workspace::focus_next_region))
+            .on_action(cx.listener(Workspace::focus_previous_region))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -3815,6 +4320,88 @@ impl Workspace {
         cx.notify();
     }
 
+    fn set_always_on_top(&mut self, always_on_top: bool, cx: &mut ViewContext<Self>) {
+        self.always_on_top = always_on_top;
+        cx.set_window_always_on_top(always_on_top);
+    }
+
+    pub fn toggle_always_on_top(&mut self, _: &ToggleAlwaysOnTop, cx: &mut ViewContext<Self>) {
+        self.set_always_on_top(!self.always_on_top, cx);
+        cx.background_executor()
+            .spawn(DB.set_always_on_top(self.database_id, self.always_on_top))
+            .detach_and_log_err(cx);
+    }
+
+    /// Returns the factor by which the UI of this window is zoomed, on top of
+    /// the `ui_font_size`.
+    pub fn window_zoom(&self) -> f32 {
+        self.window_zoom
+    }
+
+    fn set_window_zoom(&mut self, window_zoom: f32, cx: &mut ViewContext<Self>) {
+        // Round away the error accumulated by repeatedly stepping the zoom.
+        let window_zoom = (window_zoom * 100.).round() / 100.;
+        self.window_zoom = window_zoom.clamp(MIN_WINDOW_ZOOM, MAX_WINDOW_ZOOM);
+        cx.background_executor()
+            .spawn(DB.set_window_zoom(self.database_id, self.window_zoom))
+            .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    pub fn increase_window_zoom(&mut self, _: &IncreaseWindowZoom, cx: &mut ViewContext<Self>) {
+        self.set_window_zoom(self.window_zoom + WINDOW_ZOOM_STEP, cx);
+    }
+
+    pub fn decrease_window_zoom(&mut self, _: &DecreaseWindowZoom, cx: &mut ViewContext<Self>) {
+        self.set_window_zoom(self.window_zoom - WINDOW_ZOOM_STEP, cx);
+    }
+
+    pub fn reset_window_zoom(&mut self, _: &ResetWindowZoom, cx: &mut ViewContext<Self>) {
+        self.set_window_zoom(1.0, cx);
+    }
+
+    pub fn toggle_zen_mode(&mut self, _: &ToggleZenMode, cx: &mut ViewContext<Self>) {
+        if let Some(state) = self.zen_mode.take() {
+            for (dock, was_open) in [
+                (&self.left_dock, state.left_dock_open),
+                (&self.bottom_dock, state.bottom_dock_open),
+                (&self.right_dock, state.right_dock_open),
+            ] {
+                dock.update(cx, |dock, cx| dock.set_open(was_open, cx));
+            }
+        } else {
+            self.zen_mode = Some(ZenModeState {
+                left_dock_open: self.left_dock.read(cx).is_open(),
+                bottom_dock_open: self.bottom_dock.read(cx).is_open(),
+                right_dock_open: self.right_dock.read(cx).is_open(),
+            });
+            for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+                dock.update(cx, |dock, cx| dock.set_open(false, cx));
+            }
+            // Keep editing where the user was, even if a closed panel had focus.
+            self.active_pane.update(cx, |pane, cx| {
+                if pane.active_item().is_some() {
+                    pane.focus_active_item(cx);
+                } else {
+                    pane.focus(cx);
+                }
+            });
+        }
+        cx.emit(Event::ZenModeChanged);
+        cx.notify();
+    }
+
+    pub fn is_zen_mode(&self) -> bool {
+        self.zen_mode.is_some()
+    }
+
+    /// Returns the zen mode settings if zen mode is currently active.
+    pub fn zen_mode_settings(&self, cx: &AppContext) -> Option<ZenModeSettings> {
+        self.zen_mode
+            .as_ref()
+            .map(|_| WorkspaceSettings::get_global(cx).zen_mode)
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -3965,7 +4552,9 @@ impl Render for Workspace {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let mut context = KeyContext::new_with_defaults();
         context.add("Workspace");
+        let zen_mode = self.zen_mode_settings(cx);
         let centered_layout = self.centered_layout
+            && zen_mode.is_none()
             && self.center.panes().len() == 1
             && self.active_item(cx).is_some();
         let render_padding = |size| {
@@ -3996,10 +4585,12 @@ impl Render for Workspace {
 
         let theme = cx.theme().clone();
         let colors = theme.colors();
-        cx.set_rem_size(ui_font_size);
+        cx.set_rem_size(ui_font_size * self.window_zoom);
 
         self.actions(div(), cx)
             .key_context(context)
+            .on_mouse_move(cx.listener(|this, _, cx| this.report_activity(cx)))
+            .capture_key_down(cx.listener(|this, _, cx| this.report_activity(cx)))
             .relative()
             .size_full()
             .flex()
@@ -4084,15 +4675,26 @@ impl Render for Workspace {
                                             .when_some(paddings.0, |this, p| {
                                                 this.child(p.border_r_1())
                                             })
-                                            .child(self.center.render(
-                                                &self.project,
-                                                &self.follower_states,
-                                                self.active_call(),
-                                                &self.active_pane,
-                                                self.zoomed.as_ref(),
-                                                &self.app_state,
-                                                cx,
-                                            ))
+                                            .when(zen_mode.is_some(), |this| {
+                                                this.justify_center().bg(colors.editor_background)
+                                            })
+                                            .child(
+                                                div()
+                                                    .flex()
+                                                    .size_full()
+                                                    .when_some(zen_mode, |this, zen_mode| {
+                                                        this.max_w(px(zen_mode.max_width))
+                                                    })
+                                                    .child(self.center.render(
+                                                        &self.project,
+                                                        &self.follower_states,
+                                                        self.active_call(),
+                                                        &self.active_pane,
+                                                        self.zoomed.as_ref(),
+                                                        &self.app_state,
+                                                        cx,
+                                                    )),
+                                            )
                                             .when_some(paddings.1, |this, p| {
                                                 this.child(p.border_l_1())
                                             }),
@@ -4134,14 +4736,22 @@ impl Render for Workspace {
                         })
                     }))
                     .child(self.modal_layer.clone())
-                    .children(self.render_notifications(cx)),
+                    .children(self.render_notifications(cx))
+                    .children(self.render_key_hints_prompt(cx)),
+            )
+            .when(
+                zen_mode.map_or(true, |zen_mode| zen_mode.show_status_bar),
+                |this| this.child(self.status_bar.clone()),
+            )
+            .children(
+                if self.project.read(cx).is_disconnected()
+                    && !self.project.read(cx).is_editing_offline()
+                {
+                    Some(DisconnectedOverlay)
+                } else {
+                    None
+                },
             )
-            .child(self.status_bar.clone())
-            .children(if self.project.read(cx).is_disconnected() {
-                Some(DisconnectedOverlay)
-            } else {
-                None
-            })
     }
 }
 
@@ -4152,6 +4762,7 @@ impl WorkspaceStore {
             _subscriptions: vec![
                 client.add_request_handler(cx.weak_model(), Self::handle_follow),
                 client.add_message_handler(cx.weak_model(), Self::handle_update_followers),
+                client.add_message_handler(cx.weak_model(), Self::handle_invite_to_follow),
             ],
             client,
         }
@@ -4244,6 +4855,38 @@ impl WorkspaceStore {
             Ok(())
         })?
     }
+
+    async fn handle_invite_to_follow(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::InviteToFollow>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let leader_id = envelope.original_sender_id()?;
+        let project_id = envelope.payload.project_id;
+
+        this.update(&mut cx, |this, cx| {
+            // Prefer showing the invitation in the window of the leader's project.
+            let workspace = this
+                .workspaces
+                .iter()
+                .find(|workspace| {
+                    project_id.is_some()
+                        && workspace.read(cx).map_or(false, |workspace| {
+                            workspace.project.read(cx).remote_id() == project_id
+                        })
+                })
+                .or_else(|| this.workspaces.iter().next())
+                .copied();
+            if let Some(workspace) = workspace {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.show_follow_invitation(leader_id, cx)
+                    })
+                    .log_err();
+            }
+        })
+    }
 }
 
 impl ViewId {
@@ -4326,7 +4969,7 @@ pub async fn last_opened_workspace_paths() -> Option<LocalPaths> {
 }
 
 actions!(collab, [OpenChannelNotes]);
-actions!(zed, [OpenLog]);
+actions!(zed, [OpenLog, OpenLogViewer]);
 
 async fn join_channel_internal(
     channel_id: ChannelId,
@@ -4837,6 +5480,71 @@ pub fn join_remote_project(
     })
 }
 
+/// Joins the remote project of a workspace that lost its connection to the host again,
+/// in a new window, and merges in the edits that were made while offline.
+///
+/// The offline window is closed unless some of its edits couldn't be merged.
+pub fn rejoin_offline_project(
+    offline_workspace: WindowHandle<Workspace>,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<WindowHandle<Workspace>>> {
+    cx.spawn(|mut cx| async move {
+        let offline_project =
+            offline_workspace.update(&mut cx, |workspace, _| workspace.project().clone())?;
+        let project_id = offline_project
+            .read_with(&cx, |project, _| project.remote_id())?
+            .ok_or_else(|| anyhow!("not a remote project"))?;
+
+        let active_call = cx.update(|cx| ActiveCall::global(cx))?;
+        let room = active_call
+            .read_with(&cx, |call, _| call.room().cloned())?
+            .ok_or_else(|| anyhow!("join the call again to rejoin this project"))?;
+        let project = room
+            .update(&mut cx, |room, cx| {
+                room.join_project(
+                    project_id,
+                    app_state.languages.clone(),
+                    app_state.fs.clone(),
+                    cx,
+                )
+            })?
+            .await?;
+        let summary = project
+            .update(&mut cx, |project, cx| {
+                project.merge_offline_edits(&offline_project, cx)
+            })?
+            .await?;
+
+        let window_bounds_override = window_bounds_env_override();
+        let workspace = cx.update(|cx| {
+            let mut options = (app_state.build_window_options)(None, cx);
+            options.bounds = window_bounds_override;
+            cx.open_window(options, |cx| {
+                cx.new_view(|cx| Workspace::new(Default::default(), project, app_state.clone(), cx))
+            })
+        })?;
+
+        let keep_offline_workspace = summary.skipped_edit_count() > 0;
+        workspace.update(&mut cx, |workspace, cx| {
+            cx.activate_window();
+            if !summary.buffers.is_empty() {
+                let weak_workspace = cx.view().downgrade();
+                workspace.show_notification(
+                    NotificationId::unique::<OfflineMergeNotification>(),
+                    cx,
+                    |cx| cx.new_view(|_| OfflineMergeNotification::new(summary, weak_workspace)),
+                );
+            }
+        })?;
+        if !keep_offline_workspace {
+            offline_workspace.update(&mut cx, |_, cx| cx.remove_window())?;
+        }
+
+        Ok(workspace)
+    })
+}
+
 pub fn join_in_room_project(
     project_id: u64,
     follow_user_id: u64,
@@ -4845,14 +5553,21 @@ pub fn join_in_room_project(
 ) -> Task<Result<()>> {
     let windows = cx.windows();
     cx.spawn(|mut cx| async move {
+        let mut offline_workspace = None;
         let existing_workspace = windows.into_iter().find_map(|window| {
             window.downcast::<Workspace>().and_then(|window| {
                 window
                     .update(&mut cx, |workspace, cx| {
-                        if workspace.project().read(cx).remote_id() == Some(project_id) {
-                            Some(window)
-                        } else {
+                        let project = workspace.project().read(cx);
+                        if project.remote_id() != Some(project_id) {
+                            None
+                        } else if project.is_disconnected() {
+                            if project.is_editing_offline() {
+                                offline_workspace = Some(window);
+                            }
                             None
+                        } else {
+                            Some(window)
                         }
                     })
                     .unwrap_or(None)
@@ -4861,6 +5576,9 @@ pub fn join_in_room_project(
 
         let workspace = if let Some(existing_workspace) = existing_workspace {
             existing_workspace
+        } else if let Some(offline_workspace) = offline_workspace {
+            cx.update(|cx| rejoin_offline_project(offline_workspace, app_state.clone(), cx))?
+                .await?
         } else {
             let active_call = cx.update(|cx| ActiveCall::global(cx))?;
             let room = active_call
@@ -5203,6 +5921,96 @@ mod tests {
         assert_eq!(cx.window_title().as_deref(), Some("one.txt — root2"));
     }
 
+    #[gpui::test]
+    async fn test_window_title_template(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                settings.window_title = Some("{dirty}{path} [{file}] - {project}".into());
+            })
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root1", json!({ "dir": { "one.txt": "" } }))
+            .await;
+        fs.insert_tree("/root2", json!({})).await;
+
+        let project = Project::test(fs, ["/root1".as_ref(), "/root2".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let item1 = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "dir/one.txt", cx)])
+        });
+        let item2 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(2, "two.txt", cx)])
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item1), cx)
+        });
+        assert_eq!(
+            cx.window_title().as_deref(),
+            Some("dir/one.txt [one.txt] - root1, root2")
+        );
+
+        // Unsaved changes are marked.
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item2), cx)
+        });
+        assert_eq!(
+            cx.window_title().as_deref(),
+            Some("● two.txt [two.txt] - root1, root2")
+        );
+
+        // Without an active path, the surrounding whitespace is trimmed.
+        let item3 = cx.new_view(|cx| TestItem::new(cx));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item3), cx)
+        });
+        assert_eq!(cx.window_title().as_deref(), Some("[] - root1, root2"));
+    }
+
+    #[gpui::test]
+    async fn test_window_zoom_and_always_on_top(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.window_zoom(), 1.0);
+
+            // Stepping the zoom doesn't accumulate rounding errors.
+            for _ in 0..3 {
+                workspace.increase_window_zoom(&IncreaseWindowZoom, cx);
+            }
+            assert_eq!(workspace.window_zoom(), 1.3);
+            workspace.decrease_window_zoom(&DecreaseWindowZoom, cx);
+            assert_eq!(workspace.window_zoom(), 1.2);
+
+            // The zoom is kept within bounds.
+            for _ in 0..30 {
+                workspace.increase_window_zoom(&IncreaseWindowZoom, cx);
+            }
+            assert_eq!(workspace.window_zoom(), MAX_WINDOW_ZOOM);
+            for _ in 0..30 {
+                workspace.decrease_window_zoom(&DecreaseWindowZoom, cx);
+            }
+            assert_eq!(workspace.window_zoom(), MIN_WINDOW_ZOOM);
+
+            workspace.reset_window_zoom(&ResetWindowZoom, cx);
+            assert_eq!(workspace.window_zoom(), 1.0);
+
+            assert!(!workspace.always_on_top);
+            workspace.toggle_always_on_top(&ToggleAlwaysOnTop, cx);
+            assert!(workspace.always_on_top);
+            workspace.toggle_always_on_top(&ToggleAlwaysOnTop, cx);
+            assert!(!workspace.always_on_top);
+        });
+    }
+
     #[gpui::test]
     async fn test_close_window(cx: &mut TestAppContext) {
         init_test(cx);
@@ -5804,6 +6612,130 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_toggle_zen_mode(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let item = cx.new_view(|cx| TestItem::new(cx));
+
+        let left_panel = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), cx);
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(left_panel.clone(), cx);
+            let bottom_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(bottom_panel, cx);
+
+            workspace
+                .left_dock()
+                .update(cx, |left_dock, cx| left_dock.set_open(true, cx));
+            workspace.toggle_panel_focus::<TestPanel>(cx);
+            left_panel
+        });
+        cx.update(|cx| assert!(left_panel.focus_handle(cx).is_focused(cx)));
+
+        // Entering zen mode closes all docks and moves focus to the active item
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            assert!(item.focus_handle(cx).is_focused(cx));
+            assert!(workspace.is_zen_mode());
+            assert!(workspace.zen_mode_settings(cx).is_some());
+            assert!(!workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.bottom_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+        });
+
+        // Leaving zen mode restores the previous layout
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.is_zen_mode());
+            assert!(workspace.zen_mode_settings(cx).is_none());
+            assert!(workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.bottom_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_layout_profiles(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(left_panel, cx);
+            let bottom_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(bottom_panel, cx);
+
+            workspace.left_dock().update(cx, |left_dock, cx| {
+                left_dock.set_open(true, cx);
+                left_dock.resize_active_panel(Some(px(250.)), cx);
+            });
+            workspace
+                .save_layout_profile_as("coding".into(), LayoutProfileScope::Project, cx)
+                .detach_and_log_err(cx);
+
+            workspace
+                .left_dock()
+                .update(cx, |left_dock, cx| left_dock.set_open(false, cx));
+            workspace
+                .bottom_dock()
+                .update(cx, |bottom_dock, cx| bottom_dock.set_open(true, cx));
+            workspace
+                .save_layout_profile_as("review".into(), LayoutProfileScope::Global, cx)
+                .detach_and_log_err(cx);
+        });
+        cx.run_until_parked();
+
+        let profiles = workspace.update(cx, |workspace, cx| workspace.layout_profiles(cx));
+        let profiles = profiles.await.unwrap();
+        assert!(profiles.contains(&("coding".to_string(), LayoutProfileScope::Project)));
+        assert!(profiles.contains(&("review".to_string(), LayoutProfileScope::Global)));
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.switch_layout_profile(
+                &SwitchLayoutProfile {
+                    name: "coding".into(),
+                },
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.left_dock().read(cx).is_open());
+            assert_eq!(
+                workspace.left_dock().read(cx).active_panel_size(cx),
+                Some(px(250.))
+            );
+            assert!(!workspace.bottom_dock().read(cx).is_open());
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.switch_layout_profile(
+                &SwitchLayoutProfile {
+                    name: "review".into(),
+                },
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.left_dock().read(cx).is_open());
+            assert!(workspace.bottom_dock().read(cx).is_open());
+        });
+    }
+
     #[gpui::test]
     async fn test_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -6033,6 +6965,129 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_move_items_to_new_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_project_item::<TestBufferView>);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "one.txt": "one", "two.txt": "two" }))
+            .await;
+        let project = Project::test(fs, ["root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let source_window = cx.window_handle();
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "one.txt"), None, true, cx)
+            })
+            .await
+            .unwrap();
+        let buffer = item
+            .downcast::<TestBufferView>()
+            .unwrap()
+            .read_with(cx, |view, _| view.buffer.clone());
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "edited ")], None, cx));
+        assert!(buffer.read_with(cx, |buffer, _| buffer.is_dirty()));
+
+        // The item is reopened on the same buffer in the new window, and closed in
+        // the source pane without prompting to save the edit.
+        workspace.update(cx, |workspace, cx| {
+            workspace.move_items_to_new_window(pane.clone(), vec![item.item_id()], cx)
+        });
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 0));
+
+        let new_window = cx
+            .windows()
+            .into_iter()
+            .find(|window| *window != source_window)
+            .and_then(|window| window.downcast::<Workspace>())
+            .unwrap();
+        new_window
+            .update(cx, |new_workspace, cx| {
+                assert_eq!(new_workspace.project(), &project);
+                let moved_item = new_workspace.active_item_as::<TestBufferView>(cx).unwrap();
+                assert_eq!(moved_item.read(cx).buffer, buffer);
+                assert!(moved_item.is_dirty(cx));
+            })
+            .unwrap();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "edited one"
+        );
+
+        // Items that don't belong to a project file stay where they are.
+        let item = cx.new_view(|cx| TestItem::new(cx));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), cx);
+            workspace.move_items_to_new_window(pane.clone(), vec![item.item_id()], cx);
+            assert_eq!(workspace.notifications.len(), 1);
+        });
+        cx.run_until_parked();
+        assert_eq!(cx.windows().len(), 2);
+        pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 1));
+    }
+
+    struct TestBufferView {
+        buffer: Model<language::Buffer>,
+        focus_handle: FocusHandle,
+    }
+
+    impl Item for TestBufferView {
+        type Event = ();
+
+        fn for_each_project_item(
+            &self,
+            cx: &AppContext,
+            f: &mut dyn FnMut(EntityId, &dyn project::Item),
+        ) {
+            f(self.buffer.entity_id(), self.buffer.read(cx))
+        }
+
+        fn is_singleton(&self, _: &AppContext) -> bool {
+            true
+        }
+
+        fn is_dirty(&self, cx: &AppContext) -> bool {
+            self.buffer.read(cx).is_dirty()
+        }
+    }
+
+    impl EventEmitter<()> for TestBufferView {}
+
+    impl FocusableView for TestBufferView {
+        fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestBufferView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl ProjectItem for TestBufferView {
+        type Item = language::Buffer;
+
+        fn for_project_item(
+            _: Model<Project>,
+            buffer: Model<Self::Item>,
+            cx: &mut ViewContext<Self>,
+        ) -> Self {
+            Self {
+                buffer,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+
     mod register_project_item_tests {
         use ui::Context as _;
 
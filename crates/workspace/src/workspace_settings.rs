@@ -1,4 +1,6 @@
+use crate::StatusItemSide;
 use anyhow::Result;
+use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,11 +10,15 @@ use settings::{Settings, SettingsSources};
 pub struct WorkspaceSettings {
     pub active_pane_magnification: f32,
     pub centered_layout: CenteredLayoutSettings,
+    pub zen_mode: ZenModeSettings,
     pub confirm_quit: bool,
     pub show_call_status_icon: bool,
     pub autosave: AutosaveSetting,
     pub restore_on_startup: RestoreOnStartupBehaviour,
     pub drop_target_size: f32,
+    pub always_on_top: bool,
+    pub window_title: Option<String>,
+    pub reduce_motion: bool,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -20,9 +26,12 @@ pub struct WorkspaceSettings {
 pub enum RestoreOnStartupBehaviour {
     /// Always start with an empty editor
     None,
-    /// Restore the workspace that was closed last.
+    /// Restore the workspace that was closed last, or show the dashboard when
+    /// there's none.
     #[default]
     LastWorkspace,
+    /// Always start with the dashboard of recent projects.
+    Dashboard,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -35,6 +44,8 @@ pub struct WorkspaceSettingsContent {
     pub active_pane_magnification: Option<f32>,
     // Centered layout related settings.
     pub centered_layout: Option<CenteredLayoutSettings>,
+    /// Zen mode related settings.
+    pub zen_mode: Option<ZenModeSettingsContent>,
     /// Whether or not to prompt the user to confirm before closing the application.
     ///
     /// Default: false
@@ -48,7 +59,7 @@ pub struct WorkspaceSettingsContent {
     /// Default: off
     pub autosave: Option<AutosaveSetting>,
     /// Controls previous session restoration in freshly launched Zed instance.
-    /// Values: none, last_workspace
+    /// Values: none, last_workspace, dashboard
     /// Default: last_workspace
     pub restore_on_startup: Option<RestoreOnStartupBehaviour>,
     /// The size of the workspace split drop targets on the outer edges.
@@ -56,6 +67,22 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: `0.2` (20% of the smaller dimension of the workspace)
     pub drop_target_size: Option<f32>,
+    /// Whether new windows are kept above other windows, even when they
+    /// aren't focused. This can be toggled for each window.
+    ///
+    /// Default: false
+    pub always_on_top: Option<bool>,
+    /// The template for the window title, in which `{file}` is replaced with
+    /// the name of the active file, `{path}` with its path relative to the
+    /// project, `{project}` with the names of the project's folders, and
+    /// `{dirty}` with a marker when the active file has unsaved changes.
+    ///
+    /// Default: null, which shows the active file and the project's folders.
+    pub window_title: Option<String>,
+    /// Whether to skip animations, jumping straight to where they would end.
+    ///
+    /// Default: false
+    pub reduce_motion: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -71,6 +98,31 @@ pub struct TabBarSettingsContent {
     pub show_nav_history_buttons: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct StatusBarSettings {
+    pub items: HashMap<String, StatusItemSettings>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusBarSettingsContent {
+    /// Overrides of whether and where the items of the status bar are shown, by
+    /// the id of the item, like `cursor_position` or `vim_mode`.
+    ///
+    /// Default: {}
+    pub items: Option<HashMap<String, StatusItemSettings>>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusItemSettings {
+    /// Whether to show the item.
+    pub visible: Option<bool>,
+    /// The side of the status bar to show the item on.
+    pub side: Option<StatusItemSide>,
+    /// The position of the item on its side. Items with a higher priority are
+    /// shown closer to the edge of the status bar.
+    pub priority: Option<i32>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AutosaveSetting {
@@ -99,6 +151,34 @@ pub struct CenteredLayoutSettings {
     pub right_padding: Option<f32>,
 }
 
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct ZenModeSettings {
+    pub max_width: f32,
+    pub show_tab_bar: bool,
+    pub show_status_bar: bool,
+    pub show_line_numbers: bool,
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ZenModeSettingsContent {
+    /// The maximum width of the centered pane in zen mode, in pixels.
+    ///
+    /// Default: 800
+    pub max_width: Option<f32>,
+    /// Whether to show the tab bar in zen mode.
+    ///
+    /// Default: false
+    pub show_tab_bar: Option<bool>,
+    /// Whether to show the status bar in zen mode.
+    ///
+    /// Default: false
+    pub show_status_bar: Option<bool>,
+    /// Whether to show line numbers in the editor gutter in zen mode.
+    ///
+    /// Default: false
+    pub show_line_numbers: Option<bool>,
+}
+
 impl Settings for WorkspaceSettings {
     const KEY: Option<&'static str> = None;
 
@@ -118,3 +198,13 @@ impl Settings for TabBarSettings {
         sources.json_merge()
     }
 }
+
+impl Settings for StatusBarSettings {
+    const KEY: Option<&'static str> = Some("status_bar");
+
+    type FileContent = StatusBarSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -59,10 +59,10 @@ use std::{
         atomic::{AtomicUsize, Ordering::SeqCst},
         Arc,
     },
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use sum_tree::{Bias, Edit, SeekTarget, SumTree, TreeMap, TreeSet};
-use text::BufferId;
+use text::{BufferId, LoadedText};
 use util::{
     paths::{PathMatcher, HOME},
     ResultExt,
@@ -75,6 +75,13 @@ pub const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
 #[cfg(not(feature = "test-support"))]
 pub const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
 
+/// How many changed paths the initial scan accumulates before it reports its progress,
+/// so that very large repositories don't rebuild the worktree's snapshot on every tick.
+const SCAN_PROGRESS_BATCH_SIZE: usize = 4096;
+/// The longest the initial scan waits before reporting its progress, even when fewer
+/// than [`SCAN_PROGRESS_BATCH_SIZE`] paths have changed.
+const SCAN_PROGRESS_MAX_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub struct WorktreeId(usize);
 
@@ -245,6 +252,9 @@ pub struct LocalSnapshot {
     /// All of the gitignore files in the worktree, indexed by their relative path.
     /// The boolean indicates whether the gitignore needs to be updated.
     ignores_by_parent_abs_path: HashMap<Arc<Path>, (Arc<Gitignore>, bool)>,
+    /// The user's global excludes file (`core.excludesFile`), which applies to every
+    /// git repository in the worktree.
+    global_gitignore: Option<Arc<Gitignore>>,
     /// All of the git repositories in the worktree, indexed by the project entry
     /// id of their parent directory.
     git_repositories: TreeMap<ProjectEntryId, LocalRepositoryEntry>,
@@ -264,6 +274,7 @@ struct BackgroundScannerState {
     removed_entry_ids: HashMap<u64, ProjectEntryId>,
     changed_paths: Vec<Arc<Path>>,
     prev_snapshot: Snapshot,
+    last_progress_update: Option<Instant>,
 }
 
 #[derive(Debug, Clone)]
@@ -425,6 +436,7 @@ impl Worktree {
                     "private_files",
                 ),
                 ignores_by_parent_abs_path: Default::default(),
+                global_gitignore: None,
                 git_repositories: Default::default(),
                 snapshot: Snapshot {
                     id: WorktreeId::from_usize(cx.entity_id().as_u64() as usize),
@@ -757,20 +769,21 @@ impl LocalWorktree {
         let reservation = cx.reserve_model();
         let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
         cx.spawn(move |this, mut cx| async move {
-            let (file, contents, diff_base) = this
+            let (file, loaded, diff_base) = this
                 .update(&mut cx, |t, cx| t.as_local().unwrap().load(&path, cx))?
                 .await?;
-            let text_buffer = cx
-                .background_executor()
-                .spawn(async move { text::Buffer::new(0, buffer_id, contents) })
-                .await;
+            let is_lossy = loaded.is_lossy();
+            let text_buffer =
+                text::Buffer::new_normalized(0, buffer_id, loaded.line_ending, loaded.text);
             cx.insert_model(reservation, |_| {
-                Buffer::build(
+                let mut buffer = Buffer::build(
                     text_buffer,
                     diff_base,
                     Some(Arc::new(file)),
                     Capability::ReadWrite,
-                )
+                );
+                buffer.set_lossy(is_lossy);
+                buffer
             })
         })
     }
@@ -1065,7 +1078,7 @@ impl LocalWorktree {
         &self,
         path: &Path,
         cx: &mut ModelContext<Worktree>,
-    ) -> Task<Result<(File, String, Option<String>)>> {
+    ) -> Task<Result<(File, LoadedText, Option<String>)>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
@@ -1073,7 +1086,13 @@ impl LocalWorktree {
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let text = fs.load_text(&abs_path).await?;
+            if text.is_lossy() {
+                log::warn!(
+                    "replaced {} invalid UTF-8 sequences while loading {abs_path:?}",
+                    text.invalid_ranges.len()
+                );
+            }
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
             if let Some(repo) = snapshot.repository_for_path(&path) {
@@ -2325,8 +2344,20 @@ impl LocalSnapshot {
         inodes
     }
 
+    /// The ignore stack for the root of a git repository, before any of the
+    /// repository's own `.gitignore` files are applied.
+    fn repository_ignore_stack(&self, repository_abs_path: &Path) -> Arc<IgnoreStack> {
+        match &self.global_gitignore {
+            Some(global_gitignore) => {
+                IgnoreStack::none().append(repository_abs_path.into(), global_gitignore.clone())
+            }
+            None => IgnoreStack::none(),
+        }
+    }
+
     fn ignore_stack_for_abs_path(&self, abs_path: &Path, is_dir: bool) -> Arc<IgnoreStack> {
         let mut new_ignores = Vec::new();
+        let mut repository_abs_path = abs_path;
         for (index, ancestor) in abs_path.ancestors().enumerate() {
             if index > 0 {
                 if let Some((ignore, _)) = self.ignores_by_parent_abs_path.get(ancestor) {
@@ -2335,12 +2366,14 @@ impl LocalSnapshot {
                     new_ignores.push((ancestor, None));
                 }
             }
+            repository_abs_path = ancestor;
             if ancestor.join(&*DOT_GIT).is_dir() {
                 break;
             }
         }
 
-        let mut ignore_stack = IgnoreStack::none();
+        // Ignore files outside of the enclosing repository don't apply to it.
+        let mut ignore_stack = self.repository_ignore_stack(repository_abs_path);
         for (parent_abs_path, ignore) in new_ignores.into_iter().rev() {
             if ignore_stack.is_abs_path_ignored(parent_abs_path, true) {
                 ignore_stack = IgnoreStack::all();
@@ -2823,9 +2856,25 @@ impl BackgroundScannerState {
 }
 
 async fn build_gitignore(abs_path: &Path, fs: &dyn Fs) -> Result<Gitignore> {
-    let contents = fs.load(abs_path).await?;
     let parent = abs_path.parent().unwrap_or_else(|| Path::new("/"));
-    let mut builder = GitignoreBuilder::new(parent);
+    build_gitignore_with_root(abs_path, parent, fs).await
+}
+
+/// Loads the user's global excludes file, whose patterns are matched relative to
+/// the root of each repository rather than to the file's own directory.
+async fn build_global_gitignore(fs: &dyn Fs) -> Option<Gitignore> {
+    let abs_path = ::ignore::gitignore::gitconfig_excludes_path()?;
+    if !fs.is_file(&abs_path).await {
+        return None;
+    }
+    build_gitignore_with_root(&abs_path, Path::new(""), fs)
+        .await
+        .log_err()
+}
+
+async fn build_gitignore_with_root(abs_path: &Path, root: &Path, fs: &dyn Fs) -> Result<Gitignore> {
+    let contents = fs.load(abs_path).await?;
+    let mut builder = GitignoreBuilder::new(root);
     for line in contents.lines() {
         builder.add_line(Some(abs_path.into()), line)?;
     }
@@ -3410,6 +3459,7 @@ impl BackgroundScanner {
                 paths_to_scan: Default::default(),
                 removed_entry_ids: Default::default(),
                 changed_paths: Default::default(),
+                last_progress_update: None,
             }),
             phase: BackgroundScannerPhase::InitialScan,
         }
@@ -3418,6 +3468,9 @@ impl BackgroundScanner {
     async fn run(&mut self, mut fs_events_rx: Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>>) {
         use futures::FutureExt as _;
 
+        let global_gitignore = build_global_gitignore(self.fs.as_ref()).await;
+        self.state.lock().snapshot.global_gitignore = global_gitignore.map(Arc::new);
+
         // Populate ignores above the root.
         let root_abs_path = self.state.lock().snapshot.abs_path.clone();
         for (index, ancestor) in root_abs_path.ancestors().enumerate() {
@@ -3733,7 +3786,7 @@ impl BackgroundScanner {
                                     ) {
                                         Ok(_) => {
                                             last_progress_update_count += 1;
-                                            self.send_status_update(true, None);
+                                            self.send_progress_update();
                                         }
                                         Err(count) => {
                                             last_progress_update_count = count;
@@ -3759,6 +3812,25 @@ impl BackgroundScanner {
             .await;
     }
 
+    /// Report the paths found so far by the scan, once enough of them have accumulated
+    /// or enough time has passed since the last report.
+    fn send_progress_update(&self) -> bool {
+        let now = self.executor.now();
+        {
+            let mut state = self.state.lock();
+            if let Some(last_progress_update) = state.last_progress_update {
+                if state.changed_paths.len() < SCAN_PROGRESS_BATCH_SIZE
+                    && now.saturating_duration_since(last_progress_update)
+                        < SCAN_PROGRESS_MAX_INTERVAL
+                {
+                    return true;
+                }
+            }
+            state.last_progress_update = Some(now);
+        }
+        self.send_status_update(true, None)
+    }
+
     fn send_status_update(&self, scanning: bool, barrier: Option<barrier::Sender>) -> bool {
         let mut state = self.state.lock();
         if state.changed_paths.is_empty() && scanning {
@@ -3817,6 +3889,7 @@ impl BackgroundScanner {
             };
             let child_name = child_abs_path.file_name().unwrap();
             let child_path: Arc<Path> = job.path.join(child_name).into();
+            let mut ignore_stack_changed = false;
             // If we find a .gitignore, add it to the stack of ignores used to determine which paths are ignored
             if child_name == *GITIGNORE {
                 match build_gitignore(&child_abs_path, self.fs.as_ref()).await {
@@ -3833,11 +3906,33 @@ impl BackgroundScanner {
                         );
                     }
                 }
+                ignore_stack_changed = true;
+            }
+            // If we find a .git, we'll need to load the repository.
+            else if child_name == *DOT_GIT {
+                dotgit_path = Some(child_path.clone());
 
+                // A nested repository doesn't inherit the ignores of the repository containing
+                // it, so its contents are only matched against its own ignore files.
+                if job.path.as_ref() != Path::new("") && !matches!(*ignore_stack, IgnoreStack::All)
+                {
+                    ignore_stack = self
+                        .state
+                        .lock()
+                        .snapshot
+                        .repository_ignore_stack(&job.abs_path);
+                    if let Some(ignore) = &new_ignore {
+                        ignore_stack = ignore_stack.append(job.abs_path.clone(), ignore.clone());
+                    }
+                    ignore_stack_changed = true;
+                }
+            }
+
+            if ignore_stack_changed {
                 // Update ignore status of any child entries we've already processed to reflect the
-                // ignore file in the current directory. Because `.gitignore` starts with a `.`,
-                // there should rarely be too numerous. Update the ignore stack associated with any
-                // new jobs as well.
+                // new ignore stack of the current directory. Because `.gitignore` and `.git` start
+                // with a `.`, there should rarely be too numerous. Update the ignore stack associated
+                // with any new jobs as well.
                 let mut new_jobs = new_jobs.iter_mut();
                 for entry in &mut new_entries {
                     let entry_abs_path = root_abs_path.join(&entry.path);
@@ -3855,11 +3950,10 @@ impl BackgroundScanner {
                     }
                 }
             }
-            // If we find a .git, we'll need to load the repository.
-            else if child_name == *DOT_GIT {
-                dotgit_path = Some(child_path.clone());
-            }
 
+            // Check the child against the exclusion and privacy settings while holding the
+            // lock once, since every worker scanning in parallel contends for it.
+            let is_private;
             {
                 let relative_path = job.path.join(child_name);
                 let mut state = self.state.lock();
@@ -3868,6 +3962,7 @@ impl BackgroundScanner {
                     state.remove_path(&relative_path);
                     continue;
                 }
+                is_private = state.snapshot.is_path_private(&relative_path);
                 drop(state);
             }
 
@@ -3963,14 +4058,9 @@ impl BackgroundScanner {
                 }
             }
 
-            {
-                let relative_path = job.path.join(child_name);
-                let state = self.state.lock();
-                if state.snapshot.is_path_private(&relative_path) {
-                    log::debug!("detected private file: {relative_path:?}");
-                    child_entry.is_private = true;
-                }
-                drop(state)
+            if is_private {
+                log::debug!("detected private file: {:?}", child_entry.path);
+                child_entry.is_private = true;
             }
 
             new_entries.push(child_entry);
@@ -4253,6 +4343,11 @@ impl BackgroundScanner {
             if entry.is_dir() {
                 let child_ignore_stack = if entry.is_ignored {
                     IgnoreStack::all()
+                } else if snapshot
+                    .entry_for_path(entry.path.join(&*DOT_GIT))
+                    .is_some()
+                {
+                    snapshot.repository_ignore_stack(&abs_path)
                 } else {
                     ignore_stack.clone()
                 };
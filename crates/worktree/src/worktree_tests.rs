@@ -949,6 +949,64 @@ async fn test_update_gitignore(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_nested_repository_ignores(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".git": {},
+            ".gitignore": "*.log\n",
+            "a.log": "",
+            "a.txt": "",
+            "nested": {
+                ".git": {},
+                ".gitignore": "*.tmp\n",
+                "b.log": "",
+                "b.tmp": "",
+                "b.txt": "",
+            }
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        build_client(cx),
+        "/root".as_ref(),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    // The nested repository is only matched against its own ignore files.
+    cx.read(|cx| {
+        let tree = tree.read(cx);
+        assert_entry_git_state(tree, "a.log", None, true);
+        assert_entry_git_state(tree, "a.txt", None, false);
+        assert_entry_git_state(tree, "nested/b.log", None, false);
+        assert_entry_git_state(tree, "nested/b.tmp", None, true);
+        assert_entry_git_state(tree, "nested/b.txt", None, false);
+    });
+
+    // That still holds when the ignore statuses are recomputed.
+    fs.atomic_write("/root/.gitignore".into(), "*.log\n*.txt\n".into())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    cx.read(|cx| {
+        let tree = tree.read(cx);
+        assert_entry_git_state(tree, "a.txt", None, true);
+        assert_entry_git_state(tree, "nested/b.log", None, false);
+        assert_entry_git_state(tree, "nested/b.txt", None, false);
+    });
+}
+
 #[gpui::test]
 async fn test_write_file(cx: &mut TestAppContext) {
     init_test(cx);
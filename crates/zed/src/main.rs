@@ -14,6 +14,7 @@ use client::{parse_zed_link, telemetry::Telemetry, Client, DevServerToken, UserS
 use collab_ui::channel_view::ChannelView;
 use copilot::Copilot;
 use copilot_ui::CopilotCompletionProvider;
+use crash_reporter::{LocationData, Panic, PanicRequest};
 use db::kvp::KEY_VALUE_STORE;
 use editor::{Editor, EditorMode};
 use env_logger::Builder;
@@ -32,9 +33,9 @@ use mimalloc::MiMalloc;
 use node_runtime::RealNodeRuntime;
 use parking_lot::Mutex;
 use release_channel::{AppCommitSha, ReleaseChannel, RELEASE_CHANNEL};
-use serde::{Deserialize, Serialize};
 use settings::{
-    default_settings, handle_settings_file_changes, watch_config_file, Settings, SettingsStore,
+    default_settings, handle_settings_file_changes, watch_config_file, watch_user_settings_file,
+    Settings, SettingsStore,
 };
 use simplelog::ConfigBuilder;
 use smol::process::Command;
@@ -59,7 +60,7 @@ use util::{
     ResultExt, TryFutureExt,
 };
 use uuid::Uuid;
-use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
+use welcome::{show_dashboard, show_welcome_view, BaseKeymap, FIRST_OPEN};
 use workspace::{AppState, WorkspaceSettings, WorkspaceStore};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
@@ -108,9 +109,11 @@ fn init_headless(dev_server_token: DevServerToken) {
         client::init_settings(cx);
 
         let clock = Arc::new(clock::RealSystemClock);
-        let http = Arc::new(HttpClientWithUrl::new(
+        let http = Arc::new(HttpClientWithUrl::with_config(
             &client::ClientSettings::get_global(cx).server_url,
+            client::NetworkSettings::get_global(cx).http_client_config(),
         ));
+        cx.set_http_client(http.clone());
 
         let client = client::Client::new(clock, http.clone(), cx);
         let client = client.clone();
@@ -162,7 +165,14 @@ fn init_ui(args: Args) {
 
     init_logger();
 
-    if ensure_only_instance() != IsOnlyInstance::Yes {
+    let (listener, mut open_rx) = OpenListener::new();
+    let listener = Arc::new(listener);
+    let urls_to_forward = args
+        .paths_or_urls
+        .iter()
+        .map(|arg| forwarded_url_arg(arg))
+        .collect::<Vec<_>>();
+    if ensure_only_instance(&urls_to_forward, listener.clone()) != IsOnlyInstance::Yes {
         return;
     }
 
@@ -187,11 +197,7 @@ fn init_ui(args: Args) {
     log::info!("Using git binary path: {:?}", git_binary_path);
 
     let fs = Arc::new(RealFs::new(git_binary_path));
-    let user_settings_file_rx = watch_config_file(
-        &app.background_executor(),
-        fs.clone(),
-        paths::SETTINGS.clone(),
-    );
+    let user_settings_file_rx = watch_user_settings_file(&app.background_executor(), fs.clone());
     let user_keymap_file_rx = watch_config_file(
         &app.background_executor(),
         fs.clone(),
@@ -206,8 +212,6 @@ fn init_ui(args: Args) {
         })
     };
 
-    let (listener, mut open_rx) = OpenListener::new();
-    let listener = Arc::new(listener);
     let open_listener = listener.clone();
     app.on_open_urls(move |urls| open_listener.open_urls(urls));
     app.on_reopen(move |cx| {
@@ -237,6 +241,7 @@ fn init_ui(args: Args) {
 
         client::init_settings(cx);
         let client = Client::production(cx);
+        cx.set_http_client(client.http_client());
         let mut languages =
             LanguageRegistry::new(login_shell_env_loaded, cx.background_executor().clone());
         let copilot_language_server_id = languages.next_language_server_id();
@@ -315,6 +320,8 @@ fn init_ui(args: Args) {
         })
         .detach();
 
+        crash_reporter::init(installation_id.clone(), session_id.clone(), cx);
+
         let telemetry = client.telemetry();
         telemetry.start(installation_id, session_id, cx);
         telemetry.report_setting_event("theme", cx.theme().name.to_string());
@@ -345,26 +352,40 @@ fn init_ui(args: Args) {
         recent_projects::init(cx);
 
         go_to_line::init(cx);
+        credentials::init(cx);
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
+        project_templates::init(cx);
+        pull_requests::init(cx);
         project_panel::init(Assets, cx);
+        git_panel::init(cx);
+        undo_history_panel::init(cx);
         tasks_ui::init(cx);
         channel::init(&client, user_store.clone(), cx);
         search::init(cx);
+        scratch_buffers::init(cx);
+        edit_server::init(cx);
         vim::init(cx);
+        voice_commands::init(cx);
+        time_tracking::init(cx);
+        performance_hud::init(cx);
         terminal_view::init(cx);
 
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        layout_profile_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
+        log_viewer::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
         feedback::init(cx);
         markdown_preview::init(cx);
+        org_mode::init(cx);
+        injection_editor::init(cx);
         welcome::init(cx);
         extensions_ui::init(cx);
 
@@ -577,6 +598,11 @@ async fn restore_or_create_workspace(app_state: Arc<AppState>, cx: AsyncAppConte
             .log_err();
         } else if matches!(KEY_VALUE_STORE.read_kvp(FIRST_OPEN), Ok(None)) {
             cx.update(|cx| show_welcome_view(app_state, cx)).log_err();
+        } else if !matches!(
+            restore_behaviour,
+            workspace::RestoreOnStartupBehaviour::None
+        ) {
+            cx.update(|cx| show_dashboard(app_state, cx)).log_err();
         } else {
             cx.update(|cx| {
                 workspace::open_new(app_state, cx, |workspace, cx| {
@@ -632,6 +658,8 @@ fn init_logger() {
                 let config = ConfigBuilder::new()
                     .set_time_format_str("%Y-%m-%dT%T%:z")
                     .set_time_to_local(true)
+                    // Log the module of every entry, which the log viewer filters by.
+                    .set_target_level(LevelFilter::Error)
                     .build();
 
                 simplelog::WriteLogger::init(level, config, log_file)
@@ -675,35 +703,6 @@ fn init_stdout_logger() {
         .init();
 }
 
-#[derive(Serialize, Deserialize)]
-struct LocationData {
-    file: String,
-    line: u32,
-}
-
-#[derive(Serialize, Deserialize)]
-struct Panic {
-    thread: String,
-    payload: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    location_data: Option<LocationData>,
-    backtrace: Vec<String>,
-    app_version: String,
-    release_channel: String,
-    os_name: String,
-    os_version: Option<String>,
-    architecture: String,
-    panicked_on: i64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    installation_id: Option<String>,
-    session_id: String,
-}
-
-#[derive(Serialize)]
-struct PanicRequest {
-    panic: Panic,
-}
-
 static PANIC_COUNT: AtomicU32 = AtomicU32::new(0);
 
 fn init_panic_hook(app: &App, installation_id: Option<String>, session_id: String) {
@@ -846,7 +845,10 @@ async fn upload_previous_panics(
         let child = child?;
         let child_path = child.path();
 
-        if child_path.extension() != Some(OsStr::new("panic")) {
+        // Hangs are reported in the same format as panics.
+        if child_path.extension() != Some(OsStr::new("panic"))
+            && child_path.extension() != Some(OsStr::new("hang"))
+        {
             continue;
         }
         let filename = if let Some(filename) = child_path.file_name() {
@@ -891,12 +893,14 @@ async fn upload_previous_panics(
                     log::error!("Error uploading panic to server: {}", response.status());
                 }
             }
-        }
 
-        // We've done what we can, delete the file
-        std::fs::remove_file(child_path)
-            .context("error removing panic")
-            .log_err();
+            // We've done what we can, delete the file
+            std::fs::remove_file(child_path)
+                .context("error removing panic")
+                .log_err();
+        }
+        // Otherwise the file is kept, so it can be inspected and submitted from
+        // the crash reports view.
     }
     Ok::<_, anyhow::Error>(most_recent_panic)
 }
@@ -1049,6 +1053,15 @@ struct Args {
     dev_server_token: Option<String>,
 }
 
+/// Converts a command line argument into a url that can be opened by an already
+/// running instance, which doesn't share this process's working directory.
+fn forwarded_url_arg(arg: &str) -> String {
+    match std::fs::canonicalize(Path::new(arg)) {
+        Ok(path) => format!("file://{}", path.to_string_lossy()),
+        Err(_) => arg.to_string(),
+    }
+}
+
 fn parse_url_arg(arg: &str, cx: &AppContext) -> Result<String> {
     match std::fs::canonicalize(Path::new(&arg)) {
         Ok(path) => Ok(format!("file://{}", path.to_string_lossy())),
@@ -1170,8 +1183,8 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
         let mut events = fs.watch(path.as_path(), Duration::from_millis(100)).await;
         while (events.next().await).is_some() {
             cx.update(|cx| {
-                cx.update_global(|file_types, _| {
-                    *file_types = file_icons::FileIcons::new(Assets);
+                cx.update_global(|file_icons: &mut file_icons::FileIcons, _| {
+                    file_icons.reload_default_icon_theme(Assets);
                 });
             })
             .ok();
@@ -17,6 +17,7 @@ pub use open_listener::*;
 use anyhow::Context as _;
 use assets::Assets;
 use futures::{channel::mpsc, select_biased, StreamExt};
+use git_panel::GitPanel;
 use project::TaskSourceKind;
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
@@ -30,12 +31,15 @@ use settings::{
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use task::static_source::{StaticSource, TrackedFile};
 use theme::ActiveTheme;
+use undo_history_panel::UndoHistoryPanel;
 use workspace::notifications::NotificationId;
 
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use util::{
     asset_str,
-    paths::{self, LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH},
+    paths::{
+        self, LOCAL_ENV_RELATIVE_PATH, LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH,
+    },
     ResultExt,
 };
 use uuid::Uuid;
@@ -43,7 +47,8 @@ use vim::VimModeSetting;
 use welcome::BaseKeymap;
 use workspace::{
     create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, OpenLog, Toast, Workspace, WorkspaceSettings,
+    open_new, AppState, NewFile, NewWindow, OpenLog, StatusItemOptions, StatusItemSide, Toast,
+    Workspace, WorkspaceSettings,
 };
 use workspace::{notifications::DetachAndPromptErr, Pane};
 use zed_actions::{OpenBrowser, OpenSettings, OpenZedUrl, Quit};
@@ -57,11 +62,13 @@ actions!(
         Hide,
         HideOthers,
         IncreaseBufferFontSize,
+        MigrateSettingsFormat,
         Minimize,
         OpenDefaultKeymap,
         OpenDefaultSettings,
         OpenKeymap,
         OpenLicenses,
+        OpenLocalEnv,
         OpenLocalSettings,
         OpenLocalTasks,
         OpenTasks,
@@ -69,6 +76,7 @@ actions!(
         ResetBufferFontSize,
         ResetDatabase,
         ShowAll,
+        ShowProjectEnvironment,
         ToggleFullScreen,
         Zoom,
     ]
@@ -135,13 +143,40 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
+        let performance_hud = cx.new_view(performance_hud::PerformanceHud::new);
         workspace.status_bar().update(cx, |status_bar, cx| {
-            status_bar.add_left_item(diagnostic_summary, cx);
-            status_bar.add_left_item(activity_indicator, cx);
-            status_bar.add_right_item(copilot, cx);
-            status_bar.add_right_item(active_buffer_language, cx);
-            status_bar.add_right_item(vim_mode_indicator, cx);
-            status_bar.add_right_item(cursor_position, cx);
+            use StatusItemSide::{Left, Right};
+            status_bar.add_item(
+                diagnostic_summary,
+                StatusItemOptions::new("diagnostics", Left, 200),
+                cx,
+            );
+            status_bar.add_item(
+                activity_indicator,
+                StatusItemOptions::new("activity_indicator", Left, 100),
+                cx,
+            );
+            status_bar.add_item(copilot, StatusItemOptions::new("copilot", Right, 500), cx);
+            status_bar.add_item(
+                active_buffer_language,
+                StatusItemOptions::new("language", Right, 400),
+                cx,
+            );
+            status_bar.add_item(
+                vim_mode_indicator,
+                StatusItemOptions::new("vim_mode", Right, 300),
+                cx,
+            );
+            status_bar.add_item(
+                cursor_position,
+                StatusItemOptions::new("cursor_position", Right, 200),
+                cx,
+            );
+            status_bar.add_item(
+                performance_hud,
+                StatusItemOptions::new("performance_hud", Right, 100),
+                cx,
+            );
         });
 
         auto_update::notify_of_any_new_update(cx);
@@ -185,6 +220,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             let assistant_panel =
                 assistant::AssistantPanel::load(workspace_handle.clone(), cx.clone());
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
+            let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
+            let undo_history_panel = UndoHistoryPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -197,6 +234,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
 
             let (
                 project_panel,
+                git_panel,
+                undo_history_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -204,6 +243,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 notification_panel,
             ) = futures::try_join!(
                 project_panel,
+                git_panel,
+                undo_history_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -214,6 +255,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             workspace_handle.update(&mut cx, |workspace, cx| {
                 workspace.add_panel(assistant_panel, cx);
                 workspace.add_panel(project_panel, cx);
+                workspace.add_panel(git_panel, cx);
+                workspace.add_panel(undo_history_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
@@ -346,12 +389,14 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             .register_action(
                 move |_: &mut Workspace, _: &OpenSettings, cx: &mut ViewContext<Workspace>| {
                     open_settings_file(
-                        &paths::SETTINGS,
+                        settings::user_settings_path(),
                         || settings::initial_user_settings_content().as_ref().into(),
                         cx,
                     );
                 },
             )
+            .register_action(migrate_settings_format)
+            .register_action(toggle_offline)
             .register_action(
                 move |_: &mut Workspace, _: &OpenTasks, cx: &mut ViewContext<Workspace>| {
                     open_settings_file(
@@ -363,6 +408,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             )
             .register_action(open_local_settings_file)
             .register_action(open_local_tasks_file)
+            .register_action(open_local_env_file)
+            .register_action(show_project_environment)
             .register_action(
                 move |workspace: &mut Workspace,
                       _: &OpenDefaultKeymap,
@@ -396,6 +443,20 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                     workspace.toggle_panel_focus::<ProjectPanel>(cx);
                 },
             )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &git_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<GitPanel>(cx);
+                },
+            )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &undo_history_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<UndoHistoryPanel>(cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &collab_ui::collab_panel::ToggleFocus,
@@ -720,6 +781,56 @@ fn open_local_tasks_file(
     )
 }
 
+fn open_local_env_file(
+    workspace: &mut Workspace,
+    _: &OpenLocalEnv,
+    cx: &mut ViewContext<Workspace>,
+) {
+    open_local_file(
+        workspace,
+        &LOCAL_ENV_RELATIVE_PATH,
+        concat!(
+            "# Environment variables for this project's terminals, tasks and language servers.\n",
+            "# NAME=value\n",
+        )
+        .into(),
+        cx,
+    )
+}
+
+fn show_project_environment(
+    workspace: &mut Workspace,
+    _: &ShowProjectEnvironment,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let mut text = String::new();
+    for worktree in project.visible_worktrees(cx) {
+        let worktree = worktree.read(cx);
+        if !worktree.is_local() {
+            continue;
+        }
+
+        text.push_str(&format!("# {}\n", worktree.root_name()));
+        let environment = project.worktree_environment(worktree.id(), cx);
+        if environment.is_empty() {
+            text.push_str("# The project defines no environment variables.\n");
+        }
+        for (name, value, source) in environment.iter() {
+            text.push_str(&format!("{name}={value} # {source}\n"));
+        }
+        text.push('\n');
+    }
+
+    open_bundled_file(
+        workspace,
+        text.into(),
+        "Project Environment",
+        "Shell Script",
+        cx,
+    );
+}
+
 fn open_local_file(
     workspace: &mut Workspace,
     settings_relative_path: &'static Path,
@@ -881,6 +992,34 @@ fn open_bundled_file(
     .detach_and_log_err(cx);
 }
 
+fn migrate_settings_format(
+    workspace: &mut Workspace,
+    _: &MigrateSettingsFormat,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let settings_path = settings::migrate_user_settings(fs.as_ref()).await?;
+        workspace.update(&mut cx, |_, cx| {
+            open_settings_file(settings_path, Rope::default, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn toggle_offline(
+    workspace: &mut Workspace,
+    _: &client::ToggleOffline,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let offline = !client::NetworkSettings::get_global(cx).offline;
+    settings::update_settings_file::<client::NetworkSettings>(
+        workspace.app_state().fs.clone(),
+        cx,
+        move |settings| settings.offline = Some(offline),
+    );
+}
+
 fn open_settings_file(
     abs_path: &'static Path,
     default_content: impl FnOnce() -> Rope + Send + 'static,
@@ -1134,6 +1273,70 @@ mod tests {
         assert_eq!(cx.update(|cx| cx.windows().len()), 2);
     }
 
+    #[gpui::test]
+    async fn test_open_diff_view(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({"old.txt": "one\ntwo\n", "new.txt": "one\nthree\n"}),
+            )
+            .await;
+
+        let (workspace, _) = cx
+            .update(|cx| {
+                open_paths(
+                    &[PathBuf::from("/root")],
+                    app_state.clone(),
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let project = workspace
+            .update(cx, |workspace, _| workspace.project().clone())
+            .unwrap();
+        let project_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/new.txt", cx)
+            })
+            .await
+            .unwrap();
+
+        let item = open_listener::open_diff_view(
+            workspace,
+            Path::new("/root/old.txt"),
+            Path::new("/root/new.txt"),
+            app_state.fs.clone(),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+
+        let editor = item.downcast::<Editor>().unwrap();
+        workspace
+            .update(cx, |workspace, cx| {
+                let active_item = workspace.active_item(cx).unwrap();
+                assert_eq!(active_item.item_id(), editor.entity_id());
+
+                let editor = editor.read(cx);
+                assert!(editor.read_only(cx));
+                let multibuffer = editor.buffer().read(cx);
+                assert_eq!(multibuffer.title(cx), "old.txt ↔ new.txt");
+
+                let buffer = multibuffer.as_singleton().unwrap();
+                assert_ne!(buffer, project_buffer);
+                assert_eq!(buffer.read(cx).text(), "one\nthree\n");
+                assert_eq!(buffer.read(cx).diff_base(), Some("one\ntwo\n"));
+                assert_eq!(project_buffer.read(cx).diff_base(), None);
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_open_file_in_many_spaces(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -3109,6 +3312,8 @@ mod tests {
             project_panel::init_settings(cx);
             collab_ui::init(&app_state, cx);
             project_panel::init((), cx);
+            git_panel::init(cx);
+            undo_history_panel::init(cx);
             terminal_view::init(cx);
             assistant::init(app_state.client.clone(), cx);
             tasks_ui::init(cx);
@@ -162,9 +162,11 @@ pub fn app_menus() -> Vec<Menu<'static>> {
         Menu {
             name: "Help",
             items: vec![
+                MenuItem::action("View Log", workspace::OpenLogViewer),
                 MenuItem::action("View Telemetry", super::OpenTelemetryLog),
                 MenuItem::action("View Dependency Licenses", super::OpenLicenses),
                 MenuItem::action("Show Welcome", workspace::Welcome),
+                MenuItem::action("Show Dashboard", welcome::ShowDashboard),
                 MenuItem::action("Give Feedback...", feedback::GiveFeedback),
                 MenuItem::separator(),
                 MenuItem::action(
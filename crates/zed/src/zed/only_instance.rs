@@ -1,16 +1,20 @@
 use std::{
     io::{Read, Write},
-    net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener, TcpStream},
+    net::{Ipv4Addr, Shutdown, SocketAddr, SocketAddrV4, TcpListener, TcpStream},
+    sync::Arc,
     thread,
     time::Duration,
 };
 
 use release_channel::ReleaseChannel;
 
+use crate::OpenListener;
+
 const LOCALHOST: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 1);
 const CONNECT_TIMEOUT: Duration = Duration::from_millis(10);
 const RECEIVE_TIMEOUT: Duration = Duration::from_millis(35);
 const SEND_TIMEOUT: Duration = Duration::from_millis(20);
+const FORWARD_TIMEOUT: Duration = Duration::from_millis(100);
 
 fn address() -> SocketAddr {
     let port = match *release_channel::RELEASE_CHANNEL {
@@ -38,12 +42,18 @@ pub enum IsOnlyInstance {
     No,
 }
 
-pub fn ensure_only_instance() -> IsOnlyInstance {
+/// Checks whether another Zed instance is already running. If it is, the given urls
+/// are forwarded to it to be opened there. Otherwise, this instance starts listening
+/// for urls forwarded by later instances and passes them to `open_listener`.
+pub fn ensure_only_instance(
+    urls_to_forward: &[String],
+    open_listener: Arc<OpenListener>,
+) -> IsOnlyInstance {
     if *db::ZED_STATELESS || *release_channel::RELEASE_CHANNEL == ReleaseChannel::Dev {
         return IsOnlyInstance::Yes;
     }
 
-    if check_got_handshake() {
+    if check_got_handshake(address(), urls_to_forward) {
         return IsOnlyInstance::No;
     }
 
@@ -52,7 +62,7 @@ pub fn ensure_only_instance() -> IsOnlyInstance {
 
         Err(err) => {
             log::warn!("Error binding to single instance port: {err}");
-            if check_got_handshake() {
+            if check_got_handshake(address(), urls_to_forward) {
                 return IsOnlyInstance::No;
             }
 
@@ -64,24 +74,45 @@ pub fn ensure_only_instance() -> IsOnlyInstance {
         }
     };
 
-    thread::spawn(move || {
-        for stream in listener.incoming() {
-            let mut stream = match stream {
-                Ok(stream) => stream,
-                Err(_) => return,
-            };
+    thread::spawn(move || handle_instance_connections(listener, open_listener));
+
+    IsOnlyInstance::Yes
+}
 
-            _ = stream.set_nodelay(true);
-            _ = stream.set_read_timeout(Some(SEND_TIMEOUT));
-            _ = stream.write_all(instance_handshake().as_bytes());
+/// Answers the handshakes of instances launched after this one, and opens the urls
+/// they forward.
+fn handle_instance_connections(listener: TcpListener, open_listener: Arc<OpenListener>) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => return,
+        };
+
+        _ = stream.set_nodelay(true);
+        _ = stream.set_read_timeout(Some(SEND_TIMEOUT));
+        if stream.write_all(instance_handshake().as_bytes()).is_err() {
+            continue;
         }
-    });
 
-    IsOnlyInstance::Yes
+        _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+        let mut forwarded = String::new();
+        if let Err(err) = stream.read_to_string(&mut forwarded) {
+            log::warn!("Failed to read urls forwarded by another instance: {err}");
+            continue;
+        }
+        let urls = forwarded
+            .lines()
+            .filter(|url| !url.is_empty())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if !urls.is_empty() {
+            open_listener.open_urls(urls);
+        }
+    }
 }
 
-fn check_got_handshake() -> bool {
-    match TcpStream::connect_timeout(&address(), CONNECT_TIMEOUT) {
+fn check_got_handshake(address: SocketAddr, urls_to_forward: &[String]) -> bool {
+    match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
         Ok(mut stream) => {
             let mut buf = vec![0u8; instance_handshake().len()];
 
@@ -93,6 +124,11 @@ fn check_got_handshake() -> bool {
 
             if buf == instance_handshake().as_bytes() {
                 log::info!("Got instance handshake");
+                let forwarded = urls_to_forward.join("\n");
+                if let Err(err) = stream.write_all(forwarded.as_bytes()) {
+                    log::warn!("Failed to forward urls to the running instance: {err}");
+                }
+                _ = stream.shutdown(Shutdown::Write);
                 return true;
             }
 
@@ -103,3 +139,35 @@ fn check_got_handshake() -> bool {
         Err(_) => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, StreamExt};
+
+    #[test]
+    fn test_forward_urls() {
+        let listener = TcpListener::bind(SocketAddrV4::new(LOCALHOST, 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let (open_listener, mut open_requests) = OpenListener::new();
+        let open_listener = Arc::new(open_listener);
+        thread::spawn(move || handle_instance_connections(listener, open_listener));
+
+        // An instance launched without urls only checks for the handshake.
+        assert!(check_got_handshake(address, &[]));
+
+        let urls = vec![
+            "zed://file/tmp/a.rs".to_string(),
+            "zed://channel/zed-42".to_string(),
+        ];
+        assert!(check_got_handshake(address, &urls));
+        assert_eq!(block_on(open_requests.next()), Some(urls));
+
+        // Without a running instance, there's no handshake.
+        let unused_address = TcpListener::bind(SocketAddrV4::new(LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        assert!(!check_got_handshake(unused_address, &[]));
+    }
+}
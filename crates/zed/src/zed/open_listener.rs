@@ -4,12 +4,13 @@ use cli::{ipc::IpcSender, CliRequest, CliResponse};
 use client::parse_zed_link;
 use collections::HashMap;
 use editor::scroll::Autoscroll;
-use editor::Editor;
+use editor::{Editor, MultiBuffer};
+use fs::Fs;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::{FutureExt, SinkExt, StreamExt};
 use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
-use language::{Bias, Point};
+use language::{Bias, Capability, Point};
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -20,6 +21,10 @@ use util::ResultExt;
 use workspace::item::ItemHandle;
 use workspace::{AppState, Workspace};
 
+/// The maximum number of stdin chunks received from the cli to append to the stdin
+/// buffer at once.
+const STDIN_CHUNKS_PER_EDIT: usize = 16;
+
 #[derive(Default, Debug)]
 pub struct OpenRequest {
     pub cli_connection: Option<(mpsc::Receiver<CliRequest>, IpcSender<CliResponse>)>,
@@ -59,6 +64,10 @@ impl OpenRequest {
     }
 
     fn parse_request_path(&mut self, request_path: &str) -> Result<()> {
+        if let Some(query) = request_path.strip_prefix("open?") {
+            return self.parse_open_query(query);
+        }
+
         let mut parts = request_path.split('/');
         if parts.next() == Some("channel") {
             if let Some(slug) = parts.next() {
@@ -84,6 +93,37 @@ impl OpenRequest {
         }
         Err(anyhow!("invalid zed url: {}", request_path))
     }
+
+    /// Parses the query of a `zed://open?path=/abs/path&line=12&column=40` link.
+    fn parse_open_query(&mut self, query: &str) -> Result<()> {
+        let mut path = None;
+        let mut row = None;
+        let mut column = None;
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = urlencoding::decode(value)
+                .with_context(|| format!("invalid value for {key:?} in zed url"))?;
+            match key {
+                "path" => path = Some(PathBuf::from(value.as_ref())),
+                "line" => row = Some(value.parse::<u32>().context("invalid line in zed url")?),
+                "column" => {
+                    column = Some(value.parse::<u32>().context("invalid column in zed url")?)
+                }
+                _ => log::warn!("ignoring unknown parameter {key:?} in zed url"),
+            }
+        }
+
+        let path = path.context("zed url is missing a path")?;
+        if !path.is_absolute() {
+            return Err(anyhow!("zed url path {path:?} is not absolute"));
+        }
+        self.open_paths.push(PathLikeWithPosition {
+            path_like: path,
+            row,
+            column: row.and(column),
+        });
+        Ok(())
+    }
 }
 
 pub struct OpenListener {
@@ -200,6 +240,107 @@ pub async fn open_paths_with_positions(
     Ok((workspace, items))
 }
 
+/// Opens a read-only view of `new_path` in `workspace` that shows its changes relative
+/// to `old_path`. The view gets a buffer of its own, so that the diff base of the
+/// project's buffer for `new_path` is left alone.
+pub(crate) async fn open_diff_view(
+    workspace: WindowHandle<Workspace>,
+    old_path: &Path,
+    new_path: &Path,
+    fs: Arc<dyn Fs>,
+    cx: &mut AsyncAppContext,
+) -> Result<Box<dyn ItemHandle>> {
+    let old_text = fs.load(old_path).await?;
+    let new_text = fs.load(new_path).await?;
+    let languages = workspace.update(cx, |workspace, cx| {
+        workspace.project().read(cx).languages().clone()
+    })?;
+    let language = languages.language_for_file_path(new_path).await.ok();
+    let title = format!("{} ↔ {}", file_name(old_path), file_name(new_path));
+
+    workspace.update(cx, |workspace, cx| {
+        let project = workspace.project().clone();
+        let buffer = project.update(cx, |project, cx| {
+            project.create_buffer(&new_text, language, cx)
+        })?;
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_diff_base(Some(old_text), cx);
+            buffer.set_capability(Capability::ReadOnly, cx);
+        });
+        let multibuffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+        let editor = cx.new_view(|cx| Editor::for_multibuffer(multibuffer, Some(project), cx));
+        workspace.add_item_to_active_pane(Box::new(editor.clone()), cx);
+        anyhow::Ok(Box::new(editor) as Box<dyn ItemHandle>)
+    })?
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Opens a new buffer in `workspace` and appends the text piped into the cli's stdin
+/// to it as it arrives.
+fn open_stdin_buffer(
+    workspace: WindowHandle<Workspace>,
+    language: Option<String>,
+    requests: mpsc::Receiver<CliRequest>,
+    cx: &mut AsyncAppContext,
+) -> Result<Box<dyn ItemHandle>> {
+    let (buffer, editor) = workspace.update(cx, |workspace, cx| {
+        let project = workspace.project().clone();
+        let buffer = project.update(cx, |project, cx| project.create_buffer("", None, cx))?;
+        if let Some(language) = language {
+            let language = project
+                .read(cx)
+                .languages()
+                .language_for_name_or_extension(&language);
+            let buffer = buffer.downgrade();
+            cx.spawn(|_, mut cx| async move {
+                let language = language.await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_language(Some(language), cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project), cx));
+        workspace.add_item_to_active_pane(Box::new(editor.clone()), cx);
+        anyhow::Ok((buffer, editor))
+    })??;
+
+    let buffer = buffer.downgrade();
+    cx.spawn(|mut cx| async move {
+        // Apply every chunk that has already arrived in a single edit, so that fast
+        // producers don't flood the main thread with tiny edits.
+        let mut chunks = requests.ready_chunks(STDIN_CHUNKS_PER_EDIT);
+        while let Some(requests) = chunks.next().await {
+            let text = requests
+                .into_iter()
+                .filter_map(|request| match request {
+                    CliRequest::StdinChunk { text } => Some(text),
+                    CliRequest::Open { .. } => {
+                        log::error!("received an open request while reading stdin");
+                        None
+                    }
+                })
+                .collect::<String>();
+            let appended = buffer.update(&mut cx, |buffer, cx| {
+                let end = buffer.len();
+                buffer.edit([(end..end, text)], None, cx);
+            });
+            if appended.is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+
+    Ok(Box::new(editor))
+}
+
 pub async fn handle_cli_connection(
     (mut requests, responses): (mpsc::Receiver<CliRequest>, IpcSender<CliResponse>),
     app_state: Arc<AppState>,
@@ -207,13 +348,23 @@ pub async fn handle_cli_connection(
 ) {
     if let Some(request) = requests.next().await {
         match request {
+            CliRequest::StdinChunk { .. } => {
+                log::error!("received stdin from the cli before an open request");
+            }
             CliRequest::Open {
                 paths,
+                diff_paths,
                 wait,
                 open_new_workspace,
+                open_stdin,
+                stdin_language,
             } => {
+                let diff_paths = diff_paths
+                    .into_iter()
+                    .map(|[old_path, new_path]| (PathBuf::from(old_path), PathBuf::from(new_path)))
+                    .collect::<Vec<_>>();
                 let paths = if paths.is_empty() {
-                    if open_new_workspace == Some(true) {
+                    if open_new_workspace == Some(true) || !diff_paths.is_empty() || open_stdin {
                         vec![]
                     } else {
                         workspace::last_opened_workspace_paths()
@@ -249,6 +400,7 @@ pub async fn handle_cli_connection(
                 };
 
                 let mut errored = false;
+                let fs = app_state.fs.clone();
 
                 match open_paths_with_positions(
                     &paths,
@@ -292,10 +444,70 @@ pub async fn handle_cli_connection(
                             }
                         }
 
+                        for (old_path, new_path) in &diff_paths {
+                            match open_diff_view(workspace, old_path, new_path, fs.clone(), &mut cx)
+                                .await
+                            {
+                                Ok(item) => {
+                                    cx.update(|cx| {
+                                        let released = oneshot::channel();
+                                        item.on_release(
+                                            cx,
+                                            Box::new(move |_| {
+                                                let _ = released.0.send(());
+                                            }),
+                                        )
+                                        .detach();
+                                        item_release_futures.push(released.1);
+                                    })
+                                    .log_err();
+                                }
+                                Err(err) => {
+                                    responses
+                                        .send(CliResponse::Stderr {
+                                            message: format!(
+                                                "error comparing {:?} with {:?}: {}",
+                                                new_path, old_path, err
+                                            ),
+                                        })
+                                        .log_err();
+                                    errored = true;
+                                }
+                            }
+                        }
+
+                        if open_stdin {
+                            match open_stdin_buffer(workspace, stdin_language, requests, &mut cx) {
+                                Ok(editor) => {
+                                    cx.update(|cx| {
+                                        let released = oneshot::channel();
+                                        editor
+                                            .on_release(
+                                                cx,
+                                                Box::new(move |_| {
+                                                    let _ = released.0.send(());
+                                                }),
+                                            )
+                                            .detach();
+                                        item_release_futures.push(released.1);
+                                    })
+                                    .log_err();
+                                }
+                                Err(err) => {
+                                    responses
+                                        .send(CliResponse::Stderr {
+                                            message: format!("error opening stdin: {}", err),
+                                        })
+                                        .log_err();
+                                    errored = true;
+                                }
+                            }
+                        }
+
                         if wait {
                             let background = cx.background_executor().clone();
                             let wait = async move {
-                                if paths.is_empty() {
+                                if paths.is_empty() && diff_paths.is_empty() && !open_stdin {
                                     let (done_tx, done_rx) = oneshot::channel();
                                     let _subscription = workspace.update(&mut cx, |_, cx| {
                                         cx.on_release(move |_, _, _| {
@@ -345,3 +557,59 @@ pub async fn handle_cli_connection(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_open_link() {
+        let mut request = OpenRequest::default();
+        request
+            .parse_request_path("open?path=%2Ftmp%2Fa%20file.rs&line=12&column=40")
+            .unwrap();
+        assert_eq!(
+            request.open_paths,
+            vec![PathLikeWithPosition {
+                path_like: PathBuf::from("/tmp/a file.rs"),
+                row: Some(12),
+                column: Some(40),
+            }]
+        );
+
+        let mut request = OpenRequest::default();
+        request.parse_request_path("open?path=/tmp/b.rs").unwrap();
+        assert_eq!(
+            request.open_paths,
+            vec![PathLikeWithPosition {
+                path_like: PathBuf::from("/tmp/b.rs"),
+                row: None,
+                column: None,
+            }]
+        );
+
+        let mut request = OpenRequest::default();
+        assert!(request.parse_request_path("open?line=12").is_err());
+        assert!(request.parse_request_path("open?path=relative.rs").is_err());
+        assert!(request
+            .parse_request_path("open?path=/tmp/c.rs&line=twelve")
+            .is_err());
+        assert!(request.open_paths.is_empty());
+    }
+
+    #[test]
+    fn test_parse_channel_link() {
+        let mut request = OpenRequest::default();
+        request.parse_request_path("channel/zed-42").unwrap();
+        assert_eq!(request.join_channel, Some(42));
+
+        let mut request = OpenRequest::default();
+        request
+            .parse_request_path("channel/zed-42/notes#heading")
+            .unwrap();
+        assert_eq!(
+            request.open_channel_notes,
+            vec![(42, Some("heading".to_string()))]
+        );
+    }
+}
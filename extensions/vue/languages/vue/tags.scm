@@ -0,0 +1,7 @@
+(start_tag
+  (tag_name) @name
+  (#not-match? @name "^(?i:area|base|br|col|embed|hr|img|input|link|meta|param|source|track|wbr)$")) @open
+
+(_
+  (start_tag (tag_name) @open_name)
+  (end_tag (tag_name) @close_name))